@@ -5,8 +5,9 @@ use crate::config::CliConfig;
 use zero_latency_core::Result as ZeroLatencyResult;
 
 use crate::application::services::CliServiceImpl;
+use crate::infrastructure::config::project::Project;
 use crate::infrastructure::http::{
-    CollectionApiClient, DocumentApiClient, IndexApiClient, SearchApiClient, ServerApiClient,
+    BlocklistApiClient, CollectionApiClient, CurationApiClient, DocumentApiClient, IndexApiClient, SearchApiClient, ServerApiClient,
 };
 use crate::infrastructure::output::TableFormatter;
 
@@ -23,9 +24,12 @@ pub struct CliServiceContainer {
     index_client: Arc<IndexApiClient>,
     document_client: Arc<DocumentApiClient>,
     collection_client: Arc<CollectionApiClient>,
+    curation_client: Arc<CurationApiClient>,
+    blocklist_client: Arc<BlocklistApiClient>,
     server_client: Arc<ServerApiClient>,
     output_formatter: Arc<TableFormatter>,
     cli_service: Arc<CliServiceImpl>,
+    project: Option<Project>,
 }
 
 impl CliServiceContainer {
@@ -63,6 +67,16 @@ impl CliServiceContainer {
             timeout,
         )?);
 
+        let curation_client = Arc::new(CurationApiClient::new(
+            config.server_url.clone(),
+            timeout,
+        )?);
+
+        let blocklist_client = Arc::new(BlocklistApiClient::new(
+            config.server_url.clone(),
+            timeout,
+        )?);
+
         let server_client = Arc::new(ServerApiClient::new(config.server_url.clone(), timeout)?);
 
         let output_formatter = Arc::new(TableFormatter::new());
@@ -81,12 +95,26 @@ impl CliServiceContainer {
             index_client,
             document_client,
             collection_client,
+            curation_client,
+            blocklist_client,
             server_client,
             output_formatter,
             cli_service,
+            project: None,
         })
     }
 
+    /// Attaches the project discovered from the working directory.
+    pub fn with_project(mut self, project: Option<Project>) -> Self {
+        self.project = project;
+        self
+    }
+
+    /// Returns the project the CLI runs in, if any.
+    pub fn project(&self) -> Option<&Project> {
+        self.project.as_ref()
+    }
+
     /// Returns the main CLI service for command execution.
     pub fn cli_service(&self) -> Arc<CliServiceImpl> {
         self.cli_service.clone()
@@ -107,6 +135,21 @@ impl CliServiceContainer {
         self.collection_client.clone()
     }
 
+    /// Returns the curation API client for direct access if needed.
+    pub fn curation_client(&self) -> Arc<CurationApiClient> {
+        self.curation_client.clone()
+    }
+
+    /// Returns the blocklist API client for direct access if needed.
+    pub fn blocklist_client(&self) -> Arc<BlocklistApiClient> {
+        self.blocklist_client.clone()
+    }
+
+    /// Returns the server API client for direct access if needed.
+    pub fn server_client(&self) -> Arc<ServerApiClient> {
+        self.server_client.clone()
+    }
+
     /// Returns the output formatter for direct access if needed.
     pub fn output_formatter(&self) -> Arc<TableFormatter> {
         self.output_formatter.clone()
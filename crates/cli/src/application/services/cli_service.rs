@@ -1,16 +1,26 @@
 use std::sync::Arc;
+use std::time::Duration;
 
-use zero_latency_core::{values::SearchQuery, Result as ZeroLatencyResult};
+use zero_latency_api::endpoints::version::{self, Compatibility};
+use zero_latency_core::{values::SearchQuery, Result as ZeroLatencyResult, ZeroLatencyError};
+use zero_latency_search::OutputTemplate;
 
-use crate::infrastructure::http::{IndexApiClient, SearchApiClient, ServerApiClient};
+use crate::infrastructure::http::{Backoff, IndexApiClient, SearchApiClient, ServerApiClient};
 use crate::infrastructure::output::TableFormatter;
 
+/// How often a running index job is polled
+const JOB_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Command data structures for CLI operations
 #[derive(Debug, Clone)]
 pub struct SearchCommand {
     pub query: String,
     pub limit: u32,
     pub format: String,
+    /// Collections to search instead of the configured one
+    pub collections: Vec<String>,
+    /// Template file to render the results through instead of `format`
+    pub template: Option<std::path::PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -23,15 +33,16 @@ pub struct IndexCommand {
     pub clear_default_ignores: bool,
     pub follow_symlinks: bool,
     pub case_sensitive: bool,
+    pub max_file_size: Option<u64>,
+    pub nice: bool,
+    /// Run even if the estimated embedding cost is above the server's threshold
+    pub confirm_cost: bool,
 }
 
 #[derive(Debug, Clone)]
-pub struct StatusCommand {}
-
-#[derive(Debug, Clone)]
-pub struct ServerCommand {
-    pub port: u16,
-    pub host: String,
+pub struct StatusCommand {
+    /// Also show the server's recent slow queries
+    pub verbose: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -41,15 +52,66 @@ pub struct ReindexCommand {
     pub clear_default_ignores: bool,
     pub follow_symlinks: bool,
     pub case_sensitive: bool,
+    pub max_file_size: Option<u64>,
+    pub nice: bool,
+    /// Run even if the estimated embedding cost is above the server's threshold
+    pub confirm_cost: bool,
 }
 
 // Temporary response types until we create proper domain models
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct IndexResponse {
     pub documents_processed: u64,
+    #[serde(default)]
+    pub files_skipped: u64,
     pub processing_time_ms: f64,
     pub status: String,
     pub message: Option<String>,
+    #[serde(default)]
+    pub job_id: Option<String>,
+    #[serde(default)]
+    pub diagnostics: Vec<IndexDiagnostic>,
+}
+
+/// Per-file error or warning reported by the server for an indexing job
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct IndexDiagnostic {
+    pub path: String,
+    pub stage: String,
+    pub severity: String,
+    pub reason: String,
+    pub suggestion: Option<String>,
+}
+
+/// An index job as reported by the server's job endpoints
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct IndexJob {
+    pub id: String,
+    /// `running`, `completed` or `failed`
+    pub status: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub documents_processed: u64,
+    #[serde(default)]
+    pub files_skipped: u64,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub diagnostics: Vec<IndexDiagnostic>,
+}
+
+impl IndexResponse {
+    /// Number of diagnostics with error severity
+    pub fn error_count(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == "error")
+            .count()
+    }
+
+    /// Number of diagnostics with warning severity
+    pub fn warning_count(&self) -> usize {
+        self.diagnostics.len() - self.error_count()
+    }
 }
 
 /// CLI service implementation that handles command orchestration,
@@ -85,16 +147,35 @@ impl CliServiceImpl {
 
     /// Execute a search command
     pub async fn search(&self, request: SearchCommand) -> ZeroLatencyResult<()> {
+        // A broken template should fail before the search is sent
+        let template = request
+            .template
+            .as_deref()
+            .map(OutputTemplate::from_file)
+            .transpose()?;
+
         // Convert CLI command to domain model
-        let search_query = SearchQuery::new(request.query).with_limit(request.limit);
+        let search_query = SearchQuery::new(request.query.clone()).with_limit(request.limit);
 
         // Use the search-specific client
-        let response = self.search_client.search(search_query).await?;
+        let response = self
+            .search_client
+            .search(search_query, &request.collections)
+            .await?;
 
         // Format and display results
-        self.output_formatter
-            .format_search_results(response, &request.format)
-            .await?;
+        match template {
+            Some(template) => {
+                self.output_formatter
+                    .format_search_template(response, &request.query, &template)
+                    .await?
+            }
+            None => {
+                self.output_formatter
+                    .format_search_results(response, &request.format)
+                    .await?
+            }
+        }
         Ok(())
     }
 
@@ -102,16 +183,33 @@ impl CliServiceImpl {
     pub async fn index(&self, request: IndexCommand) -> ZeroLatencyResult<IndexResponse> {
         // Use the index-specific client
         let response = self.index_client.index(request).await?;
+        let response = self.wait_for_job(response).await?;
+
+        println!(
+            "Indexing completed: {} documents processed, {} unchanged files skipped",
+            response.documents_processed, response.files_skipped
+        );
+        Ok(response)
+    }
+
+    /// Resume a failed or interrupted index job, skipping the files it had
+    /// already stored
+    pub async fn resume_index(&self, job_id: &str) -> ZeroLatencyResult<IndexResponse> {
+        let response = self.index_client.resume(job_id).await?;
+        if let Some(message) = &response.message {
+            println!("{}", message);
+        }
+        let response = self.wait_for_job(response).await?;
 
         println!(
-            "Indexing completed: {} documents processed",
-            response.documents_processed
+            "Indexing completed: {} documents processed, {} unchanged files skipped",
+            response.documents_processed, response.files_skipped
         );
         Ok(response)
     }
 
     /// Execute a status command
-    pub async fn status(&self, _request: StatusCommand) -> ZeroLatencyResult<()> {
+    pub async fn status(&self, request: StatusCommand) -> ZeroLatencyResult<()> {
         // Use the server-specific client
         let status = self.server_client.get_status().await?;
 
@@ -119,24 +217,112 @@ impl CliServiceImpl {
         println!("Version: {}", status.version);
         println!("Uptime: {} seconds", status.uptime_seconds);
         println!("Total Documents: {}", status.total_documents);
+
+        if request.verbose {
+            let slow_queries = self.server_client.get_slow_queries(10).await?;
+            println!();
+            self.output_formatter
+                .format_slow_queries(&slow_queries)
+                .await?;
+        }
         Ok(())
     }
 
-    /// Execute a start server command
-    pub async fn start_server(&self, request: ServerCommand) -> ZeroLatencyResult<()> {
-        // Use the server-specific client
-        let server_info = self
-            .server_client
-            .start_server(request.host, request.port)
-            .await?;
-
-        println!("Server started: {}", server_info.message);
-        Ok(())
+    /// Compare the server's API version with the range this CLI supports
+    pub async fn check_version(&self) -> ZeroLatencyResult<Compatibility> {
+        let status = self.server_client.get_status().await?;
+        Ok(version::check(status.api_version.as_deref()))
     }
 
     /// Execute a reindex command
     pub async fn reindex(&self, command: ReindexCommand) -> ZeroLatencyResult<IndexResponse> {
         // Use the index-specific client
-        self.index_client.reindex(command).await
+        let response = self.index_client.reindex(command).await?;
+        self.wait_for_job(response).await
+    }
+
+    /// Follow a background index job until it finishes
+    ///
+    /// Losing the connection doesn't stop the job on the server, so polling
+    /// reconnects with backoff and resumes on the same job. Servers that ran
+    /// the job before responding are returned as is.
+    async fn wait_for_job(&self, response: IndexResponse) -> ZeroLatencyResult<IndexResponse> {
+        let job_id = match &response.job_id {
+            Some(job_id) if response.status == "running" => job_id.clone(),
+            _ => return Ok(response),
+        };
+        println!("Job {} started, waiting for it to finish...", job_id);
+
+        let mut backoff = Backoff::default();
+        let mut disconnected = false;
+        loop {
+            match self.index_client.get_job(&job_id).await {
+                Ok(Some(job)) => {
+                    if disconnected {
+                        println!(
+                            "Reconnected to the server, job {} is {}",
+                            job_id, job.status
+                        );
+                        disconnected = false;
+                    }
+                    backoff.reset();
+                    match job.status.as_str() {
+                        "completed" => {
+                            let elapsed =
+                                job.completed_at.unwrap_or_else(chrono::Utc::now) - job.started_at;
+                            return Ok(IndexResponse {
+                                documents_processed: job.documents_processed,
+                                files_skipped: job.files_skipped,
+                                processing_time_ms: elapsed.num_milliseconds() as f64,
+                                status: job.status,
+                                message: None,
+                                job_id: Some(job.id),
+                                diagnostics: job.diagnostics,
+                            });
+                        }
+                        "failed" => {
+                            return Err(ZeroLatencyError::external_service(
+                                "index_api",
+                                format!(
+                                    "Job {} failed: {}",
+                                    job_id,
+                                    job.error.unwrap_or_else(|| "unknown error".to_string())
+                                ),
+                            ))
+                        }
+                        _ => tokio::time::sleep(JOB_POLL_INTERVAL).await,
+                    }
+                }
+                Ok(None) => {
+                    return Err(ZeroLatencyError::external_service(
+                        "index_api",
+                        format!(
+                            "The server no longer knows job {}; it restarted before the job \
+                             finished. Run the command again to index the remaining files",
+                            job_id
+                        ),
+                    ))
+                }
+                Err(ZeroLatencyError::Network { message }) => match backoff.next_delay() {
+                    Some(delay) => {
+                        eprintln!(
+                            "Lost connection to the server ({}), retrying in {}s...",
+                            message,
+                            delay.as_secs()
+                        );
+                        disconnected = true;
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => {
+                        return Err(ZeroLatencyError::network(format!(
+                            "Gave up reconnecting to the server; job {} may still be running \
+                             there ({})",
+                            job_id, message
+                        )))
+                    }
+                },
+                Err(e) => return Err(e),
+            }
+        }
     }
 }
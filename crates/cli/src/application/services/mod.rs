@@ -1,5 +1,3 @@
 pub mod cli_service;
 
-pub use cli_service::{
-    CliServiceImpl, IndexCommand, ReindexCommand, SearchCommand, ServerCommand, StatusCommand,
-};
+pub use cli_service::{CliServiceImpl, IndexCommand, ReindexCommand, SearchCommand, StatusCommand};
@@ -0,0 +1,153 @@
+use crate::application::CliServiceContainer;
+/// Blocklist commands
+///
+/// Hide documents from a collection's search results without deleting
+/// them, and list or lift those blocks.
+use clap::{Args, Subcommand};
+use serde::{Deserialize, Serialize};
+use zero_latency_core::Result as ZeroLatencyResult;
+
+/// Blocklist command and subcommands
+#[derive(Debug, Args)]
+pub struct BlocklistCommand {
+    #[command(subcommand)]
+    pub action: BlocklistAction,
+}
+
+/// Blocklist action subcommands
+#[derive(Debug, Subcommand)]
+pub enum BlocklistAction {
+    /// List blocked documents and path patterns
+    List(ListArgs),
+    /// Hide a path pattern or document from search results
+    Add(AddArgs),
+    /// Unblock an entry
+    Remove(RemoveArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// Only entries of this collection
+    #[arg(long)]
+    collection: Option<String>,
+    /// Output format (table, json)
+    #[arg(long, default_value = "table")]
+    format: String,
+}
+
+#[derive(Debug, Args)]
+pub struct AddArgs {
+    /// Path pattern to block, or a document ID or URI with --document
+    target: String,
+    /// Treat the target as a document ID or URI
+    #[arg(long)]
+    document: bool,
+    /// Why the documents are hidden
+    #[arg(long)]
+    reason: Option<String>,
+    /// Collection (defaults to the configured collection)
+    #[arg(long)]
+    collection: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct RemoveArgs {
+    /// Entry ID
+    id: String,
+}
+
+impl BlocklistCommand {
+    pub async fn execute(&self, container: &CliServiceContainer) -> ZeroLatencyResult<()> {
+        let client = container.blocklist_client();
+
+        match &self.action {
+            BlocklistAction::List(args) => {
+                let entries = client.list(args.collection.as_deref()).await?;
+                if args.format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&entries)?);
+                    return Ok(());
+                }
+                for entry in &entries {
+                    println!(
+                        "{}  [{}] {}{}",
+                        entry.id,
+                        entry.collection,
+                        entry.target(),
+                        entry
+                            .reason
+                            .as_ref()
+                            .map(|reason| format!(" ({})", reason))
+                            .unwrap_or_default()
+                    );
+                }
+                println!("\nTotal: {} entry(ies)", entries.len());
+            }
+            BlocklistAction::Add(args) => {
+                let (path, document) = if args.document {
+                    (None, Some(args.target.clone()))
+                } else {
+                    (Some(args.target.clone()), None)
+                };
+                let entry = client
+                    .add(NewBlockEntry {
+                        collection: args
+                            .collection
+                            .clone()
+                            .unwrap_or_else(|| container.config().collection_name.clone()),
+                        path,
+                        document,
+                        reason: args.reason.clone(),
+                    })
+                    .await?;
+                println!(
+                    "Blocked {} in {} ({})",
+                    entry.target(),
+                    entry.collection,
+                    entry.id
+                );
+            }
+            BlocklistAction::Remove(args) => {
+                client.remove(&args.id).await?;
+                println!("Blocklist entry {} removed", args.id);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Request/Response types for the blocklist API
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NewBlockEntry {
+    pub collection: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockEntry {
+    pub id: String,
+    pub collection: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub document: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl BlockEntry {
+    /// The blocked path pattern or document
+    fn target(&self) -> &str {
+        self.path
+            .as_deref()
+            .or(self.document.as_deref())
+            .unwrap_or_default()
+    }
+}
@@ -29,6 +29,8 @@ pub enum CollectionAction {
     Stats(StatsArgs),
     /// Set the default collection for subsequent operations
     Set(SetArgs),
+    /// Print or export random chunks with their nearest neighbors
+    Sample(SampleArgs),
 }
 
 #[derive(Debug, Args)]
@@ -89,6 +91,24 @@ pub struct SetArgs {
     name: String,
 }
 
+#[derive(Debug, Args)]
+pub struct SampleArgs {
+    /// Collection name
+    name: String,
+    /// Number of chunks to draw
+    #[arg(short = 'n', long = "count", default_value = "50")]
+    count: usize,
+    /// Nearest neighbors to show per chunk
+    #[arg(long, default_value = "5")]
+    neighbors: usize,
+    /// Seed of a previous sample, to draw the same chunks again
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Output format (table, json, jsonl)
+    #[arg(long, default_value = "table")]
+    format: String,
+}
+
 impl CollectionCommand {
     pub async fn execute(&self, container: &CliServiceContainer) -> ZeroLatencyResult<()> {
         match &self.action {
@@ -98,6 +118,7 @@ impl CollectionCommand {
             CollectionAction::Delete(args) => self.delete_collection(container, args).await,
             CollectionAction::Stats(args) => self.get_collection_stats(container, args).await,
             CollectionAction::Set(args) => self.set_default_collection(container, args).await,
+            CollectionAction::Sample(args) => self.sample_chunks(container, args).await,
         }
     }
 
@@ -338,6 +359,14 @@ impl CollectionCommand {
                     "Index Efficiency",
                     stats.index_efficiency * 100.0
                 );
+                if stats.deduplicated_bytes > 0 {
+                    println!(
+                        "{:<20} {} of {}",
+                        "Dedup Savings",
+                        format_bytes(stats.deduplicated_bytes),
+                        format_bytes(stats.chunk_bytes)
+                    );
+                }
 
                 if let Some(last_indexed) = stats.last_indexed {
                     println!(
@@ -346,6 +375,71 @@ impl CollectionCommand {
                         last_indexed.format("%Y-%m-%d %H:%M:%S UTC")
                     );
                 }
+
+                if let Some(health) = &response.health {
+                    println!();
+                    container.output_formatter().format_collection_health(health).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn sample_chunks(
+        &self,
+        container: &CliServiceContainer,
+        args: &SampleArgs,
+    ) -> ZeroLatencyResult<()> {
+        let sample = container
+            .collection_client()
+            .sample_chunks(&args.name, args.count, args.neighbors, args.seed)
+            .await?;
+
+        match args.format.as_str() {
+            "json" => {
+                println!("{}", serde_json::to_string_pretty(&sample)?);
+            }
+            "jsonl" => {
+                // One chunk per line, for exporting to review tools
+                for chunk in &sample.chunks {
+                    println!("{}", serde_json::to_string(chunk)?);
+                }
+            }
+            _ => {
+                for chunk in &sample.chunks {
+                    println!(
+                        "{} #{}  {}",
+                        chunk.path.as_deref().unwrap_or(&chunk.title),
+                        chunk.chunk_index,
+                        chunk.chunk_id
+                    );
+                    if !chunk.heading_path.is_empty() {
+                        println!("  {}", chunk.heading_path.join(" > "));
+                    }
+                    println!("  {}", preview(&chunk.content));
+                    for neighbor in &chunk.neighbors {
+                        println!(
+                            "    {:.3}  {} #{}{}",
+                            neighbor.similarity,
+                            neighbor.path.as_deref().unwrap_or(&neighbor.title),
+                            neighbor.chunk_index,
+                            if neighbor.same_document {
+                                " (same document)"
+                            } else {
+                                ""
+                            }
+                        );
+                    }
+                    println!();
+                }
+                println!(
+                    "Sampled {} of {} chunk(s) from '{}' (seed {})",
+                    sample.chunks.len(),
+                    sample.total_chunks,
+                    sample.collection,
+                    sample.seed
+                );
             }
         }
 
@@ -384,6 +478,17 @@ impl CollectionCommand {
     }
 }
 
+/// First line of a chunk, shortened for the sample listing
+fn preview(content: &str) -> String {
+    const MAX_CHARS: usize = 100;
+    let line = content.trim().lines().next().unwrap_or_default();
+    if line.chars().count() > MAX_CHARS {
+        format!("{}...", line.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
 /// Helper function to format bytes in human-readable format
 fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
@@ -439,6 +544,10 @@ pub struct CollectionStats {
     pub average_vector_size: f64,
     pub last_indexed: Option<chrono::DateTime<chrono::Utc>>,
     pub index_efficiency: f64,
+    #[serde(default)]
+    pub chunk_bytes: u64,
+    #[serde(default)]
+    pub deduplicated_bytes: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -460,8 +569,72 @@ pub struct DeleteCollectionResponse {
     pub message: String,
 }
 
+/// Random chunks of a collection with their nearest neighbors
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkSample {
+    pub collection: String,
+    pub total_chunks: usize,
+    pub seed: u64,
+    pub chunks: Vec<SampledChunk>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SampledChunk {
+    pub chunk_id: String,
+    pub document_id: String,
+    pub chunk_index: usize,
+    pub title: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub heading_path: Vec<String>,
+    pub content: String,
+    #[serde(default)]
+    pub metadata: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    #[serde(default)]
+    pub embedded_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub neighbors: Vec<ChunkNeighbor>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkNeighbor {
+    pub chunk_id: String,
+    pub document_id: String,
+    pub chunk_index: usize,
+    pub title: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    pub similarity: f32,
+    pub same_document: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetCollectionStatsResponse {
     pub found: bool,
     pub stats: Option<CollectionStats>,
+    #[serde(default)]
+    pub health: Option<CollectionHealth>,
+}
+
+/// Index health score of a collection with recommended fixes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CollectionHealth {
+    pub collection: String,
+    pub score: u8,
+    pub status: String,
+    #[serde(default)]
+    pub signals: serde_json::Value,
+    #[serde(default)]
+    pub recommendations: Vec<HealthRecommendation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HealthRecommendation {
+    pub issue: String,
+    pub penalty: u8,
+    pub affected: usize,
+    pub action: String,
 }
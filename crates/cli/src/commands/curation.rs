@@ -0,0 +1,206 @@
+use crate::application::CliServiceContainer;
+/// Search curation commands
+///
+/// Pin documents to the top of the results of specific queries and boost or
+/// demote documents across a collection's searches.
+use clap::{Args, Subcommand};
+use serde::{Deserialize, Serialize};
+use zero_latency_core::Result as ZeroLatencyResult;
+
+/// Search curation command and subcommands
+#[derive(Debug, Args)]
+pub struct CurationCommand {
+    #[command(subcommand)]
+    pub action: CurationAction,
+}
+
+/// Curation action subcommands
+#[derive(Debug, Subcommand)]
+pub enum CurationAction {
+    /// List pins and boosts
+    List(ListArgs),
+    /// Pin documents to the top of a query's results
+    Pin(PinArgs),
+    /// Remove a pin
+    Unpin(RemoveArgs),
+    /// Boost or demote documents in every search
+    Boost(BoostArgs),
+    /// Remove a boost
+    Unboost(RemoveArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// Only rules of this collection
+    #[arg(long)]
+    collection: Option<String>,
+    /// Output format (table, json)
+    #[arg(long, default_value = "table")]
+    format: String,
+}
+
+#[derive(Debug, Args)]
+pub struct PinArgs {
+    /// Query to pin the documents for
+    query: String,
+    /// Path patterns of the documents, in the order they are placed
+    #[arg(required = true)]
+    documents: Vec<String>,
+    /// Treat the query as a regular expression found anywhere in the searched query
+    #[arg(long)]
+    pattern: bool,
+    /// Collection (defaults to the configured collection)
+    #[arg(long)]
+    collection: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct BoostArgs {
+    /// Path pattern of the documents
+    document: String,
+    /// Score factor; above 1 boosts, below 1 demotes
+    factor: f32,
+    /// Collection (defaults to the configured collection)
+    #[arg(long)]
+    collection: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct RemoveArgs {
+    /// Rule ID
+    id: String,
+}
+
+impl CurationCommand {
+    pub async fn execute(&self, container: &CliServiceContainer) -> ZeroLatencyResult<()> {
+        let client = container.curation_client();
+        let collection = |collection: &Option<String>| {
+            collection
+                .clone()
+                .unwrap_or_else(|| container.config().collection_name.clone())
+        };
+
+        match &self.action {
+            CurationAction::List(args) => {
+                let rules = client.list(args.collection.as_deref()).await?;
+                if args.format == "json" {
+                    println!("{}", serde_json::to_string_pretty(&rules)?);
+                } else {
+                    print_rules(&rules);
+                }
+            }
+            CurationAction::Pin(args) => {
+                let pin = client
+                    .add_pin(NewPin {
+                        collection: collection(&args.collection),
+                        query: args.query.clone(),
+                        query_match: if args.pattern { "pattern" } else { "exact" }.to_string(),
+                        documents: args.documents.clone(),
+                    })
+                    .await?;
+                println!(
+                    "Pinned {} document(s) for '{}' in {} ({})",
+                    pin.documents.len(),
+                    pin.query,
+                    pin.collection,
+                    pin.id
+                );
+            }
+            CurationAction::Boost(args) => {
+                let boost = client
+                    .add_boost(NewBoost {
+                        collection: collection(&args.collection),
+                        document: args.document.clone(),
+                        factor: args.factor,
+                    })
+                    .await?;
+                println!(
+                    "Boosting {} by {} in {} ({})",
+                    boost.document, boost.factor, boost.collection, boost.id
+                );
+            }
+            CurationAction::Unpin(args) => {
+                client.remove_pin(&args.id).await?;
+                println!("Pin {} removed", args.id);
+            }
+            CurationAction::Unboost(args) => {
+                client.remove_boost(&args.id).await?;
+                println!("Boost {} removed", args.id);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn print_rules(rules: &CurationRules) {
+    println!("Pins:");
+    if rules.pins.is_empty() {
+        println!("  (none)");
+    }
+    for pin in &rules.pins {
+        println!(
+            "  {}  [{}] {:?} ({}) -> {}",
+            pin.id,
+            pin.collection,
+            pin.query,
+            pin.query_match,
+            pin.documents.join(", ")
+        );
+    }
+
+    println!("Boosts:");
+    if rules.boosts.is_empty() {
+        println!("  (none)");
+    }
+    for boost in &rules.boosts {
+        println!(
+            "  {}  [{}] {} x{}",
+            boost.id, boost.collection, boost.document, boost.factor
+        );
+    }
+}
+
+// Request/Response types for the curation API
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NewPin {
+    pub collection: String,
+    pub query: String,
+    #[serde(rename = "match")]
+    pub query_match: String,
+    pub documents: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NewBoost {
+    pub collection: String,
+    pub document: String,
+    pub factor: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Pin {
+    pub id: String,
+    pub collection: String,
+    pub query: String,
+    #[serde(rename = "match")]
+    pub query_match: String,
+    pub documents: Vec<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Boost {
+    pub id: String,
+    pub collection: String,
+    pub document: String,
+    pub factor: f32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CurationRules {
+    pub pins: Vec<Pin>,
+    pub boosts: Vec<Boost>,
+}
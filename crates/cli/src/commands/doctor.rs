@@ -0,0 +1,146 @@
+use clap::Args;
+use colored::*;
+
+use crate::application::CliServiceContainer;
+use crate::infrastructure::config::project::PROJECT_FILE;
+use zero_latency_api::endpoints::version::{self, Compatibility, API_VERSION, MIN_SUPPORTED};
+use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
+
+/// Diagnose the CLI setup and its connection to the server
+///
+/// Checks the configuration, the project file, that the server is reachable,
+/// that its API version is compatible with this CLI, and that the configured
+/// collection exists.
+///
+/// Examples:
+///   mdx doctor
+///   mdx doctor --server http://docs.internal:8081
+#[derive(Args)]
+pub struct DoctorCommand {}
+
+/// Outcome of a single diagnostic
+enum Check {
+    Pass(String),
+    Warn(String),
+    Fail(String),
+}
+
+impl Check {
+    fn print(&self, name: &str) {
+        match self {
+            Check::Pass(detail) => println!("{} {}: {}", "✓".green().bold(), name, detail),
+            Check::Warn(detail) => println!("{} {}: {}", "!".yellow().bold(), name, detail),
+            Check::Fail(detail) => println!("{} {}: {}", "✗".red().bold(), name, detail),
+        }
+    }
+}
+
+impl DoctorCommand {
+    pub async fn execute(&self, container: &CliServiceContainer) -> ZeroLatencyResult<()> {
+        println!("{}", "Running diagnostics...".bright_blue().bold());
+
+        let config = container.config();
+        let mut checks = vec![(
+            "Configuration",
+            Check::Pass(format!(
+                "server {}, collection '{}'",
+                config.server_url, config.collection_name
+            )),
+        )];
+
+        checks.push((
+            "Project",
+            match container.project() {
+                Some(project) => Check::Pass(project.root.join(PROJECT_FILE).display().to_string()),
+                None => Check::Pass(format!(
+                    "no {} found, using the global configuration",
+                    PROJECT_FILE
+                )),
+            },
+        ));
+
+        match container.server_client().get_status().await {
+            Ok(status) => {
+                checks.push((
+                    "Server",
+                    Check::Pass(format!(
+                        "reachable, version {}, {} documents",
+                        status.version, status.total_documents
+                    )),
+                ));
+                let supported = format!(
+                    "this CLI supports {} to {}.x",
+                    MIN_SUPPORTED, API_VERSION.major
+                );
+                checks.push((
+                    "API version",
+                    match version::check(status.api_version.as_deref()) {
+                        Compatibility::Compatible => Check::Pass(format!(
+                            "server API {} is compatible ({})",
+                            status.api_version.unwrap_or_default(),
+                            supported
+                        )),
+                        Compatibility::Unknown => Check::Warn(format!(
+                            "server doesn't report an API version; it predates version negotiation ({})",
+                            supported
+                        )),
+                        Compatibility::Incompatible(reason) => Check::Fail(reason),
+                    },
+                ));
+                checks.push((
+                    "Collection",
+                    match container.collection_client().list_collections().await {
+                        Ok(collections)
+                            if collections.iter().any(|c| c.name == config.collection_name) =>
+                        {
+                            Check::Pass(format!("'{}' exists", config.collection_name))
+                        }
+                        Ok(_) => Check::Warn(format!(
+                            "'{}' doesn't exist yet; create it with `mdx index`",
+                            config.collection_name
+                        )),
+                        Err(e) => Check::Fail(e.to_string()),
+                    },
+                ));
+            }
+            Err(e) => checks.push((
+                "Server",
+                Check::Fail(format!(
+                    "{} is unreachable: {} (start it with `mdx server start --daemon`)",
+                    config.server_url, e
+                )),
+            )),
+        }
+
+        for (name, check) in &checks {
+            check.print(name);
+        }
+
+        let failed = checks
+            .iter()
+            .filter(|(_, check)| matches!(check, Check::Fail(_)))
+            .count();
+        if failed > 0 {
+            return Err(ZeroLatencyError::validation(
+                "doctor",
+                format!("{} of {} checks failed", failed, checks.len()),
+            ));
+        }
+
+        let warnings = checks
+            .iter()
+            .filter(|(_, check)| matches!(check, Check::Warn(_)))
+            .count();
+        if warnings > 0 {
+            println!(
+                "{}",
+                format!("No problems found, {} warnings", warnings)
+                    .yellow()
+                    .bold()
+            );
+        } else {
+            println!("{}", "All checks passed!".bright_green().bold());
+        }
+        Ok(())
+    }
+}
@@ -2,21 +2,27 @@ use clap::Args;
 use colored::*;
 
 use crate::application::{CliServiceContainer, IndexCommand as AppIndexCommand};
-use zero_latency_core::Result as ZeroLatencyResult;
+use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
 
 /// Index documents from a directory for semantic search
 ///
 /// This command processes files in the specified directory and creates
 /// searchable embeddings. Supports filtering with glob patterns.
+/// Inside a project created with `mdx init` the path may be omitted to index
+/// the project's docs paths, and the project's ignore rules always apply.
 ///
 /// Examples:
+///   mdx index
 ///   mdx index ./docs --safe-patterns "*.md" "*.txt"
 ///   mdx index ./src --ignore-patterns "target" "*.log" --recursive
 ///   mdx index ./project --clear-default-ignores --force
+///   mdx index ./legacy-docs --show-errors
+///   mdx index ./handbook --confirm-cost
+///   mdx index --resume 3f2a9c1e-...
 #[derive(Args)]
 pub struct IndexCommand {
-    /// Directory or file path to index
-    pub path: String,
+    /// Directory or file path to index (defaults to the project's docs paths)
+    pub path: Option<String>,
 
     /// Maximum number of files to process (0 = unlimited)
     #[arg(short, long)]
@@ -34,7 +40,7 @@ pub struct IndexCommand {
     #[arg(short, long, default_value = "true")]
     pub recursive: bool,
 
-    /// Overwrite existing documents in the index
+    /// Re-index files even if they are unchanged since the last run
     #[arg(short, long)]
     pub force: bool,
 
@@ -59,6 +65,27 @@ pub struct IndexCommand {
     /// Use case-sensitive pattern matching for filters
     #[arg(long)]
     pub case_sensitive: bool,
+
+    /// Skip files larger than this many bytes (defaults to the server limit)
+    #[arg(long, value_name = "BYTES")]
+    pub max_file_size: Option<u64>,
+
+    /// Run at low priority, throttling CPU and disk usage so search stays responsive
+    #[arg(long)]
+    pub nice: bool,
+
+    /// Run even if the estimated embedding cost is above the server's threshold
+    #[arg(long)]
+    pub confirm_cost: bool,
+
+    /// Show per-file errors and warnings reported during indexing
+    #[arg(long)]
+    pub show_errors: bool,
+
+    /// Resume a failed or interrupted index job, skipping the files it had
+    /// already stored; the job keeps its original path and options
+    #[arg(long, value_name = "JOB_ID", conflicts_with = "path")]
+    pub resume: Option<String>,
 }
 
 impl IndexCommand {
@@ -69,19 +96,64 @@ impl IndexCommand {
     pub async fn execute(&self, container: &CliServiceContainer) -> ZeroLatencyResult<()> {
         println!("{}", "Starting document indexing...".bright_blue().bold());
 
+        if let Some(job_id) = &self.resume {
+            let response = container.cli_service().resume_index(job_id).await?;
+            container
+                .output_formatter()
+                .format_index_diagnostics(&response, self.show_errors)
+                .await?;
+            println!(
+                "{}",
+                "Indexing completed successfully!".bright_green().bold()
+            );
+            return Ok(());
+        }
+
+        let project = container.project();
+        let paths = match (&self.path, project) {
+            (Some(path), _) => vec![path.clone()],
+            (None, Some(project)) => project
+                .docs_paths()
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect(),
+            (None, None) => {
+                return Err(ZeroLatencyError::validation(
+                    "path",
+                    "No path given and no .mdx.toml project file found (run 'mdx init')",
+                ))
+            }
+        };
+
+        // Project rules extend the command line patterns
+        let mut safe_patterns = self.safe_patterns.clone();
+        let mut ignore_patterns = self.ignore_patterns.clone();
+        if let Some(project) = project {
+            println!(
+                "Project: {} (collection {})",
+                project.root.display().to_string().cyan(),
+                container.config().collection_name.cyan()
+            );
+            if safe_patterns.is_empty() {
+                safe_patterns = project.config.include.clone();
+            }
+            ignore_patterns.extend(project.config.ignore.iter().cloned());
+        }
+
         // Display filtering configuration if any custom patterns are specified
-        if !self.safe_patterns.is_empty()
-            || !self.ignore_patterns.is_empty()
+        if !safe_patterns.is_empty()
+            || !ignore_patterns.is_empty()
             || self.clear_default_ignores
+            || self.max_file_size.is_some()
         {
             println!("{}", "Filtering Configuration:".bright_yellow().bold());
 
-            if !self.safe_patterns.is_empty() {
-                println!("  Safe list: {}", self.safe_patterns.join(", ").green());
+            if !safe_patterns.is_empty() {
+                println!("  Safe list: {}", safe_patterns.join(", ").green());
             }
 
-            if !self.ignore_patterns.is_empty() {
-                println!("  Ignore list: {}", self.ignore_patterns.join(", ").red());
+            if !ignore_patterns.is_empty() {
+                println!("  Ignore list: {}", ignore_patterns.join(", ").red());
             }
 
             if self.clear_default_ignores {
@@ -98,21 +170,39 @@ impl IndexCommand {
                 println!("  Case sensitivity: {}", "enabled".cyan());
             }
 
+            if let Some(max_file_size) = self.max_file_size {
+                println!("  Max file size: {} bytes", max_file_size.to_string().cyan());
+            }
+
             println!(); // Empty line for better readability
         }
 
-        let app_command = AppIndexCommand {
-            path: self.path.clone(),
-            recursive: self.recursive,
-            force: self.force,
-            safe_patterns: self.safe_patterns.clone(),
-            ignore_patterns: self.ignore_patterns.clone(),
-            clear_default_ignores: self.clear_default_ignores,
-            follow_symlinks: self.follow_symlinks,
-            case_sensitive: self.case_sensitive,
-        };
+        for path in paths {
+            if self.path.is_none() {
+                println!("Indexing {}", path.cyan());
+            }
 
-        container.cli_service().index(app_command).await?;
+            let app_command = AppIndexCommand {
+                path,
+                recursive: self.recursive,
+                force: self.force,
+                safe_patterns: safe_patterns.clone(),
+                ignore_patterns: ignore_patterns.clone(),
+                clear_default_ignores: self.clear_default_ignores,
+                follow_symlinks: self.follow_symlinks,
+                case_sensitive: self.case_sensitive,
+                max_file_size: self.max_file_size,
+                nice: self.nice,
+                confirm_cost: self.confirm_cost,
+            };
+
+            let response = container.cli_service().index(app_command).await?;
+
+            container
+                .output_formatter()
+                .format_index_diagnostics(&response, self.show_errors)
+                .await?;
+        }
 
         println!(
             "{}",
@@ -0,0 +1,95 @@
+use clap::Args;
+use colored::*;
+
+use crate::application::CliServiceContainer;
+use crate::infrastructure::config::project::{
+    collection_name_for, project_root, Project, ProjectConfig, PROJECT_FILE,
+};
+use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
+
+/// Set up project mode for the current repository
+///
+/// Writes a .mdx.toml file at the repository root (or the current directory
+/// outside a git repository). `mdx search` and `mdx index` run anywhere
+/// below it then use the project's collection, docs paths and ignore rules.
+///
+/// Examples:
+///   mdx init
+///   mdx init --name handbook --docs docs --docs README.md
+///   mdx init --ignore "drafts/**" --ignore "*.tmp"
+#[derive(Args)]
+pub struct InitCommand {
+    /// Collection for the project (defaults to the directory name)
+    #[arg(long = "name", value_name = "COLLECTION")]
+    pub collection: Option<String>,
+
+    /// Paths to index, relative to the project root
+    /// (defaults to docs/ if it exists, otherwise the whole project)
+    #[arg(long, value_name = "PATH")]
+    pub docs: Vec<String>,
+
+    /// Only index files matching these glob patterns
+    #[arg(long, value_name = "PATTERN")]
+    pub include: Vec<String>,
+
+    /// Skip files matching these glob patterns
+    #[arg(long, value_name = "PATTERN")]
+    pub ignore: Vec<String>,
+
+    /// Overwrite an existing project file
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl InitCommand {
+    pub async fn execute(&self, _container: &CliServiceContainer) -> ZeroLatencyResult<()> {
+        let cwd = std::env::current_dir().map_err(|e| {
+            ZeroLatencyError::io(format!("Failed to read current directory: {}", e))
+        })?;
+        let root = project_root(&cwd);
+
+        let file = root.join(PROJECT_FILE);
+        if file.exists() && !self.force {
+            return Err(ZeroLatencyError::configuration(format!(
+                "{} already exists (use --force to overwrite)",
+                file.display()
+            )));
+        }
+
+        let collection = self
+            .collection
+            .clone()
+            .unwrap_or_else(|| collection_name_for(&root));
+        let docs = if !self.docs.is_empty() {
+            self.docs.clone()
+        } else if root.join("docs").is_dir() {
+            vec!["docs".to_string()]
+        } else {
+            vec![".".to_string()]
+        };
+
+        let project = Project::create(
+            &root,
+            ProjectConfig {
+                collection,
+                docs,
+                include: self.include.clone(),
+                ignore: self.ignore.clone(),
+            },
+        )?;
+
+        println!(
+            "{} {}",
+            "Created".bright_green().bold(),
+            file.display().to_string().cyan()
+        );
+        println!("  Collection: {}", project.config.collection.cyan());
+        println!("  Docs: {}", project.config.docs.join(", ").cyan());
+        if !project.config.ignore.is_empty() {
+            println!("  Ignore: {}", project.config.ignore.join(", ").red());
+        }
+        println!("Next: {}", "mdx index".cyan());
+
+        Ok(())
+    }
+}
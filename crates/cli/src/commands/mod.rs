@@ -1,7 +1,11 @@
+pub mod blocklist;
 pub mod collection;
 pub mod config;
+pub mod curation;
+pub mod doctor;
 pub mod document;
 pub mod index;
+pub mod init;
 pub mod reindex;
 pub mod search;
 pub mod server;
@@ -44,6 +44,18 @@ pub struct ReindexCommand {
     /// Use case-sensitive pattern matching for filters
     #[arg(long)]
     pub case_sensitive: bool,
+
+    /// Skip files larger than this many bytes (defaults to the server limit)
+    #[arg(long, value_name = "BYTES")]
+    pub max_file_size: Option<u64>,
+
+    /// Run at low priority, throttling CPU and disk usage so search stays responsive
+    #[arg(long)]
+    pub nice: bool,
+
+    /// Run even if the estimated embedding cost is above the server's threshold
+    #[arg(long)]
+    pub confirm_cost: bool,
 }
 
 impl ReindexCommand {
@@ -76,6 +88,7 @@ impl ReindexCommand {
         if !self.safe_patterns.is_empty()
             || !self.ignore_patterns.is_empty()
             || self.clear_default_ignores
+            || self.max_file_size.is_some()
         {
             println!("{}", "Filtering Configuration:".bright_yellow().bold());
 
@@ -101,6 +114,10 @@ impl ReindexCommand {
                 println!("  Case sensitivity: {}", "enabled".cyan());
             }
 
+            if let Some(max_file_size) = self.max_file_size {
+                println!("  Max file size: {} bytes", max_file_size.to_string().cyan());
+            }
+
             println!(); // Empty line for better readability
         }
 
@@ -110,6 +127,9 @@ impl ReindexCommand {
             clear_default_ignores: self.clear_default_ignores,
             follow_symlinks: self.follow_symlinks,
             case_sensitive: self.case_sensitive,
+            max_file_size: self.max_file_size,
+            nice: self.nice,
+            confirm_cost: self.confirm_cost,
         };
 
         let result = container.cli_service().reindex(app_command).await?;
@@ -1,5 +1,6 @@
 use clap::Args;
 use colored::*;
+use std::path::PathBuf;
 
 use crate::application::{CliServiceContainer, SearchCommand as AppSearchCommand};
 use zero_latency_core::Result as ZeroLatencyResult;
@@ -21,6 +22,14 @@ pub struct SearchCommand {
     /// Show only the best result
     #[arg(long)]
     pub best: bool,
+
+    /// Search several collections at once (comma-separated, globs allowed)
+    #[arg(long, value_delimiter = ',')]
+    pub collections: Vec<String>,
+
+    /// Render results through a Jinja template file instead of --format
+    #[arg(long, value_name = "PATH")]
+    pub template: Option<PathBuf>,
 }
 
 impl SearchCommand {
@@ -29,20 +38,29 @@ impl SearchCommand {
     /// This method delegates to the application service layer,
     /// maintaining separation of concerns between UI and business logic.
     pub async fn execute(&self, container: &CliServiceContainer) -> ZeroLatencyResult<()> {
+        // Templated output is meant to be used as is, without decoration
+        let decorate = self.template.is_none();
+
         // Show search indicator
-        println!("{} Searching for: {}", "".blue(), self.query.cyan().bold());
+        if decorate {
+            println!("{} Searching for: {}", "".blue(), self.query.cyan().bold());
+        }
 
         // Convert CLI args to application command
         let app_command = AppSearchCommand {
             query: self.query.clone(),
             limit: if self.best { 1 } else { self.limit },
             format: self.format.clone(),
+            collections: self.collections.clone(),
+            template: self.template.clone(),
         };
 
         // Delegate to application service
         container.cli_service().search(app_command).await?;
 
-        println!("{}", "Search completed successfully!".bright_green().bold());
+        if decorate {
+            println!("{}", "Search completed successfully!".bright_green().bold());
+        }
 
         Ok(())
     }
@@ -1,30 +1,51 @@
-use clap::Args;
+use clap::{Args, Subcommand};
 use colored::*;
-use std::process::{Command, Stdio};
-
-use crate::application::{CliServiceContainer, ServerCommand as AppServerCommand};
-use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::application::CliServiceContainer;
+use crate::infrastructure::daemon::{self, ServerFiles};
+use crate::infrastructure::service::{self, ServiceManager, ServiceSpec};
 use zero_latency_config::{load_config, AppConfig};
+use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
+
+/// How long a daemonized server may take to answer its health check
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often process and log state is polled
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
 
 /// CLI arguments for the server command
 #[derive(Args)]
 pub struct ServerCommand {
-    /// Start the server locally (spawns doc-indexer process)
-    #[arg(long)]
-    pub start: bool,
-
-    /// Start server locally in the background
-    #[arg(long)]
-    pub start_local: bool,
-
-    /// Stop the background server (via API)
-    #[arg(long)]
-    pub stop: bool,
+    #[command(subcommand)]
+    pub action: ServerAction,
+}
 
-    /// Show server status (via API)
-    #[arg(long)]
-    pub status: bool,
+/// Server lifecycle subcommands
+#[derive(Subcommand)]
+pub enum ServerAction {
+    /// Start the doc-indexer server
+    Start(StartArgs),
+    /// Stop the background server
+    Stop(StopArgs),
+    /// Stop the background server and start it again
+    Restart(RestartArgs),
+    /// Show whether the server is running
+    Status,
+    /// Print the background server's log
+    Logs(LogsArgs),
+    /// Run the server as a user service (systemd on Linux, launchd on macOS)
+    InstallService(InstallServiceArgs),
+    /// Stop and remove the user service
+    UninstallService,
+}
 
+/// Options shared by `start` and `restart`
+#[derive(Args, Clone)]
+pub struct LaunchArgs {
     /// Port to run the server on (overrides config)
     #[arg(short, long)]
     pub port: Option<u16>,
@@ -38,80 +59,102 @@ pub struct ServerCommand {
     pub config_file: Option<String>,
 }
 
-impl ServerCommand {
-    /// Execute the server command using clean architecture pattern.
-    ///
-    /// This method provides both direct server startup and API-based management.
-    pub async fn execute(&self, container: &CliServiceContainer) -> ZeroLatencyResult<()> {
-        // Load configuration with CLI argument overrides
-        let config = self.load_effective_config()?;
+#[derive(Args)]
+pub struct StartArgs {
+    /// Run in the background, with a PID file and output redirected to the log file
+    #[arg(long)]
+    pub daemon: bool,
 
-        // Handle direct server startup first
-        if self.start || self.start_local {
-            return self.start_server_directly(&config).await;
-        }
+    #[command(flatten)]
+    pub launch: LaunchArgs,
+}
 
-        // For other operations, use the API-based approach
-        if self.status || self.stop {
-            println!("{}", "Managing server via API...".bright_blue().bold());
-
-            if self.status {
-                use crate::application::services::cli_service::StatusCommand;
-
-                let status_command = StatusCommand {};
-
-                // Try to get status, but provide helpful error if server isn't running
-                match container.cli_service().status(status_command).await {
-                    Ok(_) => {
-                        println!(
-                            "{}",
-                            "Server status retrieved successfully!"
-                                .bright_green()
-                                .bold()
-                        );
-                    }
-                    Err(ZeroLatencyError::Network { .. }) => {
-                        println!("{}", "Server is not running".red().bold());
-                        println!(
-                            "{} Start the server with: {}",
-                            "Tip:".yellow(),
-                            "mdx server --start".cyan()
-                        );
-                        return Ok(());
-                    }
-                    Err(e) => return Err(e),
-                }
-            }
+#[derive(Args)]
+pub struct StopArgs {
+    /// Seconds to wait for a graceful shutdown
+    #[arg(long, default_value = "10")]
+    pub timeout: u64,
 
-            if self.stop {
-                let app_command = AppServerCommand {
-                    port: config.server.port,
-                    host: config.server.host.clone(),
-                };
+    /// Kill the server if it hasn't shut down within the timeout
+    #[arg(long)]
+    pub force: bool,
+}
 
-                container.cli_service().start_server(app_command).await?;
-                println!("{}", "Server stop command sent!".bright_green().bold());
-            }
+#[derive(Args)]
+pub struct RestartArgs {
+    #[command(flatten)]
+    pub stop: StopArgs,
 
-            return Ok(());
-        }
+    #[command(flatten)]
+    pub launch: LaunchArgs,
+}
 
-        // If no flags specified, show help
-        println!("{}", "Server Management Commands:".bright_blue().bold());
-        println!("  {} - Start server locally", "--start".green());
-        println!("  {} - Start server in background", "--start-local".green());
-        println!("  {} - Check server status", "--status".yellow());
-        println!("  {} - Stop server", "--stop".red());
-        println!();
-        println!(
-            "{} {}",
-            "Example:".bright_blue().bold(),
-            "mdx server --start".cyan()
-        );
+#[derive(Args)]
+pub struct InstallServiceArgs {
+    /// doc-indexer binary to run (defaults to the one `start` would use)
+    #[arg(long)]
+    pub binary: Option<String>,
+
+    /// Print the service definition instead of installing it
+    #[arg(long)]
+    pub print: bool,
 
-        Ok(())
+    /// Install the service without starting it
+    #[arg(long)]
+    pub no_start: bool,
+
+    #[command(flatten)]
+    pub launch: LaunchArgs,
+}
+
+#[derive(Args)]
+pub struct LogsArgs {
+    /// Keep printing new lines as they are written
+    #[arg(short, long)]
+    pub follow: bool,
+
+    /// Number of lines to show
+    #[arg(short = 'n', long, default_value = "50")]
+    pub lines: usize,
+}
+
+impl ServerCommand {
+    /// Execute the server command.
+    ///
+    /// Lifecycle operations act on the local process through its PID file;
+    /// `status` additionally asks the API for the server's health.
+    pub async fn execute(&self, container: &CliServiceContainer) -> ZeroLatencyResult<()> {
+        let files = ServerFiles::default_location()?;
+
+        match &self.action {
+            ServerAction::Start(args) => {
+                let config = args.launch.load_effective_config()?;
+                if args.daemon {
+                    start_daemon(&files, &config).await
+                } else {
+                    start_foreground(&files, &config)
+                }
+            }
+            ServerAction::Stop(args) => {
+                if !stop(&files, args).await? {
+                    println!("{}", "Server is not running".yellow());
+                }
+                Ok(())
+            }
+            ServerAction::Restart(args) => {
+                let config = args.launch.load_effective_config()?;
+                stop(&files, &args.stop).await?;
+                start_daemon(&files, &config).await
+            }
+            ServerAction::Status => status(&files, container).await,
+            ServerAction::Logs(args) => logs(&files, args).await,
+            ServerAction::InstallService(args) => install_service(&files, args),
+            ServerAction::UninstallService => uninstall_service(),
+        }
     }
+}
 
+impl LaunchArgs {
     /// Load effective configuration with CLI argument overrides
     fn load_effective_config(&self) -> ZeroLatencyResult<AppConfig> {
         use zero_latency_config::load_config_from_file;
@@ -138,103 +181,404 @@ impl ServerCommand {
 
         Ok(config)
     }
+}
 
-    /// Start the doc-indexer server directly by spawning the process
-    async fn start_server_directly(&self, config: &AppConfig) -> ZeroLatencyResult<()> {
-        println!("{}", "Starting doc-indexer server...".bright_blue().bold());
+/// Run the server attached to the terminal until it exits
+fn start_foreground(files: &ServerFiles, config: &AppConfig) -> ZeroLatencyResult<()> {
+    if let Some(pid) = files.running_pid()? {
+        return Err(already_running(pid));
+    }
 
-        // Find the doc-indexer binary
-        let binary_path = self.find_doc_indexer_binary()?;
+    println!("{}", "Starting doc-indexer server...".bright_blue().bold());
+    println!(
+        "{}",
+        "Starting server in foreground (Ctrl+C to stop)...".yellow()
+    );
+    println!("Server will run on: {}", server_url(config).cyan());
+
+    let status = server_command(config)?
+        .status()
+        .map_err(|e| ZeroLatencyError::Configuration {
+            message: format!("Failed to start server: {}", e),
+        })?;
+
+    if !status.success() {
+        return Err(ZeroLatencyError::Configuration {
+            message: format!("Server exited with code: {:?}", status.code()),
+        });
+    }
 
-        println!("Using binary: {}", binary_path.bright_cyan());
+    Ok(())
+}
 
-        // Build command arguments using configuration
-        let mut args = vec!["--port".to_string(), config.server.port.to_string()];
+/// Spawn the server detached, record its PID and wait until it is healthy
+async fn start_daemon(files: &ServerFiles, config: &AppConfig) -> ZeroLatencyResult<()> {
+    if let Some(pid) = files.running_pid()? {
+        return Err(already_running(pid));
+    }
 
-        if let Some(docs_path) = &config.server.docs_path {
-            args.push("--docs".to_string());
-            args.push(docs_path.clone());
+    println!(
+        "{}",
+        "Starting doc-indexer server in the background..."
+            .bright_blue()
+            .bold()
+    );
+    files.ensure_dir()?;
+
+    let mut log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&files.log_file)
+        .map_err(|e| {
+            ZeroLatencyError::io(format!(
+                "Failed to open {}: {}",
+                files.log_file.display(),
+                e
+            ))
+        })?;
+    let _ = writeln!(
+        log,
+        "--- mdx server start {} ---",
+        chrono::Utc::now().to_rfc3339()
+    );
+    let stderr = log
+        .try_clone()
+        .map_err(|e| ZeroLatencyError::io(format!("Failed to open log file: {}", e)))?;
+
+    let mut command = server_command(config)?;
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(log))
+        .stderr(Stdio::from(stderr));
+    // A separate process group keeps terminal signals such as Ctrl+C away from the server
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| ZeroLatencyError::Configuration {
+            message: format!("Failed to start server: {}", e),
+        })?;
+    files.write_pid(child.id())?;
+
+    if let Err(e) = wait_until_healthy(&mut child, config).await {
+        files.remove_pid();
+        eprintln!(
+            "See the server log: {}",
+            files.log_file.display().to_string().cyan()
+        );
+        return Err(e);
+    }
+
+    println!(
+        "Server started with PID: {}",
+        child.id().to_string().green()
+    );
+    println!("Server running on: {}", server_url(config).cyan());
+    println!("Logs: {}", files.log_file.display().to_string().cyan());
+    Ok(())
+}
+
+/// Poll the health endpoint until it answers or the process exits
+async fn wait_until_healthy(child: &mut Child, config: &AppConfig) -> ZeroLatencyResult<()> {
+    let health_url = format!("{}/health", server_url(config));
+    let client = reqwest::Client::builder()
+        .timeout(POLL_INTERVAL * 4)
+        .build()
+        .map_err(|e| ZeroLatencyError::Configuration {
+            message: format!("Failed to create HTTP client: {}", e),
+        })?;
+
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|e| ZeroLatencyError::io(format!("Failed to check server process: {}", e)))?
+        {
+            return Err(ZeroLatencyError::Configuration {
+                message: format!(
+                    "Server exited during startup with code: {:?}",
+                    status.code()
+                ),
+            });
         }
 
-        // Start the server
-        if self.start_local {
-            // Background mode
-            println!("{}", "Starting server in background...".yellow());
-
-            let child = Command::new(&binary_path)
-                .args(&args)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .stdin(Stdio::null())
-                .spawn()
-                .map_err(|e| ZeroLatencyError::Configuration {
-                    message: format!("Failed to start server: {}", e),
-                })?;
+        if let Ok(response) = client.get(&health_url).send().await {
+            if response.status().is_success() {
+                return Ok(());
+            }
+        }
 
-            println!(
-                "Server started with PID: {}",
-                child.id().to_string().green()
-            );
-            println!(
-                "Server running on: {}",
-                format!("http://{}:{}", config.server.host, config.server.port).cyan()
-            );
-        } else {
-            // Foreground mode
-            println!(
-                "{}",
-                "Starting server in foreground (Ctrl+C to stop)...".yellow()
-            );
-            println!(
-                "Server will run on: {}",
-                format!("http://{}:{}", config.server.host, config.server.port).cyan()
-            );
+        if started.elapsed() > STARTUP_TIMEOUT {
+            return Err(ZeroLatencyError::Network {
+                message: format!(
+                    "Server did not become healthy within {}s (PID {} is still running)",
+                    STARTUP_TIMEOUT.as_secs(),
+                    child.id()
+                ),
+            });
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Stop the background server, returning whether one was running
+async fn stop(files: &ServerFiles, args: &StopArgs) -> ZeroLatencyResult<bool> {
+    let Some(pid) = files.running_pid()? else {
+        return Ok(false);
+    };
 
-            let status = Command::new(&binary_path)
-                .args(&args)
-                .status()
-                .map_err(|e| ZeroLatencyError::Configuration {
-                    message: format!("Failed to start server: {}", e),
-                })?;
+    println!("Stopping server (PID {})...", pid.to_string().yellow());
+    daemon::terminate(pid)?;
 
-            if !status.success() {
+    let deadline = Instant::now() + Duration::from_secs(args.timeout);
+    while daemon::is_alive(pid) {
+        if Instant::now() >= deadline {
+            if !args.force {
                 return Err(ZeroLatencyError::Configuration {
-                    message: format!("Server exited with code: {:?}", status.code()),
+                    message: format!(
+                        "Server (PID {}) did not stop within {}s; use --force to kill it",
+                        pid, args.timeout
+                    ),
                 });
             }
+            println!("{}", "Graceful shutdown timed out, killing server".red());
+            daemon::kill(pid)?;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    files.remove_pid();
+    println!("{}", "Server stopped".bright_green().bold());
+    Ok(true)
+}
+
+async fn status(files: &ServerFiles, container: &CliServiceContainer) -> ZeroLatencyResult<()> {
+    use crate::application::services::cli_service::StatusCommand;
+
+    match files.running_pid()? {
+        Some(pid) => println!(
+            "Background server running with PID: {}",
+            pid.to_string().green()
+        ),
+        None => println!("{}", "No background server started by mdx".yellow()),
+    }
+
+    // The server may also run in the foreground or be managed elsewhere
+    match container
+        .cli_service()
+        .status(StatusCommand { verbose: false })
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(ZeroLatencyError::Network { .. }) => {
+            println!("{}", "Server is not reachable".red().bold());
+            println!(
+                "{} Start the server with: {}",
+                "Tip:".yellow(),
+                "mdx server start --daemon".cyan()
+            );
+            Ok(())
         }
+        Err(e) => Err(e),
+    }
+}
 
-        Ok(())
+async fn logs(files: &ServerFiles, args: &LogsArgs) -> ZeroLatencyResult<()> {
+    if !files.log_file.exists() {
+        println!(
+            "{} No server log yet at {}",
+            "Tip:".yellow(),
+            files.log_file.display()
+        );
+        return Ok(());
     }
 
-    /// Find the doc-indexer binary in expected locations
-    fn find_doc_indexer_binary(&self) -> ZeroLatencyResult<String> {
-        let local_paths = vec![
-            "./target/release/doc-indexer",
-            "./target/debug/doc-indexer",
-            "./services/doc-indexer/target/release/doc-indexer",
-            "./services/doc-indexer/target/debug/doc-indexer",
-        ];
+    for line in daemon::tail_lines(&files.log_file, args.lines)? {
+        println!("{}", line);
+    }
+    if !args.follow {
+        return Ok(());
+    }
 
-        // Check local paths first
-        for path in local_paths {
-            if std::path::Path::new(path).exists() {
-                return Ok(path.to_string());
-            }
+    let read_error =
+        |e: std::io::Error| ZeroLatencyError::io(format!("Failed to read server log: {}", e));
+    let mut file = std::fs::File::open(&files.log_file).map_err(read_error)?;
+    let mut position = file.seek(SeekFrom::End(0)).map_err(read_error)?;
+    let mut stdout = std::io::stdout();
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
         }
 
-        // Check if doc-indexer is available in PATH by trying to run it with --help
-        if let Ok(output) = std::process::Command::new("doc-indexer")
-            .arg("--help")
-            .output()
-        {
-            if output.status.success() {
-                return Ok("doc-indexer".to_string());
-            }
+        let len = file.metadata().map_err(read_error)?.len();
+        if len < position {
+            // The log was truncated or replaced; start over from its beginning
+            position = file.seek(SeekFrom::Start(0)).map_err(read_error)?;
         }
+        let mut new_content = Vec::new();
+        position += file.read_to_end(&mut new_content).map_err(read_error)? as u64;
+        stdout.write_all(&new_content).map_err(read_error)?;
+        stdout.flush().map_err(read_error)?;
+    }
+}
 
-        Err(ZeroLatencyError::Configuration {
-            message: "Could not find doc-indexer binary. Please build the project first with 'cargo build --release'".to_string()
+/// Write the user service definition and start the service
+fn install_service(files: &ServerFiles, args: &InstallServiceArgs) -> ZeroLatencyResult<()> {
+    let manager = ServiceManager::detect()?;
+    let config = args.launch.load_effective_config()?;
+
+    let binary = match &args.binary {
+        Some(binary) => binary.clone(),
+        None => find_doc_indexer_binary()?,
+    };
+    // Services don't run from the current directory, so every path must be absolute
+    let absolute = |path: &str| {
+        std::fs::canonicalize(path).map_err(|e| ZeroLatencyError::Configuration {
+            message: format!("Failed to resolve {}: {}", path, e),
         })
+    };
+    let mut service_args = vec!["--port".to_string(), config.server.port.to_string()];
+    if let Some(config_file) = &args.launch.config_file {
+        service_args.push("--config".to_string());
+        service_args.push(absolute(config_file)?.display().to_string());
+    }
+    if let Some(docs_path) = &config.server.docs_path {
+        service_args.push("--docs-path".to_string());
+        service_args.push(absolute(docs_path)?.display().to_string());
     }
+    let spec = ServiceSpec {
+        binary: service::resolve_executable(&binary)?,
+        args: service_args,
+        log_file: files.log_file.clone(),
+    };
+    let definition = manager.render(&spec);
+
+    if args.print {
+        print!("{}", definition);
+        return Ok(());
+    }
+
+    if let Some(pid) = files.running_pid()? {
+        println!(
+            "{} A background server is running with PID {}; stop it with {} so the service can use the port",
+            "Warning:".yellow(),
+            pid,
+            "mdx server stop".cyan()
+        );
+    }
+
+    let path = manager.definition_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            ZeroLatencyError::io(format!("Failed to create {}: {}", parent.display(), e))
+        })?;
+    }
+    files.ensure_dir()?;
+    std::fs::write(&path, definition)
+        .map_err(|e| ZeroLatencyError::io(format!("Failed to write {}: {}", path.display(), e)))?;
+    println!(
+        "Service definition written to: {}",
+        path.display().to_string().cyan()
+    );
+
+    if args.no_start {
+        return Ok(());
+    }
+    for command in manager.activate_commands(&path) {
+        service::run(&command)?;
+    }
+    println!("{}", "Service installed and started".bright_green().bold());
+    println!("Logs: {}", files.log_file.display().to_string().cyan());
+    Ok(())
+}
+
+/// Stop the user service and remove its definition
+fn uninstall_service() -> ZeroLatencyResult<()> {
+    let manager = ServiceManager::detect()?;
+    let path = manager.definition_path()?;
+    if !path.exists() {
+        println!("{}", "Service is not installed".yellow());
+        return Ok(());
+    }
+
+    // A service that was installed with --no-start may not be loaded
+    for command in manager.deactivate_commands(&path) {
+        if let Err(e) = service::run(&command) {
+            println!("{} {}", "Warning:".yellow(), e);
+        }
+    }
+    std::fs::remove_file(&path)
+        .map_err(|e| ZeroLatencyError::io(format!("Failed to remove {}: {}", path.display(), e)))?;
+    for command in manager.cleanup_commands() {
+        service::run(&command)?;
+    }
+
+    println!("{}", "Service uninstalled".bright_green().bold());
+    Ok(())
+}
+
+/// Command that runs doc-indexer with the configured port and docs path
+fn server_command(config: &AppConfig) -> ZeroLatencyResult<Command> {
+    let binary_path = find_doc_indexer_binary()?;
+    println!("Using binary: {}", binary_path.bright_cyan());
+
+    let mut command = Command::new(&binary_path);
+    command.args(["--port", &config.server.port.to_string()]);
+    if let Some(docs_path) = &config.server.docs_path {
+        command.args(["--docs-path", docs_path]);
+    }
+    Ok(command)
+}
+
+/// Base URL the local server answers on
+fn server_url(config: &AppConfig) -> String {
+    // A server listening on all interfaces is reached through loopback
+    let host = match config.server.host.as_str() {
+        "0.0.0.0" | "::" => "127.0.0.1",
+        host => host,
+    };
+    format!("http://{}:{}", host, config.server.port)
+}
+
+fn already_running(pid: u32) -> ZeroLatencyError {
+    ZeroLatencyError::Configuration {
+        message: format!(
+            "Server is already running with PID {}; stop it with 'mdx server stop'",
+            pid
+        ),
+    }
+}
+
+/// Find the doc-indexer binary in expected locations
+fn find_doc_indexer_binary() -> ZeroLatencyResult<String> {
+    let local_paths = vec![
+        "./target/release/doc-indexer",
+        "./target/debug/doc-indexer",
+        "./services/doc-indexer/target/release/doc-indexer",
+        "./services/doc-indexer/target/debug/doc-indexer",
+    ];
+
+    // Check local paths first
+    for path in local_paths {
+        if std::path::Path::new(path).exists() {
+            return Ok(path.to_string());
+        }
+    }
+
+    // Check if doc-indexer is available in PATH by trying to run it with --help
+    if let Ok(output) = std::process::Command::new("doc-indexer")
+        .arg("--help")
+        .output()
+    {
+        if output.status.success() {
+            return Ok("doc-indexer".to_string());
+        }
+    }
+
+    Err(ZeroLatencyError::Configuration {
+        message: "Could not find doc-indexer binary. Please build the project first with 'cargo build --release'".to_string()
+    })
 }
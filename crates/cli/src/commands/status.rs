@@ -28,10 +28,29 @@ impl StatusCommand {
     pub async fn execute(&self, container: &CliServiceContainer) -> ZeroLatencyResult<()> {
         println!("{}", "Checking system status...".bright_blue().bold());
 
-        let app_command = AppStatusCommand {};
+        let app_command = AppStatusCommand {
+            verbose: container.config().verbose,
+        };
 
         container.cli_service().status(app_command).await?;
 
+        if let Some(collection) = &self.collection {
+            let response = container
+                .collection_client()
+                .get_collection_stats(collection)
+                .await?;
+            println!();
+            match response.health {
+                Some(health) => {
+                    container
+                        .output_formatter()
+                        .format_collection_health(&health)
+                        .await?
+                }
+                None => println!("Collection '{}' has no indexed vectors", collection),
+            }
+        }
+
         println!("{}", "Status check completed!".bright_green().bold());
 
         Ok(())
@@ -4,3 +4,4 @@
 //! functionality for the CLI application.
 
 pub mod config_loader;
+pub mod project;
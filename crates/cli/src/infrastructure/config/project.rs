@@ -0,0 +1,204 @@
+//! Project-local configuration.
+//!
+//! `mdx init` writes a `.mdx.toml` file at the root of a repository. Commands
+//! run anywhere below that root discover the nearest project file and use
+//! its collection, docs paths and ignore rules, so each project gets its own
+//! index without editing the global configuration.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
+
+/// Name of the project file
+pub const PROJECT_FILE: &str = ".mdx.toml";
+
+/// Contents of a `.mdx.toml` file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    /// Collection the project is indexed into and searched from
+    pub collection: String,
+
+    /// Directories to index, relative to the project root
+    #[serde(default)]
+    pub docs: Vec<String>,
+
+    /// Only index files matching these glob patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+
+    /// Skip files matching these glob patterns
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+/// A discovered project
+#[derive(Debug, Clone)]
+pub struct Project {
+    /// Directory containing the project file
+    pub root: PathBuf,
+    pub config: ProjectConfig,
+}
+
+impl Project {
+    /// Find the nearest project file in `start` or one of its ancestors
+    pub fn discover(start: &Path) -> ZeroLatencyResult<Option<Self>> {
+        for dir in start.ancestors() {
+            let file = dir.join(PROJECT_FILE);
+            if file.is_file() {
+                return Self::load(&file).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Load a project file
+    pub fn load(file: &Path) -> ZeroLatencyResult<Self> {
+        let content = std::fs::read_to_string(file).map_err(|e| {
+            ZeroLatencyError::configuration(format!("Failed to read {}: {}", file.display(), e))
+        })?;
+        let config: ProjectConfig = toml::from_str(&content).map_err(|e| {
+            ZeroLatencyError::configuration(format!(
+                "Invalid project file {}: {}",
+                file.display(),
+                e
+            ))
+        })?;
+        if config.collection.trim().is_empty() {
+            return Err(ZeroLatencyError::configuration(format!(
+                "Project file {} does not name a collection",
+                file.display()
+            )));
+        }
+
+        Ok(Self {
+            root: file.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            config,
+        })
+    }
+
+    /// Write a new project file into `root`
+    pub fn create(root: &Path, config: ProjectConfig) -> ZeroLatencyResult<Self> {
+        let file = root.join(PROJECT_FILE);
+        let content = toml::to_string_pretty(&config).map_err(|e| {
+            ZeroLatencyError::configuration(format!("Failed to serialize project file: {}", e))
+        })?;
+        std::fs::write(
+            &file,
+            format!(
+                "# mdx project settings; commands run below this directory use them\n{}",
+                content
+            ),
+        )
+        .map_err(|e| {
+            ZeroLatencyError::configuration(format!("Failed to write {}: {}", file.display(), e))
+        })?;
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            config,
+        })
+    }
+
+    /// Docs directories resolved against the project root
+    pub fn docs_paths(&self) -> Vec<PathBuf> {
+        self.config
+            .docs
+            .iter()
+            .map(|docs| self.root.join(docs))
+            .collect()
+    }
+}
+
+/// Directory a new project file belongs in: the enclosing git repository's
+/// root, or `start` itself outside a repository
+pub fn project_root(start: &Path) -> PathBuf {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(start)
+        .to_path_buf()
+}
+
+/// Collection name derived from a directory name
+///
+/// Collection names may only contain letters, digits, `_` and `-`.
+pub fn collection_name_for(root: &Path) -> String {
+    let name: String = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_matches('_');
+    if name.is_empty() {
+        "project".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ProjectConfig {
+        ProjectConfig {
+            collection: "my_repo".to_string(),
+            docs: vec!["docs".to_string(), "README.md".to_string()],
+            include: Vec::new(),
+            ignore: vec!["*.tmp".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_discover_nearest_project_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("src/deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert!(Project::discover(&nested).unwrap().is_none());
+
+        Project::create(dir.path(), config()).unwrap();
+        let project = Project::discover(&nested).unwrap().unwrap();
+        assert_eq!(project.root, dir.path());
+        assert_eq!(project.config, config());
+        assert_eq!(
+            project.docs_paths(),
+            vec![dir.path().join("docs"), dir.path().join("README.md")]
+        );
+    }
+
+    #[test]
+    fn test_project_file_requires_collection() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(PROJECT_FILE), "collection = \"\"\n").unwrap();
+        assert!(Project::discover(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_project_root_prefers_git_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("crates/cli");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(project_root(&nested), nested);
+
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        assert_eq!(project_root(&nested), dir.path());
+    }
+
+    #[test]
+    fn test_collection_name_for() {
+        assert_eq!(collection_name_for(Path::new("/src/My Repo")), "my_repo");
+        assert_eq!(
+            collection_name_for(Path::new("/src/doc-search")),
+            "doc-search"
+        );
+        assert_eq!(collection_name_for(Path::new("/")), "project");
+    }
+}
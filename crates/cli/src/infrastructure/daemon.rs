@@ -0,0 +1,231 @@
+//! Background server process management.
+//!
+//! A server started with `mdx server start --daemon` runs detached from the
+//! terminal. Its PID is recorded in a PID file and its output goes to a log
+//! file, both in the user's local data directory, so later `stop`, `restart`
+//! and `logs` invocations can find it.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
+
+/// Largest tail of the log file scanned when printing the last lines
+const MAX_TAIL_BYTES: u64 = 1024 * 1024;
+
+/// Locations of the PID and log files of a background server
+#[derive(Debug, Clone)]
+pub struct ServerFiles {
+    pub pid_file: PathBuf,
+    pub log_file: PathBuf,
+}
+
+impl ServerFiles {
+    /// Files in the user's local data directory (e.g. `~/.local/share/mdx`)
+    pub fn default_location() -> ZeroLatencyResult<Self> {
+        let dir = dirs::data_local_dir()
+            .ok_or_else(|| ZeroLatencyError::Configuration {
+                message: "Could not determine the local data directory".to_string(),
+            })?
+            .join("mdx");
+        Ok(Self::in_dir(dir))
+    }
+
+    /// Files in `dir`
+    pub fn in_dir(dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref();
+        Self {
+            pid_file: dir.join("server.pid"),
+            log_file: dir.join("server.log"),
+        }
+    }
+
+    /// Create the directory holding the files
+    pub fn ensure_dir(&self) -> ZeroLatencyResult<()> {
+        if let Some(parent) = self.pid_file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                ZeroLatencyError::io(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// PID recorded in the PID file, if any
+    pub fn read_pid(&self) -> ZeroLatencyResult<Option<u32>> {
+        match std::fs::read_to_string(&self.pid_file) {
+            Ok(content) => {
+                content
+                    .trim()
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| ZeroLatencyError::Configuration {
+                        message: format!("Invalid PID file: {}", self.pid_file.display()),
+                    })
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ZeroLatencyError::io(format!(
+                "Failed to read {}: {}",
+                self.pid_file.display(),
+                e
+            ))),
+        }
+    }
+
+    pub fn write_pid(&self, pid: u32) -> ZeroLatencyResult<()> {
+        self.ensure_dir()?;
+        std::fs::write(&self.pid_file, format!("{}\n", pid)).map_err(|e| {
+            ZeroLatencyError::io(format!(
+                "Failed to write {}: {}",
+                self.pid_file.display(),
+                e
+            ))
+        })
+    }
+
+    pub fn remove_pid(&self) {
+        let _ = std::fs::remove_file(&self.pid_file);
+    }
+
+    /// PID of the background server if it is still running
+    ///
+    /// A PID file left behind by a server that exited without cleaning up
+    /// is removed.
+    pub fn running_pid(&self) -> ZeroLatencyResult<Option<u32>> {
+        match self.read_pid()? {
+            Some(pid) if is_alive(pid) => Ok(Some(pid)),
+            Some(_) => {
+                self.remove_pid();
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Whether a process with this PID exists
+#[cfg(unix)]
+pub fn is_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 only checks that the process exists and may be signalled
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+pub fn is_alive(_pid: u32) -> bool {
+    false
+}
+
+/// Ask a process to shut down gracefully (SIGTERM)
+pub fn terminate(pid: u32) -> ZeroLatencyResult<()> {
+    send_signal(pid, Signal::Terminate)
+}
+
+/// Stop a process immediately (SIGKILL)
+pub fn kill(pid: u32) -> ZeroLatencyResult<()> {
+    send_signal(pid, Signal::Kill)
+}
+
+enum Signal {
+    Terminate,
+    Kill,
+}
+
+#[cfg(unix)]
+fn send_signal(pid: u32, signal: Signal) -> ZeroLatencyResult<()> {
+    let raw_pid = libc::pid_t::try_from(pid).map_err(|_| ZeroLatencyError::Configuration {
+        message: format!("Invalid PID: {}", pid),
+    })?;
+    let raw_signal = match signal {
+        Signal::Terminate => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+    };
+    if unsafe { libc::kill(raw_pid, raw_signal) } == 0 {
+        Ok(())
+    } else {
+        Err(ZeroLatencyError::io(format!(
+            "Failed to signal process {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        )))
+    }
+}
+
+#[cfg(not(unix))]
+fn send_signal(_pid: u32, _signal: Signal) -> ZeroLatencyResult<()> {
+    Err(ZeroLatencyError::Configuration {
+        message: "Managing a background server is only supported on Unix".to_string(),
+    })
+}
+
+/// Last `count` lines of a file
+pub fn tail_lines(path: &Path, count: usize) -> ZeroLatencyResult<Vec<String>> {
+    let mut file = File::open(path)
+        .map_err(|e| ZeroLatencyError::io(format!("Failed to open {}: {}", path.display(), e)))?;
+    let len = file
+        .metadata()
+        .map_err(|e| ZeroLatencyError::io(format!("Failed to read {}: {}", path.display(), e)))?
+        .len();
+    let start = len.saturating_sub(MAX_TAIL_BYTES);
+
+    let mut content = Vec::new();
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.read_to_end(&mut content))
+        .map_err(|e| ZeroLatencyError::io(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    let content = String::from_utf8_lossy(&content);
+    let mut lines: Vec<&str> = content.lines().collect();
+    // The first line is probably cut off when reading from the middle
+    if start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    let skip = lines.len().saturating_sub(count);
+    Ok(lines[skip..].iter().map(|line| line.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = ServerFiles::in_dir(dir.path().join("state"));
+        assert_eq!(files.read_pid().unwrap(), None);
+
+        files.write_pid(std::process::id()).unwrap();
+        assert_eq!(files.read_pid().unwrap(), Some(std::process::id()));
+        assert_eq!(files.running_pid().unwrap(), Some(std::process::id()));
+
+        files.remove_pid();
+        assert_eq!(files.read_pid().unwrap(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stale_pid_file_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = ServerFiles::in_dir(dir.path());
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        files.write_pid(pid).unwrap();
+
+        assert_eq!(files.running_pid().unwrap(), None);
+        assert!(!files.pid_file.exists());
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.log");
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+
+        assert_eq!(tail_lines(&path, 2).unwrap(), vec!["two", "three"]);
+        assert_eq!(tail_lines(&path, 10).unwrap().len(), 3);
+        assert!(tail_lines(&path, 0).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+use super::response_error;
+use crate::commands::blocklist::{BlockEntry, NewBlockEntry};
+use reqwest::Client;
+use std::time::Duration;
+use zero_latency_api::endpoints::{self, urls};
+use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
+
+/// HTTP client for the query-time blocklist API.
+pub struct BlocklistApiClient {
+    client: Client,
+    base_url: String,
+}
+
+impl BlocklistApiClient {
+    /// Creates a new blocklist API client.
+    ///
+    /// # Arguments
+    /// * `base_url` - The base URL of the Zero Latency API
+    /// * `timeout` - Request timeout duration
+    pub fn new(base_url: String, timeout: Duration) -> ZeroLatencyResult<Self> {
+        let client = Client::builder().timeout(timeout).build().map_err(|e| {
+            ZeroLatencyError::Configuration {
+                message: format!("Failed to create HTTP client: {}", e),
+            }
+        })?;
+
+        Ok(Self { client, base_url })
+    }
+
+    /// List blocklist entries, optionally of one collection
+    pub async fn list(&self, collection: Option<&str>) -> ZeroLatencyResult<Vec<BlockEntry>> {
+        let url = format!("{}{}", self.base_url, endpoints::endpoints::BLOCKLIST);
+        let mut request = self.client.get(&url);
+        if let Some(collection) = collection {
+            request = request.query(&[("collection", collection)]);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ZeroLatencyError::Network {
+                message: format!("List blocklist request failed: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(response_error(
+                "blocklist_api",
+                "List blocklist request",
+                &response,
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ZeroLatencyError::Serialization {
+                message: format!("Failed to parse blocklist response: {}", e),
+            })
+    }
+
+    /// Block a path pattern or document
+    pub async fn add(&self, entry: NewBlockEntry) -> ZeroLatencyResult<BlockEntry> {
+        let url = format!("{}{}", self.base_url, endpoints::endpoints::BLOCKLIST);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&entry)
+            .send()
+            .await
+            .map_err(|e| ZeroLatencyError::Network {
+                message: format!("Create blocklist entry request failed: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(response_error(
+                "blocklist_api",
+                "Create blocklist entry request",
+                &response,
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ZeroLatencyError::Serialization {
+                message: format!("Failed to parse blocklist entry response: {}", e),
+            })
+    }
+
+    /// Unblock an entry
+    pub async fn remove(&self, id: &str) -> ZeroLatencyResult<()> {
+        let url = urls::blocklist_entry_by_id(&self.base_url, id);
+
+        let response =
+            self.client
+                .delete(&url)
+                .send()
+                .await
+                .map_err(|e| ZeroLatencyError::Network {
+                    message: format!("Delete blocklist entry request failed: {}", e),
+                })?;
+
+        if !response.status().is_success() {
+            return Err(response_error(
+                "blocklist_api",
+                "Delete blocklist entry request",
+                &response,
+            ));
+        }
+        Ok(())
+    }
+}
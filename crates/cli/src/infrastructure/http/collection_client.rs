@@ -1,6 +1,7 @@
+use super::response_error;
 use crate::commands::collection::{
-    CollectionInfo, CreateCollectionRequest, CreateCollectionResponse, DeleteCollectionResponse,
-    GetCollectionResponse, GetCollectionStatsResponse,
+    ChunkSample, CollectionInfo, CreateCollectionRequest, CreateCollectionResponse,
+    DeleteCollectionResponse, GetCollectionResponse, GetCollectionStatsResponse,
 };
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -53,10 +54,11 @@ impl CollectionApiClient {
                 })?;
 
         if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "collection_api".to_string(),
-                message: format!("List collections request failed: {}", response.status()),
-            });
+            return Err(response_error(
+                "collection_api",
+                "List collections request",
+                &response,
+            ));
         }
 
         let response_wrapper: ListCollectionsApiResponse =
@@ -84,10 +86,11 @@ impl CollectionApiClient {
                 })?;
 
         if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "collection_api".to_string(),
-                message: format!("Get collection request failed: {}", response.status()),
-            });
+            return Err(response_error(
+                "collection_api",
+                "Get collection request",
+                &response,
+            ));
         }
 
         let collection_response: GetCollectionResponse =
@@ -119,10 +122,11 @@ impl CollectionApiClient {
             })?;
 
         if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "collection_api".to_string(),
-                message: format!("Create collection request failed: {}", response.status()),
-            });
+            return Err(response_error(
+                "collection_api",
+                "Create collection request",
+                &response,
+            ));
         }
 
         let create_response: CreateCollectionResponse =
@@ -153,10 +157,11 @@ impl CollectionApiClient {
                 })?;
 
         if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "collection_api".to_string(),
-                message: format!("Delete collection request failed: {}", response.status()),
-            });
+            return Err(response_error(
+                "collection_api",
+                "Delete collection request",
+                &response,
+            ));
         }
 
         let delete_response: DeleteCollectionResponse =
@@ -187,10 +192,11 @@ impl CollectionApiClient {
                 })?;
 
         if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "collection_api".to_string(),
-                message: format!("Get collection stats request failed: {}", response.status()),
-            });
+            return Err(response_error(
+                "collection_api",
+                "Get collection stats request",
+                &response,
+            ));
         }
 
         let stats_response: GetCollectionStatsResponse =
@@ -203,4 +209,47 @@ impl CollectionApiClient {
 
         Ok(stats_response)
     }
+
+    /// Draw random chunks of a collection with their nearest neighbors
+    pub async fn sample_chunks(
+        &self,
+        name: &str,
+        count: usize,
+        neighbors: usize,
+        seed: Option<u64>,
+    ) -> ZeroLatencyResult<ChunkSample> {
+        let url = urls::collection_sample(&self.base_url, name);
+        let mut query = vec![
+            ("n", count.to_string()),
+            ("neighbors", neighbors.to_string()),
+        ];
+        if let Some(seed) = seed {
+            query.push(("seed", seed.to_string()));
+        }
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| ZeroLatencyError::Network {
+                message: format!("Sample chunks request failed: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(response_error(
+                "collection_api",
+                "Sample chunks request",
+                &response,
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ZeroLatencyError::Serialization {
+                message: format!("Failed to parse chunk sample response: {}", e),
+            })
+    }
 }
@@ -0,0 +1,106 @@
+use super::response_error;
+use crate::commands::curation::{Boost, CurationRules, NewBoost, NewPin, Pin};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use zero_latency_api::endpoints::{self, urls};
+use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
+
+/// HTTP client for the search curation API: pinned results and document boosts.
+pub struct CurationApiClient {
+    client: Client,
+    base_url: String,
+}
+
+impl CurationApiClient {
+    /// Creates a new curation API client.
+    ///
+    /// # Arguments
+    /// * `base_url` - The base URL of the Zero Latency API
+    /// * `timeout` - Request timeout duration
+    pub fn new(base_url: String, timeout: Duration) -> ZeroLatencyResult<Self> {
+        let client = Client::builder().timeout(timeout).build().map_err(|e| {
+            ZeroLatencyError::Configuration {
+                message: format!("Failed to create HTTP client: {}", e),
+            }
+        })?;
+
+        Ok(Self { client, base_url })
+    }
+
+    /// List pins and boosts, optionally of one collection
+    pub async fn list(&self, collection: Option<&str>) -> ZeroLatencyResult<CurationRules> {
+        let url = format!("{}{}", self.base_url, endpoints::endpoints::CURATION);
+        let mut request = self.client.get(&url);
+        if let Some(collection) = collection {
+            request = request.query(&[("collection", collection)]);
+        }
+        self.send(request, "List curation request").await
+    }
+
+    /// Pin documents for a query
+    pub async fn add_pin(&self, pin: NewPin) -> ZeroLatencyResult<Pin> {
+        let url = format!("{}{}", self.base_url, endpoints::endpoints::CURATION_PINS);
+        self.send(self.client.post(&url).json(&pin), "Create pin request")
+            .await
+    }
+
+    /// Boost or demote documents
+    pub async fn add_boost(&self, boost: NewBoost) -> ZeroLatencyResult<Boost> {
+        let url = format!("{}{}", self.base_url, endpoints::endpoints::CURATION_BOOSTS);
+        self.send(self.client.post(&url).json(&boost), "Create boost request")
+            .await
+    }
+
+    /// Remove a pin
+    pub async fn remove_pin(&self, id: &str) -> ZeroLatencyResult<()> {
+        let url = urls::curation_pin_by_id(&self.base_url, id);
+        self.delete(&url, "Delete pin request").await
+    }
+
+    /// Remove a boost
+    pub async fn remove_boost(&self, id: &str) -> ZeroLatencyResult<()> {
+        let url = urls::curation_boost_by_id(&self.base_url, id);
+        self.delete(&url, "Delete boost request").await
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        action: &str,
+    ) -> ZeroLatencyResult<T> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ZeroLatencyError::Network {
+                message: format!("{} failed: {}", action, e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(response_error("curation_api", action, &response));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ZeroLatencyError::Serialization {
+                message: format!("Failed to parse response to {}: {}", action, e),
+            })
+    }
+
+    async fn delete(&self, url: &str, action: &str) -> ZeroLatencyResult<()> {
+        let response =
+            self.client
+                .delete(url)
+                .send()
+                .await
+                .map_err(|e| ZeroLatencyError::Network {
+                    message: format!("{} failed: {}", action, e),
+                })?;
+
+        if !response.status().is_success() {
+            return Err(response_error("curation_api", action, &response));
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use super::response_error;
 use crate::commands::document::{GetDocumentResponse, ListDocumentsResponse};
 use reqwest::Client;
 use std::time::Duration;
@@ -61,10 +62,11 @@ impl DocumentApiClient {
             })?;
 
         if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "document_api".to_string(),
-                message: format!("List documents request failed: {}", response.status()),
-            });
+            return Err(response_error(
+                "document_api",
+                "List documents request",
+                &response,
+            ));
         }
 
         let list_response: ListDocumentsResponse =
@@ -93,10 +95,11 @@ impl DocumentApiClient {
             })?;
 
         if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "document_api".to_string(),
-                message: format!("Get document request failed: {}", response.status()),
-            });
+            return Err(response_error(
+                "document_api",
+                "Get document request",
+                &response,
+            ));
         }
 
         let document_response: GetDocumentResponse =
@@ -1,6 +1,10 @@
-use crate::application::services::cli_service::{IndexCommand, IndexResponse, ReindexCommand};
-use reqwest::Client;
+use super::{response_error, Backoff};
+use crate::application::services::cli_service::{
+    IndexCommand, IndexJob, IndexResponse, ReindexCommand,
+};
+use reqwest::{Client, StatusCode};
 use std::time::Duration;
+use zero_latency_api::endpoints::urls;
 use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
 
 /// HTTP client for indexing operations against the Zero Latency API.
@@ -61,37 +65,15 @@ impl IndexApiClient {
             "ignore_patterns": request.ignore_patterns,
             "clear_default_ignores": request.clear_default_ignores,
             "follow_symlinks": request.follow_symlinks,
-            "case_sensitive": request.case_sensitive
+            "case_sensitive": request.case_sensitive,
+            "max_file_size": request.max_file_size,
+            "priority": if request.nice { "low" } else { "normal" },
+            "confirm_cost": request.confirm_cost,
+            "background": true
         });
 
         let url = format!("{}/api/index", self.base_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .json(&json_body)
-            .send()
-            .await
-            .map_err(|e| ZeroLatencyError::Network {
-                message: format!("Index request failed: {}", e),
-            })?;
-
-        if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "index_api".to_string(),
-                message: format!("Index request failed: {}", response.status()),
-            });
-        }
-
-        let index_response: IndexResponse =
-            response
-                .json()
-                .await
-                .map_err(|e| ZeroLatencyError::Serialization {
-                    message: format!("Failed to parse index response: {}", e),
-                })?;
-
-        Ok(index_response)
+        self.submit(&url, &json_body, "index_api", "Index").await
     }
 
     /// Reindex documents
@@ -102,36 +84,102 @@ impl IndexApiClient {
             "ignore_patterns": request.ignore_patterns,
             "clear_default_ignores": request.clear_default_ignores,
             "follow_symlinks": request.follow_symlinks,
-            "case_sensitive": request.case_sensitive
+            "case_sensitive": request.case_sensitive,
+            "max_file_size": request.max_file_size,
+            "priority": if request.nice { "low" } else { "normal" },
+            "confirm_cost": request.confirm_cost,
+            "background": true
         });
 
         let url = format!("{}/api/reindex", self.base_url);
+        self.submit(&url, &json_body, "reindex_api", "Reindex")
+            .await
+    }
 
-        let response = self
-            .client
-            .post(&url)
-            .json(&json_body)
-            .send()
+    /// Resume a failed or interrupted index job from its last checkpoint
+    pub async fn resume(&self, job_id: &str) -> ZeroLatencyResult<IndexResponse> {
+        let url = urls::job_resume(&self.base_url, job_id);
+        self.submit(&url, &serde_json::json!({}), "index_api", "Resume")
             .await
-            .map_err(|e| ZeroLatencyError::Network {
-                message: format!("Reindex request failed: {}", e),
-            })?;
+    }
 
-        if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "reindex_api".to_string(),
-                message: format!("Reindex request failed: {}", response.status()),
-            });
-        }
+    /// Get an index job, or `None` if the server doesn't know it
+    pub async fn get_job(&self, job_id: &str) -> ZeroLatencyResult<Option<IndexJob>> {
+        let url = urls::job_by_id(&self.base_url, job_id);
 
-        let reindex_response: IndexResponse =
-            response
-                .json()
+        let response =
+            self.client
+                .get(&url)
+                .send()
                 .await
-                .map_err(|e| ZeroLatencyError::Serialization {
-                    message: format!("Failed to parse reindex response: {}", e),
+                .map_err(|e| ZeroLatencyError::Network {
+                    message: format!("Job request failed: {}", e),
                 })?;
 
-        Ok(reindex_response)
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(response_error("index_api", "Job request", &response));
+        }
+
+        let job = response
+            .json()
+            .await
+            .map_err(|e| ZeroLatencyError::Serialization {
+                message: format!("Failed to parse job response: {}", e),
+            })?;
+
+        Ok(Some(job))
+    }
+
+    /// Submit an index job, waiting out a server that is restarting
+    ///
+    /// Only failures to connect are retried: such a request never reached
+    /// the server, so it can't have started a job.
+    async fn submit(
+        &self,
+        url: &str,
+        body: &serde_json::Value,
+        service: &str,
+        action: &str,
+    ) -> ZeroLatencyResult<IndexResponse> {
+        let mut backoff = Backoff::default();
+        let response = loop {
+            match self.client.post(url).json(body).send().await {
+                Ok(response) => break response,
+                Err(e) if e.is_connect() => match backoff.next_delay() {
+                    Some(delay) => {
+                        eprintln!("Server unavailable, retrying in {}s...", delay.as_secs());
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => {
+                        return Err(ZeroLatencyError::Network {
+                            message: format!("{} request failed: {}", action, e),
+                        })
+                    }
+                },
+                Err(e) => {
+                    return Err(ZeroLatencyError::Network {
+                        message: format!("{} request failed: {}", action, e),
+                    })
+                }
+            }
+        };
+
+        if !response.status().is_success() {
+            return Err(response_error(
+                service,
+                &format!("{} request", action),
+                &response,
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ZeroLatencyError::Serialization {
+                message: format!("Failed to parse {} response: {}", action.to_lowercase(), e),
+            })
     }
 }
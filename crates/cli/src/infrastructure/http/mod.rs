@@ -1,11 +1,117 @@
+pub mod blocklist_client;
 pub mod collection_client;
+pub mod curation_client;
 pub mod document_client;
 pub mod index_client;
 pub mod search_client;
 pub mod server_client;
 
+pub use blocklist_client::BlocklistApiClient;
 pub use collection_client::CollectionApiClient;
+pub use curation_client::CurationApiClient;
 pub use document_client::DocumentApiClient;
 pub use index_client::IndexApiClient;
 pub use search_client::SearchApiClient;
 pub use server_client::ServerApiClient;
+
+use reqwest::header::HeaderMap;
+use std::time::Duration;
+use zero_latency_core::ZeroLatencyError;
+
+/// Header the server uses to return the ID it assigned to a request
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// First delay before reconnecting to the server
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest delay between reconnection attempts
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// How long to keep reconnecting before giving up
+const RECONNECT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Delays between attempts to reach a server that went away, for example
+/// while it restarts
+///
+/// Doubles from one second up to 30 seconds, and gives up once the delays
+/// add up to five minutes.
+#[derive(Debug, Default)]
+pub struct Backoff {
+    attempts: u32,
+    waited: Duration,
+}
+
+impl Backoff {
+    /// Delay before the next attempt, or `None` to give up
+    pub fn next_delay(&mut self) -> Option<Duration> {
+        if self.waited >= RECONNECT_TIMEOUT {
+            return None;
+        }
+        let delay = (INITIAL_RETRY_DELAY * 2u32.pow(self.attempts.min(5))).min(MAX_RETRY_DELAY);
+        self.attempts += 1;
+        self.waited += delay;
+        Some(delay)
+    }
+
+    /// Start over after the server answered
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Build the error for a non-success API response
+///
+/// Quotes the server's request ID, when present, so it can be matched
+/// against the server logs when reporting a problem.
+pub fn response_error(
+    service: &str,
+    action: &str,
+    response: &reqwest::Response,
+) -> ZeroLatencyError {
+    ZeroLatencyError::ExternalService {
+        service: service.to_string(),
+        message: format!(
+            "{} failed: {}{}",
+            action,
+            response.status(),
+            request_id_suffix(response.headers())
+        ),
+    }
+}
+
+fn request_id_suffix(headers: &HeaderMap) -> String {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|request_id| format!(" (request ID: {})", request_id))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_suffix() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(request_id_suffix(&headers), "");
+
+        headers.insert(REQUEST_ID_HEADER, "3f2b9c4e8d1a".parse().unwrap());
+        assert_eq!(request_id_suffix(&headers), " (request ID: 3f2b9c4e8d1a)");
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::default();
+        let delays: Vec<u64> = (0..7)
+            .map(|_| backoff.next_delay().unwrap().as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 30, 30]);
+
+        while backoff.next_delay().is_some() {}
+        assert!(backoff.waited >= RECONNECT_TIMEOUT);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Some(INITIAL_RETRY_DELAY));
+    }
+}
@@ -1,8 +1,9 @@
+use super::response_error;
 use reqwest::Client;
 use std::time::Duration;
 use zero_latency_core::{values::SearchQuery, Result as ZeroLatencyResult, ZeroLatencyError};
 use zero_latency_search::SearchResponse;
-use zero_latency_api::{SearchRequest, SearchFilters};
+use zero_latency_api::SearchRequest;
 
 /// HTTP client for search operations against the Zero Latency API.
 ///
@@ -40,22 +41,27 @@ impl SearchApiClient {
     }
 
     /// Execute a search query against the API
-    pub async fn search(&self, query: SearchQuery) -> ZeroLatencyResult<SearchResponse> {
+    ///
+    /// Searches the configured collection unless `collections` names others,
+    /// in which case the server searches all of them and merges the results.
+    pub async fn search(
+        &self,
+        query: SearchQuery,
+        collections: &[String],
+    ) -> ZeroLatencyResult<SearchResponse> {
         let url = format!("{}/api/search", self.base_url);
 
-        // Create filters with collection name
-        let filters = SearchFilters {
-            collection_name: Some(self.collection_name.clone()),
-            ..Default::default()
-        };
-
-        // Create comprehensive search request using generated types
-        let search_request = SearchRequest {
-            query: query.effective_query().to_string(),
-            limit: Some(query.limit as i32),
-            filters: Some(Box::new(filters)),
-            ..Default::default()
+        // Search the configured collection unless others are named
+        let collections = if collections.is_empty() {
+            std::slice::from_ref(&self.collection_name)
+        } else {
+            collections
         };
+        let search_request = SearchRequest::builder(query.effective_query())
+            .with_limit(query.limit)
+            .with_collections(collections.iter().cloned())
+            .build()
+            .map_err(|e| ZeroLatencyError::validation(e.field, e.message))?;
 
         let response = self
             .client
@@ -68,10 +74,7 @@ impl SearchApiClient {
             })?;
 
         if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "search_api".to_string(),
-                message: format!("Search request failed: {}", response.status()),
-            });
+            return Err(response_error("search_api", "Search request", &response));
         }
 
         let search_response: SearchResponse =
@@ -1,12 +1,17 @@
+use super::response_error;
 use reqwest::Client;
 use std::time::Duration;
 use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
+use zero_latency_search::StageTiming;
 
 /// Status response structure
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct StatusResponse {
     pub status: String,
     pub version: String,
+    /// HTTP API version, missing from servers that predate version negotiation
+    #[serde(default)]
+    pub api_version: Option<String>,
     pub uptime_seconds: u64,
     pub total_documents: u64,
     pub index_size_bytes: u64,
@@ -14,13 +19,17 @@ pub struct StatusResponse {
     pub docs_path: Option<String>,
 }
 
-/// Server information response
+/// A search the server recorded in its slow query log
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-pub struct ServerInfo {
-    pub host: String,
-    pub port: u16,
-    pub status: String,
-    pub message: String,
+pub struct SlowQuery {
+    pub timestamp: String,
+    pub request_id: Option<String>,
+    pub query: String,
+    pub collection: Option<String>,
+    pub duration_ms: f64,
+    pub result_count: usize,
+    #[serde(default)]
+    pub stages: Vec<StageTiming>,
 }
 
 /// HTTP client for server lifecycle operations against the Zero Latency API.
@@ -62,10 +71,7 @@ impl ServerApiClient {
                 })?;
 
         if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "server_api".to_string(),
-                message: format!("Status request failed: {}", response.status()),
-            });
+            return Err(response_error("server_api", "Status request", &response));
         }
 
         let status_response: StatusResponse =
@@ -79,38 +85,33 @@ impl ServerApiClient {
         Ok(status_response)
     }
 
-    /// Start the server with specified configuration
-    pub async fn start_server(&self, host: String, port: u16) -> ZeroLatencyResult<ServerInfo> {
-        let url = format!("{}/api/server/start", self.base_url);
+    /// Get the most recent slow queries, newest first
+    pub async fn get_slow_queries(&self, limit: usize) -> ZeroLatencyResult<Vec<SlowQuery>> {
+        let url = format!("{}/api/analytics/slow-queries", self.base_url);
 
         let response = self
             .client
-            .post(&url)
-            .json(&serde_json::json!({
-                "host": host,
-                "port": port
-            }))
+            .get(&url)
+            .query(&[("limit", limit)])
             .send()
             .await
             .map_err(|e| ZeroLatencyError::Network {
-                message: format!("Start server request failed: {}", e),
+                message: format!("Slow queries request failed: {}", e),
             })?;
 
         if !response.status().is_success() {
-            return Err(ZeroLatencyError::ExternalService {
-                service: "server_api".to_string(),
-                message: format!("Start server request failed: {}", response.status()),
-            });
+            return Err(response_error(
+                "server_api",
+                "Slow queries request",
+                &response,
+            ));
         }
 
-        let server_info: ServerInfo =
-            response
-                .json()
-                .await
-                .map_err(|e| ZeroLatencyError::Serialization {
-                    message: format!("Failed to parse start server response: {}", e),
-                })?;
-
-        Ok(server_info)
+        response
+            .json()
+            .await
+            .map_err(|e| ZeroLatencyError::Serialization {
+                message: format!("Failed to parse slow queries response: {}", e),
+            })
     }
 }
@@ -4,5 +4,7 @@
 //! including HTTP clients, output formatters, and configuration loaders.
 
 pub mod config;
+pub mod daemon;
 pub mod http;
 pub mod output;
+pub mod service;
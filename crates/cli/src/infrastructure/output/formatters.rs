@@ -1,17 +1,24 @@
 use colored::*;
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, ContentArrangement, Table};
+use serde::Serialize;
 use serde_json;
+use std::collections::HashSet;
+use std::fmt::Write;
 use std::path::Path;
 
 use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
-use zero_latency_search::SearchResponse;
+use zero_latency_search::{OutputTemplate, ResultsContext, SearchResponse, SearchResult};
 
 use crate::application::services::cli_service::IndexResponse;
+use crate::commands::collection::CollectionHealth;
+use crate::infrastructure::http::server_client::SlowQuery;
 
 /// Table-based output formatter for CLI command results.
 ///
 /// This formatter provides clean tabular output for CLI commands,
 /// with support for different output formats and colored text.
+/// Each `format_*` method prints the output of the matching `render_*`
+/// method, which returns the text so it can be tested without a terminal.
 pub struct TableFormatter {}
 
 impl TableFormatter {
@@ -33,41 +40,92 @@ impl TableFormatter {
         table
     }
 
+    /// Helper method to pretty-print a response as JSON
+    fn to_json<T: Serialize>(value: &T) -> ZeroLatencyResult<String> {
+        serde_json::to_string_pretty(value).map_err(|e| ZeroLatencyError::Serialization {
+            message: format!("Failed to serialize response: {}", e),
+        })
+    }
+
     /// Format search results
     pub async fn format_search_results(
         &self,
         response: SearchResponse,
         format: &str,
     ) -> ZeroLatencyResult<()> {
+        print!("{}", self.render_search_results(&response, format)?);
+        Ok(())
+    }
+
+    /// Print search results rendered through a user template
+    pub async fn format_search_template(
+        &self,
+        response: SearchResponse,
+        query: &str,
+        template: &OutputTemplate,
+    ) -> ZeroLatencyResult<()> {
+        print!(
+            "{}",
+            self.render_search_template(&response, query, template)?
+        );
+        Ok(())
+    }
+
+    /// Render search results through a user template
+    pub fn render_search_template(
+        &self,
+        response: &SearchResponse,
+        query: &str,
+        template: &OutputTemplate,
+    ) -> ZeroLatencyResult<String> {
+        template.render(&ResultsContext::new(query, response))
+    }
+
+    /// Render search results
+    pub fn render_search_results(
+        &self,
+        response: &SearchResponse,
+        format: &str,
+    ) -> ZeroLatencyResult<String> {
+        let mut out = String::new();
+        // Results of a federated search are labelled with their collection
+        let collections: HashSet<&str> = response
+            .results
+            .iter()
+            .filter_map(|result| result.collection.as_deref())
+            .collect();
+        let label = |result: &SearchResult, source: String| match &result.collection {
+            Some(collection) if collections.len() > 1 => format!("[{}] {}", collection, source),
+            _ => source,
+        };
+
         match format {
             "json" => {
-                let json = serde_json::to_string_pretty(&response).map_err(|e| {
-                    ZeroLatencyError::Serialization {
-                        message: format!("Failed to serialize response: {}", e),
-                    }
-                })?;
-                println!("{}", json);
+                writeln!(out, "{}", Self::to_json(response)?).unwrap();
             }
             "simple" => {
                 if response.results.is_empty() {
-                    println!("{}", "No results found.".yellow());
+                    writeln!(out, "{}", "No results found.".yellow()).unwrap();
                 } else {
                     for (index, result) in response.results.iter().enumerate() {
                         let score = format!("{:.3}", result.final_score.value());
-                        println!(
+                        writeln!(
+                            out,
                             "{}. {} {}",
                             (index + 1).to_string().bold(),
                             format!("({})", score).dimmed(),
                             result.content.trim()
-                        );
-                        println!("   Source: {}", result.document_path.dimmed());
-                        println!();
+                        )
+                        .unwrap();
+                        let source = label(result, result_location(result));
+                        writeln!(out, "   Source: {}", source.dimmed()).unwrap();
+                        writeln!(out).unwrap();
                     }
                 }
             }
             "table" | _ => {
                 if response.results.is_empty() {
-                    println!("{}", "No results found.".yellow());
+                    writeln!(out, "{}", "No results found.".yellow()).unwrap();
                 } else {
                     let mut table = self.create_table();
                     table.set_header(vec!["#", "Score", "Content", "Source"]);
@@ -75,13 +133,13 @@ impl TableFormatter {
                     for (index, result) in response.results.iter().enumerate() {
                         // Format the score to 3 decimal places
                         let score = format!("{:.3}", result.final_score.value());
-                        let source = if !result.title.is_empty()
-                            && result.title != result.document_path
-                        {
-                            format!("{} ({})", result.title, result.document_path)
-                        } else {
-                            result.document_path.clone()
-                        };
+                        let source =
+                            if !result.title.is_empty() && result.title != result.document_path {
+                                format!("{} ({})", result.title, result_location(result))
+                            } else {
+                                result_location(result)
+                            };
+                        let source = label(result, source);
 
                         table.add_row(vec![
                             (index + 1).to_string(),
@@ -91,77 +149,190 @@ impl TableFormatter {
                         ]);
                     }
 
-                    println!("{}", table);
+                    writeln!(out, "{}", table).unwrap();
                 }
             }
         }
-        Ok(())
+        if format != "json" {
+            if let Some(confidence) = response.confidence {
+                let label = serde_json::to_value(confidence)
+                    .ok()
+                    .and_then(|value| value.as_str().map(str::to_string))
+                    .unwrap_or_default();
+                let hint = if response.answerable == Some(false) {
+                    " (the results may not answer the query)"
+                } else {
+                    ""
+                };
+                writeln!(out, "Confidence: {}{}", label.bold(), hint.dimmed()).unwrap();
+            }
+        }
+        Ok(out)
     }
 
     /// Format index results
     pub async fn format_index_results(&self, response: IndexResponse) -> ZeroLatencyResult<()> {
-        println!("{}", "Indexing completed successfully!".green().bold());
+        print!("{}", self.render_index_results(&response));
+        Ok(())
+    }
+
+    /// Render index results
+    pub fn render_index_results(&self, response: &IndexResponse) -> String {
+        let mut out = String::new();
+        writeln!(out, "{}", "Indexing completed successfully!".green().bold()).unwrap();
 
         let mut table = self.create_table();
         table.add_row(vec![
             "Docs Processed".to_string(),
             response.documents_processed.to_string(),
         ]);
+        table.add_row(vec![
+            "Unchanged (skipped)".to_string(),
+            response.files_skipped.to_string(),
+        ]);
         table.add_row(vec![
             "Time (ms)".to_string(),
             response.processing_time_ms.to_string(),
         ]);
-        table.add_row(vec!["Status".to_string(), response.status]);
+        table.add_row(vec!["Status".to_string(), response.status.clone()]);
 
-        if let Some(message) = response.message {
+        if !response.diagnostics.is_empty() {
+            table.add_row(vec![
+                "Errors".to_string(),
+                response.error_count().to_string(),
+            ]);
+            table.add_row(vec![
+                "Warnings".to_string(),
+                response.warning_count().to_string(),
+            ]);
+        }
+
+        if let Some(message) = &response.message {
             // Truncate long messages to prevent line wrapping
             let truncated_message = if message.len() > 60 {
                 format!("{}...", &message[..57])
             } else {
-                message
+                message.clone()
             };
             table.add_row(vec!["Message".to_string(), truncated_message]);
         }
 
-        println!("{}", table);
+        writeln!(out, "{}", table).unwrap();
+        out
+    }
+
+    /// Format per-file diagnostics from an indexing job
+    ///
+    /// Always prints a one-line summary when diagnostics exist; the full
+    /// table is only printed when `show_errors` is set.
+    pub async fn format_index_diagnostics(
+        &self,
+        response: &IndexResponse,
+        show_errors: bool,
+    ) -> ZeroLatencyResult<()> {
+        print!("{}", self.render_index_diagnostics(response, show_errors));
         Ok(())
     }
 
+    /// Render per-file diagnostics from an indexing job
+    pub fn render_index_diagnostics(&self, response: &IndexResponse, show_errors: bool) -> String {
+        let mut out = String::new();
+        if response.diagnostics.is_empty() {
+            return out;
+        }
+
+        let summary = format!(
+            "{} errors, {} warnings",
+            response.error_count(),
+            response.warning_count()
+        );
+        if response.error_count() > 0 {
+            writeln!(out, "{}", summary.red().bold()).unwrap();
+        } else {
+            writeln!(out, "{}", summary.yellow().bold()).unwrap();
+        }
+
+        if !show_errors {
+            let hint = match &response.job_id {
+                Some(job_id) => format!("Re-run with --show-errors for details (job {})", job_id),
+                None => "Re-run with --show-errors for details".to_string(),
+            };
+            writeln!(out, "{}", hint.dimmed()).unwrap();
+            return out;
+        }
+
+        let mut table = self.create_table();
+        table.set_header(vec!["Severity", "Stage", "Path", "Reason"]);
+
+        for diagnostic in &response.diagnostics {
+            let severity = if diagnostic.severity == "error" {
+                diagnostic.severity.red().to_string()
+            } else {
+                diagnostic.severity.yellow().to_string()
+            };
+            let reason = match &diagnostic.suggestion {
+                Some(suggestion) => format!("{}\n→ {}", diagnostic.reason, suggestion),
+                None => diagnostic.reason.clone(),
+            };
+
+            table.add_row(vec![
+                severity,
+                diagnostic.stage.clone(),
+                diagnostic.path.clone(),
+                reason,
+            ]);
+        }
+
+        writeln!(out, "{}", table).unwrap();
+        out
+    }
+
     /// Format document list
     pub async fn format_document_list(
         &self,
         response: &crate::commands::document::ListDocumentsResponse,
         format: &str,
     ) -> ZeroLatencyResult<()> {
+        print!("{}", self.render_document_list(response, format)?);
+        Ok(())
+    }
+
+    /// Render document list
+    pub fn render_document_list(
+        &self,
+        response: &crate::commands::document::ListDocumentsResponse,
+        format: &str,
+    ) -> ZeroLatencyResult<String> {
+        let mut out = String::new();
         match format {
             "json" => {
-                let json = serde_json::to_string_pretty(response).map_err(|e| {
-                    ZeroLatencyError::Serialization {
-                        message: format!("Failed to serialize response: {}", e),
-                    }
-                })?;
-                println!("{}", json);
+                writeln!(out, "{}", Self::to_json(response)?).unwrap();
             }
             "simple" => {
                 if response.documents.is_empty() {
-                    println!("{}", "No documents found.".yellow());
+                    writeln!(out, "{}", "No documents found.".yellow()).unwrap();
                 } else {
                     for doc in &response.documents {
-                        println!(
+                        writeln!(
+                            out,
                             "{} - {} ({})",
                             doc.id.bright_blue(),
                             doc.title.white(),
                             doc.path.dimmed()
-                        );
+                        )
+                        .unwrap();
                     }
                 }
-                println!(
+                writeln!(
+                    out,
                     "\nTotal: {} documents, {} bytes",
                     response.total_count, response.index_size_bytes
-                );
+                )
+                .unwrap();
             }
             "table" | _ => {
-                println!(
+                writeln!(
+                    out,
                     "{}",
                     format!(
                         "Documents (Page {} of {})",
@@ -169,10 +340,11 @@ impl TableFormatter {
                     )
                     .green()
                     .bold()
-                );
+                )
+                .unwrap();
 
                 if response.documents.is_empty() {
-                    println!("{}", "No documents found.".yellow());
+                    writeln!(out, "{}", "No documents found.".yellow()).unwrap();
                 } else {
                     let mut table = self.create_table();
                     table.set_header(vec!["ID", "Title", "Path", "Size", "Modified"]);
@@ -203,18 +375,20 @@ impl TableFormatter {
                         ]);
                     }
 
-                    println!("{}", table);
+                    writeln!(out, "{}", table).unwrap();
                 }
 
-                println!(
+                writeln!(
+                    out,
                     "\n{} Total: {} documents, {:.2} MB",
                     "".bright_blue(),
                     response.total_count,
                     response.index_size_bytes as f64 / (1024.0 * 1024.0)
-                );
+                )
+                .unwrap();
             }
         }
-        Ok(())
+        Ok(out)
     }
 
     /// Format document detail
@@ -223,40 +397,56 @@ impl TableFormatter {
         response: &crate::commands::document::GetDocumentResponse,
         format: &str,
     ) -> ZeroLatencyResult<()> {
+        print!("{}", self.render_document_detail(response, format)?);
+        Ok(())
+    }
+
+    /// Render document detail
+    pub fn render_document_detail(
+        &self,
+        response: &crate::commands::document::GetDocumentResponse,
+        format: &str,
+    ) -> ZeroLatencyResult<String> {
+        let mut out = String::new();
         if !response.found {
-            println!("Document '{}' not found", response.id.red());
-            return Ok(());
+            writeln!(out, "Document '{}' not found", response.id.red()).unwrap();
+            return Ok(out);
         }
 
         match format {
             "json" => {
-                let json = serde_json::to_string_pretty(response).map_err(|e| {
-                    ZeroLatencyError::Serialization {
-                        message: format!("Failed to serialize response: {}", e),
-                    }
-                })?;
-                println!("{}", json);
+                writeln!(out, "{}", Self::to_json(response)?).unwrap();
             }
             "metadata" => {
-                println!("{}", format!("Document {}", response.id).green().bold());
+                writeln!(
+                    out,
+                    "{}",
+                    format!("Document {}", response.id).green().bold()
+                )
+                .unwrap();
                 if let Some(metadata) = &response.metadata {
                     let formatted = serde_json::to_string_pretty(metadata)
                         .unwrap_or_else(|_| "Invalid metadata".to_string());
-                    println!("{}", formatted);
+                    writeln!(out, "{}", formatted).unwrap();
                 } else {
-                    println!("{}", "No metadata available".yellow());
+                    writeln!(out, "{}", "No metadata available".yellow()).unwrap();
                 }
             }
             "content" | _ => {
-                println!("{}", format!("Document {}", response.id).green().bold());
+                writeln!(
+                    out,
+                    "{}",
+                    format!("Document {}", response.id).green().bold()
+                )
+                .unwrap();
                 if let Some(content) = &response.content {
-                    println!("{}", content);
+                    writeln!(out, "{}", content).unwrap();
                 } else {
-                    println!("{}", "No content available".yellow());
+                    writeln!(out, "{}", "No content available".yellow()).unwrap();
                 }
             }
         }
-        Ok(())
+        Ok(out)
     }
 
     /// Format configuration display
@@ -265,8 +455,19 @@ impl TableFormatter {
         config: &crate::config::CliConfig,
         config_file_path: &Path,
     ) -> ZeroLatencyResult<()> {
-        println!("{}", "Current Configuration".blue().bold());
-        println!();
+        print!("{}", self.render_config(config, config_file_path));
+        Ok(())
+    }
+
+    /// Render configuration display
+    pub fn render_config(
+        &self,
+        config: &crate::config::CliConfig,
+        config_file_path: &Path,
+    ) -> String {
+        let mut out = String::new();
+        writeln!(out, "{}", "Current Configuration".blue().bold()).unwrap();
+        writeln!(out).unwrap();
 
         let mut table = self.create_table();
         table.set_header(vec!["Setting".to_string(), "Value".to_string()]);
@@ -285,13 +486,528 @@ impl TableFormatter {
             vec!["Verbose".to_string(), config.verbose.to_string()],
         ]);
 
-        println!("{}", table);
-        println!();
-        println!(
+        writeln!(out, "{}", table).unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
             "Config file: {}",
             config_file_path.display().to_string().dimmed()
-        );
+        )
+        .unwrap();
+
+        out
+    }
 
+    /// Format a collection's index health score and recommendations
+    pub async fn format_collection_health(
+        &self,
+        health: &CollectionHealth,
+    ) -> ZeroLatencyResult<()> {
+        print!("{}", self.render_collection_health(health));
         Ok(())
     }
+
+    /// Render a collection's index health score, worst findings first
+    pub fn render_collection_health(&self, health: &CollectionHealth) -> String {
+        let mut out = String::new();
+        let score = format!("{}/100 ({})", health.score, health.status);
+        let score = match health.status.as_str() {
+            "healthy" => score.green(),
+            "degraded" => score.yellow(),
+            _ => score.red(),
+        };
+        writeln!(
+            out,
+            "{} {}",
+            format!("Index Health of '{}':", health.collection).blue().bold(),
+            score
+        )
+        .unwrap();
+
+        if health.recommendations.is_empty() {
+            writeln!(out, "{}", "Nothing to fix.".green()).unwrap();
+            return out;
+        }
+
+        let mut table = self.create_table();
+        table.set_header(vec!["Issue", "Affected", "Penalty", "Recommendation"]);
+        for recommendation in &health.recommendations {
+            table.add_row(vec![
+                recommendation.issue.replace('_', " "),
+                recommendation.affected.to_string(),
+                format!("-{}", recommendation.penalty),
+                recommendation.action.clone(),
+            ]);
+        }
+        writeln!(out, "{}", table).unwrap();
+        out
+    }
+
+    /// Format the server's recent slow queries
+    pub async fn format_slow_queries(&self, queries: &[SlowQuery]) -> ZeroLatencyResult<()> {
+        print!("{}", self.render_slow_queries(queries));
+        Ok(())
+    }
+
+    /// Render recent slow queries with their per-stage timings
+    pub fn render_slow_queries(&self, queries: &[SlowQuery]) -> String {
+        let mut out = String::new();
+        writeln!(out, "{}", "Recent Slow Queries".blue().bold()).unwrap();
+
+        if queries.is_empty() {
+            writeln!(out, "{}", "No slow queries recorded.".green()).unwrap();
+            return out;
+        }
+
+        let mut table = self.create_table();
+        table.set_header(vec!["Query", "Collection", "Total", "Stages"]);
+
+        for query in queries {
+            let stages = query
+                .stages
+                .iter()
+                .map(|stage| format!("{} {:.1}ms", stage.stage, stage.duration_ms))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            table.add_row(vec![
+                query.query.clone(),
+                query.collection.clone().unwrap_or_else(|| "-".to_string()),
+                format!("{:.1}ms", query.duration_ms),
+                stages,
+            ]);
+        }
+
+        writeln!(out, "{}", table).unwrap();
+        out
+    }
+}
+
+/// Path of a result, suffixed with the line its chunk starts at when known,
+/// in the `path:line` form editors open
+fn result_location(result: &SearchResult) -> String {
+    match result.lines {
+        Some(lines) => format!("{}:{}", result.document_path, lines.start),
+        None => result.document_path.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::services::cli_service::IndexDiagnostic;
+    use crate::commands::document::{DocumentSummary, GetDocumentResponse, ListDocumentsResponse};
+    use std::time::Duration;
+    use zero_latency_core::{values::SearchQuery, DocId};
+    use zero_latency_search::{
+        ConfidenceLevel, FromSignals, LineRange, NormalizationMethod, ScoreBreakdown,
+        SearchMetadata, StageTiming,
+    };
+
+    /// Snapshot rendered output with colors off and volatile values redacted
+    fn assert_output(name: &str, output: &str) {
+        let mut settings = insta::Settings::clone_current();
+        settings.add_filter(
+            r"\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})",
+            "[timestamp]",
+        );
+        settings.add_filter(
+            r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}",
+            "[uuid]",
+        );
+        settings.add_filter(
+            r#""execution_time": \{[^}]*\}"#,
+            r#""execution_time": "[duration]""#,
+        );
+        settings.bind(|| insta::assert_snapshot!(name, output));
+    }
+
+    fn formatter() -> TableFormatter {
+        colored::control::set_override(false);
+        TableFormatter::new()
+    }
+
+    fn search_result(path: &str, title: &str, content: &str, score: f32) -> SearchResult {
+        let scores = ScoreBreakdown {
+            bm25_raw: None,
+            vector_raw: Some(score),
+            bm25_normalized: None,
+            vector_normalized: Some(score),
+            fused: score,
+            normalization_method: NormalizationMethod::MinMax,
+        };
+
+        SearchResult::new(
+            DocId::new("docs", path, 1),
+            path.to_string(),
+            title.to_string(),
+            content.to_string(),
+            scores,
+            FromSignals::vector_only(),
+        )
+    }
+
+    fn search_response(results: Vec<SearchResult>) -> SearchResponse {
+        SearchResponse {
+            total_count: Some(results.len()),
+            results,
+            search_metadata: SearchMetadata {
+                query: SearchQuery::new("async runtime"),
+                execution_time: Duration::from_millis(12),
+                query_enhancement_applied: false,
+                ranking_method: "vector".to_string(),
+                result_sources: vec!["vector".to_string()],
+                debug_info: None,
+                stage_timings: Vec::new(),
+                effective_settings: None,
+                intent: None,
+            },
+            pagination: None,
+            answer: None,
+            confidence: None,
+            answerable: None,
+            facets: None,
+        }
+    }
+
+    fn index_response(diagnostics: Vec<IndexDiagnostic>) -> IndexResponse {
+        IndexResponse {
+            documents_processed: 42,
+            files_skipped: 3,
+            processing_time_ms: 1234.5,
+            status: "completed".to_string(),
+            message: Some("Indexed 42 documents from /home/user/projects/docs".to_string()),
+            job_id: Some("job-7".to_string()),
+            diagnostics,
+        }
+    }
+
+    fn diagnostics() -> Vec<IndexDiagnostic> {
+        vec![
+            IndexDiagnostic {
+                path: "guides/broken.pdf".to_string(),
+                stage: "parse".to_string(),
+                severity: "error".to_string(),
+                reason: "Unsupported PDF encryption".to_string(),
+                suggestion: Some("Export an unencrypted copy".to_string()),
+            },
+            IndexDiagnostic {
+                path: "notes/empty.md".to_string(),
+                stage: "chunk".to_string(),
+                severity: "warning".to_string(),
+                reason: "Document has no content".to_string(),
+                suggestion: None,
+            },
+        ]
+    }
+
+    fn document_list(documents: Vec<DocumentSummary>) -> ListDocumentsResponse {
+        ListDocumentsResponse {
+            total_count: documents.len() as u64,
+            documents,
+            page: 1,
+            per_page: 20,
+            total_pages: 1,
+            index_size_bytes: 3_145_728,
+        }
+    }
+
+    fn documents() -> Vec<DocumentSummary> {
+        vec![
+            DocumentSummary {
+                id: "3f2b9c4e-8d1a-4f6b-9a7e-2c5d8e1f0a3b".to_string(),
+                title: "Getting Started".to_string(),
+                path: "guides/getting-started.md".to_string(),
+                size: 2048,
+                last_modified: "2025-03-14T09:26:53Z".to_string(),
+            },
+            DocumentSummary {
+                id: "short".to_string(),
+                title: "A very long title that needs truncation".to_string(),
+                path: "reference/api/v2/endpoints/search/advanced-filters.md".to_string(),
+                size: 512,
+                last_modified: "2025-04-01T12:00:00+02:00".to_string(),
+            },
+        ]
+    }
+
+    fn document_detail(found: bool, metadata: bool) -> GetDocumentResponse {
+        GetDocumentResponse {
+            id: "getting-started".to_string(),
+            found,
+            content: Some("# Getting Started\n\nInstall the CLI and run `mdx index`.".to_string()),
+            metadata: metadata.then(|| {
+                serde_json::json!({
+                    "title": "Getting Started",
+                    "indexed_at": "2025-03-14T09:26:53.123Z",
+                })
+            }),
+        }
+    }
+
+    #[test]
+    fn test_search_results_snapshots() {
+        let formatter = formatter();
+        let mut results = vec![
+            search_result(
+                "guides/runtime.md",
+                "Async Runtimes",
+                "  Tokio is an async runtime that schedules futures.  ",
+                0.91234,
+            ),
+            search_result(
+                "notes/scheduling.md",
+                "notes/scheduling.md",
+                "Work stealing moves tasks between worker threads.",
+                0.5,
+            ),
+        ];
+        // The source location shows as path:line
+        results[1].lines = Some(LineRange { start: 12, end: 30 });
+        results[1].anchor = Some("work-stealing".to_string());
+        let response = search_response(results);
+
+        for format in ["table", "json", "simple"] {
+            let output = formatter.render_search_results(&response, format).unwrap();
+            assert_output(&format!("search_results_{}", format), &output);
+        }
+    }
+
+    #[test]
+    fn test_federated_search_results_snapshots() {
+        let formatter = formatter();
+        let mut results = vec![
+            search_result(
+                "runtime.md",
+                "Async Runtimes",
+                "Tokio schedules futures.",
+                1.0,
+            ),
+            search_result(
+                "v2/tasks.md",
+                "Tasks API",
+                "Spawn a task with spawn().",
+                0.8,
+            ),
+        ];
+        results[0].collection = Some("guides".to_string());
+        results[1].collection = Some("api".to_string());
+        let response = search_response(results);
+
+        for format in ["table", "simple"] {
+            let output = formatter.render_search_results(&response, format).unwrap();
+            assert_output(&format!("search_results_federated_{}", format), &output);
+        }
+    }
+
+    #[test]
+    fn test_empty_search_results_snapshots() {
+        let formatter = formatter();
+        let response = search_response(Vec::new());
+
+        for format in ["table", "simple"] {
+            let output = formatter.render_search_results(&response, format).unwrap();
+            assert_output(&format!("search_results_empty_{}", format), &output);
+        }
+    }
+
+    #[test]
+    fn test_search_template_snapshot() {
+        let formatter = formatter();
+        let mut results = vec![
+            search_result(
+                "guides/runtime.md",
+                "Async Runtimes",
+                "Tokio is an async runtime that schedules futures on worker threads.",
+                0.91234,
+            ),
+            search_result(
+                "notes/scheduling.md",
+                "Scheduling",
+                "Work stealing moves tasks between worker threads.",
+                0.5,
+            ),
+        ];
+        results[1].url = Some("https://docs.example.com/scheduling".to_string());
+        let response = search_response(results);
+        let template = OutputTemplate::new(concat!(
+            "## Results for \"{{ query }}\" ({{ total }})\n",
+            "{% for r in results %}",
+            "{{ r.rank }}. [{{ r.title }}]({{ r.url or r.path }}) ",
+            "({{ r.score|round(2) }}): {{ r.snippet|truncate(30) }}\n",
+            "{% endfor %}",
+        ))
+        .unwrap();
+
+        let output = formatter
+            .render_search_template(&response, "async runtime", &template)
+            .unwrap();
+        assert_output("search_results_template", &output);
+    }
+
+    #[test]
+    fn test_search_results_report_confidence() {
+        let formatter = formatter();
+        let mut response = search_response(Vec::new());
+        response.confidence = Some(ConfidenceLevel::Low);
+        response.answerable = Some(false);
+
+        let output = formatter.render_search_results(&response, "table").unwrap();
+        assert!(output.ends_with("Confidence: low (the results may not answer the query)\n"));
+        let output = formatter.render_search_results(&response, "json").unwrap();
+        assert!(!output.contains("Confidence:"));
+    }
+
+    #[test]
+    fn test_index_results_snapshots() {
+        let formatter = formatter();
+
+        assert_output(
+            "index_results",
+            &formatter.render_index_results(&index_response(Vec::new())),
+        );
+        assert_output(
+            "index_results_with_diagnostics",
+            &formatter.render_index_results(&index_response(diagnostics())),
+        );
+    }
+
+    #[test]
+    fn test_index_diagnostics_snapshots() {
+        let formatter = formatter();
+        let response = index_response(diagnostics());
+
+        assert_output(
+            "index_diagnostics_summary",
+            &formatter.render_index_diagnostics(&response, false),
+        );
+        assert_output(
+            "index_diagnostics_table",
+            &formatter.render_index_diagnostics(&response, true),
+        );
+        assert!(formatter
+            .render_index_diagnostics(&index_response(Vec::new()), true)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_document_list_snapshots() {
+        let formatter = formatter();
+        let response = document_list(documents());
+
+        for format in ["table", "json", "simple"] {
+            let output = formatter.render_document_list(&response, format).unwrap();
+            assert_output(&format!("document_list_{}", format), &output);
+        }
+
+        let empty = document_list(Vec::new());
+        assert_output(
+            "document_list_empty_table",
+            &formatter.render_document_list(&empty, "table").unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_document_detail_snapshots() {
+        let formatter = formatter();
+        let response = document_detail(true, true);
+
+        for format in ["content", "metadata", "json"] {
+            let output = formatter.render_document_detail(&response, format).unwrap();
+            assert_output(&format!("document_detail_{}", format), &output);
+        }
+
+        assert_output(
+            "document_detail_no_metadata",
+            &formatter
+                .render_document_detail(&document_detail(true, false), "metadata")
+                .unwrap(),
+        );
+        assert_output(
+            "document_detail_not_found",
+            &formatter
+                .render_document_detail(&document_detail(false, false), "content")
+                .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_config_snapshot() {
+        let formatter = formatter();
+        let config = crate::config::CliConfig {
+            server_url: "http://localhost:8081".to_string(),
+            collection_name: "zero_latency_docs".to_string(),
+            default_limit: 10,
+            output_format: "table".to_string(),
+            verbose: false,
+        };
+
+        assert_output(
+            "config",
+            &formatter.render_config(&config, Path::new("/home/user/.config/mdx/config.toml")),
+        );
+    }
+
+    #[test]
+    fn test_slow_queries_snapshots() {
+        let formatter = formatter();
+        let stage = |stage: &str, duration_ms: f64| StageTiming {
+            stage: stage.to_string(),
+            duration_ms,
+        };
+        let queries = vec![SlowQuery {
+            timestamp: "2024-01-15T09:45:00Z".to_string(),
+            request_id: Some("support-1234".to_string()),
+            query: "async runtime".to_string(),
+            collection: Some("docs".to_string()),
+            duration_ms: 742.5,
+            result_count: 10,
+            stages: vec![
+                stage("embedding", 611.2),
+                stage("vector_search", 98.4),
+                stage("result_ranking", 30.1),
+            ],
+        }];
+
+        assert_output("slow_queries", &formatter.render_slow_queries(&queries));
+        assert_output("slow_queries_empty", &formatter.render_slow_queries(&[]));
+    }
+
+    #[test]
+    fn test_collection_health_snapshots() {
+        use crate::commands::collection::HealthRecommendation;
+
+        let formatter = formatter();
+        let mut health = CollectionHealth {
+            collection: "docs".to_string(),
+            score: 73,
+            status: "degraded".to_string(),
+            signals: serde_json::Value::Null,
+            recommendations: vec![
+                HealthRecommendation {
+                    issue: "stale_vectors".to_string(),
+                    penalty: 25,
+                    affected: 1000,
+                    action: "re-embed 1000 stale vector(s) with POST /api/collections/docs/reembed-stale"
+                        .to_string(),
+                },
+                HealthRecommendation {
+                    issue: "oversized_chunks".to_string(),
+                    penalty: 1,
+                    affected: 40,
+                    action: "re-chunk 12 over-long document(s) with a smaller chunk size"
+                        .to_string(),
+                },
+            ],
+        };
+
+        assert_output("collection_health", &formatter.render_collection_health(&health));
+        health.score = 100;
+        health.status = "healthy".to_string();
+        health.recommendations.clear();
+        assert_output(
+            "collection_health_clean",
+            &formatter.render_collection_health(&health),
+        );
+    }
 }
@@ -0,0 +1,306 @@
+//! User-level service definitions for the doc-indexer.
+//!
+//! `mdx server install-service` registers the server with the platform's
+//! service manager so it starts at login and restarts after failures:
+//! a systemd user unit on Linux and a launchd agent on macOS (which is also
+//! what `brew services` uses).
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
+
+/// Name of the systemd user unit
+pub const SYSTEMD_UNIT_NAME: &str = "mdx-doc-indexer.service";
+
+/// Label of the launchd agent
+pub const LAUNCHD_LABEL: &str = "com.zero-latency.doc-indexer";
+
+/// Service manager the definition is written for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceManager {
+    Systemd,
+    Launchd,
+}
+
+impl ServiceManager {
+    /// Service manager of the current platform
+    pub fn detect() -> ZeroLatencyResult<Self> {
+        if cfg!(target_os = "macos") {
+            Ok(Self::Launchd)
+        } else if cfg!(target_os = "linux") {
+            Ok(Self::Systemd)
+        } else {
+            Err(ZeroLatencyError::Configuration {
+                message:
+                    "Installing a service is only supported on Linux (systemd) and macOS (launchd)"
+                        .to_string(),
+            })
+        }
+    }
+
+    /// Where the user-level definition is installed
+    pub fn definition_path(self) -> ZeroLatencyResult<PathBuf> {
+        let home = dirs::home_dir().ok_or_else(|| ZeroLatencyError::Configuration {
+            message: "Could not determine the home directory".to_string(),
+        })?;
+        Ok(match self {
+            Self::Systemd => dirs::config_dir()
+                .unwrap_or_else(|| home.join(".config"))
+                .join("systemd/user")
+                .join(SYSTEMD_UNIT_NAME),
+            Self::Launchd => home
+                .join("Library/LaunchAgents")
+                .join(format!("{}.plist", LAUNCHD_LABEL)),
+        })
+    }
+
+    /// Render the service definition
+    pub fn render(self, spec: &ServiceSpec) -> String {
+        match self {
+            Self::Systemd => render_systemd_unit(spec),
+            Self::Launchd => render_launchd_plist(spec),
+        }
+    }
+
+    /// Commands that register and start an installed definition
+    pub fn activate_commands(self, path: &Path) -> Vec<Vec<String>> {
+        match self {
+            Self::Systemd => vec![
+                args(["systemctl", "--user", "daemon-reload"]),
+                args(["systemctl", "--user", "enable", "--now", SYSTEMD_UNIT_NAME]),
+            ],
+            Self::Launchd => vec![args([
+                "launchctl",
+                "load",
+                "-w",
+                &path.display().to_string(),
+            ])],
+        }
+    }
+
+    /// Commands that stop and unregister an installed definition
+    pub fn deactivate_commands(self, path: &Path) -> Vec<Vec<String>> {
+        match self {
+            Self::Systemd => vec![args([
+                "systemctl",
+                "--user",
+                "disable",
+                "--now",
+                SYSTEMD_UNIT_NAME,
+            ])],
+            Self::Launchd => vec![args([
+                "launchctl",
+                "unload",
+                "-w",
+                &path.display().to_string(),
+            ])],
+        }
+    }
+
+    /// Commands to run after the definition file is removed
+    pub fn cleanup_commands(self) -> Vec<Vec<String>> {
+        match self {
+            Self::Systemd => vec![args(["systemctl", "--user", "daemon-reload"])],
+            Self::Launchd => Vec::new(),
+        }
+    }
+}
+
+/// What the service runs
+#[derive(Debug, Clone)]
+pub struct ServiceSpec {
+    /// Absolute path of the doc-indexer binary
+    pub binary: PathBuf,
+    pub args: Vec<String>,
+    pub log_file: PathBuf,
+}
+
+impl ServiceSpec {
+    fn command_line(&self) -> Vec<String> {
+        std::iter::once(self.binary.display().to_string())
+            .chain(self.args.iter().cloned())
+            .collect()
+    }
+}
+
+/// Run a service manager command, failing if it exits unsuccessfully
+pub fn run(command: &[String]) -> ZeroLatencyResult<()> {
+    let (program, args) = command.split_first().expect("commands are never empty");
+    let output =
+        Command::new(program)
+            .args(args)
+            .output()
+            .map_err(|e| ZeroLatencyError::Configuration {
+                message: format!("Failed to run {}: {}", program, e),
+            })?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(ZeroLatencyError::Configuration {
+            message: format!(
+                "'{}' failed: {}",
+                command.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        })
+    }
+}
+
+/// Absolute path of an executable, looking it up on `PATH` if it is a bare name
+pub fn resolve_executable(binary: &str) -> ZeroLatencyResult<PathBuf> {
+    let path = Path::new(binary);
+    if path.components().count() > 1 {
+        return std::fs::canonicalize(path).map_err(|e| ZeroLatencyError::Configuration {
+            message: format!("Failed to resolve {}: {}", binary, e),
+        });
+    }
+
+    std::env::var_os("PATH")
+        .iter()
+        .flat_map(std::env::split_paths)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| ZeroLatencyError::Configuration {
+            message: format!("Could not find {} on PATH", binary),
+        })
+}
+
+fn render_systemd_unit(spec: &ServiceSpec) -> String {
+    let exec_start = spec
+        .command_line()
+        .iter()
+        .map(|arg| systemd_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let log_file = spec.log_file.display().to_string();
+
+    format!(
+        "# Installed by mdx server install-service\n\
+         [Unit]\n\
+         Description=Zero-Latency doc-indexer\n\
+         After=network.target\n\
+         \n\
+         [Service]\n\
+         ExecStart={exec_start}\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         StandardOutput=append:{log_file}\n\
+         StandardError=append:{log_file}\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n"
+    )
+}
+
+fn render_launchd_plist(spec: &ServiceSpec) -> String {
+    let arguments: String = spec
+        .command_line()
+        .iter()
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(arg)))
+        .collect();
+    let log_file = xml_escape(&spec.log_file.display().to_string());
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <!-- Installed by mdx server install-service -->\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n\
+         \x20   <key>Label</key>\n\
+         \x20   <string>{LAUNCHD_LABEL}</string>\n\
+         \x20   <key>ProgramArguments</key>\n\
+         \x20   <array>\n\
+         {arguments}\
+         \x20   </array>\n\
+         \x20   <key>RunAtLoad</key>\n\
+         \x20   <true/>\n\
+         \x20   <key>KeepAlive</key>\n\
+         \x20   <dict>\n\
+         \x20       <key>SuccessfulExit</key>\n\
+         \x20       <false/>\n\
+         \x20   </dict>\n\
+         \x20   <key>StandardOutPath</key>\n\
+         \x20   <string>{log_file}</string>\n\
+         \x20   <key>StandardErrorPath</key>\n\
+         \x20   <string>{log_file}</string>\n\
+         </dict>\n\
+         </plist>\n"
+    )
+}
+
+/// Quote an `ExecStart` argument when systemd would otherwise split or expand it
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%").replace('$', "$$");
+    if escaped
+        .chars()
+        .any(|c| c.is_whitespace() || c == '"' || c == '\\' || c == '\'')
+    {
+        format!("\"{}\"", escaped.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        escaped
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn args<const N: usize>(parts: [&str; N]) -> Vec<String> {
+    parts.iter().map(|part| part.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> ServiceSpec {
+        ServiceSpec {
+            binary: PathBuf::from("/opt/zero latency/doc-indexer"),
+            args: vec![
+                "--port".to_string(),
+                "8081".to_string(),
+                "--docs-path".to_string(),
+                "/home/me/docs & notes".to_string(),
+            ],
+            log_file: PathBuf::from("/home/me/.local/share/mdx/server.log"),
+        }
+    }
+
+    #[test]
+    fn test_systemd_unit() {
+        let unit = ServiceManager::Systemd.render(&spec());
+        assert!(unit.contains(
+            "ExecStart=\"/opt/zero latency/doc-indexer\" --port 8081 --docs-path \"/home/me/docs & notes\"\n"
+        ));
+        assert!(unit.contains("StandardOutput=append:/home/me/.local/share/mdx/server.log\n"));
+        assert!(unit.contains("WantedBy=default.target\n"));
+    }
+
+    #[test]
+    fn test_launchd_plist() {
+        let plist = ServiceManager::Launchd.render(&spec());
+        assert!(plist.contains("<string>com.zero-latency.doc-indexer</string>"));
+        assert!(plist.contains("        <string>/opt/zero latency/doc-indexer</string>\n"));
+        assert!(plist.contains("<string>/home/me/docs &amp; notes</string>"));
+        assert!(plist.contains("    <key>RunAtLoad</key>\n    <true/>\n"));
+    }
+
+    #[test]
+    fn test_systemd_quote() {
+        assert_eq!(systemd_quote("--port"), "--port");
+        assert_eq!(systemd_quote("100%"), "100%%");
+        assert_eq!(systemd_quote("a \"b\""), "\"a \\\"b\\\"\"");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_executable() {
+        let resolved = resolve_executable("sh").unwrap();
+        assert!(resolved.is_absolute());
+        assert!(resolve_executable("definitely-not-a-real-binary").is_err());
+    }
+}
@@ -2,6 +2,8 @@ use clap::{Parser, Subcommand};
 use colored::*;
 
 use crate::config::CliConfig;
+use crate::infrastructure::config::project::Project;
+use zero_latency_api::endpoints::version::Compatibility;
 use zero_latency_core::{Result as ZeroLatencyResult, ZeroLatencyError};
 
 // Clean architecture modules
@@ -36,6 +38,10 @@ struct Cli {
     /// Configuration file path (or use 'config' command for advanced management)
     #[arg(long, global = true)]
     config: Option<std::path::PathBuf>,
+
+    /// Don't check that the server's API version is supported before running
+    #[arg(long, global = true)]
+    skip_version_check: bool,
 }
 
 #[derive(Subcommand)]
@@ -46,12 +52,21 @@ enum Commands {
     /// Index documents from a directory
     Index(commands::index::IndexCommand),
 
+    /// Create a .mdx.toml project file in the repository root
+    Init(commands::init::InitCommand),
+
     /// Document discovery operations (list, get)
     Document(commands::document::DocumentCommand),
 
     /// Collection management operations (list, get, create, delete, stats)
     Collection(commands::collection::CollectionCommand),
 
+    /// Search curation: pinned results and document boosts
+    Curation(commands::curation::CurationCommand),
+
+    /// Hide documents from search results (list, add, remove)
+    Blocklist(commands::blocklist::BlocklistCommand),
+
     /// Show collection statistics and health
     Status(commands::status::StatusCommand),
 
@@ -63,6 +78,20 @@ enum Commands {
 
     /// Configuration management (show, set, export, reset)
     Config(commands::config::ConfigCommand),
+
+    /// Diagnose the configuration and the connection to the server
+    Doctor(commands::doctor::DoctorCommand),
+}
+
+impl Commands {
+    /// Whether the command talks to a running server, so its API version
+    /// must be checked first
+    fn uses_server(&self) -> bool {
+        !matches!(
+            self,
+            Commands::Init(_) | Commands::Server(_) | Commands::Config(_) | Commands::Doctor(_)
+        )
+    }
 }
 
 #[tokio::main]
@@ -78,23 +107,47 @@ async fn main() -> ZeroLatencyResult<()> {
         .without_time()
         .init();
 
+    // Discover the project the working directory belongs to; `init` may be
+    // replacing a broken project file, so it ignores errors
+    let project = match std::env::current_dir() {
+        Ok(cwd) => match Project::discover(&cwd) {
+            Ok(project) => project,
+            Err(_) if matches!(cli.command, Commands::Init(_)) => None,
+            Err(e) => return Err(e),
+        },
+        Err(_) => None,
+    };
+
     // Load configuration
-    let config = load_config(&cli).await?;
+    let config = load_config(&cli, project.as_ref()).await?;
 
     // Create service container with dependency injection
-    let container = application::CliServiceContainer::new(config).await?;
-
-    // Execute command using clean architecture
-    let result = match cli.command {
-        Commands::Search(cmd) => cmd.execute(&container).await,
-        Commands::Index(cmd) => cmd.execute(&container).await,
-        Commands::Document(cmd) => cmd.execute(&container).await,
-        Commands::Collection(cmd) => cmd.execute(&container).await,
-        Commands::Status(cmd) => cmd.execute(&container).await,
-        Commands::Server(cmd) => cmd.execute(&container).await,
-        Commands::Reindex(cmd) => cmd.execute(&container).await,
-        Commands::Config(cmd) => cmd.execute(&container).await,
-    };
+    let container = application::CliServiceContainer::new(config)
+        .await?
+        .with_project(project);
+
+    // Execute command using clean architecture, once the server is known
+    // to speak a supported API version
+    let result = async {
+        if cli.command.uses_server() && !cli.skip_version_check {
+            check_server_version(&container).await?;
+        }
+        match cli.command {
+            Commands::Search(cmd) => cmd.execute(&container).await,
+            Commands::Index(cmd) => cmd.execute(&container).await,
+            Commands::Init(cmd) => cmd.execute(&container).await,
+            Commands::Document(cmd) => cmd.execute(&container).await,
+            Commands::Collection(cmd) => cmd.execute(&container).await,
+            Commands::Curation(cmd) => cmd.execute(&container).await,
+            Commands::Blocklist(cmd) => cmd.execute(&container).await,
+            Commands::Status(cmd) => cmd.execute(&container).await,
+            Commands::Server(cmd) => cmd.execute(&container).await,
+            Commands::Reindex(cmd) => cmd.execute(&container).await,
+            Commands::Config(cmd) => cmd.execute(&container).await,
+            Commands::Doctor(cmd) => cmd.execute(&container).await,
+        }
+    }
+    .await;
 
     // Handle errors with user-friendly messages
     if let Err(e) = result {
@@ -103,11 +156,18 @@ async fn main() -> ZeroLatencyResult<()> {
         // Provide helpful suggestions based on error type
         match &e {
             ZeroLatencyError::Network { .. } => {
-                eprintln!("Tip: Try: {}", "mdx server --start".cyan());
+                eprintln!("Tip: Try: {}", "mdx server start --daemon".cyan());
             }
             ZeroLatencyError::NotFound { .. } => {
                 eprintln!("Tip: The requested resource was not found");
             }
+            ZeroLatencyError::Validation { field, .. } if field == "api_version" => {
+                eprintln!(
+                    "Tip: Pass {} to run anyway, or run {}",
+                    "--skip-version-check".cyan(),
+                    "mdx doctor".cyan()
+                );
+            }
             ZeroLatencyError::Configuration { message } => {
                 eprintln!("Tip: Check your configuration: {}", message);
             }
@@ -122,8 +182,32 @@ async fn main() -> ZeroLatencyResult<()> {
     Ok(())
 }
 
+/// Refuse to run against a server whose API version this CLI doesn't support
+///
+/// An unreachable server is left to the command itself, which reports it
+/// with a better message; a server too old to report its version is only
+/// warned about.
+async fn check_server_version(container: &application::CliServiceContainer) -> ZeroLatencyResult<()> {
+    match container.cli_service().check_version().await {
+        Ok(Compatibility::Compatible) | Err(_) => Ok(()),
+        Ok(Compatibility::Unknown) => {
+            eprintln!(
+                "{} the server doesn't report an API version and may not support every command",
+                "Warning:".yellow().bold()
+            );
+            Ok(())
+        }
+        Ok(Compatibility::Incompatible(reason)) => {
+            Err(ZeroLatencyError::validation("api_version", reason))
+        }
+    }
+}
+
 /// Load configuration from various sources with CLI override
-async fn load_config(cli: &Cli) -> ZeroLatencyResult<CliConfig> {
+///
+/// A project file scopes commands to the project's collection unless
+/// `--collection` is given explicitly.
+async fn load_config(cli: &Cli, project: Option<&Project>) -> ZeroLatencyResult<CliConfig> {
     // Load base configuration from file
     let mut config = if let Some(config_path) = &cli.config {
         // Load from specified config file
@@ -158,6 +242,8 @@ async fn load_config(cli: &Cli) -> ZeroLatencyResult<CliConfig> {
     }
     if let Some(collection) = &cli.collection {
         config.collection_name = collection.clone();
+    } else if let Some(project) = project {
+        config.collection_name = project.config.collection.clone();
     }
     if cli.verbose {
         config.verbose = true;
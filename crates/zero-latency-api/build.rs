@@ -19,6 +19,9 @@ fn main() {
     let generated_dir = Path::new(&out_dir).join("generated");
     fs::create_dir_all(&generated_dir).unwrap();
 
+    // The spec is embedded for runtime validation whether or not code generation runs
+    generate_schema_json(&schema_path, &generated_dir);
+
     // Check if openapi-generator-cli is available
     let generator_available = Command::new("openapi-generator-cli")
         .arg("version")
@@ -100,6 +103,15 @@ fn main() {
     println!("cargo:rustc-env=GENERATED_CODE_DIR={}", generated_dir.display());
 }
 
+/// Convert the OpenAPI spec to JSON for `schema::OPENAPI_SPEC`
+fn generate_schema_json(spec_path: &Path, output_dir: &Path) {
+    let spec_content = fs::read_to_string(spec_path).expect("Failed to read OpenAPI spec");
+    let spec: serde_json::Value =
+        serde_yaml::from_str(&spec_content).expect("OpenAPI spec is not valid YAML");
+    fs::write(output_dir.join("openapi.json"), spec.to_string())
+        .expect("Failed to write OpenAPI spec as JSON");
+}
+
 /// Generate API documentation in multiple formats
 fn generate_docs(spec_path: &Path, output_dir: &Path) {
     let docs_dir = output_dir.join("docs");
@@ -178,20 +190,48 @@ pub struct Document {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchFilters {
     pub collection_name: Option<String>,
+    pub collection_names: Option<Vec<String>>,
     pub document_type: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub exclude_tags: Option<Vec<String>>,
+    pub exclude_collection_names: Option<Vec<String>>,
+    pub metadata_patterns: Option<std::collections::HashMap<String, String>>,
+    pub ranges: Option<std::collections::HashMap<String, NumericRange>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NumericRange {
+    pub gte: Option<f64>,
+    pub lte: Option<f64>,
 }
 
 impl Default for SearchFilters {
     fn default() -> Self {
         Self {
             collection_name: None,
+            collection_names: None,
             document_type: None,
             tags: None,
+            exclude_tags: None,
+            exclude_collection_names: None,
+            metadata_patterns: None,
+            ranges: None,
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusionWeights {
+    pub bm25_weight: f32,
+    pub vector_weight: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorSpace {
+    pub name: String,
+    pub weight: Option<f32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchRequest {
     pub query: String,
@@ -200,6 +240,16 @@ pub struct SearchRequest {
     pub filters: Option<Box<SearchFilters>>,
     pub search_type: Option<String>,
     pub include_metadata: Option<bool>,
+    pub fusion_weights: Option<Box<FusionWeights>>,
+    pub query_expansion: Option<bool>,
+    pub rerank_depth: Option<i32>,
+    pub exclude_documents: Option<Vec<String>>,
+    pub extract_answer: Option<bool>,
+    pub min_score: Option<f32>,
+    pub browse_order: Option<String>,
+    pub vector_spaces: Option<Vec<VectorSpace>>,
+    pub late_interaction: Option<bool>,
+    pub context_window: Option<i32>,
 }
 
 impl Default for SearchRequest {
@@ -211,6 +261,16 @@ impl Default for SearchRequest {
             filters: None,
             search_type: None,
             include_metadata: None,
+            fusion_weights: None,
+            query_expansion: None,
+            rerank_depth: None,
+            exclude_documents: None,
+            extract_answer: None,
+            min_score: None,
+            browse_order: None,
+            vector_spaces: None,
+            late_interaction: None,
+            context_window: None,
         }
     }
 }
@@ -287,6 +347,8 @@ fn copy_generated_rust_files(rust_output: &Path, target_dir: &Path) {
                     "search_response.rs",
                     "search_result.rs",
                     "search_filters.rs",
+                    "fusion_weights.rs",
+                    "vector_space.rs",
                     "api_error.rs",
                     "document.rs",
                     "collection.rs",
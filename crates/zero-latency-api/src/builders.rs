@@ -0,0 +1,545 @@
+//! Fluent builders for API requests
+//!
+//! The generated request types mirror the OpenAPI schema field for field,
+//! including boxed sub-objects and optional collections. These builders
+//! provide a smaller surface for the common cases and check the schema's
+//! constraints in `build()`, so invalid requests fail before they are sent.
+
+use crate::types::{FusionWeights, IndexRequest, SearchFilters, SearchRequest, VectorSpace};
+
+/// Longest query accepted by `/api/search`
+pub const MAX_QUERY_LENGTH: usize = 512;
+
+/// Largest `limit` accepted by `/api/search`
+pub const MAX_SEARCH_LIMIT: u32 = 1000;
+
+/// Longest collection name accepted by the API
+pub const MAX_COLLECTION_NAME_LENGTH: usize = 64;
+
+const SEARCH_TYPES: [&str; 3] = ["semantic", "keyword", "hybrid"];
+
+const BROWSE_ORDERS: [&str; 2] = ["recent", "popular"];
+
+/// A request field that violates the API schema
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid {field}: {message}")]
+pub struct RequestBuildError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl RequestBuildError {
+    fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+/// Builder for [`SearchRequest`]
+#[derive(Debug, Clone)]
+pub struct SearchRequestBuilder {
+    request: SearchRequest,
+    limit: Option<u32>,
+    offset: Option<u32>,
+    rerank_depth: Option<u32>,
+    collections: Vec<String>,
+}
+
+impl SearchRequest {
+    /// Start building a search for `query`
+    pub fn builder(query: impl Into<String>) -> SearchRequestBuilder {
+        SearchRequestBuilder::new(query)
+    }
+}
+
+impl SearchRequestBuilder {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            request: SearchRequest {
+                query: query.into(),
+                ..Default::default()
+            },
+            limit: None,
+            offset: None,
+            rerank_depth: None,
+            collections: Vec::new(),
+        }
+    }
+
+    /// Maximum number of results (1 to [`MAX_SEARCH_LIMIT`])
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Number of results to skip
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Filters to apply; collections set with [`Self::with_collections`] take precedence
+    pub fn with_filters(mut self, filters: SearchFilters) -> Self {
+        self.request.filters = Some(Box::new(filters));
+        self
+    }
+
+    /// Collections to search; several are searched together and their results merged
+    pub fn with_collections<I, S>(mut self, collections: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.collections = collections.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Expand the query with synonyms and related terms
+    pub fn with_expansion(mut self, enabled: bool) -> Self {
+        self.request.query_expansion = Some(enabled);
+        self
+    }
+
+    /// Whether a question-style query gets an extracted answer
+    pub fn with_answer_extraction(mut self, enabled: bool) -> Self {
+        self.request.extract_answer = Some(enabled);
+        self
+    }
+
+    /// Leave out results whose final score is below `score` (0.0-1.0)
+    pub fn with_min_score(mut self, score: f32) -> Self {
+        self.request.min_score = Some(score);
+        self
+    }
+
+    /// Order of the documents listed when the query is empty or only stop
+    /// words: `recent` or `popular`
+    pub fn with_browse_order(mut self, order: impl Into<String>) -> Self {
+        self.request.browse_order = Some(order.into());
+        self
+    }
+
+    /// One of `semantic`, `keyword` or `hybrid`
+    pub fn with_search_type(mut self, search_type: impl Into<String>) -> Self {
+        self.request.search_type = Some(search_type.into());
+        self
+    }
+
+    /// Relative weights of keyword (BM25) and vector scores in hybrid search
+    pub fn with_fusion_weights(mut self, bm25_weight: f32, vector_weight: f32) -> Self {
+        self.request.fusion_weights = Some(Box::new(FusionWeights {
+            bm25_weight,
+            vector_weight,
+        }));
+        self
+    }
+
+    /// Also search the chunks' vectors named `name`, fusing the spaces'
+    /// similarities by weight; the content vector is only searched if listed
+    pub fn with_vector_space(mut self, name: impl Into<String>, weight: f32) -> Self {
+        self.request
+            .vector_spaces
+            .get_or_insert_with(Vec::new)
+            .push(VectorSpace {
+                name: name.into(),
+                weight: Some(weight),
+            });
+        self
+    }
+
+    /// Rerank the top results by token-level MaxSim; the server must have
+    /// late interaction enabled
+    pub fn with_late_interaction(mut self, enabled: bool) -> Self {
+        self.request.late_interaction = Some(enabled);
+        self
+    }
+
+    /// Stitch up to `window` neighboring chunks on each side of every hit
+    /// into its content
+    pub fn with_context_window(mut self, window: u32) -> Self {
+        self.request.context_window = Some(window as i32);
+        self
+    }
+
+    /// Number of candidates to rerank
+    pub fn with_rerank_depth(mut self, depth: u32) -> Self {
+        self.rerank_depth = Some(depth);
+        self
+    }
+
+    /// Whether results include document metadata
+    pub fn with_metadata(mut self, include: bool) -> Self {
+        self.request.include_metadata = Some(include);
+        self
+    }
+
+    /// Leave these documents out of the results
+    pub fn excluding_documents<I, S>(mut self, document_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.request.exclude_documents = Some(document_ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Check the request against the API schema and return it
+    pub fn build(self) -> Result<SearchRequest, RequestBuildError> {
+        let mut request = self.request;
+
+        // An empty query browses the collection rather than searching it
+        if request.query.chars().count() > MAX_QUERY_LENGTH {
+            return Err(RequestBuildError::new(
+                "query",
+                format!("must be at most {} characters", MAX_QUERY_LENGTH),
+            ));
+        }
+
+        if let Some(limit) = self.limit {
+            if !(1..=MAX_SEARCH_LIMIT).contains(&limit) {
+                return Err(RequestBuildError::new(
+                    "limit",
+                    format!("must be between 1 and {}", MAX_SEARCH_LIMIT),
+                ));
+            }
+            request.limit = Some(limit as i32);
+        }
+        if let Some(offset) = self.offset {
+            request.offset = Some(to_i32("offset", offset)?);
+        }
+        if let Some(depth) = self.rerank_depth {
+            if depth == 0 {
+                return Err(RequestBuildError::new("rerank_depth", "must be at least 1"));
+            }
+            request.rerank_depth = Some(to_i32("rerank_depth", depth)?);
+        }
+
+        if let Some(search_type) = &request.search_type {
+            if !SEARCH_TYPES.contains(&search_type.as_str()) {
+                return Err(RequestBuildError::new(
+                    "search_type",
+                    format!("must be one of {}", SEARCH_TYPES.join(", ")),
+                ));
+            }
+        }
+
+        if let Some(order) = &request.browse_order {
+            if !BROWSE_ORDERS.contains(&order.as_str()) {
+                return Err(RequestBuildError::new(
+                    "browse_order",
+                    format!("must be one of {}", BROWSE_ORDERS.join(", ")),
+                ));
+            }
+        }
+
+        if let Some(score) = request.min_score {
+            if !(0.0..=1.0).contains(&score) {
+                return Err(RequestBuildError::new(
+                    "min_score",
+                    "must be between 0 and 1",
+                ));
+            }
+        }
+
+        if let Some(weights) = &request.fusion_weights {
+            let valid = |weight: f32| weight.is_finite() && weight >= 0.0;
+            if !valid(weights.bm25_weight) || !valid(weights.vector_weight) {
+                return Err(RequestBuildError::new(
+                    "fusion_weights",
+                    "weights must be finite and non-negative",
+                ));
+            }
+            if weights.bm25_weight + weights.vector_weight == 0.0 {
+                return Err(RequestBuildError::new(
+                    "fusion_weights",
+                    "at least one weight must be positive",
+                ));
+            }
+        }
+
+        for space in request.vector_spaces.iter().flatten() {
+            if space.name.trim().is_empty() {
+                return Err(RequestBuildError::new(
+                    "vector_spaces",
+                    "names must not be empty",
+                ));
+            }
+            if space
+                .weight
+                .is_some_and(|weight| !weight.is_finite() || weight < 0.0)
+            {
+                return Err(RequestBuildError::new(
+                    "vector_spaces",
+                    "weights must be finite and non-negative",
+                ));
+            }
+        }
+
+        if !self.collections.is_empty() {
+            for name in &self.collections {
+                validate_collection_name(name)?;
+            }
+            let filters = request.filters.get_or_insert_with(Default::default);
+            if let [name] = self.collections.as_slice() {
+                filters.collection_name = Some(name.clone());
+                filters.collection_names = None;
+            } else {
+                filters.collection_name = None;
+                filters.collection_names = Some(self.collections);
+            }
+        }
+
+        if let Some(filters) = &request.filters {
+            for (field, range) in filters.ranges.iter().flatten() {
+                if let (Some(gte), Some(lte)) = (range.gte, range.lte) {
+                    if gte > lte {
+                        return Err(RequestBuildError::new(
+                            "filters.ranges",
+                            format!("range for '{}' has gte greater than lte", field),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(request)
+    }
+}
+
+/// Builder for [`IndexRequest`]
+#[derive(Debug, Clone)]
+pub struct IndexRequestBuilder {
+    request: IndexRequest,
+}
+
+impl IndexRequest {
+    /// Start building a request to index `path`
+    pub fn builder(path: impl Into<String>) -> IndexRequestBuilder {
+        IndexRequestBuilder::new(path)
+    }
+}
+
+impl IndexRequestBuilder {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            request: IndexRequest {
+                path: path.into(),
+                collection_name: None,
+                recursive: None,
+                force_reindex: None,
+            },
+        }
+    }
+
+    /// Collection to index into
+    pub fn with_collection(mut self, collection: impl Into<String>) -> Self {
+        self.request.collection_name = Some(collection.into());
+        self
+    }
+
+    /// Whether subdirectories are indexed (the server defaults to true)
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.request.recursive = Some(recursive);
+        self
+    }
+
+    /// Re-index files even if they are unchanged since the last run
+    pub fn force_reindex(mut self, force: bool) -> Self {
+        self.request.force_reindex = Some(force);
+        self
+    }
+
+    /// Check the request against the API schema and return it
+    pub fn build(self) -> Result<IndexRequest, RequestBuildError> {
+        if self.request.path.trim().is_empty() {
+            return Err(RequestBuildError::new("path", "must not be empty"));
+        }
+        if let Some(name) = &self.request.collection_name {
+            validate_collection_name(name)?;
+        }
+        Ok(self.request)
+    }
+}
+
+fn validate_collection_name(name: &str) -> Result<(), RequestBuildError> {
+    if name.is_empty() || name.len() > MAX_COLLECTION_NAME_LENGTH {
+        return Err(RequestBuildError::new(
+            "collection",
+            format!(
+                "name must be 1 to {} characters",
+                MAX_COLLECTION_NAME_LENGTH
+            ),
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(RequestBuildError::new(
+            "collection",
+            format!("'{}' may only contain letters, digits, '_' and '-'", name),
+        ));
+    }
+    Ok(())
+}
+
+fn to_i32(field: &'static str, value: u32) -> Result<i32, RequestBuildError> {
+    i32::try_from(value).map_err(|_| RequestBuildError::new(field, "is too large"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::NumericRange;
+
+    #[test]
+    fn test_search_builder_sets_fields() {
+        let request = SearchRequest::builder("rust async")
+            .with_limit(25)
+            .with_offset(5)
+            .with_collections(["docs", "blog"])
+            .with_expansion(true)
+            .with_answer_extraction(true)
+            .with_min_score(0.6)
+            .with_search_type("hybrid")
+            .with_fusion_weights(0.3, 0.7)
+            .with_vector_space("content", 2.0)
+            .with_vector_space("title", 1.0)
+            .with_late_interaction(true)
+            .with_context_window(2)
+            .excluding_documents(["doc-1"])
+            .build()
+            .unwrap();
+
+        assert_eq!(request.query, "rust async");
+        assert_eq!(request.limit, Some(25));
+        assert_eq!(request.offset, Some(5));
+        assert_eq!(request.query_expansion, Some(true));
+        assert_eq!(request.extract_answer, Some(true));
+        assert_eq!(request.min_score, Some(0.6));
+        assert_eq!(request.search_type.as_deref(), Some("hybrid"));
+        assert_eq!(request.exclude_documents, Some(vec!["doc-1".to_string()]));
+        assert_eq!(request.late_interaction, Some(true));
+        assert_eq!(request.context_window, Some(2));
+        let spaces = request.vector_spaces.unwrap();
+        assert_eq!(spaces[1].name, "title");
+        assert_eq!(spaces[1].weight, Some(1.0));
+        let filters = request.filters.unwrap();
+        assert_eq!(
+            filters.collection_names,
+            Some(vec!["docs".to_string(), "blog".to_string()])
+        );
+        assert_eq!(filters.collection_name, None);
+
+        // Empty queries browse instead of searching
+        let browse = SearchRequest::builder("")
+            .with_browse_order("popular")
+            .build()
+            .unwrap();
+        assert_eq!(browse.browse_order.as_deref(), Some("popular"));
+    }
+
+    #[test]
+    fn test_single_collection_keeps_other_filters() {
+        let filters = SearchFilters {
+            document_type: Some("markdown".to_string()),
+            collection_names: Some(vec!["old".to_string()]),
+            ..Default::default()
+        };
+        let request = SearchRequest::builder("q")
+            .with_filters(filters)
+            .with_collections(["docs"])
+            .build()
+            .unwrap();
+
+        let filters = request.filters.unwrap();
+        assert_eq!(filters.collection_name.as_deref(), Some("docs"));
+        assert_eq!(filters.collection_names, None);
+        assert_eq!(filters.document_type.as_deref(), Some("markdown"));
+    }
+
+    #[test]
+    fn test_search_builder_rejects_invalid_requests() {
+        let field = |builder: SearchRequestBuilder| builder.build().unwrap_err().field;
+
+        assert_eq!(
+            field(SearchRequest::builder("x".repeat(MAX_QUERY_LENGTH + 1))),
+            "query"
+        );
+        assert_eq!(field(SearchRequest::builder("q").with_limit(0)), "limit");
+        assert_eq!(
+            field(SearchRequest::builder("q").with_limit(MAX_SEARCH_LIMIT + 1)),
+            "limit"
+        );
+        assert_eq!(
+            field(SearchRequest::builder("q").with_rerank_depth(0)),
+            "rerank_depth"
+        );
+        assert_eq!(
+            field(SearchRequest::builder("q").with_min_score(1.5)),
+            "min_score"
+        );
+        assert_eq!(
+            field(SearchRequest::builder("").with_browse_order("newest")),
+            "browse_order"
+        );
+        assert_eq!(
+            field(SearchRequest::builder("q").with_search_type("fuzzy")),
+            "search_type"
+        );
+        assert_eq!(
+            field(SearchRequest::builder("q").with_fusion_weights(0.0, 0.0)),
+            "fusion_weights"
+        );
+        assert_eq!(
+            field(SearchRequest::builder("q").with_vector_space("title", -1.0)),
+            "vector_spaces"
+        );
+        assert_eq!(
+            field(SearchRequest::builder("q").with_collections(["no spaces"])),
+            "collection"
+        );
+
+        let ranges = [(
+            "year".to_string(),
+            NumericRange {
+                gte: Some(2024.0),
+                lte: Some(2020.0),
+            },
+        )]
+        .into_iter()
+        .collect();
+        let filters = SearchFilters {
+            ranges: Some(ranges),
+            ..Default::default()
+        };
+        assert_eq!(
+            field(SearchRequest::builder("q").with_filters(filters)),
+            "filters.ranges"
+        );
+    }
+
+    #[test]
+    fn test_index_builder() {
+        let request = IndexRequest::builder("/docs")
+            .with_collection("docs")
+            .recursive(false)
+            .force_reindex(true)
+            .build()
+            .unwrap();
+        assert_eq!(request.path, "/docs");
+        assert_eq!(request.collection_name.as_deref(), Some("docs"));
+        assert_eq!(request.recursive, Some(false));
+        assert_eq!(request.force_reindex, Some(true));
+
+        let err = IndexRequest::builder("").build().unwrap_err();
+        assert_eq!(err.field, "path");
+        let err = IndexRequest::builder("/docs")
+            .with_collection("x".repeat(MAX_COLLECTION_NAME_LENGTH + 1))
+            .build()
+            .unwrap_err();
+        assert_eq!(err.field, "collection");
+    }
+}
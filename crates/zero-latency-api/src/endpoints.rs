@@ -9,26 +9,89 @@ pub mod endpoints {
     pub const HEALTH: &str = "/health";
     pub const HEALTH_READY: &str = "/health/ready";
     pub const HEALTH_LIVE: &str = "/health/live";
+    /// Latest startup validation report
+    pub const HEALTH_STARTUP: &str = "/health/startup";
     
     // API status
     pub const STATUS: &str = "/api/status";
+    /// Features this build and configuration support
+    pub const CAPABILITIES: &str = "/api/capabilities";
+    /// Features, backends and crate versions this binary was built with
+    pub const BUILD_INFO: &str = "/api/build-info";
     
     // Search endpoints
     pub const SEARCH: &str = "/api/search";
     pub const DOCUMENTS_SEARCH: &str = "/api/documents/search";
+    /// Search and return a cited digest of the results, for chatbots
+    pub const SEARCH_SUMMARIZE: &str = "/api/search/summarize";
+    /// Editor lookup; a stable contract for editor extensions
+    pub const LOOKUP: &str = "/api/lookup";
+
+    // Conversation-aware search sessions
+    pub const SESSIONS: &str = "/api/sessions";
+    pub const SESSION_BY_ID: &str = "/api/sessions/{id}";
+    pub const SESSION_SEARCH: &str = "/api/sessions/{id}/search";
+    pub const SESSION_SELECTIONS: &str = "/api/sessions/{id}/selections";
+
+    // Webhook subscriptions
+    pub const WEBHOOKS: &str = "/api/webhooks";
+    pub const WEBHOOK_BY_ID: &str = "/api/webhooks/{id}";
+
+    // Search curation: pinned results and document boosts
+    pub const CURATION: &str = "/api/curation";
+    pub const CURATION_PINS: &str = "/api/curation/pins";
+    pub const CURATION_PIN_BY_ID: &str = "/api/curation/pins/{id}";
+    pub const CURATION_BOOSTS: &str = "/api/curation/boosts";
+    pub const CURATION_BOOST_BY_ID: &str = "/api/curation/boosts/{id}";
+
+    // Query-time blocklist
+    pub const BLOCKLIST: &str = "/api/blocklist";
+    pub const BLOCKLIST_ENTRY_BY_ID: &str = "/api/blocklist/{id}";
+
+    // Changefeed of index mutations
+    pub const EVENTS: &str = "/api/events";
+
+    // Digests of recent index changes
+    pub const DIGEST: &str = "/api/digest";
+    pub const DIGEST_SEND: &str = "/api/digest/send";
+
+    // Loaded pipeline plugins
+    pub const PLUGINS: &str = "/api/plugins";
     
     // Indexing endpoints
     pub const INDEX: &str = "/api/index";
     pub const REINDEX: &str = "/api/reindex";
     
+    // Job endpoints
+    pub const JOBS: &str = "/api/jobs";
+    pub const JOB_BY_ID: &str = "/api/jobs/{id}";
+    pub const JOB_ERRORS: &str = "/api/jobs/{id}/errors";
+    pub const JOB_RESUME: &str = "/api/jobs/{id}/resume";
+    
     // Collection management endpoints
     pub const COLLECTIONS: &str = "/api/collections";
     pub const COLLECTION_BY_NAME: &str = "/api/collections/{name}";
     pub const COLLECTION_STATS: &str = "/api/collections/{name}/stats";
+    pub const COLLECTION_WARM: &str = "/api/collections/{name}/warm";
+    pub const COLLECTION_MIGRATE_EMBEDDINGS: &str = "/api/collections/{name}/migrate-embeddings";
+    pub const COLLECTION_STALENESS: &str = "/api/collections/{name}/staleness";
+    pub const COLLECTION_REEMBED_STALE: &str = "/api/collections/{name}/reembed-stale";
+    pub const COLLECTION_SCHEMA: &str = "/api/collections/{name}/schema";
+    pub const COLLECTION_SAMPLE: &str = "/api/collections/{name}/sample";
+    pub const COLLECTION_DUPLICATES: &str = "/api/collections/{name}/duplicates";
+    pub const COLLECTION_HEALTH: &str = "/api/collections/{name}/health";
+    pub const COLLECTION_PROJECTION: &str = "/api/collections/{name}/projection";
+    pub const COLLECTION_CACHE: &str = "/api/collections/{name}/cache";
+    pub const COLLECTION_CACHE_PURGE: &str = "/api/collections/{name}/cache/purge";
+    pub const COLLECTION_DOCUMENTS_METADATA: &str = "/api/collections/{name}/documents/metadata";
+    pub const COLLECTION_DOCUMENTS_QUERY: &str = "/api/collections/{name}/documents/query";
+    /// Source file of an indexed document; `{path}` spans several segments
+    pub const COLLECTION_RAW: &str = "/api/collections/{name}/raw/{path}";
     
     // Document endpoints
     pub const DOCUMENTS: &str = "/api/documents";
     pub const DOCUMENT_BY_ID: &str = "/api/documents/{id}";
+    pub const DOCUMENT_OUTLINE: &str = "/api/documents/{id}/outline";
     
     // Server management
     pub const SERVER_START: &str = "/api/server/start";
@@ -38,6 +101,35 @@ pub mod endpoints {
     pub const ANALYTICS_SUMMARY: &str = "/api/analytics/summary";
     pub const ANALYTICS_POPULAR_QUERIES: &str = "/api/analytics/popular-queries";
     pub const ANALYTICS_SEARCH_TRENDS: &str = "/api/analytics/search-trends";
+    pub const ANALYTICS_SLOW_QUERIES: &str = "/api/analytics/slow-queries";
+
+    // Runtime internals and tunables (token protected)
+    pub const ADMIN: &str = "/api/admin";
+    pub const ADMIN_STATS: &str = "/api/admin/stats";
+    pub const ADMIN_TUNABLES: &str = "/api/admin/tunables";
+    /// Per-target log levels and request log sampling
+    pub const ADMIN_LOG_LEVEL: &str = "/api/admin/log-level";
+    /// Per-tenant usage export for billing
+    pub const ADMIN_USAGE: &str = "/api/admin/usage";
+    /// Production deployment status, components and transitions
+    pub const ADMIN_DEPLOYMENT: &str = "/api/admin/deployment";
+
+    // Elasticsearch/OpenSearch-compatible search for existing tooling
+    pub const ES_COMPAT: &str = "/api/es";
+    pub const ES_COMPAT_SEARCH: &str = "/api/es/{index}/_search";
+    pub const ES_COMPAT_MSEARCH: &str = "/api/es/_msearch";
+    pub const ES_COMPAT_INDEX_MSEARCH: &str = "/api/es/{index}/_msearch";
+
+    // Bundled web UI (doc-indexer `web-ui` feature)
+    pub const WEB_UI: &str = "/ui";
+
+    // Slack and Discord chat integration (doc-indexer `chat` feature)
+    pub const SLACK_COMMANDS: &str = "/api/integrations/slack/commands";
+    pub const DISCORD_INTERACTIONS: &str = "/api/integrations/discord/interactions";
+
+    // CPU profiles and allocator statistics (doc-indexer `profiling` feature)
+    pub const PPROF_PROFILE: &str = "/debug/pprof/profile";
+    pub const PPROF_HEAP: &str = "/debug/pprof/heap";
     
     /// Helper functions for dynamic endpoints
     pub fn collection_by_name(name: &str) -> String {
@@ -48,9 +140,99 @@ pub mod endpoints {
         COLLECTION_STATS.replace("{name}", name)
     }
     
+    pub fn collection_warm(name: &str) -> String {
+        COLLECTION_WARM.replace("{name}", name)
+    }
+    
+    pub fn collection_migrate_embeddings(name: &str) -> String {
+        COLLECTION_MIGRATE_EMBEDDINGS.replace("{name}", name)
+    }
+    
+    pub fn collection_staleness(name: &str) -> String {
+        COLLECTION_STALENESS.replace("{name}", name)
+    }
+    
+    pub fn collection_reembed_stale(name: &str) -> String {
+        COLLECTION_REEMBED_STALE.replace("{name}", name)
+    }
+    
+    pub fn collection_schema(name: &str) -> String {
+        COLLECTION_SCHEMA.replace("{name}", name)
+    }
+    
+    pub fn collection_documents_metadata(name: &str) -> String {
+        COLLECTION_DOCUMENTS_METADATA.replace("{name}", name)
+    }
+    
+    pub fn collection_documents_query(name: &str) -> String {
+        COLLECTION_DOCUMENTS_QUERY.replace("{name}", name)
+    }
+    
+    pub fn collection_raw(name: &str, path: &str) -> String {
+        COLLECTION_RAW
+            .replace("{name}", name)
+            .replace("{path}", path.trim_start_matches('/'))
+    }
+    
+    pub fn collection_sample(name: &str) -> String {
+        COLLECTION_SAMPLE.replace("{name}", name)
+    }
+    
+    pub fn collection_duplicates(name: &str) -> String {
+        COLLECTION_DUPLICATES.replace("{name}", name)
+    }
+    
+    pub fn collection_health(name: &str) -> String {
+        COLLECTION_HEALTH.replace("{name}", name)
+    }
+    
+    pub fn collection_projection(name: &str) -> String {
+        COLLECTION_PROJECTION.replace("{name}", name)
+    }
+    
+    pub fn collection_cache(name: &str) -> String {
+        COLLECTION_CACHE.replace("{name}", name)
+    }
+    
+    pub fn collection_cache_purge(name: &str) -> String {
+        COLLECTION_CACHE_PURGE.replace("{name}", name)
+    }
+    
+    pub fn es_compat_search(index: &str) -> String {
+        ES_COMPAT_SEARCH.replace("{index}", index)
+    }
+    
     pub fn document_by_id(id: &str) -> String {
         DOCUMENT_BY_ID.replace("{id}", id)
     }
+    
+    pub fn document_outline(id: &str) -> String {
+        DOCUMENT_OUTLINE.replace("{id}", id)
+    }
+    
+    pub fn job_by_id(id: &str) -> String {
+        JOB_BY_ID.replace("{id}", id)
+    }
+    
+    pub fn job_errors(id: &str) -> String {
+        JOB_ERRORS.replace("{id}", id)
+    }
+    
+    pub fn job_resume(id: &str) -> String {
+        JOB_RESUME.replace("{id}", id)
+    }
+    
+    pub fn curation_pin_by_id(id: &str) -> String {
+        CURATION_PIN_BY_ID.replace("{id}", id)
+    }
+    
+    pub fn curation_boost_by_id(id: &str) -> String {
+        CURATION_BOOST_BY_ID.replace("{id}", id)
+    }
+    
+    pub fn blocklist_entry_by_id(id: &str) -> String {
+        BLOCKLIST_ENTRY_BY_ID.replace("{id}", id)
+    }
 }
 
 /// URL generation utilities
@@ -67,11 +249,106 @@ pub mod urls {
         format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_stats(name))
     }
     
+    /// Generate collection warm-up URL
+    pub fn collection_warm(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_warm(name))
+    }
+    
+    /// Generate collection embedding migration URL
+    pub fn collection_migrate_embeddings(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_migrate_embeddings(name))
+    }
+    
+    /// Generate collection staleness URL
+    pub fn collection_staleness(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_staleness(name))
+    }
+    
+    /// Generate stale vector re-embedding URL
+    pub fn collection_reembed_stale(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_reembed_stale(name))
+    }
+    
+    /// Generate collection metadata schema URL
+    pub fn collection_schema(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_schema(name))
+    }
+    
+    /// Generate bulk document metadata update URL
+    pub fn collection_documents_metadata(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_documents_metadata(name))
+    }
+    
+    /// Generate collection document metadata query URL
+    pub fn collection_documents_query(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_documents_query(name))
+    }
+    
+    /// Generate indexed source file URL
+    pub fn collection_raw(base_url: &str, name: &str, path: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_raw(name, path))
+    }
+    
+    /// Generate collection chunk sample URL
+    pub fn collection_sample(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_sample(name))
+    }
+    
+    /// Generate collection duplicate report URL
+    pub fn collection_duplicates(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_duplicates(name))
+    }
+    
+    /// Generate collection health URL
+    pub fn collection_health(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_health(name))
+    }
+    
+    /// Generate collection projection URL
+    pub fn collection_projection(base_url: &str, name: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::collection_projection(name))
+    }
+    
     /// Generate document by ID URL
     pub fn document_by_id(base_url: &str, id: &str) -> String {
         format!("{}{}", base_url.trim_end_matches('/'), &endpoints::document_by_id(id))
     }
     
+    /// Generate document outline URL
+    pub fn document_outline(base_url: &str, id: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::document_outline(id))
+    }
+    
+    /// Generate job by ID URL
+    pub fn job_by_id(base_url: &str, id: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::job_by_id(id))
+    }
+    
+    /// Generate job errors URL
+    pub fn job_errors(base_url: &str, id: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::job_errors(id))
+    }
+    
+    /// Generate job resume URL
+    pub fn job_resume(base_url: &str, id: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::job_resume(id))
+    }
+    
+    /// Generate curation pin by ID URL
+    pub fn curation_pin_by_id(base_url: &str, id: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::curation_pin_by_id(id))
+    }
+    
+    /// Generate curation boost by ID URL
+    pub fn curation_boost_by_id(base_url: &str, id: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::curation_boost_by_id(id))
+    }
+    
+    /// Generate blocklist entry by ID URL
+    pub fn blocklist_entry_by_id(base_url: &str, id: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), &endpoints::blocklist_entry_by_id(id))
+    }
+    
     /// Generate full endpoint URL
     pub fn endpoint_url(base_url: &str, endpoint: &str) -> String {
         format!("{}{}", base_url.trim_end_matches('/'), endpoint)
@@ -98,6 +375,89 @@ pub mod defaults {
     }
 }
 
+/// API version negotiation between clients and the server
+pub mod version {
+    use std::fmt;
+    use std::str::FromStr;
+
+    /// Version of the HTTP API served by this build, reported by `/api/status`
+    ///
+    /// The major version changes when endpoints or fields are removed or
+    /// change meaning; the minor version when they are added.
+    pub const API_VERSION: ApiVersion = ApiVersion::new(1, 0);
+
+    /// Oldest server API version clients built from this crate work with
+    pub const MIN_SUPPORTED: ApiVersion = ApiVersion::new(1, 0);
+
+    /// A `major.minor` API version
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct ApiVersion {
+        pub major: u32,
+        pub minor: u32,
+    }
+
+    impl ApiVersion {
+        pub const fn new(major: u32, minor: u32) -> Self {
+            Self { major, minor }
+        }
+    }
+
+    impl fmt::Display for ApiVersion {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}.{}", self.major, self.minor)
+        }
+    }
+
+    impl FromStr for ApiVersion {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let invalid = || format!("Invalid API version '{}', expected major.minor", s);
+            let (major, minor) = s.trim().split_once('.').ok_or_else(invalid)?;
+            Ok(Self {
+                major: major.parse().map_err(|_| invalid())?,
+                minor: minor.parse().map_err(|_| invalid())?,
+            })
+        }
+    }
+
+    /// Whether a client can talk to a server
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Compatibility {
+        Compatible,
+        /// The server doesn't report an API version, it predates negotiation
+        Unknown,
+        /// The server is outside the supported range, with the reason
+        Incompatible(String),
+    }
+
+    /// Compare the API version a server reports against the range this
+    /// client supports: [`MIN_SUPPORTED`] up to any minor version of
+    /// [`API_VERSION`]'s major version
+    pub fn check(server: Option<&str>) -> Compatibility {
+        let Some(server) = server else {
+            return Compatibility::Unknown;
+        };
+        let server = match server.parse::<ApiVersion>() {
+            Ok(version) => version,
+            Err(e) => return Compatibility::Incompatible(e),
+        };
+        if server.major > API_VERSION.major {
+            Compatibility::Incompatible(format!(
+                "Server API {} is newer than this client supports ({}.x); upgrade the client",
+                server, API_VERSION.major
+            ))
+        } else if server < MIN_SUPPORTED {
+            Compatibility::Incompatible(format!(
+                "Server API {} is older than this client supports (at least {}); upgrade the server",
+                server, MIN_SUPPORTED
+            ))
+        } else {
+            Compatibility::Compatible
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,6 +483,18 @@ mod tests {
             endpoints::document_by_id("123"),
             "/api/documents/123"
         );
+        assert_eq!(
+            endpoints::job_by_id("abc"),
+            "/api/jobs/abc"
+        );
+        assert_eq!(
+            endpoints::job_errors("abc"),
+            "/api/jobs/abc/errors"
+        );
+        assert_eq!(
+            endpoints::curation_pin_by_id("p1"),
+            "/api/curation/pins/p1"
+        );
     }
     
     #[test]
@@ -142,4 +514,22 @@ mod tests {
         assert_eq!(defaults::default_server_url(), "http://localhost:8081");
         assert_eq!(defaults::SERVER_PORT, 8081);
     }
+
+    #[test]
+    fn test_version_compatibility() {
+        use version::*;
+        assert_eq!("1.2".parse::<ApiVersion>(), Ok(ApiVersion::new(1, 2)));
+        assert!("1".parse::<ApiVersion>().is_err());
+        assert_eq!(check(Some(&API_VERSION.to_string())), Compatibility::Compatible);
+        assert_eq!(
+            check(Some(&format!("{}.99", API_VERSION.major))),
+            Compatibility::Compatible
+        );
+        assert_eq!(check(None), Compatibility::Unknown);
+        assert!(matches!(
+            check(Some(&format!("{}.0", API_VERSION.major + 1))),
+            Compatibility::Incompatible(reason) if reason.contains("upgrade the client")
+        ));
+        assert!(matches!(check(Some("0.9")), Compatibility::Incompatible(_)));
+    }
 }
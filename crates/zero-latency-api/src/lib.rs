@@ -3,7 +3,10 @@
 //! Generated types and client code for the Zero-Latency document indexing and search API.
 //! This crate provides type-safe access to all API endpoints with support for multiple protocols.
 
+pub mod builders;
 pub mod endpoints;
+pub mod metrics;
+pub mod schema;
 
 pub mod types {
     //! API type definitions
@@ -15,16 +18,43 @@ pub mod types {
 pub mod client {
     //! HTTP client for Zero-Latency API
 
+    use crate::metrics::{RequestObserver, RequestTiming};
     use crate::types::*;
     use reqwest::Client;
+    use serde::de::DeserializeOwned;
+    use std::io::Read;
+    use std::sync::Arc;
+    use std::time::Instant;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
     use uuid::Uuid;
 
+    /// Response chunks buffered ahead of the JSON parser
+    const CHUNK_QUEUE_DEPTH: usize = 16;
+
     /// API client configuration
     #[derive(Debug, Clone)]
     pub struct ApiClientConfig {
         pub base_url: String,
         pub tenant_id: Option<Uuid>,
+        /// Limit on a whole request, including reading the response
         pub timeout_seconds: u64,
+        /// Limit on establishing the connection
+        pub connect_timeout_seconds: u64,
+        /// Limit on waiting for the next piece of the response
+        pub read_timeout_seconds: u64,
+        /// Largest response body the client accepts
+        pub max_response_bytes: u64,
+        /// Idle connections kept open per host
+        pub pool_max_idle_per_host: usize,
+        /// How long idle connections are kept, `None` to keep them indefinitely
+        pub pool_idle_timeout_seconds: Option<u64>,
+        /// Speak HTTP/2 without negotiating it first (the server must support h2c)
+        pub http2_prior_knowledge: bool,
+        /// Interval of HTTP/2 keep-alive pings, `None` to disable them
+        pub http2_keep_alive_interval_seconds: Option<u64>,
+        /// How long to wait for a keep-alive ping to be acknowledged
+        pub http2_keep_alive_timeout_seconds: u64,
         pub user_agent: String,
     }
 
@@ -34,16 +64,81 @@ pub mod client {
                 base_url: "http://localhost:8081".to_string(),
                 tenant_id: None,
                 timeout_seconds: 30,
+                connect_timeout_seconds: 10,
+                read_timeout_seconds: 30,
+                max_response_bytes: 32 * 1024 * 1024,
+                pool_max_idle_per_host: 32,
+                pool_idle_timeout_seconds: Some(90),
+                http2_prior_knowledge: false,
+                http2_keep_alive_interval_seconds: None,
+                http2_keep_alive_timeout_seconds: 20,
                 user_agent: "zero-latency-api-client/1.0.0".to_string(),
             }
         }
     }
 
+    /// A response body was larger than [`ApiClientConfig::max_response_bytes`]
+    #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+    #[error("Response body exceeds the {limit} byte limit")]
+    pub struct ResponseTooLarge {
+        pub limit: u64,
+    }
+
+    /// Blocking reader over response chunks received from the async side
+    struct ChunkReader<B> {
+        chunks: mpsc::Receiver<B>,
+        current: Option<B>,
+        position: usize,
+    }
+
+    impl<B: AsRef<[u8]>> ChunkReader<B> {
+        fn new(chunks: mpsc::Receiver<B>) -> Self {
+            Self {
+                chunks,
+                current: None,
+                position: 0,
+            }
+        }
+    }
+
+    impl<B: AsRef<[u8]>> Read for ChunkReader<B> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            loop {
+                if let Some(chunk) = &self.current {
+                    let remaining = &chunk.as_ref()[self.position..];
+                    if !remaining.is_empty() {
+                        let len = remaining.len().min(buf.len());
+                        buf[..len].copy_from_slice(&remaining[..len]);
+                        self.position += len;
+                        return Ok(len);
+                    }
+                }
+                match self.chunks.blocking_recv() {
+                    Some(chunk) => {
+                        self.current = Some(chunk);
+                        self.position = 0;
+                    }
+                    None => return Ok(0),
+                }
+            }
+        }
+    }
+
     /// Zero-Latency API Client
-    #[derive(Debug, Clone)]
+    #[derive(Clone)]
     pub struct ZeroLatencyApiClient {
         client: Client,
         config: ApiClientConfig,
+        observer: Option<Arc<dyn RequestObserver>>,
+    }
+
+    impl std::fmt::Debug for ZeroLatencyApiClient {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("ZeroLatencyApiClient")
+                .field("config", &self.config)
+                .field("observer", &self.observer.is_some())
+                .finish()
+        }
     }
 
     impl ZeroLatencyApiClient {
@@ -55,11 +150,23 @@ pub mod client {
         /// Create a new API client with custom configuration
         pub fn with_config(config: ApiClientConfig) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
             let client = Client::builder()
-                .timeout(std::time::Duration::from_secs(config.timeout_seconds))
-                .user_agent(&config.user_agent)
-                .build()?;
+                .timeout(Duration::from_secs(config.timeout_seconds))
+                .connect_timeout(Duration::from_secs(config.connect_timeout_seconds))
+                .read_timeout(Duration::from_secs(config.read_timeout_seconds))
+                .pool_max_idle_per_host(config.pool_max_idle_per_host)
+                .pool_idle_timeout(config.pool_idle_timeout_seconds.map(Duration::from_secs))
+                .http2_keep_alive_interval(config.http2_keep_alive_interval_seconds.map(Duration::from_secs))
+                .http2_keep_alive_timeout(Duration::from_secs(config.http2_keep_alive_timeout_seconds))
+                .http2_keep_alive_while_idle(config.http2_keep_alive_interval_seconds.is_some())
+                .user_agent(&config.user_agent);
+            let client = if config.http2_prior_knowledge {
+                client.http2_prior_knowledge()
+            } else {
+                client
+            }
+            .build()?;
 
-            Ok(Self { client, config })
+            Ok(Self { client, config, observer: None })
         }
 
         /// Set tenant ID for multi-tenant operations
@@ -68,6 +175,31 @@ pub mod client {
             self
         }
 
+        /// Report the timing of every request, e.g. to [`crate::metrics::LatencyMetrics`]
+        pub fn with_observer(mut self, observer: Arc<dyn RequestObserver>) -> Self {
+            self.observer = Some(observer);
+            self
+        }
+
+        /// Send a request, reporting its timing to the observer if any
+        async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, reqwest::Error> {
+            let request = request.headers(self.build_headers()).build()?;
+            let method = request.method().to_string();
+            let path = request.url().path().to_string();
+
+            let started = Instant::now();
+            let result = self.client.execute(request).await;
+            if let Some(observer) = &self.observer {
+                observer.on_request(&RequestTiming {
+                    method,
+                    path,
+                    status: result.as_ref().ok().map(|response| response.status().as_u16()),
+                    duration: started.elapsed(),
+                });
+            }
+            result
+        }
+
         /// Build request headers including tenant ID if configured
         fn build_headers(&self) -> reqwest::header::HeaderMap {
             let mut headers = reqwest::header::HeaderMap::new();
@@ -86,19 +218,59 @@ pub mod client {
             headers
         }
 
+        /// Deserialize a JSON response body as it arrives
+        ///
+        /// The body is parsed on a blocking thread while chunks are received,
+        /// so large payloads are never held in memory twice, and reading stops
+        /// with [`ResponseTooLarge`] once `max_response_bytes` is exceeded.
+        async fn read_json<T>(&self, mut response: reqwest::Response) -> Result<T, Box<dyn std::error::Error + Send + Sync>>
+        where
+            T: DeserializeOwned + Send + 'static,
+        {
+            let limit = self.config.max_response_bytes;
+            if response.content_length().is_some_and(|len| len > limit) {
+                return Err(ResponseTooLarge { limit }.into());
+            }
+
+            let (sender, receiver) = mpsc::channel(CHUNK_QUEUE_DEPTH);
+            let parser = tokio::task::spawn_blocking(move || {
+                serde_json::from_reader::<_, T>(ChunkReader::new(receiver))
+            });
+
+            let mut received = 0u64;
+            let streamed: Result<(), Box<dyn std::error::Error + Send + Sync>> = loop {
+                match response.chunk().await {
+                    Ok(Some(chunk)) => {
+                        received += chunk.len() as u64;
+                        if received > limit {
+                            break Err(ResponseTooLarge { limit }.into());
+                        }
+                        // The parser only hangs up early when the JSON is invalid
+                        if sender.send(chunk).await.is_err() {
+                            break Ok(());
+                        }
+                    }
+                    Ok(None) => break Ok(()),
+                    Err(e) => break Err(e.into()),
+                }
+            };
+            // Closing the channel ends the parser's input
+            drop(sender);
+
+            let parsed = parser.await?;
+            streamed?;
+            Ok(parsed?)
+        }
+
         /// Health check endpoint
         pub async fn health_check(&self) -> Result<HealthCheckResult, Box<dyn std::error::Error + Send + Sync>> {
             let url = format!("{}/health", self.config.base_url);
-            let response = self.client
-                .get(&url)
-                .headers(self.build_headers())
-                .send()
-                .await?;
+            let response = self.send(self.client.get(&url)).await?;
 
             if response.status().is_success() {
-                Ok(response.json().await?)
+                Ok(self.read_json(response).await?)
             } else {
-                let error: ApiError = response.json().await?;
+                let error: ApiError = self.read_json(response).await?;
                 Err(format!("API Error: {}", error.message).into())
             }
         }
@@ -106,16 +278,12 @@ pub mod client {
         /// Get API status
         pub async fn api_status(&self) -> Result<ApiStatusResponse, Box<dyn std::error::Error + Send + Sync>> {
             let url = format!("{}/api/status", self.config.base_url);
-            let response = self.client
-                .get(&url)
-                .headers(self.build_headers())
-                .send()
-                .await?;
+            let response = self.send(self.client.get(&url)).await?;
 
             if response.status().is_success() {
-                Ok(response.json().await?)
+                Ok(self.read_json(response).await?)
             } else {
-                let error: ApiError = response.json().await?;
+                let error: ApiError = self.read_json(response).await?;
                 Err(format!("API Error: {}", error.message).into())
             }
         }
@@ -123,17 +291,35 @@ pub mod client {
         /// Search documents
         pub async fn search_documents(&self, request: SearchRequest) -> Result<SearchResponse, Box<dyn std::error::Error + Send + Sync>> {
             let url = format!("{}/api/search", self.config.base_url);
-            let response = self.client
-                .post(&url)
-                .headers(self.build_headers())
-                .json(&request)
-                .send()
+            let response = self
+                .send(self.client.post(&url).json(&request))
+                .await?;
+
+            if response.status().is_success() {
+                Ok(self.read_json(response).await?)
+            } else {
+                let error: ApiError = self.read_json(response).await?;
+                Err(format!("Search Error: {}", error.message).into())
+            }
+        }
+
+        /// Search documents, reading the response as `T`
+        ///
+        /// For callers that want the service's full response, such as
+        /// `zero_latency_search::SearchResponse`, instead of [`SearchResponse`].
+        pub async fn search_documents_as<T>(&self, request: SearchRequest) -> Result<T, Box<dyn std::error::Error + Send + Sync>>
+        where
+            T: DeserializeOwned + Send + 'static,
+        {
+            let url = format!("{}/api/search", self.config.base_url);
+            let response = self
+                .send(self.client.post(&url).json(&request))
                 .await?;
 
             if response.status().is_success() {
-                Ok(response.json().await?)
+                Ok(self.read_json(response).await?)
             } else {
-                let error: ApiError = response.json().await?;
+                let error: ApiError = self.read_json(response).await?;
                 Err(format!("Search Error: {}", error.message).into())
             }
         }
@@ -141,17 +327,14 @@ pub mod client {
         /// Index documents from path
         pub async fn index_documents(&self, request: IndexRequest) -> Result<IndexResponse, Box<dyn std::error::Error + Send + Sync>> {
             let url = format!("{}/api/index", self.config.base_url);
-            let response = self.client
-                .post(&url)
-                .headers(self.build_headers())
-                .json(&request)
-                .send()
+            let response = self
+                .send(self.client.post(&url).json(&request))
                 .await?;
 
             if response.status().is_success() {
-                Ok(response.json().await?)
+                Ok(self.read_json(response).await?)
             } else {
-                let error: ApiError = response.json().await?;
+                let error: ApiError = self.read_json(response).await?;
                 Err(format!("Index Error: {}", error.message).into())
             }
         }
@@ -173,21 +356,17 @@ pub mod client {
                 url.push_str(&params.join("&"));
             }
 
-            let response = self.client
-                .get(&url)
-                .headers(self.build_headers())
-                .send()
-                .await?;
+            let response = self.send(self.client.get(&url)).await?;
 
             if response.status().is_success() {
                 #[derive(serde::Deserialize)]
                 struct ListResponse {
                     collections: Vec<Collection>,
                 }
-                let list: ListResponse = response.json().await?;
+                let list: ListResponse = self.read_json(response).await?;
                 Ok(list.collections)
             } else {
-                let error: ApiError = response.json().await?;
+                let error: ApiError = self.read_json(response).await?;
                 Err(format!("List Collections Error: {}", error.message).into())
             }
         }
@@ -195,16 +374,12 @@ pub mod client {
         /// Get collection by name
         pub async fn get_collection(&self, name: &str) -> Result<Collection, Box<dyn std::error::Error + Send + Sync>> {
             let url = format!("{}/api/collections/{}", self.config.base_url, name);
-            let response = self.client
-                .get(&url)
-                .headers(self.build_headers())
-                .send()
-                .await?;
+            let response = self.send(self.client.get(&url)).await?;
 
             if response.status().is_success() {
-                Ok(response.json().await?)
+                Ok(self.read_json(response).await?)
             } else {
-                let error: ApiError = response.json().await?;
+                let error: ApiError = self.read_json(response).await?;
                 Err(format!("Get Collection Error: {}", error.message).into())
             }
         }
@@ -215,11 +390,108 @@ pub mod client {
             Self::new().expect("Failed to create default API client")
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use tokio::io::AsyncWriteExt;
+
+        /// Serve one canned HTTP response, closing the connection after the body
+        async fn serve_once(head: &'static str, body: Vec<u8>) -> String {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 4096];
+                let _ = tokio::io::AsyncReadExt::read(&mut socket, &mut request).await;
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(&body).await;
+                let _ = socket.shutdown().await;
+            });
+            format!("http://{}", addr)
+        }
+
+        fn client(base_url: String, max_response_bytes: u64) -> ZeroLatencyApiClient {
+            ZeroLatencyApiClient::with_config(ApiClientConfig {
+                base_url,
+                max_response_bytes,
+                ..Default::default()
+            })
+            .unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_chunk_reader_joins_chunks() {
+            let (sender, receiver) = mpsc::channel(4);
+            sender.send(b"{\"a\":".to_vec()).await.unwrap();
+            sender.send(Vec::new()).await.unwrap();
+            sender.send(b" [1, 2]}".to_vec()).await.unwrap();
+            drop(sender);
+
+            let value = tokio::task::spawn_blocking(move || {
+                serde_json::from_reader::<_, serde_json::Value>(ChunkReader::new(receiver))
+            })
+            .await
+            .unwrap()
+            .unwrap();
+            assert_eq!(value, serde_json::json!({ "a": [1, 2] }));
+        }
+
+        #[tokio::test]
+        async fn test_responses_within_limit_are_parsed() {
+            let body = br#"{"status":"healthy","timestamp":"2024-01-01T00:00:00Z"}"#.to_vec();
+            let url = serve_once(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nconnection: close\r\n\r\n",
+                body,
+            )
+            .await;
+
+            let metrics = Arc::new(crate::metrics::LatencyMetrics::new());
+            let health = client(url, 1024)
+                .with_observer(metrics.clone())
+                .health_check()
+                .await
+                .unwrap();
+            assert_eq!(health.status, "healthy");
+
+            let snapshot = metrics.snapshot();
+            assert_eq!(snapshot["GET /health"].requests, 1);
+            assert_eq!(snapshot["GET /health"].errors, 0);
+        }
+
+        #[tokio::test]
+        async fn test_oversized_responses_are_rejected() {
+            // Without a content length, the limit is enforced while streaming
+            let body = format!(r#"{{"status":"{}"}}"#, "x".repeat(8192)).into_bytes();
+            let url = serve_once(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nconnection: close\r\n\r\n",
+                body,
+            )
+            .await;
+
+            let err = client(url, 1024).health_check().await.unwrap_err();
+            assert_eq!(
+                err.downcast_ref::<ResponseTooLarge>(),
+                Some(&ResponseTooLarge { limit: 1024 })
+            );
+
+            // A declared content length is rejected before reading
+            let url = serve_once(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 4096\r\n\r\n",
+                Vec::new(),
+            )
+            .await;
+            let err = client(url, 1024).api_status().await.unwrap_err();
+            assert!(err.downcast_ref::<ResponseTooLarge>().is_some(), "{}", err);
+        }
+    }
 }
 
 // Re-export commonly used items
 pub use types::*;
-pub use client::{ZeroLatencyApiClient, ApiClientConfig};
+pub use builders::{IndexRequestBuilder, RequestBuildError, SearchRequestBuilder};
+pub use client::{ZeroLatencyApiClient, ApiClientConfig, ResponseTooLarge};
+pub use metrics::{LatencyMetrics, RequestObserver, RequestTiming};
 
 /// Convenience function to create a new API client
 pub fn new_client() -> Result<ZeroLatencyApiClient, Box<dyn std::error::Error + Send + Sync>> {
@@ -0,0 +1,138 @@
+//! Client-side request latency metrics
+//!
+//! [`ZeroLatencyApiClient`](crate::ZeroLatencyApiClient) reports the timing
+//! of every request it sends to an optional [`RequestObserver`]. Closures can
+//! be used directly; [`LatencyMetrics`] is a ready-made observer that keeps
+//! per-endpoint counts and latencies for callers without their own metrics
+//! pipeline.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Timing of a single API request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestTiming {
+    pub method: String,
+    /// URL path, e.g. `/api/search`
+    pub path: String,
+    /// Response status, or `None` if no response was received
+    pub status: Option<u16>,
+    /// Time until the response headers arrived or the request failed
+    pub duration: Duration,
+}
+
+impl RequestTiming {
+    /// Whether the request failed or returned a non-2xx status
+    pub fn is_error(&self) -> bool {
+        !matches!(self.status, Some(200..=299))
+    }
+}
+
+/// Receives the timing of every request a client sends
+pub trait RequestObserver: Send + Sync {
+    fn on_request(&self, timing: &RequestTiming);
+}
+
+impl<F> RequestObserver for F
+where
+    F: Fn(&RequestTiming) + Send + Sync,
+{
+    fn on_request(&self, timing: &RequestTiming) {
+        self(timing)
+    }
+}
+
+/// Aggregated latency of one endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointLatency {
+    pub requests: u64,
+    pub errors: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl EndpointLatency {
+    /// Mean latency, zero if nothing was recorded
+    pub fn mean(&self) -> Duration {
+        if self.requests == 0 {
+            return Duration::ZERO;
+        }
+        self.total / self.requests as u32
+    }
+}
+
+/// In-process latency metrics keyed by `METHOD path`
+#[derive(Debug, Default)]
+pub struct LatencyMetrics {
+    endpoints: Mutex<HashMap<String, EndpointLatency>>,
+}
+
+impl LatencyMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy of the current metrics
+    pub fn snapshot(&self) -> HashMap<String, EndpointLatency> {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Forget everything recorded so far
+    pub fn reset(&self) {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl RequestObserver for LatencyMetrics {
+    fn on_request(&self, timing: &RequestTiming) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = endpoints
+            .entry(format!("{} {}", timing.method, timing.path))
+            .or_default();
+        entry.requests += 1;
+        if timing.is_error() {
+            entry.errors += 1;
+        }
+        entry.total += timing.duration;
+        entry.max = entry.max.max(timing.duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(status: Option<u16>, millis: u64) -> RequestTiming {
+        RequestTiming {
+            method: "POST".to_string(),
+            path: "/api/search".to_string(),
+            status,
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn test_latency_metrics_aggregate_per_endpoint() {
+        let metrics = LatencyMetrics::new();
+        metrics.on_request(&timing(Some(200), 10));
+        metrics.on_request(&timing(Some(500), 30));
+        metrics.on_request(&timing(None, 20));
+
+        let snapshot = metrics.snapshot();
+        let search = &snapshot["POST /api/search"];
+        assert_eq!(search.requests, 3);
+        assert_eq!(search.errors, 2);
+        assert_eq!(search.mean(), Duration::from_millis(20));
+        assert_eq!(search.max, Duration::from_millis(30));
+
+        metrics.reset();
+        assert!(metrics.snapshot().is_empty());
+    }
+}
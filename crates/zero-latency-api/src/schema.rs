@@ -0,0 +1,514 @@
+//! Runtime validation against the OpenAPI spec
+//!
+//! The spec in `api/schemas/zero-latency-api.yaml` is embedded at build time.
+//! [`ApiSchema`] matches a request to its documented operation and checks
+//! JSON request and response bodies against the operation's schemas, so that
+//! drift between the server's structs and the published contract shows up
+//! while testing rather than in clients.
+//!
+//! Only the JSON Schema keywords the spec uses are checked: `$ref`, `type`,
+//! `enum`, `required`, `properties`, `additionalProperties`, `items`,
+//! `allOf`/`anyOf`/`oneOf` and numeric, length and item count bounds. A
+//! `null` optional property counts as absent, since servers serialize unset
+//! options that way.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The OpenAPI spec, converted to JSON by the build script
+pub const OPENAPI_SPEC: &str = include_str!(concat!(env!("OUT_DIR"), "/generated/openapi.json"));
+
+/// Nesting depth beyond which schemas are assumed to be recursive
+const MAX_DEPTH: usize = 64;
+
+/// A place where a body does not match its schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value (empty for the whole body)
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pointer.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.pointer, self.message)
+        }
+    }
+}
+
+/// A documented operation: method, path template and body schemas
+struct Operation {
+    method: String,
+    // Path segments; `None` for a `{param}` capture
+    segments: Vec<Option<String>>,
+    request: Option<Value>,
+    responses: HashMap<String, Value>,
+}
+
+impl Operation {
+    fn matches(&self, method: &str, segments: &[&str]) -> bool {
+        self.method.eq_ignore_ascii_case(method)
+            && self.segments.len() == segments.len()
+            && self
+                .segments
+                .iter()
+                .zip(segments)
+                .all(|(expected, actual)| expected.as_deref().is_none_or(|s| s == *actual))
+    }
+
+    fn literal_segments(&self) -> usize {
+        self.segments.iter().filter(|s| s.is_some()).count()
+    }
+}
+
+/// Operations and schemas of an OpenAPI spec
+pub struct ApiSchema {
+    spec: Value,
+    operations: Vec<Operation>,
+}
+
+impl ApiSchema {
+    /// Load the embedded spec
+    pub fn load() -> Result<Self, serde_json::Error> {
+        Ok(Self::from_spec(serde_json::from_str(OPENAPI_SPEC)?))
+    }
+
+    /// Build from a parsed OpenAPI document
+    pub fn from_spec(spec: Value) -> Self {
+        let mut operations = Vec::new();
+        if let Some(paths) = spec.get("paths").and_then(Value::as_object) {
+            for (template, item) in paths {
+                let segments: Vec<Option<String>> = template
+                    .trim_matches('/')
+                    .split('/')
+                    .map(|segment| (!segment.starts_with('{')).then(|| segment.to_string()))
+                    .collect();
+                let Some(methods) = item.as_object() else {
+                    continue;
+                };
+
+                for (method, operation) in methods {
+                    let request = operation
+                        .pointer("/requestBody/content/application~1json/schema")
+                        .cloned();
+                    let responses = operation
+                        .get("responses")
+                        .and_then(Value::as_object)
+                        .map(|responses| {
+                            responses
+                                .iter()
+                                .filter_map(|(status, response)| {
+                                    let response = resolve(&spec, response)?;
+                                    let schema =
+                                        response.pointer("/content/application~1json/schema")?;
+                                    Some((status.to_uppercase(), schema.clone()))
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+
+                    operations.push(Operation {
+                        method: method.clone(),
+                        segments: segments.clone(),
+                        request,
+                        responses,
+                    });
+                }
+            }
+        }
+
+        Self { spec, operations }
+    }
+
+    /// Whether the spec documents `method` on `path`
+    pub fn documents(&self, method: &str, path: &str) -> bool {
+        self.operation(method, path).is_some()
+    }
+
+    /// Check a JSON request body
+    ///
+    /// Returns `None` when the operation is undocumented or takes no JSON body.
+    pub fn validate_request(
+        &self,
+        method: &str,
+        path: &str,
+        body: &Value,
+    ) -> Option<Vec<SchemaViolation>> {
+        let schema = self.operation(method, path)?.request.as_ref()?;
+        Some(self.validate(schema, body))
+    }
+
+    /// Check a JSON response body against the schema for its status
+    ///
+    /// Falls back from the exact status to its range (`2XX`) and then to
+    /// `default`. Returns `None` when no JSON schema is documented for it.
+    pub fn validate_response(
+        &self,
+        method: &str,
+        path: &str,
+        status: u16,
+        body: &Value,
+    ) -> Option<Vec<SchemaViolation>> {
+        let operation = self.operation(method, path)?;
+        let schema = [
+            status.to_string(),
+            format!("{}XX", status / 100),
+            "DEFAULT".to_string(),
+        ]
+        .iter()
+        .find_map(|key| operation.responses.get(key))?;
+        Some(self.validate(schema, body))
+    }
+
+    /// Check a value against a schema from this spec
+    pub fn validate(&self, schema: &Value, value: &Value) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        self.check(schema, value, String::new(), 0, &mut violations);
+        violations
+    }
+
+    /// The most specific operation matching a request, preferring literal segments
+    fn operation(&self, method: &str, path: &str) -> Option<&Operation> {
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        self.operations
+            .iter()
+            .filter(|operation| operation.matches(method, &segments))
+            .max_by_key(|operation| operation.literal_segments())
+    }
+
+    fn check(
+        &self,
+        schema: &Value,
+        value: &Value,
+        pointer: String,
+        depth: usize,
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let Some(schema) = resolve(&self.spec, schema) else {
+            violations.push(SchemaViolation {
+                pointer,
+                message: format!("unresolvable schema reference {}", schema),
+            });
+            return;
+        };
+
+        if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+            for sub in all {
+                self.check(sub, value, pointer.clone(), depth + 1, violations);
+            }
+        }
+        for keyword in ["anyOf", "oneOf"] {
+            if let Some(alternatives) = schema.get(keyword).and_then(Value::as_array) {
+                let matched = alternatives
+                    .iter()
+                    .any(|sub| self.validate_at(sub, value, depth + 1).is_empty());
+                if !matched {
+                    violations.push(SchemaViolation {
+                        pointer: pointer.clone(),
+                        message: format!("does not match any schema in {}", keyword),
+                    });
+                }
+            }
+        }
+        let mut violation = |message: String| {
+            violations.push(SchemaViolation {
+                pointer: pointer.clone(),
+                message,
+            })
+        };
+
+        if let Some(types) = schema.get("type") {
+            let nullable = schema.get("nullable").and_then(Value::as_bool) == Some(true);
+            let allowed: Vec<&str> = match types {
+                Value::String(single) => vec![single.as_str()],
+                Value::Array(many) => many.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            let matches =
+                allowed.iter().any(|ty| type_matches(ty, value)) || (nullable && value.is_null());
+            if !allowed.is_empty() && !matches {
+                violation(format!(
+                    "expected {}, found {}",
+                    allowed.join(" or "),
+                    type_name(value)
+                ));
+                return;
+            }
+        }
+
+        if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+            if !options.contains(value) {
+                violation(format!(
+                    "{} is not one of {}",
+                    value,
+                    Value::from(options.clone())
+                ));
+            }
+        }
+
+        match value {
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                    if number < minimum {
+                        violation(format!("{} is less than the minimum {}", number, minimum));
+                    }
+                }
+                if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                    if number > maximum {
+                        violation(format!(
+                            "{} is greater than the maximum {}",
+                            number, maximum
+                        ));
+                    }
+                }
+            }
+            Value::String(string) => {
+                let length = string.chars().count() as u64;
+                if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                    if length < min {
+                        violation(format!("shorter than {} characters", min));
+                    }
+                }
+                if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                    if length > max {
+                        violation(format!("longer than {} characters", max));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let count = items.len() as u64;
+                if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                    if count < min {
+                        violation(format!("fewer than {} items", min));
+                    }
+                }
+                if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                    if count > max {
+                        violation(format!("more than {} items", max));
+                    }
+                }
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.check(
+                            item_schema,
+                            item,
+                            format!("{}/{}", pointer, index),
+                            depth + 1,
+                            violations,
+                        );
+                    }
+                }
+            }
+            Value::Object(fields) => {
+                let required: Vec<&str> = schema
+                    .get("required")
+                    .and_then(Value::as_array)
+                    .map(|required| required.iter().filter_map(Value::as_str).collect())
+                    .unwrap_or_default();
+                for name in &required {
+                    if !fields.contains_key(*name) {
+                        violation(format!("missing required property '{}'", name));
+                    }
+                }
+
+                let properties = schema.get("properties").and_then(Value::as_object);
+                let additional = schema.get("additionalProperties");
+                for (name, field) in fields {
+                    let field_pointer = format!("{}/{}", pointer, escape(name));
+                    match properties.and_then(|properties| properties.get(name)) {
+                        // Unset options serialize as null
+                        Some(_) if field.is_null() && !required.contains(&name.as_str()) => {}
+                        Some(property) => {
+                            self.check(property, field, field_pointer, depth + 1, violations)
+                        }
+                        None => match additional {
+                            Some(Value::Bool(false)) => violations.push(SchemaViolation {
+                                pointer: field_pointer,
+                                message: "unexpected property".to_string(),
+                            }),
+                            Some(additional @ Value::Object(_)) => {
+                                self.check(additional, field, field_pointer, depth + 1, violations)
+                            }
+                            _ => {}
+                        },
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn validate_at(&self, schema: &Value, value: &Value, depth: usize) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        self.check(schema, value, String::new(), depth, &mut violations);
+        violations
+    }
+}
+
+/// Follow `$ref`s within the spec
+fn resolve<'a>(spec: &'a Value, mut schema: &'a Value) -> Option<&'a Value> {
+    for _ in 0..MAX_DEPTH {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => schema = spec.pointer(reference.strip_prefix('#')?)?,
+            None => return Some(schema),
+        }
+    }
+    None
+}
+
+fn type_matches(ty: &str, value: &Value) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Escape a property name for use in a JSON pointer
+fn escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> ApiSchema {
+        ApiSchema::from_spec(json!({
+            "paths": {
+                "/api/items": {
+                    "post": {
+                        "requestBody": { "content": { "application/json": {
+                            "schema": { "$ref": "#/components/schemas/Item" }
+                        } } },
+                        "responses": {
+                            "200": { "content": { "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Item" }
+                            } } },
+                            "4XX": { "$ref": "#/components/responses/Error" }
+                        }
+                    }
+                },
+                "/api/items/{id}": { "get": { "responses": {} } },
+                "/api/items/latest": { "get": { "responses": {} } }
+            },
+            "components": {
+                "schemas": {
+                    "Item": {
+                        "type": "object",
+                        "required": ["name"],
+                        "additionalProperties": false,
+                        "properties": {
+                            "name": { "type": "string", "minLength": 1 },
+                            "limit": { "type": "integer", "minimum": 1, "maximum": 10 },
+                            "kind": { "type": "string", "enum": ["a", "b"] },
+                            "tags": { "type": "array", "items": { "type": "string" } }
+                        }
+                    }
+                },
+                "responses": {
+                    "Error": { "content": { "application/json": {
+                        "schema": { "type": "object", "required": ["error"] }
+                    } } }
+                }
+            }
+        }))
+    }
+
+    #[test]
+    fn test_valid_request_passes() {
+        let violations = schema()
+            .validate_request(
+                "POST",
+                "/api/items",
+                &json!({ "name": "x", "limit": 3, "kind": "a", "tags": ["t"] }),
+            )
+            .unwrap();
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    #[test]
+    fn test_violations_point_at_fields() {
+        let violations = schema()
+            .validate_request(
+                "POST",
+                "/api/items",
+                &json!({ "limit": 30, "kind": "c", "tags": [1], "extra": true }),
+            )
+            .unwrap();
+        let pointers: Vec<&str> = violations.iter().map(|v| v.pointer.as_str()).collect();
+        assert_eq!(violations.len(), 5, "{:?}", violations);
+        assert!(pointers.contains(&""));
+        assert!(pointers.contains(&"/limit"));
+        assert!(pointers.contains(&"/kind"));
+        assert!(pointers.contains(&"/tags/0"));
+        assert!(pointers.contains(&"/extra"));
+    }
+
+    #[test]
+    fn test_null_optional_property_is_absent() {
+        let violations = schema()
+            .validate_request("POST", "/api/items", &json!({ "name": "x", "limit": null }))
+            .unwrap();
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    #[test]
+    fn test_response_falls_back_to_status_range() {
+        let schema = schema();
+        let violations = schema
+            .validate_response("POST", "/api/items", 404, &json!({ "message": "gone" }))
+            .unwrap();
+        assert_eq!(violations[0].message, "missing required property 'error'");
+        assert!(schema
+            .validate_response("POST", "/api/items", 500, &json!({}))
+            .is_none());
+    }
+
+    #[test]
+    fn test_operation_matching_prefers_literal_segments() {
+        let schema = schema();
+        assert!(schema.documents("get", "/api/items/42"));
+        assert!(schema.documents("GET", "/api/items/latest"));
+        assert!(!schema.documents("delete", "/api/items/42"));
+        assert!(schema
+            .validate_request("get", "/api/items/latest", &json!({}))
+            .is_none());
+    }
+
+    #[test]
+    fn test_embedded_spec_loads() {
+        let schema = ApiSchema::load().unwrap();
+        assert!(schema.documents("post", "/api/search"));
+        assert!(schema.documents("get", "/api/jobs/some-id"));
+    }
+}
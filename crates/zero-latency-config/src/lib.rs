@@ -4,7 +4,6 @@
 /// - Environment-based configuration with ZL_ prefix
 /// - TOML configuration file support
 /// - Configuration precedence handling (env > file > defaults)
-/// - Test configuration utilities for unique ports and collections
 /// - Service-specific config sections
 pub mod loader;
 pub mod models;
@@ -12,8 +11,8 @@ pub mod validation;
 
 // Re-export commonly used types
 pub use models::{
-    AppConfig, ServerConfig, ClientConfig, TestConfig, GlobalConfig, 
-    TestConfigHelper, Config // Legacy compatibility
+    AppConfig, ServerConfig, ClientConfig, TestConfig, GlobalConfig, SummarizerConfig, SummarizerBackend,
+    Config // Legacy compatibility
 };
 pub use loader::{
     ConfigLoader, ConfigResolver, ConfigError,
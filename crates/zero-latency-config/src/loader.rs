@@ -244,20 +244,4 @@ mod tests {
         env::remove_var("ZL_SERVER_HOST");
         env::remove_var("ZL_SERVER_PORT");
     }
-
-    #[test]
-    fn test_test_config_helper() {
-        use crate::models::TestConfigHelper;
-
-        let helper = TestConfigHelper::new();
-
-        let port1 = helper.get_unique_port();
-        let port2 = helper.get_unique_port();
-        assert_ne!(port1, port2);
-
-        let collection1 = helper.get_unique_collection_name();
-        let collection2 = helper.get_unique_collection_name();
-        assert_ne!(collection1, collection2);
-        assert!(collection1.starts_with("test_collection_"));
-    }
 }
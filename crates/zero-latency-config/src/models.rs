@@ -1,6 +1,4 @@
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicU16, Ordering};
-use uuid::Uuid;
 
 /// Main configuration structure for Zero-Latency applications
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +14,10 @@ pub struct AppConfig {
     
     /// Global application settings
     pub app: GlobalConfig,
+    
+    /// Search summarization backend for doc-indexer
+    #[serde(default)]
+    pub summarizer: SummarizerConfig,
 }
 
 /// Server configuration for doc-indexer and other services
@@ -85,6 +87,40 @@ pub struct GlobalConfig {
     pub debug: bool,
 }
 
+/// Backend that writes search summaries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummarizerBackend {
+    /// Built-in extractive summarizer; runs locally and needs no model
+    #[default]
+    Local,
+    /// Any OpenAI-compatible chat completions API (OpenAI, Ollama, vLLM, ...)
+    OpenAI,
+}
+
+/// Search summarization configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummarizerConfig {
+    /// Summarizer backend (default: local)
+    pub backend: SummarizerBackend,
+    
+    /// Most bullets in a summary unless a request asks for fewer
+    pub max_bullets: usize,
+    
+    /// Chat model for the openai backend
+    pub model: String,
+    
+    /// API base URL for the openai backend
+    pub base_url: String,
+    
+    /// API key for the openai backend (local servers usually need none)
+    pub api_key: Option<String>,
+    
+    /// Request timeout for the openai backend in milliseconds
+    pub timeout_ms: u64,
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -92,6 +128,7 @@ impl Default for AppConfig {
             client: ClientConfig::default(),
             test: TestConfig::default(),
             app: GlobalConfig::default(),
+            summarizer: SummarizerConfig::default(),
         }
     }
 }
@@ -141,6 +178,31 @@ impl Default for GlobalConfig {
     }
 }
 
+impl Default for SummarizerConfig {
+    fn default() -> Self {
+        Self {
+            backend: SummarizerBackend::Local,
+            max_bullets: 5,
+            model: "gpt-4o-mini".to_string(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: None,
+            timeout_ms: 30000,
+        }
+    }
+}
+
+impl std::str::FromStr for SummarizerBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(SummarizerBackend::Local),
+            "openai" => Ok(SummarizerBackend::OpenAI),
+            _ => Err(format!("Unknown summarizer backend: {}", s)),
+        }
+    }
+}
+
 impl ServerConfig {
     /// Get the full server URL
     pub fn server_url(&self) -> String {
@@ -157,85 +219,6 @@ impl ClientConfig {
     }
 }
 
-/// Test configuration helper for unique port allocation and collection names
-pub struct TestConfigHelper {
-    port_counter: AtomicU16,
-    base_config: TestConfig,
-}
-
-impl TestConfigHelper {
-    /// Create a new test configuration helper
-    pub fn new() -> Self {
-        Self {
-            port_counter: AtomicU16::new(0),
-            base_config: TestConfig::default(),
-        }
-    }
-    
-    /// Create with custom base configuration
-    pub fn with_config(config: TestConfig) -> Self {
-        Self {
-            port_counter: AtomicU16::new(0),
-            base_config: config,
-        }
-    }
-    
-    /// Get a unique port for testing
-    pub fn get_unique_port(&self) -> u16 {
-        let increment = self.port_counter.fetch_add(1, Ordering::SeqCst);
-        self.base_config.port_base + increment
-    }
-    
-    /// Get a unique collection name for testing
-    pub fn get_unique_collection_name(&self) -> String {
-        let uuid = Uuid::new_v4();
-        format!("{}_{}", self.base_config.collection_prefix, uuid.simple())
-    }
-    
-    /// Resolve binary path with multiple fallback locations
-    pub fn resolve_binary_path(&self) -> Option<String> {
-        if let Some(path) = &self.base_config.binary_path {
-            if std::path::Path::new(path).exists() {
-                return Some(path.clone());
-            }
-        }
-        
-        // Fallback paths to search for doc-indexer binary
-        let fallback_paths = [
-            "./target/debug/doc-indexer",
-            "./target/release/doc-indexer", 
-            "./services/doc-indexer/target/debug/doc-indexer",
-            "./services/doc-indexer/target/release/doc-indexer",
-            "doc-indexer", // In PATH
-        ];
-        
-        for path in &fallback_paths {
-            if std::path::Path::new(path).exists() {
-                return Some(path.to_string());
-            }
-        }
-        
-        None
-    }
-    
-    /// Create a complete test configuration with unique values
-    pub fn create_test_config(&self) -> TestConfig {
-        TestConfig {
-            port_base: self.get_unique_port(),
-            fixture_path: self.base_config.fixture_path.clone(),
-            binary_path: self.resolve_binary_path(),
-            collection_prefix: self.get_unique_collection_name(),
-            timeout_ms: self.base_config.timeout_ms,
-        }
-    }
-}
-
-impl Default for TestConfigHelper {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 /// Legacy Config struct for backward compatibility
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -1,4 +1,4 @@
-use crate::models::{AppConfig, ServerConfig, ClientConfig, TestConfig, GlobalConfig};
+use crate::models::{AppConfig, ServerConfig, ClientConfig, TestConfig, GlobalConfig, SummarizerConfig, SummarizerBackend};
 use crate::loader::ConfigError;
 
 /// Configuration validation trait
@@ -16,6 +16,7 @@ impl ConfigValidator<AppConfig> for DefaultValidator {
         ClientConfigValidator.validate(&config.client)?;
         TestConfigValidator.validate(&config.test)?;
         GlobalConfigValidator.validate(&config.app)?;
+        SummarizerConfigValidator.validate(&config.summarizer)?;
         Ok(())
     }
 }
@@ -176,6 +177,43 @@ impl ConfigValidator<GlobalConfig> for GlobalConfigValidator {
     }
 }
 
+/// Summarizer configuration validator
+pub struct SummarizerConfigValidator;
+
+impl ConfigValidator<SummarizerConfig> for SummarizerConfigValidator {
+    fn validate(&self, config: &SummarizerConfig) -> Result<(), ConfigError> {
+        // Validate bullet count
+        if config.max_bullets == 0 {
+            return Err(ConfigError::ValidationError(
+                "Summarizer max bullets must be greater than 0".to_string()
+            ));
+        }
+
+        // API settings only matter for the openai backend
+        if config.backend == SummarizerBackend::OpenAI {
+            if !config.base_url.starts_with("http://") && !config.base_url.starts_with("https://") {
+                return Err(ConfigError::ValidationError(
+                    "Summarizer base URL must start with http:// or https://".to_string()
+                ));
+            }
+
+            if config.model.is_empty() {
+                return Err(ConfigError::ValidationError(
+                    "Summarizer model cannot be empty".to_string()
+                ));
+            }
+
+            if config.timeout_ms == 0 {
+                return Err(ConfigError::ValidationError(
+                    "Summarizer timeout must be greater than 0".to_string()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Convenience function to validate configuration
 pub fn validate_config(config: &AppConfig) -> Result<(), ConfigError> {
     DefaultValidator.validate(config)
@@ -230,4 +268,16 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("must start with http"));
     }
+
+    #[test]
+    fn test_invalid_summarizer_base_url() {
+        let mut config = AppConfig::default();
+        config.summarizer.base_url = "api.example.com".to_string();
+        assert!(validate_config(&config).is_ok());
+
+        config.summarizer.backend = SummarizerBackend::OpenAI;
+        let result = validate_config(&config);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Summarizer base URL"));
+    }
 }
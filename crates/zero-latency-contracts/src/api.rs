@@ -47,6 +47,7 @@ pub mod endpoints {
     pub const ANALYTICS_SUMMARY: &str = "/api/analytics/summary";
     pub const ANALYTICS_POPULAR_QUERIES: &str = "/api/analytics/popular-queries";
     pub const ANALYTICS_SEARCH_TRENDS: &str = "/api/analytics/search-trends";
+    pub const ANALYTICS_SLOW_QUERIES: &str = "/api/analytics/slow-queries";
 
     // Helper functions for dynamic endpoints
     pub fn collection_by_name(name: &str) -> String {
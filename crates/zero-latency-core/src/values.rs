@@ -146,14 +146,51 @@ impl Display for ServiceVersion {
 }
 
 /// Helper function to normalize search queries
+///
+/// Lowercases the query, drops punctuation and collapses runs of whitespace
+/// in a single pass, allocating only the returned string for ASCII input.
 fn normalize_query(query: &str) -> String {
-    query
-        .trim()
-        .to_lowercase()
-        .chars()
-        .filter(|c| c.is_alphanumeric() || c.is_whitespace() || *c == '-' || *c == '_')
-        .collect::<String>()
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
+    // Non-ASCII lowercasing depends on context (e.g. a final sigma), so it
+    // is done on the whole string up front
+    let lowered;
+    let query = if query.is_ascii() {
+        query
+    } else {
+        lowered = query.to_lowercase();
+        lowered.as_str()
+    };
+
+    let mut normalized = String::with_capacity(query.len());
+    let mut pending_space = false;
+
+    for c in query.chars().map(|c| c.to_ascii_lowercase()) {
+        if c.is_whitespace() {
+            pending_space = !normalized.is_empty();
+        } else if c.is_alphanumeric() || c == '-' || c == '_' {
+            if pending_space {
+                normalized.push(' ');
+                pending_space = false;
+            }
+            normalized.push(c);
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_query() {
+        assert_eq!(normalize_query("  Rust  Async/Await!  "), "rust asyncawait");
+        assert_eq!(normalize_query("a ! b\tc"), "a b c");
+        assert_eq!(
+            normalize_query("tokio::spawn_blocking"),
+            "tokiospawn_blocking"
+        );
+        assert_eq!(normalize_query("?!"), "");
+        assert_eq!(normalize_query("ΟΔΥΣΣΕΥΣ Café"), "οδυσσευς café");
+    }
 }
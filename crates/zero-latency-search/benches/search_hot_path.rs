@@ -0,0 +1,210 @@
+/// Search hot path benchmarks
+///
+/// Measures the per-request work that dominates small-query latency: query
+/// parsing, conversion of vector hits into search results, and a full pass
+/// through the search pipeline with in-memory stubs for the embedding
+/// service, vector store, ranker and analytics.
+use async_trait::async_trait;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use std::collections::HashMap;
+use std::sync::Arc;
+use zero_latency_core::{models::HealthStatus, values::Score, Result, Uuid};
+use zero_latency_search::{
+    AnalyticsStep, EmbeddingService, PopularQuery, RankingSignals, ResultRanker, ResultRankingStep,
+    SearchAnalytics, SearchFilters, SearchPipeline, SearchRequest, SearchResponse, SearchResult,
+    SearchTrends, VectorSearchStep,
+};
+use zero_latency_vector::{SimilarityResult, VectorDocument, VectorMetadata, VectorRepository};
+
+const RESULT_COUNT: usize = 20;
+
+fn similarity_results() -> Vec<SimilarityResult> {
+    (0..RESULT_COUNT)
+        .map(|i| {
+            let mut custom = HashMap::new();
+            custom.insert("path".to_string(), format!("/docs/guide-{}.md", i));
+
+            SimilarityResult {
+                document_id: Uuid::new_v4(),
+                similarity: Score::new(1.0 - i as f32 / RESULT_COUNT as f32).unwrap(),
+                metadata: VectorMetadata {
+                    document_id: Uuid::new_v4(),
+                    chunk_index: i,
+                    content: "Async runtimes schedule futures cooperatively. ".repeat(12),
+                    title: format!("Guide {}", i),
+                    heading_path: vec!["Concurrency".to_string(), "Runtimes".to_string()],
+                    url: None,
+                    collection: Some("docs".to_string()),
+                    custom,
+                    ..Default::default()
+                },
+            }
+        })
+        .collect()
+}
+
+struct StubEmbeddings;
+
+#[async_trait]
+impl EmbeddingService for StubEmbeddings {
+    async fn generate_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(vec![0.1; 384])
+    }
+}
+
+struct StubVectorStore {
+    results: Vec<SimilarityResult>,
+}
+
+#[async_trait]
+impl VectorRepository for StubVectorStore {
+    async fn insert(&self, _vectors: Vec<VectorDocument>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn search(&self, _query_vector: Vec<f32>, k: usize) -> Result<Vec<SimilarityResult>> {
+        Ok(self.results.iter().take(k).cloned().collect())
+    }
+
+    async fn search_in_collection(
+        &self,
+        _collection_name: &str,
+        query_vector: Vec<f32>,
+        k: usize,
+    ) -> Result<Vec<SimilarityResult>> {
+        self.search(query_vector, k).await
+    }
+
+    async fn delete(&self, _document_id: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn update(&self, _document_id: &str, _vector: Vec<f32>) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus> {
+        Ok(HealthStatus::Healthy)
+    }
+
+    async fn count(&self) -> Result<usize> {
+        Ok(self.results.len())
+    }
+}
+
+struct IdentityRanker;
+
+#[async_trait]
+impl ResultRanker for IdentityRanker {
+    async fn rank(&self, results: Vec<SearchResult>) -> Result<Vec<SearchResult>> {
+        Ok(results)
+    }
+
+    async fn explain_ranking(&self, result: &SearchResult) -> Result<RankingSignals> {
+        Ok(RankingSignals {
+            vector_similarity: result.final_score,
+            term_frequency: Score::zero(),
+            document_frequency: Score::zero(),
+            title_boost: 0.0,
+            freshness_boost: 0.0,
+            custom_signals: HashMap::new(),
+        })
+    }
+}
+
+struct NoopAnalytics;
+
+#[async_trait]
+impl SearchAnalytics for NoopAnalytics {
+    async fn record_search(
+        &self,
+        request: &SearchRequest,
+        response: &SearchResponse,
+    ) -> Result<()> {
+        black_box((request, response.results.len()));
+        Ok(())
+    }
+
+    async fn get_popular_queries(&self, _limit: usize) -> Result<Vec<PopularQuery>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_search_trends(&self) -> Result<SearchTrends> {
+        Ok(SearchTrends {
+            total_searches: 0,
+            unique_queries: 0,
+            avg_response_time: 0.0,
+            top_categories: Vec::new(),
+        })
+    }
+}
+
+fn collection_request(query: &str) -> SearchRequest {
+    let mut filters = SearchFilters::default();
+    filters
+        .custom
+        .insert("collection".to_string(), "docs".to_string());
+
+    SearchRequest::new(query)
+        .with_limit(RESULT_COUNT)
+        .with_filters(filters)
+}
+
+fn bench_query_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("query_parsing");
+    for (name, query) in [
+        ("short", "tokio runtime"),
+        ("punctuated", "  How do I use async/await in Rust?  "),
+        ("unicode", "Überblick: Fehlerbehandlung mit Result<T, E>"),
+    ] {
+        group.bench_function(name, |b| b.iter(|| collection_request(black_box(query))));
+    }
+    group.finish();
+}
+
+fn bench_result_assembly(c: &mut Criterion) {
+    let results = similarity_results();
+
+    c.bench_function("result_assembly", |b| {
+        b.iter_batched(
+            || results.clone(),
+            |results| {
+                results
+                    .into_iter()
+                    .map(SearchResult::from)
+                    .collect::<Vec<_>>()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_pipeline(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let pipeline = SearchPipeline::builder()
+        .add_step(Box::new(VectorSearchStep::new(
+            Arc::new(StubVectorStore {
+                results: similarity_results(),
+            }),
+            Arc::new(StubEmbeddings),
+        )))
+        .add_step(Box::new(ResultRankingStep::new(Arc::new(IdentityRanker))))
+        .add_step(Box::new(AnalyticsStep::new(Arc::new(NoopAnalytics))))
+        .build();
+
+    c.bench_function("pipeline_small_query", |b| {
+        b.to_async(&runtime)
+            .iter(|| pipeline.execute(collection_request("tokio runtime")))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_query_parsing,
+    bench_result_assembly,
+    bench_pipeline
+);
+criterion_main!(benches);
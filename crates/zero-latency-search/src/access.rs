@@ -0,0 +1,119 @@
+//! Access control on indexed documents
+//!
+//! Connectors record who may read a document in its metadata: user and group
+//! names, comma-separated, under [`ACL_USERS_KEY`] and [`ACL_GROUPS_KEY`]. A
+//! document with neither is readable by everyone. A search made for a
+//! [`Principal`] only returns documents it may read; like exclusions, the
+//! check is applied by each retrieval step before fusion, so a document the
+//! principal cannot read contributes no score.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::SearchResult;
+
+/// Metadata key of the users allowed to read a document
+pub const ACL_USERS_KEY: &str = "acl_users";
+
+/// Metadata key of the groups allowed to read a document
+pub const ACL_GROUPS_KEY: &str = "acl_groups";
+
+/// The user a search is made for and the groups they belong to
+///
+/// The default principal is anonymous and only reads unrestricted documents.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Principal {
+    pub user: Option<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+impl Principal {
+    /// Whether the principal may read a document with `metadata`
+    ///
+    /// Names compare case-insensitively.
+    pub fn can_read(&self, metadata: &HashMap<String, String>) -> bool {
+        let users = acl_entries(metadata, ACL_USERS_KEY);
+        let groups = acl_entries(metadata, ACL_GROUPS_KEY);
+        if users.is_empty() && groups.is_empty() {
+            return true;
+        }
+        let listed =
+            |names: &[&str], name: &str| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+        self.user
+            .as_deref()
+            .is_some_and(|user| listed(&users, user))
+            || self.groups.iter().any(|group| listed(&groups, group))
+    }
+
+    /// Drop the results the principal may not read, returning how many were
+    /// dropped
+    pub fn apply(&self, results: &mut Vec<SearchResult>) -> usize {
+        let before = results.len();
+        results.retain(|result| self.can_read(&result.custom_metadata));
+        before - results.len()
+    }
+
+    /// Identifies the principal in cache keys, so cached results are only
+    /// served to principals with the same entitlements
+    pub fn cache_key(&self) -> String {
+        let mut groups: Vec<String> = self.groups.iter().map(|g| g.to_lowercase()).collect();
+        groups.sort();
+        groups.dedup();
+        format!(
+            "{}|{}",
+            self.user.as_deref().unwrap_or_default().to_lowercase(),
+            groups.join(",")
+        )
+    }
+}
+
+fn acl_entries<'a>(metadata: &'a HashMap<String, String>, key: &str) -> Vec<&'a str> {
+    metadata
+        .get(key)
+        .map(|names| {
+            names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn acl(users: &str, groups: &str) -> HashMap<String, String> {
+        HashMap::from([
+            (ACL_USERS_KEY.to_string(), users.to_string()),
+            (ACL_GROUPS_KEY.to_string(), groups.to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_principals_read_documents_listing_them_or_their_groups() {
+        let alice = Principal {
+            user: Some("alice@example.com".to_string()),
+            groups: vec!["Engineering".to_string()],
+        };
+        let anonymous = Principal::default();
+
+        // Unrestricted documents are readable by everyone
+        assert!(anonymous.can_read(&HashMap::new()));
+        assert!(anonymous.can_read(&acl("", " ")));
+
+        assert!(alice.can_read(&acl("bob, Alice@Example.com", "")));
+        assert!(alice.can_read(&acl("", "sales, engineering")));
+        assert!(!alice.can_read(&acl("bob", "sales")));
+        assert!(!anonymous.can_read(&acl("", "engineering")));
+
+        let same = Principal {
+            user: Some("ALICE@example.com".to_string()),
+            groups: vec!["engineering".to_string(), "Engineering".to_string()],
+        };
+        assert_eq!(alice.cache_key(), same.cache_key());
+        assert_ne!(alice.cache_key(), anonymous.cache_key());
+    }
+}
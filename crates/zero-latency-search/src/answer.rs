@@ -0,0 +1,325 @@
+//! Extractive question answering
+//!
+//! For question-style queries the search service can run an
+//! [`AnswerExtractor`] over the top results and return a direct answer span
+//! alongside them. The span is copied verbatim from one result's content, so
+//! it can always be traced back to its source.
+//!
+//! [`LexicalAnswerExtractor`] is the built-in local extractor. It needs no
+//! model: it picks the sentence of the top passages that covers most of the
+//! question's content words, preferring higher-ranked passages and shorter
+//! sentences. Model-backed extractors plug in through the same trait.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+use zero_latency_core::Result;
+
+use crate::models::SearchResult;
+
+/// Words that open a question
+pub(crate) const QUESTION_WORDS: &[&str] = &[
+    "what", "why", "how", "when", "where", "which", "who", "whom", "whose", "can", "could", "does",
+    "do", "did", "is", "are", "should", "would", "will",
+];
+
+/// Words that carry no content when matching a question against a passage
+pub(crate) const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "of", "to", "in", "on", "for", "with", "and", "or", "by", "at", "from", "as",
+    "be", "it", "its", "this", "that", "these", "those", "i", "you", "we", "they", "my", "your",
+    "our", "me", "there", "if", "into", "about", "use", "using", "get", "has", "have", "was",
+    "were", "been", "not", "no", "so", "any", "some",
+];
+
+/// A direct answer found in a search result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtractedAnswer {
+    /// The answer span, verbatim
+    pub text: String,
+    /// Confidence between 0 and 1
+    pub confidence: f32,
+    /// Document and chunk the span was taken from
+    pub document_id: Uuid,
+    pub chunk_id: Uuid,
+    pub uri: String,
+    pub title: String,
+    /// Byte range of the span within the chunk's content
+    pub start: usize,
+    pub end: usize,
+    /// Extractor that produced the answer
+    pub extractor: String,
+}
+
+/// Extracts a direct answer to a question from ranked passages
+#[async_trait]
+pub trait AnswerExtractor: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Best answer in `passages`, which are ordered best first, if any is
+    /// confident enough
+    async fn extract(
+        &self,
+        question: &str,
+        passages: &[SearchResult],
+    ) -> Result<Option<ExtractedAnswer>>;
+}
+
+/// Whether a query reads as a question
+pub fn is_question(query: &str) -> bool {
+    let query = query.trim();
+    if query.ends_with('?') {
+        return true;
+    }
+    query
+        .split_whitespace()
+        .next()
+        .map(|word| QUESTION_WORDS.contains(&word.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Local extractor choosing the sentence that best covers the question
+#[derive(Debug, Clone)]
+pub struct LexicalAnswerExtractor {
+    /// Top passages searched for an answer
+    pub max_passages: usize,
+    /// Answers below this confidence are dropped
+    pub min_confidence: f32,
+}
+
+impl Default for LexicalAnswerExtractor {
+    fn default() -> Self {
+        Self {
+            max_passages: 3,
+            min_confidence: 0.4,
+        }
+    }
+}
+
+impl LexicalAnswerExtractor {
+    fn best_span(&self, question: &str, passages: &[SearchResult]) -> Option<ExtractedAnswer> {
+        let question_terms: HashSet<String> = content_terms(question).collect();
+        if question_terms.is_empty() {
+            return None;
+        }
+
+        // (confidence, sentence length, passage, byte range)
+        let mut best: Option<(f32, usize, &SearchResult, usize, usize)> = None;
+        for (rank, passage) in passages.iter().take(self.max_passages).enumerate() {
+            // Later passages need clearly better coverage to win
+            let rank_prior = 1.0 / (1.0 + 0.2 * rank as f32);
+            for (start, end) in sentences(&passage.content) {
+                let sentence = &passage.content[start..end];
+                let sentence_terms: HashSet<String> = content_terms(sentence).collect();
+                let matched = question_terms
+                    .iter()
+                    .filter(|term| sentence_terms.contains(*term))
+                    .count();
+                if matched == 0 {
+                    continue;
+                }
+                let coverage = matched as f32 / question_terms.len() as f32;
+                let confidence = coverage * rank_prior;
+                let len = end - start;
+                let better = match best {
+                    None => true,
+                    Some((best_confidence, best_len, ..)) => {
+                        confidence > best_confidence
+                            || (confidence == best_confidence && len < best_len)
+                    }
+                };
+                if better {
+                    best = Some((confidence, len, passage, start, end));
+                }
+            }
+        }
+
+        let (confidence, _, passage, start, end) = best?;
+        if confidence < self.min_confidence {
+            return None;
+        }
+        Some(ExtractedAnswer {
+            text: passage.content[start..end].to_string(),
+            confidence,
+            document_id: passage.document_id,
+            chunk_id: passage.chunk_id,
+            uri: passage.uri.clone(),
+            title: passage.title.clone(),
+            start,
+            end,
+            extractor: self.name().to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl AnswerExtractor for LexicalAnswerExtractor {
+    fn name(&self) -> &str {
+        "lexical"
+    }
+
+    async fn extract(
+        &self,
+        question: &str,
+        passages: &[SearchResult],
+    ) -> Result<Option<ExtractedAnswer>> {
+        Ok(self.best_span(question, passages))
+    }
+}
+
+/// Lowercased, lightly stemmed words that aren't stop or question words
+pub(crate) fn content_terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .filter(|word| {
+            !STOP_WORDS.contains(&word.as_str()) && !QUESTION_WORDS.contains(&word.as_str())
+        })
+        .map(|word| stem(&word))
+}
+
+/// Strip common English suffixes so `indexes` matches `index` and
+/// `configured` matches `configure`
+pub(crate) fn stem(word: &str) -> String {
+    let mut stem = word;
+    if let Some(stripped) = ["ing", "ed"]
+        .iter()
+        .find_map(|suffix| stem.strip_suffix(suffix))
+        .filter(|stripped| stripped.len() >= 3)
+    {
+        stem = stripped;
+    } else if ["sses", "xes", "zes", "ches", "shes"]
+        .iter()
+        .any(|suffix| stem.ends_with(suffix))
+    {
+        stem = &stem[..stem.len() - 2];
+    } else if stem.ends_with('s') && !stem.ends_with("ss") && stem.len() > 3 {
+        stem = &stem[..stem.len() - 1];
+    }
+    if stem.len() > 4 {
+        stem = stem.strip_suffix('e').unwrap_or(stem);
+    }
+    stem.to_string()
+}
+
+/// Byte ranges of the sentences in `text`, trimmed of surrounding whitespace
+///
+/// Sentences end at `.`, `?` or `!` followed by whitespace, and at line
+/// breaks, so headings and list items stand on their own.
+pub(crate) fn sentences(text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let end = match c {
+            '\n' => Some(index),
+            '.' | '?' | '!' => match chars.peek() {
+                Some((_, next)) if next.is_whitespace() => Some(index + c.len_utf8()),
+                None => Some(index + c.len_utf8()),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(end) = end {
+            push_trimmed(text, start, end, &mut ranges);
+            start = end;
+        }
+    }
+    push_trimmed(text, start, text.len(), &mut ranges);
+    ranges
+}
+
+fn push_trimmed(text: &str, start: usize, end: usize, ranges: &mut Vec<(usize, usize)>) {
+    let slice = &text[start..end];
+    let leading = slice.len() - slice.trim_start().len();
+    let trimmed = slice.trim();
+    // Skip list bullets and heading markers left at the start of a line
+    let marker = trimmed.len() - trimmed.trim_start_matches(['#', '-', '*', '>', ' ']).len();
+    let start = start + leading + marker;
+    let end = start - marker + trimmed.len();
+    if end > start {
+        ranges.push((start, end));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fusion::{FromSignals, NormalizationMethod, ScoreBreakdown};
+    use zero_latency_core::DocId;
+
+    fn passage(content: &str) -> SearchResult {
+        SearchResult::new(
+            DocId::new("default", "guide", 1),
+            "docs/guide.md".to_string(),
+            "Guide".to_string(),
+            content.to_string(),
+            ScoreBreakdown {
+                bm25_raw: None,
+                vector_raw: Some(0.5),
+                bm25_normalized: None,
+                vector_normalized: Some(0.5),
+                fused: 0.5,
+                normalization_method: NormalizationMethod::MinMax,
+            },
+            FromSignals::vector_only(),
+        )
+    }
+
+    #[test]
+    fn test_is_question() {
+        assert!(is_question("How do I rotate the API keys?"));
+        assert!(is_question("what is the default port"));
+        assert!(is_question("rotate keys?"));
+        assert!(!is_question("api key rotation"));
+        assert!(!is_question(""));
+    }
+
+    #[test]
+    fn test_stem() {
+        assert_eq!(stem("indexes"), stem("index"));
+        assert_eq!(stem("services"), stem("service"));
+        assert_eq!(stem("configured"), stem("configure"));
+        assert_eq!(stem("listens"), "listen");
+        assert_eq!(stem("class"), "class");
+    }
+
+    #[test]
+    fn test_sentences() {
+        let text = "# Setup\nInstall it. Then run v1.2 now!\n- item one";
+        let spans: Vec<&str> = sentences(text)
+            .into_iter()
+            .map(|(start, end)| &text[start..end])
+            .collect();
+        assert_eq!(
+            spans,
+            vec!["Setup", "Install it.", "Then run v1.2 now!", "item one"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extracts_best_covering_sentence() {
+        let extractor = LexicalAnswerExtractor::default();
+        let passages = vec![
+            passage("The server reads its settings at startup. Logs go to stdout."),
+            passage("By default the server listens on port 8081. Change it with --port."),
+        ];
+
+        let answer = extractor
+            .extract("Which port does the server listen on?", &passages)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(answer.text, "By default the server listens on port 8081.");
+        assert_eq!(&passages[1].content[answer.start..answer.end], answer.text);
+        assert_eq!(answer.extractor, "lexical");
+        assert!(answer.confidence > 0.5 && answer.confidence <= 1.0);
+
+        let none = extractor
+            .extract("How are embeddings quantized?", &passages)
+            .await
+            .unwrap();
+        assert!(none.is_none());
+    }
+}
@@ -0,0 +1,256 @@
+//! BM25F field-weighted scoring
+//!
+//! Plain multi-field BM25 scores each field on its own and adds the boosted
+//! scores up, so a term repeated in the title and the body saturates twice
+//! and a long body dilutes nothing but itself. BM25F instead folds the term
+//! frequencies of all fields into one weighted, length-normalized frequency
+//! before saturating it once:
+//!
+//! ```text
+//! tf~(t, d)    = Σ_f weight_f · tf_f / (1 - b_f + b_f · len_f / avg_len_f)
+//! score(q, d)  = Σ_t idf(t) · tf~ / (k1 + tf~)
+//! ```
+//!
+//! The field weights are the [`FieldBoosts`] of the lexical index, which a
+//! collection or request can override through its search settings.
+
+use serde::{Deserialize, Serialize};
+
+use super::FieldBoosts;
+
+/// How lexical matches in several fields combine into one score
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LexicalScoring {
+    /// BM25 per field, boosted and summed
+    #[default]
+    PerField,
+    /// BM25F over the weighted fields
+    Bm25f,
+}
+
+/// Saturation and per-field length normalization of BM25F
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bm25fParameters {
+    /// How quickly repeated terms stop adding to the score
+    pub k1: f32,
+    /// How much each field's length discounts its matches (0.0-1.0)
+    pub length_normalization: FieldLengthNormalization,
+}
+
+impl Default for Bm25fParameters {
+    fn default() -> Self {
+        Self {
+            k1: 1.2,
+            length_normalization: FieldLengthNormalization::default(),
+        }
+    }
+}
+
+/// The `b` parameter of each field
+///
+/// Titles and headings are short and similar in length, so their length
+/// says little; body text varies the most.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldLengthNormalization {
+    pub title: f32,
+    pub heading: f32,
+    pub path: f32,
+    pub content: f32,
+}
+
+impl Default for FieldLengthNormalization {
+    fn default() -> Self {
+        Self {
+            title: 0.3,
+            heading: 0.3,
+            path: 0.3,
+            content: 0.75,
+        }
+    }
+}
+
+impl Bm25fParameters {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.k1.is_finite() && self.k1 > 0.0) {
+            return Err(format!("k1 must be greater than 0, got {}", self.k1));
+        }
+        for field in LexicalField::ALL {
+            let b = field.length_normalization(&self.length_normalization);
+            if !(0.0..=1.0).contains(&b) {
+                return Err(format!(
+                    "Length normalization of {} must be between 0 and 1, got {}",
+                    field.name(),
+                    b
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fields of the lexical index that scores combine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexicalField {
+    Title,
+    Heading,
+    Path,
+    Content,
+}
+
+impl LexicalField {
+    pub const ALL: [Self; 4] = [Self::Title, Self::Heading, Self::Path, Self::Content];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Heading => "heading",
+            Self::Path => "path",
+            Self::Content => "content",
+        }
+    }
+
+    pub fn weight(self, boosts: &FieldBoosts) -> f32 {
+        match self {
+            Self::Title => boosts.title,
+            Self::Heading => boosts.heading,
+            Self::Path => boosts.path,
+            Self::Content => boosts.content,
+        }
+    }
+
+    fn length_normalization(self, normalization: &FieldLengthNormalization) -> f32 {
+        match self {
+            Self::Title => normalization.title,
+            Self::Heading => normalization.heading,
+            Self::Path => normalization.path,
+            Self::Content => normalization.content,
+        }
+    }
+}
+
+/// Occurrences of a term in one field of a document
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FieldOccurrences {
+    pub term_frequency: u32,
+    /// Tokens in the document's field
+    pub length: u32,
+    /// Mean tokens in the field over the index
+    pub average_length: f32,
+}
+
+/// A query term's rarity and its occurrences in one document, by
+/// [`LexicalField::ALL`] order
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TermOccurrences {
+    pub idf: f32,
+    pub fields: [FieldOccurrences; 4],
+}
+
+/// Inverse document frequency of a term in `doc_freq` of `num_docs` documents
+pub fn inverse_document_frequency(doc_freq: u64, num_docs: u64) -> f32 {
+    let doc_freq = doc_freq.min(num_docs) as f32;
+    (1.0 + (num_docs as f32 - doc_freq + 0.5) / (doc_freq + 0.5)).ln()
+}
+
+/// BM25F score of a document for the query terms it was matched with
+pub fn bm25f_score(
+    terms: &[TermOccurrences],
+    weights: &FieldBoosts,
+    parameters: &Bm25fParameters,
+) -> f32 {
+    terms
+        .iter()
+        .map(|term| {
+            let frequency: f32 = LexicalField::ALL
+                .into_iter()
+                .zip(term.fields)
+                .filter(|(_, occurrences)| occurrences.term_frequency > 0)
+                .map(|(field, occurrences)| {
+                    let b = field.length_normalization(&parameters.length_normalization);
+                    let relative_length = if occurrences.average_length > 0.0 {
+                        occurrences.length as f32 / occurrences.average_length
+                    } else {
+                        1.0
+                    };
+                    field.weight(weights) * occurrences.term_frequency as f32
+                        / (1.0 - b + b * relative_length)
+                })
+                .sum();
+            term.idf * frequency / (parameters.k1 + frequency)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn occurrences(title: u32, content: u32, content_length: u32) -> TermOccurrences {
+        let field = |term_frequency, length| FieldOccurrences {
+            term_frequency,
+            length,
+            average_length: 10.0,
+        };
+        TermOccurrences {
+            idf: inverse_document_frequency(10, 1000),
+            fields: [
+                field(title, 4),
+                field(0, 4),
+                field(0, 4),
+                field(content, content_length),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_bm25f_weights_fields_and_saturates_once() {
+        let weights = FieldBoosts::default();
+        let parameters = Bm25fParameters::default();
+        let score = |term| bm25f_score(&[term], &weights, &parameters);
+
+        // A title match is worth more than a body match
+        assert!(score(occurrences(1, 0, 10)) > score(occurrences(0, 1, 10)));
+        // Repeats add less and less, across fields too
+        let once = score(occurrences(0, 1, 10));
+        let twice = score(occurrences(1, 1, 10));
+        assert!(twice > once && twice < 2.0 * once);
+        // The same body match in a longer body counts for less
+        assert!(score(occurrences(0, 1, 40)) < once);
+        // Never above the term's idf
+        assert!(score(occurrences(15, 15, 10)) < occurrences(0, 0, 0).idf);
+
+        // Weights can switch a field off
+        let no_title = FieldBoosts {
+            title: 0.0,
+            ..weights
+        };
+        assert_eq!(
+            bm25f_score(&[occurrences(3, 0, 10)], &no_title, &parameters),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_rare_terms_weigh_more_and_parameters_are_validated() {
+        assert!(inverse_document_frequency(1, 1000) > inverse_document_frequency(500, 1000));
+        assert!(inverse_document_frequency(1000, 1000) > 0.0);
+
+        assert!(Bm25fParameters::default().validate().is_ok());
+        let stiff = Bm25fParameters {
+            k1: 0.0,
+            ..Default::default()
+        };
+        assert!(stiff.validate().is_err());
+        let out_of_range = Bm25fParameters {
+            length_normalization: FieldLengthNormalization {
+                content: 1.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(out_of_range.validate().is_err());
+    }
+}
@@ -0,0 +1,239 @@
+//! Tiered segment merging
+//!
+//! The lexical index is a set of immutable segments: every commit writes the
+//! new documents to a fresh segment and marks deleted ones in a bitset, and
+//! searches read a snapshot of the segments without taking any lock. Left
+//! alone, a stream of small commits (as in watch mode) would leave thousands
+//! of tiny segments to search, so segments are merged in the background.
+//!
+//! Segments are grouped into tiers by size, each tier `segments_per_tier`
+//! times larger than the one below. Once a tier holds `segments_per_tier`
+//! segments, its smallest ones are merged into one segment of the next tier.
+//! A document is therefore rewritten about once per tier, and segments that
+//! reached `max_segment_docs` are never merged again, which bounds write
+//! amplification. Segments whose share of deleted documents exceeds
+//! `deletes_pct_allowed` are rewritten on their own to reclaim the space.
+
+use serde::{Deserialize, Serialize};
+
+/// Live and deleted documents of a segment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SegmentSize {
+    pub live_docs: u32,
+    pub deleted_docs: u32,
+}
+
+impl SegmentSize {
+    fn deleted_pct(&self) -> f32 {
+        let total = self.live_docs + self.deleted_docs;
+        if total == 0 {
+            return 0.0;
+        }
+        self.deleted_docs as f32 * 100.0 / total as f32
+    }
+}
+
+/// Merge policy of the lexical index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TieredMergePolicy {
+    /// Segments a tier holds before some of them are merged; also the size
+    /// ratio between tiers
+    pub segments_per_tier: usize,
+    /// Most segments merged into one at a time
+    pub max_merge_at_once: usize,
+    /// Segments smaller than this count as this size, so tiny segments share
+    /// the lowest tier
+    pub floor_segment_docs: u32,
+    /// Segments are never merged beyond this many documents
+    pub max_segment_docs: u32,
+    /// Share of deleted documents (0-100) above which a segment is rewritten
+    pub deletes_pct_allowed: f32,
+}
+
+impl Default for TieredMergePolicy {
+    fn default() -> Self {
+        Self {
+            segments_per_tier: 10,
+            max_merge_at_once: 10,
+            floor_segment_docs: 1_000,
+            max_segment_docs: 5_000_000,
+            deletes_pct_allowed: 20.0,
+        }
+    }
+}
+
+impl TieredMergePolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.segments_per_tier < 2 {
+            return Err("Segments per tier must be at least 2".to_string());
+        }
+        if self.max_merge_at_once < 2 {
+            return Err("Max merge at once must be at least 2".to_string());
+        }
+        if self.floor_segment_docs == 0 || self.max_segment_docs <= self.floor_segment_docs {
+            return Err(
+                "Max segment docs must be greater than a positive floor segment docs".to_string(),
+            );
+        }
+        if !(0.0..=100.0).contains(&self.deletes_pct_allowed) {
+            return Err(format!(
+                "Deletes percentage allowed must be between 0 and 100, got {}",
+                self.deletes_pct_allowed
+            ));
+        }
+        Ok(())
+    }
+
+    /// Groups of segments, by index into `segments`, to merge into one each
+    pub fn select_merges(&self, segments: &[SegmentSize]) -> Vec<Vec<usize>> {
+        let mut merges = Vec::new();
+        let mut merging = vec![false; segments.len()];
+
+        // Full tiers merge their smallest segments
+        let mut tiers: Vec<Vec<usize>> = Vec::new();
+        for (i, segment) in segments.iter().enumerate() {
+            if segment.live_docs >= self.max_segment_docs {
+                continue;
+            }
+            let tier = self.tier(segment.live_docs);
+            if tiers.len() <= tier {
+                tiers.resize(tier + 1, Vec::new());
+            }
+            tiers[tier].push(i);
+        }
+        for mut tier in tiers {
+            if tier.len() < self.segments_per_tier {
+                continue;
+            }
+            tier.sort_by_key(|&i| segments[i].live_docs);
+            let mut merge = Vec::new();
+            let mut docs = 0u64;
+            for i in tier.into_iter().take(self.max_merge_at_once) {
+                docs += u64::from(segments[i].live_docs);
+                if docs > u64::from(self.max_segment_docs) {
+                    break;
+                }
+                merge.push(i);
+            }
+            if merge.len() > 1 {
+                for &i in &merge {
+                    merging[i] = true;
+                }
+                merges.push(merge);
+            }
+        }
+
+        // Segments with many deletes are rewritten without them
+        for (i, segment) in segments.iter().enumerate() {
+            if !merging[i] && segment.deleted_pct() > self.deletes_pct_allowed {
+                merges.push(vec![i]);
+            }
+        }
+        merges
+    }
+
+    fn tier(&self, live_docs: u32) -> usize {
+        let mut tier = 0;
+        let mut bound = u64::from(self.floor_segment_docs);
+        while u64::from(live_docs) >= bound {
+            bound *= self.segments_per_tier as u64;
+            tier += 1;
+        }
+        tier
+    }
+}
+
+#[cfg(feature = "tantivy")]
+impl tantivy::merge_policy::MergePolicy for TieredMergePolicy {
+    fn compute_merge_candidates(
+        &self,
+        segments: &[tantivy::SegmentMeta],
+    ) -> Vec<tantivy::merge_policy::MergeCandidate> {
+        let sizes: Vec<SegmentSize> = segments
+            .iter()
+            .map(|segment| SegmentSize {
+                live_docs: segment.num_docs(),
+                deleted_docs: segment.num_deleted_docs(),
+            })
+            .collect();
+        self.select_merges(&sizes)
+            .into_iter()
+            .map(|merge| {
+                tantivy::merge_policy::MergeCandidate(
+                    merge.into_iter().map(|i| segments[i].id()).collect(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn live(live_docs: u32) -> SegmentSize {
+        SegmentSize {
+            live_docs,
+            deleted_docs: 0,
+        }
+    }
+
+    fn policy() -> TieredMergePolicy {
+        TieredMergePolicy {
+            segments_per_tier: 4,
+            max_merge_at_once: 4,
+            floor_segment_docs: 10,
+            max_segment_docs: 1_000,
+            deletes_pct_allowed: 20.0,
+        }
+    }
+
+    #[test]
+    fn test_full_tiers_merge_their_smallest_segments() {
+        let policy = policy();
+        assert!(policy.validate().is_ok());
+
+        // Three small segments wait for a fourth
+        assert!(policy
+            .select_merges(&[live(1), live(2), live(3)])
+            .is_empty());
+        let merges =
+            policy.select_merges(&[live(5), live(1), live(200), live(2), live(3), live(4)]);
+        assert_eq!(merges, vec![vec![1, 3, 4, 5]]);
+
+        // Tiers are kept apart: 1-9 docs and 10-39 docs
+        let segments = [
+            live(1),
+            live(20),
+            live(2),
+            live(30),
+            live(25),
+            live(3),
+            live(35),
+        ];
+        assert_eq!(policy.select_merges(&segments), vec![vec![1, 4, 3, 6]]);
+    }
+
+    #[test]
+    fn test_merges_stay_under_max_size_and_reclaim_deletes() {
+        let policy = policy();
+        // Full-size segments are left alone, and merges never outgrow them
+        let segments = [live(1_000), live(1_000), live(1_000), live(1_000)];
+        assert!(policy.select_merges(&segments).is_empty());
+        let segments = [live(400), live(450), live(500), live(550)];
+        assert_eq!(policy.select_merges(&segments), vec![vec![0, 1]]);
+
+        let deleted = SegmentSize {
+            live_docs: 500,
+            deleted_docs: 200,
+        };
+        assert_eq!(policy.select_merges(&[deleted, live(500)]), vec![vec![0]]);
+
+        let invalid = TieredMergePolicy {
+            segments_per_tier: 1,
+            ..policy
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -1,3 +1,11 @@
+pub mod bm25f;
+pub mod merge_policy;
+pub mod proximity;
+pub mod statistics;
 pub mod tantivy_adapter;
 
+pub use bm25f::*;
+pub use merge_policy::*;
+pub use proximity::*;
+pub use statistics::*;
 pub use tantivy_adapter::*;
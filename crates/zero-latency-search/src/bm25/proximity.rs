@@ -0,0 +1,88 @@
+//! Proximity scoring
+//!
+//! BM25 treats a document as a bag of words, so "connection pool timeout"
+//! matches a page mentioning a connection in its intro and a timeout three
+//! sections later as well as one about the connection pool timeout. The
+//! proximity boost rewards documents where the query words occur close
+//! together: it finds the smallest window of token positions holding every
+//! matched word and scales the score by how tight that window is and how many
+//! of the query's words it holds.
+
+/// Smallest span of positions covering one position of every term
+///
+/// `positions` holds the sorted positions of each term present in a field;
+/// the span is the distance between the first and last token of the window.
+pub fn minimal_span(positions: &[Vec<u32>]) -> Option<u32> {
+    if positions.is_empty() || positions.iter().any(Vec::is_empty) {
+        return None;
+    }
+    // Merge all positions, tagged by term, and slide a window over them
+    let mut tagged: Vec<(u32, usize)> = positions
+        .iter()
+        .enumerate()
+        .flat_map(|(term, positions)| positions.iter().map(move |&position| (position, term)))
+        .collect();
+    tagged.sort_unstable();
+
+    let mut counts = vec![0usize; positions.len()];
+    let mut covered = 0;
+    let mut start = 0;
+    let mut best: Option<u32> = None;
+    for end in 0..tagged.len() {
+        let term = tagged[end].1;
+        if counts[term] == 0 {
+            covered += 1;
+        }
+        counts[term] += 1;
+        while covered == positions.len() {
+            let span = tagged[end].0 - tagged[start].0;
+            best = Some(best.map_or(span, |best| best.min(span)));
+            let first = tagged[start].1;
+            counts[first] -= 1;
+            if counts[first] == 0 {
+                covered -= 1;
+            }
+            start += 1;
+        }
+    }
+    best
+}
+
+/// Score multiplier for `matched` of `total` query words found within a
+/// window of `span` positions
+///
+/// Adjacent words in query order give the full `weight`; the boost falls off
+/// as the window widens and with every query word missing from it.
+pub fn proximity_boost(matched: usize, total: usize, span: u32, weight: f32) -> f32 {
+    if matched < 2 || total < 2 || weight <= 0.0 {
+        return 1.0;
+    }
+    let tightness = (matched - 1) as f32 / span.max(matched as u32 - 1) as f32;
+    let coverage = (matched - 1) as f32 / (total - 1) as f32;
+    1.0 + weight * tightness * coverage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimal_span_finds_tightest_window() {
+        // connection(0, 40) pool(41, 90) timeout(42)
+        let positions = vec![vec![0, 40], vec![41, 90], vec![42]];
+        assert_eq!(minimal_span(&positions), Some(2));
+        assert_eq!(minimal_span(&[vec![7]]), Some(0));
+        assert_eq!(minimal_span(&[vec![1], vec![]]), None);
+        assert_eq!(minimal_span(&[]), None);
+    }
+
+    #[test]
+    fn test_boost_rewards_close_and_complete_matches() {
+        let adjacent = proximity_boost(3, 3, 2, 0.5);
+        assert_eq!(adjacent, 1.5);
+        assert!(proximity_boost(3, 3, 20, 0.5) < adjacent);
+        assert!(proximity_boost(2, 3, 1, 0.5) < adjacent);
+        assert_eq!(proximity_boost(1, 3, 0, 0.5), 1.0);
+        assert_eq!(proximity_boost(3, 3, 2, 0.0), 1.0);
+    }
+}
@@ -0,0 +1,182 @@
+//! Incremental lexical statistics
+//!
+//! BM25 scoring needs the number of documents, the mean length of each field
+//! and the number of documents holding each word. Rather than recomputing
+//! them from the whole index after every change, the index keeps them up to
+//! date as documents are added and removed: each document's contribution is
+//! recorded so that removing or replacing it subtracts exactly what it added.
+//! A periodic re-normalization rebuilds them from the live documents of the
+//! index to catch anything the increments missed.
+
+use std::collections::{HashMap, HashSet};
+
+use super::bm25f::LexicalField;
+
+/// What one document adds to the statistics
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentStatistics {
+    /// Tokens in each field, by [`LexicalField::ALL`] order
+    pub lengths: [u32; 4],
+    /// Distinct words in any field
+    pub words: Vec<String>,
+}
+
+impl DocumentStatistics {
+    pub fn new(lengths: [u32; 4], words: HashSet<String>) -> Self {
+        Self {
+            lengths,
+            words: words.into_iter().collect(),
+        }
+    }
+}
+
+/// Document count, field lengths and document frequencies of a lexical index
+#[derive(Debug, Clone, Default)]
+pub struct LexicalStatistics {
+    documents: HashMap<String, DocumentStatistics>,
+    total_lengths: [u64; 4],
+    doc_freqs: HashMap<String, u64>,
+    /// Incremented by every change, so a rebuild can tell whether the index
+    /// changed while it ran
+    generation: u64,
+}
+
+impl LexicalStatistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Statistics of the given documents, keyed by their index keys
+    pub fn from_documents(
+        documents: impl IntoIterator<Item = (String, DocumentStatistics)>,
+    ) -> Self {
+        let mut statistics = Self::new();
+        for (key, document) in documents {
+            statistics.insert(key, document);
+        }
+        statistics.generation = 0;
+        statistics
+    }
+
+    /// Count a document, replacing what it contributed before
+    pub fn add(&mut self, key: String, document: DocumentStatistics) {
+        self.remove(&key);
+        self.insert(key, document);
+    }
+
+    /// Stop counting a document; returns whether it was counted
+    pub fn remove(&mut self, key: &str) -> bool {
+        let Some(document) = self.documents.remove(key) else {
+            return false;
+        };
+        for (total, length) in self.total_lengths.iter_mut().zip(document.lengths) {
+            *total -= u64::from(length);
+        }
+        for word in document.words {
+            if let Some(doc_freq) = self.doc_freqs.get_mut(&word) {
+                *doc_freq -= 1;
+                if *doc_freq == 0 {
+                    self.doc_freqs.remove(&word);
+                }
+            }
+        }
+        self.generation += 1;
+        true
+    }
+
+    pub fn num_docs(&self) -> u64 {
+        self.documents.len() as u64
+    }
+
+    /// Documents holding `word` in any field
+    pub fn doc_freq(&self, word: &str) -> u64 {
+        self.doc_freqs.get(word).copied().unwrap_or(0)
+    }
+
+    /// Mean tokens of each field, by [`LexicalField::ALL`] order
+    pub fn average_lengths(&self) -> [f32; 4] {
+        let num_docs = self.num_docs().max(1) as f32;
+        self.total_lengths.map(|total| total as f32 / num_docs)
+    }
+
+    /// Mean tokens of `field`
+    pub fn average_length(&self, field: LexicalField) -> f32 {
+        let index = LexicalField::ALL
+            .iter()
+            .position(|candidate| *candidate == field)
+            .unwrap_or_default();
+        self.average_lengths()[index]
+    }
+
+    /// Number of changes made so far
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether both count the same documents with the same contributions
+    pub fn matches(&self, other: &LexicalStatistics) -> bool {
+        self.total_lengths == other.total_lengths
+            && self.doc_freqs == other.doc_freqs
+            && self.documents.len() == other.documents.len()
+    }
+
+    /// Carry the generation over from statistics this replaces
+    #[cfg(feature = "tantivy")]
+    pub(super) fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn insert(&mut self, key: String, document: DocumentStatistics) {
+        for (total, length) in self.total_lengths.iter_mut().zip(document.lengths) {
+            *total += u64::from(length);
+        }
+        for word in &document.words {
+            *self.doc_freqs.entry(word.clone()).or_default() += 1;
+        }
+        self.documents.insert(key, document);
+        self.generation += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(lengths: [u32; 4], words: &[&str]) -> DocumentStatistics {
+        DocumentStatistics::new(lengths, words.iter().map(|w| w.to_string()).collect())
+    }
+
+    #[test]
+    fn test_statistics_follow_additions_replacements_and_removals() {
+        let mut statistics = LexicalStatistics::new();
+        statistics.add(
+            "a".to_string(),
+            document([2, 0, 1, 10], &["pool", "timeout"]),
+        );
+        statistics.add("b".to_string(), document([4, 2, 1, 20], &["pool"]));
+        assert_eq!(statistics.num_docs(), 2);
+        assert_eq!(statistics.doc_freq("pool"), 2);
+        assert_eq!(statistics.average_length(LexicalField::Content), 15.0);
+
+        // Re-adding a document replaces its contribution
+        statistics.add("b".to_string(), document([4, 2, 1, 30], &["replica"]));
+        assert_eq!(statistics.num_docs(), 2);
+        assert_eq!(statistics.doc_freq("pool"), 1);
+        assert_eq!(statistics.doc_freq("replica"), 1);
+        assert_eq!(statistics.average_length(LexicalField::Content), 20.0);
+
+        assert!(statistics.remove("a"));
+        assert!(!statistics.remove("a"));
+        assert_eq!(statistics.doc_freq("pool"), 0);
+        assert_eq!(statistics.average_lengths(), [4.0, 2.0, 1.0, 30.0]);
+
+        // The same documents counted from scratch give the same statistics
+        let rebuilt = LexicalStatistics::from_documents([(
+            "b".to_string(),
+            document([4, 2, 1, 30], &["replica"]),
+        )]);
+        assert!(rebuilt.matches(&statistics));
+        assert_eq!(rebuilt.generation(), 0);
+        assert!(statistics.generation() > 0);
+    }
+}
@@ -2,19 +2,41 @@
 use tantivy::{
     collector::TopDocs,
     doc,
-    query::QueryParser,
-    schema::{Field, Schema, Value, FAST, INDEXED, STORED, TEXT},
-    Index, IndexReader, IndexWriter, TantivyDocument,
+    postings::Postings,
+    query::{BooleanQuery, Occur, Query, QueryParser},
+    schema::{Field, IndexRecordOption, Schema, Value, FAST, STORED, STRING, TEXT},
+    DocAddress, DocSet, Index, IndexReader, IndexWriter, Searcher, TantivyDocument, Term,
+    TERMINATED,
 };
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+#[cfg(feature = "tantivy")]
+use std::collections::HashSet;
+#[cfg(feature = "tantivy")]
+use std::path::Path;
 use std::sync::Arc;
+#[cfg(feature = "tantivy")]
+use std::sync::{Mutex, RwLock};
+#[cfg(feature = "tantivy")]
+use std::time::Duration;
 use zero_latency_core::{DocId, Result, ZeroLatencyError};
 
+#[cfg(feature = "tantivy")]
+use super::bm25f::{bm25f_score, inverse_document_frequency, FieldOccurrences, TermOccurrences};
+use super::bm25f::{Bm25fParameters, LexicalScoring};
+#[cfg(feature = "tantivy")]
+use super::merge_policy::SegmentSize;
+use super::merge_policy::TieredMergePolicy;
+#[cfg(feature = "tantivy")]
+use super::proximity::{minimal_span, proximity_boost};
+#[cfg(feature = "tantivy")]
+use super::statistics::{DocumentStatistics, LexicalStatistics};
 use crate::fusion::{FromSignals, ScoreBreakdown, SearchEngine};
 use crate::models::SearchResult;
+#[cfg(feature = "tantivy")]
+use crate::query_parser::QueryField;
 use crate::traits::SearchStep;
 
 /// BM25 search result before conversion to SearchResult
@@ -39,6 +61,41 @@ pub struct BM25Config {
     pub max_results: usize,
     /// Minimum score threshold
     pub min_score: f32,
+    /// Weight of matches in each field
+    #[serde(default)]
+    pub field_boosts: FieldBoosts,
+    /// How the fields' matches combine
+    #[serde(default)]
+    pub scoring: LexicalScoring,
+    /// Parameters of BM25F scoring
+    #[serde(default)]
+    pub bm25f: Bm25fParameters,
+    /// How much query words occurring close together boost a match; 0 turns
+    /// proximity scoring off
+    #[serde(default = "default_proximity_weight")]
+    pub proximity_weight: f32,
+    /// Seconds between rebuilds of the scoring statistics from the index;
+    /// 0 keeps only the incremental updates
+    #[serde(default = "default_statistics_renormalize_seconds")]
+    pub statistics_renormalize_seconds: u64,
+    /// Memory the index writer buffers documents in before flushing a segment
+    #[serde(default = "default_writer_memory_bytes")]
+    pub writer_memory_bytes: usize,
+    /// How the index's segments are merged in the background
+    #[serde(default)]
+    pub merge_policy: TieredMergePolicy,
+}
+
+fn default_proximity_weight() -> f32 {
+    0.5
+}
+
+fn default_statistics_renormalize_seconds() -> u64 {
+    3600
+}
+
+fn default_writer_memory_bytes() -> usize {
+    50_000_000
 }
 
 impl Default for BM25Config {
@@ -47,27 +104,102 @@ impl Default for BM25Config {
             index_path: "tantivy_index".to_string(),
             max_results: 100,
             min_score: 0.0,
+            field_boosts: FieldBoosts::default(),
+            scoring: LexicalScoring::default(),
+            bm25f: Bm25fParameters::default(),
+            proximity_weight: default_proximity_weight(),
+            statistics_renormalize_seconds: default_statistics_renormalize_seconds(),
+            writer_memory_bytes: default_writer_memory_bytes(),
+            merge_policy: TieredMergePolicy::default(),
         }
     }
 }
 
+/// Per-field boosts for BM25 scoring
+///
+/// A match in a field scores its BM25 score times the field's boost, so with
+/// the defaults a title match outranks the same term in body text. Under
+/// [`LexicalScoring::Bm25f`] the boosts weight the fields' term frequencies
+/// instead.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldBoosts {
+    pub title: f32,
+    pub heading: f32,
+    pub path: f32,
+    pub content: f32,
+}
+
+impl Default for FieldBoosts {
+    fn default() -> Self {
+        Self {
+            title: 2.0,
+            heading: 1.5,
+            path: 1.0,
+            content: 1.0,
+        }
+    }
+}
+
+impl FieldBoosts {
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        let boosts = [self.title, self.heading, self.path, self.content];
+        if boosts.iter().any(|boost| !(boost.is_finite() && *boost >= 0.0)) {
+            return Err("Field weights must be non-negative".to_string());
+        }
+        if boosts.iter().all(|boost| *boost == 0.0) {
+            return Err("At least one field weight must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Lexical ranking settings of one search that replace the configured ones
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LexicalOverrides {
+    pub field_weights: Option<FieldBoosts>,
+    pub proximity_weight: Option<f32>,
+}
+
+/// Validate a proximity weight
+pub fn validate_proximity_weight(weight: f32) -> std::result::Result<(), String> {
+    if !(weight.is_finite() && weight >= 0.0) {
+        return Err(format!(
+            "Proximity weight must be non-negative, got {}",
+            weight
+        ));
+    }
+    Ok(())
+}
+
+/// Candidates fetched per result for rescoring to reorder
+#[cfg(feature = "tantivy")]
+const RESCORED_CANDIDATES_PER_RESULT: usize = 4;
+
 /// Tantivy BM25 search adapter
+///
+/// Changes go through one long-lived writer whose commits add immutable
+/// segments; searches read a snapshot of the committed segments and never
+/// wait for the writer or for merges.
 #[cfg(feature = "tantivy")]
 pub struct TantivyAdapter {
     index: Index,
     reader: IndexReader,
-    schema: Schema,
+    writer: Mutex<IndexWriter<TantivyDocument>>,
     fields: TantivyFields,
     config: BM25Config,
+    /// Kept up to date on every change instead of being recomputed per search
+    statistics: Arc<RwLock<LexicalStatistics>>,
 }
 
 #[cfg(feature = "tantivy")]
+#[derive(Clone, Copy)]
 struct TantivyFields {
     doc_id: Field,
     title: Field,
+    heading: Field,
+    path: Field,
     content: Field,
-    uri: Field,
-    section_path: Field,
     collection: Field,
     metadata: Field,
 }
@@ -76,39 +208,58 @@ struct TantivyFields {
 impl TantivyAdapter {
     /// Create a new Tantivy BM25 adapter
     pub async fn new(config: BM25Config) -> Result<Self> {
+        config
+            .bm25f
+            .validate()
+            .map_err(|message| ZeroLatencyError::validation("bm25f", message))?;
+        validate_proximity_weight(config.proximity_weight)
+            .map_err(|message| ZeroLatencyError::validation("proximity_weight", message))?;
+        config
+            .merge_policy
+            .validate()
+            .map_err(|message| ZeroLatencyError::validation("merge_policy", message))?;
         let mut schema_builder = Schema::builder();
 
-        // Define fields for document indexing
-        let doc_id = schema_builder.add_text_field("doc_id", INDEXED | STORED | FAST);
-        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        // Define fields for document indexing; the searchable fields are named
+        // after the query syntax's field scopes, as in `title:installation`.
+        // TEXT indexes token positions, which phrases and proximity scoring read
+        let doc_id = schema_builder.add_text_field("doc_id", STRING | STORED | FAST);
+        let title = schema_builder.add_text_field(QueryField::Title.name(), TEXT | STORED);
+        let heading = schema_builder.add_text_field(QueryField::Heading.name(), TEXT | STORED);
+        let path = schema_builder.add_text_field(QueryField::Path.name(), TEXT | STORED);
         let content = schema_builder.add_text_field("content", TEXT);
-        let uri = schema_builder.add_text_field("uri", STORED);
-        let section_path = schema_builder.add_text_field("section_path", STORED);
-        let collection = schema_builder.add_text_field("collection", INDEXED | STORED | FAST);
+        let collection = schema_builder.add_text_field("collection", STRING | STORED | FAST);
         let metadata = schema_builder.add_text_field("metadata", STORED);
 
         let schema = schema_builder.build();
         let fields = TantivyFields {
             doc_id,
             title,
+            heading,
+            path,
             content,
-            uri,
-            section_path,
             collection,
             metadata,
         };
 
         // Create or open index
         let index_path = Path::new(&config.index_path);
-        let index = if index_path.exists() {
-            Index::open_in_dir(index_path).map_err(|e| {
+        let index = if index_path.join("meta.json").exists() {
+            let index = Index::open_in_dir(index_path).map_err(|e| {
                 ZeroLatencyError::search(format!("Failed to open Tantivy index: {}", e))
-            })?
+            })?;
+            if index.schema() != schema {
+                return Err(ZeroLatencyError::search(format!(
+                    "Tantivy index at {} uses an older schema; delete it and reindex",
+                    index_path.display()
+                )));
+            }
+            index
         } else {
             std::fs::create_dir_all(index_path).map_err(|e| {
                 ZeroLatencyError::io(format!("Failed to create index directory: {}", e))
             })?;
-            Index::create_in_dir(index_path, schema.clone()).map_err(|e| {
+            Index::create_in_dir(index_path, schema).map_err(|e| {
                 ZeroLatencyError::search(format!("Failed to create Tantivy index: {}", e))
             })?
         };
@@ -116,69 +267,176 @@ impl TantivyAdapter {
         let reader = index.reader().map_err(|e| {
             ZeroLatencyError::search(format!("Failed to create index reader: {}", e))
         })?;
+        let writer = index
+            .writer::<TantivyDocument>(config.writer_memory_bytes)
+            .map_err(|e| {
+                ZeroLatencyError::search(format!("Failed to create index writer: {}", e))
+            })?;
+        writer.set_merge_policy(Box::new(config.merge_policy.clone()));
+
+        // Count the documents already in the index once; changes from here on
+        // update the statistics incrementally
+        let statistics = collect_statistics(&reader.searcher(), &fields)?;
+        let statistics = Arc::new(RwLock::new(statistics));
+        if config.statistics_renormalize_seconds > 0 {
+            spawn_renormalization(
+                reader.clone(),
+                fields,
+                Arc::downgrade(&statistics),
+                Duration::from_secs(config.statistics_renormalize_seconds),
+            );
+        }
 
         Ok(Self {
             index,
             reader,
-            schema,
+            writer: Mutex::new(writer),
             fields,
             config,
+            statistics,
         })
     }
 
-    /// Index a document
+    /// Live and deleted documents of each segment searches currently read
+    pub fn segment_sizes(&self) -> Vec<SegmentSize> {
+        self.reader
+            .searcher()
+            .segment_readers()
+            .iter()
+            .map(|segment| SegmentSize {
+                live_docs: segment.num_docs(),
+                deleted_docs: segment.num_deleted_docs(),
+            })
+            .collect()
+    }
+
+    /// Snapshot of the scoring statistics
+    pub fn statistics(&self) -> LexicalStatistics {
+        self.statistics.read().unwrap().clone()
+    }
+
+    /// Rebuild the scoring statistics from the live documents of the index
+    ///
+    /// Returns false, keeping the current statistics, when the index changed
+    /// during the rebuild.
+    pub fn renormalize_statistics(&self) -> Result<bool> {
+        renormalize(&self.reader, &self.fields, &self.statistics)
+    }
+
+    /// Index a document, replacing any earlier version of it
     pub async fn index_document(&self, result: &BM25SearchResult) -> Result<()> {
-        let mut writer = self.index.writer(50_000_000).map_err(|e| {
-            ZeroLatencyError::search(format!("Failed to create index writer: {}", e))
-        })?;
+        let mut writer = self.writer.lock().unwrap();
+
+        let key = result.doc_id.to_index_key();
+        writer.delete_term(Term::from_field_text(self.fields.doc_id, &key));
 
         let mut doc = TantivyDocument::default();
-        doc.add_text(self.fields.doc_id, &result.doc_id.to_index_key());
+        doc.add_text(self.fields.doc_id, &key);
         doc.add_text(self.fields.title, &result.title);
+        doc.add_text(self.fields.heading, result.section_path.join(" > "));
+        doc.add_text(self.fields.path, &result.uri);
         doc.add_text(self.fields.content, &result.content);
-        doc.add_text(self.fields.uri, &result.uri);
-        doc.add_text(self.fields.section_path, &result.section_path.join(" > "));
         doc.add_text(self.fields.collection, &result.collection);
 
         // Serialize metadata as JSON
         let metadata_json = serde_json::to_string(&result.metadata).unwrap_or_default();
-        doc.add_text(self.fields.metadata, &metadata_json);
-
-        writer.add_document(doc).map_err(|e| {
-            ZeroLatencyError::search(format!("Failed to add document to index: {}", e))
-        })?;
+        doc.add_text(self.fields.metadata, metadata_json);
+
+        if let Err(e) = writer.add_document(doc) {
+            // Keep the earlier version rather than committing its deletion alone
+            let _ = writer.rollback();
+            return Err(ZeroLatencyError::search(format!(
+                "Failed to add document to index: {}",
+                e
+            )));
+        }
 
         writer.commit().map_err(|e| {
             ZeroLatencyError::search(format!("Failed to commit index changes: {}", e))
         })?;
 
+        // Make the document searchable right away
+        self.reader.reload().map_err(|e| {
+            ZeroLatencyError::search(format!("Failed to reload index reader: {}", e))
+        })?;
+
+        let document = self.document_statistics(result)?;
+        self.statistics.write().unwrap().add(key, document);
+
         Ok(())
     }
 
     /// Search the BM25 index
     pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<BM25SearchResult>> {
+        self.search_with(query, limit, &LexicalOverrides::default()).await
+    }
+
+    /// Search the BM25 index with the field weights and proximity weight of
+    /// `overrides` in place of the configured ones
+    pub async fn search_with(
+        &self,
+        query: &str,
+        limit: usize,
+        overrides: &LexicalOverrides,
+    ) -> Result<Vec<BM25SearchResult>> {
         let searcher = self.reader.searcher();
 
-        let query_parser = QueryParser::for_index(&self.index, vec![self.fields.title, self.fields.content]);
+        let boosts = overrides
+            .field_weights
+            .as_ref()
+            .unwrap_or(&self.config.field_boosts);
+        let proximity_weight = overrides
+            .proximity_weight
+            .unwrap_or(self.config.proximity_weight);
+        let mut query_parser = QueryParser::for_index(
+            &self.index,
+            vec![
+                self.fields.title,
+                self.fields.heading,
+                self.fields.path,
+                self.fields.content,
+            ],
+        );
+        query_parser.set_field_boost(self.fields.title, boosts.title);
+        query_parser.set_field_boost(self.fields.heading, boosts.heading);
+        query_parser.set_field_boost(self.fields.path, boosts.path);
+        query_parser.set_field_boost(self.fields.content, boosts.content);
         let query = query_parser.parse_query(query).map_err(|e| {
             ZeroLatencyError::search(format!("Failed to parse query: {}", e))
         })?;
 
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit)).map_err(|e| {
-            ZeroLatencyError::search(format!("Search failed: {}", e))
-        })?;
+        // BM25F and proximity scoring reorder a wider set of the documents
+        // the query matches
+        let words = self.query_words(query.as_ref());
+        let bm25f = self.config.scoring == LexicalScoring::Bm25f;
+        let proximity = proximity_weight > 0.0 && words.len() > 1;
+        let fetch_limit = if bm25f || proximity {
+            limit.saturating_mul(RESCORED_CANDIDATES_PER_RESULT)
+        } else {
+            limit
+        };
+        let mut top_docs = searcher
+            .search(&query, &TopDocs::with_limit(fetch_limit))
+            .map_err(|e| ZeroLatencyError::search(format!("Search failed: {}", e)))?;
+        if bm25f {
+            top_docs = self.rescore_bm25f(&searcher, &words, top_docs, boosts)?;
+        }
+        if proximity {
+            top_docs = self.boost_proximity(&searcher, &words, top_docs, proximity_weight)?;
+        }
+        top_docs.truncate(limit);
 
         let mut results = Vec::new();
 
         for (score, doc_address) in top_docs {
-            let retrieved_doc = searcher.doc(doc_address).map_err(|e| {
+            let retrieved_doc = searcher.doc::<TantivyDocument>(doc_address).map_err(|e| {
                 ZeroLatencyError::search(format!("Failed to retrieve document: {}", e))
             })?;
 
             // Extract fields from document
             let doc_id_str = retrieved_doc
                 .get_first(self.fields.doc_id)
-                .and_then(|v| v.as_text())
+                .and_then(|v| v.as_str())
                 .ok_or_else(|| ZeroLatencyError::search("Missing doc_id field".to_string()))?;
 
             let doc_id = DocId::from_index_key(doc_id_str)
@@ -186,25 +444,25 @@ impl TantivyAdapter {
 
             let title = retrieved_doc
                 .get_first(self.fields.title)
-                .and_then(|v| v.as_text())
+                .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string();
 
             let content = retrieved_doc
                 .get_first(self.fields.content)
-                .and_then(|v| v.as_text())
+                .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string();
 
             let uri = retrieved_doc
-                .get_first(self.fields.uri)
-                .and_then(|v| v.as_text())
+                .get_first(self.fields.path)
+                .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string();
 
             let section_path_str = retrieved_doc
-                .get_first(self.fields.section_path)
-                .and_then(|v| v.as_text())
+                .get_first(self.fields.heading)
+                .and_then(|v| v.as_str())
                 .unwrap_or("");
 
             let section_path = if section_path_str.is_empty() {
@@ -215,13 +473,13 @@ impl TantivyAdapter {
 
             let collection = retrieved_doc
                 .get_first(self.fields.collection)
-                .and_then(|v| v.as_text())
+                .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string();
 
             let metadata_str = retrieved_doc
                 .get_first(self.fields.metadata)
-                .and_then(|v| v.as_text())
+                .and_then(|v| v.as_str())
                 .unwrap_or("{}");
 
             let metadata: HashMap<String, String> =
@@ -244,11 +502,149 @@ impl TantivyAdapter {
         Ok(results)
     }
 
+    /// The searchable fields, in [`LexicalField::ALL`] order
+    fn lexical_fields(&self) -> [Field; 4] {
+        [
+            self.fields.title,
+            self.fields.heading,
+            self.fields.path,
+            self.fields.content,
+        ]
+    }
+
+    /// Distinct words of the terms `query` can match a document with
+    ///
+    /// Each word is looked up in every field, however it was scoped in the
+    /// query.
+    fn query_words(&self, query: &dyn Query) -> Vec<String> {
+        let fields = self.lexical_fields();
+        let mut words: Vec<String> = Vec::new();
+        collect_positive_terms(query, &mut |term| {
+            if let Some(word) = term.value().as_str() {
+                if fields.contains(&term.field()) && !words.iter().any(|w| w == word) {
+                    words.push(word.to_string());
+                }
+            }
+        });
+        words
+    }
+
+    /// Score the matched documents with BM25F and sort them by it
+    fn rescore_bm25f(
+        &self,
+        searcher: &Searcher,
+        words: &[String],
+        candidates: Vec<(f32, DocAddress)>,
+        weights: &FieldBoosts,
+    ) -> Result<Vec<(f32, DocAddress)>> {
+        let fields = self.lexical_fields();
+
+        // Lengths and document frequencies come from the maintained statistics
+        let (average_lengths, terms) = {
+            let statistics = self.statistics.read().unwrap();
+            let terms: Vec<_> = words
+                .iter()
+                .map(|word| {
+                    let idf =
+                        inverse_document_frequency(statistics.doc_freq(word), statistics.num_docs());
+                    (idf, fields.map(|field| Term::from_field_text(field, word)))
+                })
+                .collect();
+            (statistics.average_lengths(), terms)
+        };
+
+        let mut rescored = Vec::with_capacity(candidates.len());
+        for (_, address) in candidates {
+            let segment = searcher.segment_reader(address.segment_ord);
+            let mut occurrences = Vec::with_capacity(terms.len());
+            for (idf, field_terms) in &terms {
+                let mut term = TermOccurrences {
+                    idf: *idf,
+                    ..Default::default()
+                };
+                for (i, field_term) in field_terms.iter().enumerate() {
+                    let field = fields[i];
+                    let postings = segment
+                        .inverted_index(field)
+                        .map_err(|e| index_error(&e))?
+                        .read_postings(field_term, IndexRecordOption::WithFreqs)
+                        .map_err(|e| index_error(&e))?;
+                    let Some(mut postings) = postings else {
+                        continue;
+                    };
+                    if postings.seek(address.doc_id) != address.doc_id {
+                        continue;
+                    }
+                    term.fields[i] = FieldOccurrences {
+                        term_frequency: postings.term_freq(),
+                        length: segment
+                            .get_fieldnorms_reader(field)
+                            .map_err(|e| index_error(&e))?
+                            .fieldnorm(address.doc_id),
+                        average_length: average_lengths[i],
+                    };
+                }
+                occurrences.push(term);
+            }
+            rescored.push((bm25f_score(&occurrences, weights, &self.config.bm25f), address));
+        }
+        rescored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        Ok(rescored)
+    }
+
+    /// Boost the matched documents whose fields hold the query words close
+    /// together and sort them by the boosted score
+    ///
+    /// A document takes the boost of its best field.
+    fn boost_proximity(
+        &self,
+        searcher: &Searcher,
+        words: &[String],
+        candidates: Vec<(f32, DocAddress)>,
+        weight: f32,
+    ) -> Result<Vec<(f32, DocAddress)>> {
+        let mut boosted = Vec::with_capacity(candidates.len());
+        let mut positions = Vec::new();
+        for (score, address) in candidates {
+            let segment = searcher.segment_reader(address.segment_ord);
+            let mut boost = 1.0f32;
+            for field in self.lexical_fields() {
+                let inverted_index = segment
+                    .inverted_index(field)
+                    .map_err(|e| index_error(&e))?;
+                let mut word_positions = Vec::with_capacity(words.len());
+                for word in words {
+                    let term = Term::from_field_text(field, word);
+                    let postings = inverted_index
+                        .read_postings(&term, IndexRecordOption::WithFreqsAndPositions)
+                        .map_err(|e| index_error(&e))?;
+                    let Some(mut postings) = postings else {
+                        continue;
+                    };
+                    if postings.seek(address.doc_id) != address.doc_id {
+                        continue;
+                    }
+                    postings.positions(&mut positions);
+                    word_positions.push(std::mem::take(&mut positions));
+                }
+                if let Some(span) = minimal_span(&word_positions) {
+                    boost = boost.max(proximity_boost(
+                        word_positions.len(),
+                        words.len(),
+                        span,
+                        weight,
+                    ));
+                }
+            }
+            boosted.push((score * boost, address));
+        }
+        boosted.sort_by(|a, b| b.0.total_cmp(&a.0));
+        Ok(boosted)
+    }
+
     /// Delete a document from the index
     pub async fn delete_document(&self, doc_id: &DocId) -> Result<()> {
-        let mut writer = self.index.writer(50_000_000).map_err(|e| {
-            ZeroLatencyError::search(format!("Failed to create index writer: {}", e))
-        })?;
+        let mut writer = self.writer.lock().unwrap();
 
         let term = tantivy::Term::from_field_text(self.fields.doc_id, &doc_id.to_index_key());
         writer.delete_term(term);
@@ -257,8 +653,162 @@ impl TantivyAdapter {
             ZeroLatencyError::search(format!("Failed to commit delete: {}", e))
         })?;
 
+        // The statistics must not count documents the reader still sees
+        self.reader.reload().map_err(|e| {
+            ZeroLatencyError::search(format!("Failed to reload index reader: {}", e))
+        })?;
+        self.statistics
+            .write()
+            .unwrap()
+            .remove(&doc_id.to_index_key());
+
         Ok(())
     }
+
+    /// Lengths and words of a document's fields, tokenized as the index does
+    fn document_statistics(&self, result: &BM25SearchResult) -> Result<DocumentStatistics> {
+        let texts = [
+            result.title.clone(),
+            result.section_path.join(" > "),
+            result.uri.clone(),
+            result.content.clone(),
+        ];
+        let mut lengths = [0u32; 4];
+        let mut words = HashSet::new();
+        for ((length, field), text) in lengths.iter_mut().zip(self.lexical_fields()).zip(&texts) {
+            let mut analyzer = self.index.tokenizer_for_field(field).map_err(|e| {
+                ZeroLatencyError::search(format!("Failed to get field tokenizer: {}", e))
+            })?;
+            analyzer.token_stream(text).process(&mut |token| {
+                *length += 1;
+                words.insert(token.text.clone());
+            });
+        }
+        Ok(DocumentStatistics::new(lengths, words))
+    }
+}
+
+/// Count the live documents of the index from its postings
+#[cfg(feature = "tantivy")]
+fn collect_statistics(searcher: &Searcher, fields: &TantivyFields) -> Result<LexicalStatistics> {
+    let lexical_fields = [fields.title, fields.heading, fields.path, fields.content];
+    let mut documents = Vec::new();
+    for segment in searcher.segment_readers() {
+        let mut segment_documents: HashMap<u32, ([u32; 4], HashSet<String>)> = segment
+            .doc_ids_alive()
+            .map(|doc| (doc, Default::default()))
+            .collect();
+        for (i, field) in lexical_fields.into_iter().enumerate() {
+            let inverted_index = segment.inverted_index(field).map_err(|e| index_error(&e))?;
+            let mut terms = inverted_index.terms().stream().map_err(|e| index_error(&e))?;
+            while terms.advance() {
+                let word = String::from_utf8_lossy(terms.key()).into_owned();
+                let mut postings = inverted_index
+                    .read_postings_from_terminfo(terms.value(), IndexRecordOption::WithFreqs)
+                    .map_err(|e| index_error(&e))?;
+                while postings.doc() != TERMINATED {
+                    if let Some((lengths, words)) = segment_documents.get_mut(&postings.doc()) {
+                        lengths[i] += postings.term_freq();
+                        words.insert(word.clone());
+                    }
+                    postings.advance();
+                }
+            }
+        }
+
+        let store = segment.get_store_reader(1).map_err(|e| index_error(&e))?;
+        for (doc, (lengths, words)) in segment_documents {
+            let stored = store
+                .get::<TantivyDocument>(doc)
+                .map_err(|e| index_error(&e))?;
+            if let Some(key) = stored.get_first(fields.doc_id).and_then(|v| v.as_str()) {
+                documents.push((key.to_string(), DocumentStatistics::new(lengths, words)));
+            }
+        }
+    }
+    Ok(LexicalStatistics::from_documents(documents))
+}
+
+/// Replace `statistics` with ones rebuilt from the index unless it changed
+/// meanwhile
+#[cfg(feature = "tantivy")]
+fn renormalize(
+    reader: &IndexReader,
+    fields: &TantivyFields,
+    statistics: &RwLock<LexicalStatistics>,
+) -> Result<bool> {
+    // The index is changed before the statistics are, so a change that races
+    // with the rebuild always shows up as a new generation
+    let generation = statistics.read().unwrap().generation();
+    let rebuilt = collect_statistics(&reader.searcher(), fields)?;
+
+    let mut current = statistics.write().unwrap();
+    if current.generation() != generation {
+        tracing::debug!("BM25 statistics changed during rebuild; keeping incremental ones");
+        return Ok(false);
+    }
+    if !current.matches(&rebuilt) {
+        tracing::info!(
+            "BM25 statistics drifted from the index ({} counted, {} live documents); rebuilt",
+            current.num_docs(),
+            rebuilt.num_docs()
+        );
+    }
+    let generation = current.generation();
+    *current = rebuilt;
+    current.set_generation(generation);
+    Ok(true)
+}
+
+/// Rebuild the statistics every `period` until the adapter is dropped
+#[cfg(feature = "tantivy")]
+fn spawn_renormalization(
+    reader: IndexReader,
+    fields: TantivyFields,
+    statistics: std::sync::Weak<RwLock<LexicalStatistics>>,
+    period: Duration,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        // The first tick fires at once, right after the initial count
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let Some(statistics) = statistics.upgrade() else {
+                break;
+            };
+            let reader = reader.clone();
+            let rebuild = tokio::task::spawn_blocking(move || {
+                renormalize(&reader, &fields, &statistics)
+            })
+            .await;
+            match rebuild {
+                Ok(Err(e)) => tracing::warn!("Failed to rebuild BM25 statistics: {}", e),
+                Err(e) => tracing::warn!("BM25 statistics rebuild did not finish: {}", e),
+                Ok(Ok(_)) => {}
+            }
+        }
+    });
+}
+
+#[cfg(feature = "tantivy")]
+fn index_error(e: &dyn std::fmt::Display) -> ZeroLatencyError {
+    ZeroLatencyError::search(format!("Failed to read index statistics: {}", e))
+}
+
+/// Visit the terms a document can match `query` with, skipping excluded ones
+#[cfg(feature = "tantivy")]
+fn collect_positive_terms(query: &dyn Query, visit: &mut dyn FnMut(&Term)) {
+    match query.downcast_ref::<BooleanQuery>() {
+        Some(boolean) => {
+            for (occur, clause) in boolean.clauses() {
+                if *occur != Occur::MustNot {
+                    collect_positive_terms(clause.as_ref(), visit);
+                }
+            }
+        }
+        None => query.query_terms(&mut |term, _| visit(term)),
+    }
 }
 
 /// No-op BM25 adapter for when tantivy feature is disabled
@@ -279,6 +829,15 @@ impl TantivyAdapter {
         ))
     }
 
+    pub async fn search_with(
+        &self,
+        query: &str,
+        limit: usize,
+        _overrides: &LexicalOverrides,
+    ) -> Result<Vec<BM25SearchResult>> {
+        self.search(query, limit).await
+    }
+
     pub async fn index_document(&self, _result: &BM25SearchResult) -> Result<()> {
         Err(ZeroLatencyError::search(
             "BM25 indexing requires tantivy feature to be enabled".to_string()
@@ -342,25 +901,56 @@ impl SearchStep for BM25SearchStep {
         } else {
             &context.request.query.raw
         };
+        // Quoted phrases become required phrase clauses
+        let query_text = context.parsed_query.lexical_query(query_text);
 
         tracing::info!("🔍 BM25SearchStep: Searching with query: '{}'", query_text);
 
+        let started = std::time::Instant::now();
+        let exclusions = &context.request.filters.exclude;
+        let fetch_limit = context.request.filters.fetch_limit(context.request.limit);
+        // The collection's or request's ranking settings replace the configured ones
+        let overrides = LexicalOverrides {
+            field_weights: context.request.options.field_weights,
+            proximity_weight: context.request.options.proximity_weight,
+        };
         let bm25_results = self
             .adapter
-            .search(query_text, context.request.limit)
+            .search_with(
+                &query_text,
+                context.metadata_filter.fetch_limit(fetch_limit),
+                &overrides,
+            )
             .await?;
 
         tracing::info!("📊 BM25SearchStep: Found {} BM25 results", bm25_results.len());
 
         // Convert to SearchResult format
-        let search_results: Vec<SearchResult> = bm25_results
+        let mut search_results: Vec<SearchResult> = bm25_results
             .into_iter()
             .map(|result| self.convert_result(result, 0)) // Variant 0 = original query
             .collect();
 
+        // The index cannot evaluate metadata patterns, so filter here
+        search_results.retain(|result| result.matches_metadata(&context.metadata_filter));
+
+        // Drop excluded results before they reach fusion
+        let excluded = exclusions.apply(&mut search_results);
+        if excluded > 0 {
+            tracing::debug!("BM25SearchStep: excluded {} results", excluded);
+        }
+        if let Some(principal) = &context.request.filters.access {
+            let denied = principal.apply(&mut search_results);
+            if denied > 0 {
+                tracing::debug!("BM25SearchStep: withheld {} restricted results", denied);
+            }
+        }
+        search_results.truncate(context.request.limit);
+
         // Add to context (this will be merged with vector results in hybrid step)
         context.raw_results.extend(search_results);
         context.metadata.result_sources.push("bm25".to_string());
+        context.record_stage("bm25", started.elapsed());
 
         Ok(())
     }
@@ -379,6 +969,7 @@ mod tests {
             index_path: temp_dir.path().to_str().unwrap().to_string(),
             max_results: 10,
             min_score: 0.0,
+            ..Default::default()
         };
 
         let adapter = TantivyAdapter::new(config).await.unwrap();
@@ -403,4 +994,265 @@ mod tests {
         assert!(!results.is_empty());
         assert_eq!(results[0].doc_id, doc_id);
     }
+
+    #[cfg(feature = "tantivy")]
+    fn doc(id: &str, content: &str) -> BM25SearchResult {
+        BM25SearchResult {
+            doc_id: DocId::new("test", id, 1),
+            title: "Reference guide".to_string(),
+            content: content.to_string(),
+            uri: format!("/test/{}", id),
+            score: 1.0,
+            section_path: Vec::new(),
+            collection: "test".to_string(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[cfg(feature = "tantivy")]
+    async fn indexed_adapter(temp_dir: &TempDir, docs: Vec<BM25SearchResult>) -> TantivyAdapter {
+        indexed_adapter_scored(temp_dir, docs, LexicalScoring::PerField).await
+    }
+
+    #[cfg(feature = "tantivy")]
+    async fn indexed_adapter_scored(
+        temp_dir: &TempDir,
+        docs: Vec<BM25SearchResult>,
+        scoring: LexicalScoring,
+    ) -> TantivyAdapter {
+        let config = BM25Config {
+            index_path: temp_dir.path().to_str().unwrap().to_string(),
+            max_results: 10,
+            min_score: 0.0,
+            scoring,
+            ..Default::default()
+        };
+        let adapter = TantivyAdapter::new(config).await.unwrap();
+        for doc in &docs {
+            adapter.index_document(doc).await.unwrap();
+        }
+        adapter
+    }
+
+    #[cfg(feature = "tantivy")]
+    async fn search_ids(adapter: &TantivyAdapter, query: &str) -> Vec<String> {
+        let parsed = crate::query_parser::ParsedQuery::parse(query);
+        adapter
+            .search(&parsed.lexical_query(query), 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| result.doc_id.external_id)
+            .collect()
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tantivy")]
+    async fn test_phrase_requires_adjacent_words() {
+        let temp_dir = TempDir::new().unwrap();
+        let docs = vec![
+            doc("exact", "MySQL reports error code 1062 on duplicates"),
+            doc("scattered", "the code returned error 1062 again"),
+        ];
+        let adapter = indexed_adapter(&temp_dir, docs).await;
+
+        let ids = search_ids(&adapter, r#""error code 1062""#).await;
+        assert_eq!(ids, ["exact"]);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tantivy")]
+    async fn test_boolean_query_minimum_should_match() {
+        let temp_dir = TempDir::new().unwrap();
+        let docs = vec![
+            doc("all", "the shard cache keeps the index warm"),
+            doc("two", "a cache in front of the index"),
+            doc("one", "clear the cache"),
+            doc("legacy", "legacy shard cache index"),
+        ];
+        let adapter = indexed_adapter(&temp_dir, docs).await;
+
+        let ids = search_ids(&adapter, "(cache index shard)@2 AND -legacy").await;
+        assert_eq!(ids, ["all", "two"]);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tantivy")]
+    async fn test_field_scoped_queries_and_boosts() {
+        let temp_dir = TempDir::new().unwrap();
+        let docs = vec![
+            doc("body", "installation steps and installation notes"),
+            BM25SearchResult {
+                title: "Installation".to_string(),
+                ..doc("title", "getting the tools ready")
+            },
+            BM25SearchResult {
+                section_path: vec!["Setup".to_string(), "Installation".to_string()],
+                ..doc("heading", "run the script")
+            },
+            BM25SearchResult {
+                uri: "docs/installation.md".to_string(),
+                ..doc("path", "tool reference")
+            },
+        ];
+        let adapter = indexed_adapter(&temp_dir, docs).await;
+
+        for field in ["title", "heading", "path"] {
+            let ids = search_ids(&adapter, &format!("{}:installation", field)).await;
+            assert_eq!(ids, [field]);
+        }
+
+        // Unscoped, the title match outranks repeated body mentions
+        let ids = search_ids(&adapter, "installation").await;
+        assert_eq!(ids.len(), 4);
+        assert_eq!(ids[0], "title");
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tantivy")]
+    async fn test_bm25f_follows_field_weights() {
+        let temp_dir = TempDir::new().unwrap();
+        let docs = vec![
+            BM25SearchResult {
+                title: "Replication".to_string(),
+                ..doc("title", "how followers catch up")
+            },
+            doc("body", "replication lag and replication slots"),
+            BM25SearchResult {
+                title: "Legacy replication".to_string(),
+                ..doc("legacy", "replication")
+            },
+        ];
+        let adapter = indexed_adapter_scored(&temp_dir, docs, LexicalScoring::Bm25f).await;
+        let ids = |results: Vec<BM25SearchResult>| -> Vec<String> {
+            results
+                .into_iter()
+                .map(|result| result.doc_id.external_id)
+                .collect()
+        };
+
+        // Excluded documents stay excluded after rescoring
+        let query = "replication -legacy";
+        let results = adapter.search(query, 10).await.unwrap();
+        assert!(results.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert_eq!(ids(results), ["title", "body"]);
+
+        let body_only = FieldBoosts {
+            title: 0.0,
+            content: 1.0,
+            ..Default::default()
+        };
+        let overrides = LexicalOverrides {
+            field_weights: Some(body_only),
+            ..Default::default()
+        };
+        let results = adapter.search_with(query, 10, &overrides).await.unwrap();
+        assert_eq!(ids(results), ["body", "title"]);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tantivy")]
+    async fn test_proximity_favours_words_close_together() {
+        let temp_dir = TempDir::new().unwrap();
+        let docs = vec![
+            doc(
+                "scattered",
+                "connection retries back off while the pool drains before timeout",
+            ),
+            doc(
+                "adjacent",
+                "raise the connection pool timeout when requests queue up behind slow nightly batch jobs",
+            ),
+        ];
+        let adapter = indexed_adapter(&temp_dir, docs).await;
+        let ids = |results: Vec<BM25SearchResult>| -> Vec<String> {
+            results
+                .into_iter()
+                .map(|result| result.doc_id.external_id)
+                .collect()
+        };
+
+        let query = "connection pool timeout";
+        let results = adapter.search(query, 10).await.unwrap();
+        assert_eq!(ids(results), ["adjacent", "scattered"]);
+
+        // Without the boost the shorter scattered document wins on BM25 alone
+        let overrides = LexicalOverrides {
+            proximity_weight: Some(0.0),
+            ..Default::default()
+        };
+        let results = adapter.search_with(query, 10, &overrides).await.unwrap();
+        assert_eq!(ids(results), ["scattered", "adjacent"]);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tantivy")]
+    async fn test_statistics_follow_document_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let docs = vec![
+            doc("pool", "connection pool timeout"),
+            doc("replica", "replica lag"),
+        ];
+        let adapter = indexed_adapter(&temp_dir, docs).await;
+
+        adapter
+            .index_document(&doc("replica", "replica lag and replica slots"))
+            .await
+            .unwrap();
+        adapter
+            .delete_document(&DocId::new("test", "pool", 1))
+            .await
+            .unwrap();
+
+        let statistics = adapter.statistics();
+        assert_eq!(statistics.num_docs(), 1);
+        assert_eq!(statistics.doc_freq("pool"), 0);
+        assert_eq!(statistics.doc_freq("replica"), 1);
+        assert_eq!(statistics.average_length(crate::bm25::LexicalField::Content), 5.0);
+
+        // The increments agree with a count of the live documents
+        let counted = collect_statistics(&adapter.reader.searcher(), &adapter.fields).unwrap();
+        assert!(counted.matches(&statistics));
+        assert!(adapter.renormalize_statistics().unwrap());
+        assert!(adapter.statistics().matches(&statistics));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tantivy")]
+    async fn test_small_commits_are_merged_in_the_background() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = BM25Config {
+            index_path: temp_dir.path().to_str().unwrap().to_string(),
+            merge_policy: TieredMergePolicy {
+                segments_per_tier: 2,
+                max_merge_at_once: 4,
+                floor_segment_docs: 10,
+                max_segment_docs: 1_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let adapter = TantivyAdapter::new(config).await.unwrap();
+        for i in 0..8 {
+            let id = format!("doc{}", i);
+            adapter
+                .index_document(&doc(&id, "segment merge policy"))
+                .await
+                .unwrap();
+        }
+
+        // Every commit wrote a segment; merges fold them together
+        let mut segments = adapter.segment_sizes();
+        for _ in 0..100 {
+            if segments.len() < 8 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            adapter.reader.reload().unwrap();
+            segments = adapter.segment_sizes();
+        }
+        assert!(segments.len() < 8, "segments were not merged: {:?}", segments);
+        assert_eq!(segments.iter().map(|s| s.live_docs).sum::<u32>(), 8);
+        assert_eq!(search_ids(&adapter, "merge").await.len(), 8);
+    }
 }
@@ -0,0 +1,216 @@
+//! Browse mode for queries with nothing to search for
+//!
+//! An empty query, or one made only of stop words such as `the` or `how
+//! to`, has no content to embed, so searching it returns arbitrary chunks.
+//! Such queries browse the collection instead: [`browse_results`] lists its
+//! documents, most recently indexed or most popular first, together with
+//! facet counts over every document, so a UI can show a landing page through
+//! the search API.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use zero_latency_core::{values::Score, DateTime, Utc, ZeroLatencyError};
+
+use crate::answer::content_terms;
+use crate::models::SearchResult;
+use crate::query_parser::ParsedQuery;
+
+/// Value counts per facet, e.g. `{"type": {"md": 12, "rs": 3}}`
+pub type Facets = BTreeMap<String, BTreeMap<String, usize>>;
+
+/// How browsed documents are ordered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowseOrder {
+    /// Most recently indexed first
+    #[default]
+    Recent,
+    /// Most often among the top results of searches first
+    Popular,
+}
+
+impl BrowseOrder {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Recent => "recent",
+            Self::Popular => "popular",
+        }
+    }
+}
+
+impl FromStr for BrowseOrder {
+    type Err = ZeroLatencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "recent" => Ok(Self::Recent),
+            "popular" => Ok(Self::Popular),
+            _ => Err(ZeroLatencyError::validation(
+                "browse_order",
+                "must be one of recent, popular",
+            )),
+        }
+    }
+}
+
+/// A document offered for browsing
+#[derive(Debug, Clone)]
+pub struct BrowseEntry {
+    /// The document's first chunk
+    pub result: SearchResult,
+    /// When the document was last indexed
+    pub updated_at: Option<DateTime<Utc>>,
+    /// How often the document was among the top results of a search
+    pub popularity: usize,
+}
+
+/// Whether a query normalizes to nothing and should browse instead
+pub fn is_browse_query(query: &str) -> bool {
+    let parsed = ParsedQuery::parse(query);
+    content_terms(&parsed.semantic_text(query)).next().is_none()
+}
+
+/// Order browsed documents and count facets over all of them
+///
+/// Returns the first `limit` documents and the facet counts. Browsed results
+/// are not scored, so every result's score is zero.
+pub fn browse_results(
+    mut entries: Vec<BrowseEntry>,
+    order: BrowseOrder,
+    limit: usize,
+) -> (Vec<SearchResult>, Facets) {
+    let facets = facet_counts(entries.iter().map(|entry| &entry.result));
+
+    entries.sort_by(|a, b| {
+        let recency = b.updated_at.cmp(&a.updated_at);
+        let popularity = b.popularity.cmp(&a.popularity);
+        match order {
+            BrowseOrder::Recent => recency.then(popularity),
+            BrowseOrder::Popular => popularity.then(recency),
+        }
+        .then_with(|| a.result.uri.cmp(&b.result.uri))
+    });
+    entries.truncate(limit);
+
+    let results = entries
+        .into_iter()
+        .map(|entry| {
+            let mut result = entry.result;
+            result.final_score = Score::zero();
+            result
+        })
+        .collect();
+    (results, facets)
+}
+
+/// Count the collections, file types and tags of a set of results
+pub fn facet_counts<'a>(results: impl IntoIterator<Item = &'a SearchResult>) -> Facets {
+    let mut facets = Facets::new();
+    let mut count = |facet: &str, value: &str| {
+        if !value.is_empty() {
+            *facets
+                .entry(facet.to_string())
+                .or_default()
+                .entry(value.to_string())
+                .or_default() += 1;
+        }
+    };
+
+    for result in results {
+        if let Some(collection) = &result.collection {
+            count("collection", collection);
+        }
+        let file_name = result.uri.rsplit('/').next().unwrap_or_default();
+        if let Some((stem, extension)) = file_name.rsplit_once('.') {
+            if !stem.is_empty() {
+                count("type", &extension.to_lowercase());
+            }
+        }
+        if let Some(tags) = result.custom_metadata.get("tags") {
+            for tag in tags.split(',') {
+                count("tags", tag.trim());
+            }
+        }
+    }
+    facets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fusion::{FromSignals, NormalizationMethod, ScoreBreakdown};
+    use chrono::TimeZone;
+    use zero_latency_core::DocId;
+
+    fn entry(uri: &str, tags: &str, day: u32, popularity: usize) -> BrowseEntry {
+        let scores = ScoreBreakdown {
+            bm25_raw: None,
+            vector_raw: Some(0.5),
+            bm25_normalized: None,
+            vector_normalized: Some(0.5),
+            fused: 0.5,
+            normalization_method: NormalizationMethod::MinMax,
+        };
+        let mut result = SearchResult::new(
+            DocId::new("docs", uri, 1),
+            uri.to_string(),
+            "Page".to_string(),
+            "Content".to_string(),
+            scores,
+            FromSignals::vector_only(),
+        );
+        result.collection = Some("docs".to_string());
+        if !tags.is_empty() {
+            result
+                .custom_metadata
+                .insert("tags".to_string(), tags.to_string());
+        }
+        BrowseEntry {
+            result,
+            updated_at: Some(Utc.with_ymd_and_hms(2025, 8, day, 0, 0, 0).unwrap()),
+            popularity,
+        }
+    }
+
+    #[test]
+    fn test_browse_queries() {
+        assert!(is_browse_query(""));
+        assert!(is_browse_query("   "));
+        assert!(is_browse_query("the"));
+        assert!(is_browse_query("how to?"));
+        assert!(!is_browse_query("install"));
+        assert!(!is_browse_query("the installer"));
+    }
+
+    #[test]
+    fn test_browse_orders_and_facets() {
+        let entries = vec![
+            entry("guides/install.md", "setup, linux", 3, 5),
+            entry("guides/upgrade.md", "setup", 9, 1),
+            entry("src/main.rs", "", 5, 9),
+        ];
+
+        let (recent, facets) = browse_results(entries.clone(), BrowseOrder::Recent, 2);
+        let uris: Vec<&str> = recent.iter().map(|r| r.uri.as_str()).collect();
+        assert_eq!(uris, ["guides/upgrade.md", "src/main.rs"]);
+        assert_eq!(recent[0].final_score.value(), 0.0);
+
+        // Facets count every document, not only the returned ones
+        assert_eq!(facets["collection"]["docs"], 3);
+        assert_eq!(facets["type"]["md"], 2);
+        assert_eq!(facets["type"]["rs"], 1);
+        assert_eq!(facets["tags"]["setup"], 2);
+        assert_eq!(facets["tags"]["linux"], 1);
+
+        let (popular, _) = browse_results(entries, BrowseOrder::Popular, 10);
+        assert_eq!(popular[0].uri, "src/main.rs");
+        assert_eq!(popular[2].uri, "guides/upgrade.md");
+
+        assert_eq!(
+            "popular".parse::<BrowseOrder>().unwrap(),
+            BrowseOrder::Popular
+        );
+        assert!("newest".parse::<BrowseOrder>().is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! Eviction policies for the cache layer
+//!
+//! LRU evicts the entry used longest ago, which lets a burst of one-off
+//! queries flush entries that are asked for all day. LFU evicts the entry
+//! used least often instead. W-TinyLFU combines both: new entries land in a
+//! small LRU window, and an entry leaving the window only displaces the
+//! oldest entry of the main segment when a frequency sketch says it is asked
+//! for more often. The sketch remembers keys that are no longer cached, and
+//! is halved periodically so old popularity fades. Query traffic is heavily
+//! skewed towards popular queries, which is where W-TinyLFU pays off.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// How a full cache chooses the entry to evict
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// Least recently used
+    #[default]
+    Lru,
+    /// Least frequently used, the least recently used among equals
+    Lfu,
+    /// LRU admission window in front of a frequency-filtered main segment
+    WTinyLfu,
+}
+
+/// Rows of the count-min sketch; each estimate is the minimum over the rows
+const SKETCH_DEPTH: usize = 4;
+
+/// Fewest counters per row, so small caches don't collide constantly
+const MIN_WIDTH: usize = 64;
+
+/// Highest count of a counter, as in 4-bit counters
+const MAX_COUNT: u8 = 15;
+
+/// Share of a W-TinyLFU cache's entries kept in the admission window
+pub(crate) const WINDOW_PERCENT: usize = 1;
+
+/// Count-min sketch estimating how often keys were accessed recently
+#[derive(Debug)]
+pub(crate) struct FrequencySketch {
+    counters: Vec<[u8; SKETCH_DEPTH]>,
+    mask: usize,
+    additions: usize,
+    sample_size: usize,
+}
+
+impl FrequencySketch {
+    /// Sketch sized for a cache of `capacity` entries
+    pub(crate) fn new(capacity: usize) -> Self {
+        let width = capacity.max(MIN_WIDTH).next_power_of_two();
+        Self {
+            counters: vec![[0; SKETCH_DEPTH]; width],
+            mask: width - 1,
+            additions: 0,
+            sample_size: width * 10,
+        }
+    }
+
+    /// Count an access to `key`
+    pub(crate) fn increment<K: Hash>(&mut self, key: &K) {
+        for (row, slot) in self.slots(key).into_iter().enumerate() {
+            let counter = &mut self.counters[slot][row];
+            *counter = (*counter + 1).min(MAX_COUNT);
+        }
+        self.additions += 1;
+        if self.additions >= self.sample_size {
+            self.age();
+        }
+    }
+
+    /// Estimated recent accesses of `key`
+    pub(crate) fn frequency<K: Hash>(&self, key: &K) -> u8 {
+        self.slots(key)
+            .into_iter()
+            .enumerate()
+            .map(|(row, slot)| self.counters[slot][row])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Halve every counter so popularity that stopped fades
+    fn age(&mut self) {
+        for counters in &mut self.counters {
+            for counter in counters.iter_mut() {
+                *counter /= 2;
+            }
+        }
+        self.additions /= 2;
+    }
+
+    fn slots<K: Hash>(&self, key: &K) -> [usize; SKETCH_DEPTH] {
+        // Seeding the hash with the row keeps the rows independent
+        std::array::from_fn(|row| {
+            let mut hasher = DefaultHasher::new();
+            row.hash(&mut hasher);
+            key.hash(&mut hasher);
+            hasher.finish() as usize & self.mask
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::LRUCache;
+    use std::time::Duration;
+
+    fn cache(policy: EvictionPolicy, max_entries: usize) -> LRUCache<String, u32> {
+        LRUCache::new(max_entries, usize::MAX, Duration::from_secs(60)).with_eviction_policy(policy)
+    }
+
+    #[test]
+    fn test_sketch_estimates_and_ages_frequencies() {
+        let mut sketch = FrequencySketch::new(MIN_WIDTH);
+        for _ in 0..5 {
+            sketch.increment(&"popular");
+        }
+        sketch.increment(&"rare");
+        assert_eq!(sketch.frequency(&"popular"), 5);
+        assert_eq!(sketch.frequency(&"rare"), 1);
+        assert_eq!(sketch.frequency(&"unseen"), 0);
+
+        // Filling the sample halves every count
+        for _ in sketch.additions..sketch.sample_size {
+            sketch.increment(&"other");
+        }
+        assert_eq!(sketch.frequency(&"popular"), 2);
+        assert_eq!(sketch.frequency(&"other"), 7);
+    }
+
+    #[test]
+    fn test_lfu_keeps_frequently_used_entries() {
+        let mut cache = cache(EvictionPolicy::Lfu, 2);
+        cache.insert("popular".to_string(), 1, 1);
+        cache.insert("rare".to_string(), 2, 1);
+        cache.get(&"popular".to_string());
+        cache.get(&"popular".to_string());
+        // Most recent, but used least
+        cache.get(&"rare".to_string());
+
+        cache.insert("new".to_string(), 3, 1);
+        assert_eq!(cache.get(&"popular".to_string()), Some(1));
+        assert_eq!(cache.get(&"rare".to_string()), None);
+    }
+
+    #[test]
+    fn test_tiny_lfu_resists_a_scan_of_one_off_keys() {
+        let mut lru = cache(EvictionPolicy::Lru, 10);
+        let mut tiny_lfu = cache(EvictionPolicy::WTinyLfu, 10);
+        for cache in [&mut lru, &mut tiny_lfu] {
+            for i in 0..10 {
+                let key = format!("popular{}", i);
+                cache.insert(key.clone(), i, 1);
+                for _ in 0..3 {
+                    cache.get(&key);
+                }
+            }
+            // A burst of queries asked once each
+            for i in 0..50 {
+                let key = format!("scan{}", i);
+                cache.get(&key);
+                cache.insert(key, i, 1);
+            }
+        }
+
+        let kept = |cache: &mut LRUCache<String, u32>| {
+            (0..10)
+                .filter(|i| cache.get(&format!("popular{}", i)).is_some())
+                .count()
+        };
+        assert_eq!(kept(&mut lru), 0);
+        assert_eq!(kept(&mut tiny_lfu), 9);
+        assert_eq!(tiny_lfu.size(), 10);
+    }
+}
@@ -199,6 +199,8 @@ impl CachedHybridSearchPipeline {
                 from_signals: Default::default(),
                 ranking_signals: None,
                 url: Some(format!("https://docs.example.com/{}", doc_id)),
+                lines: None,
+                anchor: None,
                 collection: Some("docs".to_string()),
                 custom_metadata: HashMap::new(),
             });
@@ -3,12 +3,14 @@
 //! This module provides a comprehensive caching solution that spans
 //! multiple layers of the hybrid search pipeline.
 
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tokio::time::{interval, Duration};
 
 use super::{
-    CacheConfig, LRUCache, QueryCacheKey,
+    CacheConfig, CacheLookup, LRUCache, QueryCacheKey,
 };
 use super::performance::CacheStatistics;
 use crate::models::{SearchRequest, SearchResult};
@@ -18,6 +20,8 @@ use zero_latency_core::Result;
 pub struct HybridSearchCacheManager {
     /// Query result cache
     query_cache: Arc<RwLock<LRUCache<QueryCacheKey, Vec<SearchResult>>>>,
+    /// Stale query entries with a background refresh in flight
+    refreshing: Arc<Mutex<HashSet<QueryCacheKey>>>,
     /// Vector embedding cache
     embedding_cache: Arc<RwLock<LRUCache<String, Vec<f32>>>>,
     /// BM25 score cache
@@ -37,32 +41,48 @@ pub struct HybridSearchCacheManager {
 
 impl HybridSearchCacheManager {
     pub fn new(config: CacheConfig) -> Self {
-        let query_cache = Arc::new(RwLock::new(LRUCache::new(
-            config.query_cache.max_entries,
-            config.query_cache.max_size_mb * 1024 * 1024,
-            Duration::from_secs(config.query_cache.ttl_seconds),
-        )));
-
-        let embedding_cache = Arc::new(RwLock::new(LRUCache::new(
-            config.embedding_cache.max_entries,
-            config.embedding_cache.max_size_mb * 1024 * 1024,
-            Duration::from_secs(config.embedding_cache.ttl_seconds),
-        )));
-
-        let bm25_cache = Arc::new(RwLock::new(LRUCache::new(
-            config.bm25_cache.max_entries,
-            config.bm25_cache.max_size_mb * 1024 * 1024,
-            Duration::from_secs(config.bm25_cache.ttl_seconds),
-        )));
-
-        let fusion_cache = Arc::new(RwLock::new(LRUCache::new(
-            config.fusion_cache.max_entries,
-            config.fusion_cache.max_size_mb * 1024 * 1024,
-            Duration::from_secs(config.fusion_cache.ttl_seconds),
-        )));
+        let query_cache = Arc::new(RwLock::new(
+            LRUCache::new(
+                config.query_cache.max_entries,
+                config.query_cache.max_size_mb * 1024 * 1024,
+                Duration::from_secs(config.query_cache.ttl_seconds),
+            )
+            .with_stale_grace(Duration::from_secs(
+                config.query_cache.stale_while_revalidate_seconds,
+            ))
+            .with_eviction_policy(config.query_cache.eviction_policy),
+        ));
+
+        let embedding_cache = Arc::new(RwLock::new(
+            LRUCache::new(
+                config.embedding_cache.max_entries,
+                config.embedding_cache.max_size_mb * 1024 * 1024,
+                Duration::from_secs(config.embedding_cache.ttl_seconds),
+            )
+            .with_eviction_policy(config.embedding_cache.eviction_policy),
+        ));
+
+        let bm25_cache = Arc::new(RwLock::new(
+            LRUCache::new(
+                config.bm25_cache.max_entries,
+                config.bm25_cache.max_size_mb * 1024 * 1024,
+                Duration::from_secs(config.bm25_cache.ttl_seconds),
+            )
+            .with_eviction_policy(config.bm25_cache.eviction_policy),
+        ));
+
+        let fusion_cache = Arc::new(RwLock::new(
+            LRUCache::new(
+                config.fusion_cache.max_entries,
+                config.fusion_cache.max_size_mb * 1024 * 1024,
+                Duration::from_secs(config.fusion_cache.ttl_seconds),
+            )
+            .with_eviction_policy(config.fusion_cache.eviction_policy),
+        ));
 
         let manager = Self {
             query_cache,
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
             embedding_cache,
             bm25_cache,
             fusion_cache,
@@ -97,10 +117,56 @@ impl HybridSearchCacheManager {
         }
     }
 
+    /// Serve query results from the cache, running `search` when they are missing
+    ///
+    /// Entries within the stale-while-revalidate window are returned at once
+    /// while `search` repopulates them in the background, so only a miss waits
+    /// on the search. At most one refresh per key runs at a time.
+    pub async fn get_or_search<F, Fut>(&self, request: &SearchRequest, search: F) -> Result<Vec<SearchResult>>
+    where
+        F: FnOnce(SearchRequest) -> Fut,
+        Fut: Future<Output = Result<Vec<SearchResult>>> + Send + 'static,
+    {
+        let key = QueryCacheKey::new(request);
+        let lookup = self.query_cache.write().await.lookup(&key);
+        match lookup {
+            CacheLookup::Fresh(results) => {
+                self.query_stats.write().await.record_hit();
+                Ok(results)
+            }
+            CacheLookup::Stale(results) => {
+                self.query_stats.write().await.record_stale_hit();
+                let newly_refreshing = self.refreshing.lock().unwrap().insert(key.clone());
+                if newly_refreshing {
+                    let refresh = search(request.clone());
+                    let query_cache = Arc::clone(&self.query_cache);
+                    let refreshing = Arc::clone(&self.refreshing);
+                    tokio::spawn(async move {
+                        match refresh.await {
+                            Ok(results) => {
+                                let size_bytes = estimate_results_size(&results);
+                                query_cache.write().await.insert(key.clone(), results, size_bytes);
+                            }
+                            Err(e) => tracing::warn!("Background cache refresh failed: {}", e),
+                        }
+                        refreshing.lock().unwrap().remove(&key);
+                    });
+                }
+                Ok(results)
+            }
+            CacheLookup::Miss => {
+                self.query_stats.write().await.record_miss();
+                let results = search(request.clone()).await?;
+                self.cache_query_results(request, results.clone()).await?;
+                Ok(results)
+            }
+        }
+    }
+
     /// Cache query results
     pub async fn cache_query_results(&self, request: &SearchRequest, results: Vec<SearchResult>) -> Result<()> {
         let key = QueryCacheKey::new(request);
-        let size_bytes = self.estimate_results_size(&results);
+        let size_bytes = estimate_results_size(&results);
 
         let mut cache = self.query_cache.write().await;
         cache.insert(key, results, size_bytes);
@@ -297,15 +363,15 @@ impl HybridSearchCacheManager {
             }
         });
     }
+}
 
-    fn estimate_results_size(&self, results: &[SearchResult]) -> usize {
-        results.iter().map(|r| {
-            std::mem::size_of::<SearchResult>() +
-            r.uri.len() +
-            r.content.len() +
-            r.custom_metadata.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>()
-        }).sum()
-    }
+fn estimate_results_size(results: &[SearchResult]) -> usize {
+    results.iter().map(|r| {
+        std::mem::size_of::<SearchResult>() +
+        r.uri.len() +
+        r.content.len() +
+        r.custom_metadata.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>()
+    }).sum()
 }
 
 use std::collections::HashMap;
@@ -340,3 +406,108 @@ impl CacheManagerStatistics {
         self.total_memory_usage as f64 / (1024.0 * 1024.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fusion::{FromSignals, FusionWeights, NormalizationMethod, ScoreBreakdown};
+    use crate::access::Principal;
+    use crate::models::{SearchFilters, SearchSettings};
+    use zero_latency_core::DocId;
+
+    fn result(uri: &str) -> SearchResult {
+        let scores = ScoreBreakdown {
+            bm25_raw: None,
+            vector_raw: Some(0.5),
+            bm25_normalized: None,
+            vector_normalized: Some(0.5),
+            fused: 0.5,
+            normalization_method: NormalizationMethod::MinMax,
+        };
+        SearchResult::new(
+            DocId::new("docs", uri, 1),
+            uri.to_string(),
+            String::new(),
+            String::new(),
+            scores,
+            FromSignals::vector_only(),
+        )
+    }
+
+    #[test]
+    fn test_cache_key_covers_collection_and_settings() {
+        let base = SearchRequest::new("tokio runtime");
+        let key = QueryCacheKey::new(&base);
+        assert_eq!(key, QueryCacheKey::new(&SearchRequest::new("Tokio  runtime")));
+
+        let mut filters = SearchFilters::default();
+        filters.custom.insert("collection".to_string(), "docs".to_string());
+        // Principals with different entitlements never share results
+        let restricted = SearchFilters {
+            access: Some(Principal::default()),
+            ..Default::default()
+        };
+        let variants = [
+            base.clone().with_filters(filters),
+            base.clone().with_filters(restricted),
+            base.clone().with_settings(&SearchSettings {
+                query_expansion: Some(false),
+                ..Default::default()
+            }),
+            base.clone().with_settings(&SearchSettings {
+                fusion_weights: Some(FusionWeights {
+                    bm25_weight: 0.5,
+                    vector_weight: 0.5,
+                }),
+                ..Default::default()
+            }),
+            base.clone().with_settings(&SearchSettings {
+                rerank_depth: Some(5),
+                ..Default::default()
+            }),
+        ];
+        for variant in &variants {
+            assert_ne!(QueryCacheKey::new(variant), key, "{:?}", variant.options);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stale_entries_are_served_while_refreshing() {
+        let mut config = CacheConfig::default();
+        config.query_cache.ttl_seconds = 0;
+        config.query_cache.stale_while_revalidate_seconds = 60;
+        config.global.enable_statistics = false;
+        let manager = HybridSearchCacheManager::new(config);
+        let request = SearchRequest::new("tokio");
+        let uris = |results: Vec<SearchResult>| results[0].uri.clone();
+
+        // A miss waits for the search
+        let results = manager
+            .get_or_search(&request, |_| async { Ok(vec![result("old.md")]) })
+            .await
+            .unwrap();
+        assert_eq!(uris(results), "old.md");
+
+        // Past the zero TTL the old results are served while the refresh runs
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let results = manager
+            .get_or_search(&request, |_| async { Ok(vec![result("new.md")]) })
+            .await
+            .unwrap();
+        assert_eq!(uris(results), "old.md");
+        while !manager.refreshing.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let results = manager
+            .get_or_search(&request, |_| async { Ok(vec![result("newer.md")]) })
+            .await
+            .unwrap();
+        assert_eq!(uris(results), "new.md");
+
+        let stats = manager.query_stats.read().await;
+        assert_eq!(stats.query_cache_misses, 1);
+        assert_eq!(stats.query_cache_stale_hits, 2);
+    }
+}
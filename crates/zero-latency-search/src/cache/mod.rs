@@ -7,17 +7,27 @@ pub mod manager;
 pub mod performance;
 pub mod integration;
 pub mod cache_demo;
+pub mod eviction;
+pub mod namespace;
+pub mod negative;
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use crate::models::SearchRequest;
+use eviction::{FrequencySketch, WINDOW_PERCENT};
 
 // Re-export simplified types
 pub use manager::HybridSearchCacheManager;
 pub use integration::CachedHybridSearchPipeline;
+pub use eviction::EvictionPolicy;
+pub use namespace::{
+    CacheNamespace, CollectionCache, CollectionCacheStatistics, CollectionCounters,
+    TenantResolver, DEFAULT_TENANT,
+};
+pub use negative::{IndexGeneration, NegativeCachingOrchestrator};
 
 /// Cache configuration for performance optimization
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +54,17 @@ pub struct QueryCacheConfig {
     pub normalize_queries: bool,
     /// Cache size limit in MB
     pub max_size_mb: usize,
+    /// How long past its TTL an entry may still be served while it is
+    /// refreshed in the background; 0 disables stale-while-revalidate
+    #[serde(default)]
+    pub stale_while_revalidate_seconds: u64,
+    /// How long a search that found nothing is remembered, so repeated
+    /// identical misses skip the pipeline; 0 disables negative caching
+    #[serde(default)]
+    pub empty_results_ttl_seconds: u64,
+    /// How entries are evicted once the cache is full
+    #[serde(default)]
+    pub eviction_policy: EvictionPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +77,13 @@ pub struct EmbeddingCacheConfig {
     pub max_size_mb: usize,
     /// Preload frequently accessed embeddings
     pub enable_preloading: bool,
+    /// How long a transient embedding failure is returned for the same text
+    /// without calling the model again; 0 disables failure caching
+    #[serde(default)]
+    pub failure_ttl_seconds: u64,
+    /// How entries are evicted once the cache is full
+    #[serde(default)]
+    pub eviction_policy: EvictionPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +96,9 @@ pub struct BM25CacheConfig {
     pub cache_term_scores: bool,
     /// Cache size limit in MB
     pub max_size_mb: usize,
+    /// How entries are evicted once the cache is full
+    #[serde(default)]
+    pub eviction_policy: EvictionPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,6 +109,9 @@ pub struct FusionCacheConfig {
     pub ttl_seconds: u64,
     /// Cache size limit in MB
     pub max_size_mb: usize,
+    /// How entries are evicted once the cache is full
+    #[serde(default)]
+    pub eviction_policy: EvictionPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,23 +134,31 @@ impl Default for CacheConfig {
                 ttl_seconds: 300, // 5 minutes
                 normalize_queries: true,
                 max_size_mb: 50,
+                stale_while_revalidate_seconds: 0,
+                empty_results_ttl_seconds: 30,
+                // Popular queries repeat all day; keep them through bursts
+                eviction_policy: EvictionPolicy::WTinyLfu,
             },
             embedding_cache: EmbeddingCacheConfig {
                 max_entries: 5000,
                 ttl_seconds: 3600, // 1 hour
                 max_size_mb: 200,
                 enable_preloading: true,
+                failure_ttl_seconds: 5,
+                eviction_policy: EvictionPolicy::Lru,
             },
             bm25_cache: BM25CacheConfig {
                 max_entries: 2000,
                 ttl_seconds: 600, // 10 minutes
                 cache_term_scores: true,
                 max_size_mb: 30,
+                eviction_policy: EvictionPolicy::Lru,
             },
             fusion_cache: FusionCacheConfig {
                 max_entries: 500,
                 ttl_seconds: 180, // 3 minutes
                 max_size_mb: 20,
+                eviction_policy: EvictionPolicy::Lru,
             },
             global: GlobalCacheConfig {
                 enable_statistics: true,
@@ -161,7 +203,17 @@ impl<T> CacheEntry<T> {
     }
 }
 
-/// LRU cache with TTL support
+/// Outcome of a cache lookup that may serve expired entries
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheLookup<V> {
+    Fresh(V),
+    /// Past its TTL but within the stale grace period
+    Stale(V),
+    Miss,
+}
+
+/// Bounded cache with TTL support, evicting least recently used entries
+/// unless another [`EvictionPolicy`] is set
 #[derive(Debug)]
 pub struct LRUCache<K, V> 
 where
@@ -174,6 +226,12 @@ where
     max_size_bytes: usize,
     current_size_bytes: usize,
     ttl: Duration,
+    stale_grace: Duration,
+    policy: EvictionPolicy,
+    /// Access frequencies for W-TinyLFU admission
+    sketch: Option<FrequencySketch>,
+    /// Keys in the W-TinyLFU admission window
+    window: HashSet<K>,
 }
 
 impl<K, V> LRUCache<K, V>
@@ -189,25 +247,60 @@ where
             max_size_bytes,
             current_size_bytes: 0,
             ttl,
+            stale_grace: Duration::ZERO,
+            policy: EvictionPolicy::Lru,
+            sketch: None,
+            window: HashSet::new(),
         }
     }
 
+    /// Keep expired entries for `grace` so [`Self::lookup`] can serve them stale
+    pub fn with_stale_grace(mut self, grace: Duration) -> Self {
+        self.stale_grace = grace;
+        self
+    }
+
+    /// Choose eviction victims by `policy`
+    pub fn with_eviction_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.policy = policy;
+        self.sketch = (policy == EvictionPolicy::WTinyLfu)
+            .then(|| FrequencySketch::new(self.max_entries));
+        self
+    }
+
     pub fn get(&mut self, key: &K) -> Option<V> {
-        if let Some(entry) = self.entries.get_mut(key) {
-            if entry.is_expired(self.ttl) {
-                self.remove(key);
-                return None;
-            }
+        match self.lookup(key) {
+            CacheLookup::Fresh(value) => Some(value),
+            CacheLookup::Stale(_) | CacheLookup::Miss => None,
+        }
+    }
 
-            // Update access order
-            if let Some(pos) = self.access_order.iter().position(|k| k == key) {
-                self.access_order.remove(pos);
-            }
-            self.access_order.push(key.clone());
+    /// Look up an entry, distinguishing fresh hits from stale ones
+    pub fn lookup(&mut self, key: &K) -> CacheLookup<V> {
+        // Misses count too: a key asked for often is worth admitting
+        if let Some(sketch) = &mut self.sketch {
+            sketch.increment(key);
+        }
+        let Some(entry) = self.entries.get_mut(key) else {
+            return CacheLookup::Miss;
+        };
+        if entry.is_expired(self.ttl + self.stale_grace) {
+            self.remove(key);
+            return CacheLookup::Miss;
+        }
+        let stale = entry.is_expired(self.ttl);
+        let value = entry.access().clone();
 
-            Some(entry.access().clone())
+        // Update access order
+        if let Some(pos) = self.access_order.iter().position(|k| k == key) {
+            self.access_order.remove(pos);
+        }
+        self.access_order.push(key.clone());
+
+        if stale {
+            CacheLookup::Stale(value)
         } else {
-            None
+            CacheLookup::Fresh(value)
         }
     }
 
@@ -224,7 +317,19 @@ where
         let entry = CacheEntry::new(value, size_bytes);
         self.current_size_bytes += size_bytes;
         self.entries.insert(key.clone(), entry);
-        self.access_order.push(key);
+        self.access_order.push(key.clone());
+
+        // New entries start in the admission window; the oldest leave it
+        // for the main segment once it's full
+        if self.policy == EvictionPolicy::WTinyLfu {
+            self.window.insert(key);
+            while self.window.len() > self.window_capacity() {
+                match self.oldest_in_window() {
+                    Some(oldest) => self.window.remove(&oldest),
+                    None => break,
+                };
+            }
+        }
     }
 
     pub fn remove(&mut self, key: &K) -> Option<V> {
@@ -234,6 +339,7 @@ where
             if let Some(pos) = self.access_order.iter().position(|k| k == key) {
                 self.access_order.remove(pos);
             }
+            self.window.remove(key);
             
             Some(entry.value)
         } else {
@@ -244,13 +350,15 @@ where
     pub fn clear(&mut self) {
         self.entries.clear();
         self.access_order.clear();
+        self.window.clear();
         self.current_size_bytes = 0;
     }
 
     pub fn cleanup_expired(&mut self) {
+        let retention = self.ttl + self.stale_grace;
         let expired_keys: Vec<K> = self.entries
             .iter()
-            .filter(|(_, entry)| entry.is_expired(self.ttl))
+            .filter(|(_, entry)| entry.is_expired(retention))
             .map(|(key, _)| key.clone())
             .collect();
 
@@ -259,19 +367,84 @@ where
         }
     }
 
+    /// Evict the least recently used `fraction` of the entries, at least
+    /// one while any are cached; returns how many were evicted
+    pub fn shrink(&mut self, fraction: f64) -> usize {
+        let count = ((self.entries.len() as f64 * fraction.clamp(0.0, 1.0)).ceil() as usize)
+            .min(self.access_order.len());
+        for key in self.access_order.drain(..count).collect::<Vec<_>>() {
+            if let Some(entry) = self.entries.remove(&key) {
+                self.current_size_bytes = self.current_size_bytes.saturating_sub(entry.size_bytes);
+            }
+            self.window.remove(&key);
+        }
+        count
+    }
+
     fn evict_if_needed(&mut self, incoming_size: usize) {
         // Check if we need to evict based on count or size
         while (self.entries.len() >= self.max_entries || 
                self.current_size_bytes + incoming_size > self.max_size_bytes) &&
               !self.access_order.is_empty() {
             
-            let lru_key = self.access_order.remove(0);
-            if let Some(entry) = self.entries.remove(&lru_key) {
-                self.current_size_bytes = self.current_size_bytes.saturating_sub(entry.size_bytes);
+            let Some(victim) = self.eviction_victim() else {
+                break;
+            };
+            self.remove(&victim);
+        }
+    }
+
+    /// The entry to evict under the cache's policy
+    fn eviction_victim(&mut self) -> Option<K> {
+        match self.policy {
+            EvictionPolicy::Lru => self.access_order.first().cloned(),
+            // The first minimum is the least recently used among equals
+            EvictionPolicy::Lfu => self
+                .access_order
+                .iter()
+                .min_by_key(|key| self.entries.get(*key).map_or(0, |entry| entry.access_count))
+                .cloned(),
+            EvictionPolicy::WTinyLfu => {
+                let main_victim = self
+                    .access_order
+                    .iter()
+                    .find(|key| !self.window.contains(*key))
+                    .cloned();
+                // Below its share the window grows at the main segment's expense
+                if self.window.len() < self.window_capacity() && main_victim.is_some() {
+                    return main_victim;
+                }
+                let Some(candidate) = self.oldest_in_window() else {
+                    return main_victim;
+                };
+                let Some(main_victim) = main_victim else {
+                    return Some(candidate);
+                };
+
+                // The window's oldest entry is only admitted to the main
+                // segment if it's asked for more often than what it replaces
+                let sketch = self.sketch.as_ref()?;
+                if sketch.frequency(&candidate) > sketch.frequency(&main_victim) {
+                    self.window.remove(&candidate);
+                    Some(main_victim)
+                } else {
+                    Some(candidate)
+                }
             }
         }
     }
 
+    fn window_capacity(&self) -> usize {
+        (self.max_entries * WINDOW_PERCENT / 100).max(1)
+    }
+
+    fn oldest_in_window(&self) -> Option<K> {
+        self.access_order
+            .iter()
+            .find(|key| self.window.contains(*key))
+            .cloned()
+    }
+
     pub fn size(&self) -> usize {
         self.entries.len()
     }
@@ -279,6 +452,23 @@ where
     pub fn memory_usage(&self) -> usize {
         self.current_size_bytes
     }
+
+    /// Remove every entry whose key matches `predicate`; returns how many
+    pub fn remove_where(&mut self, predicate: impl Fn(&K) -> bool) -> usize {
+        let keys: Vec<K> = self.entries.keys().filter(|key| predicate(key)).cloned().collect();
+        for key in &keys {
+            self.remove(key);
+        }
+        keys.len()
+    }
+
+    /// Entries and bytes held under keys matching `predicate`
+    pub fn usage_where(&self, predicate: impl Fn(&K) -> bool) -> (usize, usize) {
+        self.entries
+            .iter()
+            .filter(|(key, _)| predicate(key))
+            .fold((0, 0), |(entries, bytes), (_, entry)| (entries + 1, bytes + entry.size_bytes))
+    }
 }
 
 /// Cache statistics for monitoring
@@ -338,12 +528,21 @@ impl CacheStatistics {
 }
 
 /// Query cache key for consistent hashing
+///
+/// Covers everything that changes the results of a request, so requests that
+/// differ only in settings never share an entry.
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct QueryCacheKey {
+    /// Tenant the search ran for
+    pub tenant: String,
     pub query: String,
     pub limit: usize,
     pub offset: usize,
+    /// Collection the search is scoped to
+    pub collection: Option<String>,
     pub filters: Vec<String>,
+    /// Expansion, ranking and output settings, in a fixed order
+    pub settings: Vec<String>,
 }
 
 impl QueryCacheKey {
@@ -360,18 +559,97 @@ impl QueryCacheKey {
             filters.push(format!("tag:{}", tag));
         }
 
-        // Add custom filters
+        // Add custom filters; the collection has its own field
         for (key, value) in &request.filters.custom {
-            filters.push(format!("{}:{}", key, value));
+            if key != "collection" {
+                filters.push(format!("{}:{}", key, value));
+            }
+        }
+        if let Some(date_range) = &request.filters.date_range {
+            filters.push(format!("date:{:?}..{:?}", date_range.start, date_range.end));
+        }
+        if let Some(minimum_score) = request.filters.minimum_score {
+            filters.push(format!("min_score:{}", minimum_score.value()));
+        }
+
+        // Add exclusions
+        let exclude = &request.filters.exclude;
+        for tag in &exclude.tags {
+            filters.push(format!("-tag:{}", tag));
+        }
+        for collection in &exclude.collections {
+            filters.push(format!("-collection:{}", collection));
+        }
+        for document in &exclude.documents {
+            filters.push(format!("-doc:{}", document));
+        }
+        for path in &exclude.paths {
+            filters.push(format!("-path:{}", path));
+        }
+        for (key, pattern) in &request.filters.metadata_patterns {
+            filters.push(format!("~{}:{}", key, pattern));
+        }
+        for (key, range) in &request.filters.ranges {
+            filters.push(format!("{}:[{:?},{:?}]", key, range.gte, range.lte));
+        }
+        if let Some(principal) = &request.filters.access {
+            filters.push(format!("access:{}", principal.cache_key()));
         }
 
         filters.sort(); // Ensure consistent ordering
 
+        let options = &request.options;
+        let mut settings = vec![
+            format!("expansion:{}", options.enable_query_enhancement),
+            format!("personalization:{}", options.enable_personalization),
+            format!("snippets:{}:{}", options.include_snippets, options.snippet_length),
+            format!("signals:{}", options.include_ranking_signals),
+            format!("format:{:?}", options.response_format),
+        ];
+        if let Some(weights) = options.fusion_weights {
+            settings.push(format!(
+                "fusion:{}:{}",
+                weights.bm25_weight, weights.vector_weight
+            ));
+        }
+        if let Some(depth) = options.rerank_depth {
+            settings.push(format!("rerank:{}", depth));
+        }
+        if let Some(weights) = options.field_weights {
+            settings.push(format!(
+                "fields:{}:{}:{}:{}",
+                weights.title, weights.heading, weights.path, weights.content
+            ));
+        }
+        if let Some(weight) = options.proximity_weight {
+            settings.push(format!("proximity:{}", weight));
+        }
+        for space in options.vector_spaces.iter().flatten() {
+            settings.push(format!("vectors:{}:{}", space.name, space.weight));
+        }
+        if options.late_interaction {
+            settings.push("late_interaction".to_string());
+        }
+
         Self {
+            tenant: DEFAULT_TENANT.to_string(),
             query: request.query.normalized.clone(),
             limit: request.limit,
             offset: request.offset,
+            collection: request.filters.custom.get("collection").cloned(),
             filters,
+            settings,
         }
     }
+
+    /// The same key for `tenant`
+    pub fn with_tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = tenant.into();
+        self
+    }
+
+    /// Tenant and collection the entry belongs to
+    pub fn namespace(&self) -> CacheNamespace {
+        CacheNamespace::new(self.tenant.clone(), self.collection.clone())
+    }
 }
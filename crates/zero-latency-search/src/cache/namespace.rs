@@ -0,0 +1,160 @@
+//! Cache namespaces
+//!
+//! Every cache entry derived from a search belongs to the tenant the search
+//! ran for and the collection it was scoped to, and both are part of its key,
+//! so one collection or tenant can never be answered from another's entries.
+//! The namespace also lets a collection's entries be counted and purged on
+//! their own, e.g. after its documents were replaced out of band.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Tenant of searches that don't name one
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Tenant and collection a cache entry belongs to
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CacheNamespace {
+    pub tenant: String,
+    /// None for searches across every collection
+    pub collection: Option<String>,
+}
+
+impl CacheNamespace {
+    pub fn new(tenant: impl Into<String>, collection: Option<String>) -> Self {
+        Self {
+            tenant: tenant.into(),
+            collection,
+        }
+    }
+
+    /// Whether the entry was scoped to `collection`
+    pub fn is_scoped_to(&self, collection: &str) -> bool {
+        self.collection.as_deref() == Some(collection)
+    }
+
+    /// Whether the entry may hold data of `collection`: scoped to it, or to
+    /// no collection at all
+    pub fn covers(&self, collection: &str) -> bool {
+        self.collection.is_none() || self.is_scoped_to(collection)
+    }
+}
+
+/// Source of the tenant the current search runs for
+pub trait TenantResolver: Send + Sync {
+    fn current_tenant(&self) -> String;
+}
+
+/// Usage of a cache by one collection, across tenants
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CollectionCacheStatistics {
+    pub entries: usize,
+    pub memory_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+}
+
+impl CollectionCacheStatistics {
+    pub fn new(entries: usize, memory_bytes: usize, (hits, misses): (u64, u64)) -> Self {
+        let total = hits + misses;
+        Self {
+            entries,
+            memory_bytes,
+            hits,
+            misses,
+            hit_rate: if total > 0 {
+                hits as f64 / total as f64
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+/// Hits and misses per collection
+#[derive(Debug, Default)]
+pub struct CollectionCounters(Mutex<HashMap<String, (u64, u64)>>);
+
+impl CollectionCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_hit(&self, collection: Option<&str>) {
+        self.record(collection, |counts| counts.0 += 1);
+    }
+
+    pub fn record_miss(&self, collection: Option<&str>) {
+        self.record(collection, |counts| counts.1 += 1);
+    }
+
+    /// Hits and misses of `collection`
+    pub fn get(&self, collection: &str) -> (u64, u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .get(collection)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Start counting `collection` afresh
+    pub fn reset(&self, collection: &str) {
+        self.0.lock().unwrap().remove(collection);
+    }
+
+    // Searches across every collection aren't attributed to any
+    fn record(&self, collection: Option<&str>, update: impl FnOnce(&mut (u64, u64))) {
+        if let Some(collection) = collection {
+            update(
+                self.0
+                    .lock()
+                    .unwrap()
+                    .entry(collection.to_string())
+                    .or_default(),
+            );
+        }
+    }
+}
+
+/// A cache whose entries are namespaced by collection
+#[async_trait]
+pub trait CollectionCache: Send + Sync {
+    /// Entries scoped to `collection` and their hits and misses
+    async fn collection_statistics(&self, collection: &str) -> CollectionCacheStatistics;
+
+    /// Remove every entry that may hold data of `collection`, for every
+    /// tenant; returns how many were removed
+    async fn purge_collection(&self, collection: &str) -> usize;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespaces_cover_their_collection_and_unscoped_searches() {
+        let scoped = CacheNamespace::new("acme", Some("docs".to_string()));
+        let unscoped = CacheNamespace::new("acme", None);
+        assert!(scoped.is_scoped_to("docs") && scoped.covers("docs"));
+        assert!(!scoped.covers("api"));
+        assert!(!unscoped.is_scoped_to("docs") && unscoped.covers("docs"));
+        assert_ne!(
+            scoped,
+            CacheNamespace::new("globex", Some("docs".to_string()))
+        );
+
+        let counters = CollectionCounters::new();
+        counters.record_hit(Some("docs"));
+        counters.record_miss(Some("docs"));
+        counters.record_miss(None);
+        assert_eq!(counters.get("docs"), (1, 1));
+        let statistics = CollectionCacheStatistics::new(2, 100, counters.get("docs"));
+        assert_eq!(statistics.hit_rate, 0.5);
+        counters.reset("docs");
+        assert_eq!(counters.get("docs"), (0, 0));
+    }
+}
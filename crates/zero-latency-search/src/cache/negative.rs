@@ -0,0 +1,343 @@
+//! Negative caching of searches that found nothing
+//!
+//! A UI typeahead sends the same unmatched prefix many times over, and each
+//! one runs the whole pipeline, embedding included, only to find nothing
+//! again. Searches with no results are remembered for a short TTL under the
+//! same key as the query result cache, so a repeat is answered from memory.
+//! Searches with results always run. Entries are tagged with the index
+//! generation they were found at, and any index mutation since invalidates
+//! them, so a document that now matches shows up on the next search. Keys
+//! carry the tenant, so tenants never share remembered misses.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use zero_latency_core::Result;
+
+use super::{
+    CacheStatistics, CollectionCache, CollectionCacheStatistics, CollectionCounters, LRUCache,
+    QueryCacheConfig, QueryCacheKey, TenantResolver,
+};
+use crate::models::{SearchRequest, SearchResponse};
+use crate::traits::SearchOrchestrator;
+
+/// Rough size of a cached empty response besides its key
+const ENTRY_OVERHEAD_BYTES: usize = 512;
+
+/// Source of a counter that advances whenever the index changes
+pub trait IndexGeneration: Send + Sync {
+    fn generation(&self) -> u64;
+}
+
+/// Orchestrator remembering which searches found nothing
+pub struct NegativeCachingOrchestrator {
+    inner: Arc<dyn SearchOrchestrator>,
+    /// Empty responses with the index generation they were found at; None
+    /// when negative caching is disabled
+    cache: Option<Mutex<LRUCache<QueryCacheKey, (u64, SearchResponse)>>>,
+    generation: Option<Arc<dyn IndexGeneration>>,
+    tenants: Option<Arc<dyn TenantResolver>>,
+    statistics: Mutex<CacheStatistics>,
+    collections: CollectionCounters,
+}
+
+impl NegativeCachingOrchestrator {
+    pub fn new(inner: Arc<dyn SearchOrchestrator>, config: &QueryCacheConfig) -> Self {
+        Self {
+            inner,
+            cache: (config.empty_results_ttl_seconds > 0).then(|| {
+                Mutex::new(
+                    LRUCache::new(
+                        config.max_entries,
+                        config.max_size_mb * 1024 * 1024,
+                        Duration::from_secs(config.empty_results_ttl_seconds),
+                    )
+                    .with_eviction_policy(config.eviction_policy),
+                )
+            }),
+            generation: None,
+            tenants: None,
+            statistics: Mutex::new(CacheStatistics::new()),
+            collections: CollectionCounters::new(),
+        }
+    }
+
+    /// Drop remembered misses whenever `generation` advances
+    pub fn with_generation(mut self, generation: Arc<dyn IndexGeneration>) -> Self {
+        self.generation = Some(generation);
+        self
+    }
+
+    /// Keep each tenant's misses apart, as named by `tenants`
+    pub fn with_tenant_resolver(mut self, tenants: Arc<dyn TenantResolver>) -> Self {
+        self.tenants = Some(tenants);
+        self
+    }
+
+    /// Forget every remembered miss
+    pub async fn invalidate(&self) {
+        if let Some(cache) = &self.cache {
+            cache.lock().await.clear();
+        }
+    }
+
+    /// Hits, misses and current size of the cache
+    pub async fn statistics(&self) -> CacheStatistics {
+        let mut statistics = self.statistics.lock().await.clone();
+        if let Some(cache) = &self.cache {
+            let cache = cache.lock().await;
+            statistics.size = cache.size();
+            statistics.memory_usage = cache.memory_usage();
+        }
+        statistics
+    }
+
+    fn current_generation(&self) -> u64 {
+        self.generation
+            .as_ref()
+            .map_or(0, |generation| generation.generation())
+    }
+}
+
+#[async_trait]
+impl SearchOrchestrator for NegativeCachingOrchestrator {
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
+        let Some(cache) = &self.cache else {
+            return self.inner.search(request).await;
+        };
+
+        let mut key = QueryCacheKey::new(&request);
+        if let Some(tenants) = &self.tenants {
+            key = key.with_tenant(tenants.current_tenant());
+        }
+        let collection = key.collection.clone();
+        // Read before searching, so a mutation during the search leaves the
+        // entry already outdated
+        let generation = self.current_generation();
+        {
+            let mut cache = cache.lock().await;
+            match cache.get(&key) {
+                Some((found_at, response)) if found_at == generation => {
+                    drop(cache);
+                    self.statistics.lock().await.record_hit();
+                    self.collections.record_hit(collection.as_deref());
+                    return Ok(response);
+                }
+                Some(_) => {
+                    // The index changed since; the old miss says nothing
+                    cache.remove(&key);
+                    self.statistics.lock().await.record_eviction();
+                }
+                None => {}
+            }
+        }
+        self.statistics.lock().await.record_miss();
+        self.collections.record_miss(collection.as_deref());
+
+        let response = self.inner.search(request).await?;
+        if response.results.is_empty() {
+            let size_bytes = key.query.len() + ENTRY_OVERHEAD_BYTES;
+            cache
+                .lock()
+                .await
+                .insert(key, (generation, response.clone()), size_bytes);
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl CollectionCache for NegativeCachingOrchestrator {
+    async fn collection_statistics(&self, collection: &str) -> CollectionCacheStatistics {
+        let (entries, memory_bytes) = match &self.cache {
+            Some(cache) => cache
+                .lock()
+                .await
+                .usage_where(|key| key.namespace().is_scoped_to(collection)),
+            None => (0, 0),
+        };
+        CollectionCacheStatistics::new(entries, memory_bytes, self.collections.get(collection))
+    }
+
+    async fn purge_collection(&self, collection: &str) -> usize {
+        self.collections.reset(collection);
+        match &self.cache {
+            Some(cache) => cache
+                .lock()
+                .await
+                .remove_where(|key| key.namespace().covers(collection)),
+            None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheConfig;
+    use crate::fusion::{FromSignals, NormalizationMethod, ScoreBreakdown};
+    use crate::models::{SearchContext, SearchResult};
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    /// Finds one result for queries mentioning "rust" and counts searches
+    struct CountingOrchestrator(AtomicUsize);
+
+    #[async_trait]
+    impl SearchOrchestrator for CountingOrchestrator {
+        async fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            let found = request.query.raw.contains("rust");
+            let mut context = SearchContext::new(request);
+            if found {
+                context.set_ranked_results(vec![SearchResult::new(
+                    zero_latency_core::DocId::new("docs", "rust", 1),
+                    "rust.md".to_string(),
+                    "Rust".to_string(),
+                    String::new(),
+                    ScoreBreakdown {
+                        bm25_raw: None,
+                        vector_raw: None,
+                        bm25_normalized: None,
+                        vector_normalized: None,
+                        fused: 0.5,
+                        normalization_method: NormalizationMethod::MinMax,
+                    },
+                    FromSignals::vector_only(),
+                )]);
+            }
+            Ok(context.into_response())
+        }
+    }
+
+    struct Counter(AtomicU64);
+
+    impl IndexGeneration for Counter {
+        fn generation(&self) -> u64 {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    fn orchestrator(
+        ttl_seconds: u64,
+    ) -> (
+        NegativeCachingOrchestrator,
+        Arc<CountingOrchestrator>,
+        Arc<Counter>,
+    ) {
+        let inner = Arc::new(CountingOrchestrator(AtomicUsize::new(0)));
+        let generation = Arc::new(Counter(AtomicU64::new(1)));
+        let mut config = CacheConfig::default().query_cache;
+        config.empty_results_ttl_seconds = ttl_seconds;
+        let orchestrator = NegativeCachingOrchestrator::new(inner.clone(), &config)
+            .with_generation(generation.clone());
+        (orchestrator, inner, generation)
+    }
+
+    #[tokio::test]
+    async fn test_empty_results_are_remembered_until_the_index_changes() {
+        let (orchestrator, inner, generation) = orchestrator(30);
+
+        for _ in 0..3 {
+            let response = orchestrator
+                .search(SearchRequest::new("tokio"))
+                .await
+                .unwrap();
+            assert!(response.results.is_empty());
+        }
+        assert_eq!(inner.0.load(Ordering::SeqCst), 1);
+        // Normalization makes these the same query
+        orchestrator
+            .search(SearchRequest::new("  Tokio "))
+            .await
+            .unwrap();
+        assert_eq!(inner.0.load(Ordering::SeqCst), 1);
+
+        // Searches with results always run
+        for _ in 0..2 {
+            orchestrator
+                .search(SearchRequest::new("rust"))
+                .await
+                .unwrap();
+        }
+        assert_eq!(inner.0.load(Ordering::SeqCst), 3);
+
+        generation.0.fetch_add(1, Ordering::SeqCst);
+        orchestrator
+            .search(SearchRequest::new("tokio"))
+            .await
+            .unwrap();
+        assert_eq!(inner.0.load(Ordering::SeqCst), 4);
+
+        let statistics = orchestrator.statistics().await;
+        assert_eq!((statistics.hits, statistics.misses), (3, 4));
+        assert_eq!(statistics.evictions, 1);
+        assert_eq!(statistics.size, 1);
+
+        orchestrator.invalidate().await;
+        orchestrator
+            .search(SearchRequest::new("tokio"))
+            .await
+            .unwrap();
+        assert_eq!(inner.0.load(Ordering::SeqCst), 5);
+    }
+
+    struct FixedTenant(&'static str);
+
+    impl TenantResolver for FixedTenant {
+        fn current_tenant(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    fn in_collection(query: &str, collection: &str) -> SearchRequest {
+        let mut request = SearchRequest::new(query);
+        request
+            .filters
+            .custom
+            .insert("collection".to_string(), collection.to_string());
+        request
+    }
+
+    #[tokio::test]
+    async fn test_misses_are_kept_per_tenant_and_purged_per_collection() {
+        let inner = Arc::new(CountingOrchestrator(AtomicUsize::new(0)));
+        let config = CacheConfig::default().query_cache;
+        let acme = NegativeCachingOrchestrator::new(inner.clone(), &config)
+            .with_tenant_resolver(Arc::new(FixedTenant("acme")));
+
+        acme.search(in_collection("tokio", "docs")).await.unwrap();
+        acme.search(in_collection("tokio", "docs")).await.unwrap();
+        acme.search(in_collection("tokio", "api")).await.unwrap();
+        acme.search(SearchRequest::new("tokio")).await.unwrap();
+        assert_eq!(inner.0.load(Ordering::SeqCst), 3);
+
+        let docs = acme.collection_statistics("docs").await;
+        assert_eq!((docs.entries, docs.hits, docs.misses), (1, 1, 1));
+
+        // The purge takes the unscoped entry along, but leaves other collections
+        assert_eq!(acme.purge_collection("docs").await, 2);
+        assert_eq!(acme.collection_statistics("docs").await.entries, 0);
+        assert_eq!(acme.collection_statistics("api").await.entries, 1);
+        acme.search(in_collection("tokio", "docs")).await.unwrap();
+        assert_eq!(inner.0.load(Ordering::SeqCst), 4);
+
+        // Another tenant's identical search isn't answered from acme's entry
+        let globex = acme.with_tenant_resolver(Arc::new(FixedTenant("globex")));
+        globex.search(in_collection("tokio", "docs")).await.unwrap();
+        assert_eq!(inner.0.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_zero_ttl_disables_negative_caching() {
+        let (orchestrator, inner, _) = orchestrator(0);
+        for _ in 0..2 {
+            orchestrator
+                .search(SearchRequest::new("tokio"))
+                .await
+                .unwrap();
+        }
+        assert_eq!(inner.0.load(Ordering::SeqCst), 2);
+        assert_eq!(orchestrator.statistics().await.misses, 0);
+    }
+}
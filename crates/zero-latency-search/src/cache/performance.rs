@@ -28,6 +28,9 @@ impl Default for PerformanceThresholds {
 pub struct CacheStatistics {
     pub query_cache_hits: u64,
     pub query_cache_misses: u64,
+    /// Hits served past their TTL while a background refresh ran
+    #[serde(default)]
+    pub query_cache_stale_hits: u64,
     pub embedding_cache_hits: u64,
     pub embedding_cache_misses: u64,
     pub bm25_cache_hits: u64,
@@ -48,6 +51,7 @@ impl CacheStatistics {
         Self {
             query_cache_hits: 0,
             query_cache_misses: 0,
+            query_cache_stale_hits: 0,
             embedding_cache_hits: 0,
             embedding_cache_misses: 0,
             bm25_cache_hits: 0,
@@ -67,6 +71,10 @@ impl CacheStatistics {
         self.query_cache_misses += 1;
     }
 
+    pub fn record_stale_hit(&mut self) {
+        self.query_cache_stale_hits += 1;
+    }
+
     pub fn memory_usage_mb(&self) -> f64 {
         self.memory_usage_bytes as f64 / (1024.0 * 1024.0)
     }
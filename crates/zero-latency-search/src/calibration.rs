@@ -0,0 +1,201 @@
+//! Score calibration against per-collection background distributions
+//!
+//! Fused scores are only meaningful relative to the other results of the
+//! same query: a vague query tops out at 0.3 while a precise one returns
+//! dozens of results above 0.9, so no single `min_score` works for both.
+//! [`ScoreCalibrator`] keeps the running mean and variance of the scores each
+//! collection returns and maps a result's score onto that background with a
+//! sigmoid of its z-score. A calibrated score of 0.5 is a typical result for
+//! the collection, 0.73 one standard deviation above it and 0.88 two.
+//!
+//! Calibration stays off for a collection until its background holds
+//! `min_samples` scores, so a fresh index keeps its raw scores rather than
+//! ones measured against a handful of searches.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use zero_latency_core::values::Score;
+
+use crate::models::SearchResult;
+
+/// Weight past which older scores fade from a background, so it follows the
+/// collection as documents come and go
+const MAX_BACKGROUND_WEIGHT: f64 = 10_000.0;
+
+/// Smallest standard deviation scores are divided by, for collections whose
+/// scores barely vary
+const MIN_STD_DEV: f64 = 1e-3;
+
+/// Running distribution of the scores a collection returns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackgroundStats {
+    /// Scores observed, capped at the weight older scores fade beyond
+    pub samples: f64,
+    pub mean: f64,
+    // Sum of squared distances from the mean (Welford's M2)
+    m2: f64,
+}
+
+impl BackgroundStats {
+    fn observe(&mut self, score: f64) {
+        self.samples = (self.samples + 1.0).min(MAX_BACKGROUND_WEIGHT);
+        let delta = score - self.mean;
+        self.mean += delta / self.samples;
+        self.m2 += delta * (score - self.mean);
+        // Shrink the accumulated spread along with the capped weight
+        if self.samples >= MAX_BACKGROUND_WEIGHT {
+            self.m2 *= (MAX_BACKGROUND_WEIGHT - 1.0) / MAX_BACKGROUND_WEIGHT;
+        }
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        if self.samples < 2.0 {
+            return 0.0;
+        }
+        (self.m2 / (self.samples - 1.0)).sqrt()
+    }
+
+    /// Map a raw score onto this background
+    pub fn calibrate(&self, score: f32) -> f32 {
+        let z = (score as f64 - self.mean) / self.std_dev().max(MIN_STD_DEV);
+        (1.0 / (1.0 + (-z).exp())) as f32
+    }
+}
+
+/// Calibrates final scores against each collection's background distribution
+pub struct ScoreCalibrator {
+    backgrounds: RwLock<HashMap<String, BackgroundStats>>,
+    min_samples: usize,
+}
+
+impl ScoreCalibrator {
+    pub fn new(min_samples: usize) -> Self {
+        Self {
+            backgrounds: RwLock::new(HashMap::new()),
+            min_samples,
+        }
+    }
+
+    /// Add the raw scores of a search to a collection's background
+    pub fn observe(&self, collection: &str, results: &[SearchResult]) {
+        if results.is_empty() {
+            return;
+        }
+        let mut backgrounds = self.backgrounds.write().unwrap_or_else(|e| e.into_inner());
+        let background = backgrounds.entry(collection.to_string()).or_default();
+        for result in results {
+            background.observe(raw_score(result) as f64);
+        }
+    }
+
+    /// Replace final scores with their calibrated values
+    ///
+    /// The raw score is kept in `from_signals.uncalibrated_score`, so
+    /// calibrating twice is harmless. Returns false, leaving the scores as
+    /// they are, while the collection's background is too small.
+    pub fn calibrate(&self, collection: &str, results: &mut [SearchResult]) -> bool {
+        let Some(background) = self.background(collection) else {
+            return false;
+        };
+        if background.samples < self.min_samples as f64 {
+            return false;
+        }
+
+        for result in results.iter_mut() {
+            let raw = raw_score(result);
+            result.from_signals.uncalibrated_score = Some(raw);
+            result.final_score =
+                Score::new(background.calibrate(raw)).unwrap_or(result.final_score);
+        }
+        true
+    }
+
+    /// A collection's background, if any of its searches were observed
+    pub fn background(&self, collection: &str) -> Option<BackgroundStats> {
+        let backgrounds = self.backgrounds.read().unwrap_or_else(|e| e.into_inner());
+        backgrounds.get(collection).copied()
+    }
+
+    /// Forget a collection's background, as when the collection is deleted
+    pub fn reset(&self, collection: &str) {
+        let mut backgrounds = self.backgrounds.write().unwrap_or_else(|e| e.into_inner());
+        backgrounds.remove(collection);
+    }
+}
+
+fn raw_score(result: &SearchResult) -> f32 {
+    result
+        .from_signals
+        .uncalibrated_score
+        .unwrap_or(result.final_score.value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fusion::{FromSignals, NormalizationMethod, ScoreBreakdown};
+    use zero_latency_core::DocId;
+
+    fn results(scores: &[f32]) -> Vec<SearchResult> {
+        scores
+            .iter()
+            .map(|&score| {
+                let scores = ScoreBreakdown {
+                    bm25_raw: None,
+                    vector_raw: Some(score),
+                    bm25_normalized: None,
+                    vector_normalized: Some(score),
+                    fused: score,
+                    normalization_method: NormalizationMethod::MinMax,
+                };
+                SearchResult::new(
+                    DocId::new("docs", "guide.md", 1),
+                    "guide.md".to_string(),
+                    "Guide".to_string(),
+                    "Content".to_string(),
+                    scores,
+                    FromSignals::vector_only(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scores_are_comparable_across_queries() {
+        let calibrator = ScoreCalibrator::new(20);
+        let background: Vec<f32> = (0..40).map(|i| 0.2 + 0.01 * (i % 20) as f32).collect();
+
+        // Too few samples leaves the raw scores alone
+        calibrator.observe("docs", &results(&background[..10]));
+        let mut vague = results(&[0.35, 0.25]);
+        assert!(!calibrator.calibrate("docs", &mut vague));
+        assert_eq!(vague[0].final_score.value(), 0.35);
+
+        calibrator.observe("docs", &results(&background[10..]));
+        let stats = calibrator.background("docs").unwrap();
+        assert_eq!(stats.samples, 40.0);
+
+        // The same raw score calibrates the same way in any query, and an
+        // average score lands in the middle
+        let mut precise = results(&[0.9, 0.35]);
+        assert!(calibrator.calibrate("docs", &mut vague));
+        assert!(calibrator.calibrate("docs", &mut precise));
+        assert_eq!(vague[0].final_score.value(), precise[1].final_score.value());
+        assert!(precise[0].final_score.value() > 0.99);
+        let mut average = results(&[stats.mean as f32]);
+        calibrator.calibrate("docs", &mut average);
+        assert!((average[0].final_score.value() - 0.5).abs() < 1e-3);
+
+        // Calibrating again starts from the raw score
+        let calibrated = vague[0].final_score.value();
+        calibrator.calibrate("docs", &mut vague);
+        assert_eq!(vague[0].final_score.value(), calibrated);
+        assert_eq!(vague[0].from_signals.uncalibrated_score, Some(0.35));
+
+        // Other collections have their own backgrounds
+        assert!(!calibrator.calibrate("other", &mut results(&[0.5])));
+        calibrator.reset("docs");
+        assert!(calibrator.background("docs").is_none());
+    }
+}
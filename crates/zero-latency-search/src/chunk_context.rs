@@ -0,0 +1,143 @@
+//! Neighboring chunks stitched around search hits
+//!
+//! Documents are split into chunks of a fixed number of sentences, so a hit
+//! can start or end mid-thought. With a context window of `n`, the `n` chunks
+//! before and after a hit in its document are joined around its content in
+//! order, each seam marked by [`CHUNK_BOUNDARY`], and the indexes of the
+//! first and last chunk stitched are recorded in the result's metadata.
+
+use std::ops::RangeInclusive;
+use zero_latency_core::Uuid;
+
+use crate::models::SearchResult;
+
+/// Largest context window a search may ask for
+pub const MAX_CONTEXT_WINDOW: usize = 5;
+
+/// Separator between stitched chunks
+pub const CHUNK_BOUNDARY: &str = "\n\n[...]\n\n";
+
+/// Metadata key of the index of the first chunk in a stitched result
+pub const CONTEXT_START_KEY: &str = "context_chunk_start";
+
+/// Metadata key of the index of the last chunk in a stitched result
+pub const CONTEXT_END_KEY: &str = "context_chunk_end";
+
+/// Document and chunk index of a result, if it is a chunk of an indexed
+/// document
+pub fn chunk_position(result: &SearchResult) -> Option<(Uuid, usize)> {
+    let document_id = result.custom_metadata.get("parent_document_id")?;
+    let chunk_index = result.custom_metadata.get("chunk_index")?;
+    Some((
+        Uuid::parse_str(document_id).ok()?,
+        chunk_index.parse().ok()?,
+    ))
+}
+
+/// Chunk indexes a window of `window` chunks spans around a hit
+pub fn context_range(chunk_index: usize, window: usize) -> RangeInclusive<usize> {
+    chunk_index.saturating_sub(window)..=chunk_index.saturating_add(window)
+}
+
+/// Join `chunks`, the hit's neighbors in chunk order, around its content
+///
+/// The hit keeps its own content even if `chunks` includes it. A result
+/// without neighbors is left as it is.
+pub fn stitch_context(result: &mut SearchResult, chunk_index: usize, chunks: &[(usize, String)]) {
+    let before: Vec<&(usize, String)> = chunks
+        .iter()
+        .filter(|(index, _)| *index < chunk_index)
+        .collect();
+    let after: Vec<&(usize, String)> = chunks
+        .iter()
+        .filter(|(index, _)| *index > chunk_index)
+        .collect();
+    if before.is_empty() && after.is_empty() {
+        return;
+    }
+
+    let start = before.first().map_or(chunk_index, |(index, _)| *index);
+    let end = after.last().map_or(chunk_index, |(index, _)| *index);
+    let mut parts: Vec<&str> = before.iter().map(|(_, content)| content.as_str()).collect();
+    parts.push(&result.content);
+    parts.extend(after.iter().map(|(_, content)| content.as_str()));
+    result.content = parts.join(CHUNK_BOUNDARY);
+    result
+        .custom_metadata
+        .insert(CONTEXT_START_KEY.to_string(), start.to_string());
+    result
+        .custom_metadata
+        .insert(CONTEXT_END_KEY.to_string(), end.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fusion::{FromSignals, NormalizationMethod, ScoreBreakdown};
+    use zero_latency_core::DocId;
+
+    fn chunk_hit(document_id: Uuid, chunk_index: usize, content: &str) -> SearchResult {
+        let scores = ScoreBreakdown {
+            bm25_raw: None,
+            vector_raw: Some(0.8),
+            bm25_normalized: None,
+            vector_normalized: Some(0.8),
+            fused: 0.8,
+            normalization_method: NormalizationMethod::MinMax,
+        };
+        let mut result = SearchResult::new(
+            DocId::new("docs", "guide.md", 1),
+            "guide.md".to_string(),
+            "Guide".to_string(),
+            content.to_string(),
+            scores,
+            FromSignals::vector_only(),
+        );
+        result
+            .custom_metadata
+            .insert("parent_document_id".to_string(), document_id.to_string());
+        result
+            .custom_metadata
+            .insert("chunk_index".to_string(), chunk_index.to_string());
+        result
+    }
+
+    #[test]
+    fn test_neighbors_are_stitched_around_the_hit() {
+        let document_id = Uuid::new_v4();
+        let mut result = chunk_hit(document_id, 0, "Hit.");
+        assert_eq!(chunk_position(&result), Some((document_id, 0)));
+        assert_eq!(context_range(0, 2), 0..=2);
+        assert_eq!(context_range(4, 2), 2..=6);
+
+        // The first chunk has no predecessors, and a missing neighbor is
+        // skipped
+        let chunks = vec![
+            (0, "Stale hit.".to_string()),
+            (1, "After.".to_string()),
+            (2, "Further.".to_string()),
+        ];
+        stitch_context(&mut result, 0, &chunks);
+        assert_eq!(
+            result.content,
+            "Hit.\n\n[...]\n\nAfter.\n\n[...]\n\nFurther."
+        );
+        assert_eq!(result.custom_metadata[CONTEXT_START_KEY], "0");
+        assert_eq!(result.custom_metadata[CONTEXT_END_KEY], "2");
+
+        let mut middle = chunk_hit(document_id, 3, "Hit.");
+        stitch_context(&mut middle, 3, &[(2, "Before.".to_string())]);
+        assert_eq!(middle.content, "Before.\n\n[...]\n\nHit.");
+        assert_eq!(middle.custom_metadata[CONTEXT_END_KEY], "3");
+
+        // A chunk alone in its document is left untouched
+        let mut alone = chunk_hit(document_id, 0, "Hit.");
+        stitch_context(&mut alone, 0, &[(0, "Hit.".to_string())]);
+        assert_eq!(alone.content, "Hit.");
+        assert!(!alone.custom_metadata.contains_key(CONTEXT_START_KEY));
+
+        let mut untracked = chunk_hit(document_id, 0, "Hit.");
+        untracked.custom_metadata.clear();
+        assert_eq!(chunk_position(&untracked), None);
+    }
+}
@@ -0,0 +1,205 @@
+//! Confidence of a search response as a whole
+//!
+//! Retrieval-augmented clients need to know when to say "I don't know"
+//! rather than answer from weak matches. [`assess_confidence`] rates a
+//! response from three signals:
+//!
+//! - the score distribution: how high the top score is and how clearly it
+//!   stands out from the results below it,
+//! - lexical overlap: how many of the query's content words the top results
+//!   actually contain,
+//! - agreement: whether the results below the top one mention the same query
+//!   words, rather than each matching a different part of the query.
+//!
+//! The signals are combined into a score and a `high`, `medium` or `low`
+//! label. A response is answerable when its confidence is not low and the
+//! top results cover most of the query.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::answer::content_terms;
+use crate::models::SearchResult;
+use crate::query_parser::ParsedQuery;
+
+/// Results whose scores, overlap and agreement are considered
+const CONSIDERED_RESULTS: usize = 5;
+
+/// Combined score at or above which confidence is high
+const HIGH_CONFIDENCE: f32 = 0.7;
+
+/// Combined score at or above which confidence is medium
+const MEDIUM_CONFIDENCE: f32 = 0.45;
+
+/// Share of the query's content words the top results must contain for the
+/// response to be answerable
+const ANSWERABLE_OVERLAP: f32 = 0.5;
+
+/// Overall confidence label of a response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfidenceLevel {
+    High,
+    Medium,
+    Low,
+}
+
+/// Signals a confidence assessment is derived from, each between 0 and 1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceSignals {
+    /// Final score of the top result
+    pub top_score: f32,
+    /// How far the top score stands above the mean of the next results
+    pub score_margin: f32,
+    /// Share of the query's content words found in the best of the top results
+    pub lexical_overlap: f32,
+    /// Share of the top result's matched query words the next results also contain
+    pub agreement: f32,
+}
+
+/// Confidence of a response, for clients deciding whether to answer from it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceAssessment {
+    pub level: ConfidenceLevel,
+    /// Whether the results likely contain an answer to the query
+    pub answerable: bool,
+    /// Weighted combination of the signals
+    pub score: f32,
+    pub signals: ConfidenceSignals,
+}
+
+/// Rate how much a response's results can be trusted to answer `query`
+pub fn assess_confidence(query: &str, results: &[SearchResult]) -> ConfidenceAssessment {
+    let Some(top) = results.first() else {
+        return ConfidenceAssessment {
+            level: ConfidenceLevel::Low,
+            answerable: false,
+            score: 0.0,
+            signals: ConfidenceSignals::default(),
+        };
+    };
+    let considered = &results[..results.len().min(CONSIDERED_RESULTS)];
+
+    let top_score = top.final_score.value();
+    let rest = &considered[1..];
+    let score_margin = if rest.is_empty() || top_score <= 0.0 {
+        // A lone result stands out by default
+        1.0
+    } else {
+        let mean = rest.iter().map(|r| r.final_score.value()).sum::<f32>() / rest.len() as f32;
+        ((top_score - mean) / top_score).clamp(0.0, 1.0)
+    };
+
+    let parsed = ParsedQuery::parse(query);
+    let query_terms: HashSet<String> = content_terms(&parsed.semantic_text(query)).collect();
+    let (lexical_overlap, agreement) = if query_terms.is_empty() {
+        // Nothing to match against, so neither signal argues either way
+        (0.5, 0.5)
+    } else {
+        let matched: Vec<HashSet<&String>> = considered
+            .iter()
+            .map(|result| {
+                let text = format!("{} {}", result.title, result.content);
+                let terms: HashSet<String> = content_terms(&text).collect();
+                query_terms
+                    .iter()
+                    .filter(|term| terms.contains(*term))
+                    .collect()
+            })
+            .collect();
+        let best = matched.iter().map(HashSet::len).max().unwrap_or(0);
+        let overlap = best as f32 / query_terms.len() as f32;
+
+        let top_matched = &matched[0];
+        let agreement = if matched.len() < 2 || top_matched.is_empty() {
+            0.0
+        } else {
+            let shared: usize = matched[1..]
+                .iter()
+                .map(|terms| terms.intersection(top_matched).count())
+                .sum();
+            shared as f32 / ((matched.len() - 1) * top_matched.len()) as f32
+        };
+        (overlap, agreement)
+    };
+
+    let signals = ConfidenceSignals {
+        top_score,
+        score_margin,
+        lexical_overlap,
+        agreement,
+    };
+    let score = 0.4 * top_score + 0.15 * score_margin + 0.3 * lexical_overlap + 0.15 * agreement;
+    let level = if score >= HIGH_CONFIDENCE {
+        ConfidenceLevel::High
+    } else if score >= MEDIUM_CONFIDENCE {
+        ConfidenceLevel::Medium
+    } else {
+        ConfidenceLevel::Low
+    };
+
+    ConfidenceAssessment {
+        level,
+        answerable: level != ConfidenceLevel::Low && lexical_overlap >= ANSWERABLE_OVERLAP,
+        score,
+        signals,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fusion::{FromSignals, NormalizationMethod, ScoreBreakdown};
+    use zero_latency_core::DocId;
+
+    fn result(content: &str, score: f32) -> SearchResult {
+        let scores = ScoreBreakdown {
+            bm25_raw: None,
+            vector_raw: Some(score),
+            bm25_normalized: None,
+            vector_normalized: Some(score),
+            fused: score,
+            normalization_method: NormalizationMethod::MinMax,
+        };
+        SearchResult::new(
+            DocId::new("docs", "guide.md", 1),
+            "guide.md".to_string(),
+            "Guide".to_string(),
+            content.to_string(),
+            scores,
+            FromSignals::vector_only(),
+        )
+    }
+
+    #[test]
+    fn test_confidence_levels() {
+        let query = "how do I configure the server port?";
+
+        let strong = assess_confidence(
+            query,
+            &[
+                result("The server port is configured with DOC_INDEXER_PORT.", 0.92),
+                result("Configure the server port before starting it.", 0.55),
+                result("Server settings include the port and host.", 0.5),
+            ],
+        );
+        assert_eq!(strong.level, ConfidenceLevel::High);
+        assert!(strong.answerable);
+        assert_eq!(strong.signals.lexical_overlap, 1.0);
+
+        let weak = assess_confidence(
+            query,
+            &[
+                result("Meeting notes from the planning session.", 0.31),
+                result("Release checklist for the desktop app.", 0.3),
+            ],
+        );
+        assert_eq!(weak.level, ConfidenceLevel::Low);
+        assert!(!weak.answerable);
+        assert_eq!(weak.signals.lexical_overlap, 0.0);
+
+        let empty = assess_confidence(query, &[]);
+        assert_eq!(empty.level, ConfidenceLevel::Low);
+        assert!(!empty.answerable);
+    }
+}
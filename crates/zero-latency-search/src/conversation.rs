@@ -0,0 +1,288 @@
+//! Conversation context for multi-turn search
+//!
+//! Chat-style clients search in turns: "collection management" followed by
+//! "how do I delete one?". On its own the second query has lost its topic.
+//! [`ConversationContext`] remembers the topic of recent turns and the
+//! results the user opened, so a follow-up can be searched as "how do I
+//! delete one? collection management" and documents the user already chose
+//! rank a little higher.
+//!
+//! A query is a follow-up when it refers back with a pronoun ("one", "it",
+//! "those") or has too few content words to stand alone. Follow-ups inherit
+//! the topic of the turn they follow; any other query starts a new topic.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use uuid::Uuid;
+use zero_latency_core::values::Score;
+
+use crate::answer::{content_terms, stem, QUESTION_WORDS, STOP_WORDS};
+use crate::models::SearchResult;
+
+/// Words that refer back to an earlier turn
+const ANAPHORA: &[&str] = &[
+    "it", "its", "one", "ones", "that", "this", "those", "these", "them", "they", "same",
+    "another", "else", "other", "others",
+];
+
+/// Most topic words appended to a follow-up
+const MAX_CONTEXT_WORDS: usize = 4;
+
+/// Most documents remembered as selected
+const MAX_SELECTED: usize = 20;
+
+/// Score multiplier for documents selected earlier in the conversation
+pub const SELECTION_BOOST: f32 = 1.2;
+
+/// One search in a conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationTurn {
+    /// Query as the user sent it
+    pub query: String,
+    /// Query that was searched, when context was added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contextualized_query: Option<String>,
+    /// Topic words carried into follow-ups
+    pub topic: Vec<String>,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Recent turns and selections of one conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationContext {
+    turns: VecDeque<ConversationTurn>,
+    selected: VecDeque<Uuid>,
+    max_turns: usize,
+}
+
+impl Default for ConversationContext {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl ConversationContext {
+    /// Context remembering the last `max_turns` queries
+    pub fn new(max_turns: usize) -> Self {
+        Self {
+            turns: VecDeque::new(),
+            selected: VecDeque::new(),
+            max_turns: max_turns.max(1),
+        }
+    }
+
+    /// Recent turns, oldest first
+    pub fn turns(&self) -> impl Iterator<Item = &ConversationTurn> {
+        self.turns.iter()
+    }
+
+    /// Documents the user opened, most recent first
+    pub fn selected(&self) -> impl Iterator<Item = &Uuid> {
+        self.selected.iter()
+    }
+
+    /// The query to search for `query`: unchanged unless it is a follow-up,
+    /// in which case the current topic's words are appended
+    pub fn contextualize(&self, query: &str) -> Option<String> {
+        let topic = &self.turns.back()?.topic;
+        if topic.is_empty() || !is_follow_up(query) {
+            return None;
+        }
+        let present: HashSet<String> = content_terms(query).collect();
+        let missing: Vec<&str> = topic
+            .iter()
+            .filter(|word| !present.contains(&stem(word)))
+            .take(MAX_CONTEXT_WORDS)
+            .map(String::as_str)
+            .collect();
+        if missing.is_empty() {
+            return None;
+        }
+        Some(format!("{} {}", query.trim(), missing.join(" ")))
+    }
+
+    /// Record a searched query and return the turn
+    pub fn record_query(
+        &mut self,
+        query: &str,
+        contextualized_query: Option<String>,
+    ) -> &ConversationTurn {
+        let own_topic = topic_words(query);
+        let topic = match self.turns.back() {
+            Some(previous) if is_follow_up(query) => {
+                // Keep the inherited topic and add whatever the follow-up names
+                let mut topic = previous.topic.clone();
+                for word in own_topic {
+                    if !topic.contains(&word) {
+                        topic.push(word);
+                    }
+                }
+                topic
+            }
+            _ => own_topic,
+        };
+
+        if self.turns.len() == self.max_turns {
+            self.turns.pop_front();
+        }
+        self.turns.push_back(ConversationTurn {
+            query: query.to_string(),
+            contextualized_query,
+            topic,
+            at: chrono::Utc::now(),
+        });
+        self.turns.back().expect("turn was just pushed")
+    }
+
+    /// Record that the user opened a document
+    pub fn record_selection(&mut self, document_id: Uuid) {
+        self.selected.retain(|selected| *selected != document_id);
+        self.selected.push_front(document_id);
+        self.selected.truncate(MAX_SELECTED);
+    }
+
+    /// Boost documents selected earlier and re-sort; returns how many
+    /// results were boosted
+    pub fn rerank(&self, results: &mut [SearchResult]) -> usize {
+        let mut boosted = 0;
+        for result in results.iter_mut() {
+            if self.selected.contains(&result.document_id) {
+                let score = (result.final_score.value() * SELECTION_BOOST).min(1.0);
+                result.final_score = Score::new(score).unwrap_or(result.final_score);
+                boosted += 1;
+            }
+        }
+        if boosted > 0 {
+            results.sort_by(|a, b| b.final_score.value().total_cmp(&a.final_score.value()));
+        }
+        boosted
+    }
+}
+
+/// Whether a query leans on an earlier turn
+pub fn is_follow_up(query: &str) -> bool {
+    let words: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    words.iter().any(|word| ANAPHORA.contains(&word.as_str())) || topic_words(query).len() < 2
+}
+
+/// Lowercased content words of a query, in order, without repeats
+fn topic_words(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in text
+        .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+        .map(|word| word.trim_matches('-').to_lowercase())
+    {
+        if word.len() < 2
+            || STOP_WORDS.contains(&word.as_str())
+            || QUESTION_WORDS.contains(&word.as_str())
+            || ANAPHORA.contains(&word.as_str())
+            || words.contains(&word)
+        {
+            continue;
+        }
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fusion::{FromSignals, NormalizationMethod, ScoreBreakdown};
+    use zero_latency_core::DocId;
+
+    fn result(name: &str, score: f32) -> SearchResult {
+        let mut result = SearchResult::new(
+            DocId::new("default", name, 1),
+            format!("docs/{}.md", name),
+            name.to_string(),
+            String::new(),
+            ScoreBreakdown {
+                bm25_raw: None,
+                vector_raw: Some(score),
+                bm25_normalized: None,
+                vector_normalized: Some(score),
+                fused: score,
+                normalization_method: NormalizationMethod::MinMax,
+            },
+            FromSignals::vector_only(),
+        );
+        result.document_id = Uuid::new_v4();
+        result.final_score = Score::new(score).unwrap();
+        result
+    }
+
+    #[test]
+    fn test_is_follow_up() {
+        assert!(is_follow_up("how do I delete one?"));
+        assert!(is_follow_up("and the defaults?"));
+        assert!(is_follow_up("what about it"));
+        assert!(!is_follow_up("collection management"));
+        assert!(!is_follow_up("rotate api keys"));
+    }
+
+    #[test]
+    fn test_follow_up_inherits_topic() {
+        let mut context = ConversationContext::default();
+        assert_eq!(context.contextualize("how do I delete one?"), None);
+
+        context.record_query("collection management", None);
+        let query = context.contextualize("how do I delete one?");
+        assert_eq!(
+            query.as_deref(),
+            Some("how do I delete one? collection management")
+        );
+
+        // The topic carries through a chain of follow-ups
+        let turn = context.record_query("how do I delete one?", query);
+        assert_eq!(turn.topic, vec!["collection", "management", "delete"]);
+        assert_eq!(
+            context.contextualize("can I restore it?").as_deref(),
+            Some("can I restore it? collection management delete")
+        );
+
+        // Words the follow-up already has aren't repeated
+        assert_eq!(
+            context.contextualize("delete those in collection management?"),
+            None
+        );
+
+        // A standalone query starts a new topic
+        context.record_query("embedding model migration", None);
+        assert_eq!(
+            context.contextualize("is it slow?").as_deref(),
+            Some("is it slow? embedding model migration")
+        );
+    }
+
+    #[test]
+    fn test_turns_are_bounded() {
+        let mut context = ConversationContext::new(2);
+        context.record_query("first topic here", None);
+        context.record_query("second topic here", None);
+        context.record_query("third topic here", None);
+        let queries: Vec<&str> = context.turns().map(|turn| turn.query.as_str()).collect();
+        assert_eq!(queries, vec!["second topic here", "third topic here"]);
+    }
+
+    #[test]
+    fn test_selected_documents_rank_higher() {
+        let mut results = vec![result("a", 0.8), result("b", 0.75), result("c", 0.5)];
+        let selected = results[1].document_id;
+
+        let mut context = ConversationContext::default();
+        assert_eq!(context.rerank(&mut results), 0);
+
+        context.record_selection(selected);
+        context.record_selection(selected);
+        assert_eq!(context.selected().count(), 1);
+        assert_eq!(context.rerank(&mut results), 1);
+        assert_eq!(results[0].document_id, selected);
+        assert!((results[0].final_score.value() - 0.9).abs() < 1e-6);
+    }
+}
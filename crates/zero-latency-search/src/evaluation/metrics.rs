@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use zero_latency_core::{DocId, Result};
 
 use crate::evaluation::dataset::{EvaluationDataset, RelevanceRating};
-use crate::models::SearchResult;
+use crate::models::{SearchResult, SearchSettings};
 
 /// Individual search quality metrics for a single query
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +56,10 @@ pub struct EvaluationReport {
     pub timestamp: String,
     /// Model/system being evaluated
     pub system_name: String,
+    /// Ranking profile the results were produced with, including the lexical
+    /// field weights, so runs with different profiles can be compared
+    #[serde(default)]
+    pub ranking: SearchSettings,
     /// Per-query metrics
     pub query_metrics: Vec<QueryMetrics>,
     /// Aggregated metrics
@@ -461,6 +465,8 @@ mod tests {
                 from_signals: FromSignals::default(),
                 ranking_signals: None,
                 url: None,
+                lines: None,
+                anchor: None,
                 collection: None,
                 custom_metadata: std::collections::HashMap::new(),
             },
@@ -487,6 +493,8 @@ mod tests {
                 from_signals: FromSignals::default(),
                 ranking_signals: None,
                 url: None,
+                lines: None,
+                anchor: None,
                 collection: None,
                 custom_metadata: std::collections::HashMap::new(),
             },
@@ -513,6 +521,8 @@ mod tests {
                 from_signals: FromSignals::default(),
                 ranking_signals: None,
                 url: None,
+                lines: None,
+                anchor: None,
                 collection: None,
                 custom_metadata: std::collections::HashMap::new(),
             },
@@ -539,6 +549,8 @@ mod tests {
                 from_signals: FromSignals::default(),
                 ranking_signals: None,
                 url: None,
+                lines: None,
+                anchor: None,
                 collection: None,
                 custom_metadata: std::collections::HashMap::new(),
             },
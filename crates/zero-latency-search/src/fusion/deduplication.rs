@@ -1,7 +1,11 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashSet;
 use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use crate::fusion::AlternateSource;
 use crate::models::SearchResult;
 use zero_latency_core::error::ZeroLatencyError;
+use zero_latency_vector::{MetadataFilter, PATH_KEY};
 
 /// Configuration for result deduplication behavior
 #[derive(Debug, Clone)]
@@ -16,6 +20,9 @@ pub struct DeduplicationConfig {
     pub content_similarity_enabled: bool,
     /// Minimum content length to apply similarity detection
     pub min_content_length: usize,
+    /// Sources to keep when near-identical results are collapsed, most
+    /// preferred first: collection names or `path` patterns
+    pub canonical_sources: Vec<String>,
 }
 
 impl Default for DeduplicationConfig {
@@ -26,6 +33,7 @@ impl Default for DeduplicationConfig {
             max_results: 1000,
             content_similarity_enabled: true,
             min_content_length: 50,
+            canonical_sources: Vec::new(),
         }
     }
 }
@@ -131,6 +139,102 @@ impl ResultDeduplicator {
         Ok((deduplicated, metrics))
     }
 
+    /// Collapse near-identical results from different sources into one
+    ///
+    /// Results whose content overlaps by at least `similarity_threshold`
+    /// (Jaccard similarity of word trigrams) but that come from different
+    /// URIs are treated as mirrors of each other, such as a README in a
+    /// repository and the same page on a docs site. Each group keeps the
+    /// member from the most preferred canonical source, or its best-ranked
+    /// member when no canonical source matches. The kept result takes the
+    /// group's place and best score, and the others are listed in its
+    /// `from_signals.alternates`. Pinned results are never collapsed.
+    /// Returns how many results were collapsed.
+    pub fn collapse_mirrors(&self, results: &mut Vec<SearchResult>) -> usize {
+        if !self.config.content_similarity_enabled || results.len() < 2 {
+            return 0;
+        }
+
+        // Groups of indices into `results`, in the order of their best member
+        let mut groups: Vec<(HashSet<u64>, Vec<usize>)> = Vec::new();
+        let mut ungrouped = Vec::new();
+        for (index, result) in results.iter().enumerate() {
+            if result.content.len() < self.config.min_content_length
+                || result.from_signals.pinned().is_some()
+            {
+                ungrouped.push(index);
+                continue;
+            }
+            let shingles = shingles(&result.content);
+            let mirror = groups.iter_mut().find(|(group_shingles, members)| {
+                members.iter().all(|&member| results[member].uri != result.uri)
+                    && jaccard(group_shingles, &shingles) >= self.config.similarity_threshold
+            });
+            match mirror {
+                Some((_, members)) => members.push(index),
+                None => groups.push((shingles, vec![index])),
+            }
+        }
+        let collapsed: usize = groups.iter().map(|(_, members)| members.len() - 1).sum();
+        if collapsed == 0 {
+            return 0;
+        }
+
+        let canonical = self.canonical_filters();
+        let mut slots: Vec<Option<SearchResult>> = std::mem::take(results).into_iter().map(Some).collect();
+        // Position of each kept result, and the result itself
+        let mut kept: Vec<(usize, SearchResult)> = ungrouped
+            .into_iter()
+            .filter_map(|index| slots[index].take().map(|result| (index, result)))
+            .collect();
+        for (_, members) in groups {
+            let position = members[0];
+            let primary = members
+                .iter()
+                .copied()
+                .min_by_key(|&member| {
+                    slots[member]
+                        .as_ref()
+                        .map(|result| canonical_rank(&canonical, result))
+                        .unwrap_or(usize::MAX)
+                })
+                .unwrap_or(position);
+            let Some(mut result) = slots[primary].take() else {
+                continue;
+            };
+            for member in members {
+                let Some(alternate) = slots[member].take() else {
+                    continue;
+                };
+                if alternate.final_score.value() > result.final_score.value() {
+                    result.final_score = alternate.final_score;
+                }
+                result.from_signals.merge(&alternate.from_signals);
+                result.from_signals.alternates.push(AlternateSource {
+                    uri: alternate.uri.clone(),
+                    collection: alternate.collection.clone(),
+                    document_id: alternate.document_id.to_string(),
+                    score: alternate.final_score.value(),
+                });
+            }
+            kept.push((position, result));
+        }
+
+        kept.sort_by_key(|(position, _)| *position);
+        *results = kept.into_iter().map(|(_, result)| result).collect();
+        collapsed
+    }
+
+    /// Canonical source patterns compiled as `path` filters, skipping malformed ones
+    fn canonical_filters(&self) -> Vec<(String, Option<MetadataFilter>)> {
+        let key = PATH_KEY.to_string();
+        self.config
+            .canonical_sources
+            .iter()
+            .map(|source| (source.clone(), MetadataFilter::compile([(&key, source)]).ok()))
+            .collect()
+    }
+
     /// Get deduplication key for a result
     fn get_deduplication_key(&self, result: &SearchResult) -> String {
         // Primary deduplication by document ID
@@ -169,6 +273,52 @@ impl ResultDeduplicator {
     }
 }
 
+/// Index of the first canonical source a result comes from, or the number
+/// of sources when it matches none
+fn canonical_rank(canonical: &[(String, Option<MetadataFilter>)], result: &SearchResult) -> usize {
+    let collection = result.collection.as_deref().unwrap_or(&result.doc_id.collection);
+    canonical
+        .iter()
+        .position(|(source, filter)| {
+            source == collection || filter.as_ref().is_some_and(|filter| result.matches_metadata(filter))
+        })
+        .unwrap_or(canonical.len())
+}
+
+/// Content overlap of two texts (0.0-1.0), the Jaccard similarity of their
+/// word trigrams
+///
+/// This is the measure `collapse_mirrors` compares results with.
+pub fn content_similarity(a: &str, b: &str) -> f64 {
+    jaccard(&shingles(a), &shingles(b))
+}
+
+/// Hashes of the word trigrams of a text, or of its words when it is shorter
+fn shingles(text: &str) -> HashSet<u64> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let hash = |words: &[String]| {
+        let mut hasher = DefaultHasher::new();
+        words.hash(&mut hasher);
+        hasher.finish()
+    };
+    if words.len() < 3 {
+        return words.chunks(1).map(hash).collect();
+    }
+    words.windows(3).map(hash).collect()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,6 +363,53 @@ mod tests {
         assert_eq!(metrics.duplicates_found, 1);
     }
 
+    #[test]
+    fn test_collapse_mirrors_prefers_canonical_source() {
+        let readme = "Install the CLI with cargo install mdx, then run mdx init in the \
+                      repository root to create a project file.";
+        let mirror = |uri: &str, collection: &str, content: &str, score: f32| {
+            let mut result = create_test_result(uri, score);
+            result.uri = uri.to_string();
+            result.content = content.to_string();
+            result.collection = Some(collection.to_string());
+            result.final_score = Score::new(score).unwrap();
+            result
+        };
+        let mut results = vec![
+            mirror("repo/README.md", "repo", readme, 0.9),
+            mirror("guides/other.md", "site", "Something else entirely, about configuring search settings for a collection.", 0.8),
+            mirror("site/getting-started.md", "site", &format!("{} ", readme), 0.7),
+        ];
+
+        let deduplicator = ResultDeduplicator::new(
+            DeduplicationConfig {
+                canonical_sources: vec!["site/**".to_string()],
+                ..Default::default()
+            },
+            DuplicationStrategy::MergeWithProvenance,
+        );
+        assert_eq!(deduplicator.collapse_mirrors(&mut results), 1);
+
+        // The canonical copy takes the README's place and score
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].uri, "site/getting-started.md");
+        assert_eq!(results[0].final_score.value(), 0.9);
+        let alternates = &results[0].from_signals.alternates;
+        assert_eq!(alternates.len(), 1);
+        assert_eq!(alternates[0].uri, "repo/README.md");
+        assert_eq!(alternates[0].collection.as_deref(), Some("repo"));
+        assert!(results[1].from_signals.alternates.is_empty());
+
+        // Without a canonical source the best-ranked copy is kept
+        let mut results = vec![
+            mirror("repo/README.md", "repo", readme, 0.9),
+            mirror("site/getting-started.md", "site", readme, 0.7),
+        ];
+        ResultDeduplicator::with_default().collapse_mirrors(&mut results);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].uri, "repo/README.md");
+    }
+
     #[test]
     fn test_stable_ranking() {
         let deduplicator = ResultDeduplicator::with_default();
@@ -0,0 +1,148 @@
+use crate::models::SearchResult;
+use zero_latency_core::values::Score;
+
+/// Ranked results from one collection of a federated search
+#[derive(Debug, Clone)]
+pub struct CollectionResults {
+    pub collection: String,
+    pub results: Vec<SearchResult>,
+}
+
+/// Merge the results of searching several collections into one ranking
+///
+/// Score scales differ between collections (BM25 statistics are per
+/// collection), so each collection's final scores are divided by its best
+/// score before merging. The best match of every collection therefore scores
+/// 1.0 and the rest keep their relative distance to it. The score breakdown
+/// is left untouched so the per-collection scores remain visible.
+///
+/// Every result is tagged with the collection it came from. Ties are broken
+/// by position within the collection and then by collection order. Results
+/// a curation rule pinned stay ahead of the rest, in their pinned order.
+pub fn federate_results(sets: Vec<CollectionResults>, limit: usize) -> Vec<SearchResult> {
+    let mut merged: Vec<(usize, usize, SearchResult)> = Vec::new();
+
+    for (set_index, set) in sets.into_iter().enumerate() {
+        let best = set
+            .results
+            .iter()
+            .map(|result| result.final_score.value())
+            .fold(0.0_f32, f32::max);
+
+        for (rank, mut result) in set.results.into_iter().enumerate() {
+            if best > 0.0 {
+                let normalized = (result.final_score.value() / best).min(1.0);
+                result.final_score = Score::new(normalized).unwrap_or_else(|_| Score::zero());
+            }
+            result.doc_id.collection = set.collection.clone();
+            result.collection = Some(set.collection.clone());
+            merged.push((set_index, rank, result));
+        }
+    }
+
+    merged.sort_by(|(a_set, a_rank, a), (b_set, b_rank, b)| {
+        let pin = |result: &SearchResult| result.from_signals.pinned().unwrap_or(usize::MAX);
+        pin(a)
+            .cmp(&pin(b))
+            .then(b.final_score.value().total_cmp(&a.final_score.value()))
+            .then(a_rank.cmp(b_rank))
+            .then(a_set.cmp(b_set))
+    });
+
+    merged
+        .into_iter()
+        .take(limit)
+        .map(|(_, _, result)| result)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fusion::{FromSignals, NormalizationMethod, ScoreBreakdown};
+    use zero_latency_core::DocId;
+
+    fn result(id: &str, score: f32) -> SearchResult {
+        SearchResult::new(
+            DocId::new("default", id, 1),
+            id.to_string(),
+            id.to_string(),
+            String::new(),
+            ScoreBreakdown {
+                bm25_raw: None,
+                vector_raw: Some(score),
+                bm25_normalized: None,
+                vector_normalized: Some(score),
+                fused: score,
+                normalization_method: NormalizationMethod::MinMax,
+            },
+            FromSignals::vector_only(),
+        )
+    }
+
+    fn set(collection: &str, results: Vec<SearchResult>) -> CollectionResults {
+        CollectionResults {
+            collection: collection.to_string(),
+            results,
+        }
+    }
+
+    #[test]
+    fn test_scores_are_normalized_per_collection() {
+        let merged = federate_results(
+            vec![
+                set("api", vec![result("a1", 0.8), result("a2", 0.2)]),
+                set("guides", vec![result("g1", 0.5), result("g2", 0.375)]),
+            ],
+            10,
+        );
+
+        let ranking: Vec<(&str, f32)> = merged
+            .iter()
+            .map(|r| (r.title.as_str(), r.final_score.value()))
+            .collect();
+        assert_eq!(
+            ranking,
+            vec![("a1", 1.0), ("g1", 1.0), ("g2", 0.75), ("a2", 0.25)]
+        );
+
+        // The breakdown keeps the collection's own score
+        assert_eq!(merged[1].scores.fused, 0.5);
+    }
+
+    #[test]
+    fn test_results_are_tagged_and_limited() {
+        let merged = federate_results(
+            vec![
+                set("api", vec![result("a1", 0.9), result("a2", 0.5)]),
+                set("guides", vec![result("g1", 0.6)]),
+                set("empty", Vec::new()),
+            ],
+            2,
+        );
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].collection.as_deref(), Some("api"));
+        assert_eq!(merged[1].collection.as_deref(), Some("guides"));
+        assert_eq!(merged[1].doc_id.collection, "guides");
+    }
+
+    #[test]
+    fn test_pinned_results_stay_first() {
+        let mut pinned = result("g2", 0.1);
+        pinned.from_signals.curation = Some(crate::fusion::CurationMark {
+            pinned: Some(0),
+            ..Default::default()
+        });
+        let merged = federate_results(
+            vec![
+                set("api", vec![result("a1", 0.9)]),
+                set("guides", vec![result("g1", 0.6), pinned]),
+            ],
+            2,
+        );
+
+        let titles: Vec<&str> = merged.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["g2", "a1"]);
+    }
+}
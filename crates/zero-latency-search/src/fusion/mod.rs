@@ -2,8 +2,12 @@ pub mod provenance;
 pub mod score_fusion;
 pub mod deduplication;
 pub mod result_merger;
+pub mod federation;
+pub mod vector_spaces;
 
 pub use provenance::*;
 pub use score_fusion::*;
 pub use deduplication::*;
 pub use result_merger::*;
+pub use federation::*;
+pub use vector_spaces::*;
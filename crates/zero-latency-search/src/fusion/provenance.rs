@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+
+/// Query variant indices, stored inline since results rarely match more than a few variants
+pub type VariantIndices = SmallVec<[usize; 4]>;
 
 /// Engine that provided this result
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,9 +20,47 @@ pub struct FromSignals {
     /// Result found by vector search
     pub vector: bool,
     /// Query variant indices that found this result (for multi-query expansion)
-    pub variants: Vec<usize>,
+    pub variants: VariantIndices,
     /// Result enhanced by query expansion
     pub query_expansion: bool,
+    /// Curation applied to the result after fusion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curation: Option<CurationMark>,
+    /// Near-identical copies of this result found in other sources
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<AlternateSource>,
+    /// Final score before calibration mapped it onto the collection's
+    /// background distribution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uncalibrated_score: Option<f32>,
+    /// MaxSim of the chunk's token vectors against the query's, when late
+    /// interaction reranked the result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub late_interaction_score: Option<f32>,
+}
+
+/// Another location of a result's content, collapsed into it by deduplication
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlternateSource {
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    pub document_id: String,
+    /// Final score the copy had before it was collapsed
+    pub score: f32,
+}
+
+/// How curation rules changed a result's placement
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CurationMark {
+    /// Position among the results pinned for the query
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<usize>,
+    /// Factor the final score was multiplied by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boost: Option<f32>,
+    /// Ids of the rules that applied
+    pub rules: Vec<String>,
 }
 
 impl FromSignals {
@@ -27,8 +69,12 @@ impl FromSignals {
         Self {
             bm25: false,
             vector: true,
-            variants: vec![0], // Original query
+            variants: smallvec![0], // Original query
             query_expansion: false,
+            curation: None,
+            alternates: Vec::new(),
+            uncalibrated_score: None,
+            late_interaction_score: None,
         }
     }
 
@@ -37,8 +83,12 @@ impl FromSignals {
         Self {
             bm25: true,
             vector: false,
-            variants: vec![0], // Original query
+            variants: smallvec![0], // Original query
             query_expansion: false,
+            curation: None,
+            alternates: Vec::new(),
+            uncalibrated_score: None,
+            late_interaction_score: None,
         }
     }
 
@@ -47,8 +97,12 @@ impl FromSignals {
         Self {
             bm25: true,
             vector: true,
-            variants: vec![0], // Original query
+            variants: smallvec![0], // Original query
             query_expansion: false,
+            curation: None,
+            alternates: Vec::new(),
+            uncalibrated_score: None,
+            late_interaction_score: None,
         }
     }    /// Create signals for specific query variant
     pub fn from_variant(variant_index: usize, engine: SearchEngine) -> Self {
@@ -56,20 +110,32 @@ impl FromSignals {
             SearchEngine::Vector => Self {
                 bm25: false,
                 vector: true,
-                variants: vec![variant_index],
+                variants: smallvec![variant_index],
                 query_expansion: false,
+                curation: None,
+                alternates: Vec::new(),
+                uncalibrated_score: None,
+                late_interaction_score: None,
             },
             SearchEngine::BM25 => Self {
                 bm25: true,
                 vector: false,
-                variants: vec![variant_index],
+                variants: smallvec![variant_index],
                 query_expansion: false,
+                curation: None,
+                alternates: Vec::new(),
+                uncalibrated_score: None,
+                late_interaction_score: None,
             },
             SearchEngine::Hybrid => Self {
                 bm25: true,
                 vector: true,
-                variants: vec![variant_index],
+                variants: smallvec![variant_index],
                 query_expansion: false,
+                curation: None,
+                alternates: Vec::new(),
+                uncalibrated_score: None,
+                late_interaction_score: None,
             },
         }
     }
@@ -79,6 +145,14 @@ impl FromSignals {
         self.bm25 |= other.bm25;
         self.vector |= other.vector;
         self.query_expansion |= other.query_expansion;
+        if self.curation.is_none() {
+            self.curation = other.curation.clone();
+        }
+        for alternate in &other.alternates {
+            if !self.alternates.contains(alternate) {
+                self.alternates.push(alternate.clone());
+            }
+        }
         
         // Merge variant indices, keeping unique values
         for &variant in &other.variants {
@@ -98,6 +172,11 @@ impl FromSignals {
             (false, false) => SearchEngine::Vector, // Default fallback
         }
     }
+
+    /// Position among the pinned results, if a curation rule pinned this one
+    pub fn pinned(&self) -> Option<usize> {
+        self.curation.as_ref().and_then(|curation| curation.pinned)
+    }
 }
 
 impl Default for FromSignals {
@@ -115,7 +194,7 @@ mod tests {
         let vector_signals = FromSignals::vector_only();
         assert!(!vector_signals.bm25);
         assert!(vector_signals.vector);
-        assert_eq!(vector_signals.variants, vec![0]);
+        assert_eq!(vector_signals.variants.as_slice(), [0]);
 
         let bm25_signals = FromSignals::bm25_only();
         assert!(bm25_signals.bm25);
@@ -135,14 +214,14 @@ mod tests {
         
         assert!(signals.bm25);
         assert!(signals.vector);
-        assert_eq!(signals.variants, vec![0, 1]);
+        assert_eq!(signals.variants.as_slice(), [0, 1]);
         assert!(matches!(signals.primary_engine(), SearchEngine::Hybrid));
     }
 
     #[test]
     fn test_variant_signals() {
         let signals = FromSignals::from_variant(2, SearchEngine::Vector);
-        assert_eq!(signals.variants, vec![2]);
+        assert_eq!(signals.variants.as_slice(), [2]);
         assert!(signals.vector);
         assert!(!signals.bm25);
     }
@@ -120,6 +120,41 @@ impl FusionConfig {
         self
     }
 
+    /// Set the BM25 and vector weights
+    pub fn with_weights(mut self, weights: FusionWeights) -> Self {
+        self.bm25_weight = weights.bm25_weight;
+        self.vector_weight = weights.vector_weight;
+        self
+    }
+
+    /// The BM25 and vector weights
+    pub fn weights(&self) -> FusionWeights {
+        FusionWeights {
+            bm25_weight: self.bm25_weight,
+            vector_weight: self.vector_weight,
+        }
+    }
+
+    /// Validate weights sum to 1.0 (approximately)
+    pub fn validate(&self) -> Result<(), String> {
+        self.weights().validate()
+    }
+}
+
+/// Relative weights of BM25 and vector scores in fused results
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FusionWeights {
+    pub bm25_weight: f32,
+    pub vector_weight: f32,
+}
+
+impl Default for FusionWeights {
+    fn default() -> Self {
+        FusionConfig::default().weights()
+    }
+}
+
+impl FusionWeights {
     /// Validate weights sum to 1.0 (approximately)
     pub fn validate(&self) -> Result<(), String> {
         let sum = self.bm25_weight + self.vector_weight;
@@ -149,6 +184,11 @@ impl ScoreFusion {
         }
     }
 
+    /// Copy of this engine using different weights, e.g. from a request's options
+    pub fn with_weights(&self, weights: FusionWeights) -> Result<Self, String> {
+        Self::new(self.config.clone().with_weights(weights))
+    }
+
     /// Fuse BM25 and vector scores according to configuration
     pub fn fuse_scores(
         &self,
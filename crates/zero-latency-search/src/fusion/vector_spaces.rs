@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use zero_latency_core::values::Score;
+use zero_latency_vector::SimilarityResult;
+
+/// A named vector space to search and its weight in the fused similarity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VectorSpace {
+    /// Vector name, e.g. `content` or `title`
+    pub name: String,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+impl VectorSpace {
+    pub fn new(name: impl Into<String>, weight: f32) -> Self {
+        Self {
+            name: name.into(),
+            weight,
+        }
+    }
+
+    /// Check that `spaces` can be searched together
+    pub fn validate_all(spaces: &[VectorSpace]) -> Result<(), String> {
+        if spaces.is_empty() {
+            return Err("At least one vector space is required".to_string());
+        }
+        let mut total = 0.0;
+        for (i, space) in spaces.iter().enumerate() {
+            if space.name.trim().is_empty() {
+                return Err("Vector space names must not be empty".to_string());
+            }
+            if spaces[..i].iter().any(|other| other.name == space.name) {
+                return Err(format!("Vector space '{}' is listed twice", space.name));
+            }
+            if !space.weight.is_finite() || space.weight < 0.0 {
+                return Err(format!(
+                    "Weight of vector space '{}' must be finite and non-negative",
+                    space.name
+                ));
+            }
+            total += space.weight;
+        }
+        if total == 0.0 {
+            return Err("At least one vector space weight must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Ranked results from searching one vector space
+#[derive(Debug, Clone)]
+pub struct VectorSpaceResults {
+    pub space: VectorSpace,
+    pub results: Vec<SimilarityResult>,
+}
+
+/// Merge the results of searching several vector spaces into one ranking
+///
+/// A chunk's fused similarity is the weighted mean of its similarity in each
+/// space, counting 0 for spaces it was not found in, so chunks that match in
+/// several spaces rank above chunks that match equally well in one. Ties keep
+/// the order in which chunks were first found.
+pub fn fuse_vector_spaces(sets: Vec<VectorSpaceResults>, limit: usize) -> Vec<SimilarityResult> {
+    let total_weight: f32 = sets.iter().map(|set| set.space.weight).sum();
+    if total_weight <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut fused: Vec<(f32, SimilarityResult)> = Vec::new();
+    let mut positions = HashMap::new();
+    for set in sets {
+        let weight = set.space.weight / total_weight;
+        for result in set.results {
+            let contribution = weight * result.similarity.value();
+            match positions.get(&result.document_id) {
+                Some(&position) => {
+                    let (similarity, _): &mut (f32, SimilarityResult) = &mut fused[position];
+                    *similarity += contribution;
+                }
+                None => {
+                    positions.insert(result.document_id, fused.len());
+                    fused.push((contribution, result));
+                }
+            }
+        }
+    }
+
+    fused.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    fused
+        .into_iter()
+        .take(limit)
+        .map(|(similarity, mut result)| {
+            result.similarity = Score::new(similarity.min(1.0)).unwrap_or_else(|_| Score::zero());
+            result
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zero_latency_core::Uuid;
+    use zero_latency_vector::VectorMetadata;
+
+    fn result(id: Uuid, similarity: f32) -> SimilarityResult {
+        SimilarityResult {
+            document_id: id,
+            similarity: Score::new(similarity).unwrap(),
+            metadata: VectorMetadata::default(),
+        }
+    }
+
+    #[test]
+    fn test_fusion_weights_spaces_and_rewards_agreement() {
+        let (both, content, title) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let sets = vec![
+            VectorSpaceResults {
+                space: VectorSpace::new("content", 3.0),
+                results: vec![result(content, 0.9), result(both, 0.8)],
+            },
+            VectorSpaceResults {
+                space: VectorSpace::new("title", 1.0),
+                results: vec![result(title, 1.0), result(both, 0.8)],
+            },
+        ];
+
+        let fused = fuse_vector_spaces(sets, 10);
+        let ids: Vec<Uuid> = fused.iter().map(|result| result.document_id).collect();
+        assert_eq!(ids, vec![both, content, title]);
+        assert!((fused[0].similarity.value() - 0.8).abs() < 1e-6);
+        assert!((fused[1].similarity.value() - 0.675).abs() < 1e-6);
+        assert!((fused[2].similarity.value() - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_vector_space_validation() {
+        let content = VectorSpace::new("content", 1.0);
+        assert!(
+            VectorSpace::validate_all(&[content.clone(), VectorSpace::new("title", 0.0)]).is_ok()
+        );
+        assert!(VectorSpace::validate_all(&[]).is_err());
+        assert!(VectorSpace::validate_all(&[content.clone(), content.clone()]).is_err());
+        assert!(VectorSpace::validate_all(&[VectorSpace::new("title", -1.0)]).is_err());
+        assert!(VectorSpace::validate_all(&[VectorSpace::new("title", 0.0)]).is_err());
+
+        let parsed: VectorSpace = serde_json::from_str(r#"{"name": "title"}"#).unwrap();
+        assert_eq!(parsed, VectorSpace::new("title", 1.0));
+    }
+}
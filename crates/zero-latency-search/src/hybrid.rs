@@ -1,16 +1,30 @@
 //! Hybrid search pipeline combining BM25 and vector search
 
 use async_trait::async_trait;
+use std::time::Instant;
 use tracing;
 
 use zero_latency_core::Result;
 
 use crate::fusion::ScoreFusion;
-use crate::models::SearchContext;
+use crate::models::{SearchContext, SearchResult};
 use crate::traits::SearchStep;
 use crate::vector_search::VectorSearchStep;
 use crate::bm25::BM25SearchStep;
 
+/// Fuse results with the request's fusion weights if it sets them,
+/// otherwise with the step's configured weights
+fn fuse_for_request(
+    score_fusion: &ScoreFusion,
+    context: &SearchContext,
+    results: Vec<SearchResult>,
+) -> std::result::Result<Vec<SearchResult>, String> {
+    match context.request.options.fusion_weights {
+        Some(weights) => score_fusion.with_weights(weights)?.fuse_results(results),
+        None => score_fusion.fuse_results(results),
+    }
+}
+
 /// Hybrid search step that combines BM25 and vector search results
 pub struct HybridSearchStep {
     bm25_step: BM25SearchStep,
@@ -42,14 +56,16 @@ impl SearchStep for HybridSearchStep {
         tracing::info!("🔀 HybridSearchStep: Starting parallel BM25 and vector search");
         
         // Create separate contexts for each search engine
-        let mut bm25_context = context.clone();
-        let mut vector_context = context.clone();
+        let mut bm25_context = context.branch();
+        let mut vector_context = context.branch();
         
         // Execute both searches in parallel
         let (_bm25_result, _vector_result) = tokio::try_join!(
             self.bm25_step.execute(&mut bm25_context),
             self.vector_step.execute(&mut vector_context)
         )?;
+        context.merge_stage_timings(&bm25_context);
+        context.merge_stage_timings(&vector_context);
         
         tracing::info!(
             "📊 HybridSearchStep: BM25 found {} results, Vector found {} results",
@@ -63,7 +79,9 @@ impl SearchStep for HybridSearchStep {
         all_results.extend(vector_context.raw_results);
         
         // Apply score fusion
-        let fused_results = self.score_fusion.fuse_results(all_results)?;
+        let started = Instant::now();
+        let fused_results = fuse_for_request(&self.score_fusion, context, all_results)?;
+        context.record_stage("fusion", started.elapsed());
         
         tracing::info!(
             "✨ HybridSearchStep: Fusion produced {} results",
@@ -154,19 +172,23 @@ impl AdvancedHybridSearchStep {
     async fn execute_parallel(&self, context: &mut SearchContext) -> Result<()> {
         tracing::info!("🔀 AdvancedHybridSearchStep: Parallel execution mode");
         
-        let mut bm25_context = context.clone();
-        let mut vector_context = context.clone();
+        let mut bm25_context = context.branch();
+        let mut vector_context = context.branch();
         
         let (_bm25_result, _vector_result) = tokio::try_join!(
             self.bm25_step.execute(&mut bm25_context),
             self.vector_step.execute(&mut vector_context)
         )?;
+        context.merge_stage_timings(&bm25_context);
+        context.merge_stage_timings(&vector_context);
         
         let mut all_results = Vec::new();
         all_results.extend(bm25_context.raw_results);
         all_results.extend(vector_context.raw_results);
         
-        let fused_results = self.score_fusion.fuse_results(all_results)?;
+        let started = Instant::now();
+        let fused_results = fuse_for_request(&self.score_fusion, context, all_results)?;
+        context.record_stage("fusion", started.elapsed());
         
         context.raw_results = fused_results;
         context.metadata.result_sources.push("hybrid_parallel".to_string());
@@ -178,8 +200,9 @@ impl AdvancedHybridSearchStep {
         tracing::info!("🔄 AdvancedHybridSearchStep: Sequential execution mode (threshold: {})", vector_threshold);
         
         // Execute vector search first
-        let mut vector_context = context.clone();
+        let mut vector_context = context.branch();
         self.vector_step.execute(&mut vector_context).await?;
+        context.merge_stage_timings(&vector_context);
         
         let vector_count = vector_context.raw_results.len();
         tracing::info!("📊 Vector search returned {} results", vector_count);
@@ -189,14 +212,18 @@ impl AdvancedHybridSearchStep {
         // If vector search didn't return enough results, supplement with BM25
         if vector_count < vector_threshold {
             tracing::info!("🔍 Vector results below threshold, running BM25 search");
-            let mut bm25_context = context.clone();
+            let mut bm25_context = context.branch();
             self.bm25_step.execute(&mut bm25_context).await?;
+            context.merge_stage_timings(&bm25_context);
             all_results.extend(bm25_context.raw_results);
         }
         
         let fused_results = if all_results.len() > vector_count {
             // We have results from both engines, apply fusion
-            self.score_fusion.fuse_results(all_results)?
+            let started = Instant::now();
+            let fused_results = fuse_for_request(&self.score_fusion, context, all_results)?;
+            context.record_stage("fusion", started.elapsed());
+            fused_results
         } else {
             // Only vector results, no fusion needed
             all_results
@@ -212,8 +239,9 @@ impl AdvancedHybridSearchStep {
         tracing::info!("🎯 AdvancedHybridSearchStep: BM25-then-Vector execution mode (rerank: {})", rerank_count);
         
         // Execute BM25 search first to get candidates
-        let mut bm25_context = context.clone();
+        let mut bm25_context = context.branch();
         self.bm25_step.execute(&mut bm25_context).await?;
+        context.merge_stage_timings(&bm25_context);
         
         tracing::info!("📊 BM25 search returned {} candidates", bm25_context.raw_results.len());
         
@@ -228,14 +256,17 @@ impl AdvancedHybridSearchStep {
         let top_candidates = bm25_context.raw_results.into_iter().take(candidates_to_rerank).collect::<Vec<_>>();
         
         // Execute vector search for reranking
-        let mut vector_context = context.clone();
+        let mut vector_context = context.branch();
         self.vector_step.execute(&mut vector_context).await?;
+        context.merge_stage_timings(&vector_context);
         
         // Merge results and apply fusion
         let mut all_results = top_candidates;
         all_results.extend(vector_context.raw_results);
         
-        let fused_results = self.score_fusion.fuse_results(all_results)?;
+        let started = Instant::now();
+        let fused_results = fuse_for_request(&self.score_fusion, context, all_results)?;
+        context.record_stage("fusion", started.elapsed());
         
         context.raw_results = fused_results;
         context.metadata.result_sources.push("hybrid_bm25_then_vector".to_string());
@@ -0,0 +1,291 @@
+//! Query intent classification
+//!
+//! Different kinds of queries want different pipelines: synonyms help a
+//! keyword search but turn an exact identifier into noise, and someone
+//! looking for `CONTRIBUTING.md` wants that page rather than every page that
+//! mentions contributing. [`SearchIntent::classify`] sorts a query into one
+//! of four intents with a few cheap lexical rules, checked in order:
+//!
+//! - `question`: the query ends with `?` or starts with a question word,
+//! - `navigational`: the query names a path, a documentation file or a
+//!   well-known page such as the readme or changelog,
+//! - `code_lookup`: the query contains an identifier, such as `snake_case`,
+//!   `camelCase`, `SCREAMING_CASE`, `path::to::item` or `call()`,
+//! - `keyword`: anything else.
+//!
+//! Navigational and code lookups skip query expansion, and their results are
+//! boosted towards the named pages or towards code by [`boost_intent_matches`].
+
+use serde::{Deserialize, Serialize};
+use zero_latency_core::values::Score;
+
+use crate::answer::is_question;
+use crate::models::SearchResult;
+
+/// Score added to code results of a code lookup
+const CODE_BOOST: f32 = 0.1;
+
+/// Score added to the pages a navigational query names
+const NAVIGATION_BOOST: f32 = 0.15;
+
+/// Extensions of files indexed as code
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "ts", "tsx", "go", "java", "kt", "rb", "c", "h", "cc", "cpp", "hpp",
+    "cs", "swift", "lua", "sh", "bash", "zsh", "fish",
+];
+
+/// Extensions of documentation files a query may name directly
+const DOC_EXTENSIONS: &[&str] = &[
+    "md", "markdown", "rst", "txt", "html", "htm", "adoc", "asciidoc", "org",
+];
+
+/// Pages people look for by name
+const PAGE_NAMES: &[&str] = &[
+    "readme",
+    "changelog",
+    "license",
+    "contributing",
+    "faq",
+    "glossary",
+    "roadmap",
+    "quickstart",
+];
+
+/// Leading keywords of a definition lookup, as in `fn parse_query`
+const DEFINITION_KEYWORDS: &[&str] = &["fn", "struct", "impl", "trait", "enum", "def", "func"];
+
+/// What a query is looking for, which decides how it is searched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchIntent {
+    /// Topic words, searched with expansion and no intent boosts
+    Keyword,
+    /// A specific page or file
+    Navigational,
+    /// A question, which may be answered directly
+    Question,
+    /// An identifier from code or configuration
+    CodeLookup,
+}
+
+impl SearchIntent {
+    /// Classify a raw query
+    pub fn classify(query: &str) -> Self {
+        let query = query.trim();
+        if is_question(query) {
+            return Self::Question;
+        }
+
+        let tokens: Vec<&str> = query.split_whitespace().map(trim_token).collect();
+        if tokens
+            .iter()
+            .any(|token| navigation_target(token).is_some())
+        {
+            return Self::Navigational;
+        }
+        let defines =
+            tokens.len() > 1 && DEFINITION_KEYWORDS.contains(&tokens[0].to_lowercase().as_str());
+        if defines || tokens.iter().any(|token| is_identifier(token)) {
+            return Self::CodeLookup;
+        }
+        Self::Keyword
+    }
+
+    /// Whether synonyms and expansions help queries of this intent
+    pub fn expands_query(self) -> bool {
+        matches!(self, Self::Keyword | Self::Question)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Keyword => "keyword",
+            Self::Navigational => "navigational",
+            Self::Question => "question",
+            Self::CodeLookup => "code_lookup",
+        }
+    }
+}
+
+impl std::fmt::Display for SearchIntent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Favour the results a query of the given intent is after
+///
+/// Code lookups favour code files and chunks with fenced code blocks;
+/// navigational queries favour results whose path or title names the page.
+/// Returns whether any result was boosted, leaving the results sorted by
+/// their new scores if so.
+pub fn boost_intent_matches(
+    intent: SearchIntent,
+    query: &str,
+    results: &mut [SearchResult],
+) -> bool {
+    let boost = match intent {
+        SearchIntent::CodeLookup => CODE_BOOST,
+        SearchIntent::Navigational => NAVIGATION_BOOST,
+        SearchIntent::Keyword | SearchIntent::Question => return false,
+    };
+    let targets: Vec<String> = query
+        .split_whitespace()
+        .filter_map(|token| navigation_target(trim_token(token)))
+        .collect();
+    let matches = |result: &SearchResult| {
+        if intent == SearchIntent::CodeLookup {
+            return is_code_result(result);
+        }
+        let uri = result.uri.to_lowercase();
+        let title = result.title.to_lowercase();
+        targets
+            .iter()
+            .any(|target| uri.contains(target.as_str()) || title.contains(target.as_str()))
+    };
+
+    let mut boosted = false;
+    for result in results.iter_mut().filter(|result| matches(result)) {
+        let score = (result.final_score.value() + boost).min(1.0);
+        result.final_score = Score::new(score).unwrap_or(result.final_score);
+        boosted = true;
+    }
+    if boosted {
+        results.sort_by(|a, b| b.final_score.value().total_cmp(&a.final_score.value()));
+    }
+    boosted
+}
+
+// Strip the quotes and punctuation people wrap identifiers and paths in
+fn trim_token(token: &str) -> &str {
+    token.trim_matches(|c: char| matches!(c, '`' | '"' | '\'' | ',' | ';' | ':' | '!'))
+}
+
+// The lowercase path, file or page name a token navigates to, if any
+fn navigation_target(token: &str) -> Option<String> {
+    let lower = token.to_lowercase();
+    let is_path = lower.contains('/') && lower.chars().any(|c| c.is_alphanumeric());
+    let is_doc_file = lower
+        .rsplit_once('.')
+        .is_some_and(|(stem, extension)| !stem.is_empty() && DOC_EXTENSIONS.contains(&extension));
+    let is_code_file = lower.rsplit_once('.').is_some_and(|(stem, extension)| {
+        !stem.is_empty() && !stem.contains('(') && CODE_EXTENSIONS.contains(&extension)
+    });
+    if is_path || is_doc_file || is_code_file || PAGE_NAMES.contains(&lower.as_str()) {
+        Some(lower.trim_matches('/').to_string())
+    } else {
+        None
+    }
+}
+
+fn is_identifier(token: &str) -> bool {
+    if token.contains("::") || token.contains("->") || token.ends_with("()") {
+        return true;
+    }
+    let word = token.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_');
+    if word.is_empty() || !word.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return false;
+    }
+    let snake = word.trim_matches('_').contains('_');
+    let camel = word
+        .chars()
+        .zip(word.chars().skip(1))
+        .any(|(a, b)| a.is_lowercase() && b.is_uppercase());
+    snake || camel
+}
+
+fn is_code_result(result: &SearchResult) -> bool {
+    let path = result.uri.split(['?', '#']).next().unwrap_or_default();
+    let is_code_file = path
+        .rsplit_once('.')
+        .filter(|(_, extension)| !extension.contains('/'))
+        .is_some_and(|(_, extension)| CODE_EXTENSIONS.contains(&extension.to_lowercase().as_str()));
+    is_code_file || result.content.contains("```")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fusion::{FromSignals, NormalizationMethod, ScoreBreakdown};
+    use zero_latency_core::DocId;
+
+    fn result(uri: &str, content: &str, score: f32) -> SearchResult {
+        let scores = ScoreBreakdown {
+            bm25_raw: None,
+            vector_raw: Some(score),
+            bm25_normalized: None,
+            vector_normalized: Some(score),
+            fused: score,
+            normalization_method: NormalizationMethod::MinMax,
+        };
+        SearchResult::new(
+            DocId::new("docs", uri, 1),
+            uri.to_string(),
+            "Page".to_string(),
+            content.to_string(),
+            scores,
+            FromSignals::vector_only(),
+        )
+    }
+
+    #[test]
+    fn test_classify_intent() {
+        let cases = [
+            ("how do I configure the port?", SearchIntent::Question),
+            ("what does DOC_INDEXER_PORT do", SearchIntent::Question),
+            ("CONTRIBUTING.md", SearchIntent::Navigational),
+            ("docs/api/search", SearchIntent::Navigational),
+            ("changelog", SearchIntent::Navigational),
+            ("src/lib.rs", SearchIntent::Navigational),
+            ("DOC_INDEXER_PORT", SearchIntent::CodeLookup),
+            ("`parse_query`", SearchIntent::CodeLookup),
+            ("ScoreCalibrator::calibrate", SearchIntent::CodeLookup),
+            ("searchDocuments()", SearchIntent::CodeLookup),
+            ("fn main", SearchIntent::CodeLookup),
+            ("vector search performance", SearchIntent::Keyword),
+            ("Zero-Latency indexing", SearchIntent::Keyword),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(SearchIntent::classify(query), expected, "{}", query);
+        }
+        assert!(!SearchIntent::CodeLookup.expands_query());
+        assert!(SearchIntent::Keyword.expands_query());
+    }
+
+    #[test]
+    fn test_intent_boosts() {
+        let uris =
+            |results: &[SearchResult]| results.iter().map(|r| r.uri.clone()).collect::<Vec<_>>();
+        let mut results = vec![
+            result("docs/guide.md", "Configure the port.", 0.6),
+            result("src/config.rs", "pub port: u16", 0.55),
+            result("docs/usage.md", "```sh\nmdx search port\n```", 0.45),
+        ];
+
+        // Keyword searches keep their ranking
+        assert!(!boost_intent_matches(
+            SearchIntent::Keyword,
+            "port",
+            &mut results
+        ));
+
+        // Code files lead a code lookup, and chunks with code blocks gain too
+        assert!(boost_intent_matches(
+            SearchIntent::CodeLookup,
+            "server_port",
+            &mut results
+        ));
+        assert_eq!(
+            uris(&results),
+            ["src/config.rs", "docs/guide.md", "docs/usage.md"]
+        );
+        assert!((results[2].final_score.value() - 0.55).abs() < 1e-6);
+
+        // The named page leads a navigational search
+        assert!(boost_intent_matches(
+            SearchIntent::Navigational,
+            "usage.md",
+            &mut results
+        ));
+        assert_eq!(results[0].uri, "docs/usage.md");
+    }
+}
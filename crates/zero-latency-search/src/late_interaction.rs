@@ -0,0 +1,282 @@
+//! Late-interaction (ColBERT-style) reranking
+//!
+//! A single embedding per chunk blurs the individual terms a technical query
+//! hinges on, such as an option name or an error code. Late interaction keeps
+//! one vector per token instead and scores a chunk by MaxSim: every query
+//! token is matched with its most similar chunk token, and the matches are
+//! averaged. Token vectors are stored compressed as a [`TokenMatrix`], one
+//! signed byte per dimension plus a scale per token, and only the top of the
+//! ranking is rescored with them.
+//!
+//! Tokens are the distinct content terms of a text, as extracted answers
+//! match them, each embedded on its own. The vectors are therefore not
+//! contextualized the way a ColBERT encoder's are, but work with any
+//! embedding model.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use zero_latency_core::{values::Score, Result, Uuid};
+
+use crate::answer::content_terms;
+use crate::models::SearchContext;
+use crate::traits::SearchStep;
+use crate::vector_search::EmbeddingService;
+
+/// Largest magnitude of a quantized component
+const CODE_RANGE: f32 = 127.0;
+
+/// Distinct content terms of `text` in order of appearance, at most `limit`
+pub fn token_terms(text: &str, limit: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    content_terms(text)
+        .filter(|term| seen.insert(term.clone()))
+        .take(limit)
+        .collect()
+}
+
+/// Unit-length token vectors quantized to one signed byte per dimension
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenMatrix {
+    dimension: usize,
+    /// Value of a code of 1, per token
+    scales: Vec<f32>,
+    /// Codes of every token, one row of `dimension` codes after another
+    codes: Vec<i8>,
+}
+
+impl TokenMatrix {
+    /// Normalize and quantize token vectors
+    ///
+    /// Vectors whose dimension differs from the first one's are skipped.
+    pub fn compress(tokens: &[Vec<f32>]) -> Self {
+        let dimension = tokens.first().map_or(0, Vec::len);
+        let mut scales = Vec::with_capacity(tokens.len());
+        let mut codes = Vec::with_capacity(tokens.len() * dimension);
+        for token in tokens.iter().filter(|token| token.len() == dimension) {
+            let norm = token.iter().map(|x| x * x).sum::<f32>().sqrt();
+            let max = token.iter().fold(0.0f32, |max, x| max.max(x.abs()));
+            if norm == 0.0 || !norm.is_finite() {
+                scales.push(0.0);
+                codes.extend(std::iter::repeat_n(0, dimension));
+                continue;
+            }
+            let scale = max / norm / CODE_RANGE;
+            scales.push(scale);
+            codes.extend(
+                token
+                    .iter()
+                    .map(|x| (x / norm / scale).round().clamp(-CODE_RANGE, CODE_RANGE) as i8),
+            );
+        }
+        Self {
+            dimension,
+            scales,
+            codes,
+        }
+    }
+
+    /// Rebuild a matrix from its stored parts
+    pub fn from_parts(
+        dimension: usize,
+        scales: Vec<f32>,
+        codes: Vec<i8>,
+    ) -> std::result::Result<Self, String> {
+        if codes.len() != scales.len() * dimension {
+            return Err(format!(
+                "{} codes do not make {} tokens of {} dimensions",
+                codes.len(),
+                scales.len(),
+                dimension
+            ));
+        }
+        Ok(Self {
+            dimension,
+            scales,
+            codes,
+        })
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Number of tokens
+    pub fn len(&self) -> usize {
+        self.scales.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scales.is_empty()
+    }
+
+    pub fn scales(&self) -> &[f32] {
+        &self.scales
+    }
+
+    pub fn codes(&self) -> &[i8] {
+        &self.codes
+    }
+
+    /// MaxSim of `query` tokens against this matrix, between 0 and 1
+    ///
+    /// Each query token scores the cosine similarity of its closest token
+    /// here, negative similarities counting as 0, and the scores are
+    /// averaged. Query tokens of another dimension score 0.
+    pub fn max_sim(&self, query: &[Vec<f32>]) -> f32 {
+        if query.is_empty() || self.is_empty() {
+            return 0.0;
+        }
+        let total: f32 = query
+            .iter()
+            .map(|token| {
+                let norm = token.iter().map(|x| x * x).sum::<f32>().sqrt();
+                if token.len() != self.dimension || norm == 0.0 {
+                    return 0.0;
+                }
+                self.codes
+                    .chunks_exact(self.dimension)
+                    .zip(&self.scales)
+                    .map(|(codes, scale)| {
+                        let dot: f32 = codes.iter().zip(token).map(|(&c, x)| c as f32 * x).sum();
+                        dot * scale / norm
+                    })
+                    .fold(0.0f32, f32::max)
+            })
+            .sum();
+        (total / query.len() as f32).min(1.0)
+    }
+}
+
+/// Store of the token vectors of indexed chunks
+#[async_trait]
+pub trait TokenVectorSource: Send + Sync {
+    /// Token vectors of the chunks with these vector ids; chunks without any
+    /// are left out
+    async fn token_vectors(&self, chunk_ids: &[Uuid]) -> Result<HashMap<Uuid, TokenMatrix>>;
+}
+
+/// Step reranking the top results by MaxSim of their token vectors
+///
+/// Runs on requests with `late_interaction` set. Results whose chunks have
+/// no token vectors, e.g. because they were indexed before late interaction
+/// was enabled, keep their score.
+pub struct LateInteractionStep {
+    embedder: Arc<dyn EmbeddingService>,
+    source: Arc<dyn TokenVectorSource>,
+    /// Number of top results rescored
+    depth: usize,
+    /// Query tokens matched, at most
+    max_query_tokens: usize,
+}
+
+impl LateInteractionStep {
+    pub fn new(
+        embedder: Arc<dyn EmbeddingService>,
+        source: Arc<dyn TokenVectorSource>,
+        depth: usize,
+        max_query_tokens: usize,
+    ) -> Self {
+        Self {
+            embedder,
+            source,
+            depth,
+            max_query_tokens,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchStep for LateInteractionStep {
+    fn name(&self) -> &str {
+        "late_interaction"
+    }
+
+    async fn execute(&self, context: &mut SearchContext) -> Result<()> {
+        if !context.request.options.late_interaction || context.ranked_results.is_empty() {
+            return Ok(());
+        }
+        let started = std::time::Instant::now();
+        let terms = token_terms(&context.request.query.raw, self.max_query_tokens);
+        if terms.is_empty() {
+            return Ok(());
+        }
+
+        let collection = context.request.filters.custom.get("collection").cloned();
+        let mut query = Vec::with_capacity(terms.len());
+        for term in &terms {
+            query.push(
+                self.embedder
+                    .generate_embedding_for(term, collection.as_deref())
+                    .await?,
+            );
+        }
+
+        let depth = self.depth.min(context.ranked_results.len());
+        let ids: Vec<Uuid> = context.ranked_results[..depth]
+            .iter()
+            .map(|result| result.document_id)
+            .collect();
+        let matrices = self.source.token_vectors(&ids).await?;
+        if matrices.is_empty() {
+            return Ok(());
+        }
+
+        // Token vectors embedded with another model cannot be compared
+        let dimension = query[0].len();
+        let head = &mut context.ranked_results[..depth];
+        for result in head.iter_mut() {
+            let matrix = matrices.get(&result.document_id);
+            if let Some(matrix) = matrix.filter(|matrix| matrix.dimension() == dimension) {
+                let score = matrix.max_sim(&query);
+                result.from_signals.late_interaction_score = Some(score);
+                result.final_score = Score::new(score).unwrap_or(result.final_score);
+            }
+        }
+        head.sort_by(|a, b| b.final_score.value().total_cmp(&a.final_score.value()));
+
+        context
+            .metadata
+            .ranking_method
+            .push_str("+late_interaction");
+        context.record_stage("late_interaction", started.elapsed());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_keeps_max_sim_close() {
+        let tokens = vec![vec![3.0, 4.0, 0.0], vec![0.0, -1.0, 1.0]];
+        let matrix = TokenMatrix::compress(&tokens);
+        assert_eq!((matrix.len(), matrix.dimension()), (2, 3));
+        assert_eq!(matrix.codes().len(), 6);
+
+        // Each query token matches its closest chunk token
+        let exact = matrix.max_sim(&[vec![0.6, 0.8, 0.0], vec![0.0, -2.0, 2.0]]);
+        assert!((exact - 1.0).abs() < 0.01, "scored {}", exact);
+        let partial = matrix.max_sim(&[vec![3.0, 4.0, 0.0], vec![-1.0, 0.0, 0.0]]);
+        assert!((partial - 0.5).abs() < 0.01, "scored {}", partial);
+        assert_eq!(matrix.max_sim(&[vec![1.0, 0.0]]), 0.0);
+
+        let rebuilt = TokenMatrix::from_parts(3, matrix.scales().to_vec(), matrix.codes().to_vec());
+        assert_eq!(rebuilt.unwrap(), matrix);
+        assert!(TokenMatrix::from_parts(4, vec![1.0], vec![0; 3]).is_err());
+    }
+
+    #[test]
+    fn test_token_terms_are_distinct_content_terms() {
+        assert_eq!(
+            token_terms(
+                "How do I set the max_connections option? Set max_connections",
+                10
+            ),
+            vec!["set", "max_connection", "option"]
+        );
+        assert_eq!(token_terms("indexing indexes", 1), vec!["index"]);
+    }
+}
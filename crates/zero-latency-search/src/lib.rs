@@ -2,30 +2,65 @@
 ///
 /// This crate provides reusable search capabilities including:
 /// - Query processing and enhancement
+/// - Access control on indexed documents
+/// - Browsing collections when a query has nothing to search for
+/// - Query intent classification and per-intent routing
+/// - Neighboring chunks stitched around hits
+/// - Extractive answers for question-style queries
+/// - Confidence and answerability of search responses
+/// - Source-attributed summaries of search results
+/// - Conversation context for multi-turn search
 /// - Result ranking and scoring
+/// - Late-interaction reranking with per-token vectors
+/// - Score calibration against per-collection backgrounds
 /// - Search orchestration patterns
 /// - Search analytics and metrics
+/// - User-supplied output templates for results
 /// - Performance optimization and caching
+pub mod access;
+pub mod answer;
 pub mod bm25;
+pub mod browse;
 pub mod cache;
+pub mod calibration;
+pub mod chunk_context;
+pub mod confidence;
+pub mod conversation;
 pub mod evaluation;
 pub mod fusion;
 pub mod hybrid;
+pub mod intent;
+pub mod late_interaction;
 pub mod models;
 pub mod pipeline;
 pub mod query_expansion;
+pub mod query_parser;
 // pub mod examples;
 pub mod services;
+pub mod summary;
+pub mod template;
 pub mod traits;
 pub mod vector_search;
 
+pub use access::*;
+pub use answer::*;
 pub use bm25::*;
+pub use browse::*;
+pub use calibration::*;
+pub use chunk_context::*;
+pub use confidence::*;
+pub use conversation::*;
 pub use evaluation::*;
 pub use fusion::*;
 pub use hybrid::*;
+pub use intent::*;
+pub use late_interaction::*;
 pub use models::*;
 pub use pipeline::*;
 pub use query_expansion::*;
+pub use query_parser::*;
 pub use services::*;
+pub use summary::*;
+pub use template::*;
 pub use traits::*;
 pub use vector_search::*;
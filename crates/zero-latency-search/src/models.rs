@@ -2,10 +2,17 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
 use tracing;
-use zero_latency_core::{values::*, DateTime, DocId, Utc, Uuid};
+use zero_latency_core::{values::*, DateTime, DocId, Result, Utc, Uuid, ZeroLatencyError};
 
 // Re-export fusion types for convenience
-pub use crate::fusion::{FromSignals, ScoreBreakdown};
+pub use crate::fusion::{FromSignals, FusionWeights, ScoreBreakdown, VectorSpace};
+
+use crate::access::Principal;
+use crate::bm25::{validate_proximity_weight, FieldBoosts};
+use crate::chunk_context::MAX_CONTEXT_WINDOW;
+use crate::intent::SearchIntent;
+use crate::query_parser::{tokenize, ParsedQuery};
+use zero_latency_vector::{MetadataFilter, NumericRange, FILTER_OVERFETCH, PATH_KEY};
 
 /// Search request with all parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +45,153 @@ impl SearchRequest {
         self.filters = filters;
         self
     }
+
+    /// Move exclusion operators in the query text into the filters
+    ///
+    /// See [`Exclusions::from_query`] for the syntax.
+    pub fn with_query_exclusions(mut self) -> Self {
+        let (text, exclusions) = Exclusions::from_query(&self.query.raw);
+        if !exclusions.is_empty() {
+            self.query = SearchQuery::new(text).with_limit(self.limit as u32);
+            self.filters.exclude.merge(exclusions);
+        }
+        self
+    }
+
+    /// Apply the settings that are set, leaving the rest of the request as is
+    pub fn with_settings(mut self, settings: &SearchSettings) -> Self {
+        if let Some(limit) = settings.limit {
+            self = self.with_limit(limit);
+        }
+        if let Some(query_expansion) = settings.query_expansion {
+            self.options.enable_query_enhancement = query_expansion;
+        }
+        if settings.fusion_weights.is_some() {
+            self.options.fusion_weights = settings.fusion_weights;
+        }
+        if settings.rerank_depth.is_some() {
+            self.options.rerank_depth = settings.rerank_depth;
+        }
+        if settings.field_weights.is_some() {
+            self.options.field_weights = settings.field_weights;
+        }
+        if settings.proximity_weight.is_some() {
+            self.options.proximity_weight = settings.proximity_weight;
+        }
+        if settings.vector_spaces.is_some() {
+            self.options.vector_spaces = settings.vector_spaces.clone();
+        }
+        if let Some(late_interaction) = settings.late_interaction {
+            self.options.late_interaction = late_interaction;
+        }
+        self
+    }
+}
+
+/// Search behaviour a collection can set defaults for and a request can override
+///
+/// Every field is optional; unset fields are filled from the next layer with
+/// [`SearchSettings::or`] (request, then collection defaults, then service
+/// configuration).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchSettings {
+    /// Maximum number of results
+    pub limit: Option<usize>,
+    /// BM25 and vector weights for hybrid fusion
+    pub fusion_weights: Option<FusionWeights>,
+    /// Whether the query is expanded before searching
+    pub query_expansion: Option<bool>,
+    /// Number of top results to rerank; unset reranks all of them
+    pub rerank_depth: Option<usize>,
+    /// Whether question-style queries get an extracted answer
+    pub extract_answer: Option<bool>,
+    /// Lowest final score a result may have; pinned results are always kept
+    pub min_score: Option<f32>,
+    /// Weights of title, heading, path and body matches in lexical scoring
+    pub field_weights: Option<FieldBoosts>,
+    /// Boost of lexical matches with the query words close together; 0 turns
+    /// it off
+    pub proximity_weight: Option<f32>,
+    /// Named vector spaces searched and fused; unset searches the content
+    /// vector alone
+    pub vector_spaces: Option<Vec<VectorSpace>>,
+    /// Whether the top results are reranked by MaxSim of their token vectors
+    pub late_interaction: Option<bool>,
+    /// Neighboring chunks stitched on each side of a hit; 0 returns hits alone
+    pub context_window: Option<usize>,
+}
+
+impl SearchSettings {
+    /// These settings, with anything unset taken from `defaults`
+    pub fn or(&self, defaults: &SearchSettings) -> SearchSettings {
+        SearchSettings {
+            limit: self.limit.or(defaults.limit),
+            fusion_weights: self.fusion_weights.or(defaults.fusion_weights),
+            query_expansion: self.query_expansion.or(defaults.query_expansion),
+            rerank_depth: self.rerank_depth.or(defaults.rerank_depth),
+            extract_answer: self.extract_answer.or(defaults.extract_answer),
+            min_score: self.min_score.or(defaults.min_score),
+            field_weights: self.field_weights.or(defaults.field_weights),
+            proximity_weight: self.proximity_weight.or(defaults.proximity_weight),
+            vector_spaces: self
+                .vector_spaces
+                .clone()
+                .or_else(|| defaults.vector_spaces.clone()),
+            late_interaction: self.late_interaction.or(defaults.late_interaction),
+            context_window: self.context_window.or(defaults.context_window),
+        }
+    }
+
+    /// Check that the settings that are set are usable
+    pub fn validate(&self) -> Result<()> {
+        if self.limit == Some(0) {
+            return Err(ZeroLatencyError::validation(
+                "limit",
+                "Limit must be greater than 0",
+            ));
+        }
+        if self.rerank_depth == Some(0) {
+            return Err(ZeroLatencyError::validation(
+                "rerank_depth",
+                "Rerank depth must be greater than 0",
+            ));
+        }
+        if let Some(weights) = &self.fusion_weights {
+            weights
+                .validate()
+                .map_err(|message| ZeroLatencyError::validation("fusion_weights", message))?;
+        }
+        if self.min_score.is_some_and(|score| !(0.0..=1.0).contains(&score)) {
+            return Err(ZeroLatencyError::validation(
+                "min_score",
+                "Minimum score must be between 0 and 1",
+            ));
+        }
+        if let Some(weights) = &self.field_weights {
+            weights
+                .validate()
+                .map_err(|message| ZeroLatencyError::validation("field_weights", message))?;
+        }
+        if let Some(weight) = self.proximity_weight {
+            validate_proximity_weight(weight)
+                .map_err(|message| ZeroLatencyError::validation("proximity_weight", message))?;
+        }
+        if let Some(spaces) = &self.vector_spaces {
+            VectorSpace::validate_all(spaces)
+                .map_err(|message| ZeroLatencyError::validation("vector_spaces", message))?;
+        }
+        if self
+            .context_window
+            .is_some_and(|window| window > MAX_CONTEXT_WINDOW)
+        {
+            return Err(ZeroLatencyError::validation(
+                "context_window",
+                format!("Context window must be at most {}", MAX_CONTEXT_WINDOW),
+            ));
+        }
+        Ok(())
+    }
 }
 
 /// Search filters for refining results
@@ -48,6 +202,200 @@ pub struct SearchFilters {
     pub tags: Vec<String>,
     pub minimum_score: Option<Score>,
     pub custom: HashMap<String, String>,
+    /// Results to leave out, whatever their score
+    #[serde(default)]
+    pub exclude: Exclusions,
+    /// Glob or `re:` regex patterns on `path` and custom metadata values
+    #[serde(default)]
+    pub metadata_patterns: HashMap<String, String>,
+    /// Inclusive numeric bounds on custom metadata values
+    #[serde(default)]
+    pub ranges: HashMap<String, NumericRange>,
+    /// Principal whose entitlements limit the results, when access control
+    /// is enforced; set by the server from the request's authentication,
+    /// never read from requests
+    #[serde(skip)]
+    pub access: Option<Principal>,
+}
+
+impl SearchFilters {
+    /// How many results to retrieve so that `limit` can remain after
+    /// excluded and restricted ones are dropped
+    pub fn fetch_limit(&self, limit: usize) -> usize {
+        let limit = self.exclude.fetch_limit(limit);
+        match self.access {
+            Some(_) => limit.saturating_mul(FILTER_OVERFETCH),
+            None => limit,
+        }
+    }
+}
+
+/// Results a search must not return
+///
+/// Exclusions are applied by each retrieval step before fusion, so an
+/// excluded document cannot contribute a score through either engine.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Exclusions {
+    /// Drop results carrying any of these tags (case-insensitive)
+    pub tags: Vec<String>,
+    /// Drop results from these collections
+    pub collections: Vec<String>,
+    /// Drop these documents, given by document ID or URI
+    pub documents: Vec<String>,
+    /// Drop results whose path matches any of these patterns, as in the
+    /// `path` metadata pattern
+    pub paths: Vec<String>,
+}
+
+impl Exclusions {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.collections.is_empty()
+            && self.documents.is_empty()
+            && self.paths.is_empty()
+    }
+
+    /// Add the exclusions from `other` that aren't already present
+    pub fn merge(&mut self, other: Exclusions) {
+        fn extend(into: &mut Vec<String>, values: Vec<String>) {
+            for value in values {
+                if !into.contains(&value) {
+                    into.push(value);
+                }
+            }
+        }
+        extend(&mut self.tags, other.tags);
+        extend(&mut self.collections, other.collections);
+        extend(&mut self.documents, other.documents);
+        extend(&mut self.paths, other.paths);
+    }
+
+    /// Whether a result matches any exclusion
+    pub fn excludes(&self, result: &SearchResult) -> bool {
+        !self.is_empty() && self.excludes_with(result, &self.path_filters())
+    }
+
+    /// Path patterns compiled one filter each, skipping malformed ones
+    fn path_filters(&self) -> Vec<MetadataFilter> {
+        let key = PATH_KEY.to_string();
+        self.paths
+            .iter()
+            .filter_map(|pattern| MetadataFilter::compile([(&key, pattern)]).ok())
+            .collect()
+    }
+
+    fn excludes_with(&self, result: &SearchResult, paths: &[MetadataFilter]) -> bool {
+        let collection = result
+            .collection
+            .as_deref()
+            .unwrap_or(&result.doc_id.collection);
+        if self
+            .collections
+            .iter()
+            .any(|excluded| excluded == collection)
+        {
+            return true;
+        }
+
+        if let Some(tags) = result.custom_metadata.get("tags") {
+            let mut tags = tags.split(',').map(str::trim);
+            if tags.any(|tag| {
+                self.tags
+                    .iter()
+                    .any(|excluded| excluded.eq_ignore_ascii_case(tag))
+            }) {
+                return true;
+            }
+        }
+
+        let document_id = result.document_id.to_string();
+        let ids = [
+            Some(document_id.as_str()),
+            Some(result.doc_id.external_id.as_str()),
+            Some(result.uri.as_str()),
+            Some(result.document_path.as_str()),
+            result
+                .custom_metadata
+                .get("parent_document_id")
+                .map(String::as_str),
+        ];
+        self.documents
+            .iter()
+            .any(|excluded| ids.contains(&Some(excluded.as_str())))
+            || paths.iter().any(|filter| result.matches_metadata(filter))
+    }
+
+    /// How many results to retrieve so that `limit` can remain after
+    /// excluded ones are dropped
+    pub fn fetch_limit(&self, limit: usize) -> usize {
+        if self.is_empty() {
+            limit
+        } else {
+            limit + limit.max(self.documents.len() + self.paths.len())
+        }
+    }
+
+    /// Remove excluded results, returning how many were dropped
+    pub fn apply(&self, results: &mut Vec<SearchResult>) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let paths = self.path_filters();
+        let before = results.len();
+        results.retain(|result| !self.excludes_with(result, &paths));
+        before - results.len()
+    }
+
+    /// Split exclusion operators out of a query string
+    ///
+    /// `-field:value` and `NOT field:value` are recognized for the `tag`,
+    /// `collection` and `doc` fields; the remaining words are returned as the
+    /// query text. Other negated words are left in the query untouched.
+    pub fn from_query(query: &str) -> (String, Self) {
+        let mut exclusions = Self::default();
+        let mut words = Vec::new();
+        let mut tokens = tokenize(query).into_iter().peekable();
+
+        while let Some(token) = tokens.next() {
+            if let Some(operand) = token.strip_prefix('-') {
+                if exclusions.add(operand) {
+                    continue;
+                }
+            } else if token == "NOT" {
+                if let Some(operand) = tokens.peek() {
+                    if exclusions.add(operand) {
+                        tokens.next();
+                        continue;
+                    }
+                }
+            }
+            words.push(token);
+        }
+
+        (words.join(" "), exclusions)
+    }
+
+    // Add an operand such as `tag:deprecated`, returning false if it isn't one
+    fn add(&mut self, operand: &str) -> bool {
+        let (field, value) = match operand.split_once(':') {
+            Some((field, value)) => (field, value.trim_matches('"')),
+            None => return false,
+        };
+        if value.is_empty() {
+            return false;
+        }
+        let values = match field {
+            "tag" => &mut self.tags,
+            "collection" => &mut self.collections,
+            "doc" => &mut self.documents,
+            _ => return false,
+        };
+        if !values.iter().any(|existing| existing == value) {
+            values.push(value.to_string());
+        }
+        true
+    }
 }
 
 /// Date range filter
@@ -66,6 +414,26 @@ pub struct SearchOptions {
     pub response_format: ResponseFormat,
     pub enable_query_enhancement: bool,
     pub enable_personalization: bool,
+    /// Fusion weights overriding those configured on hybrid search steps
+    pub fusion_weights: Option<FusionWeights>,
+    /// Number of top results to rerank; the rest keep their retrieval order
+    pub rerank_depth: Option<usize>,
+    /// Lexical field weights overriding the index's configured boosts
+    #[serde(default)]
+    pub field_weights: Option<FieldBoosts>,
+    /// Lexical proximity weight overriding the index's configured one
+    #[serde(default)]
+    pub proximity_weight: Option<f32>,
+    /// Leave the search out of analytics, as for internal warm-up queries
+    #[serde(default)]
+    pub skip_analytics: bool,
+    /// Named vector spaces the vector search fuses; unset searches the
+    /// content vector alone
+    #[serde(default)]
+    pub vector_spaces: Option<Vec<VectorSpace>>,
+    /// Rerank the top results by MaxSim of their token vectors
+    #[serde(default)]
+    pub late_interaction: bool,
 }
 
 impl Default for SearchOptions {
@@ -77,10 +445,43 @@ impl Default for SearchOptions {
             response_format: ResponseFormat::default(),
             enable_query_enhancement: true,
             enable_personalization: false,
+            fusion_weights: None,
+            rerank_depth: None,
+            field_weights: None,
+            proximity_weight: None,
+            skip_analytics: false,
+            vector_spaces: None,
+            late_interaction: false,
         }
     }
 }
 
+/// Metadata key of the first source line of a chunk
+pub const LINE_START_KEY: &str = "line_start";
+
+/// Metadata key of the last source line of a chunk
+pub const LINE_END_KEY: &str = "line_end";
+
+/// Metadata key of the heading anchor of a chunk's section
+pub const ANCHOR_KEY: &str = "anchor";
+
+/// Source lines a chunk spans, 1-based and inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl LineRange {
+    /// Lines recorded in a chunk's metadata at index time
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Option<Self> {
+        Some(Self {
+            start: metadata.get(LINE_START_KEY)?.parse().ok()?,
+            end: metadata.get(LINE_END_KEY)?.parse().ok()?,
+        })
+    }
+}
+
 /// Individual search result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -114,6 +515,13 @@ pub struct SearchResult {
     pub ranking_signals: Option<RankingSignals>,
     /// URL for accessing the document
     pub url: Option<String>,
+    /// Source lines of the chunk, for editors to jump to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines: Option<LineRange>,
+    /// Anchor of the chunk's section heading, for deep links into the
+    /// rendered document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
     /// Collection this document belongs to
     pub collection: Option<String>,
     /// Custom metadata
@@ -150,6 +558,8 @@ impl SearchResult {
             from_signals,
             ranking_signals: None,
             url: None,
+            lines: None,
+            anchor: None,
             collection: None,
             custom_metadata: HashMap::new(),
         }
@@ -174,11 +584,22 @@ impl SearchResult {
         self
     }
     
-    /// Set custom metadata
+    /// Set custom metadata, and the source location recorded in it
     pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.lines = LineRange::from_metadata(&metadata);
+        self.anchor = metadata.get(ANCHOR_KEY).cloned();
         self.custom_metadata = metadata;
         self
     }
+
+    /// Check `filter` against custom metadata, using the URI as the path
+    pub fn matches_metadata(&self, filter: &MetadataFilter) -> bool {
+        filter.matches(|key| match self.custom_metadata.get(key) {
+            Some(value) => Some(value.as_str()),
+            None if key == PATH_KEY => Some(self.uri.as_str()),
+            None => None,
+        })
+    }
 }
 
 /// Ranking signals for transparency
@@ -199,6 +620,19 @@ pub struct SearchResponse {
     pub total_count: Option<usize>,
     pub search_metadata: SearchMetadata,
     pub pagination: Option<zero_latency_core::models::Pagination>,
+    /// Direct answer to a question-style query, when answer extraction is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer: Option<crate::answer::ExtractedAnswer>,
+    /// How much the results can be trusted to answer the query
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<crate::confidence::ConfidenceLevel>,
+    /// Whether the results likely contain an answer, for clients that
+    /// should otherwise say they don't know
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answerable: Option<bool>,
+    /// Value counts per facet over every browsed document, for browse mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facets: Option<crate::browse::Facets>,
 }
 
 /// Search execution metadata
@@ -210,6 +644,23 @@ pub struct SearchMetadata {
     pub ranking_method: String,
     pub result_sources: Vec<String>,
     pub debug_info: Option<HashMap<String, serde_json::Value>>,
+    /// Time spent in each pipeline stage, in execution order
+    #[serde(default)]
+    pub stage_timings: Vec<StageTiming>,
+    /// Settings the search ran with, after applying collection defaults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_settings: Option<SearchSettings>,
+    /// Intent the query was classified as, which decided how it was searched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intent: Option<SearchIntent>,
+}
+
+/// Time spent in one stage of the search pipeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageTiming {
+    /// Stage name, e.g. `embedding`, `vector_search`, `bm25`, `fusion`
+    pub stage: String,
+    pub duration_ms: f64,
 }
 
 /// Enhanced query with synonyms and expansions
@@ -286,6 +737,10 @@ pub struct SearchContext {
     pub request: SearchRequest,
     pub enhanced_query: Option<EnhancedQuery>,
     pub analysis: Option<QueryAnalysis>,
+    /// Phrases and terms of the raw query
+    pub parsed_query: ParsedQuery,
+    /// Metadata patterns of the request, compiled once for every step
+    pub metadata_filter: MetadataFilter,
     pub raw_results: Vec<SearchResult>,
     pub ranked_results: Vec<SearchResult>,
     pub metadata: SearchMetadata,
@@ -294,10 +749,21 @@ pub struct SearchContext {
 
 impl SearchContext {
     pub fn new(request: SearchRequest) -> Self {
+        let parsed_query = ParsedQuery::parse(&request.query.raw);
+        // Report how phrases and boolean operators were understood
+        let debug_info = parsed_query.is_structured().then(|| {
+            HashMap::from([(
+                "parsed_query".to_string(),
+                serde_json::to_value(&parsed_query).unwrap_or_default(),
+            )])
+        });
+
         Self {
             request,
             enhanced_query: None,
             analysis: None,
+            parsed_query,
+            metadata_filter: MetadataFilter::default(),
             raw_results: Vec::new(),
             ranked_results: Vec::new(),
             metadata: SearchMetadata {
@@ -306,12 +772,47 @@ impl SearchContext {
                 query_enhancement_applied: false,
                 ranking_method: "unknown".to_string(),
                 result_sources: Vec::new(),
-                debug_info: None,
+                debug_info,
+                stage_timings: Vec::new(),
+                effective_settings: None,
+                intent: None,
             },
             execution_start: Utc::now(),
         }
     }
 
+    /// Record the time spent in a pipeline stage
+    pub fn record_stage(&mut self, stage: impl Into<String>, duration: Duration) {
+        self.metadata.stage_timings.push(StageTiming {
+            stage: stage.into(),
+            duration_ms: duration.as_secs_f64() * 1000.0,
+        });
+    }
+
+    /// Time elapsed since the pipeline started executing
+    pub fn elapsed(&self) -> Duration {
+        Utc::now()
+            .signed_duration_since(self.execution_start)
+            .to_std()
+            .unwrap_or_default()
+    }
+
+    /// Copy of this context for a sub-search, with no stage timings of its own
+    ///
+    /// Pass the branch back to [`Self::merge_stage_timings`] once it has run.
+    pub fn branch(&self) -> Self {
+        let mut branch = self.clone();
+        branch.metadata.stage_timings.clear();
+        branch
+    }
+
+    /// Append the stages recorded by a branch created with [`Self::branch`]
+    pub fn merge_stage_timings(&mut self, branch: &SearchContext) {
+        self.metadata
+            .stage_timings
+            .extend(branch.metadata.stage_timings.iter().cloned());
+    }
+
     pub fn set_enhanced_query(&mut self, enhanced: EnhancedQuery) {
         self.enhanced_query = Some(enhanced);
         self.metadata.query_enhancement_applied = true;
@@ -330,10 +831,7 @@ impl SearchContext {
     }
 
     pub fn into_response(mut self) -> SearchResponse {
-        self.metadata.execution_time = Utc::now()
-            .signed_duration_since(self.execution_start)
-            .to_std()
-            .unwrap_or_default();
+        self.metadata.execution_time = self.elapsed();
 
         // Use enhanced query if available, otherwise use the original request query
         if let Some(enhanced) = &self.enhanced_query {
@@ -358,6 +856,236 @@ impl SearchContext {
             total_count: None,
             search_metadata: self.metadata,
             pagination: None,
+            answer: None,
+            confidence: None,
+            answerable: None,
+            facets: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_settings_layering() {
+        let request = SearchSettings {
+            limit: Some(5),
+            ..Default::default()
+        };
+        let collection = SearchSettings {
+            limit: Some(20),
+            query_expansion: Some(false),
+            rerank_depth: Some(10),
+            ..Default::default()
+        };
+        let service = SearchSettings {
+            limit: Some(10),
+            fusion_weights: Some(FusionWeights::default()),
+            query_expansion: Some(true),
+            rerank_depth: None,
+            extract_answer: Some(false),
+            min_score: None,
+            field_weights: None,
+            proximity_weight: Some(0.5),
+            vector_spaces: Some(vec![VectorSpace::new("title", 1.0)]),
+            late_interaction: Some(true),
+            context_window: Some(0),
+        };
+
+        let effective = request.or(&collection).or(&service);
+
+        assert_eq!(
+            effective,
+            SearchSettings {
+                limit: Some(5),
+                fusion_weights: Some(FusionWeights::default()),
+                query_expansion: Some(false),
+                rerank_depth: Some(10),
+                extract_answer: Some(false),
+                min_score: None,
+                field_weights: None,
+                proximity_weight: Some(0.5),
+                vector_spaces: Some(vec![VectorSpace::new("title", 1.0)]),
+                late_interaction: Some(true),
+                context_window: Some(0),
+            }
+        );
+
+        let search = SearchRequest::new("q").with_settings(&effective);
+        assert_eq!(search.limit, 5);
+        assert!(!search.options.enable_query_enhancement);
+        assert_eq!(search.options.rerank_depth, Some(10));
+        assert_eq!(search.options.proximity_weight, Some(0.5));
+        assert_eq!(search.options.vector_spaces, effective.vector_spaces);
+        assert!(search.options.late_interaction);
+    }
+
+    #[test]
+    fn test_search_settings_validation() {
+        assert!(SearchSettings::default().validate().is_ok());
+
+        let unbalanced = SearchSettings {
+            fusion_weights: Some(FusionWeights {
+                bm25_weight: 0.8,
+                vector_weight: 0.8,
+            }),
+            ..Default::default()
+        };
+        assert!(unbalanced.validate().is_err());
+
+        let no_rerank = SearchSettings {
+            rerank_depth: Some(0),
+            ..Default::default()
+        };
+        assert!(no_rerank.validate().is_err());
+
+        let out_of_range = SearchSettings {
+            min_score: Some(1.5),
+            ..Default::default()
+        };
+        assert!(out_of_range.validate().is_err());
+
+        let no_fields = SearchSettings {
+            field_weights: Some(FieldBoosts {
+                title: 0.0,
+                heading: 0.0,
+                path: 0.0,
+                content: 0.0,
+            }),
+            ..Default::default()
+        };
+        assert!(no_fields.validate().is_err());
+
+        let negative_proximity = SearchSettings {
+            proximity_weight: Some(-1.0),
+            ..Default::default()
+        };
+        assert!(negative_proximity.validate().is_err());
+
+        let repeated_space = SearchSettings {
+            vector_spaces: Some(vec![
+                VectorSpace::new("title", 1.0),
+                VectorSpace::new("title", 2.0),
+            ]),
+            ..Default::default()
+        };
+        assert!(repeated_space.validate().is_err());
+
+        let wide_window = SearchSettings {
+            context_window: Some(MAX_CONTEXT_WINDOW + 1),
+            ..Default::default()
+        };
+        assert!(wide_window.validate().is_err());
+    }
+
+    #[test]
+    fn test_exclusions_from_query() {
+        let (text, exclusions) = Exclusions::from_query(
+            "rust -tag:deprecated async NOT collection:archive -doc:guides/old.md -word NOT tokio",
+        );
+
+        assert_eq!(text, "rust async -word NOT tokio");
+        assert_eq!(exclusions.tags, vec!["deprecated"]);
+        assert_eq!(exclusions.collections, vec!["archive"]);
+        assert_eq!(exclusions.documents, vec!["guides/old.md"]);
+
+        // Operators inside a quoted phrase are part of the phrase
+        let (text, exclusions) = Exclusions::from_query(r#"flags "use -tag:beta  here" -tag:old"#);
+        assert_eq!(text, r#"flags "use -tag:beta  here""#);
+        assert_eq!(exclusions.tags, vec!["old"]);
+
+        let (_, exclusions) = Exclusions::from_query(r#"-doc:"guides/old setup.md""#);
+        assert_eq!(exclusions.documents, vec!["guides/old setup.md"]);
+
+        let request = SearchRequest::new("setup -tag:beta")
+            .with_limit(5)
+            .with_query_exclusions();
+        assert_eq!(request.query.raw, "setup");
+        assert_eq!(request.query.limit, 5);
+        assert_eq!(request.filters.exclude.tags, vec!["beta"]);
+    }
+
+    #[test]
+    fn test_exclusions_match_results() {
+        let mut result = SearchResult::new(
+            DocId::new("docs", "guide", 1),
+            "guides/setup.md".to_string(),
+            "Setup".to_string(),
+            String::new(),
+            crate::fusion::ScoreBreakdown {
+                bm25_raw: None,
+                vector_raw: Some(0.5),
+                bm25_normalized: None,
+                vector_normalized: Some(0.5),
+                fused: 0.5,
+                normalization_method: crate::fusion::NormalizationMethod::MinMax,
+            },
+            crate::fusion::FromSignals::vector_only(),
+        );
+        result
+            .custom_metadata
+            .insert("tags".to_string(), "stable, Deprecated".to_string());
+
+        let excludes = |exclusions: Exclusions| exclusions.excludes(&result);
+        assert!(!excludes(Exclusions::default()));
+        assert!(excludes(Exclusions {
+            tags: vec!["deprecated".to_string()],
+            ..Default::default()
+        }));
+        assert!(excludes(Exclusions {
+            collections: vec!["docs".to_string()],
+            ..Default::default()
+        }));
+        assert!(excludes(Exclusions {
+            documents: vec!["guides/setup.md".to_string()],
+            ..Default::default()
+        }));
+        assert!(excludes(Exclusions {
+            paths: vec!["guides/*".to_string()],
+            ..Default::default()
+        }));
+        assert!(!excludes(Exclusions {
+            tags: vec!["beta".to_string()],
+            collections: vec!["archive".to_string()],
+            documents: vec!["guides/other.md".to_string()],
+            paths: vec!["api/**".to_string()],
+        }));
+    }
+
+    #[test]
+    fn test_metadata_filter_matches_results() {
+        let mut result = SearchResult::new(
+            DocId::new("docs", "guide", 1),
+            "/repo/docs/api/auth.md".to_string(),
+            "Auth".to_string(),
+            String::new(),
+            crate::fusion::ScoreBreakdown {
+                bm25_raw: Some(1.0),
+                vector_raw: None,
+                bm25_normalized: None,
+                vector_normalized: None,
+                fused: 1.0,
+                normalization_method: crate::fusion::NormalizationMethod::MinMax,
+            },
+            crate::fusion::FromSignals::bm25_only(),
+        );
+        result
+            .custom_metadata
+            .insert("lang".to_string(), "rust".to_string());
+
+        let matches = |pairs: &[(&str, &str)]| {
+            let patterns: HashMap<String, String> = pairs
+                .iter()
+                .map(|(key, pattern)| (key.to_string(), pattern.to_string()))
+                .collect();
+            result.matches_metadata(&MetadataFilter::compile(&patterns).unwrap())
+        };
+        // Without a `path` entry the URI stands in for it
+        assert!(matches(&[("path", "docs/api/**"), ("lang", "rust")]));
+        assert!(!matches(&[("path", "docs/guides/**")]));
+        assert!(!matches(&[("lang", "re:^go$")]));
+        assert!(!matches(&[("team", "*")]));
+    }
+}
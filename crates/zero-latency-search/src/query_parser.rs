@@ -0,0 +1,792 @@
+//! Query syntax understood by the search pipeline
+//!
+//! Text in double quotes is a phrase: its words must appear next to each
+//! other, in order, for the BM25 branch to return a document, and results
+//! containing the phrase are boosted during ranking. A single quoted word is
+//! an exact term. `title:`, `heading:` and `path:` scope a term or phrase to
+//! one field, as in `title:installation`. Everything else is free text.
+//!
+//! Queries using `AND`, `OR` or `(...)@N` are boolean queries. Parentheses
+//! group clauses, `AND` binds tighter than `OR`, clauses side by side are
+//! optional, `+` requires a clause, `-` or `NOT` prohibits one, and `@N` after
+//! a group requires at least N of its clauses to match. The BM25 branch
+//! enforces the structure, scoring documents that match more clauses higher;
+//! the vector branch searches the same words without the operators.
+
+use serde::{Deserialize, Serialize};
+use zero_latency_core::{Result, ZeroLatencyError};
+
+/// Most clause combinations a `(...)@N` group may expand to
+const MAX_GROUP_COMBINATIONS: usize = 64;
+
+/// Structure of a query string
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParsedQuery {
+    /// Free-text terms
+    pub terms: Vec<String>,
+    /// Quoted phrases and exact terms, with whitespace collapsed
+    pub phrases: Vec<String>,
+    /// Terms and phrases scoped to a field
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scoped: Vec<ScopedTerm>,
+    /// Boolean structure, for queries using `AND`, `OR` or `(...)@N`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boolean: Option<LexicalClause>,
+}
+
+impl ParsedQuery {
+    /// Parse a query
+    ///
+    /// Malformed boolean syntax is treated as free text here; use
+    /// [`LexicalClause::parse`] to report it.
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = Self::default();
+
+        if let Ok(Some(boolean)) = LexicalClause::parse(query) {
+            boolean.collect(&mut parsed);
+            parsed.boolean = Some(boolean);
+            return parsed;
+        }
+
+        for token in tokenize(query) {
+            if let Some(scoped) = ScopedTerm::parse(token) {
+                parsed.scoped.push(scoped);
+                continue;
+            }
+            match unquote(token) {
+                Some(phrase) => {
+                    let phrase = collapse_whitespace(phrase);
+                    if !phrase.is_empty() && !parsed.phrases.contains(&phrase) {
+                        parsed.phrases.push(phrase);
+                    }
+                }
+                None => parsed.terms.push(token.to_string()),
+            }
+        }
+        parsed
+    }
+
+    pub fn has_phrases(&self) -> bool {
+        !self.phrases.is_empty()
+    }
+
+    /// Whether the query uses phrases, field scopes or boolean operators
+    pub fn is_structured(&self) -> bool {
+        self.has_phrases() || !self.scoped.is_empty() || self.boolean.is_some()
+    }
+
+    /// Lexical query for `text`, enforcing this query's structure
+    ///
+    /// `text` is usually the enhanced query. Phrases and boolean clauses are
+    /// required; the remaining words of `text` are optional.
+    pub fn lexical_query(&self, text: &str) -> String {
+        if let Some(boolean) = &self.boolean {
+            let required = format!("+{}", boolean.render());
+            let optional = boolean.expansions(text).into_iter().map(str::to_string);
+            return std::iter::once(required)
+                .chain(optional)
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+
+        let required = self.phrases.iter().map(|phrase| format!("+\"{}\"", phrase));
+        let optional = tokenize(text)
+            .into_iter()
+            .filter(|token| unquote(token).is_none())
+            .map(str::to_string);
+        required.chain(optional).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Text to embed for `text`: the wanted words, without boolean operators
+    /// or field scopes
+    pub fn semantic_text(&self, text: &str) -> String {
+        let words: Vec<&str> = match &self.boolean {
+            Some(boolean) => self
+                .terms
+                .iter()
+                .chain(&self.phrases)
+                .chain(self.scoped.iter().map(|scoped| &scoped.text))
+                .map(String::as_str)
+                .chain(boolean.expansions(text))
+                .collect(),
+            None if self.scoped.is_empty() => return text.to_string(),
+            None => tokenize(text)
+                .into_iter()
+                .map(|token| match ScopedTerm::parse(token) {
+                    Some(_) => unquote(field_value(token)).unwrap_or(field_value(token)),
+                    None => token,
+                })
+                .collect(),
+        };
+        words.join(" ")
+    }
+
+    /// Number of phrases that occur in `text`
+    ///
+    /// Matching ignores case and punctuation but respects word boundaries,
+    /// so `"error code"` does not match `terror codes`.
+    pub fn phrase_matches(&self, text: &str) -> usize {
+        let text = words(text);
+        self.phrases
+            .iter()
+            .filter(|phrase| contains_words(&text, phrase))
+            .count()
+    }
+}
+
+/// Fields a query term can be scoped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryField {
+    Title,
+    /// Section heading path
+    Heading,
+    /// File path
+    Path,
+}
+
+impl QueryField {
+    pub const ALL: [Self; 3] = [Self::Title, Self::Heading, Self::Path];
+
+    /// Name used in queries and as the lexical index field
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Heading => "heading",
+            Self::Path => "path",
+        }
+    }
+}
+
+/// A term or phrase scoped to a field, as in `title:installation`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScopedTerm {
+    pub field: QueryField,
+    pub text: String,
+}
+
+impl ScopedTerm {
+    /// Parse a `field:text` or `field:"some text"` token
+    pub fn parse(token: &str) -> Option<Self> {
+        let (name, value) = token.split_once(':')?;
+        let field = QueryField::ALL
+            .into_iter()
+            .find(|field| field.name() == name)?;
+        let text = collapse_whitespace(unquote(value).unwrap_or(value));
+        (!text.is_empty()).then_some(Self { field, text })
+    }
+
+    /// Whether `text` contains this term's words, in order
+    pub fn matches(&self, text: &str) -> bool {
+        contains_words(&words(text), &self.text)
+    }
+}
+
+/// Boolean structure of a lexical query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LexicalClause {
+    Term(String),
+    Phrase(String),
+    /// Must match (`+clause`)
+    Required(Box<LexicalClause>),
+    /// Must not match (`-clause`, `NOT clause`)
+    Prohibited(Box<LexicalClause>),
+    /// Every clause must match (`a AND b`)
+    All(Vec<LexicalClause>),
+    /// At least `minimum_should_match` of the unmodified clauses must match
+    /// (`a OR b`, `(a b c)@2`)
+    Any {
+        clauses: Vec<LexicalClause>,
+        minimum_should_match: usize,
+    },
+}
+
+impl LexicalClause {
+    /// Parse the boolean structure of a query
+    ///
+    /// Returns `None` for queries that don't use `AND`, `OR` or `(...)@N`,
+    /// and a validation error for ones that do but are malformed.
+    pub fn parse(query: &str) -> Result<Option<Self>> {
+        let tokens = lex(query);
+        let boolean = tokens
+            .iter()
+            .any(|token| matches!(token, Token::And | Token::Or | Token::Close(Some(_))));
+        if !boolean {
+            return Ok(None);
+        }
+
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let clauses = parser.sequence()?;
+        if parser.position < parser.tokens.len() {
+            return Err(syntax_error("unmatched ')'"));
+        }
+        Ok(Some(any(clauses)))
+    }
+
+    /// The clause in Tantivy's query syntax
+    ///
+    /// Tantivy has no minimum-should-match, so `(a b c)@2` is expanded into
+    /// the combinations satisfying it, `(+((+a +b) (+a +c) (+b +c)))`. A
+    /// document matching more clauses matches more combinations and scores
+    /// higher.
+    pub fn render(&self) -> String {
+        match self {
+            Self::Term(term) => term.clone(),
+            Self::Phrase(phrase) => format!("\"{}\"", phrase),
+            Self::Required(clause) => format!("+{}", clause.render()),
+            Self::Prohibited(clause) => format!("-{}", clause.render()),
+            Self::All(clauses) => group(clauses.iter().map(Self::render_required)),
+            Self::Any {
+                clauses,
+                minimum_should_match: 1,
+            } => group(clauses.iter().map(Self::render)),
+            Self::Any {
+                clauses,
+                minimum_should_match,
+            } => {
+                let (modified, optional): (Vec<_>, Vec<_>) =
+                    clauses.iter().partition(|clause| clause.is_modified());
+                let combinations = combinations(optional.len(), *minimum_should_match)
+                    .into_iter()
+                    .map(|combination| {
+                        group(
+                            combination
+                                .into_iter()
+                                .map(|i| optional[i].render_required()),
+                        )
+                    });
+                let satisfied = format!("+{}", group(combinations));
+                group(
+                    modified
+                        .into_iter()
+                        .map(Self::render)
+                        .chain(std::iter::once(satisfied)),
+                )
+            }
+        }
+    }
+
+    fn render_required(&self) -> String {
+        if self.is_modified() {
+            self.render()
+        } else {
+            format!("+{}", self.render())
+        }
+    }
+
+    fn is_modified(&self) -> bool {
+        matches!(self, Self::Required(_) | Self::Prohibited(_))
+    }
+
+    // Record the terms and phrases asked for, skipping prohibited clauses
+    fn collect(&self, parsed: &mut ParsedQuery) {
+        match self {
+            Self::Term(term) => match ScopedTerm::parse(term) {
+                Some(scoped) => parsed.scoped.push(scoped),
+                None => parsed.terms.push(term.clone()),
+            },
+            Self::Phrase(phrase) => {
+                if !parsed.phrases.contains(phrase) {
+                    parsed.phrases.push(phrase.clone());
+                }
+            }
+            Self::Required(clause) => clause.collect(parsed),
+            Self::Prohibited(_) => {}
+            Self::All(clauses) | Self::Any { clauses, .. } => {
+                clauses.iter().for_each(|clause| clause.collect(parsed))
+            }
+        }
+    }
+
+    fn contains_term(&self, word: &str) -> bool {
+        match self {
+            Self::Term(term) => term == word,
+            Self::Phrase(_) => false,
+            Self::Required(clause) | Self::Prohibited(clause) => clause.contains_term(word),
+            Self::All(clauses) | Self::Any { clauses, .. } => {
+                clauses.iter().any(|clause| clause.contains_term(word))
+            }
+        }
+    }
+
+    // Words of `text` that aren't part of this clause, such as terms added by
+    // query enhancement
+    fn expansions<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        lex(text)
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Word(word) if !self.contains_term(word) => Some(word),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Open,
+    /// Closing parenthesis, with the group's `@N` if given
+    Close(Option<usize>),
+    And,
+    Or,
+    Required,
+    Prohibited,
+    Phrase(&'a str),
+    Word(&'a str),
+}
+
+fn lex(query: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+
+    for token in tokenize(query) {
+        match token {
+            "AND" => tokens.push(Token::And),
+            "OR" => tokens.push(Token::Or),
+            "NOT" => tokens.push(Token::Prohibited),
+            _ => lex_token(token, &mut tokens),
+        }
+    }
+
+    tokens
+}
+
+fn lex_token<'a>(mut rest: &'a str, tokens: &mut Vec<Token<'a>>) {
+    loop {
+        if let Some(after) = rest.strip_prefix('(') {
+            tokens.push(Token::Open);
+            rest = after;
+        } else if rest.len() > 1 && rest.starts_with('+') {
+            tokens.push(Token::Required);
+            rest = &rest[1..];
+        } else if rest.len() > 1 && rest.starts_with('-') {
+            tokens.push(Token::Prohibited);
+            rest = &rest[1..];
+        } else {
+            break;
+        }
+    }
+
+    if let Some(phrase) = unquote(rest) {
+        tokens.push(Token::Phrase(phrase));
+        return;
+    }
+
+    // A word may end in closing parentheses, as in `b)` or `c)@2`
+    let (word, closing) = rest
+        .find(')')
+        .and_then(|index| Some((&rest[..index], closing_tokens(&rest[index..])?)))
+        .unwrap_or((rest, Vec::new()));
+    if !word.is_empty() {
+        tokens.push(Token::Word(word));
+    }
+    tokens.extend(closing);
+}
+
+// `)`, `))@2` and the like, or None if `rest` is anything else
+fn closing_tokens<'a>(mut rest: &str) -> Option<Vec<Token<'a>>> {
+    let mut tokens = Vec::new();
+
+    while !rest.is_empty() {
+        rest = rest.strip_prefix(')')?;
+        let mut minimum = None;
+        if let Some(after) = rest.strip_prefix('@') {
+            let end = after
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len());
+            minimum = Some(after[..end].parse().ok()?);
+            rest = &after[end..];
+        }
+        tokens.push(Token::Close(minimum));
+    }
+
+    Some(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    // Clauses side by side, up to a closing parenthesis or the end
+    fn sequence(&mut self) -> Result<Vec<LexicalClause>> {
+        let mut clauses = Vec::new();
+        while !matches!(self.peek(), None | Some(Token::Close(_))) {
+            clauses.push(self.disjunction()?);
+        }
+        Ok(clauses)
+    }
+
+    fn disjunction(&mut self) -> Result<LexicalClause> {
+        let mut clauses = vec![self.conjunction()?];
+        while self.peek() == Some(Token::Or) {
+            self.position += 1;
+            clauses.push(self.conjunction()?);
+        }
+        Ok(any(clauses))
+    }
+
+    fn conjunction(&mut self) -> Result<LexicalClause> {
+        let mut clauses = vec![self.unary()?];
+        while self.peek() == Some(Token::And) {
+            self.position += 1;
+            clauses.push(self.unary()?);
+        }
+        Ok(if clauses.len() == 1 {
+            clauses.remove(0)
+        } else {
+            LexicalClause::All(clauses)
+        })
+    }
+
+    fn unary(&mut self) -> Result<LexicalClause> {
+        match self.next() {
+            Some(Token::Required) => Ok(LexicalClause::Required(Box::new(self.unary()?))),
+            Some(Token::Prohibited) => Ok(LexicalClause::Prohibited(Box::new(self.unary()?))),
+            Some(Token::Open) => self.group(),
+            Some(Token::Word(word)) => Ok(LexicalClause::Term(word.to_string())),
+            Some(Token::Phrase(phrase)) => match collapse_whitespace(phrase) {
+                phrase if phrase.is_empty() => Err(syntax_error("empty phrase")),
+                phrase => Ok(LexicalClause::Phrase(phrase)),
+            },
+            Some(Token::And | Token::Or) => {
+                Err(syntax_error("AND and OR need a clause on each side"))
+            }
+            Some(Token::Close(_)) | None => Err(syntax_error("expected a term, phrase or group")),
+        }
+    }
+
+    // A parenthesized group, after its opening parenthesis
+    fn group(&mut self) -> Result<LexicalClause> {
+        let clauses = self.sequence()?;
+        let minimum = match self.next() {
+            Some(Token::Close(minimum)) => minimum,
+            _ => return Err(syntax_error("missing ')'")),
+        };
+        if clauses.is_empty() {
+            return Err(syntax_error("empty group"));
+        }
+
+        match minimum {
+            Some(minimum) => with_minimum(any(clauses), minimum),
+            None => Ok(any(clauses)),
+        }
+    }
+}
+
+// Clauses side by side: a single clause stands alone, several are optional
+fn any(mut clauses: Vec<LexicalClause>) -> LexicalClause {
+    if clauses.len() == 1 {
+        clauses.remove(0)
+    } else {
+        LexicalClause::Any {
+            clauses,
+            minimum_should_match: 1,
+        }
+    }
+}
+
+fn with_minimum(clause: LexicalClause, minimum: usize) -> Result<LexicalClause> {
+    let clauses = match clause {
+        LexicalClause::Any { clauses, .. } => clauses,
+        clause => vec![clause],
+    };
+
+    let optional = clauses
+        .iter()
+        .filter(|clause| !clause.is_modified())
+        .count();
+    if minimum == 0 || minimum > optional {
+        return Err(syntax_error(format!(
+            "@{} must be between 1 and the number of optional clauses in its group ({})",
+            minimum, optional
+        )));
+    }
+    if binomial(optional, minimum) > MAX_GROUP_COMBINATIONS {
+        return Err(syntax_error(format!(
+            "group with @{} has too many clause combinations; split it into smaller groups",
+            minimum
+        )));
+    }
+
+    Ok(LexicalClause::Any {
+        clauses,
+        minimum_should_match: minimum,
+    })
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    (0..k).fold(1_usize, |acc, i| acc.saturating_mul(n - i) / (i + 1))
+}
+
+// Every k-element subset of 0..n, as sorted indices
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    (k - 1..n)
+        .flat_map(|last| {
+            combinations(last, k - 1)
+                .into_iter()
+                .map(move |mut combination| {
+                    combination.push(last);
+                    combination
+                })
+        })
+        .collect()
+}
+
+fn group(parts: impl Iterator<Item = String>) -> String {
+    format!("({})", parts.collect::<Vec<_>>().join(" "))
+}
+
+fn syntax_error(message: impl Into<String>) -> ZeroLatencyError {
+    ZeroLatencyError::validation("query", message)
+}
+
+/// Split a query on whitespace, keeping quoted phrases (with their quotes)
+/// together; an unterminated quote runs to the end of the query
+pub(crate) fn tokenize(query: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = query.trim_start();
+
+    while !rest.is_empty() {
+        // Quotes may follow opening parentheses, modifiers and a field name,
+        // as in `(+"a b"` or `title:"a b"`
+        let prefix = rest.len() - rest.trim_start_matches(&['(', '+', '-'][..]).len();
+        let prefix = prefix + field_name_len(&rest[prefix..]);
+        let end = if let Some(quoted) = rest[prefix..].strip_prefix('"') {
+            quoted
+                .find('"')
+                .map(|close| prefix + close + 2)
+                .unwrap_or(rest.len())
+        } else {
+            rest.find(char::is_whitespace).unwrap_or(rest.len())
+        };
+        tokens.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+
+    tokens
+}
+
+// Length of a leading `name:` followed by a quote, or 0
+fn field_name_len(text: &str) -> usize {
+    match text.split_once(':') {
+        Some((name, value))
+            if !name.is_empty()
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                && value.starts_with('"') =>
+        {
+            name.len() + 1
+        }
+        _ => 0,
+    }
+}
+
+// Text after the `field:` of a scoped token
+fn field_value(token: &str) -> &str {
+    token.split_once(':').map_or(token, |(_, value)| value)
+}
+
+// Contents of a quoted token, or None for a bare word
+fn unquote(token: &str) -> Option<&str> {
+    let inner = token.strip_prefix('"')?;
+    Some(inner.strip_suffix('"').unwrap_or(inner))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Whether `phrase`'s words appear in `text`, next to each other and in order
+fn contains_words(text: &[String], phrase: &str) -> bool {
+    let phrase = words(phrase);
+    !phrase.is_empty() && text.windows(phrase.len()).any(|window| window == phrase)
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(word: &str) -> LexicalClause {
+        LexicalClause::Term(word.to_string())
+    }
+
+    #[test]
+    fn test_parse_phrases_and_terms() {
+        let parsed = ParsedQuery::parse(r#"mysql "error  code 1062" duplicate "key"#);
+
+        assert_eq!(parsed.terms, vec!["mysql", "duplicate"]);
+        assert_eq!(parsed.phrases, vec!["error code 1062", "key"]);
+        assert_eq!(tokenize(r#"a "b c" d"#), vec!["a", "\"b c\"", "d"]);
+        assert_eq!(tokenize(r#"(+"b c")@2"#), vec!["(+\"b c\"", ")@2"]);
+        assert!(!ParsedQuery::parse("plain words").has_phrases());
+    }
+
+    #[test]
+    fn test_lexical_query_requires_phrases() {
+        let parsed = ParsedQuery::parse(r#""error code 1062" mysql"#);
+
+        assert_eq!(
+            parsed.lexical_query(r#""error code 1062" mysql database"#),
+            r#"+"error code 1062" mysql database"#
+        );
+    }
+
+    #[test]
+    fn test_phrase_matches_respect_word_boundaries() {
+        let parsed = ParsedQuery::parse(r#""error code 1062" "timeout""#);
+
+        assert_eq!(
+            parsed.phrase_matches("Got Error code 1062: duplicate entry"),
+            1
+        );
+        assert_eq!(parsed.phrase_matches("error 1062 code, then a timeout"), 1);
+        assert_eq!(parsed.phrase_matches("terror code 10620"), 0);
+    }
+
+    #[test]
+    fn test_parse_field_scoped_terms() {
+        let parsed =
+            ParsedQuery::parse(r#"title:installation heading:"quick  start" docker author:me"#);
+
+        assert_eq!(
+            parsed.scoped,
+            vec![
+                ScopedTerm {
+                    field: QueryField::Title,
+                    text: "installation".to_string(),
+                },
+                ScopedTerm {
+                    field: QueryField::Heading,
+                    text: "quick start".to_string(),
+                },
+            ]
+        );
+        assert_eq!(parsed.terms, vec!["docker", "author:me"]);
+        assert!(parsed.is_structured());
+        assert_eq!(
+            parsed.semantic_text(r#"title:installation heading:"quick  start" docker author:me"#),
+            "installation quick  start docker author:me"
+        );
+
+        let parsed = ParsedQuery::parse("path:guides AND (setup OR title:install)");
+        assert_eq!(parsed.terms, vec!["setup"]);
+        assert_eq!(parsed.scoped.len(), 2);
+        assert_eq!(
+            parsed.semantic_text("path:guides AND (setup OR title:install)"),
+            "setup guides install"
+        );
+
+        let scoped = ScopedTerm::parse("path:guides/setup").unwrap();
+        assert!(scoped.matches("/docs/guides/setup.md"));
+        assert!(!scoped.matches("/docs/setup/guides.md"));
+    }
+
+    #[test]
+    fn test_parse_boolean_structure() {
+        let clause = LexicalClause::parse(r#"rust AND (tokio OR "async std") -legacy"#)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            clause,
+            LexicalClause::Any {
+                clauses: vec![
+                    LexicalClause::All(vec![
+                        term("rust"),
+                        LexicalClause::Any {
+                            clauses: vec![
+                                term("tokio"),
+                                LexicalClause::Phrase("async std".to_string()),
+                            ],
+                            minimum_should_match: 1,
+                        },
+                    ]),
+                    LexicalClause::Prohibited(Box::new(term("legacy"))),
+                ],
+                minimum_should_match: 1,
+            }
+        );
+        assert_eq!(clause.render(), r#"((+rust +(tokio "async std")) -legacy)"#);
+
+        // Parentheses alone don't make a boolean query
+        assert_eq!(LexicalClause::parse("call map() on (a b)").unwrap(), None);
+    }
+
+    #[test]
+    fn test_minimum_should_match_expands_to_combinations() {
+        let clause = LexicalClause::parse("(cache index +shard -replica)@2")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(clause.render(), "(+shard -replica +((+cache +index)))");
+
+        let clause = LexicalClause::parse("(a b c)@2 AND d").unwrap().unwrap();
+        assert_eq!(clause.render(), "(+(+((+a +b) (+a +c) (+b +c))) +d)");
+    }
+
+    #[test]
+    fn test_malformed_boolean_queries_are_rejected() {
+        for query in [
+            "a AND",
+            "OR b",
+            "(a OR b",
+            "a OR b)",
+            "(a b)@3",
+            "(a b)@0",
+            "() OR a",
+            r#""" OR a"#,
+        ] {
+            assert!(
+                LexicalClause::parse(query).is_err(),
+                "{} should be rejected",
+                query
+            );
+        }
+
+        let words: Vec<String> = (0..16).map(|i| format!("w{}", i)).collect();
+        let query = format!("({})@8", words.join(" "));
+        assert!(LexicalClause::parse(&query).is_err());
+
+        // Free-text parsing falls back to the words as written
+        assert_eq!(ParsedQuery::parse("a AND").terms, vec!["a", "AND"]);
+    }
+
+    #[test]
+    fn test_boolean_query_keeps_enhancement_terms_optional() {
+        let parsed = ParsedQuery::parse("(timeout OR deadline) AND -retry");
+
+        assert_eq!(parsed.terms, vec!["timeout", "deadline"]);
+        assert_eq!(
+            parsed.lexical_query("(timeout OR deadline) AND -retry latency"),
+            "+(+(timeout deadline) -retry) latency"
+        );
+        assert_eq!(
+            parsed.semantic_text("(timeout OR deadline) AND -retry latency"),
+            "timeout deadline latency"
+        );
+    }
+}
@@ -29,10 +29,24 @@ impl SearchAnalytics for StubSearchAnalytics {
         })
     }
 }
-use crate::{models::*, traits::*};
+use crate::{
+    calibration::ScoreCalibrator,
+    fusion::ResultDeduplicator,
+    intent::{boost_intent_matches, SearchIntent},
+    models::*,
+    query_parser::{LexicalClause, ParsedQuery, QueryField},
+    traits::*,
+};
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::Arc;
-use zero_latency_core::Result;
+use std::time::Instant;
+use zero_latency_core::{values::Score, Result};
+use zero_latency_vector::MetadataFilter;
+
+/// Score added to results that contain every quoted phrase of the query;
+/// results containing only some of them get a proportional share
+const EXACT_MATCH_BOOST: f32 = 0.2;
 
 /// Search pipeline that executes steps in sequence
 pub struct SearchPipeline {
@@ -45,10 +59,23 @@ impl SearchPipeline {
     }
 
     pub async fn execute(&self, request: SearchRequest) -> Result<SearchResponse> {
+        let request = request.with_query_exclusions();
+        // Reject malformed boolean syntax instead of searching it as text
+        LexicalClause::parse(&request.query.raw)?;
+        let metadata_filter = MetadataFilter::compile(&request.filters.metadata_patterns)?
+            .with_ranges(&request.filters.ranges)?;
         let mut context = SearchContext::new(request);
+        context.metadata_filter = metadata_filter;
 
         for step in &self.steps {
+            let recorded = context.metadata.stage_timings.len();
+            let started = Instant::now();
             step.execute(&mut context).await?;
+
+            // Steps that report finer-grained stages themselves are not timed twice
+            if context.metadata.stage_timings.len() == recorded {
+                context.record_stage(step.name(), started.elapsed());
+            }
         }
 
         Ok(context.into_response())
@@ -93,18 +120,30 @@ impl SearchStep for AnalyticsStep {
     }
 
     async fn execute(&self, context: &mut SearchContext) -> Result<()> {
-        // Build a SearchResponse for analytics without consuming context
+        if context.request.options.skip_analytics {
+            return Ok(());
+        }
+
+        // Lend the ranked results to a SearchResponse for analytics and take
+        // them back afterwards instead of cloning every result
+        let mut search_metadata = context.metadata.clone();
+        search_metadata.execution_time = context.elapsed();
         let response = SearchResponse {
-            results: context.ranked_results.clone(),
+            results: std::mem::take(&mut context.ranked_results),
             total_count: None,
-            search_metadata: context.metadata.clone(),
+            search_metadata,
             pagination: None,
+            answer: None,
+            confidence: None,
+            answerable: None,
+            facets: None,
         };
         // Ignore errors from analytics for now
         let _ = self
             .analytics
             .record_search(&context.request, &response)
             .await;
+        context.ranked_results = response.results;
         Ok(())
     }
 }
@@ -115,6 +154,34 @@ impl Default for SearchPipelineBuilder {
     }
 }
 
+/// Step classifying the query's intent and routing the pipeline for it
+///
+/// Runs first, so later steps see the routed options: navigational and code
+/// lookups skip query enhancement, and result ranking boosts the results the
+/// intent is after.
+pub struct QueryIntentStep;
+
+#[async_trait]
+impl SearchStep for QueryIntentStep {
+    fn name(&self) -> &str {
+        "query_intent"
+    }
+
+    async fn execute(&self, context: &mut SearchContext) -> Result<()> {
+        let intent = SearchIntent::classify(&context.request.query.raw);
+        if !intent.expands_query() {
+            context.request.options.enable_query_enhancement = false;
+        }
+        context.metadata.intent = Some(intent);
+        context
+            .metadata
+            .debug_info
+            .get_or_insert_with(HashMap::new)
+            .insert("intent".to_string(), serde_json::json!(intent));
+        Ok(())
+    }
+}
+
 /// Basic query enhancement step
 pub struct QueryEnhancementStep {
     enhancer: Arc<dyn QueryEnhancer>,
@@ -133,6 +200,10 @@ impl SearchStep for QueryEnhancementStep {
     }
 
     async fn execute(&self, context: &mut SearchContext) -> Result<()> {
+        if !context.request.options.enable_query_enhancement {
+            return Ok(());
+        }
+
         let enhanced = self.enhancer.enhance(&context.request.query.raw).await?;
         context.set_enhanced_query(enhanced);
         Ok(())
@@ -157,13 +228,120 @@ impl SearchStep for ResultRankingStep {
     }
 
     async fn execute(&self, context: &mut SearchContext) -> Result<()> {
-        let ranked_results = self.ranker.rank(context.raw_results.clone()).await?;
+        // Raw results are not read after ranking, so hand them over without cloning
+        let mut raw_results = std::mem::take(&mut context.raw_results);
+
+        // Only the top results are reranked; the tail keeps its retrieval order
+        let tail = match context.request.options.rerank_depth {
+            Some(depth) if depth < raw_results.len() => raw_results.split_off(depth),
+            _ => Vec::new(),
+        };
+
+        let mut ranked_results = self.ranker.rank(raw_results).await?;
+        boost_exact_matches(&context.parsed_query, &mut ranked_results);
+        if let Some(intent) = context.metadata.intent {
+            boost_intent_matches(intent, &context.request.query.raw, &mut ranked_results);
+        }
+        ranked_results.extend(tail);
         context.set_ranked_results(ranked_results);
         context.metadata.ranking_method = "multi_factor".to_string();
         Ok(())
     }
 }
 
+/// Step calibrating final scores against the collection's background distribution
+pub struct ScoreCalibrationStep {
+    calibrator: Arc<ScoreCalibrator>,
+}
+
+impl ScoreCalibrationStep {
+    pub fn new(calibrator: Arc<ScoreCalibrator>) -> Self {
+        Self { calibrator }
+    }
+}
+
+#[async_trait]
+impl SearchStep for ScoreCalibrationStep {
+    fn name(&self) -> &str {
+        "score_calibration"
+    }
+
+    async fn execute(&self, context: &mut SearchContext) -> Result<()> {
+        let Some(collection) = context.request.filters.custom.get("collection") else {
+            return Ok(());
+        };
+        // Internal searches such as warm-up replays would skew the background
+        if !context.request.options.skip_analytics {
+            self.calibrator.observe(collection, &context.ranked_results);
+        }
+        if self.calibrator.calibrate(collection, &mut context.ranked_results) {
+            context.metadata.ranking_method.push_str("+calibrated");
+        }
+        Ok(())
+    }
+}
+
+/// Step collapsing near-identical results from mirrored sources
+pub struct DeduplicationStep {
+    deduplicator: Arc<ResultDeduplicator>,
+}
+
+impl DeduplicationStep {
+    pub fn new(deduplicator: Arc<ResultDeduplicator>) -> Self {
+        Self { deduplicator }
+    }
+}
+
+#[async_trait]
+impl SearchStep for DeduplicationStep {
+    fn name(&self) -> &str {
+        "deduplication"
+    }
+
+    async fn execute(&self, context: &mut SearchContext) -> Result<()> {
+        if self.deduplicator.collapse_mirrors(&mut context.ranked_results) > 0 {
+            context.metadata.ranking_method.push_str("+dedup");
+        }
+        Ok(())
+    }
+}
+
+// Favour results containing the query's quoted phrases verbatim, or its
+// field-scoped terms in the named field
+fn boost_exact_matches(parsed_query: &ParsedQuery, results: &mut [SearchResult]) {
+    let clauses = parsed_query.phrases.len() + parsed_query.scoped.len();
+    if clauses == 0 {
+        return;
+    }
+
+    for result in results.iter_mut() {
+        let phrases = parsed_query
+            .phrase_matches(&result.content)
+            .max(parsed_query.phrase_matches(&result.title));
+        let scoped = parsed_query
+            .scoped
+            .iter()
+            .filter(|scoped| scoped.matches(&field_text(result, scoped.field)))
+            .count();
+        let matched = phrases + scoped;
+        if matched > 0 {
+            let boosted =
+                result.final_score.value() + EXACT_MATCH_BOOST * matched as f32 / clauses as f32;
+            result.final_score = Score::new(boosted.min(1.0)).unwrap_or(result.final_score);
+        }
+    }
+
+    results.sort_by(|a, b| b.final_score.value().total_cmp(&a.final_score.value()));
+}
+
+fn field_text(result: &SearchResult, field: QueryField) -> String {
+    match field {
+        QueryField::Title => result.title.clone(),
+        QueryField::Heading => result.section_path.join(" "),
+        QueryField::Path => result.uri.clone(),
+    }
+}
+
 /// Simple search orchestrator implementation
 pub struct SimpleSearchOrchestrator {
     pipeline: SearchPipeline,
@@ -181,3 +359,237 @@ impl SearchOrchestrator for SimpleSearchOrchestrator {
         self.pipeline.execute(request).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    struct NamedStep(&'static str);
+
+    #[async_trait]
+    impl SearchStep for NamedStep {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        async fn execute(&self, _context: &mut SearchContext) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct StagedStep;
+
+    #[async_trait]
+    impl SearchStep for StagedStep {
+        fn name(&self) -> &str {
+            "staged"
+        }
+
+        async fn execute(&self, context: &mut SearchContext) -> Result<()> {
+            let mut branch = context.branch();
+            branch.record_stage("embedding", Duration::from_millis(3));
+            context.merge_stage_timings(&branch);
+            context.record_stage("fusion", Duration::from_millis(1));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_records_stage_timings() {
+        let pipeline = SearchPipeline::builder()
+            .add_step(Box::new(NamedStep("query_enhancement")))
+            .add_step(Box::new(StagedStep))
+            .add_step(Box::new(NamedStep("result_ranking")))
+            .build();
+
+        let response = pipeline.execute(SearchRequest::new("tokio")).await.unwrap();
+        let timings = &response.search_metadata.stage_timings;
+
+        let stages: Vec<&str> = timings.iter().map(|t| t.stage.as_str()).collect();
+        // The staged step reports its own stages instead of a "staged" total
+        assert_eq!(
+            stages,
+            ["query_enhancement", "embedding", "fusion", "result_ranking"]
+        );
+        assert_eq!(timings[1].duration_ms, 3.0);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_boolean_query_is_rejected() {
+        let pipeline = SearchPipeline::builder()
+            .add_step(Box::new(NamedStep("vector_search")))
+            .build();
+
+        let error = pipeline
+            .execute(SearchRequest::new("(timeout OR deadline"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            zero_latency_core::ZeroLatencyError::Validation { .. }
+        ));
+
+        let response = pipeline
+            .execute(SearchRequest::new("(timeout OR deadline)"))
+            .await
+            .unwrap();
+        let debug_info = response.search_metadata.debug_info.unwrap();
+        assert_eq!(
+            debug_info["parsed_query"]["terms"],
+            serde_json::json!(["timeout", "deadline"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_metadata_pattern_is_rejected() {
+        let pipeline = SearchPipeline::builder()
+            .add_step(Box::new(NamedStep("vector_search")))
+            .build();
+
+        let mut filters = SearchFilters::default();
+        filters
+            .metadata_patterns
+            .insert("path".to_string(), "re:docs/(api".to_string());
+        let error = pipeline
+            .execute(SearchRequest::new("auth").with_filters(filters))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            zero_latency_core::ZeroLatencyError::Validation { .. }
+        ));
+    }
+
+    struct ReversingRanker;
+
+    #[async_trait]
+    impl ResultRanker for ReversingRanker {
+        async fn rank(&self, mut results: Vec<SearchResult>) -> Result<Vec<SearchResult>> {
+            results.reverse();
+            Ok(results)
+        }
+
+        async fn explain_ranking(&self, _result: &SearchResult) -> Result<RankingSignals> {
+            unimplemented!()
+        }
+    }
+
+    struct FailingEnhancer;
+
+    #[async_trait]
+    impl QueryEnhancer for FailingEnhancer {
+        async fn enhance(&self, _query: &str) -> Result<EnhancedQuery> {
+            panic!("query enhancement should be skipped")
+        }
+
+        async fn analyze(&self, _query: &str) -> Result<QueryAnalysis> {
+            unimplemented!()
+        }
+    }
+
+    fn result(title: &str) -> SearchResult {
+        SearchResult::new(
+            zero_latency_core::DocId::new("docs", title, 1),
+            title.to_string(),
+            title.to_string(),
+            String::new(),
+            crate::fusion::ScoreBreakdown {
+                bm25_raw: None,
+                vector_raw: None,
+                bm25_normalized: None,
+                vector_normalized: None,
+                fused: 0.5,
+                normalization_method: crate::fusion::NormalizationMethod::MinMax,
+            },
+            crate::fusion::FromSignals::vector_only(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_ranking_respects_rerank_depth() {
+        let step = ResultRankingStep::new(Arc::new(ReversingRanker));
+        let settings = SearchSettings {
+            rerank_depth: Some(2),
+            ..Default::default()
+        };
+        let mut context = SearchContext::new(SearchRequest::new("q").with_settings(&settings));
+        context.set_raw_results(["a", "b", "c", "d"].into_iter().map(result).collect());
+
+        step.execute(&mut context).await.unwrap();
+
+        let titles: Vec<&str> = context
+            .ranked_results
+            .iter()
+            .map(|r| r.title.as_str())
+            .collect();
+        assert_eq!(titles, ["b", "a", "c", "d"]);
+    }
+
+    #[tokio::test]
+    async fn test_ranking_boosts_exact_phrase_matches() {
+        let step = ResultRankingStep::new(Arc::new(ReversingRanker));
+        let mut context = SearchContext::new(SearchRequest::new(r#""error code 1062" mysql"#));
+        let mut exact = result("exact");
+        exact.content = "MySQL error code 1062: duplicate entry".to_string();
+        context.set_raw_results(vec![exact, result("other")]);
+
+        step.execute(&mut context).await.unwrap();
+
+        assert_eq!(context.ranked_results[0].title, "exact");
+        assert!((context.ranked_results[0].final_score.value() - 0.7).abs() < 1e-6);
+        assert_eq!(context.ranked_results[1].final_score.value(), 0.5);
+
+        let debug_info = context.metadata.debug_info.as_ref().unwrap();
+        assert_eq!(
+            debug_info["parsed_query"]["phrases"],
+            serde_json::json!(["error code 1062"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ranking_boosts_field_scoped_matches() {
+        let step = ResultRankingStep::new(Arc::new(ReversingRanker));
+        let mut context = SearchContext::new(SearchRequest::new("title:installation"));
+        let mut body = result("Configuration");
+        body.content = "Installation is covered elsewhere".to_string();
+        context.set_raw_results(vec![result("Installation guide"), body]);
+
+        step.execute(&mut context).await.unwrap();
+
+        assert_eq!(context.ranked_results[0].title, "Installation guide");
+        assert!(context.ranked_results[0].final_score.value() > 0.5);
+        assert_eq!(context.ranked_results[1].final_score.value(), 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_query_enhancement_can_be_disabled() {
+        let step = QueryEnhancementStep::new(Arc::new(FailingEnhancer));
+        let settings = SearchSettings {
+            query_expansion: Some(false),
+            ..Default::default()
+        };
+        let mut context = SearchContext::new(SearchRequest::new("q").with_settings(&settings));
+
+        step.execute(&mut context).await.unwrap();
+
+        assert!(context.enhanced_query.is_none());
+        assert!(!context.metadata.query_enhancement_applied);
+    }
+
+    #[tokio::test]
+    async fn test_code_lookups_skip_query_enhancement() {
+        let mut context = SearchContext::new(SearchRequest::new("DOC_INDEXER_PORT"));
+
+        QueryIntentStep.execute(&mut context).await.unwrap();
+        QueryEnhancementStep::new(Arc::new(FailingEnhancer))
+            .execute(&mut context)
+            .await
+            .unwrap();
+
+        assert_eq!(context.metadata.intent, Some(SearchIntent::CodeLookup));
+        assert!(context.enhanced_query.is_none());
+        let debug_info = context.metadata.debug_info.as_ref().unwrap();
+        assert_eq!(debug_info["intent"], "code_lookup");
+    }
+}
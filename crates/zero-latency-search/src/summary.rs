@@ -0,0 +1,334 @@
+//! Source-attributed search summaries
+//!
+//! A [`Summarizer`] condenses the top results of a search into a few bullets.
+//! Every bullet cites the results it was drawn from as `[n]`, where `n` is
+//! the 1-based position of the result among the sources it was given, so a
+//! chatbot can show the digest and link each claim back to a document.
+//!
+//! [`ExtractiveSummarizer`] is the built-in local summarizer. It needs no
+//! model: each bullet is the sentence of a source that best covers the query,
+//! and sources that say the same thing share a bullet. Model-backed
+//! summarizers plug in through the same trait and read their output with
+//! [`parse_bullets`].
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use zero_latency_core::Result;
+
+use crate::answer::{content_terms, sentences};
+use crate::models::SearchResult;
+
+/// One line of a digest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummaryBullet {
+    pub text: String,
+    /// 1-based positions of the cited sources, ascending
+    pub citations: Vec<usize>,
+}
+
+/// Summarizes ranked search results
+#[async_trait]
+pub trait Summarizer: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// At most `max_bullets` bullets about `query` drawn from `sources`,
+    /// which are ordered best first. Every bullet cites at least one source.
+    async fn summarize(
+        &self,
+        query: &str,
+        sources: &[SearchResult],
+        max_bullets: usize,
+    ) -> Result<Vec<SummaryBullet>>;
+}
+
+/// Render bullets as a Markdown list with `[n]` citation markers
+pub fn render_summary(bullets: &[SummaryBullet]) -> String {
+    bullets
+        .iter()
+        .map(|bullet| {
+            let citations: String = bullet
+                .citations
+                .iter()
+                .map(|citation| format!("[{}]", citation))
+                .collect();
+            format!("- {} {}", bullet.text, citations)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Read bullets with `[n]` citation markers from generated text
+///
+/// Each non-empty line is a bullet; list markers are dropped. Markers may
+/// cite several sources (`[1][3]` or `[1, 3]`). Citations outside
+/// `1..=source_count` are discarded, and so are bullets left without any,
+/// so a digest never points at a result that wasn't returned.
+pub fn parse_bullets(text: &str, source_count: usize, max_bullets: usize) -> Vec<SummaryBullet> {
+    let mut bullets = Vec::new();
+    for line in text.lines() {
+        let line = strip_list_marker(line.trim());
+        let mut citations = Vec::new();
+        let mut prose = String::new();
+        let mut rest = line;
+        while let Some(open) = rest.find('[') {
+            let Some(close) = rest[open..].find(']').map(|close| open + close) else {
+                break;
+            };
+            let inner = &rest[open + 1..close];
+            let cited: Option<Vec<usize>> = inner
+                .split(',')
+                .map(|part| part.trim().parse().ok())
+                .collect();
+            prose.push_str(&rest[..open]);
+            match cited {
+                Some(cited) => {
+                    prose.truncate(prose.trim_end().len());
+                    citations.extend(cited);
+                }
+                // Not a citation marker, e.g. a Markdown link
+                None => prose.push_str(&rest[open..=close]),
+            }
+            rest = &rest[close + 1..];
+        }
+        prose.push_str(rest);
+
+        citations.retain(|citation| (1..=source_count).contains(citation));
+        citations.sort_unstable();
+        citations.dedup();
+        let text = prose.split_whitespace().collect::<Vec<_>>().join(" ");
+        let text = text.trim_end_matches([' ', ',', ';']).to_string();
+        if text.is_empty() || citations.is_empty() {
+            continue;
+        }
+        bullets.push(SummaryBullet { text, citations });
+        if bullets.len() == max_bullets {
+            break;
+        }
+    }
+    bullets
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    let unbulleted = line.trim_start_matches(['-', '*', '•']);
+    if unbulleted.len() != line.len() {
+        return unbulleted.trim_start();
+    }
+    // Numbered items: "1." or "1)"
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    match line[digits..].chars().next() {
+        Some('.' | ')') if digits > 0 => line[digits + 1..].trim_start(),
+        _ => line,
+    }
+}
+
+/// Local summarizer quoting the most relevant sentence of each source
+#[derive(Debug, Clone)]
+pub struct ExtractiveSummarizer {
+    /// Longer sentences are cut at a word boundary
+    pub max_bullet_chars: usize,
+}
+
+impl Default for ExtractiveSummarizer {
+    fn default() -> Self {
+        Self {
+            max_bullet_chars: 300,
+        }
+    }
+}
+
+impl ExtractiveSummarizer {
+    /// The sentence covering most query terms, or the lead sentence when
+    /// none mentions them
+    fn key_sentence<'a>(&self, query_terms: &HashSet<String>, content: &'a str) -> Option<&'a str> {
+        let spans = sentences(content);
+        let mut best: Option<(usize, &str)> = None;
+        for &(start, end) in &spans {
+            let sentence = &content[start..end];
+            let matched = content_terms(sentence)
+                .collect::<HashSet<_>>()
+                .intersection(query_terms)
+                .count();
+            if matched > best.map_or(0, |(best_matched, _)| best_matched) {
+                best = Some((matched, sentence));
+            }
+        }
+        best.map(|(_, sentence)| sentence)
+            .or_else(|| spans.first().map(|&(start, end)| &content[start..end]))
+    }
+
+    fn build(
+        &self,
+        query: &str,
+        sources: &[SearchResult],
+        max_bullets: usize,
+    ) -> Vec<SummaryBullet> {
+        let query_terms: HashSet<String> = content_terms(query).collect();
+        let mut bullets: Vec<SummaryBullet> = Vec::new();
+        let mut seen: Vec<String> = Vec::new();
+        for (index, source) in sources.iter().enumerate() {
+            let Some(sentence) = self.key_sentence(&query_terms, &source.content) else {
+                continue;
+            };
+            let text = truncate(sentence, self.max_bullet_chars);
+            let key = text.to_lowercase();
+            if let Some(existing) = seen.iter().position(|seen| *seen == key) {
+                // Same sentence in another result: cite it on the existing bullet
+                bullets[existing].citations.push(index + 1);
+            } else if bullets.len() < max_bullets {
+                seen.push(key);
+                bullets.push(SummaryBullet {
+                    text,
+                    citations: vec![index + 1],
+                });
+            }
+        }
+        bullets
+    }
+}
+
+#[async_trait]
+impl Summarizer for ExtractiveSummarizer {
+    fn name(&self) -> &str {
+        "extractive"
+    }
+
+    async fn summarize(
+        &self,
+        query: &str,
+        sources: &[SearchResult],
+        max_bullets: usize,
+    ) -> Result<Vec<SummaryBullet>> {
+        Ok(self.build(query, sources, max_bullets))
+    }
+}
+
+/// Collapse whitespace and cut the text at a word boundary
+fn truncate(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > max_chars / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fusion::{FromSignals, NormalizationMethod, ScoreBreakdown};
+    use zero_latency_core::DocId;
+
+    fn source(content: &str) -> SearchResult {
+        SearchResult::new(
+            DocId::new("default", "guide", 1),
+            "docs/guide.md".to_string(),
+            "Guide".to_string(),
+            content.to_string(),
+            ScoreBreakdown {
+                bm25_raw: None,
+                vector_raw: Some(0.5),
+                bm25_normalized: None,
+                vector_normalized: Some(0.5),
+                fused: 0.5,
+                normalization_method: NormalizationMethod::MinMax,
+            },
+            FromSignals::vector_only(),
+        )
+    }
+
+    #[test]
+    fn test_parse_bullets() {
+        let text = "Here is a summary:\n\
+            - Keys rotate every 90 days [1].\n\
+            * Rotation is manual [2][3]\n\
+            2. See the [runbook](ops.md) for details [1, 4]\n\
+            - Unsupported claim\n\
+            - Cites a missing source [7]";
+        let bullets = parse_bullets(text, 4, 10);
+        assert_eq!(
+            bullets,
+            vec![
+                SummaryBullet {
+                    text: "Keys rotate every 90 days.".to_string(),
+                    citations: vec![1],
+                },
+                SummaryBullet {
+                    text: "Rotation is manual".to_string(),
+                    citations: vec![2, 3],
+                },
+                SummaryBullet {
+                    text: "See the [runbook](ops.md) for details".to_string(),
+                    citations: vec![1, 4],
+                },
+            ]
+        );
+        assert_eq!(parse_bullets(text, 4, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_render_summary() {
+        let bullets = vec![
+            SummaryBullet {
+                text: "Keys rotate every 90 days.".to_string(),
+                citations: vec![1, 2],
+            },
+            SummaryBullet {
+                text: "Rotation is manual.".to_string(),
+                citations: vec![3],
+            },
+        ];
+        let rendered = render_summary(&bullets);
+        assert_eq!(
+            rendered,
+            "- Keys rotate every 90 days. [1][2]\n- Rotation is manual. [3]"
+        );
+        assert_eq!(parse_bullets(&rendered, 3, 10), bullets);
+    }
+
+    #[tokio::test]
+    async fn test_extractive_summary_cites_sources() {
+        let summarizer = ExtractiveSummarizer::default();
+        let sources = vec![
+            source("Deployment overview. API keys are rotated every 90 days by the ops team."),
+            source("Changelog for 2.1."),
+            source("API keys are rotated every 90 days by the ops team."),
+            source("Rotated keys stay valid for a one day grace period. Contact ops."),
+        ];
+
+        let bullets = summarizer
+            .summarize("how often are api keys rotated", &sources, 5)
+            .await
+            .unwrap();
+        assert_eq!(bullets.len(), 3);
+        assert_eq!(
+            bullets[0].text,
+            "API keys are rotated every 90 days by the ops team."
+        );
+        assert_eq!(bullets[0].citations, vec![1, 3]);
+        // No sentence mentions the query, so the lead sentence stands in
+        assert_eq!(bullets[1].text, "Changelog for 2.1.");
+        assert_eq!(bullets[2].citations, vec![4]);
+
+        let capped = summarizer
+            .summarize("api key rotation", &sources, 1)
+            .await
+            .unwrap();
+        assert_eq!(capped.len(), 1);
+        assert_eq!(capped[0].citations, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate(" a\n b ", 10), "a b");
+        let long = "word ".repeat(100);
+        let cut = truncate(&long, 50);
+        assert!(cut.ends_with('…'));
+        assert!(cut.chars().count() <= 51);
+    }
+}
@@ -0,0 +1,191 @@
+//! User-supplied output templates
+//!
+//! Search results and webhook payloads can be rendered through a template
+//! instead of a built-in format, e.g. into a Markdown digest or Slack
+//! blocks, without a post-processing script. Templates use Jinja2 syntax
+//! via MiniJinja. Output is not HTML-escaped: use the `tojson` filter to
+//! embed strings in JSON, and `truncate` to shorten long text.
+
+use minijinja::{AutoEscape, Environment, UndefinedBehavior};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use crate::models::{LineRange, SearchResponse};
+
+/// Name the template is registered under, as shown in render errors
+const TEMPLATE_NAME: &str = "output";
+
+/// A compiled output template
+pub struct OutputTemplate {
+    env: Environment<'static>,
+}
+
+impl std::fmt::Debug for OutputTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutputTemplate").finish_non_exhaustive()
+    }
+}
+
+impl OutputTemplate {
+    /// Compile a template, rejecting syntax errors
+    pub fn new(source: impl Into<String>) -> Result<Self> {
+        let mut env = Environment::new();
+        env.set_auto_escape_callback(|_| AutoEscape::None);
+        // A misspelled variable should fail loudly, not render as nothing
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        env.add_filter("truncate", truncate);
+        env.add_template_owned(TEMPLATE_NAME, source.into())
+            .map_err(|e| ZeroLatencyError::validation("template", describe(&e)))?;
+        Ok(Self { env })
+    }
+
+    /// Read and compile a template file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            ZeroLatencyError::validation(
+                "template",
+                format!("cannot read {}: {}", path.display(), e),
+            )
+        })?;
+        Self::new(source)
+    }
+
+    /// Render the template with `context` as its variables
+    pub fn render(&self, context: &impl Serialize) -> Result<String> {
+        self.env
+            .get_template(TEMPLATE_NAME)
+            .and_then(|template| template.render(context))
+            .map_err(|e| ZeroLatencyError::validation("template", describe(&e)))
+    }
+}
+
+/// Error message with the line it points at
+fn describe(error: &minijinja::Error) -> String {
+    match error.line() {
+        Some(line) => format!("{} (line {})", error, line),
+        None => error.to_string(),
+    }
+}
+
+/// `text|truncate(n)`: at most `n` characters, ending in `…` when cut
+fn truncate(text: String, length: Option<usize>) -> String {
+    let length = length.unwrap_or(255);
+    if text.chars().count() <= length {
+        return text;
+    }
+    let mut cut: String = text.chars().take(length.saturating_sub(1)).collect();
+    cut.truncate(cut.trim_end().len());
+    cut.push('…');
+    cut
+}
+
+/// Variables a search result template sees
+///
+/// `query`, `total` and `results`, plus `answer` when one was extracted.
+/// Each result has `rank` (from 1), `title`, `path`, `uri`, `url`,
+/// `collection`, `score`, `snippet`, `content`, `section_path`, `lines`
+/// (with `start` and `end`), `anchor` and `metadata`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultsContext<'a> {
+    pub query: &'a str,
+    pub total: usize,
+    pub results: Vec<ResultContext<'a>>,
+    pub answer: Option<&'a str>,
+}
+
+/// One result as a template sees it
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultContext<'a> {
+    pub rank: usize,
+    pub title: &'a str,
+    pub path: &'a str,
+    pub uri: &'a str,
+    pub url: Option<&'a str>,
+    pub collection: Option<&'a str>,
+    pub score: f32,
+    /// The extracted snippet, or the content when there is none
+    pub snippet: &'a str,
+    pub content: &'a str,
+    pub section_path: &'a [String],
+    pub lines: Option<LineRange>,
+    pub anchor: Option<&'a str>,
+    pub metadata: &'a HashMap<String, String>,
+}
+
+impl<'a> ResultsContext<'a> {
+    pub fn new(query: &'a str, response: &'a SearchResponse) -> Self {
+        let results = response
+            .results
+            .iter()
+            .enumerate()
+            .map(|(index, result)| ResultContext {
+                rank: index + 1,
+                title: &result.title,
+                path: &result.document_path,
+                uri: &result.uri,
+                url: result.url.as_deref(),
+                collection: result.collection.as_deref(),
+                score: result.final_score.value(),
+                snippet: result.snippet.as_deref().unwrap_or(&result.content),
+                content: &result.content,
+                section_path: &result.section_path,
+                lines: result.lines,
+                anchor: result.anchor.as_deref(),
+                metadata: &result.custom_metadata,
+            })
+            .collect();
+        Self {
+            query,
+            total: response.total_count.unwrap_or(response.results.len()),
+            results,
+            answer: response.answer.as_ref().map(|answer| answer.text.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_renders_context() {
+        let template = OutputTemplate::new(
+            "{% for r in results %}{{ r.rank }}. {{ r.title }} ({{ r.path }})\n{% endfor %}",
+        )
+        .unwrap();
+        let rendered = template
+            .render(&json!({
+                "results": [
+                    { "rank": 1, "title": "Keys & tokens", "path": "a.md" },
+                    { "rank": 2, "title": "Limits", "path": "b.md" }
+                ]
+            }))
+            .unwrap();
+        // Nothing is HTML-escaped
+        assert_eq!(rendered, "1. Keys & tokens (a.md)\n2. Limits (b.md)\n");
+    }
+
+    #[test]
+    fn test_json_and_truncate_filters() {
+        let template = OutputTemplate::new(r#"{"text": {{ text|truncate(8)|tojson }}}"#).unwrap();
+        let rendered = template
+            .render(&json!({ "text": "say \"hello\" world" }))
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(parsed["text"], "say \"he…");
+        assert_eq!(truncate("short".to_string(), Some(8)), "short");
+    }
+
+    #[test]
+    fn test_reports_errors() {
+        let error = OutputTemplate::new("{% for r in results %}").unwrap_err();
+        assert!(matches!(error, ZeroLatencyError::Validation { .. }));
+
+        // Unknown variables fail instead of rendering empty
+        let template = OutputTemplate::new("{{ reslts }}").unwrap();
+        assert!(template.render(&json!({ "results": [] })).is_err());
+    }
+}
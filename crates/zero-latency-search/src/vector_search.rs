@@ -1,8 +1,17 @@
+use crate::cache::{
+    CacheNamespace, CacheStatistics, CollectionCache, CollectionCacheStatistics,
+    CollectionCounters, EmbeddingCacheConfig, LRUCache, TenantResolver, DEFAULT_TENANT,
+};
+use crate::fusion::{
+    fuse_vector_spaces, FromSignals, NormalizationMethod, ScoreBreakdown, VectorSpaceResults,
+};
 use crate::{models::*, traits::*};
 use async_trait::async_trait;
 use std::sync::Arc;
-use zero_latency_core::{Result, DocId};
-use zero_latency_vector::VectorRepository;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use zero_latency_core::{DocId, Result, ZeroLatencyError};
+use zero_latency_vector::{SimilarityResult, VectorRepository};
 
 /// Vector search step that queries the vector database
 pub struct VectorSearchStep {
@@ -30,144 +39,88 @@ impl SearchStep for VectorSearchStep {
 
     async fn execute(&self, context: &mut SearchContext) -> Result<()> {
         // Use enhanced query if available, otherwise fall back to original query
-        let query_text = if let Some(ref enhanced) = context.enhanced_query {
-            println!(
-                "🔍 VectorSearchStep: Using enhanced query: '{}'",
-                enhanced.enhanced
-            );
-            &enhanced.enhanced
-        } else {
-            println!(
-                "🔍 VectorSearchStep: Using original query: '{}'",
-                context.request.query.raw
-            );
-            &context.request.query.raw
+        let query_text = match &context.enhanced_query {
+            Some(enhanced) => enhanced.enhanced.as_str(),
+            None => context.request.query.raw.as_str(),
         };
+        // Boolean operators carry no meaning for the embedding model
+        let query_text = context.parsed_query.semantic_text(query_text);
 
-        println!(
-            "🔍 VectorSearchStep: Generating embedding for query: '{}'",
+        tracing::debug!(
+            "VectorSearchStep: generating embedding for query '{}'",
             query_text
         );
+        let started = Instant::now();
+        let collection_name = context.request.filters.custom.get("collection").cloned();
         let query_embedding = self
             .embedding_service
-            .generate_embedding(query_text)
+            .generate_embedding_for(&query_text, collection_name.as_deref())
             .await?;
-        println!(
-            "✅ VectorSearchStep: Generated embedding with {} dimensions",
-            query_embedding.len()
-        );
-
-        // Search the vector database
-        println!(
-            "🔍 VectorSearchStep: Searching vector database with limit {}",
-            context.request.limit
-        );
+        context.record_stage("embedding", started.elapsed());
+        let started = Instant::now();
 
-        // Check if collection filter is specified
-        let vector_results =
-            if let Some(collection_name) = context.request.filters.custom.get("collection") {
-                println!(
-                    "🔍 VectorSearchStep: Searching in collection: '{}'",
-                    collection_name
-                );
-                self.vector_repo
-                    .search_in_collection(collection_name, query_embedding, context.request.limit)
-                    .await?
-            } else {
-                println!("🔍 VectorSearchStep: Searching across all collections");
+        // Scope to the requested collection and let the store apply metadata patterns
+        let exclusions = &context.request.filters.exclude;
+        let fetch_limit = context.request.filters.fetch_limit(context.request.limit);
+        let vector_results = match context.request.options.vector_spaces.as_deref() {
+            // Each named space is searched with the same query vector, then fused
+            Some(spaces) if !spaces.is_empty() => {
+                let mut sets = Vec::with_capacity(spaces.len());
+                for space in spaces.iter().filter(|space| space.weight > 0.0) {
+                    let results = self
+                        .vector_repo
+                        .search_named(
+                            collection_name.as_deref(),
+                            &space.name,
+                            query_embedding.clone(),
+                            fetch_limit,
+                            &context.metadata_filter,
+                        )
+                        .await?;
+                    sets.push(VectorSpaceResults {
+                        space: space.clone(),
+                        results,
+                    });
+                }
+                fuse_vector_spaces(sets, fetch_limit)
+            }
+            _ => {
                 self.vector_repo
-                    .search(query_embedding, context.request.limit)
+                    .search_filtered(
+                        collection_name.as_deref(),
+                        query_embedding,
+                        fetch_limit,
+                        &context.metadata_filter,
+                    )
                     .await?
-            };
-        println!(
-            "📊 VectorSearchStep: Found {} vector results",
-            vector_results.len()
+            }
+        };
+        tracing::debug!(
+            "VectorSearchStep: found {} vector results (limit {})",
+            vector_results.len(),
+            context.request.limit
         );
 
-        if vector_results.is_empty() {
-            println!("⚠️  VectorSearchStep: No results found from vector database!");
-        } else {
-            for (i, result) in vector_results.iter().take(3).enumerate() {
-                println!(
-                    "📄 VectorSearchStep: Result {}: similarity={:.4}, doc_id={}",
-                    i, result.similarity, result.document_id
-                );
+        // Convert vector results to search results, moving their metadata
+        let mut search_results: Vec<SearchResult> =
+            vector_results.into_iter().map(SearchResult::from).collect();
+
+        // Drop excluded results before they reach fusion
+        let excluded = exclusions.apply(&mut search_results);
+        if excluded > 0 {
+            tracing::debug!("VectorSearchStep: excluded {} results", excluded);
+        }
+        if let Some(principal) = &context.request.filters.access {
+            let denied = principal.apply(&mut search_results);
+            if denied > 0 {
+                tracing::debug!("VectorSearchStep: withheld {} restricted results", denied);
             }
         }
-
-        // Convert vector results to search results
-        let search_results: Vec<SearchResult> = vector_results
-            .into_iter()
-            .map(|result| {
-                use crate::fusion::{ScoreBreakdown, FromSignals, NormalizationMethod};
-                
-                // Create a DocId from the result
-                let doc_id = DocId::new(
-                    result.metadata.collection.as_deref().unwrap_or("default"),
-                    &result.document_id.to_string(),
-                    1,
-                );
-                
-                // Create score breakdown for vector-only search
-                let similarity_f32 = result.similarity.value();
-                let scores = ScoreBreakdown {
-                    bm25_raw: None,
-                    vector_raw: Some(similarity_f32),
-                    bm25_normalized: None,
-                    vector_normalized: Some(similarity_f32), // Already normalized in most vector DBs
-                    fused: similarity_f32,
-                    normalization_method: NormalizationMethod::MinMax,
-                };
-                
-                // Create from_signals tracking
-                let from_signals = FromSignals::vector_only();
-                
-                let uri = result
-                    .metadata
-                    .custom
-                    .get("path")
-                    .cloned()
-                    .unwrap_or_else(|| format!("doc:{}", result.document_id));
-                
-                let title = result.metadata.title.clone();
-                let content = result.metadata.content.clone();
-                
-                SearchResult {
-                    doc_id,
-                    chunk_id: uuid::Uuid::new_v4(),
-                    document_id: result.document_id, // Legacy compatibility
-                    uri: uri.clone(),
-                    title: title.clone(),
-                    document_path: uri, // Legacy compatibility
-                    content: content.clone(),
-                    snippet: Some({
-                        // Create a snippet (first 200 characters)
-                        if content.len() > 200 {
-                            format!("{}...", &content[..200])
-                        } else {
-                            content.clone()
-                        }
-                    }),
-                    section_path: Vec::new(),
-                    heading_path: result.metadata.heading_path.clone(), // Legacy compatibility
-                    scores,
-                    final_score: result.similarity, // Legacy compatibility
-                    from_signals,
-                    ranking_signals: None,
-                    url: result.metadata.url.clone(),
-                    collection: result.metadata.collection.clone(),
-                    custom_metadata: result.metadata.custom.clone(),
-                }
-            })
-            .collect();
-
-        println!(
-            "🎯 VectorSearchStep: Converted to {} search results",
-            search_results.len()
-        );
+        search_results.truncate(context.request.limit);
 
         // Set the results in context
         context.set_raw_results(search_results);
+        context.record_stage("vector_search", started.elapsed());
         context
             .metadata
             .result_sources
@@ -177,7 +130,380 @@ impl SearchStep for VectorSearchStep {
     }
 }
 
+/// Maximum snippet length in bytes for vector search results
+const SNIPPET_LENGTH: usize = 200;
+
+impl From<SimilarityResult> for SearchResult {
+    fn from(result: SimilarityResult) -> Self {
+        let SimilarityResult {
+            document_id,
+            similarity,
+            metadata,
+        } = result;
+
+        // Create a DocId from the result
+        let doc_id = DocId::new(
+            metadata.collection.as_deref().unwrap_or("default"),
+            document_id.to_string(),
+            1,
+        );
+
+        // Create score breakdown for vector-only search
+        let similarity_f32 = similarity.value();
+        let scores = ScoreBreakdown {
+            bm25_raw: None,
+            vector_raw: Some(similarity_f32),
+            bm25_normalized: None,
+            vector_normalized: Some(similarity_f32), // Already normalized in most vector DBs
+            fused: similarity_f32,
+            normalization_method: NormalizationMethod::MinMax,
+        };
+
+        let uri = metadata
+            .custom
+            .get("path")
+            .cloned()
+            .unwrap_or_else(|| format!("doc:{}", document_id));
+        let snippet = snippet(&metadata.content, SNIPPET_LENGTH);
+
+        SearchResult {
+            doc_id,
+            chunk_id: uuid::Uuid::new_v4(),
+            document_id,                // Legacy compatibility
+            document_path: uri.clone(), // Legacy compatibility
+            uri,
+            title: metadata.title,
+            content: metadata.content,
+            snippet: Some(snippet),
+            section_path: Vec::new(),
+            heading_path: metadata.heading_path, // Legacy compatibility
+            scores,
+            final_score: similarity, // Legacy compatibility
+            from_signals: FromSignals::vector_only(),
+            ranking_signals: None,
+            url: metadata.url,
+            lines: LineRange::from_metadata(&metadata.custom),
+            anchor: metadata.custom.get(ANCHOR_KEY).cloned(),
+            collection: metadata.collection,
+            custom_metadata: metadata.custom,
+        }
+    }
+}
+
+/// Truncate content to at most `max_len` bytes on a character boundary
+fn snippet(content: &str, max_len: usize) -> String {
+    if content.len() <= max_len {
+        return content.to_string();
+    }
+
+    let mut end = max_len;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &content[..end])
+}
+
 #[async_trait]
 pub trait EmbeddingService: Send + Sync {
     async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Embed `text` for a search scoped to `collection`; caching services
+    /// keep each collection's embeddings apart
+    async fn generate_embedding_for(&self, text: &str, collection: Option<&str>) -> Result<Vec<f32>> {
+        let _ = collection;
+        self.generate_embedding(text).await
+    }
+}
+
+/// Embedding service that remembers the embeddings of recent query texts
+///
+/// Repeated queries, including those replayed by an index warm-up, skip the
+/// embedding model entirely. When the model is unreachable, the failure is
+/// remembered briefly too, so a typeahead retyping the same query doesn't
+/// send every keystroke to a service that is already failing. Embeddings are
+/// kept per tenant and collection; failures say nothing about either and are
+/// shared.
+pub struct CachedEmbeddingService {
+    inner: Arc<dyn EmbeddingService>,
+    cache: Mutex<LRUCache<(CacheNamespace, String), Vec<f32>>>,
+    /// Messages of recent transient failures, by text; None when disabled
+    failures: Option<Mutex<LRUCache<String, String>>>,
+    tenants: Option<Arc<dyn TenantResolver>>,
+    statistics: Mutex<CacheStatistics>,
+    collections: CollectionCounters,
+}
+
+impl CachedEmbeddingService {
+    pub fn new(inner: Arc<dyn EmbeddingService>, config: &EmbeddingCacheConfig) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(
+                LRUCache::new(
+                    config.max_entries,
+                    config.max_size_mb * 1024 * 1024,
+                    Duration::from_secs(config.ttl_seconds),
+                )
+                .with_eviction_policy(config.eviction_policy),
+            ),
+            failures: (config.failure_ttl_seconds > 0).then(|| {
+                Mutex::new(LRUCache::new(
+                    config.max_entries,
+                    usize::MAX,
+                    Duration::from_secs(config.failure_ttl_seconds),
+                ))
+            }),
+            tenants: None,
+            statistics: Mutex::new(CacheStatistics::new()),
+            collections: CollectionCounters::new(),
+        }
+    }
+
+    /// Keep each tenant's embeddings apart, as named by `tenants`
+    pub fn with_tenant_resolver(mut self, tenants: Arc<dyn TenantResolver>) -> Self {
+        self.tenants = Some(tenants);
+        self
+    }
+
+    /// Hits, misses and current size of the cache
+    pub async fn statistics(&self) -> CacheStatistics {
+        let mut statistics = self.statistics.lock().await.clone();
+        let cache = self.cache.lock().await;
+        statistics.size = cache.size();
+        statistics.memory_usage = cache.memory_usage();
+        statistics
+    }
+
+    /// Estimated bytes held by cached embeddings
+    pub async fn memory_usage(&self) -> usize {
+        self.cache.lock().await.memory_usage()
+    }
+
+    /// Evict the least recently used `fraction` of the cached embeddings;
+    /// returns how many were evicted
+    pub async fn shrink(&self, fraction: f64) -> usize {
+        let evicted = self.cache.lock().await.shrink(fraction);
+        self.statistics.lock().await.evictions += evicted as u64;
+        evicted
+    }
+
+    /// Number of cached embeddings
+    pub async fn len(&self) -> usize {
+        self.cache.lock().await.size()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+#[async_trait]
+impl EmbeddingService for CachedEmbeddingService {
+    async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        self.generate_embedding_for(text, None).await
+    }
+
+    async fn generate_embedding_for(&self, text: &str, collection: Option<&str>) -> Result<Vec<f32>> {
+        let tenant = self
+            .tenants
+            .as_ref()
+            .map_or_else(|| DEFAULT_TENANT.to_string(), |tenants| tenants.current_tenant());
+        let key = (
+            CacheNamespace::new(tenant, collection.map(str::to_string)),
+            text.to_string(),
+        );
+        if let Some(embedding) = self.cache.lock().await.get(&key) {
+            self.statistics.lock().await.record_hit();
+            self.collections.record_hit(collection);
+            return Ok(embedding);
+        }
+        if let Some(failures) = &self.failures {
+            if let Some(message) = failures.lock().await.get(&text.to_string()) {
+                return Err(ZeroLatencyError::external_service(
+                    "embedding",
+                    format!("{} (failed moments ago; not retried yet)", message),
+                ));
+            }
+        }
+        self.statistics.lock().await.record_miss();
+        self.collections.record_miss(collection);
+
+        // Generate without holding the lock so other queries are not blocked
+        let embedding = match self.inner.generate_embedding(text).await {
+            Ok(embedding) => embedding,
+            Err(e) => {
+                // Invalid input fails the same way every time and is cheap to
+                // reject; only failures of the model itself are worth remembering
+                let transient = matches!(
+                    e,
+                    ZeroLatencyError::ExternalService { .. } | ZeroLatencyError::Network { .. }
+                );
+                if let Some(failures) = self.failures.as_ref().filter(|_| transient) {
+                    let message = e.to_string();
+                    failures
+                        .lock()
+                        .await
+                        .insert(text.to_string(), message.clone(), message.len());
+                }
+                return Err(e);
+            }
+        };
+        let size_bytes = embedding.len() * std::mem::size_of::<f32>();
+        self.cache
+            .lock()
+            .await
+            .insert(key, embedding.clone(), size_bytes);
+        Ok(embedding)
+    }
+}
+
+#[async_trait]
+impl CollectionCache for CachedEmbeddingService {
+    async fn collection_statistics(&self, collection: &str) -> CollectionCacheStatistics {
+        let (entries, memory_bytes) = self
+            .cache
+            .lock()
+            .await
+            .usage_where(|(namespace, _)| namespace.is_scoped_to(collection));
+        CollectionCacheStatistics::new(entries, memory_bytes, self.collections.get(collection))
+    }
+
+    async fn purge_collection(&self, collection: &str) -> usize {
+        self.collections.reset(collection);
+        self.cache
+            .lock()
+            .await
+            .remove_where(|(namespace, _)| namespace.covers(collection))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zero_latency_core::{values::Score, Uuid};
+    use zero_latency_vector::VectorMetadata;
+
+    #[test]
+    fn test_search_result_from_similarity_result() {
+        let mut custom = std::collections::HashMap::new();
+        custom.insert("path".to_string(), "/docs/guide.md".to_string());
+        custom.insert(LINE_START_KEY.to_string(), "12".to_string());
+        custom.insert(LINE_END_KEY.to_string(), "30".to_string());
+        custom.insert(ANCHOR_KEY.to_string(), "intro".to_string());
+
+        let result = SearchResult::from(SimilarityResult {
+            document_id: Uuid::new_v4(),
+            similarity: Score::new(0.75).unwrap(),
+            metadata: VectorMetadata {
+                document_id: Uuid::new_v4(),
+                chunk_index: 0,
+                content: format!("a{}", "é".repeat(150)),
+                title: "Guide".to_string(),
+                heading_path: vec!["Intro".to_string()],
+                url: None,
+                collection: Some("docs".to_string()),
+                custom,
+                ..Default::default()
+            },
+        });
+
+        assert_eq!(result.uri, "/docs/guide.md");
+        assert_eq!(result.document_path, "/docs/guide.md");
+        assert_eq!(result.title, "Guide");
+        assert_eq!(result.heading_path, vec!["Intro".to_string()]);
+        assert_eq!(result.lines, Some(LineRange { start: 12, end: 30 }));
+        assert_eq!(result.anchor.as_deref(), Some("intro"));
+        assert_eq!(result.collection.as_deref(), Some("docs"));
+        assert_eq!(result.scores.fused, 0.75);
+
+        // Byte 200 falls inside a two-byte character, so the snippet stops before it
+        let snippet = result.snippet.unwrap();
+        assert_eq!(snippet, format!("a{}...", "é".repeat(99)));
+    }
+
+    #[tokio::test]
+    async fn test_cached_embedding_service_reuses_embeddings() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct CountingEmbedder(AtomicUsize);
+
+        #[async_trait]
+        impl EmbeddingService for CountingEmbedder {
+            async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(vec![text.len() as f32])
+            }
+        }
+
+        let inner = Arc::new(CountingEmbedder(AtomicUsize::new(0)));
+        let cached = CachedEmbeddingService::new(
+            inner.clone(),
+            &crate::cache::CacheConfig::default().embedding_cache,
+        );
+
+        assert_eq!(cached.generate_embedding("rust").await.unwrap(), vec![4.0]);
+        assert_eq!(cached.generate_embedding("rust").await.unwrap(), vec![4.0]);
+        assert_eq!(cached.generate_embedding("go").await.unwrap(), vec![2.0]);
+        assert_eq!(inner.0.load(Ordering::SeqCst), 2);
+        assert_eq!(cached.len().await, 2);
+
+        let statistics = cached.statistics().await;
+        assert_eq!((statistics.hits, statistics.misses), (1, 2));
+        assert_eq!(statistics.size, 2);
+        assert_eq!(statistics.memory_usage, 2 * std::mem::size_of::<f32>());
+
+        // Shrinking drops the least recently used embedding first
+        assert_eq!(cached.shrink(0.5).await, 1);
+        assert_eq!(cached.memory_usage().await, std::mem::size_of::<f32>());
+        cached.generate_embedding("go").await.unwrap();
+        assert_eq!(inner.0.load(Ordering::SeqCst), 2);
+        assert_eq!(cached.statistics().await.evictions, 1);
+
+        // Each collection embeds for itself, and can be purged on its own
+        cached.generate_embedding_for("go", Some("docs")).await.unwrap();
+        cached.generate_embedding_for("go", Some("docs")).await.unwrap();
+        assert_eq!(inner.0.load(Ordering::SeqCst), 3);
+        let docs = cached.collection_statistics("docs").await;
+        assert_eq!((docs.entries, docs.hits, docs.misses), (1, 1, 1));
+        assert_eq!(cached.purge_collection("docs").await, 2);
+        assert!(cached.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_cached_embedding_service_remembers_transient_failures() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct FailingEmbedder(AtomicUsize);
+
+        #[async_trait]
+        impl EmbeddingService for FailingEmbedder {
+            async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                if text.is_empty() {
+                    return Err(ZeroLatencyError::validation("text", "is empty"));
+                }
+                Err(ZeroLatencyError::network("connection refused"))
+            }
+        }
+
+        let inner = Arc::new(FailingEmbedder(AtomicUsize::new(0)));
+        let mut config = crate::cache::CacheConfig::default().embedding_cache;
+        let cached = CachedEmbeddingService::new(inner.clone(), &config);
+
+        assert!(cached.generate_embedding("rust").await.is_err());
+        let error = cached.generate_embedding("rust").await.unwrap_err();
+        assert!(error.to_string().contains("connection refused"));
+        assert_eq!(inner.0.load(Ordering::SeqCst), 1);
+        assert!(cached.is_empty().await);
+
+        // Invalid input isn't transient and reaches the model every time
+        assert!(cached.generate_embedding("").await.is_err());
+        assert!(cached.generate_embedding("").await.is_err());
+        assert_eq!(inner.0.load(Ordering::SeqCst), 3);
+
+        config.failure_ttl_seconds = 0;
+        let uncached = CachedEmbeddingService::new(inner.clone(), &config);
+        assert!(uncached.generate_embedding("rust").await.is_err());
+        assert!(uncached.generate_embedding("rust").await.is_err());
+        assert_eq!(inner.0.load(Ordering::SeqCst), 5);
+    }
 }
@@ -0,0 +1,25 @@
+/// Integration test fixtures for Zero-Latency
+///
+/// This crate provides everything needed to test against a real doc-indexer
+/// without spawning a binary:
+/// - An in-process server on a random port with a temporary data directory
+/// - Deterministic mock embeddings, so no model or API key is needed
+///
+/// ```no_run
+/// # async fn example() -> zero_latency_core::Result<()> {
+/// use zero_latency_test_utils::TestServer;
+///
+/// let server = TestServer::builder()
+///     .with_document("guide.md", "# Guide\n\nTokio is an async runtime.")
+///     .start()
+///     .await?;
+///
+/// server.index("docs").await?;
+/// let results = server.search("async runtime", "docs").await?;
+/// assert!(!results["results"].as_array().unwrap().is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub mod server;
+
+pub use server::{TestServer, TestServerBuilder};
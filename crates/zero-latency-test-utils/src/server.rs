@@ -0,0 +1,368 @@
+use doc_indexer::application::ServiceContainer;
+use doc_indexer::config::{Config, EmbeddingProvider, VectorBackend};
+use doc_indexer::infrastructure::HttpServer;
+use serde_json::{json, Value};
+/// In-process doc-indexer server fixture
+///
+/// Starts the real HTTP router on an ephemeral localhost port, backed by a
+/// temporary data directory and the mock embedding provider. The server runs
+/// on the caller's tokio runtime and stops when the fixture is dropped.
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use zero_latency_core::{Result, ZeroLatencyError};
+use zero_latency_vector::EmbeddingGenerator;
+
+/// Embedding dimension used unless overridden; matches the default collection size
+const DEFAULT_DIMENSION: usize = 384;
+
+type ConfigHook = Box<dyn FnOnce(&mut Config) + Send>;
+
+/// Builder for [`TestServer`]
+pub struct TestServerBuilder {
+    dimension: usize,
+    seed: u64,
+    backend: VectorBackend,
+    embedding_generator: Option<Arc<dyn EmbeddingGenerator>>,
+    documents: Vec<(PathBuf, String)>,
+    configure: Vec<ConfigHook>,
+}
+
+impl TestServerBuilder {
+    fn new() -> Self {
+        Self {
+            dimension: DEFAULT_DIMENSION,
+            seed: 0,
+            backend: VectorBackend::Embedded,
+            embedding_generator: None,
+            documents: Vec::new(),
+            configure: Vec::new(),
+        }
+    }
+
+    /// Set the embedding dimension
+    pub fn with_dimension(mut self, dimension: usize) -> Self {
+        self.dimension = dimension;
+        self
+    }
+
+    /// Set the seed of the mock embedding provider
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Replace the mock embedding provider with a custom generator
+    ///
+    /// The generator's dimension should match [`Self::with_dimension`].
+    pub fn with_embedding_generator(mut self, generator: Arc<dyn EmbeddingGenerator>) -> Self {
+        self.embedding_generator = Some(generator);
+        self
+    }
+
+    /// Use the in-memory vector store instead of the embedded SQLite store
+    pub fn with_memory_store(mut self) -> Self {
+        self.backend = VectorBackend::Memory;
+        self
+    }
+
+    /// Write a document into the docs directory before the server starts
+    pub fn with_document(
+        mut self,
+        relative_path: impl Into<PathBuf>,
+        content: impl Into<String>,
+    ) -> Self {
+        self.documents.push((relative_path.into(), content.into()));
+        self
+    }
+
+    /// Adjust the service configuration after the fixture defaults are applied
+    pub fn with_config(mut self, configure: impl FnOnce(&mut Config) + Send + 'static) -> Self {
+        self.configure.push(Box::new(configure));
+        self
+    }
+
+    /// Start the server and wait until it accepts connections
+    pub async fn start(self) -> Result<TestServer> {
+        let data_dir = tempfile::tempdir()
+            .map_err(|e| ZeroLatencyError::io(format!("Failed to create data dir: {}", e)))?;
+        let docs_dir = data_dir.path().join("docs");
+
+        for (relative_path, content) in &self.documents {
+            let path = docs_dir.join(relative_path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    ZeroLatencyError::io(format!("Failed to create {}: {}", parent.display(), e))
+                })?;
+            }
+            std::fs::write(&path, content).map_err(|e| {
+                ZeroLatencyError::io(format!("Failed to write {}: {}", path.display(), e))
+            })?;
+        }
+        std::fs::create_dir_all(&docs_dir)
+            .map_err(|e| ZeroLatencyError::io(format!("Failed to create docs dir: {}", e)))?;
+
+        let mut config = Config::default();
+        config.server.host = "127.0.0.1".to_string();
+        config.server.port = 0;
+        config.vector.backend = self.backend;
+        config.vector.embedded.db_path = data_dir.path().join("vectors.db");
+        config.vector.embedded.dimension = self.dimension;
+        config.embedding.provider = EmbeddingProvider::Mock;
+        config.embedding.mock.dimension = self.dimension;
+        config.embedding.mock.seed = self.seed;
+        config.service.docs_path = docs_dir.clone();
+        for configure in self.configure {
+            configure(&mut config);
+        }
+
+        let container = match self.embedding_generator {
+            Some(generator) => {
+                ServiceContainer::with_embedding_generator(config.clone(), generator).await?
+            }
+            None => ServiceContainer::new(config.clone()).await?,
+        };
+        let container = Arc::new(container);
+
+        let server = HttpServer::new(config.server.clone(), container.clone())
+            .await
+            .map_err(|e| {
+                ZeroLatencyError::internal(format!("Failed to create HTTP server: {}", e))
+            })?;
+
+        // Bind before spawning so the port is known and accepting immediately
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .map_err(|e| ZeroLatencyError::network(format!("Failed to bind test port: {}", e)))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| ZeroLatencyError::network(format!("Failed to read test port: {}", e)))?;
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let handle = tokio::spawn(async move {
+            let shutdown = async {
+                let _ = shutdown_rx.await;
+            };
+            if let Err(e) = server.serve(listener, shutdown).await {
+                tracing::error!("Test server error: {}", e);
+            }
+        });
+
+        Ok(TestServer {
+            addr,
+            data_dir,
+            docs_dir,
+            container,
+            client: reqwest::Client::new(),
+            shutdown: Some(shutdown_tx),
+            handle: Some(handle),
+        })
+    }
+}
+
+/// A doc-indexer server running inside the test process
+pub struct TestServer {
+    addr: SocketAddr,
+    data_dir: TempDir,
+    docs_dir: PathBuf,
+    container: Arc<ServiceContainer>,
+    client: reqwest::Client,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl TestServer {
+    /// Start a server with default settings and an empty docs directory
+    pub async fn start() -> Result<Self> {
+        Self::builder().start().await
+    }
+
+    /// Create a builder for a customized server
+    pub fn builder() -> TestServerBuilder {
+        TestServerBuilder::new()
+    }
+
+    /// Address the server is listening on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Base URL, e.g. `http://127.0.0.1:40123`
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Absolute URL for an API path
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url(), path)
+    }
+
+    /// Temporary directory holding the vector database and documents
+    pub fn data_dir(&self) -> &Path {
+        self.data_dir.path()
+    }
+
+    /// Directory populated by [`TestServerBuilder::with_document`]
+    pub fn docs_dir(&self) -> &Path {
+        &self.docs_dir
+    }
+
+    /// Service container behind the server, for direct inspection
+    pub fn container(&self) -> Arc<ServiceContainer> {
+        self.container.clone()
+    }
+
+    /// HTTP client for issuing requests against the server
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// POST a JSON body and return the JSON response, failing on non-2xx status
+    pub async fn post_json(&self, path: &str, body: &Value) -> Result<Value> {
+        let response = self
+            .client
+            .post(self.url(path))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| ZeroLatencyError::network(format!("POST {} failed: {}", path, e)))?;
+        Self::json_body(path, response).await
+    }
+
+    /// GET a path and return the JSON response, failing on non-2xx status
+    pub async fn get_json(&self, path: &str) -> Result<Value> {
+        let response = self
+            .client
+            .get(self.url(path))
+            .send()
+            .await
+            .map_err(|e| ZeroLatencyError::network(format!("GET {} failed: {}", path, e)))?;
+        Self::json_body(path, response).await
+    }
+
+    /// Index the docs directory into a collection
+    pub async fn index(&self, collection: &str) -> Result<Value> {
+        self.post_json(
+            "/api/index",
+            &json!({
+                "path": self.docs_dir.to_string_lossy(),
+                "collection": collection,
+            }),
+        )
+        .await
+    }
+
+    /// Search a collection
+    pub async fn search(&self, query: &str, collection: &str) -> Result<Value> {
+        self.post_json(
+            "/api/search",
+            &json!({
+                "query": query,
+                "limit": 10,
+                "filters": { "collection_name": collection },
+            }),
+        )
+        .await
+    }
+
+    /// Stop the server and wait for in-flight requests to finish
+    pub async fn shutdown(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.await;
+        }
+    }
+
+    async fn json_body(path: &str, response: reqwest::Response) -> Result<Value> {
+        let status = response.status();
+        let body = response.text().await.map_err(|e| {
+            ZeroLatencyError::network(format!("Reading {} response failed: {}", path, e))
+        })?;
+
+        if !status.is_success() {
+            return Err(ZeroLatencyError::external_service(
+                "doc-indexer",
+                format!("{} returned {}: {}", path, status, body),
+            ));
+        }
+
+        serde_json::from_str(&body).map_err(|e| {
+            ZeroLatencyError::serialization(format!("Invalid JSON from {}: {}", path, e))
+        })
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_server_starts_on_random_port() {
+        let first = TestServer::start().await.unwrap();
+        let second = TestServer::builder()
+            .with_memory_store()
+            .start()
+            .await
+            .unwrap();
+
+        assert_ne!(first.addr().port(), 0);
+        assert_ne!(first.addr(), second.addr());
+        assert_ne!(first.data_dir(), second.data_dir());
+
+        assert_eq!(
+            first.container().embedding_generator().model_name(),
+            "mock-hash-embedding"
+        );
+
+        let health = first.get_json("/health").await.unwrap();
+        assert!(health.is_object());
+
+        first.shutdown().await;
+        second.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_index_and_search_round_trip() {
+        let server = TestServer::builder()
+            .with_document(
+                "runtime.md",
+                "# Async runtimes\n\nTokio is an async runtime that schedules futures.",
+            )
+            .with_document(
+                "baking/bread.md",
+                "# Bread\n\nBake the loaf at two hundred degrees until golden.",
+            )
+            .start()
+            .await
+            .unwrap();
+
+        let indexed = server.index("fixture_docs").await.unwrap();
+        assert_eq!(indexed["documents_processed"], 2);
+
+        let results = server
+            .search("tokio async runtime", "fixture_docs")
+            .await
+            .unwrap();
+        let results = results["results"].as_array().unwrap();
+        assert!(!results.is_empty());
+        assert!(results[0]["content"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("Tokio"));
+
+        server.shutdown().await;
+    }
+}
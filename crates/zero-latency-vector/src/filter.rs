@@ -0,0 +1,339 @@
+use crate::models::VectorMetadata;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use zero_latency_core::{Result, ZeroLatencyError};
+
+/// Metadata key holding a document's source path
+pub const PATH_KEY: &str = "path";
+
+/// Prefix marking a pattern as a regular expression rather than a glob
+pub const REGEX_PREFIX: &str = "re:";
+
+/// How many candidates to fetch per requested result when a store cannot
+/// evaluate a filter natively and has to post-filter
+pub const FILTER_OVERFETCH: usize = 4;
+
+/// How a single metadata value is matched
+#[derive(Debug, Clone)]
+enum ValueMatcher {
+    /// Whole-value equality, which every store can evaluate natively
+    Exact(String),
+    Pattern(Regex),
+    Range(NumericRange),
+}
+
+/// Inclusive bounds on a numeric metadata value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct NumericRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gte: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lte: Option<f64>,
+}
+
+impl NumericRange {
+    pub fn contains(&self, value: f64) -> bool {
+        self.gte.is_none_or(|min| value >= min) && self.lte.is_none_or(|max| value <= max)
+    }
+}
+
+/// Read a metadata value as a number
+///
+/// Metadata is stored as strings, so numbers are recognised when filtering.
+/// A leading `v` is ignored so `v2` and `2` compare alike.
+pub fn parse_numeric(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix(['v', 'V'])
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(value);
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
+
+/// A pattern on one metadata key
+#[derive(Debug, Clone)]
+pub struct MetadataCondition {
+    key: String,
+    matcher: ValueMatcher,
+}
+
+impl MetadataCondition {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The value to match exactly, if this condition is a plain equality
+    pub fn exact_value(&self) -> Option<&str> {
+        match &self.matcher {
+            ValueMatcher::Exact(value) => Some(value),
+            ValueMatcher::Pattern(_) | ValueMatcher::Range(_) => None,
+        }
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        match &self.matcher {
+            ValueMatcher::Exact(expected) => value == expected,
+            ValueMatcher::Pattern(regex) => regex.is_match(value),
+            ValueMatcher::Range(range) => parse_numeric(value).is_some_and(|n| range.contains(n)),
+        }
+    }
+
+    /// The numeric bounds, if this condition is a range
+    pub fn range(&self) -> Option<&NumericRange> {
+        match &self.matcher {
+            ValueMatcher::Range(range) => Some(range),
+            _ => None,
+        }
+    }
+}
+
+/// Glob, regex and range conditions on document metadata, compiled once per
+/// request
+///
+/// Values are globs (`docs/api/**`, `v1.*`) or, with a `re:` prefix, regular
+/// expressions. Globs on `path` match at any directory boundary so relative
+/// patterns work against absolute paths; globs on other keys must match the
+/// whole value. Ranges only match values that read as numbers. A document
+/// passes only if every condition matches.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataFilter {
+    conditions: Vec<MetadataCondition>,
+}
+
+impl MetadataFilter {
+    /// Compile `key → pattern` pairs, rejecting malformed regexes
+    pub fn compile<'a, I>(patterns: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (&'a String, &'a String)>,
+    {
+        let mut conditions = patterns
+            .into_iter()
+            .map(|(key, pattern)| {
+                Ok(MetadataCondition {
+                    key: key.clone(),
+                    matcher: compile_pattern(key, pattern)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // Stable order keeps pushed-down store filters deterministic
+        conditions.sort_by(|a, b| a.key.cmp(&b.key));
+
+        Ok(Self { conditions })
+    }
+
+    /// Add numeric range conditions, rejecting empty or inverted bounds
+    pub fn with_ranges<'a, I>(mut self, ranges: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (&'a String, &'a NumericRange)>,
+    {
+        for (key, range) in ranges {
+            let field = format!("filters.ranges.{}", key);
+            let bounds = [range.gte, range.lte];
+            if bounds.iter().all(Option::is_none) {
+                return Err(ZeroLatencyError::validation(field, "set gte, lte or both"));
+            }
+            if bounds.iter().flatten().any(|bound| !bound.is_finite()) {
+                return Err(ZeroLatencyError::validation(field, "bounds must be finite"));
+            }
+            if let (Some(min), Some(max)) = (range.gte, range.lte) {
+                if min > max {
+                    return Err(ZeroLatencyError::validation(
+                        field,
+                        "gte is greater than lte",
+                    ));
+                }
+            }
+            self.conditions.push(MetadataCondition {
+                key: key.clone(),
+                matcher: ValueMatcher::Range(*range),
+            });
+        }
+        self.conditions.sort_by(|a, b| a.key.cmp(&b.key));
+
+        Ok(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    pub fn conditions(&self) -> &[MetadataCondition] {
+        &self.conditions
+    }
+
+    /// Whether every condition is a plain equality a store can evaluate
+    pub fn is_exact(&self) -> bool {
+        self.conditions.iter().all(|c| c.exact_value().is_some())
+    }
+
+    /// How many candidates to fetch to fill `k` results after post-filtering
+    pub fn fetch_limit(&self, k: usize) -> usize {
+        if self.is_empty() {
+            k
+        } else {
+            k.saturating_mul(FILTER_OVERFETCH)
+        }
+    }
+
+    /// Check the filter against values looked up by key; missing keys fail
+    pub fn matches<'a>(&self, lookup: impl Fn(&str) -> Option<&'a str>) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| lookup(&condition.key).is_some_and(|v| condition.matches(v)))
+    }
+
+    pub fn matches_metadata(&self, metadata: &VectorMetadata) -> bool {
+        self.matches(|key| metadata.custom.get(key).map(String::as_str))
+    }
+}
+
+fn compile_pattern(key: &str, pattern: &str) -> Result<ValueMatcher> {
+    let invalid = |message: String| {
+        ZeroLatencyError::validation(format!("filters.metadata_patterns.{}", key), message)
+    };
+
+    if let Some(expression) = pattern.strip_prefix(REGEX_PREFIX) {
+        return Regex::new(expression)
+            .map(ValueMatcher::Pattern)
+            .map_err(|e| invalid(format!("invalid regex '{}': {}", expression, e)));
+    }
+    if pattern.is_empty() {
+        return Err(invalid("pattern must not be empty".to_string()));
+    }
+
+    let is_glob = pattern.contains(['*', '?']);
+    if !is_glob && key != PATH_KEY {
+        return Ok(ValueMatcher::Exact(pattern.to_string()));
+    }
+
+    let anchor = if key == PATH_KEY { "(?:^|/)" } else { "^" };
+    let pattern = pattern.strip_prefix("./").unwrap_or(pattern);
+    Regex::new(&format!("{}{}$", anchor, glob_to_regex(pattern)))
+        .map(ValueMatcher::Pattern)
+        .map_err(|e| invalid(format!("invalid glob '{}': {}", pattern, e)))
+}
+
+/// Translate a glob into a regex body: `**` crosses directories, `*` and `?`
+/// stay within one path segment
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::with_capacity(glob.len() * 2);
+    let mut rest = glob;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(tail) = rest.strip_prefix("**/") {
+            regex.push_str("(?:.*/)?");
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("**") {
+            regex.push_str(".*");
+            rest = tail;
+        } else {
+            match c {
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                _ => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn filter(pairs: &[(&str, &str)]) -> Result<MetadataFilter> {
+        let patterns: HashMap<String, String> = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        MetadataFilter::compile(&patterns)
+    }
+
+    fn path_matches(pattern: &str, path: &str) -> bool {
+        filter(&[(PATH_KEY, pattern)])
+            .unwrap()
+            .matches(|_| Some(path))
+    }
+
+    #[test]
+    fn test_path_globs_match_at_directory_boundaries() {
+        assert!(path_matches("docs/api/**", "/repo/docs/api/v1/auth.md"));
+        assert!(path_matches("docs/api/**", "docs/api/index.md"));
+        assert!(!path_matches("docs/api/**", "/repo/mydocs/api/index.md"));
+        assert!(!path_matches("docs/api/**", "/repo/docs/guides/api.md"));
+
+        assert!(path_matches("docs/*.md", "/repo/docs/intro.md"));
+        assert!(!path_matches("docs/*.md", "/repo/docs/api/auth.md"));
+        assert!(path_matches("**/auth.md", "/repo/docs/api/auth.md"));
+        assert!(path_matches("./docs/intro.md", "/repo/docs/intro.md"));
+        assert!(!path_matches("docs/intro.md", "/repo/docs/intro.mdx"));
+    }
+
+    #[test]
+    fn test_custom_values_match_whole_value() {
+        let filter = filter(&[("version", "v1.*"), ("lang", "re:^(rust|go)$")]).unwrap();
+        let metadata = |version: &'static str, lang: &'static str| {
+            move |key: &str| match key {
+                "version" => Some(version),
+                "lang" => Some(lang),
+                _ => None,
+            }
+        };
+
+        assert!(filter.matches(metadata("v1.2", "rust")));
+        assert!(!filter.matches(metadata("v10", "rust")));
+        assert!(!filter.matches(metadata("v1.2", "python")));
+        assert!(!filter.matches(|_| None));
+    }
+
+    #[test]
+    fn test_plain_values_are_exact_conditions() {
+        let exact = filter(&[("team", "search")]).unwrap();
+        assert!(exact.is_exact());
+        assert_eq!(exact.conditions()[0].exact_value(), Some("search"));
+        assert!(!exact.matches(|_| Some("search-infra")));
+
+        // Paths always match by suffix, so they are never pushed down as equality
+        assert!(!filter(&[(PATH_KEY, "docs/intro.md")]).unwrap().is_exact());
+    }
+
+    #[test]
+    fn test_numeric_ranges() {
+        let ranges = HashMap::from([(
+            "version".to_string(),
+            NumericRange {
+                gte: Some(2.0),
+                lte: None,
+            },
+        )]);
+        let filter = MetadataFilter::default().with_ranges(&ranges).unwrap();
+
+        assert!(filter.matches(|_| Some("2")));
+        assert!(filter.matches(|_| Some("v2.5")));
+        assert!(filter.matches(|_| Some(" 10 ")));
+        assert!(!filter.matches(|_| Some("1.9")));
+        assert!(!filter.matches(|_| Some("latest")));
+        assert!(!filter.is_exact());
+
+        let invalid = |gte, lte| {
+            let ranges = HashMap::from([("size".to_string(), NumericRange { gte, lte })]);
+            MetadataFilter::default().with_ranges(&ranges).is_err()
+        };
+        assert!(invalid(None, None));
+        assert!(invalid(Some(5.0), Some(1.0)));
+        assert!(invalid(Some(f64::NAN), None));
+        assert!(!invalid(Some(1.0), Some(1.0)));
+    }
+
+    #[test]
+    fn test_invalid_patterns_are_rejected() {
+        let error = filter(&[("lang", "re:(rust")]).unwrap_err();
+        assert!(error.to_string().contains("metadata_patterns.lang"));
+        assert!(filter(&[("lang", "")]).is_err());
+    }
+}
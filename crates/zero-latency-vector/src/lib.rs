@@ -4,11 +4,19 @@
 /// - Vector storage abstractions
 /// - Embedding generation
 /// - Similarity calculations
+/// - Metadata filtering
+/// - 2D projections for plotting embeddings
 /// - Vector database integrations
+pub mod filter;
+pub mod metric;
 pub mod models;
+pub mod projection;
 pub mod services;
 pub mod traits;
 
+pub use filter::*;
+pub use metric::*;
 pub use models::*;
+pub use projection::*;
 pub use services::*;
 pub use traits::*;
@@ -0,0 +1,195 @@
+//! Distance metrics of collections
+//!
+//! Each collection compares its vectors with one [`SimilarityMetric`], chosen
+//! when it is created. Scores are reported between 0 and 1, higher meaning
+//! closer, whatever the metric:
+//!
+//! - `cosine` compares directions only, so vectors need no normalization.
+//!   Opposite vectors score 0 rather than negative.
+//! - `dot` is the raw inner product, squashed with a logistic so that scores
+//!   stay in range but keep their order. It suits models trained for inner
+//!   product search; for unit-length embeddings it ranks like cosine, but
+//!   scores are not comparable to cosine scores.
+//! - `euclidean` scores `1 / (1 + distance)`, so identical vectors score 1.
+//!   Vectors are compared as stored, without normalization.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use crate::models::SimilarityMetric;
+
+impl SimilarityMetric {
+    pub const ALL: [Self; 3] = [Self::Cosine, Self::DotProduct, Self::Euclidean];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cosine => "cosine",
+            Self::DotProduct => "dot",
+            Self::Euclidean => "euclidean",
+        }
+    }
+
+    /// Cosine similarity, inner product or Euclidean distance of two vectors,
+    /// or `None` when their dimensions differ
+    pub fn raw(self, a: &[f32], b: &[f32]) -> Option<f32> {
+        if a.len() != b.len() {
+            return None;
+        }
+        let dot = || a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+        Some(match self {
+            Self::Cosine => {
+                let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+                let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+                if norm_a == 0.0 || norm_b == 0.0 {
+                    0.0
+                } else {
+                    dot() / (norm_a * norm_b)
+                }
+            }
+            Self::DotProduct => dot(),
+            Self::Euclidean => a
+                .iter()
+                .zip(b)
+                .map(|(x, y)| (x - y) * (x - y))
+                .sum::<f32>()
+                .sqrt(),
+        })
+    }
+
+    /// Score between 0 and 1 of a raw value of this metric, as computed by
+    /// [`SimilarityMetric::raw`] or returned by a vector database
+    pub fn normalize(self, raw: f32) -> f32 {
+        if !raw.is_finite() {
+            return 0.0;
+        }
+        match self {
+            Self::Cosine => raw.clamp(0.0, 1.0),
+            Self::DotProduct => 1.0 / (1.0 + (-raw).exp()),
+            Self::Euclidean => 1.0 / (1.0 + raw.max(0.0)),
+        }
+    }
+
+    /// Score between 0 and 1 of two vectors; 0 when their dimensions differ
+    pub fn score(self, a: &[f32], b: &[f32]) -> f32 {
+        self.raw(a, b).map_or(0.0, |raw| self.normalize(raw))
+    }
+}
+
+impl fmt::Display for SimilarityMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SimilarityMetric {
+    type Err = ZeroLatencyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "cosine" => Ok(Self::Cosine),
+            "dot" | "dot_product" | "dotproduct" => Ok(Self::DotProduct),
+            "euclidean" | "euclid" | "l2" => Ok(Self::Euclidean),
+            _ => Err(ZeroLatencyError::validation(
+                "distance_metric",
+                format!(
+                    "Unknown distance metric '{}', expected cosine, dot or euclidean",
+                    s
+                ),
+            )),
+        }
+    }
+}
+
+/// Dimension and metric of a collection's vectors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionVectorSettings {
+    pub dimension: usize,
+    #[serde(default)]
+    pub metric: SimilarityMetric,
+}
+
+impl CollectionVectorSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.dimension == 0 {
+            return Err(ZeroLatencyError::validation(
+                "vector_size",
+                "Vector size must be greater than 0",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reject a query vector the collection's vectors cannot be compared with
+    pub fn check_query(&self, collection_name: &str, query: &[f32]) -> Result<()> {
+        if query.len() != self.dimension {
+            return Err(ZeroLatencyError::validation(
+                "query_vector",
+                format!(
+                    "Collection '{}' stores {}-dimensional vectors compared by {} distance, \
+                     but the query vector has {} dimensions",
+                    collection_name,
+                    self.dimension,
+                    self.metric,
+                    query.len()
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_score_closer_vectors_higher() {
+        let query = [1.0, 0.0];
+        let near = [2.0, 0.2];
+        let far = [0.0, 3.0];
+        for metric in SimilarityMetric::ALL {
+            let (near, far) = (metric.score(&query, &near), metric.score(&query, &far));
+            assert!(near > far, "{} ranked the far vector first", metric);
+            assert!((0.0..=1.0).contains(&near) && (0.0..=1.0).contains(&far));
+        }
+
+        // Cosine ignores length, the others do not
+        let cosine = SimilarityMetric::Cosine;
+        assert!((cosine.score(&query, &[5.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(SimilarityMetric::Euclidean.score(&query, &query), 1.0);
+        assert!(
+            SimilarityMetric::DotProduct.score(&query, &[5.0, 0.0])
+                > SimilarityMetric::DotProduct.score(&query, &[1.0, 0.0])
+        );
+        assert_eq!(cosine.score(&query, &[1.0, 0.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_metric_names_and_query_checks() {
+        for metric in SimilarityMetric::ALL {
+            assert_eq!(metric.name().parse::<SimilarityMetric>().unwrap(), metric);
+        }
+        assert_eq!(
+            "L2".parse::<SimilarityMetric>().unwrap(),
+            SimilarityMetric::Euclidean
+        );
+        assert!("manhattan".parse::<SimilarityMetric>().is_err());
+
+        let settings = CollectionVectorSettings {
+            dimension: 3,
+            metric: SimilarityMetric::DotProduct,
+        };
+        assert!(settings.check_query("docs", &[0.0; 3]).is_ok());
+        let error = settings.check_query("docs", &[0.0; 4]).unwrap_err();
+        assert!(error.to_string().contains("4 dimensions"));
+        assert!(CollectionVectorSettings {
+            dimension: 0,
+            ..settings
+        }
+        .validate()
+        .is_err());
+    }
+}
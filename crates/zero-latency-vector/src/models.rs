@@ -1,15 +1,35 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use zero_latency_core::{values::Score, Uuid};
+use zero_latency_core::{values::Score, DateTime, Utc, Uuid};
+
+/// Name of the vector every chunk has, stored in [`VectorDocument::embedding`]
+pub const DEFAULT_VECTOR: &str = "content";
+
+/// Name of the optional vector of a chunk's document title and headings
+pub const TITLE_VECTOR: &str = "title";
 
 /// Vector document for storage
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorDocument {
     pub id: Uuid,
     pub embedding: Vec<f32>,
+    /// Further vectors of the chunk by name, e.g. `title`, each searchable as
+    /// its own vector space
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub named_vectors: HashMap<String, Vec<f32>>,
     pub metadata: VectorMetadata,
 }
 
+impl VectorDocument {
+    /// The chunk's vector named `name`; [`DEFAULT_VECTOR`] is its embedding
+    pub fn vector(&self, name: &str) -> Option<&[f32]> {
+        if name == DEFAULT_VECTOR {
+            return Some(&self.embedding);
+        }
+        self.named_vectors.get(name).map(Vec::as_slice)
+    }
+}
+
 /// Metadata associated with vectors
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct VectorMetadata {
@@ -21,6 +41,13 @@ pub struct VectorMetadata {
     pub url: Option<String>,
     pub collection: Option<String>,
     pub custom: HashMap<String, String>,
+    /// Model that produced the embedding; unknown for vectors stored before
+    /// models were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    /// When the embedding was produced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedded_at: Option<DateTime<Utc>>,
 }
 
 /// Similarity search result
@@ -31,6 +58,39 @@ pub struct SimilarityResult {
     pub metadata: VectorMetadata,
 }
 
+/// How a vector store's contents are laid out, for diagnostics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageInfo {
+    /// Name of the backend, e.g. `embedded` or `memory`
+    pub backend: String,
+    pub vector_count: usize,
+    /// Size of the store's files, for stores that keep them locally
+    pub disk_bytes: Option<u64>,
+    /// Vectors held in the store's in-process cache
+    pub cached_vectors: usize,
+    /// Chunk text bytes not stored because identical text was already stored
+    #[serde(default)]
+    pub deduplicated_bytes: u64,
+    /// Breakdown by collection
+    pub segments: Vec<StorageSegment>,
+}
+
+/// Vectors of one collection within a store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageSegment {
+    /// Collection name; `None` for legacy vectors stored without one
+    pub collection: Option<String>,
+    pub vector_count: usize,
+    /// Dimension of the segment's vectors, if they all share one
+    pub dimension: Option<usize>,
+    /// Chunk text bytes of the segment's vectors, counting repeats
+    #[serde(default)]
+    pub chunk_bytes: u64,
+    /// Bytes of repeated chunk text within the segment that are stored once
+    #[serde(default)]
+    pub deduplicated_bytes: u64,
+}
+
 /// Embedding generation request
 #[derive(Debug, Clone)]
 pub struct EmbeddingRequest {
@@ -67,9 +127,14 @@ pub enum VectorStoreType {
 }
 
 /// Similarity calculation methods
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SimilarityMetric {
+    #[default]
+    #[serde(alias = "Cosine")]
     Cosine,
+    #[serde(alias = "Euclidean")]
     Euclidean,
+    #[serde(rename = "dot", alias = "DotProduct", alias = "dot_product")]
     DotProduct,
 }
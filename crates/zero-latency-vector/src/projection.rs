@@ -0,0 +1,391 @@
+//! Two-dimensional projections of embeddings
+//!
+//! Plotting a collection's chunks in 2D shows its structure at a glance:
+//! documentation topics form clusters, and so do navigation menus, license
+//! headers and other boilerplate that crowds real content out of results.
+//! Two methods are provided, both in plain Rust:
+//!
+//! - [`ProjectionMethod::Pca`] projects onto the two principal components.
+//!   It is fast and deterministic but only shows the directions of largest
+//!   variance, so distinct clusters may overlap.
+//! - [`ProjectionMethod::Umap`] lays out the k-nearest-neighbor graph of the
+//!   embeddings, keeping neighbors together and pushing unrelated chunks
+//!   apart, which separates clusters far better. This is a compact version
+//!   of UMAP: exact neighbors by cosine distance, PCA initialization and the
+//!   usual stochastic layout with negative sampling. Finding neighbors is
+//!   quadratic in the number of points, so callers should sample large
+//!   collections first.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use zero_latency_core::ZeroLatencyError;
+
+/// Neighbors each point is connected to in the UMAP graph
+const UMAP_NEIGHBORS: usize = 15;
+
+/// Passes over the UMAP graph's edges
+const UMAP_EPOCHS: usize = 200;
+
+/// Random non-neighbors each point is pushed away from per attraction
+const UMAP_NEGATIVE_SAMPLES: usize = 5;
+
+/// Curve parameters of UMAP's low-dimensional similarity for a `min_dist` of 0.1
+const UMAP_A: f32 = 1.577;
+const UMAP_B: f32 = 0.895;
+
+/// Iterations of the power method per principal component
+const PCA_ITERATIONS: usize = 100;
+
+/// How embeddings are projected to two dimensions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectionMethod {
+    /// Principal component analysis
+    Pca,
+    /// Uniform manifold approximation and projection
+    #[default]
+    Umap,
+}
+
+impl ProjectionMethod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pca => "pca",
+            Self::Umap => "umap",
+        }
+    }
+
+    /// Project `vectors`, which must all have the same dimension
+    ///
+    /// `seed` makes UMAP's layout reproducible; PCA ignores it.
+    pub fn project(self, vectors: &[Vec<f32>], seed: u64) -> Vec<[f32; 2]> {
+        match self {
+            Self::Pca => pca(vectors),
+            Self::Umap => umap(vectors, seed),
+        }
+    }
+}
+
+impl FromStr for ProjectionMethod {
+    type Err = ZeroLatencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pca" => Ok(Self::Pca),
+            "umap" => Ok(Self::Umap),
+            _ => Err(ZeroLatencyError::validation(
+                "method",
+                "must be one of pca, umap",
+            )),
+        }
+    }
+}
+
+/// Coordinates along the two principal components of `vectors`
+pub fn pca(vectors: &[Vec<f32>]) -> Vec<[f32; 2]> {
+    let Some(dimension) = vectors.first().map(Vec::len) else {
+        return Vec::new();
+    };
+    let mut mean = vec![0.0f32; dimension];
+    for vector in vectors {
+        for (m, x) in mean.iter_mut().zip(vector) {
+            *m += x / vectors.len() as f32;
+        }
+    }
+    let centered: Vec<Vec<f32>> = vectors
+        .iter()
+        .map(|vector| vector.iter().zip(&mean).map(|(x, m)| x - m).collect())
+        .collect();
+
+    // Deterministic, asymmetric starting directions
+    let first = principal_component(&centered, &[], |i| 1.0 + i as f32 / dimension as f32);
+    let second = principal_component(&centered, std::slice::from_ref(&first), |i| {
+        if i % 2 == 0 {
+            1.0
+        } else {
+            -1.0
+        }
+    });
+
+    centered
+        .iter()
+        .map(|row| [dot(row, &first), dot(row, &second)])
+        .collect()
+}
+
+// Leading eigenvector of the covariance of `rows`, orthogonal to `previous`
+fn principal_component(
+    rows: &[Vec<f32>],
+    previous: &[Vec<f32>],
+    start: impl Fn(usize) -> f32,
+) -> Vec<f32> {
+    let dimension = rows[0].len();
+    let mut component: Vec<f32> = (0..dimension).map(start).collect();
+    for _ in 0..PCA_ITERATIONS {
+        // Multiply by the covariance without forming it: Xᵀ(Xv)
+        let mut next = vec![0.0f32; dimension];
+        for row in rows {
+            let projection = dot(row, &component);
+            for (n, x) in next.iter_mut().zip(row) {
+                *n += projection * x;
+            }
+        }
+        for other in previous {
+            let overlap = dot(&next, other);
+            for (n, o) in next.iter_mut().zip(other) {
+                *n -= overlap * o;
+            }
+        }
+        let norm = dot(&next, &next).sqrt();
+        if norm == 0.0 {
+            break;
+        }
+        component = next.into_iter().map(|n| n / norm).collect();
+    }
+    component
+}
+
+/// UMAP layout of `vectors` by cosine distance
+pub fn umap(vectors: &[Vec<f32>], seed: u64) -> Vec<[f32; 2]> {
+    let n = vectors.len();
+    if n < 3 {
+        return pca(vectors);
+    }
+    let k = UMAP_NEIGHBORS.min(n - 1);
+    let edges = fuzzy_graph(vectors, k);
+
+    // Start from PCA scaled to a box of width 20, as UMAP's spectral start does
+    let mut layout = pca(vectors);
+    let extent = layout
+        .iter()
+        .flat_map(|point| point.iter().map(|c| c.abs()))
+        .fold(0.0f32, f32::max);
+    let scale = if extent > 0.0 { 10.0 / extent } else { 1.0 };
+    let mut rng = SplitMix64(seed);
+    for point in &mut layout {
+        for coordinate in point.iter_mut() {
+            // A little jitter keeps identical embeddings from sitting on top of each other
+            *coordinate = *coordinate * scale + (rng.next_f32() - 0.5) * 1e-3;
+        }
+    }
+
+    // Strong edges are sampled every epoch, weak ones proportionally less often
+    let max_weight = edges.iter().map(|e| e.2).fold(0.0f32, f32::max);
+    let epochs_per_sample: Vec<f32> = edges.iter().map(|e| max_weight / e.2).collect();
+    let mut next_sample = epochs_per_sample.clone();
+
+    for epoch in 0..UMAP_EPOCHS {
+        let alpha = 1.0 - epoch as f32 / UMAP_EPOCHS as f32;
+        for (edge, &(i, j, _)) in edges.iter().enumerate() {
+            if next_sample[edge] > (epoch + 1) as f32 {
+                continue;
+            }
+            next_sample[edge] += epochs_per_sample[edge];
+
+            let distance = squared_distance(layout[i], layout[j]);
+            if distance > 0.0 {
+                let coefficient = -2.0 * UMAP_A * UMAP_B * distance.powf(UMAP_B - 1.0)
+                    / (1.0 + UMAP_A * distance.powf(UMAP_B));
+                move_points(&mut layout, i, j, coefficient, alpha, true);
+            }
+
+            for _ in 0..UMAP_NEGATIVE_SAMPLES {
+                let other = rng.next_below(n);
+                if other == i {
+                    continue;
+                }
+                let distance = squared_distance(layout[i], layout[other]);
+                let coefficient =
+                    2.0 * UMAP_B / ((0.001 + distance) * (1.0 + UMAP_A * distance.powf(UMAP_B)));
+                move_points(&mut layout, i, other, coefficient, alpha, false);
+            }
+        }
+    }
+    layout
+}
+
+// Symmetric fuzzy k-nearest-neighbor graph as (from, to, weight) edges
+fn fuzzy_graph(vectors: &[Vec<f32>], k: usize) -> Vec<(usize, usize, f32)> {
+    let norms: Vec<f32> = vectors.iter().map(|v| dot(v, v).sqrt()).collect();
+    let target = (k as f32).log2();
+    let mut weights: HashMap<(usize, usize), f32> = HashMap::new();
+
+    for (i, vector) in vectors.iter().enumerate() {
+        let mut distances: Vec<(usize, f32)> = vectors
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(j, other)| {
+                let norm = norms[i] * norms[j];
+                let similarity = if norm > 0.0 {
+                    dot(vector, other) / norm
+                } else {
+                    0.0
+                };
+                (j, (1.0 - similarity).max(0.0))
+            })
+            .collect();
+        distances.sort_by(|a, b| a.1.total_cmp(&b.1));
+        distances.truncate(k);
+
+        // Distance to the nearest neighbor counts as zero, and sigma is
+        // chosen so the neighbors' memberships sum to log2(k)
+        let rho = distances
+            .iter()
+            .map(|d| d.1)
+            .find(|d| *d > 0.0)
+            .unwrap_or(0.0);
+        let membership = |distance: f32, sigma: f32| (-(distance - rho).max(0.0) / sigma).exp();
+        let (mut low, mut high, mut sigma) = (0.0f32, f32::INFINITY, 1.0f32);
+        for _ in 0..64 {
+            let total: f32 = distances.iter().map(|d| membership(d.1, sigma)).sum();
+            if (total - target).abs() < 1e-5 {
+                break;
+            }
+            if total > target {
+                high = sigma;
+                sigma = (low + high) / 2.0;
+            } else {
+                low = sigma;
+                sigma = if high.is_finite() {
+                    (low + high) / 2.0
+                } else {
+                    sigma * 2.0
+                };
+            }
+        }
+        let sigma = sigma.max(1e-3);
+
+        for (j, distance) in distances {
+            let weight = membership(distance, sigma);
+            let key = (i.min(j), i.max(j));
+            // Fuzzy union of the two directed memberships
+            weights
+                .entry(key)
+                .and_modify(|w| *w = *w + weight - *w * weight)
+                .or_insert(weight);
+        }
+    }
+
+    let mut edges: Vec<(usize, usize, f32)> = weights
+        .into_iter()
+        .filter(|(_, weight)| *weight > 0.0)
+        .map(|((i, j), weight)| (i, j, weight))
+        .collect();
+    // Map iteration order is random; the layout must only depend on the seed
+    edges.sort_by_key(|edge| (edge.0, edge.1));
+    edges
+}
+
+// Move `i` along the gradient towards or away from `j`, and `j` the other
+// way for attractions
+fn move_points(
+    layout: &mut [[f32; 2]],
+    i: usize,
+    j: usize,
+    coefficient: f32,
+    alpha: f32,
+    move_other: bool,
+) {
+    let (a, b) = (layout[i], layout[j]);
+    for (axis, (a, b)) in a.into_iter().zip(b).enumerate() {
+        let gradient = (coefficient * (a - b)).clamp(-4.0, 4.0);
+        layout[i][axis] += gradient * alpha;
+        if move_other {
+            layout[j][axis] -= gradient * alpha;
+        }
+    }
+}
+
+fn squared_distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+// Small seeded generator for negative sampling, so layouts are reproducible
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two tight groups of embeddings pointing in different directions
+    fn clusters() -> Vec<Vec<f32>> {
+        (0..20)
+            .map(|i| {
+                let jitter = (i % 5) as f32 * 0.01;
+                if i < 10 {
+                    vec![1.0, jitter, 0.0, 0.1]
+                } else {
+                    vec![0.0, 0.1, 1.0, jitter]
+                }
+            })
+            .collect()
+    }
+
+    fn centroid(points: &[[f32; 2]]) -> [f32; 2] {
+        let n = points.len() as f32;
+        [
+            points.iter().map(|p| p[0]).sum::<f32>() / n,
+            points.iter().map(|p| p[1]).sum::<f32>() / n,
+        ]
+    }
+
+    #[test]
+    fn test_projections_separate_clusters() {
+        let vectors = clusters();
+        for method in [ProjectionMethod::Pca, ProjectionMethod::Umap] {
+            let layout = method.project(&vectors, 7);
+            assert_eq!(layout.len(), vectors.len());
+            assert!(layout.iter().flatten().all(|c| c.is_finite()));
+
+            // Points sit closer to their own cluster's centre than to the other's
+            let centres = [centroid(&layout[..10]), centroid(&layout[10..])];
+            for (index, point) in layout.iter().enumerate() {
+                let own = usize::from(index >= 10);
+                assert!(
+                    squared_distance(*point, centres[own])
+                        < squared_distance(*point, centres[1 - own]),
+                    "{:?} point {}",
+                    method,
+                    index
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_umap_is_reproducible() {
+        let vectors = clusters();
+        assert_eq!(umap(&vectors, 3), umap(&vectors, 3));
+        assert_eq!(pca(&[]), Vec::<[f32; 2]>::new());
+        assert_eq!(umap(&vectors[..2], 3).len(), 2);
+        assert_eq!(
+            "UMAP".parse::<ProjectionMethod>().unwrap(),
+            ProjectionMethod::Umap
+        );
+        assert!("tsne".parse::<ProjectionMethod>().is_err());
+    }
+}
@@ -1,6 +1,7 @@
-use crate::models::*;
+use crate::{filter::MetadataFilter, metric::CollectionVectorSettings, models::*};
 use async_trait::async_trait;
-use zero_latency_core::{models::HealthStatus, Result};
+use std::ops::RangeInclusive;
+use zero_latency_core::{models::HealthStatus, Result, Uuid};
 
 /// Vector storage operations
 #[async_trait]
@@ -13,6 +14,158 @@ pub trait VectorRepository: Send + Sync {
         query_vector: Vec<f32>,
         k: usize,
     ) -> Result<Vec<SimilarityResult>>;
+
+    /// Search, keeping only results whose metadata matches `filter`
+    ///
+    /// Stores that can evaluate the filter natively should override this. The
+    /// default over-fetches and post-filters, so rare matches may yield fewer
+    /// than `k` results.
+    async fn search_filtered(
+        &self,
+        collection_name: Option<&str>,
+        query_vector: Vec<f32>,
+        k: usize,
+        filter: &MetadataFilter,
+    ) -> Result<Vec<SimilarityResult>> {
+        let fetch_limit = filter.fetch_limit(k);
+        let mut results = match collection_name {
+            Some(name) => {
+                self.search_in_collection(name, query_vector, fetch_limit)
+                    .await?
+            }
+            None => self.search(query_vector, fetch_limit).await?,
+        };
+        results.retain(|result| filter.matches_metadata(&result.metadata));
+        results.truncate(k);
+        Ok(results)
+    }
+
+    /// Search the chunks' vectors named `vector_name` instead of their
+    /// embeddings
+    ///
+    /// Chunks without a vector of that name are left out. [`DEFAULT_VECTOR`]
+    /// names the embedding itself. Stores that keep named vectors should
+    /// override this; the default can only search the embedding.
+    async fn search_named(
+        &self,
+        collection_name: Option<&str>,
+        vector_name: &str,
+        query_vector: Vec<f32>,
+        k: usize,
+        filter: &MetadataFilter,
+    ) -> Result<Vec<SimilarityResult>> {
+        if vector_name == DEFAULT_VECTOR {
+            return self
+                .search_filtered(collection_name, query_vector, k, filter)
+                .await;
+        }
+        Err(zero_latency_core::ZeroLatencyError::validation(
+            "vector_spaces",
+            format!(
+                "Vector backend does not store named vectors, so '{}' cannot be searched",
+                vector_name
+            ),
+        ))
+    }
+
+    /// Load a collection's vectors into memory ahead of the first search
+    ///
+    /// Returns how many vectors were loaded. Stores that have nothing to
+    /// preload in process, such as remote databases, keep the default.
+    async fn preload_collection(&self, _collection_name: &str) -> Result<usize> {
+        Ok(0)
+    }
+
+    /// Every vector stored in a collection, with its embedding and metadata
+    ///
+    /// Used to copy a collection, e.g. when re-embedding it with a new model.
+    /// Stores that cannot enumerate their contents keep the default error.
+    async fn collection_vectors(&self, collection_name: &str) -> Result<Vec<VectorDocument>> {
+        Err(zero_latency_core::ZeroLatencyError::configuration(format!(
+            "Vector backend cannot list the vectors of collection '{}'",
+            collection_name
+        )))
+    }
+
+    /// Ids and metadata of a document's chunks in a collection whose index
+    /// is in `chunk_range`, in chunk order
+    ///
+    /// Used to stitch a hit's neighboring chunks around it and to outline a
+    /// document. The default scans
+    /// [`collection_vectors`](Self::collection_vectors); stores that can look
+    /// chunks up by document should override it.
+    async fn document_chunks(
+        &self,
+        collection_name: &str,
+        document_id: Uuid,
+        chunk_range: RangeInclusive<usize>,
+    ) -> Result<Vec<(Uuid, VectorMetadata)>> {
+        let mut chunks: Vec<(Uuid, VectorMetadata)> = self
+            .collection_vectors(collection_name)
+            .await?
+            .into_iter()
+            .filter(|vector| {
+                vector.metadata.document_id == document_id
+                    && chunk_range.contains(&vector.metadata.chunk_index)
+            })
+            .map(|vector| (vector.id, vector.metadata))
+            .collect();
+        chunks.sort_by_key(|(_, metadata)| metadata.chunk_index);
+        Ok(chunks)
+    }
+
+    /// Size and per-collection layout of the store
+    ///
+    /// The default only knows the total count; stores that can break their
+    /// contents down by collection should override it.
+    async fn storage_info(&self) -> Result<StorageInfo> {
+        Ok(StorageInfo {
+            backend: "unknown".to_string(),
+            vector_count: self.count().await?,
+            ..Default::default()
+        })
+    }
+
+    /// Compare a collection's vectors with `settings` from now on
+    ///
+    /// Called when a collection is created. Stores should score its searches
+    /// with the metric and reject query vectors of another dimension. The
+    /// default only accepts cosine, the metric every store assumes otherwise.
+    async fn configure_collection(
+        &self,
+        collection_name: &str,
+        settings: CollectionVectorSettings,
+    ) -> Result<()> {
+        if settings.metric == SimilarityMetric::Cosine {
+            return Ok(());
+        }
+        Err(zero_latency_core::ZeroLatencyError::configuration(format!(
+            "Vector backend cannot compare the vectors of collection '{}' by {} distance",
+            collection_name, settings.metric
+        )))
+    }
+
+    /// Insert or replace many vectors in as few round trips as the store allows
+    ///
+    /// The indexing pipeline writes through this. Stores with a bulk write
+    /// path should override it; the default hands the batch to `insert`.
+    async fn upsert_batch(&self, vectors: Vec<VectorDocument>) -> Result<()> {
+        self.insert(vectors).await
+    }
+
+    /// Delete many vectors at once, returning how many were stored
+    ///
+    /// The default deletes them one at a time.
+    async fn delete_batch(&self, document_ids: &[String]) -> Result<usize> {
+        let mut deleted = 0;
+        for document_id in document_ids {
+            if self.delete(document_id).await? {
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
     async fn delete(&self, document_id: &str) -> Result<bool>;
     async fn update(&self, document_id: &str, vector: Vec<f32>) -> Result<bool>;
     async fn health_check(&self) -> Result<HealthStatus>;
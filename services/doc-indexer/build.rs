@@ -0,0 +1,130 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Crates whose locked versions are reported by `about --build-info`: the
+/// workspace crates linked in, storage, model runtimes, plugin hosts and
+/// network clients
+const AUDITED_CRATES: &[&str] = &[
+    "aes-gcm",
+    "axum",
+    "cudarc",
+    "lettre",
+    "libloading",
+    "libsqlite3-sys",
+    "ort",
+    "qdrant-client",
+    "reqwest",
+    "rusqlite",
+    "rustls",
+    "tantivy",
+    "tokenizers",
+    "tokio",
+    "tonic",
+    "wasmtime",
+    "zero-latency-api",
+    "zero-latency-config",
+    "zero-latency-core",
+    "zero-latency-observability",
+    "zero-latency-search",
+    "zero-latency-vector",
+    "zstd",
+];
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let workspace_lock = Path::new(&manifest_dir).join("../../Cargo.lock");
+    println!("cargo:rerun-if-changed={}", workspace_lock.display());
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .unwrap_or_default();
+
+    println!(
+        "cargo:rustc-env=DOC_INDEXER_BUILD_TARGET={}",
+        env::var("TARGET").unwrap_or_default()
+    );
+    println!(
+        "cargo:rustc-env=DOC_INDEXER_BUILD_PROFILE={}",
+        env::var("PROFILE").unwrap_or_default()
+    );
+    println!("cargo:rustc-env=DOC_INDEXER_BUILD_RUSTC={}", rustc_version);
+
+    // Cargo writes the lockfile before build scripts run; a package built
+    // outside the workspace falls back to its own
+    let lock = fs::read_to_string(&workspace_lock)
+        .or_else(|_| fs::read_to_string(Path::new(&manifest_dir).join("Cargo.lock")))
+        .unwrap_or_default();
+    println!(
+        "cargo:rustc-env=DOC_INDEXER_LOCKED_CRATES={}",
+        locked_crates(&lock).join(";")
+    );
+}
+
+/// `name=version` of the audited crates doc-indexer depends on, directly or
+/// not, in a Cargo.lock
+fn locked_crates(lock: &str) -> Vec<String> {
+    let packages: Vec<(String, String, Vec<String>)> = lock
+        .split("[[package]]")
+        .skip(1)
+        .filter_map(|package| {
+            let field = |key: &str| {
+                package.lines().find_map(|line| {
+                    line.strip_prefix(key)
+                        .and_then(|rest| rest.trim().strip_prefix('='))
+                        .map(|value| value.trim().trim_matches('"').to_string())
+                })
+            };
+            let dependencies = package
+                .split_once("dependencies = [")
+                .map(|(_, list)| list.split(']').next().unwrap_or_default())
+                .unwrap_or_default()
+                .split(',')
+                .map(|dependency| dependency.trim().trim_matches('"').to_string())
+                .filter(|dependency| !dependency.is_empty())
+                .collect();
+            Some((field("name")?, field("version")?, dependencies))
+        })
+        .collect();
+
+    // Dependencies are listed by name, with the version when several are locked
+    let find = |dependency: &str| {
+        let mut parts = dependency.split(' ');
+        let name = parts.next().unwrap_or_default();
+        let version = parts.next();
+        packages
+            .iter()
+            .position(|(candidate, candidate_version, _)| {
+                candidate == name && version.is_none_or(|version| version == candidate_version)
+            })
+    };
+
+    let mut seen = vec![false; packages.len()];
+    let mut pending: Vec<usize> = find(&env::var("CARGO_PKG_NAME").unwrap())
+        .into_iter()
+        .collect();
+    let mut crates = Vec::new();
+    while let Some(index) = pending.pop() {
+        if std::mem::replace(&mut seen[index], true) {
+            continue;
+        }
+        let (name, version, dependencies) = &packages[index];
+        if AUDITED_CRATES.contains(&name.as_str()) {
+            crates.push(format!("{}={}", name, version));
+        }
+        pending.extend(
+            dependencies
+                .iter()
+                .filter_map(|dependency| find(dependency)),
+        );
+    }
+    crates.sort();
+    crates
+}
@@ -0,0 +1,30 @@
+#![no_main]
+
+use doc_indexer::{StandardIndexingStrategy, StrategyConfig};
+use libfuzzer_sys::fuzz_target;
+
+// Input is four big-endian u16 values (chunk size, overlap, max chunks,
+// min chunk size; a max of zero means unlimited) followed by the document text
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 8 {
+        return;
+    }
+    let field = |i: usize| u16::from_be_bytes([data[i * 2], data[i * 2 + 1]]) as usize;
+    let max_chunks = field(2);
+
+    let strategy = StandardIndexingStrategy::with_config(StrategyConfig {
+        name: "fuzz".to_string(),
+        chunk_size: field(0),
+        chunk_overlap: field(1),
+        max_chunks_per_document: (max_chunks > 0).then_some(max_chunks),
+        min_chunk_size: field(3),
+    });
+
+    let content = String::from_utf8_lossy(&data[8..]);
+    let chunks = strategy.chunk_content(&content);
+
+    assert!(chunks.len() <= content.split_whitespace().count());
+    if max_chunks > 0 {
+        assert!(chunks.len() <= max_chunks);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use doc_indexer::application::services::filter_service::glob_match;
+use libfuzzer_sys::fuzz_target;
+
+// Input is `text \0 pattern`
+fuzz_target!(|data: &[u8]| {
+    let input = String::from_utf8_lossy(data);
+    let (text, pattern) = input.split_once('\0').unwrap_or((&input, "*"));
+    let _ = glob_match(text, pattern);
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use doc_indexer::infrastructure::api::jsonrpc::JsonRpcRequest;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        // Seeds hold one request per line; the whole input is tried as well
+        let _ = JsonRpcRequest::parse(input);
+        for line in input.lines() {
+            let _ = JsonRpcRequest::parse(line);
+        }
+    }
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use doc_indexer::infrastructure::api::jsonrpc::JsonRpcRequest;
+use doc_indexer::infrastructure::stdio::read_frame;
+use libfuzzer_sys::fuzz_target;
+use std::io::Cursor;
+
+fuzz_target!(|data: &[u8]| {
+    let mut reader = Cursor::new(data);
+    while let Some(frame) = read_frame(&mut reader).expect("in-memory reads cannot fail") {
+        assert!(!frame.contains('\n'));
+        let _ = JsonRpcRequest::parse(&frame);
+    }
+});
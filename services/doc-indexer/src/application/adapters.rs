@@ -33,13 +33,17 @@ impl VectorStorage for VectorStorageAdapter {
     }
 
     async fn store_vectors(&self, documents: Vec<VectorDocument>) -> Result<()> {
-        self.repository.insert(documents).await
+        self.repository.upsert_batch(documents).await
     }
 
     async fn remove_vectors(&self, document_id: &str) -> Result<()> {
         self.repository.delete(document_id).await.map(|_| ())
     }
 
+    async fn remove_vectors_batch(&self, document_ids: &[String]) -> Result<()> {
+        self.repository.delete_batch(document_ids).await.map(|_| ())
+    }
+
     async fn has_vectors(&self, _document_id: &str) -> Result<bool> {
         // This would need to be implemented in the VectorRepository trait
         // For now, we'll return false and implement this later
@@ -114,10 +118,19 @@ impl StandardFileSystemService {
 #[async_trait]
 impl FileSystemService for StandardFileSystemService {
     async fn read_file_content(&self, path: &Path) -> Result<String> {
+        use crate::application::content_processing::{ContentDecoder, DecodedContent};
         use tokio::fs;
-        fs::read_to_string(path).await.map_err(|e| {
+        let bytes = fs::read(path).await.map_err(|e| {
             zero_latency_core::ZeroLatencyError::internal(&format!("Failed to read file: {}", e))
-        })
+        })?;
+
+        match ContentDecoder::decode(&bytes) {
+            DecodedContent::Text { content, .. } => Ok(content),
+            DecodedContent::Binary => Err(zero_latency_core::ZeroLatencyError::validation(
+                "content",
+                "File appears to contain binary data",
+            )),
+        }
     }
 
     async fn is_file(&self, path: &Path) -> Result<bool> {
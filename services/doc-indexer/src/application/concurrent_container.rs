@@ -39,7 +39,12 @@ impl ConcurrentServiceContainer {
 
         // Create analytics service first so it can be shared
         let analytics = Arc::new(
-            crate::infrastructure::operations::analytics::ProductionSearchAnalytics::with_default_config(),
+            crate::infrastructure::operations::analytics::ProductionSearchAnalytics::new(
+                crate::infrastructure::operations::analytics::AnalyticsConfig {
+                    slow_query_threshold_ms: config.service.slow_query_threshold_ms,
+                    ..Default::default()
+                },
+            ),
         );
 
         // Create search pipeline and orchestrator with shared analytics
@@ -119,6 +124,12 @@ impl ConcurrentServiceContainer {
                     cache_size: config.vector.embedded.cache_size,
                     enable_string_interning: true,
                     enable_smart_caching: true,
+                    enable_gpu_scoring: config.vector.embedded.enable_gpu_scoring,
+                    scoring_batch_size: config.vector.embedded.scoring_batch_size,
+                    compression: config.vector.embedded.compression.clone(),
+                    encryption: config.vector.embedded.encryption.clone(),
+                    memtable_capacity: config.vector.embedded.memtable_capacity,
+                    max_segments: config.vector.embedded.max_segments,
                 };
                 let store = EmbeddedVectorStore::new(embedded_config).await?;
                 Ok(Arc::new(store))
@@ -130,7 +141,7 @@ impl ConcurrentServiceContainer {
                     api_key: config.vector.qdrant.api_key.clone(),
                     collection_name: config.vector.qdrant.collection_name.clone(),
                     timeout_seconds: config.vector.qdrant.timeout_seconds,
-                    vector_size: 384, // Use default dimension
+                    batch_size: config.vector.qdrant.batch_size,
                 };
                 let adapter = QdrantAdapter::new(qdrant_config).await?;
                 Ok(Arc::new(adapter))
@@ -185,6 +196,12 @@ impl ConcurrentServiceContainer {
                     ));
                 }
             }
+            EmbeddingProvider::Mock => {
+                use crate::infrastructure::MockEmbeddingAdapter;
+
+                let adapter = MockEmbeddingAdapter::new(config.embedding.mock.clone())?;
+                Ok(Arc::new(adapter))
+            }
         }
     }
 
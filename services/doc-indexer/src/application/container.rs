@@ -6,13 +6,38 @@
 use std::sync::Arc;
 use zero_latency_core::{Result, ZeroLatencyError};
 use zero_latency_search::{
-    QueryEnhancementStep, ResultRankingStep, SearchOrchestrator, SearchPipeline,
-    SimpleSearchOrchestrator,
+    cache::NegativeCachingOrchestrator, CachedEmbeddingService, DeduplicationConfig, DeduplicationStep, DuplicationStrategy,
+    QueryEnhancementStep, QueryIntentStep, ResultDeduplicator, ResultRankingStep,
+    ScoreCalibrationStep, ScoreCalibrator, SearchOrchestrator, SearchPipeline,
+    SimpleSearchOrchestrator, Summarizer,
 };
 use zero_latency_vector::{EmbeddingGenerator, VectorRepository};
 
+use crate::application::services::webhook_service::WebhookService;
 use crate::config::Config;
+use crate::infrastructure::api::http::rate_limit::ApiRateLimiter;
+use crate::infrastructure::api::http::tenant::RequestTenants;
+use crate::infrastructure::memory::{VectorPool, VectorPoolConfig};
+use crate::infrastructure::operations::lanes::ExecutionLanes;
+use crate::infrastructure::operations::memory_pressure::MemoryPressureMonitor;
+use crate::infrastructure::operations::offline::{self, OfflineStatus};
+use crate::infrastructure::operations::usage::UsageMeter;
+use crate::infrastructure::persistence::change_log::ChangeLog;
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+use crate::infrastructure::persistence::fingerprint_registry::FingerprintRegistry;
+use crate::infrastructure::persistence::metadata_schemas::MetadataSchemaRegistry;
+use crate::infrastructure::persistence::blocklist::Blocklist;
+use crate::infrastructure::persistence::job_store::JobStore;
+use crate::infrastructure::persistence::curation::CurationStore;
+use crate::infrastructure::persistence::projection_cache::ProjectionCache;
+#[cfg(feature = "late-interaction")]
+use crate::infrastructure::persistence::token_vectors::TokenVectorStore;
+use crate::infrastructure::persistence::vector::scoring::AccelerationStatus;
+use crate::infrastructure::persistence::webhook_registry::WebhookRegistry;
+use crate::infrastructure::plugins::{PluginHost, PluginRankingStep};
+use crate::infrastructure::scripting::{ScriptHost, ScriptScoringStep};
 use crate::infrastructure::search_enhancement::{MultiFactorResultRanker, SimpleQueryEnhancer};
+use crate::infrastructure::webhooks::WebhookDispatcher;
 
 /// Central dependency injection container for the doc-indexer service
 pub struct ServiceContainer {
@@ -23,6 +48,30 @@ pub struct ServiceContainer {
     // Infrastructure services
     vector_repository: Arc<dyn VectorRepository>,
     embedding_generator: Arc<dyn EmbeddingGenerator>,
+    query_embedding_cache: Arc<CachedEmbeddingService>,
+    empty_results_cache: Arc<NegativeCachingOrchestrator>,
+    summarizer: Arc<dyn Summarizer>,
+    fingerprint_registry: Arc<FingerprintRegistry>,
+    collection_aliases: Arc<CollectionAliases>,
+    metadata_schemas: Arc<MetadataSchemaRegistry>,
+    curation: Arc<CurationStore>,
+    blocklist: Arc<Blocklist>,
+    job_store: Arc<JobStore>,
+    projections: Arc<ProjectionCache>,
+    deduplicator: Option<Arc<ResultDeduplicator>>,
+    calibrator: Option<Arc<ScoreCalibrator>>,
+    #[cfg(feature = "late-interaction")]
+    token_vectors: Option<Arc<TokenVectorStore>>,
+    change_log: Arc<ChangeLog>,
+    webhooks: WebhookService,
+    plugins: Arc<PluginHost>,
+    scripts: Arc<ScriptHost>,
+    vector_pool: Arc<VectorPool>,
+    acceleration: AccelerationStatus,
+    rate_limiter: Arc<ApiRateLimiter>,
+    lanes: Arc<ExecutionLanes>,
+    memory_monitor: Arc<MemoryPressureMonitor>,
+    offline: OfflineStatus,
 
     // Configuration
     #[allow(dead_code)]
@@ -31,33 +80,151 @@ pub struct ServiceContainer {
 
 impl ServiceContainer {
     /// Create a new service container with all dependencies initialized
-    pub async fn new(config: Config) -> Result<Self> {
-        let config = Arc::new(config);
+    pub async fn new(mut config: Config) -> Result<Self> {
+        // Swap cloud adapters for local fallbacks before any are created
+        let offline = offline::apply(&mut config)?;
+
+        // Create infrastructure services based on configuration, sharing one
+        // vector pool between embedding generation and the vector store
+        let vector_pool = Self::create_vector_pool(&config);
+        let embedding_generator = Self::create_embedding_generator(&config, &vector_pool).await?;
 
-        // Create infrastructure services based on configuration
-        let vector_repository = Self::create_vector_repository(&config).await?;
-        let embedding_generator = Self::create_embedding_generator(&config).await?;
+        Self::assemble(config, offline, vector_pool, embedding_generator).await
+    }
+
+    /// Create a service container around an externally supplied embedding generator
+    ///
+    /// Everything else is still built from configuration. Used by test
+    /// fixtures that need deterministic embeddings without loading a model.
+    #[allow(dead_code)]
+    pub async fn with_embedding_generator(
+        mut config: Config,
+        embedding_generator: Arc<dyn EmbeddingGenerator>,
+    ) -> Result<Self> {
+        let offline = offline::apply(&mut config)?;
+        let vector_pool = Self::create_vector_pool(&config);
+        Self::assemble(config, offline, vector_pool, embedding_generator).await
+    }
+
+    async fn assemble(
+        config: Config,
+        offline: OfflineStatus,
+        vector_pool: Arc<VectorPool>,
+        embedding_generator: Arc<dyn EmbeddingGenerator>,
+    ) -> Result<Self> {
+        let config = Arc::new(config);
+        let (vector_repository, acceleration) =
+            Self::create_vector_repository(&config, &vector_pool).await?;
+        let fingerprint_registry = Self::create_fingerprint_registry(&config)?;
+        let collection_aliases = Self::create_collection_aliases(&config)?;
+        let metadata_schemas = Self::create_metadata_schemas(&config)?;
+        let curation = Self::create_curation(&config)?;
+        let blocklist = Self::create_blocklist(&config)?;
+        let job_store = Self::create_job_store(&config);
+        let projections = Self::create_projection_cache(&config);
+        let change_log = Self::create_change_log(&config)?;
+        let summarizer = Self::create_summarizer(&config)?;
+        let webhooks = Self::create_webhooks(&config)?;
+        let usage = Self::create_usage_meter(&config)?;
+        let plugins = Arc::new(PluginHost::discover(&config.plugins)?);
+        let scripts = Arc::new(ScriptHost::new(&config.scripts));
+        let rate_limiter = Arc::new(ApiRateLimiter::new(&config.rate_limit));
+        let lanes = Arc::new(ExecutionLanes::new(&config.lanes));
+        let deduplicator = Self::create_deduplicator(&config);
+        let calibrator = config
+            .calibration
+            .enabled
+            .then(|| Arc::new(ScoreCalibrator::new(config.calibration.min_samples)));
+        #[cfg(feature = "late-interaction")]
+        let token_vectors = Self::create_token_vectors(&config)?;
 
         // Create analytics service first so it can be shared
         let analytics = Arc::new(
-            crate::infrastructure::operations::analytics::ProductionSearchAnalytics::with_default_config(),
+            crate::infrastructure::operations::analytics::ProductionSearchAnalytics::new(
+                crate::infrastructure::operations::analytics::AnalyticsConfig {
+                    slow_query_threshold_ms: config.service.slow_query_threshold_ms,
+                    ..Default::default()
+                },
+            )
+            .with_usage(usage),
         );
 
         // Create search pipeline and orchestrator with shared analytics
+        let query_embedding_cache =
+            Self::create_query_embedding_cache(embedding_generator.clone(), &config);
+        #[cfg(feature = "late-interaction")]
+        let late_interaction = token_vectors.clone().map(|store| {
+            zero_latency_search::LateInteractionStep::new(
+                query_embedding_cache.clone(),
+                store,
+                config.late_interaction.depth,
+                config.late_interaction.max_query_tokens,
+            )
+        });
+        #[cfg(not(feature = "late-interaction"))]
+        let late_interaction = None;
         let search_pipeline = Self::create_search_pipeline(
             vector_repository.clone(),
-            embedding_generator.clone(),
+            query_embedding_cache.clone(),
             analytics.clone(),
+            plugins.clone(),
+            scripts.clone(),
+            deduplicator.clone(),
+            calibrator.clone(),
+            late_interaction,
+            config.service.enable_intent_routing,
         )
         .await?;
 
-        let search_orchestrator = Arc::new(SimpleSearchOrchestrator::new(search_pipeline));
+        // Repeated searches that found nothing skip the pipeline until the
+        // index changes
+        let mut query_cache = zero_latency_search::cache::CacheConfig::default().query_cache;
+        query_cache.empty_results_ttl_seconds = config.negative_cache.empty_results_ttl_seconds;
+        let empty_results_cache = Arc::new(
+            NegativeCachingOrchestrator::new(
+                Arc::new(SimpleSearchOrchestrator::new(search_pipeline)),
+                &query_cache,
+            )
+            .with_generation(change_log.clone())
+            .with_tenant_resolver(Arc::new(RequestTenants)),
+        );
+        let search_orchestrator: Arc<dyn SearchOrchestrator> = empty_results_cache.clone();
+
+        let memory_monitor = Arc::new(MemoryPressureMonitor::new(
+            &config.memory_pressure,
+            lanes.clone(),
+        ));
+        memory_monitor.register("query_embeddings", query_embedding_cache.clone());
 
         Ok(Self {
             search_orchestrator,
             analytics,
             vector_repository,
             embedding_generator,
+            query_embedding_cache,
+            empty_results_cache,
+            summarizer,
+            fingerprint_registry,
+            collection_aliases,
+            metadata_schemas,
+            curation,
+            blocklist,
+            job_store,
+            projections,
+            deduplicator,
+            calibrator,
+            #[cfg(feature = "late-interaction")]
+            token_vectors,
+            change_log,
+            webhooks,
+            plugins,
+            scripts,
+            vector_pool,
+            acceleration,
+            rate_limiter,
+            lanes,
+            memory_monitor,
+            offline,
             config,
         })
     }
@@ -82,14 +249,162 @@ impl ServiceContainer {
         self.embedding_generator.clone()
     }
 
+    /// Get the query embedding cache used by the search pipeline
+    pub fn query_embedding_cache(&self) -> Arc<CachedEmbeddingService> {
+        self.query_embedding_cache.clone()
+    }
+
+    /// Get the cache of searches that found nothing
+    pub fn empty_results_cache(&self) -> Arc<NegativeCachingOrchestrator> {
+        self.empty_results_cache.clone()
+    }
+
+    /// Get the search summarizer
+    pub fn summarizer(&self) -> Arc<dyn Summarizer> {
+        self.summarizer.clone()
+    }
+
+    /// Get the file fingerprint registry
+    pub fn fingerprint_registry(&self) -> Arc<FingerprintRegistry> {
+        self.fingerprint_registry.clone()
+    }
+
+    /// Get the collection alias registry
+    pub fn collection_aliases(&self) -> Arc<CollectionAliases> {
+        self.collection_aliases.clone()
+    }
+
+    /// Get the registry of collection metadata schemas
+    pub fn metadata_schemas(&self) -> Arc<MetadataSchemaRegistry> {
+        self.metadata_schemas.clone()
+    }
+
+    /// Get the search curation rules
+    pub fn curation(&self) -> Arc<CurationStore> {
+        self.curation.clone()
+    }
+
+    /// Get the query-time blocklist
+    pub fn blocklist(&self) -> Arc<Blocklist> {
+        self.blocklist.clone()
+    }
+
+    /// Get the store job records are kept in
+    pub fn job_store(&self) -> Arc<JobStore> {
+        self.job_store.clone()
+    }
+
+    /// Get the cached 2D projections of collections
+    pub fn projections(&self) -> Arc<ProjectionCache> {
+        self.projections.clone()
+    }
+
+    /// Get the mirrored-source deduplicator, if deduplication is enabled
+    pub fn deduplicator(&self) -> Option<Arc<ResultDeduplicator>> {
+        self.deduplicator.clone()
+    }
+
+    /// Get the score calibrator, if calibration is enabled
+    pub fn calibrator(&self) -> Option<Arc<ScoreCalibrator>> {
+        self.calibrator.clone()
+    }
+
+    /// Get the store of chunks' token vectors, if late interaction is enabled
+    #[cfg(feature = "late-interaction")]
+    pub fn token_vectors(&self) -> Option<Arc<TokenVectorStore>> {
+        self.token_vectors.clone()
+    }
+
+    /// Whether searches can rerank by late interaction
+    pub fn late_interaction_available(&self) -> bool {
+        cfg!(feature = "late-interaction") && self.config.late_interaction.enabled
+    }
+
+    /// Get the index change log
+    pub fn change_log(&self) -> Arc<ChangeLog> {
+        self.change_log.clone()
+    }
+
+    /// Get the per-tenant usage meter, shared with analytics
+    pub fn usage(&self) -> Arc<UsageMeter> {
+        self.analytics.usage()
+    }
+
+    /// Get the webhook service
+    pub fn webhooks(&self) -> WebhookService {
+        self.webhooks.clone()
+    }
+
+    /// Get the loaded pipeline plugins
+    pub fn plugins(&self) -> Arc<PluginHost> {
+        self.plugins.clone()
+    }
+
+    /// Get the per-collection scripting hooks
+    pub fn scripts(&self) -> Arc<ScriptHost> {
+        self.scripts.clone()
+    }
+
+    /// Get the shared vector buffer pool
+    pub fn vector_pool(&self) -> Arc<VectorPool> {
+        self.vector_pool.clone()
+    }
+
+    /// Get the result of the startup GPU probe
+    pub fn acceleration(&self) -> AccelerationStatus {
+        self.acceleration.clone()
+    }
+
+    /// Get the API rate limiter
+    pub fn rate_limiter(&self) -> Arc<ApiRateLimiter> {
+        self.rate_limiter.clone()
+    }
+
+    /// Get the interactive and background execution lanes
+    pub fn lanes(&self) -> Arc<ExecutionLanes> {
+        self.lanes.clone()
+    }
+
+    /// Get the memory pressure monitor
+    pub fn memory_monitor(&self) -> Arc<MemoryPressureMonitor> {
+        self.memory_monitor.clone()
+    }
+
+    /// Get the offline mode status and the capabilities it degraded
+    pub fn offline(&self) -> OfflineStatus {
+        self.offline.clone()
+    }
+
     /// Get the configuration
     #[allow(dead_code)]
     pub fn config(&self) -> Arc<Config> {
         self.config.clone()
     }
 
+    /// Create the vector pool sized for the configured embedding dimension
+    fn create_vector_pool(config: &Config) -> Arc<VectorPool> {
+        use crate::config::EmbeddingProvider;
+
+        let dimension = match config.embedding.provider {
+            EmbeddingProvider::Local => config.embedding.local.dimension,
+            EmbeddingProvider::Mock => config.embedding.mock.dimension,
+            _ => config.vector.embedded.dimension,
+        };
+
+        Arc::new(VectorPool::new(VectorPoolConfig {
+            dimension,
+            ..Default::default()
+        }))
+    }
+
     /// Create vector repository based on configuration
-    async fn create_vector_repository(config: &Config) -> Result<Arc<dyn VectorRepository>> {
+    ///
+    /// The embedded store probes for a GPU to score its scans; other backends
+    /// don't scan in process and report CPU scoring.
+    async fn create_vector_repository(
+        config: &Config,
+        vector_pool: &Arc<VectorPool>,
+    ) -> Result<(Arc<dyn VectorRepository>, AccelerationStatus)> {
         use crate::config::VectorBackend;
         use crate::infrastructure::InMemoryVectorStore;
 
@@ -97,14 +412,17 @@ impl ServiceContainer {
         use crate::infrastructure::QdrantAdapter;
 
         #[cfg(feature = "embedded")]
-        use crate::infrastructure::EmbeddedVectorStore;
+        use crate::infrastructure::{persistence::vector::scoring, EmbeddedVectorStore};
 
         match config.vector.backend {
-            VectorBackend::Memory => Ok(Arc::new(InMemoryVectorStore::new())),
+            VectorBackend::Memory => Ok((
+                Arc::new(InMemoryVectorStore::new().with_vector_pool(vector_pool.clone())),
+                AccelerationStatus::default(),
+            )),
             #[cfg(feature = "cloud")]
             VectorBackend::Qdrant => {
                 let adapter = QdrantAdapter::new(config.vector.qdrant.clone()).await?;
-                Ok(Arc::new(adapter))
+                Ok((Arc::new(adapter), AccelerationStatus::default()))
             }
             #[cfg(not(feature = "cloud"))]
             VectorBackend::Qdrant => Err(ZeroLatencyError::Configuration {
@@ -112,8 +430,13 @@ impl ServiceContainer {
             }),
             #[cfg(feature = "embedded")]
             VectorBackend::Embedded => {
-                let adapter = EmbeddedVectorStore::new(config.vector.embedded.clone()).await?;
-                Ok(Arc::new(adapter))
+                let (scorer, acceleration) =
+                    scoring::probe(config.vector.embedded.enable_gpu_scoring);
+                let adapter = EmbeddedVectorStore::new(config.vector.embedded.clone())
+                    .await?
+                    .with_vector_pool(vector_pool.clone())
+                    .with_scorer(scorer);
+                Ok((Arc::new(adapter), acceleration))
             }
             #[cfg(not(feature = "embedded"))]
             VectorBackend::Embedded => Err(ZeroLatencyError::Configuration {
@@ -122,9 +445,254 @@ impl ServiceContainer {
         }
     }
 
+    /// Create the fingerprint registry alongside the vector store
+    ///
+    /// Fingerprints are only persisted for durable backends; with the
+    /// in-memory store they would outlive the vectors they describe.
+    fn create_fingerprint_registry(config: &Config) -> Result<Arc<FingerprintRegistry>> {
+        use crate::config::VectorBackend;
+
+        let registry = match config.vector.backend {
+            VectorBackend::Memory => FingerprintRegistry::in_memory(),
+            _ => FingerprintRegistry::load(
+                config
+                    .vector
+                    .embedded
+                    .db_path
+                    .with_file_name("fingerprints.json"),
+            )?,
+        };
+
+        Ok(Arc::new(registry))
+    }
+
+    /// Create the collection alias registry alongside the vector store
+    fn create_collection_aliases(config: &Config) -> Result<Arc<CollectionAliases>> {
+        use crate::config::VectorBackend;
+
+        let aliases = match config.vector.backend {
+            VectorBackend::Memory => CollectionAliases::in_memory(),
+            _ => CollectionAliases::load(
+                config
+                    .vector
+                    .embedded
+                    .db_path
+                    .with_file_name("collection_aliases.json"),
+            )?,
+        };
+
+        Ok(Arc::new(aliases))
+    }
+
+    /// Create the metadata schema registry alongside the vector store
+    fn create_metadata_schemas(config: &Config) -> Result<Arc<MetadataSchemaRegistry>> {
+        use crate::config::VectorBackend;
+
+        let schemas = match config.vector.backend {
+            VectorBackend::Memory => MetadataSchemaRegistry::in_memory(),
+            _ => MetadataSchemaRegistry::load(
+                config
+                    .vector
+                    .embedded
+                    .db_path
+                    .with_file_name("metadata_schemas.json"),
+            )?,
+        };
+
+        Ok(Arc::new(schemas))
+    }
+
+    /// Create the search curation store alongside the vector store
+    fn create_curation(config: &Config) -> Result<Arc<CurationStore>> {
+        use crate::config::VectorBackend;
+
+        let curation = match config.vector.backend {
+            VectorBackend::Memory => CurationStore::in_memory(),
+            _ => CurationStore::load(
+                config
+                    .vector
+                    .embedded
+                    .db_path
+                    .with_file_name("curation.json"),
+            )?,
+        };
+
+        Ok(Arc::new(curation))
+    }
+
+    /// Create the projection cache alongside the vector store
+    fn create_projection_cache(config: &Config) -> Arc<ProjectionCache> {
+        use crate::config::VectorBackend;
+
+        Arc::new(match config.vector.backend {
+            VectorBackend::Memory => ProjectionCache::in_memory(),
+            _ => ProjectionCache::open(
+                config
+                    .vector
+                    .embedded
+                    .db_path
+                    .with_file_name("projections"),
+            ),
+        })
+    }
+
+    /// Create the query-time blocklist alongside the vector store
+    fn create_blocklist(config: &Config) -> Result<Arc<Blocklist>> {
+        use crate::config::VectorBackend;
+
+        let blocklist = match config.vector.backend {
+            VectorBackend::Memory => Blocklist::in_memory(),
+            _ => Blocklist::load(
+                config
+                    .vector
+                    .embedded
+                    .db_path
+                    .with_file_name("blocklist.json"),
+            )?,
+        };
+
+        Ok(Arc::new(blocklist))
+    }
+
+    /// Create the job record store alongside the vector store
+    fn create_job_store(config: &Config) -> Arc<JobStore> {
+        use crate::config::VectorBackend;
+
+        Arc::new(match config.vector.backend {
+            VectorBackend::Memory => JobStore::in_memory(),
+            _ => JobStore::at(
+                config
+                    .vector
+                    .embedded
+                    .db_path
+                    .with_file_name("jobs.json"),
+            ),
+        })
+    }
+
+    /// Create the token vector store alongside the vector store, or None
+    /// when late interaction is disabled
+    #[cfg(feature = "late-interaction")]
+    fn create_token_vectors(config: &Config) -> Result<Option<Arc<TokenVectorStore>>> {
+        use crate::config::VectorBackend;
+
+        if !config.late_interaction.enabled {
+            return Ok(None);
+        }
+        let store = match config.vector.backend {
+            VectorBackend::Memory => TokenVectorStore::in_memory()?,
+            _ => TokenVectorStore::open(
+                config
+                    .vector
+                    .embedded
+                    .db_path
+                    .with_file_name("token_vectors.db"),
+                config.vector.embedded.encryption.cipher()?,
+            )?,
+        };
+
+        Ok(Some(Arc::new(store)))
+    }
+
+    /// Create the mirrored-source deduplicator, or None when disabled
+    fn create_deduplicator(config: &Config) -> Option<Arc<ResultDeduplicator>> {
+        if !config.dedup.enabled {
+            return None;
+        }
+
+        Some(Arc::new(ResultDeduplicator::new(
+            DeduplicationConfig {
+                similarity_threshold: config.dedup.similarity_threshold,
+                canonical_sources: config.dedup.canonical_sources.clone(),
+                ..Default::default()
+            },
+            DuplicationStrategy::MergeWithProvenance,
+        )))
+    }
+
+    /// Create the index change log alongside the vector store
+    fn create_change_log(config: &Config) -> Result<Arc<ChangeLog>> {
+        use crate::config::VectorBackend;
+
+        let capacity = config.service.change_log_capacity;
+        let log = match config.vector.backend {
+            VectorBackend::Memory => ChangeLog::in_memory(capacity),
+            _ => ChangeLog::load(
+                config
+                    .vector
+                    .embedded
+                    .db_path
+                    .with_file_name("changes.jsonl"),
+                capacity,
+            )?,
+        };
+
+        Ok(Arc::new(log))
+    }
+
+    /// Create the usage meter, keeping totals alongside the vector store
+    fn create_usage_meter(config: &Config) -> Result<Arc<UsageMeter>> {
+        use crate::config::VectorBackend;
+
+        let meter = match config.vector.backend {
+            VectorBackend::Memory => UsageMeter::in_memory(),
+            _ => UsageMeter::load(config.vector.embedded.db_path.with_file_name("usage.json"))?,
+        };
+
+        Ok(Arc::new(meter))
+    }
+
+    /// Create the webhook service, keeping subscriptions alongside the vector store
+    fn create_webhooks(config: &Config) -> Result<WebhookService> {
+        use crate::config::VectorBackend;
+
+        let registry = match config.vector.backend {
+            VectorBackend::Memory => WebhookRegistry::in_memory(),
+            _ => WebhookRegistry::load(
+                config
+                    .vector
+                    .embedded
+                    .db_path
+                    .with_file_name("webhooks.json"),
+            )?,
+        };
+        let dispatcher = WebhookDispatcher::new(config.webhooks.clone())?;
+
+        Ok(WebhookService::new(
+            Arc::new(registry),
+            Arc::new(dispatcher),
+        ))
+    }
+
+    /// Create the search summarizer based on configuration
+    fn create_summarizer(config: &Config) -> Result<Arc<dyn Summarizer>> {
+        use zero_latency_config::SummarizerBackend;
+        use zero_latency_search::ExtractiveSummarizer;
+
+        #[cfg(feature = "cloud")]
+        use crate::infrastructure::OpenAISummarizer;
+
+        match config.summarizer.backend {
+            SummarizerBackend::Local => Ok(Arc::new(ExtractiveSummarizer::default())),
+            #[cfg(feature = "cloud")]
+            SummarizerBackend::OpenAI => {
+                Ok(Arc::new(OpenAISummarizer::new(config.summarizer.clone())?))
+            }
+            #[cfg(not(feature = "cloud"))]
+            SummarizerBackend::OpenAI => Err(ZeroLatencyError::Configuration {
+                message: "OpenAI summarizer backend requires 'cloud' feature to be enabled"
+                    .to_string(),
+            }),
+        }
+    }
+
     /// Create embedding generator based on configuration
-    async fn create_embedding_generator(config: &Config) -> Result<Arc<dyn EmbeddingGenerator>> {
+    async fn create_embedding_generator(
+        config: &Config,
+        vector_pool: &Arc<VectorPool>,
+    ) -> Result<Arc<dyn EmbeddingGenerator>> {
         use crate::config::EmbeddingProvider;
+        use crate::infrastructure::MockEmbeddingAdapter;
 
         #[cfg(feature = "embedded")]
         use crate::infrastructure::LocalEmbeddingAdapter;
@@ -135,7 +703,10 @@ impl ServiceContainer {
         match config.embedding.provider {
             #[cfg(feature = "embedded")]
             EmbeddingProvider::Local => {
-                let adapter = LocalEmbeddingAdapter::new(config.embedding.local.clone())?;
+                let adapter = LocalEmbeddingAdapter::with_vector_pool(
+                    config.embedding.local.clone(),
+                    vector_pool.clone(),
+                )?;
                 Ok(Arc::new(adapter))
             }
             #[cfg(not(feature = "embedded"))]
@@ -153,15 +724,17 @@ impl ServiceContainer {
                 message: "OpenAI embedding provider requires 'cloud' feature to be enabled"
                     .to_string(),
             }),
+            EmbeddingProvider::Mock => Ok(Arc::new(MockEmbeddingAdapter::new(
+                config.embedding.mock.clone(),
+            )?)),
         }
     }
 
-    /// Create search pipeline with all steps
-    async fn create_search_pipeline(
-        vector_repository: Arc<dyn VectorRepository>,
+    /// Create the query embedding cache in front of the embedding generator
+    fn create_query_embedding_cache(
         embedding_generator: Arc<dyn EmbeddingGenerator>,
-        analytics: Arc<crate::infrastructure::operations::analytics::ProductionSearchAnalytics>,
-    ) -> Result<SearchPipeline> {
+        config: &Config,
+    ) -> Arc<CachedEmbeddingService> {
         // Create a simple embedding service adapter
         struct EmbeddingServiceAdapter {
             generator: Arc<dyn EmbeddingGenerator>,
@@ -174,10 +747,33 @@ impl ServiceContainer {
             }
         }
 
-        let embedding_service = Arc::new(EmbeddingServiceAdapter {
-            generator: embedding_generator,
-        });
+        // Cache query embeddings so repeated and warmed-up queries skip the model
+        let mut cache_config = zero_latency_search::cache::CacheConfig::default().embedding_cache;
+        cache_config.failure_ttl_seconds = config.negative_cache.embedding_failure_ttl_seconds;
+        Arc::new(
+            CachedEmbeddingService::new(
+                Arc::new(EmbeddingServiceAdapter {
+                    generator: embedding_generator,
+                }),
+                &cache_config,
+            )
+            .with_tenant_resolver(Arc::new(RequestTenants)),
+        )
+    }
 
+    /// Create search pipeline with all steps
+    #[allow(clippy::too_many_arguments)]
+    async fn create_search_pipeline(
+        vector_repository: Arc<dyn VectorRepository>,
+        embedding_service: Arc<CachedEmbeddingService>,
+        analytics: Arc<crate::infrastructure::operations::analytics::ProductionSearchAnalytics>,
+        plugins: Arc<PluginHost>,
+        scripts: Arc<ScriptHost>,
+        deduplicator: Option<Arc<ResultDeduplicator>>,
+        calibrator: Option<Arc<ScoreCalibrator>>,
+        late_interaction: Option<zero_latency_search::LateInteractionStep>,
+        intent_routing: bool,
+    ) -> Result<SearchPipeline> {
         // Create enhanced search components
         let query_enhancer = Arc::new(SimpleQueryEnhancer::new());
         let result_ranker = Arc::new(MultiFactorResultRanker::new());
@@ -195,12 +791,36 @@ impl ServiceContainer {
         // Build the enhanced pipeline: Query Enhancement → Vector Search → Result Ranking → Analytics
         tracing::info!("Activating advanced search pipeline: QueryEnhancementStep, VectorSearchStep, ResultRankingStep, AnalyticsStep");
         let analytics_step = Box::new(zero_latency_search::services::AnalyticsStep::new(analytics));
-        let pipeline = SearchPipeline::builder()
+        let mut builder = SearchPipeline::builder();
+        // The query's intent decides how the remaining steps treat it
+        if intent_routing {
+            builder = builder.add_step(Box::new(QueryIntentStep));
+        }
+        builder = builder
             .add_step(query_enhancement_step)
             .add_step(vector_search_step)
-            .add_step(result_ranking_step)
-            .add_step(analytics_step)
-            .build();
+            .add_step(result_ranking_step);
+        // Token vectors rescore the top of the built-in ranking
+        if let Some(step) = late_interaction {
+            builder = builder.add_step(Box::new(step));
+        }
+        // Ranker plugins refine the built-in ranking
+        if plugins.has_rankers() {
+            builder = builder.add_step(Box::new(PluginRankingStep::new(plugins)));
+        }
+        // Score scripts adjust the final ranking per collection
+        if scripts.is_enabled() {
+            builder = builder.add_step(Box::new(ScriptScoringStep::new(scripts)));
+        }
+        // Final scores are calibrated once every step has adjusted them
+        if let Some(calibrator) = calibrator {
+            builder = builder.add_step(Box::new(ScoreCalibrationStep::new(calibrator)));
+        }
+        // Mirrored copies are collapsed once the final ranking is known
+        if let Some(deduplicator) = deduplicator {
+            builder = builder.add_step(Box::new(DeduplicationStep::new(deduplicator)));
+        }
+        let pipeline = builder.add_step(analytics_step).build();
         tracing::info!("Advanced search pipeline is now active.");
         Ok(pipeline)
     }
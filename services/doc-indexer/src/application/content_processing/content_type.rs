@@ -38,6 +38,26 @@ pub enum ContentType {
 }
 
 impl ContentType {
+    /// MIME type for serving files of this type
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ContentType::Markdown => "text/markdown",
+            ContentType::PlainText | ContentType::Config => "text/plain",
+            ContentType::Html => "text/html",
+            ContentType::RestructuredText => "text/x-rst",
+            ContentType::AsciiDoc => "text/asciidoc",
+            ContentType::OrgMode => "text/org",
+            ContentType::Json => "application/json",
+            ContentType::Yaml => "application/yaml",
+            ContentType::Toml => "application/toml",
+            ContentType::Rust => "text/x-rust",
+            ContentType::JavaScript => "text/javascript",
+            ContentType::Python => "text/x-python",
+            ContentType::Shell => "text/x-shellscript",
+            ContentType::Unknown => "application/octet-stream",
+        }
+    }
+
     /// Check if this content type should be indexed
     ///
     /// This follows SRP by separating indexing policy from content processing
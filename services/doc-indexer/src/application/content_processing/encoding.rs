@@ -0,0 +1,213 @@
+//! Raw byte decoding for ingested files
+//!
+//! Follows SRP by having a single responsibility: turning the raw bytes of a
+//! file into indexable text, or rejecting them as binary. Handles BOM-marked
+//! UTF-8/UTF-16, BOM-less UTF-16 and falls back to Latin-1 so that legacy
+//! files are transcoded instead of stored as mojibake.
+
+/// Number of leading bytes inspected when sniffing for binary content
+const SNIFF_LEN: usize = 8192;
+
+/// Fraction of control bytes above which a sample is treated as binary
+const BINARY_CONTROL_RATIO: f64 = 0.3;
+
+/// Text encodings recognised by the decoder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl TextEncoding {
+    /// Canonical label used in document metadata
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "utf-8",
+            TextEncoding::Utf16Le => "utf-16le",
+            TextEncoding::Utf16Be => "utf-16be",
+            TextEncoding::Latin1 => "iso-8859-1",
+        }
+    }
+}
+
+/// Outcome of decoding a file's bytes
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedContent {
+    /// Text content with the encoding it was decoded from
+    Text {
+        content: String,
+        encoding: TextEncoding,
+    },
+    /// Content that does not look like text and should not be indexed
+    Binary,
+}
+
+/// Service responsible for decoding raw file bytes into text
+pub struct ContentDecoder;
+
+impl ContentDecoder {
+    /// Decode raw bytes into text, detecting the encoding
+    pub fn decode(bytes: &[u8]) -> DecodedContent {
+        // Byte order marks are authoritative
+        if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+            return match std::str::from_utf8(rest) {
+                Ok(text) => Self::text(text.to_string(), TextEncoding::Utf8),
+                Err(_) => DecodedContent::Binary,
+            };
+        }
+        if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+            return Self::decode_utf16(rest, TextEncoding::Utf16Le);
+        }
+        if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+            return Self::decode_utf16(rest, TextEncoding::Utf16Be);
+        }
+
+        // BOM-less UTF-16 shows up as NUL bytes in every other position
+        if let Some(encoding) = Self::sniff_utf16(bytes) {
+            return Self::decode_utf16(bytes, encoding);
+        }
+
+        if Self::looks_binary(bytes) {
+            return DecodedContent::Binary;
+        }
+
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::text(text.to_string(), TextEncoding::Utf8),
+            // Every byte sequence is valid Latin-1, so this never fails
+            Err(_) => Self::text(
+                bytes.iter().map(|&b| b as char).collect(),
+                TextEncoding::Latin1,
+            ),
+        }
+    }
+
+    /// Check whether a byte sample looks like binary (non-text) content
+    ///
+    /// Any NUL byte, or a high ratio of non-whitespace control bytes within
+    /// the sniffed prefix, marks the content as binary.
+    pub fn looks_binary(bytes: &[u8]) -> bool {
+        let sample = &bytes[..bytes.len().min(SNIFF_LEN)];
+        if sample.is_empty() {
+            return false;
+        }
+        if sample.contains(&0) {
+            return true;
+        }
+
+        let control = sample
+            .iter()
+            .filter(|&&b| b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t' | 0x0C))
+            .count();
+
+        (control as f64 / sample.len() as f64) > BINARY_CONTROL_RATIO
+    }
+
+    /// Detect BOM-less UTF-16 from the distribution of NUL bytes
+    fn sniff_utf16(bytes: &[u8]) -> Option<TextEncoding> {
+        let sample = &bytes[..bytes.len().min(SNIFF_LEN) & !1];
+        if sample.len() < 4 {
+            return None;
+        }
+
+        let pairs = sample.len() / 2;
+        let even_nuls = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+        let odd_nuls = sample
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .filter(|&&b| b == 0)
+            .count();
+
+        // Mostly-ASCII UTF-16 text has a NUL in nearly every high byte
+        // and almost none in the low bytes
+        let threshold = pairs * 9 / 10;
+        if odd_nuls >= threshold && even_nuls == 0 {
+            Some(TextEncoding::Utf16Le)
+        } else if even_nuls >= threshold && odd_nuls == 0 {
+            Some(TextEncoding::Utf16Be)
+        } else {
+            None
+        }
+    }
+
+    fn decode_utf16(bytes: &[u8], encoding: TextEncoding) -> DecodedContent {
+        let units = bytes.chunks_exact(2).map(|pair| match encoding {
+            TextEncoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+            _ => u16::from_le_bytes([pair[0], pair[1]]),
+        });
+
+        match char::decode_utf16(units).collect::<Result<String, _>>() {
+            Ok(text) => Self::text(text, encoding),
+            Err(_) => DecodedContent::Binary,
+        }
+    }
+
+    fn text(content: String, encoding: TextEncoding) -> DecodedContent {
+        DecodedContent::Text { content, encoding }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoded(bytes: &[u8]) -> (String, TextEncoding) {
+        match ContentDecoder::decode(bytes) {
+            DecodedContent::Text { content, encoding } => (content, encoding),
+            DecodedContent::Binary => panic!("expected text content"),
+        }
+    }
+
+    #[test]
+    fn test_decode_utf8_and_bom() {
+        assert_eq!(
+            decoded("héllo".as_bytes()),
+            ("héllo".to_string(), TextEncoding::Utf8)
+        );
+        assert_eq!(
+            decoded(b"\xEF\xBB\xBFhello"),
+            ("hello".to_string(), TextEncoding::Utf8)
+        );
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        let le: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain("hi é".encode_utf16().flat_map(|u| u.to_le_bytes()))
+            .collect();
+        assert_eq!(decoded(&le), ("hi é".to_string(), TextEncoding::Utf16Le));
+
+        let be_no_bom: Vec<u8> = "# Title\nbody"
+            .encode_utf16()
+            .flat_map(|u| u.to_be_bytes())
+            .collect();
+        assert_eq!(
+            decoded(&be_no_bom),
+            ("# Title\nbody".to_string(), TextEncoding::Utf16Be)
+        );
+    }
+
+    #[test]
+    fn test_decode_latin1_fallback() {
+        assert_eq!(
+            decoded(b"caf\xE9 cr\xE8me"),
+            ("café crème".to_string(), TextEncoding::Latin1)
+        );
+    }
+
+    #[test]
+    fn test_binary_detection() {
+        assert_eq!(
+            ContentDecoder::decode(b"\x7FELF\x02\x01\x01\x00\x00\x00"),
+            DecodedContent::Binary
+        );
+        assert_eq!(
+            ContentDecoder::decode(&[0x01, 0x02, 0x03, 0x04, b'a', 0x05]),
+            DecodedContent::Binary
+        );
+        assert!(!ContentDecoder::looks_binary(b"plain text\n\twith tabs"));
+    }
+}
@@ -0,0 +1,351 @@
+/// Heading structure of indexed documents
+///
+/// Content processing flattens markup, so headings are read from the source
+/// text and then located in the processed text, where each is still a line of
+/// its own. Chunking splits a document into sections at the located headings
+/// and gives each chunk the path of headings it falls under, which the
+/// document outline is built from. Headings also carry the anchor a renderer
+/// gives them, for deep links into a section.
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use super::ContentType;
+
+/// A heading of a source document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    /// 1 for a top-level heading, up to 6
+    pub level: usize,
+    /// Heading text without markup
+    pub text: String,
+    /// Fragment linking to the heading: the slug GitHub renders for a
+    /// Markdown heading, or the `id` of an HTML one
+    pub anchor: Option<String>,
+    /// The heading line as content processing leaves it, for locating it
+    match_key: String,
+}
+
+/// A heading and the byte offset of its line in processed content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocatedHeading {
+    pub heading: Heading,
+    pub offset: usize,
+}
+
+/// A stretch of a document under one heading path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// Byte range in the content, starting with the heading line
+    pub range: Range<usize>,
+    /// Headings the section falls under, outermost first; empty before the
+    /// first heading
+    pub heading_path: Vec<String>,
+    /// Anchor of the innermost heading
+    pub anchor: Option<String>,
+}
+
+/// Headings of a source document, in document order
+///
+/// Markdown ATX headings outside fenced code and HTML `<h1>`-`<h6>`
+/// elements are recognized; other content types have none.
+pub fn extract_headings(source: &str, content_type: &ContentType) -> Vec<Heading> {
+    match content_type {
+        ContentType::Markdown => markdown_headings(source),
+        ContentType::Html => html_headings(source),
+        _ => Vec::new(),
+    }
+}
+
+fn markdown_headings(source: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut slugs = HashMap::new();
+    let mut in_fence = false;
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        // Indented by four spaces or more, a line is code
+        if in_fence || line.len() - trimmed.len() > 3 {
+            continue;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        let rest = &trimmed[level..];
+        if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+            continue;
+        }
+        // A closing sequence of #s is not part of the text, though
+        // processing keeps it
+        let line = rest.trim();
+        let text = match line.trim_end_matches('#') {
+            stripped if stripped.is_empty() || stripped.ends_with([' ', '\t']) => stripped,
+            _ => line,
+        };
+        let rendered = link_pattern().replace_all(text, "$1").replace('`', "");
+        let text = normalize(&rendered);
+        if !text.is_empty() {
+            headings.push(Heading {
+                level,
+                text,
+                anchor: Some(github_anchor(&rendered, &mut slugs)),
+                match_key: match_key(line),
+            });
+        }
+    }
+    headings
+}
+
+fn html_headings(source: &str) -> Vec<Heading> {
+    static HEADING: OnceLock<Regex> = OnceLock::new();
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    static ID: OnceLock<Regex> = OnceLock::new();
+    let heading =
+        HEADING.get_or_init(|| Regex::new(r"(?is)<h([1-6])([^>]*)>(.*?)</h[1-6]\s*>").unwrap());
+    let tag = TAG.get_or_init(|| Regex::new(r"<[^>]*>").unwrap());
+    let id = ID.get_or_init(|| Regex::new(r#"(?i)\bid\s*=\s*["']([^"']+)["']"#).unwrap());
+    heading
+        .captures_iter(source)
+        .filter_map(|captures| {
+            let text = normalize(&tag.replace_all(&captures[3], ""));
+            (!text.is_empty()).then(|| Heading {
+                level: captures[1].parse().unwrap_or(1),
+                anchor: id.captures(&captures[2]).map(|id| id[1].to_string()),
+                match_key: text.clone(),
+                text,
+            })
+        })
+        .collect()
+}
+
+/// The anchor GitHub renders for a heading: its text lowercased, without
+/// punctuation and with spaces as hyphens, suffixed `-1`, `-2`, ... when
+/// an earlier heading of the document has the same slug
+fn github_anchor(text: &str, slugs: &mut HashMap<String, usize>) -> String {
+    let slug: String = text
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect();
+    let seen = slugs.entry(slug.clone()).or_insert(0);
+    let anchor = match *seen {
+        0 => slug,
+        n => format!("{}-{}", slug, n),
+    };
+    *seen += 1;
+    anchor
+}
+
+/// Find each heading's line in `content`, keeping document order
+///
+/// Headings whose line cannot be found after the previous heading's, e.g.
+/// because a plugin rewrote the content, are left out.
+pub fn locate_headings(content: &str, headings: Vec<Heading>) -> Vec<LocatedHeading> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        lines.push((offset, line));
+        offset += line.len();
+    }
+
+    let mut located = Vec::new();
+    let mut next_line = 0;
+    for heading in headings {
+        let found = lines[next_line.min(lines.len())..]
+            .iter()
+            .position(|(_, line)| {
+                match_key(line.trim_start().trim_start_matches('#')) == heading.match_key
+            });
+        if let Some(position) = found {
+            let line = next_line + position;
+            located.push(LocatedHeading {
+                heading,
+                offset: lines[line].0,
+            });
+            next_line = line + 1;
+        }
+    }
+    located
+}
+
+/// Split `content` into sections at the located headings
+///
+/// Text before the first heading is a section without a heading path. A
+/// heading directly followed by a subheading starts no section of its own;
+/// its line opens the subheading's section, so no chunk holds a bare heading.
+pub fn sections(content: &str, headings: &[LocatedHeading]) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let first = headings
+        .first()
+        .map_or(content.len(), |heading| heading.offset);
+    if !content[..first].trim().is_empty() || headings.is_empty() {
+        sections.push(Section {
+            range: 0..first,
+            heading_path: Vec::new(),
+            anchor: None,
+        });
+    }
+
+    let mut stack: Vec<&Heading> = Vec::new();
+    let mut pending_start = None;
+    for (i, located) in headings.iter().enumerate() {
+        while stack
+            .last()
+            .is_some_and(|open| open.level >= located.heading.level)
+        {
+            stack.pop();
+        }
+        stack.push(&located.heading);
+
+        let end = headings
+            .get(i + 1)
+            .map_or(content.len(), |next| next.offset);
+        let start = pending_start.take().unwrap_or(located.offset);
+        let body = content[located.offset..end]
+            .split_once('\n')
+            .map_or("", |(_, body)| body);
+        let opens_subsection = headings
+            .get(i + 1)
+            .is_some_and(|next| next.heading.level > located.heading.level);
+        if body.trim().is_empty() && opens_subsection {
+            pending_start = Some(start);
+            continue;
+        }
+        sections.push(Section {
+            range: start..end,
+            heading_path: stack.iter().map(|heading| heading.text.clone()).collect(),
+            anchor: located.heading.anchor.clone(),
+        });
+    }
+    sections
+}
+
+/// `[text](url)` links
+fn link_pattern() -> &'static Regex {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    LINK.get_or_init(|| Regex::new(r"\[([^\]]+)\]\([^)]+\)").unwrap())
+}
+
+/// Text of a heading line as content processing leaves it: links reduced
+/// to their text, inline code dropped, whitespace collapsed
+fn match_key(line: &str) -> String {
+    static CODE: OnceLock<Regex> = OnceLock::new();
+    let code = CODE.get_or_init(|| Regex::new(r"`[^`]+`").unwrap());
+    let text = link_pattern().replace_all(line, "$1");
+    normalize(&code.replace_all(&text, ""))
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUIDE: &str = "Intro text.\n\
+        # Guide\n\
+        ## Install `v2`\n\
+        Run the [installer](install.sh).\n\
+        ```\n\
+        # not a heading\n\
+        ```\n\
+        ## Configure ##\n\
+        Edit the file.\n\
+        # Reference\n\
+        Flags.\n";
+
+    #[test]
+    fn test_markdown_headings_are_located_in_processed_content() {
+        let headings = extract_headings(GUIDE, &ContentType::Markdown);
+        let texts: Vec<(usize, &str)> = headings
+            .iter()
+            .map(|heading| (heading.level, heading.text.as_str()))
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                (1, "Guide"),
+                (2, "Install v2"),
+                (2, "Configure"),
+                (1, "Reference")
+            ]
+        );
+
+        // Processing strips the markers, links and inline code
+        let processed = "Intro text.\nGuide\nInstall \nRun the installer.\n\nConfigure ##\nEdit the file.\nReference\nFlags.\n";
+        let located = locate_headings(processed, headings);
+        assert_eq!(located.len(), 4);
+        assert_eq!(
+            &processed[located[1].offset..located[2].offset],
+            "Install \nRun the installer.\n\n"
+        );
+
+        let sections = sections(processed, &located);
+        let paths: Vec<Vec<&str>> = sections
+            .iter()
+            .map(|section| section.heading_path.iter().map(String::as_str).collect())
+            .collect();
+        // "Guide" has no text of its own, so it opens its first subsection
+        assert_eq!(
+            paths,
+            vec![
+                vec![],
+                vec!["Guide", "Install v2"],
+                vec!["Guide", "Configure"],
+                vec!["Reference"]
+            ]
+        );
+        assert!(processed[sections[1].range.clone()].starts_with("Guide\nInstall"));
+        assert_eq!(sections[1].anchor.as_deref(), Some("install-v2"));
+        assert_eq!(sections[2].anchor.as_deref(), Some("configure"));
+        assert_eq!(sections[3].range.end, processed.len());
+    }
+
+    #[test]
+    fn test_markdown_anchors_follow_github() {
+        let headings = extract_headings(
+            "# What's new in **v2.0**?\n## FAQ\n## FAQ\n## Über `uns`\n",
+            &ContentType::Markdown,
+        );
+        let anchors: Vec<&str> = headings
+            .iter()
+            .map(|heading| heading.anchor.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            anchors,
+            vec!["whats-new-in-v20", "faq", "faq-1", "über-uns"]
+        );
+    }
+
+    #[test]
+    fn test_html_headings_and_unstructured_content() {
+        let headings = extract_headings(
+            "<h1 class=\"title\">API <em>Guide</em></h1><p>x</p>\n<H2>Auth</H2>",
+            &ContentType::Html,
+        );
+        assert_eq!(headings.len(), 2);
+        assert_eq!(
+            (headings[0].level, headings[0].text.as_str()),
+            (1, "API Guide")
+        );
+        assert_eq!(headings[1].text, "Auth");
+        assert_eq!(headings[0].anchor, None);
+
+        let anchored = extract_headings("<h2 id=\"auth\">Auth</h2>", &ContentType::Html);
+        assert_eq!(anchored[0].anchor.as_deref(), Some("auth"));
+
+        assert!(extract_headings("# comment", &ContentType::Python).is_empty());
+        let whole = sections("Plain text.", &[]);
+        assert_eq!(whole.len(), 1);
+        assert_eq!(whole[0].range, 0..11);
+        assert!(whole[0].heading_path.is_empty());
+    }
+}
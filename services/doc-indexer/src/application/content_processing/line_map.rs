@@ -0,0 +1,121 @@
+/// Source line numbers of processed content
+///
+/// Content processing rewrites lines and drops some (markup, fenced code,
+/// JSON lines without a key), so chunk offsets in processed content do not
+/// point into the source file. Each processed line is aligned back to the
+/// source instead: it is matched, in order, to the first source line at or
+/// after the previous match that holds its words in the same order. Lines
+/// without words, or that cannot be matched, take the line of the previous
+/// match.
+use std::ops::Range;
+
+/// How many source lines past the previous match a processed line is looked
+/// for, bounding the work a rewritten document costs
+const MAX_LOOKAHEAD: usize = 1000;
+
+/// Maps byte offsets of processed content to 1-based source lines
+#[derive(Debug, Clone, Default)]
+pub struct LineMap {
+    /// Byte offset of each processed line
+    offsets: Vec<usize>,
+    /// Source line of each processed line
+    source_lines: Vec<usize>,
+}
+
+impl LineMap {
+    /// Align the lines of `processed` with those of `source`
+    pub fn new(source: &str, processed: &str) -> Self {
+        let source_words: Vec<Vec<&str>> = source.lines().map(words).collect();
+        let mut offsets = Vec::new();
+        let mut source_lines = Vec::new();
+        // Source line of the previous match and the words it has used up, so
+        // that several processed lines can come from one source line
+        let (mut line, mut used) = (0, 0);
+        let mut offset = 0;
+        for processed_line in processed.split_inclusive('\n') {
+            let wanted = words(processed_line);
+            if !wanted.is_empty() {
+                let last = source_words.len().min(line + MAX_LOOKAHEAD);
+                let found = (line..last).find_map(|candidate| {
+                    let skip = if candidate == line { used } else { 0 };
+                    match_words(&wanted, &source_words[candidate][skip..])
+                        .map(|end| (candidate, skip + end))
+                });
+                if let Some(found) = found {
+                    (line, used) = found;
+                }
+            }
+            offsets.push(offset);
+            source_lines.push(line + 1);
+            offset += processed_line.len();
+        }
+        Self {
+            offsets,
+            source_lines,
+        }
+    }
+
+    /// First and last source line of a byte range of processed content
+    pub fn lines(&self, range: Range<usize>) -> (usize, usize) {
+        let line_at = |offset: usize| {
+            let index = self.offsets.partition_point(|start| *start <= offset);
+            self.source_lines
+                .get(index.saturating_sub(1))
+                .copied()
+                .unwrap_or(1)
+        };
+        let start = line_at(range.start);
+        (start, line_at(range.end.saturating_sub(1)).max(start))
+    }
+}
+
+fn words(line: &str) -> Vec<&str> {
+    line.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Where `wanted` ends if it is a subsequence of `words`
+fn match_words(wanted: &[&str], words: &[&str]) -> Option<usize> {
+    let mut position = 0;
+    for word in wanted {
+        position += words[position..].iter().position(|w| w == word)? + 1;
+    }
+    Some(position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_processed_lines_map_to_their_source_lines() {
+        let source = "# Guide\n\
+            Run the [installer](install.sh).\n\
+            ```\n\
+            installer --help\n\
+            ```\n\
+            Run the installer again.\n\
+            Run the installer again.\n";
+        let processed =
+            "Guide\nRun the installer.\n\nRun the installer again.\nRun the installer again.\n";
+        let map = LineMap::new(source, processed);
+        let offset = |needle: &str| processed.find(needle).unwrap();
+
+        assert_eq!(map.lines(0..5), (1, 1));
+        assert_eq!(map.lines(offset("Run")..offset("Run") + 4), (2, 2));
+        // The fenced code was dropped, and a repeated line takes the next
+        // source line rather than the first
+        let again = processed.rfind("Run").unwrap();
+        assert_eq!(map.lines(offset("Guide")..again + 5), (1, 7));
+        assert_eq!(map.lines(offset(" again")..offset(" again") + 3), (6, 6));
+
+        // One source line can split into several processed lines
+        let html = LineMap::new(
+            "<p>One.</p><p>Two.</p>\n<p>Three.</p>",
+            "One.\n\nTwo.\nThree.",
+        );
+        assert_eq!(html.lines(6..10), (1, 1));
+        assert_eq!(html.lines(11..17), (2, 2));
+    }
+}
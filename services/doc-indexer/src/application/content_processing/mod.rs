@@ -7,12 +7,16 @@
 /// - Dependency Inversion: Depends on abstractions, not concretions
 pub mod content_type;
 pub mod detector;
+pub mod encoding;
 pub mod handlers;
+pub mod headings;
+pub mod line_map;
 pub mod processor;
 pub mod registry;
 
 pub use content_type::ContentType;
 pub use detector::ContentTypeDetector;
+pub use encoding::{ContentDecoder, DecodedContent, TextEncoding};
 pub use handlers::ContentHandler;
 pub use processor::ContentProcessor;
 pub use registry::ContentProcessorRegistry;
@@ -68,7 +68,10 @@ impl StandardIndexingStrategy {
     }
 
     /// Chunk document content into smaller pieces
-    fn chunk_content(&self, content: &str) -> Vec<String> {
+    ///
+    /// Always terminates: every window covers at least one word and advances
+    /// by at least one word, whatever the configured size and overlap.
+    pub fn chunk_content(&self, content: &str) -> Vec<String> {
         let words: Vec<&str> = content.split_whitespace().collect();
         let mut chunks = Vec::new();
 
@@ -76,8 +79,9 @@ impl StandardIndexingStrategy {
             return chunks;
         }
 
-        let chunk_size_words = self.config.chunk_size / 6; // Approximate words per chunk
+        let chunk_size_words = (self.config.chunk_size / 6).max(1); // Approximate words per chunk
         let overlap_words = self.config.chunk_overlap / 6;
+        let step = chunk_size_words.saturating_sub(overlap_words).max(1);
 
         let mut i = 0;
         while i < words.len() {
@@ -96,11 +100,13 @@ impl StandardIndexingStrategy {
                 }
             }
 
-            // Move forward with overlap
-            i += chunk_size_words.saturating_sub(overlap_words);
-            if i == 0 {
-                i = 1; // Prevent infinite loop
+            // The last window reached the end; later ones would only repeat its tail
+            if end == words.len() {
+                break;
             }
+
+            // Move forward with overlap
+            i += step;
         }
 
         chunks
@@ -158,6 +164,7 @@ impl IndexingStrategy for StandardIndexingStrategy {
             let vector_doc = VectorDocument {
                 id: Uuid::new_v4(),
                 embedding,
+                named_vectors: Default::default(),
                 metadata: VectorMetadata {
                     document_id: document.id,
                     chunk_index,
@@ -167,6 +174,9 @@ impl IndexingStrategy for StandardIndexingStrategy {
                     url: None,
                     collection: Some(collection.to_string()),
                     custom: HashMap::new(),
+                    // Strategies embed through a service that doesn't name its model
+                    embedding_model: None,
+                    embedded_at: Some(chrono::Utc::now()),
                 },
             };
 
@@ -255,6 +265,7 @@ impl IndexingStrategy for FastIndexingStrategy {
             let vector_doc = VectorDocument {
                 id: document.id,
                 embedding,
+                named_vectors: Default::default(),
                 metadata: VectorMetadata {
                     document_id: document.id,
                     chunk_index: 0,
@@ -264,6 +275,8 @@ impl IndexingStrategy for FastIndexingStrategy {
                     url: None,
                     collection: Some(collection.to_string()),
                     custom: HashMap::from([("strategy".to_string(), "fast_single".to_string())]),
+                    embedding_model: None,
+                    embedded_at: Some(chrono::Utc::now()),
                 },
             };
 
@@ -308,6 +321,7 @@ impl IndexingStrategy for FastIndexingStrategy {
                     let vector_doc = VectorDocument {
                         id: Uuid::new_v4(),
                         embedding,
+                        named_vectors: Default::default(),
                         metadata: VectorMetadata {
                             document_id: document.id,
                             chunk_index,
@@ -320,6 +334,8 @@ impl IndexingStrategy for FastIndexingStrategy {
                                 "strategy".to_string(),
                                 "fast_chunked".to_string(),
                             )]),
+                            embedding_model: None,
+                            embedded_at: Some(chrono::Utc::now()),
                         },
                     };
 
@@ -477,6 +493,7 @@ impl IndexingStrategy for PrecisionIndexingStrategy {
             let vector_doc = VectorDocument {
                 id: Uuid::new_v4(),
                 embedding,
+                named_vectors: Default::default(),
                 metadata: VectorMetadata {
                     document_id: document.id,
                     chunk_index,
@@ -486,6 +503,8 @@ impl IndexingStrategy for PrecisionIndexingStrategy {
                     url: None,
                     collection: Some(collection.to_string()),
                     custom: HashMap::from([("strategy".to_string(), "precision".to_string())]),
+                    embedding_model: None,
+                    embedded_at: Some(chrono::Utc::now()),
                 },
             };
 
@@ -514,3 +533,106 @@ impl IndexingStrategy for PrecisionIndexingStrategy {
         self.config.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn strategy(
+        chunk_size: usize,
+        chunk_overlap: usize,
+        max_chunks_per_document: Option<usize>,
+        min_chunk_size: usize,
+    ) -> StandardIndexingStrategy {
+        StandardIndexingStrategy::with_config(StrategyConfig {
+            name: "test".to_string(),
+            chunk_size,
+            chunk_overlap,
+            max_chunks_per_document,
+            min_chunk_size,
+        })
+    }
+
+    #[test]
+    fn test_chunk_content_terminates_when_overlap_covers_chunk() {
+        let chunks = strategy(16, 16, None, 0).chunk_content("one two three four five six");
+        assert_eq!(chunks.first().map(String::as_str), Some("one two"));
+        assert_eq!(chunks.last().map(String::as_str), Some("five six"));
+        assert_eq!(chunks.len(), 5);
+
+        let chunks = strategy(0, 0, None, 0).chunk_content("one two three");
+        assert_eq!(chunks, vec!["one", "two", "three"]);
+    }
+
+    #[test]
+    fn test_chunk_content_does_not_repeat_tail() {
+        let chunks = strategy(24, 6, None, 0).chunk_content("a b c d e f g");
+        assert_eq!(chunks, vec!["a b c d", "d e f g"]);
+    }
+
+    #[test]
+    fn test_chunk_content_seed_cases() {
+        // Seeds use the fuzz target layout: four big-endian u16 settings, then text
+        for seed in [
+            &include_bytes!("../../fuzz/corpus/chunker/markdown_document")[..],
+            &include_bytes!("../../fuzz/corpus/chunker/overlap_equals_size")[..],
+        ] {
+            let field = |i: usize| u16::from_be_bytes([seed[i * 2], seed[i * 2 + 1]]) as usize;
+            let content = std::str::from_utf8(&seed[8..]).unwrap();
+            let chunks =
+                strategy(field(0), field(1), Some(field(2)), field(3)).chunk_content(content);
+
+            assert!(!chunks.is_empty());
+            assert!(chunks.len() <= field(2));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn chunk_content_respects_limits(
+            content in "\\PC{0,400}",
+            chunk_size in 0usize..256,
+            chunk_overlap in 0usize..512,
+            max_chunks in prop::option::of(1usize..16),
+            min_chunk_size in 0usize..64,
+        ) {
+            let chunks = strategy(chunk_size, chunk_overlap, max_chunks, min_chunk_size)
+                .chunk_content(&content);
+
+            let word_count = content.split_whitespace().count();
+            prop_assert!(chunks.len() <= word_count);
+            if let Some(max_chunks) = max_chunks {
+                prop_assert!(chunks.len() <= max_chunks);
+            }
+            for chunk in &chunks {
+                prop_assert!(!chunk.is_empty());
+                prop_assert!(chunk.len() >= min_chunk_size);
+                prop_assert!(chunk.split_whitespace().count() <= (chunk_size / 6).max(1));
+            }
+        }
+
+        #[test]
+        fn chunk_content_covers_every_word(
+            word_count in 0usize..80,
+            chunk_size in 0usize..128,
+            chunk_overlap in 0usize..128,
+        ) {
+            let content = (0..word_count).map(|n| format!("w{}", n)).collect::<Vec<_>>().join(" ");
+            let chunks = strategy(chunk_size, chunk_overlap, None, 0).chunk_content(&content);
+
+            // Each chunk is a contiguous run of word positions with no gaps between chunks
+            let mut covered = 0;
+            for chunk in &chunks {
+                let positions: Vec<usize> = chunk
+                    .split_whitespace()
+                    .map(|word| word[1..].parse().unwrap())
+                    .collect();
+                prop_assert!(positions[0] <= covered);
+                prop_assert!(positions.windows(2).all(|pair| pair[1] == pair[0] + 1));
+                covered = positions[positions.len() - 1] + 1;
+            }
+            prop_assert_eq!(covered, word_count);
+        }
+    }
+}
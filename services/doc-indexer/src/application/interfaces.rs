@@ -23,6 +23,14 @@ pub trait VectorStorage: Send + Sync {
     /// Remove vectors for a specific document
     async fn remove_vectors(&self, document_id: &str) -> Result<()>;
 
+    /// Remove the vectors of several documents in batch
+    async fn remove_vectors_batch(&self, document_ids: &[String]) -> Result<()> {
+        for document_id in document_ids {
+            self.remove_vectors(document_id).await?;
+        }
+        Ok(())
+    }
+
     /// Check if vectors exist for a document
     async fn has_vectors(&self, document_id: &str) -> Result<bool>;
 }
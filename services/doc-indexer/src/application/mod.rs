@@ -22,8 +22,10 @@ pub use content_processing::ContentProcessor;
 pub use container::ServiceContainer;
 pub use concurrent_container::ConcurrentServiceContainer;
 pub use services::{
-    collection_service::CollectionService, document_service::DocumentIndexingService,
-    health_service::HealthService,
+    admin_service::AdminService, browse_service::BrowseService, collection_service::CollectionService, document_service::DocumentIndexingService,
+    health_service::HealthService, job_service::JobService, lookup_service::LookupService,
+    session_service::SessionService, summary_service::SummaryService,
+    webhook_service::WebhookService,
 };
 
 // Export SOLID-compliant services
@@ -0,0 +1,249 @@
+/// Runtime internals and tunables for operators
+///
+/// Backs `/api/admin`: cache, buffer pool and vector store statistics, the
+/// jobs currently running, execution lane utilization, and settings that can be changed without a
+/// restart (log filter and rate limit). Access needs a bearer token; the
+/// read token may only look, the admin token may also change tunables, and
+/// the whole API is off unless an admin token is configured.
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Instant;
+use zero_latency_core::{Result, ZeroLatencyError};
+use zero_latency_vector::StorageInfo;
+
+use crate::application::services::job_service::{IndexingJob, JobService, JobStatus};
+use crate::application::{LookupService, ServiceContainer};
+use crate::config::{AdminConfig, RateLimitConfig};
+use crate::infrastructure::memory::VectorPoolStats;
+use crate::infrastructure::operations::lanes::LaneUtilization;
+use crate::infrastructure::operations::log_level::{self, LogLevelUpdate, LogLevels};
+use crate::infrastructure::operations::memory_pressure::MemoryPressureStats;
+
+/// Access an admin endpoint needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminScope {
+    Read,
+    Write,
+}
+
+/// Statistics of one cache
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheReport {
+    pub name: String,
+    pub entries: usize,
+    pub memory_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+}
+
+/// Snapshot of the service's internals
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminStats {
+    pub uptime_seconds: u64,
+    pub caches: Vec<CacheReport>,
+    pub vector_pool: VectorPoolStats,
+    pub storage: StorageInfo,
+    pub active_jobs: Vec<IndexingJob>,
+    pub lanes: Vec<LaneUtilization>,
+    pub memory: MemoryPressureStats,
+}
+
+/// Settings adjustable at runtime
+#[derive(Debug, Clone, Serialize)]
+pub struct Tunables {
+    /// Current log filter directives; `None` when the process did not
+    /// install a reloadable filter
+    pub log_level: Option<String>,
+    pub rate_limit: RateLimitConfig,
+}
+
+/// Change to the tunables; omitted fields are left as they are
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TunablesUpdate {
+    /// A level (`debug`) or `RUST_LOG`-style directives
+    pub log_level: Option<String>,
+    pub rate_limit: Option<RateLimitConfig>,
+}
+
+/// Service behind the admin API
+#[derive(Clone)]
+pub struct AdminService {
+    config: AdminConfig,
+    container: Arc<ServiceContainer>,
+    lookup_service: LookupService,
+    job_service: JobService,
+    started: Instant,
+}
+
+impl AdminService {
+    pub fn new(
+        container: Arc<ServiceContainer>,
+        lookup_service: LookupService,
+        job_service: JobService,
+    ) -> Self {
+        Self {
+            config: container.config().admin.clone(),
+            container,
+            lookup_service,
+            job_service,
+            started: Instant::now(),
+        }
+    }
+
+    /// Check a bearer token against the access an endpoint needs
+    pub fn authorize(&self, token: Option<&str>, scope: AdminScope) -> Result<()> {
+        authorize(&self.config, token, scope)
+    }
+
+    /// Collect statistics from caches, pools, storage and jobs
+    pub async fn stats(&self) -> Result<AdminStats> {
+        let query_embeddings = self.container.query_embedding_cache().statistics().await;
+        let empty_results = self.container.empty_results_cache().statistics().await;
+        let (lookup_entries, lookup) = self.lookup_service.cache_stats();
+        let caches = vec![
+            CacheReport {
+                name: "query_embeddings".to_string(),
+                entries: query_embeddings.size,
+                memory_bytes: query_embeddings.memory_usage,
+                hits: query_embeddings.hits,
+                misses: query_embeddings.misses,
+                hit_rate: query_embeddings.hit_rate,
+            },
+            CacheReport {
+                name: "empty_results".to_string(),
+                entries: empty_results.size,
+                memory_bytes: empty_results.memory_usage,
+                hits: empty_results.hits,
+                misses: empty_results.misses,
+                hit_rate: empty_results.hit_rate,
+            },
+            CacheReport {
+                name: "lookup".to_string(),
+                entries: lookup_entries,
+                memory_bytes: lookup.total_memory_bytes,
+                hits: lookup.hits,
+                misses: lookup.misses,
+                hit_rate: lookup.hit_rate(),
+            },
+        ];
+
+        let active_jobs = self
+            .job_service
+            .list_jobs()
+            .await
+            .into_iter()
+            .filter(|job| job.status == JobStatus::Running)
+            .collect();
+
+        Ok(AdminStats {
+            uptime_seconds: self.started.elapsed().as_secs(),
+            caches,
+            vector_pool: self.container.vector_pool().stats(),
+            storage: self.container.vector_repository().storage_info().await?,
+            active_jobs,
+            lanes: self.container.lanes().utilization(),
+            memory: self.container.memory_monitor().stats(),
+        })
+    }
+
+    /// Current tunables
+    pub fn tunables(&self) -> Tunables {
+        Tunables {
+            log_level: log_level::current(),
+            rate_limit: self.container.rate_limiter().settings(),
+        }
+    }
+
+    /// Apply a change to the tunables and return the result
+    pub fn update_tunables(&self, update: TunablesUpdate) -> Result<Tunables> {
+        // Validate the rate limit first so a rejected update changes nothing
+        if let Some(rate_limit) = &update.rate_limit {
+            if rate_limit.requests_per_second > 0 && rate_limit.burst == 0 {
+                return Err(ZeroLatencyError::validation(
+                    "rate_limit.burst",
+                    "must be greater than 0 when rate limiting is enabled",
+                ));
+            }
+        }
+        if let Some(level) = &update.log_level {
+            log_level::set(level)?;
+        }
+        if let Some(rate_limit) = update.rate_limit {
+            tracing::info!(
+                "Rate limit changed to {} requests/s (burst {})",
+                rate_limit.requests_per_second,
+                rate_limit.burst
+            );
+            self.container.rate_limiter().update(rate_limit)?;
+        }
+        Ok(self.tunables())
+    }
+
+    /// Log levels by target and the request sample rate
+    pub fn log_levels(&self) -> LogLevels {
+        log_level::levels()
+    }
+
+    /// Change the level of one log target or the request sample rate
+    pub fn update_log_levels(&self, update: LogLevelUpdate) -> Result<LogLevels> {
+        log_level::update(update)
+    }
+}
+
+fn authorize(config: &AdminConfig, token: Option<&str>, scope: AdminScope) -> Result<()> {
+    let admin_token = config.token.as_deref().ok_or_else(|| {
+        ZeroLatencyError::permission_denied("admin API is disabled; set DOC_INDEXER_ADMIN_TOKEN")
+    })?;
+    let token = token
+        .ok_or_else(|| ZeroLatencyError::permission_denied("admin API requires a bearer token"))?;
+
+    if tokens_match(token, admin_token) {
+        return Ok(());
+    }
+    match &config.read_token {
+        Some(read_token) if tokens_match(token, read_token) => match scope {
+            AdminScope::Read => Ok(()),
+            AdminScope::Write => Err(ZeroLatencyError::permission_denied(
+                "changing tunables requires the admin token",
+            )),
+        },
+        _ => Err(ZeroLatencyError::permission_denied("invalid admin token")),
+    }
+}
+
+// Compares digests in constant time so response timing doesn't leak the token
+pub(crate) fn tokens_match(given: &str, expected: &str) -> bool {
+    let given = Sha256::digest(given.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    given
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |difference, (a, b)| difference | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize_scopes() {
+        let disabled = AdminConfig {
+            token: None,
+            read_token: Some("reader".to_string()),
+        };
+        assert!(authorize(&disabled, Some("reader"), AdminScope::Read).is_err());
+
+        let config = AdminConfig {
+            token: Some("admin".to_string()),
+            read_token: Some("reader".to_string()),
+        };
+        assert!(authorize(&config, Some("admin"), AdminScope::Write).is_ok());
+        assert!(authorize(&config, Some("reader"), AdminScope::Read).is_ok());
+        assert!(authorize(&config, Some("reader"), AdminScope::Write).is_err());
+        assert!(authorize(&config, Some("wrong"), AdminScope::Read).is_err());
+        assert!(authorize(&config, None, AdminScope::Read).is_err());
+    }
+}
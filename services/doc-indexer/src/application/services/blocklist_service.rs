@@ -0,0 +1,52 @@
+/// Query-time blocklist management
+///
+/// Blocked documents stay indexed but drop out of every search of their
+/// collection until they are unblocked; see
+/// [`crate::infrastructure::persistence::blocklist`].
+use std::sync::Arc;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use crate::application::container::ServiceContainer;
+use crate::infrastructure::persistence::blocklist::{BlockEntry, Blocklist, NewBlockEntry};
+
+/// Application service managing blocklist entries
+#[derive(Clone)]
+pub struct BlocklistService {
+    blocklist: Arc<Blocklist>,
+}
+
+impl BlocklistService {
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            blocklist: container.blocklist(),
+        }
+    }
+
+    /// Entries of one collection, or of every collection
+    pub fn list(&self, collection: Option<&str>) -> Vec<BlockEntry> {
+        self.blocklist.list(collection)
+    }
+
+    /// Hide a document or path pattern from a collection's searches
+    pub fn add(&self, request: NewBlockEntry) -> Result<BlockEntry> {
+        let entry = self.blocklist.add(request)?;
+        self.blocklist.save()?;
+        tracing::info!(
+            "Blocked {} in {}",
+            entry
+                .path
+                .as_deref()
+                .or(entry.document.as_deref())
+                .unwrap_or_default(),
+            entry.collection
+        );
+        Ok(entry)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<()> {
+        self.blocklist
+            .remove(id)
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("blocklist entry {}", id)))?;
+        self.blocklist.save()
+    }
+}
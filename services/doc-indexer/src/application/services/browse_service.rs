@@ -0,0 +1,153 @@
+/// Browse mode for searches without content
+///
+/// A query that is empty or made only of stop words lists the selected
+/// collections' documents instead of searching them, most recently indexed
+/// or most popular first, with facet counts over every document. Request
+/// filters, exclusions, the blocklist and document ACLs apply as they do to
+/// searches.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use zero_latency_core::values::{Score, SearchQuery};
+use zero_latency_core::{Result, Uuid};
+use zero_latency_search::{
+    BrowseEntry, BrowseOrder, SearchFilters, SearchMetadata, SearchResponse, SearchResult,
+};
+use zero_latency_vector::{MetadataFilter, SimilarityResult, VectorRepository};
+
+use crate::application::container::ServiceContainer;
+use crate::config::AccessConfig;
+use crate::infrastructure::api::http::principal::access_scope;
+use crate::infrastructure::operations::analytics::ProductionSearchAnalytics;
+use crate::infrastructure::persistence::blocklist::Blocklist;
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+
+/// Application service listing documents for queries with nothing to search for
+#[derive(Clone)]
+pub struct BrowseService {
+    vector_repository: Arc<dyn VectorRepository>,
+    collection_aliases: Arc<CollectionAliases>,
+    blocklist: Arc<Blocklist>,
+    analytics: Arc<ProductionSearchAnalytics>,
+    access: AccessConfig,
+}
+
+impl BrowseService {
+    /// Create a browse service sharing the container's stores
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            vector_repository: container.vector_repository(),
+            collection_aliases: container.collection_aliases(),
+            blocklist: container.blocklist(),
+            analytics: container.analytics(),
+            access: container.config().access.clone(),
+        }
+    }
+
+    /// List the documents of `collections`, one result per document
+    pub async fn browse(
+        &self,
+        query: &str,
+        collections: &[String],
+        order: BrowseOrder,
+        limit: usize,
+        filters: &SearchFilters,
+    ) -> Result<SearchResponse> {
+        let started = Instant::now();
+        let metadata_filter =
+            MetadataFilter::compile(&filters.metadata_patterns)?.with_ranges(&filters.ranges)?;
+
+        let mut entries = Vec::new();
+        for collection in collections {
+            entries.extend(
+                self.collection_entries(collection, &metadata_filter, filters)
+                    .await?,
+            );
+        }
+        let total_count = entries.len();
+        let (results, facets) = zero_latency_search::browse_results(entries, order, limit);
+
+        Ok(SearchResponse {
+            results,
+            total_count: Some(total_count),
+            search_metadata: SearchMetadata {
+                query: SearchQuery::new(query),
+                execution_time: started.elapsed(),
+                query_enhancement_applied: false,
+                ranking_method: format!("browse:{}", order.as_str()),
+                result_sources: vec!["browse".to_string()],
+                debug_info: None,
+                stage_timings: Vec::new(),
+                effective_settings: None,
+                intent: None,
+            },
+            pagination: None,
+            answer: None,
+            confidence: None,
+            answerable: None,
+            facets: Some(facets),
+        })
+    }
+
+    /// One entry per document of a collection that the filters admit
+    async fn collection_entries(
+        &self,
+        collection: &str,
+        metadata_filter: &MetadataFilter,
+        filters: &SearchFilters,
+    ) -> Result<Vec<BrowseEntry>> {
+        let stored_collection = self.collection_aliases.resolve(collection);
+        let vectors = self
+            .vector_repository
+            .collection_vectors(&stored_collection)
+            .await?;
+        let popularity = self.analytics.document_popularity(&stored_collection).await;
+
+        // A document is shown by its first chunk and dated by its newest one
+        let mut documents: HashMap<Uuid, (usize, BrowseEntry)> = HashMap::new();
+        for vector in vectors {
+            if !metadata_filter.matches_metadata(&vector.metadata) {
+                continue;
+            }
+            let document_id = vector.metadata.document_id;
+            let chunk_index = vector.metadata.chunk_index;
+            let embedded_at = vector.metadata.embedded_at;
+            let mut result = SearchResult::from(SimilarityResult {
+                document_id: vector.id,
+                similarity: Score::zero(),
+                metadata: vector.metadata,
+            });
+            result.collection = Some(collection.to_string());
+            result.doc_id.collection = collection.to_string();
+
+            match documents.get_mut(&document_id) {
+                Some((first_chunk, entry)) => {
+                    entry.updated_at = entry.updated_at.max(embedded_at);
+                    if chunk_index < *first_chunk {
+                        *first_chunk = chunk_index;
+                        entry.result = result;
+                    }
+                }
+                None => {
+                    let popularity = popularity.get(&result.uri).copied().unwrap_or(0);
+                    let entry = BrowseEntry {
+                        result,
+                        updated_at: embedded_at,
+                        popularity,
+                    };
+                    documents.insert(document_id, (chunk_index, entry));
+                }
+            }
+        }
+
+        let mut exclusions = filters.exclude.clone();
+        exclusions.merge(self.blocklist.exclusions(collection));
+        let mut entries: Vec<BrowseEntry> =
+            documents.into_values().map(|(_, entry)| entry).collect();
+        entries.retain(|entry| !exclusions.excludes(&entry.result));
+        if let Some(principal) = access_scope(&self.access) {
+            entries.retain(|entry| principal.can_read(&entry.result.custom_metadata));
+        }
+        Ok(entries)
+    }
+}
@@ -0,0 +1,269 @@
+/// Build information for audits
+///
+/// Everything here is fixed when the binary is compiled: the Cargo features,
+/// the storage and model backends they link in, the models bundled or used by
+/// default, the locked versions of the crates involved and the external
+/// services the code can contact. Unlike [`super::capabilities`], nothing
+/// depends on configuration, so `doc-indexer about --build-info` reports the
+/// same as `/api/build-info` without starting a server, and operators can
+/// check a binary against a FIPS or self-contained deployment profile before
+/// rolling it out.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::capabilities::build_features;
+
+/// Backend compiled into the binary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedBackend {
+    pub name: String,
+    /// What the backend is used for, e.g. `vector_store` or `plugins`
+    pub role: String,
+    /// Crate providing it
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    /// Locked version of the crate
+    pub version: Option<String>,
+}
+
+/// Model bundled with the binary or used unless configured otherwise
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// `embeddings` or `summaries`
+    pub purpose: String,
+    /// `local` or `openai`
+    pub provider: String,
+    pub name: String,
+    /// Whether the model runs in-process rather than behind an API
+    pub bundled: bool,
+}
+
+/// External service the binary may contact once configured
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalService {
+    pub name: String,
+    /// Setting that turns it on; nothing is contacted while it is unset
+    pub configured_by: String,
+    /// Address used when the setting doesn't give one
+    pub default_endpoint: Option<String>,
+}
+
+/// What this binary was built with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    pub version: String,
+    /// Target triple
+    pub target: String,
+    /// Cargo profile, `debug` or `release`
+    pub profile: String,
+    /// Compiler that built the binary
+    pub rustc: String,
+    /// Optional Cargo features compiled in
+    pub features: Vec<String>,
+    pub backends: Vec<LinkedBackend>,
+    pub models: Vec<ModelInfo>,
+    /// Locked versions of the workspace crates and linked dependencies
+    pub crates: BTreeMap<String, String>,
+    pub external_services: Vec<ExternalService>,
+}
+
+impl BuildInfo {
+    /// Information about the running binary
+    pub fn current() -> Self {
+        let locked = locked_crates();
+        let version = |name: &str| locked.get(name).cloned();
+
+        let backends: Vec<LinkedBackend> = linked_backends()
+            .into_iter()
+            .map(|(name, role, crate_name)| LinkedBackend {
+                name: name.to_string(),
+                role: role.to_string(),
+                crate_name: crate_name.to_string(),
+                version: version(crate_name),
+            })
+            .collect();
+
+        let mut linked = vec![
+            "axum",
+            "tokio",
+            "reqwest",
+            "rustls",
+            "lettre",
+            "zero-latency-api",
+            "zero-latency-config",
+            "zero-latency-core",
+            "zero-latency-observability",
+            "zero-latency-search",
+            "zero-latency-vector",
+        ];
+        if cfg!(feature = "embedded") {
+            linked.push("libsqlite3-sys");
+        }
+        linked.extend(backends.iter().map(|backend| backend.crate_name.as_str()));
+        let crates = linked
+            .into_iter()
+            .filter_map(|name| version(name).map(|version| (name.to_string(), version)))
+            .collect();
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            target: env!("DOC_INDEXER_BUILD_TARGET").to_string(),
+            profile: env!("DOC_INDEXER_BUILD_PROFILE").to_string(),
+            rustc: env!("DOC_INDEXER_BUILD_RUSTC").to_string(),
+            features: build_features(),
+            backends,
+            models: models(),
+            crates,
+            external_services: external_services(),
+        }
+    }
+}
+
+/// Versions of the audited crates in the lockfile the binary was built from
+///
+/// The lockfile also pins the dependencies of features that are off, so a
+/// crate locked at several versions lists them all.
+fn locked_crates() -> BTreeMap<String, String> {
+    let mut crates: BTreeMap<String, String> = BTreeMap::new();
+    for entry in env!("DOC_INDEXER_LOCKED_CRATES").split(';') {
+        if let Some((name, version)) = entry.split_once('=') {
+            crates
+                .entry(name.to_string())
+                .and_modify(|versions| {
+                    versions.push_str(", ");
+                    versions.push_str(version);
+                })
+                .or_insert_with(|| version.to_string());
+        }
+    }
+    crates
+}
+
+/// `(name, role, crate)` of the backends the enabled features link in
+fn linked_backends() -> Vec<(&'static str, &'static str, &'static str)> {
+    let mut backends = Vec::new();
+    if cfg!(feature = "embedded") {
+        backends.extend([
+            ("sqlite", "vector_store", "rusqlite"),
+            ("onnx-runtime", "embeddings", "ort"),
+            ("tokenizers", "embeddings", "tokenizers"),
+            ("zstd", "compression", "zstd"),
+            ("aes-256-gcm", "encryption", "aes-gcm"),
+        ]);
+    }
+    if cfg!(feature = "cloud") {
+        backends.extend([
+            ("qdrant", "vector_store", "qdrant-client"),
+            ("grpc", "vector_store", "tonic"),
+        ]);
+    }
+    if cfg!(any(feature = "embedded", feature = "cloud")) {
+        backends.push(("tantivy", "keyword_index", "tantivy"));
+    }
+    if cfg!(feature = "gpu") {
+        backends.push(("cuda", "gpu_scoring", "cudarc"));
+    }
+    if cfg!(feature = "wasm-plugins") {
+        backends.push(("wasmtime", "plugins", "wasmtime"));
+    }
+    if cfg!(feature = "native-plugins") {
+        backends.push(("native", "plugins", "libloading"));
+    }
+    backends
+}
+
+fn models() -> Vec<ModelInfo> {
+    let model = |purpose: &str, provider: &str, name: &str, bundled: bool| ModelInfo {
+        purpose: purpose.to_string(),
+        provider: provider.to_string(),
+        name: name.to_string(),
+        bundled,
+    };
+    let mut models = Vec::new();
+    if cfg!(feature = "embedded") {
+        models.push(model("embeddings", "local", "local-deterministic", true));
+    }
+    if cfg!(feature = "cloud") {
+        models.push(model(
+            "embeddings",
+            "openai",
+            "text-embedding-3-small",
+            false,
+        ));
+    }
+    models.push(model("summaries", "local", "extractive", true));
+    models.push(model("summaries", "openai", "gpt-4o-mini", false));
+    models
+}
+
+fn external_services() -> Vec<ExternalService> {
+    let service =
+        |name: &str, configured_by: &str, default_endpoint: Option<&str>| ExternalService {
+            name: name.to_string(),
+            configured_by: configured_by.to_string(),
+            default_endpoint: default_endpoint.map(str::to_string),
+        };
+    let openai = Some("https://api.openai.com/v1");
+    let mut services = Vec::new();
+    if cfg!(feature = "cloud") {
+        services.extend([
+            service(
+                "qdrant",
+                "DOC_INDEXER_VECTOR_BACKEND=qdrant",
+                Some("http://localhost:6333"),
+            ),
+            service(
+                "openai_embeddings",
+                "DOC_INDEXER_EMBEDDING_PROVIDER=openai",
+                openai,
+            ),
+        ]);
+    }
+    services.extend([
+        service(
+            "openai_summaries",
+            "DOC_INDEXER_SUMMARIZER_BACKEND=openai",
+            openai,
+        ),
+        service("smtp", "DOC_INDEXER_DIGEST_SMTP_URL", None),
+        service("webhooks", "POST /api/webhooks", None),
+    ]);
+    if cfg!(feature = "chat") {
+        services.extend([
+            service("slack_alerts", "DOC_INDEXER_SLACK_ALERT_URL", None),
+            service("discord_alerts", "DOC_INDEXER_DISCORD_ALERT_URL", None),
+        ]);
+    }
+    services
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info_reports_linked_crates() {
+        let info = BuildInfo::current();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.target.is_empty());
+        assert!(info.rustc.starts_with("rustc"));
+        assert_eq!(
+            info.crates.get("zero-latency-core").map(String::as_str),
+            Some("0.1.0")
+        );
+        assert!(info.crates.contains_key("tokio"));
+        // Every linked backend is pinned by the lockfile
+        for backend in &info.backends {
+            assert!(backend.version.is_some(), "{} is unversioned", backend.name);
+            assert!(info.crates.contains_key(&backend.crate_name));
+        }
+        assert_eq!(
+            info.backends.iter().any(|backend| backend.name == "sqlite"),
+            cfg!(feature = "embedded")
+        );
+        assert!(!info
+            .external_services
+            .iter()
+            .any(|service| service.name == "qdrant" && !cfg!(feature = "cloud")));
+    }
+}
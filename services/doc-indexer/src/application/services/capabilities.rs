@@ -0,0 +1,151 @@
+/// Feature capability discovery
+///
+/// What a doc-indexer can do depends on the Cargo features it was built with
+/// and on its configuration: the web UI and the chat integration are
+/// optional features, summaries may come from an extractive summarizer or a
+/// model, and offline mode swaps cloud adapters for local ones. Clients such
+/// as the CLI read the capabilities once and adapt their UX instead of
+/// failing on endpoints this server doesn't serve.
+use serde::{Deserialize, Serialize};
+use zero_latency_config::SummarizerBackend;
+
+use crate::application::container::ServiceContainer;
+use crate::config::{EmbeddingProvider, VectorBackend};
+
+/// Embedding model vectors are generated with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingCapability {
+    /// `local`, `openai` or `mock`
+    pub provider: String,
+    pub model: String,
+    pub dimension: usize,
+}
+
+/// Features this build and configuration support
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub version: String,
+    /// Optional Cargo features compiled into this build
+    pub build_features: Vec<String>,
+    /// `memory`, `embedded` or `qdrant`
+    pub vector_backend: String,
+    pub embeddings: EmbeddingCapability,
+    /// `local` (extractive) or `openai`
+    pub summarizer: String,
+    /// Whether results are re-ranked after vector search
+    pub result_ranking: bool,
+    /// Whether vector scans are scored on a GPU
+    pub gpu_scoring: bool,
+    /// Whether searches can rerank by late interaction
+    pub late_interaction: bool,
+    /// Whether cloud adapters were replaced by local fallbacks
+    pub offline: bool,
+    /// Protocols served besides plain REST
+    pub protocols: Vec<String>,
+    /// Integrations that are configured and will run
+    pub integrations: Vec<String>,
+    /// Whether the admin API accepts requests
+    pub admin_api: bool,
+    /// Pipeline plugins loaded at startup
+    pub plugins: usize,
+}
+
+/// Reports the capabilities detected at startup
+#[derive(Clone)]
+pub struct CapabilityService {
+    capabilities: Capabilities,
+}
+
+impl CapabilityService {
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            capabilities: detect(container),
+        }
+    }
+
+    /// Features this server supports
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities.clone()
+    }
+}
+
+fn detect(container: &ServiceContainer) -> Capabilities {
+    let config = container.config();
+    let embedding_generator = container.embedding_generator();
+
+    let mut protocols = vec!["jsonrpc", "sse", "opensearch"];
+    if cfg!(feature = "web-ui") {
+        protocols.push("web_ui");
+    }
+
+    let mut integrations = Vec::new();
+    if cfg!(feature = "chat") {
+        let chat = &config.chat;
+        for (name, configured) in [
+            ("slack_commands", chat.slack_signing_secret.is_some()),
+            ("discord_commands", chat.discord_public_key.is_some()),
+            ("slack_alerts", chat.slack_alert_url.is_some()),
+            ("discord_alerts", chat.discord_alert_url.is_some()),
+        ] {
+            if configured {
+                integrations.push(name);
+            }
+        }
+    }
+    if config.digest.smtp_url.is_some() {
+        integrations.push("digest_email");
+    }
+
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        build_features: build_features(),
+        vector_backend: match config.vector.backend {
+            VectorBackend::Memory => "memory",
+            VectorBackend::Embedded => "embedded",
+            VectorBackend::Qdrant => "qdrant",
+        }
+        .to_string(),
+        embeddings: EmbeddingCapability {
+            provider: match config.embedding.provider {
+                EmbeddingProvider::Local => "local",
+                EmbeddingProvider::OpenAI => "openai",
+                EmbeddingProvider::Mock => "mock",
+            }
+            .to_string(),
+            model: embedding_generator.model_name().to_string(),
+            dimension: embedding_generator.dimension(),
+        },
+        summarizer: match config.summarizer.backend {
+            SummarizerBackend::Local => "local",
+            SummarizerBackend::OpenAI => "openai",
+        }
+        .to_string(),
+        result_ranking: config.service.enable_result_ranking,
+        gpu_scoring: container.acceleration().backend == "cuda",
+        late_interaction: container.late_interaction_available(),
+        offline: container.offline().enabled,
+        protocols: protocols.into_iter().map(String::from).collect(),
+        integrations: integrations.into_iter().map(String::from).collect(),
+        admin_api: config.admin.token.is_some() || config.admin.read_token.is_some(),
+        plugins: container.plugins().list().len(),
+    }
+}
+
+/// Optional Cargo features this binary was compiled with
+pub(crate) fn build_features() -> Vec<String> {
+    [
+        ("embedded", cfg!(feature = "embedded")),
+        ("cloud", cfg!(feature = "cloud")),
+        ("gpu", cfg!(feature = "gpu")),
+        ("wasm-plugins", cfg!(feature = "wasm-plugins")),
+        ("native-plugins", cfg!(feature = "native-plugins")),
+        ("web-ui", cfg!(feature = "web-ui")),
+        ("chat", cfg!(feature = "chat")),
+        ("profiling", cfg!(feature = "profiling")),
+        ("late-interaction", cfg!(feature = "late-interaction")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
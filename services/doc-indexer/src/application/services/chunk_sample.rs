@@ -0,0 +1,268 @@
+/// Random samples of indexed chunks
+///
+/// When relevance is off, the cause is often upstream of ranking: chunks cut
+/// mid-sentence, boilerplate indexed as content, or embeddings that place
+/// unrelated text side by side. A sample draws random chunks of a collection
+/// with their metadata and their nearest neighbors among the collection's
+/// other chunks, so chunk quality and embedding neighborhoods can be audited
+/// by eye or exported for offline review. Chunks of documents the request
+/// principal may not read are left out of both the sample and neighbors.
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use zero_latency_core::{DateTime, Result, Utc, Uuid, ZeroLatencyError};
+use zero_latency_vector::{VectorDocument, VectorRepository};
+
+use crate::application::container::ServiceContainer;
+use crate::config::AccessConfig;
+use crate::infrastructure::api::http::principal::access_scope;
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+use crate::infrastructure::persistence::vector::scoring::cosine_similarity;
+
+/// A chunk close to a sampled chunk in embedding space
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkNeighbor {
+    pub chunk_id: Uuid,
+    pub document_id: Uuid,
+    pub chunk_index: usize,
+    pub title: String,
+    pub path: Option<String>,
+    /// Cosine similarity to the sampled chunk
+    pub similarity: f32,
+    /// Whether the neighbor is another chunk of the sampled chunk's document
+    pub same_document: bool,
+}
+
+/// A randomly drawn chunk with its metadata and nearest neighbors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampledChunk {
+    pub chunk_id: Uuid,
+    pub document_id: Uuid,
+    pub chunk_index: usize,
+    pub title: String,
+    pub path: Option<String>,
+    pub heading_path: Vec<String>,
+    pub content: String,
+    pub metadata: HashMap<String, String>,
+    pub embedding_model: Option<String>,
+    pub embedded_at: Option<DateTime<Utc>>,
+    /// Most similar other chunks of the collection, closest first
+    pub neighbors: Vec<ChunkNeighbor>,
+}
+
+/// A random sample of a collection's chunks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkSample {
+    pub collection: String,
+    pub total_chunks: usize,
+    /// Seed the sample was drawn with; passing it again draws the same chunks
+    pub seed: u64,
+    pub chunks: Vec<SampledChunk>,
+}
+
+/// Application service drawing random chunks for inspection
+#[derive(Clone)]
+pub struct ChunkSampleService {
+    vector_repository: Arc<dyn VectorRepository>,
+    collection_aliases: Arc<CollectionAliases>,
+    access: AccessConfig,
+}
+
+impl ChunkSampleService {
+    /// Create a sample service sharing the container's stores
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            vector_repository: container.vector_repository(),
+            collection_aliases: container.collection_aliases(),
+            access: container.config().access.clone(),
+        }
+    }
+
+    /// Draw up to `size` random chunks of a collection with `neighbors`
+    /// nearest neighbors each
+    ///
+    /// Without a seed a fresh one is chosen; the response reports it either
+    /// way. Neighbors are found by exact comparison against every chunk of
+    /// the collection.
+    pub async fn sample(
+        &self,
+        collection: &str,
+        size: usize,
+        neighbors: usize,
+        seed: Option<u64>,
+    ) -> Result<ChunkSample> {
+        let mut vectors = self
+            .vector_repository
+            .collection_vectors(&self.collection_aliases.resolve(collection))
+            .await?;
+        if let Some(principal) = access_scope(&self.access) {
+            vectors.retain(|vector| principal.can_read(&vector.metadata.custom));
+        }
+        if vectors.is_empty() {
+            return Err(ZeroLatencyError::not_found(format!(
+                "vectors of collection {}",
+                collection
+            )));
+        }
+
+        let seed = seed.unwrap_or_else(rand::random);
+        let total_chunks = vectors.len();
+        // Comparing every sampled chunk with the whole collection is CPU bound
+        let chunks =
+            tokio::task::spawn_blocking(move || draw_sample(&vectors, size, neighbors, seed))
+                .await
+                .map_err(|e| ZeroLatencyError::internal(format!("Sampling failed: {}", e)))?;
+
+        Ok(ChunkSample {
+            collection: collection.to_string(),
+            total_chunks,
+            seed,
+            chunks,
+        })
+    }
+}
+
+// Sampled chunks in storage order, each with its nearest neighbors
+fn draw_sample(
+    vectors: &[VectorDocument],
+    size: usize,
+    neighbors: usize,
+    seed: u64,
+) -> Vec<SampledChunk> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut indices =
+        rand::seq::index::sample(&mut rng, vectors.len(), size.min(vectors.len())).into_vec();
+    indices.sort_unstable();
+
+    indices
+        .into_iter()
+        .map(|index| {
+            let vector = &vectors[index];
+            let metadata = &vector.metadata;
+            SampledChunk {
+                chunk_id: vector.id,
+                document_id: metadata.document_id,
+                chunk_index: metadata.chunk_index,
+                title: metadata.title.clone(),
+                path: metadata.custom.get("path").cloned(),
+                heading_path: metadata.heading_path.clone(),
+                content: metadata.content.clone(),
+                metadata: metadata.custom.clone(),
+                embedding_model: metadata.embedding_model.clone(),
+                embedded_at: metadata.embedded_at,
+                neighbors: nearest_neighbors(vectors, index, neighbors),
+            }
+        })
+        .collect()
+}
+
+fn nearest_neighbors(vectors: &[VectorDocument], index: usize, k: usize) -> Vec<ChunkNeighbor> {
+    let sampled = &vectors[index];
+    let mut scored: Vec<(f32, &VectorDocument)> = vectors
+        .iter()
+        .enumerate()
+        .filter(|(other, _)| *other != index)
+        .map(|(_, vector)| {
+            (
+                cosine_similarity(&sampled.embedding, &vector.embedding),
+                vector,
+            )
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.truncate(k);
+
+    scored
+        .into_iter()
+        .map(|(similarity, vector)| ChunkNeighbor {
+            chunk_id: vector.id,
+            document_id: vector.metadata.document_id,
+            chunk_index: vector.metadata.chunk_index,
+            title: vector.metadata.title.clone(),
+            path: vector.metadata.custom.get("path").cloned(),
+            similarity,
+            same_document: vector.metadata.document_id == sampled.metadata.document_id,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, VectorBackend};
+    use crate::infrastructure::persistence::embeddings::mock_adapter::MockEmbeddingConfig;
+    use crate::infrastructure::MockEmbeddingAdapter;
+    use zero_latency_vector::VectorMetadata;
+
+    async fn container() -> ServiceContainer {
+        let mut config = Config::default();
+        config.vector.backend = VectorBackend::Memory;
+        ServiceContainer::with_embedding_generator(
+            config,
+            Arc::new(
+                MockEmbeddingAdapter::new(MockEmbeddingConfig {
+                    dimension: 2,
+                    seed: 7,
+                })
+                .unwrap(),
+            ),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn chunk(document_id: Uuid, chunk_index: usize, embedding: [f32; 2]) -> VectorDocument {
+        VectorDocument {
+            id: Uuid::new_v4(),
+            embedding: embedding.to_vec(),
+            named_vectors: Default::default(),
+            metadata: VectorMetadata {
+                document_id,
+                chunk_index,
+                content: format!("chunk {}", chunk_index),
+                collection: Some("docs".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_samples_chunks_with_neighbors() {
+        let container = container().await;
+        let (guide, notes) = (Uuid::new_v4(), Uuid::new_v4());
+        container
+            .vector_repository()
+            .insert(vec![
+                chunk(guide, 0, [1.0, 0.0]),
+                chunk(guide, 1, [0.9, 0.1]),
+                chunk(notes, 0, [0.0, 1.0]),
+                chunk(notes, 1, [0.2, 0.8]),
+            ])
+            .await
+            .unwrap();
+        let service = ChunkSampleService::new(&container);
+
+        let sample = service.sample("docs", 10, 1, Some(42)).await.unwrap();
+        assert_eq!(sample.total_chunks, 4);
+        assert_eq!(sample.chunks.len(), 4);
+        for chunk in &sample.chunks {
+            assert_eq!(chunk.neighbors.len(), 1);
+            assert_ne!(chunk.neighbors[0].chunk_id, chunk.chunk_id);
+            // Each chunk's closest neighbor is the other chunk of its document
+            assert!(chunk.neighbors[0].same_document, "{:?}", chunk);
+        }
+
+        // The same seed draws the same chunks
+        let first = service.sample("docs", 2, 0, Some(42)).await.unwrap();
+        let again = service.sample("docs", 2, 0, Some(42)).await.unwrap();
+        let ids = |sample: &ChunkSample| -> Vec<Uuid> {
+            sample.chunks.iter().map(|chunk| chunk.chunk_id).collect()
+        };
+        assert_eq!(first.chunks.len(), 2);
+        assert_eq!(ids(&first), ids(&again));
+
+        assert!(service.sample("missing", 10, 1, None).await.is_err());
+    }
+}
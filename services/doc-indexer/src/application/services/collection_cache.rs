@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+/// Per-collection view of the query caches
+///
+/// Every cache derived from searches keys its entries by tenant and
+/// collection. This service reports how much of each cache a collection
+/// occupies and how often its entries are hit, and purges a collection's
+/// entries for every tenant, e.g. after its documents were replaced outside
+/// the indexer. A purge also drops entries of searches across all
+/// collections, since those may include the collection's documents.
+use std::sync::Arc;
+use zero_latency_search::cache::{CollectionCache, CollectionCacheStatistics};
+
+use crate::application::container::ServiceContainer;
+use crate::application::LookupService;
+
+/// One cache's share of a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionCacheReport {
+    pub name: String,
+    #[serde(flatten)]
+    pub statistics: CollectionCacheStatistics,
+}
+
+/// Cache usage of a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionCacheStats {
+    pub collection: String,
+    pub caches: Vec<CollectionCacheReport>,
+}
+
+/// Entries a purge removed from one cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachePurgeCount {
+    pub name: String,
+    pub entries: usize,
+}
+
+/// Outcome of purging a collection's cache entries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachePurgeReport {
+    pub collection: String,
+    pub caches: Vec<CachePurgeCount>,
+    pub total_entries: usize,
+}
+
+/// Service reporting and purging the cache entries of collections
+#[derive(Clone)]
+pub struct CollectionCacheService {
+    caches: Vec<(&'static str, Arc<dyn CollectionCache>)>,
+}
+
+impl CollectionCacheService {
+    pub fn new(container: &ServiceContainer, lookup_service: LookupService) -> Self {
+        Self {
+            caches: vec![
+                (
+                    "query_embeddings",
+                    container.query_embedding_cache() as Arc<dyn CollectionCache>,
+                ),
+                ("empty_results", container.empty_results_cache()),
+                ("lookup", Arc::new(lookup_service)),
+            ],
+        }
+    }
+
+    /// Entries, memory and hit rate of `collection` in every cache
+    pub async fn stats(&self, collection: &str) -> CollectionCacheStats {
+        let mut caches = Vec::with_capacity(self.caches.len());
+        for (name, cache) in &self.caches {
+            caches.push(CollectionCacheReport {
+                name: name.to_string(),
+                statistics: cache.collection_statistics(collection).await,
+            });
+        }
+        CollectionCacheStats {
+            collection: collection.to_string(),
+            caches,
+        }
+    }
+
+    /// Remove the entries of `collection` from every cache
+    pub async fn purge(&self, collection: &str) -> CachePurgeReport {
+        let mut caches = Vec::with_capacity(self.caches.len());
+        for (name, cache) in &self.caches {
+            let entries = cache.purge_collection(collection).await;
+            caches.push(CachePurgeCount {
+                name: name.to_string(),
+                entries,
+            });
+        }
+        let total_entries = caches.iter().map(|cache| cache.entries).sum();
+        tracing::info!(
+            "Purged {} cache entries of collection '{}'",
+            total_entries,
+            collection
+        );
+        CachePurgeReport {
+            collection: collection.to_string(),
+            caches,
+            total_entries,
+        }
+    }
+}
@@ -0,0 +1,418 @@
+/// Index health of a collection
+///
+/// A collection degrades quietly: the embedding model is upgraded and old
+/// vectors stay behind, files are deleted while their chunks linger, mirrors
+/// of the same page pile up, and a bad chunking setting leaves chunks too
+/// long to embed well. The health check rolls these signals into one score
+/// from 0 to 100 and turns each problem it finds into a concrete
+/// recommendation, so operators know what to fix first.
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+use zero_latency_vector::{VectorDocument, VectorRepository};
+
+use crate::application::container::ServiceContainer;
+use crate::application::services::duplicate_report::{
+    DuplicateReportService, DEFAULT_CONTENT_THRESHOLD, DEFAULT_VECTOR_THRESHOLD,
+};
+use crate::application::services::indexing_report::{DiagnosticSeverity, IndexingStage};
+use crate::application::services::job_service::{JobKind, JobService, JobStatus};
+use crate::application::services::vector_staleness::VectorStalenessService;
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+use crate::infrastructure::persistence::fingerprint_registry::FingerprintRegistry;
+
+/// Chunks shorter than this carry too little text to be found by meaning
+const MIN_CHUNK_CHARS: usize = 32;
+
+/// Scores at or above this are healthy
+const HEALTHY_SCORE: u8 = 90;
+
+/// Scores at or above this, but below healthy, are degraded
+const DEGRADED_SCORE: u8 = 60;
+
+/// Overall condition of a collection's index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// A problem the health check can find
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthIssue {
+    StaleVectors,
+    OrphanedChunks,
+    DuplicateDocuments,
+    OversizedChunks,
+    UndersizedChunks,
+    EmbeddingFailures,
+}
+
+impl HealthIssue {
+    /// Most points the issue can take off the score, when it affects
+    /// everything it could
+    fn weight(self) -> f64 {
+        match self {
+            HealthIssue::StaleVectors => 25.0,
+            HealthIssue::OrphanedChunks => 20.0,
+            HealthIssue::DuplicateDocuments => 15.0,
+            HealthIssue::OversizedChunks => 15.0,
+            HealthIssue::UndersizedChunks => 5.0,
+            HealthIssue::EmbeddingFailures => 20.0,
+        }
+    }
+}
+
+/// Raw signals the score is computed from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HealthSignals {
+    pub total_vectors: usize,
+    pub documents: usize,
+    /// Vectors of another model, or older than the staleness TTL
+    pub stale_vectors: usize,
+    /// Vectors no indexed file accounts for; `None` when the collection was
+    /// not indexed from files
+    pub orphaned_chunks: Option<usize>,
+    /// Documents that are near-identical copies of another document
+    pub duplicate_documents: usize,
+    pub median_chunk_chars: usize,
+    /// Chunks longer than twice the configured chunk size
+    pub oversized_chunks: usize,
+    /// Documents with at least one oversized chunk
+    pub oversized_documents: usize,
+    /// Chunks shorter than a meaningful sentence
+    pub undersized_chunks: usize,
+    /// Files that failed to chunk or embed in the latest indexing run
+    pub embedding_failures: usize,
+    /// Files the latest indexing run attempted
+    pub files_attempted: usize,
+}
+
+/// A finding with the action that resolves it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthRecommendation {
+    pub issue: HealthIssue,
+    /// Points the issue takes off the score
+    pub penalty: u8,
+    /// Vectors, chunks, documents or files affected, depending on the issue
+    pub affected: usize,
+    pub action: String,
+}
+
+/// Health score of a collection with what to do about it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionHealth {
+    pub collection: String,
+    /// 0 (unusable) to 100 (nothing to fix)
+    pub score: u8,
+    pub status: HealthStatus,
+    pub signals: HealthSignals,
+    /// Findings, largest penalty first
+    pub recommendations: Vec<HealthRecommendation>,
+}
+
+/// Application service scoring the health of collections
+#[derive(Clone)]
+pub struct CollectionHealthService {
+    vector_repository: Arc<dyn VectorRepository>,
+    collection_aliases: Arc<CollectionAliases>,
+    fingerprint_registry: Arc<FingerprintRegistry>,
+    staleness: VectorStalenessService,
+    duplicates: DuplicateReportService,
+    jobs: JobService,
+    chunk_size: usize,
+}
+
+impl CollectionHealthService {
+    /// Create a health service sharing the container's stores and the
+    /// server's job registry
+    pub fn new(container: &ServiceContainer, jobs: JobService) -> Self {
+        Self {
+            vector_repository: container.vector_repository(),
+            collection_aliases: container.collection_aliases(),
+            fingerprint_registry: container.fingerprint_registry(),
+            staleness: VectorStalenessService::new(container),
+            duplicates: DuplicateReportService::new(container),
+            jobs,
+            chunk_size: container.config().service.chunk_size,
+        }
+    }
+
+    /// Score a collection's index health
+    pub async fn check(&self, collection: &str) -> Result<CollectionHealth> {
+        let stored = self.collection_aliases.resolve(collection);
+        let vectors = self.vector_repository.collection_vectors(&stored).await?;
+        if vectors.is_empty() {
+            return Err(ZeroLatencyError::not_found(format!(
+                "vectors of collection {}",
+                collection
+            )));
+        }
+
+        let mut signals = chunk_signals(&vectors, self.chunk_size * 2);
+        signals.stale_vectors = self.staleness.summarize(collection, &vectors).stale_vectors;
+        signals.orphaned_chunks =
+            self.fingerprint_registry
+                .collection_chunk_ids(&stored)
+                .map(|recorded| {
+                    vectors
+                        .iter()
+                        .filter(|vector| !recorded.contains(&vector.id.to_string()))
+                        .count()
+                });
+        (signals.embedding_failures, signals.files_attempted) =
+            self.latest_index_failures(collection).await;
+        signals.duplicate_documents = self
+            .duplicates
+            .scan(
+                collection,
+                vectors,
+                DEFAULT_VECTOR_THRESHOLD,
+                DEFAULT_CONTENT_THRESHOLD,
+            )
+            .await?
+            .redundant_documents;
+
+        Ok(assess(collection, signals))
+    }
+
+    /// Files that failed at the index stage of the collection's latest
+    /// finished indexing job, and the files it attempted
+    async fn latest_index_failures(&self, collection: &str) -> (usize, usize) {
+        let jobs = self.jobs.list_jobs().await;
+        let Some(job) = jobs.iter().find(|job| {
+            matches!(job.kind, JobKind::Index | JobKind::Reindex)
+                && job.collection == collection
+                && job.status != JobStatus::Running
+        }) else {
+            return (0, 0);
+        };
+        let failures = job
+            .diagnostics
+            .iter()
+            .filter(|d| d.stage == IndexingStage::Index && d.severity == DiagnosticSeverity::Error)
+            .count();
+        (failures, job.documents_processed as usize + failures)
+    }
+}
+
+// Document, chunk length and size anomaly counts of a collection's vectors
+fn chunk_signals(vectors: &[VectorDocument], max_chunk_chars: usize) -> HealthSignals {
+    let mut lengths: Vec<usize> = vectors
+        .iter()
+        .map(|vector| vector.metadata.content.chars().count())
+        .collect();
+    lengths.sort_unstable();
+
+    let documents: HashSet<Uuid> = vectors.iter().map(|v| v.metadata.document_id).collect();
+    let oversized: Vec<&VectorDocument> = vectors
+        .iter()
+        .filter(|vector| vector.metadata.content.chars().count() > max_chunk_chars)
+        .collect();
+    let oversized_documents: HashSet<Uuid> =
+        oversized.iter().map(|v| v.metadata.document_id).collect();
+
+    HealthSignals {
+        total_vectors: vectors.len(),
+        documents: documents.len(),
+        median_chunk_chars: lengths[lengths.len() / 2],
+        oversized_chunks: oversized.len(),
+        oversized_documents: oversized_documents.len(),
+        undersized_chunks: lengths.iter().filter(|&&len| len < MIN_CHUNK_CHARS).count(),
+        ..Default::default()
+    }
+}
+
+/// Score the signals and recommend a fix for each problem found
+fn assess(collection: &str, signals: HealthSignals) -> CollectionHealth {
+    let ratio = |affected: usize, total: usize| {
+        if total == 0 {
+            0.0
+        } else {
+            (affected as f64 / total as f64).min(1.0)
+        }
+    };
+
+    let mut findings: Vec<(HealthIssue, f64, usize, String)> = Vec::new();
+    if signals.stale_vectors > 0 {
+        findings.push((
+            HealthIssue::StaleVectors,
+            ratio(signals.stale_vectors, signals.total_vectors),
+            signals.stale_vectors,
+            format!(
+                "re-embed {} stale vector(s) with POST /api/collections/{}/reembed-stale",
+                signals.stale_vectors, collection
+            ),
+        ));
+    }
+    if let Some(orphaned) = signals.orphaned_chunks.filter(|&n| n > 0) {
+        findings.push((
+            HealthIssue::OrphanedChunks,
+            ratio(orphaned, signals.total_vectors),
+            orphaned,
+            format!(
+                "remove {} orphaned chunk(s) left by deleted or moved files by reindexing the collection",
+                orphaned
+            ),
+        ));
+    }
+    if signals.duplicate_documents > 0 {
+        findings.push((
+            HealthIssue::DuplicateDocuments,
+            ratio(signals.duplicate_documents, signals.documents),
+            signals.duplicate_documents,
+            format!(
+                "prune or blocklist {} near-duplicate document(s) listed by GET /api/collections/{}/duplicates",
+                signals.duplicate_documents, collection
+            ),
+        ));
+    }
+    if signals.oversized_chunks > 0 {
+        findings.push((
+            HealthIssue::OversizedChunks,
+            ratio(signals.oversized_chunks, signals.total_vectors),
+            signals.oversized_chunks,
+            format!(
+                "re-chunk {} over-long document(s) with a smaller chunk size",
+                signals.oversized_documents
+            ),
+        ));
+    }
+    if signals.undersized_chunks > 0 {
+        findings.push((
+            HealthIssue::UndersizedChunks,
+            ratio(signals.undersized_chunks, signals.total_vectors),
+            signals.undersized_chunks,
+            format!(
+                "merge or exclude {} near-empty chunk(s), often headings or boilerplate",
+                signals.undersized_chunks
+            ),
+        ));
+    }
+    if signals.embedding_failures > 0 {
+        findings.push((
+            HealthIssue::EmbeddingFailures,
+            ratio(signals.embedding_failures, signals.files_attempted),
+            signals.embedding_failures,
+            format!(
+                "fix {} file(s) that failed to embed in the latest indexing job and index again",
+                signals.embedding_failures
+            ),
+        ));
+    }
+
+    let mut recommendations: Vec<HealthRecommendation> = findings
+        .into_iter()
+        .map(|(issue, ratio, affected, action)| HealthRecommendation {
+            issue,
+            // Any occurrence costs at least a point, so only a clean index scores 100
+            penalty: (issue.weight() * ratio).ceil().max(1.0) as u8,
+            affected,
+            action,
+        })
+        .collect();
+    recommendations.sort_by_key(|r| std::cmp::Reverse(r.penalty));
+
+    let penalty: u32 = recommendations.iter().map(|r| r.penalty as u32).sum();
+    let score = 100u32.saturating_sub(penalty) as u8;
+    let status = if score >= HEALTHY_SCORE {
+        HealthStatus::Healthy
+    } else if score >= DEGRADED_SCORE {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Unhealthy
+    };
+
+    CollectionHealth {
+        collection: collection.to_string(),
+        score,
+        status,
+        signals,
+        recommendations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals() -> HealthSignals {
+        HealthSignals {
+            total_vectors: 1000,
+            documents: 100,
+            orphaned_chunks: Some(0),
+            median_chunk_chars: 600,
+            files_attempted: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_clean_index_scores_full_marks() {
+        let health = assess("docs", signals());
+        assert_eq!(health.score, 100);
+        assert_eq!(health.status, HealthStatus::Healthy);
+        assert!(health.recommendations.is_empty());
+    }
+
+    #[test]
+    fn test_problems_lower_the_score_with_recommendations() {
+        let health = assess(
+            "docs",
+            HealthSignals {
+                stale_vectors: 1000,
+                oversized_chunks: 40,
+                oversized_documents: 12,
+                embedding_failures: 2,
+                ..signals()
+            },
+        );
+        // All vectors stale: the full stale penalty, then 1 point for 4%
+        // oversized chunks and 1 point for 2% failed files
+        assert_eq!(health.score, 100 - 25 - 1 - 1);
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert_eq!(health.recommendations[0].issue, HealthIssue::StaleVectors);
+        assert_eq!(health.recommendations[0].penalty, 25);
+        let rechunk = health
+            .recommendations
+            .iter()
+            .find(|r| r.issue == HealthIssue::OversizedChunks)
+            .unwrap();
+        assert_eq!(rechunk.affected, 40);
+        assert!(rechunk
+            .action
+            .starts_with("re-chunk 12 over-long document(s)"));
+    }
+
+    #[test]
+    fn test_chunk_signals() {
+        let document = Uuid::new_v4();
+        let chunk = |content: String| VectorDocument {
+            id: Uuid::new_v4(),
+            embedding: vec![1.0],
+            named_vectors: Default::default(),
+            metadata: zero_latency_vector::VectorMetadata {
+                document_id: document,
+                content,
+                ..Default::default()
+            },
+        };
+        let signals = chunk_signals(
+            &[
+                chunk("x".repeat(10)),
+                chunk("x".repeat(100)),
+                chunk("x".repeat(5000)),
+            ],
+            2000,
+        );
+        assert_eq!(signals.documents, 1);
+        assert_eq!(signals.median_chunk_chars, 100);
+        assert_eq!(signals.oversized_chunks, 1);
+        assert_eq!(signals.oversized_documents, 1);
+        assert_eq!(signals.undersized_chunks, 1);
+    }
+}
@@ -1,4 +1,8 @@
+use crate::application::services::filter_service::glob_match;
 use crate::application::ServiceContainer;
+use crate::infrastructure::persistence::change_log::{Change, ChangeKind};
+use crate::infrastructure::persistence::metadata_schemas::MetadataSchema;
+use crate::infrastructure::persistence::webhook_registry::WebhookEvent;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 /// Collection management service
@@ -7,6 +11,8 @@ use std::collections::HashMap;
 /// and managing vector collections in the storage backend.
 use std::sync::Arc;
 use zero_latency_core::{Result, ZeroLatencyError};
+use zero_latency_search::{FusionWeights, SearchSettings};
+use zero_latency_vector::{CollectionVectorSettings, SimilarityMetric};
 
 /// Collection metadata and statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,7 +23,13 @@ pub struct CollectionInfo {
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
     pub vector_size: Option<u64>,
+    /// How vector searches compare the collection's vectors
+    #[serde(default)]
+    pub distance_metric: SimilarityMetric,
     pub status: CollectionStatus,
+    /// Search settings used when a request doesn't specify them
+    #[serde(default)]
+    pub search_defaults: SearchSettings,
 }
 
 /// Collection status enumeration
@@ -36,6 +48,8 @@ pub struct CreateCollectionRequest {
     pub vector_size: u64,
     pub distance_metric: Option<String>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub search_defaults: SearchSettings,
 }
 
 /// Service for managing collections
@@ -78,7 +92,9 @@ impl CollectionService {
             created_at: Some(chrono::Utc::now() - chrono::Duration::days(30)),
             last_modified: Some(chrono::Utc::now()),
             vector_size: Some(384),
+            distance_metric: SimilarityMetric::Cosine,
             status: CollectionStatus::Active,
+            search_defaults: SearchSettings::default(),
         };
 
         collections_guard.insert("zero_latency_docs".to_string(), default_collection);
@@ -108,6 +124,16 @@ impl CollectionService {
         Ok(())
     }
 
+    /// Record the embedding dimension of a collection's vectors
+    pub async fn set_vector_size(&self, collection_name: &str, vector_size: u64) -> Result<()> {
+        let mut collections_guard = self.collections.write().await;
+        if let Some(collection) = collections_guard.get_mut(collection_name) {
+            collection.vector_size = Some(vector_size);
+            collection.last_modified = Some(chrono::Utc::now());
+        }
+        Ok(())
+    }
+
     /// List all available collections
     pub async fn list_collections(&self) -> Result<Vec<CollectionInfo>> {
         let collections_guard = self.collections.read().await;
@@ -150,6 +176,12 @@ impl CollectionService {
             ));
         }
 
+        request.search_defaults.validate()?;
+        let distance_metric = match &request.distance_metric {
+            Some(metric) => metric.parse()?,
+            None => SimilarityMetric::default(),
+        };
+
         // Check if collection already exists
         {
             let collections_guard = self.collections.read().await;
@@ -161,6 +193,19 @@ impl CollectionService {
             }
         }
 
+        // The vector store scores the collection's searches with its metric
+        // and rejects queries of another dimension
+        self.container
+            .vector_repository()
+            .configure_collection(
+                &request.name,
+                CollectionVectorSettings {
+                    dimension: request.vector_size as usize,
+                    metric: distance_metric,
+                },
+            )
+            .await?;
+
         // Create the collection info
         let collection = CollectionInfo {
             name: request.name.clone(),
@@ -169,7 +214,9 @@ impl CollectionService {
             created_at: Some(chrono::Utc::now()),
             last_modified: Some(chrono::Utc::now()),
             vector_size: Some(request.vector_size),
+            distance_metric,
             status: CollectionStatus::Active,
+            search_defaults: request.search_defaults,
         };
 
         // Add to registry
@@ -179,23 +226,205 @@ impl CollectionService {
         }
 
         tracing::info!("Created collection: {}", collection.name);
+        self.container.change_log().append(Change::collection(
+            ChangeKind::CollectionCreated,
+            &collection.name,
+        ));
+        self.container.webhooks().emit(
+            WebhookEvent::CollectionCreated,
+            serde_json::json!({ "collection": &collection }),
+        );
         Ok(collection)
     }
 
     /// Delete a collection
     pub async fn delete_collection(&self, name: &str) -> Result<bool> {
+        // Drop the collection's fingerprints and the chunks they point at so
+        // that files indexed into it again are not skipped as unchanged
+        let registry = self.container.fingerprint_registry();
+        let vector_repository = self.container.vector_repository();
+        let chunk_ids = registry.clear_collection(name);
+        if !chunk_ids.is_empty() {
+            if let Err(e) = vector_repository.delete_batch(&chunk_ids).await {
+                tracing::warn!(
+                    "Failed to remove {} chunks of {}: {}",
+                    chunk_ids.len(),
+                    name,
+                    e
+                );
+            }
+        }
+        if let Err(e) = registry.save() {
+            tracing::warn!("Failed to persist fingerprint registry: {}", e);
+        }
+
+        // A collection recreated under this name starts without a migration
+        // alias or a score background
+        let aliases = self.container.collection_aliases();
+        if let Some(calibrator) = self.container.calibrator() {
+            calibrator.reset(&aliases.resolve(name));
+            calibrator.reset(name);
+        }
+        #[cfg(feature = "late-interaction")]
+        if let Some(token_vectors) = self.container.token_vectors() {
+            if let Err(e) = token_vectors.remove_collection(&aliases.resolve(name)) {
+                tracing::warn!("Failed to remove token vectors of {}: {}", name, e);
+            }
+        }
+        if aliases.remove(name).is_some() {
+            if let Err(e) = aliases.save() {
+                tracing::warn!("Failed to persist collection aliases: {}", e);
+            }
+        }
+
+        let schemas = self.container.metadata_schemas();
+        if schemas.remove(name).is_some() {
+            if let Err(e) = schemas.save() {
+                tracing::warn!("Failed to persist metadata schemas: {}", e);
+            }
+        }
+
+        let curation = self.container.curation();
+        if curation.remove_collection(name) {
+            if let Err(e) = curation.save() {
+                tracing::warn!("Failed to persist curation rules: {}", e);
+            }
+        }
+
+        let blocklist = self.container.blocklist();
+        if blocklist.remove_collection(name) {
+            if let Err(e) = blocklist.save() {
+                tracing::warn!("Failed to persist blocklist: {}", e);
+            }
+        }
+
+        if let Err(e) = self.container.projections().remove(name) {
+            tracing::warn!("Failed to remove projection: {}", e);
+        }
+
         let mut collections_guard = self.collections.write().await;
         if collections_guard.remove(name).is_some() {
             println!("🗑️ Deleted collection: {}", name);
+            self.container
+                .change_log()
+                .append(Change::collection(ChangeKind::CollectionDeleted, name));
+            self.container.webhooks().emit(
+                WebhookEvent::CollectionDeleted,
+                serde_json::json!({ "collection": name }),
+            );
             Ok(true)
         } else {
             Ok(false)
         }
     }
 
+    /// Metadata schema declared by a collection
+    pub fn metadata_schema(&self, name: &str) -> Result<MetadataSchema> {
+        self.container
+            .metadata_schemas()
+            .get(name)
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("metadata schema of {}", name)))
+    }
+
+    /// Declare or replace a collection's metadata schema
+    ///
+    /// Only documents indexed from now on are checked; stored documents are
+    /// left as they are until they are re-indexed.
+    pub fn set_metadata_schema(
+        &self,
+        name: &str,
+        schema: MetadataSchema,
+    ) -> Result<MetadataSchema> {
+        let schemas = self.container.metadata_schemas();
+        schemas.set(name, schema.clone())?;
+        schemas.save()?;
+        tracing::info!(
+            "Metadata schema of {} declares {} fields",
+            name,
+            schema.fields.len()
+        );
+        Ok(schema)
+    }
+
+    /// Drop a collection's metadata schema
+    pub fn delete_metadata_schema(&self, name: &str) -> Result<()> {
+        let schemas = self.container.metadata_schemas();
+        if schemas.remove(name).is_none() {
+            return Err(ZeroLatencyError::not_found(format!(
+                "metadata schema of {}",
+                name
+            )));
+        }
+        schemas.save()
+    }
+
+    /// Settings for a search of `collection_name`
+    ///
+    /// Anything `overrides` leaves unset comes from the collection's defaults,
+    /// then from the service configuration. The limit is capped at the
+    /// configured maximum.
+    pub async fn effective_search_settings(
+        &self,
+        collection_name: &str,
+        overrides: &SearchSettings,
+    ) -> Result<SearchSettings> {
+        overrides.validate()?;
+
+        let collection_defaults = self
+            .get_collection_info(collection_name)
+            .await?
+            .map(|collection| collection.search_defaults)
+            .unwrap_or_default();
+
+        let config = &self.container.config().service;
+        let service_defaults = SearchSettings {
+            limit: Some(config.default_search_limit),
+            fusion_weights: Some(FusionWeights::default()),
+            query_expansion: Some(config.enable_query_enhancement),
+            rerank_depth: None,
+            extract_answer: Some(config.enable_answer_extraction),
+            min_score: None,
+            // Unset keeps the lexical index's configured boosts
+            field_weights: None,
+            proximity_weight: None,
+            vector_spaces: None,
+            late_interaction: None,
+            context_window: Some(config.default_context_window),
+        };
+
+        let mut settings = overrides.or(&collection_defaults).or(&service_defaults);
+        settings.limit = settings
+            .limit
+            .map(|limit| limit.min(config.max_search_limit));
+        Ok(settings)
+    }
+
+    /// Resolve the collections named by a federated search
+    ///
+    /// Plain names are used as given; entries containing `*` or `?` expand
+    /// to the registered collections they match, in name order.
+    pub async fn resolve_collections(&self, patterns: &[String]) -> Result<Vec<String>> {
+        let mut known: Vec<String> = self.collections.read().await.keys().cloned().collect();
+        known.sort();
+
+        let resolved = expand_collection_patterns(patterns, &known);
+        if resolved.is_empty() {
+            return Err(ZeroLatencyError::validation(
+                "collection_names",
+                format!("No collections match '{}'", patterns.join(",")),
+            ));
+        }
+        Ok(resolved)
+    }
+
     /// Get statistics for a collection
     pub async fn get_collection_stats(&self, name: &str) -> Result<Option<CollectionStats>> {
         if let Some(collection) = self.get_collection_info(name).await? {
+            let storage = self.container.vector_repository().storage_info().await?;
+            let segment = storage
+                .segments
+                .iter()
+                .find(|segment| segment.collection.as_deref() == Some(name));
             let stats = CollectionStats {
                 name: collection.name,
                 vector_count: collection.vector_count,
@@ -203,6 +432,8 @@ impl CollectionService {
                 average_vector_size: collection.vector_size.unwrap_or(0) as f64,
                 last_indexed: collection.last_modified,
                 index_efficiency: 0.95, // Mock efficiency
+                chunk_bytes: segment.map_or(0, |segment| segment.chunk_bytes),
+                deduplicated_bytes: segment.map_or(0, |segment| segment.deduplicated_bytes),
             };
             Ok(Some(stats))
         } else {
@@ -231,4 +462,56 @@ pub struct CollectionStats {
     pub average_vector_size: f64,
     pub last_indexed: Option<chrono::DateTime<chrono::Utc>>,
     pub index_efficiency: f64,
+    /// Chunk text of the collection's vectors, counting repeated chunks
+    pub chunk_bytes: u64,
+    /// Repeated chunk text that is stored only once
+    pub deduplicated_bytes: u64,
+}
+
+/// Expand collection names and glob patterns against the known collections,
+/// dropping duplicates while keeping the order they were requested in
+fn expand_collection_patterns(patterns: &[String], known: &[String]) -> Vec<String> {
+    let mut resolved: Vec<String> = Vec::new();
+    for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let matches: Vec<String> = if pattern.contains(['*', '?']) {
+            known
+                .iter()
+                .filter(|name| glob_match(name, pattern))
+                .cloned()
+                .collect()
+        } else {
+            vec![pattern.to_string()]
+        };
+        for name in matches {
+            if !resolved.contains(&name) {
+                resolved.push(name);
+            }
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_expand_collection_patterns() {
+        let known = names(&["api_v1", "api_v2", "archive", "guides"]);
+
+        assert_eq!(
+            expand_collection_patterns(&names(&["guides", "api_*"]), &known),
+            names(&["guides", "api_v1", "api_v2"])
+        );
+        // Plain names are kept even if not registered; duplicates are dropped
+        assert_eq!(
+            expand_collection_patterns(&names(&["new", " api_v1", "api_v?"]), &known),
+            names(&["new", "api_v1", "api_v2"])
+        );
+        assert!(expand_collection_patterns(&names(&["missing_*", ""]), &known).is_empty());
+    }
 }
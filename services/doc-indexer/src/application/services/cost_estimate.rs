@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+/// Embedding cost estimates for index jobs
+///
+/// Indexing a large tree against a cloud embedding model can cost real
+/// money. Before such a job runs, a dry run of the indexing pipeline counts
+/// the chunks that need embedding and their estimated tokens, and the
+/// estimator prices them with the configured model's pricing. Jobs estimated
+/// above the configured threshold only run once the caller confirms the
+/// cost.
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use crate::application::services::indexing_report::IndexingReport;
+use crate::config::{Config, EmbeddingProvider};
+
+/// Projected embedding cost of an index job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostEstimate {
+    /// Documents with chunks to embed
+    pub documents: u64,
+    /// Chunks to embed; unchanged chunks of indexed files are not counted
+    pub chunks: u64,
+    /// Estimated tokens of those chunks
+    pub tokens: u64,
+    pub model: String,
+    pub price_per_million_tokens: f64,
+    pub estimated_cost_usd: f64,
+    /// Whether the job needs `confirm_cost` to run
+    pub requires_confirmation: bool,
+}
+
+/// Prices index jobs with the configured embedding model's pricing
+#[derive(Debug, Clone)]
+pub struct CostEstimator {
+    model: String,
+    // USD per million tokens, or None for an unknown OpenAI model
+    price_per_million_tokens: Option<f64>,
+    confirm_threshold_usd: f64,
+}
+
+impl CostEstimator {
+    pub fn new(config: &Config) -> Self {
+        let (model, list_price) = match config.embedding.provider {
+            EmbeddingProvider::OpenAI => {
+                let model = config.embedding.openai.model.clone();
+                let price = list_price(&model);
+                (model, price)
+            }
+            EmbeddingProvider::Local => ("local".to_string(), Some(0.0)),
+            EmbeddingProvider::Mock => ("mock".to_string(), Some(0.0)),
+        };
+        Self {
+            model,
+            price_per_million_tokens: config.cost.price_per_million_tokens.or(list_price),
+            confirm_threshold_usd: config.cost.confirm_threshold_usd,
+        }
+    }
+
+    /// Whether index jobs cost anything worth estimating
+    ///
+    /// False for free models and for OpenAI models without a known or
+    /// configured price, so jobs skip the dry run.
+    pub fn is_billed(&self) -> bool {
+        self.price_per_million_tokens
+            .is_some_and(|price| price > 0.0)
+    }
+
+    /// Price what a dry run found to embed
+    pub fn estimate(&self, report: &IndexingReport) -> CostEstimate {
+        let price = self.price_per_million_tokens.unwrap_or(0.0);
+        let cost = report.embedding_tokens as f64 * price / 1_000_000.0;
+        CostEstimate {
+            documents: report.documents_processed,
+            chunks: report.chunks_embedded,
+            tokens: report.embedding_tokens,
+            model: self.model.clone(),
+            price_per_million_tokens: price,
+            estimated_cost_usd: cost,
+            requires_confirmation: self.confirm_threshold_usd > 0.0
+                && cost > self.confirm_threshold_usd,
+        }
+    }
+
+    /// Refuse a job that needs confirming unless the caller confirmed it
+    pub fn check(&self, estimate: &CostEstimate, confirmed: bool) -> Result<()> {
+        if estimate.requires_confirmation && !confirmed {
+            return Err(ZeroLatencyError::validation(
+                "confirm_cost",
+                format!(
+                    "Embedding {} tokens in {} chunks with {} is estimated to cost ${:.2}, \
+                     above the ${:.2} threshold; resubmit with confirm_cost to index",
+                    estimate.tokens,
+                    estimate.chunks,
+                    estimate.model,
+                    estimate.estimated_cost_usd,
+                    self.confirm_threshold_usd
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// List price in USD per million tokens of an OpenAI embedding model
+pub fn list_price(model: &str) -> Option<f64> {
+    match model {
+        "text-embedding-3-small" => Some(0.02),
+        "text-embedding-3-large" => Some(0.13),
+        "text-embedding-ada-002" => Some(0.10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimator(price: Option<f64>, threshold: f64) -> CostEstimator {
+        CostEstimator {
+            model: "text-embedding-3-small".to_string(),
+            price_per_million_tokens: price,
+            confirm_threshold_usd: threshold,
+        }
+    }
+
+    fn dry_run(tokens: u64) -> IndexingReport {
+        IndexingReport {
+            documents_processed: 3,
+            chunks_embedded: 40,
+            embedding_tokens: tokens,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_prices_dry_run() {
+        let estimate = estimator(Some(0.02), 1.0).estimate(&dry_run(10_000_000));
+        assert_eq!(estimate.documents, 3);
+        assert_eq!(estimate.chunks, 40);
+        assert!((estimate.estimated_cost_usd - 0.2).abs() < 1e-9);
+        assert!(!estimate.requires_confirmation);
+
+        assert_eq!(list_price("text-embedding-3-large"), Some(0.13));
+        assert_eq!(list_price("some-future-model"), None);
+        assert!(!estimator(None, 1.0).is_billed());
+        assert!(!estimator(Some(0.0), 1.0).is_billed());
+    }
+
+    #[test]
+    fn test_requires_confirmation_above_threshold() {
+        let pricing = estimator(Some(0.13), 5.0);
+        let estimate = pricing.estimate(&dry_run(100_000_000));
+        assert!(estimate.requires_confirmation);
+        let err = pricing.check(&estimate, false).unwrap_err();
+        assert!(err.to_string().contains("$13.00"));
+        assert!(pricing.check(&estimate, true).is_ok());
+
+        // A zero threshold never asks
+        let estimate = estimator(Some(0.13), 0.0).estimate(&dry_run(100_000_000));
+        assert!(!estimate.requires_confirmation);
+    }
+}
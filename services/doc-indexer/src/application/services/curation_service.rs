@@ -0,0 +1,72 @@
+/// Search curation: pinned results and document boosts
+///
+/// Editors pin documents to the top of the results for specific queries and
+/// boost or demote documents across all of a collection's searches. Rules
+/// take effect on the next search; see
+/// [`crate::infrastructure::persistence::curation`] for how they apply.
+use std::sync::Arc;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use crate::application::container::ServiceContainer;
+use crate::infrastructure::persistence::curation::{
+    Boost, CurationRules, CurationStore, NewBoost, NewPin, Pin,
+};
+
+/// Application service managing search curation rules
+#[derive(Clone)]
+pub struct CurationService {
+    store: Arc<CurationStore>,
+}
+
+impl CurationService {
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            store: container.curation(),
+        }
+    }
+
+    /// Rules of one collection, or of every collection
+    pub fn list(&self, collection: Option<&str>) -> CurationRules {
+        self.store.list(collection)
+    }
+
+    /// Pin documents to the top of a query's results
+    pub fn add_pin(&self, request: NewPin) -> Result<Pin> {
+        let pin = self.store.add_pin(request)?;
+        self.store.save()?;
+        tracing::info!(
+            "Pinned {} documents for '{}' in {}",
+            pin.documents.len(),
+            pin.query,
+            pin.collection
+        );
+        Ok(pin)
+    }
+
+    pub fn remove_pin(&self, id: &str) -> Result<()> {
+        self.store
+            .remove_pin(id)
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("pin {}", id)))?;
+        self.store.save()
+    }
+
+    /// Boost or demote documents in every search of a collection
+    pub fn add_boost(&self, request: NewBoost) -> Result<Boost> {
+        let boost = self.store.add_boost(request)?;
+        self.store.save()?;
+        tracing::info!(
+            "Boosting {} by {} in {}",
+            boost.document,
+            boost.factor,
+            boost.collection
+        );
+        Ok(boost)
+    }
+
+    pub fn remove_boost(&self, id: &str) -> Result<()> {
+        self.store
+            .remove_boost(id)
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("boost {}", id)))?;
+        self.store.save()
+    }
+}
@@ -0,0 +1,616 @@
+/// Scheduled digests of index changes
+///
+/// Summarizes what changed in each collection over a period, read from the
+/// change log: documents indexed for the first time, documents whose
+/// re-indexing changed enough of their chunks to matter, deletions, and the
+/// sections most of the changes fall under. Digests go out on a fixed cadence
+/// to webhooks subscribed to `digest.compiled` and, when SMTP is configured,
+/// by email, so readers can follow their docs without polling the changefeed.
+use lettre::message::header::ContentType;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zero_latency_core::{DateTime, Result, Utc, ZeroLatencyError};
+use zero_latency_vector::{VectorRepository, PATH_KEY};
+
+use crate::application::container::ServiceContainer;
+use crate::application::services::webhook_service::WebhookService;
+use crate::config::DigestConfig;
+use crate::infrastructure::persistence::change_log::{ChangeEvent, ChangeKind, ChangeLog};
+use crate::infrastructure::persistence::webhook_registry::WebhookEvent;
+
+/// Change log events read per page while compiling
+const READ_PAGE: usize = 1000;
+
+/// Sections listed per collection
+const TOP_SECTIONS: usize = 5;
+
+/// How a listed document changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentChangeKind {
+    Added,
+    Updated,
+}
+
+/// A document listed in a digest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestDocument {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub change: DocumentChangeKind,
+    /// Largest share of chunks changed by a re-indexing in the period
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub churn: Option<f32>,
+}
+
+/// A section and how many changed documents it appears in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestSection {
+    pub section: String,
+    pub documents: usize,
+}
+
+/// Changes of one collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionDigest {
+    pub collection: String,
+    pub added: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    /// The changed documents, most changed first, up to the configured limit
+    pub documents: Vec<DigestDocument>,
+    pub top_sections: Vec<DigestSection>,
+}
+
+/// Changes over a period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Digest {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// Documents added, updated or deleted
+    pub total_changes: usize,
+    pub collections: Vec<CollectionDigest>,
+    /// Deletions by document id, which the log records without a collection
+    pub deleted_documents: usize,
+    pub created_collections: Vec<String>,
+    pub deleted_collections: Vec<String>,
+}
+
+impl Digest {
+    fn empty(since: DateTime<Utc>, until: DateTime<Utc>) -> Self {
+        Self {
+            since,
+            until,
+            total_changes: 0,
+            collections: Vec::new(),
+            deleted_documents: 0,
+            created_collections: Vec::new(),
+            deleted_collections: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_changes == 0
+            && self.created_collections.is_empty()
+            && self.deleted_collections.is_empty()
+    }
+
+    /// Email subject line
+    pub fn subject(&self) -> String {
+        format!(
+            "Docs digest: {} change{} in {} collection{}",
+            self.total_changes,
+            if self.total_changes == 1 { "" } else { "s" },
+            self.collections.len(),
+            if self.collections.len() == 1 { "" } else { "s" },
+        )
+    }
+
+    /// Plain text rendering, as emailed
+    pub fn render_text(&self) -> String {
+        let mut text = format!(
+            "Documentation changes from {} to {}\n",
+            self.since.format("%Y-%m-%d %H:%M UTC"),
+            self.until.format("%Y-%m-%d %H:%M UTC"),
+        );
+        if self.is_empty() {
+            text.push_str("\nNothing changed.\n");
+            return text;
+        }
+
+        for collection in &self.collections {
+            text.push_str(&format!(
+                "\n{}: {} added, {} updated, {} deleted\n",
+                collection.collection, collection.added, collection.updated, collection.deleted
+            ));
+            for document in &collection.documents {
+                let marker = match document.change {
+                    DocumentChangeKind::Added => '+',
+                    DocumentChangeKind::Updated => '~',
+                };
+                text.push_str(&format!("  {} {}", marker, document.path));
+                if let Some(title) = &document.title {
+                    text.push_str(&format!(" ({})", title));
+                }
+                if let Some(churn) = document.churn {
+                    text.push_str(&format!(", {:.0}% changed", churn * 100.0));
+                }
+                text.push('\n');
+            }
+            let listed = collection.documents.len();
+            let changed = collection.added + collection.updated;
+            if changed > listed {
+                text.push_str(&format!("  and {} more\n", changed - listed));
+            }
+            if !collection.top_sections.is_empty() {
+                let sections: Vec<String> = collection
+                    .top_sections
+                    .iter()
+                    .map(|section| format!("{} ({})", section.section, section.documents))
+                    .collect();
+                text.push_str(&format!(
+                    "  Most changed sections: {}\n",
+                    sections.join(", ")
+                ));
+            }
+        }
+
+        if self.deleted_documents > 0 {
+            text.push_str(&format!(
+                "\n{} documents deleted by id\n",
+                self.deleted_documents
+            ));
+        }
+        if !self.created_collections.is_empty() {
+            text.push_str(&format!(
+                "\nNew collections: {}\n",
+                self.created_collections.join(", ")
+            ));
+        }
+        if !self.deleted_collections.is_empty() {
+            text.push_str(&format!(
+                "\nDeleted collections: {}\n",
+                self.deleted_collections.join(", ")
+            ));
+        }
+        text
+    }
+}
+
+/// Where a digest was sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestDelivery {
+    pub digest: Digest,
+    /// Email recipients, empty when SMTP is not configured
+    pub emailed: Vec<String>,
+}
+
+/// Compiles digests and sends them on schedule
+#[derive(Clone)]
+pub struct DigestService {
+    change_log: Arc<ChangeLog>,
+    vector_repository: Arc<dyn VectorRepository>,
+    webhooks: WebhookService,
+    config: DigestConfig,
+    mailer: Option<AsyncSmtpTransport<Tokio1Executor>>,
+    // End of the period the last scheduled digest covered
+    last_sent: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl DigestService {
+    /// Create the service, rejecting an unusable SMTP URL or address
+    pub fn new(container: &ServiceContainer) -> Result<Self> {
+        let config = container.config().digest.clone();
+        let mailer = match &config.smtp_url {
+            Some(url) => Some(
+                AsyncSmtpTransport::<Tokio1Executor>::from_url(url)
+                    .map_err(|e| {
+                        ZeroLatencyError::configuration(format!("Invalid SMTP URL: {}", e))
+                    })?
+                    .build(),
+            ),
+            None => None,
+        };
+        let service = Self {
+            change_log: container.change_log(),
+            vector_repository: container.vector_repository(),
+            webhooks: container.webhooks(),
+            config,
+            mailer,
+            last_sent: Arc::new(Mutex::new(Utc::now())),
+        };
+        if service.mailer.is_some() {
+            service.message(&Digest::empty(Utc::now(), Utc::now()))?;
+        }
+        Ok(service)
+    }
+
+    /// Hours a digest covers unless asked otherwise
+    pub fn default_hours(&self) -> u64 {
+        match self.config.interval_hours {
+            0 => 24,
+            hours => hours,
+        }
+    }
+
+    /// Compile the changes made after `since`
+    pub async fn compile(&self, since: DateTime<Utc>) -> Result<Digest> {
+        let mut events = Vec::new();
+        let mut cursor = 0;
+        loop {
+            let page = self.change_log.read(cursor, READ_PAGE, None);
+            events.extend(
+                page.events
+                    .into_iter()
+                    .filter(|event| event.occurred_at > since),
+            );
+            if !page.has_more {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+
+        let mut digest = summarize(
+            &events,
+            since,
+            Utc::now(),
+            self.config.min_churn,
+            self.config.max_documents,
+        );
+        for collection in &mut digest.collections {
+            if let Err(e) = self.annotate(collection).await {
+                tracing::debug!(
+                    "No sections for digest of collection {}: {}",
+                    collection.collection,
+                    e
+                );
+            }
+        }
+        Ok(digest)
+    }
+
+    /// Fill in document titles and the sections the changed documents cover
+    async fn annotate(&self, digest: &mut CollectionDigest) -> Result<()> {
+        if digest.documents.is_empty() {
+            return Ok(());
+        }
+        let listed: HashSet<&str> = digest
+            .documents
+            .iter()
+            .map(|document| document.path.as_str())
+            .collect();
+        let vectors = self
+            .vector_repository
+            .collection_vectors(&digest.collection)
+            .await?;
+
+        let mut titles: HashMap<String, String> = HashMap::new();
+        let mut sections: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+        for vector in &vectors {
+            let Some(path) = vector.metadata.custom.get(PATH_KEY) else {
+                continue;
+            };
+            if !listed.contains(path.as_str()) {
+                continue;
+            }
+            if !vector.metadata.title.is_empty() {
+                titles
+                    .entry(path.clone())
+                    .or_insert_with(|| vector.metadata.title.clone());
+            }
+            if let Some(section) = vector.metadata.heading_path.first() {
+                sections
+                    .entry(section.clone())
+                    .or_default()
+                    .insert(path.clone());
+            }
+        }
+
+        for document in &mut digest.documents {
+            document.title = titles.remove(&document.path);
+        }
+        let mut top: Vec<DigestSection> = sections
+            .into_iter()
+            .map(|(section, paths)| DigestSection {
+                section,
+                documents: paths.len(),
+            })
+            .collect();
+        // Stable sort keeps sections with equal counts in name order
+        top.sort_by_key(|section| std::cmp::Reverse(section.documents));
+        top.truncate(TOP_SECTIONS);
+        digest.top_sections = top;
+        Ok(())
+    }
+
+    /// Send a digest to `digest.compiled` webhooks and, with SMTP, by email
+    pub async fn send(&self, digest: Digest) -> Result<DigestDelivery> {
+        self.webhooks.emit(
+            WebhookEvent::DigestCompiled,
+            serde_json::to_value(&digest).unwrap_or_default(),
+        );
+
+        let mut emailed = Vec::new();
+        if let Some(mailer) = &self.mailer {
+            if !self.config.email_to.is_empty() {
+                mailer.send(self.message(&digest)?).await.map_err(|e| {
+                    ZeroLatencyError::network(format!("Failed to email digest: {}", e))
+                })?;
+                emailed = self.config.email_to.clone();
+            }
+        }
+        Ok(DigestDelivery { digest, emailed })
+    }
+
+    /// Send the digest of the changes since the last scheduled one
+    ///
+    /// Nothing is sent when nothing changed; the next digest then covers the
+    /// longer period.
+    pub async fn send_due(&self) -> Result<Option<DigestDelivery>> {
+        let since = *self.last_sent.lock().unwrap_or_else(|e| e.into_inner());
+        let digest = self.compile(since).await?;
+        if digest.is_empty() {
+            return Ok(None);
+        }
+        let until = digest.until;
+        let delivery = self.send(digest).await?;
+        *self.last_sent.lock().unwrap_or_else(|e| e.into_inner()) = until;
+        Ok(Some(delivery))
+    }
+
+    /// Send a digest every `interval` in the background
+    pub fn spawn_scheduler(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick fires immediately, with nothing to report yet
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match service.send_due().await {
+                    Ok(Some(delivery)) => {
+                        tracing::info!("Sent digest of {} changes", delivery.digest.total_changes)
+                    }
+                    Ok(None) => tracing::debug!("No changes to send a digest for"),
+                    Err(e) => tracing::warn!("Failed to send digest: {}", e),
+                }
+            }
+        })
+    }
+
+    fn message(&self, digest: &Digest) -> Result<Message> {
+        let address = |value: &str| {
+            value.parse().map_err(|e| {
+                ZeroLatencyError::configuration(format!("Invalid email address {}: {}", value, e))
+            })
+        };
+        let mut builder = Message::builder()
+            .from(address(&self.config.email_from)?)
+            .subject(digest.subject())
+            .header(ContentType::TEXT_PLAIN);
+        for recipient in &self.config.email_to {
+            builder = builder.to(address(recipient)?);
+        }
+        builder.body(digest.render_text()).map_err(|e| {
+            ZeroLatencyError::configuration(format!("Cannot build digest email: {}", e))
+        })
+    }
+}
+
+/// Group change events by collection
+///
+/// A document added and then re-indexed in the same period is reported as
+/// added. An update only counts when its churn reaches `min_churn`; updates
+/// with no churn measured, such as metadata patches, always count.
+fn summarize(
+    events: &[ChangeEvent],
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    min_churn: f32,
+    max_documents: usize,
+) -> Digest {
+    #[derive(Default)]
+    struct Changes {
+        documents: BTreeMap<String, DigestDocument>,
+        deleted: usize,
+    }
+
+    let mut digest = Digest::empty(since, until);
+    let mut collections: BTreeMap<String, Changes> = BTreeMap::new();
+    for event in events {
+        let collection = event.collection.clone();
+        match event.kind {
+            ChangeKind::CollectionCreated => digest.created_collections.extend(collection),
+            ChangeKind::CollectionDeleted => digest.deleted_collections.extend(collection),
+            ChangeKind::DocumentDeleted => match collection {
+                Some(name) => collections.entry(name).or_default().deleted += 1,
+                None => digest.deleted_documents += 1,
+            },
+            ChangeKind::DocumentAdded | ChangeKind::DocumentUpdated => {
+                let (Some(name), Some(path)) = (collection, event.path.clone()) else {
+                    continue;
+                };
+                let added = event.kind == ChangeKind::DocumentAdded;
+                if !added && event.churn.is_some_and(|churn| churn < min_churn) {
+                    continue;
+                }
+                let document = collections
+                    .entry(name)
+                    .or_default()
+                    .documents
+                    .entry(path.clone())
+                    .or_insert_with(|| DigestDocument {
+                        path,
+                        title: None,
+                        change: DocumentChangeKind::Updated,
+                        churn: None,
+                    });
+                if added {
+                    document.change = DocumentChangeKind::Added;
+                }
+                if let Some(churn) = event.churn {
+                    document.churn = Some(document.churn.map_or(churn, |seen| seen.max(churn)));
+                }
+            }
+        }
+    }
+
+    for (collection, changes) in collections {
+        let mut documents: Vec<DigestDocument> = changes.documents.into_values().collect();
+        let added = documents
+            .iter()
+            .filter(|document| document.change == DocumentChangeKind::Added)
+            .count();
+        let updated = documents.len() - added;
+        digest.total_changes += documents.len() + changes.deleted;
+        // New documents first, then the most changed
+        documents.sort_by(|a, b| {
+            let rank = |document: &DigestDocument| match document.change {
+                DocumentChangeKind::Added => 2.0,
+                DocumentChangeKind::Updated => document.churn.unwrap_or(0.0),
+            };
+            rank(b).total_cmp(&rank(a))
+        });
+        documents.truncate(max_documents);
+        digest.collections.push(CollectionDigest {
+            collection,
+            added,
+            updated,
+            deleted: changes.deleted,
+            documents,
+            top_sections: Vec::new(),
+        });
+    }
+    digest.total_changes += digest.deleted_documents;
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::persistence::change_log::Change;
+
+    fn change(kind: ChangeKind, collection: &str, path: &str, churn: Option<f32>) -> Change {
+        Change {
+            kind,
+            collection: Some(collection.to_string()),
+            document_id: None,
+            path: Some(path.to_string()),
+            churn,
+        }
+    }
+
+    fn events(log: &ChangeLog) -> Vec<ChangeEvent> {
+        log.read(0, 100, None).events
+    }
+
+    #[test]
+    fn test_groups_changes_by_collection() {
+        let log = ChangeLog::in_memory(100);
+        log.append(Change::collection(ChangeKind::CollectionCreated, "docs"));
+        log.append(change(ChangeKind::DocumentAdded, "docs", "a.md", None));
+        log.append(change(
+            ChangeKind::DocumentUpdated,
+            "docs",
+            "a.md",
+            Some(0.9),
+        ));
+        log.append(change(
+            ChangeKind::DocumentUpdated,
+            "docs",
+            "b.md",
+            Some(0.5),
+        ));
+        // Below the threshold: a typo fix
+        log.append(change(
+            ChangeKind::DocumentUpdated,
+            "docs",
+            "c.md",
+            Some(0.05),
+        ));
+        log.append(change(ChangeKind::DocumentUpdated, "api", "d.md", None));
+        log.append(Change {
+            kind: ChangeKind::DocumentDeleted,
+            collection: None,
+            document_id: Some("id".to_string()),
+            path: None,
+            churn: None,
+        });
+
+        let now = Utc::now();
+        let digest = summarize(&events(&log), now, now, 0.2, 10);
+        assert_eq!(digest.total_changes, 4);
+        assert_eq!(digest.created_collections, vec!["docs"]);
+        assert_eq!(digest.deleted_documents, 1);
+
+        let names: Vec<_> = digest
+            .collections
+            .iter()
+            .map(|c| c.collection.as_str())
+            .collect();
+        assert_eq!(names, vec!["api", "docs"]);
+        let docs = &digest.collections[1];
+        assert_eq!((docs.added, docs.updated, docs.deleted), (1, 1, 0));
+        // Added first, even though it was re-indexed afterwards
+        assert_eq!(docs.documents[0].path, "a.md");
+        assert_eq!(docs.documents[0].change, DocumentChangeKind::Added);
+        assert_eq!(docs.documents[1].churn, Some(0.5));
+        // An update without churn (a metadata patch) is always reported
+        assert_eq!(digest.collections[0].updated, 1);
+    }
+
+    #[test]
+    fn test_caps_listed_documents() {
+        let log = ChangeLog::in_memory(100);
+        for i in 0..5 {
+            log.append(change(
+                ChangeKind::DocumentUpdated,
+                "docs",
+                &format!("{}.md", i),
+                Some(0.3 + i as f32 / 10.0),
+            ));
+        }
+        let now = Utc::now();
+        let digest = summarize(&events(&log), now, now, 0.2, 2);
+        let docs = &digest.collections[0];
+        assert_eq!(docs.updated, 5);
+        let paths: Vec<_> = docs.documents.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, vec!["4.md", "3.md"]);
+        assert!(digest.render_text().contains("and 3 more"));
+    }
+
+    #[test]
+    fn test_renders_text() {
+        let now = Utc::now();
+        let mut digest = Digest::empty(now, now);
+        assert!(digest.is_empty());
+        assert!(digest.render_text().contains("Nothing changed."));
+
+        digest.total_changes = 1;
+        digest.collections.push(CollectionDigest {
+            collection: "docs".to_string(),
+            added: 0,
+            updated: 1,
+            deleted: 0,
+            documents: vec![DigestDocument {
+                path: "auth.md".to_string(),
+                title: Some("Authentication".to_string()),
+                change: DocumentChangeKind::Updated,
+                churn: Some(0.45),
+            }],
+            top_sections: vec![DigestSection {
+                section: "Tokens".to_string(),
+                documents: 1,
+            }],
+        });
+        let text = digest.render_text();
+        assert!(text.contains("docs: 0 added, 1 updated, 0 deleted"));
+        assert!(text.contains("~ auth.md (Authentication), 45% changed"));
+        assert!(text.contains("Most changed sections: Tokens (1)"));
+        assert_eq!(digest.subject(), "Docs digest: 1 change in 1 collection");
+    }
+}
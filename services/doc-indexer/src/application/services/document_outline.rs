@@ -0,0 +1,220 @@
+/// Outlines of indexed documents
+///
+/// Indexing splits documents at their headings and records each chunk's
+/// heading path (see [`crate::application::content_processing::headings`]),
+/// so a document's heading tree can be rebuilt from its chunks, listing the
+/// chunks of every section. UIs show it as a navigable outline next to search
+/// hits and fetch the chunks of a section through it.
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+use zero_latency_vector::{VectorMetadata, VectorRepository};
+
+use crate::application::container::ServiceContainer;
+use crate::config::AccessConfig;
+use crate::infrastructure::api::http::principal::access_scope;
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+
+/// Separator of the headings in a section path, as in `Guide > Install`
+pub const SECTION_SEPARATOR: &str = " > ";
+
+/// A heading of a document with the chunks under it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutlineSection {
+    pub heading: String,
+    /// Depth in the tree, 1 for top-level sections
+    pub level: usize,
+    /// Chunks directly under this heading, in document order
+    pub chunk_ids: Vec<Uuid>,
+    pub sections: Vec<OutlineSection>,
+}
+
+/// A chunk of a requested section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlineChunk {
+    pub chunk_id: Uuid,
+    pub chunk_index: usize,
+    pub heading_path: Vec<String>,
+    pub content: String,
+}
+
+/// Heading tree of an indexed document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentOutline {
+    pub document_id: Uuid,
+    pub collection: String,
+    pub title: String,
+    pub path: Option<String>,
+    pub chunk_count: usize,
+    /// Chunks before the first heading
+    pub chunk_ids: Vec<Uuid>,
+    pub sections: Vec<OutlineSection>,
+    /// Chunks of the requested section and its subsections, in document
+    /// order; only present when a section was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunks: Option<Vec<OutlineChunk>>,
+}
+
+/// Application service outlining indexed documents
+#[derive(Clone)]
+pub struct DocumentOutlineService {
+    vector_repository: Arc<dyn VectorRepository>,
+    collection_aliases: Arc<CollectionAliases>,
+    access: AccessConfig,
+}
+
+impl DocumentOutlineService {
+    /// Create an outline service sharing the container's stores
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            vector_repository: container.vector_repository(),
+            collection_aliases: container.collection_aliases(),
+            access: container.config().access.clone(),
+        }
+    }
+
+    /// Outline a document of a collection
+    ///
+    /// With `section`, a heading path, the chunks of that section and its
+    /// subsections are returned with their content as well.
+    pub async fn outline(
+        &self,
+        collection: &str,
+        document_id: Uuid,
+        section: Option<&[String]>,
+    ) -> Result<DocumentOutline> {
+        let chunks = self
+            .vector_repository
+            .document_chunks(
+                &self.collection_aliases.resolve(collection),
+                document_id,
+                0..=usize::MAX,
+            )
+            .await?;
+        // Documents the request principal may not read are not found, so
+        // their existence isn't disclosed either
+        let readable = |first: &VectorMetadata| {
+            access_scope(&self.access).is_none_or(|principal| principal.can_read(&first.custom))
+        };
+        let Some((_, first)) = chunks.first().filter(|(_, first)| readable(first)) else {
+            return Err(ZeroLatencyError::not_found(format!(
+                "document {} in collection {}",
+                document_id, collection
+            )));
+        };
+
+        let (chunk_ids, sections) = build_outline(&chunks);
+        let mut outline = DocumentOutline {
+            document_id,
+            collection: collection.to_string(),
+            title: first.title.clone(),
+            path: first.custom.get(zero_latency_vector::PATH_KEY).cloned(),
+            chunk_count: chunks.len(),
+            chunk_ids,
+            sections,
+            chunks: None,
+        };
+        if let Some(section) = section {
+            let selected: Vec<OutlineChunk> = chunks
+                .into_iter()
+                .filter(|(_, chunk)| chunk.heading_path.starts_with(section))
+                .map(|(chunk_id, chunk)| OutlineChunk {
+                    chunk_id,
+                    chunk_index: chunk.chunk_index,
+                    heading_path: chunk.heading_path,
+                    content: chunk.content,
+                })
+                .collect();
+            if selected.is_empty() {
+                return Err(ZeroLatencyError::not_found(format!(
+                    "section '{}' of document {}",
+                    section.join(SECTION_SEPARATOR),
+                    document_id
+                )));
+            }
+            outline.chunks = Some(selected);
+        }
+        Ok(outline)
+    }
+}
+
+/// Heading tree of chunks in document order, with the ids of the chunks
+/// before the first heading
+///
+/// A heading that recurs after another section starts a new node.
+fn build_outline(chunks: &[(Uuid, VectorMetadata)]) -> (Vec<Uuid>, Vec<OutlineSection>) {
+    let mut preamble = Vec::new();
+    let mut sections: Vec<OutlineSection> = Vec::new();
+    for (chunk_id, chunk) in chunks {
+        let Some((heading, parents)) = chunk.heading_path.split_last() else {
+            preamble.push(*chunk_id);
+            continue;
+        };
+        let mut siblings = &mut sections;
+        for (depth, parent) in parents.iter().enumerate() {
+            siblings = &mut last_section(siblings, parent, depth + 1).sections;
+        }
+        last_section(siblings, heading, parents.len() + 1)
+            .chunk_ids
+            .push(*chunk_id);
+    }
+    (preamble, sections)
+}
+
+/// The last of `siblings` if it has `heading`, or a new section appended
+fn last_section<'a>(
+    siblings: &'a mut Vec<OutlineSection>,
+    heading: &str,
+    level: usize,
+) -> &'a mut OutlineSection {
+    if siblings.last().is_none_or(|last| last.heading != heading) {
+        siblings.push(OutlineSection {
+            heading: heading.to_string(),
+            level,
+            chunk_ids: Vec::new(),
+            sections: Vec::new(),
+        });
+    }
+    siblings.last_mut().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(chunk_index: usize, heading_path: &[&str]) -> (Uuid, VectorMetadata) {
+        (
+            Uuid::new_v4(),
+            VectorMetadata {
+                chunk_index,
+                heading_path: heading_path.iter().map(|h| h.to_string()).collect(),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_outline_nests_sections_by_heading_path() {
+        let chunks = vec![
+            chunk(0, &[]),
+            chunk(1, &["Guide", "Install"]),
+            chunk(2, &["Guide", "Install"]),
+            chunk(3, &["Guide", "Configure"]),
+            chunk(4, &["Reference"]),
+            chunk(5, &["Guide"]),
+        ];
+        let (preamble, sections) = build_outline(&chunks);
+        assert_eq!(preamble, vec![chunks[0].0]);
+
+        let headings: Vec<&str> = sections.iter().map(|s| s.heading.as_str()).collect();
+        // "Guide" recurs after "Reference", so it is outlined twice
+        assert_eq!(headings, vec!["Guide", "Reference", "Guide"]);
+        let guide = &sections[0];
+        assert!(guide.chunk_ids.is_empty());
+        assert_eq!(guide.sections.len(), 2);
+        assert_eq!(guide.sections[0].level, 2);
+        assert_eq!(guide.sections[0].chunk_ids, vec![chunks[1].0, chunks[2].0]);
+        assert_eq!(guide.sections[1].chunk_ids, vec![chunks[3].0]);
+        assert_eq!(sections[2].chunk_ids, vec![chunks[5].0]);
+    }
+}
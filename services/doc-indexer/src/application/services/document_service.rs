@@ -9,13 +9,128 @@ use zero_latency_core::{
     Result,
 };
 use zero_latency_search::{
-    QueryEnhancer, ResultRanker, SearchOrchestrator, SearchRequest, SearchResponse,
+    AnswerExtractor, LexicalAnswerExtractor, QueryEnhancer, ResultDeduplicator, ResultRanker,
+    SearchOrchestrator, SearchRequest, SearchResponse, ANCHOR_KEY, LINE_END_KEY, LINE_START_KEY,
 };
 use zero_latency_vector::{EmbeddingGenerator, VectorDocument, VectorRepository};
 
 use crate::application::container::ServiceContainer;
+use crate::application::content_processing::headings::{self, LocatedHeading, Section};
+use crate::application::content_processing::line_map::LineMap;
+use crate::application::content_processing::{ContentDecoder, DecodedContent, TextEncoding};
 use crate::application::services::filter_service::{FilterService, IndexingFilters};
+use crate::application::services::indexing_pipeline::{IndexingPipeline, PipelineProgress};
+use crate::application::services::indexing_report::{ChunkChurn, IndexingReport, IndexingStage};
+use crate::application::services::throttle::{IndexingPriority, Throttle};
 use crate::application::ContentProcessor;
+use crate::config::{AccessConfig, PipelineConfig};
+use crate::infrastructure::api::http::principal::access_scope;
+use crate::infrastructure::operations::lanes::ExecutionLanes;
+use crate::infrastructure::operations::usage::{estimate_tokens, UsageCounters, UsageMeter};
+use crate::infrastructure::persistence::change_log::{Change, ChangeKind, ChangeLog};
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+use crate::infrastructure::persistence::fingerprint_registry::{
+    FileFingerprint, FingerprintRegistry,
+};
+use crate::infrastructure::persistence::metadata_schemas::MetadataSchemaRegistry;
+use crate::infrastructure::persistence::blocklist::Blocklist;
+use crate::infrastructure::persistence::curation::{self, CurationStore};
+#[cfg(feature = "late-interaction")]
+use crate::infrastructure::persistence::token_vectors::TokenVectorStore;
+use crate::infrastructure::plugins::PluginHost;
+use crate::infrastructure::scripting::ScriptHost;
+
+/// Decoded text of a source file together with its fingerprint
+struct SourceText {
+    content: String,
+    encoding: TextEncoding,
+    fingerprint: FileFingerprint,
+}
+
+/// Options for a single indexing run
+#[derive(Clone)]
+pub struct IndexRunOptions {
+    /// Descend into subdirectories
+    pub recursive: bool,
+    /// Filters overriding the service defaults
+    pub filters: Option<IndexingFilters>,
+    /// Re-index files even if their fingerprint is unchanged
+    pub force: bool,
+    /// Scheduling priority; low-priority runs are throttled
+    pub priority: IndexingPriority,
+    /// Live per-stage progress counters
+    pub progress: Arc<PipelineProgress>,
+    /// Count the chunks and tokens that would be embedded without embedding
+    /// or storing anything
+    pub dry_run: bool,
+}
+
+/// Outcome of warming a collection ahead of user searches
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WarmupReport {
+    pub collection: String,
+    /// Vectors the store loaded into memory
+    pub vectors_loaded: usize,
+    /// Popular queries replayed to prime the query embedding cache
+    pub queries_primed: usize,
+    /// Replayed queries that failed; warming continues past them
+    pub queries_failed: usize,
+    pub duration_ms: f64,
+}
+
+impl Default for IndexRunOptions {
+    fn default() -> Self {
+        Self {
+            recursive: true,
+            filters: None,
+            force: false,
+            priority: IndexingPriority::Normal,
+            progress: Arc::new(PipelineProgress::new()),
+            dry_run: false,
+        }
+    }
+}
+
+/// Document read from disk and ready to be chunked
+pub(crate) struct PreparedDocument {
+    pub path: std::path::PathBuf,
+    pub document: Document,
+    pub fingerprint: FileFingerprint,
+    /// Headings of the source located in the document's content
+    pub headings: Vec<LocatedHeading>,
+    /// Source lines of the document's content
+    pub lines: LineMap,
+}
+
+/// Document split into chunks and ready to be embedded
+pub(crate) struct ChunkedDocument {
+    pub prepared: PreparedDocument,
+    pub chunks: Vec<DocumentChunk>,
+}
+
+/// Embedded chunks of a document ready to be stored
+pub(crate) struct EmbeddedDocument {
+    pub document_id: String,
+    pub path: std::path::PathBuf,
+    pub fingerprint: FileFingerprint,
+    pub vectors: Vec<VectorDocument>,
+    pub chunks: ChunkDiff,
+}
+
+/// A document's chunks compared with the ones stored for its file
+pub(crate) struct ChunkDiff {
+    /// Id of every chunk of the document in order; reused chunks keep the
+    /// id of their stored vector
+    pub chunk_ids: Vec<String>,
+    /// Hash of every chunk, parallel to `chunk_ids`
+    pub chunk_hashes: Vec<String>,
+    /// Stored chunks the document no longer has
+    pub removed: Vec<String>,
+    /// Chunks that need embedding
+    pub embedded: usize,
+    /// Whether the file had been indexed before
+    pub reindexed: bool,
+}
 
 /// Application service for document indexing operations
 #[derive(Clone)]
@@ -24,15 +139,38 @@ pub struct DocumentIndexingService {
     embedding_generator: Arc<dyn EmbeddingGenerator>,
     search_orchestrator: Arc<dyn SearchOrchestrator>,
     filter_service: Arc<FilterService>,
+    fingerprint_registry: Arc<FingerprintRegistry>,
+    collection_aliases: Arc<CollectionAliases>,
+    metadata_schemas: Arc<MetadataSchemaRegistry>,
+    curation: Arc<CurationStore>,
+    blocklist: Arc<Blocklist>,
+    deduplicator: Option<Arc<ResultDeduplicator>>,
+    change_log: Arc<ChangeLog>,
+    usage: Arc<UsageMeter>,
+    lanes: Arc<ExecutionLanes>,
+    plugins: Arc<PluginHost>,
+    scripts: Arc<ScriptHost>,
+    pipeline_config: PipelineConfig,
+    access: AccessConfig,
+    /// Whether chunks get a title vector besides their content vector
+    title_vectors: bool,
+    /// Store of the chunks' token vectors, when late interaction is enabled
+    #[cfg(feature = "late-interaction")]
+    token_vectors: Option<Arc<TokenVectorStore>>,
+    /// Distinct tokens embedded per chunk for late interaction
+    #[cfg(feature = "late-interaction")]
+    max_tokens: usize,
     content_processor: ContentProcessor,
     query_enhancer: Option<Arc<dyn QueryEnhancer>>,
     result_ranker: Option<Arc<dyn ResultRanker>>,
+    answer_extractor: Arc<dyn AnswerExtractor>,
 }
 
 impl DocumentIndexingService {
     /// Create a new document indexing service with default filters
     pub fn new(container: &ServiceContainer) -> Self {
-        let default_filters = IndexingFilters::new();
+        let mut default_filters = IndexingFilters::new();
+        default_filters.set_max_file_size(container.config().service.max_document_size as u64);
         Self::with_filters(container, default_filters)
     }
 
@@ -43,9 +181,28 @@ impl DocumentIndexingService {
             embedding_generator: container.embedding_generator(),
             search_orchestrator: container.search_orchestrator(),
             filter_service: Arc::new(FilterService::new(filters)),
+            fingerprint_registry: container.fingerprint_registry(),
+            collection_aliases: container.collection_aliases(),
+            metadata_schemas: container.metadata_schemas(),
+            curation: container.curation(),
+            blocklist: container.blocklist(),
+            deduplicator: container.deduplicator(),
+            change_log: container.change_log(),
+            usage: container.usage(),
+            lanes: container.lanes(),
+            plugins: container.plugins(),
+            scripts: container.scripts(),
+            pipeline_config: container.config().pipeline.clone(),
+            access: container.config().access.clone(),
+            title_vectors: container.config().embedding.title_vectors,
+            #[cfg(feature = "late-interaction")]
+            token_vectors: container.token_vectors(),
+            #[cfg(feature = "late-interaction")]
+            max_tokens: container.config().late_interaction.max_tokens,
             content_processor: ContentProcessor::new(),
             query_enhancer: None,
             result_ranker: None,
+            answer_extractor: Arc::new(LexicalAnswerExtractor::default()),
         }
     }
 
@@ -61,9 +218,28 @@ impl DocumentIndexingService {
             embedding_generator: container.embedding_generator(),
             search_orchestrator: container.search_orchestrator(),
             filter_service: Arc::new(FilterService::new(filters)),
+            fingerprint_registry: container.fingerprint_registry(),
+            collection_aliases: container.collection_aliases(),
+            metadata_schemas: container.metadata_schemas(),
+            curation: container.curation(),
+            blocklist: container.blocklist(),
+            deduplicator: container.deduplicator(),
+            change_log: container.change_log(),
+            usage: container.usage(),
+            lanes: container.lanes(),
+            plugins: container.plugins(),
+            scripts: container.scripts(),
+            pipeline_config: container.config().pipeline.clone(),
+            access: container.config().access.clone(),
+            title_vectors: container.config().embedding.title_vectors,
+            #[cfg(feature = "late-interaction")]
+            token_vectors: container.token_vectors(),
+            #[cfg(feature = "late-interaction")]
+            max_tokens: container.config().late_interaction.max_tokens,
             content_processor: ContentProcessor::new(),
             query_enhancer,
             result_ranker,
+            answer_extractor: Arc::new(LexicalAnswerExtractor::default()),
         }
     }
 
@@ -84,23 +260,105 @@ impl DocumentIndexingService {
         document: Document,
         collection_name: &str,
     ) -> Result<()> {
-        // Create chunks from the document
-        let chunks = self.create_document_chunks(&document).await?;
+        self.store_document(document, collection_name, ChangeKind::DocumentAdded)
+            .await
+    }
 
-        // Generate embeddings for each chunk
-        let mut vector_documents = Vec::new();
+    /// Chunk, embed and store a document, logging the change as `kind`
+    async fn store_document(
+        &self,
+        mut document: Document,
+        collection_name: &str,
+        kind: ChangeKind,
+    ) -> Result<()> {
+        if let Err(e) = self.scripts.enrich(collection_name, &mut document) {
+            tracing::warn!("{} ({})", e, document.path.display());
+        }
+        if let Some(schema) = self.metadata_schemas.get(collection_name) {
+            if let Some(violation) = schema.validate(&document.metadata.custom).first() {
+                return Err(zero_latency_core::ZeroLatencyError::validation(
+                    format!("metadata.{}", violation.field),
+                    violation.message.clone(),
+                ));
+            }
+        }
+        // Documents added through the API carry their source text
+        let content_type = self
+            .content_processor
+            .detect_content_type(&document.path, &document.content);
+        let headings = headings::locate_headings(
+            &document.content,
+            headings::extract_headings(&document.content, &content_type),
+        );
+        let lines = LineMap::new(&document.content, &document.content);
+        let chunks = self
+            .create_document_chunks(&document, &headings, &lines)
+            .await?;
+        let vector_documents = self
+            .embed_chunks(&document, chunks, collection_name)
+            .await?;
+        let usage = UsageCounters {
+            indexed_bytes: document.content.len() as u64,
+            stored_vectors: vector_documents.len() as u64,
+            embedding_tokens: vector_documents
+                .iter()
+                .map(|vector| estimate_tokens(&vector.metadata.content))
+                .sum(),
+            ..Default::default()
+        };
+        self.upsert_vectors(vector_documents).await?;
+        self.usage.record(usage);
+        self.change_log.append(Change {
+            kind,
+            collection: Some(collection_name.to_string()),
+            document_id: Some(document.id.to_string()),
+            path: Some(document.path.to_string_lossy().into_owned()),
+            churn: None,
+        });
+        Ok(())
+    }
+
+    /// Generate embeddings for the chunks of a document
+    pub(crate) async fn embed_chunks(
+        &self,
+        document: &Document,
+        chunks: Vec<DocumentChunk>,
+        collection_name: &str,
+    ) -> Result<Vec<VectorDocument>> {
+        // Vectors are stored under the collection the name currently aliases
+        let collection_name = self.collection_aliases.resolve(collection_name);
+        let model = self.embedding_generator.model_name().to_string();
+        let mut vector_documents = Vec::with_capacity(chunks.len());
         for chunk in chunks {
             let embedding = self
                 .embedding_generator
                 .generate_embedding(&chunk.content)
                 .await?;
+            let mut named_vectors = std::collections::HashMap::new();
+            if self.title_vectors {
+                let title = std::iter::once(&document.title)
+                    .chain(&chunk.heading_path)
+                    .filter(|part| !part.is_empty())
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" > ");
+                if !title.is_empty() {
+                    let vector = self.embedding_generator.generate_embedding(&title).await?;
+                    named_vectors.insert(zero_latency_vector::TITLE_VECTOR.to_string(), vector);
+                }
+            }
 
             let mut custom_metadata = chunk.metadata.custom.clone();
-            custom_metadata.insert("collection".to_string(), collection_name.to_string());
+            custom_metadata.insert("collection".to_string(), collection_name.clone());
+            // Path patterns and result URIs read the source path from here
+            custom_metadata
+                .entry(zero_latency_vector::PATH_KEY.to_string())
+                .or_insert_with(|| document.path.to_string_lossy().into_owned());
 
             let vector_doc = VectorDocument {
                 id: chunk.id,
                 embedding,
+                named_vectors,
                 metadata: zero_latency_vector::VectorMetadata {
                     document_id: chunk.document_id,
                     chunk_index: chunk.chunk_index,
@@ -108,45 +366,160 @@ impl DocumentIndexingService {
                     title: document.title.clone(),
                     heading_path: chunk.heading_path.clone(),
                     url: None,
-                    collection: Some(collection_name.to_string()),
+                    collection: Some(collection_name.clone()),
                     custom: custom_metadata,
+                    embedding_model: Some(model.clone()),
+                    embedded_at: Some(chrono::Utc::now()),
                 },
             };
 
             vector_documents.push(vector_doc);
         }
 
-        // Store in vector repository
-        self.vector_repository.insert(vector_documents).await?;
+        #[cfg(feature = "late-interaction")]
+        self.embed_tokens(&vector_documents, &collection_name)
+            .await?;
 
-        Ok(())
+        Ok(vector_documents)
+    }
+
+    /// Embed and store the token vectors of chunks for late interaction
+    #[cfg(feature = "late-interaction")]
+    async fn embed_tokens(
+        &self,
+        vector_documents: &[VectorDocument],
+        collection_name: &str,
+    ) -> Result<()> {
+        let Some(store) = &self.token_vectors else {
+            return Ok(());
+        };
+        let mut matrices = Vec::with_capacity(vector_documents.len());
+        for vector_doc in vector_documents {
+            let terms =
+                zero_latency_search::token_terms(&vector_doc.metadata.content, self.max_tokens);
+            if terms.is_empty() {
+                continue;
+            }
+            let tokens = self
+                .embedding_generator
+                .generate_batch_embeddings(terms.iter().map(String::as_str).collect())
+                .await?;
+            matrices.push((vector_doc.id, zero_latency_search::TokenMatrix::compress(&tokens)));
+        }
+        store.store(collection_name, matrices)
+    }
+
+    /// Drop the token vectors of removed chunks
+    #[cfg(feature = "late-interaction")]
+    fn remove_tokens(&self, chunk_ids: &[String]) {
+        if let Some(store) = &self.token_vectors {
+            if let Err(e) = store.remove(chunk_ids) {
+                tracing::warn!("Failed to remove token vectors: {}", e);
+            }
+        }
+    }
+
+    /// Store vectors in the vector repository, returning the ids of the stored chunks
+    pub(crate) async fn upsert_vectors(
+        &self,
+        vector_documents: Vec<VectorDocument>,
+    ) -> Result<Vec<String>> {
+        let chunk_ids = vector_documents
+            .iter()
+            .map(|doc| doc.id.to_string())
+            .collect();
+
+        self.vector_repository.upsert_batch(vector_documents).await?;
+
+        Ok(chunk_ids)
     }
 
     /// Delete a document from the index
     pub async fn delete_document(&self, document_id: &str) -> Result<()> {
         let _deleted = self.vector_repository.delete(document_id).await?;
+        #[cfg(feature = "late-interaction")]
+        self.remove_tokens(&[document_id.to_string()]);
+        self.change_log.append(Change {
+            kind: ChangeKind::DocumentDeleted,
+            collection: None,
+            document_id: Some(document_id.to_string()),
+            path: None,
+            churn: None,
+        });
         Ok(())
     }
 
     /// Search for documents similar to a query
     pub async fn search_documents(&self, query: &str, limit: usize) -> Result<SearchResponse> {
-        let search_request = SearchRequest::new(query).with_limit(limit);
+        let mut search_request = SearchRequest::new(query).with_limit(limit);
+        search_request.filters.access = access_scope(&self.access);
 
         self.search_orchestrator.search(search_request).await
     }
 
     /// Search for documents similar to a query in a specific collection
+    ///
+    /// `context_window` neighboring chunks are stitched on each side of
+    /// every hit.
     pub async fn search_documents_in_collection(
         &self,
         query: &str,
         collection_name: &str,
         limit: usize,
+        context_window: Option<usize>,
+    ) -> Result<SearchResponse> {
+        let settings = zero_latency_search::SearchSettings {
+            limit: Some(limit),
+            context_window,
+            ..Default::default()
+        };
+        settings.validate()?;
+        self.search_documents_with_settings(query, collection_name, &settings, &Default::default())
+            .await
+    }
+
+    /// Search within a specific collection using resolved search settings
+    ///
+    /// The settings are echoed in the response's `effective_settings`.
+    /// `filters` carries exclusions and metadata patterns; the collection
+    /// filter is added here.
+    pub async fn search_documents_with_settings(
+        &self,
+        query: &str,
+        collection_name: &str,
+        settings: &zero_latency_search::SearchSettings,
+        filters: &zero_latency_search::SearchFilters,
+    ) -> Result<SearchResponse> {
+        self.search_collection(query, collection_name, settings, filters, false)
+            .await
+    }
+
+    /// Search a collection for an editor lookup
+    ///
+    /// Lookups fire on every cursor rest, so they are kept out of analytics.
+    pub async fn lookup_documents(
+        &self,
+        query: &str,
+        collection_name: &str,
+        settings: &zero_latency_search::SearchSettings,
+    ) -> Result<SearchResponse> {
+        self.search_collection(query, collection_name, settings, &Default::default(), true)
+            .await
+    }
+
+    async fn search_collection(
+        &self,
+        query: &str,
+        collection_name: &str,
+        settings: &zero_latency_search::SearchSettings,
+        filters: &zero_latency_search::SearchFilters,
+        skip_analytics: bool,
     ) -> Result<SearchResponse> {
         tracing::info!(
-            "[AdvancedSearch] Starting search with query: '{}', collection: '{}', limit: {}",
+            "[AdvancedSearch] Starting search with query: '{}', collection: '{}', settings: {:?}",
             query,
             collection_name,
-            limit
+            settings
         );
         tracing::info!(
             "[AdvancedSearch] Components available - Query Enhancer: {}, Result Ranker: {}",
@@ -155,26 +528,338 @@ impl DocumentIndexingService {
         );
 
         // Use the SearchOrchestrator (which includes analytics) instead of direct vector search
-        let mut filters = zero_latency_search::SearchFilters::default();
+        let stored_collection = self.collection_aliases.resolve(collection_name);
+        let mut filters = filters.clone();
         filters
             .custom
-            .insert("collection".to_string(), collection_name.to_string());
+            .insert("collection".to_string(), stored_collection.clone());
+        // Blocked documents are excluded like those the request leaves out
+        filters
+            .exclude
+            .merge(self.blocklist.exclusions(collection_name));
+        // Documents the request principal may not read are withheld
+        filters.access = access_scope(&self.access);
 
-        let search_request = zero_latency_search::SearchRequest::new(query)
-            .with_limit(limit)
-            .with_filters(filters);
+        let mut search_request = zero_latency_search::SearchRequest::new(query)
+            .with_filters(filters.clone())
+            .with_settings(settings);
+        search_request.options.skip_analytics = skip_analytics;
 
         // This will go through the full pipeline including analytics
-        self.search_orchestrator.search(search_request).await
+        let mut response = self.search_orchestrator.search(search_request).await?;
+        response.search_metadata.effective_settings = Some(settings.clone());
+
+        let limit = response.results.len().max(settings.limit.unwrap_or(0));
+        if self
+            .curate(
+                query,
+                collection_name,
+                &stored_collection,
+                &filters,
+                &mut response.results,
+            )
+            .await?
+        {
+            response.results.truncate(limit);
+            response.search_metadata.ranking_method.push_str("+curation");
+        }
+
+        // Scores are calibrated by now, so one threshold suits every query
+        if let Some(min_score) = settings.min_score {
+            response.results.retain(|result| {
+                result.from_signals.pinned().is_some() || result.final_score.value() >= min_score
+            });
+        }
+
+        // Report results under the public name rather than a migration's shadow collection
+        if stored_collection != collection_name {
+            for result in &mut response.results {
+                result.collection = Some(collection_name.to_string());
+                result.doc_id.collection = collection_name.to_string();
+            }
+        }
+
+        if settings.extract_answer == Some(true) && zero_latency_search::is_question(query) {
+            let started = std::time::Instant::now();
+            response.answer = self
+                .answer_extractor
+                .extract(query, &response.results)
+                .await?;
+            let elapsed = started.elapsed();
+            response.search_metadata.execution_time += elapsed;
+            response
+                .search_metadata
+                .stage_timings
+                .push(zero_latency_search::StageTiming {
+                    stage: "answer_extraction".to_string(),
+                    duration_ms: elapsed.as_secs_f64() * 1000.0,
+                });
+        }
+        // Answers come from the hits alone, so neighbors are stitched after
+        if let Some(window) = settings.context_window.filter(|window| *window > 0) {
+            self.stitch_neighbors(&stored_collection, window, &mut response.results)
+                .await?;
+        }
+        rate_confidence(query, &mut response);
+        Ok(response)
+    }
+
+    /// Stitch up to `window` neighboring chunks on each side of every hit
+    /// into its content
+    ///
+    /// Results that are not chunks of an indexed document, such as pinned
+    /// documents, are left as they are.
+    async fn stitch_neighbors(
+        &self,
+        stored_collection: &str,
+        window: usize,
+        results: &mut [zero_latency_search::SearchResult],
+    ) -> Result<()> {
+        for result in results.iter_mut() {
+            let Some((document_id, chunk_index)) = zero_latency_search::chunk_position(result)
+            else {
+                continue;
+            };
+            let chunks: Vec<(usize, String)> = self
+                .vector_repository
+                .document_chunks(
+                    stored_collection,
+                    document_id,
+                    zero_latency_search::context_range(chunk_index, window),
+                )
+                .await?
+                .into_iter()
+                .map(|(_, chunk)| (chunk.chunk_index, chunk.content))
+                .collect();
+            zero_latency_search::stitch_context(result, chunk_index, &chunks);
+        }
+        Ok(())
+    }
+
+    /// Apply a collection's curation rules to its search results
+    ///
+    /// Boosts rescale the scores of matching results, then the documents
+    /// pinned for `query` move to the top. A pinned document the search did
+    /// not return is looked up by its path, as long as the request's filters
+    /// admit it. Returns whether any rule applied.
+    async fn curate(
+        &self,
+        query: &str,
+        collection_name: &str,
+        stored_collection: &str,
+        filters: &zero_latency_search::SearchFilters,
+        results: &mut Vec<zero_latency_search::SearchResult>,
+    ) -> Result<bool> {
+        let Some(curation) = self.curation.get(collection_name) else {
+            return Ok(false);
+        };
+        let boosted = curation.apply_boosts(results);
+
+        let pinned = curation.pinned_documents(query);
+        let missing: Vec<_> = pinned
+            .iter()
+            .filter(|document| !results.iter().any(|result| document.matches(result)))
+            .collect();
+        if !missing.is_empty() {
+            let request_filter =
+                zero_latency_vector::MetadataFilter::compile(&filters.metadata_patterns)?
+                    .with_ranges(&filters.ranges)?;
+            let embedding = self.embedding_generator.generate_embedding(query).await?;
+            for document in missing {
+                let mut found: Vec<_> = self
+                    .vector_repository
+                    .search_filtered(
+                        Some(stored_collection),
+                        embedding.clone(),
+                        1,
+                        &document.filter,
+                    )
+                    .await?
+                    .into_iter()
+                    .filter(|result| request_filter.matches_metadata(&result.metadata))
+                    .map(zero_latency_search::SearchResult::from)
+                    .collect();
+                filters.exclude.apply(&mut found);
+                if let Some(principal) = &filters.access {
+                    principal.apply(&mut found);
+                }
+                results.extend(found);
+            }
+        }
+
+        curation::pin_results(results, &pinned);
+        Ok(boosted || results.iter().any(|result| result.from_signals.pinned().is_some()))
+    }
+
+    /// Search several collections concurrently and merge the results
+    ///
+    /// Each collection is searched with its own resolved settings; the merged
+    /// results are normalized per collection and tagged with their source
+    /// collection (see [`zero_latency_search::federate_results`]).
+    pub async fn search_documents_federated(
+        &self,
+        query: &str,
+        collections: &[(String, zero_latency_search::SearchSettings)],
+        limit: usize,
+        filters: &zero_latency_search::SearchFilters,
+    ) -> Result<SearchResponse> {
+        let started = std::time::Instant::now();
+        let responses =
+            futures::future::try_join_all(collections.iter().map(|(collection_name, settings)| {
+                self.search_documents_with_settings(query, collection_name, settings, filters)
+            }))
+            .await?;
+
+        let mut metadata = zero_latency_search::SearchMetadata {
+            query: zero_latency_core::values::SearchQuery::new(query),
+            execution_time: std::time::Duration::ZERO,
+            query_enhancement_applied: false,
+            ranking_method: "federated".to_string(),
+            result_sources: Vec::new(),
+            debug_info: None,
+            stage_timings: Vec::new(),
+            effective_settings: None,
+            intent: None,
+        };
+        let mut sets = Vec::with_capacity(responses.len());
+        let mut answer: Option<zero_latency_search::ExtractedAnswer> = None;
+        for ((collection_name, _), response) in collections.iter().zip(responses) {
+            // Keep the most confident answer any collection found
+            if let Some(candidate) = response.answer {
+                if answer
+                    .as_ref()
+                    .is_none_or(|answer| candidate.confidence > answer.confidence)
+                {
+                    answer = Some(candidate);
+                }
+            }
+            let collection_metadata = response.search_metadata;
+            metadata.query_enhancement_applied |= collection_metadata.query_enhancement_applied;
+            // Every collection parses the same query, so any one's report will do
+            if metadata.debug_info.is_none() {
+                metadata.debug_info = collection_metadata.debug_info;
+            }
+            metadata.intent = metadata.intent.or(collection_metadata.intent);
+            for source in collection_metadata.result_sources {
+                if !metadata.result_sources.contains(&source) {
+                    metadata.result_sources.push(source);
+                }
+            }
+            metadata
+                .stage_timings
+                .push(zero_latency_search::StageTiming {
+                    stage: format!("collection:{}", collection_name),
+                    duration_ms: collection_metadata.execution_time.as_secs_f64() * 1000.0,
+                });
+            sets.push(zero_latency_search::CollectionResults {
+                collection: collection_name.clone(),
+                results: response.results,
+            });
+        }
+
+        let merge_started = std::time::Instant::now();
+        // Mirrors usually live in separate collections, so collapse them
+        // after merging and only then cut the ranking to size
+        let mut results = zero_latency_search::federate_results(sets, usize::MAX);
+        if let Some(deduplicator) = &self.deduplicator {
+            if deduplicator.collapse_mirrors(&mut results) > 0 {
+                metadata.ranking_method.push_str("+dedup");
+            }
+        }
+        results.truncate(limit);
+        metadata
+            .stage_timings
+            .push(zero_latency_search::StageTiming {
+                stage: "federation".to_string(),
+                duration_ms: merge_started.elapsed().as_secs_f64() * 1000.0,
+            });
+        metadata.execution_time = started.elapsed();
+
+        let mut response = SearchResponse {
+            results,
+            total_count: None,
+            search_metadata: metadata,
+            pagination: None,
+            answer,
+            confidence: None,
+            answerable: None,
+            facets: None,
+        };
+        rate_confidence(query, &mut response);
+        Ok(response)
+    }
+
+    /// Preload a collection's vectors and replay `queries` against it
+    ///
+    /// Replayed queries run through the full search pipeline, so their
+    /// embeddings are cached for the first real search, but are left out of
+    /// analytics. Queries are skipped when the collection holds no vectors.
+    pub async fn warm_collection(
+        &self,
+        collection_name: &str,
+        settings: &zero_latency_search::SearchSettings,
+        queries: &[String],
+    ) -> Result<WarmupReport> {
+        let started = std::time::Instant::now();
+        let stored_collection = self.collection_aliases.resolve(collection_name);
+        let vectors_loaded = self
+            .vector_repository
+            .preload_collection(&stored_collection)
+            .await?;
+
+        let mut filters = zero_latency_search::SearchFilters::default();
+        filters
+            .custom
+            .insert("collection".to_string(), stored_collection);
+
+        let mut queries_primed = 0;
+        let mut queries_failed = 0;
+        // Nothing to search in an empty collection
+        let queries = if vectors_loaded == 0 { &[] } else { queries };
+        for query in queries {
+            let mut request = SearchRequest::new(query)
+                .with_filters(filters.clone())
+                .with_settings(settings);
+            request.options.skip_analytics = true;
+
+            match self.search_orchestrator.search(request).await {
+                Ok(_) => queries_primed += 1,
+                Err(e) => {
+                    queries_failed += 1;
+                    tracing::warn!("Warm-up query '{}' failed: {}", query, e);
+                }
+            }
+        }
+
+        let report = WarmupReport {
+            collection: collection_name.to_string(),
+            vectors_loaded,
+            queries_primed,
+            queries_failed,
+            duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        };
+        tracing::info!(
+            "Warmed collection '{}': {} vectors loaded, {} queries primed in {:.0}ms",
+            collection_name,
+            report.vectors_loaded,
+            report.queries_primed,
+            report.duration_ms
+        );
+        Ok(report)
     }
 
     /// Update an existing document in the index
     pub async fn update_document(&self, document: Document) -> Result<()> {
         // Delete from vector store
-        self.delete_document(&document.id.to_string()).await?;
+        self.vector_repository
+            .delete(&document.id.to_string())
+            .await?;
+        #[cfg(feature = "late-interaction")]
+        self.remove_tokens(&[document.id.to_string()]);
 
         // Re-index the updated document
-        self.index_document(document).await
+        self.store_document(document, "zero_latency_docs", ChangeKind::DocumentUpdated)
+            .await
     }
 
     /// Get document health/status information
@@ -226,9 +911,28 @@ impl DocumentIndexingService {
             embedding_generator: Arc::clone(&self.embedding_generator),
             search_orchestrator: Arc::clone(&self.search_orchestrator),
             filter_service: Arc::new(FilterService::new(filters)),
+            fingerprint_registry: Arc::clone(&self.fingerprint_registry),
+            collection_aliases: Arc::clone(&self.collection_aliases),
+            metadata_schemas: Arc::clone(&self.metadata_schemas),
+            curation: Arc::clone(&self.curation),
+            blocklist: Arc::clone(&self.blocklist),
+            deduplicator: self.deduplicator.clone(),
+            change_log: Arc::clone(&self.change_log),
+            usage: Arc::clone(&self.usage),
+            lanes: Arc::clone(&self.lanes),
+            plugins: Arc::clone(&self.plugins),
+            scripts: Arc::clone(&self.scripts),
+            pipeline_config: self.pipeline_config.clone(),
+            access: self.access.clone(),
+            title_vectors: self.title_vectors,
+            #[cfg(feature = "late-interaction")]
+            token_vectors: self.token_vectors.clone(),
+            #[cfg(feature = "late-interaction")]
+            max_tokens: self.max_tokens,
             content_processor: self.content_processor.clone(),
             query_enhancer: self.query_enhancer.clone(),
             result_ranker: self.result_ranker.clone(),
+            answer_extractor: Arc::clone(&self.answer_extractor),
         }
     }
 
@@ -266,34 +970,38 @@ impl DocumentIndexingService {
         filters: Option<IndexingFilters>,
         collection_name: &str,
     ) -> Result<(u64, f64)> {
-        use std::fs;
-        use std::time::Instant;
-
-        let start_time = Instant::now();
-        let mut documents_processed = 0u64;
-
-        // Create a temporary service with filters if provided
-        let service = if let Some(filters) = filters {
-            Self {
-                vector_repository: Arc::clone(&self.vector_repository),
-                embedding_generator: Arc::clone(&self.embedding_generator),
-                search_orchestrator: Arc::clone(&self.search_orchestrator),
-                filter_service: Arc::new(FilterService::new(filters)),
-                content_processor: self.content_processor.clone(),
-                query_enhancer: self.query_enhancer.clone(),
-                result_ranker: self.result_ranker.clone(),
-            }
-        } else {
-            // Clone current service (uses existing filters)
-            Self {
-                vector_repository: Arc::clone(&self.vector_repository),
-                embedding_generator: Arc::clone(&self.embedding_generator),
-                search_orchestrator: Arc::clone(&self.search_orchestrator),
-                filter_service: Arc::clone(&self.filter_service),
-                content_processor: self.content_processor.clone(),
-                query_enhancer: self.query_enhancer.clone(),
-                result_ranker: self.result_ranker.clone(),
-            }
+        let report = self
+            .index_path_with_report(
+                path,
+                collection_name,
+                IndexRunOptions {
+                    recursive,
+                    filters,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        Ok((report.documents_processed, report.processing_time_ms))
+    }
+
+    /// Index all documents from a path, returning a report with per-file diagnostics
+    ///
+    /// Files whose fingerprint matches the one recorded by a previous run are
+    /// skipped unless `options.force` is set. Per-stage throughput is
+    /// published to `options.progress` while the pipeline runs. A dry run
+    /// stops short of embedding and only counts what would be embedded.
+    pub async fn index_path_with_report(
+        &self,
+        path: &str,
+        collection_name: &str,
+        options: IndexRunOptions,
+    ) -> Result<IndexingReport> {
+        let start_time = std::time::Instant::now();
+
+        // Use a service with the request filters if provided
+        let service = match options.filters {
+            Some(filters) => self.with_updated_filters(filters),
+            None => self.clone(),
         };
 
         let path = std::path::Path::new(path);
@@ -304,240 +1012,521 @@ impl DocumentIndexingService {
             ));
         }
 
-        if path.is_file() {
-            // Check if we should index this file
-            if !service.filter_service.should_index(path) {
-                return Ok((0, 0.0));
-            }
+        let pipeline_config = service.pipeline_config.for_priority(options.priority);
+        let throttle = Throttle::for_priority(options.priority, &pipeline_config.nice);
+        let lanes = service.lanes.clone();
+        if options.priority == IndexingPriority::Low {
+            tracing::info!("Indexing {} in nice mode", path.display());
+        }
 
-            // Index single file
-            if let Ok(content) = fs::read_to_string(path) {
-                let mut document = Document {
-                    id: zero_latency_core::Uuid::new_v4(),
-                    title: path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("Unknown")
-                        .to_string(),
-                    content,
-                    path: path.to_path_buf(),
-                    last_modified: chrono::Utc::now(),
-                    size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
-                    metadata: zero_latency_core::models::DocumentMetadata::default(),
-                };
-
-                // Add collection information to document metadata
-                document
-                    .metadata
-                    .custom
-                    .insert("collection".to_string(), collection_name.to_string());
-
-                service
-                    .index_document_with_collection(document, collection_name)
-                    .await?;
-                documents_processed += 1;
-            }
-        } else if path.is_dir() {
-            // Index directory with collection awareness
-            documents_processed = service
-                .index_directory_with_collection(path, recursive, collection_name)
+        let mut report =
+            IndexingPipeline::new(service, pipeline_config, options.progress, throttle, lanes)
+                .with_dry_run(options.dry_run)
+                .run(path, options.recursive, collection_name, options.force)
                 .await?;
+
+        report.processing_time_ms = start_time.elapsed().as_millis() as f64;
+        if options.dry_run {
+            return Ok(report);
         }
 
-        let processing_time = start_time.elapsed().as_millis() as f64;
-        Ok((documents_processed, processing_time))
+        if let Err(e) = self.fingerprint_registry.save() {
+            tracing::warn!("Failed to persist fingerprint registry: {}", e);
+        }
+
+        self.usage.record(UsageCounters {
+            indexed_bytes: report.bytes_read,
+            stored_vectors: report.vectors_stored,
+            embedding_tokens: report.embedding_tokens,
+            ..Default::default()
+        });
+
+        tracing::info!(
+            "Completed indexing: {} - {} documents processed, {} unchanged files skipped in {:.0}ms",
+            path.display(),
+            report.documents_processed,
+            report.files_skipped,
+            report.processing_time_ms
+        );
+        Ok(report)
     }
 
-    /// Recursively index documents in a directory
-    fn index_directory<'a>(
-        &'a self,
-        dir: &'a std::path::Path,
-        recursive: bool,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<u64>> + Send + 'a>> {
-        self.index_directory_with_collection(dir, recursive, "zero_latency_docs")
+    /// Get the filter service shared with pipeline workers
+    pub(crate) fn filter_service(&self) -> Arc<FilterService> {
+        Arc::clone(&self.filter_service)
     }
 
-    /// Recursively index documents in a directory with collection awareness
-    fn index_directory_with_collection<'a>(
-        &'a self,
-        dir: &'a std::path::Path,
-        recursive: bool,
-        collection_name: &'a str,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<u64>> + Send + 'a>> {
-        Box::pin(async move {
-            use std::fs;
+    /// Read a file and turn it into a document ready for chunking
+    ///
+    /// Returns `None` (after recording why in `report`) for files that are
+    /// skipped by the ingestion guards, unchanged since the last run,
+    /// excluded by a filter plugin, of a content type that is not indexed, or
+    /// that fail content processing. Content handler plugins take precedence
+    /// over the built-in handlers and can index otherwise unsupported types.
+    pub(crate) fn prepare_document(
+        &self,
+        path: &std::path::Path,
+        collection_name: &str,
+        force: bool,
+        report: &mut IndexingReport,
+    ) -> Option<PreparedDocument> {
+        let source = self.read_document_text(path, collection_name, force, report)?;
+
+        if !self.plugins.accepts(path, source.fingerprint.size) {
+            tracing::debug!("Skipping {} (excluded by a plugin)", path.display());
+            return None;
+        }
 
-            let mut documents_processed = 0u64;
-            let mut files_scanned = 0u64;
-            let start_time = std::time::Instant::now();
+        let content_type = self
+            .content_processor
+            .detect_content_type(path, &source.content);
+        let processed = match self.plugins.process(path, &source.content) {
+            Ok(Some(processed_content)) => Ok(processed_content),
+            Ok(None) => {
+                // Check whether the detected content type should be indexed
+                if !self.content_processor.should_index(&content_type) {
+                    tracing::debug!("Skipping {:?} file: {}", content_type, path.display());
+                    return None;
+                }
+                self.content_processor
+                    .process_content(&source.content, &content_type)
+            }
+            Err(e) => Err(e),
+        };
 
-            tracing::info!("Starting directory indexing: {}", dir.display());
+        let processed_content = match processed {
+            Ok(processed_content) => processed_content,
+            Err(e) => {
+                tracing::warn!("Failed to process content for {}: {}", path.display(), e);
+                report.error(
+                    path,
+                    IndexingStage::Process,
+                    e.to_string(),
+                    Some("Check that the file is well-formed for its content type"),
+                );
+                return None;
+            }
+        };
 
-            if let Ok(entries) = fs::read_dir(dir) {
-                let entries: Vec<_> = entries.flatten().collect();
-                let total_entries = entries.len();
+        // A re-indexed file keeps its document id, so unchanged chunks still match
+        let previous_id = self
+            .fingerprint_registry
+            .get(collection_name, path)
+            .and_then(|entry| entry.document_id)
+            .and_then(|id| zero_latency_core::Uuid::parse_str(&id).ok());
+        let mut document = Document {
+            id: previous_id.unwrap_or_else(zero_latency_core::Uuid::new_v4),
+            title: path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("Unknown")
+                .to_string(),
+            content: processed_content,
+            path: path.to_path_buf(),
+            last_modified: chrono::Utc::now(),
+            size: source.fingerprint.size,
+            metadata: zero_latency_core::models::DocumentMetadata {
+                content_type: Some(format!("{:?}", content_type)),
+                custom: std::collections::HashMap::from([(
+                    "collection".to_string(),
+                    collection_name.to_string(),
+                )]),
+                ..Default::default()
+            },
+        };
+        Self::record_source_encoding(&mut document, source.encoding);
 
-                tracing::info!(
-                    "Found {} entries to process in {}",
-                    total_entries,
-                    dir.display()
+        if let Err(e) = self.scripts.enrich(collection_name, &mut document) {
+            tracing::warn!("{} ({})", e, path.display());
+            report.warn(
+                path,
+                IndexingStage::Process,
+                e.to_string(),
+                Some("Fix the collection's enrich script; the document was indexed without it"),
+            );
+        }
+        // Metadata patched through the API outlives re-indexing
+        self.fingerprint_registry
+            .metadata_patch(collection_name, path)
+            .apply(&mut document.metadata.custom);
+
+        if let Some(schema) = self.metadata_schemas.get(collection_name) {
+            let violations = schema.validate(&document.metadata.custom);
+            if !violations.is_empty() {
+                tracing::warn!(
+                    "Skipping {}: metadata does not match the schema of {}",
+                    path.display(),
+                    collection_name
                 );
+                report.reject_metadata(path, violations);
+                return None;
+            }
+        }
 
-                for (index, entry) in entries.into_iter().enumerate() {
-                    let path = entry.path();
-                    files_scanned += 1;
-
-                    // Apply filtering rules early to skip unwanted files/directories
-                    if path.is_file() && !self.filter_service.should_index(&path) {
-                        tracing::debug!("Skipping file (filtered): {}", path.display());
-                        continue;
-                    }
-
-                    if path.is_dir() && !self.filter_service.should_traverse(&path) {
-                        tracing::debug!("Skipping directory (filtered): {}", path.display());
-                        continue;
-                    }
-
-                    // Log progress every 100 files or every 10 seconds
-                    if files_scanned % 100 == 0 || start_time.elapsed().as_secs() % 10 == 0 {
-                        tracing::info!(
-                            "Progress: {}/{} files scanned, {} documents indexed ({:.1}%)",
-                            files_scanned,
-                            total_entries,
-                            documents_processed,
-                            (index as f64 / total_entries as f64) * 100.0
-                        );
-                    }
-
-                    if path.is_file() {
-                        // Read file content first
-                        if let Ok(raw_content) = fs::read_to_string(&path) {
-                            // Detect content type
-                            let content_type = self
-                                .content_processor
-                                .detect_content_type(&path, &raw_content);
-
-                            // Check if this content type should be indexed
-                            if self.content_processor.should_index(&content_type) {
-                                // Process content based on type
-                                match self
-                                    .content_processor
-                                    .process_content(&raw_content, &content_type)
-                                {
-                                    Ok(processed_content) => {
-                                        let document = Document {
-                                            id: zero_latency_core::Uuid::new_v4(),
-                                            title: path
-                                                .file_name()
-                                                .and_then(|n| n.to_str())
-                                                .unwrap_or("Unknown")
-                                                .to_string(),
-                                            content: processed_content,
-                                            path: path.clone(),
-                                            last_modified: chrono::Utc::now(),
-                                            size: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
-                                            metadata: {
-                                                let mut metadata = zero_latency_core::models::DocumentMetadata::default();
-                                                metadata.content_type =
-                                                    Some(format!("{:?}", content_type));
-                                                metadata.custom.insert(
-                                                    "collection".to_string(),
-                                                    collection_name.to_string(),
-                                                );
-                                                metadata
-                                            },
-                                        };
-
-                                        if let Err(e) = self
-                                            .index_document_with_collection(
-                                                document,
-                                                collection_name,
-                                            )
-                                            .await
-                                        {
-                                            tracing::warn!(
-                                                "Failed to index {}: {}",
-                                                path.display(),
-                                                e
-                                            );
-                                        } else {
-                                            documents_processed += 1;
-                                            tracing::debug!(
-                                                "Indexed {} as {:?}",
-                                                path.display(),
-                                                content_type
-                                            );
-                                        }
-                                    }
-                                    Err(e) => {
-                                        tracing::warn!(
-                                            "Failed to process content for {}: {}",
-                                            path.display(),
-                                            e
-                                        );
-                                    }
-                                }
-                            } else {
-                                tracing::debug!(
-                                    "Skipping {:?} file: {}",
-                                    content_type,
-                                    path.display()
-                                );
-                            }
-                        } else {
-                            tracing::debug!("Could not read file as UTF-8: {}", path.display());
-                        }
-                    } else if path.is_dir() && recursive {
-                        // Recursively index subdirectories
-                        tracing::debug!("Recursing into directory: {}", path.display());
-                        documents_processed += self
-                            .index_directory_with_collection(&path, recursive, collection_name)
-                            .await?;
-                    }
-                }
+        let headings = headings::locate_headings(
+            &document.content,
+            headings::extract_headings(&source.content, &content_type),
+        );
+        let lines = LineMap::new(&source.content, &document.content);
+        Some(PreparedDocument {
+            path: path.to_path_buf(),
+            document,
+            fingerprint: source.fingerprint,
+            headings,
+            lines,
+        })
+    }
+
+    /// Read a file as text, applying the ingestion guards
+    ///
+    /// Returns `None` (after recording why in `report`) for files that exceed
+    /// the configured size limit, cannot be read, or look like binary content.
+    /// Files unchanged since they were last indexed into the collection are
+    /// also skipped unless `force` is set. Non-UTF-8 text is transcoded and
+    /// returned together with its detected encoding.
+    fn read_document_text(
+        &self,
+        path: &std::path::Path,
+        collection_name: &str,
+        force: bool,
+        report: &mut IndexingReport,
+    ) -> Option<SourceText> {
+        let (size, modified_ms) = match std::fs::metadata(path) {
+            Ok(metadata) => (
+                metadata.len(),
+                metadata
+                    .modified()
+                    .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp_millis())
+                    .unwrap_or(0),
+            ),
+            Err(e) => {
+                tracing::warn!("Could not read metadata for {}: {}", path.display(), e);
+                report.error(
+                    path,
+                    IndexingStage::Read,
+                    format!("Could not read file metadata: {}", e),
+                    Some("Check that the file exists and is readable"),
+                );
+                return None;
             }
+        };
+
+        // Cheap check first: unchanged size and mtime means the file can be skipped unread
+        if !force
+            && self
+                .fingerprint_registry
+                .is_unchanged(collection_name, path, size, modified_ms)
+        {
+            tracing::debug!("Skipping unchanged file: {}", path.display());
+            report.files_skipped += 1;
+            return None;
+        }
 
-            let elapsed = start_time.elapsed();
-            tracing::info!(
-                "Completed directory indexing: {} - {} documents processed in {:.2}s",
-                dir.display(),
-                documents_processed,
-                elapsed.as_secs_f64()
+        if !self.filter_service.within_size_limit(size) {
+            let max_file_size = self.filter_service.filters().max_file_size;
+            tracing::warn!(
+                "Skipping {} ({} bytes exceeds max file size of {} bytes)",
+                path.display(),
+                size,
+                max_file_size
+            );
+            report.warn(
+                path,
+                IndexingStage::Read,
+                format!(
+                    "File size of {} bytes exceeds the limit of {} bytes",
+                    size, max_file_size
+                ),
+                Some("Raise the limit with --max-file-size or add an ignore pattern"),
             );
+            return None;
+        }
 
-            Ok(documents_processed)
-        })
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => {
+                report.bytes_read += bytes.len() as u64;
+                bytes
+            }
+            Err(e) => {
+                tracing::warn!("Could not read {}: {}", path.display(), e);
+                report.error(
+                    path,
+                    IndexingStage::Read,
+                    format!("Could not read file: {}", e),
+                    Some("Check file permissions"),
+                );
+                return None;
+            }
+        };
+
+        let fingerprint = FileFingerprint::new(size, modified_ms, &bytes);
+        if !force {
+            // The mtime changed but the content did not (e.g. a fresh checkout)
+            let unchanged = self
+                .fingerprint_registry
+                .get(collection_name, path)
+                .is_some_and(|entry| entry.fingerprint.hash == fingerprint.hash);
+            if unchanged {
+                tracing::debug!("Skipping unchanged file: {}", path.display());
+                self.fingerprint_registry
+                    .touch(collection_name, path, modified_ms);
+                report.files_skipped += 1;
+                return None;
+            }
+        }
+
+        match ContentDecoder::decode(&bytes) {
+            DecodedContent::Text { content, encoding } => {
+                if encoding != TextEncoding::Utf8 {
+                    tracing::debug!(
+                        "Transcoded {} from {}",
+                        path.display(),
+                        encoding.label()
+                    );
+                    report.warn(
+                        path,
+                        IndexingStage::Decode,
+                        format!("Transcoded from {}", encoding.label()),
+                        Some("Convert the file to UTF-8 to avoid transcoding"),
+                    );
+                }
+                Some(SourceText {
+                    content,
+                    encoding,
+                    fingerprint,
+                })
+            }
+            DecodedContent::Binary => {
+                tracing::debug!("Skipping binary file: {}", path.display());
+                report.warn(
+                    path,
+                    IndexingStage::Decode,
+                    "File appears to contain binary data",
+                    Some("Add an ignore pattern to skip this file"),
+                );
+                None
+            }
+        }
+    }
+
+    /// Compare a document's chunks with the ones stored for its file
+    ///
+    /// Returns the chunks that need embedding: those whose hash matches no
+    /// stored chunk of the file, or every chunk when `force` is set. The
+    /// stored vectors of the other chunks are kept as they are.
+    pub(crate) fn diff_chunks(
+        &self,
+        collection_name: &str,
+        path: &std::path::Path,
+        document: &Document,
+        chunks: Vec<DocumentChunk>,
+        force: bool,
+    ) -> (Vec<DocumentChunk>, ChunkDiff) {
+        let previous = self.fingerprint_registry.get(collection_name, path);
+        let reindexed = previous.is_some();
+
+        // Stored chunk ids by hash; entries from before chunks were hashed match nothing
+        let mut stored: std::collections::HashMap<String, Vec<String>> =
+            std::collections::HashMap::new();
+        let mut removed = Vec::new();
+        if let Some(previous) = previous {
+            if force || previous.chunk_hashes.len() != previous.chunk_ids.len() {
+                removed = previous.chunk_ids;
+            } else {
+                for (id, hash) in previous.chunk_ids.into_iter().zip(previous.chunk_hashes) {
+                    stored.entry(hash).or_default().push(id);
+                }
+            }
+        }
+
+        let mut changed = Vec::new();
+        let mut chunk_ids = Vec::with_capacity(chunks.len());
+        let mut chunk_hashes = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            let hash = Self::chunk_hash(document, &chunk);
+            match stored.get_mut(&hash).and_then(Vec::pop) {
+                Some(id) => chunk_ids.push(id),
+                None => {
+                    chunk_ids.push(chunk.id.to_string());
+                    changed.push(chunk);
+                }
+            }
+            chunk_hashes.push(hash);
+        }
+        removed.extend(stored.into_values().flatten());
+
+        let diff = ChunkDiff {
+            chunk_ids,
+            chunk_hashes,
+            removed,
+            embedded: changed.len(),
+            reindexed,
+        };
+        (changed, diff)
+    }
+
+    /// Hash of everything a chunk's stored vector is built from
+    ///
+    /// Taken before the collection alias is resolved, so vectors carried
+    /// over by an embedding migration still match.
+    fn chunk_hash(document: &Document, chunk: &DocumentChunk) -> String {
+        let custom: std::collections::BTreeMap<_, _> = chunk.metadata.custom.iter().collect();
+        let key = serde_json::json!([
+            chunk.content,
+            chunk.chunk_index,
+            document.title,
+            chunk.heading_path,
+            custom
+        ]);
+        FileFingerprint::hash_content(key.to_string().as_bytes())
+    }
+
+    /// Record the fingerprint and chunks of a freshly indexed file
+    ///
+    /// Chunks stored by a previous run over the same file that the file no
+    /// longer has are removed, so re-indexing a changed file does not leave
+    /// stale vectors behind. The file is logged as updated if it had been
+    /// indexed before, else as added. Returns the chunk churn of a
+    /// re-indexed file.
+    pub(crate) async fn commit_fingerprint(
+        &self,
+        collection_name: &str,
+        document_id: String,
+        path: &std::path::Path,
+        fingerprint: FileFingerprint,
+        chunks: ChunkDiff,
+    ) -> Option<ChunkChurn> {
+        if !chunks.removed.is_empty() {
+            if let Err(e) = self.vector_repository.delete_batch(&chunks.removed).await {
+                tracing::warn!(
+                    "Failed to remove {} stale chunks of {}: {}",
+                    chunks.removed.len(),
+                    path.display(),
+                    e
+                );
+            }
+            #[cfg(feature = "late-interaction")]
+            self.remove_tokens(&chunks.removed);
+        }
+
+        // New and removed chunks against every chunk the file had or has
+        let changed = (chunks.embedded + chunks.removed.len()) as f32;
+        let share = changed / (chunks.chunk_ids.len() + chunks.removed.len()).max(1) as f32;
+        let churn = chunks.reindexed.then(|| ChunkChurn {
+            path: path.display().to_string(),
+            embedded: chunks.embedded,
+            reused: chunks.chunk_ids.len() - chunks.embedded,
+            removed: chunks.removed.len(),
+        });
+        self.fingerprint_registry.record_chunks(
+            collection_name,
+            path,
+            fingerprint,
+            document_id.clone(),
+            chunks.chunk_ids,
+            chunks.chunk_hashes,
+        );
+        self.change_log.append(Change {
+            kind: if chunks.reindexed {
+                ChangeKind::DocumentUpdated
+            } else {
+                ChangeKind::DocumentAdded
+            },
+            collection: Some(collection_name.to_string()),
+            document_id: Some(document_id),
+            path: Some(path.to_string_lossy().into_owned()),
+            churn: chunks.reindexed.then_some(share),
+        });
+        churn
+    }
+
+    /// Persist the recorded fingerprints so an interrupted run keeps them
+    pub(crate) fn save_fingerprints(&self) -> Result<()> {
+        self.fingerprint_registry.save()
+    }
+
+    /// Record the original encoding of a transcoded document in its metadata
+    fn record_source_encoding(document: &mut Document, encoding: TextEncoding) {
+        if encoding != TextEncoding::Utf8 {
+            document
+                .metadata
+                .custom
+                .insert("source_encoding".to_string(), encoding.label().to_string());
+        }
     }
 
     /// Create document chunks from a document
-    async fn create_document_chunks(&self, document: &Document) -> Result<Vec<DocumentChunk>> {
+    ///
+    /// The document is split into sections at `headings`, so no chunk spans
+    /// two of them, and each chunk records its section's heading path.
+    pub(crate) async fn create_document_chunks(
+        &self,
+        document: &Document,
+        headings: &[LocatedHeading],
+        lines: &LineMap,
+    ) -> Result<Vec<DocumentChunk>> {
         // Simple chunking strategy - split by sentences
         // In a real implementation, this might use more sophisticated chunking
-        let sentences: Vec<&str> = document
-            .content
-            .split('.')
-            .filter(|s| !s.trim().is_empty())
+        let sections = headings::sections(&document.content, headings);
+        let sentences: Vec<(&Section, Vec<(usize, &str)>)> = sections
+            .iter()
+            .map(|section| {
+                // Each sentence with its offset in the content
+                let mut offset = section.range.start;
+                let sentences = document.content[section.range.clone()]
+                    .split('.')
+                    .map(|sentence| {
+                        let start = offset;
+                        offset += sentence.len() + 1;
+                        (start, sentence)
+                    })
+                    .filter(|(_, s)| !s.trim().is_empty())
+                    .collect();
+                (section, sentences)
+            })
             .collect();
 
         let mut chunks = Vec::new();
         let chunk_size = 50; // 50 sentences per chunk (much more reasonable)
 
-        for (i, chunk_sentences) in sentences.chunks(chunk_size).enumerate() {
-            let content = chunk_sentences.join(". ") + ".";
+        let section_chunks = sentences.iter().flat_map(|(section, sentences)| {
+            sentences
+                .chunks(chunk_size)
+                .map(move |chunk_sentences| (*section, chunk_sentences))
+        });
+        for (i, (section, chunk_sentences)) in section_chunks.enumerate() {
+            let content = chunk_sentences
+                .iter()
+                .map(|(_, sentence)| *sentence)
+                .collect::<Vec<_>>()
+                .join(". ")
+                + ".";
+            let (first, last) = (chunk_sentences[0], chunk_sentences[chunk_sentences.len() - 1]);
+            let start_offset = first.0 + first.1.len() - first.1.trim_start().len();
+            let end_offset = last.0 + last.1.trim_end().len();
+            let (line_start, line_end) = lines.lines(start_offset..end_offset);
 
             let chunk = DocumentChunk {
                 id: zero_latency_core::Uuid::new_v4(),
                 document_id: document.id,
                 content,
                 chunk_index: i,
-                heading_path: vec![], // Would be extracted in real implementation
-                start_offset: 0,      // Would be calculated in real implementation
-                end_offset: 0,        // Would be calculated in real implementation
+                heading_path: section.heading_path.clone(),
+                start_offset,
+                end_offset,
                 metadata: zero_latency_core::models::ChunkMetadata {
                     custom: {
                         let mut custom = document.metadata.custom.clone(); // Start with document metadata
+                        if !document.metadata.tags.is_empty() {
+                            custom.insert("tags".to_string(), document.metadata.tags.join(","));
+                        }
                         custom.insert("chunk_index".to_string(), i.to_string());
                         custom.insert("parent_document_id".to_string(), document.id.to_string());
+                        custom.insert(LINE_START_KEY.to_string(), line_start.to_string());
+                        custom.insert(LINE_END_KEY.to_string(), line_end.to_string());
+                        if let Some(anchor) = &section.anchor {
+                            custom.insert(ANCHOR_KEY.to_string(), anchor.clone());
+                        }
                         custom
                     },
                     ..Default::default()
@@ -551,6 +1540,22 @@ impl DocumentIndexingService {
     }
 }
 
+/// Label a response's confidence and answerability, keeping the signals
+/// behind them in the search's debug info
+fn rate_confidence(query: &str, response: &mut SearchResponse) {
+    let assessment = zero_latency_search::assess_confidence(query, &response.results);
+    response.confidence = Some(assessment.level);
+    // An extracted answer shows the results do answer the query
+    response.answerable = Some(assessment.answerable || response.answer.is_some());
+    if let Ok(value) = serde_json::to_value(assessment) {
+        response
+            .search_metadata
+            .debug_info
+            .get_or_insert_with(Default::default)
+            .insert("confidence".to_string(), value);
+    }
+}
+
 /// Health information about the document index
 #[derive(Debug, Clone)]
 pub struct IndexHealth {
@@ -0,0 +1,461 @@
+/// Near-duplicate documents of a collection
+///
+/// Corpora gathered from several sources often hold the same page more than
+/// once: a README copied into the docs site, a vendored guide, an old export
+/// left next to its replacement. The report finds clusters of such documents
+/// so they can be pruned, or fed to deduplication as canonical sources and to
+/// curation as pins. Documents are candidates when their mean embeddings are
+/// close, and a candidate pair is only confirmed when their text also
+/// overlaps, so documents that are merely about the same topic are not
+/// reported.
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use zero_latency_core::{DateTime, Result, Utc, Uuid, ZeroLatencyError};
+use zero_latency_search::fusion::content_similarity;
+use zero_latency_vector::{MetadataFilter, VectorDocument, VectorRepository, PATH_KEY};
+
+use crate::application::container::ServiceContainer;
+use crate::config::AccessConfig;
+use crate::infrastructure::api::http::principal::access_scope;
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+use crate::infrastructure::persistence::vector::scoring::cosine_similarity;
+
+/// Default cosine similarity of mean document embeddings for candidates
+pub const DEFAULT_VECTOR_THRESHOLD: f32 = 0.95;
+
+/// Default content overlap confirming a candidate pair
+pub const DEFAULT_CONTENT_THRESHOLD: f64 = 0.8;
+
+/// Why a cluster member was suggested as its canonical document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CanonicalReason {
+    /// The document comes from the most preferred configured canonical source
+    CanonicalSource,
+    /// No configured canonical source matched; the earliest indexed copy wins
+    EarliestIndexed,
+}
+
+/// A document of a duplicate cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateDocument {
+    pub document_id: Uuid,
+    pub title: String,
+    pub path: Option<String>,
+    pub chunks: usize,
+    pub indexed_at: Option<DateTime<Utc>>,
+    /// Cosine similarity of the mean embeddings to the canonical document
+    pub vector_similarity: f32,
+    /// Overlap of the text with the canonical document's (0.0-1.0)
+    pub content_similarity: f64,
+}
+
+/// Documents that are near-identical copies of each other
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCluster {
+    /// Suggested document to keep
+    pub canonical: DuplicateDocument,
+    pub reason: CanonicalReason,
+    /// The other copies, most similar first
+    pub duplicates: Vec<DuplicateDocument>,
+}
+
+/// Near-duplicate clusters of a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateReport {
+    pub collection: String,
+    pub documents_scanned: usize,
+    pub vector_threshold: f32,
+    pub content_threshold: f64,
+    /// Documents that could be removed by keeping one per cluster
+    pub redundant_documents: usize,
+    /// Clusters, largest first
+    pub clusters: Vec<DuplicateCluster>,
+}
+
+/// Application service finding near-duplicate documents
+#[derive(Clone)]
+pub struct DuplicateReportService {
+    vector_repository: Arc<dyn VectorRepository>,
+    collection_aliases: Arc<CollectionAliases>,
+    canonical_sources: Vec<String>,
+    access: AccessConfig,
+}
+
+impl DuplicateReportService {
+    /// Create a report service sharing the container's stores
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            vector_repository: container.vector_repository(),
+            collection_aliases: container.collection_aliases(),
+            canonical_sources: container.config().dedup.canonical_sources.clone(),
+            access: container.config().access.clone(),
+        }
+    }
+
+    /// Scan a collection for clusters of near-identical documents
+    ///
+    /// Every pair of documents is compared, so the scan is quadratic in the
+    /// number of documents; it runs off the async runtime.
+    pub async fn report(
+        &self,
+        collection: &str,
+        vector_threshold: f32,
+        content_threshold: f64,
+    ) -> Result<DuplicateReport> {
+        let mut vectors = self
+            .vector_repository
+            .collection_vectors(&self.collection_aliases.resolve(collection))
+            .await?;
+        // Documents the request principal may not read aren't reported
+        if let Some(principal) = access_scope(&self.access) {
+            vectors.retain(|vector| principal.can_read(&vector.metadata.custom));
+        }
+        if vectors.is_empty() {
+            return Err(ZeroLatencyError::not_found(format!(
+                "vectors of collection {}",
+                collection
+            )));
+        }
+
+        self.scan(collection, vectors, vector_threshold, content_threshold)
+            .await
+    }
+
+    /// Scan a collection's already loaded vectors for duplicate clusters
+    pub async fn scan(
+        &self,
+        collection: &str,
+        vectors: Vec<VectorDocument>,
+        vector_threshold: f32,
+        content_threshold: f64,
+    ) -> Result<DuplicateReport> {
+        let documents = documents(vectors);
+        let documents_scanned = documents.len();
+        let canonical_sources = canonical_filters(&self.canonical_sources);
+        let clusters = tokio::task::spawn_blocking(move || {
+            find_clusters(
+                &documents,
+                vector_threshold,
+                content_threshold,
+                &canonical_sources,
+            )
+        })
+        .await
+        .map_err(|e| ZeroLatencyError::internal(format!("Duplicate scan failed: {}", e)))?;
+
+        Ok(DuplicateReport {
+            collection: collection.to_string(),
+            documents_scanned,
+            vector_threshold,
+            content_threshold,
+            redundant_documents: clusters.iter().map(|c| c.duplicates.len()).sum(),
+            clusters,
+        })
+    }
+}
+
+/// A document assembled from its chunks
+struct ScannedDocument {
+    id: Uuid,
+    title: String,
+    path: Option<String>,
+    chunks: usize,
+    indexed_at: Option<DateTime<Utc>>,
+    /// Mean of the chunk embeddings
+    embedding: Vec<f32>,
+    /// Chunk texts in chunk order
+    content: String,
+}
+
+impl ScannedDocument {
+    fn describe(&self, vector_similarity: f32, content_similarity: f64) -> DuplicateDocument {
+        DuplicateDocument {
+            document_id: self.id,
+            title: self.title.clone(),
+            path: self.path.clone(),
+            chunks: self.chunks,
+            indexed_at: self.indexed_at,
+            vector_similarity,
+            content_similarity,
+        }
+    }
+}
+
+// Documents in a stable order, each with its chunks joined
+fn documents(vectors: Vec<VectorDocument>) -> Vec<ScannedDocument> {
+    let mut by_document: BTreeMap<Uuid, Vec<VectorDocument>> = BTreeMap::new();
+    for vector in vectors {
+        by_document
+            .entry(vector.metadata.document_id)
+            .or_default()
+            .push(vector);
+    }
+
+    by_document
+        .into_iter()
+        .map(|(id, mut chunks)| {
+            chunks.sort_by_key(|chunk| chunk.metadata.chunk_index);
+            let dimension = chunks[0].embedding.len();
+            let mut embedding = vec![0.0; dimension];
+            for chunk in &chunks {
+                for (sum, value) in embedding.iter_mut().zip(&chunk.embedding) {
+                    *sum += value;
+                }
+            }
+            for value in &mut embedding {
+                *value /= chunks.len() as f32;
+            }
+            let first = &chunks[0].metadata;
+            ScannedDocument {
+                id,
+                title: first.title.clone(),
+                path: first.custom.get(PATH_KEY).cloned(),
+                chunks: chunks.len(),
+                indexed_at: chunks.iter().filter_map(|c| c.metadata.embedded_at).min(),
+                embedding,
+                content: chunks
+                    .iter()
+                    .map(|c| c.metadata.content.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            }
+        })
+        .collect()
+}
+
+fn find_clusters(
+    documents: &[ScannedDocument],
+    vector_threshold: f32,
+    content_threshold: f64,
+    canonical_sources: &[MetadataFilter],
+) -> Vec<DuplicateCluster> {
+    // Union-find over confirmed pairs
+    let mut parent: Vec<usize> = (0..documents.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for a in 0..documents.len() {
+        for b in a + 1..documents.len() {
+            if cosine_similarity(&documents[a].embedding, &documents[b].embedding)
+                < vector_threshold
+            {
+                continue;
+            }
+            if content_similarity(&documents[a].content, &documents[b].content) < content_threshold
+            {
+                continue;
+            }
+            let (root_a, root_b) = (root(&mut parent, a), root(&mut parent, b));
+            parent[root_b] = root_a;
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for index in 0..documents.len() {
+        let group = root(&mut parent, index);
+        groups.entry(group).or_default().push(index);
+    }
+
+    let mut clusters: Vec<DuplicateCluster> = groups
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|members| cluster(documents, members, canonical_sources))
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.duplicates
+            .len()
+            .cmp(&a.duplicates.len())
+            .then_with(|| a.canonical.path.cmp(&b.canonical.path))
+    });
+    clusters
+}
+
+fn cluster(
+    documents: &[ScannedDocument],
+    members: Vec<usize>,
+    canonical_sources: &[MetadataFilter],
+) -> DuplicateCluster {
+    let source_rank = |document: &ScannedDocument| {
+        canonical_sources
+            .iter()
+            .position(|filter| {
+                filter.matches(|key| match key {
+                    PATH_KEY => document.path.as_deref(),
+                    _ => None,
+                })
+            })
+            .unwrap_or(canonical_sources.len())
+    };
+    let canonical = members
+        .iter()
+        .copied()
+        .min_by(|&a, &b| {
+            let (a, b) = (&documents[a], &documents[b]);
+            source_rank(a)
+                .cmp(&source_rank(b))
+                // Documents indexed before timestamps were recorded sort last
+                .then_with(|| match (a.indexed_at, b.indexed_at) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                })
+                .then_with(|| a.path.cmp(&b.path))
+        })
+        .unwrap_or(members[0]);
+    let kept = &documents[canonical];
+    let reason = if source_rank(kept) < canonical_sources.len() {
+        CanonicalReason::CanonicalSource
+    } else {
+        CanonicalReason::EarliestIndexed
+    };
+
+    let mut duplicates: Vec<DuplicateDocument> = members
+        .into_iter()
+        .filter(|&member| member != canonical)
+        .map(|member| {
+            let document = &documents[member];
+            document.describe(
+                cosine_similarity(&kept.embedding, &document.embedding),
+                content_similarity(&kept.content, &document.content),
+            )
+        })
+        .collect();
+    duplicates.sort_by(|a, b| b.content_similarity.total_cmp(&a.content_similarity));
+
+    DuplicateCluster {
+        canonical: kept.describe(1.0, 1.0),
+        reason,
+        duplicates,
+    }
+}
+
+/// Canonical source path patterns as filters, skipping malformed ones
+fn canonical_filters(sources: &[String]) -> Vec<MetadataFilter> {
+    let key = PATH_KEY.to_string();
+    sources
+        .iter()
+        .filter_map(|source| MetadataFilter::compile([(&key, source)]).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, VectorBackend};
+    use crate::infrastructure::persistence::embeddings::mock_adapter::MockEmbeddingConfig;
+    use crate::infrastructure::MockEmbeddingAdapter;
+    use zero_latency_vector::VectorMetadata;
+
+    async fn container(canonical_sources: Vec<String>) -> ServiceContainer {
+        let mut config = Config::default();
+        config.vector.backend = VectorBackend::Memory;
+        config.dedup.canonical_sources = canonical_sources;
+        ServiceContainer::with_embedding_generator(
+            config,
+            Arc::new(
+                MockEmbeddingAdapter::new(MockEmbeddingConfig {
+                    dimension: 2,
+                    seed: 7,
+                })
+                .unwrap(),
+            ),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn document(path: &str, content: &str, embedding: [f32; 2], age_days: i64) -> VectorDocument {
+        VectorDocument {
+            id: Uuid::new_v4(),
+            embedding: embedding.to_vec(),
+            named_vectors: Default::default(),
+            metadata: VectorMetadata {
+                document_id: Uuid::new_v4(),
+                content: content.to_string(),
+                title: path.to_string(),
+                collection: Some("docs".to_string()),
+                custom: HashMap::from([(PATH_KEY.to_string(), path.to_string())]),
+                embedded_at: Some(Utc::now() - chrono::Duration::days(age_days)),
+                ..Default::default()
+            },
+        }
+    }
+
+    const INSTALL: &str = "Install the CLI with cargo install mdx, then run mdx init in the \
+                           repository root to create a project file.";
+
+    #[tokio::test]
+    async fn test_reports_confirmed_clusters() {
+        let container = container(vec!["site/**".to_string()]).await;
+        container
+            .vector_repository()
+            .insert(vec![
+                document("repo/README.md", INSTALL, [1.0, 0.0], 3),
+                document("site/install.md", INSTALL, [0.99, 0.01], 1),
+                // Close in embedding space but different text
+                document(
+                    "repo/UPGRADE.md",
+                    "Upgrading replaces the binary in place and keeps the project file.",
+                    [0.98, 0.02],
+                    2,
+                ),
+                document(
+                    "site/search.md",
+                    "Searching ranks chunks by relevance.",
+                    [0.0, 1.0],
+                    1,
+                ),
+            ])
+            .await
+            .unwrap();
+        let service = DuplicateReportService::new(&container);
+
+        let report = service
+            .report("docs", DEFAULT_VECTOR_THRESHOLD, DEFAULT_CONTENT_THRESHOLD)
+            .await
+            .unwrap();
+        assert_eq!(report.documents_scanned, 4);
+        assert_eq!(report.redundant_documents, 1);
+        assert_eq!(report.clusters.len(), 1);
+        let cluster = &report.clusters[0];
+        assert_eq!(cluster.canonical.path.as_deref(), Some("site/install.md"));
+        assert_eq!(cluster.reason, CanonicalReason::CanonicalSource);
+        assert_eq!(cluster.duplicates.len(), 1);
+        assert_eq!(
+            cluster.duplicates[0].path.as_deref(),
+            Some("repo/README.md")
+        );
+        assert_eq!(cluster.duplicates[0].content_similarity, 1.0);
+
+        assert!(service.report("missing", 0.9, 0.8).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_earliest_indexed_copy_is_canonical_without_sources() {
+        let container = container(Vec::new()).await;
+        container
+            .vector_repository()
+            .insert(vec![
+                document("b/install.md", INSTALL, [1.0, 0.0], 1),
+                document("a/install.md", INSTALL, [1.0, 0.0], 5),
+                document("c/install.md", INSTALL, [1.0, 0.0], 2),
+            ])
+            .await
+            .unwrap();
+
+        let report = DuplicateReportService::new(&container)
+            .report("docs", 0.9, 0.8)
+            .await
+            .unwrap();
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.redundant_documents, 2);
+        let cluster = &report.clusters[0];
+        assert_eq!(cluster.canonical.path.as_deref(), Some("a/install.md"));
+        assert_eq!(cluster.reason, CanonicalReason::EarliestIndexed);
+    }
+}
@@ -0,0 +1,438 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+/// Embedding dimension migration
+///
+/// When the embedding model changes dimension, the vectors already stored
+/// for a collection no longer match new query embeddings. A migration
+/// re-embeds every chunk of the collection into a shadow collection, checks a
+/// sample of the new vectors by searching for each of them, and only then
+/// points the collection's alias at the shadow and removes the old vectors.
+/// Searches keep using the old vectors until the swap.
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+use zero_latency_vector::{EmbeddingGenerator, VectorDocument, VectorRepository};
+
+use crate::application::container::ServiceContainer;
+use crate::application::services::indexing_pipeline::{PipelineProgress, PipelineStage};
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+use crate::infrastructure::persistence::fingerprint_registry::FingerprintRegistry;
+
+/// Chunks sampled to validate a migration unless the request says otherwise
+pub const DEFAULT_VALIDATION_SAMPLE: usize = 20;
+
+/// Upper bound on the validation sample a request may ask for
+pub const MAX_VALIDATION_SAMPLE: usize = 500;
+
+/// Fraction of sampled chunks that must find themselves for the swap to go ahead
+const MIN_VALIDATION_RECALL: f64 = 0.9;
+
+/// A sampled chunk passes when it is among this many nearest neighbours
+const VALIDATION_TOP_K: usize = 5;
+
+/// Chunks embedded per call to the embedding generator
+const MIGRATION_BATCH_SIZE: usize = 32;
+
+/// Outcome of the sampled similarity checks run before the swap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationValidation {
+    /// Chunks searched for in the shadow collection
+    pub sampled: usize,
+    /// Sampled chunks found among their own nearest neighbours
+    pub matched: usize,
+    /// `matched / sampled`
+    pub recall: f64,
+}
+
+/// Outcome of a completed embedding migration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingMigrationReport {
+    pub collection: String,
+    /// Stored collection the vectors were read from
+    pub source_collection: String,
+    /// Stored collection the alias now points at
+    pub target_collection: String,
+    pub from_dimension: usize,
+    pub to_dimension: usize,
+    pub model: String,
+    pub vectors_migrated: usize,
+    pub validation: MigrationValidation,
+    pub duration_ms: f64,
+}
+
+/// A collection's vectors, loaded and checked before a migration starts
+pub struct MigrationPlan {
+    pub collection: String,
+    pub source_collection: String,
+    pub from_dimension: usize,
+    pub to_dimension: usize,
+    vectors: Vec<VectorDocument>,
+}
+
+impl MigrationPlan {
+    /// Number of chunks that will be re-embedded
+    pub fn chunk_count(&self) -> usize {
+        self.vectors.len()
+    }
+}
+
+/// Application service that moves collections onto the current embedding model
+#[derive(Clone)]
+pub struct EmbeddingMigrationService {
+    vector_repository: Arc<dyn VectorRepository>,
+    embedding_generator: Arc<dyn EmbeddingGenerator>,
+    fingerprint_registry: Arc<FingerprintRegistry>,
+    collection_aliases: Arc<CollectionAliases>,
+}
+
+impl EmbeddingMigrationService {
+    /// Create a migration service sharing the container's stores
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            vector_repository: container.vector_repository(),
+            embedding_generator: container.embedding_generator(),
+            fingerprint_registry: container.fingerprint_registry(),
+            collection_aliases: container.collection_aliases(),
+        }
+    }
+
+    /// Load a collection's vectors and check that they need migrating
+    ///
+    /// Fails if the collection holds no vectors, or if every vector already
+    /// has the current model's dimension and `force` is not set.
+    pub async fn plan(&self, collection: &str, force: bool) -> Result<MigrationPlan> {
+        let source_collection = self.collection_aliases.resolve(collection);
+        let vectors = self
+            .vector_repository
+            .collection_vectors(&source_collection)
+            .await?;
+        let Some(first) = vectors.first() else {
+            return Err(ZeroLatencyError::not_found(format!(
+                "vectors of collection {}",
+                collection
+            )));
+        };
+
+        let from_dimension = first.embedding.len();
+        let to_dimension = self.embedding_generator.dimension();
+        let up_to_date = vectors.iter().all(|v| v.embedding.len() == to_dimension);
+        if up_to_date && !force {
+            return Err(ZeroLatencyError::validation(
+                "force",
+                format!(
+                    "Collection '{}' already uses {}-dimension embeddings; set force to re-embed it anyway",
+                    collection, to_dimension
+                ),
+            ));
+        }
+
+        Ok(MigrationPlan {
+            collection: collection.to_string(),
+            source_collection,
+            from_dimension,
+            to_dimension,
+            vectors,
+        })
+    }
+
+    /// Re-embed a planned collection, validate the result and swap the alias
+    ///
+    /// Progress is published to `progress`: the `read` stage counts the
+    /// chunks to migrate, `embed` and `upsert` the chunks done so far. On
+    /// failure the shadow collection is removed and the alias left alone.
+    pub async fn migrate(
+        &self,
+        plan: MigrationPlan,
+        sample_size: usize,
+        progress: Arc<PipelineProgress>,
+    ) -> Result<EmbeddingMigrationReport> {
+        let started = Instant::now();
+        let target_collection = format!(
+            "{}__dim{}_{}",
+            plan.collection,
+            plan.to_dimension,
+            &Uuid::new_v4().simple().to_string()[..8]
+        );
+        progress.record(
+            PipelineStage::Read,
+            plan.chunk_count() as u64,
+            Duration::ZERO,
+        );
+
+        let mut replacements = HashMap::with_capacity(plan.chunk_count());
+        let copied = self
+            .copy_and_validate(
+                &plan,
+                &target_collection,
+                sample_size,
+                &progress,
+                &mut replacements,
+            )
+            .await;
+        let validation = match copied {
+            Ok(validation) => validation,
+            Err(e) => {
+                self.remove_vectors(replacements.values()).await;
+                return Err(e);
+            }
+        };
+
+        // Swap the alias first, and persist it, so that the old vectors are
+        // only removed once nothing can resolve to them any more
+        self.collection_aliases
+            .set(&plan.collection, &target_collection);
+        if let Err(e) = self.collection_aliases.save() {
+            self.collection_aliases
+                .set(&plan.collection, &plan.source_collection);
+            self.remove_vectors(replacements.values()).await;
+            return Err(e);
+        }
+
+        self.fingerprint_registry
+            .remap_chunk_ids(&plan.collection, &replacements);
+        if let Err(e) = self.fingerprint_registry.save() {
+            tracing::warn!("Failed to persist fingerprint registry: {}", e);
+        }
+        self.remove_vectors(replacements.keys()).await;
+
+        let report = EmbeddingMigrationReport {
+            collection: plan.collection,
+            source_collection: plan.source_collection,
+            target_collection,
+            from_dimension: plan.from_dimension,
+            to_dimension: plan.to_dimension,
+            model: self.embedding_generator.model_name().to_string(),
+            vectors_migrated: replacements.len(),
+            validation,
+            duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        };
+        tracing::info!(
+            "Migrated collection '{}' from {} to {} dimensions: {} vectors in {:.0}ms, now stored as '{}'",
+            report.collection,
+            report.from_dimension,
+            report.to_dimension,
+            report.vectors_migrated,
+            report.duration_ms,
+            report.target_collection
+        );
+        Ok(report)
+    }
+
+    /// Embed every chunk into the shadow collection, then check a sample
+    ///
+    /// `replacements` maps each old chunk id to its copy as copies are stored,
+    /// so the caller can clean up after a failure.
+    async fn copy_and_validate(
+        &self,
+        plan: &MigrationPlan,
+        target_collection: &str,
+        sample_size: usize,
+        progress: &PipelineProgress,
+        replacements: &mut HashMap<String, String>,
+    ) -> Result<MigrationValidation> {
+        // Spread the sample evenly over the collection
+        let sample_size = sample_size.clamp(1, plan.chunk_count());
+        let stride = plan.chunk_count() / sample_size;
+        let mut samples = Vec::with_capacity(sample_size);
+
+        for (batch_index, batch) in plan.vectors.chunks(MIGRATION_BATCH_SIZE).enumerate() {
+            let embed_started = Instant::now();
+            let texts = batch
+                .iter()
+                .map(|vector| vector.metadata.content.as_str())
+                .collect();
+            let embeddings = self
+                .embedding_generator
+                .generate_batch_embeddings(texts)
+                .await?;
+            progress.record(
+                PipelineStage::Embed,
+                batch.len() as u64,
+                embed_started.elapsed(),
+            );
+
+            let mut copies = Vec::with_capacity(batch.len());
+            for (offset, (vector, embedding)) in batch.iter().zip(embeddings).enumerate() {
+                if embedding.len() != plan.to_dimension {
+                    return Err(ZeroLatencyError::internal(format!(
+                        "Embedding model produced {} dimensions, expected {}",
+                        embedding.len(),
+                        plan.to_dimension
+                    )));
+                }
+
+                let mut metadata = vector.metadata.clone();
+                metadata.collection = Some(target_collection.to_string());
+                metadata
+                    .custom
+                    .insert("collection".to_string(), target_collection.to_string());
+                metadata.embedding_model = Some(self.embedding_generator.model_name().to_string());
+                metadata.embedded_at = Some(chrono::Utc::now());
+                let copy = VectorDocument {
+                    id: Uuid::new_v4(),
+                    embedding,
+                    named_vectors: Default::default(),
+                    metadata,
+                };
+
+                let index = batch_index * MIGRATION_BATCH_SIZE + offset;
+                if index.is_multiple_of(stride) && samples.len() < sample_size {
+                    samples.push((copy.id, copy.embedding.clone()));
+                }
+                copies.push(copy);
+            }
+
+            let upsert_started = Instant::now();
+            let ids: Vec<(String, String)> = batch
+                .iter()
+                .zip(&copies)
+                .map(|(vector, copy)| (vector.id.to_string(), copy.id.to_string()))
+                .collect();
+            self.vector_repository.upsert_batch(copies).await?;
+            replacements.extend(ids);
+            progress.record(
+                PipelineStage::Upsert,
+                batch.len() as u64,
+                upsert_started.elapsed(),
+            );
+        }
+
+        let mut matched = 0;
+        for (id, embedding) in &samples {
+            let neighbours = self
+                .vector_repository
+                .search_in_collection(target_collection, embedding.clone(), VALIDATION_TOP_K)
+                .await?;
+            if neighbours.iter().any(|result| result.document_id == *id) {
+                matched += 1;
+            }
+        }
+
+        let validation = MigrationValidation {
+            sampled: samples.len(),
+            matched,
+            recall: matched as f64 / samples.len() as f64,
+        };
+        if validation.recall < MIN_VALIDATION_RECALL {
+            return Err(ZeroLatencyError::internal(format!(
+                "Migration validation failed: {} of {} sampled chunks were not among their own nearest neighbours",
+                validation.sampled - validation.matched,
+                validation.sampled
+            )));
+        }
+        Ok(validation)
+    }
+
+    /// Delete vectors by id, logging failures
+    async fn remove_vectors<'a>(&self, ids: impl Iterator<Item = &'a String>) {
+        let ids: Vec<String> = ids.cloned().collect();
+        if let Err(e) = self.vector_repository.delete_batch(&ids).await {
+            tracing::warn!("Failed to remove {} vectors: {}", ids.len(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, VectorBackend};
+    use crate::infrastructure::persistence::embeddings::mock_adapter::MockEmbeddingConfig;
+    use crate::infrastructure::persistence::fingerprint_registry::FileFingerprint;
+    use crate::infrastructure::MockEmbeddingAdapter;
+    use std::path::Path;
+    use zero_latency_vector::VectorMetadata;
+
+    /// Container on the in-memory backend with a mock model of `dimension`
+    async fn container(dimension: usize) -> ServiceContainer {
+        let mut config = Config::default();
+        config.vector.backend = VectorBackend::Memory;
+        ServiceContainer::with_embedding_generator(
+            config,
+            Arc::new(
+                MockEmbeddingAdapter::new(MockEmbeddingConfig { dimension, seed: 7 }).unwrap(),
+            ),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn chunk(content: &str, dimension: usize) -> VectorDocument {
+        VectorDocument {
+            id: Uuid::new_v4(),
+            embedding: vec![0.5; dimension],
+            named_vectors: Default::default(),
+            metadata: VectorMetadata {
+                document_id: Uuid::new_v4(),
+                content: content.to_string(),
+                collection: Some("docs".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migration_swaps_alias_after_validation() {
+        let container = container(16).await;
+        let repository = container.vector_repository();
+        let old: Vec<VectorDocument> = (0..10)
+            .map(|i| chunk(&format!("chunk number {}", i), 8))
+            .collect();
+        let old_id = old[0].id.to_string();
+        repository.insert(old).await.unwrap();
+        container.fingerprint_registry().record(
+            "docs",
+            Path::new("/docs/a.md"),
+            FileFingerprint::new(1, 1, b"a"),
+            vec![old_id.clone()],
+        );
+
+        let service = EmbeddingMigrationService::new(&container);
+        let plan = service.plan("docs", false).await.unwrap();
+        assert_eq!((plan.from_dimension, plan.to_dimension), (8, 16));
+
+        let progress = Arc::new(PipelineProgress::new());
+        let report = service.migrate(plan, 5, progress.clone()).await.unwrap();
+        assert_eq!(report.vectors_migrated, 10);
+        assert_eq!(report.validation.sampled, 5);
+        assert_eq!(report.validation.matched, 5);
+        assert_eq!(
+            progress.snapshot()[PipelineStage::Upsert as usize].items,
+            10
+        );
+
+        // Reads go to the shadow collection and the old vectors are gone
+        let aliases = container.collection_aliases();
+        assert_eq!(aliases.resolve("docs"), report.target_collection);
+        let migrated = repository
+            .collection_vectors(&report.target_collection)
+            .await
+            .unwrap();
+        assert!(migrated.iter().all(|v| v.embedding.len() == 16));
+        assert!(repository
+            .collection_vectors("docs")
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Fingerprints point at the copies
+        let entry = container
+            .fingerprint_registry()
+            .get("docs", Path::new("/docs/a.md"))
+            .unwrap();
+        assert_ne!(entry.chunk_ids, vec![old_id]);
+        assert!(migrated
+            .iter()
+            .any(|v| v.id.to_string() == entry.chunk_ids[0]));
+
+        // The collection now matches the model
+        assert!(service.plan("docs", false).await.is_err());
+        assert!(service.plan("docs", true).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_plan_rejects_empty_collection() {
+        let container = container(16).await;
+        let service = EmbeddingMigrationService::new(&container);
+        assert!(service.plan("missing", true).await.is_err());
+    }
+}
@@ -4,8 +4,11 @@
 /// for controlling which files and directories should be indexed.
 use std::path::Path;
 
+/// Default maximum size of a single file accepted for indexing (10MB)
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
 /// File filtering patterns and configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct IndexingFilters {
     /// Safe list patterns - only files/dirs matching these patterns will be indexed
     /// If empty, all files are allowed (subject to ignore list)
@@ -19,6 +22,9 @@ pub struct IndexingFilters {
 
     /// Whether to follow symbolic links
     pub follow_symlinks: bool,
+
+    /// Maximum file size in bytes - larger files are skipped without being read
+    pub max_file_size: u64,
 }
 
 impl IndexingFilters {
@@ -29,6 +35,7 @@ impl IndexingFilters {
             ignore_list: Self::default_ignore_patterns(),
             case_sensitive: false,
             follow_symlinks: false,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
         }
     }
 
@@ -39,6 +46,7 @@ impl IndexingFilters {
             ignore_list,
             case_sensitive: false,
             follow_symlinks: false,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
         }
     }
 
@@ -144,6 +152,11 @@ impl IndexingFilters {
     pub fn set_follow_symlinks(&mut self, follow_symlinks: bool) {
         self.follow_symlinks = follow_symlinks;
     }
+
+    /// Set the maximum file size in bytes
+    pub fn set_max_file_size(&mut self, max_file_size: u64) {
+        self.max_file_size = max_file_size;
+    }
 }
 
 impl Default for IndexingFilters {
@@ -231,6 +244,11 @@ impl FilterService {
         true
     }
 
+    /// Check if a file of the given size is within the configured size limit
+    pub fn within_size_limit(&self, size: u64) -> bool {
+        size <= self.filters.max_file_size
+    }
+
     /// Check if a path matches any pattern in the given list
     fn matches_patterns(&self, path_str: &str, file_name: &str, patterns: &[String]) -> bool {
         for pattern in patterns {
@@ -314,63 +332,47 @@ impl FilterService {
 /// Simple glob pattern matching
 ///
 /// Supports:
-/// - * matches any sequence of characters (except path separators)
-/// - ? matches any single character (except path separators)
+/// - * matches any sequence of characters, including path separators
+/// - ? matches any single character
 /// - Exact string matching
-fn glob_match(text: &str, pattern: &str) -> bool {
+///
+/// Matching works on characters rather than bytes and backtracks only to the
+/// most recent `*`, so it runs in O(text * pattern) time for any input.
+pub fn glob_match(text: &str, pattern: &str) -> bool {
     if pattern == "*" {
         return true;
     }
 
-    // Use a simple recursive approach instead of iterators
-    match_recursive(text, pattern)
-}
+    let text: Vec<char> = text.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
 
-/// Recursive helper for glob matching
-fn match_recursive(text: &str, pattern: &str) -> bool {
-    if pattern.is_empty() {
-        return text.is_empty();
-    }
-
-    if text.is_empty() {
-        return pattern.chars().all(|c| c == '*');
-    }
+    let (mut t, mut p) = (0, 0);
+    // Pattern position after the last `*` seen, and the text position it resumes from
+    let mut backtrack: Option<(usize, usize)> = None;
 
-    let mut pattern_chars = pattern.chars();
-    if let Some(pattern_char) = pattern_chars.next() {
-        let remaining_pattern = pattern_chars.as_str();
-
-        match pattern_char {
-            '*' => {
-                // Try matching with different amounts of text consumed
-                for i in 0..=text.len() {
-                    if match_recursive(&text[i..], remaining_pattern) {
-                        return true;
-                    }
-                }
-                false
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, t));
             }
-            '?' => {
-                if text.len() == 0 {
-                    false
-                } else {
-                    let mut text_chars = text.chars();
-                    text_chars.next(); // consume one character
-                    match_recursive(text_chars.as_str(), remaining_pattern)
-                }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
             }
-            _ => {
-                let mut text_chars = text.chars();
-                if text_chars.next() == Some(pattern_char) {
-                    match_recursive(text_chars.as_str(), remaining_pattern)
-                } else {
-                    false
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    // Let the last `*` absorb one more character and retry
+                    p = star_p;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
                 }
-            }
+                None => return false,
+            },
         }
-    } else {
-        text.is_empty()
     }
+
+    pattern[p..].iter().all(|&c| c == '*')
 }
 
 #[cfg(test)]
@@ -387,6 +389,82 @@ mod tests {
         assert!(!glob_match("hello.txt", "goodbye.*"));
     }
 
+    #[test]
+    fn test_glob_match_unicode() {
+        assert!(glob_match("Überblick.md", "?berblick.*"));
+        assert!(glob_match("日本語のドキュメント.md", "*ドキュ*.md"));
+        assert!(!glob_match("日本語.md", "??.md"));
+    }
+
+    #[test]
+    fn test_glob_match_many_stars() {
+        let text = "a".repeat(200);
+        let pattern = format!("{}b", "*a".repeat(30));
+        assert!(!glob_match(&text, &pattern));
+    }
+
+    /// Reference matcher used to check `glob_match`; exponential but obviously correct
+    fn naive_glob_match(text: &[char], pattern: &[char]) -> bool {
+        match pattern.split_first() {
+            None => text.is_empty(),
+            Some(('*', rest)) => (0..=text.len()).any(|i| naive_glob_match(&text[i..], rest)),
+            Some((&c, rest)) => match text.split_first() {
+                Some((&t, text_rest)) if c == '?' || c == t => naive_glob_match(text_rest, rest),
+                _ => false,
+            },
+        }
+    }
+
+    const SEED_CASES: [(&[u8], bool); 4] = [
+        (
+            include_bytes!("../../../fuzz/corpus/glob_pattern/nested_markdown"),
+            true,
+        ),
+        (
+            include_bytes!("../../../fuzz/corpus/glob_pattern/log_suffix"),
+            true,
+        ),
+        (
+            include_bytes!("../../../fuzz/corpus/glob_pattern/unicode_path"),
+            true,
+        ),
+        (
+            include_bytes!("../../../fuzz/corpus/glob_pattern/star_backtracking"),
+            false,
+        ),
+    ];
+
+    #[test]
+    fn test_glob_match_seed_cases() {
+        for (seed, expected) in SEED_CASES {
+            let seed = std::str::from_utf8(seed).unwrap();
+            let (text, pattern) = seed.split_once('\0').unwrap();
+            assert_eq!(
+                glob_match(text, pattern),
+                expected,
+                "{} against {}",
+                text,
+                pattern
+            );
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn glob_match_never_panics(text in "\\PC*", pattern in "\\PC*") {
+            let _ = glob_match(&text, &pattern);
+        }
+
+        #[test]
+        fn glob_match_agrees_with_reference(text in "[ab/.é]{0,12}", pattern in "[ab/.é*?]{0,8}") {
+            let expected = naive_glob_match(
+                &text.chars().collect::<Vec<_>>(),
+                &pattern.chars().collect::<Vec<_>>(),
+            );
+            proptest::prop_assert_eq!(glob_match(&text, &pattern), expected);
+        }
+    }
+
     #[test]
     fn test_default_filters() {
         let filters = IndexingFilters::new();
@@ -438,4 +516,16 @@ mod tests {
         assert!(!service.should_index(Path::new("test_main.rs")));
         assert!(!service.should_index(Path::new("main_test.rs")));
     }
+
+    #[test]
+    fn test_max_file_size() {
+        let mut filters = IndexingFilters::new();
+        filters.set_max_file_size(1024);
+
+        let service = FilterService::new(filters);
+
+        assert!(service.within_size_limit(0));
+        assert!(service.within_size_limit(1024));
+        assert!(!service.within_size_limit(1025));
+    }
 }
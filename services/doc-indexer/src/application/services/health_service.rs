@@ -1,7 +1,11 @@
+use crate::application::services::webhook_service::WebhookService;
 use crate::infrastructure::api::jsonrpc::types::{
     HealthCheckItem, HealthCheckResult, LivenessResult, ReadinessResult,
 };
+use crate::infrastructure::operations::production::startup::StartupReport;
+use crate::infrastructure::persistence::webhook_registry::WebhookEvent;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use zero_latency_core::{
     models::{ComponentHealth, HealthStatus},
@@ -11,15 +15,34 @@ use zero_latency_core::{
 #[derive(Clone)]
 pub struct HealthService {
     start_time: Instant,
+    // Overall status of the last check, to notice when health degrades
+    last_status: Arc<Mutex<String>>,
+    webhooks: Option<WebhookService>,
+    // Latest startup validation, which readiness waits for
+    startup: StartupReport,
 }
 
 impl HealthService {
     pub fn new() -> Self {
         Self {
             start_time: Instant::now(),
+            last_status: Arc::new(Mutex::new("healthy".to_string())),
+            webhooks: None,
+            startup: StartupReport::default(),
         }
     }
 
+    /// Startup validation report readiness depends on
+    pub fn startup_report(&self) -> StartupReport {
+        self.startup.clone()
+    }
+
+    /// Notify webhooks when a health check finds the service no longer healthy
+    pub fn with_webhooks(mut self, webhooks: WebhookService) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
     pub async fn health_check(&self) -> Result<HealthCheckResult> {
         let mut checks = HashMap::new();
 
@@ -51,16 +74,59 @@ impl HealthService {
             "degraded".to_string()
         };
 
-        Ok(HealthCheckResult {
+        let result = HealthCheckResult {
             status: overall_status,
             timestamp: chrono::Utc::now().to_rfc3339(),
             checks,
-        })
+        };
+        self.observe(&result);
+        Ok(result)
     }
 
+    /// Record the overall status and report a change away from healthy
+    fn observe(&self, result: &HealthCheckResult) {
+        let previous = {
+            let mut last_status = self.last_status.lock().unwrap_or_else(|e| e.into_inner());
+            std::mem::replace(&mut *last_status, result.status.clone())
+        };
+        if previous != "healthy" || result.status == "healthy" {
+            return;
+        }
+        tracing::warn!(
+            "Service health changed from {} to {}",
+            previous,
+            result.status
+        );
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.emit(
+                WebhookEvent::HealthDegraded,
+                serde_json::json!({
+                    "status": result.status,
+                    "previous_status": previous,
+                    "checks": result.checks,
+                }),
+            );
+        }
+    }
+
+    /// Whether the service can take traffic: it is healthy or degraded and
+    /// startup validation, if enabled, has passed
     pub async fn readiness_check(&self) -> Result<ReadinessResult> {
-        let health = self.health_check().await?;
-        let is_ready = health.status == "healthy" || health.status == "degraded";
+        let mut health = self.health_check().await?;
+        let startup = self.startup.status();
+        let started = self.startup.ready();
+        let is_ready = started && (health.status == "healthy" || health.status == "degraded");
+
+        health.checks.insert(
+            "startup".to_string(),
+            HealthCheckItem {
+                status: if started { "healthy" } else { "unhealthy" }.to_string(),
+                message: (!started).then(|| match startup.critical_failures.first() {
+                    Some(failure) => format!("Startup validation failed: {}", failure),
+                    None => format!("Startup validation is {}", startup.status),
+                }),
+            },
+        );
 
         Ok(ReadinessResult {
             ready: is_ready,
@@ -0,0 +1,771 @@
+use crate::application::services::document_service::{
+    ChunkedDocument, DocumentIndexingService, EmbeddedDocument, PreparedDocument,
+};
+use crate::application::services::filter_service::FilterService;
+use crate::application::services::indexing_report::{IndexingReport, IndexingStage};
+use crate::application::services::throttle::Throttle;
+use crate::config::PipelineConfig;
+use crate::infrastructure::operations::lanes::ExecutionLanes;
+use crate::infrastructure::operations::usage::estimate_tokens;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+/// Staged, bounded-concurrency indexing pipeline
+///
+/// Indexing is split into five stages connected by bounded channels:
+/// walk -> read -> chunk -> embed -> upsert. When a file that was indexed
+/// before changes, the embed stage only embeds the chunks that differ from
+/// the ones stored for it and the upsert stage removes the chunks it no
+/// longer has. Each stage runs a configurable
+/// number of concurrent workers, so large corpora keep both the CPU and the
+/// embedding backend busy while the channel capacity caps how much work is
+/// buffered in memory at any time. In low-priority runs the read, chunk and
+/// embed workers are additionally slowed down by a [`Throttle`]. The read,
+/// chunk, embed and upsert work of every pipeline also runs in the service's
+/// background lane, so indexing jobs together never take more than the
+/// background budget; a worker gives its slot back before it waits on a
+/// channel.
+///
+/// Every few seconds the upsert stage saves the fingerprints and checkpoints
+/// the files stored so far, so an interrupted job can be resumed without
+/// redoing them.
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Semaphore};
+use tokio_stream::wrappers::ReceiverStream;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+/// How often stored files are checkpointed
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Stage of the indexing pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PipelineStage {
+    /// Discovering files to index
+    Walk,
+    /// Reading, decoding and processing file content
+    Read,
+    /// Splitting documents into chunks
+    Chunk,
+    /// Generating embeddings for chunks
+    Embed,
+    /// Storing vectors in the vector repository
+    Upsert,
+}
+
+impl PipelineStage {
+    /// All stages in pipeline order
+    pub const ALL: [PipelineStage; 5] = [
+        PipelineStage::Walk,
+        PipelineStage::Read,
+        PipelineStage::Chunk,
+        PipelineStage::Embed,
+        PipelineStage::Upsert,
+    ];
+}
+
+/// Throughput of a single pipeline stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageThroughput {
+    pub stage: PipelineStage,
+    /// Items handled by the stage: files for walk and read, documents for
+    /// chunk and upsert, chunks for embed
+    pub items: u64,
+    /// Time spent working on items, summed across the stage's workers
+    pub busy_ms: f64,
+    /// Items per second of wall-clock time since the pipeline started
+    pub items_per_second: f64,
+}
+
+#[derive(Default)]
+struct StageCounter {
+    items: AtomicU64,
+    busy_micros: AtomicU64,
+}
+
+/// Live per-stage counters of a running pipeline
+///
+/// Shared with the job service so that progress can be inspected while a
+/// job is still running.
+pub struct PipelineProgress {
+    started_at: Instant,
+    stages: [StageCounter; 5],
+    // Files stored with their fingerprints saved, skipped when resuming
+    checkpointed: Mutex<HashSet<PathBuf>>,
+}
+
+impl PipelineProgress {
+    /// Create progress counters starting now
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            stages: Default::default(),
+            checkpointed: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Create progress counters for a job resuming after the files it had
+    /// checkpointed
+    pub fn resume_from(files: impl IntoIterator<Item = PathBuf>) -> Self {
+        let progress = Self::new();
+        progress.checkpoint(files);
+        progress
+    }
+
+    /// Record files as stored and safe to skip on resume
+    pub fn checkpoint(&self, files: impl IntoIterator<Item = PathBuf>) {
+        lock(&self.checkpointed).extend(files);
+    }
+
+    /// Whether a file was stored before the last checkpoint
+    pub fn is_checkpointed(&self, path: &Path) -> bool {
+        lock(&self.checkpointed).contains(path)
+    }
+
+    /// Files checkpointed so far, in path order
+    pub fn checkpointed_files(&self) -> Vec<String> {
+        let mut files: Vec<String> = lock(&self.checkpointed)
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Record items completed by a stage and the time spent on them
+    pub fn record(&self, stage: PipelineStage, items: u64, busy: Duration) {
+        let counter = &self.stages[stage as usize];
+        counter.items.fetch_add(items, Ordering::Relaxed);
+        counter
+            .busy_micros
+            .fetch_add(busy.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Snapshot the throughput of every stage
+    pub fn snapshot(&self) -> Vec<StageThroughput> {
+        let elapsed = self.started_at.elapsed().as_secs_f64();
+
+        PipelineStage::ALL
+            .iter()
+            .map(|&stage| {
+                let counter = &self.stages[stage as usize];
+                let items = counter.items.load(Ordering::Relaxed);
+                StageThroughput {
+                    stage,
+                    items,
+                    busy_ms: counter.busy_micros.load(Ordering::Relaxed) as f64 / 1000.0,
+                    items_per_second: if elapsed > 0.0 {
+                        items as f64 / elapsed
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for PipelineProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pipeline that indexes a file or directory tree into a collection
+pub struct IndexingPipeline {
+    service: DocumentIndexingService,
+    config: PipelineConfig,
+    progress: Arc<PipelineProgress>,
+    throttle: Arc<Throttle>,
+    lanes: Arc<ExecutionLanes>,
+    dry_run: bool,
+}
+
+impl IndexingPipeline {
+    /// Create a pipeline driving the given indexing service
+    pub fn new(
+        service: DocumentIndexingService,
+        config: PipelineConfig,
+        progress: Arc<PipelineProgress>,
+        throttle: Throttle,
+        lanes: Arc<ExecutionLanes>,
+    ) -> Self {
+        Self {
+            service,
+            config,
+            progress,
+            throttle: Arc::new(throttle),
+            lanes,
+            dry_run: false,
+        }
+    }
+
+    /// Stop after counting the chunks that need embedding
+    ///
+    /// Nothing is embedded or stored; the report counts the documents and
+    /// chunks that would be embedded and their estimated tokens.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Run the pipeline to completion
+    ///
+    /// Per-file failures are recorded in the returned report; only a failure
+    /// of the pipeline itself is returned as an error.
+    pub async fn run(
+        &self,
+        root: &Path,
+        recursive: bool,
+        collection_name: &str,
+        force: bool,
+    ) -> Result<IndexingReport> {
+        let report = Arc::new(Mutex::new(IndexingReport::new()));
+        let collection: Arc<str> = Arc::from(collection_name);
+        let capacity = self.config.channel_capacity.max(1);
+
+        let (path_tx, path_rx) = mpsc::channel::<PathBuf>(capacity);
+        let (prepared_tx, prepared_rx) = mpsc::channel::<PreparedDocument>(capacity);
+        let (chunked_tx, chunked_rx) = mpsc::channel::<ChunkedDocument>(capacity);
+        let (embedded_tx, embedded_rx) = mpsc::channel::<EmbeddedDocument>(capacity);
+
+        let stages = [
+            tokio::spawn(Self::walk_stage(
+                self.service.filter_service(),
+                root.to_path_buf(),
+                recursive,
+                self.config.walk_concurrency.max(1),
+                path_tx,
+                report.clone(),
+                self.progress.clone(),
+            )),
+            tokio::spawn(Self::read_stage(
+                self.service.clone(),
+                collection.clone(),
+                force,
+                self.config.read_concurrency.max(1),
+                path_rx,
+                prepared_tx,
+                report.clone(),
+                self.progress.clone(),
+                self.throttle.clone(),
+                self.lanes.clone(),
+            )),
+            tokio::spawn(Self::chunk_stage(
+                self.service.clone(),
+                self.config.chunk_concurrency.max(1),
+                prepared_rx,
+                chunked_tx,
+                report.clone(),
+                self.progress.clone(),
+                self.throttle.clone(),
+                self.lanes.clone(),
+            )),
+            tokio::spawn(Self::embed_stage(
+                self.service.clone(),
+                collection.clone(),
+                force,
+                self.dry_run,
+                self.config.embed_concurrency.max(1),
+                chunked_rx,
+                embedded_tx,
+                report.clone(),
+                self.progress.clone(),
+                self.throttle.clone(),
+                self.lanes.clone(),
+            )),
+            tokio::spawn(Self::upsert_stage(
+                self.service.clone(),
+                collection,
+                self.config.upsert_concurrency.max(1),
+                embedded_rx,
+                report.clone(),
+                self.progress.clone(),
+                self.lanes.clone(),
+            )),
+        ];
+
+        for stage in stages {
+            stage.await.map_err(|e| {
+                ZeroLatencyError::internal(format!("Indexing pipeline stage failed: {}", e))
+            })?;
+        }
+
+        let mut report = std::mem::take(&mut *lock(&report));
+        report.stages = self.progress.snapshot();
+
+        for stage in &report.stages {
+            tracing::info!(
+                stage = ?stage.stage,
+                items = stage.items,
+                busy_ms = stage.busy_ms,
+                items_per_second = stage.items_per_second,
+                "Pipeline stage completed"
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// Discover files, walking top-level subdirectories in parallel
+    async fn walk_stage(
+        filters: Arc<FilterService>,
+        root: PathBuf,
+        recursive: bool,
+        concurrency: usize,
+        tx: mpsc::Sender<PathBuf>,
+        report: Arc<Mutex<IndexingReport>>,
+        progress: Arc<PipelineProgress>,
+    ) {
+        if root.is_file() {
+            if filters.should_index(&root) {
+                progress.record(PipelineStage::Walk, 1, Duration::ZERO);
+                let _ = tx.send(root).await;
+            }
+            return;
+        }
+
+        tracing::info!("Starting directory indexing: {}", root.display());
+
+        let entries = match std::fs::read_dir(&root) {
+            Ok(entries) => entries,
+            Err(e) => {
+                lock(&report).error(
+                    &root,
+                    IndexingStage::Read,
+                    format!("Could not read directory: {}", e),
+                    Some("Check directory permissions"),
+                );
+                return;
+            }
+        };
+
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+        let mut walkers = Vec::new();
+
+        for entry in entries.flatten() {
+            let started = Instant::now();
+            let path = entry.path();
+
+            if path.is_file() {
+                if filters.should_index(&path) {
+                    progress.record(PipelineStage::Walk, 1, started.elapsed());
+                    if tx.send(path).await.is_err() {
+                        break;
+                    }
+                }
+            } else if path.is_dir() && recursive && filters.should_traverse(&path) {
+                let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                    break;
+                };
+                let filters = filters.clone();
+                let tx = tx.clone();
+                let report = report.clone();
+                let progress = progress.clone();
+
+                walkers.push(tokio::task::spawn_blocking(move || {
+                    let _permit = permit;
+                    Self::walk_subtree(&path, &filters, &tx, &report, &progress);
+                }));
+            }
+        }
+
+        for walker in walkers {
+            if let Err(e) = walker.await {
+                tracing::error!("Directory walker failed: {}", e);
+            }
+        }
+    }
+
+    /// Walk a directory subtree on a blocking thread
+    fn walk_subtree(
+        dir: &Path,
+        filters: &FilterService,
+        tx: &mpsc::Sender<PathBuf>,
+        report: &Mutex<IndexingReport>,
+        progress: &PipelineProgress,
+    ) {
+        let walker = walkdir::WalkDir::new(dir)
+            .follow_links(filters.filters().follow_symlinks)
+            .into_iter()
+            .filter_entry(|entry| {
+                !entry.file_type().is_dir() || filters.should_traverse(entry.path())
+            });
+
+        let mut started = Instant::now();
+        for entry in walker {
+            match entry {
+                Ok(entry) => {
+                    let path = entry.path();
+                    if path.is_file() && filters.should_index(path) {
+                        progress.record(PipelineStage::Walk, 1, started.elapsed());
+                        if tx.blocking_send(entry.into_path()).is_err() {
+                            return;
+                        }
+                        started = Instant::now();
+                    }
+                }
+                Err(e) => {
+                    let path = e.path().unwrap_or(dir).to_path_buf();
+                    tracing::warn!("Could not walk {}: {}", path.display(), e);
+                    lock(report).error(
+                        &path,
+                        IndexingStage::Read,
+                        format!("Could not read directory entry: {}", e),
+                        Some("Check directory permissions"),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Read, decode and process files into documents
+    #[allow(clippy::too_many_arguments)]
+    async fn read_stage(
+        service: DocumentIndexingService,
+        collection: Arc<str>,
+        force: bool,
+        concurrency: usize,
+        rx: mpsc::Receiver<PathBuf>,
+        tx: mpsc::Sender<PreparedDocument>,
+        report: Arc<Mutex<IndexingReport>>,
+        progress: Arc<PipelineProgress>,
+        throttle: Arc<Throttle>,
+        lanes: Arc<ExecutionLanes>,
+    ) {
+        ReceiverStream::new(rx)
+            .for_each_concurrent(concurrency, |path| {
+                let service = service.clone();
+                let collection = collection.clone();
+                let (tx, report, progress, throttle, lanes) =
+                    (&tx, &report, &progress, &throttle, &lanes);
+
+                async move {
+                    if progress.is_checkpointed(&path) {
+                        lock(report).files_skipped += 1;
+                        return;
+                    }
+
+                    let started = Instant::now();
+                    let result = lanes
+                        .background()
+                        .run(tokio::task::spawn_blocking(move || {
+                            let mut local = IndexingReport::new();
+                            let prepared =
+                                service.prepare_document(&path, &collection, force, &mut local);
+                            (prepared, local)
+                        }))
+                        .await;
+
+                    match result {
+                        Ok((prepared, local)) => {
+                            let busy = started.elapsed();
+                            let bytes_read = local.bytes_read;
+                            lock(report).merge(local);
+                            progress.record(PipelineStage::Read, 1, busy);
+                            throttle.after_work(busy).await;
+                            throttle.after_read(bytes_read).await;
+                            if let Some(prepared) = prepared {
+                                let _ = tx.send(prepared).await;
+                            }
+                        }
+                        Err(e) => tracing::error!("Read worker failed: {}", e),
+                    }
+                }
+            })
+            .await;
+    }
+
+    /// Split documents into chunks
+    #[allow(clippy::too_many_arguments)]
+    async fn chunk_stage(
+        service: DocumentIndexingService,
+        concurrency: usize,
+        rx: mpsc::Receiver<PreparedDocument>,
+        tx: mpsc::Sender<ChunkedDocument>,
+        report: Arc<Mutex<IndexingReport>>,
+        progress: Arc<PipelineProgress>,
+        throttle: Arc<Throttle>,
+        lanes: Arc<ExecutionLanes>,
+    ) {
+        ReceiverStream::new(rx)
+            .for_each_concurrent(concurrency, |prepared| {
+                let (service, tx, report, progress, throttle, lanes) =
+                    (&service, &tx, &report, &progress, &throttle, &lanes);
+
+                async move {
+                    let started = Instant::now();
+                    let chunks = lanes
+                        .background()
+                        .run(service.create_document_chunks(
+                            &prepared.document,
+                            &prepared.headings,
+                            &prepared.lines,
+                        ))
+                        .await;
+                    match chunks {
+                        Ok(chunks) => {
+                            let busy = started.elapsed();
+                            progress.record(PipelineStage::Chunk, 1, busy);
+                            throttle.after_work(busy).await;
+                            let _ = tx.send(ChunkedDocument { prepared, chunks }).await;
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to chunk {}: {}", prepared.path.display(), e);
+                            lock(report).error(
+                                &prepared.path,
+                                IndexingStage::Index,
+                                e.to_string(),
+                                Some("Check that the file is well-formed for its content type"),
+                            );
+                        }
+                    }
+                }
+            })
+            .await;
+    }
+
+    /// Generate embeddings for the new and changed chunks of documents
+    #[allow(clippy::too_many_arguments)]
+    async fn embed_stage(
+        service: DocumentIndexingService,
+        collection: Arc<str>,
+        force: bool,
+        dry_run: bool,
+        concurrency: usize,
+        rx: mpsc::Receiver<ChunkedDocument>,
+        tx: mpsc::Sender<EmbeddedDocument>,
+        report: Arc<Mutex<IndexingReport>>,
+        progress: Arc<PipelineProgress>,
+        throttle: Arc<Throttle>,
+        lanes: Arc<ExecutionLanes>,
+    ) {
+        ReceiverStream::new(rx)
+            .for_each_concurrent(concurrency, |chunked| {
+                let (service, collection, tx, report, progress, throttle, lanes) = (
+                    &service,
+                    &collection,
+                    &tx,
+                    &report,
+                    &progress,
+                    &throttle,
+                    &lanes,
+                );
+
+                async move {
+                    let started = Instant::now();
+                    let ChunkedDocument { prepared, chunks } = chunked;
+                    let (chunks, diff) = service.diff_chunks(
+                        collection,
+                        &prepared.path,
+                        &prepared.document,
+                        chunks,
+                        force,
+                    );
+                    let chunk_count = chunks.len() as u64;
+
+                    if dry_run {
+                        let tokens = chunks
+                            .iter()
+                            .map(|chunk| estimate_tokens(&chunk.content))
+                            .sum::<u64>();
+                        let mut report = lock(report);
+                        report.documents_processed += 1;
+                        report.chunks_embedded += chunk_count;
+                        report.embedding_tokens += tokens;
+                        progress.record(PipelineStage::Embed, chunk_count, started.elapsed());
+                        return;
+                    }
+
+                    let vectors = lanes
+                        .background()
+                        .run(service.embed_chunks(&prepared.document, chunks, collection))
+                        .await;
+                    match vectors {
+                        Ok(vectors) => {
+                            let busy = started.elapsed();
+                            progress.record(PipelineStage::Embed, chunk_count, busy);
+                            {
+                                let mut report = lock(report);
+                                report.chunks_embedded += chunk_count;
+                                report.embedding_tokens += vectors
+                                    .iter()
+                                    .map(|vector| estimate_tokens(&vector.metadata.content))
+                                    .sum::<u64>();
+                            }
+                            throttle.after_work(busy).await;
+                            let _ = tx
+                                .send(EmbeddedDocument {
+                                    document_id: prepared.document.id.to_string(),
+                                    path: prepared.path,
+                                    fingerprint: prepared.fingerprint,
+                                    vectors,
+                                    chunks: diff,
+                                })
+                                .await;
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to embed {}: {}", prepared.path.display(), e);
+                            lock(report).error(
+                                &prepared.path,
+                                IndexingStage::Index,
+                                e.to_string(),
+                                Some("Check that the embedding model is available"),
+                            );
+                        }
+                    }
+                }
+            })
+            .await;
+    }
+
+    /// Store embedded documents, record their fingerprints and checkpoint
+    /// the stored files
+    async fn upsert_stage(
+        service: DocumentIndexingService,
+        collection: Arc<str>,
+        concurrency: usize,
+        rx: mpsc::Receiver<EmbeddedDocument>,
+        report: Arc<Mutex<IndexingReport>>,
+        progress: Arc<PipelineProgress>,
+        lanes: Arc<ExecutionLanes>,
+    ) {
+        let checkpoints = Checkpoints::new(CHECKPOINT_INTERVAL);
+        ReceiverStream::new(rx)
+            .for_each_concurrent(concurrency, |embedded| {
+                let (service, collection, report, progress, checkpoints, lanes) = (
+                    &service,
+                    &collection,
+                    &report,
+                    &progress,
+                    &checkpoints,
+                    &lanes,
+                );
+
+                async move {
+                    let started = Instant::now();
+                    let EmbeddedDocument {
+                        document_id,
+                        path,
+                        fingerprint,
+                        vectors,
+                        chunks,
+                    } = embedded;
+
+                    let vector_count = vectors.len() as u64;
+                    let stored = lanes
+                        .background()
+                        .run(service.upsert_vectors(vectors))
+                        .await;
+                    match stored {
+                        Ok(_) => {
+                            let churn = service
+                                .commit_fingerprint(
+                                    collection,
+                                    document_id,
+                                    &path,
+                                    fingerprint,
+                                    chunks,
+                                )
+                                .await;
+                            progress.record(PipelineStage::Upsert, 1, started.elapsed());
+                            let mut report = lock(report);
+                            report.documents_processed += 1;
+                            report.vectors_stored += vector_count;
+                            report.chunk_churn.extend(churn);
+                            drop(report);
+                            tracing::debug!("Indexed {}", path.display());
+
+                            if let Some(files) = checkpoints.stored(path) {
+                                match service.save_fingerprints() {
+                                    Ok(()) => progress.checkpoint(files),
+                                    Err(e) => {
+                                        tracing::warn!("Failed to checkpoint fingerprints: {}", e)
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to store {}: {}", path.display(), e);
+                            lock(report).error(
+                                &path,
+                                IndexingStage::Index,
+                                e.to_string(),
+                                Some("Check that the vector store is available"),
+                            );
+                        }
+                    }
+                }
+            })
+            .await;
+    }
+}
+
+/// Files stored since the last checkpoint
+///
+/// A file is only checkpointed once the fingerprints are saved after it was
+/// stored, so a resumed job never skips a file whose chunk hashes were lost.
+struct Checkpoints {
+    interval: Duration,
+    pending: Mutex<(Instant, Vec<PathBuf>)>,
+}
+
+impl Checkpoints {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            pending: Mutex::new((Instant::now(), Vec::new())),
+        }
+    }
+
+    /// Record a stored file, returning the files to checkpoint when a
+    /// checkpoint is due
+    fn stored(&self, path: PathBuf) -> Option<Vec<PathBuf>> {
+        let mut pending = lock(&self.pending);
+        pending.1.push(path);
+        if pending.0.elapsed() < self.interval {
+            return None;
+        }
+        pending.0 = Instant::now();
+        Some(std::mem::take(&mut pending.1))
+    }
+}
+
+/// Lock shared pipeline state, recovering from a poisoned lock
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_snapshot_reports_every_stage() {
+        let progress = PipelineProgress::new();
+        progress.record(PipelineStage::Walk, 3, Duration::from_millis(2));
+        progress.record(PipelineStage::Embed, 10, Duration::from_millis(40));
+        progress.record(PipelineStage::Embed, 5, Duration::from_millis(10));
+
+        let snapshot = progress.snapshot();
+        assert_eq!(snapshot.len(), PipelineStage::ALL.len());
+
+        let embed = snapshot
+            .iter()
+            .find(|s| s.stage == PipelineStage::Embed)
+            .unwrap();
+        assert_eq!(embed.items, 15);
+        assert!((embed.busy_ms - 50.0).abs() < f64::EPSILON);
+
+        let upsert = snapshot
+            .iter()
+            .find(|s| s.stage == PipelineStage::Upsert)
+            .unwrap();
+        assert_eq!(upsert.items, 0);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::application::services::indexing_pipeline::StageThroughput;
+use crate::infrastructure::persistence::metadata_schemas::MetadataViolation;
+use serde::{Deserialize, Serialize};
+/// Per-file diagnostics collected during an indexing run
+///
+/// Indexing a directory should never fail as a whole because a handful of
+/// files could not be processed. Instead, each problem is recorded as a
+/// diagnostic describing where in the pipeline it happened and what the user
+/// can do about it.
+use std::path::Path;
+
+/// Pipeline stage at which a diagnostic was raised
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexingStage {
+    /// Reading file metadata or bytes from disk
+    Read,
+    /// Detecting the encoding and decoding the bytes into text
+    Decode,
+    /// Extracting indexable text with the content type handler
+    Process,
+    /// Checking custom metadata against the collection's schema
+    Validate,
+    /// Chunking, embedding and storing the document
+    Index,
+}
+
+/// Severity of a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    /// The file was indexed, or skipped by design, but deserves attention
+    Warning,
+    /// The file could not be indexed
+    Error,
+}
+
+/// A single problem encountered while indexing a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiagnostic {
+    pub path: String,
+    pub stage: IndexingStage,
+    pub severity: DiagnosticSeverity,
+    pub reason: String,
+    pub suggestion: Option<String>,
+    /// Fields that don't match the collection's metadata schema
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metadata_errors: Vec<MetadataViolation>,
+}
+
+/// What re-indexing a changed file did to its chunks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkChurn {
+    pub path: String,
+    /// New or changed chunks that were embedded
+    pub embedded: usize,
+    /// Unchanged chunks whose stored vectors were kept
+    pub reused: usize,
+    /// Stored chunks the file no longer has
+    pub removed: usize,
+}
+
+/// Outcome of an indexing run including per-file diagnostics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexingReport {
+    pub documents_processed: u64,
+    pub files_skipped: u64,
+    pub bytes_read: u64,
+    /// Vectors written to the store
+    #[serde(default)]
+    pub vectors_stored: u64,
+    /// Chunks embedded; unchanged chunks of re-indexed files are not
+    #[serde(default)]
+    pub chunks_embedded: u64,
+    /// Estimated tokens of the chunks embedded
+    #[serde(default)]
+    pub embedding_tokens: u64,
+    pub processing_time_ms: f64,
+    pub diagnostics: Vec<FileDiagnostic>,
+    pub stages: Vec<StageThroughput>,
+    /// Chunk churn of files that had been indexed before
+    #[serde(default)]
+    pub chunk_churn: Vec<ChunkChurn>,
+}
+
+impl IndexingReport {
+    /// Create an empty report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a warning for a file
+    pub fn warn(
+        &mut self,
+        path: &Path,
+        stage: IndexingStage,
+        reason: impl Into<String>,
+        suggestion: Option<&str>,
+    ) {
+        self.push(path, stage, DiagnosticSeverity::Warning, reason, suggestion);
+    }
+
+    /// Record an error for a file
+    pub fn error(
+        &mut self,
+        path: &Path,
+        stage: IndexingStage,
+        reason: impl Into<String>,
+        suggestion: Option<&str>,
+    ) {
+        self.push(path, stage, DiagnosticSeverity::Error, reason, suggestion);
+    }
+
+    /// Record that a file's metadata doesn't match the collection's schema
+    pub fn reject_metadata(&mut self, path: &Path, violations: Vec<MetadataViolation>) {
+        let fields: Vec<&str> = violations.iter().map(|v| v.field.as_str()).collect();
+        self.error(
+            path,
+            IndexingStage::Validate,
+            format!(
+                "Metadata does not match the collection schema: {}",
+                fields.join(", ")
+            ),
+            Some("Fix the listed fields, or change the collection's metadata schema"),
+        );
+        if let Some(diagnostic) = self.diagnostics.last_mut() {
+            diagnostic.metadata_errors = violations;
+        }
+    }
+
+    /// Fold the counters and diagnostics of a partial report into this one
+    pub fn merge(&mut self, other: IndexingReport) {
+        self.documents_processed += other.documents_processed;
+        self.files_skipped += other.files_skipped;
+        self.bytes_read += other.bytes_read;
+        self.vectors_stored += other.vectors_stored;
+        self.chunks_embedded += other.chunks_embedded;
+        self.embedding_tokens += other.embedding_tokens;
+        self.diagnostics.extend(other.diagnostics);
+        self.chunk_churn.extend(other.chunk_churn);
+    }
+
+    /// Number of error diagnostics
+    pub fn error_count(&self) -> usize {
+        self.count(DiagnosticSeverity::Error)
+    }
+
+    /// Number of warning diagnostics
+    pub fn warning_count(&self) -> usize {
+        self.count(DiagnosticSeverity::Warning)
+    }
+
+    fn push(
+        &mut self,
+        path: &Path,
+        stage: IndexingStage,
+        severity: DiagnosticSeverity,
+        reason: impl Into<String>,
+        suggestion: Option<&str>,
+    ) {
+        self.diagnostics.push(FileDiagnostic {
+            path: path.display().to_string(),
+            stage,
+            severity,
+            reason: reason.into(),
+            suggestion: suggestion.map(str::to_string),
+            metadata_errors: Vec::new(),
+        });
+    }
+
+    fn count(&self, severity: DiagnosticSeverity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+}
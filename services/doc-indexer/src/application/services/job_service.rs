@@ -0,0 +1,682 @@
+use crate::application::services::document_service::IndexRunOptions;
+use crate::application::services::embedding_migration::EmbeddingMigrationReport;
+use crate::application::services::filter_service::IndexingFilters;
+use crate::application::services::indexing_pipeline::{PipelineProgress, StageThroughput};
+use crate::application::services::indexing_report::{
+    ChunkChurn, DiagnosticSeverity, FileDiagnostic, IndexingReport,
+};
+use crate::application::services::projection_service::ProjectionReport;
+use crate::application::services::throttle::IndexingPriority;
+use crate::application::services::vector_staleness::ReembedReport;
+use crate::application::services::webhook_service::WebhookService;
+use crate::infrastructure::persistence::job_store::JobStore;
+use crate::infrastructure::persistence::webhook_registry::WebhookEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+/// Indexing job tracking service
+///
+/// Every index or reindex request runs as a job with its own identifier so
+/// that the per-file diagnostics it produced can be retrieved after the
+/// request has completed, and so that per-stage pipeline throughput can be
+/// followed while it is still running. With a persistent store, job records
+/// survive restarts; jobs the restart interrupted are marked failed. Index
+/// and reindex jobs keep a checkpoint of the files they have stored, so a
+/// failed or interrupted one can be resumed where it stopped.
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+
+/// Maximum number of finished jobs retained in memory
+const MAX_RETAINED_JOBS: usize = 100;
+
+/// Error recorded on jobs that were still running when the service stopped
+const INTERRUPTED_ERROR: &str =
+    "Interrupted: the doc-indexer stopped before the job finished; run the job again";
+
+/// What a failed index or reindex job needs to resume where it stopped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobCheckpoint {
+    pub recursive: bool,
+    pub force: bool,
+    pub filters: Option<IndexingFilters>,
+    /// Files stored before the job stopped, skipped when it resumes
+    pub completed_files: Vec<String>,
+}
+
+/// Kind of work performed by a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobKind {
+    Index,
+    Reindex,
+    /// Re-embedding a collection with the current embedding model
+    Migrate,
+    /// Re-embedding just the stale vectors of a collection
+    Reembed,
+    /// Projecting a collection's vectors to 2D for plotting
+    Project,
+}
+
+/// Lifecycle status of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Record of an indexing job and its outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexingJob {
+    pub id: String,
+    pub kind: JobKind,
+    pub priority: IndexingPriority,
+    pub path: String,
+    pub collection: String,
+    pub status: JobStatus,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub documents_processed: u64,
+    /// Files skipped because they were unchanged since the last run
+    #[serde(default)]
+    pub files_skipped: u64,
+    pub error: Option<String>,
+    pub diagnostics: Vec<FileDiagnostic>,
+    pub stages: Vec<StageThroughput>,
+    /// Chunks embedded, reused and removed for each re-indexed file
+    #[serde(default)]
+    pub chunk_churn: Vec<ChunkChurn>,
+    /// Outcome of a completed embedding migration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migration: Option<EmbeddingMigrationReport>,
+    /// Outcome of a completed re-embedding of stale vectors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reembed: Option<ReembedReport>,
+    /// Outcome of a completed projection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub projection: Option<ProjectionReport>,
+    /// Resume point of an unfinished index or reindex job
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<JobCheckpoint>,
+}
+
+/// Service for tracking indexing jobs
+#[derive(Clone)]
+pub struct JobService {
+    // In-memory job registry (in production, this would be persistent storage)
+    jobs: Arc<tokio::sync::RwLock<HashMap<String, IndexingJob>>>,
+    order: Arc<tokio::sync::RwLock<VecDeque<String>>>,
+    // Live pipeline counters of running jobs
+    progress: Arc<tokio::sync::RwLock<HashMap<String, Arc<PipelineProgress>>>>,
+    // Notified when index and reindex jobs finish
+    webhooks: Option<WebhookService>,
+    // Where job records are kept across restarts
+    store: Arc<JobStore>,
+    // Held while a snapshot of the jobs is taken and saved, so saves don't
+    // interleave and a newer snapshot is never replaced by an older one
+    persisting: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl JobService {
+    /// Create a new job service
+    pub fn new() -> Self {
+        Self {
+            jobs: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            order: Arc::new(tokio::sync::RwLock::new(VecDeque::new())),
+            progress: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            webhooks: None,
+            store: Arc::new(JobStore::in_memory()),
+            persisting: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    /// Keep job records in `store`, restoring the jobs it holds
+    ///
+    /// Jobs that were running when the service stopped are marked failed,
+    /// so clients polling them learn the job won't finish.
+    pub async fn with_store(mut self, store: Arc<JobStore>) -> Result<Self> {
+        let mut restored: Vec<IndexingJob> = store.load()?;
+        restored.sort_by_key(|job| job.started_at);
+        let excess = restored.len().saturating_sub(MAX_RETAINED_JOBS);
+        restored.drain(..excess);
+
+        let mut interrupted = 0;
+        for job in restored.iter_mut() {
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Failed;
+                job.completed_at = Some(chrono::Utc::now());
+                job.error = Some(match job.checkpoint {
+                    Some(_) => format!(
+                        "Interrupted: the doc-indexer stopped before the job finished; \
+                         resume it with `mdx index --resume {}`",
+                        job.id
+                    ),
+                    None => INTERRUPTED_ERROR.to_string(),
+                });
+                interrupted += 1;
+            }
+        }
+        if interrupted > 0 {
+            tracing::warn!(
+                "Marked {} jobs interrupted by the restart as failed",
+                interrupted
+            );
+            store.save(&restored).await?;
+        }
+
+        self.order = Arc::new(tokio::sync::RwLock::new(
+            restored.iter().map(|job| job.id.clone()).collect(),
+        ));
+        self.jobs = Arc::new(tokio::sync::RwLock::new(
+            restored
+                .into_iter()
+                .map(|job| (job.id.clone(), job))
+                .collect(),
+        ));
+        self.store = store;
+        Ok(self)
+    }
+
+    /// Notify webhooks when index and reindex jobs complete or fail
+    pub fn with_webhooks(mut self, webhooks: WebhookService) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Register a new running job and return its identifier
+    pub async fn start_job(
+        &self,
+        kind: JobKind,
+        priority: IndexingPriority,
+        path: &str,
+        collection: &str,
+    ) -> String {
+        let id = Uuid::new_v4().to_string();
+        let job = IndexingJob {
+            id: id.clone(),
+            kind,
+            priority,
+            path: path.to_string(),
+            collection: collection.to_string(),
+            status: JobStatus::Running,
+            started_at: chrono::Utc::now(),
+            completed_at: None,
+            documents_processed: 0,
+            files_skipped: 0,
+            error: None,
+            diagnostics: Vec::new(),
+            stages: Vec::new(),
+            chunk_churn: Vec::new(),
+            migration: None,
+            reembed: None,
+            projection: None,
+            checkpoint: None,
+        };
+
+        let mut jobs = self.jobs.write().await;
+        let mut order = self.order.write().await;
+        jobs.insert(id.clone(), job);
+        order.push_back(id.clone());
+
+        // Evict the oldest jobs once the retention limit is exceeded
+        while order.len() > MAX_RETAINED_JOBS {
+            if let Some(oldest) = order.pop_front() {
+                jobs.remove(&oldest);
+                self.progress.write().await.remove(&oldest);
+            }
+        }
+        drop((jobs, order));
+
+        self.persist().await;
+        id
+    }
+
+    /// Create the pipeline progress counters of a running job
+    pub async fn track_progress(&self, id: &str) -> Arc<PipelineProgress> {
+        let progress = Arc::new(PipelineProgress::new());
+        self.progress
+            .write()
+            .await
+            .insert(id.to_string(), progress.clone());
+        progress
+    }
+
+    /// Record the options an index or reindex job runs with, so it can be
+    /// resumed if it fails or is interrupted
+    pub async fn make_resumable(&self, id: &str, options: &IndexRunOptions) {
+        if let Some(job) = self.jobs.write().await.get_mut(id) {
+            job.checkpoint = Some(JobCheckpoint {
+                recursive: options.recursive,
+                force: options.force,
+                filters: options.filters.clone(),
+                completed_files: options.progress.checkpointed_files(),
+            });
+        }
+    }
+
+    /// Restart a failed index or reindex job from its checkpoint
+    ///
+    /// Returns the job and the options to run it with; the files it had
+    /// stored are skipped.
+    pub async fn resume_job(&self, id: &str) -> Result<(IndexingJob, IndexRunOptions)> {
+        let mut jobs = self.jobs.write().await;
+        let job = jobs
+            .get_mut(id)
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("job {}", id)))?;
+        let state = match job.status {
+            JobStatus::Failed => None,
+            JobStatus::Running => Some("still running"),
+            JobStatus::Completed => Some("already completed"),
+        };
+        if let Some(state) = state {
+            return Err(ZeroLatencyError::validation(
+                "job",
+                format!("job {} is {}; only failed jobs can be resumed", id, state),
+            ));
+        }
+        let Some(checkpoint) = job.checkpoint.clone() else {
+            return Err(ZeroLatencyError::validation(
+                "job",
+                format!("job {} has no checkpoint to resume from", id),
+            ));
+        };
+
+        job.status = JobStatus::Running;
+        job.completed_at = None;
+        job.error = None;
+        let job = job.clone();
+        drop(jobs);
+
+        let progress = Arc::new(PipelineProgress::resume_from(
+            checkpoint.completed_files.iter().map(PathBuf::from),
+        ));
+        self.progress
+            .write()
+            .await
+            .insert(id.to_string(), progress.clone());
+        self.persist().await;
+
+        let options = IndexRunOptions {
+            recursive: checkpoint.recursive,
+            filters: checkpoint.filters,
+            force: checkpoint.force,
+            priority: job.priority,
+            progress,
+            dry_run: false,
+        };
+        Ok((job, options))
+    }
+
+    /// Mark a job as completed with the report it produced
+    pub async fn complete_job(&self, id: &str, report: &IndexingReport) {
+        self.progress.write().await.remove(id);
+        if let Some(job) = self.jobs.write().await.get_mut(id) {
+            job.status = JobStatus::Completed;
+            job.completed_at = Some(chrono::Utc::now());
+            job.documents_processed = report.documents_processed;
+            job.files_skipped = report.files_skipped;
+            job.diagnostics = report.diagnostics.clone();
+            job.stages = report.stages.clone();
+            job.chunk_churn = report.chunk_churn.clone();
+            job.checkpoint = None;
+            self.notify(job);
+        }
+        self.persist().await;
+    }
+
+    /// Mark a migration job as completed with its report
+    pub async fn complete_migration(&self, id: &str, report: &EmbeddingMigrationReport) {
+        let progress = self.progress.write().await.remove(id);
+        if let Some(job) = self.jobs.write().await.get_mut(id) {
+            job.status = JobStatus::Completed;
+            job.completed_at = Some(chrono::Utc::now());
+            job.documents_processed = report.vectors_migrated as u64;
+            job.migration = Some(report.clone());
+            if let Some(progress) = progress {
+                job.stages = progress.snapshot();
+            }
+        }
+        self.persist().await;
+    }
+
+    /// Mark a stale vector re-embedding job as completed with its report
+    pub async fn complete_reembed(&self, id: &str, report: &ReembedReport) {
+        let progress = self.progress.write().await.remove(id);
+        if let Some(job) = self.jobs.write().await.get_mut(id) {
+            job.status = JobStatus::Completed;
+            job.completed_at = Some(chrono::Utc::now());
+            job.documents_processed = report.vectors_reembedded as u64;
+            job.reembed = Some(report.clone());
+            if let Some(progress) = progress {
+                job.stages = progress.snapshot();
+            }
+        }
+        self.persist().await;
+    }
+
+    /// Mark a projection job as completed with its report
+    pub async fn complete_projection(&self, id: &str, report: &ProjectionReport) {
+        let progress = self.progress.write().await.remove(id);
+        if let Some(job) = self.jobs.write().await.get_mut(id) {
+            job.status = JobStatus::Completed;
+            job.completed_at = Some(chrono::Utc::now());
+            job.documents_processed = report.points as u64;
+            job.projection = Some(report.clone());
+            if let Some(progress) = progress {
+                job.stages = progress.snapshot();
+            }
+        }
+        self.persist().await;
+    }
+
+    /// Mark a job as failed
+    pub async fn fail_job(&self, id: &str, error: &ZeroLatencyError) {
+        let progress = self.progress.write().await.remove(id);
+        if let Some(job) = self.jobs.write().await.get_mut(id) {
+            job.status = JobStatus::Failed;
+            job.completed_at = Some(chrono::Utc::now());
+            job.error = Some(error.to_string());
+            if let Some(progress) = progress {
+                job.stages = progress.snapshot();
+                if let Some(checkpoint) = &mut job.checkpoint {
+                    checkpoint.completed_files = progress.checkpointed_files();
+                }
+            }
+            self.notify(job);
+        }
+        self.persist().await;
+    }
+
+    /// Write the retained jobs, with the current progress of running ones,
+    /// to the store
+    ///
+    /// Failing to persist doesn't fail the job; the records are written again
+    /// on the next change.
+    pub async fn persist(&self) {
+        let _persisting = self.persisting.lock().await;
+        let jobs = self.list_jobs().await;
+        let oldest_first: Vec<&IndexingJob> = jobs.iter().rev().collect();
+        if let Err(e) = self.store.save(&oldest_first).await {
+            tracing::warn!("Failed to persist jobs: {}", e);
+        }
+    }
+
+    /// Periodically persist the progress of running jobs, so it outlives a
+    /// crash
+    pub fn spawn_checkpointer(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if !service.progress.read().await.is_empty() {
+                    service.persist().await;
+                }
+            }
+        })
+    }
+
+    /// Tell webhooks that an index or reindex job finished
+    fn notify(&self, job: &IndexingJob) {
+        let Some(webhooks) = &self.webhooks else {
+            return;
+        };
+        let event = match (job.kind, job.status) {
+            (JobKind::Index, JobStatus::Completed) => WebhookEvent::IndexCompleted,
+            (JobKind::Index, JobStatus::Failed) => WebhookEvent::IndexFailed,
+            (JobKind::Reindex, JobStatus::Completed) => WebhookEvent::ReindexCompleted,
+            (JobKind::Reindex, JobStatus::Failed) => WebhookEvent::ReindexFailed,
+            _ => return,
+        };
+        let diagnostics = |severity| {
+            job.diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == severity)
+                .count()
+        };
+        webhooks.emit(
+            event,
+            serde_json::json!({
+                "job_id": job.id,
+                "kind": job.kind,
+                "path": job.path,
+                "collection": job.collection,
+                "status": job.status,
+                "started_at": job.started_at,
+                "completed_at": job.completed_at,
+                "documents_processed": job.documents_processed,
+                "errors": diagnostics(DiagnosticSeverity::Error),
+                "warnings": diagnostics(DiagnosticSeverity::Warning),
+                "error": job.error,
+            }),
+        );
+    }
+
+    /// Identifier of a running job of `kind` on a collection, if any
+    pub async fn running_job(&self, kind: JobKind, collection: &str) -> Option<String> {
+        self.jobs
+            .read()
+            .await
+            .values()
+            .find(|job| {
+                job.kind == kind && job.collection == collection && job.status == JobStatus::Running
+            })
+            .map(|job| job.id.clone())
+    }
+
+    /// Get a job by identifier
+    pub async fn get_job(&self, id: &str) -> Result<IndexingJob> {
+        let job = self
+            .jobs
+            .read()
+            .await
+            .get(id)
+            .cloned()
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("job {}", id)))?;
+        Ok(self.with_live_progress(job).await)
+    }
+
+    /// List retained jobs, most recent first
+    pub async fn list_jobs(&self) -> Vec<IndexingJob> {
+        let jobs: Vec<IndexingJob> = {
+            let jobs = self.jobs.read().await;
+            let order = self.order.read().await;
+            order
+                .iter()
+                .rev()
+                .filter_map(|id| jobs.get(id).cloned())
+                .collect()
+        };
+
+        let mut result = Vec::with_capacity(jobs.len());
+        for job in jobs {
+            result.push(self.with_live_progress(job).await);
+        }
+        result
+    }
+
+    /// Fill in the current stage throughput and checkpoint of a running job
+    async fn with_live_progress(&self, mut job: IndexingJob) -> IndexingJob {
+        if job.status == JobStatus::Running {
+            if let Some(progress) = self.progress.read().await.get(&job.id) {
+                job.stages = progress.snapshot();
+                if let Some(checkpoint) = &mut job.checkpoint {
+                    checkpoint.completed_files = progress.checkpointed_files();
+                }
+            }
+        }
+        job
+    }
+}
+
+impl Default for JobService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::services::indexing_report::IndexingStage;
+    use std::path::Path;
+
+    #[tokio::test]
+    async fn test_job_lifecycle_records_diagnostics() {
+        let service = JobService::new();
+        let id = service
+            .start_job(JobKind::Index, IndexingPriority::Low, "/docs", "docs")
+            .await;
+        let job = service.get_job(&id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Running);
+        assert_eq!(job.priority, IndexingPriority::Low);
+
+        let mut report = IndexingReport::new();
+        report.documents_processed = 3;
+        report.error(
+            Path::new("/docs/bad.md"),
+            IndexingStage::Read,
+            "permission denied",
+            Some("check file permissions"),
+        );
+        service.complete_job(&id, &report).await;
+
+        let job = service.get_job(&id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!(job.documents_processed, 3);
+        assert_eq!(job.diagnostics.len(), 1);
+        assert_eq!(job.diagnostics[0].path, "/docs/bad.md");
+    }
+
+    #[tokio::test]
+    async fn test_running_job_exposes_stage_throughput() {
+        use crate::application::services::indexing_pipeline::PipelineStage;
+
+        let service = JobService::new();
+        let id = service
+            .start_job(JobKind::Index, IndexingPriority::Normal, "/docs", "docs")
+            .await;
+        let progress = service.track_progress(&id).await;
+        progress.record(PipelineStage::Read, 4, std::time::Duration::from_millis(8));
+
+        let job = service.get_job(&id).await.unwrap();
+        let read = job
+            .stages
+            .iter()
+            .find(|s| s.stage == PipelineStage::Read)
+            .unwrap();
+        assert_eq!(read.items, 4);
+        assert_eq!(service.list_jobs().await[0].id, id);
+    }
+
+    #[tokio::test]
+    async fn test_running_job_is_found_by_kind_and_collection() {
+        let service = JobService::new();
+        let id = service
+            .start_job(JobKind::Migrate, IndexingPriority::Normal, "", "docs")
+            .await;
+        assert_eq!(
+            service.running_job(JobKind::Migrate, "docs").await,
+            Some(id.clone())
+        );
+        assert_eq!(service.running_job(JobKind::Index, "docs").await, None);
+
+        service
+            .fail_job(&id, &ZeroLatencyError::internal("boom"))
+            .await;
+        assert_eq!(service.running_job(JobKind::Migrate, "docs").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_job_is_not_found() {
+        let service = JobService::new();
+        assert!(service.get_job("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_oldest_jobs_are_evicted() {
+        let service = JobService::new();
+        let first = service
+            .start_job(JobKind::Index, IndexingPriority::Normal, "/a", "docs")
+            .await;
+        for _ in 0..MAX_RETAINED_JOBS {
+            service
+                .start_job(JobKind::Index, IndexingPriority::Normal, "/b", "docs")
+                .await;
+        }
+        assert!(service.get_job(&first).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_restores_jobs_and_fails_interrupted_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(JobStore::at(dir.path().join("jobs.json")));
+
+        let service = JobService::new().with_store(store.clone()).await.unwrap();
+        let finished = service
+            .start_job(JobKind::Index, IndexingPriority::Normal, "/docs", "docs")
+            .await;
+        let mut report = IndexingReport::new();
+        report.documents_processed = 2;
+        service.complete_job(&finished, &report).await;
+        let running = service
+            .start_job(JobKind::Reindex, IndexingPriority::Normal, "/docs", "docs")
+            .await;
+
+        // The service stops while the reindex runs
+        drop(service);
+        let restarted = JobService::new().with_store(store).await.unwrap();
+
+        let job = restarted.get_job(&finished).await.unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!(job.documents_processed, 2);
+
+        let job = restarted.get_job(&running).await.unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.error.as_deref(), Some(INTERRUPTED_ERROR));
+        assert!(job.completed_at.is_some());
+        assert_eq!(restarted.list_jobs().await[0].id, running);
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_job_resumes_from_its_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(JobStore::at(dir.path().join("jobs.json")));
+
+        let service = JobService::new().with_store(store.clone()).await.unwrap();
+        let id = service
+            .start_job(JobKind::Index, IndexingPriority::Low, "/docs", "docs")
+            .await;
+        let progress = service.track_progress(&id).await;
+        let options = IndexRunOptions {
+            force: true,
+            progress: progress.clone(),
+            ..Default::default()
+        };
+        service.make_resumable(&id, &options).await;
+        progress.checkpoint([PathBuf::from("/docs/a.md")]);
+        service.persist().await;
+
+        // Running jobs can't be resumed
+        assert!(service.resume_job(&id).await.is_err());
+
+        drop(service);
+        let restarted = JobService::new().with_store(store).await.unwrap();
+        let job = restarted.get_job(&id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert!(job.error.unwrap().contains("mdx index --resume"));
+
+        let (job, options) = restarted.resume_job(&id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Running);
+        assert!(job.error.is_none());
+        assert!(options.force);
+        assert_eq!(options.priority, IndexingPriority::Low);
+        assert!(options.progress.is_checkpointed(Path::new("/docs/a.md")));
+        assert!(!options.progress.is_checkpointed(Path::new("/docs/b.md")));
+
+        restarted.complete_job(&id, &IndexingReport::new()).await;
+        assert!(restarted.get_job(&id).await.unwrap().checkpoint.is_none());
+    }
+}
@@ -0,0 +1,372 @@
+/// Definition-style documentation lookup for editor plugins
+///
+/// Editors fire a lookup whenever the cursor rests on a symbol, so this path
+/// trades recall for latency: it never expands the query, reranks only the
+/// handful of candidates it fetches, stays out of search analytics and
+/// answers repeated lookups from a short-lived cache. Optional file context
+/// (the language or path of the file being edited) breaks ties in favour of
+/// snippets that mention it. Cached answers are kept per tenant, collection
+/// and request principal.
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use zero_latency_core::{Result, ZeroLatencyError};
+use zero_latency_search::cache::{
+    CacheNamespace, CollectionCache, CollectionCacheStatistics, CollectionCounters,
+};
+use zero_latency_search::{SearchResult, SearchSettings};
+
+use crate::application::{CollectionService, DocumentIndexingService};
+use crate::infrastructure::api::http::principal::current_principal;
+use crate::infrastructure::api::http::tenant::current_tenant;
+use crate::infrastructure::memory::{CacheConfig, CacheStats, MemoryEfficientCache};
+
+/// Snippets returned by default and at most
+pub const MAX_LOOKUP_RESULTS: usize = 3;
+
+/// Candidates fetched before context boosting picks the top snippets
+const LOOKUP_CANDIDATES: usize = 10;
+
+/// Longest symbol or phrase accepted
+const MAX_LOOKUP_QUERY_CHARS: usize = 256;
+
+/// Characters of snippet text returned per result
+const SNIPPET_CHARS: usize = 280;
+
+/// Score multiplier for results that mention the editor's file context
+const CONTEXT_BOOST: f32 = 1.15;
+
+/// How long a cached lookup is served; results can lag indexing by this much
+const CACHE_TTL: Duration = Duration::from_secs(30);
+const CACHE_ENTRIES: usize = 1024;
+
+/// A lookup from an editor
+#[derive(Debug, Clone, Deserialize)]
+pub struct LookupRequest {
+    /// Symbol or short phrase under the cursor
+    pub symbol: String,
+    /// Collection to search; the service default if unset
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Number of snippets, at most [`MAX_LOOKUP_RESULTS`]
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// The file being edited
+    #[serde(default)]
+    pub context: Option<LookupContext>,
+}
+
+/// What the editor knows about the file the symbol is in
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LookupContext {
+    /// Path of the file, absolute or relative to the workspace
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Editor language identifier, e.g. `rust` or `typescript`
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl LookupContext {
+    /// Lowercase terms a relevant snippet is likely to mention
+    fn terms(&self) -> Vec<String> {
+        let mut terms = Vec::new();
+        if let Some(language) = &self.language {
+            terms.push(language.to_lowercase());
+        }
+        if let Some(file_path) = &self.file_path {
+            let path = Path::new(file_path);
+            if self.language.is_none() {
+                if let Some(language) = path
+                    .extension()
+                    .and_then(|ext| language_for_extension(&ext.to_string_lossy()))
+                {
+                    terms.push(language.to_string());
+                }
+            }
+            if let Some(stem) = path.file_stem() {
+                terms.push(stem.to_string_lossy().to_lowercase());
+            }
+        }
+        terms.retain(|term| term.len() > 1);
+        terms.dedup();
+        terms
+    }
+}
+
+/// Lookup answer in the stable editor contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LookupResponse {
+    pub symbol: String,
+    pub collection: String,
+    pub results: Vec<LookupResult>,
+    /// Whether the answer came from the lookup cache
+    pub cached: bool,
+    pub took_ms: f64,
+}
+
+/// One documentation snippet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LookupResult {
+    pub document_id: String,
+    pub title: String,
+    pub uri: String,
+    /// Heading breadcrumb of the snippet within its document
+    pub section_path: Vec<String>,
+    pub snippet: String,
+    pub score: f32,
+}
+
+/// Cached lookups, by namespace and the rest of the request
+type LookupKey = (CacheNamespace, String);
+
+/// Service answering editor lookups
+#[derive(Clone)]
+pub struct LookupService {
+    document_service: DocumentIndexingService,
+    collection_service: CollectionService,
+    default_collection: String,
+    cache: Arc<MemoryEfficientCache<LookupKey, LookupResponse>>,
+    collections: Arc<CollectionCounters>,
+}
+
+impl LookupService {
+    pub fn new(
+        document_service: DocumentIndexingService,
+        collection_service: CollectionService,
+        default_collection: String,
+    ) -> Self {
+        let cache = MemoryEfficientCache::new(CacheConfig {
+            max_entries: CACHE_ENTRIES,
+            ttl: CACHE_TTL,
+            ..Default::default()
+        });
+        Self {
+            document_service,
+            collection_service,
+            default_collection,
+            cache: Arc::new(cache),
+            collections: Arc::new(CollectionCounters::new()),
+        }
+    }
+
+    /// The lookup cache, for the memory pressure monitor to shrink
+    pub fn cache(&self) -> Arc<MemoryEfficientCache<LookupKey, LookupResponse>> {
+        self.cache.clone()
+    }
+
+    /// Number of cached lookups and the cache's statistics
+    pub fn cache_stats(&self) -> (usize, CacheStats) {
+        (self.cache.len(), self.cache.stats())
+    }
+
+    /// Look up documentation for a symbol
+    pub async fn lookup(&self, request: &LookupRequest) -> Result<LookupResponse> {
+        let started = Instant::now();
+        let symbol = request.symbol.trim();
+        if symbol.is_empty() {
+            return Err(ZeroLatencyError::validation("symbol", "must not be empty"));
+        }
+        if symbol.chars().count() > MAX_LOOKUP_QUERY_CHARS {
+            return Err(ZeroLatencyError::validation(
+                "symbol",
+                format!("must be at most {} characters", MAX_LOOKUP_QUERY_CHARS),
+            ));
+        }
+        let limit = request.limit.unwrap_or(MAX_LOOKUP_RESULTS);
+        if !(1..=MAX_LOOKUP_RESULTS).contains(&limit) {
+            return Err(ZeroLatencyError::validation(
+                "limit",
+                format!("must be between 1 and {}", MAX_LOOKUP_RESULTS),
+            ));
+        }
+        let collection = request
+            .collection
+            .clone()
+            .unwrap_or_else(|| self.default_collection.clone());
+        let terms = request
+            .context
+            .as_ref()
+            .map(LookupContext::terms)
+            .unwrap_or_default();
+
+        let key = (
+            CacheNamespace::new(current_tenant(), Some(collection.clone())),
+            // Principals entitled to different documents get different answers
+            format!(
+                "{}\u{0}{}\u{0}{}\u{0}{}",
+                symbol,
+                limit,
+                terms.join(" "),
+                current_principal()
+                    .map(|principal| principal.cache_key())
+                    .unwrap_or_default()
+            ),
+        );
+        if let Some(mut cached) = self.cache.get(&key) {
+            self.collections.record_hit(Some(&collection));
+            cached.cached = true;
+            cached.took_ms = started.elapsed().as_secs_f64() * 1000.0;
+            return Ok(cached);
+        }
+        self.collections.record_miss(Some(&collection));
+
+        // Collection fusion weights still apply; everything costly is trimmed
+        let overrides = SearchSettings {
+            limit: Some(LOOKUP_CANDIDATES),
+            query_expansion: Some(false),
+            rerank_depth: Some(MAX_LOOKUP_RESULTS),
+            extract_answer: Some(false),
+            context_window: Some(0),
+            ..Default::default()
+        };
+        let settings = self
+            .collection_service
+            .effective_search_settings(&collection, &overrides)
+            .await?;
+        let response = self
+            .document_service
+            .lookup_documents(symbol, &collection, &settings)
+            .await?;
+
+        let results = select_results(response.results, &terms, limit);
+        let answer = LookupResponse {
+            symbol: symbol.to_string(),
+            collection,
+            results,
+            cached: false,
+            took_ms: started.elapsed().as_secs_f64() * 1000.0,
+        };
+        self.cache.insert(key, answer.clone());
+        Ok(answer)
+    }
+}
+
+#[async_trait]
+impl CollectionCache for LookupService {
+    async fn collection_statistics(&self, collection: &str) -> CollectionCacheStatistics {
+        let (entries, memory_bytes) = self
+            .cache
+            .usage_where(|(namespace, _)| namespace.is_scoped_to(collection));
+        CollectionCacheStatistics::new(entries, memory_bytes, self.collections.get(collection))
+    }
+
+    async fn purge_collection(&self, collection: &str) -> usize {
+        self.collections.reset(collection);
+        self.cache
+            .remove_where(|(namespace, _)| namespace.covers(collection))
+    }
+}
+
+/// Best `limit` results after boosting those that mention a context term
+fn select_results(results: Vec<SearchResult>, terms: &[String], limit: usize) -> Vec<LookupResult> {
+    let mut scored: Vec<(f32, SearchResult)> = results
+        .into_iter()
+        .map(|result| {
+            let score = result.final_score.value();
+            let boost = if mentions_any(&result, terms) {
+                CONTEXT_BOOST
+            } else {
+                1.0
+            };
+            (score * boost, result)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(score, result)| LookupResult {
+            document_id: result.document_id.to_string(),
+            snippet: trim_snippet(result.snippet.as_deref().unwrap_or(&result.content)),
+            title: result.title,
+            uri: result.uri,
+            section_path: result.section_path,
+            score,
+        })
+        .collect()
+}
+
+fn mentions_any(result: &SearchResult, terms: &[String]) -> bool {
+    if terms.is_empty() {
+        return false;
+    }
+    let haystack = format!(
+        "{} {} {} {}",
+        result.uri,
+        result.title,
+        result.section_path.join(" "),
+        result.snippet.as_deref().unwrap_or(&result.content)
+    )
+    .to_lowercase();
+    terms.iter().any(|term| haystack.contains(term.as_str()))
+}
+
+/// Collapse whitespace and cut the text at a word boundary
+fn trim_snippet(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= SNIPPET_CHARS {
+        return text;
+    }
+    let cut: String = text.chars().take(SNIPPET_CHARS).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > SNIPPET_CHARS / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut)
+}
+
+/// Language named by a file extension, for editors that don't send one
+fn language_for_extension(extension: &str) -> Option<&'static str> {
+    Some(match extension {
+        "rs" => "rust",
+        "py" => "python",
+        "ts" | "tsx" => "typescript",
+        "js" | "jsx" | "mjs" => "javascript",
+        "go" => "go",
+        "java" => "java",
+        "kt" => "kotlin",
+        "rb" => "ruby",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "hpp" => "c++",
+        "cs" => "c#",
+        "swift" => "swift",
+        "lua" => "lua",
+        "sh" | "bash" => "shell",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_terms() {
+        let context = LookupContext {
+            file_path: Some("src/server/router.rs".to_string()),
+            language: None,
+        };
+        assert_eq!(context.terms(), vec!["rust", "router"]);
+
+        let context = LookupContext {
+            file_path: Some("main.go".to_string()),
+            language: Some("Go".to_string()),
+        };
+        assert_eq!(context.terms(), vec!["go", "main"]);
+    }
+
+    #[test]
+    fn test_trim_snippet() {
+        assert_eq!(trim_snippet("  spawn   a\ntask "), "spawn a task");
+
+        let long = "word ".repeat(100);
+        let trimmed = trim_snippet(&long);
+        assert!(trimmed.ends_with('…'));
+        assert!(trimmed.chars().count() <= SNIPPET_CHARS + 1);
+        assert!(!trimmed.contains("  "));
+    }
+}
@@ -0,0 +1,784 @@
+/// Read-only queries over a collection's document registry
+///
+/// Dashboards and audits often need a slice of what is indexed, such as the
+/// most recently changed API pages or every document still embedded with an
+/// old model, without exporting the whole collection. Queries use a small
+/// SQL-like syntax over one row per indexed file:
+///
+/// ```text
+/// SELECT path, modified_at WHERE tag = 'api' ORDER BY modified_at DESC LIMIT 50
+/// ```
+///
+/// Built-in columns come from the file's fingerprint and its stored chunks;
+/// any other name reads that custom metadata field. `tag` matches any one of
+/// a document's tags. Conditions compare with `=`, `!=`, `<`, `<=`, `>`,
+/// `>=` and `LIKE` (`%` and `_` wildcards), test `IS [NOT] NULL`, and
+/// combine with `AND`, `OR`, `NOT` and parentheses. Values that both read as
+/// numbers compare as numbers, everything else as text; timestamps are
+/// RFC 3339 in UTC, so they order as text.
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+use zero_latency_core::{Result, ZeroLatencyError};
+use zero_latency_vector::{VectorMetadata, VectorRepository, PATH_KEY};
+
+use crate::application::container::ServiceContainer;
+use crate::config::AccessConfig;
+use crate::infrastructure::api::http::principal::access_scope;
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+use crate::infrastructure::persistence::fingerprint_registry::{
+    FingerprintEntry, FingerprintRegistry,
+};
+
+/// Rows returned when a query has no `LIMIT`
+pub const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Most rows a query may return
+pub const MAX_QUERY_LIMIT: usize = 1000;
+
+/// Longest query accepted, in bytes
+pub const MAX_QUERY_LENGTH: usize = 4096;
+
+/// Deepest nesting of `NOT` and parentheses a condition may use
+pub const MAX_CONDITION_DEPTH: usize = 64;
+
+/// Columns of every row, in the order `SELECT *` returns them
+const BUILTIN_COLUMNS: [&str; 9] = [
+    "path",
+    "document_id",
+    "title",
+    "chunks",
+    "size",
+    "modified_at",
+    "indexed_at",
+    "embedding_model",
+    "hash",
+];
+
+/// Virtual column matching any one of a document's comma-separated tags
+const TAG_COLUMN: &str = "tag";
+
+/// Result of a metadata query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataQueryResult {
+    pub collection: String,
+    pub columns: Vec<String>,
+    /// Selected values of each row, parallel to `columns`; `null` where a
+    /// document has no such field
+    pub rows: Vec<Vec<Option<String>>>,
+    /// Rows matching the condition before `LIMIT` was applied
+    pub total_matched: usize,
+}
+
+/// A parsed query
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataQuery {
+    /// Selected columns; empty for `SELECT *`
+    pub columns: Vec<String>,
+    pub condition: Option<Condition>,
+    pub order_by: Option<(String, SortOrder)>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A `LIKE` pattern, compiled once when the query is parsed
+#[derive(Debug, Clone)]
+pub struct LikePattern {
+    pattern: String,
+    regex: Regex,
+}
+
+impl LikePattern {
+    fn new(pattern: String) -> Self {
+        let mut regex = String::from("(?is)^");
+        for c in pattern.chars() {
+            match c {
+                '%' => regex.push_str(".*"),
+                '_' => regex.push('.'),
+                c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        regex.push('$');
+        // Every character outside the wildcards is escaped, so this always
+        // compiles
+        let regex = Regex::new(&regex).expect("escaped LIKE pattern");
+        Self { pattern, regex }
+    }
+
+    fn is_match(&self, value: &str) -> bool {
+        self.regex.is_match(value)
+    }
+}
+
+impl PartialEq for LikePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+/// A `WHERE` condition
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Compare {
+        column: String,
+        op: Comparison,
+        value: String,
+    },
+    Like {
+        column: String,
+        pattern: LikePattern,
+    },
+    IsNull {
+        column: String,
+        negated: bool,
+    },
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl MetadataQuery {
+    /// Parse a query, reporting where it stopped making sense
+    pub fn parse(query: &str) -> Result<Self> {
+        if query.len() > MAX_QUERY_LENGTH {
+            return Err(invalid(format!(
+                "must be at most {} bytes long",
+                MAX_QUERY_LENGTH
+            )));
+        }
+        let mut parser = Parser {
+            tokens: tokenize(query)?,
+            position: 0,
+            depth: 0,
+        };
+        parser.query()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Number(String),
+    Symbol(&'static str),
+}
+
+fn invalid(message: impl Into<String>) -> ZeroLatencyError {
+    ZeroLatencyError::validation("query", message)
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' {
+            // SQL strings escape a quote by doubling it
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '\'')) if chars.peek().map(|&(_, c)| c) == Some('\'') => {
+                        chars.next();
+                        text.push('\'');
+                    }
+                    Some((_, '\'')) => break,
+                    Some((_, c)) => text.push(c),
+                    None => return Err(invalid(format!("unterminated string at {}", start))),
+                }
+            }
+            tokens.push(Token::Text(text));
+        } else if c.is_ascii_digit() || c == '-' {
+            let mut number = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_ascii_digit() || c == '.' || (c == '-' && number.is_empty()) {
+                    number.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if number.parse::<f64>().is_err() {
+                return Err(invalid(format!("invalid number '{}'", number)));
+            }
+            tokens.push(Token::Number(number));
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut word = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' || c == '.' {
+                    word.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Word(word));
+        } else {
+            let rest = &query[start..];
+            let symbol = ["!=", "<>", "<=", ">=", "=", "<", ">", ",", "(", ")", "*"]
+                .into_iter()
+                .find(|symbol| rest.starts_with(symbol))
+                .ok_or_else(|| invalid(format!("unexpected '{}'", c)))?;
+            for _ in 0..symbol.len() {
+                chars.next();
+            }
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// Enclosing `NOT`s and parentheses of the condition being parsed
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(s)) if *s == symbol => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.keyword(keyword) {
+            Ok(())
+        } else {
+            Err(invalid(format!("expected {}", keyword)))
+        }
+    }
+
+    fn column(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Word(word)) if !is_reserved(&word) => {
+                // Built-in columns are case-insensitive, custom fields are not
+                let lower = word.to_lowercase();
+                if BUILTIN_COLUMNS.contains(&lower.as_str()) || lower == TAG_COLUMN {
+                    Ok(lower)
+                } else {
+                    Ok(word)
+                }
+            }
+            Some(token) => Err(invalid(format!(
+                "expected a column name, found {:?}",
+                token
+            ))),
+            None => Err(invalid("expected a column name")),
+        }
+    }
+
+    fn query(&mut self) -> Result<MetadataQuery> {
+        self.expect_keyword("SELECT")?;
+        let mut columns = Vec::new();
+        if !self.symbol("*") {
+            loop {
+                columns.push(self.column()?);
+                if !self.symbol(",") {
+                    break;
+                }
+            }
+        }
+
+        let condition = if self.keyword("WHERE") {
+            Some(self.or()?)
+        } else {
+            None
+        };
+
+        let order_by = if self.keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            let column = self.column()?;
+            let order = if self.keyword("DESC") {
+                SortOrder::Desc
+            } else {
+                self.keyword("ASC");
+                SortOrder::Asc
+            };
+            Some((column, order))
+        } else {
+            None
+        };
+
+        let limit = if self.keyword("LIMIT") {
+            match self.next() {
+                Some(Token::Number(number)) => Some(
+                    number
+                        .parse::<usize>()
+                        .map_err(|_| invalid(format!("invalid LIMIT '{}'", number)))?,
+                ),
+                _ => return Err(invalid("expected a number after LIMIT")),
+            }
+        } else {
+            None
+        };
+
+        if let Some(token) = self.peek() {
+            return Err(invalid(format!("unexpected {:?}", token)));
+        }
+        Ok(MetadataQuery {
+            columns,
+            condition,
+            order_by,
+            limit,
+        })
+    }
+
+    fn or(&mut self) -> Result<Condition> {
+        let mut condition = self.and()?;
+        while self.keyword("OR") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition> {
+        let mut condition = self.unary()?;
+        while self.keyword("AND") {
+            condition = Condition::And(Box::new(condition), Box::new(self.unary()?));
+        }
+        Ok(condition)
+    }
+
+    fn unary(&mut self) -> Result<Condition> {
+        if self.keyword("NOT") {
+            let condition = self.nested(Self::unary)?;
+            return Ok(Condition::Not(Box::new(condition)));
+        }
+        if self.symbol("(") {
+            let condition = self.nested(Self::or)?;
+            if !self.symbol(")") {
+                return Err(invalid("expected ')'"));
+            }
+            return Ok(condition);
+        }
+
+        let column = self.column()?;
+        if self.keyword("IS") {
+            let negated = self.keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(Condition::IsNull { column, negated });
+        }
+        let like = self.keyword("LIKE");
+        let op = if like {
+            None
+        } else {
+            Some(match self.next() {
+                Some(Token::Symbol("=")) => Comparison::Eq,
+                Some(Token::Symbol("!=")) | Some(Token::Symbol("<>")) => Comparison::Ne,
+                Some(Token::Symbol("<")) => Comparison::Lt,
+                Some(Token::Symbol("<=")) => Comparison::Le,
+                Some(Token::Symbol(">")) => Comparison::Gt,
+                Some(Token::Symbol(">=")) => Comparison::Ge,
+                _ => return Err(invalid(format!("expected a comparison after {}", column))),
+            })
+        };
+        let value = match self.next() {
+            Some(Token::Text(text)) | Some(Token::Number(text)) => text,
+            _ => {
+                return Err(invalid(format!(
+                    "expected a value to compare {} with",
+                    column
+                )))
+            }
+        };
+        Ok(match op {
+            Some(op) => Condition::Compare { column, op, value },
+            None => Condition::Like {
+                column,
+                pattern: LikePattern::new(value),
+            },
+        })
+    }
+
+    /// Parse a condition one level deeper, so hostile queries can't exhaust
+    /// the stack
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Condition>) -> Result<Condition> {
+        if self.depth >= MAX_CONDITION_DEPTH {
+            return Err(invalid(format!(
+                "conditions may nest at most {} levels deep",
+                MAX_CONDITION_DEPTH
+            )));
+        }
+        self.depth += 1;
+        let condition = parse(self);
+        self.depth -= 1;
+        condition
+    }
+}
+
+fn is_reserved(word: &str) -> bool {
+    [
+        "SELECT", "WHERE", "AND", "OR", "NOT", "ORDER", "BY", "ASC", "DESC", "LIMIT", "LIKE", "IS",
+        "NULL",
+    ]
+    .iter()
+    .any(|keyword| word.eq_ignore_ascii_case(keyword))
+}
+
+/// One indexed file with the metadata of its stored chunks
+struct Row {
+    path: String,
+    entry: FingerprintEntry,
+    chunk: Option<VectorMetadata>,
+}
+
+impl Row {
+    fn get(&self, column: &str) -> Option<String> {
+        let chunk = self.chunk.as_ref();
+        match column {
+            "path" => Some(self.path.clone()),
+            "document_id" => self
+                .entry
+                .document_id
+                .clone()
+                .or_else(|| chunk.map(|c| c.document_id.to_string())),
+            "title" => chunk.map(|c| c.title.clone()),
+            "chunks" => Some(self.entry.chunk_ids.len().to_string()),
+            "size" => Some(self.entry.fingerprint.size.to_string()),
+            "modified_at" => {
+                chrono::DateTime::from_timestamp_millis(self.entry.fingerprint.modified_ms)
+                    .map(timestamp)
+            }
+            "indexed_at" => Some(timestamp(self.entry.indexed_at)),
+            "embedding_model" => chunk.and_then(|c| c.embedding_model.clone()),
+            "hash" => Some(self.entry.fingerprint.hash.clone()),
+            TAG_COLUMN => self.get("tags"),
+            field => chunk.and_then(|c| c.custom.get(field).cloned()),
+        }
+    }
+}
+
+fn timestamp(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Order two values as numbers when both read as numbers, otherwise as text
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+fn matches_value(value: &str, op: Comparison, expected: &str) -> bool {
+    match op {
+        Comparison::Eq => compare_values(value, expected) == Ordering::Equal,
+        Comparison::Ne => compare_values(value, expected) != Ordering::Equal,
+        Comparison::Lt => compare_values(value, expected) == Ordering::Less,
+        Comparison::Le => compare_values(value, expected) != Ordering::Greater,
+        Comparison::Gt => compare_values(value, expected) == Ordering::Greater,
+        Comparison::Ge => compare_values(value, expected) != Ordering::Less,
+    }
+}
+
+impl Condition {
+    fn matches(&self, row: &Row) -> bool {
+        match self {
+            Condition::Compare { column, op, value } if column == TAG_COLUMN => {
+                let tags = row.get(TAG_COLUMN).unwrap_or_default();
+                let mut tags = tags.split(',').map(str::trim).filter(|t| !t.is_empty());
+                match op {
+                    Comparison::Ne => !tags.any(|tag| tag == value),
+                    op => tags.any(|tag| matches_value(tag, *op, value)),
+                }
+            }
+            // Missing fields match no comparison, as NULL does in SQL
+            Condition::Compare { column, op, value } => row
+                .get(column)
+                .is_some_and(|actual| matches_value(&actual, *op, value)),
+            Condition::Like { column, pattern } if column == TAG_COLUMN => row
+                .get(TAG_COLUMN)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .any(|tag| !tag.is_empty() && pattern.is_match(tag)),
+            Condition::Like { column, pattern } => row
+                .get(column)
+                .is_some_and(|actual| pattern.is_match(&actual)),
+            Condition::IsNull { column, negated } => row.get(column).is_none() != *negated,
+            Condition::Not(condition) => !condition.matches(row),
+            Condition::And(a, b) => a.matches(row) && b.matches(row),
+            Condition::Or(a, b) => a.matches(row) || b.matches(row),
+        }
+    }
+}
+
+/// Application service answering metadata queries
+#[derive(Clone)]
+pub struct MetadataQueryService {
+    vector_repository: Arc<dyn VectorRepository>,
+    fingerprint_registry: Arc<FingerprintRegistry>,
+    collection_aliases: Arc<CollectionAliases>,
+    access: AccessConfig,
+}
+
+impl MetadataQueryService {
+    /// Create a query service sharing the container's stores
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            vector_repository: container.vector_repository(),
+            fingerprint_registry: container.fingerprint_registry(),
+            collection_aliases: container.collection_aliases(),
+            access: container.config().access.clone(),
+        }
+    }
+
+    /// Run a query over the files indexed into a collection
+    pub async fn query(&self, collection: &str, query: &str) -> Result<MetadataQueryResult> {
+        let query = MetadataQuery::parse(query)?;
+        let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+        if limit > MAX_QUERY_LIMIT {
+            return Err(invalid(format!(
+                "LIMIT must be at most {}",
+                MAX_QUERY_LIMIT
+            )));
+        }
+
+        let stored = self.collection_aliases.resolve(collection);
+        let entries = self.fingerprint_registry.collection_entries(&stored);
+        if entries.is_empty() {
+            return Err(ZeroLatencyError::not_found(format!(
+                "indexed files of collection {}",
+                collection
+            )));
+        }
+
+        // The first chunk of each file carries the document's metadata
+        let mut chunks: HashMap<String, VectorMetadata> = HashMap::new();
+        for vector in self.vector_repository.collection_vectors(&stored).await? {
+            let Some(path) = vector.metadata.custom.get(PATH_KEY).cloned() else {
+                continue;
+            };
+            let first = chunks
+                .get(&path)
+                .is_none_or(|chunk| vector.metadata.chunk_index < chunk.chunk_index);
+            if first {
+                chunks.insert(path, vector.metadata);
+            }
+        }
+
+        // Files of documents the request principal may not read don't match,
+        // nor do files without chunks to read an ACL from
+        let principal = access_scope(&self.access);
+        let mut rows: Vec<Row> = entries
+            .into_iter()
+            .map(|(path, entry)| Row {
+                chunk: chunks.remove(&path),
+                path,
+                entry,
+            })
+            .filter(|row| {
+                principal.as_ref().is_none_or(|principal| {
+                    row.chunk
+                        .as_ref()
+                        .is_some_and(|chunk| principal.can_read(&chunk.custom))
+                })
+            })
+            .filter(|row| {
+                query
+                    .condition
+                    .as_ref()
+                    .is_none_or(|condition| condition.matches(row))
+            })
+            .collect();
+        let total_matched = rows.len();
+
+        if let Some((column, order)) = &query.order_by {
+            // Rows without the field sort last either way
+            rows.sort_by(|a, b| match (a.get(column), b.get(column)) {
+                (Some(a), Some(b)) => match order {
+                    SortOrder::Asc => compare_values(&a, &b),
+                    SortOrder::Desc => compare_values(&b, &a),
+                },
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            });
+        }
+        rows.truncate(limit);
+
+        let columns: Vec<String> = if query.columns.is_empty() {
+            BUILTIN_COLUMNS.iter().map(|c| c.to_string()).collect()
+        } else {
+            query.columns
+        };
+        let rows = rows
+            .iter()
+            .map(|row| columns.iter().map(|column| row.get(column)).collect())
+            .collect();
+
+        Ok(MetadataQueryResult {
+            collection: collection.to_string(),
+            columns,
+            rows,
+            total_matched,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::persistence::fingerprint_registry::FileFingerprint;
+
+    fn row(path: &str, modified_ms: i64, tags: Option<&str>) -> Row {
+        let mut custom = HashMap::from([(PATH_KEY.to_string(), path.to_string())]);
+        if let Some(tags) = tags {
+            custom.insert("tags".to_string(), tags.to_string());
+        }
+        Row {
+            path: path.to_string(),
+            entry: FingerprintEntry {
+                fingerprint: FileFingerprint::new(120, modified_ms, b"content"),
+                chunk_ids: vec!["a".to_string(), "b".to_string()],
+                chunk_hashes: Vec::new(),
+                document_id: None,
+                metadata_patch: Default::default(),
+                indexed_at: chrono::Utc::now(),
+            },
+            chunk: Some(VectorMetadata {
+                custom,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn matches(condition: &str, row: &Row) -> bool {
+        MetadataQuery::parse(&format!("SELECT * WHERE {}", condition))
+            .unwrap()
+            .condition
+            .unwrap()
+            .matches(row)
+    }
+
+    #[test]
+    fn test_parses_queries() {
+        let query = MetadataQuery::parse(
+            "select path, modified_at where tag='api' order by modified_at desc limit 50",
+        )
+        .unwrap();
+        assert_eq!(query.columns, vec!["path", "modified_at"]);
+        assert_eq!(
+            query.condition,
+            Some(Condition::Compare {
+                column: "tag".to_string(),
+                op: Comparison::Eq,
+                value: "api".to_string(),
+            })
+        );
+        assert_eq!(
+            query.order_by,
+            Some(("modified_at".to_string(), SortOrder::Desc))
+        );
+        assert_eq!(query.limit, Some(50));
+
+        let query = MetadataQuery::parse("SELECT *").unwrap();
+        assert!(query.columns.is_empty());
+        assert_eq!(query.condition, None);
+
+        for malformed in [
+            "path",
+            "SELECT",
+            "SELECT path WHERE",
+            "SELECT path WHERE tag = ",
+            "SELECT path WHERE tag = 'api",
+            "SELECT path LIMIT many",
+            "SELECT path WHERE (tag = 'a'",
+            "SELECT path; DROP TABLE documents",
+        ] {
+            assert!(MetadataQuery::parse(malformed).is_err(), "{}", malformed);
+        }
+
+        // Deep nesting is refused rather than overflowing the stack
+        let nested = |depth: usize| {
+            format!(
+                "SELECT path WHERE {}tag = 'api'{}",
+                "(".repeat(depth),
+                ")".repeat(depth)
+            )
+        };
+        assert!(MetadataQuery::parse(&nested(MAX_CONDITION_DEPTH)).is_ok());
+        assert!(MetadataQuery::parse(&nested(MAX_CONDITION_DEPTH + 1)).is_err());
+        let negated = format!(
+            "SELECT path WHERE {}tag = 'api'",
+            "NOT ".repeat(MAX_CONDITION_DEPTH + 1)
+        );
+        assert!(MetadataQuery::parse(&negated).is_err());
+        let long = format!(
+            "SELECT path WHERE title = '{}'",
+            "a".repeat(MAX_QUERY_LENGTH)
+        );
+        assert!(MetadataQuery::parse(&long).is_err());
+    }
+
+    #[test]
+    fn test_conditions() {
+        let guide = row("docs/api/auth.md", 1_700_000_000_000, Some("api, security"));
+
+        assert!(matches("tag = 'api'", &guide));
+        assert!(matches("tag = 'security'", &guide));
+        assert!(!matches("tag = 'guide'", &guide));
+        assert!(matches("tag != 'guide'", &guide));
+        assert!(matches("path LIKE 'docs/api/%'", &guide));
+        assert!(!matches("path LIKE 'docs/_.md'", &guide));
+        assert!(matches("size > 100 AND chunks = 2", &guide));
+        assert!(matches(
+            "size < 100 OR (chunks >= 2 AND NOT tag = 'guide')",
+            &guide
+        ));
+        assert!(matches("modified_at >= '2023-11-14'", &guide));
+        assert!(matches("owner IS NULL", &guide));
+        assert!(!matches("owner = 'docs-team'", &guide));
+        assert!(!matches("owner != 'docs-team'", &guide));
+        assert!(matches("title = ''", &guide));
+        assert!(matches("path = 'docs/api/it''s.md' OR tag = 'api'", &guide));
+        // Numbers compare numerically, not as text
+        assert!(matches("size < 1000", &guide));
+
+        let untagged = row("notes.txt", 0, None);
+        assert!(!matches("tag = 'api'", &untagged));
+        assert!(matches("tag != 'api'", &untagged));
+    }
+}
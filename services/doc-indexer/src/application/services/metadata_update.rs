@@ -0,0 +1,299 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+/// Bulk updates of custom metadata
+///
+/// Retags the documents of a collection that match a metadata filter without
+/// embedding anything again: the stored vectors are rewritten with the same
+/// ids and embeddings and only their metadata changed. The patch is also
+/// kept with each file's fingerprint, so it is applied again whenever the
+/// file is re-indexed.
+use std::path::Path;
+use std::sync::Arc;
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+use zero_latency_vector::{MetadataFilter, VectorDocument, VectorRepository, PATH_KEY};
+
+use crate::application::container::ServiceContainer;
+use crate::infrastructure::persistence::change_log::{Change, ChangeKind, ChangeLog};
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+use crate::infrastructure::persistence::fingerprint_registry::{
+    FingerprintRegistry, MetadataPatch,
+};
+use crate::infrastructure::persistence::metadata_schemas::MetadataSchemaRegistry;
+
+/// Fields the indexer maintains itself; patching them would detach vectors
+/// from their collection or source file
+const RESERVED_FIELDS: [&str; 4] = ["collection", PATH_KEY, "chunk_index", "parent_document_id"];
+
+/// A metadata patch and the documents it applies to
+#[derive(Debug, Clone, Deserialize)]
+pub struct MetadataUpdate {
+    /// Metadata patterns a document must match, as in search filters
+    pub filter: HashMap<String, String>,
+    #[serde(flatten)]
+    pub patch: MetadataPatch,
+}
+
+/// Outcome of a bulk metadata update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataUpdateReport {
+    pub collection: String,
+    pub documents_updated: usize,
+    pub chunks_updated: usize,
+}
+
+/// Application service patching the metadata of stored documents
+#[derive(Clone)]
+pub struct MetadataUpdateService {
+    vector_repository: Arc<dyn VectorRepository>,
+    fingerprint_registry: Arc<FingerprintRegistry>,
+    collection_aliases: Arc<CollectionAliases>,
+    metadata_schemas: Arc<MetadataSchemaRegistry>,
+    change_log: Arc<ChangeLog>,
+}
+
+impl MetadataUpdateService {
+    /// Create a metadata update service sharing the container's stores
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            vector_repository: container.vector_repository(),
+            fingerprint_registry: container.fingerprint_registry(),
+            collection_aliases: container.collection_aliases(),
+            metadata_schemas: container.metadata_schemas(),
+            change_log: container.change_log(),
+        }
+    }
+
+    /// Patch the metadata of every document in `collection` matching the filter
+    ///
+    /// A document matches when any of its chunks does, and then all of its
+    /// chunks are patched. Nothing is written unless the patched metadata of
+    /// every chunk conforms to the collection's schema.
+    pub async fn update(
+        &self,
+        collection: &str,
+        update: MetadataUpdate,
+    ) -> Result<MetadataUpdateReport> {
+        let MetadataUpdate { filter, patch } = update;
+        if filter.is_empty() {
+            return Err(ZeroLatencyError::validation(
+                "filter",
+                "Set at least one metadata pattern",
+            ));
+        }
+        if patch.is_empty() {
+            return Err(ZeroLatencyError::validation(
+                "set",
+                "Set or remove at least one field",
+            ));
+        }
+        if let Some(field) = patch.fields().find(|f| RESERVED_FIELDS.contains(f)) {
+            return Err(ZeroLatencyError::validation(
+                field,
+                "Field is maintained by the indexer and cannot be patched",
+            ));
+        }
+        let filter = MetadataFilter::compile(&filter)?;
+
+        let vectors = self
+            .vector_repository
+            .collection_vectors(&self.collection_aliases.resolve(collection))
+            .await?;
+        if vectors.is_empty() {
+            return Err(ZeroLatencyError::not_found(format!(
+                "vectors of collection {}",
+                collection
+            )));
+        }
+        let matched: HashSet<Uuid> = vectors
+            .iter()
+            .filter(|vector| filter.matches_metadata(&vector.metadata))
+            .map(|vector| vector.metadata.document_id)
+            .collect();
+
+        let schema = self.metadata_schemas.get(collection);
+        let mut patched = Vec::new();
+        for vector in vectors {
+            if !matched.contains(&vector.metadata.document_id) {
+                continue;
+            }
+            let mut metadata = vector.metadata;
+            patch.apply(&mut metadata.custom);
+            if let Some(violation) = schema
+                .as_ref()
+                .and_then(|schema| schema.validate(&metadata.custom).into_iter().next())
+            {
+                return Err(ZeroLatencyError::validation(
+                    format!("metadata.{}", violation.field),
+                    violation.message,
+                ));
+            }
+            patched.push(VectorDocument {
+                id: vector.id,
+                embedding: vector.embedding,
+                named_vectors: vector.named_vectors,
+                metadata,
+            });
+        }
+
+        // Source path of each patched document, for its fingerprint and change event
+        let documents: HashMap<Uuid, Option<String>> = patched
+            .iter()
+            .map(|vector| {
+                (
+                    vector.metadata.document_id,
+                    vector.metadata.custom.get(PATH_KEY).cloned(),
+                )
+            })
+            .collect();
+        let chunks_updated = patched.len();
+        if chunks_updated > 0 {
+            self.vector_repository.upsert_batch(patched).await?;
+        }
+
+        for (document_id, path) in &documents {
+            if let Some(path) = path {
+                self.fingerprint_registry
+                    .patch_metadata(collection, Path::new(path), &patch);
+            }
+            self.change_log.append(Change {
+                kind: ChangeKind::DocumentUpdated,
+                collection: Some(collection.to_string()),
+                document_id: Some(document_id.to_string()),
+                path: path.clone(),
+                churn: None,
+            });
+        }
+        if let Err(e) = self.fingerprint_registry.save() {
+            tracing::warn!("Failed to persist fingerprint registry: {}", e);
+        }
+
+        tracing::info!(
+            "Patched metadata of {} documents ({} chunks) in collection '{}'",
+            documents.len(),
+            chunks_updated,
+            collection
+        );
+        Ok(MetadataUpdateReport {
+            collection: collection.to_string(),
+            documents_updated: documents.len(),
+            chunks_updated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, VectorBackend};
+    use crate::infrastructure::persistence::embeddings::mock_adapter::MockEmbeddingConfig;
+    use crate::infrastructure::MockEmbeddingAdapter;
+    use zero_latency_vector::VectorMetadata;
+
+    fn chunk(document_id: Uuid, path: &str, team: &str) -> VectorDocument {
+        let custom = [
+            ("collection", "docs"),
+            (PATH_KEY, path),
+            ("team", team),
+            ("draft", "true"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        VectorDocument {
+            id: Uuid::new_v4(),
+            embedding: vec![0.25; 4],
+            named_vectors: Default::default(),
+            metadata: VectorMetadata {
+                document_id,
+                content: format!("a chunk of {}", path),
+                collection: Some("docs".to_string()),
+                custom,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn update(filter: &[(&str, &str)], set: &[(&str, &str)], remove: &[&str]) -> MetadataUpdate {
+        let pairs = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        MetadataUpdate {
+            filter: pairs(filter),
+            patch: MetadataPatch {
+                set: pairs(set),
+                remove: remove.iter().map(|k| k.to_string()).collect(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_patches_matching_documents_in_place() {
+        let mut config = Config::default();
+        config.vector.backend = VectorBackend::Memory;
+        let container = ServiceContainer::with_embedding_generator(
+            config,
+            Arc::new(
+                MockEmbeddingAdapter::new(MockEmbeddingConfig {
+                    dimension: 4,
+                    seed: 7,
+                })
+                .unwrap(),
+            ),
+        )
+        .await
+        .unwrap();
+        let repository = container.vector_repository();
+
+        let (guide, other) = (Uuid::new_v4(), Uuid::new_v4());
+        repository
+            .insert(vec![
+                chunk(guide, "/docs/guides/setup.md", "core"),
+                // A second chunk of the same document that the filter alone misses
+                chunk(guide, "/docs/guides/setup.md", "infra"),
+                chunk(other, "/docs/api/index.md", "core"),
+            ])
+            .await
+            .unwrap();
+
+        let service = MetadataUpdateService::new(&container);
+        let report = service
+            .update(
+                "docs",
+                update(
+                    &[(PATH_KEY, "guides/*"), ("team", "core")],
+                    &[("section", "handbook")],
+                    &["draft"],
+                ),
+            )
+            .await
+            .unwrap();
+        assert_eq!((report.documents_updated, report.chunks_updated), (1, 2));
+
+        for vector in repository.collection_vectors("docs").await.unwrap() {
+            let custom = &vector.metadata.custom;
+            if vector.metadata.document_id == guide {
+                assert_eq!(custom.get("section").map(String::as_str), Some("handbook"));
+                assert!(!custom.contains_key("draft"));
+                assert_eq!(vector.embedding, vec![0.25; 4]);
+            } else {
+                assert!(!custom.contains_key("section"));
+            }
+        }
+
+        // Reserved fields and empty filters are rejected
+        assert!(service
+            .update(
+                "docs",
+                update(&[("team", "core")], &[(PATH_KEY, "/x")], &[])
+            )
+            .await
+            .is_err());
+        assert!(service
+            .update("docs", update(&[], &[("section", "x")], &[]))
+            .await
+            .is_err());
+    }
+}
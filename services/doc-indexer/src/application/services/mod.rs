@@ -1,8 +1,35 @@
+pub mod admin_service;
+pub mod blocklist_service;
+pub mod browse_service;
+pub mod build_info;
+pub mod capabilities;
+pub mod chunk_sample;
+pub mod collection_cache;
+pub mod collection_health;
 pub mod collection_service;
+pub mod cost_estimate;
+pub mod curation_service;
+pub mod digest;
+pub mod document_outline;
 /// Application services module
 ///
 /// Contains the business logic services that coordinate domain operations.
 pub mod document_service;
+pub mod duplicate_report;
+pub mod embedding_migration;
 pub mod filter_service;
 pub mod health_service;
+pub mod indexing_pipeline;
+pub mod indexing_report;
 pub mod indexing_service;
+pub mod job_service;
+pub mod lookup_service;
+pub mod metadata_query;
+pub mod metadata_update;
+pub mod projection_service;
+pub mod raw_document;
+pub mod session_service;
+pub mod summary_service;
+pub mod throttle;
+pub mod vector_staleness;
+pub mod webhook_service;
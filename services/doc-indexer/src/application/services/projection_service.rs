@@ -0,0 +1,311 @@
+/// 2D projections of a collection's embeddings
+///
+/// Clusters of navigation menus, license headers or other boilerplate degrade
+/// search without showing up in any one result. Projecting a collection's
+/// vectors to two dimensions makes them visible on a scatter plot. A
+/// projection runs as a background job over a random sample of the
+/// collection's chunks, and the latest one is cached for export as JSON or
+/// CSV.
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use zero_latency_core::{Result, Utc, ZeroLatencyError};
+use zero_latency_search::{ACL_GROUPS_KEY, ACL_USERS_KEY};
+use zero_latency_vector::{ProjectionMethod, VectorDocument, VectorRepository};
+
+use crate::application::container::ServiceContainer;
+use crate::application::services::indexing_pipeline::{PipelineProgress, PipelineStage};
+use crate::config::AccessConfig;
+use crate::infrastructure::api::http::principal::access_scope;
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+use crate::infrastructure::persistence::projection_cache::{
+    ProjectedPoint, Projection, ProjectionCache,
+};
+
+/// Characters of chunk content kept as a point's label
+const LABEL_CHARS: usize = 80;
+
+/// Outcome of a completed projection job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectionReport {
+    pub collection: String,
+    pub method: ProjectionMethod,
+    pub points: usize,
+    /// Chunks left out because their dimension differs from the collection's
+    pub skipped: usize,
+    pub duration_ms: f64,
+}
+
+/// A collection's sampled vectors, loaded before projecting them
+pub struct ProjectionPlan {
+    pub collection: String,
+    pub method: ProjectionMethod,
+    seed: u64,
+    total_chunks: usize,
+    skipped: usize,
+    vectors: Vec<VectorDocument>,
+}
+
+impl ProjectionPlan {
+    /// Number of chunks that will be projected
+    pub fn point_count(&self) -> usize {
+        self.vectors.len()
+    }
+}
+
+/// Application service projecting collections for plotting
+#[derive(Clone)]
+pub struct ProjectionService {
+    vector_repository: Arc<dyn VectorRepository>,
+    collection_aliases: Arc<CollectionAliases>,
+    projections: Arc<ProjectionCache>,
+    access: AccessConfig,
+}
+
+impl ProjectionService {
+    /// Create a projection service sharing the container's stores
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            vector_repository: container.vector_repository(),
+            collection_aliases: container.collection_aliases(),
+            projections: container.projections(),
+            access: container.config().access.clone(),
+        }
+    }
+
+    /// Load up to `max_points` random chunks of a collection to project
+    ///
+    /// Chunks whose dimension differs from most of the collection's, as
+    /// during an embedding migration, are left out. Fails if the collection
+    /// holds no vectors.
+    pub async fn plan(
+        &self,
+        collection: &str,
+        method: ProjectionMethod,
+        max_points: usize,
+        seed: Option<u64>,
+    ) -> Result<ProjectionPlan> {
+        let vectors = self
+            .vector_repository
+            .collection_vectors(&self.collection_aliases.resolve(collection))
+            .await?;
+        if vectors.is_empty() {
+            return Err(ZeroLatencyError::not_found(format!(
+                "vectors of collection {}",
+                collection
+            )));
+        }
+        let total_chunks = vectors.len();
+
+        let mut dimensions: HashMap<usize, usize> = HashMap::new();
+        for vector in &vectors {
+            *dimensions.entry(vector.embedding.len()).or_default() += 1;
+        }
+        let dimension = dimensions
+            .into_iter()
+            .max_by_key(|&(dimension, count)| (count, dimension))
+            .map(|(dimension, _)| dimension)
+            .unwrap_or_default();
+        let mut vectors: Vec<VectorDocument> = vectors
+            .into_iter()
+            .filter(|vector| vector.embedding.len() == dimension)
+            .collect();
+        let skipped = total_chunks - vectors.len();
+
+        let seed = seed.unwrap_or_else(rand::random);
+        if vectors.len() > max_points {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut indices =
+                rand::seq::index::sample(&mut rng, vectors.len(), max_points).into_vec();
+            indices.sort_unstable();
+            let mut vectors_by_index: Vec<Option<VectorDocument>> =
+                vectors.into_iter().map(Some).collect();
+            vectors = indices
+                .into_iter()
+                .filter_map(|index| vectors_by_index[index].take())
+                .collect();
+        }
+
+        Ok(ProjectionPlan {
+            collection: collection.to_string(),
+            method,
+            seed,
+            total_chunks,
+            skipped,
+            vectors,
+        })
+    }
+
+    /// Project a plan's vectors and cache the result
+    ///
+    /// Progress is published to `progress`: the `read` stage counts the
+    /// chunks loaded, `embed` the chunks placed once the layout is done.
+    pub async fn project(
+        &self,
+        plan: ProjectionPlan,
+        progress: Arc<PipelineProgress>,
+    ) -> Result<ProjectionReport> {
+        let started = Instant::now();
+        progress.record(
+            PipelineStage::Read,
+            plan.point_count() as u64,
+            Duration::ZERO,
+        );
+
+        let ProjectionPlan {
+            collection,
+            method,
+            seed,
+            total_chunks,
+            skipped,
+            vectors,
+        } = plan;
+        // Layouts are CPU bound and quadratic in the number of points
+        let (vectors, layout) = tokio::task::spawn_blocking(move || {
+            let embeddings: Vec<Vec<f32>> = vectors
+                .iter()
+                .map(|vector| vector.embedding.clone())
+                .collect();
+            let layout = method.project(&embeddings, seed);
+            (vectors, layout)
+        })
+        .await
+        .map_err(|e| ZeroLatencyError::internal(format!("Projection failed: {}", e)))?;
+        progress.record(PipelineStage::Embed, layout.len() as u64, started.elapsed());
+
+        let points: Vec<ProjectedPoint> = vectors
+            .into_iter()
+            .zip(layout)
+            .map(|(vector, [x, y])| ProjectedPoint {
+                x,
+                y,
+                chunk_id: vector.id,
+                document_id: vector.metadata.document_id,
+                chunk_index: vector.metadata.chunk_index,
+                path: vector.metadata.custom.get("path").cloned(),
+                label: label(&vector.metadata.content),
+                title: vector.metadata.title,
+                acl: vector
+                    .metadata
+                    .custom
+                    .into_iter()
+                    .filter(|(key, _)| key == ACL_USERS_KEY || key == ACL_GROUPS_KEY)
+                    .collect(),
+            })
+            .collect();
+        let duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+        let report = ProjectionReport {
+            collection: collection.clone(),
+            method,
+            points: points.len(),
+            skipped,
+            duration_ms,
+        };
+
+        self.projections.store(Projection {
+            collection,
+            method,
+            seed,
+            total_chunks,
+            computed_at: Utc::now(),
+            duration_ms,
+            points,
+        })?;
+        Ok(report)
+    }
+
+    /// The latest projection of a collection
+    ///
+    /// Points of documents the current principal may not read are left out.
+    pub fn cached(&self, collection: &str) -> Result<Projection> {
+        let mut projection = self.projections.get(collection)?.ok_or_else(|| {
+            ZeroLatencyError::not_found(format!("projection of collection {}", collection))
+        })?;
+        if let Some(principal) = access_scope(&self.access) {
+            projection
+                .points
+                .retain(|point| principal.can_read(&point.acl));
+        }
+        Ok(projection)
+    }
+}
+
+// Start of a chunk's content on one line
+fn label(content: &str) -> String {
+    let text = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > LABEL_CHARS {
+        format!("{}...", text.chars().take(LABEL_CHARS).collect::<String>())
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, VectorBackend};
+    use crate::infrastructure::persistence::embeddings::mock_adapter::MockEmbeddingConfig;
+    use crate::infrastructure::MockEmbeddingAdapter;
+    use zero_latency_core::Uuid;
+    use zero_latency_vector::VectorMetadata;
+
+    #[tokio::test]
+    async fn test_projects_sampled_chunks() {
+        let mut config = Config::default();
+        config.vector.backend = VectorBackend::Memory;
+        let container = ServiceContainer::with_embedding_generator(
+            config,
+            Arc::new(
+                MockEmbeddingAdapter::new(MockEmbeddingConfig {
+                    dimension: 3,
+                    seed: 7,
+                })
+                .unwrap(),
+            ),
+        )
+        .await
+        .unwrap();
+        let chunk = |i: usize, embedding: Vec<f32>| VectorDocument {
+            id: Uuid::new_v4(),
+            embedding,
+            named_vectors: Default::default(),
+            metadata: VectorMetadata {
+                document_id: Uuid::new_v4(),
+                content: format!("Chunk\n  number {}", i),
+                collection: Some("docs".to_string()),
+                ..Default::default()
+            },
+        };
+        let mut vectors: Vec<VectorDocument> = (0..12)
+            .map(|i| chunk(i, vec![1.0, i as f32 * 0.1, (i % 3) as f32]))
+            .collect();
+        // A leftover of another dimension is skipped
+        vectors.push(chunk(12, vec![1.0, 0.0]));
+        container.vector_repository().insert(vectors).await.unwrap();
+
+        let service = ProjectionService::new(&container);
+        assert!(service.cached("docs").is_err());
+        let plan = service
+            .plan("docs", ProjectionMethod::Umap, 10, Some(3))
+            .await
+            .unwrap();
+        assert_eq!(plan.point_count(), 10);
+        let report = service
+            .project(plan, Arc::new(PipelineProgress::new()))
+            .await
+            .unwrap();
+        assert_eq!((report.points, report.skipped), (10, 1));
+
+        let projection = service.cached("docs").unwrap();
+        assert_eq!(projection.total_chunks, 13);
+        assert_eq!(projection.points.len(), 10);
+        assert!(projection.points[0].label.starts_with("Chunk number"));
+        assert!(service
+            .plan("missing", ProjectionMethod::Pca, 10, None)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,430 @@
+/// Source files behind indexed documents
+///
+/// Search results point at chunks, but UIs and RAG pipelines often want the
+/// whole file a result came from. Only files recorded in a collection's
+/// fingerprint registry can be fetched, so the endpoint never exposes
+/// anything the indexer didn't read. Files are served as they are on disk
+/// now, which may differ from what was indexed; `stale` says so. Files of
+/// documents the request principal may not read are not found.
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+use zero_latency_vector::{VectorRepository, PATH_KEY};
+
+use crate::application::container::ServiceContainer;
+use crate::application::content_processing::ContentTypeDetector;
+use crate::config::AccessConfig;
+use crate::infrastructure::api::http::principal::access_scope;
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+use crate::infrastructure::persistence::fingerprint_registry::{
+    FingerprintEntry, FingerprintRegistry,
+};
+
+/// Bytes of a file sniffed for its content type when the extension is unknown
+const SNIFF_BYTES: usize = 4096;
+
+/// Bytes read at a time while hashing a file
+const HASH_BUFFER_BYTES: usize = 64 * 1024;
+
+/// An indexed file on disk, opened for reading
+#[derive(Debug, Clone)]
+pub struct RawDocument {
+    /// Path the file was indexed from
+    pub path: PathBuf,
+    /// Size of the file in bytes
+    pub len: u64,
+    /// MIME type, with a UTF-8 charset for text whose first bytes decode as
+    /// UTF-8
+    pub content_type: String,
+    /// Hash of the current content, usable as an ETag
+    pub hash: String,
+    /// Whether the file changed since it was last indexed
+    pub stale: bool,
+}
+
+impl RawDocument {
+    /// `Content-Disposition` value naming the file for clients that save it
+    ///
+    /// Names outside printable ASCII are also given in RFC 8187 encoding,
+    /// with an ASCII fallback for clients that don't read it.
+    pub fn content_disposition(&self) -> String {
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let fallback: String = name
+            .chars()
+            .map(|c| match c {
+                '"' | '\\' => '_',
+                c if c.is_ascii_graphic() || c == ' ' => c,
+                _ => '_',
+            })
+            .collect();
+        if fallback == name {
+            return format!("inline; filename=\"{}\"", name);
+        }
+
+        let encoded: String = name
+            .bytes()
+            .map(|byte| {
+                if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+                    (byte as char).to_string()
+                } else {
+                    format!("%{:02X}", byte)
+                }
+            })
+            .collect();
+        format!(
+            "inline; filename=\"{}\"; filename*=UTF-8''{}",
+            fallback, encoded
+        )
+    }
+}
+
+/// Part of a file a `Range` header asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum ByteRange {
+    /// No usable range; send the whole file
+    Full,
+    /// Send these bytes
+    Partial(Range<u64>),
+    /// The range starts past the end of the file
+    Unsatisfiable,
+}
+
+impl ByteRange {
+    /// Parse a `Range` header for a file of `len` bytes
+    ///
+    /// Only a single `bytes` range is honored. Multiple ranges and malformed
+    /// headers fall back to the whole file, which RFC 9110 allows.
+    pub fn parse(header: Option<&str>, len: u64) -> Self {
+        let Some(spec) = header.and_then(|header| header.trim().strip_prefix("bytes=")) else {
+            return Self::Full;
+        };
+        if spec.contains(',') {
+            return Self::Full;
+        }
+        let Some((start, end)) = spec.trim().split_once('-') else {
+            return Self::Full;
+        };
+        let (start, end) = (start.trim(), end.trim());
+
+        let range = if start.is_empty() {
+            // Suffix range: the last `end` bytes
+            match end.parse::<u64>() {
+                Ok(0) => return Self::Unsatisfiable,
+                Ok(suffix) => len.saturating_sub(suffix)..len,
+                Err(_) => return Self::Full,
+            }
+        } else {
+            let Ok(start) = start.parse::<u64>() else {
+                return Self::Full;
+            };
+            let end = match end {
+                "" => len,
+                end => match end.parse::<u64>() {
+                    Ok(end) if end >= start => end.saturating_add(1).min(len),
+                    _ => return Self::Full,
+                },
+            };
+            start..end
+        };
+
+        if range.start >= len {
+            Self::Unsatisfiable
+        } else {
+            Self::Partial(range)
+        }
+    }
+}
+
+/// Reads the source files of indexed documents
+#[derive(Clone)]
+pub struct RawDocumentService {
+    fingerprint_registry: Arc<FingerprintRegistry>,
+    collection_aliases: Arc<CollectionAliases>,
+    vector_repository: Arc<dyn VectorRepository>,
+    access: AccessConfig,
+    max_document_size: u64,
+}
+
+impl RawDocumentService {
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            fingerprint_registry: container.fingerprint_registry(),
+            collection_aliases: container.collection_aliases(),
+            vector_repository: container.vector_repository(),
+            access: container.config().access.clone(),
+            max_document_size: container.config().service.max_document_size as u64,
+        }
+    }
+
+    /// Open the file of `collection` found at `path`
+    ///
+    /// `path` may be the indexed path or any trailing part of it that starts
+    /// at a directory boundary, as long as only one file matches. Files over
+    /// the maximum document size are refused. Content is only read to hash a
+    /// file that changed since it was indexed; [`Self::read`] fetches it.
+    pub async fn open(&self, collection: &str, path: &str) -> Result<RawDocument> {
+        let stored = self.collection_aliases.resolve(collection);
+        let (indexed, entry) = self.resolve(&stored, path)?;
+        if !self.readable(&stored, &indexed, &entry).await? {
+            return Err(ZeroLatencyError::not_found(format!(
+                "indexed file {} in collection {}",
+                path, collection
+            )));
+        }
+        let path = PathBuf::from(&indexed);
+        let read_error = |e: std::io::Error| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                ZeroLatencyError::not_found(format!("source file {}", indexed))
+            }
+            _ => ZeroLatencyError::internal(format!("Failed to read {}: {}", indexed, e)),
+        };
+
+        let mut file = tokio::fs::File::open(&path).await.map_err(read_error)?;
+        let metadata = file.metadata().await.map_err(read_error)?;
+        let len = metadata.len();
+        if len > self.max_document_size {
+            return Err(ZeroLatencyError::validation(
+                "path",
+                format!(
+                    "{} is {} bytes, over the maximum document size of {} bytes",
+                    path.display(),
+                    len,
+                    self.max_document_size
+                ),
+            ));
+        }
+
+        // Unchanged size and mtime means the indexed hash still holds, as
+        // when indexing skips a file
+        let modified_ms = metadata
+            .modified()
+            .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp_millis())
+            .unwrap_or(0);
+        let hash = if len == entry.fingerprint.size && modified_ms == entry.fingerprint.modified_ms
+        {
+            entry.fingerprint.hash.clone()
+        } else {
+            let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+            let mut buffer = vec![0; HASH_BUFFER_BYTES];
+            loop {
+                let read = file.read(&mut buffer).await.map_err(read_error)?;
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&buffer[..read]);
+            }
+            format!("{:016x}", hasher.digest())
+        };
+
+        let mut sniffed = Vec::new();
+        file.rewind().await.map_err(read_error)?;
+        (&mut file)
+            .take(SNIFF_BYTES as u64)
+            .read_to_end(&mut sniffed)
+            .await
+            .map_err(read_error)?;
+
+        Ok(RawDocument {
+            content_type: content_type(&path, &sniffed),
+            stale: hash != entry.fingerprint.hash,
+            path,
+            len,
+            hash,
+        })
+    }
+
+    /// Read `range` of an opened file
+    pub async fn read(&self, document: &RawDocument, range: Range<u64>) -> Result<Vec<u8>> {
+        let read_error = |e: std::io::Error| {
+            ZeroLatencyError::internal(format!("Failed to read {}: {}", document.path.display(), e))
+        };
+        let mut file = tokio::fs::File::open(&document.path)
+            .await
+            .map_err(read_error)?;
+        file.seek(std::io::SeekFrom::Start(range.start))
+            .await
+            .map_err(read_error)?;
+        let mut content = Vec::with_capacity((range.end - range.start) as usize);
+        file.take(range.end - range.start)
+            .read_to_end(&mut content)
+            .await
+            .map_err(read_error)?;
+        Ok(content)
+    }
+
+    /// Whether the request principal may read the document indexed from
+    /// `indexed`
+    ///
+    /// The ACL is read from the document's first chunk; a file whose chunks
+    /// can't be found is only readable when access control is off.
+    async fn readable(
+        &self,
+        collection: &str,
+        indexed: &str,
+        entry: &FingerprintEntry,
+    ) -> Result<bool> {
+        let Some(principal) = access_scope(&self.access) else {
+            return Ok(true);
+        };
+        let document_id = entry
+            .document_id
+            .as_deref()
+            .and_then(|id| Uuid::parse_str(id).ok());
+        let metadata = match document_id {
+            Some(document_id) => self
+                .vector_repository
+                .document_chunks(collection, document_id, 0..=0)
+                .await?
+                .into_iter()
+                .next()
+                .map(|(_, metadata)| metadata),
+            // Entries recorded before document ids were kept
+            None => self
+                .vector_repository
+                .collection_vectors(collection)
+                .await?
+                .into_iter()
+                .find(|vector| {
+                    vector.metadata.custom.get(PATH_KEY).map(String::as_str) == Some(indexed)
+                })
+                .map(|vector| vector.metadata),
+        };
+        Ok(metadata.is_some_and(|metadata| principal.can_read(&metadata.custom)))
+    }
+
+    fn resolve(&self, collection: &str, path: &str) -> Result<(String, FingerprintEntry)> {
+        let wanted = normalize(path);
+        if wanted.is_empty() || wanted.split('/').any(|part| part == "..") {
+            return Err(ZeroLatencyError::validation("path", "must name a file"));
+        }
+
+        let mut matches: Vec<_> = self
+            .fingerprint_registry
+            .collection_entries(collection)
+            .into_iter()
+            .filter(|(indexed, _)| {
+                let indexed = normalize(indexed);
+                indexed == wanted || indexed.ends_with(&format!("/{}", wanted))
+            })
+            .collect();
+        // An exact match wins over files that merely end the same way
+        if let Some(exact) = matches
+            .iter()
+            .position(|(indexed, _)| normalize(indexed) == wanted)
+        {
+            return Ok(matches.swap_remove(exact));
+        }
+        match matches.len() {
+            0 => Err(ZeroLatencyError::not_found(format!(
+                "indexed file {} in collection {}",
+                path, collection
+            ))),
+            1 => Ok(matches.remove(0)),
+            n => Err(ZeroLatencyError::validation(
+                "path",
+                format!("matches {} indexed files; give more of the path", n),
+            )),
+        }
+    }
+}
+
+/// Compare paths without leading `/` or `./`
+fn normalize(path: &str) -> &str {
+    let path = path.trim_start_matches('/');
+    path.strip_prefix("./").unwrap_or(path)
+}
+
+// Content type of a file from its first bytes; a UTF-8 sequence cut off at
+// the end of `sniffed` still counts as UTF-8
+fn content_type(path: &std::path::Path, sniffed: &[u8]) -> String {
+    let utf8 = match std::str::from_utf8(sniffed) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    let mime = ContentTypeDetector::detect_content_type(path, &String::from_utf8_lossy(sniffed))
+        .mime_type();
+    if mime.starts_with("text/") && utf8 {
+        format!("{}; charset=utf-8", mime)
+    } else {
+        mime.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_byte_ranges() {
+        let parse = |header: &str| ByteRange::parse(Some(header), 100);
+
+        assert_eq!(ByteRange::parse(None, 100), ByteRange::Full);
+        assert_eq!(parse("bytes=0-9"), ByteRange::Partial(0..10));
+        assert_eq!(parse("bytes=90-"), ByteRange::Partial(90..100));
+        assert_eq!(parse("bytes=-10"), ByteRange::Partial(90..100));
+        assert_eq!(parse("bytes=-500"), ByteRange::Partial(0..100));
+        assert_eq!(parse("bytes=50-500"), ByteRange::Partial(50..100));
+        assert_eq!(
+            parse("bytes=0-18446744073709551615"),
+            ByteRange::Partial(0..100)
+        );
+        assert_eq!(parse("bytes=100-"), ByteRange::Unsatisfiable);
+        assert_eq!(parse("bytes=-0"), ByteRange::Unsatisfiable);
+        assert_eq!(parse("bytes=0-1,5-6"), ByteRange::Full);
+        assert_eq!(parse("bytes=9-0"), ByteRange::Full);
+        assert_eq!(parse("items=0-9"), ByteRange::Full);
+        assert_eq!(
+            ByteRange::parse(Some("bytes=0-"), 0),
+            ByteRange::Unsatisfiable
+        );
+    }
+
+    #[test]
+    fn test_names_the_file_in_content_disposition() {
+        let document = |path: &str| RawDocument {
+            path: PathBuf::from(path),
+            len: 0,
+            content_type: "text/plain".to_string(),
+            hash: String::new(),
+            stale: false,
+        };
+
+        assert_eq!(
+            document("/docs/guides/setup.md").content_disposition(),
+            "inline; filename=\"setup.md\""
+        );
+        assert_eq!(
+            document("/docs/say \"hi\".md").content_disposition(),
+            "inline; filename=\"say _hi_.md\"; filename*=UTF-8''say%20%22hi%22.md"
+        );
+        assert_eq!(
+            document("/docs/résumé.md").content_disposition(),
+            "inline; filename=\"r_sum_.md\"; filename*=UTF-8''r%C3%A9sum%C3%A9.md"
+        );
+    }
+
+    #[test]
+    fn test_detects_content_types() {
+        let markdown = std::path::Path::new("guide.md");
+        assert_eq!(
+            content_type(markdown, b"# Guide"),
+            "text/markdown; charset=utf-8"
+        );
+        // Text that isn't UTF-8 gets no charset
+        assert_eq!(content_type(markdown, &[0x23, 0xff, 0xfe]), "text/markdown");
+        assert_eq!(
+            content_type(markdown, "# Résumé".as_bytes().split_last().unwrap().1),
+            "text/markdown; charset=utf-8"
+        );
+        assert_eq!(
+            content_type(std::path::Path::new("openapi.yaml"), b"a: 1"),
+            "application/yaml"
+        );
+    }
+}
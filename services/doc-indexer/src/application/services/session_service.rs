@@ -0,0 +1,242 @@
+/// Conversation-aware search sessions
+///
+/// A session remembers the recent queries of one conversation and the
+/// results the user opened. Searches made through it are conditioned on that
+/// history: follow-ups such as "how do I delete one?" are searched with the
+/// topic of the turns before them, and documents already selected rank
+/// higher. Sessions expire after a period without activity.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+use zero_latency_search::{ConversationContext, ConversationTurn, SearchResponse, SearchResult};
+
+/// Most sessions kept at once; the least recently active is dropped first
+const MAX_SESSIONS: usize = 10_000;
+
+/// Queries remembered per session
+const MAX_TURNS: usize = 10;
+
+/// State of one session
+#[derive(Debug, Clone)]
+struct SearchSession {
+    created_at: chrono::DateTime<chrono::Utc>,
+    last_active: chrono::DateTime<chrono::Utc>,
+    context: ConversationContext,
+}
+
+/// A session as returned by the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionView {
+    pub session_id: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_active: chrono::DateTime<chrono::Utc>,
+    /// When the session expires unless it is used again
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    /// Recent queries, oldest first
+    pub turns: Vec<ConversationTurn>,
+    /// Documents the user opened, most recent first
+    pub selected_documents: Vec<String>,
+}
+
+/// Search results from within a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSearchResponse {
+    pub session_id: String,
+    /// Query that was searched, when the session's topic was added to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contextualized_query: Option<String>,
+    /// Results ranked higher because the user selected them earlier
+    pub boosted_results: usize,
+    #[serde(flatten)]
+    pub search: SearchResponse,
+}
+
+/// Service holding search sessions in memory
+#[derive(Clone)]
+pub struct SessionService {
+    sessions: Arc<tokio::sync::RwLock<HashMap<String, SearchSession>>>,
+    ttl: Duration,
+}
+
+impl SessionService {
+    /// Create a session service whose sessions expire after `ttl` idle
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            sessions: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            ttl,
+        }
+    }
+
+    /// Start a new session
+    pub async fn create(&self) -> SessionView {
+        let now = chrono::Utc::now();
+        let id = Uuid::new_v4().to_string();
+        let session = SearchSession {
+            created_at: now,
+            last_active: now,
+            context: ConversationContext::new(MAX_TURNS),
+        };
+        let view = self.view(&id, &session);
+
+        let mut sessions = self.sessions.write().await;
+        self.purge_expired(&mut sessions);
+        if sessions.len() >= MAX_SESSIONS {
+            let idle = sessions
+                .iter()
+                .min_by_key(|(_, session)| session.last_active)
+                .map(|(id, _)| id.clone());
+            if let Some(idle) = idle {
+                sessions.remove(&idle);
+            }
+        }
+        sessions.insert(id, session);
+        view
+    }
+
+    /// Look up a live session
+    pub async fn get(&self, id: &str) -> Result<SessionView> {
+        let sessions = self.sessions.read().await;
+        let session = self.live(&sessions, id)?;
+        Ok(self.view(id, session))
+    }
+
+    /// End a session
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        self.purge_expired(&mut sessions);
+        sessions
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("session {}", id)))
+    }
+
+    /// The query to search for `query` in a session, if its history
+    /// changes it
+    pub async fn contextualize(&self, id: &str, query: &str) -> Result<Option<String>> {
+        let sessions = self.sessions.read().await;
+        let session = self.live(&sessions, id)?;
+        Ok(session.context.contextualize(query))
+    }
+
+    /// Record a search made in a session and rank its results with the
+    /// session's selections; returns how many results were boosted
+    pub async fn record_search(
+        &self,
+        id: &str,
+        query: &str,
+        contextualized_query: Option<String>,
+        results: &mut [SearchResult],
+    ) -> Result<usize> {
+        let mut sessions = self.sessions.write().await;
+        self.purge_expired(&mut sessions);
+        let session = sessions
+            .get_mut(id)
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("session {}", id)))?;
+        session.last_active = chrono::Utc::now();
+        session.context.record_query(query, contextualized_query);
+        Ok(session.context.rerank(results))
+    }
+
+    /// Record that the user opened a search result
+    pub async fn record_selection(&self, id: &str, document_id: &str) -> Result<SessionView> {
+        let document_id = Uuid::parse_str(document_id)
+            .map_err(|_| ZeroLatencyError::validation("document_id", "must be a UUID"))?;
+
+        let mut sessions = self.sessions.write().await;
+        self.purge_expired(&mut sessions);
+        let session = sessions
+            .get_mut(id)
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("session {}", id)))?;
+        session.last_active = chrono::Utc::now();
+        session.context.record_selection(document_id);
+        Ok(self.view(id, session))
+    }
+
+    fn expires_at(&self, session: &SearchSession) -> chrono::DateTime<chrono::Utc> {
+        chrono::Duration::from_std(self.ttl)
+            .ok()
+            .and_then(|ttl| session.last_active.checked_add_signed(ttl))
+            .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC)
+    }
+
+    fn live<'a>(
+        &self,
+        sessions: &'a HashMap<String, SearchSession>,
+        id: &str,
+    ) -> Result<&'a SearchSession> {
+        sessions
+            .get(id)
+            .filter(|session| self.expires_at(session) > chrono::Utc::now())
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("session {}", id)))
+    }
+
+    fn purge_expired(&self, sessions: &mut HashMap<String, SearchSession>) {
+        let now = chrono::Utc::now();
+        sessions.retain(|_, session| self.expires_at(session) > now);
+    }
+
+    fn view(&self, id: &str, session: &SearchSession) -> SessionView {
+        SessionView {
+            session_id: id.to_string(),
+            created_at: session.created_at,
+            last_active: session.last_active,
+            expires_at: self.expires_at(session),
+            turns: session.context.turns().cloned().collect(),
+            selected_documents: session
+                .context
+                .selected()
+                .map(|document_id| document_id.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let service = SessionService::new(Duration::from_secs(60));
+        let session = service.create().await;
+        assert!(session.expires_at > session.created_at);
+
+        let id = session.session_id;
+        let mut results = Vec::new();
+        service
+            .record_search(&id, "collection management", None, &mut results)
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .contextualize(&id, "how do I delete one?")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("how do I delete one? collection management")
+        );
+
+        let document_id = Uuid::new_v4().to_string();
+        let view = service.record_selection(&id, &document_id).await.unwrap();
+        assert_eq!(view.selected_documents, vec![document_id]);
+        assert_eq!(view.turns.len(), 1);
+        assert!(service.record_selection(&id, "not-a-uuid").await.is_err());
+
+        service.delete(&id).await.unwrap();
+        assert!(service.get(&id).await.is_err());
+        assert!(service.delete(&id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sessions_expire() {
+        let service = SessionService::new(Duration::from_millis(20));
+        let id = service.create().await.session_id;
+        assert!(service.get(&id).await.is_ok());
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert!(service.get(&id).await.is_err());
+        assert!(service.contextualize(&id, "anything").await.is_err());
+    }
+}
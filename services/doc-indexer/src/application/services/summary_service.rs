@@ -0,0 +1,111 @@
+/// Source-attributed digests of search results for chatbot integrations
+///
+/// The summarize endpoint runs an ordinary search and hands the results to
+/// the configured [`Summarizer`]. The response pairs its bullets with the
+/// numbered sources their `[n]` citations point at, so a bot can quote the
+/// digest and link every claim to a document.
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use zero_latency_core::{Result, ZeroLatencyError};
+use zero_latency_search::{render_summary, SearchResult, Summarizer, SummaryBullet};
+
+/// Results summarized when the request sets no limit
+pub const DEFAULT_SUMMARY_SOURCES: usize = 5;
+
+/// Most results a summary may draw on
+pub const MAX_SUMMARY_SOURCES: usize = 20;
+
+/// Most bullets a request may ask for
+pub const MAX_SUMMARY_BULLETS: usize = 10;
+
+/// A digest with its sources
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryResponse {
+    pub query: String,
+    /// The bullets as a Markdown list with `[n]` citation markers
+    pub summary: String,
+    pub bullets: Vec<SummaryBullet>,
+    /// Every summarized result; citations refer to `id`
+    pub sources: Vec<SummarySource>,
+    /// Summarizer that wrote the digest
+    pub summarizer: String,
+    /// Search and summarization time
+    pub took_ms: f64,
+}
+
+/// A search result a digest can cite
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummarySource {
+    /// Citation number, the result's 1-based rank
+    pub id: usize,
+    pub document_id: String,
+    pub chunk_id: String,
+    pub title: String,
+    pub uri: String,
+    pub section_path: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    pub score: f32,
+}
+
+/// Service writing digests with the configured summarizer
+#[derive(Clone)]
+pub struct SummaryService {
+    summarizer: Arc<dyn Summarizer>,
+    default_max_bullets: usize,
+}
+
+impl SummaryService {
+    pub fn new(summarizer: Arc<dyn Summarizer>, default_max_bullets: usize) -> Self {
+        Self {
+            summarizer,
+            default_max_bullets: default_max_bullets.clamp(1, MAX_SUMMARY_BULLETS),
+        }
+    }
+
+    /// Summarize the results of a search for `query` that began at `started`
+    pub async fn summarize(
+        &self,
+        query: &str,
+        results: Vec<SearchResult>,
+        max_bullets: Option<usize>,
+        started: Instant,
+    ) -> Result<SummaryResponse> {
+        let max_bullets = max_bullets.unwrap_or(self.default_max_bullets);
+        if !(1..=MAX_SUMMARY_BULLETS).contains(&max_bullets) {
+            return Err(ZeroLatencyError::validation(
+                "max_bullets",
+                format!("must be between 1 and {}", MAX_SUMMARY_BULLETS),
+            ));
+        }
+
+        let bullets = self
+            .summarizer
+            .summarize(query, &results, max_bullets)
+            .await?;
+        let sources = results
+            .into_iter()
+            .enumerate()
+            .map(|(index, result)| SummarySource {
+                id: index + 1,
+                document_id: result.document_id.to_string(),
+                chunk_id: result.chunk_id.to_string(),
+                title: result.title,
+                uri: result.uri,
+                section_path: result.section_path,
+                collection: result.collection,
+                score: result.final_score.value(),
+            })
+            .collect();
+
+        Ok(SummaryResponse {
+            query: query.to_string(),
+            summary: render_summary(&bullets),
+            bullets,
+            sources,
+            summarizer: self.summarizer.name().to_string(),
+            took_ms: started.elapsed().as_secs_f64() * 1000.0,
+        })
+    }
+}
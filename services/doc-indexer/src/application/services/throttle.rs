@@ -0,0 +1,152 @@
+use crate::config::NiceModeConfig;
+use serde::{Deserialize, Serialize};
+/// Resource throttling for low-priority indexing
+///
+/// A reindex on a developer laptop or a shared host should not starve
+/// interactive search traffic. Jobs running at low priority ("nice mode")
+/// have their pipeline workers capped, sleep after each unit of work so that
+/// only a fraction of their time is spent on the CPU, and pace file reads to
+/// a maximum byte rate.
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Scheduling priority of an indexing job
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexingPriority {
+    /// Use the full pipeline concurrency without throttling
+    #[default]
+    Normal,
+    /// Throttle CPU and I/O usage according to the nice mode configuration
+    Low,
+}
+
+/// Sleep-based CPU and I/O throttle shared by the workers of a pipeline
+pub struct Throttle {
+    // Seconds of sleep per second of work, or None when unthrottled
+    sleep_ratio: Option<f64>,
+    io_pacer: Option<IoPacer>,
+}
+
+impl Throttle {
+    /// Create a throttle that never sleeps
+    pub fn unlimited() -> Self {
+        Self {
+            sleep_ratio: None,
+            io_pacer: None,
+        }
+    }
+
+    /// Create the throttle for a job of the given priority
+    pub fn for_priority(priority: IndexingPriority, config: &NiceModeConfig) -> Self {
+        match priority {
+            IndexingPriority::Normal => Self::unlimited(),
+            IndexingPriority::Low => Self::nice(config),
+        }
+    }
+
+    /// Create a throttle enforcing the nice mode limits
+    pub fn nice(config: &NiceModeConfig) -> Self {
+        let duty_cycle = config.cpu_duty_cycle.clamp(0.01, 1.0);
+
+        Self {
+            sleep_ratio: (duty_cycle < 1.0).then(|| (1.0 - duty_cycle) / duty_cycle),
+            io_pacer: (config.max_read_bytes_per_sec > 0)
+                .then(|| IoPacer::new(config.max_read_bytes_per_sec)),
+        }
+    }
+
+    /// Delay needed after `busy` worth of work to respect the CPU duty cycle
+    pub fn work_delay(&self, busy: Duration) -> Duration {
+        self.sleep_ratio
+            .map(|ratio| busy.mul_f64(ratio))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Sleep after a unit of work to respect the CPU duty cycle
+    pub async fn after_work(&self, busy: Duration) {
+        let delay = self.work_delay(busy);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Sleep after reading `bytes` to respect the I/O rate limit
+    pub async fn after_read(&self, bytes: u64) {
+        if let Some(pacer) = &self.io_pacer {
+            let delay = pacer.delay_for(bytes);
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Paces cumulative reads to a maximum byte rate
+struct IoPacer {
+    bytes_per_second: u64,
+    // Start of pacing and total bytes read so far
+    state: Mutex<(Instant, u64)>,
+}
+
+impl IoPacer {
+    fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second,
+            state: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    /// Record a read and return how long to wait before the next one
+    fn delay_for(&self, bytes: u64) -> Duration {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.1 += bytes;
+
+        let target = Duration::from_secs_f64(state.1 as f64 / self.bytes_per_second as f64);
+        target.saturating_sub(state.0.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nice_config(cpu_duty_cycle: f64, max_read_bytes_per_sec: u64) -> NiceModeConfig {
+        NiceModeConfig {
+            max_threads: 1,
+            cpu_duty_cycle,
+            max_read_bytes_per_sec,
+        }
+    }
+
+    #[test]
+    fn test_normal_priority_is_unthrottled() {
+        let throttle = Throttle::for_priority(IndexingPriority::Normal, &nice_config(0.25, 1));
+        assert_eq!(throttle.work_delay(Duration::from_secs(1)), Duration::ZERO);
+        assert!(throttle.io_pacer.is_none());
+    }
+
+    #[test]
+    fn test_duty_cycle_sleep() {
+        let throttle = Throttle::for_priority(IndexingPriority::Low, &nice_config(0.25, 0));
+        // Working 25% of the time means sleeping three times as long as the work
+        assert_eq!(
+            throttle.work_delay(Duration::from_millis(100)),
+            Duration::from_millis(300)
+        );
+        assert!(throttle.io_pacer.is_none());
+    }
+
+    #[test]
+    fn test_io_pacing() {
+        let pacer = IoPacer::new(1000);
+        let delay = pacer.delay_for(500);
+        assert!(delay > Duration::from_millis(400) && delay <= Duration::from_millis(500));
+
+        let delay = pacer.delay_for(500);
+        assert!(delay > Duration::from_millis(900) && delay <= Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,384 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+/// Staleness of stored vectors
+///
+/// Every vector records the model that embedded it and when. A vector is
+/// stale when another model (or an unrecorded one) produced it, or when it is
+/// older than the configured TTL. Stale vectors can be re-embedded in place:
+/// they keep their ids, so fingerprints and search results stay valid, and
+/// vectors that are still current are left alone.
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use zero_latency_core::{DateTime, Result, Utc, ZeroLatencyError};
+use zero_latency_vector::{EmbeddingGenerator, VectorDocument, VectorRepository};
+
+use crate::application::container::ServiceContainer;
+use crate::application::services::indexing_pipeline::{PipelineProgress, PipelineStage};
+use crate::config::StalenessConfig;
+use crate::infrastructure::persistence::collection_aliases::CollectionAliases;
+
+/// Chunks embedded per call to the embedding generator
+const REEMBED_BATCH_SIZE: usize = 32;
+
+/// Vectors of a collection produced by one model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVectors {
+    /// Model name; `None` for vectors stored before models were recorded
+    pub model: Option<String>,
+    pub vectors: usize,
+    pub oldest: Option<DateTime<Utc>>,
+    pub newest: Option<DateTime<Utc>>,
+}
+
+/// How many of a collection's vectors are stale, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StalenessReport {
+    pub collection: String,
+    pub current_model: String,
+    pub ttl_days: Option<u64>,
+    pub total_vectors: usize,
+    /// Vectors that need re-embedding for either reason below
+    pub stale_vectors: usize,
+    /// Vectors produced by another model, or by an unrecorded one
+    pub outdated_model: usize,
+    /// Vectors of the current model that are older than the TTL
+    pub expired: usize,
+    /// Vectors grouped by the model that produced them
+    pub models: Vec<ModelVectors>,
+}
+
+/// Outcome of a completed re-embedding of stale vectors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReembedReport {
+    pub collection: String,
+    pub model: String,
+    pub vectors_reembedded: usize,
+    pub duration_ms: f64,
+}
+
+/// A collection's stale vectors, loaded and checked before re-embedding
+pub struct ReembedPlan {
+    pub collection: String,
+    vectors: Vec<VectorDocument>,
+}
+
+impl ReembedPlan {
+    /// Number of chunks that will be re-embedded
+    pub fn chunk_count(&self) -> usize {
+        self.vectors.len()
+    }
+}
+
+/// Application service reporting and refreshing stale vectors
+#[derive(Clone)]
+pub struct VectorStalenessService {
+    vector_repository: Arc<dyn VectorRepository>,
+    embedding_generator: Arc<dyn EmbeddingGenerator>,
+    collection_aliases: Arc<CollectionAliases>,
+    config: StalenessConfig,
+}
+
+impl VectorStalenessService {
+    /// Create a staleness service sharing the container's stores
+    pub fn new(container: &ServiceContainer) -> Self {
+        Self {
+            vector_repository: container.vector_repository(),
+            embedding_generator: container.embedding_generator(),
+            collection_aliases: container.collection_aliases(),
+            config: container.config().vector.staleness.clone(),
+        }
+    }
+
+    /// Count a collection's stale vectors
+    pub async fn report(&self, collection: &str) -> Result<StalenessReport> {
+        let vectors = self.load(collection).await?;
+        Ok(self.summarize(collection, &vectors))
+    }
+
+    /// Count the stale vectors among a collection's already loaded vectors
+    pub fn summarize(&self, collection: &str, vectors: &[VectorDocument]) -> StalenessReport {
+        let current_model = self.embedding_generator.model_name().to_string();
+        let cutoff = self.cutoff();
+
+        let mut models: BTreeMap<Option<String>, ModelVectors> = BTreeMap::new();
+        let (mut outdated_model, mut expired) = (0, 0);
+        for vector in vectors {
+            let metadata = &vector.metadata;
+            if metadata.embedding_model.as_deref() != Some(current_model.as_str()) {
+                outdated_model += 1;
+            } else if is_expired(metadata.embedded_at, cutoff) {
+                expired += 1;
+            }
+
+            let entry = models
+                .entry(metadata.embedding_model.clone())
+                .or_insert_with(|| ModelVectors {
+                    model: metadata.embedding_model.clone(),
+                    vectors: 0,
+                    oldest: None,
+                    newest: None,
+                });
+            entry.vectors += 1;
+            if let Some(embedded_at) = metadata.embedded_at {
+                entry.oldest = Some(entry.oldest.map_or(embedded_at, |t| t.min(embedded_at)));
+                entry.newest = Some(entry.newest.map_or(embedded_at, |t| t.max(embedded_at)));
+            }
+        }
+
+        StalenessReport {
+            collection: collection.to_string(),
+            current_model,
+            ttl_days: self.config.ttl_days,
+            total_vectors: vectors.len(),
+            stale_vectors: outdated_model + expired,
+            outdated_model,
+            expired,
+            models: models.into_values().collect(),
+        }
+    }
+
+    /// Load a collection's stale vectors and check they can be re-embedded
+    ///
+    /// Fails if the collection holds no vectors or none are stale, and if the
+    /// current model's dimension differs from the stored vectors', which
+    /// needs a full embedding migration instead.
+    pub async fn plan(&self, collection: &str) -> Result<ReembedPlan> {
+        let current_model = self.embedding_generator.model_name();
+        let dimension = self.embedding_generator.dimension();
+        let cutoff = self.cutoff();
+
+        let vectors = self.load(collection).await?;
+        if vectors.iter().any(|v| v.embedding.len() != dimension) {
+            return Err(ZeroLatencyError::validation(
+                "name",
+                format!(
+                    "Collection '{}' has vectors of another dimension than the current model's {}; migrate its embeddings instead",
+                    collection, dimension
+                ),
+            ));
+        }
+
+        let stale: Vec<VectorDocument> = vectors
+            .into_iter()
+            .filter(|v| {
+                v.metadata.embedding_model.as_deref() != Some(current_model)
+                    || is_expired(v.metadata.embedded_at, cutoff)
+            })
+            .collect();
+        if stale.is_empty() {
+            return Err(ZeroLatencyError::validation(
+                "name",
+                format!("Collection '{}' has no stale vectors", collection),
+            ));
+        }
+
+        Ok(ReembedPlan {
+            collection: collection.to_string(),
+            vectors: stale,
+        })
+    }
+
+    /// Re-embed a plan's vectors in place with the current model
+    ///
+    /// Progress is published to `progress`: the `read` stage counts the
+    /// stale chunks, `embed` and `upsert` the chunks done so far.
+    pub async fn reembed(
+        &self,
+        plan: ReembedPlan,
+        progress: Arc<PipelineProgress>,
+    ) -> Result<ReembedReport> {
+        let started = Instant::now();
+        let model = self.embedding_generator.model_name().to_string();
+        progress.record(
+            PipelineStage::Read,
+            plan.chunk_count() as u64,
+            Duration::ZERO,
+        );
+
+        for batch in plan.vectors.chunks(REEMBED_BATCH_SIZE) {
+            let embed_started = Instant::now();
+            let texts = batch
+                .iter()
+                .map(|vector| vector.metadata.content.as_str())
+                .collect();
+            let embeddings = self
+                .embedding_generator
+                .generate_batch_embeddings(texts)
+                .await?;
+            progress.record(
+                PipelineStage::Embed,
+                batch.len() as u64,
+                embed_started.elapsed(),
+            );
+
+            let embedded_at = Utc::now();
+            let refreshed = batch
+                .iter()
+                .zip(embeddings)
+                .map(|(vector, embedding)| {
+                    let mut metadata = vector.metadata.clone();
+                    metadata.embedding_model = Some(model.clone());
+                    metadata.embedded_at = Some(embedded_at);
+                    VectorDocument {
+                        id: vector.id,
+                        embedding,
+                        named_vectors: Default::default(),
+                        metadata,
+                    }
+                })
+                .collect();
+
+            let upsert_started = Instant::now();
+            self.vector_repository.upsert_batch(refreshed).await?;
+            progress.record(
+                PipelineStage::Upsert,
+                batch.len() as u64,
+                upsert_started.elapsed(),
+            );
+        }
+
+        let report = ReembedReport {
+            collection: plan.collection,
+            model,
+            vectors_reembedded: plan.vectors.len(),
+            duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        };
+        tracing::info!(
+            "Re-embedded {} stale vectors of collection '{}' with {} in {:.0}ms",
+            report.vectors_reembedded,
+            report.collection,
+            report.model,
+            report.duration_ms
+        );
+        Ok(report)
+    }
+
+    /// Every vector of a collection, read through its alias
+    async fn load(&self, collection: &str) -> Result<Vec<VectorDocument>> {
+        let vectors = self
+            .vector_repository
+            .collection_vectors(&self.collection_aliases.resolve(collection))
+            .await?;
+        if vectors.is_empty() {
+            return Err(ZeroLatencyError::not_found(format!(
+                "vectors of collection {}",
+                collection
+            )));
+        }
+        Ok(vectors)
+    }
+
+    /// Vectors embedded before this are expired
+    fn cutoff(&self) -> Option<DateTime<Utc>> {
+        self.config
+            .ttl_days
+            .map(|days| Utc::now() - chrono::Duration::days(days as i64))
+    }
+}
+
+fn is_expired(embedded_at: Option<DateTime<Utc>>, cutoff: Option<DateTime<Utc>>) -> bool {
+    match (embedded_at, cutoff) {
+        (Some(embedded_at), Some(cutoff)) => embedded_at < cutoff,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, VectorBackend};
+    use crate::infrastructure::persistence::embeddings::mock_adapter::MockEmbeddingConfig;
+    use crate::infrastructure::MockEmbeddingAdapter;
+    use zero_latency_core::Uuid;
+    use zero_latency_vector::VectorMetadata;
+
+    async fn container(ttl_days: Option<u64>) -> ServiceContainer {
+        let mut config = Config::default();
+        config.vector.backend = VectorBackend::Memory;
+        config.vector.staleness.ttl_days = ttl_days;
+        ServiceContainer::with_embedding_generator(
+            config,
+            Arc::new(
+                MockEmbeddingAdapter::new(MockEmbeddingConfig {
+                    dimension: 8,
+                    seed: 7,
+                })
+                .unwrap(),
+            ),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn chunk(model: Option<&str>, age_days: i64) -> VectorDocument {
+        VectorDocument {
+            id: Uuid::new_v4(),
+            embedding: vec![0.5; 8],
+            named_vectors: Default::default(),
+            metadata: VectorMetadata {
+                document_id: Uuid::new_v4(),
+                content: format!("chunk embedded {} days ago", age_days),
+                collection: Some("docs".to_string()),
+                embedding_model: model.map(str::to_string),
+                embedded_at: Some(Utc::now() - chrono::Duration::days(age_days)),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reembeds_only_stale_vectors() {
+        let container = container(Some(30)).await;
+        let current = container.embedding_generator().model_name().to_string();
+        let repository = container.vector_repository();
+        let fresh = chunk(Some(&current), 1);
+        let fresh_id = fresh.id;
+        repository
+            .insert(vec![
+                fresh,
+                chunk(Some(&current), 60),
+                chunk(Some("old-model"), 1),
+                chunk(None, 1),
+            ])
+            .await
+            .unwrap();
+
+        let service = VectorStalenessService::new(&container);
+        let report = service.report("docs").await.unwrap();
+        assert_eq!(report.total_vectors, 4);
+        assert_eq!((report.outdated_model, report.expired), (2, 1));
+        assert_eq!(report.stale_vectors, 3);
+        assert_eq!(report.models.len(), 3);
+
+        let plan = service.plan("docs").await.unwrap();
+        assert_eq!(plan.chunk_count(), 3);
+        let progress = Arc::new(PipelineProgress::new());
+        let reembedded = service.reembed(plan, progress).await.unwrap();
+        assert_eq!(reembedded.vectors_reembedded, 3);
+
+        // Ids are kept and the current vector is untouched
+        let vectors = repository.collection_vectors("docs").await.unwrap();
+        assert_eq!(vectors.len(), 4);
+        let untouched = vectors.iter().find(|v| v.id == fresh_id).unwrap();
+        assert_eq!(untouched.embedding, vec![0.5; 8]);
+        assert_eq!(service.report("docs").await.unwrap().stale_vectors, 0);
+        assert!(service.plan("docs").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plan_rejects_dimension_change() {
+        let container = container(None).await;
+        let mut other = chunk(Some("old-model"), 1);
+        other.embedding = vec![0.5; 4];
+        container
+            .vector_repository()
+            .insert(vec![other])
+            .await
+            .unwrap();
+
+        let service = VectorStalenessService::new(&container);
+        assert_eq!(service.report("docs").await.unwrap().stale_vectors, 1);
+        assert!(service.plan("docs").await.is_err());
+        assert!(service.report("missing").await.is_err());
+    }
+}
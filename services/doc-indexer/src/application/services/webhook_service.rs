@@ -0,0 +1,555 @@
+/// Webhook subscriptions and event notification
+///
+/// Other services report lifecycle events here (an index job finished, a
+/// collection was deleted, health degraded) and every active webhook
+/// subscribed to the event is notified in the background, so the operation
+/// that raised the event never waits on a subscriber. The outcome of recent
+/// deliveries is kept per webhook for troubleshooting. A webhook with a
+/// template gets its payload rendered through it, so events can be posted
+/// straight to a chat channel or any endpoint expecting its own format.
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+use zero_latency_search::OutputTemplate;
+
+use crate::infrastructure::persistence::webhook_registry::{
+    Webhook, WebhookEvent, WebhookRegistry,
+};
+use crate::infrastructure::webhooks::{DeliveryOutcome, WebhookDispatcher, JSON_CONTENT_TYPE};
+
+/// Deliveries remembered per webhook
+const MAX_RECENT_DELIVERIES: usize = 20;
+
+/// Shortest secret accepted from a client
+const MIN_SECRET_LEN: usize = 16;
+
+/// Events buffered for a slow in-process subscriber before it misses some
+#[cfg(feature = "chat")]
+const SUBSCRIBER_BUFFER: usize = 64;
+
+/// An event as seen by in-process subscribers
+#[cfg(feature = "chat")]
+#[derive(Debug, Clone)]
+pub struct EmittedEvent {
+    pub event: WebhookEvent,
+    pub data: serde_json::Value,
+}
+
+/// Request to register a webhook
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// Events to deliver; all events when omitted or empty
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    /// Signing secret; generated when omitted
+    pub secret: Option<String>,
+    pub description: Option<String>,
+    #[serde(default = "default_active")]
+    pub active: bool,
+    /// Template rendering the payload; the JSON payload when omitted
+    pub template: Option<String>,
+    /// Content type of templated payloads; `application/json` when omitted
+    pub content_type: Option<String>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Changes to a webhook; omitted fields are kept
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    /// New signing secret
+    pub secret: Option<String>,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+    /// New payload template; an empty string removes it
+    pub template: Option<String>,
+    /// New content type of templated payloads; an empty string resets it
+    pub content_type: Option<String>,
+}
+
+/// Outcome of delivering one event to a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub event: WebhookEvent,
+    pub delivered: bool,
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub completed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A webhook as returned by the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookView {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// Only returned when the secret is created or replaced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Most recent first
+    pub recent_deliveries: Vec<WebhookDelivery>,
+}
+
+/// Service managing webhooks and notifying them of events
+#[derive(Clone)]
+pub struct WebhookService {
+    registry: Arc<WebhookRegistry>,
+    dispatcher: Arc<WebhookDispatcher>,
+    deliveries: Arc<tokio::sync::RwLock<HashMap<String, VecDeque<WebhookDelivery>>>>,
+    // In-process subscribers, e.g. chat integrations forwarding alerts
+    #[cfg(feature = "chat")]
+    subscribers: tokio::sync::broadcast::Sender<EmittedEvent>,
+}
+
+impl WebhookService {
+    pub fn new(registry: Arc<WebhookRegistry>, dispatcher: Arc<WebhookDispatcher>) -> Self {
+        Self {
+            registry,
+            dispatcher,
+            deliveries: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            #[cfg(feature = "chat")]
+            subscribers: tokio::sync::broadcast::channel(SUBSCRIBER_BUFFER).0,
+        }
+    }
+
+    /// Receive every emitted event in process, whether or not a webhook
+    /// subscribes to it
+    #[cfg(feature = "chat")]
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<EmittedEvent> {
+        self.subscribers.subscribe()
+    }
+
+    /// Register a webhook; the response carries its secret
+    pub async fn create(&self, request: CreateWebhookRequest) -> Result<WebhookView> {
+        validate_url(&request.url)?;
+        let template = request.template.map(validate_template).transpose()?;
+        let content_type = request
+            .content_type
+            .map(validate_content_type)
+            .transpose()?;
+        let secret = match request.secret {
+            Some(secret) => validate_secret(secret)?,
+            None => generate_secret(),
+        };
+
+        let now = chrono::Utc::now();
+        let webhook = Webhook {
+            id: Uuid::new_v4().to_string(),
+            url: request.url,
+            events: dedup_events(request.events),
+            secret: secret.clone(),
+            description: request.description,
+            active: request.active,
+            template,
+            content_type,
+            created_at: now,
+            updated_at: now,
+        };
+        self.registry.insert(webhook.clone());
+        self.registry.save()?;
+
+        let mut view = self.view(&webhook).await;
+        view.secret = Some(secret);
+        Ok(view)
+    }
+
+    /// All webhooks, oldest first
+    pub async fn list(&self) -> Vec<WebhookView> {
+        let mut views = Vec::new();
+        for webhook in self.registry.list() {
+            views.push(self.view(&webhook).await);
+        }
+        views
+    }
+
+    /// A webhook by id
+    pub async fn get(&self, id: &str) -> Result<WebhookView> {
+        let webhook = self.find(id)?;
+        Ok(self.view(&webhook).await)
+    }
+
+    /// Change a webhook; the response carries the secret if it was replaced
+    pub async fn update(&self, id: &str, request: UpdateWebhookRequest) -> Result<WebhookView> {
+        let mut webhook = self.find(id)?;
+        if let Some(url) = request.url {
+            validate_url(&url)?;
+            webhook.url = url;
+        }
+        if let Some(events) = request.events {
+            webhook.events = dedup_events(events);
+        }
+        let secret = request.secret.map(validate_secret).transpose()?;
+        if let Some(secret) = &secret {
+            webhook.secret = secret.clone();
+        }
+        if let Some(description) = request.description {
+            webhook.description = Some(description);
+        }
+        if let Some(active) = request.active {
+            webhook.active = active;
+        }
+        if let Some(template) = request.template {
+            webhook.template = if template.is_empty() {
+                None
+            } else {
+                Some(validate_template(template)?)
+            };
+        }
+        if let Some(content_type) = request.content_type {
+            webhook.content_type = if content_type.is_empty() {
+                None
+            } else {
+                Some(validate_content_type(content_type)?)
+            };
+        }
+        webhook.updated_at = chrono::Utc::now();
+        self.registry.insert(webhook.clone());
+        self.registry.save()?;
+
+        let mut view = self.view(&webhook).await;
+        view.secret = secret;
+        Ok(view)
+    }
+
+    /// Remove a webhook
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        self.registry
+            .remove(id)
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("webhook {}", id)))?;
+        self.registry.save()?;
+        self.deliveries.write().await.remove(id);
+        Ok(())
+    }
+
+    /// Notify the webhooks subscribed to `event` in the background
+    pub fn emit(&self, event: WebhookEvent, data: serde_json::Value) {
+        // Sending only fails when nobody is subscribed
+        #[cfg(feature = "chat")]
+        let _ = self.subscribers.send(EmittedEvent {
+            event,
+            data: data.clone(),
+        });
+        for webhook in self
+            .registry
+            .list()
+            .into_iter()
+            .filter(|webhook| webhook.subscribes_to(event))
+        {
+            let delivery_id = Uuid::new_v4().to_string();
+            let payload = serde_json::json!({
+                "id": delivery_id,
+                "event": event,
+                "created_at": chrono::Utc::now(),
+                "webhook_id": webhook.id,
+                "data": data,
+            });
+            let service = self.clone();
+            tokio::spawn(async move {
+                let outcome = match render_payload(&webhook, &payload) {
+                    Ok(body) => {
+                        service
+                            .dispatcher
+                            .deliver(
+                                &webhook.url,
+                                Some(&webhook.secret),
+                                event.as_str(),
+                                &delivery_id,
+                                webhook.content_type.as_deref().unwrap_or(JSON_CONTENT_TYPE),
+                                &body,
+                            )
+                            .await
+                    }
+                    // Nothing is sent, but the failure shows in the history
+                    Err(e) => DeliveryOutcome {
+                        delivered: false,
+                        attempts: 0,
+                        status_code: None,
+                        error: Some(e.to_string()),
+                    },
+                };
+                if !outcome.delivered {
+                    tracing::warn!(
+                        "Webhook {} gave up on {} after {} attempts: {}",
+                        webhook.id,
+                        event,
+                        outcome.attempts,
+                        outcome.error.as_deref().unwrap_or_default()
+                    );
+                }
+                service
+                    .record(
+                        &webhook.id,
+                        WebhookDelivery {
+                            id: delivery_id,
+                            event,
+                            delivered: outcome.delivered,
+                            attempts: outcome.attempts,
+                            status_code: outcome.status_code,
+                            error: outcome.error,
+                            completed_at: chrono::Utc::now(),
+                        },
+                    )
+                    .await;
+            });
+        }
+    }
+
+    async fn record(&self, webhook_id: &str, delivery: WebhookDelivery) {
+        // A webhook deleted while the delivery was in flight keeps no history
+        if self.registry.get(webhook_id).is_none() {
+            return;
+        }
+        let mut deliveries = self.deliveries.write().await;
+        let recent = deliveries.entry(webhook_id.to_string()).or_default();
+        recent.push_front(delivery);
+        recent.truncate(MAX_RECENT_DELIVERIES);
+    }
+
+    fn find(&self, id: &str) -> Result<Webhook> {
+        self.registry
+            .get(id)
+            .ok_or_else(|| ZeroLatencyError::not_found(format!("webhook {}", id)))
+    }
+
+    async fn view(&self, webhook: &Webhook) -> WebhookView {
+        let recent_deliveries = self
+            .deliveries
+            .read()
+            .await
+            .get(&webhook.id)
+            .map(|recent| recent.iter().cloned().collect())
+            .unwrap_or_default();
+        WebhookView {
+            id: webhook.id.clone(),
+            url: webhook.url.clone(),
+            events: webhook.events.clone(),
+            description: webhook.description.clone(),
+            active: webhook.active,
+            template: webhook.template.clone(),
+            content_type: webhook.content_type.clone(),
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+            secret: None,
+            recent_deliveries,
+        }
+    }
+}
+
+fn validate_url(url: &str) -> Result<()> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| ZeroLatencyError::validation("url", format!("invalid URL: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(ZeroLatencyError::validation(
+            "url",
+            "must be an http or https URL",
+        ));
+    }
+    Ok(())
+}
+
+/// Reject templates that don't compile, so mistakes surface on
+/// registration instead of as failed deliveries
+fn validate_template(template: String) -> Result<String> {
+    OutputTemplate::new(template.as_str())?;
+    Ok(template)
+}
+
+fn validate_content_type(content_type: String) -> Result<String> {
+    let valid =
+        content_type.contains('/') && reqwest::header::HeaderValue::from_str(&content_type).is_ok();
+    if !valid {
+        return Err(ZeroLatencyError::validation(
+            "content_type",
+            "must be a MIME type such as text/markdown",
+        ));
+    }
+    Ok(content_type)
+}
+
+/// Body sent to `webhook`: the JSON payload, or its template's rendering
+fn render_payload(webhook: &Webhook, payload: &serde_json::Value) -> Result<Vec<u8>> {
+    match &webhook.template {
+        Some(template) => Ok(OutputTemplate::new(template.as_str())?
+            .render(payload)?
+            .into_bytes()),
+        None => Ok(payload.to_string().into_bytes()),
+    }
+}
+
+fn validate_secret(secret: String) -> Result<String> {
+    if secret.chars().count() < MIN_SECRET_LEN {
+        return Err(ZeroLatencyError::validation(
+            "secret",
+            format!("must be at least {} characters", MIN_SECRET_LEN),
+        ));
+    }
+    Ok(secret)
+}
+
+fn generate_secret() -> String {
+    format!(
+        "whsec_{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+fn dedup_events(mut events: Vec<WebhookEvent>) -> Vec<WebhookEvent> {
+    let mut seen = Vec::new();
+    events.retain(|event| {
+        let first = !seen.contains(event);
+        seen.push(*event);
+        first
+    });
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WebhookConfig;
+
+    fn service() -> WebhookService {
+        let dispatcher = WebhookDispatcher::new(WebhookConfig {
+            max_attempts: 1,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 1,
+            timeout_ms: 1_000,
+        })
+        .unwrap();
+        WebhookService::new(Arc::new(WebhookRegistry::in_memory()), Arc::new(dispatcher))
+    }
+
+    fn request(url: &str) -> CreateWebhookRequest {
+        CreateWebhookRequest {
+            url: url.to_string(),
+            events: vec![WebhookEvent::IndexCompleted, WebhookEvent::IndexCompleted],
+            secret: None,
+            description: None,
+            active: true,
+            template: None,
+            content_type: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhook_crud() {
+        let service = service();
+        let created = service
+            .create(request("https://example.com/hook"))
+            .await
+            .unwrap();
+        assert!(created.secret.as_deref().unwrap().starts_with("whsec_"));
+        assert_eq!(created.events, vec![WebhookEvent::IndexCompleted]);
+
+        // The secret is not shown again
+        let fetched = service.get(&created.id).await.unwrap();
+        assert!(fetched.secret.is_none());
+        assert_eq!(service.list().await.len(), 1);
+
+        let updated = service
+            .update(
+                &created.id,
+                UpdateWebhookRequest {
+                    active: Some(false),
+                    events: Some(Vec::new()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(!updated.active);
+        assert!(updated.events.is_empty());
+        assert!(updated.secret.is_none());
+
+        service.delete(&created.id).await.unwrap();
+        assert!(service.get(&created.id).await.is_err());
+        assert!(service.delete(&created.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_validation() {
+        let service = service();
+        assert!(service.create(request("not a url")).await.is_err());
+        assert!(service.create(request("ftp://example.com")).await.is_err());
+
+        let mut short_secret = request("https://example.com/hook");
+        short_secret.secret = Some("short".to_string());
+        assert!(service.create(short_secret).await.is_err());
+        assert!(service.list().await.is_empty());
+    }
+
+    #[cfg(feature = "chat")]
+    #[tokio::test]
+    async fn test_subscribers_see_every_event() {
+        let service = service();
+        let mut events = service.subscribe();
+        // No webhook is registered, but in-process subscribers still hear it
+        service.emit(
+            WebhookEvent::IndexFailed,
+            serde_json::json!({ "collection": "docs" }),
+        );
+        let emitted = events.try_recv().unwrap();
+        assert_eq!(emitted.event, WebhookEvent::IndexFailed);
+        assert_eq!(emitted.data["collection"], "docs");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_templates() {
+        let service = service();
+        let mut templated = request("https://example.com/hook");
+        templated.template = Some("{% if data.failed %}".to_string());
+        assert!(service.create(templated.clone()).await.is_err());
+
+        templated.template = Some(r#"{"text": "{{ event }}: {{ data.collection }}"}"#.to_string());
+        templated.content_type = Some("not a type".to_string());
+        assert!(service.create(templated.clone()).await.is_err());
+
+        templated.content_type = None;
+        let created = service.create(templated).await.unwrap();
+        let webhook = service.find(&created.id).unwrap();
+        let payload = serde_json::json!({
+            "event": WebhookEvent::IndexCompleted,
+            "data": { "collection": "docs" },
+        });
+        assert_eq!(
+            render_payload(&webhook, &payload).unwrap(),
+            br#"{"text": "index.completed: docs"}"#
+        );
+
+        // A template using a field the event lacks fails to render
+        let error = render_payload(&webhook, &serde_json::json!({ "event": "x" }));
+        assert!(error.is_err());
+
+        let updated = service
+            .update(
+                &created.id,
+                UpdateWebhookRequest {
+                    template: Some(String::new()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(updated.template.is_none());
+    }
+}
@@ -0,0 +1,591 @@
+/// Slack and Discord integration (`chat` feature)
+///
+/// Answers slash commands such as `/docs rotate api keys` with the top
+/// results of one configured collection, and posts to a channel when an
+/// index or reindex job fails. Searches go through the API client crate,
+/// against this server unless another is configured, so replies match what
+/// any other client would get. Requests are only answered when they carry a
+/// valid Slack or Discord signature.
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use zero_latency_api::endpoints::endpoints;
+use zero_latency_api::{ApiClientConfig, SearchRequest, ZeroLatencyApiClient};
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+use zero_latency_search::{SearchResponse, SearchResult};
+
+use super::handlers::{AppError, AppState};
+use crate::application::services::webhook_service::EmittedEvent;
+use crate::config::ChatConfig;
+use crate::infrastructure::persistence::webhook_registry::WebhookEvent;
+use crate::infrastructure::webhooks::{hmac_sha256, WebhookDispatcher, JSON_CONTENT_TYPE};
+
+/// Oldest request timestamp accepted, so captured requests can't be replayed
+const MAX_REQUEST_AGE_SECS: i64 = 300;
+
+/// Longest snippet shown per result
+const SNIPPET_CHARS: usize = 240;
+
+/// Discord flag making a reply visible only to the user who asked
+const DISCORD_EPHEMERAL: u64 = 64;
+
+/// Slack slash command payload; the fields a reply needs
+#[derive(Debug, Deserialize)]
+struct SlackCommand {
+    command: String,
+    #[serde(default)]
+    text: String,
+}
+
+/// Searches for chat commands and posts alerts to chat channels
+#[derive(Clone)]
+pub struct ChatIntegration {
+    config: ChatConfig,
+    client: ZeroLatencyApiClient,
+    discord_key: Option<VerifyingKey>,
+}
+
+impl ChatIntegration {
+    /// Integration searching the API at `api_url`
+    pub fn new(config: ChatConfig, api_url: String) -> Result<Self> {
+        let discord_key = config
+            .discord_public_key
+            .as_deref()
+            .map(parse_public_key)
+            .transpose()?;
+        let client = ZeroLatencyApiClient::with_config(ApiClientConfig {
+            base_url: api_url.trim_end_matches('/').to_string(),
+            ..ApiClientConfig::default()
+        })
+        .map_err(|e| {
+            ZeroLatencyError::configuration(format!("Failed to create chat API client: {}", e))
+        })?;
+        Ok(Self {
+            config,
+            client,
+            discord_key,
+        })
+    }
+
+    async fn search(&self, query: &str) -> Result<SearchResponse> {
+        let request = SearchRequest::builder(query)
+            .with_limit(self.config.result_limit)
+            .with_collections([self.config.collection.clone()])
+            .build()
+            .map_err(|e| ZeroLatencyError::validation(e.field, e.message))?;
+        self.client
+            .search_documents_as(request)
+            .await
+            .map_err(|e| ZeroLatencyError::network(e.to_string()))
+    }
+
+    /// Reply to a Slack slash command
+    async fn slack_reply(&self, command: &SlackCommand) -> Value {
+        let query = command.text.trim();
+        if query.is_empty() {
+            return json!({
+                "response_type": "ephemeral",
+                "text": format!(
+                    "Usage: `{} <query>` searches `{}`",
+                    command.command, self.config.collection
+                ),
+            });
+        }
+        match self.search(query).await {
+            Ok(response) => slack_results(query, &self.config.collection, &response),
+            Err(e) => json!({
+                "response_type": "ephemeral",
+                "text": format!("Search failed: {}", e),
+            }),
+        }
+    }
+
+    /// Reply to a Discord application command
+    async fn discord_reply(&self, query: &str) -> Value {
+        if query.is_empty() {
+            return json!({
+                "type": 4,
+                "data": {
+                    "content": format!("Give a query to search `{}`", self.config.collection),
+                    "flags": DISCORD_EPHEMERAL,
+                },
+            });
+        }
+        match self.search(query).await {
+            Ok(response) => discord_results(query, &self.config.collection, &response),
+            Err(e) => json!({
+                "type": 4,
+                "data": {
+                    "content": format!("Search failed: {}", e),
+                    "flags": DISCORD_EPHEMERAL,
+                },
+            }),
+        }
+    }
+
+    /// Post a message to the alert channels for every failed job
+    fn forward_alerts(
+        &self,
+        mut events: broadcast::Receiver<EmittedEvent>,
+        dispatcher: WebhookDispatcher,
+    ) {
+        let targets: Vec<(String, &'static str)> = [
+            (self.config.slack_alert_url.clone(), "text"),
+            (self.config.discord_alert_url.clone(), "content"),
+        ]
+        .into_iter()
+        .filter_map(|(url, field)| url.map(|url| (url, field)))
+        .collect();
+        if targets.is_empty() {
+            return;
+        }
+
+        tokio::spawn(async move {
+            loop {
+                let emitted = match events.recv().await {
+                    Ok(emitted) => emitted,
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::warn!("Chat alerts missed {} events", missed);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                let Some(message) = alert_message(emitted.event, &emitted.data) else {
+                    continue;
+                };
+                for (url, field) in &targets {
+                    let mut body = json!({});
+                    body[*field] = json!(message);
+                    let body = body.to_string();
+                    let outcome = dispatcher
+                        .deliver(
+                            url,
+                            None,
+                            emitted.event.as_str(),
+                            &Uuid::new_v4().to_string(),
+                            JSON_CONTENT_TYPE,
+                            body.as_bytes(),
+                        )
+                        .await;
+                    if !outcome.delivered {
+                        tracing::warn!(
+                            "Chat alert for {} was not delivered: {}",
+                            emitted.event,
+                            outcome.error.as_deref().unwrap_or_default()
+                        );
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Routes answering Slack and Discord; merged into the main router
+///
+/// Also starts posting failure alerts when alert channels are configured.
+pub fn create_router<S>(state: &AppState) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let config = state.container.config();
+    let api_url = config.chat.api_url.clone().unwrap_or_else(|| {
+        let host = match config.server.host.as_str() {
+            "0.0.0.0" | "::" => "127.0.0.1",
+            host => host,
+        };
+        format!("http://{}:{}", host, config.server.port)
+    });
+    let integration = match ChatIntegration::new(config.chat.clone(), api_url) {
+        Ok(integration) => integration,
+        Err(e) => {
+            tracing::error!("Chat integration disabled: {}", e);
+            return Router::new();
+        }
+    };
+    match WebhookDispatcher::new(config.webhooks.clone()) {
+        Ok(dispatcher) => integration.forward_alerts(state.webhook_service.subscribe(), dispatcher),
+        Err(e) => tracing::error!("Chat alerts disabled: {}", e),
+    }
+
+    Router::new()
+        .route(endpoints::SLACK_COMMANDS, post(slack_command))
+        .route(endpoints::DISCORD_INTERACTIONS, post(discord_interaction))
+        .with_state(Arc::new(integration))
+}
+
+async fn slack_command(
+    State(chat): State<Arc<ChatIntegration>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(secret) = chat.config.slack_signing_secret.as_deref() else {
+        return AppError::from(ZeroLatencyError::not_found("Slack integration")).into_response();
+    };
+    if !verify_slack(secret, &headers, &body, chrono::Utc::now().timestamp()) {
+        return unauthorized();
+    }
+    let command: SlackCommand = match serde_urlencoded::from_bytes(&body) {
+        Ok(command) => command,
+        Err(e) => {
+            return AppError::from(ZeroLatencyError::validation("body", e.to_string()))
+                .into_response()
+        }
+    };
+    Json(chat.slack_reply(&command).await).into_response()
+}
+
+async fn discord_interaction(
+    State(chat): State<Arc<ChatIntegration>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(key) = &chat.discord_key else {
+        return AppError::from(ZeroLatencyError::not_found("Discord integration")).into_response();
+    };
+    if !verify_discord(key, &headers, &body) {
+        return unauthorized();
+    }
+    let interaction: Value = match serde_json::from_slice(&body) {
+        Ok(interaction) => interaction,
+        Err(e) => {
+            return AppError::from(ZeroLatencyError::validation("body", e.to_string()))
+                .into_response()
+        }
+    };
+    match interaction["type"].as_u64() {
+        // Discord pings the endpoint when it is registered
+        Some(1) => Json(json!({ "type": 1 })).into_response(),
+        Some(2) => Json(chat.discord_reply(&discord_query(&interaction)).await).into_response(),
+        _ => AppError::from(ZeroLatencyError::validation(
+            "type",
+            "only pings and application commands are supported",
+        ))
+        .into_response(),
+    }
+}
+
+/// Slack and Discord both expect 401 for a bad signature
+fn unauthorized() -> Response {
+    (StatusCode::UNAUTHORIZED, "invalid request signature").into_response()
+}
+
+/// Check Slack's `v0` signature over the timestamp and raw body
+fn verify_slack(secret: &str, headers: &HeaderMap, body: &[u8], now: i64) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let (Some(timestamp), Some(signature)) = (
+        header("x-slack-request-timestamp"),
+        header("x-slack-signature"),
+    ) else {
+        return false;
+    };
+    let Ok(sent_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now - sent_at).abs() > MAX_REQUEST_AGE_SECS {
+        return false;
+    }
+    let mut message = format!("v0:{}:", timestamp).into_bytes();
+    message.extend_from_slice(body);
+    let expected = format!("v0={}", hex(&hmac_sha256(secret.as_bytes(), &message)));
+    constant_time_eq(expected.as_bytes(), signature.as_bytes())
+}
+
+/// Check Discord's Ed25519 signature over the timestamp and raw body
+fn verify_discord(key: &VerifyingKey, headers: &HeaderMap, body: &[u8]) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let (Some(timestamp), Some(signature)) = (
+        header("x-signature-timestamp"),
+        header("x-signature-ed25519"),
+    ) else {
+        return false;
+    };
+    let Some(signature) = decode_hex(signature)
+        .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+        .map(|bytes| Signature::from_bytes(&bytes))
+    else {
+        return false;
+    };
+    let mut message = timestamp.as_bytes().to_vec();
+    message.extend_from_slice(body);
+    key.verify_strict(&message, &signature).is_ok()
+}
+
+fn parse_public_key(hex_key: &str) -> Result<VerifyingKey> {
+    decode_hex(hex_key.trim())
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| {
+            ZeroLatencyError::configuration("Discord public key must be 64 hex characters")
+        })
+}
+
+/// The `query` option of a Discord command, or its first string option
+fn discord_query(interaction: &Value) -> String {
+    let options = interaction["data"]["options"].as_array();
+    let option = options.and_then(|options| {
+        options
+            .iter()
+            .find(|option| option["name"] == "query")
+            .or_else(|| options.iter().find(|option| option["value"].is_string()))
+    });
+    option
+        .and_then(|option| option["value"].as_str())
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+fn slack_results(query: &str, collection: &str, response: &SearchResponse) -> Value {
+    let heading = format!(
+        "Top results for *{}* in `{}`",
+        slack_escape(query),
+        collection
+    );
+    if response.results.is_empty() {
+        return json!({
+            "response_type": "in_channel",
+            "text": format!("No results for *{}* in `{}`", slack_escape(query), collection),
+        });
+    }
+
+    let mut blocks = vec![json!({
+        "type": "section",
+        "text": { "type": "mrkdwn", "text": heading },
+    })];
+    for (index, result) in response.results.iter().enumerate() {
+        let title = slack_escape(&result.title);
+        let link = match &result.url {
+            Some(url) => format!("<{}|{}>", url, title),
+            None => format!("{} (`{}`)", title, result.document_path),
+        };
+        blocks.push(json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": format!(
+                    "*{}.* {}  _{:.2}_\n{}",
+                    index + 1,
+                    link,
+                    result.final_score.value(),
+                    slack_escape(&snippet(result))
+                ),
+            },
+        }));
+    }
+    json!({ "response_type": "in_channel", "text": heading, "blocks": blocks })
+}
+
+fn discord_results(query: &str, collection: &str, response: &SearchResponse) -> Value {
+    if response.results.is_empty() {
+        return json!({
+            "type": 4,
+            "data": { "content": format!("No results for **{}** in `{}`", query, collection) },
+        });
+    }
+    let embeds: Vec<Value> = response
+        .results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| {
+            let mut embed = json!({
+                "title": format!("{}. {}", index + 1, result.title),
+                "description": snippet(result),
+                "footer": {
+                    "text": format!("{} · score {:.2}", result.document_path, result.final_score.value()),
+                },
+            });
+            if let Some(url) = &result.url {
+                embed["url"] = json!(url);
+            }
+            embed
+        })
+        .collect();
+    json!({
+        "type": 4,
+        "data": {
+            "content": format!("Top results for **{}** in `{}`", query, collection),
+            "embeds": embeds,
+        },
+    })
+}
+
+/// Message posted to alert channels, for the events that warrant one
+fn alert_message(event: WebhookEvent, data: &Value) -> Option<String> {
+    let status = match event {
+        WebhookEvent::AlertTriggered => Some(":rotating_light:"),
+        WebhookEvent::AlertResolved => Some(":white_check_mark:"),
+        _ => None,
+    };
+    if let Some(status) = status {
+        let message = data["message"].as_str().unwrap_or("no message was given");
+        return Some(format!("{} {}", status, message));
+    }
+
+    let job = match event {
+        WebhookEvent::IndexFailed => "Indexing",
+        WebhookEvent::ReindexFailed => "Reindexing",
+        _ => return None,
+    };
+    let field = |name: &str| data[name].as_str().unwrap_or("unknown").to_string();
+    Some(format!(
+        ":warning: {} `{}` into `{}` failed: {}",
+        job,
+        field("path"),
+        field("collection"),
+        data["error"].as_str().unwrap_or("no error was reported")
+    ))
+}
+
+/// The result's snippet on one line, cut to [`SNIPPET_CHARS`]
+fn snippet(result: &SearchResult) -> String {
+    let text = result.snippet.as_deref().unwrap_or(&result.content);
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= SNIPPET_CHARS {
+        return text;
+    }
+    let mut cut: String = text.chars().take(SNIPPET_CHARS - 1).collect();
+    cut.push('…');
+    cut
+}
+
+/// Escape the characters Slack treats as markup
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_verifies_slack_signatures() {
+        let body = b"command=%2Fdocs&text=rotate+keys";
+        let now = 1_700_000_000;
+        let mut message = format!("v0:{}:", now).into_bytes();
+        message.extend_from_slice(body);
+        let signature = format!("v0={}", hex(&hmac_sha256(b"slack-secret", &message)));
+        let signed = |timestamp: i64, signature: &str| {
+            headers(&[
+                ("x-slack-request-timestamp", timestamp.to_string()),
+                ("x-slack-signature", signature.to_string()),
+            ])
+        };
+
+        assert!(verify_slack(
+            "slack-secret",
+            &signed(now, &signature),
+            body,
+            now + 10
+        ));
+        assert!(!verify_slack(
+            "other-secret",
+            &signed(now, &signature),
+            body,
+            now
+        ));
+        assert!(!verify_slack(
+            "slack-secret",
+            &signed(now, &signature),
+            b"text=x",
+            now
+        ));
+        // Old requests are replays
+        assert!(!verify_slack(
+            "slack-secret",
+            &signed(now, &signature),
+            body,
+            now + 600
+        ));
+        assert!(!verify_slack("slack-secret", &HeaderMap::new(), body, now));
+
+        let command: SlackCommand = serde_urlencoded::from_bytes(body).unwrap();
+        assert_eq!(command.command, "/docs");
+        assert_eq!(command.text, "rotate keys");
+    }
+
+    #[test]
+    fn test_verifies_discord_signatures() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let key = parse_public_key(&hex(signing_key.verifying_key().as_bytes())).unwrap();
+        let body = br#"{"type":1}"#;
+        let signature = signing_key.sign(&[b"1700000000".as_slice(), body].concat());
+        let signed = headers(&[
+            ("x-signature-timestamp", "1700000000".to_string()),
+            ("x-signature-ed25519", hex(&signature.to_bytes())),
+        ]);
+
+        assert!(verify_discord(&key, &signed, body));
+        assert!(!verify_discord(&key, &signed, br#"{"type":2}"#));
+        assert!(!verify_discord(&key, &HeaderMap::new(), body));
+        assert!(parse_public_key("not hex").is_err());
+        assert!(parse_public_key("abcd").is_err());
+    }
+
+    #[test]
+    fn test_formats_replies() {
+        let interaction = json!({
+            "type": 2,
+            "data": { "options": [{ "name": "query", "type": 3, "value": " rotate keys " }] },
+        });
+        assert_eq!(discord_query(&interaction), "rotate keys");
+        assert_eq!(discord_query(&json!({ "type": 2 })), "");
+
+        let message = alert_message(
+            WebhookEvent::IndexFailed,
+            &json!({ "path": "/docs", "collection": "docs", "error": "disk full" }),
+        );
+        assert_eq!(
+            message.as_deref(),
+            Some(":warning: Indexing `/docs` into `docs` failed: disk full")
+        );
+        assert!(alert_message(WebhookEvent::IndexCompleted, &json!({})).is_none());
+        let message = alert_message(
+            WebhookEvent::AlertResolved,
+            &json!({ "message": "CPU usage is back to 40.0%" }),
+        );
+        assert_eq!(
+            message.as_deref(),
+            Some(":white_check_mark: CPU usage is back to 40.0%")
+        );
+        assert_eq!(slack_escape("a < b & c"), "a &lt; b &amp; c");
+    }
+}
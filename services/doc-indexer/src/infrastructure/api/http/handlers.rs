@@ -1,9 +1,9 @@
 use crate::infrastructure::api::jsonrpc::types::{HealthCheckResult, LivenessResult, ReadinessResult};
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     response::Json,
-    routing::{delete, get, post},
+    routing::{delete, get, patch, post, put},
     Router,
 };
 use serde::{Deserialize, Serialize};
@@ -14,12 +14,71 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Instant;
 use zero_latency_api::endpoints::endpoints;
-use zero_latency_core::ZeroLatencyError;
+use zero_latency_api::endpoints::version::API_VERSION;
+use zero_latency_core::{Uuid, ZeroLatencyError};
 use zero_latency_search::traits::{PopularQuery, SearchAnalytics, SearchTrends};
 
+use crate::application::services::admin_service::{AdminScope, AdminStats, Tunables, TunablesUpdate};
+use crate::infrastructure::operations::log_level::{LogLevelUpdate, LogLevels};
+use crate::application::services::indexing_report::{
+    DiagnosticSeverity, FileDiagnostic, IndexingReport,
+};
+use crate::application::services::document_service::{IndexRunOptions, WarmupReport};
+use crate::application::services::digest::{Digest, DigestDelivery, DigestService};
+use crate::application::services::embedding_migration::{
+    EmbeddingMigrationService, DEFAULT_VALIDATION_SAMPLE, MAX_VALIDATION_SAMPLE,
+};
+use crate::application::services::build_info::BuildInfo;
+use crate::application::services::capabilities::{Capabilities, CapabilityService};
+use crate::application::services::chunk_sample::{ChunkSample, ChunkSampleService};
+use crate::application::services::cost_estimate::{CostEstimate, CostEstimator};
+use crate::application::services::collection_cache::{
+    CachePurgeReport, CollectionCacheService, CollectionCacheStats,
+};
+use crate::application::services::collection_health::{CollectionHealth, CollectionHealthService};
+use crate::application::services::duplicate_report::{
+    DuplicateReport, DuplicateReportService, DEFAULT_CONTENT_THRESHOLD, DEFAULT_VECTOR_THRESHOLD,
+};
+use crate::application::services::projection_service::ProjectionService;
+use crate::application::services::raw_document::{ByteRange, RawDocumentService};
+use crate::application::services::document_outline::{
+    DocumentOutline, DocumentOutlineService, SECTION_SEPARATOR,
+};
+use crate::application::services::vector_staleness::{StalenessReport, VectorStalenessService};
+use crate::application::services::metadata_query::{MetadataQueryResult, MetadataQueryService};
+use crate::application::services::metadata_update::{
+    MetadataUpdate, MetadataUpdateReport, MetadataUpdateService,
+};
+use crate::infrastructure::persistence::metadata_schemas::MetadataSchema;
+use super::opensearch;
+use crate::application::services::curation_service::CurationService;
+use crate::application::services::blocklist_service::BlocklistService;
+use crate::infrastructure::persistence::blocklist::{BlockEntry, NewBlockEntry};
+use crate::infrastructure::persistence::curation::{Boost, CurationRules, NewBoost, NewPin, Pin};
+use crate::application::services::job_service::{IndexingJob, JobKind, JobStatus};
+use crate::application::services::lookup_service::{LookupRequest, LookupResponse};
+use crate::application::services::session_service::{SessionSearchResponse, SessionView};
+use crate::application::services::summary_service::{
+    SummaryResponse, DEFAULT_SUMMARY_SOURCES, MAX_SUMMARY_SOURCES,
+};
+use crate::application::services::throttle::IndexingPriority;
+use crate::application::services::webhook_service::{
+    CreateWebhookRequest, UpdateWebhookRequest, WebhookView,
+};
 use crate::application::{
-    CollectionService, DocumentIndexingService, HealthService, ServiceContainer,
+    AdminService, BrowseService, CollectionService, DocumentIndexingService, HealthService, JobService, LookupService,
+    ServiceContainer, SessionService, SummaryService, WebhookService,
+};
+use crate::infrastructure::memory::VectorPoolStats;
+use crate::infrastructure::operations::offline::OfflineStatus;
+use crate::infrastructure::operations::production::startup::StartupStatus;
+use crate::infrastructure::operations::production::{
+    DeploymentReport, DeploymentTracker, RequestMetrics,
 };
+use crate::infrastructure::operations::usage::{to_csv, UsagePeriod, UsageRecord};
+use crate::infrastructure::persistence::change_log::ChangePage;
+use crate::infrastructure::persistence::vector::scoring::AccelerationStatus;
+use crate::infrastructure::plugins::PluginSummary;
 
 /// Application state shared across all handlers
 #[derive(Clone)]
@@ -29,7 +88,31 @@ pub struct AppState {
     pub document_service: DocumentIndexingService,
     pub health_service: HealthService,
     pub collection_service: CollectionService,
+    pub job_service: JobService,
+    pub lookup_service: LookupService,
+    pub browse_service: BrowseService,
+    pub capability_service: CapabilityService,
+    pub summary_service: SummaryService,
+    pub session_service: SessionService,
+    pub webhook_service: WebhookService,
+    pub migration_service: EmbeddingMigrationService,
+    pub staleness_service: VectorStalenessService,
+    pub sample_service: ChunkSampleService,
+    pub duplicate_service: DuplicateReportService,
+    pub collection_health_service: CollectionHealthService,
+    pub collection_cache_service: CollectionCacheService,
+    pub projection_service: ProjectionService,
+    pub metadata_update_service: MetadataUpdateService,
+    pub metadata_query_service: MetadataQueryService,
+    pub raw_document_service: RawDocumentService,
+    pub outline_service: DocumentOutlineService,
+    pub curation_service: CurationService,
+    pub blocklist_service: BlocklistService,
+    pub digest_service: DigestService,
     pub analytics_service: Arc<crate::infrastructure::operations::analytics::ProductionSearchAnalytics>,
+    pub admin_service: AdminService,
+    pub deployment: DeploymentTracker,
+    pub request_metrics: RequestMetrics,
     pub start_time: Instant,
 }
 
@@ -90,7 +173,7 @@ impl AppState {
             DocumentIndexingService::new(&container)
         };
 
-        let health_service = HealthService::new();
+        let health_service = HealthService::new().with_webhooks(container.webhooks());
         let collection_service = CollectionService::new(&container);
 
         // Initialize collection stats from actual vector repository
@@ -98,37 +181,255 @@ impl AppState {
 
         // Use the analytics service from the container (shared with search pipeline)
         let analytics_service = container.analytics();
+        let migration_service = EmbeddingMigrationService::new(&container);
+        let staleness_service = VectorStalenessService::new(&container);
+        let sample_service = ChunkSampleService::new(&container);
+        let duplicate_service = DuplicateReportService::new(&container);
+        let projection_service = ProjectionService::new(&container);
+        let metadata_update_service = MetadataUpdateService::new(&container);
+        let metadata_query_service = MetadataQueryService::new(&container);
+        let raw_document_service = RawDocumentService::new(&container);
+        let outline_service = DocumentOutlineService::new(&container);
+        let curation_service = CurationService::new(&container);
+        let blocklist_service = BlocklistService::new(&container);
+        let digest_service = DigestService::new(&container)?;
+        let lookup_service = LookupService::new(
+            document_service.clone(),
+            collection_service.clone(),
+            config.service.default_collection.clone(),
+        );
+        container
+            .memory_monitor()
+            .register("lookup", lookup_service.cache());
+        let collection_cache_service =
+            CollectionCacheService::new(&container, lookup_service.clone());
+        let browse_service = BrowseService::new(&container);
+        let capability_service = CapabilityService::new(&container);
+        let summary_service =
+            SummaryService::new(container.summarizer(), config.summarizer.max_bullets);
+        let session_service = SessionService::new(std::time::Duration::from_secs(
+            config.service.session_ttl_secs,
+        ));
+        let webhook_service = container.webhooks();
+        let job_service = JobService::new()
+            .with_store(container.job_store())
+            .await?
+            .with_webhooks(webhook_service.clone());
+        let admin_service =
+            AdminService::new(container.clone(), lookup_service.clone(), job_service.clone());
+        let collection_health_service = CollectionHealthService::new(&container, job_service.clone());
+        let deployment = DeploymentTracker::new().with_webhooks(webhook_service.clone());
 
         Ok(Self {
             container,
             document_service,
             health_service,
             collection_service,
+            job_service,
+            lookup_service,
+            browse_service,
+            capability_service,
+            summary_service,
+            session_service,
+            webhook_service,
+            migration_service,
+            staleness_service,
+            sample_service,
+            duplicate_service,
+            collection_health_service,
+            collection_cache_service,
+            projection_service,
+            metadata_update_service,
+            metadata_query_service,
+            raw_document_service,
+            outline_service,
+            curation_service,
+            blocklist_service,
+            digest_service,
             analytics_service,
+            admin_service,
+            deployment,
+            request_metrics: RequestMetrics::new(),
             start_time: Instant::now(),
         })
     }
 }
 
+/// Translate an endpoint template's `{param}` captures into axum's `:param`
+fn route_path(template: &str) -> String {
+    template.replace('{', ":").replace('}', "")
+}
+
 /// Create the application router with all routes
 pub fn create_router(state: AppState) -> Router {
     Router::new()
         // API endpoints (expected by CLI)
         .route(endpoints::STATUS, get(api_status))
+        .route(endpoints::CAPABILITIES, get(get_capabilities))
+        .route(endpoints::BUILD_INFO, get(get_build_info))
         .route(endpoints::SEARCH, post(search_documents))
+        .route(endpoints::SEARCH_SUMMARIZE, post(summarize_search))
+        .route(endpoints::LOOKUP, post(lookup_symbol))
+        .route(endpoints::SESSIONS, post(create_session))
+        .route(&route_path(endpoints::SESSION_BY_ID), get(get_session))
+        .route(
+            &route_path(endpoints::SESSION_BY_ID),
+            delete(delete_session),
+        )
+        .route(
+            &route_path(endpoints::SESSION_SEARCH),
+            post(search_in_session),
+        )
+        .route(
+            &route_path(endpoints::SESSION_SELECTIONS),
+            post(record_session_selection),
+        )
+        .route(endpoints::WEBHOOKS, get(list_webhooks))
+        .route(endpoints::WEBHOOKS, post(create_webhook))
+        .route(&route_path(endpoints::WEBHOOK_BY_ID), get(get_webhook))
+        .route(&route_path(endpoints::WEBHOOK_BY_ID), patch(update_webhook))
+        .route(
+            &route_path(endpoints::WEBHOOK_BY_ID),
+            delete(delete_webhook),
+        )
+        .route(endpoints::CURATION, get(list_curation))
+        .route(endpoints::CURATION_PINS, post(create_pin))
+        .route(
+            &route_path(endpoints::CURATION_PIN_BY_ID),
+            delete(delete_pin),
+        )
+        .route(endpoints::CURATION_BOOSTS, post(create_boost))
+        .route(
+            &route_path(endpoints::CURATION_BOOST_BY_ID),
+            delete(delete_boost),
+        )
+        .route(endpoints::BLOCKLIST, get(list_blocklist))
+        .route(endpoints::BLOCKLIST, post(create_blocklist_entry))
+        .route(
+            &route_path(endpoints::BLOCKLIST_ENTRY_BY_ID),
+            delete(delete_blocklist_entry),
+        )
+        .route(endpoints::EVENTS, get(list_change_events))
+        .route(endpoints::DIGEST, get(preview_digest))
+        .route(endpoints::DIGEST_SEND, post(send_digest))
+        .route(endpoints::PLUGINS, get(list_plugins))
+        .route(endpoints::ADMIN_STATS, get(admin_stats))
+        .route(endpoints::ADMIN_TUNABLES, get(get_tunables))
+        .route(endpoints::ADMIN_TUNABLES, put(update_tunables))
+        .route(endpoints::ADMIN_LOG_LEVEL, get(get_log_levels))
+        .route(endpoints::ADMIN_LOG_LEVEL, put(update_log_levels))
+        .route(endpoints::ADMIN_USAGE, get(export_usage))
+        .route(endpoints::ADMIN_DEPLOYMENT, get(deployment_status))
         .route(endpoints::INDEX, post(index_documents_from_path))
         .route(endpoints::REINDEX, post(reindex_documents))
+        .route(endpoints::JOBS, get(list_jobs))
+        .route(&route_path(endpoints::JOB_BY_ID), get(get_job))
+        .route(&route_path(endpoints::JOB_RESUME), post(resume_job))
+        .route(&route_path(endpoints::JOB_ERRORS), get(get_job_errors))
         .route(endpoints::SERVER_START, post(start_server))
         .route(endpoints::SERVER_STOP, post(stop_server))
         // Collection endpoints
         .route(endpoints::COLLECTIONS, get(list_collections))
         .route(endpoints::COLLECTIONS, post(create_collection))
-        .route(endpoints::COLLECTION_BY_NAME, get(get_collection))
-        .route(endpoints::COLLECTION_BY_NAME, delete(delete_collection))
-        .route(endpoints::COLLECTION_STATS, get(get_collection_stats))
+        .route(
+            &route_path(endpoints::COLLECTION_BY_NAME),
+            get(get_collection),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_BY_NAME),
+            delete(delete_collection),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_STATS),
+            get(get_collection_stats),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_WARM),
+            post(warm_collection),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_MIGRATE_EMBEDDINGS),
+            post(migrate_collection_embeddings),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_STALENESS),
+            get(get_collection_staleness),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_REEMBED_STALE),
+            post(reembed_stale_vectors),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_SAMPLE),
+            get(sample_collection_chunks),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_DUPLICATES),
+            get(get_collection_duplicates),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_HEALTH),
+            get(get_collection_health),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_PROJECTION),
+            post(project_collection),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_PROJECTION),
+            get(export_collection_projection),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_CACHE),
+            get(get_collection_cache),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_CACHE_PURGE),
+            post(purge_collection_cache),
+        )
+        .route(
+            // The path is a wildcard so it can contain slashes
+            &route_path(endpoints::COLLECTION_RAW).replace(":path", "*path"),
+            get(get_raw_document),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_SCHEMA),
+            get(get_metadata_schema),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_SCHEMA),
+            put(put_metadata_schema),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_SCHEMA),
+            delete(delete_metadata_schema),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_DOCUMENTS_METADATA),
+            patch(update_documents_metadata),
+        )
+        .route(
+            &route_path(endpoints::COLLECTION_DOCUMENTS_QUERY),
+            post(query_documents_metadata),
+        )
         // Document endpoints (read-only for discovery)
+        // Elasticsearch/OpenSearch compatibility
+        .route(endpoints::ES_COMPAT, get(es_compat_info))
+        .route(
+            &route_path(endpoints::ES_COMPAT_SEARCH),
+            get(es_compat_search).post(es_compat_search),
+        )
+        .route(endpoints::ES_COMPAT_MSEARCH, post(es_compat_msearch))
+        .route(
+            &route_path(endpoints::ES_COMPAT_INDEX_MSEARCH),
+            post(es_compat_index_msearch),
+        )
         .route(endpoints::DOCUMENTS, get(list_documents))
-        .route(endpoints::DOCUMENT_BY_ID, get(get_document))
+        .route(&route_path(endpoints::DOCUMENT_BY_ID), get(get_document))
+        .route(
+            &route_path(endpoints::DOCUMENT_OUTLINE),
+            get(get_document_outline),
+        )
         .route(endpoints::DOCUMENTS_SEARCH, post(search_documents))
         // Analytics endpoints - partially enabled for testing
         .route(endpoints::ANALYTICS_SUMMARY, get(get_analytics_summary))
@@ -137,9 +438,11 @@ pub fn create_router(state: AppState) -> Router {
             get(get_popular_queries),
         )
         .route(endpoints::ANALYTICS_SEARCH_TRENDS, get(get_search_trends))
+        .route(endpoints::ANALYTICS_SLOW_QUERIES, get(get_slow_queries))
         // Health endpoints
         .route("/health", get(health_check))
         .route("/health/ready", get(readiness_check))
+        .route(endpoints::HEALTH_STARTUP, get(startup_check))
         .route("/health/live", get(liveness_check))
         // Info endpoints
         .route("/info", get(service_info))
@@ -270,11 +573,191 @@ async fn get_document(
     }))
 }
 
+/// Query parameters for a document outline
+#[derive(Debug, Deserialize)]
+pub struct DocumentOutlineQuery {
+    /// Collection of the document; the default collection if unset
+    pub collection: Option<String>,
+    /// Heading path, joined by ` > `, of a section to return the chunks of
+    pub section: Option<String>,
+}
+
+/// Outline a document by the headings it was chunked at
+///
+/// Lists the chunk ids of every section; with `section`, the chunks of
+/// that section are returned as well.
+async fn get_document_outline(
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<DocumentOutlineQuery>,
+) -> Result<Json<DocumentOutline>, AppError> {
+    let document_id = Uuid::parse_str(&id)
+        .map_err(|_| AppError(ZeroLatencyError::validation("id", "must be a UUID")))?;
+    let section: Option<Vec<String>> = params.section.as_deref().map(|section| {
+        section
+            .split(SECTION_SEPARATOR)
+            .map(|heading| heading.trim().to_string())
+            .collect()
+    });
+    let collection = params
+        .collection
+        .unwrap_or_else(|| state.container.config().service.default_collection.clone());
+
+    Ok(Json(
+        state
+            .outline_service
+            .outline(&collection, document_id, section.as_deref())
+            .await?,
+    ))
+}
+
 /// Search for documents
 async fn search_documents(
     State(state): State<AppState>,
     Json(request): Json<zero_latency_api::SearchRequest>,
 ) -> Result<Json<zero_latency_search::SearchResponse>, AppError> {
+    Ok(Json(run_search(&state, &request).await?))
+}
+
+/// Body of a summarize request: a search request plus digest options
+#[derive(Debug, Deserialize)]
+struct SummarizeRequest {
+    #[serde(flatten)]
+    search: zero_latency_api::SearchRequest,
+    /// Most bullets in the digest; the configured default if unset
+    #[serde(default)]
+    max_bullets: Option<usize>,
+}
+
+/// Search and return a source-attributed digest of the results
+///
+/// Takes every search option; `limit` is the number of results summarized.
+async fn summarize_search(
+    State(state): State<AppState>,
+    Json(request): Json<SummarizeRequest>,
+) -> Result<Json<SummaryResponse>, AppError> {
+    let started = Instant::now();
+    let mut search = request.search;
+    let limit = search.limit.unwrap_or(DEFAULT_SUMMARY_SOURCES as i32);
+    if !(1..=MAX_SUMMARY_SOURCES as i32).contains(&limit) {
+        return Err(AppError(ZeroLatencyError::validation(
+            "limit",
+            format!("must be between 1 and {}", MAX_SUMMARY_SOURCES),
+        )));
+    }
+    search.limit = Some(limit);
+
+    let response = run_search(&state, &search).await?;
+    let (query, _) = zero_latency_search::Exclusions::from_query(&search.query);
+    let summary = state
+        .summary_service
+        .summarize(&query, response.results, request.max_bullets, started)
+        .await?;
+    Ok(Json(summary))
+}
+
+/// Cluster info for Elasticsearch clients that check it before searching
+async fn es_compat_info() -> (HeaderMap, Json<serde_json::Value>) {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-elastic-product",
+        header::HeaderValue::from_static("Elasticsearch"),
+    );
+    let info = serde_json::json!({
+        "name": "doc-indexer",
+        "cluster_name": "doc-indexer",
+        "version": {
+            "number": opensearch::COMPATIBLE_VERSION,
+            "build_flavor": "default",
+            "doc_indexer": env!("CARGO_PKG_VERSION"),
+        },
+        "tagline": "You Know, for Search",
+    });
+    (headers, Json(info))
+}
+
+/// Elasticsearch-compatible `_search`; see [`opensearch`] for the
+/// supported query DSL
+async fn es_compat_search(
+    Path(index): Path<String>,
+    State(state): State<AppState>,
+    body: axum::body::Bytes,
+) -> Result<Json<opensearch::CompatResponse>, AppError> {
+    // Clients send the body with GET as well as POST, and may omit it
+    let body = if body.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_slice(&body)
+            .map_err(|e| AppError(ZeroLatencyError::validation("body", e.to_string())))?
+    };
+    Ok(Json(run_compat_search(&state, &index, &body).await?))
+}
+
+/// Elasticsearch-compatible `_msearch`, as sent by Grafana
+async fn es_compat_msearch(
+    State(state): State<AppState>,
+    body: String,
+) -> Result<Json<serde_json::Value>, AppError> {
+    msearch(&state, None, &body).await
+}
+
+/// `_msearch` whose searches default to the index in the path
+async fn es_compat_index_msearch(
+    Path(index): Path<String>,
+    State(state): State<AppState>,
+    body: String,
+) -> Result<Json<serde_json::Value>, AppError> {
+    msearch(&state, Some(&index), &body).await
+}
+
+async fn msearch(
+    state: &AppState,
+    default_index: Option<&str>,
+    body: &str,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let started = Instant::now();
+    let searches = opensearch::parse_msearch(body, default_index)?;
+    let mut responses = Vec::with_capacity(searches.len());
+    // A failed search is reported in its slot; the others still run
+    for (index, body) in searches {
+        let response = match run_compat_search(state, &index, &body).await {
+            Ok(response) => {
+                let mut response = serde_json::to_value(response)
+                    .map_err(|e| AppError(ZeroLatencyError::internal(e.to_string())))?;
+                response["status"] = 200.into();
+                response
+            }
+            Err(AppError(error)) => opensearch::error_item(&error),
+        };
+        responses.push(response);
+    }
+    Ok(Json(serde_json::json!({
+        "took": started.elapsed().as_millis() as u64,
+        "responses": responses,
+    })))
+}
+
+async fn run_compat_search(
+    state: &AppState,
+    index: &str,
+    body: &serde_json::Value,
+) -> Result<opensearch::CompatResponse, AppError> {
+    let started = Instant::now();
+    let search = opensearch::translate(index, body)?;
+    let response = run_search(state, &search.request).await?;
+    Ok(opensearch::CompatResponse::new(
+        &search,
+        index,
+        response,
+        started.elapsed(),
+    ))
+}
+
+/// Run a search request against one collection or, federated, several
+async fn run_search(
+    state: &AppState,
+    request: &zero_latency_api::SearchRequest,
+) -> Result<zero_latency_search::SearchResponse, AppError> {
     let default_collection = &state.container.config().service.default_collection;
     
     // Extract collection from filters or use default
@@ -284,14 +767,630 @@ async fn search_documents(
         default_collection
     };
     
-    let limit = request.limit.unwrap_or(10) as usize;
+    let overrides = zero_latency_search::SearchSettings {
+        limit: request.limit.map(|limit| limit.max(0) as usize),
+        fusion_weights: request.fusion_weights.as_deref().map(|weights| {
+            zero_latency_search::FusionWeights {
+                bm25_weight: weights.bm25_weight,
+                vector_weight: weights.vector_weight,
+            }
+        }),
+        query_expansion: request.query_expansion,
+        rerank_depth: request.rerank_depth.map(|depth| depth.max(0) as usize),
+        extract_answer: request.extract_answer,
+        min_score: request.min_score,
+        field_weights: None,
+        proximity_weight: None,
+        vector_spaces: request.vector_spaces.as_ref().map(|spaces| {
+            spaces
+                .iter()
+                .map(|space| {
+                    zero_latency_search::VectorSpace::new(
+                        space.name.clone(),
+                        space.weight.unwrap_or(1.0),
+                    )
+                })
+                .collect()
+        }),
+        late_interaction: request.late_interaction,
+        context_window: request.context_window.map(|window| window.max(0) as usize),
+    };
+    if request.late_interaction == Some(true) && !state.container.late_interaction_available() {
+        return Err(AppError(ZeroLatencyError::validation(
+            "late_interaction",
+            "Late interaction needs the late-interaction feature and DOC_INDEXER_LATE_INTERACTION=true",
+        )));
+    }
+
+    // Exclusions come from the request fields and from operators in the query
+    let (query, mut exclusions) = zero_latency_search::Exclusions::from_query(&request.query);
+    if let Some(filters) = &request.filters {
+        exclusions.merge(zero_latency_search::Exclusions {
+            tags: filters.exclude_tags.clone().unwrap_or_default(),
+            collections: filters.exclude_collection_names.clone().unwrap_or_default(),
+            ..Default::default()
+        });
+    }
+    exclusions.merge(zero_latency_search::Exclusions {
+        documents: request.exclude_documents.clone().unwrap_or_default(),
+        ..Default::default()
+    });
+
+    // Several collections (or glob patterns) make this a federated search
+    let collection_patterns = request
+        .filters
+        .as_ref()
+        .and_then(|filters| filters.collection_names.clone())
+        .unwrap_or_default();
+    let collections = if collection_patterns.is_empty() {
+        vec![collection_name.to_string()]
+    } else {
+        let mut collections = state
+            .collection_service
+            .resolve_collections(&collection_patterns)
+            .await?;
+        collections.retain(|name| !exclusions.collections.contains(name));
+        if collections.is_empty() {
+            return Err(AppError(ZeroLatencyError::validation(
+                "collection_names",
+                "Every requested collection is excluded",
+            )));
+        }
+        collections
+    };
+    let filters = zero_latency_search::SearchFilters {
+        exclude: exclusions,
+        metadata_patterns: request
+            .filters
+            .as_ref()
+            .and_then(|filters| filters.metadata_patterns.clone())
+            .unwrap_or_default(),
+        ranges: request
+            .filters
+            .iter()
+            .flat_map(|filters| filters.ranges.iter().flatten())
+            .map(|(key, range)| {
+                let range = zero_latency_vector::NumericRange {
+                    gte: range.gte,
+                    lte: range.lte,
+                };
+                (key.clone(), range)
+            })
+            .collect(),
+        ..Default::default()
+    };
+    // Queries with nothing to search for list the collections' documents
+    if zero_latency_search::is_browse_query(&query) {
+        let order = match request.browse_order.as_deref() {
+            Some(order) => order.parse::<zero_latency_search::BrowseOrder>()?,
+            None => zero_latency_search::BrowseOrder::default(),
+        };
+        let settings = state
+            .collection_service
+            .effective_search_settings(&collections[0], &overrides)
+            .await?;
+        let response = state
+            .browse_service
+            .browse(
+                &query,
+                &collections,
+                order,
+                settings.limit.unwrap_or(10),
+                &filters,
+            )
+            .await?;
+        return Ok(response);
+    }
+    if collections.len() > 1 {
+        return search_federated(state, &query, collections, &overrides, &filters).await;
+    }
+
+    let settings = state
+        .collection_service
+        .effective_search_settings(&collections[0], &overrides)
+        .await?;
+
+    let search_response = state
+        .document_service
+        .search_documents_with_settings(&query, &collections[0], &settings, &filters)
+        .await?;
+
+    Ok(search_response)
+}
+
+/// Definition-style lookup for editor extensions
+///
+/// Returns up to three short snippets for a symbol; see
+/// [`LookupService`] for how the pipeline is trimmed for latency.
+async fn lookup_symbol(
+    State(state): State<AppState>,
+    Json(request): Json<LookupRequest>,
+) -> Result<Json<LookupResponse>, AppError> {
+    let response = state.lookup_service.lookup(&request).await?;
+    Ok(Json(response))
+}
+
+/// Start a conversation-aware search session
+async fn create_session(State(state): State<AppState>) -> (StatusCode, Json<SessionView>) {
+    (
+        StatusCode::CREATED,
+        Json(state.session_service.create().await),
+    )
+}
+
+/// Get a session's recent queries and selections
+async fn get_session(
+    Path(session_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<SessionView>, AppError> {
+    Ok(Json(state.session_service.get(&session_id).await?))
+}
+
+/// End a session
+async fn delete_session(
+    Path(session_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    state.session_service.delete(&session_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Search within a session
+///
+/// Follow-up queries are searched with the session's topic added, and
+/// documents selected earlier in the session rank higher. The query is
+/// recorded as the session's latest turn.
+async fn search_in_session(
+    Path(session_id): Path<String>,
+    State(state): State<AppState>,
+    Json(mut request): Json<zero_latency_api::SearchRequest>,
+) -> Result<Json<SessionSearchResponse>, AppError> {
+    let query = request.query.clone();
+    let contextualized_query = state
+        .session_service
+        .contextualize(&session_id, &query)
+        .await?;
+    if let Some(contextualized) = &contextualized_query {
+        request.query = contextualized.clone();
+    }
+
+    let mut search = run_search(&state, &request).await?;
+    let boosted_results = state
+        .session_service
+        .record_search(
+            &session_id,
+            &query,
+            contextualized_query.clone(),
+            &mut search.results,
+        )
+        .await?;
+
+    Ok(Json(SessionSearchResponse {
+        session_id,
+        contextualized_query,
+        boosted_results,
+        search,
+    }))
+}
+
+/// A search result the user opened
+#[derive(Debug, Deserialize)]
+struct SessionSelectionRequest {
+    document_id: String,
+}
+
+/// Record that the user opened a result, so it ranks higher in later searches
+async fn record_session_selection(
+    Path(session_id): Path<String>,
+    State(state): State<AppState>,
+    Json(request): Json<SessionSelectionRequest>,
+) -> Result<Json<SessionView>, AppError> {
+    let session = state
+        .session_service
+        .record_selection(&session_id, &request.document_id)
+        .await?;
+    Ok(Json(session))
+}
+
+/// List registered webhooks
+async fn list_webhooks(State(state): State<AppState>) -> Json<Vec<WebhookView>> {
+    Json(state.webhook_service.list().await)
+}
+
+/// Register a webhook; the only response that includes its signing secret
+async fn create_webhook(
+    State(state): State<AppState>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookView>), AppError> {
+    let webhook = state.webhook_service.create(request).await?;
+    Ok((StatusCode::CREATED, Json(webhook)))
+}
+
+/// Get a webhook and its recent deliveries
+async fn get_webhook(
+    Path(webhook_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<WebhookView>, AppError> {
+    Ok(Json(state.webhook_service.get(&webhook_id).await?))
+}
+
+/// Change a webhook's URL, events, secret or active flag
+async fn update_webhook(
+    Path(webhook_id): Path<String>,
+    State(state): State<AppState>,
+    Json(request): Json<UpdateWebhookRequest>,
+) -> Result<Json<WebhookView>, AppError> {
+    Ok(Json(
+        state.webhook_service.update(&webhook_id, request).await?,
+    ))
+}
+
+/// Remove a webhook
+async fn delete_webhook(
+    Path(webhook_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    state.webhook_service.delete(&webhook_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionScopeQuery {
+    /// Only entries of this collection
+    pub collection: Option<String>,
+}
+
+/// List search curation rules
+async fn list_curation(
+    State(state): State<AppState>,
+    Query(params): Query<CollectionScopeQuery>,
+) -> Json<CurationRules> {
+    Json(state.curation_service.list(params.collection.as_deref()))
+}
+
+/// Pin documents to the top of a query's results
+async fn create_pin(
+    State(state): State<AppState>,
+    Json(request): Json<NewPin>,
+) -> Result<(StatusCode, Json<Pin>), AppError> {
+    let pin = state.curation_service.add_pin(request)?;
+    Ok((StatusCode::CREATED, Json(pin)))
+}
+
+/// Remove a pin
+async fn delete_pin(
+    Path(pin_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    state.curation_service.remove_pin(&pin_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Boost or demote documents in a collection's searches
+async fn create_boost(
+    State(state): State<AppState>,
+    Json(request): Json<NewBoost>,
+) -> Result<(StatusCode, Json<Boost>), AppError> {
+    let boost = state.curation_service.add_boost(request)?;
+    Ok((StatusCode::CREATED, Json(boost)))
+}
+
+/// Remove a boost
+async fn delete_boost(
+    Path(boost_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    state.curation_service.remove_boost(&boost_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List blocked documents and path patterns
+async fn list_blocklist(
+    State(state): State<AppState>,
+    Query(params): Query<CollectionScopeQuery>,
+) -> Json<Vec<BlockEntry>> {
+    Json(state.blocklist_service.list(params.collection.as_deref()))
+}
+
+/// Hide a document or path pattern from a collection's searches
+async fn create_blocklist_entry(
+    State(state): State<AppState>,
+    Json(request): Json<NewBlockEntry>,
+) -> Result<(StatusCode, Json<BlockEntry>), AppError> {
+    let entry = state.blocklist_service.add(request)?;
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// Unblock an entry
+async fn delete_blocklist_entry(
+    Path(entry_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    state.blocklist_service.remove(&entry_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Events returned per page unless `limit` says otherwise
+const DEFAULT_EVENTS_LIMIT: usize = 100;
+
+/// Upper bound on `limit` for change events
+const MAX_EVENTS_LIMIT: usize = 1000;
+
+/// Query parameters for reading the change log
+#[derive(Debug, Deserialize)]
+pub struct ChangeEventsQuery {
+    /// Sequence of the last event already seen; 0 reads from the start
+    pub since: Option<u64>,
+    pub limit: Option<usize>,
+    /// Only events of this collection
+    pub collection: Option<String>,
+}
+
+/// Read index mutations after a cursor
+///
+/// Clients store `next_cursor` and pass it back as `since`. A `truncated`
+/// page means events were dropped before the client read them, so it has to
+/// resync from a full listing.
+async fn list_change_events(
+    State(state): State<AppState>,
+    Query(params): Query<ChangeEventsQuery>,
+) -> Result<Json<ChangePage>, AppError> {
+    let limit = params.limit.unwrap_or(DEFAULT_EVENTS_LIMIT);
+    if limit == 0 || limit > MAX_EVENTS_LIMIT {
+        return Err(AppError(ZeroLatencyError::validation(
+            "limit",
+            format!("must be between 1 and {}", MAX_EVENTS_LIMIT),
+        )));
+    }
+
+    Ok(Json(state.container.change_log().read(
+        params.since.unwrap_or(0),
+        limit,
+        params.collection.as_deref(),
+    )))
+}
+
+/// Query parameters for compiling a digest
+#[derive(Debug, Deserialize)]
+pub struct DigestQuery {
+    /// Hours the digest looks back; the digest interval (or 24) by default
+    pub hours: Option<u64>,
+}
+
+impl DigestQuery {
+    fn since(&self, state: &AppState) -> Result<chrono::DateTime<chrono::Utc>, AppError> {
+        let hours = self
+            .hours
+            .unwrap_or_else(|| state.digest_service.default_hours());
+        if hours == 0 || hours > MAX_DIGEST_HOURS {
+            return Err(AppError(ZeroLatencyError::validation(
+                "hours",
+                format!("must be between 1 and {}", MAX_DIGEST_HOURS),
+            )));
+        }
+        Ok(chrono::Utc::now() - chrono::Duration::hours(hours as i64))
+    }
+}
+
+/// Upper bound on the period a digest covers, a year
+const MAX_DIGEST_HOURS: u64 = 24 * 366;
+
+/// Compile the digest of recent changes without sending it
+async fn preview_digest(
+    State(state): State<AppState>,
+    Query(params): Query<DigestQuery>,
+) -> Result<Json<Digest>, AppError> {
+    let since = params.since(&state)?;
+    Ok(Json(state.digest_service.compile(since).await?))
+}
+
+/// Compile the digest of recent changes and send it now
+async fn send_digest(
+    State(state): State<AppState>,
+    Query(params): Query<DigestQuery>,
+) -> Result<Json<DigestDelivery>, AppError> {
+    let since = params.since(&state)?;
+    let digest = state.digest_service.compile(since).await?;
+    Ok(Json(state.digest_service.send(digest).await?))
+}
+
+/// List loaded plugins in the order they run
+async fn list_plugins(State(state): State<AppState>) -> Json<Vec<PluginSummary>> {
+    Json(state.container.plugins().list())
+}
+
+/// Bearer token from the `Authorization` header
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+/// Runtime statistics of caches, pools, storage and jobs
+async fn admin_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<AdminStats>, AppError> {
+    state
+        .admin_service
+        .authorize(bearer_token(&headers), AdminScope::Read)?;
+    Ok(Json(state.admin_service.stats().await?))
+}
+
+/// Production deployment status, component states and recent transitions
+async fn deployment_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<DeploymentReport>, AppError> {
+    state
+        .admin_service
+        .authorize(bearer_token(&headers), AdminScope::Read)?;
+    Ok(Json(state.deployment.report()))
+}
+
+/// Current runtime tunables
+async fn get_tunables(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Tunables>, AppError> {
+    state
+        .admin_service
+        .authorize(bearer_token(&headers), AdminScope::Read)?;
+    Ok(Json(state.admin_service.tunables()))
+}
+
+/// Change runtime tunables
+async fn update_tunables(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(update): Json<TunablesUpdate>,
+) -> Result<Json<Tunables>, AppError> {
+    state
+        .admin_service
+        .authorize(bearer_token(&headers), AdminScope::Write)?;
+    Ok(Json(state.admin_service.update_tunables(update)?))
+}
+
+/// Log levels by target and the request sample rate
+async fn get_log_levels(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<LogLevels>, AppError> {
+    state
+        .admin_service
+        .authorize(bearer_token(&headers), AdminScope::Read)?;
+    Ok(Json(state.admin_service.log_levels()))
+}
+
+/// Change the level of a log target or the request sample rate
+async fn update_log_levels(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(update): Json<LogLevelUpdate>,
+) -> Result<Json<LogLevels>, AppError> {
+    state
+        .admin_service
+        .authorize(bearer_token(&headers), AdminScope::Write)?;
+    Ok(Json(state.admin_service.update_log_levels(update)?))
+}
+
+/// Query parameters for exporting usage
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// First day to include; the first day of the current month by default
+    pub from: Option<chrono::NaiveDate>,
+    /// Last day to include; today by default
+    pub to: Option<chrono::NaiveDate>,
+    #[serde(default)]
+    pub period: UsagePeriod,
+    /// Only usage of this tenant
+    pub tenant: Option<String>,
+    /// `json` (default) or `csv`
+    pub format: Option<String>,
+}
+
+/// Usage per tenant and period
+#[derive(Debug, Serialize)]
+pub struct UsageExport {
+    pub from: chrono::NaiveDate,
+    pub to: chrono::NaiveDate,
+    pub period: UsagePeriod,
+    pub records: Vec<UsageRecord>,
+}
+
+/// Export per-tenant usage for billing
+async fn export_usage(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<UsageQuery>,
+) -> Result<axum::response::Response, AppError> {
+    use axum::response::IntoResponse;
+    use chrono::Datelike;
+
+    state
+        .admin_service
+        .authorize(bearer_token(&headers), AdminScope::Read)?;
+
+    let today = chrono::Utc::now().date_naive();
+    let from = params.from.unwrap_or_else(|| today.with_day(1).unwrap_or(today));
+    let to = params.to.unwrap_or(today);
+    if from > to {
+        return Err(AppError(ZeroLatencyError::validation(
+            "from",
+            "must not be after to",
+        )));
+    }
+    let records = state.container.usage().export(
+        from,
+        to,
+        params.period,
+        params.tenant.as_deref(),
+    );
+
+    match params.format.as_deref().unwrap_or("json") {
+        "json" => Ok(Json(UsageExport {
+            from,
+            to,
+            period: params.period,
+            records,
+        })
+        .into_response()),
+        "csv" => Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"usage-{}-{}.csv\"", from, to),
+                ),
+            ],
+            to_csv(&records),
+        )
+            .into_response()),
+        _ => Err(AppError(ZeroLatencyError::validation(
+            "format",
+            "must be json or csv",
+        ))),
+    }
+}
+
+/// Search several collections at once
+///
+/// Each collection runs with its own resolved settings, but all of them fetch
+/// the largest resolved limit so any collection can fill the merged page.
+async fn search_federated(
+    state: &AppState,
+    query: &str,
+    collections: Vec<String>,
+    overrides: &zero_latency_search::SearchSettings,
+    filters: &zero_latency_search::SearchFilters,
+) -> Result<zero_latency_search::SearchResponse, AppError> {
+    let mut per_collection = Vec::with_capacity(collections.len());
+    for collection_name in collections {
+        let settings = state
+            .collection_service
+            .effective_search_settings(&collection_name, overrides)
+            .await?;
+        per_collection.push((collection_name, settings));
+    }
+
+    let limit = per_collection
+        .iter()
+        .filter_map(|(_, settings)| settings.limit)
+        .max()
+        .unwrap_or(state.container.config().service.default_search_limit);
+    for (_, settings) in &mut per_collection {
+        settings.limit = Some(limit);
+    }
 
     let search_response = state
         .document_service
-        .search_documents_in_collection(&request.query, collection_name, limit)
+        .search_documents_federated(query, &per_collection, limit, filters)
         .await?;
 
-    Ok(Json(search_response))
+    Ok(search_response)
 }
 
 /// Health check endpoint
@@ -314,6 +1413,7 @@ async fn api_status(State(state): State<AppState>) -> Json<ApiStatusResponse> {
     Json(ApiStatusResponse {
         status: "healthy".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
+        api_version: API_VERSION.to_string(),
         uptime_seconds,
         total_documents: document_count,
         index_size_bytes: index_size,
@@ -327,15 +1427,39 @@ async fn api_status(State(state): State<AppState>) -> Json<ApiStatusResponse> {
                 .display()
                 .to_string(),
         ),
+        vector_pool: state.container.vector_pool().stats(),
+        acceleration: state.container.acceleration(),
+        offline: state.container.offline(),
     })
 }
 
+/// Features this build and configuration support
+async fn get_capabilities(State(state): State<AppState>) -> Json<Capabilities> {
+    Json(state.capability_service.capabilities())
+}
+
+/// Report what this binary was built with
+async fn get_build_info() -> Json<BuildInfo> {
+    Json(BuildInfo::current())
+}
+
 /// Readiness check endpoint
 async fn readiness_check(State(state): State<AppState>) -> Result<Json<ReadinessResult>, AppError> {
     let readiness = state.health_service.readiness_check().await?;
     Ok(Json(readiness))
 }
 
+/// Latest startup validation report; 503 until validation has passed
+async fn startup_check(State(state): State<AppState>) -> (StatusCode, Json<StartupStatus>) {
+    let report = state.health_service.startup_report();
+    let status = if report.ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report.status()))
+}
+
 /// Liveness check endpoint
 async fn liveness_check(State(state): State<AppState>) -> Result<Json<LivenessResult>, AppError> {
     let liveness = state.health_service.liveness_check().await?;
@@ -361,7 +1485,7 @@ async fn service_info(State(_state): State<AppState>) -> Json<ServiceInfoRespons
 async fn index_documents_from_path(
     State(state): State<AppState>,
     Json(request): Json<IndexPathRequest>,
-) -> Result<Json<IndexPathResponse>, AppError> {
+) -> Result<(StatusCode, Json<IndexPathResponse>), AppError> {
     let collection_name = request.collection.as_deref().unwrap_or("zero_latency_docs");
     tracing::info!(
         "Starting document indexing from path: {} into collection: {}",
@@ -375,6 +1499,7 @@ async fn index_documents_from_path(
         || request.clear_default_ignores.is_some()
         || request.follow_symlinks.is_some()
         || request.case_sensitive.is_some()
+        || request.max_file_size.is_some()
     {
         use crate::application::services::filter_service::IndexingFilters;
 
@@ -394,11 +1519,30 @@ async fn index_documents_from_path(
             ignore_list,
             case_sensitive: request.case_sensitive.unwrap_or(false),
             follow_symlinks: request.follow_symlinks.unwrap_or(false),
+            // Requests can lower the server's limit but never raise it
+            max_file_size: {
+                let max = state.container.config().service.max_document_size as u64;
+                request.max_file_size.map(|n| n.min(max)).unwrap_or(max)
+            },
         })
     } else {
         None
     };
 
+    let cost_estimate = estimate_index_cost(
+        &state,
+        &request.path,
+        collection_name,
+        IndexRunOptions {
+            recursive: request.recursive.unwrap_or(true),
+            filters: filters.clone(),
+            force: request.force.unwrap_or(false),
+            ..Default::default()
+        },
+        request.confirm_cost.unwrap_or(false),
+    )
+    .await?;
+
     // Ensure the collection exists (create if it doesn't exist)
     if collection_name != "zero_latency_docs" {
         let collections = state.collection_service.list_collections().await?;
@@ -412,85 +1556,185 @@ async fn index_documents_from_path(
                     description: Some(format!("Collection created for indexing {}", request.path)),
                     vector_size: 384, // Default embedding size
                     distance_metric: Some("cosine".to_string()),
+                    search_defaults: Default::default(),
                 })
                 .await?;
         }
     }
 
-    // Use the document service to actually index documents with collection context
-    let result = state
-        .document_service
-        .index_documents_from_path_with_filters_and_collection(
+    let job_id = state
+        .job_service
+        .start_job(
+            JobKind::Index,
+            request.priority.unwrap_or_default(),
             &request.path,
-            request.recursive.unwrap_or(true),
-            filters,
             collection_name,
         )
         .await;
+    let progress = state.job_service.track_progress(&job_id).await;
+    let options = IndexRunOptions {
+        recursive: request.recursive.unwrap_or(true),
+        filters,
+        force: request.force.unwrap_or(false),
+        priority: request.priority.unwrap_or_default(),
+        progress,
+        dry_run: false,
+    };
+
+    if request.background.unwrap_or(false) {
+        let message = format!(
+            "Indexing {} in the background as job {}",
+            request.path, job_id
+        );
+        spawn_index_job(&state, &job_id, &request.path, collection_name, options);
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(IndexPathResponse {
+                documents_processed: 0,
+                files_skipped: 0,
+                processing_time_ms: 0.0,
+                status: "running".to_string(),
+                message: Some(message),
+                job_id,
+                diagnostics: Vec::new(),
+                cost_estimate,
+            }),
+        ));
+    }
+
+    // Use the document service to actually index documents with collection context
+    let report = run_index_job(&state, &job_id, &request.path, collection_name, options).await?;
+    Ok((
+        StatusCode::OK,
+        Json(IndexPathResponse {
+            documents_processed: report.documents_processed,
+            files_skipped: report.files_skipped,
+            processing_time_ms: report.processing_time_ms,
+            status: "success".to_string(),
+            message: Some(format!(
+                "Successfully indexed {} documents from path: {} ({} unchanged files skipped)",
+                report.documents_processed, request.path, report.files_skipped
+            )),
+            job_id,
+            diagnostics: report.diagnostics,
+            cost_estimate,
+        }),
+    ))
+}
 
-    match result {
-        Ok((documents_processed, processing_time_ms)) => {
+/// Run an index job to completion and record its outcome on the job
+async fn run_index_job(
+    state: &AppState,
+    job_id: &str,
+    path: &str,
+    collection_name: &str,
+    options: IndexRunOptions,
+) -> Result<IndexingReport, AppError> {
+    state.job_service.make_resumable(job_id, &options).await;
+    match state
+        .document_service
+        .index_path_with_report(path, collection_name, options)
+        .await
+    {
+        Ok(report) => {
             tracing::info!(
-                documents_processed = documents_processed,
-                processing_time_ms = processing_time_ms,
+                job_id = %job_id,
+                documents_processed = report.documents_processed,
+                files_skipped = report.files_skipped,
+                processing_time_ms = report.processing_time_ms,
+                errors = report.error_count(),
+                warnings = report.warning_count(),
                 "Indexing completed successfully"
             );
+            state.job_service.complete_job(job_id, &report).await;
 
             // Update collection statistics after successful indexing
-            if let Err(e) = update_collection_statistics(&state, collection_name).await {
+            if let Err(e) = update_collection_statistics(state, collection_name).await {
                 tracing::warn!("Failed to update collection statistics: {}", e);
             }
-
-            Ok(Json(IndexPathResponse {
-                documents_processed,
-                processing_time_ms,
-                status: "success".to_string(),
-                message: Some(format!(
-                    "Successfully indexed {} documents from path: {}",
-                    documents_processed, request.path
-                )),
-            }))
+            Ok(report)
         }
         Err(e) => {
-            tracing::error!(error = %e, path = %request.path, "Failed to index documents");
+            tracing::error!(error = %e, job_id = %job_id, path = %path, "Failed to index documents");
+            state.job_service.fail_job(job_id, &e).await;
             Err(AppError(e))
         }
     }
 }
 
-/// Reindex all documents (equivalent to clearing and re-indexing)
-#[tracing::instrument(skip(state), fields(collection = ?request.collection))]
-async fn reindex_documents(
-    State(state): State<AppState>,
-    Json(request): Json<ReindexRequest>,
-) -> Result<Json<ReindexResponse>, AppError> {
-    tracing::info!("Starting full reindex operation");
-
-    // Get the current collection name (either from request or default)
-    let collection_name = request.collection.as_deref().unwrap_or("zero_latency_docs"); // Default collection
+/// Run an index job after the response is sent
+///
+/// The caller follows the job through the job endpoints, so it can drop the
+/// connection, or lose it, without cancelling the indexing.
+fn spawn_index_job(
+    state: &AppState,
+    job_id: &str,
+    path: &str,
+    collection_name: &str,
+    options: IndexRunOptions,
+) {
+    let state = state.clone();
+    let job_id = job_id.to_string();
+    let path = path.to_string();
+    let collection_name = collection_name.to_string();
+    tokio::spawn(async move {
+        // The outcome is recorded on the job
+        let _ = run_index_job(&state, &job_id, &path, &collection_name, options).await;
+    });
+}
 
-    // Step 1: Delete the existing collection to clear all vectors
-    tracing::info!("Clearing existing collection: {}", collection_name);
-    let _deleted = state
-        .collection_service
-        .delete_collection(collection_name)
-        .await?;
+/// Estimate the embedding cost of an index job with a dry run
+///
+/// Skipped (returning `None`) when the configured model costs nothing or has
+/// no known price. Jobs estimated above the confirmation threshold are
+/// refused unless the caller confirmed the cost.
+async fn estimate_index_cost(
+    state: &AppState,
+    path: &str,
+    collection_name: &str,
+    options: IndexRunOptions,
+    confirmed: bool,
+) -> Result<Option<CostEstimate>, AppError> {
+    let estimator = CostEstimator::new(&state.container.config());
+    if !estimator.is_billed() {
+        return Ok(None);
+    }
 
-    // Step 2: Recreate the collection with default settings
-    tracing::info!("Recreating collection: {}", collection_name);
-    use crate::application::services::collection_service::CreateCollectionRequest;
-    let create_request = CreateCollectionRequest {
-        name: collection_name.to_string(),
-        vector_size: 384, // Default embedding size
-        distance_metric: Some("cosine".to_string()),
-        description: Some(format!("Reindexed collection: {}", collection_name)),
-    };
-    let _collection = state
-        .collection_service
-        .create_collection(create_request)
+    let report = state
+        .document_service
+        .index_path_with_report(
+            path,
+            collection_name,
+            IndexRunOptions {
+                dry_run: true,
+                ..options
+            },
+        )
         .await?;
+    let estimate = estimator.estimate(&report);
+    tracing::info!(
+        chunks = estimate.chunks,
+        tokens = estimate.tokens,
+        cost_usd = estimate.estimated_cost_usd,
+        "Estimated embedding cost of {}",
+        path
+    );
+    estimator.check(&estimate, confirmed)?;
+    Ok(Some(estimate))
+}
+
+/// Reindex all documents (equivalent to clearing and re-indexing)
+#[tracing::instrument(skip(state), fields(collection = ?request.collection))]
+async fn reindex_documents(
+    State(state): State<AppState>,
+    Json(request): Json<ReindexRequest>,
+) -> Result<(StatusCode, Json<ReindexResponse>), AppError> {
+    tracing::info!("Starting full reindex operation");
 
-    // Step 3: Use the same path as configured in the CLI
+    // Get the current collection name (either from request or default)
+    let collection_name = request.collection.as_deref().unwrap_or("zero_latency_docs"); // Default collection
+
+    // Use the same path as configured in the CLI
     // In a full implementation, we'd store the original indexing paths
     let default_path = std::env::current_dir()
         .map_err(|e| {
@@ -505,6 +1749,7 @@ async fn reindex_documents(
         || request.clear_default_ignores.is_some()
         || request.follow_symlinks.is_some()
         || request.case_sensitive.is_some()
+        || request.max_file_size.is_some()
     {
         use crate::application::services::filter_service::IndexingFilters;
 
@@ -524,51 +1769,195 @@ async fn reindex_documents(
             ignore_list,
             case_sensitive: request.case_sensitive.unwrap_or(false),
             follow_symlinks: request.follow_symlinks.unwrap_or(false),
+            // Requests can lower the server's limit but never raise it
+            max_file_size: {
+                let max = state.container.config().service.max_document_size as u64;
+                request.max_file_size.map(|n| n.min(max)).unwrap_or(max)
+            },
         })
     } else {
         None
     };
 
-    // For reindexing, we first clear the existing index and then rebuild it
-    // TODO: In production, implement atomic reindexing with backup/restore
-    let result = state
-        .document_service
-        .index_documents_from_path_with_filters_and_collection(
+    // Every file is embedded again, so price the whole tree before clearing it
+    let cost_estimate = estimate_index_cost(
+        &state,
+        &default_path,
+        collection_name,
+        IndexRunOptions {
+            filters: filters.clone(),
+            force: true,
+            ..Default::default()
+        },
+        request.confirm_cost.unwrap_or(false),
+    )
+    .await?;
+
+    // Keep the collection's search defaults across the delete and recreate
+    let search_defaults = state
+        .collection_service
+        .get_collection_info(collection_name)
+        .await?
+        .map(|collection| collection.search_defaults)
+        .unwrap_or_default();
+
+    // Step 1: Delete the existing collection to clear all vectors
+    tracing::info!("Clearing existing collection: {}", collection_name);
+    let _deleted = state
+        .collection_service
+        .delete_collection(collection_name)
+        .await?;
+
+    // Step 2: Recreate the collection with default settings
+    tracing::info!("Recreating collection: {}", collection_name);
+    use crate::application::services::collection_service::CreateCollectionRequest;
+    let create_request = CreateCollectionRequest {
+        name: collection_name.to_string(),
+        vector_size: 384, // Default embedding size
+        distance_metric: Some("cosine".to_string()),
+        description: Some(format!("Reindexed collection: {}", collection_name)),
+        search_defaults,
+    };
+    let _collection = state
+        .collection_service
+        .create_collection(create_request)
+        .await?;
+
+    let job_id = state
+        .job_service
+        .start_job(
+            JobKind::Reindex,
+            request.priority.unwrap_or_default(),
             &default_path,
-            true,
-            filters,
             collection_name,
         )
         .await;
+    let progress = state.job_service.track_progress(&job_id).await;
 
-    match result {
-        Ok((documents_processed, processing_time_ms)) => {
-            tracing::info!(
-                documents_processed = documents_processed,
-                processing_time_ms = processing_time_ms,
-                "Reindexing completed successfully"
-            );
-
-            // Update collection statistics after successful reindexing
-            if let Err(e) = update_collection_statistics(&state, collection_name).await {
-                tracing::warn!("Failed to update collection statistics: {}", e);
-            }
+    // For reindexing, we first clear the existing index and then rebuild it,
+    // so every file is indexed regardless of its fingerprint
+    // TODO: In production, implement atomic reindexing with backup/restore
+    let options = IndexRunOptions {
+        recursive: true,
+        filters,
+        force: true,
+        priority: request.priority.unwrap_or_default(),
+        progress,
+        dry_run: false,
+    };
 
-            Ok(Json(ReindexResponse {
-                documents_processed,
-                processing_time_ms,
-                status: "completed".to_string(),
-                message: Some(format!(
-                    "Successfully reindexed {} documents",
-                    documents_processed
-                )),
-            }))
-        }
-        Err(e) => {
-            tracing::error!(error = %e, "Failed to reindex documents");
-            Err(AppError(e))
-        }
+    if request.background.unwrap_or(false) {
+        let message = format!(
+            "Reindexing {} in the background as job {}",
+            collection_name, job_id
+        );
+        spawn_index_job(&state, &job_id, &default_path, collection_name, options);
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(ReindexResponse {
+                documents_processed: 0,
+                files_skipped: 0,
+                processing_time_ms: 0.0,
+                status: "running".to_string(),
+                message: Some(message),
+                job_id,
+                diagnostics: Vec::new(),
+                cost_estimate,
+            }),
+        ));
     }
+
+    let report = run_index_job(&state, &job_id, &default_path, collection_name, options).await?;
+    Ok((
+        StatusCode::OK,
+        Json(ReindexResponse {
+            documents_processed: report.documents_processed,
+            files_skipped: report.files_skipped,
+            processing_time_ms: report.processing_time_ms,
+            status: "completed".to_string(),
+            message: Some(format!(
+                "Successfully reindexed {} documents",
+                report.documents_processed
+            )),
+            job_id,
+            diagnostics: report.diagnostics,
+            cost_estimate,
+        }),
+    ))
+}
+
+/// List recent indexing jobs with their per-stage throughput
+async fn list_jobs(State(state): State<AppState>) -> Result<Json<JobListResponse>, AppError> {
+    let jobs = state.job_service.list_jobs().await;
+    Ok(Json(JobListResponse {
+        total: jobs.len(),
+        jobs,
+    }))
+}
+
+/// Get an indexing job, including live per-stage throughput while it runs
+async fn get_job(
+    Path(job_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<IndexingJob>, AppError> {
+    Ok(Json(state.job_service.get_job(&job_id).await?))
+}
+
+/// Resume a failed or interrupted index or reindex job in the background
+///
+/// Files the job stored before it stopped are skipped. A resumed reindex
+/// keeps what it already rebuilt rather than clearing the collection again.
+async fn resume_job(
+    Path(job_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<IndexPathResponse>), AppError> {
+    let (job, options) = state.job_service.resume_job(&job_id).await?;
+    let skipped = options.progress.checkpointed_files().len();
+    tracing::info!(
+        job_id = %job_id,
+        checkpointed_files = skipped,
+        "Resuming indexing job"
+    );
+    spawn_index_job(&state, &job_id, &job.path, &job.collection, options);
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(IndexPathResponse {
+            documents_processed: 0,
+            files_skipped: 0,
+            processing_time_ms: 0.0,
+            status: "running".to_string(),
+            message: Some(format!(
+                "Resuming job {} on {}, skipping {} files it already stored",
+                job_id, job.path, skipped
+            )),
+            job_id,
+            diagnostics: Vec::new(),
+            cost_estimate: None,
+        }),
+    ))
+}
+
+/// Get the per-file diagnostics recorded for an indexing job
+async fn get_job_errors(
+    Path(job_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<JobErrorsResponse>, AppError> {
+    let job = state.job_service.get_job(&job_id).await?;
+    let error_count = job
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == DiagnosticSeverity::Error)
+        .count();
+
+    Ok(Json(JobErrorsResponse {
+        job_id: job.id,
+        status: job.status,
+        error: job.error,
+        error_count,
+        warning_count: job.diagnostics.len() - error_count,
+        diagnostics: job.diagnostics,
+    }))
 }
 
 /// Update collection statistics after indexing
@@ -650,7 +2039,6 @@ pub struct IndexPathRequest {
     pub collection: Option<String>,
     #[allow(dead_code)]
     pub recursive: Option<bool>,
-    #[allow(dead_code)]
     pub force: Option<bool>,
     #[allow(dead_code)]
     pub safe_patterns: Option<Vec<String>>,
@@ -662,14 +2050,27 @@ pub struct IndexPathRequest {
     pub follow_symlinks: Option<bool>,
     #[allow(dead_code)]
     pub case_sensitive: Option<bool>,
+    pub max_file_size: Option<u64>,
+    pub priority: Option<IndexingPriority>,
+    /// Run even if the estimated embedding cost is above the threshold
+    pub confirm_cost: Option<bool>,
+    /// Respond as soon as the job starts and run it in the background;
+    /// progress and the outcome are read from the job endpoints
+    pub background: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct IndexPathResponse {
     pub documents_processed: u64,
+    pub files_skipped: u64,
     pub processing_time_ms: f64,
     pub status: String,
     pub message: Option<String>,
+    pub job_id: String,
+    pub diagnostics: Vec<FileDiagnostic>,
+    /// Projected embedding cost, when the embedding model is billed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_estimate: Option<CostEstimate>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -685,14 +2086,43 @@ pub struct ReindexRequest {
     pub follow_symlinks: Option<bool>,
     #[allow(dead_code)]
     pub case_sensitive: Option<bool>,
+    pub max_file_size: Option<u64>,
+    pub priority: Option<IndexingPriority>,
+    /// Run even if the estimated embedding cost is above the threshold
+    pub confirm_cost: Option<bool>,
+    /// Respond as soon as the job starts and run it in the background;
+    /// progress and the outcome are read from the job endpoints
+    pub background: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ReindexResponse {
     pub documents_processed: u64,
+    pub files_skipped: u64,
     pub processing_time_ms: f64,
     pub status: String,
     pub message: Option<String>,
+    pub job_id: String,
+    pub diagnostics: Vec<FileDiagnostic>,
+    /// Projected embedding cost, when the embedding model is billed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_estimate: Option<CostEstimate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobListResponse {
+    pub jobs: Vec<IndexingJob>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobErrorsResponse {
+    pub job_id: String,
+    pub status: JobStatus,
+    pub error: Option<String>,
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub diagnostics: Vec<FileDiagnostic>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -719,11 +2149,18 @@ pub struct ServerResponse {
 pub struct ApiStatusResponse {
     pub status: String,
     pub version: String,
+    /// `major.minor` version of the HTTP API, for clients to check compatibility
+    pub api_version: String,
     pub uptime_seconds: u64,
     pub total_documents: u64,
     pub index_size_bytes: u64,
     pub last_index_update: Option<String>,
     pub docs_path: Option<String>,
+    pub vector_pool: VectorPoolStats,
+    /// Backend scoring brute-force vector scans, from the startup GPU probe
+    pub acceleration: AccelerationStatus,
+    /// Offline mode and the features it degraded
+    pub offline: OfflineStatus,
 }
 
 #[derive(Debug, Serialize)]
@@ -797,7 +2234,8 @@ impl axum::response::IntoResponse for AppError {
         let error_response = serde_json::json!({
             "error": {
                 "message": message,
-                "type": format!("{:?}", self.0)
+                "type": format!("{:?}", self.0),
+                "trace_id": super::request_id::current_request_id(),
             }
         });
 
@@ -847,6 +2285,7 @@ async fn create_collection(
         vector_size: request.vector_size,
         distance_metric: request.distance_metric,
         description: request.description,
+        search_defaults: request.search_defaults,
     };
 
     let collection = state
@@ -882,22 +2321,607 @@ async fn get_collection_stats(
     State(state): State<AppState>,
 ) -> Result<Json<GetCollectionStatsResponse>, AppError> {
     if let Some(stats) = state.collection_service.get_collection_stats(&name).await? {
+        // A collection without vectors has no index to score
+        let health = state.collection_health_service.check(&name).await.ok();
         Ok(Json(GetCollectionStatsResponse {
             found: true,
             stats: Some(stats),
+            health,
         }))
     } else {
         Ok(Json(GetCollectionStatsResponse {
             found: false,
             stats: None,
+            health: None,
         }))
     }
 }
 
+/// Score a collection's index health and recommend fixes
+async fn get_collection_health(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<CollectionHealth>, AppError> {
+    Ok(Json(state.collection_health_service.check(&name).await?))
+}
+
+/// Popular queries replayed by a warm-up when the caller doesn't say
+const DEFAULT_WARMUP_QUERIES: usize = 20;
+const MAX_WARMUP_QUERIES: usize = 100;
+
+/// Preload a collection and prime the query embedding cache
+///
+/// Meant for deployment hooks, so the first user search after a restart
+/// doesn't pay for loading vectors or embedding common queries.
+async fn warm_collection(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<WarmCollectionQuery>,
+) -> Result<Json<WarmupReport>, AppError> {
+    let limit = params
+        .queries
+        .unwrap_or(DEFAULT_WARMUP_QUERIES)
+        .min(MAX_WARMUP_QUERIES);
+    let queries: Vec<String> = state
+        .analytics_service
+        .get_popular_queries(limit)
+        .await?
+        .into_iter()
+        .map(|popular| popular.query)
+        .collect();
+    let settings = state
+        .collection_service
+        .effective_search_settings(&name, &Default::default())
+        .await?;
+
+    let report = state
+        .document_service
+        .warm_collection(&name, &settings, &queries)
+        .await?;
+
+    // Indexing doesn't register collections, so only an empty, unregistered
+    // name is unknown
+    let registered = state.collection_service.get_collection_info(&name).await?;
+    if report.vectors_loaded == 0 && registered.is_none() {
+        return Err(AppError(ZeroLatencyError::not_found(format!(
+            "collection '{}'",
+            name
+        ))));
+    }
+    Ok(Json(report))
+}
+
+/// Re-embed a collection with the current embedding model
+///
+/// The collection's vectors are loaded and checked before responding; the
+/// re-embedding, validation and alias swap then run as a background job whose
+/// progress and report are read from the job endpoints.
+async fn migrate_collection_embeddings(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<MigrateEmbeddingsQuery>,
+) -> Result<(StatusCode, Json<MigrateEmbeddingsResponse>), AppError> {
+    for kind in [JobKind::Migrate, JobKind::Reembed] {
+        if let Some(job_id) = state.job_service.running_job(kind, &name).await {
+            return Err(AppError(ZeroLatencyError::validation(
+                "name",
+                format!(
+                    "Collection '{}' is already being re-embedded by job {}",
+                    name, job_id
+                ),
+            )));
+        }
+    }
+
+    let sample_size = params
+        .sample_size
+        .unwrap_or(DEFAULT_VALIDATION_SAMPLE)
+        .clamp(1, MAX_VALIDATION_SAMPLE);
+    let plan = state
+        .migration_service
+        .plan(&name, params.force.unwrap_or(false))
+        .await?;
+
+    let job_id = state
+        .job_service
+        .start_job(JobKind::Migrate, IndexingPriority::Normal, "", &name)
+        .await;
+    let progress = state.job_service.track_progress(&job_id).await;
+    let response = MigrateEmbeddingsResponse {
+        job_id: job_id.clone(),
+        collection: name.clone(),
+        from_dimension: plan.from_dimension,
+        to_dimension: plan.to_dimension,
+        vectors: plan.chunk_count(),
+    };
+
+    tokio::spawn(async move {
+        let lanes = state.container.lanes();
+        let migrated = lanes
+            .background()
+            .run(state.migration_service.migrate(plan, sample_size, progress))
+            .await;
+        match migrated {
+            Ok(report) => {
+                if let Err(e) = state
+                    .collection_service
+                    .set_vector_size(&name, report.to_dimension as u64)
+                    .await
+                {
+                    tracing::warn!("Failed to record vector size of {}: {}", name, e);
+                }
+                state.job_service.complete_migration(&job_id, &report).await;
+            }
+            Err(e) => {
+                tracing::error!(error = %e, collection = %name, "Embedding migration failed");
+                state.job_service.fail_job(&job_id, &e).await;
+            }
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+/// Get the metadata schema a collection declares
+async fn get_metadata_schema(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<MetadataSchema>, AppError> {
+    Ok(Json(state.collection_service.metadata_schema(&name)?))
+}
+
+/// Declare or replace a collection's metadata schema
+async fn put_metadata_schema(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Json(schema): Json<MetadataSchema>,
+) -> Result<Json<MetadataSchema>, AppError> {
+    Ok(Json(
+        state.collection_service.set_metadata_schema(&name, schema)?,
+    ))
+}
+
+/// Drop a collection's metadata schema
+async fn delete_metadata_schema(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    state.collection_service.delete_metadata_schema(&name)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Patch the metadata of a collection's documents matching a filter
+///
+/// Stored vectors are updated in place; nothing is embedded again.
+async fn update_documents_metadata(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Json(update): Json<MetadataUpdate>,
+) -> Result<Json<MetadataUpdateReport>, AppError> {
+    Ok(Json(
+        state.metadata_update_service.update(&name, update).await?,
+    ))
+}
+
+/// Run a read-only SQL-like query over a collection's indexed files
+async fn query_documents_metadata(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Json(request): Json<MetadataQueryRequest>,
+) -> Result<Json<MetadataQueryResult>, AppError> {
+    Ok(Json(
+        state
+            .metadata_query_service
+            .query(&name, &request.query)
+            .await?,
+    ))
+}
+
+/// Report how many of a collection's vectors are stale
+async fn get_collection_staleness(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<StalenessReport>, AppError> {
+    Ok(Json(state.staleness_service.report(&name).await?))
+}
+
+const DEFAULT_SAMPLE_SIZE: usize = 50;
+const MAX_SAMPLE_SIZE: usize = 500;
+const DEFAULT_SAMPLE_NEIGHBORS: usize = 5;
+const MAX_SAMPLE_NEIGHBORS: usize = 50;
+
+/// Draw random chunks of a collection with their nearest neighbors
+///
+/// For auditing chunking and embedding neighborhoods when relevance is off.
+async fn sample_collection_chunks(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<ChunkSampleQuery>,
+) -> Result<Json<ChunkSample>, AppError> {
+    let size = params.n.unwrap_or(DEFAULT_SAMPLE_SIZE);
+    if size == 0 || size > MAX_SAMPLE_SIZE {
+        return Err(AppError(ZeroLatencyError::validation(
+            "n",
+            format!("must be between 1 and {}", MAX_SAMPLE_SIZE),
+        )));
+    }
+    let neighbors = params.neighbors.unwrap_or(DEFAULT_SAMPLE_NEIGHBORS);
+    if neighbors > MAX_SAMPLE_NEIGHBORS {
+        return Err(AppError(ZeroLatencyError::validation(
+            "neighbors",
+            format!("must be at most {}", MAX_SAMPLE_NEIGHBORS),
+        )));
+    }
+
+    Ok(Json(
+        state
+            .sample_service
+            .sample(&name, size, neighbors, params.seed)
+            .await?,
+    ))
+}
+
+/// Report clusters of near-identical documents in a collection
+///
+/// Each cluster suggests a canonical document to keep.
+async fn get_collection_duplicates(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<DuplicateReportQuery>,
+) -> Result<Json<DuplicateReport>, AppError> {
+    let vector_threshold = params.threshold.unwrap_or(DEFAULT_VECTOR_THRESHOLD);
+    if !(vector_threshold > 0.0 && vector_threshold <= 1.0) {
+        return Err(AppError(ZeroLatencyError::validation(
+            "threshold",
+            "must be between 0 and 1",
+        )));
+    }
+    let content_threshold = params.content_threshold.unwrap_or(DEFAULT_CONTENT_THRESHOLD);
+    if !(content_threshold > 0.0 && content_threshold <= 1.0) {
+        return Err(AppError(ZeroLatencyError::validation(
+            "content_threshold",
+            "must be between 0 and 1",
+        )));
+    }
+
+    Ok(Json(
+        state
+            .duplicate_service
+            .report(&name, vector_threshold, content_threshold)
+            .await?,
+    ))
+}
+
+const DEFAULT_PROJECTION_POINTS: usize = 2000;
+const MAX_PROJECTION_POINTS: usize = 10000;
+
+/// Project a collection's vectors to 2D for plotting
+///
+/// A random sample of the collection's chunks is loaded before responding;
+/// the projection then runs as a background job and replaces the cached
+/// projection when it completes.
+async fn project_collection(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<ProjectCollectionQuery>,
+) -> Result<(StatusCode, Json<ProjectCollectionResponse>), AppError> {
+    if let Some(job_id) = state.job_service.running_job(JobKind::Project, &name).await {
+        return Err(AppError(ZeroLatencyError::validation(
+            "name",
+            format!(
+                "Collection '{}' is already being projected by job {}",
+                name, job_id
+            ),
+        )));
+    }
+    let method = match params.method.as_deref() {
+        Some(method) => method.parse::<zero_latency_vector::ProjectionMethod>()?,
+        None => zero_latency_vector::ProjectionMethod::default(),
+    };
+    let max_points = params.max_points.unwrap_or(DEFAULT_PROJECTION_POINTS);
+    if max_points == 0 || max_points > MAX_PROJECTION_POINTS {
+        return Err(AppError(ZeroLatencyError::validation(
+            "max_points",
+            format!("must be between 1 and {}", MAX_PROJECTION_POINTS),
+        )));
+    }
+
+    let plan = state
+        .projection_service
+        .plan(&name, method, max_points, params.seed)
+        .await?;
+    let job_id = state
+        .job_service
+        .start_job(JobKind::Project, IndexingPriority::Normal, "", &name)
+        .await;
+    let progress = state.job_service.track_progress(&job_id).await;
+    let response = ProjectCollectionResponse {
+        job_id: job_id.clone(),
+        collection: name.clone(),
+        method,
+        points: plan.point_count(),
+    };
+
+    tokio::spawn(async move {
+        let lanes = state.container.lanes();
+        let projected = lanes
+            .background()
+            .run(state.projection_service.project(plan, progress))
+            .await;
+        match projected {
+            Ok(report) => {
+                state
+                    .job_service
+                    .complete_projection(&job_id, &report)
+                    .await
+            }
+            Err(e) => {
+                tracing::error!(error = %e, collection = %name, "Collection projection failed");
+                state.job_service.fail_job(&job_id, &e).await;
+            }
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+/// Export the latest projection of a collection as JSON or CSV
+async fn export_collection_projection(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<ProjectionExportQuery>,
+) -> Result<axum::response::Response, AppError> {
+    use axum::response::IntoResponse;
+
+    let projection = state.projection_service.cached(&name)?;
+    match params.format.as_deref().unwrap_or("json") {
+        "json" => Ok(Json(projection).into_response()),
+        "csv" => Ok((
+            [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            projection.to_csv(),
+        )
+            .into_response()),
+        _ => Err(AppError(ZeroLatencyError::validation(
+            "format",
+            "must be one of json, csv",
+        ))),
+    }
+}
+
+/// Entries, memory and hit rates of a collection in each query cache
+async fn get_collection_cache(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Json<CollectionCacheStats> {
+    Json(state.collection_cache_service.stats(&name).await)
+}
+
+/// Drop a collection's entries from every query cache, for all tenants
+async fn purge_collection_cache(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Json<CachePurgeReport> {
+    Json(state.collection_cache_service.purge(&name).await)
+}
+
+/// Fetch the source file of an indexed document
+///
+/// Serves the file as it is on disk, with its detected content type. A
+/// single `Range` is honored with `206 Partial Content`, and the content
+/// hash doubles as an `ETag` for `If-None-Match`. `x-index-stale: true`
+/// marks files that changed since they were indexed.
+async fn get_raw_document(
+    Path((name, path)): Path<(String, String)>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<axum::response::Response, AppError> {
+    use axum::response::IntoResponse;
+
+    let document = state.raw_document_service.open(&name, &path).await?;
+    let disposition = document.content_disposition();
+    let etag = format!("\"{}\"", document.hash);
+    let len = document.len;
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|tags| {
+            tags.split(',')
+                .any(|tag| tag.trim() == etag || tag.trim() == "*")
+        });
+    let range = headers
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    let (status, content_range, body) = if not_modified {
+        (StatusCode::NOT_MODIFIED, None, Vec::new())
+    } else {
+        let raw_documents = &state.raw_document_service;
+        match ByteRange::parse(range, len) {
+            ByteRange::Full => (
+                StatusCode::OK,
+                None,
+                raw_documents.read(&document, 0..len).await?,
+            ),
+            ByteRange::Partial(range) => (
+                StatusCode::PARTIAL_CONTENT,
+                Some(format!("bytes {}-{}/{}", range.start, range.end - 1, len)),
+                raw_documents.read(&document, range).await?,
+            ),
+            ByteRange::Unsatisfiable => (
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                Some(format!("bytes */{}", len)),
+                Vec::new(),
+            ),
+        }
+    };
+
+    let mut response = (
+        status,
+        [
+            (header::CONTENT_TYPE, document.content_type),
+            (header::ACCEPT_RANGES, "bytes".to_string()),
+            (header::ETAG, etag),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response();
+    let response_headers = response.headers_mut();
+    if let Some(content_range) = content_range.and_then(|value| value.parse().ok()) {
+        response_headers.insert(header::CONTENT_RANGE, content_range);
+    }
+    response_headers.insert(
+        "x-index-stale",
+        header::HeaderValue::from_static(if document.stale { "true" } else { "false" }),
+    );
+    Ok(response)
+}
+
+/// Re-embed just the stale vectors of a collection
+///
+/// The stale vectors are selected before responding; re-embedding them runs
+/// as a background job read from the job endpoints.
+async fn reembed_stale_vectors(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<ReembedStaleResponse>), AppError> {
+    for kind in [JobKind::Reembed, JobKind::Migrate] {
+        if let Some(job_id) = state.job_service.running_job(kind, &name).await {
+            return Err(AppError(ZeroLatencyError::validation(
+                "name",
+                format!(
+                    "Collection '{}' is already being re-embedded by job {}",
+                    name, job_id
+                ),
+            )));
+        }
+    }
+
+    let plan = state.staleness_service.plan(&name).await?;
+    let job_id = state
+        .job_service
+        .start_job(JobKind::Reembed, IndexingPriority::Normal, "", &name)
+        .await;
+    let progress = state.job_service.track_progress(&job_id).await;
+    let response = ReembedStaleResponse {
+        job_id: job_id.clone(),
+        collection: name.clone(),
+        vectors: plan.chunk_count(),
+    };
+
+    tokio::spawn(async move {
+        let lanes = state.container.lanes();
+        let reembedded = lanes
+            .background()
+            .run(state.staleness_service.reembed(plan, progress))
+            .await;
+        match reembedded {
+            Ok(report) => state.job_service.complete_reembed(&job_id, &report).await,
+            Err(e) => {
+                tracing::error!(error = %e, collection = %name, "Stale vector re-embedding failed");
+                state.job_service.fail_job(&job_id, &e).await;
+            }
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
 //
 // Collection API Request/Response Types
 //
 
+/// Response for a started re-embedding of stale vectors
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReembedStaleResponse {
+    pub job_id: String,
+    pub collection: String,
+    /// Stale chunks that will be re-embedded
+    pub vectors: usize,
+}
+
+/// Query parameters for migrating a collection's embeddings
+#[derive(Debug, Deserialize)]
+pub struct MigrateEmbeddingsQuery {
+    /// Re-embed even if the stored dimension already matches the model
+    pub force: Option<bool>,
+    /// How many chunks to check in the shadow collection before the swap
+    pub sample_size: Option<usize>,
+}
+
+/// Response for a started embedding migration
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MigrateEmbeddingsResponse {
+    pub job_id: String,
+    pub collection: String,
+    pub from_dimension: usize,
+    pub to_dimension: usize,
+    /// Chunks that will be re-embedded
+    pub vectors: usize,
+}
+
+/// Query parameters for projecting a collection
+#[derive(Debug, Deserialize)]
+pub struct ProjectCollectionQuery {
+    /// `umap` or `pca`
+    pub method: Option<String>,
+    /// How many chunks to sample for the projection
+    pub max_points: Option<usize>,
+    /// Seed of the sample and layout, to reproduce a projection
+    pub seed: Option<u64>,
+}
+
+/// Response for a started projection
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectCollectionResponse {
+    pub job_id: String,
+    pub collection: String,
+    pub method: zero_latency_vector::ProjectionMethod,
+    /// Sampled chunks that will be projected
+    pub points: usize,
+}
+
+/// Query parameters for exporting a projection
+#[derive(Debug, Deserialize)]
+pub struct ProjectionExportQuery {
+    /// `json` or `csv`
+    pub format: Option<String>,
+}
+
+/// Request body for querying a collection's document metadata
+#[derive(Debug, Deserialize)]
+pub struct MetadataQueryRequest {
+    /// `SELECT ... [WHERE ...] [ORDER BY ...] [LIMIT n]`
+    pub query: String,
+}
+
+/// Query parameters for a collection's duplicate report
+#[derive(Debug, Deserialize)]
+pub struct DuplicateReportQuery {
+    /// Cosine similarity of document embeddings above which documents are candidates
+    pub threshold: Option<f32>,
+    /// Content overlap confirming a candidate pair
+    pub content_threshold: Option<f64>,
+}
+
+/// Query parameters for sampling a collection's chunks
+#[derive(Debug, Deserialize)]
+pub struct ChunkSampleQuery {
+    /// How many chunks to draw
+    pub n: Option<usize>,
+    /// How many nearest neighbors to return per chunk
+    pub neighbors: Option<usize>,
+    /// Seed of a previous sample to draw the same chunks again
+    pub seed: Option<u64>,
+}
+
+/// Query parameters for warming a collection
+#[derive(Debug, Deserialize)]
+pub struct WarmCollectionQuery {
+    /// How many popular queries to replay
+    pub queries: Option<usize>,
+}
+
 /// Response for listing collections
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ListCollectionsResponse {
@@ -918,6 +2942,9 @@ pub struct CreateCollectionApiRequest {
     pub vector_size: u64,
     pub distance_metric: Option<String>,
     pub description: Option<String>,
+    /// Search settings used when a search of this collection doesn't specify them
+    #[serde(default)]
+    pub search_defaults: zero_latency_search::SearchSettings,
 }
 
 /// Response for creating a collection
@@ -940,6 +2967,9 @@ pub struct DeleteCollectionResponse {
 pub struct GetCollectionStatsResponse {
     pub found: bool,
     pub stats: Option<crate::application::services::collection_service::CollectionStats>,
+    /// Index health score with recommendations, when the collection has vectors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<CollectionHealth>,
 }
 
 // ===== Analytics Handlers =====
@@ -1009,6 +3039,19 @@ async fn get_search_trends(State(state): State<AppState>) -> Result<Json<SearchT
     }
 }
 
+/// Get the most recent slow queries with their per-stage timings
+async fn get_slow_queries(
+    State(state): State<AppState>,
+    Query(params): Query<AnalyticsQuery>,
+) -> Json<Vec<crate::infrastructure::operations::analytics::SlowQueryRecord>> {
+    let slow_queries = state
+        .analytics_service
+        .get_slow_queries(params.limit.unwrap_or(10))
+        .await;
+    tracing::info!("[Analytics] Retrieved {} slow queries", slow_queries.len());
+    Json(slow_queries)
+}
+
 /// Query parameters for analytics endpoints
 #[derive(Debug, Deserialize)]
 pub struct AnalyticsQuery {
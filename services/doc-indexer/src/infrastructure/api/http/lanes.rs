@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+/// Interactive lane admission
+///
+/// Search requests run in the interactive lane, so they only ever wait for
+/// other searches; everything else is served as before. Background work
+/// enters its lane where the job runs, not here, since index requests
+/// return before their jobs do.
+use std::sync::Arc;
+use zero_latency_api::endpoints::endpoints;
+
+use crate::infrastructure::operations::lanes::ExecutionLanes;
+
+/// Path prefixes served in the interactive lane
+const INTERACTIVE_PREFIXES: &[&str] = &[
+    endpoints::SEARCH,
+    endpoints::DOCUMENTS_SEARCH,
+    endpoints::LOOKUP,
+    endpoints::ES_COMPAT,
+];
+
+/// Whether a request path is interactive traffic
+fn is_interactive(path: &str) -> bool {
+    INTERACTIVE_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+        || (path.starts_with(endpoints::SESSIONS) && path.ends_with("/search"))
+}
+
+/// Run search requests in the interactive lane
+pub async fn lane_middleware(
+    State(lanes): State<Arc<ExecutionLanes>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_interactive(request.uri().path()) {
+        lanes.interactive().run(next.run(request)).await
+    } else {
+        next.run(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classifies_search_traffic_as_interactive() {
+        assert!(is_interactive("/api/search"));
+        assert!(is_interactive("/api/search/summarize"));
+        assert!(is_interactive("/api/documents/search"));
+        assert!(is_interactive("/api/es/docs/_search"));
+        assert!(is_interactive("/api/sessions/abc/search"));
+        assert!(!is_interactive("/api/sessions/abc"));
+        assert!(!is_interactive("/api/index"));
+        assert!(!is_interactive("/api/jobs"));
+    }
+}
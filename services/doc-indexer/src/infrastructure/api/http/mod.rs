@@ -2,8 +2,21 @@
 ///
 /// This module contains the HTTP server implementation using Axum,
 /// including route handlers, middleware, and server configuration.
+#[cfg(feature = "chat")]
+pub mod chat;
 pub mod handlers;
+pub mod lanes;
+pub mod opensearch;
+pub mod principal;
+#[cfg(feature = "profiling")]
+pub mod profiling;
+pub mod rate_limit;
+pub mod request_id;
+pub mod schema_validation;
 pub mod server;
+pub mod tenant;
+#[cfg(feature = "web-ui")]
+pub mod web_ui;
 
 // Re-export commonly used types
 pub use server::{HttpServer, ServerConfig};
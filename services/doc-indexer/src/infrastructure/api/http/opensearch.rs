@@ -0,0 +1,663 @@
+/// Elasticsearch/OpenSearch-compatible search
+///
+/// Translates a useful subset of the `_search` query DSL into a regular
+/// search request, so tooling written against Elasticsearch (Grafana
+/// datasources, legacy clients) can point at `/api/es` instead. Supported:
+///
+/// - `match`, `match_phrase`, `multi_match`, `query_string` and
+///   `simple_query_string` contribute their text to the hybrid query; the
+///   field they name is ignored because every field is searched
+/// - `match_all` searches nothing, which lists documents
+/// - `term` and `terms` filter on a metadata field; `tags` matches any one
+///   of a document's tags
+/// - `range` filters numeric fields; `gt` and `lt` are treated as
+///   inclusive bounds
+/// - `bool` with `must`, `filter`, `should` (text queries only) and
+///   `must_not` (`term`/`terms` on `tags`, `collection` or `_id`)
+/// - `from`, `size` and `min_score`
+///
+/// Anything else is rejected rather than silently ignored, so a client
+/// never gets results for a different query than it asked for.
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+use zero_latency_api::{NumericRange, SearchFilters, SearchRequest};
+use zero_latency_core::{Result, ZeroLatencyError};
+use zero_latency_search::SearchResponse;
+use zero_latency_vector::PATH_KEY;
+
+/// Hits returned when a request has no `size`
+pub const DEFAULT_SIZE: usize = 10;
+
+/// Largest `from + size` a request may page to
+pub const MAX_RESULT_WINDOW: usize = 1000;
+
+/// Version reported to clients that check the cluster before searching
+pub const COMPATIBLE_VERSION: &str = "7.10.2";
+
+/// Top-level request keys that are accepted without affecting the search
+const IGNORED_KEYS: [&str; 4] = ["_source", "track_total_hits", "timeout", "highlight"];
+
+/// Metadata key of a document's comma-separated tags
+const TAGS_KEY: &str = "tags";
+
+/// A `_search` request translated for the search pipeline
+#[derive(Debug, Clone)]
+pub struct CompatSearch {
+    /// Search request asking for the first `from + size` results
+    pub request: SearchRequest,
+    /// Results to skip
+    pub from: usize,
+    /// Results to return after skipping
+    pub size: usize,
+}
+
+/// Translate a `_search` body addressed to `index`
+///
+/// `index` is a collection name, a comma-separated list, a glob pattern,
+/// or `_all`; anything but a single name makes the search federated.
+pub fn translate(index: &str, body: &Value) -> Result<CompatSearch> {
+    let empty = Map::new();
+    let body = match body {
+        Value::Null => &empty,
+        Value::Object(body) => body,
+        _ => return Err(invalid("body", "must be a JSON object")),
+    };
+
+    let mut clauses = Clauses::default();
+    let mut min_score = None;
+    for (key, value) in body {
+        match key.as_str() {
+            "query" => clauses.add(value, Occur::Must, "query")?,
+            "from" | "size" => {}
+            "min_score" => {
+                min_score = Some(
+                    value
+                        .as_f64()
+                        .ok_or_else(|| invalid("min_score", "must be a number"))?
+                        as f32,
+                )
+            }
+            "sort" => check_sort(value)?,
+            key if IGNORED_KEYS.contains(&key) => {}
+            key => return Err(invalid(key, "is not supported")),
+        }
+    }
+    let from = count(body, "from", 0)?;
+    let size = count(body, "size", DEFAULT_SIZE)?;
+    let window = from
+        .checked_add(size)
+        .filter(|window| *window <= MAX_RESULT_WINDOW)
+        .ok_or_else(|| {
+            invalid(
+                "from",
+                format!("from + size must not exceed {}", MAX_RESULT_WINDOW),
+            )
+        })?;
+
+    let indices: Vec<String> = index
+        .split(',')
+        .map(|name| match name.trim() {
+            "_all" => "*".to_string(),
+            name => name.to_string(),
+        })
+        .filter(|name| !name.is_empty())
+        .collect();
+    let single = match indices.as_slice() {
+        [name] if !name.contains(['*', '?']) => Some(name.clone()),
+        [] => return Err(invalid("index", "must name a collection")),
+        _ => None,
+    };
+
+    let filters = SearchFilters {
+        collection_names: single.is_none().then_some(indices),
+        collection_name: single,
+        exclude_tags: non_empty(clauses.exclude_tags),
+        exclude_collection_names: non_empty(clauses.exclude_collections),
+        metadata_patterns: (!clauses.patterns.is_empty()).then_some(clauses.patterns),
+        ranges: (!clauses.ranges.is_empty()).then_some(clauses.ranges),
+        ..Default::default()
+    };
+    let request = SearchRequest {
+        query: clauses.text.join(" "),
+        limit: Some(window as i32),
+        filters: Some(Box::new(filters)),
+        exclude_documents: non_empty(clauses.exclude_documents),
+        min_score,
+        ..Default::default()
+    };
+
+    Ok(CompatSearch {
+        request,
+        from,
+        size,
+    })
+}
+
+/// How a clause contributes to the search
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Occur {
+    Must,
+    Should,
+    MustNot,
+}
+
+/// Parts of a search collected from the query DSL
+#[derive(Debug, Default)]
+struct Clauses {
+    text: Vec<String>,
+    patterns: HashMap<String, String>,
+    ranges: HashMap<String, NumericRange>,
+    exclude_tags: Vec<String>,
+    exclude_collections: Vec<String>,
+    exclude_documents: Vec<String>,
+}
+
+impl Clauses {
+    fn add(&mut self, clause: &Value, occur: Occur, at: &str) -> Result<()> {
+        let (kind, body) = match clause.as_object() {
+            Some(clause) if clause.len() == 1 => clause.iter().next().unwrap(),
+            _ => return Err(invalid(at, "must be an object with a single query type")),
+        };
+        let at = format!("{}.{}", at, kind);
+        match kind.as_str() {
+            "match_all" if occur != Occur::MustNot => Ok(()),
+            "match" | "match_phrase" | "match_phrase_prefix" | "match_bool_prefix" => {
+                let (_, value) = single_field(body, &at)?;
+                let text = match value {
+                    Value::Object(options) => options.get("query").unwrap_or(&Value::Null),
+                    value => value,
+                };
+                self.add_text(text, occur, &at)
+            }
+            "multi_match" | "query_string" | "simple_query_string" => {
+                let text = body.get("query").unwrap_or(&Value::Null);
+                self.add_text(text, occur, &at)
+            }
+            "term" => {
+                let (field, value) = single_field(body, &at)?;
+                let value = match value {
+                    Value::Object(options) => options.get("value").unwrap_or(&Value::Null),
+                    value => value,
+                };
+                self.add_terms(field, &[scalar(value, &at)?], occur, &at)
+            }
+            "terms" => {
+                let (field, values) = single_field(body, &at)?;
+                let values = values
+                    .as_array()
+                    .filter(|values| !values.is_empty())
+                    .ok_or_else(|| invalid(&at, "must list at least one value"))?
+                    .iter()
+                    .map(|value| scalar(value, &at))
+                    .collect::<Result<Vec<_>>>()?;
+                self.add_terms(field, &values, occur, &at)
+            }
+            "range" if occur == Occur::Must => {
+                let (field, bounds) = single_field(body, &at)?;
+                let bound = |names: [&str; 2]| -> Result<Option<f64>> {
+                    match names.iter().find_map(|name| bounds.get(*name)) {
+                        None => Ok(None),
+                        Some(value) => number(value)
+                            .map(Some)
+                            .ok_or_else(|| invalid(&at, "bounds must be numbers")),
+                    }
+                };
+                let range = NumericRange {
+                    gte: bound(["gte", "gt"])?,
+                    lte: bound(["lte", "lt"])?,
+                };
+                if self.ranges.insert(field_name(field), range).is_some() {
+                    return Err(invalid(&at, format!("{} already has a range", field)));
+                }
+                Ok(())
+            }
+            "bool" => {
+                let body = body
+                    .as_object()
+                    .ok_or_else(|| invalid(&at, "must be an object"))?;
+                for (occurrence, clauses) in body {
+                    let nested = match (occurrence.as_str(), occur) {
+                        ("must" | "filter", occur) => occur,
+                        ("should", Occur::Must) => Occur::Should,
+                        ("must_not", Occur::Must) => Occur::MustNot,
+                        ("minimum_should_match" | "boost", _) => continue,
+                        _ => {
+                            return Err(invalid(
+                                format!("{}.{}", at, occurrence),
+                                "is not supported here",
+                            ))
+                        }
+                    };
+                    let at = format!("{}.{}", at, occurrence);
+                    match clauses {
+                        Value::Array(clauses) => {
+                            for (i, clause) in clauses.iter().enumerate() {
+                                self.add(clause, nested, &format!("{}[{}]", at, i))?;
+                            }
+                        }
+                        clause => self.add(clause, nested, &at)?,
+                    }
+                }
+                Ok(())
+            }
+            _ => Err(invalid(at, "is not supported here")),
+        }
+    }
+
+    fn add_text(&mut self, text: &Value, occur: Occur, at: &str) -> Result<()> {
+        if occur == Occur::MustNot {
+            return Err(invalid(
+                at,
+                "text queries cannot be negated; use a term filter",
+            ));
+        }
+        let text = text
+            .as_str()
+            .ok_or_else(|| invalid(at, "query must be a string"))?;
+        if !text.trim().is_empty() {
+            self.text.push(text.trim().to_string());
+        }
+        Ok(())
+    }
+
+    fn add_terms(&mut self, field: &str, values: &[String], occur: Occur, at: &str) -> Result<()> {
+        let field = field_name(field);
+        match occur {
+            Occur::Should => Err(invalid(at, "should only supports text queries")),
+            Occur::MustNot => {
+                let excluded = match field.as_str() {
+                    "tag" | TAGS_KEY => &mut self.exclude_tags,
+                    "collection" | "_index" => &mut self.exclude_collections,
+                    "_id" | "document_id" | "doc" => &mut self.exclude_documents,
+                    _ => return Err(invalid(at, "must_not supports tags, collection and _id")),
+                };
+                excluded.extend(values.iter().cloned());
+                Ok(())
+            }
+            Occur::Must => {
+                let (key, pattern) = match field.as_str() {
+                    "tag" | TAGS_KEY => (TAGS_KEY.to_string(), tag_pattern(values)),
+                    "_id" | "_index" => {
+                        return Err(invalid(at, format!("cannot filter on {}", field)))
+                    }
+                    _ => {
+                        let pattern = value_pattern(&field, values);
+                        (field, pattern)
+                    }
+                };
+                if self.patterns.contains_key(&key) {
+                    return Err(invalid(at, format!("{} already has a filter", key)));
+                }
+                self.patterns.insert(key, pattern);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Metadata pattern matching a field that equals any of `values`
+fn value_pattern(field: &str, values: &[String]) -> String {
+    // Paths match at any directory boundary, as in path filters
+    let anchor = if field == PATH_KEY { "(?:^|/)" } else { "^" };
+    format!("re:{}(?:{})$", anchor, alternatives(values))
+}
+
+/// Metadata pattern matching a tag list holding any of `values`
+fn tag_pattern(values: &[String]) -> String {
+    format!(r"re:(?i)(?:^|,)\s*(?:{})\s*(?:,|$)", alternatives(values))
+}
+
+fn alternatives(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| regex::escape(value))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Metadata key of a field, without the `.keyword` suffix of string fields
+fn field_name(field: &str) -> String {
+    field.strip_suffix(".keyword").unwrap_or(field).to_string()
+}
+
+/// The only entry of a `{ "<field>": ... }` object
+fn single_field<'a>(body: &'a Value, at: &str) -> Result<(&'a String, &'a Value)> {
+    match body.as_object() {
+        Some(body) if body.len() == 1 => Ok(body.iter().next().unwrap()),
+        _ => Err(invalid(at, "must name exactly one field")),
+    }
+}
+
+fn scalar(value: &Value, at: &str) -> Result<String> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Number(value) => Ok(value.to_string()),
+        Value::Bool(value) => Ok(value.to_string()),
+        _ => Err(invalid(at, "values must be strings, numbers or booleans")),
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(value) => value.as_f64(),
+        Value::String(value) => value.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn count(body: &Map<String, Value>, key: &str, default: usize) -> Result<usize> {
+    match body.get(key) {
+        None | Some(Value::Null) => Ok(default),
+        Some(value) => value
+            .as_u64()
+            .map(|value| value as usize)
+            .ok_or_else(|| invalid(key, "must be a non-negative integer")),
+    }
+}
+
+/// Results are always ordered by score, so only score sorts are accepted
+fn check_sort(sort: &Value) -> Result<()> {
+    let by_score = |sort: &Value| match sort {
+        Value::String(field) => field == "_score",
+        Value::Object(sort) => sort.len() == 1 && sort.contains_key("_score"),
+        _ => false,
+    };
+    let supported = match sort {
+        Value::Array(sorts) => sorts.iter().all(by_score),
+        sort => by_score(sort),
+    };
+    if supported {
+        Ok(())
+    } else {
+        Err(invalid("sort", "results can only be sorted by _score"))
+    }
+}
+
+fn non_empty(values: Vec<String>) -> Option<Vec<String>> {
+    (!values.is_empty()).then_some(values)
+}
+
+fn invalid(field: impl Into<String>, message: impl Into<String>) -> ZeroLatencyError {
+    ZeroLatencyError::validation(field, message)
+}
+
+/// Body of a `_search` response
+#[derive(Debug, Clone, Serialize)]
+pub struct CompatResponse {
+    pub took: u64,
+    pub timed_out: bool,
+    #[serde(rename = "_shards")]
+    pub shards: Shards,
+    pub hits: Hits,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Shards {
+    pub total: u32,
+    pub successful: u32,
+    pub skipped: u32,
+    pub failed: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Hits {
+    pub total: TotalHits,
+    pub max_score: Option<f32>,
+    pub hits: Vec<Hit>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TotalHits {
+    pub value: usize,
+    /// `gte` when the search stopped at the requested page
+    pub relation: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Hit {
+    #[serde(rename = "_index")]
+    pub index: String,
+    /// Chunk ID
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "_score")]
+    pub score: f32,
+    /// Custom metadata plus the chunk's title, content, path and document
+    #[serde(rename = "_source")]
+    pub source: Map<String, Value>,
+}
+
+impl CompatResponse {
+    /// Page a search response the way the translated request asked for
+    pub fn new(
+        search: &CompatSearch,
+        index: &str,
+        response: SearchResponse,
+        took: Duration,
+    ) -> Self {
+        let found = response.results.len();
+        let hits: Vec<Hit> = response
+            .results
+            .into_iter()
+            .skip(search.from)
+            .take(search.size)
+            .map(|result| {
+                let mut source: Map<String, Value> = result
+                    .custom_metadata
+                    .into_iter()
+                    .map(|(key, value)| (key, Value::String(value)))
+                    .collect();
+                for (key, value) in [
+                    ("title", result.title),
+                    ("content", result.content),
+                    ("path", result.document_path),
+                    ("uri", result.uri),
+                    ("document_id", result.document_id.to_string()),
+                ] {
+                    source.insert(key.to_string(), Value::String(value));
+                }
+                if !result.section_path.is_empty() {
+                    source.insert("section_path".to_string(), result.section_path.into());
+                }
+                Hit {
+                    index: result.collection.unwrap_or_else(|| index.to_string()),
+                    id: result.chunk_id.to_string(),
+                    score: result.final_score.value(),
+                    source,
+                }
+            })
+            .collect();
+
+        Self {
+            took: took.as_millis() as u64,
+            timed_out: false,
+            shards: Shards {
+                total: 1,
+                successful: 1,
+                skipped: 0,
+                failed: 0,
+            },
+            hits: Hits {
+                total: TotalHits {
+                    value: response.total_count.unwrap_or(found).max(found),
+                    relation: if found >= search.from + search.size {
+                        "gte"
+                    } else {
+                        "eq"
+                    },
+                },
+                max_score: hits.iter().map(|hit| hit.score).reduce(f32::max),
+                hits,
+            },
+        }
+    }
+}
+
+/// Split an `_msearch` body into (index, search body) pairs
+///
+/// Each search is a header line naming the index, falling back to
+/// `default_index`, followed by a line with the search body.
+pub fn parse_msearch(body: &str, default_index: Option<&str>) -> Result<Vec<(String, Value)>> {
+    let lines: Vec<&str> = body
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if !lines.len().is_multiple_of(2) {
+        return Err(invalid(
+            "body",
+            "every header line must be followed by a search body",
+        ));
+    }
+    lines
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            let at = format!("searches[{}]", i);
+            let header: Value = serde_json::from_str(pair[0])
+                .map_err(|e| invalid(&at, format!("invalid header: {}", e)))?;
+            let body: Value = serde_json::from_str(pair[1])
+                .map_err(|e| invalid(&at, format!("invalid body: {}", e)))?;
+            let index = match header.get("index") {
+                Some(Value::String(index)) => index.clone(),
+                Some(Value::Array(indices)) => indices
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(","),
+                _ => default_index
+                    .ok_or_else(|| invalid(&at, "header must name an index"))?
+                    .to_string(),
+            };
+            Ok((index, body))
+        })
+        .collect()
+}
+
+/// Entry of an `_msearch` response for a search that failed
+pub fn error_item(error: &ZeroLatencyError) -> Value {
+    let (kind, status) = match error {
+        ZeroLatencyError::Validation { .. } => ("parsing_exception", 400),
+        ZeroLatencyError::NotFound { .. } => ("index_not_found_exception", 404),
+        _ => ("search_phase_execution_exception", 500),
+    };
+    serde_json::json!({
+        "error": { "type": kind, "reason": error.to_string() },
+        "status": status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_translates_bool_queries() {
+        let search = translate(
+            "docs",
+            &json!({
+                "from": 5,
+                "size": 20,
+                "query": {
+                    "bool": {
+                        "must": [{ "match": { "content": "rate limits" } }],
+                        "should": { "multi_match": { "query": "quota", "fields": ["title"] } },
+                        "filter": [
+                            { "term": { "tags.keyword": "api" } },
+                            { "terms": { "section": ["guides", "reference"] } },
+                            { "range": { "version": { "gte": 2, "lt": "4" } } }
+                        ],
+                        "must_not": [
+                            { "term": { "tags": "deprecated" } },
+                            { "terms": { "_id": ["doc-1"] } }
+                        ]
+                    }
+                }
+            }),
+        )
+        .unwrap();
+
+        assert_eq!((search.from, search.size), (5, 20));
+        let request = search.request;
+        assert_eq!(request.query, "rate limits quota");
+        assert_eq!(request.limit, Some(25));
+        assert_eq!(request.exclude_documents, Some(vec!["doc-1".to_string()]));
+        let filters = request.filters.unwrap();
+        assert_eq!(filters.collection_name.as_deref(), Some("docs"));
+        assert_eq!(filters.collection_names, None);
+        assert_eq!(filters.exclude_tags, Some(vec!["deprecated".to_string()]));
+        let patterns = filters.metadata_patterns.unwrap();
+        assert_eq!(patterns["section"], "re:^(?:guides|reference)$");
+        let tags = regex::Regex::new(patterns["tags"].strip_prefix("re:").unwrap()).unwrap();
+        assert!(tags.is_match("guide, API"));
+        assert!(!tags.is_match("apis"));
+        let ranges = filters.ranges.unwrap();
+        let range = &ranges["version"];
+        assert_eq!((range.gte, range.lte), (Some(2.0), Some(4.0)));
+    }
+
+    #[test]
+    fn test_translates_indices_and_defaults() {
+        let search = translate("_all", &Value::Null).unwrap();
+        assert_eq!((search.from, search.size), (0, DEFAULT_SIZE));
+        assert_eq!(search.request.query, "");
+        let filters = search.request.filters.unwrap();
+        assert_eq!(filters.collection_names, Some(vec!["*".to_string()]));
+
+        let search = translate(
+            "docs,api-*",
+            &json!({ "query": { "match_all": {} }, "sort": ["_score"] }),
+        )
+        .unwrap();
+        let filters = search.request.filters.unwrap();
+        assert_eq!(filters.collection_name, None);
+        assert_eq!(
+            filters.collection_names,
+            Some(vec!["docs".to_string(), "api-*".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_rejects_unsupported_queries() {
+        for body in [
+            json!({ "aggs": {} }),
+            json!({ "sort": [{ "modified_at": "desc" }] }),
+            json!({ "size": 900, "from": 200 }),
+            json!({ "from": u64::MAX }),
+            json!({ "size": u64::MAX, "from": 1 }),
+            json!({ "query": { "fuzzy": { "title": "serch" } } }),
+            json!({ "query": { "bool": { "must_not": { "match": { "content": "beta" } } } } }),
+            json!({ "query": { "bool": { "should": { "term": { "tags": "api" } } } } }),
+            json!({ "query": { "bool": { "must_not": { "term": { "owner": "me" } } } } }),
+            json!({ "query": { "range": { "version": { "gte": "now-1d" } } } }),
+            json!({ "query": { "match": { "title": "a", "content": "b" } } }),
+            json!({ "query": { "bool": { "filter": [
+                { "term": { "owner": "a" } },
+                { "term": { "owner": "b" } }
+            ] } } }),
+        ] {
+            let error = translate("docs", &body).unwrap_err();
+            assert!(
+                matches!(error, ZeroLatencyError::Validation { .. }),
+                "{}: {:?}",
+                body,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_parses_msearch() {
+        let body = "{\"index\":\"docs\"}\n{\"query\":{\"match_all\":{}}}\n{}\n{\"size\":1}\n";
+        let searches = parse_msearch(body, Some("default")).unwrap();
+        assert_eq!(searches.len(), 2);
+        assert_eq!(searches[0].0, "docs");
+        assert_eq!(searches[1].0, "default");
+        assert_eq!(searches[1].1, json!({ "size": 1 }));
+
+        assert!(parse_msearch("{}\n", None).is_err());
+        assert!(parse_msearch("{}\n{}\n", None).is_err());
+    }
+}
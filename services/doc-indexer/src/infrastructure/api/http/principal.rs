@@ -0,0 +1,172 @@
+use axum::{
+    extract::{Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::IntoResponse,
+    response::Response,
+};
+/// Request principals
+///
+/// An authenticating gateway in front of the indexer names the user a request
+/// is made for in `X-Principal-User` and their groups, comma-separated, in
+/// `X-Principal-Groups`, and vouches for them with the configured secret in
+/// `X-Principal-Token`. The principal is available while the request is
+/// handled through [`current_principal`]; when access control is enforced,
+/// searches only return documents whose ACLs admit it (see
+/// [`zero_latency_search::access`]).
+use std::sync::Arc;
+use zero_latency_core::ZeroLatencyError;
+use zero_latency_search::Principal;
+
+use super::handlers::AppError;
+use crate::application::services::admin_service::tokens_match;
+use crate::config::{AccessConfig, Config};
+
+/// Header naming the user of a request
+pub const PRINCIPAL_USER_HEADER: &str = "x-principal-user";
+
+/// Header listing the groups of the user of a request
+pub const PRINCIPAL_GROUPS_HEADER: &str = "x-principal-groups";
+
+/// Header carrying the gateway's secret
+pub const PRINCIPAL_TOKEN_HEADER: &str = "x-principal-token";
+
+tokio::task_local! {
+    static CURRENT_PRINCIPAL: Option<Principal>;
+}
+
+/// Principal of the HTTP request currently being handled, if it named one
+pub fn current_principal() -> Option<Principal> {
+    CURRENT_PRINCIPAL
+        .try_with(|principal| principal.clone())
+        .ok()
+        .flatten()
+}
+
+/// Run `future` on behalf of `principal`, e.g. work spawned off a request
+pub async fn with_principal<F: std::future::Future>(
+    principal: Option<Principal>,
+    future: F,
+) -> F::Output {
+    CURRENT_PRINCIPAL.scope(principal, future).await
+}
+
+/// Principal searches are filtered for, if access control is enforced
+///
+/// Requests that don't name a principal are anonymous and only see
+/// unrestricted documents.
+pub fn access_scope(config: &AccessConfig) -> Option<Principal> {
+    config
+        .enforce
+        .then(|| current_principal().unwrap_or_default())
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// The principal named by `headers`, if the token vouches for it
+fn principal_from(
+    headers: &HeaderMap,
+    config: &AccessConfig,
+) -> Result<Option<Principal>, ZeroLatencyError> {
+    let user = header(headers, PRINCIPAL_USER_HEADER);
+    let groups = header(headers, PRINCIPAL_GROUPS_HEADER);
+    if user.is_none() && groups.is_none() {
+        return Ok(None);
+    }
+    let Some(expected) = config.principal_token.as_deref() else {
+        return Err(ZeroLatencyError::permission_denied(
+            "principal headers are disabled; set DOC_INDEXER_PRINCIPAL_TOKEN",
+        ));
+    };
+    match header(headers, PRINCIPAL_TOKEN_HEADER) {
+        Some(token) if tokens_match(token, expected) => Ok(Some(Principal {
+            user: user.map(str::to_string),
+            groups: groups
+                .map(|groups| {
+                    groups
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|group| !group.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })),
+        _ => Err(ZeroLatencyError::permission_denied(
+            "principal headers need a valid X-Principal-Token",
+        )),
+    }
+}
+
+/// Attribute the request to the principal named by its headers
+///
+/// Principal headers without a valid token are rejected rather than ignored,
+/// so a misconfigured gateway doesn't silently turn users anonymous.
+pub async fn principal_middleware(
+    State(config): State<Arc<Config>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match principal_from(request.headers(), &config.access) {
+        Ok(principal) => with_principal(principal, next.run(request)).await,
+        Err(error) => AppError::from(error).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_principal_headers_need_the_gateway_token() {
+        let config = AccessConfig {
+            enforce: true,
+            principal_token: Some("secret".to_string()),
+        };
+        assert_eq!(principal_from(&headers(&[]), &config).unwrap(), None);
+
+        let named = headers(&[
+            (PRINCIPAL_USER_HEADER, "alice"),
+            (PRINCIPAL_GROUPS_HEADER, "eng, ops,"),
+            (PRINCIPAL_TOKEN_HEADER, "secret"),
+        ]);
+        let principal = principal_from(&named, &config).unwrap().unwrap();
+        assert_eq!(principal.user.as_deref(), Some("alice"));
+        assert_eq!(principal.groups, vec!["eng", "ops"]);
+
+        let forged = headers(&[
+            (PRINCIPAL_USER_HEADER, "alice"),
+            (PRINCIPAL_TOKEN_HEADER, "guess"),
+        ]);
+        assert!(principal_from(&forged, &config).is_err());
+        let disabled = AccessConfig {
+            principal_token: None,
+            ..config.clone()
+        };
+        assert!(principal_from(&named, &disabled).is_err());
+
+        // Enforced searches without a principal are anonymous
+        assert_eq!(access_scope(&config), Some(Principal::default()));
+        let scope = with_principal(Some(principal.clone()), async { access_scope(&config) }).await;
+        assert_eq!(scope, Some(principal));
+        let relaxed = AccessConfig {
+            enforce: false,
+            ..config
+        };
+        assert_eq!(access_scope(&relaxed), None);
+    }
+}
@@ -0,0 +1,207 @@
+/// Profiling endpoints (`profiling` feature)
+///
+/// Lets operators capture a CPU profile of a running server when latency
+/// regresses, instead of reproducing the load locally. `/debug/pprof/profile`
+/// samples the stacks of every thread for a few seconds and returns a
+/// flamegraph or a pprof protobuf; `/debug/pprof/heap` reports jemalloc's
+/// allocator statistics. Both are disabled unless `DOC_INDEXER_PROFILING` is
+/// set, and need the admin token since sampling costs CPU while it runs.
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use pprof::protos::Message;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tikv_jemalloc_ctl::{epoch, stats};
+use zero_latency_api::endpoints::endpoints;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use super::handlers::{bearer_token, AppError, AppState};
+use crate::application::services::admin_service::AdminScope;
+
+/// Profile length when the request doesn't set one
+const DEFAULT_SECONDS: u64 = 10;
+
+/// Default sampling frequency, off 100 Hz so samples don't line up with timers
+const DEFAULT_FREQUENCY: i32 = 99;
+
+/// Libraries whose frames can't be unwound safely from a signal handler
+const BLOCKLIST: &[&str] = &["libc", "libgcc", "pthread", "vdso"];
+
+// The profiler samples the whole process, so only one profile runs at a time
+static PROFILING: AtomicBool = AtomicBool::new(false);
+
+/// Query parameters of a CPU profile
+#[derive(Debug, Deserialize)]
+pub struct ProfileQuery {
+    /// Seconds to sample for
+    pub seconds: Option<u64>,
+    /// Samples per second
+    pub frequency: Option<i32>,
+    /// `flamegraph` (SVG, default) or `pprof` (protobuf for `go tool pprof`)
+    pub format: Option<String>,
+}
+
+/// Allocator statistics, in bytes
+#[derive(Debug, Serialize)]
+pub struct HeapStats {
+    /// Bytes allocated by the application
+    pub allocated: usize,
+    /// Bytes in pages the allocator has handed out
+    pub active: usize,
+    /// Bytes of physical memory the allocator holds
+    pub resident: usize,
+    /// Bytes mapped by the allocator
+    pub mapped: usize,
+    /// Bytes unmapped but kept reserved for reuse
+    pub retained: usize,
+    /// Bytes used for the allocator's own bookkeeping
+    pub metadata: usize,
+}
+
+/// Routes of the profiling endpoints
+pub fn create_router<S>(state: AppState) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(endpoints::PPROF_PROFILE, get(cpu_profile))
+        .route(endpoints::PPROF_HEAP, get(heap_stats))
+        .with_state(state)
+}
+
+/// Refuse the request unless profiling is enabled and it carries the admin token
+fn authorize(state: &AppState, headers: &HeaderMap) -> Result<()> {
+    if !state.container.config().profiling.enabled {
+        return Err(ZeroLatencyError::permission_denied(
+            "profiling is disabled; set DOC_INDEXER_PROFILING=true",
+        ));
+    }
+    state
+        .admin_service
+        .authorize(bearer_token(headers), AdminScope::Write)
+}
+
+/// Releases the profiler when the profile finishes or the request is dropped
+struct ProfilingSlot;
+
+impl ProfilingSlot {
+    fn acquire() -> Result<Self> {
+        if PROFILING.swap(true, Ordering::AcqRel) {
+            return Err(ZeroLatencyError::validation(
+                "profile",
+                "another CPU profile is already running",
+            ));
+        }
+        Ok(Self)
+    }
+}
+
+impl Drop for ProfilingSlot {
+    fn drop(&mut self) {
+        PROFILING.store(false, Ordering::Release);
+    }
+}
+
+/// Sample the process's stacks for a while
+async fn cpu_profile(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ProfileQuery>,
+) -> std::result::Result<Response, AppError> {
+    authorize(&state, &headers)?;
+
+    let max_seconds = state.container.config().profiling.max_seconds;
+    let seconds = params.seconds.unwrap_or(DEFAULT_SECONDS.min(max_seconds));
+    if !(1..=max_seconds).contains(&seconds) {
+        return Err(ZeroLatencyError::validation(
+            "seconds",
+            format!("must be between 1 and {}", max_seconds),
+        )
+        .into());
+    }
+    let frequency = params.frequency.unwrap_or(DEFAULT_FREQUENCY);
+    if !(1..=1000).contains(&frequency) {
+        return Err(ZeroLatencyError::validation("frequency", "must be between 1 and 1000").into());
+    }
+    let pprof_format = match params.format.as_deref().unwrap_or("flamegraph") {
+        "flamegraph" => false,
+        "pprof" => true,
+        other => {
+            return Err(ZeroLatencyError::validation(
+                "format",
+                format!("unknown format '{}'; use flamegraph or pprof", other),
+            )
+            .into())
+        }
+    };
+
+    let _slot = ProfilingSlot::acquire()?;
+    tracing::info!(seconds, frequency, "Capturing CPU profile");
+    let report = tokio::task::spawn_blocking(move || {
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(frequency)
+            .blocklist(BLOCKLIST)
+            .build()
+            .map_err(profiler_error)?;
+        std::thread::sleep(Duration::from_secs(seconds));
+        guard.report().build().map_err(profiler_error)
+    })
+    .await
+    .map_err(|e| ZeroLatencyError::internal(format!("Profiler task failed: {}", e)))??;
+
+    let mut body = Vec::new();
+    let content_type = if pprof_format {
+        report
+            .pprof()
+            .map_err(profiler_error)?
+            .encode(&mut body)
+            .map_err(|e| {
+                ZeroLatencyError::serialization(format!("Failed to encode profile: {}", e))
+            })?;
+        "application/octet-stream"
+    } else {
+        // A flamegraph of nothing is no image at all
+        if report.data.is_empty() {
+            return Err(ZeroLatencyError::not_found(
+                "CPU samples; the server was idle while profiling",
+            )
+            .into());
+        }
+        report.flamegraph(&mut body).map_err(profiler_error)?;
+        "image/svg+xml"
+    };
+    Ok(([(header::CONTENT_TYPE, content_type)], body).into_response())
+}
+
+/// Current allocator statistics
+async fn heap_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> std::result::Result<Json<HeapStats>, AppError> {
+    authorize(&state, &headers)?;
+
+    // Statistics are cached until the epoch advances
+    epoch::advance().map_err(allocator_error)?;
+    Ok(Json(HeapStats {
+        allocated: stats::allocated::read().map_err(allocator_error)?,
+        active: stats::active::read().map_err(allocator_error)?,
+        resident: stats::resident::read().map_err(allocator_error)?,
+        mapped: stats::mapped::read().map_err(allocator_error)?,
+        retained: stats::retained::read().map_err(allocator_error)?,
+        metadata: stats::metadata::read().map_err(allocator_error)?,
+    }))
+}
+
+fn profiler_error(e: pprof::Error) -> ZeroLatencyError {
+    ZeroLatencyError::internal(format!("Profiler failed: {}", e))
+}
+
+fn allocator_error(e: tikv_jemalloc_ctl::Error) -> ZeroLatencyError {
+    ZeroLatencyError::internal(format!("Failed to read allocator statistics: {}", e))
+}
@@ -0,0 +1,154 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use governor::{
+    clock::{Clock, DefaultClock},
+    DefaultDirectRateLimiter, Quota,
+};
+/// API rate limiting
+///
+/// A single token bucket shared by all clients, configured from
+/// `[rate_limit]` and adjustable at runtime through the admin API. Health
+/// checks, the admin API itself and the bundled UI are never limited, so an
+/// operator can always inspect the service and loosen a limit that is too
+/// tight. Rejected requests get `429 Too Many Requests` with `Retry-After`.
+use std::num::NonZeroU32;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use zero_latency_api::endpoints::endpoints;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use crate::config::RateLimitConfig;
+
+/// Path prefixes that are never rate limited
+const EXEMPT_PREFIXES: &[&str] = &[endpoints::HEALTH, endpoints::ADMIN, endpoints::WEB_UI];
+
+/// Rate limiter whose settings can be replaced while serving
+pub struct ApiRateLimiter {
+    state: RwLock<LimiterState>,
+}
+
+struct LimiterState {
+    settings: RateLimitConfig,
+    limiter: Option<Arc<DefaultDirectRateLimiter>>,
+}
+
+impl ApiRateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            state: RwLock::new(LimiterState::from_settings(config.clone())),
+        }
+    }
+
+    /// Current settings
+    pub fn settings(&self) -> RateLimitConfig {
+        self.state.read().unwrap().settings.clone()
+    }
+
+    /// Replace the settings; the bucket starts full under the new quota
+    pub fn update(&self, settings: RateLimitConfig) -> Result<()> {
+        if settings.requests_per_second > 0 && settings.burst == 0 {
+            return Err(ZeroLatencyError::validation(
+                "rate_limit.burst",
+                "must be greater than 0 when rate limiting is enabled",
+            ));
+        }
+        *self.state.write().unwrap() = LimiterState::from_settings(settings);
+        Ok(())
+    }
+
+    /// Take a request from the bucket, or return how long to wait for one
+    pub fn check(&self) -> std::result::Result<(), Duration> {
+        let limiter = match &self.state.read().unwrap().limiter {
+            Some(limiter) => limiter.clone(),
+            None => return Ok(()),
+        };
+        limiter
+            .check()
+            .map_err(|not_until| not_until.wait_time_from(DefaultClock::default().now()))
+    }
+}
+
+impl LimiterState {
+    fn from_settings(settings: RateLimitConfig) -> Self {
+        let limiter = NonZeroU32::new(settings.requests_per_second).map(|rate| {
+            let burst = NonZeroU32::new(settings.burst).unwrap_or(rate);
+            Arc::new(governor::RateLimiter::direct(
+                Quota::per_second(rate).allow_burst(burst),
+            ))
+        });
+        Self { settings, limiter }
+    }
+}
+
+/// Reject requests over the configured rate
+pub async fn rate_limit_middleware(
+    State(limiter): State<Arc<ApiRateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if EXEMPT_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+    {
+        return next.run(request).await;
+    }
+
+    match limiter.check() {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            let body = serde_json::json!({
+                "error": {
+                    "message": "Rate limit exceeded",
+                    "type": "RateLimited",
+                    "trace_id": super::request_id::current_request_id(),
+                }
+            });
+            // Whole seconds, rounded up so a prompt retry is not rejected again
+            let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after.max(1).to_string())],
+                Json(body),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(requests_per_second: u32, burst: u32) -> RateLimitConfig {
+        RateLimitConfig {
+            requests_per_second,
+            burst,
+        }
+    }
+
+    #[test]
+    fn test_limit_applies_and_updates() {
+        let limiter = ApiRateLimiter::new(&settings(0, 50));
+        for _ in 0..100 {
+            assert!(limiter.check().is_ok());
+        }
+
+        limiter.update(settings(1, 2)).unwrap();
+        assert!(limiter.check().is_ok());
+        assert!(limiter.check().is_ok());
+        let wait = limiter.check().unwrap_err();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_secs(1));
+
+        assert!(limiter.update(settings(1, 0)).is_err());
+        assert_eq!(limiter.settings().burst, 2);
+
+        limiter.update(settings(0, 2)).unwrap();
+        assert!(limiter.check().is_ok());
+    }
+}
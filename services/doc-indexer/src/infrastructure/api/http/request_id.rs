@@ -0,0 +1,140 @@
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+/// Request ID propagation
+///
+/// Every HTTP request is assigned an ID: the caller's `X-Request-Id` header
+/// when present and well formed, otherwise a fresh UUID. The ID is echoed in
+/// the response header, recorded on the request's tracing span so every log
+/// line carries it, and available to error responses through
+/// [`current_request_id`] so clients can quote it when reporting problems.
+use tracing::Span;
+use uuid::Uuid;
+
+/// Header used to receive and return the request ID
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied request ID that is accepted as-is
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Request ID stored in the request extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: RequestId;
+}
+
+impl RequestId {
+    /// Use the caller's request ID if it is usable, otherwise generate one
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| Self::is_valid(value))
+            .map(|value| Self(value.to_string()))
+            .unwrap_or_else(Self::generate)
+    }
+
+    /// Generate a new request ID
+    pub fn generate() -> Self {
+        Self(Uuid::new_v4().simple().to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    // Visible ASCII only, so the ID is safe to echo in headers and logs
+    fn is_valid(value: &str) -> bool {
+        !value.is_empty()
+            && value.len() <= MAX_REQUEST_ID_LEN
+            && value.bytes().all(|b| b.is_ascii_graphic())
+    }
+}
+
+/// Request ID of the HTTP request currently being handled, if any
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(|id| id.0.clone()).ok()
+}
+
+/// Assign a request ID and return it in the `X-Request-Id` response header
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = RequestId::from_headers(request.headers());
+    let header =
+        HeaderValue::from_str(request_id.as_str()).expect("request IDs contain only visible ASCII");
+
+    request
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, header.clone());
+    request.extensions_mut().insert(request_id.clone());
+
+    let mut response = CURRENT_REQUEST_ID
+        .scope(request_id, next.run(request))
+        .await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, header);
+    response
+}
+
+/// Span for an HTTP request, carrying its request ID
+///
+/// Used with `TraceLayer::make_span_with`; must run inside
+/// [`request_id_middleware`] so the ID is already assigned.
+pub fn make_request_span(request: &Request) -> Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(RequestId::as_str)
+        .unwrap_or_default();
+
+    tracing::info_span!(
+        "http_request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_caller_request_id_is_kept() {
+        let id = RequestId::from_headers(&headers("support-1234"));
+        assert_eq!(id.as_str(), "support-1234");
+    }
+
+    #[test]
+    fn test_invalid_request_id_is_replaced() {
+        for value in ["", "has space", &"x".repeat(MAX_REQUEST_ID_LEN + 1)] {
+            let id = RequestId::from_headers(&headers(value));
+            assert_ne!(id.as_str(), value);
+            assert_eq!(id.as_str().len(), 32);
+        }
+
+        assert_eq!(
+            RequestId::from_headers(&HeaderMap::new()).as_str().len(),
+            32
+        );
+    }
+
+    #[tokio::test]
+    async fn test_current_request_id_is_scoped() {
+        assert_eq!(current_request_id(), None);
+
+        let id = CURRENT_REQUEST_ID
+            .scope(RequestId("abc".to_string()), async { current_request_id() })
+            .await;
+        assert_eq!(id.as_deref(), Some("abc"));
+    }
+}
@@ -0,0 +1,190 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::header::CONTENT_TYPE,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+/// Runtime validation of HTTP bodies against the OpenAPI spec
+///
+/// Intended for debug and staging deployments: JSON request and response
+/// bodies of documented operations are checked against the schemas in
+/// `api/schemas/zero-latency-api.yaml`, so drift between the spec and the
+/// handlers shows up as soon as a request exercises it. Depending on the
+/// configured mode, mismatches are either logged or rejected.
+use std::sync::Arc;
+use zero_latency_api::schema::{ApiSchema, SchemaViolation};
+use zero_latency_core::ZeroLatencyError;
+
+use super::handlers::AppError;
+use crate::config::SchemaValidationMode;
+
+/// Largest body that is buffered for validation
+const MAX_VALIDATED_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Violations listed in a single log line or error message
+const MAX_REPORTED_VIOLATIONS: usize = 5;
+
+/// Validator shared by all requests
+pub struct SchemaValidator {
+    schema: ApiSchema,
+    mode: SchemaValidationMode,
+}
+
+impl SchemaValidator {
+    /// Load the spec embedded in `zero-latency-api`
+    pub fn new(mode: SchemaValidationMode) -> Result<Self, ZeroLatencyError> {
+        let schema = ApiSchema::load().map_err(|e| {
+            ZeroLatencyError::configuration(format!("Failed to load OpenAPI schema: {}", e))
+        })?;
+        Ok(Self { schema, mode })
+    }
+}
+
+/// Validate request and response bodies of documented operations
+pub async fn schema_validation_middleware(
+    State(validator): State<Arc<SchemaValidator>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().as_str().to_string();
+    let path = request.uri().path().to_string();
+
+    if validator.mode == SchemaValidationMode::Off || !validator.schema.documents(&method, &path) {
+        return next.run(request).await;
+    }
+
+    let request = if is_json(request.headers()) {
+        let (parts, body) = request.into_parts();
+        let bytes = match to_bytes(body, MAX_VALIDATED_BODY_BYTES).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return AppError::from(ZeroLatencyError::validation(
+                    "body",
+                    format!("Failed to read request body: {}", e),
+                ))
+                .into_response()
+            }
+        };
+
+        // Bodies that aren't valid JSON are left to the handler's extractor
+        if let Ok(value) = serde_json::from_slice(&bytes) {
+            if let Some(violations) = validator.schema.validate_request(&method, &path, &value) {
+                if !violations.is_empty() {
+                    let summary = summarize(&violations);
+                    tracing::warn!(
+                        "Request body for {} {} does not match the OpenAPI schema: {}",
+                        method,
+                        path,
+                        summary
+                    );
+                    if validator.mode == SchemaValidationMode::Enforce {
+                        return AppError::from(ZeroLatencyError::validation(
+                            "body",
+                            format!("Request does not match the API schema: {}", summary),
+                        ))
+                        .into_response();
+                    }
+                }
+            }
+        }
+
+        Request::from_parts(parts, Body::from(bytes))
+    } else {
+        request
+    };
+
+    let response = next.run(request).await;
+    if !is_json(response.headers()) {
+        return response;
+    }
+
+    let status = response.status().as_u16();
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_VALIDATED_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to buffer response for {} {} for schema validation: {}",
+                method,
+                path,
+                e
+            );
+            return AppError::from(ZeroLatencyError::internal(format!(
+                "Failed to read response body: {}",
+                e
+            )))
+            .into_response();
+        }
+    };
+
+    if let Ok(value) = serde_json::from_slice(&bytes) {
+        if let Some(violations) = validator
+            .schema
+            .validate_response(&method, &path, status, &value)
+        {
+            if !violations.is_empty() {
+                let summary = summarize(&violations);
+                tracing::warn!(
+                    "Response body ({}) for {} {} does not match the OpenAPI schema: {}",
+                    status,
+                    method,
+                    path,
+                    summary
+                );
+                if validator.mode == SchemaValidationMode::Enforce {
+                    return AppError::from(ZeroLatencyError::internal(format!(
+                        "Response does not match the API schema: {}",
+                        summary
+                    )))
+                    .into_response();
+                }
+            }
+        }
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+fn is_json(headers: &axum::http::HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/json"))
+        .unwrap_or(false)
+}
+
+fn summarize(violations: &[SchemaViolation]) -> String {
+    let mut summary = violations
+        .iter()
+        .take(MAX_REPORTED_VIOLATIONS)
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ");
+    if violations.len() > MAX_REPORTED_VIOLATIONS {
+        summary.push_str(&format!(
+            " (and {} more)",
+            violations.len() - MAX_REPORTED_VIOLATIONS
+        ));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_is_truncated() {
+        let violations: Vec<SchemaViolation> = (0..7)
+            .map(|i| SchemaViolation {
+                pointer: format!("/{}", i),
+                message: "bad".to_string(),
+            })
+            .collect();
+
+        let summary = summarize(&violations);
+        assert_eq!(summary.matches("bad").count(), MAX_REPORTED_VIOLATIONS);
+        assert!(summary.ends_with("(and 2 more)"));
+    }
+}
@@ -1,6 +1,6 @@
 use axum::{
-    extract::Request,
-    http::{HeaderValue, Method},
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, Method},
     middleware::{self, Next},
     response::Response,
     Router,
@@ -19,7 +19,26 @@ use tower_http::{cors::CorsLayer, timeout::TimeoutLayer, trace::TraceLayer};
 use tracing::{info, warn};
 
 use super::handlers::AppState;
+use super::lanes::lane_middleware;
+use super::rate_limit::rate_limit_middleware;
+use super::request_id::{make_request_span, request_id_middleware, REQUEST_ID_HEADER};
+use super::schema_validation::{schema_validation_middleware, SchemaValidator};
+use super::principal::{
+    principal_middleware, PRINCIPAL_GROUPS_HEADER, PRINCIPAL_TOKEN_HEADER, PRINCIPAL_USER_HEADER,
+};
+use super::tenant::{tenant_middleware, TENANT_HEADER};
 use crate::application::ServiceContainer;
+use crate::config::SchemaValidationMode;
+use crate::infrastructure::operations::production::health::ServiceHealthCheck;
+use crate::infrastructure::operations::production::{
+    ProductionConfig, ProductionDeployment, RequestMetrics, StartupValidator,
+};
+
+/// Seconds between writes of the usage totals to disk
+const USAGE_FLUSH_INTERVAL_SECS: u64 = 60;
+
+/// Seconds between writes of running jobs' progress to disk
+const JOB_CHECKPOINT_INTERVAL_SECS: u64 = 10;
 
 /// HTTP server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,10 +62,57 @@ impl HttpServer {
         config: ServerConfig,
         container: Arc<ServiceContainer>,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let production = container.config().production.clone();
         let app_state = AppState::new_async(container)
             .await
             .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
 
+        // Validate startup and follow health in the background; readiness
+        // waits for validation to pass
+        let mut deployment = ProductionDeployment::new(ProductionConfig {
+            // The server handles SIGINT and SIGTERM and stops the deployment
+            shutdown_signal_handlers: false,
+            ..ProductionConfig::from(&production)
+        })
+        .with_tracker(app_state.deployment.clone())
+        .with_startup_steps(
+            StartupValidator::service_steps(&app_state.container),
+            app_state.health_service.startup_report(),
+        )
+        .with_health_check(Box::new(ServiceHealthCheck::new(
+            app_state.health_service.clone(),
+        )))
+        .with_request_metrics(app_state.request_metrics.clone());
+        deployment.initialize().await?;
+        tokio::spawn(deployment.run());
+
+        app_state
+            .container
+            .usage()
+            .spawn_flusher(Duration::from_secs(USAGE_FLUSH_INTERVAL_SECS));
+
+        app_state
+            .job_service
+            .spawn_checkpointer(Duration::from_secs(JOB_CHECKPOINT_INTERVAL_SECS));
+
+        app_state
+            .container
+            .memory_monitor()
+            .spawn_monitor(Duration::from_secs(
+                app_state
+                    .container
+                    .config()
+                    .memory_pressure
+                    .check_interval_seconds,
+            ));
+
+        let digest_hours = app_state.container.config().digest.interval_hours;
+        if digest_hours > 0 {
+            app_state
+                .digest_service
+                .spawn_scheduler(Duration::from_secs(digest_hours * 3600));
+        }
+
         Ok(Self { config, app_state })
     }
 
@@ -58,21 +124,61 @@ impl HttpServer {
 
         // Build middleware stack
         let middleware_stack = ServiceBuilder::new()
-            .layer(TraceLayer::new_for_http())
+            .layer(TraceLayer::new_for_http().make_span_with(make_request_span))
             .layer(TimeoutLayer::new(Duration::from_secs(
                 self.config.timeout_seconds,
             )))
-            .layer(middleware::from_fn(request_logging_middleware));
+            .layer(middleware::from_fn_with_state(
+                self.app_state.request_metrics.clone(),
+                request_logging_middleware,
+            ));
 
         let mut app = router.layer(middleware_stack);
 
+        // Innermost, so validation sees the bodies exactly as the handlers do
+        let mode = self.app_state.container.config().service.schema_validation;
+        if mode != SchemaValidationMode::Off {
+            match SchemaValidator::new(mode) {
+                Ok(validator) => {
+                    info!("OpenAPI schema validation enabled ({:?})", mode);
+                    app = app.layer(middleware::from_fn_with_state(
+                        Arc::new(validator),
+                        schema_validation_middleware,
+                    ));
+                }
+                Err(e) => warn!("OpenAPI schema validation disabled: {}", e),
+            }
+        }
+
+        // Admit searches to the interactive lane once tenant and rate limit pass
+        app = app.layer(middleware::from_fn_with_state(
+            self.app_state.container.lanes(),
+            lane_middleware,
+        ));
+
+        // Name the principal searches are filtered for
+        app = app.layer(middleware::from_fn_with_state(
+            self.app_state.container.config(),
+            principal_middleware,
+        ));
+
+        // Attribute usage to the tenant named by the request
+        app = app.layer(middleware::from_fn(tenant_middleware));
+
+        // Reject requests over the rate limit before validation or handlers run
+        app = app.layer(middleware::from_fn_with_state(
+            self.app_state.container.rate_limiter(),
+            rate_limit_middleware,
+        ));
+
         // Add CORS if enabled
         if self.config.enable_cors {
             let cors = self.build_cors_layer();
             app = app.layer(cors);
         }
 
-        app
+        // Outermost, so every response (including CORS and timeout) carries the ID
+        app.layer(middleware::from_fn(request_id_middleware))
     }
 
     /// Start the HTTP server
@@ -89,18 +195,37 @@ impl HttpServer {
             self.config.port,
         ));
 
-        let app = self.build_router();
-
         info!("Starting HTTP server on {}", addr);
 
         // Create the server
         let listener = tokio::net::TcpListener::bind(addr).await?;
 
         // Start server with graceful shutdown
+        self.serve(listener, shutdown_signal()).await
+    }
+
+    /// Serve on an already bound listener until `shutdown` resolves
+    ///
+    /// Lets callers bind an ephemeral port themselves and stop the server
+    /// without a process signal, as in-process test fixtures do.
+    pub async fn serve(
+        &self,
+        listener: tokio::net::TcpListener,
+        shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let app = self.build_router();
+
+        let deployment = self.app_state.deployment.clone();
         axum::serve(listener, app)
-            .with_graceful_shutdown(shutdown_signal())
+            .with_graceful_shutdown(async move {
+                shutdown.await;
+                deployment.request_shutdown();
+            })
             .await?;
 
+        if let Err(e) = self.app_state.container.usage().save() {
+            warn!("Failed to persist usage: {}", e);
+        }
         info!("HTTP server stopped");
         Ok(())
     }
@@ -118,7 +243,13 @@ impl HttpServer {
             .allow_headers([
                 axum::http::header::CONTENT_TYPE,
                 axum::http::header::AUTHORIZATION,
-            ]);
+                HeaderName::from_static(REQUEST_ID_HEADER),
+                HeaderName::from_static(TENANT_HEADER),
+                HeaderName::from_static(PRINCIPAL_USER_HEADER),
+                HeaderName::from_static(PRINCIPAL_GROUPS_HEADER),
+                HeaderName::from_static(PRINCIPAL_TOKEN_HEADER),
+            ])
+            .expose_headers([HeaderName::from_static(REQUEST_ID_HEADER)]);
 
         // Configure origins
         if self.config.cors_origins.is_empty() {
@@ -143,8 +274,12 @@ impl HttpServer {
     }
 }
 
-/// Request logging middleware
-async fn request_logging_middleware(request: Request, next: Next) -> Response {
+/// Request logging middleware, which also feeds the production monitor
+async fn request_logging_middleware(
+    State(metrics): State<RequestMetrics>,
+    request: Request,
+    next: Next,
+) -> Response {
     let method = request.method().clone();
     let path = request.uri().path().to_string();
     let start = std::time::Instant::now();
@@ -154,6 +289,7 @@ async fn request_logging_middleware(request: Request, next: Next) -> Response {
 
     let duration = start.elapsed();
     let status = response.status();
+    metrics.record(status.as_u16(), duration);
 
     info!(
         method = %method,
@@ -0,0 +1,103 @@
+use axum::{extract::Request, middleware::Next, response::IntoResponse, response::Response};
+/// Tenant attribution
+///
+/// Hosted deployments serve several tenants from one indexer and name the
+/// tenant of a request in the `X-Tenant-ID` header. The tenant is available
+/// while the request is handled through [`current_tenant`], so usage can be
+/// metered per tenant; requests without the header count towards
+/// [`DEFAULT_TENANT`]. Caches key their entries by it too, through
+/// [`RequestTenants`].
+use zero_latency_core::ZeroLatencyError;
+use zero_latency_search::cache::TenantResolver;
+
+use super::handlers::AppError;
+
+/// Header naming the tenant of a request
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// Tenant of requests that don't name one, and of work outside requests
+pub const DEFAULT_TENANT: &str = zero_latency_search::cache::DEFAULT_TENANT;
+
+/// Longest tenant ID accepted
+const MAX_TENANT_LEN: usize = 64;
+
+tokio::task_local! {
+    static CURRENT_TENANT: String;
+}
+
+/// Tenant of the HTTP request currently being handled
+pub fn current_tenant() -> String {
+    CURRENT_TENANT
+        .try_with(|tenant| tenant.clone())
+        .unwrap_or_else(|_| DEFAULT_TENANT.to_string())
+}
+
+/// Run `future` on behalf of `tenant`, e.g. work spawned off a request
+pub async fn with_tenant<F: std::future::Future>(tenant: String, future: F) -> F::Output {
+    CURRENT_TENANT.scope(tenant, future).await
+}
+
+/// Resolves cache namespaces to the tenant of the current request
+pub struct RequestTenants;
+
+impl TenantResolver for RequestTenants {
+    fn current_tenant(&self) -> String {
+        current_tenant()
+    }
+}
+
+// Letters, digits, `.`, `_` and `-` only, so IDs are safe in CSV exports
+fn is_valid(tenant: &str) -> bool {
+    !tenant.is_empty()
+        && tenant.len() <= MAX_TENANT_LEN
+        && tenant
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-'))
+}
+
+/// Attribute the request to the tenant in `X-Tenant-ID`
+///
+/// A malformed tenant ID is rejected rather than billed to the default
+/// tenant.
+pub async fn tenant_middleware(request: Request, next: Next) -> Response {
+    let tenant = match request.headers().get(TENANT_HEADER) {
+        None => DEFAULT_TENANT.to_string(),
+        Some(value) => match value.to_str() {
+            Ok(tenant) if is_valid(tenant) => tenant.to_string(),
+            _ => {
+                return AppError::from(ZeroLatencyError::validation(
+                    TENANT_HEADER,
+                    format!(
+                        "must be 1 to {} letters, digits, '.', '_' or '-'",
+                        MAX_TENANT_LEN
+                    ),
+                ))
+                .into_response()
+            }
+        },
+    };
+    with_tenant(tenant, next.run(request)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validates_tenant_ids() {
+        assert!(is_valid("acme"));
+        assert!(is_valid("550e8400-e29b-41d4-a716-446655440000"));
+        assert!(is_valid("team_a.prod"));
+        assert!(!is_valid(""));
+        assert!(!is_valid("a,b"));
+        assert!(!is_valid("with space"));
+        assert!(!is_valid(&"x".repeat(MAX_TENANT_LEN + 1)));
+    }
+
+    #[tokio::test]
+    async fn test_current_tenant() {
+        assert_eq!(current_tenant(), DEFAULT_TENANT);
+        let tenant = with_tenant("acme".to_string(), async { current_tenant() }).await;
+        assert_eq!(tenant, "acme");
+    }
+}
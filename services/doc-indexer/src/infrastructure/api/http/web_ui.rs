@@ -0,0 +1,96 @@
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::get,
+    Router,
+};
+/// Bundled web UI served at `/ui`
+///
+/// A small search page compiled into the binary with the `web-ui` feature,
+/// so a deployment gets a usable frontend without building one. The page
+/// only calls the public REST API: search with highlighted snippets and
+/// facets over the results, collection stats and index job monitoring.
+/// Sources live in the crate's `ui/` directory.
+use include_dir::{include_dir, Dir};
+use zero_latency_api::endpoints::endpoints;
+
+static ASSETS: Dir<'static> = include_dir!("$CARGO_MANIFEST_DIR/ui");
+
+const INDEX: &str = "index.html";
+
+/// Routes serving the UI; merged into the main router
+pub fn create_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let root = format!("{}/", endpoints::WEB_UI);
+    Router::new()
+        // Relative asset URLs in the page need the trailing slash
+        .route(
+            endpoints::WEB_UI,
+            get(|| async { Redirect::permanent(&format!("{}/", endpoints::WEB_UI)) }),
+        )
+        .route(&root, get(|| async { serve(INDEX) }))
+        .route(&format!("{}*path", root), get(serve_path))
+}
+
+async fn serve_path(Path(path): Path<String>) -> Response {
+    serve(&path)
+}
+
+fn serve(path: &str) -> Response {
+    match asset(path) {
+        Some((content_type, contents)) => (
+            [
+                (header::CONTENT_TYPE, content_type),
+                // Assets aren't fingerprinted, so revalidate after upgrades
+                (header::CACHE_CONTROL, "no-cache"),
+            ],
+            contents,
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Content type and contents of a bundled file
+fn asset(path: &str) -> Option<(&'static str, &'static [u8])> {
+    let path = if path.is_empty() { INDEX } else { path };
+    let file = ASSETS.get_file(path)?;
+    Some((content_type(path), file.contents()))
+}
+
+fn content_type(path: &str) -> &'static str {
+    match path.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_assets() {
+        let (content_type, index) = asset("").unwrap();
+        assert_eq!(content_type, "text/html; charset=utf-8");
+        let index = std::str::from_utf8(index).unwrap();
+
+        // Everything the page references is bundled
+        for referenced in ["style.css", "app.js"] {
+            assert!(index.contains(referenced));
+            assert!(asset(referenced).is_some(), "{} is not bundled", referenced);
+        }
+        assert_eq!(asset("app.js").unwrap().0, "text/javascript; charset=utf-8");
+        assert!(asset("missing.js").is_none());
+        assert!(asset("../Cargo.toml").is_none());
+    }
+}
@@ -333,7 +333,8 @@ pub async fn handle_search_documents(
                                 .search_documents_in_collection(
                                     &params.query, 
                                     collection_name, 
-                                    params.limit.unwrap_or(10)
+                                    params.limit.unwrap_or(10),
+                                    params.context_window,
                                 )
                                 .await
                         } else {
@@ -21,6 +21,24 @@ pub struct JsonRpcRequest {
     pub id: Option<Value>,
 }
 
+impl JsonRpcRequest {
+    /// Parse a single request from raw text
+    ///
+    /// Input that is not valid JSON yields a parse error; valid JSON that is
+    /// not a JSON-RPC 2.0 request object yields an invalid request error.
+    pub fn parse(input: &str) -> Result<Self, JsonRpcError> {
+        let value: Value = serde_json::from_str(input).map_err(|_| JsonRpcError::parse_error())?;
+        let request: Self =
+            serde_json::from_value(value).map_err(|_| JsonRpcError::invalid_request())?;
+
+        if request.jsonrpc != "2.0" {
+            return Err(JsonRpcError::invalid_request());
+        }
+
+        Ok(request)
+    }
+}
+
 /// JSON-RPC 2.0 Response structure
 #[derive(Debug, Serialize)]
 pub struct JsonRpcResponse {
@@ -143,3 +161,115 @@ impl JsonRpcError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const SEED_REQUESTS: &str = include_str!("../../../../fuzz/corpus/jsonrpc_request/seeds.jsonl");
+
+    #[test]
+    fn test_parse_seed_requests() {
+        for line in SEED_REQUESTS.lines().filter(|line| !line.trim().is_empty()) {
+            let request = JsonRpcRequest::parse(line).unwrap();
+            assert!(!request.method.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_parse_error_codes() {
+        let err = JsonRpcRequest::parse("{\"jsonrpc\": \"2.0\", ").unwrap_err();
+        assert_eq!(err.code, error_codes::PARSE_ERROR);
+
+        let err = JsonRpcRequest::parse("[1, 2, 3]").unwrap_err();
+        assert_eq!(err.code, error_codes::INVALID_REQUEST);
+
+        let err =
+            JsonRpcRequest::parse(r#"{"jsonrpc": "1.0", "method": "x", "id": 1}"#).unwrap_err();
+        assert_eq!(err.code, error_codes::INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_index_params_accept_numeric_metadata() {
+        let params: types::IndexDocumentParams = serde_json::from_value(serde_json::json!({
+            "id": "doc-1",
+            "content": "text",
+            "metadata": { "version": 2, "size": 1.5, "draft": false, "team": "search" },
+        }))
+        .unwrap();
+        let metadata = params.metadata.unwrap();
+        assert_eq!(metadata["version"], "2");
+        assert_eq!(metadata["size"], "1.5");
+        assert_eq!(metadata["draft"], "false");
+        assert_eq!(metadata["team"], "search");
+
+        let nested = serde_json::json!({
+            "id": "doc-1",
+            "content": "text",
+            "metadata": { "owners": ["a", "b"] },
+        });
+        assert!(serde_json::from_value::<types::IndexDocumentParams>(nested).is_err());
+    }
+
+    fn json_value() -> impl Strategy<Value = Value> {
+        let leaf = prop_oneof![
+            Just(Value::Null),
+            any::<bool>().prop_map(Value::from),
+            any::<i64>().prop_map(Value::from),
+            ".*".prop_map(Value::from),
+        ];
+        leaf.prop_recursive(4, 32, 6, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..6).prop_map(Value::from),
+                prop::collection::hash_map(".*", inner, 0..6)
+                    .prop_map(|map| Value::Object(map.into_iter().collect())),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in ".*") {
+            let _ = JsonRpcRequest::parse(&input);
+        }
+
+        #[test]
+        fn parse_never_panics_on_truncated_seeds(index in 0usize..64, cut in 0usize..512) {
+            let lines: Vec<&str> = SEED_REQUESTS.lines().collect();
+            let line = lines[index % lines.len()];
+            let cut = line.char_indices().map(|(i, _)| i).nth(cut).unwrap_or(line.len());
+            let _ = JsonRpcRequest::parse(&line[..cut]);
+        }
+
+        #[test]
+        fn parse_accepts_any_params_and_id(
+            method in "[a-z_.]{1,32}",
+            params in prop::option::of(json_value()),
+            id in prop::option::of(json_value()),
+        ) {
+            let mut object = serde_json::Map::new();
+            object.insert("jsonrpc".to_string(), Value::from("2.0"));
+            object.insert("method".to_string(), Value::from(method.clone()));
+            if let Some(params) = &params {
+                object.insert("params".to_string(), params.clone());
+            }
+            if let Some(id) = &id {
+                object.insert("id".to_string(), id.clone());
+            }
+
+            let request = JsonRpcRequest::parse(&Value::Object(object).to_string()).unwrap();
+            prop_assert_eq!(request.method, method);
+            // A JSON null member deserializes to None like a missing one
+            prop_assert_eq!(request.params, params.filter(|p| !p.is_null()));
+            prop_assert_eq!(request.id, id.filter(|i| !i.is_null()));
+        }
+
+        #[test]
+        fn parse_rejects_arbitrary_json_shapes(value in json_value()) {
+            if let Err(err) = JsonRpcRequest::parse(&value.to_string()) {
+                prop_assert_eq!(err.code, error_codes::INVALID_REQUEST);
+            }
+        }
+    }
+}
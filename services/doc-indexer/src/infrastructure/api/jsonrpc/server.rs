@@ -124,8 +124,16 @@ async fn handle_batch_jsonrpc_request(
     Json(responses)
 }
 
-/// Create a combined router that includes REST, JSON-RPC, and streaming endpoints
+/// Create a combined router that includes REST, JSON-RPC, and streaming endpoints,
+/// plus the bundled web UI, the Slack and Discord integration and the
+/// profiling endpoints when built with the `web-ui`, `chat` and `profiling`
+/// features
 pub fn create_dual_protocol_router(app_state: AppState) -> Router {
+    #[cfg(feature = "chat")]
+    let chat_router = crate::infrastructure::api::http::chat::create_router(&app_state);
+    #[cfg(feature = "profiling")]
+    let profiling_router =
+        crate::infrastructure::api::http::profiling::create_router(app_state.clone());
     let rest_router = crate::infrastructure::api::http::handlers::create_router(app_state.clone());
     let jsonrpc_server = JsonRpcServer::new(app_state.clone());
     let jsonrpc_router = jsonrpc_server.create_router();
@@ -133,7 +141,14 @@ pub fn create_dual_protocol_router(app_state: AppState) -> Router {
         crate::infrastructure::protocols::streaming::create_streaming_router().with_state(app_state);
 
     // Combine all routers
-    rest_router.merge(jsonrpc_router).merge(streaming_router)
+    let router = rest_router.merge(jsonrpc_router).merge(streaming_router);
+    #[cfg(feature = "web-ui")]
+    let router = router.merge(crate::infrastructure::api::http::web_ui::create_router());
+    #[cfg(feature = "chat")]
+    let router = router.merge(chat_router);
+    #[cfg(feature = "profiling")]
+    let router = router.merge(profiling_router);
+    router
 }
 
 #[cfg(test)]
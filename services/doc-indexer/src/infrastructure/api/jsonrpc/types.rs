@@ -7,12 +7,42 @@ use std::collections::HashMap;
 
 // Document Management Types
 
+/// Accept numbers and booleans as metadata values alongside strings
+///
+/// Metadata is stored as strings; numbers keep their JSON spelling so range
+/// filters can read them back.
+fn deserialize_metadata<'de, D>(
+    deserializer: D,
+) -> Result<Option<HashMap<String, String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let Some(values) = Option::<HashMap<String, serde_json::Value>>::deserialize(deserializer)?
+    else {
+        return Ok(None);
+    };
+    values
+        .into_iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(text) => Ok((key, text)),
+            serde_json::Value::Number(number) => Ok((key, number.to_string())),
+            serde_json::Value::Bool(flag) => Ok((key, flag.to_string())),
+            other => Err(serde::de::Error::custom(format!(
+                "metadata value for '{}' must be a string, number or boolean, got {}",
+                key, other
+            ))),
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
 #[derive(Debug, Deserialize)]
 pub struct IndexDocumentParams {
     pub id: String,
     pub title: Option<String>,
     pub content: String,
     pub path: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_metadata")]
     pub metadata: Option<HashMap<String, String>>,
 }
 
@@ -43,6 +73,7 @@ pub struct UpdateDocumentParams {
     pub title: Option<String>,
     pub content: Option<String>,
     pub path: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_metadata")]
     pub metadata: Option<HashMap<String, String>>,
 }
 
@@ -73,6 +104,9 @@ pub struct SearchDocumentsParams {
     pub limit: Option<usize>,
     pub filters: Option<HashMap<String, String>>,
     pub include_content: Option<bool>,
+    /// Neighboring chunks stitched on each side of a hit; applies to
+    /// searches filtered to a collection
+    pub context_window: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
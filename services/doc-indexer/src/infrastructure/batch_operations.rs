@@ -1,5 +1,7 @@
 use crate::application::interfaces::VectorStorage;
+use crate::config::WebhookConfig;
 use crate::infrastructure::enhanced_search::EnhancedSearchService;
+use crate::infrastructure::webhooks::{WebhookDispatcher, JSON_CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 /// Batch Operations System
 ///
@@ -128,6 +130,10 @@ pub struct BatchOperationRequest {
     /// Callback URL for completion notification
     pub callback_url: Option<String>,
 
+    /// Secret the completion notification is signed with
+    #[serde(default)]
+    pub callback_secret: Option<String>,
+
     /// Custom metadata for the operation
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
@@ -427,6 +433,9 @@ pub struct BatchProcessor {
     /// Search services
     search_services: Arc<RwLock<HashMap<String, Arc<EnhancedSearchService>>>>,
 
+    /// Delivers completion notifications to callback URLs
+    callbacks: Option<Arc<WebhookDispatcher>>,
+
     /// Configuration
     config: BatchProcessorConfig,
 }
@@ -491,6 +500,10 @@ impl BatchProcessor {
             semaphore: Arc::new(Semaphore::new(config.max_concurrent_operations)),
             vector_stores: Arc::new(RwLock::new(HashMap::new())),
             search_services: Arc::new(RwLock::new(HashMap::new())),
+            callbacks: WebhookDispatcher::new(WebhookConfig::default())
+                .map_err(|e| eprintln!("Batch completion callbacks disabled: {}", e))
+                .ok()
+                .map(Arc::new),
             config,
         }
     }
@@ -566,6 +579,10 @@ impl BatchProcessor {
         let processor = self.clone();
         let request_clone = request.clone();
         let operation_id_for_task = operation_id.clone();
+        let callback = request
+            .callback_url
+            .clone()
+            .map(|url| (url, request.callback_secret.clone()));
         tokio::spawn(async move {
             if let Err(error) = processor
                 .process_operation(operation_id_for_task.clone(), request_clone)
@@ -595,11 +612,60 @@ impl BatchProcessor {
                     });
                 }
             }
+
+            if let Some((callback_url, secret)) = callback {
+                processor
+                    .notify_callback(&operation_id_for_task, &callback_url, secret.as_deref())
+                    .await;
+            }
         });
         println!("✅ Submitted batch operation: {}", operation_id);
         Ok(operation_id)
     }
 
+    /// Post the final result of an operation to its callback URL
+    async fn notify_callback(&self, operation_id: &str, callback_url: &str, secret: Option<&str>) {
+        let (Some(callbacks), Some(result)) = (
+            &self.callbacks,
+            self.get_operation_status(operation_id).await,
+        ) else {
+            return;
+        };
+
+        let event = match result.status {
+            BatchOperationStatus::Failed => "batch.failed",
+            BatchOperationStatus::Cancelled => "batch.cancelled",
+            _ => "batch.completed",
+        };
+        let delivery_id = uuid::Uuid::new_v4().to_string();
+        let body = serde_json::json!({
+            "id": delivery_id,
+            "event": event,
+            "created_at": chrono::Utc::now(),
+            "data": result,
+        })
+        .to_string();
+
+        let outcome = callbacks
+            .deliver(
+                callback_url,
+                secret,
+                event,
+                &delivery_id,
+                JSON_CONTENT_TYPE,
+                body.as_bytes(),
+            )
+            .await;
+        if !outcome.delivered {
+            eprintln!(
+                "Callback for batch operation {} failed after {} attempts: {}",
+                operation_id,
+                outcome.attempts,
+                outcome.error.unwrap_or_default()
+            );
+        }
+    }
+
     /// Get the status of a batch operation
     pub async fn get_operation_status(&self, operation_id: &str) -> Option<BatchOperationResult> {
         self.operations.read().await.get(operation_id).cloned()
@@ -1094,6 +1160,7 @@ impl Clone for BatchProcessor {
             semaphore: self.semaphore.clone(),
             vector_stores: self.vector_stores.clone(),
             search_services: self.search_services.clone(),
+            callbacks: self.callbacks.clone(),
             config: self.config.clone(),
         }
     }
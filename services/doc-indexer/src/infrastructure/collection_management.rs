@@ -311,7 +311,7 @@ impl CollectionManager {
     /// Create a new collection
     pub async fn create_collection(
         &self,
-        request: CreateCollectionRequest,
+        mut request: CreateCollectionRequest,
     ) -> Result<CollectionConfig, Box<dyn std::error::Error + Send + Sync>> {
         let mut collections = self.collections.write().await;
 
@@ -324,6 +324,10 @@ impl CollectionManager {
             .into());
         }
 
+        // Store the metric under its canonical name
+        let metric: zero_latency_vector::SimilarityMetric = request.vector_config.metric.parse()?;
+        request.vector_config.metric = metric.name().to_string();
+
         // Generate unique ID
         let collection_id = self.generate_collection_id(&request.name);
 
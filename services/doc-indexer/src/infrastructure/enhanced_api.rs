@@ -309,6 +309,10 @@ pub struct ApiBatchOperationRequest {
     /// Callback URL for completion notification
     pub callback_url: Option<String>,
 
+    /// Secret the completion notification is signed with
+    #[serde(default)]
+    pub callback_secret: Option<String>,
+
     /// Maximum processing time (seconds)
     pub timeout_seconds: Option<u64>,
 
@@ -713,6 +717,7 @@ impl EnhancedApiService {
             },
             priority: request.priority,
             callback_url: request.callback_url,
+            callback_secret: request.callback_secret,
             metadata: request.metadata,
         };
 
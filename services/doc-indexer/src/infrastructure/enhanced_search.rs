@@ -492,9 +492,10 @@ impl EnhancedSearchService {
     // Helper methods
 
     fn generate_cache_key(&self, request: &EnhancedSearchRequest) -> String {
-        // Generate a cache key based on request parameters
+        // Generate a cache key based on the tenant and request parameters
         format!(
-            "{}:{}:{:?}:{:?}",
+            "{}:{}:{}:{:?}:{:?}",
+            crate::infrastructure::api::http::tenant::current_tenant(),
             request.query,
             request.limit.unwrap_or(10),
             request.min_confidence,
@@ -2,6 +2,7 @@
 ///
 /// Comprehensive metrics collection and analysis for load testing scenarios,
 /// enabling detailed performance validation and optimization regression detection.
+use crate::infrastructure::memory::VectorPoolStats as PoolSnapshot;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use sysinfo::System;
@@ -185,6 +186,7 @@ pub struct MetricsCollector {
     error_counts: HashMap<String, u64>,
     memory_samples: Vec<(Duration, f64)>,
     scenario_data: HashMap<String, ScenarioData>,
+    vector_pool_samples: Vec<PoolSnapshot>,
     system: System,
 }
 
@@ -196,6 +198,7 @@ impl Clone for MetricsCollector {
             error_counts: self.error_counts.clone(),
             memory_samples: self.memory_samples.clone(),
             scenario_data: self.scenario_data.clone(),
+            vector_pool_samples: self.vector_pool_samples.clone(),
             system: System::new_all(),
         }
     }
@@ -219,6 +222,7 @@ impl MetricsCollector {
             error_counts: HashMap::new(),
             memory_samples: Vec::new(),
             scenario_data: HashMap::new(),
+            vector_pool_samples: Vec::new(),
             system: System::new_all(),
         }
     }
@@ -259,6 +263,14 @@ impl MetricsCollector {
         }
     }
 
+    /// Record a snapshot of the shared vector pool counters
+    ///
+    /// Pool counters are cumulative, so the reported effectiveness is the
+    /// difference between the first and last snapshot of the test.
+    pub fn record_vector_pool(&mut self, stats: PoolSnapshot) {
+        self.vector_pool_samples.push(stats);
+    }
+
     /// Generate final metrics report
     pub fn finalize(self, test_name: String) -> LoadTestMetrics {
         let duration = self.start_time.elapsed();
@@ -372,13 +384,41 @@ impl MetricsCollector {
 
         MemoryMetrics {
             process_memory,
-            vector_pool: None,       // TODO: Integrate with vector pool metrics
+            vector_pool: self.calculate_vector_pool_stats(),
             string_intern: None,     // TODO: Integrate with string intern metrics
             cache_performance: None, // TODO: Integrate with cache metrics
             leak_detection,
         }
     }
 
+    fn calculate_vector_pool_stats(&self) -> Option<VectorPoolStats> {
+        let first = self.vector_pool_samples.first()?;
+        let last = self.vector_pool_samples.last()?;
+
+        let pool_hits = last.hits.saturating_sub(first.hits);
+        let pool_misses = last.misses.saturating_sub(first.misses);
+        let bytes_saved = last.bytes_saved.saturating_sub(first.bytes_saved);
+        let avg_pool_size = self
+            .vector_pool_samples
+            .iter()
+            .map(|sample| sample.pool_size as f64)
+            .sum::<f64>()
+            / self.vector_pool_samples.len() as f64;
+
+        Some(VectorPoolStats {
+            pool_hits,
+            pool_misses,
+            hit_rate: if pool_hits + pool_misses > 0 {
+                pool_hits as f64 / (pool_hits + pool_misses) as f64
+            } else {
+                0.0
+            },
+            avg_pool_size,
+            max_pool_size: last.max_pool_size,
+            memory_saved_mb: bytes_saved as f64 / 1024.0 / 1024.0,
+        })
+    }
+
     fn calculate_process_memory_stats(&self) -> ProcessMemoryStats {
         if self.memory_samples.is_empty() {
             return ProcessMemoryStats {
@@ -9,6 +9,7 @@ use crate::infrastructure::load_testing::{
     scenario::{LoadTestScenario, ScenarioRequest, ScenarioResponse},
     LoadTestConfig,
 };
+use crate::infrastructure::memory::VectorPool;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -31,6 +32,7 @@ pub struct LoadTestRunner {
     scenarios: Vec<Box<dyn LoadTestScenario>>,
     embedding_service: Arc<dyn EmbeddingService>,
     search_service: Arc<dyn SearchService>,
+    vector_pool: Option<Arc<VectorPool>>,
 }
 
 impl LoadTestRunner {
@@ -45,9 +47,16 @@ impl LoadTestRunner {
             scenarios,
             embedding_service,
             search_service,
+            vector_pool: None,
         }
     }
 
+    /// Track the effectiveness of a shared vector pool during the test
+    pub fn with_vector_pool(mut self, pool: Arc<VectorPool>) -> Self {
+        self.vector_pool = Some(pool);
+        self
+    }
+
     /// Execute the complete load test suite
     pub async fn run(
         &self,
@@ -63,6 +72,10 @@ impl LoadTestRunner {
         let semaphore = Arc::new(Semaphore::new(self.config.concurrency));
         let start_time = Instant::now();
 
+        if let Some(pool) = &self.vector_pool {
+            metrics_collector.record_vector_pool(pool.stats());
+        }
+
         // Start memory monitoring task
         let memory_monitor = self
             .start_memory_monitoring(metrics_collector.clone())
@@ -76,6 +89,10 @@ impl LoadTestRunner {
         // Stop memory monitoring
         memory_monitor.abort();
 
+        if let Some(pool) = &self.vector_pool {
+            metrics_collector.record_vector_pool(pool.stats());
+        }
+
         // Finalize metrics
         let metrics = metrics_collector.finalize(test_name.clone());
 
@@ -209,7 +226,7 @@ impl LoadTestRunner {
                     Ok(ScenarioResponse::Embedding(embedding))
                 }
                 ScenarioRequest::Search(search_req) => {
-                    let results = search_service.search(search_req).await?;
+                    let results = search_service.search(*search_req).await?;
                     Ok(ScenarioResponse::Search(serde_json::to_value(results)?))
                 }
                 ScenarioRequest::Batch(requests) => {
@@ -322,6 +339,22 @@ impl LoadTestRunner {
             ));
         }
 
+        // Validate vector pool reuse
+        if let Some(pool) = &metrics.memory.vector_pool {
+            if pool.hit_rate >= 0.5 {
+                recommendations.push(format!(
+                    "✅ Vector pool hit rate {:.1}% ({:.2} MB saved)",
+                    pool.hit_rate * 100.0,
+                    pool.memory_saved_mb
+                ));
+            } else {
+                recommendations.push(format!(
+                    "⚠️ Vector pool hit rate {:.1}% - review pool size and dimension tolerance",
+                    pool.hit_rate * 100.0
+                ));
+            }
+        }
+
         // Validate memory efficiency for scenarios
         for (scenario_name, scenario_metrics) in &metrics.scenarios {
             if scenario_metrics.memory_efficiency > 0.8 {
@@ -44,7 +44,7 @@ pub trait LoadTestScenario: Send + Sync {
 #[derive(Debug, Clone)]
 pub enum ScenarioRequest {
     Embedding(EmbeddingInput),
-    Search(SearchRequest),
+    Search(Box<SearchRequest>),
     Batch(Vec<ScenarioRequest>),
     Mixed(HashMap<String, Value>),
 }
@@ -195,13 +195,13 @@ impl LoadTestScenario for SearchIntensiveScenario {
         let mut rng = rand::thread_rng();
         let query = &self.query_pool[rng.gen_range(0..self.query_pool.len())];
 
-        ScenarioRequest::Search(SearchRequest {
+        ScenarioRequest::Search(Box::new(SearchRequest {
             query: SearchQuery::new(query.clone()),
             limit: rng.gen_range(1..=10),
             offset: 0,
             filters: SearchFilters::default(),
             options: SearchOptions::default(),
-        })
+        }))
     }
 
     fn validate_response(&self, response: &ScenarioResponse) -> Result<(), String> {
@@ -191,6 +191,65 @@ where
         self.len() == 0
     }
 
+    /// Estimated bytes held by the entries
+    pub fn memory_usage(&self) -> usize {
+        self.stats.read().unwrap().total_memory_bytes
+    }
+
+    /// Evict the `fraction` of the entries most likely to be evicted anyway,
+    /// when memory pressure handling is enabled; returns how many were
+    /// evicted
+    pub fn shrink(&self, fraction: f64) -> usize {
+        if !self.config.memory_pressure_enabled {
+            return 0;
+        }
+        let mut entries = self.entries.write().unwrap();
+        let mut stats = self.stats.write().unwrap();
+
+        let count = (entries.len() as f64 * fraction.clamp(0.0, 1.0)).ceil() as usize;
+        let mut scored_entries: Vec<(K, f64)> = entries
+            .iter()
+            .map(|(k, entry)| (k.clone(), entry.score()))
+            .collect();
+        scored_entries.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+        for (key, _) in scored_entries.into_iter().take(count) {
+            if let Some(entry) = entries.remove(&key) {
+                stats.total_memory_bytes =
+                    stats.total_memory_bytes.saturating_sub(entry.size_bytes);
+            }
+        }
+        stats.evictions += count as u64;
+        count
+    }
+
+    /// Remove every entry whose key matches `predicate`; returns how many
+    pub fn remove_where(&self, predicate: impl Fn(&K) -> bool) -> usize {
+        let mut entries = self.entries.write().unwrap();
+        let mut stats = self.stats.write().unwrap();
+
+        let keys: Vec<K> = entries.keys().filter(|key| predicate(key)).cloned().collect();
+        for key in &keys {
+            if let Some(entry) = entries.remove(key) {
+                stats.total_memory_bytes =
+                    stats.total_memory_bytes.saturating_sub(entry.size_bytes);
+            }
+        }
+        keys.len()
+    }
+
+    /// Entries and bytes held under keys matching `predicate`
+    pub fn usage_where(&self, predicate: impl Fn(&K) -> bool) -> (usize, usize) {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| predicate(key))
+            .fold((0, 0), |(count, bytes), (_, entry)| {
+                (count + 1, bytes + entry.size_bytes)
+            })
+    }
+
     /// Clean up expired entries
     pub fn cleanup_expired(&self) {
         let mut entries = self.entries.write().unwrap();
@@ -354,6 +413,31 @@ mod tests {
         assert_eq!(cache.get(&"key3".to_string()), Some("value3".to_string()));
     }
 
+    #[test]
+    fn test_shrink_evicts_a_share_of_the_entries() {
+        let config = CacheConfig {
+            max_entries: 100,
+            ..Default::default()
+        };
+        let cache = MemoryEfficientCache::new(config.clone());
+        for i in 0..10 {
+            cache.insert(format!("key{}", i), i);
+        }
+
+        assert_eq!(cache.shrink(0.25), 3);
+        assert_eq!(cache.len(), 7);
+        assert_eq!(cache.stats().evictions, 3);
+
+        // Caches that opt out of memory pressure keep everything
+        let pinned = MemoryEfficientCache::new(CacheConfig {
+            memory_pressure_enabled: false,
+            ..config
+        });
+        pinned.insert("key".to_string(), 1);
+        assert_eq!(pinned.shrink(1.0), 0);
+        assert_eq!(pinned.len(), 1);
+    }
+
     #[test]
     fn test_vector_cache() {
         let config = CacheConfig::default();
@@ -8,6 +8,6 @@ pub mod intern;
 /// performance.
 pub mod pool;
 
-pub use cache::{CacheConfig, MemoryEfficientCache};
+pub use cache::{CacheConfig, CacheStats, MemoryEfficientCache};
 pub use intern::StringInterner;
-pub use pool::{PooledVector, VectorPool, VectorPoolConfig};
+pub use pool::{PooledVector, VectorPool, VectorPoolConfig, VectorPoolStats};
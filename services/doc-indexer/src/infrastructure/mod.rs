@@ -13,8 +13,15 @@ pub mod protocols;
 pub mod concurrent_search;
 pub mod load_testing;
 pub mod memory;
+pub mod plugins;
+pub mod scripting;
 pub mod search_enhancement;
 pub mod stdio;
+pub mod webhooks;
+
+// Search summaries from a chat completions API
+#[cfg(feature = "cloud")]
+pub mod openai_summarizer;
 
 // Phase 4D: Enhanced API Features
 pub mod batch_operations;
@@ -25,6 +32,7 @@ pub mod enhanced_search;
 // Re-export commonly used types
 pub use api::http::{HttpServer, ServerConfig};
 pub use persistence::vector::InMemoryVectorStore;
+pub use persistence::embeddings::{MockEmbeddingAdapter, MockEmbeddingConfig};
 
 // Phase 4D: Enhanced API Features
 pub use batch_operations::{
@@ -42,6 +50,9 @@ pub use persistence::vector::{QdrantAdapter, QdrantConfig};
 #[cfg(feature = "cloud")]
 pub use persistence::embeddings::{OpenAIAdapter, OpenAIConfig};
 
+#[cfg(feature = "cloud")]
+pub use openai_summarizer::OpenAISummarizer;
+
 // Embedded-dependent exports
 #[cfg(feature = "embedded")]
 pub use persistence::vector::{EmbeddedConfig, EmbeddedVectorStore};
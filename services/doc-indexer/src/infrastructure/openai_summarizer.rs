@@ -0,0 +1,131 @@
+/// Search summaries from an OpenAI-compatible chat completions API
+///
+/// Works with OpenAI itself and with local servers that speak the same
+/// protocol (Ollama, vLLM, llama.cpp). The model sees the numbered sources
+/// and is asked for cited bullets; its answer is read back with
+/// [`parse_bullets`], so bullets citing nothing or citing a source that was
+/// never sent are dropped rather than shown unattributed.
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+use zero_latency_config::SummarizerConfig;
+use zero_latency_core::{Result, ZeroLatencyError};
+use zero_latency_search::{parse_bullets, SearchResult, Summarizer, SummaryBullet};
+
+/// Characters of each source sent to the model
+const SOURCE_CHARS: usize = 1500;
+
+const SYSTEM_PROMPT: &str = "You summarize documentation search results. \
+Answer with at most {max_bullets} short bullet points, one per line, starting with \"- \". \
+End every bullet with the numbers of the sources that support it in square brackets, \
+for example [1] or [2][3]. Only state what the sources say and write nothing else.";
+
+/// Summarizer backed by a chat completions endpoint
+pub struct OpenAISummarizer {
+    config: SummarizerConfig,
+    client: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletion {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: Option<String>,
+}
+
+impl OpenAISummarizer {
+    pub fn new(config: SummarizerConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .map_err(|e| {
+                ZeroLatencyError::configuration(format!("Failed to create HTTP client: {}", e))
+            })?;
+        Ok(Self { config, client })
+    }
+
+    fn prompt(query: &str, sources: &[SearchResult]) -> String {
+        let mut prompt = format!("Query: {}\n\nSources:\n", query);
+        for (index, source) in sources.iter().enumerate() {
+            let content: String = source.content.chars().take(SOURCE_CHARS).collect();
+            prompt.push_str(&format!(
+                "\n[{}] {} ({})\n{}\n",
+                index + 1,
+                source.title,
+                source.uri,
+                content.trim()
+            ));
+        }
+        prompt
+    }
+}
+
+#[async_trait]
+impl Summarizer for OpenAISummarizer {
+    fn name(&self) -> &str {
+        &self.config.model
+    }
+
+    async fn summarize(
+        &self,
+        query: &str,
+        sources: &[SearchResult],
+        max_bullets: usize,
+    ) -> Result<Vec<SummaryBullet>> {
+        if sources.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let body = json!({
+            "model": self.config.model,
+            "temperature": 0,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": SYSTEM_PROMPT.replace("{max_bullets}", &max_bullets.to_string()),
+                },
+                { "role": "user", "content": Self::prompt(query, sources) },
+            ],
+        });
+        let url = format!(
+            "{}/chat/completions",
+            self.config.base_url.trim_end_matches('/')
+        );
+        let mut request = self.client.post(&url).json(&body);
+        if let Some(api_key) = &self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            ZeroLatencyError::external_service("summarizer", format!("Request failed: {}", e))
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().await.unwrap_or_default();
+            return Err(ZeroLatencyError::external_service(
+                "summarizer",
+                format!("{} returned {}: {}", url, status, detail),
+            ));
+        }
+        let completion: ChatCompletion = response.json().await.map_err(|e| {
+            ZeroLatencyError::external_service("summarizer", format!("Invalid response: {}", e))
+        })?;
+
+        let text = completion
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .unwrap_or_default();
+        Ok(parse_bullets(&text, sources.len(), max_bullets))
+    }
+}
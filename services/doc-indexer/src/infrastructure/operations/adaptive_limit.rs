@@ -0,0 +1,140 @@
+/// Adaptive concurrency limit
+///
+/// A static limit on in-flight searches is either too low for a fast store
+/// or too high for a slow one. The adaptive limit follows the observed
+/// latency instead, AIMD style: after every window of completed requests it
+/// compares the window's P95 latency with the target, and raises the limit
+/// by one while latency is on target or cuts it by a tenth once latency
+/// exceeds it. The limit stays between a configured floor and the lane's
+/// budget. Latency is measured from admission, so time spent queueing for a
+/// slot doesn't feed back into the limit.
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Requests observed before the limit is adjusted
+const WINDOW_SIZE: usize = 50;
+
+/// Factor the limit is multiplied by when latency exceeds the target
+const BACKOFF: f64 = 0.9;
+
+/// AIMD controller of a concurrency limit
+pub struct AdaptiveLimit {
+    target: Duration,
+    min: usize,
+    max: usize,
+    state: Mutex<WindowState>,
+}
+
+#[derive(Default)]
+struct WindowState {
+    samples: Vec<Duration>,
+    last_p95: Option<Duration>,
+}
+
+impl AdaptiveLimit {
+    /// Create a controller keeping P95 latency under `target`, with the
+    /// limit between `min` and `max`
+    pub fn new(target: Duration, min: usize, max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            target,
+            min: min.clamp(1, max),
+            max,
+            state: Mutex::new(WindowState::default()),
+        }
+    }
+
+    /// Target P95 latency
+    pub fn target(&self) -> Duration {
+        self.target
+    }
+
+    /// P95 latency of the last complete window
+    pub fn last_p95(&self) -> Option<Duration> {
+        self.lock().last_p95
+    }
+
+    /// Record the latency of a completed request; returns the new limit
+    /// when this request closes a window
+    pub fn record(&self, latency: Duration, limit: usize) -> Option<usize> {
+        let p95 = {
+            let mut state = self.lock();
+            state.samples.push(latency);
+            if state.samples.len() < WINDOW_SIZE {
+                return None;
+            }
+            let mut samples = std::mem::take(&mut state.samples);
+            samples.sort_unstable();
+            let p95 = samples[(samples.len() * 95).div_ceil(100) - 1];
+            state.last_p95 = Some(p95);
+            p95
+        };
+        Some(self.next_limit(limit, p95))
+    }
+
+    /// Limit following a window with the given P95 latency
+    fn next_limit(&self, limit: usize, p95: Duration) -> usize {
+        if p95 > self.target {
+            let reduced = ((limit as f64) * BACKOFF) as usize;
+            reduced.min(limit.saturating_sub(1)).max(self.min)
+        } else {
+            (limit + 1).min(self.max)
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, WindowState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(limit: &AdaptiveLimit, latency: Duration, current: usize) -> Option<usize> {
+        (0..WINDOW_SIZE)
+            .map(|_| limit.record(latency, current))
+            .last()
+            .flatten()
+    }
+
+    #[test]
+    fn test_limit_adjusts_once_per_window() {
+        let limit = AdaptiveLimit::new(Duration::from_millis(100), 4, 64);
+        assert_eq!(limit.record(Duration::from_millis(10), 32), None);
+        assert_eq!(limit.last_p95(), None);
+
+        // The rest of the window
+        let next = (1..WINDOW_SIZE)
+            .map(|_| limit.record(Duration::from_millis(10), 32))
+            .last()
+            .flatten();
+        assert_eq!(next, Some(33));
+        assert_eq!(limit.last_p95(), Some(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn test_slow_windows_cut_the_limit_multiplicatively() {
+        let limit = AdaptiveLimit::new(Duration::from_millis(100), 4, 64);
+        let slow = Duration::from_millis(250);
+        assert_eq!(window(&limit, slow, 40), Some(36));
+        assert_eq!(window(&limit, slow, 5), Some(4));
+        assert_eq!(window(&limit, slow, 4), Some(4));
+
+        let fast = Duration::from_millis(20);
+        assert_eq!(window(&limit, fast, 64), Some(64));
+    }
+
+    #[test]
+    fn test_p95_ignores_the_slowest_twentieth() {
+        let limit = AdaptiveLimit::new(Duration::from_millis(100), 1, 10);
+        for i in 0..WINDOW_SIZE {
+            // Two outliers in fifty stay above the 95th percentile
+            let latency = if i < 2 { 1000 } else { 50 };
+            limit.record(Duration::from_millis(latency), 5);
+        }
+        assert_eq!(limit.last_p95(), Some(Duration::from_millis(50)));
+    }
+}
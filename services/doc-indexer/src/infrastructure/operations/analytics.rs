@@ -1,15 +1,21 @@
+use crate::infrastructure::api::http::request_id::current_request_id;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use zero_latency_core::Result;
+use crate::infrastructure::operations::usage::{estimate_tokens, UsageCounters, UsageMeter};
 use zero_latency_search::{
-    models::{SearchRequest, SearchResponse},
+    intent::SearchIntent,
+    models::{SearchFilters, SearchRequest, SearchResponse, StageTiming},
     traits::{CategoryTrend, PopularQuery, SearchAnalytics, SearchTrends},
 };
 
+/// Top results of a search that count towards their documents' popularity
+const POPULAR_RESULTS: usize = 3;
+
 /// Production-ready SearchAnalytics implementation with in-memory storage
 /// and comprehensive tracking capabilities for advanced search pipeline
 #[derive(Debug)]
@@ -20,6 +26,12 @@ pub struct ProductionSearchAnalytics {
     search_metrics: Arc<RwLock<SearchMetrics>>,
     /// Performance tracking
     performance_data: Arc<RwLock<Vec<PerformanceRecord>>>,
+    /// Most recent searches that exceeded the slow query threshold
+    slow_queries: Arc<RwLock<VecDeque<SlowQueryRecord>>>,
+    /// Times each document was among a search's top results, by collection and URI
+    document_hits: Arc<RwLock<HashMap<String, HashMap<String, usize>>>>,
+    /// Per-tenant usage, for billing
+    usage: Arc<UsageMeter>,
     /// Configuration
     config: AnalyticsConfig,
 }
@@ -34,6 +46,10 @@ pub struct AnalyticsConfig {
     pub enable_detailed_logging: bool,
     /// Enable performance tracking
     pub enable_performance_tracking: bool,
+    /// Searches taking at least this long are logged as slow (0 disables)
+    pub slow_query_threshold_ms: u64,
+    /// Maximum number of slow queries to keep
+    pub max_slow_queries: usize,
 }
 
 impl Default for AnalyticsConfig {
@@ -43,6 +59,8 @@ impl Default for AnalyticsConfig {
             max_performance_records: 5000,
             enable_detailed_logging: true,
             enable_performance_tracking: true,
+            slow_query_threshold_ms: 500,
+            max_slow_queries: 100,
         }
     }
 }
@@ -71,6 +89,7 @@ struct SearchMetrics {
     query_enhancement_count: usize,
     result_ranking_count: usize,
     collections_searched: HashMap<String, usize>,
+    intents: HashMap<SearchIntent, usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,10 +102,26 @@ pub struct PerformanceRecord {
     pub top_score: Option<f32>,
     pub query_enhancement_applied: bool,
     pub ranking_method: Option<String>,
+    pub intent: Option<SearchIntent>,
     pub success: bool,
     pub error_details: Option<String>,
 }
 
+/// A search that exceeded the slow query threshold, with the time spent in
+/// each pipeline stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowQueryRecord {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub request_id: Option<String>,
+    pub query: String,
+    pub collection: Option<String>,
+    pub filters: SearchFilters,
+    pub limit: usize,
+    pub duration_ms: f64,
+    pub result_count: usize,
+    pub stages: Vec<StageTiming>,
+}
+
 impl ProductionSearchAnalytics {
     pub fn new(config: AnalyticsConfig) -> Self {
         info!(
@@ -105,14 +140,25 @@ impl ProductionSearchAnalytics {
                 query_enhancement_count: 0,
                 result_ranking_count: 0,
                 collections_searched: HashMap::new(),
+                intents: HashMap::new(),
             })),
             performance_data: Arc::new(RwLock::new(Vec::new())),
+            slow_queries: Arc::new(RwLock::new(VecDeque::new())),
+            document_hits: Arc::new(RwLock::new(HashMap::new())),
+            usage: Arc::new(UsageMeter::in_memory()),
             config,
         }
     }
 
-    pub fn with_default_config() -> Self {
-        Self::new(AnalyticsConfig::default())
+    /// Meter searches into `usage` instead of a meter of its own
+    pub fn with_usage(mut self, usage: Arc<UsageMeter>) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Per-tenant usage
+    pub fn usage(&self) -> Arc<UsageMeter> {
+        self.usage.clone()
     }
 
     /// Get comprehensive analytics summary
@@ -170,10 +216,53 @@ impl ProductionSearchAnalytics {
             },
             top_queries,
             collections_searched: search_metrics.collections_searched.clone(),
+            intents: search_metrics.intents.clone(),
             recent_performance: performance_data.iter().rev().take(10).cloned().collect(),
         }
     }
 
+    /// Most recent slow queries, newest first
+    pub async fn get_slow_queries(&self, limit: usize) -> Vec<SlowQueryRecord> {
+        let slow_queries = self.slow_queries.read().await;
+        slow_queries.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// How often each document of a collection was among a search's top
+    /// results, keyed by URI
+    pub async fn document_popularity(&self, collection: &str) -> HashMap<String, usize> {
+        let document_hits = self.document_hits.read().await;
+        document_hits.get(collection).cloned().unwrap_or_default()
+    }
+
+    /// Log a search and keep it in the slow query log if it exceeded the threshold
+    async fn record_slow_query(&self, record: SlowQueryRecord) {
+        let threshold_ms = self.config.slow_query_threshold_ms;
+        if threshold_ms == 0 || record.duration_ms < threshold_ms as f64 {
+            return;
+        }
+
+        let stages = record
+            .stages
+            .iter()
+            .map(|stage| format!("{}={:.1}ms", stage.stage, stage.duration_ms))
+            .collect::<Vec<_>>()
+            .join(" ");
+        warn!(
+            query = %record.query,
+            collection = ?record.collection,
+            duration_ms = record.duration_ms,
+            threshold_ms,
+            stages = %stages,
+            "[SearchAnalytics] Slow query"
+        );
+
+        let mut slow_queries = self.slow_queries.write().await;
+        slow_queries.push_back(record);
+        while slow_queries.len() > self.config.max_slow_queries {
+            slow_queries.pop_front();
+        }
+    }
+
     /// Clear old performance records to prevent memory leaks
     async fn cleanup_performance_records(&self) {
         let mut performance_data = self.performance_data.write().await;
@@ -221,7 +310,7 @@ impl SearchAnalytics for ProductionSearchAnalytics {
 
         // Determine if search was successful
         let success = response.results.len() > 0;
-        let response_time_ms = 0.0; // Would be calculated from request timing in real scenario
+        let response_time_ms = response.search_metadata.execution_time.as_secs_f64() * 1000.0;
 
         // Extract analytics data
         let query = request.query.raw.clone();
@@ -233,11 +322,12 @@ impl SearchAnalytics for ProductionSearchAnalytics {
         let query_enhancement_applied = response.search_metadata.query_enhancement_applied;
 
         let ranking_method = Some(response.search_metadata.ranking_method.clone());
+        let intent = response.search_metadata.intent;
 
         if self.config.enable_detailed_logging {
             info!(
-                "[SearchAnalytics] Recording search: query='{}', collection={:?}, results={}, success={}, enhancement={}, ranking={:?}",
-                query, collection, result_count, success, query_enhancement_applied, ranking_method
+                "[SearchAnalytics] Recording search: query='{}', collection={:?}, results={}, success={}, enhancement={}, ranking={:?}, intent={:?}",
+                query, collection, result_count, success, query_enhancement_applied, ranking_method, intent
             );
         }
 
@@ -310,11 +400,43 @@ impl SearchAnalytics for ProductionSearchAnalytics {
                     .or_insert(0usize) += 1;
             }
 
+            if let Some(intent) = intent {
+                *search_metrics.intents.entry(intent).or_insert(0) += 1;
+            }
+
             // Recalculate unique queries count
             let query_stats = self.query_stats.read().await;
             search_metrics.unique_queries = query_stats.len();
         }
 
+        // Every query is embedded, whether or not the embedding was cached
+        self.usage.record(UsageCounters {
+            searches: 1,
+            embedding_tokens: estimate_tokens(&query),
+            ..Default::default()
+        });
+
+        if let Some(ref coll) = collection {
+            let mut document_hits = self.document_hits.write().await;
+            let hits = document_hits.entry(coll.clone()).or_default();
+            for result in response.results.iter().take(POPULAR_RESULTS) {
+                *hits.entry(result.uri.clone()).or_insert(0) += 1;
+            }
+        }
+
+        self.record_slow_query(SlowQueryRecord {
+            timestamp,
+            request_id: current_request_id(),
+            query: query.clone(),
+            collection: collection.clone(),
+            filters: request.filters.clone(),
+            limit: request.limit,
+            duration_ms: response_time_ms,
+            result_count,
+            stages: response.search_metadata.stage_timings.clone(),
+        })
+        .await;
+
         // Record performance data
         if self.config.enable_performance_tracking {
             let mut performance_data = self.performance_data.write().await;
@@ -327,6 +449,7 @@ impl SearchAnalytics for ProductionSearchAnalytics {
                 top_score: top_score.map(|s| s.value()), // Convert Score to f32
                 query_enhancement_applied,
                 ranking_method,
+                intent,
                 success,
                 error_details: None,
             });
@@ -412,6 +535,9 @@ impl Clone for ProductionSearchAnalytics {
             query_stats: Arc::clone(&self.query_stats),
             search_metrics: Arc::clone(&self.search_metrics),
             performance_data: Arc::clone(&self.performance_data),
+            slow_queries: Arc::clone(&self.slow_queries),
+            document_hits: Arc::clone(&self.document_hits),
+            usage: Arc::clone(&self.usage),
             config: self.config.clone(),
         }
     }
@@ -427,6 +553,8 @@ pub struct AnalyticsSummary {
     pub result_ranking_rate: f32,
     pub top_queries: Vec<QueryAnalytics>,
     pub collections_searched: HashMap<String, usize>,
+    /// Searches per classified query intent
+    pub intents: HashMap<SearchIntent, usize>,
     pub recent_performance: Vec<PerformanceRecord>,
 }
 
@@ -439,3 +567,103 @@ pub struct QueryAnalytics {
     pub avg_results: f32,
     pub avg_score: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use zero_latency_core::values::SearchQuery;
+    use zero_latency_search::models::SearchMetadata;
+
+    fn response(execution_ms: u64) -> SearchResponse {
+        SearchResponse {
+            results: Vec::new(),
+            total_count: Some(0),
+            search_metadata: SearchMetadata {
+                query: SearchQuery::new("async runtime"),
+                execution_time: Duration::from_millis(execution_ms),
+                query_enhancement_applied: false,
+                ranking_method: "vector".to_string(),
+                result_sources: Vec::new(),
+                debug_info: None,
+                stage_timings: vec![StageTiming {
+                    stage: "embedding".to_string(),
+                    duration_ms: execution_ms as f64,
+                }],
+                effective_settings: None,
+                intent: None,
+            },
+            pagination: None,
+            answer: None,
+            confidence: None,
+            answerable: None,
+            facets: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_queries_are_recorded_above_threshold() {
+        let analytics = ProductionSearchAnalytics::new(AnalyticsConfig {
+            slow_query_threshold_ms: 100,
+            max_slow_queries: 2,
+            ..Default::default()
+        });
+        let request = SearchRequest::new("async runtime");
+
+        for execution_ms in [50, 150, 200, 250] {
+            analytics
+                .record_search(&request, &response(execution_ms))
+                .await
+                .unwrap();
+        }
+
+        // Only the newest slow queries are kept, newest first
+        let slow_queries = analytics.get_slow_queries(10).await;
+        let durations: Vec<f64> = slow_queries.iter().map(|q| q.duration_ms).collect();
+        assert_eq!(durations, vec![250.0, 200.0]);
+        assert_eq!(slow_queries[0].stages[0].stage, "embedding");
+        assert_eq!(analytics.get_slow_queries(1).await.len(), 1);
+
+        let summary = analytics.get_analytics_summary().await;
+        assert_eq!(summary.avg_response_time_ms, 162.5);
+    }
+
+    #[tokio::test]
+    async fn test_zero_threshold_disables_slow_query_log() {
+        let analytics = ProductionSearchAnalytics::new(AnalyticsConfig {
+            slow_query_threshold_ms: 0,
+            ..Default::default()
+        });
+
+        analytics
+            .record_search(&SearchRequest::new("query"), &response(1000))
+            .await
+            .unwrap();
+
+        assert!(analytics.get_slow_queries(10).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_summary_counts_query_intents() {
+        let analytics = ProductionSearchAnalytics::new(AnalyticsConfig::default());
+
+        for intent in [
+            Some(SearchIntent::CodeLookup),
+            Some(SearchIntent::CodeLookup),
+            Some(SearchIntent::Question),
+            None,
+        ] {
+            let mut response = response(10);
+            response.search_metadata.intent = intent;
+            analytics
+                .record_search(&SearchRequest::new("query"), &response)
+                .await
+                .unwrap();
+        }
+
+        let summary = analytics.get_analytics_summary().await;
+        assert_eq!(summary.intents[&SearchIntent::CodeLookup], 2);
+        assert_eq!(summary.intents[&SearchIntent::Question], 1);
+        assert_eq!(summary.intents.len(), 2);
+    }
+}
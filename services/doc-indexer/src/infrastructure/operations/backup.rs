@@ -0,0 +1,481 @@
+/// Backup and restore of all server state
+///
+/// `doc-indexer backup` writes everything the service keeps on disk into one
+/// gzipped tar archive: the vector database, the document fingerprint
+/// registry, collection aliases, the change log and webhook subscriptions.
+/// A manifest leads the archive with its format version, the index settings
+/// the vectors were built with and a SHA-256 checksum of every file.
+/// `doc-indexer restore` verifies all of it before replacing anything, so a
+/// damaged archive never leaves a half-restored data directory.
+use chrono::{DateTime, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use crate::config::{Config, EmbeddingProvider, VectorBackend};
+
+/// Version of the archive layout written by this build
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Name of the vector database inside the archive, whatever it is called on disk
+const DATABASE_NAME: &str = "vectors.db";
+
+/// State kept next to the vector database
+const STATE_FILES: &[&str] = &[
+    "fingerprints.json",
+    "collection_aliases.json",
+    "curation.json",
+    "blocklist.json",
+    "metadata_schemas.json",
+    "changes.jsonl",
+    "webhooks.json",
+];
+
+/// Settings stored vectors depend on; restoring them under different
+/// settings would leave an index that can't be searched meaningfully
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexSettings {
+    pub embedding_provider: String,
+    /// Remote model name, for providers that have one
+    pub embedding_model: Option<String>,
+    pub dimension: usize,
+}
+
+impl IndexSettings {
+    pub fn from_config(config: &Config) -> Self {
+        let (embedding_model, dimension) = match config.embedding.provider {
+            EmbeddingProvider::OpenAI => (
+                Some(config.embedding.openai.model.clone()),
+                config.vector.embedded.dimension,
+            ),
+            EmbeddingProvider::Mock => (None, config.embedding.mock.dimension),
+            EmbeddingProvider::Local => (None, config.vector.embedded.dimension),
+        };
+        Self {
+            embedding_provider: format!("{:?}", config.embedding.provider).to_lowercase(),
+            embedding_model,
+            dimension,
+        }
+    }
+
+    /// Stable hash of the settings
+    pub fn fingerprint(&self) -> String {
+        let canonical = serde_json::to_vec(self).expect("index settings serialize");
+        format!("{:x}", Sha256::digest(canonical))
+    }
+}
+
+/// Describes an archive and everything in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    /// Version of the doc-indexer that wrote the archive
+    pub service_version: String,
+    pub index_settings: IndexSettings,
+    pub config_fingerprint: String,
+    pub files: Vec<BackupFile>,
+}
+
+/// A file in the archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFile {
+    pub name: String,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Write a backup archive of the configured data directory into `out_dir`
+///
+/// The database is copied with `VACUUM INTO`, which gives a consistent
+/// snapshot even while a server is writing to it.
+pub fn backup(config: &Config, out_dir: &Path) -> Result<(PathBuf, BackupManifest)> {
+    let db_path = durable_db_path(config)?;
+    if !db_path.exists() {
+        return Err(ZeroLatencyError::not_found(format!(
+            "vector database {}",
+            db_path.display()
+        )));
+    }
+    std::fs::create_dir_all(out_dir).map_err(io_error)?;
+
+    // Stage copies next to the archive so it is built from files nobody writes to
+    let staging = out_dir.join(format!(".backup-{}", uuid::Uuid::new_v4().simple()));
+    std::fs::create_dir(&staging).map_err(io_error)?;
+    let result = write_archive(config, &db_path, &staging, out_dir);
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+fn write_archive(
+    config: &Config,
+    db_path: &Path,
+    staging: &Path,
+    out_dir: &Path,
+) -> Result<(PathBuf, BackupManifest)> {
+    snapshot_database(db_path, &staging.join(DATABASE_NAME))?;
+    let mut names = vec![DATABASE_NAME];
+    for name in STATE_FILES {
+        let source = db_path.with_file_name(name);
+        if source.exists() {
+            std::fs::copy(&source, staging.join(name)).map_err(io_error)?;
+            names.push(name);
+        }
+    }
+
+    let files = names
+        .iter()
+        .map(|name| {
+            let mut file = File::open(staging.join(name)).map_err(io_error)?;
+            let mut hasher = HashingWriter::new(io::sink());
+            let bytes = io::copy(&mut file, &mut hasher).map_err(io_error)?;
+            Ok(BackupFile {
+                name: name.to_string(),
+                bytes,
+                sha256: hasher.finish(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let index_settings = IndexSettings::from_config(config);
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: Utc::now(),
+        service_version: env!("CARGO_PKG_VERSION").to_string(),
+        config_fingerprint: index_settings.fingerprint(),
+        index_settings,
+        files,
+    };
+
+    let archive_path = out_dir.join(format!(
+        "doc-indexer-backup-{}.tar.gz",
+        manifest.created_at.format("%Y%m%dT%H%M%SZ")
+    ));
+    let partial = archive_path.with_extension("gz.partial");
+    let encoder = GzEncoder::new(
+        File::create(&partial).map_err(io_error)?,
+        Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+
+    // The manifest goes first so restore can check it before reading the rest
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| ZeroLatencyError::serialization(e.to_string()))?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.created_at.timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())
+        .map_err(io_error)?;
+    for file in &manifest.files {
+        builder
+            .append_path_with_name(staging.join(&file.name), &file.name)
+            .map_err(io_error)?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .and_then(|file| file.sync_all())
+        .map_err(io_error)?;
+    std::fs::rename(&partial, &archive_path).map_err(io_error)?;
+
+    tracing::info!(
+        "Wrote backup {} ({} files)",
+        archive_path.display(),
+        manifest.files.len()
+    );
+    Ok((archive_path, manifest))
+}
+
+/// Restore the configured data directory from a backup archive
+///
+/// Refuses to replace existing state, or to restore vectors built with
+/// different index settings, unless `force` is set. The server must not be
+/// running against the data directory.
+pub fn restore(config: &Config, archive_path: &Path, force: bool) -> Result<BackupManifest> {
+    let db_path = durable_db_path(config)?;
+    let data_dir = db_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."))
+        .to_path_buf();
+    if db_path.exists() && !force {
+        return Err(ZeroLatencyError::validation(
+            "restore",
+            format!(
+                "{} already exists; restore into a fresh instance or pass --force to replace it",
+                db_path.display()
+            ),
+        ));
+    }
+
+    let file = File::open(archive_path).map_err(io_error)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut entries = archive.entries().map_err(io_error)?;
+
+    let manifest: BackupManifest = match entries.next() {
+        Some(entry) => {
+            let mut entry = entry.map_err(io_error)?;
+            if entry.path().map_err(io_error)?.as_ref() != Path::new(MANIFEST_NAME) {
+                return Err(invalid_archive("it does not start with a manifest"));
+            }
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents).map_err(io_error)?;
+            serde_json::from_slice(&contents)
+                .map_err(|e| invalid_archive(&format!("unreadable manifest: {}", e)))?
+        }
+        None => return Err(invalid_archive("it is empty")),
+    };
+    if manifest.format_version != BACKUP_FORMAT_VERSION {
+        return Err(invalid_archive(&format!(
+            "format version {} is not supported (expected {})",
+            manifest.format_version, BACKUP_FORMAT_VERSION
+        )));
+    }
+    let settings = IndexSettings::from_config(config);
+    if manifest.config_fingerprint != settings.fingerprint() && !force {
+        return Err(ZeroLatencyError::validation(
+            "restore",
+            format!(
+                "the backup was built with {:?} but this instance is configured for {:?}; \
+                 pass --force to restore anyway",
+                manifest.index_settings, settings
+            ),
+        ));
+    }
+
+    // Unpack everything to temporary files and verify it before replacing anything
+    std::fs::create_dir_all(&data_dir).map_err(io_error)?;
+    let mut unpacked: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let result = (|| {
+        for entry in entries {
+            let mut entry = entry.map_err(io_error)?;
+            let name = entry
+                .path()
+                .map_err(io_error)?
+                .to_string_lossy()
+                .to_string();
+            let expected = manifest
+                .files
+                .iter()
+                .find(|file| file.name == name)
+                .ok_or_else(|| invalid_archive(&format!("{} is not in the manifest", name)))?;
+            if unpacked
+                .iter()
+                .any(|(_, target)| *target == target_path(&db_path, &name))
+            {
+                return Err(invalid_archive(&format!("{} appears twice", name)));
+            }
+
+            let temporary = data_dir.join(format!(".restore-{}", name));
+            let mut writer = HashingWriter::new(File::create(&temporary).map_err(io_error)?);
+            unpacked.push((temporary, target_path(&db_path, &name)));
+            let bytes = io::copy(&mut entry, &mut writer).map_err(io_error)?;
+            let (file, sha256) = writer.into_parts();
+            file.sync_all().map_err(io_error)?;
+            if bytes != expected.bytes || sha256 != expected.sha256 {
+                return Err(invalid_archive(&format!("checksum mismatch for {}", name)));
+            }
+        }
+        if unpacked.len() != manifest.files.len() {
+            return Err(invalid_archive("files listed in the manifest are missing"));
+        }
+        Ok(())
+    })();
+    if let Err(e) = result {
+        for (temporary, _) in &unpacked {
+            let _ = std::fs::remove_file(temporary);
+        }
+        return Err(e);
+    }
+
+    // State the backup didn't have must not survive alongside the restored files
+    for name in STATE_FILES {
+        let path = db_path.with_file_name(name);
+        if !manifest.files.iter().any(|file| file.name == *name) && path.exists() {
+            std::fs::remove_file(&path).map_err(io_error)?;
+        }
+    }
+    for (temporary, target) in &unpacked {
+        std::fs::rename(temporary, target).map_err(io_error)?;
+    }
+
+    tracing::info!(
+        "Restored {} files from {} into {}",
+        unpacked.len(),
+        archive_path.display(),
+        data_dir.display()
+    );
+    Ok(manifest)
+}
+
+/// Database path of a backend that keeps its state on local disk
+fn durable_db_path(config: &Config) -> Result<PathBuf> {
+    match config.vector.backend {
+        VectorBackend::Embedded => Ok(config.vector.embedded.db_path.clone()),
+        VectorBackend::Memory => Err(ZeroLatencyError::configuration(
+            "the memory backend keeps no state on disk to back up or restore",
+        )),
+        VectorBackend::Qdrant => Err(ZeroLatencyError::configuration(
+            "Qdrant vectors live outside doc-indexer; use Qdrant's own snapshots",
+        )),
+    }
+}
+
+fn target_path(db_path: &Path, name: &str) -> PathBuf {
+    if name == DATABASE_NAME {
+        db_path.to_path_buf()
+    } else {
+        db_path.with_file_name(name)
+    }
+}
+
+#[cfg(feature = "embedded")]
+fn snapshot_database(source: &Path, target: &Path) -> Result<()> {
+    use rusqlite::{Connection, OpenFlags};
+
+    let connection = Connection::open_with_flags(source, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to open database: {}", e)))?;
+    connection
+        .execute("VACUUM INTO ?1", [target.to_string_lossy()])
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to snapshot database: {}", e)))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "embedded"))]
+fn snapshot_database(_source: &Path, _target: &Path) -> Result<()> {
+    Err(ZeroLatencyError::configuration(
+        "backing up the vector database requires the 'embedded' feature",
+    ))
+}
+
+fn io_error(e: io::Error) -> ZeroLatencyError {
+    ZeroLatencyError::io(e.to_string())
+}
+
+fn invalid_archive(reason: &str) -> ZeroLatencyError {
+    ZeroLatencyError::validation("archive", format!("not a usable backup: {}", reason))
+}
+
+/// Writer that hashes everything written through it
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    fn finish(self) -> String {
+        self.into_parts().1
+    }
+
+    fn into_parts(self) -> (W, String) {
+        (self.inner, format!("{:x}", self.hasher.finalize()))
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(all(test, feature = "embedded"))]
+mod tests {
+    use super::*;
+
+    fn config_for(dir: &Path) -> Config {
+        let mut config = Config::default();
+        config.vector.backend = VectorBackend::Embedded;
+        config.vector.embedded.db_path = dir.join("vectors.db");
+        config
+    }
+
+    fn populate(config: &Config) {
+        let db = rusqlite::Connection::open(&config.vector.embedded.db_path).unwrap();
+        db.execute_batch(
+            "CREATE TABLE vectors (id TEXT PRIMARY KEY, embedding BLOB NOT NULL, metadata TEXT);
+             INSERT INTO vectors VALUES ('a', x'00', '{\"collection\":\"docs\"}');",
+        )
+        .unwrap();
+        let path = &config.vector.embedded.db_path;
+        std::fs::write(path.with_file_name("fingerprints.json"), r#"{"a":1}"#).unwrap();
+        std::fs::write(path.with_file_name("changes.jsonl"), "{}\n").unwrap();
+    }
+
+    #[test]
+    fn test_backup_and_restore_round_trip() {
+        let source = tempfile::tempdir().unwrap();
+        let config = config_for(source.path());
+        populate(&config);
+        let out = tempfile::tempdir().unwrap();
+
+        let (archive, manifest) = backup(&config, out.path()).unwrap();
+        let names: Vec<_> = manifest.files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["vectors.db", "fingerprints.json", "changes.jsonl"]);
+
+        // A fresh instance takes the archive as-is
+        let target = tempfile::tempdir().unwrap();
+        let target_config = config_for(target.path());
+        restore(&target_config, &archive, false).unwrap();
+        let db = rusqlite::Connection::open(&target_config.vector.embedded.db_path).unwrap();
+        let count: i64 = db
+            .query_row("SELECT COUNT(*) FROM vectors", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        let fingerprints = std::fs::read_to_string(target.path().join("fingerprints.json"));
+        assert_eq!(fingerprints.unwrap(), r#"{"a":1}"#);
+
+        // Existing state is only replaced on request, and then completely
+        std::fs::write(target.path().join("webhooks.json"), "[]").unwrap();
+        assert!(restore(&target_config, &archive, false).is_err());
+        restore(&target_config, &archive, true).unwrap();
+        assert!(!target.path().join("webhooks.json").exists());
+    }
+
+    #[test]
+    fn test_restore_rejects_mismatched_settings_and_damage() {
+        let source = tempfile::tempdir().unwrap();
+        let config = config_for(source.path());
+        populate(&config);
+        let out = tempfile::tempdir().unwrap();
+        let (archive, _) = backup(&config, out.path()).unwrap();
+
+        let target = tempfile::tempdir().unwrap();
+        let mut other = config_for(target.path());
+        other.vector.embedded.dimension += 1;
+        other.embedding.mock.dimension += 1;
+        let error = restore(&other, &archive, false).unwrap_err();
+        assert!(error.to_string().contains("--force"), "{}", error);
+
+        // Flip a byte in the middle of the archive
+        let mut bytes = std::fs::read(&archive).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xff;
+        let damaged = out.path().join("damaged.tar.gz");
+        std::fs::write(&damaged, bytes).unwrap();
+        assert!(restore(&config_for(target.path()), &damaged, false).is_err());
+        assert!(!target.path().join("vectors.db").exists());
+        assert_eq!(std::fs::read_dir(target.path()).unwrap().count(), 0);
+    }
+}
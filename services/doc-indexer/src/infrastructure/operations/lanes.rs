@@ -0,0 +1,456 @@
+use serde::{Deserialize, Serialize};
+/// Execution lanes for interactive and background work
+///
+/// Search traffic and background jobs share the same CPU, disk and embedding
+/// model. To keep a large reindex from inflating search tail latency, work
+/// is split into two lanes with separate concurrency budgets: the
+/// interactive lane serves search requests, the background lane runs
+/// indexing pipeline work and batch jobs. Work over a lane's budget waits
+/// for a slot in that lane only, so a saturated background lane can never
+/// delay a search. Each lane counts its waits and busy time so operators
+/// can see how close to its budget it runs. With a latency target the
+/// interactive lane's limit adapts to the observed search latency, so an
+/// overloaded store gets fewer concurrent searches than its static budget.
+/// A lane can also be paused, holding new work back until it resumes; the
+/// memory pressure monitor pauses the background lane this way.
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, Semaphore, SemaphorePermit};
+
+use crate::config::LanesConfig;
+use crate::infrastructure::operations::adaptive_limit::AdaptiveLimit;
+
+/// Kind of traffic a lane carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LaneKind {
+    /// Search and lookup requests
+    Interactive,
+    /// Indexing, migration, re-embedding and projection jobs
+    Background,
+}
+
+/// Point-in-time utilization of a lane
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaneUtilization {
+    pub lane: LaneKind,
+    /// Units of work the lane runs at once; follows the latency with an
+    /// adaptive limit
+    pub capacity: usize,
+    /// Configured budget, the most the adaptive limit can grow to
+    pub max_capacity: usize,
+    /// Units of work running now
+    pub in_flight: usize,
+    /// Units of work waiting for a slot, or for the lane to resume
+    pub queued: u64,
+    /// Whether new work is held back until the lane resumes
+    pub paused: bool,
+    /// Share of the capacity in use, from 0 to 1
+    pub utilization: f64,
+    /// Units of work completed since startup
+    pub completed: u64,
+    /// Mean and longest time spent waiting for a slot
+    pub mean_wait_ms: f64,
+    pub max_wait_ms: f64,
+    /// Total time spent running work, summed over all slots
+    pub busy_ms: u64,
+    /// Latency the adaptive limit aims to keep P95 under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_p95_ms: Option<f64>,
+    /// P95 latency of the last window the adaptive limit observed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observed_p95_ms: Option<f64>,
+}
+
+/// Concurrency budget for one kind of work
+pub struct Lane {
+    kind: LaneKind,
+    max_capacity: usize,
+    // Current limit, and slots still held that must be retired on release
+    // because the limit shrank below the work in flight
+    capacity: AtomicUsize,
+    retiring: AtomicUsize,
+    adaptive: Option<AdaptiveLimit>,
+    paused: watch::Sender<bool>,
+    slots: Semaphore,
+    queued: AtomicU64,
+    completed: AtomicU64,
+    wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+    busy_us: AtomicU64,
+}
+
+impl Lane {
+    /// Create a lane running at most `capacity` units of work at once
+    pub fn new(kind: LaneKind, capacity: usize) -> Self {
+        let capacity = capacity.clamp(1, Semaphore::MAX_PERMITS);
+        Self {
+            kind,
+            max_capacity: capacity,
+            capacity: AtomicUsize::new(capacity),
+            retiring: AtomicUsize::new(0),
+            adaptive: None,
+            paused: watch::Sender::new(false),
+            slots: Semaphore::new(capacity),
+            queued: AtomicU64::new(0),
+            completed: AtomicU64::new(0),
+            wait_us: AtomicU64::new(0),
+            max_wait_us: AtomicU64::new(0),
+            busy_us: AtomicU64::new(0),
+        }
+    }
+
+    /// Adapt the limit to keep P95 latency under `target`, never going
+    /// below `min_capacity`
+    pub fn with_adaptive_limit(mut self, target: Duration, min_capacity: usize) -> Self {
+        self.adaptive = Some(AdaptiveLimit::new(target, min_capacity, self.max_capacity));
+        self
+    }
+
+    /// Hold back new work until [`Self::resume`]; work already running
+    /// finishes. Returns whether the lane was running.
+    pub fn pause(&self) -> bool {
+        !self.paused.send_replace(true)
+    }
+
+    /// Admit work again after [`Self::pause`]. Returns whether the lane was
+    /// paused.
+    pub fn resume(&self) -> bool {
+        self.paused.send_replace(false)
+    }
+
+    /// Run a unit of work once the lane has a free slot
+    pub async fn run<F: Future>(&self, work: F) -> F::Output {
+        let waiting = Instant::now();
+        let slot = {
+            let _queued = QueuedGuard::new(&self.queued);
+            let _ = self.paused.subscribe().wait_for(|paused| !paused).await;
+            SlotGuard::new(
+                self,
+                self.slots
+                    .acquire()
+                    .await
+                    .expect("lane semaphore is never closed"),
+            )
+        };
+        let wait_us = waiting.elapsed().as_micros() as u64;
+        self.wait_us.fetch_add(wait_us, Ordering::Relaxed);
+        self.max_wait_us.fetch_max(wait_us, Ordering::Relaxed);
+
+        let started = Instant::now();
+        let output = work.await;
+        let latency = started.elapsed();
+        drop(slot);
+
+        self.busy_us
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        if let Some(adaptive) = &self.adaptive {
+            let capacity = self.capacity.load(Ordering::Relaxed);
+            if let Some(limit) = adaptive.record(latency, capacity) {
+                self.resize(limit);
+            }
+        }
+        output
+    }
+
+    /// Change the limit; slots in use above a lower limit are retired as
+    /// their work finishes
+    fn resize(&self, limit: usize) {
+        let previous = self.capacity.swap(limit, Ordering::Relaxed);
+        if limit > previous {
+            let mut added = limit - previous;
+            while added > 0 && self.retire_slot() {
+                added -= 1;
+            }
+            self.slots.add_permits(added);
+        } else if limit < previous {
+            let removed = previous - limit;
+            let forgotten = self.slots.forget_permits(removed);
+            self.retiring
+                .fetch_add(removed - forgotten, Ordering::Relaxed);
+        }
+        if limit != previous {
+            tracing::debug!(lane = ?self.kind, limit, previous, "Adjusted lane limit");
+        }
+    }
+
+    /// Take one slot off the retiring count, if any are left to retire
+    fn retire_slot(&self) -> bool {
+        self.retiring
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    /// Current utilization of the lane
+    pub fn utilization(&self) -> LaneUtilization {
+        let capacity = self.capacity.load(Ordering::Relaxed);
+        let in_flight = (capacity + self.retiring.load(Ordering::Relaxed))
+            .saturating_sub(self.slots.available_permits());
+        let completed = self.completed.load(Ordering::Relaxed);
+        let wait_us = self.wait_us.load(Ordering::Relaxed);
+        let millis = |latency: Duration| latency.as_secs_f64() * 1000.0;
+
+        LaneUtilization {
+            lane: self.kind,
+            capacity,
+            max_capacity: self.max_capacity,
+            in_flight,
+            queued: self.queued.load(Ordering::Relaxed),
+            paused: *self.paused.borrow(),
+            utilization: in_flight as f64 / capacity as f64,
+            completed,
+            mean_wait_ms: if completed > 0 {
+                wait_us as f64 / completed as f64 / 1000.0
+            } else {
+                0.0
+            },
+            max_wait_ms: self.max_wait_us.load(Ordering::Relaxed) as f64 / 1000.0,
+            busy_ms: self.busy_us.load(Ordering::Relaxed) / 1000,
+            target_p95_ms: self.adaptive.as_ref().map(|a| millis(a.target())),
+            observed_p95_ms: self
+                .adaptive
+                .as_ref()
+                .and_then(|a| a.last_p95())
+                .map(millis),
+        }
+    }
+}
+
+/// Counts a unit of work as queued until it gets a slot or is cancelled
+struct QueuedGuard<'a>(&'a AtomicU64);
+
+impl<'a> QueuedGuard<'a> {
+    fn new(queued: &'a AtomicU64) -> Self {
+        queued.fetch_add(1, Ordering::Relaxed);
+        Self(queued)
+    }
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// A slot held by running work. It is released, or retired if the limit
+// shrank meanwhile, when the guard drops, so work that is cancelled, e.g. by
+// a request timeout or a client hanging up, doesn't leak the retirement.
+struct SlotGuard<'a> {
+    lane: &'a Lane,
+    permit: Option<SemaphorePermit<'a>>,
+}
+
+impl<'a> SlotGuard<'a> {
+    fn new(lane: &'a Lane, permit: SemaphorePermit<'a>) -> Self {
+        Self {
+            lane,
+            permit: Some(permit),
+        }
+    }
+}
+
+impl Drop for SlotGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(permit) = self.permit.take() {
+            if self.lane.retire_slot() {
+                permit.forget();
+            }
+        }
+    }
+}
+
+/// The interactive and background lanes of a service
+pub struct ExecutionLanes {
+    interactive: Lane,
+    background: Lane,
+}
+
+impl ExecutionLanes {
+    pub fn new(config: &LanesConfig) -> Self {
+        let mut interactive = Lane::new(LaneKind::Interactive, config.interactive_concurrency);
+        if config.search_target_p95_ms > 0 {
+            interactive = interactive.with_adaptive_limit(
+                Duration::from_millis(config.search_target_p95_ms),
+                config.min_interactive_concurrency,
+            );
+        }
+        Self {
+            interactive,
+            background: Lane::new(LaneKind::Background, config.background_concurrency),
+        }
+    }
+
+    /// Lane of search requests
+    pub fn interactive(&self) -> &Lane {
+        &self.interactive
+    }
+
+    /// Lane of indexing and batch jobs
+    pub fn background(&self) -> &Lane {
+        &self.background
+    }
+
+    /// Utilization of both lanes
+    pub fn utilization(&self) -> Vec<LaneUtilization> {
+        vec![
+            self.interactive.utilization(),
+            self.background.utilization(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_lane_caps_concurrent_work() {
+        let lane = Arc::new(Lane::new(LaneKind::Background, 1));
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let running = tokio::spawn({
+            let lane = lane.clone();
+            async move {
+                lane.run(async move {
+                    let _ = started_tx.send(());
+                    let _ = release_rx.await;
+                })
+                .await
+            }
+        });
+        started_rx.await.unwrap();
+
+        let waiting = tokio::spawn({
+            let lane = lane.clone();
+            async move { lane.run(async { 7 }).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let busy = lane.utilization();
+        assert_eq!(busy.in_flight, 1);
+        assert_eq!(busy.queued, 1);
+        assert_eq!(busy.utilization, 1.0);
+
+        release_tx.send(()).unwrap();
+        running.await.unwrap();
+        assert_eq!(waiting.await.unwrap(), 7);
+
+        let idle = lane.utilization();
+        assert_eq!(idle.in_flight, 0);
+        assert_eq!(idle.queued, 0);
+        assert_eq!(idle.completed, 2);
+        assert!(idle.max_wait_ms >= 10.0);
+    }
+
+    #[tokio::test]
+    async fn test_lanes_have_separate_budgets() {
+        let lanes = ExecutionLanes::new(&LanesConfig {
+            interactive_concurrency: 4,
+            background_concurrency: 1,
+            ..Default::default()
+        });
+
+        // A full background lane doesn't hold up interactive work
+        lanes
+            .background()
+            .run(async {
+                let result = tokio::time::timeout(
+                    Duration::from_secs(1),
+                    lanes.interactive().run(async { "searched" }),
+                )
+                .await;
+                assert_eq!(result.unwrap(), "searched");
+            })
+            .await;
+
+        let utilization = lanes.utilization();
+        assert_eq!(utilization[0].lane, LaneKind::Interactive);
+        assert_eq!(utilization[0].capacity, 4);
+        assert_eq!(utilization[1].completed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_limit_shrinks_under_slow_work_and_regrows() {
+        let lane =
+            Lane::new(LaneKind::Interactive, 8).with_adaptive_limit(Duration::from_millis(1), 2);
+
+        for _ in 0..50 {
+            lane.run(tokio::time::sleep(Duration::from_millis(2))).await;
+        }
+        let slow = lane.utilization();
+        assert_eq!(slow.capacity, 7);
+        assert_eq!(slow.max_capacity, 8);
+        assert!(slow.observed_p95_ms.unwrap() >= 2.0);
+        assert_eq!(lane.slots.available_permits(), 7);
+
+        for _ in 0..50 {
+            lane.run(async {}).await;
+        }
+        assert_eq!(lane.utilization().capacity, 8);
+        assert_eq!(lane.slots.available_permits(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_shrinking_below_work_in_flight_retires_slots_on_release() {
+        let lane = Lane::new(LaneKind::Interactive, 4);
+        let slots: Vec<_> = (0..4)
+            .map(|_| SlotGuard::new(&lane, lane.slots.try_acquire().unwrap()))
+            .collect();
+
+        lane.resize(2);
+        assert_eq!(lane.utilization().in_flight, 4);
+
+        // Released slots are retired until the lower limit holds
+        drop(slots);
+        assert_eq!(lane.slots.available_permits(), 2);
+        assert_eq!(lane.utilization().in_flight, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_work_retires_its_slot() {
+        let lane = Arc::new(Lane::new(LaneKind::Interactive, 2));
+        let running: Vec<_> = (0..2)
+            .map(|_| {
+                let lane = lane.clone();
+                tokio::spawn(async move { lane.run(std::future::pending::<()>()).await })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(lane.utilization().in_flight, 2);
+
+        lane.resize(1);
+        // The work is dropped before finishing, as when a request times out
+        for task in running {
+            task.abort();
+            assert!(task.await.unwrap_err().is_cancelled());
+        }
+        assert_eq!(lane.slots.available_permits(), 1);
+        assert_eq!(lane.retiring.load(Ordering::Relaxed), 0);
+        assert_eq!(lane.utilization().in_flight, 0);
+    }
+
+    #[tokio::test]
+    async fn test_paused_lane_holds_work_until_resumed() {
+        let lane = Arc::new(Lane::new(LaneKind::Background, 2));
+        assert!(lane.pause());
+        assert!(!lane.pause());
+
+        let held = tokio::spawn({
+            let lane = lane.clone();
+            async move { lane.run(async { "indexed" }).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let utilization = lane.utilization();
+        assert!(utilization.paused);
+        assert_eq!(utilization.queued, 1);
+        assert_eq!(utilization.completed, 0);
+
+        assert!(lane.resume());
+        assert_eq!(held.await.unwrap(), "indexed");
+        assert!(!lane.utilization().paused);
+    }
+}
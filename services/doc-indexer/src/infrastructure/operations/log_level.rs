@@ -0,0 +1,332 @@
+/// Runtime control of the log filter
+///
+/// The binary installs its log filter through a reload layer and registers
+/// the handle here, so the admin API can change what is logged without a
+/// restart. Processes that never register a handle, such as tests embedding
+/// the library, report the log level as not adjustable.
+///
+/// Debug logs of HTTP requests can also be sampled: with
+/// [`RequestSampling`] on the output layer, only a share of requests log
+/// below info, so a busy server can be debugged without drowning in output.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use tracing::span::{Attributes, Id};
+use tracing::subscriber::Interest;
+use tracing::{Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Filter};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+use zero_latency_core::{Result, ZeroLatencyError};
+
+/// Handle to the installed log filter
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+static HANDLE: OnceLock<LogFilterHandle> = OnceLock::new();
+
+const LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "off"];
+
+/// Name of the span each HTTP request runs in
+const REQUEST_SPAN: &str = "http_request";
+
+/// Share of HTTP requests whose debug and trace logs are kept, as `f64` bits
+static REQUEST_SAMPLE_RATE: AtomicU64 = AtomicU64::new(1.0f64.to_bits());
+
+/// HTTP requests seen by the sampler
+static REQUESTS_SEEN: AtomicU64 = AtomicU64::new(0);
+
+/// Log levels by target and the request sample rate
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogLevels {
+    /// Current filter directives; `None` when the process did not install
+    /// a reloadable filter
+    pub filter: Option<String>,
+    /// Level of each target the filter names
+    pub targets: BTreeMap<String, String>,
+    /// Share of HTTP requests whose debug and trace logs are kept
+    pub request_sample_rate: f64,
+}
+
+/// Change to the log levels; omitted fields are left as they are
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogLevelUpdate {
+    /// Module path whose level to change, e.g. `zero_latency_search`
+    pub target: Option<String>,
+    /// New level of `target`; `null` removes its directive so it falls back
+    /// to the rest of the filter
+    pub level: Option<String>,
+    pub request_sample_rate: Option<f64>,
+}
+
+/// Filter directives for a log level
+///
+/// A bare level applies to the service and core crates the way `--log-level`
+/// does at startup; anything else is taken as `RUST_LOG`-style directives.
+pub fn directives(level: &str) -> String {
+    if LEVELS.contains(&level.to_ascii_lowercase().as_str()) {
+        format!(
+            "doc_indexer={},zero_latency_core=info,zero_latency_search=info,zero_latency_vector=info,zero_latency_observability=info",
+            level
+        )
+    } else {
+        level.to_string()
+    }
+}
+
+/// Register the handle of the installed filter; later registrations are ignored
+pub fn register(handle: LogFilterHandle) {
+    let _ = HANDLE.set(handle);
+}
+
+/// Current filter directives, if the filter can be changed in this process
+pub fn current() -> Option<String> {
+    HANDLE.get()?.with_current(|filter| filter.to_string()).ok()
+}
+
+/// Replace the log filter, returning the new directives
+pub fn set(level: &str) -> Result<String> {
+    let directives = reload(&directives(level), "log_level")?;
+    tracing::info!("Log filter changed to {}", level);
+    Ok(directives)
+}
+
+/// Current log levels
+pub fn levels() -> LogLevels {
+    let filter = current();
+    let targets = filter
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|directive| directive.split_once('='))
+        .map(|(target, level)| (target.to_string(), level.to_string()))
+        .collect();
+    LogLevels {
+        filter,
+        targets,
+        request_sample_rate: request_sample_rate(),
+    }
+}
+
+/// Apply a change to the log levels and return the result
+pub fn update(update: LogLevelUpdate) -> Result<LogLevels> {
+    // Validate everything first so a rejected update changes nothing
+    if let Some(rate) = update.request_sample_rate {
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(ZeroLatencyError::validation(
+                "request_sample_rate",
+                "must be between 0 and 1",
+            ));
+        }
+    }
+    match (&update.target, &update.level) {
+        (Some(target), level) => {
+            let valid_target = !target.is_empty()
+                && target
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':');
+            if !valid_target {
+                return Err(ZeroLatencyError::validation(
+                    "target",
+                    "must be a module path such as zero_latency_search",
+                ));
+            }
+            if let Some(level) = level {
+                if !LEVELS.contains(&level.to_ascii_lowercase().as_str()) {
+                    return Err(ZeroLatencyError::validation(
+                        "level",
+                        format!("must be one of {}", LEVELS.join(", ")),
+                    ));
+                }
+            }
+            let filter = current().ok_or_else(not_adjustable)?;
+            reload(&with_target(&filter, target, level.as_deref()), "target")?;
+            tracing::info!(
+                "Log level of {} changed to {}",
+                target,
+                level.as_deref().unwrap_or("the default")
+            );
+        }
+        (None, Some(_)) => {
+            return Err(ZeroLatencyError::validation(
+                "target",
+                "is required to change a level",
+            ))
+        }
+        (None, None) => {}
+    }
+    if let Some(rate) = update.request_sample_rate {
+        set_request_sample_rate(rate);
+        tracing::info!("Request debug logs sampled at {}", rate);
+    }
+    Ok(levels())
+}
+
+/// Share of HTTP requests whose debug and trace logs are kept
+pub fn request_sample_rate() -> f64 {
+    f64::from_bits(REQUEST_SAMPLE_RATE.load(Ordering::Relaxed))
+}
+
+/// Keep the debug and trace logs of `rate` of HTTP requests, clamped to
+/// between none and all
+pub fn set_request_sample_rate(rate: f64) {
+    let rate = if rate.is_nan() {
+        1.0
+    } else {
+        rate.clamp(0.0, 1.0)
+    };
+    REQUEST_SAMPLE_RATE.store(rate.to_bits(), Ordering::Relaxed);
+}
+
+/// Install `directives` as the log filter, returning the result
+fn reload(directives: &str, field: &str) -> Result<String> {
+    let handle = HANDLE.get().ok_or_else(not_adjustable)?;
+    let filter = EnvFilter::try_new(directives)
+        .map_err(|e| ZeroLatencyError::validation(field, e.to_string()))?;
+    handle
+        .reload(filter)
+        .map_err(|e| ZeroLatencyError::internal(e.to_string()))?;
+    Ok(current().unwrap_or_else(|| directives.to_string()))
+}
+
+fn not_adjustable() -> ZeroLatencyError {
+    ZeroLatencyError::configuration("log level cannot be changed in this process")
+}
+
+/// `filter` with the directive for `target` replaced by `target=level`, or
+/// removed without a level
+fn with_target(filter: &str, target: &str, level: Option<&str>) -> String {
+    let mut directives: Vec<String> = filter
+        .split(',')
+        .filter(|directive| {
+            !directive.is_empty() && directive.split(['[', '=']).next() != Some(target)
+        })
+        .map(str::to_string)
+        .collect();
+    if let Some(level) = level {
+        directives.push(format!("{}={}", target, level.to_ascii_lowercase()));
+    }
+    directives.join(",")
+}
+
+/// Whether the `seen`th request is sampled at `rate`; spreads the sampled
+/// requests evenly, e.g. every fourth at 0.25
+fn sampled(seen: u64, rate: f64) -> bool {
+    rate >= 1.0 || ((seen + 1) as f64 * rate).floor() > (seen as f64 * rate).floor()
+}
+
+/// Marks a request span whose debug and trace logs are dropped
+struct Unsampled;
+
+/// Per-layer filter that drops the debug and trace logs of HTTP requests
+/// left out of the sample
+///
+/// Whether a request is sampled is decided when its span opens, at the
+/// current [`request_sample_rate`]. Logs at info and above, and logs outside
+/// requests, always pass; the global filter still applies on top.
+pub struct RequestSampling;
+
+impl<S> Filter<S> for RequestSampling
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn enabled(&self, meta: &Metadata<'_>, cx: &Context<'_, S>) -> bool {
+        if meta.is_span() || *meta.level() <= Level::INFO {
+            return true;
+        }
+        cx.lookup_current().is_none_or(|span| {
+            !span
+                .scope()
+                .any(|span| span.extensions().get::<Unsampled>().is_some())
+        })
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> Interest {
+        if meta.is_span() || *meta.level() <= Level::INFO {
+            Interest::always()
+        } else {
+            Interest::sometimes()
+        }
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != REQUEST_SPAN {
+            return;
+        }
+        let seen = REQUESTS_SEEN.fetch_add(1, Ordering::Relaxed);
+        if !sampled(seen, request_sample_rate()) {
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(Unsampled);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives() {
+        assert!(directives("DEBUG").starts_with("doc_indexer=DEBUG,"));
+        assert_eq!(directives("hyper=warn,info"), "hyper=warn,info");
+        assert!(EnvFilter::try_new(directives("debug")).is_ok());
+    }
+
+    #[test]
+    fn test_with_target() {
+        let filter = "doc_indexer=info,zero_latency_search=info,hyper[conn]=warn";
+        assert_eq!(
+            with_target(filter, "zero_latency_search", Some("DEBUG")),
+            "doc_indexer=info,hyper[conn]=warn,zero_latency_search=debug"
+        );
+        assert_eq!(
+            with_target(filter, "hyper", None),
+            "doc_indexer=info,zero_latency_search=info"
+        );
+        assert_eq!(
+            with_target("", "doc_indexer", Some("trace")),
+            "doc_indexer=trace"
+        );
+    }
+
+    #[test]
+    fn test_sampling_spreads_requests() {
+        let kept = |rate| (0..100).filter(|&seen| sampled(seen, rate)).count();
+        assert_eq!(kept(1.0), 100);
+        assert_eq!(kept(0.25), 25);
+        assert_eq!(kept(0.0), 0);
+        assert!(!sampled(0, 0.5) && sampled(1, 0.5));
+    }
+
+    #[test]
+    fn test_request_sampling_drops_unsampled_debug_logs() {
+        use std::sync::atomic::AtomicUsize;
+        use std::sync::Arc;
+        use tracing_subscriber::layer::SubscriberExt;
+        use tracing_subscriber::Layer;
+
+        struct Count(Arc<AtomicUsize>);
+        impl<S: Subscriber> Layer<S> for Count {
+            fn on_event(&self, _: &tracing::Event<'_>, _: Context<'_, S>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let events = Arc::new(AtomicUsize::new(0));
+        let subscriber =
+            tracing_subscriber::registry().with(Count(events.clone()).with_filter(RequestSampling));
+        set_request_sample_rate(0.5);
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..4 {
+                let _request = tracing::info_span!("http_request").entered();
+                let _search = tracing::info_span!("search").entered();
+                tracing::debug!("kept for half the requests");
+                tracing::info!("always kept");
+            }
+            tracing::debug!("outside requests");
+        });
+        set_request_sample_rate(1.0);
+        assert_eq!(events.load(Ordering::SeqCst), 2 + 4 + 1);
+    }
+}
@@ -0,0 +1,319 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+/// Memory pressure monitor
+///
+/// Keeps the doc-indexer from being OOM-killed in a memory-constrained
+/// container. The monitor samples the process's RSS and, once it reaches the
+/// configured share of the memory limit, shrinks every registered cache and
+/// pauses the background lane so indexing stops allocating. Each cache gives
+/// back a share of its entries proportional to how far RSS is over the
+/// threshold, at least a quarter per sample. Background work resumes once RSS
+/// falls clearly below the threshold again, so the lane doesn't flap around
+/// it. Every action is logged and counted in the admin statistics.
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sysinfo::System;
+use zero_latency_search::CachedEmbeddingService;
+
+use crate::config::MemoryPressureConfig;
+use crate::infrastructure::memory::MemoryEfficientCache;
+use crate::infrastructure::operations::lanes::ExecutionLanes;
+
+/// Smallest share of each cache evicted while under pressure
+const MIN_SHRINK: f64 = 0.25;
+
+/// Share of the threshold RSS must fall under before background work resumes
+const RESUME_RATIO: f64 = 0.9;
+
+/// A cache that can give memory back under pressure
+#[async_trait]
+pub trait ShrinkableCache: Send + Sync {
+    /// Estimated bytes held by the entries
+    async fn memory_usage(&self) -> usize;
+
+    /// Evict a share of the entries; returns how many were evicted
+    async fn shrink(&self, fraction: f64) -> usize;
+}
+
+#[async_trait]
+impl ShrinkableCache for CachedEmbeddingService {
+    async fn memory_usage(&self) -> usize {
+        CachedEmbeddingService::memory_usage(self).await
+    }
+
+    async fn shrink(&self, fraction: f64) -> usize {
+        CachedEmbeddingService::shrink(self, fraction).await
+    }
+}
+
+#[async_trait]
+impl<K, V> ShrinkableCache for MemoryEfficientCache<K, V>
+where
+    K: Clone + Eq + Hash + Send + Sync,
+    V: Clone + Send + Sync,
+{
+    async fn memory_usage(&self) -> usize {
+        MemoryEfficientCache::memory_usage(self)
+    }
+
+    async fn shrink(&self, fraction: f64) -> usize {
+        MemoryEfficientCache::shrink(self, fraction)
+    }
+}
+
+/// Memory use and the actions the monitor took since startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryPressureStats {
+    /// RSS at the last sample
+    pub rss_bytes: u64,
+    /// Memory limit RSS is compared with
+    pub limit_bytes: u64,
+    /// RSS as a share of the limit
+    pub usage_ratio: f64,
+    /// Share of the limit at which the monitor acts; 0 when disabled
+    pub threshold: f64,
+    /// Whether caches are being shrunk and background work is paused
+    pub under_pressure: bool,
+    /// Times RSS crossed the threshold
+    pub pressure_events: u64,
+    /// Times the caches were shrunk
+    pub cache_shrinks: u64,
+    /// Cache entries evicted under pressure
+    pub evicted_entries: u64,
+}
+
+/// Samples RSS and relieves memory pressure
+pub struct MemoryPressureMonitor {
+    threshold: f64,
+    limit_bytes: u64,
+    lanes: Arc<ExecutionLanes>,
+    caches: Mutex<Vec<(String, Arc<dyn ShrinkableCache>)>>,
+    under_pressure: AtomicBool,
+    rss_bytes: AtomicU64,
+    pressure_events: AtomicU64,
+    cache_shrinks: AtomicU64,
+    evicted_entries: AtomicU64,
+}
+
+impl MemoryPressureMonitor {
+    /// Create a monitor pausing the background lane of `lanes` under pressure
+    pub fn new(config: &MemoryPressureConfig, lanes: Arc<ExecutionLanes>) -> Self {
+        let limit_bytes = if config.limit_mb > 0 {
+            config.limit_mb * 1024 * 1024
+        } else {
+            detect_limit()
+        };
+        Self {
+            threshold: config.threshold,
+            limit_bytes,
+            lanes,
+            caches: Mutex::new(Vec::new()),
+            under_pressure: AtomicBool::new(false),
+            rss_bytes: AtomicU64::new(0),
+            pressure_events: AtomicU64::new(0),
+            cache_shrinks: AtomicU64::new(0),
+            evicted_entries: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether the monitor has a threshold and a limit to act on
+    fn enabled(&self) -> bool {
+        self.threshold > 0.0 && self.limit_bytes > 0
+    }
+
+    /// Shrink `cache` under pressure
+    pub fn register(&self, name: impl Into<String>, cache: Arc<dyn ShrinkableCache>) {
+        self.lock().push((name.into(), cache));
+    }
+
+    /// Sample RSS and act on it
+    pub async fn check(&self) {
+        match current_rss() {
+            Some(rss) => self.respond(rss).await,
+            None => tracing::debug!("RSS unavailable; skipping the memory pressure check"),
+        }
+    }
+
+    /// Shrink caches and pause background work while `rss` is over the
+    /// threshold, and resume once it falls back
+    async fn respond(&self, rss: u64) {
+        self.rss_bytes.store(rss, Ordering::Relaxed);
+        if !self.enabled() {
+            return;
+        }
+
+        let ratio = rss as f64 / self.limit_bytes as f64;
+        if ratio >= self.threshold {
+            if !self.under_pressure.swap(true, Ordering::Relaxed) {
+                self.pressure_events.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(
+                    rss_mb = rss / (1024 * 1024),
+                    limit_mb = self.limit_bytes / (1024 * 1024),
+                    "Memory pressure at {:.0}% of the limit; shrinking caches and pausing background work",
+                    ratio * 100.0
+                );
+                self.lanes.background().pause();
+            }
+            self.shrink_caches(rss).await;
+        } else if ratio < self.threshold * RESUME_RATIO
+            && self.under_pressure.swap(false, Ordering::Relaxed)
+        {
+            self.lanes.background().resume();
+            tracing::info!(
+                "Memory pressure relieved at {:.0}% of the limit; resuming background work",
+                ratio * 100.0
+            );
+        }
+    }
+
+    /// Shrink every cache by the share of their memory RSS is over the threshold
+    async fn shrink_caches(&self, rss: u64) {
+        let caches = self.lock().clone();
+        let mut cached_bytes = 0;
+        for (_, cache) in &caches {
+            cached_bytes += cache.memory_usage().await;
+        }
+        if cached_bytes == 0 {
+            return;
+        }
+
+        let excess = rss as f64 - self.threshold * self.limit_bytes as f64;
+        let fraction = (excess / cached_bytes as f64).clamp(MIN_SHRINK, 1.0);
+        let mut evicted = 0;
+        for (name, cache) in &caches {
+            let count = cache.shrink(fraction).await;
+            if count > 0 {
+                tracing::info!(cache = %name, evicted = count, "Shrank cache under memory pressure");
+            }
+            evicted += count as u64;
+        }
+        self.cache_shrinks.fetch_add(1, Ordering::Relaxed);
+        self.evicted_entries.fetch_add(evicted, Ordering::Relaxed);
+    }
+
+    /// Memory use and the actions taken so far
+    pub fn stats(&self) -> MemoryPressureStats {
+        let rss_bytes = self.rss_bytes.load(Ordering::Relaxed);
+        MemoryPressureStats {
+            rss_bytes,
+            limit_bytes: self.limit_bytes,
+            usage_ratio: if self.limit_bytes > 0 {
+                rss_bytes as f64 / self.limit_bytes as f64
+            } else {
+                0.0
+            },
+            threshold: self.threshold,
+            under_pressure: self.under_pressure.load(Ordering::Relaxed),
+            pressure_events: self.pressure_events.load(Ordering::Relaxed),
+            cache_shrinks: self.cache_shrinks.load(Ordering::Relaxed),
+            evicted_entries: self.evicted_entries.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Sample memory every `interval` in the background, acting on it when
+    /// the monitor is enabled
+    pub fn spawn_monitor(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                monitor.check().await;
+            }
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(String, Arc<dyn ShrinkableCache>)>> {
+        self.caches.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Resident set size of this process
+fn current_rss() -> Option<u64> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    let mut system = System::new();
+    system.refresh_process(pid);
+    system.process(pid).map(|process| process.memory())
+}
+
+/// The container's cgroup memory limit, or the machine's memory without one
+fn detect_limit() -> u64 {
+    let mut system = System::new();
+    system.refresh_memory();
+    let total = system.total_memory();
+    system
+        .cgroup_limits()
+        .map_or(total, |limits| limits.total_memory.min(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LanesConfig;
+    use crate::infrastructure::memory::CacheConfig;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn monitor() -> (
+        MemoryPressureMonitor,
+        Arc<MemoryEfficientCache<String, u64>>,
+    ) {
+        let config = MemoryPressureConfig {
+            threshold: 0.8,
+            limit_mb: 100,
+            check_interval_seconds: 1,
+        };
+        let lanes = Arc::new(ExecutionLanes::new(&LanesConfig::default()));
+        let monitor = MemoryPressureMonitor::new(&config, lanes);
+
+        let cache = Arc::new(MemoryEfficientCache::new(CacheConfig {
+            max_entries: 100,
+            ..Default::default()
+        }));
+        for i in 0..20 {
+            cache.insert_with_size(format!("key{}", i), i, MB as usize);
+        }
+        monitor.register("test", cache.clone());
+        (monitor, cache)
+    }
+
+    #[tokio::test]
+    async fn test_pressure_shrinks_caches_and_pauses_background_work() {
+        let (monitor, cache) = monitor();
+
+        monitor.respond(50 * MB).await;
+        assert_eq!(cache.len(), 20);
+        assert!(!monitor.stats().under_pressure);
+
+        // 10MB over the threshold is half of the 20MB cached
+        monitor.respond(90 * MB).await;
+        assert_eq!(cache.len(), 10);
+        let stats = monitor.stats();
+        assert!(stats.under_pressure);
+        assert_eq!(stats.pressure_events, 1);
+        assert_eq!(stats.evicted_entries, 10);
+        assert!(monitor.lanes.utilization()[1].paused);
+
+        // Slightly over the threshold still takes a quarter
+        monitor.respond(81 * MB).await;
+        assert_eq!(cache.len(), 7);
+        assert_eq!(monitor.stats().pressure_events, 1);
+    }
+
+    #[tokio::test]
+    async fn test_background_work_resumes_clearly_below_the_threshold() {
+        let (monitor, _cache) = monitor();
+        monitor.respond(85 * MB).await;
+
+        // Just under the threshold isn't enough to resume
+        monitor.respond(75 * MB).await;
+        assert!(monitor.stats().under_pressure);
+
+        monitor.respond(60 * MB).await;
+        assert!(!monitor.stats().under_pressure);
+        assert!(!monitor.lanes.utilization()[1].paused);
+        assert_eq!(monitor.stats().usage_ratio, 0.6);
+    }
+}
@@ -3,5 +3,12 @@
 /// This module contains operational concerns including analytics, monitoring,
 /// production deployment, and system management capabilities.
 
+pub mod adaptive_limit;
 pub mod analytics;
+pub mod backup;
+pub mod lanes;
+pub mod log_level;
+pub mod memory_pressure;
+pub mod offline;
 pub mod production;
+pub mod usage;
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+/// Offline (local-only) mode
+///
+/// Air-gapped deployments can't reach OpenAI, Slack or Discord, and should
+/// find out at startup rather than from the first failing request. Offline
+/// mode swaps every cloud adapter in the configuration for its local
+/// fallback before the service is assembled and records what was lost, so
+/// `/api/status` can report the degraded features. A configured capability
+/// without a local fallback stops the service from starting.
+use zero_latency_config::SummarizerBackend;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use crate::config::{Config, EmbeddingProvider, VectorBackend};
+
+/// A feature that runs with less than it was configured for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DegradedCapability {
+    pub capability: String,
+    /// What replaced the cloud adapter, or what no longer works
+    pub reason: String,
+}
+
+/// Whether the service runs offline and what that costs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfflineStatus {
+    pub enabled: bool,
+    pub degraded: Vec<DegradedCapability>,
+}
+
+impl OfflineStatus {
+    fn degrade(&mut self, capability: &str, reason: impl Into<String>) {
+        tracing::warn!("Offline mode: {} degraded", capability);
+        self.degraded.push(DegradedCapability {
+            capability: capability.to_string(),
+            reason: reason.into(),
+        });
+    }
+}
+
+/// Replace the cloud adapters of `config` with local fallbacks if offline
+/// mode is enabled
+///
+/// Fails when a configured capability has no local fallback, such as a
+/// Qdrant vector store or OpenAI embeddings in a build without a local model.
+pub fn apply(config: &mut Config) -> Result<OfflineStatus> {
+    let mut status = OfflineStatus {
+        enabled: config.offline.enabled,
+        degraded: Vec::new(),
+    };
+    if !status.enabled {
+        return Ok(status);
+    }
+
+    if matches!(config.vector.backend, VectorBackend::Qdrant) {
+        return Err(ZeroLatencyError::configuration(
+            "Offline mode has no local fallback for the Qdrant vector backend; \
+             use the embedded or memory backend",
+        ));
+    }
+
+    if matches!(config.embedding.provider, EmbeddingProvider::OpenAI) {
+        if !cfg!(feature = "embedded") {
+            return Err(ZeroLatencyError::configuration(
+                "Offline mode has no local fallback for OpenAI embeddings; \
+                 build with the 'embedded' feature or use the local provider",
+            ));
+        }
+        config.embedding.provider = EmbeddingProvider::Local;
+        status.degrade(
+            "embeddings",
+            "OpenAI embeddings replaced by the local model; collections embedded \
+             with OpenAI need re-indexing to be searched",
+        );
+    }
+
+    if config.summarizer.backend == SummarizerBackend::OpenAI {
+        config.summarizer.backend = SummarizerBackend::Local;
+        status.degrade(
+            "summaries",
+            "OpenAI summaries replaced by extractive summaries",
+        );
+    }
+
+    let alert_urls = [
+        config.chat.slack_alert_url.take(),
+        config.chat.discord_alert_url.take(),
+    ];
+    if alert_urls.iter().any(Option::is_some) {
+        status.degrade(
+            "chat_alerts",
+            "Index failures are not posted to Slack or Discord",
+        );
+    }
+
+    tracing::info!(
+        "Offline mode enabled with {} degraded capabilities",
+        status.degraded.len()
+    );
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offline_config() -> Config {
+        let mut config = Config::default();
+        config.offline.enabled = true;
+        config.vector.backend = VectorBackend::Memory;
+        config.embedding.provider = EmbeddingProvider::Mock;
+        config.summarizer.backend = SummarizerBackend::Local;
+        config.chat.slack_alert_url = None;
+        config.chat.discord_alert_url = None;
+        config
+    }
+
+    #[test]
+    fn test_replaces_cloud_adapters() {
+        let mut config = offline_config();
+        assert!(apply(&mut config).unwrap().degraded.is_empty());
+
+        config.summarizer.backend = SummarizerBackend::OpenAI;
+        config.chat.slack_alert_url = Some("https://hooks.slack.com/services/x".to_string());
+        let status = apply(&mut config).unwrap();
+        let degraded: Vec<&str> = status
+            .degraded
+            .iter()
+            .map(|d| d.capability.as_str())
+            .collect();
+        assert_eq!(degraded, vec!["summaries", "chat_alerts"]);
+        assert_eq!(config.summarizer.backend, SummarizerBackend::Local);
+        assert!(config.chat.slack_alert_url.is_none());
+
+        // Disabled, the configuration is left alone
+        config.offline.enabled = false;
+        config.summarizer.backend = SummarizerBackend::OpenAI;
+        assert_eq!(apply(&mut config).unwrap(), OfflineStatus::default());
+        assert_eq!(config.summarizer.backend, SummarizerBackend::OpenAI);
+    }
+
+    #[test]
+    fn test_refuses_capabilities_without_fallback() {
+        let mut config = offline_config();
+        config.vector.backend = VectorBackend::Qdrant;
+        let err = apply(&mut config).unwrap_err();
+        assert!(err.to_string().contains("Qdrant"));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::application::interfaces::EmbeddingService;
+use crate::application::services::health_service::HealthService;
 use serde::{Deserialize, Serialize};
 /// Health Monitoring System
 ///
@@ -332,6 +333,69 @@ impl HealthCheck for VectorStoreHealthCheck {
     }
 }
 
+/// Health of the service as its health endpoints report it
+pub struct ServiceHealthCheck {
+    name: String,
+    health_service: HealthService,
+}
+
+impl ServiceHealthCheck {
+    pub fn new(health_service: HealthService) -> Self {
+        Self {
+            name: "service".to_string(),
+            health_service,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthCheck for ServiceHealthCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(10)
+    }
+
+    async fn check(&self) -> HealthCheckResult {
+        let start = Instant::now();
+        let timestamp = chrono::Utc::now().timestamp() as u64;
+
+        match self.health_service.health_check().await {
+            Ok(result) => {
+                let status = match result.status.as_str() {
+                    "healthy" => HealthStatus::Healthy,
+                    "degraded" => HealthStatus::Degraded,
+                    _ => HealthStatus::Unhealthy,
+                };
+                let details = result
+                    .checks
+                    .into_iter()
+                    .map(|(name, item)| (name, item.status))
+                    .collect();
+
+                HealthCheckResult {
+                    name: self.name.clone(),
+                    status,
+                    message: format!("Service {}", result.status),
+                    duration: start.elapsed(),
+                    timestamp,
+                    details,
+                }
+            }
+            Err(error) => HealthCheckResult {
+                name: self.name.clone(),
+                status: HealthStatus::Unhealthy,
+                message: format!("Service health check failed: {}", error),
+                duration: start.elapsed(),
+                timestamp,
+                details: HashMap::new(),
+            },
+        }
+    }
+}
+
 /// Aggregated health status for all checks
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AggregatedHealthStatus {
@@ -410,6 +474,11 @@ impl HealthChecker {
         Ok(())
     }
 
+    /// Whether checks are running in the background
+    pub async fn is_running(&self) -> bool {
+        *self.running.read().await
+    }
+
     /// Get the latest health status
     pub async fn get_latest_status(&self) -> HealthStatus {
         if let Some(status) = self.latest_status.read().await.as_ref() {
@@ -8,11 +8,25 @@ pub mod shutdown;
 pub mod startup;
 
 pub use health::{HealthChecker, HealthStatus};
-pub use monitoring::{ProductionMonitor, SystemMetrics};
+pub use monitoring::{ProductionMonitor, RequestMetrics, SystemMetrics};
 pub use shutdown::{GracefulShutdown, ShutdownSignal};
 pub use startup::StartupValidator;
 
-use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+use crate::application::services::webhook_service::WebhookService;
+use crate::infrastructure::persistence::webhook_registry::WebhookEvent;
+use health::HealthCheck;
+use monitoring::MonitoringConfig;
+use startup::{StartupConfig, StartupReport, ValidationStep};
+
+/// Status transitions kept for the deployment report
+const MAX_TRANSITIONS: usize = 50;
 
 /// Configuration for production deployment features
 #[derive(Debug, Clone)]
@@ -98,8 +112,31 @@ impl Default for ProductionConfig {
     }
 }
 
+impl From<&crate::config::ProductionConfig> for ProductionConfig {
+    fn from(config: &crate::config::ProductionConfig) -> Self {
+        Self {
+            health_check_interval: Duration::from_secs(config.health_check_interval_seconds.max(1)),
+            health_check_timeout: Duration::from_secs(config.health_check_timeout_seconds),
+            health_check_enabled: config.health_check_enabled,
+            monitoring_enabled: config.monitoring_enabled,
+            metrics_collection_interval: Duration::from_secs(
+                config.metrics_collection_interval_seconds.max(1),
+            ),
+            performance_alerts_enabled: config.performance_alerts_enabled,
+            graceful_shutdown_timeout: Duration::from_secs(
+                config.graceful_shutdown_timeout_seconds,
+            ),
+            shutdown_signal_handlers: config.shutdown_signal_handlers,
+            startup_validation_enabled: config.startup_validation_enabled,
+            startup_timeout: Duration::from_secs(config.startup_timeout_seconds),
+            dependency_check_enabled: config.dependency_check_enabled,
+        }
+    }
+}
+
 /// Overall production deployment status
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DeploymentStatus {
     Starting,
     Healthy,
@@ -109,6 +146,187 @@ pub enum DeploymentStatus {
     Stopped,
 }
 
+impl DeploymentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeploymentStatus::Starting => "starting",
+            DeploymentStatus::Healthy => "healthy",
+            DeploymentStatus::Degraded => "degraded",
+            DeploymentStatus::Unhealthy => "unhealthy",
+            DeploymentStatus::ShuttingDown => "shutting_down",
+            DeploymentStatus::Stopped => "stopped",
+        }
+    }
+}
+
+/// A change of deployment status
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub from: DeploymentStatus,
+    pub to: DeploymentStatus,
+    pub reason: String,
+    pub at: DateTime<Utc>,
+}
+
+/// State of one orchestrated component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentState {
+    pub enabled: bool,
+    /// e.g. `running` or `stopped`; `disabled` when not enabled
+    pub state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ComponentState {
+    fn new(state: impl Into<String>) -> Self {
+        Self {
+            enabled: true,
+            state: state.into(),
+            detail: None,
+        }
+    }
+
+    fn disabled() -> Self {
+        Self {
+            enabled: false,
+            state: "disabled".to_string(),
+            detail: None,
+        }
+    }
+
+    fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// Snapshot of a deployment for operators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentReport {
+    pub status: DeploymentStatus,
+    pub started_at: DateTime<Utc>,
+    pub uptime_seconds: u64,
+    /// `startup_validator`, `health_checker`, `monitor` and `shutdown_handler`
+    pub components: BTreeMap<String, ComponentState>,
+    /// Most recent last
+    pub transitions: Vec<StatusTransition>,
+}
+
+struct TrackerState {
+    status: DeploymentStatus,
+    started_at: DateTime<Utc>,
+    components: BTreeMap<String, ComponentState>,
+    transitions: VecDeque<StatusTransition>,
+}
+
+/// Shared view of a deployment's status, readable while the orchestrator
+/// owning the components is busy starting or stopping them
+#[derive(Clone)]
+pub struct DeploymentTracker {
+    state: Arc<Mutex<TrackerState>>,
+    started: Instant,
+    shutdown: Arc<Notify>,
+    webhooks: Option<WebhookService>,
+}
+
+impl Default for DeploymentTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeploymentTracker {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(TrackerState {
+                status: DeploymentStatus::Starting,
+                started_at: Utc::now(),
+                components: BTreeMap::new(),
+                transitions: VecDeque::new(),
+            })),
+            started: Instant::now(),
+            shutdown: Arc::new(Notify::new()),
+            webhooks: None,
+        }
+    }
+
+    /// Notify webhooks of status transitions
+    pub fn with_webhooks(mut self, webhooks: WebhookService) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    pub fn status(&self) -> DeploymentStatus {
+        self.lock().status
+    }
+
+    /// Status, components and recent transitions
+    pub fn report(&self) -> DeploymentReport {
+        let state = self.lock();
+        DeploymentReport {
+            status: state.status,
+            started_at: state.started_at,
+            uptime_seconds: self.started.elapsed().as_secs(),
+            components: state.components.clone(),
+            transitions: state.transitions.iter().cloned().collect(),
+        }
+    }
+
+    /// Ask the orchestrator to shut the deployment down
+    pub fn request_shutdown(&self) {
+        self.shutdown.notify_one();
+    }
+
+    async fn shutdown_requested(&self) {
+        self.shutdown.notified().await
+    }
+
+    fn set_components(&self, components: BTreeMap<String, ComponentState>) {
+        self.lock().components = components;
+    }
+
+    /// Move to `to`, recording the transition and reporting it as a log
+    /// event and a webhook; staying in the same status records nothing
+    fn transition(&self, to: DeploymentStatus, reason: impl Into<String>) {
+        let transition = {
+            let mut state = self.lock();
+            if state.status == to {
+                return;
+            }
+            let transition = StatusTransition {
+                from: state.status,
+                to,
+                reason: reason.into(),
+                at: Utc::now(),
+            };
+            state.status = to;
+            if state.transitions.len() == MAX_TRANSITIONS {
+                state.transitions.pop_front();
+            }
+            state.transitions.push_back(transition.clone());
+            transition
+        };
+
+        tracing::info!(
+            from = transition.from.as_str(),
+            to = transition.to.as_str(),
+            reason = %transition.reason,
+            "Deployment status changed"
+        );
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.emit(
+                WebhookEvent::DeploymentStatusChanged,
+                serde_json::json!(transition),
+            );
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TrackerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
 /// Production deployment orchestrator
 pub struct ProductionDeployment {
     config: ProductionConfig,
@@ -116,7 +334,13 @@ pub struct ProductionDeployment {
     monitor: Option<ProductionMonitor>,
     shutdown_handler: Option<GracefulShutdown>,
     startup_validator: Option<StartupValidator>,
-    status: DeploymentStatus,
+    startup_steps: Vec<ValidationStep>,
+    startup_report: Option<StartupReport>,
+    startup_state: ComponentState,
+    health_checks: Vec<Box<dyn HealthCheck>>,
+    request_metrics: Option<RequestMetrics>,
+    tracker: DeploymentTracker,
+    started: bool,
 }
 
 impl ProductionDeployment {
@@ -127,69 +351,113 @@ impl ProductionDeployment {
             monitor: None,
             shutdown_handler: None,
             startup_validator: None,
-            status: DeploymentStatus::Starting,
+            startup_steps: Vec::new(),
+            startup_report: None,
+            startup_state: ComponentState::disabled(),
+            health_checks: Vec::new(),
+            request_metrics: None,
+            tracker: DeploymentTracker::new(),
+            started: false,
         }
     }
 
+    /// Report status through `tracker` instead of a private one
+    pub fn with_tracker(mut self, tracker: DeploymentTracker) -> Self {
+        self.tracker = tracker;
+        self
+    }
+
+    /// Steps startup validation runs, and the report to publish them to
+    pub fn with_startup_steps(mut self, steps: Vec<ValidationStep>, report: StartupReport) -> Self {
+        self.startup_steps = steps;
+        self.startup_report = Some(report);
+        self
+    }
+
+    /// Add a check the health checker runs
+    pub fn with_health_check(mut self, check: Box<dyn HealthCheck>) -> Self {
+        self.health_checks.push(check);
+        self
+    }
+
+    /// Requests the monitor derives error rates and response times from
+    pub fn with_request_metrics(mut self, request_metrics: RequestMetrics) -> Self {
+        self.request_metrics = Some(request_metrics);
+        self
+    }
+
+    /// Shared view of this deployment's status
+    pub fn tracker(&self) -> DeploymentTracker {
+        self.tracker.clone()
+    }
+
     /// Initialize all production deployment components
     pub async fn initialize(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         println!("🚀 Initializing production deployment...");
 
         // Initialize startup validator
         if self.config.startup_validation_enabled {
-            let startup_config = crate::infrastructure::operations::production::startup::StartupConfig {
+            let startup_config = StartupConfig {
                 startup_timeout_seconds: self.config.startup_timeout.as_secs(),
-                continue_on_warnings: true, // or derive from ProductionConfig if available
-                enable_parallel_validation: true, // or derive from ProductionConfig if available
-                retry_attempts: 3,          // or derive from ProductionConfig if available
-                retry_delay_seconds: 5,     // or derive from ProductionConfig if available
-                save_startup_results: false, // or derive from ProductionConfig if available
-                results_file_path: "startup_results.json".to_string(), // or derive from ProductionConfig if available
+                ..StartupConfig::default()
             };
-            self.startup_validator = Some(StartupValidator::new(startup_config));
+            let mut validator = StartupValidator::new(startup_config);
+            if let Some(report) = self.startup_report.clone() {
+                validator = validator.with_report(report);
+            }
+            validator.add_steps(std::mem::take(&mut self.startup_steps));
+            self.startup_validator = Some(validator);
+            self.startup_state = ComponentState::new("pending");
         }
 
         // Initialize health checker
         if self.config.health_check_enabled {
-            self.health_checker = Some(HealthChecker::new(
+            let mut health_checker = HealthChecker::new(
                 self.config.health_check_interval,
                 self.config.health_check_timeout,
-            ));
+            );
+            for check in std::mem::take(&mut self.health_checks) {
+                health_checker.add_check(check);
+            }
+            self.health_checker = Some(health_checker);
         }
 
         // Initialize monitoring
         if self.config.monitoring_enabled {
-            let monitoring_config =
-                crate::infrastructure::operations::production::monitoring::MonitoringConfig {
-                    collection_interval_seconds: self.config.metrics_collection_interval.as_secs(),
-                    enable_detailed_logging: false, // or derive from ProductionConfig if available
-                    enable_profiling: false,        // or derive from ProductionConfig if available
-                    export_endpoints: vec![],       // or derive from ProductionConfig if available
-                    alert_thresholds:
-                        crate::infrastructure::operations::production::monitoring::AlertThresholds {
-                            cpu_threshold_percent: 80.0,
-                            memory_threshold_percent: 85.0,
-                            disk_threshold_percent: 90.0,
-                            error_rate_threshold: 0.05,
-                            response_time_threshold_ms: 1000.0,
-                        },
-                };
-            self.monitor = Some(ProductionMonitor::new(monitoring_config));
+            let monitoring_config = MonitoringConfig {
+                collection_interval_seconds: self.config.metrics_collection_interval.as_secs(),
+                enable_detailed_logging: false, // or derive from ProductionConfig if available
+                enable_profiling: false,        // or derive from ProductionConfig if available
+                export_endpoints: vec![],       // or derive from ProductionConfig if available
+                alert_thresholds: MonitoringConfig::default().alert_thresholds,
+            };
+            let mut monitor = ProductionMonitor::new(monitoring_config);
+            if let Some(request_metrics) = self.request_metrics.clone() {
+                monitor = monitor.with_request_metrics(request_metrics);
+            }
+            // Alerts are logged either way
+            if self.config.performance_alerts_enabled {
+                if let Some(webhooks) = self.tracker.webhooks.clone() {
+                    monitor = monitor.with_webhooks(webhooks);
+                }
+            }
+            self.monitor = Some(monitor);
         }
 
-        // Initialize graceful shutdown
-        if self.config.shutdown_signal_handlers {
-            let shutdown_config = crate::infrastructure::operations::production::shutdown::ShutdownConfig {
+        // Initialize graceful shutdown; whoever owns the process signals may
+        // turn off the handlers and call `shutdown` instead
+        let shutdown_config =
+            crate::infrastructure::operations::production::shutdown::ShutdownConfig {
                 grace_period_seconds: self.config.graceful_shutdown_timeout.as_secs(),
                 service_timeout_seconds: 30, // or derive from ProductionConfig if available
                 save_state_on_shutdown: false, // or derive from ProductionConfig if available
                 cleanup_timeout_seconds: 10, // or derive from ProductionConfig if available
-                enable_signal_handling: true, // or derive from ProductionConfig if available
-                shutdown_order: vec![],      // or derive from ProductionConfig if available
+                enable_signal_handling: self.config.shutdown_signal_handlers,
+                shutdown_order: vec![], // or derive from ProductionConfig if available
             };
-            self.shutdown_handler = Some(GracefulShutdown::new(shutdown_config));
-        }
+        self.shutdown_handler = Some(GracefulShutdown::new(shutdown_config));
 
+        self.publish_components().await;
         println!("✅ Production deployment components initialized");
         Ok(())
     }
@@ -199,15 +467,26 @@ impl ProductionDeployment {
         println!("🚀 Starting production deployment...");
 
         // Run startup validation
-        if let Some(validator) = &mut self.startup_validator {
+        if let Some(mut validator) = self.startup_validator.take() {
+            self.startup_state = ComponentState::new("running");
+            self.publish_components().await;
+
             let validation_result = validator.validate_startup().await;
             if !validation_result.success {
+                let failures = validation_result.critical_failures.join("; ");
+                self.startup_state = ComponentState::new("failed").with_detail(failures.clone());
+                self.publish_components().await;
+                self.tracker.transition(
+                    DeploymentStatus::Unhealthy,
+                    format!("startup validation failed: {}", failures),
+                );
                 return Err(format!(
                     "Startup validation failed: {:?}",
                     validation_result.critical_failures
                 )
                 .into());
             }
+            self.startup_state = ComponentState::new("passed");
             println!("✅ Startup validation passed");
         }
 
@@ -229,47 +508,94 @@ impl ProductionDeployment {
             println!("✅ Shutdown handlers configured");
         }
 
-        self.status = DeploymentStatus::Healthy;
+        self.started = true;
+        self.publish_components().await;
+        self.tracker
+            .transition(DeploymentStatus::Healthy, "deployment started");
         println!("🎉 Production deployment started successfully");
         Ok(())
     }
 
+    /// Start, then follow the health checks until a shutdown is requested
+    /// through the tracker or a signal handler, and shut down
+    pub async fn run(mut self) {
+        if let Err(e) = self.start().await {
+            tracing::warn!("Production deployment failed to start: {}", e);
+        }
+
+        let mut signals = self
+            .shutdown_handler
+            .as_ref()
+            .map(|handler| handler.subscribe());
+        let tracker = self.tracker.clone();
+        let mut ticker = tokio::time::interval(self.config.health_check_interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.check_status().await;
+                }
+                _ = tracker.shutdown_requested() => break,
+                _ = async {
+                    match &mut signals {
+                        Some(signals) => signals.recv().await.ok(),
+                        None => std::future::pending().await,
+                    }
+                } => break,
+            }
+        }
+
+        if let Err(e) = self.shutdown().await {
+            tracing::warn!("Production deployment failed to shut down: {}", e);
+        }
+    }
+
     /// Check current deployment status
+    ///
+    /// Follows the health checks once the deployment has started and until
+    /// it shuts down.
     pub async fn check_status(&mut self) -> DeploymentStatus {
-        if let Some(health_checker) = &self.health_checker {
-            match health_checker.get_latest_status().await {
-                HealthStatus::Healthy => {
-                    if self.status != DeploymentStatus::ShuttingDown {
-                        self.status = DeploymentStatus::Healthy;
+        if self.started {
+            if let Some(health_checker) = &self.health_checker {
+                if let Some(health) = health_checker.get_detailed_status().await {
+                    let status = match health.overall_status {
+                        HealthStatus::Healthy => DeploymentStatus::Healthy,
+                        HealthStatus::Degraded => DeploymentStatus::Degraded,
+                        HealthStatus::Unhealthy => DeploymentStatus::Unhealthy,
+                    };
+                    if !matches!(
+                        self.tracker.status(),
+                        DeploymentStatus::ShuttingDown | DeploymentStatus::Stopped
+                    ) {
+                        self.tracker.transition(
+                            status,
+                            format!(
+                                "health checks: {} healthy, {} degraded, {} unhealthy",
+                                health.healthy_count, health.degraded_count, health.unhealthy_count
+                            ),
+                        );
                     }
                 }
-                HealthStatus::Degraded => {
-                    self.status = DeploymentStatus::Degraded;
-                }
-                HealthStatus::Unhealthy => {
-                    self.status = DeploymentStatus::Unhealthy;
-                }
             }
         }
+        self.publish_components().await;
 
-        self.status.clone()
+        self.tracker.status()
     }
 
     /// Get current system metrics
     pub async fn get_metrics(&self) -> Option<SystemMetrics> {
-        if let Some(monitor) = &self.monitor {
-            Some(monitor.get_system_metrics())
-        } else {
-            None
-        }
+        self.monitor
+            .as_ref()
+            .map(|monitor| monitor.get_system_metrics())
     }
 
     /// Initiate graceful shutdown
     pub async fn shutdown(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         println!("🛑 Initiating graceful shutdown...");
-        self.status = DeploymentStatus::ShuttingDown;
+        self.tracker
+            .transition(DeploymentStatus::ShuttingDown, "shutdown requested");
 
-        if let Some(shutdown_handler) = &mut self.shutdown_handler {
+        if let Some(shutdown_handler) = &self.shutdown_handler {
             use crate::infrastructure::operations::production::ShutdownSignal;
             shutdown_handler
                 .initiate_shutdown(ShutdownSignal::Graceful)
@@ -282,13 +608,85 @@ impl ProductionDeployment {
             println!("✅ Health checking stopped");
         }
 
-        self.status = DeploymentStatus::Stopped;
+        if let Some(monitor) = &self.monitor {
+            monitor.stop_monitoring();
+        }
+
+        self.publish_components().await;
+        self.tracker
+            .transition(DeploymentStatus::Stopped, "shutdown completed");
         println!("✅ Graceful shutdown completed");
         Ok(())
     }
 
-    pub fn status(&self) -> &DeploymentStatus {
-        &self.status
+    pub fn status(&self) -> DeploymentStatus {
+        self.tracker.status()
+    }
+
+    /// Publish the components' current states to the tracker
+    async fn publish_components(&self) {
+        let mut components = BTreeMap::new();
+        components.insert("startup_validator".to_string(), self.startup_state.clone());
+
+        let health_checker = match &self.health_checker {
+            Some(health_checker) => {
+                let state = if health_checker.is_running().await {
+                    "running"
+                } else {
+                    "stopped"
+                };
+                match health_checker.get_detailed_status().await {
+                    Some(health) => ComponentState::new(state).with_detail(format!(
+                        "{} of {} checks healthy",
+                        health.healthy_count,
+                        health.checks.len()
+                    )),
+                    None => ComponentState::new(state),
+                }
+            }
+            None => ComponentState::disabled(),
+        };
+        components.insert("health_checker".to_string(), health_checker);
+
+        let monitor = match &self.monitor {
+            Some(monitor) if monitor.is_running() => {
+                let alerts = monitor.check_alerts();
+                let detail = if alerts.is_empty() {
+                    format!("up {}s", monitor.uptime().as_secs())
+                } else {
+                    let firing: Vec<_> = alerts
+                        .iter()
+                        .map(|alert| alert.alert_type.as_str())
+                        .collect();
+                    format!(
+                        "up {}s, alerting on {}",
+                        monitor.uptime().as_secs(),
+                        firing.join(", ")
+                    )
+                };
+                ComponentState::new("running").with_detail(detail)
+            }
+            Some(_) => ComponentState::new("stopped"),
+            None => ComponentState::disabled(),
+        };
+        components.insert("monitor".to_string(), monitor);
+
+        let shutdown_handler = match &self.shutdown_handler {
+            Some(shutdown_handler) => {
+                let state = shutdown_handler.get_state().await;
+                let component =
+                    ComponentState::new(format!("{:?}", state.current_phase).to_lowercase());
+                if self.config.shutdown_signal_handlers {
+                    component.with_detail("handling SIGINT and SIGTERM")
+                } else {
+                    component
+                }
+            }
+            None => ComponentState::disabled(),
+        };
+        components.insert("shutdown_handler".to_string(), shutdown_handler);
+
+        self.tracker.set_components(components);
     }
 }
 
@@ -317,4 +715,43 @@ mod tests {
         let result = deployment.initialize().await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_deployment_records_status_transitions() {
+        let config = ProductionConfig {
+            health_check_enabled: false,
+            monitoring_enabled: false,
+            shutdown_signal_handlers: false,
+            startup_validation_enabled: false,
+            ..Default::default()
+        };
+        let mut deployment = ProductionDeployment::new(config);
+        let tracker = deployment.tracker();
+        deployment.initialize().await.unwrap();
+        assert_eq!(
+            tracker.report().components["health_checker"].state,
+            "disabled"
+        );
+
+        deployment.start().await.unwrap();
+        assert_eq!(tracker.status(), DeploymentStatus::Healthy);
+
+        deployment.shutdown().await.unwrap();
+        let report = tracker.report();
+        assert_eq!(report.status, DeploymentStatus::Stopped);
+        let path: Vec<_> = report
+            .transitions
+            .iter()
+            .map(|transition| (transition.from, transition.to))
+            .collect();
+        assert_eq!(
+            path,
+            vec![
+                (DeploymentStatus::Starting, DeploymentStatus::Healthy),
+                (DeploymentStatus::Healthy, DeploymentStatus::ShuttingDown),
+                (DeploymentStatus::ShuttingDown, DeploymentStatus::Stopped),
+            ]
+        );
+        assert_eq!(report.components["shutdown_handler"].state, "complete");
+    }
 }
@@ -1,19 +1,45 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
+use sysinfo::System;
+
+use crate::application::services::webhook_service::WebhookService;
+use crate::infrastructure::persistence::webhook_registry::WebhookEvent;
+
+/// Fraction of its threshold a reading must fall below for a firing alert to
+/// resolve, so a value hovering at the threshold doesn't flap
+const RESOLVE_RATIO: f64 = 0.9;
+
+/// Most response times kept per collection interval for the p95
+const MAX_RESPONSE_SAMPLES: usize = 10_000;
 
 /// Production monitoring service
 pub struct ProductionMonitor {
-    /// System metrics collector
-    system_metrics: SystemMetrics,
+    /// Latest system metrics collected
+    system_metrics: Arc<RwLock<SystemMetrics>>,
 
-    /// Service-specific metrics
-    service_metrics: ServiceMetrics,
+    /// Latest service metrics collected
+    service_metrics: Arc<RwLock<ServiceMetrics>>,
 
     /// Monitoring configuration
     config: MonitoringConfig,
 
     /// Start time for uptime calculation
     start_time: Instant,
+
+    /// Whether the collection task is running
+    running: Arc<AtomicBool>,
+
+    /// Outcomes of the requests served, for the service metrics
+    requests: Option<RequestMetrics>,
+
+    /// Alerts currently firing
+    alerts: Arc<Mutex<AlertState>>,
+
+    /// Where alerts are delivered; without it they are only logged
+    webhooks: Option<WebhookService>,
 }
 
 /// System-level metrics
@@ -25,6 +51,9 @@ pub struct SystemMetrics {
     /// Memory usage in MB
     pub memory_usage_mb: f64,
 
+    /// Memory usage percentage
+    pub memory_usage_percent: f64,
+
     /// Available memory in MB
     pub memory_available_mb: f64,
 
@@ -164,49 +193,346 @@ impl Default for MonitoringConfig {
 impl ProductionMonitor {
     pub fn new(config: MonitoringConfig) -> Self {
         Self {
-            system_metrics: SystemMetrics::default(),
-            service_metrics: ServiceMetrics::default(),
+            system_metrics: Arc::new(RwLock::new(SystemMetrics::default())),
+            service_metrics: Arc::new(RwLock::new(ServiceMetrics::default())),
             config,
             start_time: Instant::now(),
+            running: Arc::new(AtomicBool::new(false)),
+            requests: None,
+            alerts: Arc::new(Mutex::new(AlertState::default())),
+            webhooks: None,
         }
     }
 
+    /// Derive request rate, error rate and response times from `requests`
+    pub fn with_request_metrics(mut self, requests: RequestMetrics) -> Self {
+        self.requests = Some(requests);
+        self
+    }
+
+    /// Deliver alerts and their resolutions to webhooks as `alert.triggered`
+    /// and `alert.resolved`
+    pub fn with_webhooks(mut self, webhooks: WebhookService) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
     /// Start monitoring background tasks
     pub async fn start_monitoring(&self) {
         let interval = Duration::from_secs(self.config.collection_interval_seconds);
+        let running = self.running.clone();
+        running.store(true, Ordering::SeqCst);
+
+        let collector = Collector {
+            system_metrics: self.system_metrics.clone(),
+            service_metrics: self.service_metrics.clone(),
+            thresholds: self.config.alert_thresholds.clone(),
+            start_time: self.start_time,
+            requests: self.requests.clone(),
+            alerts: self.alerts.clone(),
+            webhooks: self.webhooks.clone(),
+        };
 
         tokio::spawn(async move {
             let mut interval_timer = tokio::time::interval(interval);
+            let mut system = System::new();
+            let mut last_collected = Instant::now();
 
-            loop {
+            while running.load(Ordering::SeqCst) {
                 interval_timer.tick().await;
 
-                // Collect and report metrics
-                // PENDING: Full metrics collection implementation requires external monitoring system integration
-                println!("📊 Collecting production metrics...");
+                collector.collect(&mut system, last_collected.elapsed());
+                last_collected = Instant::now();
             }
         });
     }
 
+    /// Stop the collection task after its current interval
+    pub fn stop_monitoring(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether metrics are being collected in the background
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Time since the monitor was created
+    pub fn uptime(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
     /// Get current system metrics
     pub fn get_system_metrics(&self) -> SystemMetrics {
-        // PENDING: System metrics collection requires OS-specific implementation (Phase 5)
-        SystemMetrics::default()
+        self.system_metrics
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
     }
 
     /// Get current service metrics
     pub fn get_service_metrics(&self) -> ServiceMetrics {
-        // PENDING: Service-specific metrics require per-service instrumentation (Phase 5)
-        ServiceMetrics::default()
+        self.service_metrics
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
     }
 
-    /// Check if any alert thresholds are exceeded
+    /// Alerts whose thresholds are exceeded
     pub fn check_alerts(&self) -> Vec<AlertTriggered> {
-        let alerts = Vec::new();
+        self.alerts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .firing
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+/// What the collection task shares with the monitor
+struct Collector {
+    system_metrics: Arc<RwLock<SystemMetrics>>,
+    service_metrics: Arc<RwLock<ServiceMetrics>>,
+    thresholds: AlertThresholds,
+    start_time: Instant,
+    requests: Option<RequestMetrics>,
+    alerts: Arc<Mutex<AlertState>>,
+    webhooks: Option<WebhookService>,
+}
+
+impl Collector {
+    /// Sample the metrics, compare them with the thresholds and report
+    /// alerts that started or stopped firing
+    fn collect(&self, system: &mut System, elapsed: Duration) {
+        system.refresh_cpu_usage();
+        system.refresh_memory();
+        let total_memory_mb = system.total_memory() as f64 / 1024.0 / 1024.0;
+        let memory_usage_mb = system.used_memory() as f64 / 1024.0 / 1024.0;
+        let system_metrics = SystemMetrics {
+            cpu_usage_percent: system.global_cpu_info().cpu_usage() as f64,
+            memory_usage_mb,
+            memory_usage_percent: if total_memory_mb > 0.0 {
+                memory_usage_mb / total_memory_mb * 100.0
+            } else {
+                0.0
+            },
+            memory_available_mb: system.available_memory() as f64 / 1024.0 / 1024.0,
+            load_average: System::load_average().one,
+            uptime_seconds: self.start_time.elapsed().as_secs(),
+            ..SystemMetrics::default()
+        };
+        let service_metrics = self
+            .requests
+            .as_ref()
+            .map(|requests| requests.take(elapsed))
+            .unwrap_or_default();
+
+        let changes = self
+            .alerts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .update(&readings(
+                &self.thresholds,
+                &system_metrics,
+                &service_metrics,
+            ));
+        *self
+            .system_metrics
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = system_metrics;
+        *self
+            .service_metrics
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = service_metrics;
+
+        for change in changes {
+            match change {
+                AlertChange::Triggered(alert) => {
+                    tracing::warn!(alert = %alert.alert_type, "{}", alert.message);
+                    if let Some(webhooks) = &self.webhooks {
+                        webhooks.emit(WebhookEvent::AlertTriggered, serde_json::json!(alert));
+                    }
+                }
+                AlertChange::Resolved(resolved) => {
+                    tracing::info!(alert = %resolved.alert_type, "{}", resolved.message);
+                    if let Some(webhooks) = &self.webhooks {
+                        webhooks.emit(WebhookEvent::AlertResolved, serde_json::json!(resolved));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `(alert type, reading, threshold)` for each threshold
+fn readings(
+    thresholds: &AlertThresholds,
+    system: &SystemMetrics,
+    service: &ServiceMetrics,
+) -> Vec<(&'static str, f64, f64)> {
+    vec![
+        (
+            "cpu",
+            system.cpu_usage_percent,
+            thresholds.cpu_threshold_percent,
+        ),
+        (
+            "memory",
+            system.memory_usage_percent,
+            thresholds.memory_threshold_percent,
+        ),
+        (
+            "error_rate",
+            service.error_rate,
+            thresholds.error_rate_threshold,
+        ),
+        (
+            "response_time",
+            service.p95_response_time_ms,
+            thresholds.response_time_threshold_ms,
+        ),
+    ]
+}
+
+/// What an alert of `alert_type` measures
+fn label(alert_type: &str) -> &'static str {
+    match alert_type {
+        "cpu" => "CPU usage",
+        "memory" => "Memory usage",
+        "error_rate" => "Error rate",
+        _ => "p95 response time",
+    }
+}
+
+/// A reading or threshold of `alert_type` with its unit
+fn format_value(alert_type: &str, value: f64) -> String {
+    match alert_type {
+        "cpu" | "memory" => format!("{:.1}%", value),
+        "error_rate" => format!("{:.1}%", value * 100.0),
+        _ => format!("{:.0}ms", value),
+    }
+}
+
+/// Outcomes of the HTTP requests served, which the monitor turns into
+/// request rate, error rate and response times once per interval
+#[derive(Clone, Default)]
+pub struct RequestMetrics {
+    window: Arc<Mutex<RequestWindow>>,
+}
 
-        // PENDING: Alert system requires external alerting service integration (Phase 5)
+#[derive(Default)]
+struct RequestWindow {
+    total: u64,
+    requests: u64,
+    errors: u64,
+    response_times_ms: Vec<f64>,
+}
+
+impl RequestMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a response; server errors count towards the error rate
+    pub fn record(&self, status: u16, duration: Duration) {
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        window.total += 1;
+        window.requests += 1;
+        if status >= 500 {
+            window.errors += 1;
+        }
+        if window.response_times_ms.len() < MAX_RESPONSE_SAMPLES {
+            window
+                .response_times_ms
+                .push(duration.as_secs_f64() * 1000.0);
+        }
+    }
+
+    /// Metrics of the requests recorded over the last `elapsed`, starting a
+    /// new window
+    fn take(&self, elapsed: Duration) -> ServiceMetrics {
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        let requests = std::mem::take(&mut window.requests);
+        let errors = std::mem::take(&mut window.errors);
+        let mut response_times = std::mem::take(&mut window.response_times_ms);
+        response_times.sort_by(f64::total_cmp);
+
+        let (avg_response_time_ms, p95_response_time_ms) = if response_times.is_empty() {
+            (0.0, 0.0)
+        } else {
+            (
+                response_times.iter().sum::<f64>() / response_times.len() as f64,
+                response_times[(response_times.len() * 95).div_ceil(100) - 1],
+            )
+        };
+        ServiceMetrics {
+            total_requests: window.total,
+            requests_per_second: requests as f64 / elapsed.as_secs_f64().max(1.0),
+            avg_response_time_ms,
+            p95_response_time_ms,
+            error_rate: if requests == 0 {
+                0.0
+            } else {
+                errors as f64 / requests as f64
+            },
+            ..ServiceMetrics::default()
+        }
+    }
+}
+
+/// Alerts firing by type; each is reported once when its threshold is
+/// crossed and once when it resolves
+#[derive(Default)]
+struct AlertState {
+    firing: BTreeMap<String, AlertTriggered>,
+}
+
+enum AlertChange {
+    Triggered(AlertTriggered),
+    Resolved(AlertResolved),
+}
 
-        alerts
+impl AlertState {
+    fn update(&mut self, readings: &[(&'static str, f64, f64)]) -> Vec<AlertChange> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let mut changes = Vec::new();
+        for &(alert_type, value, threshold) in readings {
+            if let Some(alert) = self.firing.get(alert_type) {
+                if value < threshold * RESOLVE_RATIO {
+                    changes.push(AlertChange::Resolved(AlertResolved {
+                        alert_type: alert.alert_type.clone(),
+                        current_value: value,
+                        threshold_value: threshold,
+                        message: format!(
+                            "{} is back to {}",
+                            label(alert_type),
+                            format_value(alert_type, value)
+                        ),
+                        triggered_at: alert.triggered_at,
+                        resolved_at: now,
+                    }));
+                    self.firing.remove(alert_type);
+                }
+            } else if value > threshold {
+                let alert = AlertTriggered {
+                    alert_type: alert_type.to_string(),
+                    current_value: value,
+                    threshold_value: threshold,
+                    severity: AlertSeverity::Warning,
+                    message: format!(
+                        "{} {} is above the {} threshold",
+                        label(alert_type),
+                        format_value(alert_type, value),
+                        format_value(alert_type, threshold)
+                    ),
+                    triggered_at: now,
+                };
+                self.firing.insert(alert_type.to_string(), alert.clone());
+                changes.push(AlertChange::Triggered(alert));
+            }
+        }
+        changes
     }
 }
 
@@ -232,6 +558,25 @@ pub struct AlertTriggered {
     pub triggered_at: u64,
 }
 
+/// Alert that stopped firing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertResolved {
+    pub alert_type: String,
+
+    /// Value that resolved it
+    pub current_value: f64,
+
+    pub threshold_value: f64,
+
+    pub message: String,
+
+    /// Timestamp when the alert was triggered
+    pub triggered_at: u64,
+
+    /// Timestamp when it resolved
+    pub resolved_at: u64,
+}
+
 /// Alert severity levels
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AlertSeverity {
@@ -245,6 +590,7 @@ impl Default for SystemMetrics {
         Self {
             cpu_usage_percent: 0.0,
             memory_usage_mb: 0.0,
+            memory_usage_percent: 0.0,
             memory_available_mb: 0.0,
             disk_usage_percent: 0.0,
             network_io_bytes_per_sec: 0,
@@ -271,3 +617,50 @@ impl Default for ServiceMetrics {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alerts_fire_once_and_resolve_below_the_margin() {
+        let mut alerts = AlertState::default();
+        let changes = alerts.update(&[("cpu", 95.0, 80.0), ("memory", 40.0, 85.0)]);
+        assert!(
+            matches!(&changes[..], [AlertChange::Triggered(alert)] if alert.alert_type == "cpu")
+        );
+        assert_eq!(
+            alerts.firing["cpu"].message,
+            "CPU usage 95.0% is above the 80.0% threshold"
+        );
+
+        // Still above, then just under the threshold: nothing new to report
+        assert!(alerts.update(&[("cpu", 90.0, 80.0)]).is_empty());
+        assert!(alerts.update(&[("cpu", 75.0, 80.0)]).is_empty());
+
+        let changes = alerts.update(&[("cpu", 50.0, 80.0)]);
+        assert!(
+            matches!(&changes[..], [AlertChange::Resolved(resolved)] if resolved.message == "CPU usage is back to 50.0%")
+        );
+        assert!(alerts.firing.is_empty());
+    }
+
+    #[test]
+    fn test_request_metrics_cover_one_window() {
+        let requests = RequestMetrics::new();
+        for millis in 1..=19 {
+            requests.record(200, Duration::from_millis(millis));
+        }
+        requests.record(503, Duration::from_millis(100));
+
+        let metrics = requests.take(Duration::from_secs(10));
+        assert_eq!(metrics.total_requests, 20);
+        assert_eq!(metrics.requests_per_second, 2.0);
+        assert_eq!(metrics.error_rate, 0.05);
+        assert_eq!(metrics.p95_response_time_ms, 19.0);
+
+        let metrics = requests.take(Duration::from_secs(10));
+        assert_eq!(metrics.total_requests, 20);
+        assert_eq!(metrics.error_rate, 0.0);
+    }
+}
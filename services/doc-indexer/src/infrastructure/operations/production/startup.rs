@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
+use zero_latency_core::models::HealthStatus;
+
+use crate::application::ServiceContainer;
 
 /// Startup validator coordinates system initialization and validation
 pub struct StartupValidator {
@@ -12,6 +16,12 @@ pub struct StartupValidator {
 
     /// Results from validation
     results: HashMap<String, ValidationResult>,
+
+    /// Outcome of each step, in the order the steps were added
+    checks: Vec<StartupCheck>,
+
+    /// Where the latest result is published, if anywhere
+    report: Option<StartupReport>,
 }
 
 /// Individual validation step
@@ -84,6 +94,55 @@ pub struct ValidationResult {
     pub completed_at: u64,
 }
 
+impl ValidationResult {
+    /// Result of a step that completed without problems
+    pub fn passed(step_id: &str, message: impl Into<String>) -> Self {
+        Self::new(step_id, true, message.into())
+    }
+
+    /// Result of a step that found a problem
+    pub fn failed(step_id: &str, message: impl Into<String>) -> Self {
+        Self::new(step_id, false, message.into())
+    }
+
+    fn new(step_id: &str, passed: bool, message: String) -> Self {
+        Self {
+            step_id: step_id.to_string(),
+            passed,
+            message,
+            execution_time_ms: 0,
+            details: HashMap::new(),
+            warnings: Vec::new(),
+            completed_at: chrono::Utc::now().timestamp() as u64,
+        }
+    }
+}
+
+/// How a validation step ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckOutcome {
+    Passed,
+    Failed,
+    /// Not run because a required step failed before it
+    Skipped,
+}
+
+/// Outcome of one validation step, as reported by `/health/startup`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupCheck {
+    pub id: String,
+    pub name: String,
+    /// Whether the service can't become ready while this step fails
+    pub required: bool,
+    pub outcome: CheckOutcome,
+    pub message: String,
+    /// Time spent on the step, over all attempts
+    pub duration_ms: u64,
+    pub attempts: u64,
+    pub warnings: Vec<String>,
+}
+
 /// Complete startup result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StartupResult {
@@ -96,6 +155,10 @@ pub struct StartupResult {
     /// Results from all validation steps
     pub step_results: HashMap<String, ValidationResult>,
 
+    /// Outcome of every step in order, including skipped ones
+    #[serde(default)]
+    pub checks: Vec<StartupCheck>,
+
     /// Critical failures (if any)
     pub critical_failures: Vec<String>,
 
@@ -179,25 +242,114 @@ impl Default for StartupConfig {
             save_startup_results: std::env::var("STARTUP_SAVE_RESULTS")
                 .ok()
                 .map(|v| v.to_lowercase() == "true")
-                .unwrap_or(true),
+                .unwrap_or(false),
             results_file_path: std::env::var("STARTUP_RESULTS_FILE")
                 .unwrap_or_else(|_| "./data/startup_results.json".to_string()),
         }
     }
 }
 
+/// Where startup validation stands, as served at `/health/startup`
+///
+/// System information is left out, since the endpoint needs no credentials.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupStatus {
+    /// `disabled`, `running`, `passed` or `failed`
+    pub status: String,
+    /// When validation started, in seconds since the epoch
+    pub started_at: Option<u64>,
+    pub total_duration_ms: Option<u64>,
+    pub checks: Vec<StartupCheck>,
+    pub critical_failures: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+enum StartupState {
+    Disabled,
+    Running { started_at: u64 },
+    Finished(Box<StartupResult>),
+}
+
+/// Latest startup validation result, shared between the validator and the
+/// health endpoints
+///
+/// Until a validator reports to it, startup validation counts as disabled
+/// and doesn't hold back readiness.
+#[derive(Debug, Clone)]
+pub struct StartupReport {
+    state: Arc<RwLock<StartupState>>,
+}
+
+impl Default for StartupReport {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(RwLock::new(StartupState::Disabled)),
+        }
+    }
+}
+
+impl StartupReport {
+    fn begin(&self, started_at: u64) {
+        *self.state.write().unwrap_or_else(|e| e.into_inner()) =
+            StartupState::Running { started_at };
+    }
+
+    fn finish(&self, result: StartupResult) {
+        *self.state.write().unwrap_or_else(|e| e.into_inner()) =
+            StartupState::Finished(Box::new(result));
+    }
+
+    /// Whether startup validation lets the service become ready: it passed
+    /// or isn't enabled
+    pub fn ready(&self) -> bool {
+        match &*self.state.read().unwrap_or_else(|e| e.into_inner()) {
+            StartupState::Disabled => true,
+            StartupState::Running { .. } => false,
+            StartupState::Finished(result) => result.success,
+        }
+    }
+
+    /// Current state of startup validation
+    pub fn status(&self) -> StartupStatus {
+        let status = |status: &str, started_at| StartupStatus {
+            status: status.to_string(),
+            started_at,
+            total_duration_ms: None,
+            checks: Vec::new(),
+            critical_failures: Vec::new(),
+            warnings: Vec::new(),
+        };
+        match &*self.state.read().unwrap_or_else(|e| e.into_inner()) {
+            StartupState::Disabled => status("disabled", None),
+            StartupState::Running { started_at } => status("running", Some(*started_at)),
+            StartupState::Finished(result) => StartupStatus {
+                status: if result.success { "passed" } else { "failed" }.to_string(),
+                started_at: Some(result.startup_timestamp),
+                total_duration_ms: Some(result.total_startup_time_ms),
+                checks: result.checks.clone(),
+                critical_failures: result.critical_failures.clone(),
+                warnings: result.warnings.clone(),
+            },
+        }
+    }
+}
+
 impl StartupValidator {
     pub fn new(config: StartupConfig) -> Self {
         Self {
             validation_steps: Vec::new(),
             config,
             results: HashMap::new(),
+            checks: Vec::new(),
+            report: None,
         }
     }
 
-    /// Add a validation step
-    pub fn add_step(&mut self, step: ValidationStep) {
-        self.validation_steps.push(step);
+    /// Publish the progress and result of validation to `report`
+    pub fn with_report(mut self, report: StartupReport) -> Self {
+        self.report = Some(report);
+        self
     }
 
     /// Add multiple validation steps
@@ -211,6 +363,9 @@ impl StartupValidator {
         let startup_timestamp = chrono::Utc::now().timestamp() as u64;
 
         println!("🚀 Starting system validation...");
+        if let Some(report) = &self.report {
+            report.begin(startup_timestamp);
+        }
 
         // Collect system information
         let system_info = self.collect_system_info().await;
@@ -231,6 +386,7 @@ impl StartupValidator {
             success: validation_success && success,
             total_startup_time_ms: total_time,
             step_results: self.results.clone(),
+            checks: self.checks.clone(),
             critical_failures,
             warnings,
             system_info,
@@ -244,6 +400,10 @@ impl StartupValidator {
             }
         }
 
+        if let Some(report) = &self.report {
+            report.finish(startup_result.clone());
+        }
+
         if startup_result.success {
             println!(
                 "✅ System validation completed successfully in {}ms",
@@ -273,11 +433,38 @@ impl StartupValidator {
                 total_steps,
                 step.name
             );
-            let result = self.run_validation_step(step).await;
+            let (result, attempts) = self.run_validation_step(step).await;
             let passed = result.passed;
+            self.checks.push(StartupCheck {
+                id: step.id.clone(),
+                name: step.name.clone(),
+                required: step.required,
+                outcome: if passed {
+                    CheckOutcome::Passed
+                } else {
+                    CheckOutcome::Failed
+                },
+                message: result.message.clone(),
+                duration_ms: result.execution_time_ms,
+                attempts,
+                warnings: result.warnings.clone(),
+            });
             self.results.insert(step.id.clone(), result.clone());
             if !passed && step.required {
                 println!("❌ Critical validation step failed: {}", step.name);
+                let skipped = self.validation_steps[index + 1..]
+                    .iter()
+                    .map(|step| StartupCheck {
+                        id: step.id.clone(),
+                        name: step.name.clone(),
+                        required: step.required,
+                        outcome: CheckOutcome::Skipped,
+                        message: "Not run after a required step failed".to_string(),
+                        duration_ms: 0,
+                        attempts: 0,
+                        warnings: Vec::new(),
+                    });
+                self.checks.extend(skipped);
                 return false;
             } else if !passed {
                 println!("⚠️  Non-critical validation step failed: {}", step.name);
@@ -288,8 +475,10 @@ impl StartupValidator {
         true
     }
 
-    /// Run a single validation step with retries
-    async fn run_validation_step(&self, step: &ValidationStep) -> ValidationResult {
+    /// Run a single validation step with retries, returning the last result,
+    /// timed over all attempts, and the number of attempts
+    async fn run_validation_step(&self, step: &ValidationStep) -> (ValidationResult, u64) {
+        let started = std::time::Instant::now();
         let mut attempts = 0;
         let max_attempts = if step.required {
             self.config.retry_attempts + 1
@@ -299,7 +488,6 @@ impl StartupValidator {
 
         loop {
             attempts += 1;
-            let start_time = std::time::Instant::now();
 
             // Run validation with timeout
             let timeout_duration = Duration::from_secs(step.timeout_seconds);
@@ -322,7 +510,11 @@ impl StartupValidator {
             };
 
             if result.passed || attempts >= max_attempts {
-                return result;
+                let result = ValidationResult {
+                    execution_time_ms: started.elapsed().as_millis() as u64,
+                    ..result
+                };
+                return (result, attempts);
             }
 
             println!(
@@ -408,140 +600,107 @@ impl StartupValidator {
         Ok(())
     }
 
-    /// Create standard validation steps for the doc-indexer service
-    pub fn create_standard_steps() -> Vec<ValidationStep> {
+    /// Validation steps checking the dependencies of a running service
+    ///
+    /// The vector store and the embedding generator are required: the service
+    /// can't search without them. A missing docs directory only warns, since
+    /// documents can still be indexed through the API.
+    pub fn service_steps(container: &ServiceContainer) -> Vec<ValidationStep> {
+        let vector_store = container.vector_repository();
+        let embedding_generator = container.embedding_generator();
+        let docs_path = container.config().service.docs_path.clone();
+
         vec![
             ValidationStep {
-                id: "config_validation".to_string(),
-                name: "Configuration Validation".to_string(),
-                description: "Validate service configuration".to_string(),
-                required: true,
-                timeout_seconds: 10,
-                dependencies: Vec::new(),
-                validator: Box::new(|| {
-                    Box::new(Box::pin(async {
-                        // TODO: Implement actual config validation
-                        ValidationResult {
-                            step_id: "config_validation".to_string(),
-                            passed: true,
-                            message: "Configuration is valid".to_string(),
-                            execution_time_ms: 50,
-                            details: HashMap::new(),
-                            warnings: Vec::new(),
-                            completed_at: chrono::Utc::now().timestamp() as u64,
-                        }
-                    }))
-                }),
-            },
-            ValidationStep {
-                id: "database_connectivity".to_string(),
-                name: "Database Connectivity".to_string(),
-                description: "Test database connection".to_string(),
+                id: "vector_store".to_string(),
+                name: "Vector Store".to_string(),
+                description: "Check that the vector store is reachable and healthy".to_string(),
                 required: true,
                 timeout_seconds: 30,
-                dependencies: vec!["config_validation".to_string()],
-                validator: Box::new(|| {
-                    Box::new(Box::pin(async {
-                        // TODO: Implement actual database connectivity test
-                        ValidationResult {
-                            step_id: "database_connectivity".to_string(),
-                            passed: true,
-                            message: "Database connection successful".to_string(),
-                            execution_time_ms: 200,
-                            details: HashMap::new(),
-                            warnings: Vec::new(),
-                            completed_at: chrono::Utc::now().timestamp() as u64,
-                        }
-                    }))
-                }),
-            },
-            ValidationStep {
-                id: "vector_store_initialization".to_string(),
-                name: "Vector Store Initialization".to_string(),
-                description: "Initialize and test vector store".to_string(),
-                required: true,
-                timeout_seconds: 60,
-                dependencies: vec!["config_validation".to_string()],
-                validator: Box::new(|| {
-                    Box::new(Box::pin(async {
-                        // TODO: Implement actual vector store initialization
-                        ValidationResult {
-                            step_id: "vector_store_initialization".to_string(),
-                            passed: true,
-                            message: "Vector store initialized successfully".to_string(),
-                            execution_time_ms: 1500,
-                            details: HashMap::new(),
-                            warnings: Vec::new(),
-                            completed_at: chrono::Utc::now().timestamp() as u64,
+                dependencies: Vec::new(),
+                validator: Box::new(move || {
+                    let vector_store = vector_store.clone();
+                    Box::new(Box::pin(async move {
+                        const ID: &str = "vector_store";
+                        match vector_store.health_check().await {
+                            Ok(HealthStatus::Healthy) => {
+                                ValidationResult::passed(ID, "Vector store is healthy")
+                            }
+                            Ok(HealthStatus::Degraded { message }) => ValidationResult {
+                                warnings: vec![message],
+                                ..ValidationResult::passed(ID, "Vector store is degraded")
+                            },
+                            Ok(HealthStatus::Unhealthy { message }) => {
+                                ValidationResult::failed(ID, message)
+                            }
+                            Err(e) => ValidationResult::failed(
+                                ID,
+                                format!("Vector store health check failed: {}", e),
+                            ),
                         }
                     }))
                 }),
             },
             ValidationStep {
-                id: "embedding_service_check".to_string(),
-                name: "Embedding Service Check".to_string(),
-                description: "Test embedding service connectivity".to_string(),
+                id: "embedding_generator".to_string(),
+                name: "Embedding Generator".to_string(),
+                description: "Embed a probe text with the configured model".to_string(),
                 required: true,
                 timeout_seconds: 30,
-                dependencies: vec!["config_validation".to_string()],
-                validator: Box::new(|| {
-                    Box::new(Box::pin(async {
-                        // TODO: Implement actual embedding service check
-                        ValidationResult {
-                            step_id: "embedding_service_check".to_string(),
-                            passed: true,
-                            message: "Embedding service is accessible".to_string(),
-                            execution_time_ms: 800,
-                            details: HashMap::new(),
-                            warnings: Vec::new(),
-                            completed_at: chrono::Utc::now().timestamp() as u64,
-                        }
-                    }))
-                }),
-            },
-            ValidationStep {
-                id: "memory_pool_initialization".to_string(),
-                name: "Memory Pool Initialization".to_string(),
-                description: "Initialize memory optimization pools".to_string(),
-                required: false, // Non-critical
-                timeout_seconds: 15,
                 dependencies: Vec::new(),
-                validator: Box::new(|| {
-                    Box::new(Box::pin(async {
-                        // TODO: Implement actual memory pool initialization
-                        ValidationResult {
-                            step_id: "memory_pool_initialization".to_string(),
-                            passed: true,
-                            message: "Memory pools initialized".to_string(),
-                            execution_time_ms: 300,
-                            details: HashMap::new(),
-                            warnings: Vec::new(),
-                            completed_at: chrono::Utc::now().timestamp() as u64,
+                validator: Box::new(move || {
+                    let embedding_generator = embedding_generator.clone();
+                    Box::new(Box::pin(async move {
+                        const ID: &str = "embedding_generator";
+                        let expected = embedding_generator.dimension();
+                        match embedding_generator
+                            .generate_embedding("startup check")
+                            .await
+                        {
+                            Ok(embedding) if embedding.len() == expected => {
+                                let mut result = ValidationResult::passed(
+                                    ID,
+                                    format!("{} is available", embedding_generator.model_name()),
+                                );
+                                result
+                                    .details
+                                    .insert("dimension".to_string(), expected.into());
+                                result
+                            }
+                            Ok(embedding) => ValidationResult::failed(
+                                ID,
+                                format!(
+                                    "{} returned {} dimensions instead of {}",
+                                    embedding_generator.model_name(),
+                                    embedding.len(),
+                                    expected
+                                ),
+                            ),
+                            Err(e) => {
+                                ValidationResult::failed(ID, format!("Embedding failed: {}", e))
+                            }
                         }
                     }))
                 }),
             },
             ValidationStep {
-                id: "health_check_endpoints".to_string(),
-                name: "Health Check Endpoints".to_string(),
-                description: "Verify health check endpoints are responsive".to_string(),
-                required: false, // Non-critical
-                timeout_seconds: 10,
-                dependencies: vec![
-                    "vector_store_initialization".to_string(),
-                    "embedding_service_check".to_string(),
-                ],
-                validator: Box::new(|| {
-                    Box::new(Box::pin(async {
-                        // TODO: Implement actual health check validation
-                        ValidationResult {
-                            step_id: "health_check_endpoints".to_string(),
-                            passed: true,
-                            message: "Health check endpoints are responsive".to_string(),
-                            execution_time_ms: 100,
-                            details: HashMap::new(),
-                            warnings: Vec::new(),
-                            completed_at: chrono::Utc::now().timestamp() as u64,
+                id: "docs_path".to_string(),
+                name: "Documentation Directory".to_string(),
+                description: "Check that the documentation directory exists".to_string(),
+                required: false,
+                timeout_seconds: 5,
+                dependencies: Vec::new(),
+                validator: Box::new(move || {
+                    let docs_path = docs_path.clone();
+                    Box::new(Box::pin(async move {
+                        const ID: &str = "docs_path";
+                        if docs_path.is_dir() {
+                            ValidationResult::passed(ID, format!("{} exists", docs_path.display()))
+                        } else {
+                            ValidationResult::failed(
+                                ID,
+                                format!("{} is not a directory", docs_path.display()),
+                            )
                         }
                     }))
                 }),
@@ -563,13 +722,73 @@ mod tests {
         assert_eq!(validator.results.len(), 0);
     }
 
-    #[test]
-    fn test_standard_validation_steps() {
-        let steps = StartupValidator::create_standard_steps();
+    fn step(id: &'static str, required: bool, passes: bool) -> ValidationStep {
+        ValidationStep {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            required,
+            timeout_seconds: 5,
+            dependencies: Vec::new(),
+            validator: Box::new(move || {
+                Box::new(Box::pin(async move {
+                    if passes {
+                        ValidationResult::passed(id, "ok")
+                    } else {
+                        ValidationResult::failed(id, "unreachable")
+                    }
+                }))
+            }),
+        }
+    }
 
-        assert!(!steps.is_empty());
-        assert!(steps.iter().any(|s| s.id == "config_validation"));
-        assert!(steps.iter().any(|s| s.id == "vector_store_initialization"));
+    #[tokio::test]
+    async fn test_report_holds_back_readiness_until_validation_passes() {
+        let config = StartupConfig {
+            retry_attempts: 1,
+            retry_delay_seconds: 0,
+            save_startup_results: false,
+            ..StartupConfig::default()
+        };
+        let report = StartupReport::default();
+        assert!(report.ready());
+        assert_eq!(report.status().status, "disabled");
+
+        let mut validator = StartupValidator::new(config.clone()).with_report(report.clone());
+        validator.add_steps(vec![
+            step("optional", false, false),
+            step("store", true, false),
+            step("embeddings", true, true),
+        ]);
+        let result = validator.validate_startup().await;
+        assert!(!result.success);
+        assert!(!report.ready());
+
+        let status = report.status();
+        assert_eq!(status.status, "failed");
+        let outcomes: Vec<_> = status
+            .checks
+            .iter()
+            .map(|check| (check.id.as_str(), check.outcome, check.attempts))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("optional", CheckOutcome::Failed, 1),
+                ("store", CheckOutcome::Failed, 2),
+                ("embeddings", CheckOutcome::Skipped, 0),
+            ]
+        );
+        assert_eq!(status.critical_failures, vec!["store: unreachable"]);
+
+        let mut validator = StartupValidator::new(config).with_report(report.clone());
+        validator.add_steps(vec![
+            step("optional", false, false),
+            step("store", true, true),
+        ]);
+        assert!(validator.validate_startup().await.success);
+        assert!(report.ready());
+        assert_eq!(report.status().status, "passed");
     }
 
     #[test]
@@ -0,0 +1,364 @@
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+/// Per-tenant usage metering
+///
+/// Counts what each tenant consumes so hosted deployments can bill or charge
+/// back internally: searches run, bytes read for indexing, vectors stored and
+/// the tokens sent to the embedding model. Usage is added up per tenant and
+/// UTC day and exported per day or month. Tokens are estimated from text
+/// length, as the embedding backends don't all report them. Totals are kept
+/// next to the vector store and written out periodically, so a crash loses
+/// at most the usage of the last flush interval.
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use crate::infrastructure::api::http::tenant::current_tenant;
+use crate::infrastructure::persistence::write_json_atomic;
+
+/// Usage over some period
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageCounters {
+    pub searches: u64,
+    /// Bytes of source files and documents read for indexing
+    pub indexed_bytes: u64,
+    /// Vectors written to the store
+    pub stored_vectors: u64,
+    /// Estimated tokens embedded, for documents and queries
+    pub embedding_tokens: u64,
+}
+
+impl UsageCounters {
+    fn add(&mut self, other: &UsageCounters) {
+        self.searches += other.searches;
+        self.indexed_bytes += other.indexed_bytes;
+        self.stored_vectors += other.stored_vectors;
+        self.embedding_tokens += other.embedding_tokens;
+    }
+}
+
+/// Length of the periods usage is exported in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsagePeriod {
+    Day,
+    #[default]
+    Month,
+}
+
+impl UsagePeriod {
+    /// First day of the period holding `day`
+    fn start(&self, day: NaiveDate) -> NaiveDate {
+        match self {
+            UsagePeriod::Day => day,
+            UsagePeriod::Month => day.with_day(1).unwrap_or(day),
+        }
+    }
+
+    /// Last day of the period starting on `start`
+    fn end(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            UsagePeriod::Day => start,
+            UsagePeriod::Month => start
+                .checked_add_months(chrono::Months::new(1))
+                .and_then(|next| next.pred_opt())
+                .unwrap_or(start),
+        }
+    }
+}
+
+/// Usage of one tenant in one period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub tenant: String,
+    pub period_start: NaiveDate,
+    /// Last day of the period, inclusive
+    pub period_end: NaiveDate,
+    #[serde(flatten)]
+    pub usage: UsageCounters,
+}
+
+/// Usage of a tenant on a day, as stored
+#[derive(Serialize, Deserialize)]
+struct StoredUsage {
+    tenant: String,
+    day: NaiveDate,
+    #[serde(flatten)]
+    usage: UsageCounters,
+}
+
+/// Usage totals per tenant and day
+#[derive(Debug)]
+pub struct UsageMeter {
+    days: Mutex<BTreeMap<(String, NaiveDate), UsageCounters>>,
+    // Set when there is usage the backing file doesn't have yet
+    dirty: AtomicBool,
+    // Backing file, or None when usage must not outlive the process
+    path: Option<PathBuf>,
+}
+
+impl UsageMeter {
+    /// Create a meter that only lives in memory
+    pub fn in_memory() -> Self {
+        Self {
+            days: Mutex::new(BTreeMap::new()),
+            dirty: AtomicBool::new(false),
+            path: None,
+        }
+    }
+
+    /// Load usage from disk, starting empty if the file does not exist
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut days = BTreeMap::new();
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| ZeroLatencyError::database(format!("Failed to read usage: {}", e)))?;
+            let stored: Vec<StoredUsage> = serde_json::from_str(&content)
+                .map_err(|e| ZeroLatencyError::database(format!("Failed to parse usage: {}", e)))?;
+            for entry in stored {
+                days.insert((entry.tenant, entry.day), entry.usage);
+            }
+        }
+
+        Ok(Self {
+            days: Mutex::new(days),
+            dirty: AtomicBool::new(false),
+            path: Some(path),
+        })
+    }
+
+    /// Add usage of the current request's tenant today
+    pub fn record(&self, usage: UsageCounters) {
+        self.record_for(&current_tenant(), Utc::now().date_naive(), usage);
+    }
+
+    /// Add usage of `tenant` on `day`
+    pub fn record_for(&self, tenant: &str, day: NaiveDate, usage: UsageCounters) {
+        if usage == UsageCounters::default() {
+            return;
+        }
+        self.lock()
+            .entry((tenant.to_string(), day))
+            .or_default()
+            .add(&usage);
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Usage from `from` to `to` (inclusive) per tenant and period
+    ///
+    /// Records are ordered by period, then tenant. Periods are whole months
+    /// or days even when `from` or `to` fall inside one; only the days in
+    /// range are counted.
+    pub fn export(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        period: UsagePeriod,
+        tenant: Option<&str>,
+    ) -> Vec<UsageRecord> {
+        let mut totals: BTreeMap<(NaiveDate, String), UsageCounters> = BTreeMap::new();
+        for ((day_tenant, day), usage) in self.lock().iter() {
+            if *day < from || *day > to || tenant.is_some_and(|wanted| wanted != day_tenant) {
+                continue;
+            }
+            totals
+                .entry((period.start(*day), day_tenant.clone()))
+                .or_default()
+                .add(usage);
+        }
+
+        totals
+            .into_iter()
+            .map(|((period_start, tenant), usage)| UsageRecord {
+                tenant,
+                period_start,
+                period_end: period.end(period_start),
+                usage,
+            })
+            .collect()
+    }
+
+    /// Persist usage recorded since the last save, if there is a backing file
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let stored: Vec<StoredUsage> = self
+            .lock()
+            .iter()
+            .map(|((tenant, day), usage)| StoredUsage {
+                tenant: tenant.clone(),
+                day: *day,
+                usage: *usage,
+            })
+            .collect();
+        let result = write_json_atomic(path, &stored, "usage");
+        if result.is_err() {
+            // Try again on the next save
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+
+    /// Save usage every `interval` in the background
+    pub fn spawn_flusher(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let meter = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = meter.save() {
+                    tracing::warn!("Failed to persist usage: {}", e);
+                }
+            }
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<(String, NaiveDate), UsageCounters>> {
+        self.days.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Usage records as CSV with a header row
+pub fn to_csv(records: &[UsageRecord]) -> String {
+    let mut csv = String::from(
+        "tenant,period_start,period_end,searches,indexed_bytes,stored_vectors,embedding_tokens\n",
+    );
+    for record in records {
+        // Tenant IDs are restricted to characters that need no quoting
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            record.tenant,
+            record.period_start,
+            record.period_end,
+            record.usage.searches,
+            record.usage.indexed_bytes,
+            record.usage.stored_vectors,
+            record.usage.embedding_tokens,
+        ));
+    }
+    csv
+}
+
+/// Estimated tokens of `text` for an embedding model
+///
+/// About four characters per token, the usual rule of thumb for English
+/// text with subword tokenizers.
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn searches(n: u64) -> UsageCounters {
+        UsageCounters {
+            searches: n,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_exports_per_period() {
+        let meter = UsageMeter::in_memory();
+        meter.record_for("acme", day("2025-01-30"), searches(2));
+        meter.record_for("acme", day("2025-01-31"), searches(3));
+        meter.record_for("acme", day("2025-02-01"), searches(5));
+        meter.record_for(
+            "globex",
+            day("2025-01-15"),
+            UsageCounters {
+                indexed_bytes: 100,
+                stored_vectors: 4,
+                embedding_tokens: 25,
+                ..Default::default()
+            },
+        );
+
+        let monthly = meter.export(
+            day("2025-01-01"),
+            day("2025-12-31"),
+            UsagePeriod::Month,
+            None,
+        );
+        let summary: Vec<_> = monthly
+            .iter()
+            .map(|r| {
+                (
+                    r.tenant.as_str(),
+                    r.period_start,
+                    r.period_end,
+                    r.usage.searches,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("acme", day("2025-01-01"), day("2025-01-31"), 5),
+                ("globex", day("2025-01-01"), day("2025-01-31"), 0),
+                ("acme", day("2025-02-01"), day("2025-02-28"), 5),
+            ]
+        );
+        assert_eq!(monthly[1].usage.stored_vectors, 4);
+
+        // Only days in range count, and a tenant can be singled out
+        let daily = meter.export(
+            day("2025-01-31"),
+            day("2025-02-01"),
+            UsagePeriod::Day,
+            Some("acme"),
+        );
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].period_end, day("2025-01-31"));
+        assert_eq!(daily[0].usage.searches, 3);
+    }
+
+    #[test]
+    fn test_formats_csv() {
+        let meter = UsageMeter::in_memory();
+        meter.record_for("acme", day("2025-01-02"), searches(7));
+        let records = meter.export(
+            day("2025-01-01"),
+            day("2025-01-31"),
+            UsagePeriod::Month,
+            None,
+        );
+        assert_eq!(
+            to_csv(&records),
+            "tenant,period_start,period_end,searches,indexed_bytes,stored_vectors,embedding_tokens\n\
+             acme,2025-01-01,2025-01-31,7,0,0,0\n"
+        );
+        assert_eq!(estimate_tokens("sixteen chars..."), 4);
+        assert_eq!(estimate_tokens("a"), 1);
+    }
+
+    #[test]
+    fn test_persists_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("usage.json");
+
+        let meter = UsageMeter::load(&path).unwrap();
+        meter.record_for("acme", day("2025-01-02"), searches(1));
+        meter.save().unwrap();
+
+        let reloaded = UsageMeter::load(&path).unwrap();
+        let records = reloaded.export(day("2025-01-01"), day("2025-01-31"), UsagePeriod::Day, None);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].usage.searches, 1);
+    }
+}
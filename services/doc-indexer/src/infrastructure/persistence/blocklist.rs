@@ -0,0 +1,237 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+/// Query-time blocklist
+///
+/// Hides documents from a collection's searches without deleting them, so
+/// deprecated pages can disappear at once while their owners clean up.
+/// Entries name documents by path pattern, as in the `path` metadata filter,
+/// or by document ID or URI. They become search exclusions, which every
+/// retrieval step applies before fusion. Entries are keyed by the public
+/// collection name and persisted next to the vector store.
+use std::path::PathBuf;
+use zero_latency_core::{DateTime, Result, Utc, Uuid, ZeroLatencyError};
+use zero_latency_search::Exclusions;
+use zero_latency_vector::{MetadataFilter, PATH_KEY};
+
+use super::write_json_atomic;
+
+/// A document or path pattern hidden from a collection's searches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockEntry {
+    pub id: String,
+    pub collection: String,
+    /// Path pattern of the hidden documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// ID or URI of the hidden document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document: Option<String>,
+    /// Why the documents are hidden, for whoever cleans up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An entry to add; exactly one of `path` and `document` must be set
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewBlockEntry {
+    pub collection: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub document: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Blocklist entries of every collection, keyed by entry ID
+pub struct Blocklist {
+    entries: DashMap<String, BlockEntry>,
+    // Backing file, or None when entries must not outlive the process
+    path: Option<PathBuf>,
+}
+
+impl Blocklist {
+    /// Create a blocklist that only lives in memory
+    pub fn in_memory() -> Self {
+        Self {
+            entries: DashMap::new(),
+            path: None,
+        }
+    }
+
+    /// Load a blocklist from disk, starting empty if the file does not exist
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut entries = DashMap::new();
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read blocklist: {}", e))
+            })?;
+            let stored: Vec<BlockEntry> = serde_json::from_str(&content).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to parse blocklist: {}", e))
+            })?;
+            entries.extend(stored.into_iter().map(|entry| (entry.id.clone(), entry)));
+        }
+
+        Ok(Self {
+            entries,
+            path: Some(path),
+        })
+    }
+
+    /// Entries of one collection, or of every collection, oldest first
+    pub fn list(&self, collection: Option<&str>) -> Vec<BlockEntry> {
+        let mut entries: Vec<BlockEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| collection.is_none_or(|name| entry.collection == name))
+            .map(|entry| entry.value().clone())
+            .collect();
+        entries.sort_by_key(|entry| entry.created_at);
+        entries
+    }
+
+    /// Search exclusions hiding a collection's blocked documents
+    pub fn exclusions(&self, collection: &str) -> Exclusions {
+        let mut exclusions = Exclusions::default();
+        for entry in self.list(Some(collection)) {
+            exclusions.paths.extend(entry.path);
+            exclusions.documents.extend(entry.document);
+        }
+        exclusions
+    }
+
+    /// Block a document or path pattern
+    ///
+    /// Blocking the same target again only replaces the entry's reason.
+    pub fn add(&self, request: NewBlockEntry) -> Result<BlockEntry> {
+        if request.collection.trim().is_empty() {
+            return Err(ZeroLatencyError::validation(
+                "collection",
+                "must not be empty",
+            ));
+        }
+        let non_empty = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+        let (path, document) = (non_empty(request.path), non_empty(request.document));
+        match (&path, &document) {
+            (Some(pattern), None) => {
+                MetadataFilter::compile([(&PATH_KEY.to_string(), pattern)])
+                    .map_err(|e| ZeroLatencyError::validation("path", e.to_string()))?;
+            }
+            (None, Some(_)) => {}
+            _ => {
+                return Err(ZeroLatencyError::validation(
+                    "path",
+                    "Set exactly one of path and document",
+                ))
+            }
+        }
+
+        if let Some(mut existing) = self.entries.iter_mut().find(|entry| {
+            entry.collection == request.collection
+                && entry.path == path
+                && entry.document == document
+        }) {
+            existing.reason = request.reason;
+            return Ok(existing.clone());
+        }
+
+        let entry = BlockEntry {
+            id: Uuid::new_v4().to_string(),
+            collection: request.collection,
+            path,
+            document,
+            reason: request.reason,
+            created_at: Utc::now(),
+        };
+        self.entries.insert(entry.id.clone(), entry.clone());
+        Ok(entry)
+    }
+
+    /// Unblock an entry, returning it
+    pub fn remove(&self, id: &str) -> Option<BlockEntry> {
+        self.entries.remove(id).map(|(_, entry)| entry)
+    }
+
+    /// Drop every entry of a collection, returning whether it had any
+    pub fn remove_collection(&self, collection: &str) -> bool {
+        let before = self.entries.len();
+        self.entries
+            .retain(|_, entry| entry.collection != collection);
+        self.entries.len() != before
+    }
+
+    /// Persist the blocklist to its backing file, if any
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        write_json_atomic(path, &self.list(None), "blocklist")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(collection: &str, path: Option<&str>, document: Option<&str>) -> NewBlockEntry {
+        NewBlockEntry {
+            collection: collection.to_string(),
+            path: path.map(str::to_string),
+            document: document.map(str::to_string),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_entries_become_collection_exclusions() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("blocklist.json");
+        let blocklist = Blocklist::load(&file).unwrap();
+
+        let deprecated = blocklist
+            .add(block("docs", Some("deprecated/**"), None))
+            .unwrap();
+        blocklist
+            .add(block(
+                "docs",
+                None,
+                Some("5b0e0c1e-8f7a-4b43-9d0e-2a1c7f3e9b11"),
+            ))
+            .unwrap();
+        blocklist.add(block("other", Some("*.md"), None)).unwrap();
+
+        // Re-blocking a target keeps its entry
+        let again = blocklist
+            .add(NewBlockEntry {
+                reason: Some("replaced by guides/".to_string()),
+                ..block("docs", Some("deprecated/**"), None)
+            })
+            .unwrap();
+        assert_eq!(again.id, deprecated.id);
+
+        // Exactly one target, and only valid patterns
+        assert!(blocklist.add(block("docs", None, None)).is_err());
+        assert!(blocklist
+            .add(block("docs", Some("a.md"), Some("b")))
+            .is_err());
+        assert!(blocklist.add(block("docs", Some("re:("), None)).is_err());
+
+        blocklist.save().unwrap();
+        let reloaded = Blocklist::load(&file).unwrap();
+        let exclusions = reloaded.exclusions("docs");
+        assert_eq!(exclusions.paths, vec!["deprecated/**"]);
+        assert_eq!(exclusions.documents.len(), 1);
+        assert_eq!(
+            reloaded.list(Some("docs"))[0].reason.as_deref(),
+            Some("replaced by guides/")
+        );
+
+        assert!(reloaded.remove(&deprecated.id).is_some());
+        assert!(reloaded.remove_collection("other"));
+        assert_eq!(reloaded.list(None).len(), 1);
+    }
+}
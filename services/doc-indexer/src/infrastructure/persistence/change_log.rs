@@ -0,0 +1,371 @@
+use serde::{Deserialize, Serialize};
+/// Ordered log of index mutations
+///
+/// Every document stored, replaced or deleted and every collection created or
+/// deleted is appended with the next sequence number. Downstream systems read
+/// the log from a cursor (the last sequence they have seen) instead of
+/// comparing full listings. The log is kept as JSON lines next to the vector
+/// store and holds the most recent events only; a reader whose cursor fell
+/// behind the oldest retained event is told so and has to resync.
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use zero_latency_core::{Result, ZeroLatencyError};
+use zero_latency_search::cache::IndexGeneration;
+
+/// Kind of index mutation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// A file was indexed for the first time
+    DocumentAdded,
+    /// A changed file replaced its earlier version, or its metadata was patched
+    DocumentUpdated,
+    DocumentDeleted,
+    CollectionCreated,
+    CollectionDeleted,
+}
+
+/// One entry of the change log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    pub sequence: u64,
+    pub kind: ChangeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<String>,
+    /// Source path of the document; stays the same across updates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Share of the document's chunks that changed, for re-indexed files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub churn: Option<f32>,
+    pub occurred_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A mutation to append; the log assigns the sequence number
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub collection: Option<String>,
+    pub document_id: Option<String>,
+    pub path: Option<String>,
+    pub churn: Option<f32>,
+}
+
+impl Change {
+    pub fn collection(kind: ChangeKind, collection: &str) -> Self {
+        Self {
+            kind,
+            collection: Some(collection.to_string()),
+            document_id: None,
+            path: None,
+            churn: None,
+        }
+    }
+}
+
+/// Events read from a cursor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangePage {
+    pub events: Vec<ChangeEvent>,
+    /// Cursor for the next read: the last sequence returned, or the cursor
+    /// that was passed in when nothing new was found
+    pub next_cursor: u64,
+    /// More events are waiting after `next_cursor`
+    pub has_more: bool,
+    /// Events after the cursor were dropped from the log before being read
+    pub truncated: bool,
+    /// Sequence of the newest event, 0 when the log is empty
+    pub latest_sequence: u64,
+}
+
+struct ChangeLogState {
+    events: VecDeque<ChangeEvent>,
+    next_sequence: u64,
+    // Lines in the backing file, to know when it needs compacting
+    file_lines: usize,
+}
+
+/// Append-only change log with bounded retention
+pub struct ChangeLog {
+    state: Mutex<ChangeLogState>,
+    capacity: usize,
+    // Backing file, or None when the log must not outlive the process
+    path: Option<PathBuf>,
+}
+
+impl ChangeLog {
+    /// Create a log that only lives in memory and keeps `capacity` events
+    pub fn in_memory(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(ChangeLogState {
+                events: VecDeque::new(),
+                next_sequence: 1,
+                file_lines: 0,
+            }),
+            capacity: capacity.max(1),
+            path: None,
+        }
+    }
+
+    /// Load a log from disk, starting empty if the file does not exist
+    pub fn load(path: impl Into<PathBuf>, capacity: usize) -> Result<Self> {
+        let path = path.into();
+        let capacity = capacity.max(1);
+        let mut events = VecDeque::new();
+        let mut file_lines = 0;
+
+        if path.exists() {
+            let file = std::fs::File::open(&path).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read change log: {}", e))
+            })?;
+            for line in std::io::BufReader::new(file).lines() {
+                let line = line.map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to read change log: {}", e))
+                })?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                file_lines += 1;
+                // A crash can leave the last line half written
+                match serde_json::from_str::<ChangeEvent>(&line) {
+                    Ok(event) => {
+                        events.push_back(event);
+                        if events.len() > capacity {
+                            events.pop_front();
+                        }
+                    }
+                    Err(e) => tracing::warn!("Skipping unreadable change log entry: {}", e),
+                }
+            }
+        }
+
+        let next_sequence = events.back().map_or(1, |event| event.sequence + 1);
+        Ok(Self {
+            state: Mutex::new(ChangeLogState {
+                events,
+                next_sequence,
+                file_lines,
+            }),
+            capacity,
+            path: Some(path),
+        })
+    }
+
+    /// Append a mutation and return its event
+    pub fn append(&self, change: Change) -> ChangeEvent {
+        let mut state = self.lock();
+        let event = ChangeEvent {
+            sequence: state.next_sequence,
+            kind: change.kind,
+            collection: change.collection,
+            document_id: change.document_id,
+            path: change.path,
+            churn: change.churn,
+            occurred_at: chrono::Utc::now(),
+        };
+        state.next_sequence += 1;
+        state.events.push_back(event.clone());
+        if state.events.len() > self.capacity {
+            state.events.pop_front();
+        }
+
+        if let Err(e) = self.persist(&mut state, &event) {
+            tracing::warn!("Failed to persist change log: {}", e);
+        }
+        event
+    }
+
+    /// Sequence of the newest event, 0 when the log is empty
+    pub fn latest_sequence(&self) -> u64 {
+        self.lock().next_sequence - 1
+    }
+
+    /// Up to `limit` events after `cursor`, optionally of one collection
+    pub fn read(&self, cursor: u64, limit: usize, collection: Option<&str>) -> ChangePage {
+        let state = self.lock();
+        let latest_sequence = state.next_sequence - 1;
+        let truncated = state
+            .events
+            .front()
+            .is_some_and(|oldest| cursor + 1 < oldest.sequence);
+
+        let mut matching = state.events.iter().filter(|event| {
+            event.sequence > cursor
+                && collection.is_none_or(|name| event.collection.as_deref() == Some(name))
+        });
+        let events: Vec<ChangeEvent> = matching.by_ref().take(limit).cloned().collect();
+        let has_more = matching.next().is_some();
+
+        ChangePage {
+            next_cursor: events.last().map_or(cursor, |event| event.sequence),
+            events,
+            has_more,
+            truncated,
+            latest_sequence,
+        }
+    }
+
+    fn persist(&self, state: &mut ChangeLogState, event: &ChangeEvent) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to create change log directory: {}", e))
+            })?;
+        }
+
+        // Rewrite the file with the retained events once it holds twice as many
+        if state.file_lines >= self.capacity * 2 {
+            let mut content = String::new();
+            for retained in &state.events {
+                content.push_str(&serialize(retained)?);
+                content.push('\n');
+            }
+            // Write to a temporary file first so a crash never loses the log
+            let tmp_path = path.with_extension("jsonl.tmp");
+            std::fs::write(&tmp_path, content)
+                .and_then(|_| std::fs::rename(&tmp_path, path))
+                .map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to compact change log: {}", e))
+                })?;
+            state.file_lines = state.events.len();
+            return Ok(());
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| {
+                writeln!(file, "{}", serialize(event)?)?;
+                Ok(file)
+            })
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to write change log: {}", e))
+            })?;
+        file.flush().map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to write change log: {}", e))
+        })?;
+        state.file_lines += 1;
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ChangeLogState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// Every index mutation is appended, so the newest sequence tells caches
+// whether the index changed
+impl IndexGeneration for ChangeLog {
+    fn generation(&self) -> u64 {
+        self.latest_sequence()
+    }
+}
+
+fn serialize(event: &ChangeEvent) -> std::io::Result<String> {
+    serde_json::to_string(event).map_err(std::io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(kind: ChangeKind, collection: &str, path: &str) -> Change {
+        Change {
+            kind,
+            collection: Some(collection.to_string()),
+            document_id: Some(zero_latency_core::Uuid::new_v4().to_string()),
+            path: Some(path.to_string()),
+            churn: None,
+        }
+    }
+
+    #[test]
+    fn test_read_from_cursor() {
+        let log = ChangeLog::in_memory(100);
+        assert_eq!(log.read(0, 10, None).latest_sequence, 0);
+
+        log.append(Change::collection(ChangeKind::CollectionCreated, "docs"));
+        log.append(document(ChangeKind::DocumentAdded, "docs", "a.md"));
+        log.append(document(ChangeKind::DocumentAdded, "notes", "b.md"));
+        log.append(document(ChangeKind::DocumentUpdated, "docs", "a.md"));
+
+        let page = log.read(0, 2, None);
+        let sequences: Vec<u64> = page.events.iter().map(|event| event.sequence).collect();
+        assert_eq!(sequences, vec![1, 2]);
+        assert_eq!(page.next_cursor, 2);
+        assert!(page.has_more);
+        assert!(!page.truncated);
+        assert_eq!(page.latest_sequence, 4);
+        assert_eq!(log.latest_sequence(), 4);
+
+        let page = log.read(page.next_cursor, 10, None);
+        assert_eq!(page.events.len(), 2);
+        assert!(!page.has_more);
+
+        // Nothing new leaves the cursor where it was
+        let page = log.read(4, 10, None);
+        assert!(page.events.is_empty());
+        assert_eq!(page.next_cursor, 4);
+
+        let page = log.read(0, 10, Some("docs"));
+        assert_eq!(page.events.len(), 3);
+        assert_eq!(page.events[2].kind, ChangeKind::DocumentUpdated);
+    }
+
+    #[test]
+    fn test_evicted_events_truncate_old_cursors() {
+        let log = ChangeLog::in_memory(2);
+        for path in ["a.md", "b.md", "c.md"] {
+            log.append(document(ChangeKind::DocumentAdded, "docs", path));
+        }
+
+        let page = log.read(0, 10, None);
+        assert!(page.truncated);
+        assert_eq!(page.events[0].sequence, 2);
+        assert!(!log.read(1, 10, None).truncated);
+    }
+
+    #[test]
+    fn test_log_persists_and_compacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("changes.jsonl");
+
+        let log = ChangeLog::load(&file, 3).unwrap();
+        for i in 0..10 {
+            log.append(document(
+                ChangeKind::DocumentAdded,
+                "docs",
+                &format!("{}.md", i),
+            ));
+        }
+        let lines = std::fs::read_to_string(&file).unwrap().lines().count();
+        assert!(lines <= 6, "{} lines", lines);
+
+        // Sequence numbers continue after a restart
+        let reloaded = ChangeLog::load(&file, 3).unwrap();
+        let page = reloaded.read(0, 10, None);
+        let sequences: Vec<u64> = page.events.iter().map(|event| event.sequence).collect();
+        assert_eq!(sequences, vec![8, 9, 10]);
+        let event = reloaded.append(Change::collection(ChangeKind::CollectionDeleted, "docs"));
+        assert_eq!(event.sequence, 11);
+
+        // A half-written last line is skipped
+        let mut file_handle = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&file)
+            .unwrap();
+        write!(file_handle, "{{\"sequence\":12,\"ki").unwrap();
+        let reloaded = ChangeLog::load(&file, 3).unwrap();
+        assert_eq!(reloaded.read(0, 10, None).latest_sequence, 11);
+    }
+}
@@ -0,0 +1,122 @@
+use dashmap::DashMap;
+/// Collection alias registry
+///
+/// Maps the public name of a collection to the stored collection its vectors
+/// currently live in. Collections without an alias are stored under their own
+/// name. An embedding migration re-embeds a collection into a shadow
+/// collection and then points the alias at it, so searches and indexing
+/// switch over in one step.
+use std::path::PathBuf;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use super::write_json_atomic;
+
+/// Registry of collection aliases keyed by public name
+pub struct CollectionAliases {
+    aliases: DashMap<String, String>,
+    // Backing file, or None when aliases must not outlive the process
+    path: Option<PathBuf>,
+}
+
+impl CollectionAliases {
+    /// Create a registry that only lives in memory
+    ///
+    /// Used with the in-memory vector backend, whose shadow collections are
+    /// gone after a restart.
+    pub fn in_memory() -> Self {
+        Self {
+            aliases: DashMap::new(),
+            path: None,
+        }
+    }
+
+    /// Load a registry from disk, starting empty if the file does not exist
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut aliases = DashMap::new();
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read collection aliases: {}", e))
+            })?;
+            let stored: std::collections::HashMap<String, String> = serde_json::from_str(&content)
+                .map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to parse collection aliases: {}", e))
+                })?;
+            aliases.extend(stored);
+        }
+
+        Ok(Self {
+            aliases,
+            path: Some(path),
+        })
+    }
+
+    /// Stored collection behind a public collection name
+    pub fn resolve(&self, collection: &str) -> String {
+        self.aliases
+            .get(collection)
+            .map(|target| target.clone())
+            .unwrap_or_else(|| collection.to_string())
+    }
+
+    /// Point a public collection name at a stored collection
+    pub fn set(&self, collection: &str, target: &str) {
+        if collection == target {
+            self.aliases.remove(collection);
+        } else {
+            self.aliases
+                .insert(collection.to_string(), target.to_string());
+        }
+    }
+
+    /// Drop the alias of a collection, returning the stored collection it named
+    pub fn remove(&self, collection: &str) -> Option<String> {
+        self.aliases.remove(collection).map(|(_, target)| target)
+    }
+
+    /// Persist the registry to its backing file, if any
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let snapshot: std::collections::HashMap<String, String> = self
+            .aliases
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        write_json_atomic(path, &snapshot, "collection aliases")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_falls_back_to_name() {
+        let aliases = CollectionAliases::in_memory();
+        assert_eq!(aliases.resolve("docs"), "docs");
+
+        aliases.set("docs", "docs__dim768_1a2b");
+        assert_eq!(aliases.resolve("docs"), "docs__dim768_1a2b");
+
+        // Pointing a collection back at itself clears the alias
+        aliases.set("docs", "docs");
+        assert_eq!(aliases.remove("docs"), None);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("collection_aliases.json");
+
+        let aliases = CollectionAliases::load(&file).unwrap();
+        aliases.set("docs", "docs__dim768_1a2b");
+        aliases.save().unwrap();
+
+        let reloaded = CollectionAliases::load(&file).unwrap();
+        assert_eq!(reloaded.resolve("docs"), "docs__dim768_1a2b");
+    }
+}
@@ -0,0 +1,583 @@
+use dashmap::DashMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+/// Search curation rules
+///
+/// Curators can pin documents to the top of the results of specific queries
+/// and boost or demote documents in every search of a collection. Rules are
+/// applied after fusion and every other ranking step, and the results they
+/// change are marked in their `from_signals.curation`. Documents are named by
+/// path patterns, as in the `path` metadata filter, so a rule keeps applying
+/// when its document is re-indexed. Rules are keyed by the public collection
+/// name and persisted next to the vector store.
+use std::path::PathBuf;
+use std::sync::Arc;
+use zero_latency_core::{DateTime, Result, Utc, Uuid, ZeroLatencyError};
+use zero_latency_search::{CurationMark, SearchResult};
+use zero_latency_vector::{MetadataFilter, PATH_KEY};
+
+use super::write_json_atomic;
+
+/// Largest factor a boost can multiply scores by
+pub const MAX_BOOST_FACTOR: f32 = 10.0;
+
+/// Most documents a single pin can place
+pub const MAX_PINNED_DOCUMENTS: usize = 10;
+
+/// How a pin's query is compared with the searched one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryMatch {
+    /// Equal, ignoring case and extra whitespace
+    #[default]
+    Exact,
+    /// A case-insensitive regular expression found anywhere in the query
+    Pattern,
+}
+
+/// Documents placed at the top of the results of matching queries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pin {
+    pub id: String,
+    pub collection: String,
+    pub query: String,
+    #[serde(rename = "match", default)]
+    pub query_match: QueryMatch,
+    /// Path patterns of the pinned documents, in the order they are placed
+    pub documents: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A factor applied to the scores of matching documents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Boost {
+    pub id: String,
+    pub collection: String,
+    /// Path pattern of the boosted documents
+    pub document: String,
+    /// Above 1 boosts, below 1 demotes
+    pub factor: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A pin to create
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewPin {
+    pub collection: String,
+    pub query: String,
+    #[serde(rename = "match", default)]
+    pub query_match: QueryMatch,
+    pub documents: Vec<String>,
+}
+
+/// A boost to create or replace
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewBoost {
+    pub collection: String,
+    pub document: String,
+    pub factor: f32,
+}
+
+/// Curation rules, as listed and persisted
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CurationRules {
+    pub pins: Vec<Pin>,
+    pub boosts: Vec<Boost>,
+}
+
+/// A document pinned for the current query
+#[derive(Debug, Clone)]
+pub struct PinnedDocument {
+    /// Id of the pin that placed it
+    pub rule: String,
+    /// Matches the document's path
+    pub filter: MetadataFilter,
+}
+
+impl PinnedDocument {
+    pub fn matches(&self, result: &SearchResult) -> bool {
+        matches_path(&self.filter, result)
+    }
+}
+
+#[derive(Clone)]
+enum QueryMatcher {
+    Exact(String),
+    Pattern(Regex),
+}
+
+impl QueryMatcher {
+    fn matches(&self, query: &str) -> bool {
+        match self {
+            QueryMatcher::Exact(expected) => normalize_query(query) == *expected,
+            QueryMatcher::Pattern(pattern) => pattern.is_match(query),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct CompiledPin {
+    pin: Pin,
+    query: QueryMatcher,
+    documents: Vec<MetadataFilter>,
+}
+
+#[derive(Clone)]
+struct CompiledBoost {
+    boost: Boost,
+    document: MetadataFilter,
+}
+
+/// The rules of one collection, compiled for searching
+#[derive(Clone, Default)]
+pub struct CollectionCuration {
+    pins: Vec<CompiledPin>,
+    boosts: Vec<CompiledBoost>,
+}
+
+impl CollectionCuration {
+    /// Multiply the scores of boosted results and re-sort them, returning
+    /// whether any result was boosted
+    pub fn apply_boosts(&self, results: &mut [SearchResult]) -> bool {
+        let mut boosted = false;
+        for result in results.iter_mut() {
+            let matching: Vec<&Boost> = self
+                .boosts
+                .iter()
+                .filter(|compiled| matches_path(&compiled.document, result))
+                .map(|compiled| &compiled.boost)
+                .collect();
+            if matching.is_empty() {
+                continue;
+            }
+
+            let factor: f32 = matching.iter().map(|boost| boost.factor).product();
+            let score = (result.final_score.value() * factor).clamp(0.0, 1.0);
+            result.final_score =
+                zero_latency_core::values::Score::new(score).unwrap_or(result.final_score);
+            let mark = result
+                .from_signals
+                .curation
+                .get_or_insert_with(CurationMark::default);
+            mark.boost = Some(factor);
+            mark.rules
+                .extend(matching.iter().map(|boost| boost.id.clone()));
+            boosted = true;
+        }
+
+        if boosted {
+            results.sort_by(|a, b| b.final_score.value().total_cmp(&a.final_score.value()));
+        }
+        boosted
+    }
+
+    /// Documents pinned for `query`, in the order they are placed
+    pub fn pinned_documents(&self, query: &str) -> Vec<PinnedDocument> {
+        self.pins
+            .iter()
+            .filter(|compiled| compiled.query.matches(query))
+            .flat_map(|compiled| {
+                compiled.documents.iter().map(|filter| PinnedDocument {
+                    rule: compiled.pin.id.clone(),
+                    filter: filter.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Move the best result of every pinned document to the top, in pin order
+///
+/// Pinned documents missing from `results` are skipped.
+pub fn pin_results(results: &mut Vec<SearchResult>, pinned: &[PinnedDocument]) {
+    let mut placed = 0;
+    for document in pinned {
+        let Some(offset) = results[placed..]
+            .iter()
+            .position(|result| document.matches(result))
+        else {
+            continue;
+        };
+        let mut result = results.remove(placed + offset);
+        let mark = result
+            .from_signals
+            .curation
+            .get_or_insert_with(CurationMark::default);
+        mark.pinned = Some(placed);
+        mark.rules.push(document.rule.clone());
+        results.insert(placed, result);
+        placed += 1;
+    }
+}
+
+/// Curation rules of every collection
+pub struct CurationStore {
+    collections: DashMap<String, Arc<CollectionCuration>>,
+    // Backing file, or None when rules must not outlive the process
+    path: Option<PathBuf>,
+}
+
+impl CurationStore {
+    /// Create a store that only lives in memory
+    pub fn in_memory() -> Self {
+        Self {
+            collections: DashMap::new(),
+            path: None,
+        }
+    }
+
+    /// Load a store from disk, starting empty if the file does not exist
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let store = Self {
+            collections: DashMap::new(),
+            path: Some(path.clone()),
+        };
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read curation rules: {}", e))
+            })?;
+            let rules: CurationRules = serde_json::from_str(&content).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to parse curation rules: {}", e))
+            })?;
+            for pin in rules.pins {
+                let compiled = compile_pin(pin)?;
+                store.update(&compiled.pin.collection.clone(), |curation| {
+                    curation.pins.push(compiled)
+                });
+            }
+            for boost in rules.boosts {
+                let compiled = compile_boost(boost)?;
+                store.update(&compiled.boost.collection.clone(), |curation| {
+                    curation.boosts.push(compiled)
+                });
+            }
+        }
+
+        Ok(store)
+    }
+
+    /// Compiled rules of a collection
+    pub fn get(&self, collection: &str) -> Option<Arc<CollectionCuration>> {
+        self.collections
+            .get(collection)
+            .map(|curation| Arc::clone(&curation))
+    }
+
+    /// Rules of one collection, or of all of them
+    pub fn list(&self, collection: Option<&str>) -> CurationRules {
+        let mut rules = CurationRules::default();
+        for entry in self.collections.iter() {
+            if collection.is_some_and(|name| name != entry.key()) {
+                continue;
+            }
+            rules
+                .pins
+                .extend(entry.pins.iter().map(|compiled| compiled.pin.clone()));
+            rules
+                .boosts
+                .extend(entry.boosts.iter().map(|compiled| compiled.boost.clone()));
+        }
+        rules.pins.sort_by_key(|pin| pin.created_at);
+        rules.boosts.sort_by_key(|boost| boost.created_at);
+        rules
+    }
+
+    /// Pin documents for a query
+    pub fn add_pin(&self, request: NewPin) -> Result<Pin> {
+        let compiled = compile_pin(Pin {
+            id: Uuid::new_v4().to_string(),
+            collection: request.collection,
+            query: request.query,
+            query_match: request.query_match,
+            documents: request.documents,
+            created_at: Utc::now(),
+        })?;
+        let pin = compiled.pin.clone();
+        self.update(&pin.collection, |curation| curation.pins.push(compiled));
+        Ok(pin)
+    }
+
+    /// Drop a pin, returning it
+    pub fn remove_pin(&self, id: &str) -> Option<Pin> {
+        let collection = self
+            .collections
+            .iter()
+            .find(|entry| entry.pins.iter().any(|compiled| compiled.pin.id == id))?
+            .key()
+            .clone();
+        let mut removed = None;
+        self.update(&collection, |curation| {
+            if let Some(index) = curation.pins.iter().position(|c| c.pin.id == id) {
+                removed = Some(curation.pins.remove(index).pin);
+            }
+        });
+        removed
+    }
+
+    /// Boost or demote documents, replacing an earlier boost of the same pattern
+    pub fn add_boost(&self, request: NewBoost) -> Result<Boost> {
+        let compiled = compile_boost(Boost {
+            id: Uuid::new_v4().to_string(),
+            collection: request.collection,
+            document: request.document,
+            factor: request.factor,
+            created_at: Utc::now(),
+        })?;
+        let boost = compiled.boost.clone();
+        self.update(&boost.collection, |curation| {
+            curation
+                .boosts
+                .retain(|c| c.boost.document != compiled.boost.document);
+            curation.boosts.push(compiled);
+        });
+        Ok(boost)
+    }
+
+    /// Drop a boost, returning it
+    pub fn remove_boost(&self, id: &str) -> Option<Boost> {
+        let collection = self
+            .collections
+            .iter()
+            .find(|entry| entry.boosts.iter().any(|compiled| compiled.boost.id == id))?
+            .key()
+            .clone();
+        let mut removed = None;
+        self.update(&collection, |curation| {
+            if let Some(index) = curation.boosts.iter().position(|c| c.boost.id == id) {
+                removed = Some(curation.boosts.remove(index).boost);
+            }
+        });
+        removed
+    }
+
+    /// Drop every rule of a collection, returning whether it had any
+    pub fn remove_collection(&self, collection: &str) -> bool {
+        self.collections.remove(collection).is_some()
+    }
+
+    /// Persist the store to its backing file, if any
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        write_json_atomic(path, &self.list(None), "curation rules")
+    }
+
+    /// Replace a collection's rules with an edited copy
+    ///
+    /// Searches keep the copy they started with, so rules never change
+    /// under a running search.
+    fn update(&self, collection: &str, edit: impl FnOnce(&mut CollectionCuration)) {
+        let mut entry = self.collections.entry(collection.to_string()).or_default();
+        let mut curation = CollectionCuration::clone(entry.value());
+        edit(&mut curation);
+        *entry.value_mut() = Arc::new(curation);
+    }
+}
+
+fn compile_pin(pin: Pin) -> Result<CompiledPin> {
+    check_collection(&pin.collection)?;
+    if pin.documents.is_empty() || pin.documents.len() > MAX_PINNED_DOCUMENTS {
+        return Err(ZeroLatencyError::validation(
+            "documents",
+            format!("Pin between 1 and {} documents", MAX_PINNED_DOCUMENTS),
+        ));
+    }
+    let query = match pin.query_match {
+        QueryMatch::Exact => {
+            let query = normalize_query(&pin.query);
+            if query.is_empty() {
+                return Err(ZeroLatencyError::validation("query", "must not be empty"));
+            }
+            QueryMatcher::Exact(query)
+        }
+        QueryMatch::Pattern => Regex::new(&format!("(?i){}", pin.query))
+            .map(QueryMatcher::Pattern)
+            .map_err(|e| ZeroLatencyError::validation("query", format!("invalid regex: {}", e)))?,
+    };
+    let documents = pin
+        .documents
+        .iter()
+        .map(|document| compile_document("documents", document))
+        .collect::<Result<_>>()?;
+
+    Ok(CompiledPin {
+        pin,
+        query,
+        documents,
+    })
+}
+
+fn compile_boost(boost: Boost) -> Result<CompiledBoost> {
+    check_collection(&boost.collection)?;
+    if !boost.factor.is_finite() || boost.factor <= 0.0 || boost.factor > MAX_BOOST_FACTOR {
+        return Err(ZeroLatencyError::validation(
+            "factor",
+            format!("must be above 0 and at most {}", MAX_BOOST_FACTOR),
+        ));
+    }
+    let document = compile_document("document", &boost.document)?;
+
+    Ok(CompiledBoost { boost, document })
+}
+
+fn check_collection(collection: &str) -> Result<()> {
+    if collection.trim().is_empty() {
+        return Err(ZeroLatencyError::validation(
+            "collection",
+            "must not be empty",
+        ));
+    }
+    Ok(())
+}
+
+/// Compile a document's path pattern into a `path` metadata filter
+fn compile_document(field: &str, pattern: &str) -> Result<MetadataFilter> {
+    MetadataFilter::compile([(&PATH_KEY.to_string(), &pattern.to_string())])
+        .map_err(|e| ZeroLatencyError::validation(field, e.to_string()))
+}
+
+fn matches_path(filter: &MetadataFilter, result: &SearchResult) -> bool {
+    let path = result
+        .custom_metadata
+        .get(PATH_KEY)
+        .map(String::as_str)
+        .unwrap_or(&result.uri);
+    filter.matches(|_| Some(path))
+}
+
+fn normalize_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zero_latency_core::values::Score;
+
+    fn result(path: &str, score: f32) -> SearchResult {
+        let mut result = SearchResult::from(zero_latency_vector::SimilarityResult {
+            document_id: Uuid::new_v4(),
+            similarity: Score::new(score).unwrap(),
+            metadata: zero_latency_vector::VectorMetadata {
+                title: path.to_string(),
+                ..Default::default()
+            },
+        });
+        result
+            .custom_metadata
+            .insert(PATH_KEY.to_string(), path.to_string());
+        result
+    }
+
+    fn titles(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_boosts_and_pins_reorder_results() {
+        let store = CurationStore::in_memory();
+        let boost = |document: &str, factor: f32| NewBoost {
+            collection: "docs".to_string(),
+            document: document.to_string(),
+            factor,
+        };
+        store.add_boost(boost("legacy/**", 0.5)).unwrap();
+        store.add_boost(boost("guides/setup.md", 1.5)).unwrap();
+        let pin = store
+            .add_pin(NewPin {
+                collection: "docs".to_string(),
+                query: "  Install   GUIDE ".to_string(),
+                query_match: QueryMatch::Exact,
+                documents: vec!["faq.md".to_string(), "missing.md".to_string()],
+            })
+            .unwrap();
+
+        let curation = store.get("docs").unwrap();
+        let mut results = vec![
+            result("/docs/legacy/install.md", 0.9),
+            result("/docs/guides/setup.md", 0.5),
+            result("/docs/faq.md", 0.3),
+        ];
+        assert!(curation.apply_boosts(&mut results));
+        assert_eq!(
+            titles(&results),
+            vec![
+                "/docs/guides/setup.md",
+                "/docs/legacy/install.md",
+                "/docs/faq.md"
+            ]
+        );
+        let mark = results[1].from_signals.curation.as_ref().unwrap();
+        assert_eq!(mark.boost, Some(0.5));
+
+        assert!(curation.pinned_documents("install").is_empty());
+        let pinned = curation.pinned_documents("install guide");
+        assert_eq!(pinned.len(), 2);
+        pin_results(&mut results, &pinned);
+        assert_eq!(titles(&results)[0], "/docs/faq.md");
+        assert_eq!(results[0].from_signals.pinned(), Some(0));
+        assert_eq!(
+            results[0].from_signals.curation.as_ref().unwrap().rules,
+            vec![pin.id]
+        );
+        assert_eq!(results[1].from_signals.pinned(), None);
+    }
+
+    #[test]
+    fn test_rules_are_validated_replaced_and_persisted() {
+        let store = CurationStore::in_memory();
+        let invalid_factor = NewBoost {
+            collection: "docs".to_string(),
+            document: "a.md".to_string(),
+            factor: 0.0,
+        };
+        assert!(store.add_boost(invalid_factor).is_err());
+        let invalid_pattern = NewPin {
+            collection: "docs".to_string(),
+            query: "(unclosed".to_string(),
+            query_match: QueryMatch::Pattern,
+            documents: vec!["a.md".to_string()],
+        };
+        assert!(store.add_pin(invalid_pattern).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("curation.json");
+        let store = CurationStore::load(&file).unwrap();
+        for factor in [2.0, 3.0] {
+            store
+                .add_boost(NewBoost {
+                    collection: "docs".to_string(),
+                    document: "a.md".to_string(),
+                    factor,
+                })
+                .unwrap();
+        }
+        let pin = store
+            .add_pin(NewPin {
+                collection: "docs".to_string(),
+                query: "^release".to_string(),
+                query_match: QueryMatch::Pattern,
+                documents: vec!["notes/*.md".to_string()],
+            })
+            .unwrap();
+        store.save().unwrap();
+
+        let reloaded = CurationStore::load(&file).unwrap();
+        let rules = reloaded.list(Some("docs"));
+        assert_eq!(rules.boosts.len(), 1);
+        assert_eq!(rules.boosts[0].factor, 3.0);
+        let curation = reloaded.get("docs").unwrap();
+        assert_eq!(curation.pinned_documents("Release notes").len(), 1);
+        assert!(reloaded.remove_pin(&pin.id).is_some());
+        assert!(reloaded.list(None).pins.is_empty());
+        assert!(reloaded.list(Some("other")).boosts.is_empty());
+    }
+}
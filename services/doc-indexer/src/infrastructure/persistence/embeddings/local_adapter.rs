@@ -53,6 +53,18 @@ impl LocalEmbeddingAdapter {
         })
     }
 
+    /// Create a local embedding adapter drawing buffers from a shared vector pool
+    ///
+    /// Sharing the pool with the vector store lets buffers released after
+    /// search and upserts be reused for new embeddings. The pool is ignored
+    /// when vector pooling is disabled in the configuration.
+    pub fn with_vector_pool(config: LocalEmbeddingConfig, pool: Arc<VectorPool>) -> Result<Self> {
+        let enable_vector_pooling = config.enable_vector_pooling;
+        let mut adapter = Self::new(config)?;
+        adapter.vector_pool = enable_vector_pooling.then_some(pool);
+        Ok(adapter)
+    }
+
     /// Generate embedding for text using deterministic algorithm with optional pooling
     fn generate_deterministic_embedding(&self, text: &str) -> Vec<f32> {
         let mut embedding = if let Some(ref pool) = self.vector_pool {
@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+/// Mock embedding adapter for tests and offline development
+///
+/// Produces hashed bag-of-words vectors: every lowercase alphanumeric token
+/// adds a signed contribution to one dimension chosen by its hash, and the
+/// result is L2-normalized. Texts that share words land close together, so
+/// search results are meaningful, and the same text, dimension and seed
+/// always give the same vector on every platform and run. No model files or
+/// API keys are needed.
+use zero_latency_core::{Result, ZeroLatencyError};
+use zero_latency_vector::EmbeddingGenerator;
+
+/// Configuration for mock embeddings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockEmbeddingConfig {
+    pub dimension: usize,
+    /// Changing the seed reshuffles which dimension each token lands in
+    pub seed: u64,
+}
+
+impl Default for MockEmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            dimension: 384,
+            seed: 0,
+        }
+    }
+}
+
+/// Deterministic, hash-based embedding generator
+#[derive(Debug, Clone)]
+pub struct MockEmbeddingAdapter {
+    config: MockEmbeddingConfig,
+}
+
+impl MockEmbeddingAdapter {
+    /// Create a new mock embedding adapter
+    pub fn new(config: MockEmbeddingConfig) -> Result<Self> {
+        if config.dimension == 0 {
+            return Err(ZeroLatencyError::configuration(
+                "Embedding dimension must be greater than 0",
+            ));
+        }
+
+        Ok(Self { config })
+    }
+
+    /// Embed text synchronously
+    pub fn embed(&self, text: &str) -> Vec<f32> {
+        let dimension = self.config.dimension;
+        let mut vector = vec![0.0; dimension];
+
+        for token in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+        {
+            let hash = self.hash_token(&token.to_lowercase());
+            let index = (hash % dimension as u64) as usize;
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[index] += sign;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            // Text without tokens still needs a valid unit vector
+            vector[0] = 1.0;
+        } else {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+
+        vector
+    }
+
+    // FNV-1a seeded through the offset basis; std's hashers are not stable across releases
+    fn hash_token(&self, token: &str) -> u64 {
+        let basis = 0xcbf29ce484222325 ^ self.config.seed.wrapping_mul(0x9e3779b97f4a7c15);
+        token.bytes().fold(basis, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingGenerator for MockEmbeddingAdapter {
+    async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(self.embed(text))
+    }
+
+    async fn generate_batch_embeddings(&self, texts: Vec<&str>) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.into_iter().map(|text| self.embed(text)).collect())
+    }
+
+    fn dimension(&self) -> usize {
+        self.config.dimension
+    }
+
+    fn model_name(&self) -> &str {
+        "mock-hash-embedding"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adapter(dimension: usize, seed: u64) -> MockEmbeddingAdapter {
+        MockEmbeddingAdapter::new(MockEmbeddingConfig { dimension, seed }).unwrap()
+    }
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn test_embeddings_are_deterministic_and_normalized() {
+        let embedder = adapter(64, 7);
+        let a = embedder.embed("Tokio is an async runtime");
+
+        assert_eq!(a, adapter(64, 7).embed("Tokio is an async runtime"));
+        assert_eq!(a.len(), 64);
+        assert!((cosine(&a, &a) - 1.0).abs() < 1e-5);
+        assert_eq!(embedder.embed("").len(), 64);
+    }
+
+    #[test]
+    fn test_seed_changes_embeddings() {
+        let text = "Tokio is an async runtime";
+        assert_ne!(adapter(384, 1).embed(text), adapter(384, 2).embed(text));
+    }
+
+    #[test]
+    fn test_shared_words_are_closer() {
+        let embedder = adapter(384, 0);
+        let query = embedder.embed("async runtime");
+        let related = embedder.embed("Tokio is an ASYNC runtime for Rust");
+        let unrelated = embedder.embed("Bake the bread at two hundred degrees");
+
+        assert!(cosine(&query, &related) > cosine(&query, &unrelated));
+    }
+
+    #[test]
+    fn test_zero_dimension_is_rejected() {
+        assert!(MockEmbeddingAdapter::new(MockEmbeddingConfig {
+            dimension: 0,
+            seed: 0
+        })
+        .is_err());
+    }
+}
@@ -2,6 +2,7 @@
 ///
 /// This module contains concrete implementations of the EmbeddingGenerator trait
 /// for different embedding services and local implementations.
+pub mod mock_adapter;
 
 #[cfg(feature = "cloud")]
 pub mod openai_adapter;
@@ -10,6 +11,8 @@ pub mod openai_adapter;
 pub mod local_adapter;
 
 // Re-export commonly used types
+pub use mock_adapter::{MockEmbeddingAdapter, MockEmbeddingConfig};
+
 #[cfg(feature = "cloud")]
 pub use openai_adapter::{OpenAIAdapter, OpenAIConfig};
 
@@ -0,0 +1,479 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+/// File fingerprint registry for idempotent indexing
+///
+/// Tracks a fingerprint (size, modification time and content hash) for every
+/// indexed file so that repeated indexing runs over the same path can skip
+/// unchanged files without reading or embedding them again. The registry also
+/// remembers the id and hash of every chunk produced for each file, so that
+/// when a file changes only its changed chunks are embedded again and the
+/// vectors of chunks it no longer has are removed. Metadata patched through
+/// the API is kept with the file so it is applied again on re-indexing.
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use super::write_json_atomic;
+
+/// Fingerprint of a file's content at the time it was indexed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileFingerprint {
+    pub size: u64,
+    pub modified_ms: i64,
+    pub hash: String,
+}
+
+impl FileFingerprint {
+    /// Build a fingerprint from file metadata and content
+    pub fn new(size: u64, modified_ms: i64, content: &[u8]) -> Self {
+        Self {
+            size,
+            modified_ms,
+            hash: Self::hash_content(content),
+        }
+    }
+
+    /// Hash file content with xxh3
+    pub fn hash_content(content: &[u8]) -> String {
+        format!("{:016x}", xxhash_rust::xxh3::xxh3_64(content))
+    }
+}
+
+/// Registry entry for an indexed file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FingerprintEntry {
+    pub fingerprint: FileFingerprint,
+    pub chunk_ids: Vec<String>,
+    /// Hash of each chunk, parallel to `chunk_ids`; empty for entries
+    /// recorded before chunks were hashed
+    #[serde(default)]
+    pub chunk_hashes: Vec<String>,
+    /// Document the chunks belong to, kept across re-indexing
+    #[serde(default)]
+    pub document_id: Option<String>,
+    /// Metadata patched since the file was indexed
+    #[serde(default, skip_serializing_if = "MetadataPatch::is_empty")]
+    pub metadata_patch: MetadataPatch,
+    pub indexed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Changes to a document's custom metadata made after indexing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataPatch {
+    /// Fields to add or overwrite
+    #[serde(default)]
+    pub set: HashMap<String, String>,
+    /// Fields to remove
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+impl MetadataPatch {
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.remove.is_empty()
+    }
+
+    /// Names of every field the patch touches
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.set
+            .keys()
+            .chain(self.remove.iter())
+            .map(String::as_str)
+    }
+
+    /// Apply the patch to a metadata map
+    pub fn apply(&self, metadata: &mut HashMap<String, String>) {
+        for field in &self.remove {
+            metadata.remove(field);
+        }
+        for (field, value) in &self.set {
+            metadata.insert(field.clone(), value.clone());
+        }
+    }
+
+    /// Fold a later patch into this one, so applying the result equals
+    /// applying both in order
+    pub fn merge(&mut self, later: &MetadataPatch) {
+        for field in &later.remove {
+            self.set.remove(field);
+            if !self.remove.contains(field) {
+                self.remove.push(field.clone());
+            }
+        }
+        for (field, value) in &later.set {
+            self.remove.retain(|removed| removed != field);
+            self.set.insert(field.clone(), value.clone());
+        }
+    }
+}
+
+/// Registry of file fingerprints keyed by collection and path
+pub struct FingerprintRegistry {
+    entries: DashMap<String, FingerprintEntry>,
+    // Backing file, or None when fingerprints must not outlive the process
+    path: Option<PathBuf>,
+}
+
+impl FingerprintRegistry {
+    /// Create a registry that only lives in memory
+    ///
+    /// Used with the in-memory vector backend, where persisted fingerprints
+    /// would cause files to be skipped after a restart even though their
+    /// vectors are gone.
+    pub fn in_memory() -> Self {
+        Self {
+            entries: DashMap::new(),
+            path: None,
+        }
+    }
+
+    /// Load a registry from disk, starting empty if the file does not exist
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut entries = DashMap::new();
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read fingerprint registry: {}", e))
+            })?;
+            let stored: std::collections::HashMap<String, FingerprintEntry> =
+                serde_json::from_str(&content).map_err(|e| {
+                    ZeroLatencyError::database(format!(
+                        "Failed to parse fingerprint registry: {}",
+                        e
+                    ))
+                })?;
+            entries.extend(stored);
+        }
+
+        tracing::info!(
+            "Loaded fingerprint registry with {} entries from {}",
+            entries.len(),
+            path.display()
+        );
+
+        Ok(Self {
+            entries,
+            path: Some(path),
+        })
+    }
+
+    /// Look up the entry for a file in a collection
+    pub fn get(&self, collection: &str, path: &Path) -> Option<FingerprintEntry> {
+        self.entries
+            .get(&Self::key(collection, path))
+            .map(|entry| entry.clone())
+    }
+
+    /// Check whether size and modification time still match the recorded fingerprint
+    ///
+    /// This is the cheap check performed before a file is even read.
+    pub fn is_unchanged(&self, collection: &str, path: &Path, size: u64, modified_ms: i64) -> bool {
+        self.entries
+            .get(&Self::key(collection, path))
+            .map(|entry| {
+                entry.fingerprint.size == size && entry.fingerprint.modified_ms == modified_ms
+            })
+            .unwrap_or(false)
+    }
+
+    /// Refresh the modification time of an entry whose content hash still matches
+    pub fn touch(&self, collection: &str, path: &Path, modified_ms: i64) {
+        if let Some(mut entry) = self.entries.get_mut(&Self::key(collection, path)) {
+            entry.fingerprint.modified_ms = modified_ms;
+        }
+    }
+
+    /// Record the fingerprint and chunk ids of a freshly indexed file
+    #[cfg(test)]
+    pub fn record(
+        &self,
+        collection: &str,
+        path: &Path,
+        fingerprint: FileFingerprint,
+        chunk_ids: Vec<String>,
+    ) {
+        self.entries.insert(
+            Self::key(collection, path),
+            FingerprintEntry {
+                fingerprint,
+                chunk_ids,
+                chunk_hashes: Vec::new(),
+                document_id: None,
+                metadata_patch: self.metadata_patch(collection, path),
+                indexed_at: chrono::Utc::now(),
+            },
+        );
+    }
+
+    /// Record a freshly indexed file together with its document and chunk hashes
+    ///
+    /// `chunk_hashes` runs parallel to `chunk_ids`; the next run over the
+    /// file compares its chunks against them.
+    pub fn record_chunks(
+        &self,
+        collection: &str,
+        path: &Path,
+        fingerprint: FileFingerprint,
+        document_id: String,
+        chunk_ids: Vec<String>,
+        chunk_hashes: Vec<String>,
+    ) {
+        self.entries.insert(
+            Self::key(collection, path),
+            FingerprintEntry {
+                fingerprint,
+                chunk_ids,
+                chunk_hashes,
+                document_id: Some(document_id),
+                metadata_patch: self.metadata_patch(collection, path),
+                indexed_at: chrono::Utc::now(),
+            },
+        );
+    }
+
+    /// Metadata patched for a file since it was indexed
+    pub fn metadata_patch(&self, collection: &str, path: &Path) -> MetadataPatch {
+        self.entries
+            .get(&Self::key(collection, path))
+            .map(|entry| entry.metadata_patch.clone())
+            .unwrap_or_default()
+    }
+
+    /// Fold a metadata patch into a file's entry, returning false if the
+    /// file has no entry
+    ///
+    /// The stored chunk hashes still cover the metadata from before the
+    /// patch, so the next change to the file embeds all of its chunks again.
+    pub fn patch_metadata(&self, collection: &str, path: &Path, patch: &MetadataPatch) -> bool {
+        match self.entries.get_mut(&Self::key(collection, path)) {
+            Some(mut entry) => {
+                entry.metadata_patch.merge(patch);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Entries of a collection's files with their paths, in path order
+    pub fn collection_entries(&self, collection: &str) -> Vec<(String, FingerprintEntry)> {
+        let prefix = format!("{}::", collection);
+        let mut entries: Vec<(String, FingerprintEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .key()
+                    .strip_prefix(&prefix)
+                    .map(|path| (path.to_string(), entry.value().clone()))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+
+    /// Chunk ids recorded for a collection's files, or None when no file of
+    /// the collection has been recorded
+    pub fn collection_chunk_ids(&self, collection: &str) -> Option<HashSet<String>> {
+        let prefix = format!("{}::", collection);
+        let mut recorded = false;
+        let mut chunk_ids = HashSet::new();
+        for entry in self.entries.iter() {
+            if entry.key().starts_with(&prefix) {
+                recorded = true;
+                chunk_ids.extend(entry.value().chunk_ids.iter().cloned());
+            }
+        }
+        recorded.then_some(chunk_ids)
+    }
+
+    /// Forget all fingerprints recorded for a collection
+    ///
+    /// Returns the chunk ids of the forgotten files so the caller can remove
+    /// their vectors.
+    pub fn clear_collection(&self, collection: &str) -> Vec<String> {
+        let prefix = format!("{}::", collection);
+        let keys: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.key().starts_with(&prefix))
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        keys.iter()
+            .filter_map(|key| self.entries.remove(key))
+            .flat_map(|(_, entry)| entry.chunk_ids)
+            .collect()
+    }
+
+    /// Replace the chunk ids recorded for a collection's files
+    ///
+    /// Used after a collection's chunks are copied under new ids, so that
+    /// changed files still remove their current vectors. Ids missing from
+    /// `replacements` are kept.
+    pub fn remap_chunk_ids(
+        &self,
+        collection: &str,
+        replacements: &std::collections::HashMap<String, String>,
+    ) {
+        let prefix = format!("{}::", collection);
+        for mut entry in self.entries.iter_mut() {
+            if !entry.key().starts_with(&prefix) {
+                continue;
+            }
+            for chunk_id in entry.value_mut().chunk_ids.iter_mut() {
+                if let Some(replacement) = replacements.get(chunk_id) {
+                    *chunk_id = replacement.clone();
+                }
+            }
+        }
+    }
+
+    /// Persist the registry to its backing file, if any
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let snapshot: std::collections::HashMap<String, FingerprintEntry> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        write_json_atomic(path, &snapshot, "fingerprint registry")
+    }
+
+    fn key(collection: &str, path: &Path) -> String {
+        format!("{}::{}", collection, path.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unchanged_detection() {
+        let registry = FingerprintRegistry::in_memory();
+        let path = Path::new("/docs/readme.md");
+        registry.record(
+            "docs",
+            path,
+            FileFingerprint::new(5, 1000, b"hello"),
+            vec!["chunk-1".to_string()],
+        );
+
+        assert!(registry.is_unchanged("docs", path, 5, 1000));
+        assert!(!registry.is_unchanged("docs", path, 6, 1000));
+        assert!(!registry.is_unchanged("docs", path, 5, 2000));
+        assert!(!registry.is_unchanged("other", path, 5, 1000));
+
+        registry.touch("docs", path, 2000);
+        assert!(registry.is_unchanged("docs", path, 5, 2000));
+    }
+
+    #[test]
+    fn test_clear_collection() {
+        let registry = FingerprintRegistry::in_memory();
+        registry.record(
+            "a",
+            Path::new("/x"),
+            FileFingerprint::new(1, 1, b"x"),
+            vec!["a-1".to_string()],
+        );
+        registry.record(
+            "b",
+            Path::new("/x"),
+            FileFingerprint::new(1, 1, b"x"),
+            vec![],
+        );
+
+        assert_eq!(registry.clear_collection("a"), vec!["a-1".to_string()]);
+        assert!(registry.get("a", Path::new("/x")).is_none());
+        assert!(registry.get("b", Path::new("/x")).is_some());
+    }
+
+    #[test]
+    fn test_remap_chunk_ids() {
+        let registry = FingerprintRegistry::in_memory();
+        let fingerprint = FileFingerprint::new(1, 1, b"x");
+        registry.record(
+            "a",
+            Path::new("/x"),
+            fingerprint.clone(),
+            vec!["old-1".to_string(), "old-2".to_string()],
+        );
+        registry.record("b", Path::new("/x"), fingerprint, vec!["old-1".to_string()]);
+
+        let replacements = [("old-1".to_string(), "new-1".to_string())]
+            .into_iter()
+            .collect();
+        registry.remap_chunk_ids("a", &replacements);
+
+        let entry = registry.get("a", Path::new("/x")).unwrap();
+        assert_eq!(
+            entry.chunk_ids,
+            vec!["new-1".to_string(), "old-2".to_string()]
+        );
+        // Other collections keep their ids
+        let entry = registry.get("b", Path::new("/x")).unwrap();
+        assert_eq!(entry.chunk_ids, vec!["old-1".to_string()]);
+    }
+
+    #[test]
+    fn test_metadata_patches_merge_and_survive_reindexing() {
+        let registry = FingerprintRegistry::in_memory();
+        let path = Path::new("/docs/a.md");
+        let fingerprint = FileFingerprint::new(1, 1, b"x");
+        let patch = |set: &[(&str, &str)], remove: &[&str]| MetadataPatch {
+            set: set
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            remove: remove.iter().map(|k| k.to_string()).collect(),
+        };
+        assert!(!registry.patch_metadata("docs", path, &patch(&[("team", "a")], &[])));
+
+        registry.record("docs", path, fingerprint.clone(), vec![]);
+        registry.patch_metadata(
+            "docs",
+            path,
+            &patch(&[("team", "a"), ("tier", "1")], &["draft"]),
+        );
+        registry.patch_metadata("docs", path, &patch(&[("draft", "no")], &["tier"]));
+        let merged = registry.metadata_patch("docs", path);
+        assert_eq!(merged, patch(&[("team", "a"), ("draft", "no")], &["tier"]));
+
+        let mut metadata: HashMap<String, String> = [("tier", "2"), ("draft", "yes")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        merged.apply(&mut metadata);
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata["draft"], "no");
+
+        // Re-indexing the file keeps its patch
+        registry.record_chunks("docs", path, fingerprint, "doc".into(), vec![], vec![]);
+        assert_eq!(registry.metadata_patch("docs", path), merged);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("fingerprints.json");
+
+        let registry = FingerprintRegistry::load(&file).unwrap();
+        registry.record(
+            "docs",
+            Path::new("/docs/a.md"),
+            FileFingerprint::new(3, 42, b"abc"),
+            vec!["c1".to_string(), "c2".to_string()],
+        );
+        registry.save().unwrap();
+
+        let reloaded = FingerprintRegistry::load(&file).unwrap();
+        let entry = reloaded.get("docs", Path::new("/docs/a.md")).unwrap();
+        assert_eq!(entry.fingerprint, FileFingerprint::new(3, 42, b"abc"));
+        assert_eq!(entry.chunk_ids.len(), 2);
+    }
+}
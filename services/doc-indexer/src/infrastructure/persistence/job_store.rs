@@ -0,0 +1,125 @@
+use serde::{de::DeserializeOwned, Serialize};
+/// Persistent job records
+///
+/// Keeps the records of indexing and batch jobs in a JSON file next to the
+/// vector store, so submitted jobs, their last recorded progress and their
+/// outcome can still be read after the doc-indexer restarts. Like the other
+/// registries it only lives in memory with the in-memory backend. The job
+/// service decides what a record holds; the store only reads and writes the
+/// whole list. A file that can't be parsed is moved aside, so a damaged
+/// record file costs the job history rather than the service.
+use std::path::PathBuf;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use super::write_json_atomic;
+
+/// File holding the job records of a service
+pub struct JobStore {
+    // Backing file, or None when jobs must not outlive the process
+    path: Option<PathBuf>,
+}
+
+impl JobStore {
+    /// Create a store that keeps nothing
+    pub fn in_memory() -> Self {
+        Self { path: None }
+    }
+
+    /// Create a store backed by a file, which is created on the first save
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+
+    /// Read the stored records, oldest first
+    pub fn load<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to read jobs: {}", e)))?;
+        match serde_json::from_str(&content) {
+            Ok(jobs) => Ok(jobs),
+            Err(e) => {
+                let aside = path.with_extension(format!(
+                    "json.corrupt-{}",
+                    chrono::Utc::now().format("%Y%m%dT%H%M%S")
+                ));
+                std::fs::rename(path, &aside).map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to move aside jobs: {}", e))
+                })?;
+                tracing::warn!(
+                    "Failed to parse jobs, starting without them; moved {} to {}: {}",
+                    path.display(),
+                    aside.display(),
+                    e
+                );
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Replace the stored records
+    ///
+    /// The file is written off the async runtime. Callers saving from
+    /// several tasks must serialize their saves, or an older list may
+    /// replace a newer one.
+    pub async fn save<T: Serialize>(&self, jobs: &[T]) -> Result<()> {
+        let Some(path) = self.path.clone() else {
+            return Ok(());
+        };
+        // The records are borrowed, so they are converted before moving
+        // off the runtime
+        let jobs = serde_json::to_value(jobs).map_err(|e| {
+            ZeroLatencyError::serialization(format!("Failed to serialize jobs: {}", e))
+        })?;
+
+        tokio::task::spawn_blocking(move || write_json_atomic(&path, &jobs, "jobs"))
+            .await
+            .map_err(|e| ZeroLatencyError::internal(format!("Saving jobs failed: {}", e)))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_round_trips_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JobStore::at(dir.path().join("jobs.json"));
+        assert!(store.load::<String>().unwrap().is_empty());
+
+        store
+            .save(&["first".to_string(), "second".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(store.load::<String>().unwrap(), vec!["first", "second"]);
+
+        let memory = JobStore::in_memory();
+        memory.save(&["gone".to_string()]).await.unwrap();
+        assert!(memory.load::<String>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_moves_unparsable_records_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+        std::fs::write(&path, "[{\"id\":").unwrap();
+
+        let store = JobStore::at(&path);
+        assert!(store.load::<String>().unwrap().is_empty());
+        assert!(!path.exists());
+        let moved: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert_eq!(moved.len(), 1);
+        assert!(moved[0].starts_with("jobs.json.corrupt-"), "{:?}", moved);
+    }
+}
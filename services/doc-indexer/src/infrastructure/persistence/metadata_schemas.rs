@@ -0,0 +1,271 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+/// Typed custom metadata schemas
+///
+/// Custom metadata is stored as strings. A collection can declare the fields
+/// its documents carry, with a type and whether they are required, and every
+/// document indexed into it is checked against that schema once scripts and
+/// plugins have added their metadata. Documents that don't conform are not
+/// indexed, so filters can rely on the declared types. Schemas are keyed by
+/// the public collection name and persisted next to the vector store.
+use std::path::PathBuf;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use super::write_json_atomic;
+
+/// Type of a custom metadata field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    /// A 64-bit signed integer
+    Integer,
+    /// A finite floating point number
+    Number,
+    /// `true` or `false`
+    Boolean,
+    /// An RFC 3339 timestamp or a `YYYY-MM-DD` date
+    Date,
+}
+
+impl FieldType {
+    fn accepts(self, value: &str) -> bool {
+        match self {
+            FieldType::String => true,
+            FieldType::Integer => value.parse::<i64>().is_ok(),
+            FieldType::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+            FieldType::Boolean => matches!(value, "true" | "false"),
+            FieldType::Date => {
+                chrono::DateTime::parse_from_rfc3339(value).is_ok()
+                    || chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+            }
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FieldType::String => "string",
+            FieldType::Integer => "integer",
+            FieldType::Number => "number",
+            FieldType::Boolean => "boolean",
+            FieldType::Date => "date",
+        }
+    }
+}
+
+/// A declared custom metadata field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Custom metadata fields declared by a collection; undeclared fields are
+/// accepted as they are
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataSchema {
+    pub fields: Vec<MetadataField>,
+}
+
+/// A metadata field that doesn't match its declaration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataViolation {
+    pub field: String,
+    pub message: String,
+}
+
+impl MetadataSchema {
+    /// Check the schema itself: field names must be non-empty and unique
+    pub fn check(&self) -> Result<()> {
+        let mut seen = HashSet::new();
+        for field in &self.fields {
+            if field.name.trim().is_empty() {
+                return Err(ZeroLatencyError::validation(
+                    "fields",
+                    "Field names cannot be empty",
+                ));
+            }
+            if !seen.insert(field.name.as_str()) {
+                return Err(ZeroLatencyError::validation(
+                    "fields",
+                    format!("Field '{}' is declared more than once", field.name),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fields of `metadata` that are missing or don't parse as their type
+    pub fn validate(&self, metadata: &HashMap<String, String>) -> Vec<MetadataViolation> {
+        self.fields
+            .iter()
+            .filter_map(|field| {
+                let message = match metadata.get(&field.name) {
+                    None if field.required => "is required".to_string(),
+                    None => return None,
+                    Some(value) if field.field_type.accepts(value) => return None,
+                    Some(value) => format!("expected {}, got '{}'", field.field_type.name(), value),
+                };
+                Some(MetadataViolation {
+                    field: field.name.clone(),
+                    message,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Registry of metadata schemas keyed by collection
+pub struct MetadataSchemaRegistry {
+    schemas: DashMap<String, MetadataSchema>,
+    // Backing file, or None when schemas must not outlive the process
+    path: Option<PathBuf>,
+}
+
+impl MetadataSchemaRegistry {
+    /// Create a registry that only lives in memory
+    pub fn in_memory() -> Self {
+        Self {
+            schemas: DashMap::new(),
+            path: None,
+        }
+    }
+
+    /// Load a registry from disk, starting empty if the file does not exist
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut schemas = DashMap::new();
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read metadata schemas: {}", e))
+            })?;
+            let stored: HashMap<String, MetadataSchema> =
+                serde_json::from_str(&content).map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to parse metadata schemas: {}", e))
+                })?;
+            schemas.extend(stored);
+        }
+
+        Ok(Self {
+            schemas,
+            path: Some(path),
+        })
+    }
+
+    /// Schema declared by a collection
+    pub fn get(&self, collection: &str) -> Option<MetadataSchema> {
+        self.schemas.get(collection).map(|schema| schema.clone())
+    }
+
+    /// Declare or replace a collection's schema
+    pub fn set(&self, collection: &str, schema: MetadataSchema) -> Result<()> {
+        schema.check()?;
+        self.schemas.insert(collection.to_string(), schema);
+        Ok(())
+    }
+
+    /// Drop a collection's schema, returning it
+    pub fn remove(&self, collection: &str) -> Option<MetadataSchema> {
+        self.schemas.remove(collection).map(|(_, schema)| schema)
+    }
+
+    /// Persist the registry to its backing file, if any
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let snapshot: HashMap<String, MetadataSchema> = self
+            .schemas
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        write_json_atomic(path, &snapshot, "metadata schemas")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, field_type: FieldType, required: bool) -> MetadataField {
+        MetadataField {
+            name: name.to_string(),
+            field_type,
+            required,
+        }
+    }
+
+    #[test]
+    fn test_validate_types_and_required_fields() {
+        let schema = MetadataSchema {
+            fields: vec![
+                field("version", FieldType::Integer, true),
+                field("score", FieldType::Number, false),
+                field("draft", FieldType::Boolean, false),
+                field("published", FieldType::Date, false),
+                field("owner", FieldType::String, true),
+            ],
+        };
+        let metadata = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        let valid = metadata(&[
+            ("version", "3"),
+            ("score", "0.5"),
+            ("draft", "false"),
+            ("published", "2025-08-24"),
+            ("owner", "docs"),
+            ("undeclared", "anything"),
+        ]);
+        assert!(schema.validate(&valid).is_empty());
+
+        let invalid = metadata(&[
+            ("version", "3.1"),
+            ("score", "NaN"),
+            ("draft", "yes"),
+            ("published", "2025-08-24T14:30:00Z"),
+        ]);
+        let fields: Vec<String> = schema
+            .validate(&invalid)
+            .into_iter()
+            .map(|violation| violation.field)
+            .collect();
+        assert_eq!(fields, vec!["version", "score", "draft", "owner"]);
+    }
+
+    #[test]
+    fn test_rejects_duplicate_fields_and_round_trips() {
+        let registry = MetadataSchemaRegistry::in_memory();
+        let duplicate = MetadataSchema {
+            fields: vec![
+                field("version", FieldType::Integer, true),
+                field("version", FieldType::String, false),
+            ],
+        };
+        assert!(registry.set("docs", duplicate).is_err());
+        assert!(registry.get("docs").is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("metadata_schemas.json");
+        let registry = MetadataSchemaRegistry::load(&file).unwrap();
+        let schema = MetadataSchema {
+            fields: vec![field("version", FieldType::Integer, true)],
+        };
+        registry.set("docs", schema.clone()).unwrap();
+        registry.save().unwrap();
+
+        let reloaded = MetadataSchemaRegistry::load(&file).unwrap();
+        assert_eq!(reloaded.get("docs"), Some(schema));
+        assert!(reloaded.remove("docs").is_some());
+    }
+}
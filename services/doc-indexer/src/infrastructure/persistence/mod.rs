@@ -1,2 +1,45 @@
+pub mod blocklist;
+pub mod change_log;
+pub mod collection_aliases;
+pub mod curation;
 pub mod embeddings;
+pub mod fingerprint_registry;
+pub mod job_store;
+pub mod metadata_schemas;
+pub mod projection_cache;
+#[cfg(feature = "late-interaction")]
+pub mod token_vectors;
 pub mod vector;
+pub mod webhook_registry;
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+
+/// Replace the file at `path` with `value` as JSON
+///
+/// The JSON is written to a temporary file next to `path` and renamed over
+/// it, so a crash never leaves a truncated file; each write uses its own
+/// temporary file, so concurrent writers don't clobber each other's. `what`
+/// names the contents in error messages.
+pub fn write_json_atomic<T: Serialize + ?Sized>(path: &Path, value: &T, what: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to create {} directory: {}", what, e))
+        })?;
+    }
+
+    let content = serde_json::to_string(value).map_err(|e| {
+        ZeroLatencyError::serialization(format!("Failed to serialize {}: {}", what, e))
+    })?;
+
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(format!(".{}.tmp", Uuid::new_v4()));
+    let tmp_path = PathBuf::from(tmp_path);
+    std::fs::write(&tmp_path, content)
+        .and_then(|_| std::fs::rename(&tmp_path, path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&tmp_path);
+            ZeroLatencyError::database(format!("Failed to write {}: {}", what, e))
+        })
+}
@@ -0,0 +1,223 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+/// Cached 2D projections of collections
+///
+/// Projecting a collection takes seconds to minutes, while plotting it is
+/// done again and again, so the latest projection of each collection is kept
+/// and served until the next one replaces it. Projections are keyed by the
+/// public collection name and stored as one JSON file per collection next to
+/// the vector store.
+use std::collections::HashMap;
+use std::path::PathBuf;
+use zero_latency_core::{DateTime, Result, Utc, Uuid, ZeroLatencyError};
+use zero_latency_vector::ProjectionMethod;
+
+use super::write_json_atomic;
+
+/// A chunk's position in a projection, with labels for plotting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectedPoint {
+    pub x: f32,
+    pub y: f32,
+    pub chunk_id: Uuid,
+    pub document_id: Uuid,
+    pub chunk_index: usize,
+    pub title: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Start of the chunk's content, to tell points apart on hover
+    pub label: String,
+    /// ACL entries of the chunk's document, so exports can leave out the
+    /// points a caller may not read
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub acl: HashMap<String, String>,
+}
+
+/// A collection's chunks projected to two dimensions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Projection {
+    pub collection: String,
+    pub method: ProjectionMethod,
+    pub seed: u64,
+    /// Chunks stored in the collection when it was projected
+    pub total_chunks: usize,
+    pub computed_at: DateTime<Utc>,
+    pub duration_ms: f64,
+    pub points: Vec<ProjectedPoint>,
+}
+
+impl Projection {
+    /// The points as CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("x,y,chunk_id,document_id,chunk_index,path,title,label\n");
+        for point in &self.points {
+            let fields = [
+                point.x.to_string(),
+                point.y.to_string(),
+                point.chunk_id.to_string(),
+                point.document_id.to_string(),
+                point.chunk_index.to_string(),
+                csv_field(point.path.as_deref().unwrap_or_default()),
+                csv_field(&point.title),
+                csv_field(&point.label),
+            ];
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+// Quote a field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Latest projection of every collection
+pub struct ProjectionCache {
+    projections: DashMap<String, Projection>,
+    // Directory of the projection files, or None when they must not outlive the process
+    dir: Option<PathBuf>,
+}
+
+impl ProjectionCache {
+    /// Create a cache that only lives in memory
+    pub fn in_memory() -> Self {
+        Self {
+            projections: DashMap::new(),
+            dir: None,
+        }
+    }
+
+    /// Create a cache backed by a directory of projection files
+    ///
+    /// Files are read when a collection's projection is first requested.
+    pub fn open(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            projections: DashMap::new(),
+            dir: Some(dir.into()),
+        }
+    }
+
+    /// The latest projection of a collection, if any
+    pub fn get(&self, collection: &str) -> Result<Option<Projection>> {
+        if let Some(projection) = self.projections.get(collection) {
+            return Ok(Some(projection.clone()));
+        }
+        let Some(path) = self.file(collection) else {
+            return Ok(None);
+        };
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to read projection: {}", e)))?;
+        let projection: Projection = serde_json::from_str(&content).map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to parse projection: {}", e))
+        })?;
+        self.projections
+            .insert(collection.to_string(), projection.clone());
+        Ok(Some(projection))
+    }
+
+    /// Replace a collection's projection and persist it
+    pub fn store(&self, projection: Projection) -> Result<()> {
+        if let Some(path) = self.file(&projection.collection) {
+            write_json_atomic(&path, &projection, "projection")?;
+        }
+        self.projections
+            .insert(projection.collection.clone(), projection);
+        Ok(())
+    }
+
+    /// Drop a collection's projection, returning whether it had one
+    pub fn remove(&self, collection: &str) -> Result<bool> {
+        let mut removed = self.projections.remove(collection).is_some();
+        if let Some(path) = self.file(collection) {
+            if path.exists() {
+                std::fs::remove_file(&path).map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to remove projection: {}", e))
+                })?;
+                removed = true;
+            }
+        }
+        Ok(removed)
+    }
+
+    // File of a collection's projection; names are escaped so that any
+    // collection name maps to a plain file name
+    fn file(&self, collection: &str) -> Option<PathBuf> {
+        let name: String = collection
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c.to_string(),
+                _ => format!("%{:02X}", c as u32),
+            })
+            .collect();
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.json", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn projection(collection: &str) -> Projection {
+        Projection {
+            collection: collection.to_string(),
+            method: ProjectionMethod::Umap,
+            seed: 7,
+            total_chunks: 1,
+            computed_at: Utc::now(),
+            duration_ms: 1.5,
+            points: vec![ProjectedPoint {
+                x: 0.5,
+                y: -1.0,
+                chunk_id: Uuid::new_v4(),
+                document_id: Uuid::new_v4(),
+                chunk_index: 0,
+                title: "Setup, quickly".to_string(),
+                path: Some("docs/setup.md".to_string()),
+                label: "Say \"hi\"".to_string(),
+                acl: HashMap::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_projections_persist_per_collection() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ProjectionCache::open(dir.path().join("projections"));
+        assert_eq!(cache.get("docs/v2").unwrap(), None);
+
+        let stored = projection("docs/v2");
+        cache.store(stored.clone()).unwrap();
+        assert!(dir.path().join("projections/docs%2Fv2.json").exists());
+
+        // A new cache over the same directory reads the file back
+        let reopened = ProjectionCache::open(dir.path().join("projections"));
+        assert_eq!(reopened.get("docs/v2").unwrap(), Some(stored));
+        assert!(reopened.remove("docs/v2").unwrap());
+        assert_eq!(reopened.get("docs/v2").unwrap(), None);
+        assert!(!reopened.remove("docs/v2").unwrap());
+    }
+
+    #[test]
+    fn test_csv_export_quotes_fields() {
+        let csv = projection("docs").to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "x,y,chunk_id,document_id,chunk_index,path,title,label"
+        );
+        assert!(lines[1].starts_with("0.5,-1,"));
+        assert!(lines[1].ends_with(",docs/setup.md,\"Setup, quickly\",\"Say \"\"hi\"\"\""));
+    }
+}
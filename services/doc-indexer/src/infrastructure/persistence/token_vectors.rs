@@ -0,0 +1,270 @@
+use async_trait::async_trait;
+/// Token vectors of indexed chunks for late-interaction reranking
+///
+/// Each chunk's token vectors are kept as one compressed [`TokenMatrix`] row
+/// in a SQLite database next to the vector store, keyed by the chunk's
+/// vector id. Searches only read the rows of the results they rerank, so the
+/// matrices are never loaded as a whole. With encryption at rest configured
+/// the matrices are sealed like the vector store's values; rows record whether
+/// they were, since quantized codes can hold any bytes.
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use zero_latency_core::{Result, Uuid, ZeroLatencyError};
+use zero_latency_search::{TokenMatrix, TokenVectorSource};
+
+use super::vector::encryption::{self, Cipher};
+
+/// Compressed token vectors of every chunk indexed with late interaction
+pub struct TokenVectorStore {
+    connection: Mutex<Connection>,
+    cipher: Option<Cipher>,
+}
+
+impl TokenVectorStore {
+    /// Create a store that only lives in memory
+    pub fn in_memory() -> Result<Self> {
+        let connection = Connection::open_in_memory().map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to open token vector store: {}", e))
+        })?;
+        Self::init(connection, None)
+    }
+
+    /// Open or create the store at `path`, sealing rows with `cipher`
+    pub fn open(path: impl AsRef<Path>, cipher: Option<Cipher>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                ZeroLatencyError::database(format!(
+                    "Failed to create token vector directory: {}",
+                    e
+                ))
+            })?;
+        }
+        let connection = Connection::open(path).map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to open token vector store: {}", e))
+        })?;
+        Self::init(connection, cipher)
+    }
+
+    fn init(connection: Connection, cipher: Option<Cipher>) -> Result<Self> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS token_vectors (
+                    chunk_id TEXT PRIMARY KEY,
+                    collection TEXT NOT NULL,
+                    dimension INTEGER NOT NULL,
+                    scales BLOB NOT NULL,
+                    codes BLOB NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS token_vectors_collection
+                    ON token_vectors (collection);",
+            )
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to create token vector table: {}", e))
+            })?;
+        let has_sealed = connection
+            .prepare("SELECT 1 FROM pragma_table_info('token_vectors') WHERE name = 'sealed'")
+            .and_then(|mut stmt| stmt.exists([]))
+            .map_err(database_error)?;
+        if !has_sealed {
+            connection
+                .execute(
+                    "ALTER TABLE token_vectors ADD COLUMN sealed INTEGER NOT NULL DEFAULT 0",
+                    [],
+                )
+                .map_err(database_error)?;
+        }
+        let store = Self {
+            connection: Mutex::new(connection),
+            cipher,
+        };
+        store.seal_plaintext_rows()?;
+        Ok(store)
+    }
+
+    /// Encrypt rows written before encryption was configured
+    fn seal_plaintext_rows(&self) -> Result<()> {
+        let Some(cipher) = &self.cipher else {
+            return Ok(());
+        };
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction().map_err(database_error)?;
+        let rows: Vec<(String, Vec<u8>, Vec<u8>)> = transaction
+            .prepare("SELECT chunk_id, scales, codes FROM token_vectors WHERE sealed = 0")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                    .collect()
+            })
+            .map_err(database_error)?;
+        for (chunk_id, scales, codes) in &rows {
+            transaction
+                .execute(
+                    "UPDATE token_vectors SET scales = ?1, codes = ?2, sealed = 1 \
+                     WHERE chunk_id = ?3",
+                    params![cipher.seal(scales)?, cipher.seal(codes)?, chunk_id],
+                )
+                .map_err(database_error)?;
+        }
+        transaction.commit().map_err(database_error)?;
+        if !rows.is_empty() {
+            connection.execute_batch("VACUUM").map_err(database_error)?;
+        }
+        Ok(())
+    }
+
+    /// Store the token vectors of chunks of a collection, replacing any
+    /// they had
+    pub fn store(&self, collection: &str, matrices: Vec<(Uuid, TokenMatrix)>) -> Result<()> {
+        if matrices.is_empty() {
+            return Ok(());
+        }
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction().map_err(database_error)?;
+        {
+            let mut insert = transaction
+                .prepare(
+                    "INSERT OR REPLACE INTO token_vectors
+                        (chunk_id, collection, dimension, scales, codes, sealed)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                )
+                .map_err(database_error)?;
+            for (chunk_id, matrix) in matrices {
+                let scales: Vec<u8> = matrix
+                    .scales()
+                    .iter()
+                    .flat_map(|scale| scale.to_le_bytes())
+                    .collect();
+                let codes: Vec<u8> = matrix.codes().iter().map(|&code| code as u8).collect();
+                insert
+                    .execute(params![
+                        chunk_id.to_string(),
+                        collection,
+                        matrix.dimension() as i64,
+                        encryption::seal_bytes(scales, self.cipher.as_ref())?,
+                        encryption::seal_bytes(codes, self.cipher.as_ref())?,
+                        self.cipher.is_some()
+                    ])
+                    .map_err(database_error)?;
+            }
+        }
+        transaction.commit().map_err(database_error)
+    }
+
+    /// Token vectors of the chunks with these vector ids
+    pub fn get(&self, chunk_ids: &[Uuid]) -> Result<HashMap<Uuid, TokenMatrix>> {
+        let connection = self.connection.lock().unwrap();
+        let mut select = connection
+            .prepare_cached(
+                "SELECT dimension, scales, codes, sealed FROM token_vectors WHERE chunk_id = ?1",
+            )
+            .map_err(database_error)?;
+        let mut matrices = HashMap::new();
+        for chunk_id in chunk_ids {
+            let row = select.query_row(params![chunk_id.to_string()], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Vec<u8>>(1)?,
+                    row.get::<_, Vec<u8>>(2)?,
+                    row.get::<_, bool>(3)?,
+                ))
+            });
+            let (dimension, mut scales, mut codes, sealed) = match row {
+                Ok(row) => row,
+                Err(rusqlite::Error::QueryReturnedNoRows) => continue,
+                Err(e) => return Err(database_error(e)),
+            };
+            if sealed {
+                let cipher = self.cipher.as_ref().ok_or_else(encryption::missing_key)?;
+                scales = cipher.open(&scales)?;
+                codes = cipher.open(&codes)?;
+            }
+            let scales = scales
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+            let codes = codes.into_iter().map(|code| code as i8).collect();
+            let matrix =
+                TokenMatrix::from_parts(dimension as usize, scales, codes).map_err(|message| {
+                    ZeroLatencyError::database(format!(
+                        "Corrupt token vectors of chunk {}: {}",
+                        chunk_id, message
+                    ))
+                })?;
+            matrices.insert(*chunk_id, matrix);
+        }
+        Ok(matrices)
+    }
+
+    /// Drop the token vectors of chunks, returning how many were stored
+    pub fn remove(&self, chunk_ids: &[String]) -> Result<usize> {
+        let connection = self.connection.lock().unwrap();
+        let mut delete = connection
+            .prepare_cached("DELETE FROM token_vectors WHERE chunk_id = ?1")
+            .map_err(database_error)?;
+        let mut removed = 0;
+        for chunk_id in chunk_ids {
+            removed += delete.execute(params![chunk_id]).map_err(database_error)?;
+        }
+        Ok(removed)
+    }
+
+    /// Drop the token vectors of every chunk of a collection
+    pub fn remove_collection(&self, collection: &str) -> Result<usize> {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "DELETE FROM token_vectors WHERE collection = ?1",
+                params![collection],
+            )
+            .map_err(database_error)
+    }
+}
+
+#[async_trait]
+impl TokenVectorSource for TokenVectorStore {
+    async fn token_vectors(&self, chunk_ids: &[Uuid]) -> Result<HashMap<Uuid, TokenMatrix>> {
+        self.get(chunk_ids)
+    }
+}
+
+fn database_error(e: rusqlite::Error) -> ZeroLatencyError {
+    ZeroLatencyError::database(format!("Token vector store error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_vectors_persist_per_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token_vectors.db");
+        let (first, second, missing) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let matrix = TokenMatrix::compress(&[vec![0.5, -0.25, 1.0], vec![0.0, 1.0, 0.0]]);
+
+        let store = TokenVectorStore::open(&path, None).unwrap();
+        store
+            .store(
+                "docs",
+                vec![(first, matrix.clone()), (second, matrix.clone())],
+            )
+            .unwrap();
+        store
+            .store("blog", vec![(Uuid::new_v4(), matrix.clone())])
+            .unwrap();
+        drop(store);
+
+        // A new store over the same file reads the rows back
+        let reopened = TokenVectorStore::open(&path, None).unwrap();
+        let found = reopened.get(&[first, missing]).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[&first], matrix);
+
+        assert_eq!(reopened.remove(&[first.to_string()]).unwrap(), 1);
+        assert_eq!(reopened.remove_collection("docs").unwrap(), 1);
+        assert_eq!(reopened.remove_collection("blog").unwrap(), 1);
+        assert!(reopened.get(&[second]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,181 @@
+/// Compression of text stored by the embedded vector store
+///
+/// Chunk text and metadata payloads are written zstd-compressed when
+/// compression is enabled for their collection. The SQLite value records the
+/// encoding: plain UTF-8 is stored as `TEXT` and compressed bytes as `BLOB`,
+/// so rows written before compression, or with it turned off, stay readable
+/// and the settings can change at any time without rewriting the database.
+use rusqlite::types::{Value, ValueRef};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+/// Text shorter than this is stored as is; the zstd frame would eat the savings
+const MIN_COMPRESSED_LEN: usize = 64;
+
+/// Compression of chunk text and metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Compress text written from now on
+    pub enabled: bool,
+
+    /// zstd level, from 1 (fastest) to 22 (smallest)
+    pub level: i32,
+
+    /// Settings of individual collections, overriding the ones above
+    #[serde(default)]
+    pub collections: HashMap<String, CollectionCompression>,
+}
+
+/// Compression settings of one collection; unset fields use the store's
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionCompression {
+    pub enabled: Option<bool>,
+    pub level: Option<i32>,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: std::env::var("DOC_INDEXER_EMBEDDED_COMPRESSION")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(true),
+            level: std::env::var("DOC_INDEXER_EMBEDDED_COMPRESSION_LEVEL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            collections: std::env::var("DOC_INDEXER_EMBEDDED_COMPRESSION_COLLECTIONS")
+                .map(|spec| parse_collections(&spec))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// zstd level for text written to `collection`, or `None` to store it plain
+    pub fn level_for(&self, collection: Option<&str>) -> Option<i32> {
+        let overrides = collection.and_then(|name| self.collections.get(name));
+        let enabled = overrides.and_then(|o| o.enabled).unwrap_or(self.enabled);
+        let level = overrides.and_then(|o| o.level).unwrap_or(self.level);
+        enabled.then_some(level)
+    }
+
+    /// Check every level is one zstd accepts
+    pub fn validate(&self) -> Result<()> {
+        let levels =
+            std::iter::once(self.level).chain(self.collections.values().filter_map(|c| c.level));
+        for level in levels {
+            if !(1..=22).contains(&level) {
+                return Err(ZeroLatencyError::configuration(format!(
+                    "Compression level {} is out of range (1-22)",
+                    level
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse `name=setting` pairs separated by commas, where a setting is `on`,
+/// `off` or a level
+fn parse_collections(spec: &str) -> HashMap<String, CollectionCompression> {
+    spec.split(',')
+        .filter_map(|pair| {
+            let (name, setting) = pair.split_once('=')?;
+            let setting = setting.trim();
+            let compression = match setting {
+                "on" | "true" => CollectionCompression {
+                    enabled: Some(true),
+                    level: None,
+                },
+                "off" | "false" => CollectionCompression {
+                    enabled: Some(false),
+                    level: None,
+                },
+                level => CollectionCompression {
+                    enabled: Some(true),
+                    level: Some(level.parse().ok()?),
+                },
+            };
+            Some((name.trim().to_string(), compression))
+        })
+        .collect()
+}
+
+/// Encode text for storage, compressing it at `level` when that makes it smaller
+pub fn encode(text: &str, level: Option<i32>) -> Result<Value> {
+    if let Some(level) = level.filter(|_| text.len() >= MIN_COMPRESSED_LEN) {
+        let compressed = zstd::encode_all(text.as_bytes(), level)
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to compress text: {}", e)))?;
+        if compressed.len() < text.len() {
+            return Ok(Value::Blob(compressed));
+        }
+    }
+    Ok(Value::Text(text.to_string()))
+}
+
+/// Decode text written by [`encode`]
+pub fn decode(value: ValueRef<'_>) -> Result<String> {
+    match value {
+        ValueRef::Text(text) => String::from_utf8(text.to_vec())
+            .map_err(|e| ZeroLatencyError::database(format!("Stored text is not UTF-8: {}", e))),
+        ValueRef::Blob(compressed) => {
+            let text = zstd::decode_all(compressed).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to decompress text: {}", e))
+            })?;
+            String::from_utf8(text)
+                .map_err(|e| ZeroLatencyError::database(format!("Stored text is not UTF-8: {}", e)))
+        }
+        ValueRef::Null => Ok(String::new()),
+        other => Err(ZeroLatencyError::database(format!(
+            "Unexpected {:?} value where text was stored",
+            other.data_type()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn as_ref(value: &Value) -> ValueRef<'_> {
+        ValueRef::from(value)
+    }
+
+    #[test]
+    fn test_encode_round_trips() {
+        let text = "Licensed under the Apache License, Version 2.0. ".repeat(20);
+        let compressed = encode(&text, Some(3)).unwrap();
+        assert!(matches!(&compressed, Value::Blob(bytes) if bytes.len() < text.len()));
+        assert_eq!(decode(as_ref(&compressed)).unwrap(), text);
+
+        // Short text and disabled compression are stored plain
+        assert!(matches!(encode("short", Some(3)).unwrap(), Value::Text(_)));
+        let plain = encode(&text, None).unwrap();
+        assert_eq!(plain, Value::Text(text.clone()));
+        assert_eq!(decode(as_ref(&plain)).unwrap(), text);
+    }
+
+    #[test]
+    fn test_collection_overrides() {
+        let config = CompressionConfig {
+            enabled: true,
+            level: 3,
+            collections: parse_collections("logs=off, archive=19,drafts=on,bad=x"),
+        };
+        assert_eq!(config.level_for(None), Some(3));
+        assert_eq!(config.level_for(Some("guides")), Some(3));
+        assert_eq!(config.level_for(Some("logs")), None);
+        assert_eq!(config.level_for(Some("archive")), Some(19));
+        assert_eq!(config.level_for(Some("drafts")), Some(3));
+        assert!(!config.collections.contains_key("bad"));
+        assert!(config.validate().is_ok());
+
+        let invalid = CompressionConfig {
+            level: 40,
+            ..config
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
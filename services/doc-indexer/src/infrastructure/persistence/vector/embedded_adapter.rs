@@ -1,23 +1,44 @@
-use crate::infrastructure::memory::{CacheConfig, MemoryEfficientCache, StringInterner};
+use crate::infrastructure::memory::{
+    CacheConfig, MemoryEfficientCache, StringInterner, VectorPool,
+};
 use async_trait::async_trait;
 use dashmap::DashMap;
 use lru::LruCache;
-use rusqlite::{params, Connection, OpenFlags};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
 /// Embedded vector store adapter using SQLite
 ///
 /// This adapter provides a self-contained, persistent vector storage solution
 /// that doesn't require external databases. It uses SQLite with binary blob
-/// storage for vectors and provides efficient similarity search.
+/// storage for vectors and provides efficient similarity search. Chunk text
+/// is stored content-addressed, so boilerplate repeated across many files
+/// (license headers, shared footers) is kept once and reference counted, and
+/// chunk text and metadata are compressed per collection settings and, with
+/// a key configured, encrypted (see [`super::encryption`]). Searches
+/// score an in-memory snapshot of the vectors (see [`super::snapshot`]) and
+/// read metadata through their own connection, so they never wait for
+/// indexing; metadata is decompressed just for the rows that make it into the
+/// results. Named vectors of a chunk (see [`VectorDocument::named_vectors`])
+/// are kept in their own table and snapshot per name.
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{Mutex, Semaphore};
 use zero_latency_core::{models::HealthStatus, values::Score, Result, Uuid, ZeroLatencyError};
 use zero_latency_vector::{
-    SimilarityCalculator, SimilarityMetric, SimilarityResult, VectorDocument, VectorMetadata,
-    VectorRepository,
+    CollectionVectorSettings, MetadataFilter, SimilarityCalculator, SimilarityMetric,
+    SimilarityResult, StorageInfo, StorageSegment, VectorDocument, VectorMetadata,
+    VectorRepository, DEFAULT_VECTOR,
 };
 
+use super::compression::CompressionConfig;
+use super::encryption::{self, Cipher, EncryptionConfig};
+use super::scoring::{BatchScorer, CpuScorer, GPU_MIN_BATCH};
+use super::snapshot::{VectorRow, VectorSegments};
+
 /// Configuration for embedded vector store
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddedConfig {
@@ -31,6 +52,42 @@ pub struct EmbeddedConfig {
     pub enable_string_interning: bool,
     /// Enable memory-efficient caching
     pub enable_smart_caching: bool,
+    /// Score brute-force scans on the GPU when built with the `gpu` feature
+    /// and a device is available
+    #[serde(default = "default_enable_gpu_scoring")]
+    pub enable_gpu_scoring: bool,
+    /// Vectors scored together during a scan
+    #[serde(default = "default_scoring_batch_size")]
+    pub scoring_batch_size: usize,
+    /// Compression of stored chunk text and metadata
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    /// Encryption at rest of stored vectors, chunk text and metadata
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+    /// Recently written vectors kept apart before they are frozen into a
+    /// segment
+    #[serde(default = "default_memtable_capacity")]
+    pub memtable_capacity: usize,
+    /// Segments searched before the newer ones are merged
+    #[serde(default = "default_max_segments")]
+    pub max_segments: usize,
+}
+
+fn default_enable_gpu_scoring() -> bool {
+    true
+}
+
+fn default_memtable_capacity() -> usize {
+    1024
+}
+
+fn default_max_segments() -> usize {
+    8
+}
+
+fn default_scoring_batch_size() -> usize {
+    8192
 }
 
 impl Default for EmbeddedConfig {
@@ -42,6 +99,12 @@ impl Default for EmbeddedConfig {
             cache_size: 10000,
             enable_string_interning: true,
             enable_smart_caching: true,
+            enable_gpu_scoring: default_enable_gpu_scoring(),
+            scoring_batch_size: default_scoring_batch_size(),
+            compression: CompressionConfig::default(),
+            encryption: EncryptionConfig::default(),
+            memtable_capacity: default_memtable_capacity(),
+            max_segments: default_max_segments(),
         }
     }
 }
@@ -49,17 +112,60 @@ impl Default for EmbeddedConfig {
 /// Embedded vector store using SQLite
 pub struct EmbeddedVectorStore {
     db_path: PathBuf,
+    /// Connection of writers
     connection: Arc<Mutex<Connection>>,
+    /// Connection of readers; with write-ahead logging it reads the last
+    /// committed state while a write is in progress
+    read_connection: Arc<Mutex<Connection>>,
+    /// Vectors searches score, updated by writers as they commit
+    vectors: Arc<VectorSegments>,
+    /// Named vectors by name, updated along with `vectors`
+    named_vectors: Arc<DashMap<String, Arc<VectorSegments>>>,
+    /// Dimension and metric of each configured collection; others are
+    /// compared by cosine
+    collection_settings: Arc<DashMap<String, CollectionVectorSettings>>,
     config: EmbeddedConfig,
+    /// Seals stored values when encryption at rest is configured
+    cipher: Option<Cipher>,
     cache: Arc<DashMap<String, Vec<f32>>>,
     string_interner: Option<Arc<StringInterner>>,
     smart_cache: Option<Arc<MemoryEfficientCache<String, Vec<f32>>>>,
+    vector_pool: Option<Arc<VectorPool>>,
+    scorer: Arc<dyn BatchScorer>,
     read_semaphore: Arc<Semaphore>,
     write_semaphore: Arc<Semaphore>,
 }
+
+/// Stored vectors waiting to be scored against the query together
+struct ScoringBatch {
+    metric: SimilarityMetric,
+    /// Position in the results of each batched row
+    rows: Vec<usize>,
+    embeddings: Vec<f32>,
+}
+
+impl ScoringBatch {
+    fn new(metric: SimilarityMetric) -> Self {
+        Self {
+            metric,
+            rows: Vec::new(),
+            embeddings: Vec::new(),
+        }
+    }
+}
+
+/// A document as committed, to publish to searches
+struct WrittenDocument {
+    row: VectorRow,
+    named_vectors: HashMap<String, Vec<f32>>,
+}
+
 impl EmbeddedVectorStore {
     /// Create a new embedded vector store
     pub async fn new(config: EmbeddedConfig) -> Result<Self> {
+        config.compression.validate()?;
+        let cipher = config.encryption.cipher()?;
+
         // Ensure directory exists
         if let Some(parent) = config.db_path.parent() {
             std::fs::create_dir_all(parent).map_err(|e| {
@@ -67,12 +173,37 @@ impl EmbeddedVectorStore {
             })?;
         }
 
-        // Open SQLite connection
-        let connection = Connection::open_with_flags(
-            &config.db_path,
-            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE,
-        )
-        .map_err(|e| ZeroLatencyError::database(format!("Failed to open database: {}", e)))?;
+        // Open SQLite connections; write-ahead logging lets the reader's
+        // queries run while the writer commits
+        let open = || {
+            let connection = Connection::open_with_flags(
+                &config.db_path,
+                OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE,
+            )
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to open database: {}", e)))?;
+            connection
+                .busy_timeout(std::time::Duration::from_secs(5))
+                .map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to set busy timeout: {}", e))
+                })?;
+            Ok::<_, ZeroLatencyError>(connection)
+        };
+        let connection = open()?;
+        if cipher.is_some() {
+            // Freed pages are zeroed so plaintext of rows written before the
+            // key was set doesn't linger in the file
+            connection
+                .execute_batch("PRAGMA secure_delete = ON")
+                .map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to enable secure delete: {}", e))
+                })?;
+        }
+        connection
+            .query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to enable write-ahead log: {}", e))
+            })?;
+        let read_connection = open()?;
 
         // Initialize optional optimizations
         let string_interner = if config.enable_string_interning {
@@ -92,23 +223,317 @@ impl EmbeddedVectorStore {
             None
         };
 
-        let store = Self {
+        let mut store = Self {
             db_path: config.db_path.clone(),
             connection: Arc::new(Mutex::new(connection)),
+            read_connection: Arc::new(Mutex::new(read_connection)),
+            vectors: Arc::new(VectorSegments::new(Vec::new(), 1, 1)),
+            named_vectors: Arc::new(DashMap::new()),
+            collection_settings: Arc::new(DashMap::new()),
             config: config.clone(),
+            cipher,
             cache: Arc::new(DashMap::new()),
             string_interner,
             smart_cache,
+            vector_pool: None,
+            scorer: Arc::new(CpuScorer),
             read_semaphore: Arc::new(Semaphore::new(100)), // Allow 100 concurrent reads
             write_semaphore: Arc::new(Semaphore::new(10)), // Allow 10 concurrent writes
         };
 
         // Initialize database schema
         store.initialize_schema().await?;
+        store.seal_plaintext_rows().await?;
+
+        store.load_collection_settings().await?;
+        let rows = store.load_rows().await?;
+        tracing::debug!("EmbeddedVectorStore: loaded {} vectors", rows.len());
+        store.vectors = Arc::new(VectorSegments::new(
+            rows,
+            config.memtable_capacity,
+            config.max_segments,
+        ));
+        for (name, rows) in store.load_named_rows().await? {
+            store.named_vectors.insert(
+                name,
+                Arc::new(VectorSegments::new(
+                    rows,
+                    config.memtable_capacity,
+                    config.max_segments,
+                )),
+            );
+        }
 
         Ok(store)
     }
 
+    /// Read every stored vector for the initial snapshot
+    async fn load_rows(&self) -> Result<Vec<VectorRow>> {
+        let conn = self.connection.lock().await;
+        let mut stmt = conn
+            .prepare(SCAN_ROWS)
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare load: {}", e)))?;
+        let rows = stmt
+            .query_map([], |row| {
+                let id: String = row.get(0)?;
+                let embedding_blob: Vec<u8> = row.get(1)?;
+                let collection: Option<String> = row.get(2)?;
+                Ok((id, embedding_blob, collection))
+            })
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to load vectors: {}", e)))?;
+
+        let mut vectors = Vec::new();
+        for row in rows {
+            let (id, embedding_blob, collection) =
+                row.map_err(|e| ZeroLatencyError::database(format!("Failed to read row: {}", e)))?;
+            vectors.push(VectorRow {
+                id: Uuid::parse_str(&id)
+                    .map_err(|e| ZeroLatencyError::database(format!("Invalid UUID: {}", e)))?,
+                collection,
+                embedding: self.deserialize_vector(&embedding_blob)?,
+            });
+        }
+        Ok(vectors)
+    }
+
+    /// Read every stored named vector, by name
+    async fn load_named_rows(&self) -> Result<HashMap<String, Vec<VectorRow>>> {
+        let conn = self.connection.lock().await;
+        let mut stmt = conn
+            .prepare(
+                "SELECT n.id, n.name, n.embedding, v.collection \
+                 FROM named_vectors n JOIN vectors v ON v.id = n.id",
+            )
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare load: {}", e)))?;
+        let rows = stmt
+            .query_map([], |row| {
+                let id: String = row.get(0)?;
+                let name: String = row.get(1)?;
+                let embedding_blob: Vec<u8> = row.get(2)?;
+                let collection: Option<String> = row.get(3)?;
+                Ok((id, name, embedding_blob, collection))
+            })
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to load named vectors: {}", e))
+            })?;
+
+        let mut vectors: HashMap<String, Vec<VectorRow>> = HashMap::new();
+        for row in rows {
+            let (id, name, embedding_blob, collection) =
+                row.map_err(|e| ZeroLatencyError::database(format!("Failed to read row: {}", e)))?;
+            vectors.entry(name).or_default().push(VectorRow {
+                id: Uuid::parse_str(&id)
+                    .map_err(|e| ZeroLatencyError::database(format!("Invalid UUID: {}", e)))?,
+                collection,
+                embedding: self.deserialize_vector(&embedding_blob)?,
+            });
+        }
+        Ok(vectors)
+    }
+
+    /// Read the configured collections' dimensions and metrics
+    async fn load_collection_settings(&self) -> Result<()> {
+        let conn = self.connection.lock().await;
+        let mut stmt = conn
+            .prepare("SELECT collection, dimension, metric FROM collection_settings")
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare load: {}", e)))?;
+        let rows = stmt
+            .query_map([], |row| {
+                let collection: String = row.get(0)?;
+                let dimension: i64 = row.get(1)?;
+                let metric: String = row.get(2)?;
+                Ok((collection, dimension, metric))
+            })
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to load collection settings: {}", e))
+            })?;
+        for row in rows {
+            let (collection, dimension, metric) =
+                row.map_err(|e| ZeroLatencyError::database(format!("Failed to read row: {}", e)))?;
+            self.collection_settings.insert(
+                collection,
+                CollectionVectorSettings {
+                    dimension: dimension as usize,
+                    metric: metric.parse()?,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Metric comparing the vectors of `collection`
+    fn metric_of(&self, collection: Option<&str>) -> SimilarityMetric {
+        collection
+            .and_then(|collection| self.collection_settings.get(collection))
+            .map(|settings| settings.metric)
+            .unwrap_or_default()
+    }
+
+    /// Snapshots of the vectors named `name`, created on first use
+    fn named_segments(&self, name: &str) -> Arc<VectorSegments> {
+        let segments = self
+            .named_vectors
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                Arc::new(VectorSegments::new(
+                    Vec::new(),
+                    self.config.memtable_capacity,
+                    self.config.max_segments,
+                ))
+            });
+        Arc::clone(&segments)
+    }
+
+    /// Number of frozen vector segments searches currently scan
+    #[cfg(test)]
+    pub fn segment_count(&self) -> usize {
+        self.vectors.snapshot().segment_count()
+    }
+
+    /// Recycle vector buffers through a shared pool
+    ///
+    /// Query vectors, embeddings evicted from the cache and the scratch
+    /// buffers used while scanning are returned to the pool, so they can be
+    /// reused by embedding generation instead of being freed.
+    pub fn with_vector_pool(mut self, pool: Arc<VectorPool>) -> Self {
+        self.vector_pool = Some(pool);
+        self
+    }
+
+    /// Score brute-force scans with `scorer` instead of on the CPU
+    ///
+    /// Small batches still run on the CPU, and a batch the scorer fails on is
+    /// rescored there.
+    pub fn with_scorer(mut self, scorer: Arc<dyn BatchScorer>) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    /// Add a scanned row to the batch, scoring the batch once it is full
+    fn push_scored_row(
+        &self,
+        query: &[f32],
+        embedding: &[f32],
+        row: usize,
+        batch: &mut ScoringBatch,
+        results: &mut [SimilarityResult],
+    ) {
+        // Rows of another dimension keep their zero score
+        if embedding.len() != query.len() {
+            return;
+        }
+        batch.rows.push(row);
+        batch.embeddings.extend_from_slice(embedding);
+        if batch.rows.len() >= self.config.scoring_batch_size {
+            self.score_batch(query, batch, results);
+        }
+    }
+
+    /// Score rows against the query, each by its collection's metric, best
+    /// first; with `collection_name` set, rows of other collections are skipped
+    fn score_rows<'a>(
+        &self,
+        rows: impl Iterator<Item = (Uuid, Option<&'a str>, &'a [f32])>,
+        collection_name: Option<&str>,
+        query: &[f32],
+    ) -> Vec<SimilarityResult> {
+        let mut results = Vec::new();
+        let mut batches = SimilarityMetric::ALL.map(ScoringBatch::new);
+        for (document_id, collection, embedding) in rows {
+            if collection_name.is_some_and(|name| !in_collection(collection, name)) {
+                continue;
+            }
+            // Metadata is loaded once the results are known
+            results.push(SimilarityResult {
+                document_id,
+                similarity: Score::new(0.0).unwrap(),
+                metadata: VectorMetadata {
+                    collection: collection.map(str::to_string),
+                    ..Default::default()
+                },
+            });
+            let row = results.len() - 1;
+            let metric = self.metric_of(collection);
+            let batch = batches
+                .iter_mut()
+                .find(|batch| batch.metric == metric)
+                .expect("a batch per metric");
+            self.push_scored_row(query, embedding, row, batch, &mut results);
+        }
+        for batch in &mut batches {
+            self.score_batch(query, batch, &mut results);
+        }
+
+        // Sort by similarity score (descending)
+        results.sort_by(|a, b| {
+            b.similarity
+                .value()
+                .partial_cmp(&a.similarity.value())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results
+    }
+
+    /// Write the batch's similarities into their results and empty it
+    fn score_batch(
+        &self,
+        query: &[f32],
+        batch: &mut ScoringBatch,
+        results: &mut [SimilarityResult],
+    ) {
+        if batch.rows.is_empty() {
+            return;
+        }
+
+        let mut scores = Vec::with_capacity(batch.rows.len());
+        if batch.metric == SimilarityMetric::Cosine {
+            let scorer: &dyn BatchScorer = if batch.rows.len() >= GPU_MIN_BATCH {
+                self.scorer.as_ref()
+            } else {
+                &CpuScorer
+            };
+            if let Err(e) = scorer.score(query, &batch.embeddings, &mut scores) {
+                tracing::warn!(
+                    "{} scoring failed, rescoring on CPU: {}",
+                    scorer.backend(),
+                    e
+                );
+                scores.clear();
+                let _ = CpuScorer.score(query, &batch.embeddings, &mut scores);
+            }
+        } else {
+            // Only cosine runs on the GPU
+            scores.extend(
+                batch
+                    .embeddings
+                    .chunks_exact(query.len())
+                    .map(|row| batch.metric.raw(query, row).unwrap_or_default()),
+            );
+        }
+
+        for (&row, similarity) in batch.rows.iter().zip(scores) {
+            results[row].similarity = Score::new(batch.metric.normalize(similarity))
+                .unwrap_or_else(|_| Score::new(0.0).unwrap());
+        }
+        batch.rows.clear();
+        batch.embeddings.clear();
+    }
+
+    /// Get a scratch buffer for decoding stored vectors
+    fn scratch_vector(&self) -> Vec<f32> {
+        match &self.vector_pool {
+            Some(pool) => pool.get_vector(self.config.dimension),
+            None => Vec::with_capacity(self.config.dimension),
+        }
+    }
+
+    /// Return a vector that is no longer needed to the pool
+    fn recycle_vector(&self, vector: Vec<f32>) {
+        if let Some(pool) = &self.vector_pool {
+            pool.return_vector(vector);
+        }
+    }
+
     /// Initialize database schema
     async fn initialize_schema(&self) -> Result<()> {
         let conn = self.connection.lock().await;
@@ -135,21 +560,262 @@ impl EmbeddedVectorStore {
         )
         .map_err(|e| ZeroLatencyError::database(format!("Failed to create index: {}", e)))?;
 
+        // Chunk text keyed by its hash, shared by every vector with that text
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS chunk_contents (
+                hash TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                ref_count INTEGER NOT NULL,
+                size INTEGER NOT NULL DEFAULT 0
+            )
+            "#,
+            [],
+        )
+        .map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to create chunk_contents table: {}", e))
+        })?;
+
+        // Collections compared by something other than cosine, or whose
+        // dimension is fixed
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS collection_settings (
+                collection TEXT PRIMARY KEY,
+                dimension INTEGER NOT NULL,
+                metric TEXT NOT NULL
+            )
+            "#,
+            [],
+        )
+        .map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to create collection_settings table: {}", e))
+        })?;
+
+        // Vectors of a chunk besides its embedding, e.g. of its title
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS named_vectors (
+                id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                embedding BLOB NOT NULL,
+                PRIMARY KEY (id, name)
+            )
+            "#,
+            [],
+        )
+        .map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to create named_vectors table: {}", e))
+        })?;
+
+        // Databases from before content addressing keep their text inline
+        // in the metadata; those rows have no hash until re-indexed
+        add_column(&conn, "vectors", "content_hash", "TEXT")?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_vectors_content_hash ON vectors(content_hash)",
+            [],
+        )
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to create index: {}", e)))?;
+
+        // The collection is kept beside the (possibly compressed) metadata so
+        // scans can filter without decoding it
+        if add_column(&conn, "vectors", "collection", "TEXT")? {
+            conn.execute(
+                "UPDATE vectors SET collection = json_extract(metadata, '$.collection') \
+                 WHERE typeof(metadata) = 'text'",
+                [],
+            )
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to backfill collections: {}", e))
+            })?;
+        }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_vectors_collection ON vectors(collection)",
+            [],
+        )
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to create index: {}", e)))?;
+
+        // Each chunk's position in its document, so a hit's neighbors can be
+        // looked up without decoding every row of the collection
+        let added = add_column(&conn, "vectors", "document_id", "TEXT")?;
+        if add_column(&conn, "vectors", "chunk_index", "INTEGER")? || added {
+            backfill_chunk_positions(&conn, self.cipher.as_ref())?;
+        }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_vectors_chunk_position \
+             ON vectors(document_id, chunk_index)",
+            [],
+        )
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to create index: {}", e)))?;
+
+        // Uncompressed length of each chunk, for storage statistics
+        if add_column(
+            &conn,
+            "chunk_contents",
+            "size",
+            "INTEGER NOT NULL DEFAULT 0",
+        )? {
+            conn.execute(
+                "UPDATE chunk_contents SET size = length(CAST(content AS BLOB))",
+                [],
+            )
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to backfill chunk sizes: {}", e))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Encrypt rows written before encryption was configured
+    ///
+    /// Runs once after a key is first set; the database is vacuumed
+    /// afterwards so the plaintext pages are gone from the file and its
+    /// write-ahead log.
+    async fn seal_plaintext_rows(&self) -> Result<()> {
+        let Some(cipher) = &self.cipher else {
+            return Ok(());
+        };
+        let mut conn = self.connection.lock().await;
+        let transaction = conn
+            .transaction()
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to begin: {}", e)))?;
+        let read_error = |e: rusqlite::Error| {
+            ZeroLatencyError::database(format!("Failed to read rows to encrypt: {}", e))
+        };
+        let write_error = |e: rusqlite::Error| {
+            ZeroLatencyError::database(format!("Failed to encrypt rows: {}", e))
+        };
+        let sealed = |value: &Value| match value {
+            Value::Blob(bytes) => encryption::is_sealed(bytes),
+            Value::Null => true,
+            _ => false,
+        };
+
+        let vectors: Vec<(String, Vec<u8>, Value)> = transaction
+            .prepare("SELECT id, embedding, metadata FROM vectors")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                    .collect()
+            })
+            .map_err(read_error)?;
+        let mut encrypted = 0;
+        for (id, embedding, metadata) in vectors {
+            if encryption::is_sealed(&embedding) && sealed(&metadata) {
+                continue;
+            }
+            transaction
+                .execute(
+                    "UPDATE vectors SET embedding = ?, metadata = ? WHERE id = ?",
+                    params![
+                        encryption::seal_bytes(embedding, Some(cipher))?,
+                        encryption::seal_value(metadata, cipher)?,
+                        id
+                    ],
+                )
+                .map_err(write_error)?;
+            encrypted += 1;
+        }
+
+        let named: Vec<(String, String, Vec<u8>)> = transaction
+            .prepare("SELECT id, name, embedding FROM named_vectors")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                    .collect()
+            })
+            .map_err(read_error)?;
+        for (id, name, embedding) in named {
+            if encryption::is_sealed(&embedding) {
+                continue;
+            }
+            transaction
+                .execute(
+                    "UPDATE named_vectors SET embedding = ? WHERE id = ? AND name = ?",
+                    params![encryption::seal_bytes(embedding, Some(cipher))?, id, name],
+                )
+                .map_err(write_error)?;
+            encrypted += 1;
+        }
+
+        let contents: Vec<(String, Value)> = transaction
+            .prepare("SELECT hash, content FROM chunk_contents")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect()
+            })
+            .map_err(read_error)?;
+        for (hash, content) in contents {
+            if sealed(&content) {
+                continue;
+            }
+            transaction
+                .execute(
+                    "UPDATE chunk_contents SET content = ? WHERE hash = ?",
+                    params![encryption::seal_value(content, cipher)?, hash],
+                )
+                .map_err(write_error)?;
+            encrypted += 1;
+        }
+        transaction
+            .commit()
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to commit: {}", e)))?;
+
+        if encrypted > 0 {
+            conn.execute_batch("VACUUM; PRAGMA wal_checkpoint(TRUNCATE);")
+                .map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to compact database: {}", e))
+                })?;
+            tracing::info!("EmbeddedVectorStore: encrypted {} stored rows", encrypted);
+        }
         Ok(())
     }
 
     /// Serialize vector to binary format
     fn serialize_vector(&self, vector: &[f32]) -> Result<Vec<u8>> {
-        bincode::serialize(vector)
-            .map_err(|e| ZeroLatencyError::database(format!("Failed to serialize vector: {}", e)))
+        let bytes = bincode::serialize(vector).map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to serialize vector: {}", e))
+        })?;
+        encryption::seal_bytes(bytes, self.cipher.as_ref())
     }
 
     /// Deserialize vector from binary format
     fn deserialize_vector(&self, data: &[u8]) -> Result<Vec<f32>> {
-        bincode::deserialize(data)
+        let data = encryption::open_bytes(data, self.cipher.as_ref())?;
+        bincode::deserialize(&data)
             .map_err(|e| ZeroLatencyError::database(format!("Failed to deserialize vector: {}", e)))
     }
 
+    /// Decode a vector into an existing buffer, reusing its allocation
+    ///
+    /// Mirrors the bincode layout written by `serialize_vector`: a
+    /// little-endian u64 length followed by little-endian f32 values.
+    fn deserialize_vector_into(&self, data: &[u8], vector: &mut Vec<f32>) -> Result<()> {
+        let data = encryption::open_bytes(data, self.cipher.as_ref())?;
+        if data.len() < 8 {
+            return Err(ZeroLatencyError::database(
+                "Failed to deserialize vector: missing length prefix",
+            ));
+        }
+
+        let (length, values) = data.split_at(8);
+        let length = u64::from_le_bytes(length.try_into().unwrap()) as usize;
+        if values.len() != length * std::mem::size_of::<f32>() {
+            return Err(ZeroLatencyError::database(format!(
+                "Failed to deserialize vector: expected {} values, found {} bytes",
+                length,
+                values.len()
+            )));
+        }
+
+        vector.clear();
+        vector.extend(
+            values
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap())),
+        );
+        Ok(())
+    }
+
     /// Get vector from cache or database
     async fn get_vector(&self, document_id: &str) -> Result<Option<Vec<f32>>> {
         let _permit = self.read_semaphore.acquire().await.unwrap();
@@ -160,7 +826,7 @@ impl EmbeddedVectorStore {
         }
 
         // Load from database
-        let conn = self.connection.lock().await;
+        let conn = self.read_connection.lock().await;
         let mut stmt = conn
             .prepare("SELECT embedding FROM vectors WHERE id = ?")
             .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare query: {}", e)))?;
@@ -185,30 +851,205 @@ impl EmbeddedVectorStore {
         }
     }
 
-    /// Get database statistics
-    pub async fn get_stats(&self) -> Result<EmbeddedStats> {
-        let conn = self.connection.lock().await;
+    /// Store documents one transaction each, collecting the committed ones
+    fn insert_rows(
+        &self,
+        conn: &Connection,
+        vectors: Vec<VectorDocument>,
+        committed: &mut Vec<WrittenDocument>,
+    ) -> Result<()> {
+        for document in vectors {
+            let transaction = conn.unchecked_transaction().map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to begin insert: {}", e))
+            })?;
+            let written = self.write_document(&transaction, document)?;
+            transaction.commit().map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to commit insert: {}", e))
+            })?;
+            committed.push(written);
+        }
+        Ok(())
+    }
 
-        let document_count: i64 = conn
-            .query_row("SELECT COUNT(*) FROM vectors", [], |row| row.get(0))
-            .map_err(|e| ZeroLatencyError::database(format!("Failed to count documents: {}", e)))?;
+    /// Write a document, replacing any stored under its id
+    fn write_document(
+        &self,
+        conn: &Connection,
+        document: VectorDocument,
+    ) -> Result<WrittenDocument> {
+        let VectorDocument {
+            id,
+            embedding,
+            named_vectors,
+            mut metadata,
+        } = document;
+        let embedding_blob = self.serialize_vector(&embedding)?;
+        let content = std::mem::take(&mut metadata.content);
+        let hash = content_hash(&content);
+        let metadata_json = serde_json::to_string(&metadata).map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to serialize metadata: {}", e))
+        })?;
+        let level = self
+            .config
+            .compression
+            .level_for(metadata.collection.as_deref());
+        let metadata_value = encryption::encode_text(&metadata_json, level, self.cipher.as_ref())?;
+
+        let replaced: Option<String> = conn
+            .query_row(
+                "SELECT content_hash FROM vectors WHERE id = ?",
+                params![id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to read document: {}", e)))?
+            .flatten();
 
-        let db_size = std::fs::metadata(&self.config.db_path)
-            .map(|m| m.len())
-            .unwrap_or(0);
+        acquire_content(conn, &hash, &content, level, self.cipher.as_ref())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO vectors \
+             (id, embedding, metadata, content_hash, collection, document_id, chunk_index) \
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![
+                id.to_string(),
+                embedding_blob,
+                metadata_value,
+                hash,
+                metadata.collection,
+                metadata.document_id.to_string(),
+                metadata.chunk_index as i64
+            ],
+        )
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to insert document: {}", e)))?;
+        if let Some(replaced) = replaced {
+            release_content(conn, &replaced)?;
+        }
 
-        Ok(EmbeddedStats {
-            document_count: document_count as usize,
-            db_size_bytes: db_size,
-            cache_size: self.cache.len(),
-            db_path: self.config.db_path.clone(),
+        conn.execute(
+            "DELETE FROM named_vectors WHERE id = ?",
+            params![id.to_string()],
+        )
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to replace vectors: {}", e)))?;
+        for (name, vector) in &named_vectors {
+            conn.execute(
+                "INSERT INTO named_vectors (id, name, embedding) VALUES (?, ?, ?)",
+                params![id.to_string(), name, self.serialize_vector(vector)?],
+            )
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to insert vector '{}': {}", name, e))
+            })?;
+        }
+
+        Ok(WrittenDocument {
+            row: VectorRow {
+                id,
+                collection: metadata.collection,
+                embedding,
+            },
+            named_vectors,
         })
     }
 
-    /// Compact database (VACUUM)
-    pub async fn compact(&self) -> Result<()> {
-        let conn = self.connection.lock().await;
-        conn.execute("VACUUM", []).map_err(|e| {
+    /// Make committed documents visible to searches together
+    fn publish(&self, documents: Vec<WrittenDocument>) {
+        // A replaced chunk loses the named vectors it no longer has
+        for segments in self.named_vectors.iter() {
+            segments.remove_batch(
+                documents
+                    .iter()
+                    .filter(|document| !document.named_vectors.contains_key(segments.key()))
+                    .map(|document| document.row.id),
+            );
+        }
+
+        let mut named_rows: HashMap<String, Vec<VectorRow>> = HashMap::new();
+        let mut rows = Vec::with_capacity(documents.len());
+        for document in documents {
+            for (name, embedding) in document.named_vectors {
+                named_rows.entry(name).or_default().push(VectorRow {
+                    id: document.row.id,
+                    collection: document.row.collection.clone(),
+                    embedding,
+                });
+            }
+            self.cache_row(&document.row);
+            rows.push(document.row);
+        }
+        for (name, rows) in named_rows {
+            self.named_segments(&name).upsert(rows);
+        }
+        self.vectors.upsert(rows);
+    }
+
+    /// Every stored named vector, by document id and name
+    fn named_vectors_by_id(
+        &self,
+        conn: &Connection,
+    ) -> Result<HashMap<String, HashMap<String, Vec<f32>>>> {
+        let mut stmt = conn
+            .prepare("SELECT id, name, embedding FROM named_vectors")
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare scan: {}", e)))?;
+        let rows = stmt
+            .query_map([], |row| {
+                let id: String = row.get(0)?;
+                let name: String = row.get(1)?;
+                let embedding_blob: Vec<u8> = row.get(2)?;
+                Ok((id, name, embedding_blob))
+            })
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read named vectors: {}", e))
+            })?;
+
+        let mut vectors: HashMap<String, HashMap<String, Vec<f32>>> = HashMap::new();
+        for row in rows {
+            let (id, name, embedding_blob) =
+                row.map_err(|e| ZeroLatencyError::database(format!("Failed to read row: {}", e)))?;
+            vectors
+                .entry(id)
+                .or_default()
+                .insert(name, self.deserialize_vector(&embedding_blob)?);
+        }
+        Ok(vectors)
+    }
+
+    /// Update the cache with a committed row, recycling any replaced vector
+    fn cache_row(&self, row: &VectorRow) {
+        if let Some(previous) = self.cache.insert(row.id.to_string(), row.embedding.clone()) {
+            self.recycle_vector(previous);
+        }
+    }
+
+    /// Stop searching the named vectors of deleted documents
+    fn remove_named(&self, ids: &[Uuid]) {
+        for segments in self.named_vectors.iter() {
+            segments.remove_batch(ids.iter().copied());
+        }
+    }
+
+    /// Get database statistics
+    pub async fn get_stats(&self) -> Result<EmbeddedStats> {
+        let conn = self.read_connection.lock().await;
+
+        let document_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM vectors", [], |row| row.get(0))
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to count documents: {}", e)))?;
+
+        let db_size = std::fs::metadata(&self.config.db_path)
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        Ok(EmbeddedStats {
+            document_count: document_count as usize,
+            db_size_bytes: db_size,
+            cache_size: self.cache.len(),
+            db_path: self.config.db_path.clone(),
+        })
+    }
+
+    /// Compact database (VACUUM)
+    pub async fn compact(&self) -> Result<()> {
+        let conn = self.connection.lock().await;
+        conn.execute("VACUUM", []).map_err(|e| {
             ZeroLatencyError::database(format!("Failed to compact database: {}", e))
         })?;
         Ok(())
@@ -220,78 +1061,97 @@ impl VectorRepository for EmbeddedVectorStore {
     async fn insert(&self, vectors: Vec<VectorDocument>) -> Result<()> {
         let _permit = self.write_semaphore.acquire().await.unwrap();
 
-        for document in vectors {
-            let embedding_blob = self.serialize_vector(&document.embedding)?;
-            let metadata_json = serde_json::to_string(&document.metadata).map_err(|e| {
-                ZeroLatencyError::database(format!("Failed to serialize metadata: {}", e))
-            })?;
-
-            {
-                let conn = self.connection.lock().await;
-                let mut stmt = conn
-                    .prepare(
-                        "INSERT OR REPLACE INTO vectors (id, embedding, metadata) VALUES (?, ?, ?)",
-                    )
-                    .map_err(|e| {
-                        ZeroLatencyError::database(format!("Failed to prepare insert: {}", e))
-                    })?;
-
-                stmt.execute(params![
-                    document.id.to_string(),
-                    embedding_blob,
-                    metadata_json
-                ])
-                .map_err(|e| {
-                    ZeroLatencyError::database(format!("Failed to insert document: {}", e))
-                })?;
-            }
+        // Searches see the committed rows together, even if a later one failed
+        let conn = self.connection.lock().await;
+        let mut committed = Vec::with_capacity(vectors.len());
+        let inserted = self.insert_rows(&conn, vectors, &mut committed);
+        self.publish(committed);
+        inserted
+    }
 
-            // Update cache with concurrent access
-            self.cache.insert(document.id.to_string(), document.embedding);
-        }
+    async fn upsert_batch(&self, vectors: Vec<VectorDocument>) -> Result<()> {
+        let _permit = self.write_semaphore.acquire().await.unwrap();
 
+        // One transaction and one snapshot for the whole batch
+        let conn = self.connection.lock().await;
+        let transaction = conn
+            .unchecked_transaction()
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to begin upsert: {}", e)))?;
+        let documents = vectors
+            .into_iter()
+            .map(|document| self.write_document(&transaction, document))
+            .collect::<Result<Vec<_>>>()?;
+        transaction
+            .commit()
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to commit upsert: {}", e)))?;
+
+        self.publish(documents);
         Ok(())
     }
 
     async fn search(&self, query_vector: Vec<f32>, k: usize) -> Result<Vec<SimilarityResult>> {
         let _permit = self.read_semaphore.acquire().await.unwrap();
 
-        let conn = self.connection.lock().await;
-        let mut stmt = conn
-            .prepare("SELECT id, embedding, metadata FROM vectors")
-            .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare search: {}", e)))?;
+        // Score a snapshot of the vectors, which indexing never blocks. Each
+        // row is compared by its collection's metric.
+        let snapshot = self.vectors.snapshot();
+        let mut results = self.score_rows(snapshot.rows(), None, &query_vector);
+        drop(snapshot);
 
-        let rows = stmt
-            .query_map([], |row| {
-                let id: String = row.get(0)?;
-                let embedding_blob: Vec<u8> = row.get(1)?;
-                let metadata_json: String = row.get(2)?;
-                Ok((id, embedding_blob, metadata_json))
-            })
-            .map_err(|e| ZeroLatencyError::database(format!("Failed to execute search: {}", e)))?;
+        // Limit results
+        results.truncate(k);
+        let conn = self.read_connection.lock().await;
+        load_metadata(&conn, &mut results, self.cipher.as_ref())?;
 
-        let mut results = Vec::new();
+        self.recycle_vector(query_vector);
 
-        for row_result in rows {
-            let (id, embedding_blob, metadata_json) = row_result
-                .map_err(|e| ZeroLatencyError::database(format!("Failed to read row: {}", e)))?;
+        Ok(results)
+    }
 
-            let embedding = self.deserialize_vector(&embedding_blob)?;
-            let metadata: VectorMetadata = serde_json::from_str(&metadata_json).map_err(|e| {
-                ZeroLatencyError::database(format!("Failed to parse metadata: {}", e))
-            })?;
+    async fn search_in_collection(
+        &self,
+        collection_name: &str,
+        query_vector: Vec<f32>,
+        k: usize,
+    ) -> Result<Vec<SimilarityResult>> {
+        let _permit = self.read_semaphore.acquire().await.unwrap();
+
+        let settings = self
+            .collection_settings
+            .get(collection_name)
+            .map(|settings| *settings);
+        if let Some(settings) = &settings {
+            settings.check_query(collection_name, &query_vector)?;
+        }
+        let snapshot = self.vectors.snapshot();
+        let mut results = Vec::new();
+        let mut batch = ScoringBatch::new(settings.map(|s| s.metric).unwrap_or_default());
+        let mut total_processed = 0;
+        let mut collection_mismatches = 0;
 
-            let similarity = calculate_cosine_similarity(&query_vector, &embedding);
+        for (document_id, collection, embedding) in snapshot.rows() {
+            total_processed += 1;
 
-            let document_id = Uuid::parse_str(&id)
-                .map_err(|e| ZeroLatencyError::database(format!("Invalid UUID: {}", e)))?;
+            // Legacy documents without a collection belong to the default ones
+            if !in_collection(collection, collection_name) {
+                collection_mismatches += 1;
+                continue;
+            }
 
             results.push(SimilarityResult {
                 document_id,
-                similarity: Score::new(similarity).unwrap_or_else(|_| Score::new(0.0).unwrap()),
-                metadata,
+                similarity: Score::new(0.0).unwrap(),
+                metadata: VectorMetadata {
+                    collection: collection.map(str::to_string),
+                    ..Default::default()
+                },
             });
+            let row = results.len() - 1;
+            self.push_scored_row(&query_vector, embedding, row, &mut batch, &mut results);
         }
+        self.score_batch(&query_vector, &mut batch, &mut results);
+        drop(snapshot);
+        let collection_matches = results.len();
 
         // Sort by similarity score (descending)
         results.sort_by(|a, b| {
@@ -303,131 +1163,307 @@ impl VectorRepository for EmbeddedVectorStore {
 
         // Limit results
         results.truncate(k);
+        let conn = self.read_connection.lock().await;
+        load_metadata(&conn, &mut results, self.cipher.as_ref())?;
+
+        self.recycle_vector(query_vector);
 
+        tracing::debug!("EmbeddedVectorStore: Collection-specific search in '{}' - processed {} vectors, {} matches, {} mismatches, returned {} results",
+                       collection_name, total_processed, collection_matches, collection_mismatches, results.len());
         Ok(results)
     }
 
-    async fn search_in_collection(
+    async fn search_named(
         &self,
-        collection_name: &str,
+        collection_name: Option<&str>,
+        vector_name: &str,
         query_vector: Vec<f32>,
         k: usize,
+        filter: &MetadataFilter,
     ) -> Result<Vec<SimilarityResult>> {
+        if vector_name == DEFAULT_VECTOR {
+            return self
+                .search_filtered(collection_name, query_vector, k, filter)
+                .await;
+        }
         let _permit = self.read_semaphore.acquire().await.unwrap();
 
-        let conn = self.connection.lock().await;
+        // No chunk has a vector of that name yet
+        let Some(segments) = self
+            .named_vectors
+            .get(vector_name)
+            .map(|segments| Arc::clone(&segments))
+        else {
+            return Ok(Vec::new());
+        };
+        let snapshot = segments.snapshot();
+        let mut results = self.score_rows(snapshot.rows(), collection_name, &query_vector);
+        drop(snapshot);
+
+        results.truncate(filter.fetch_limit(k));
+        let conn = self.read_connection.lock().await;
+        load_metadata(&conn, &mut results, self.cipher.as_ref())?;
+        results.retain(|result| filter.matches_metadata(&result.metadata));
+        results.truncate(k);
+
+        self.recycle_vector(query_vector);
+        Ok(results)
+    }
+
+    async fn preload_collection(&self, collection_name: &str) -> Result<usize> {
+        let _permit = self.read_semaphore.acquire().await.unwrap();
+
+        // Reading every row pulls the collection's pages into SQLite's page
+        // cache; the vectors themselves fill the in-process cache up to its limit
+        let conn = self.read_connection.lock().await;
         let mut stmt = conn
-            .prepare("SELECT id, embedding, metadata FROM vectors")
-            .map_err(|e| {
-                ZeroLatencyError::database(format!("Failed to prepare collection search: {}", e))
-            })?;
+            .prepare(SCAN_ROWS)
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare preload: {}", e)))?;
 
         let rows = stmt
             .query_map([], |row| {
                 let id: String = row.get(0)?;
                 let embedding_blob: Vec<u8> = row.get(1)?;
-                let metadata_json: String = row.get(2)?;
-                Ok((id, embedding_blob, metadata_json))
+                let collection: Option<String> = row.get(2)?;
+                Ok((id, embedding_blob, collection))
             })
-            .map_err(|e| {
-                ZeroLatencyError::database(format!("Failed to execute collection search: {}", e))
-            })?;
-
-        let mut results = Vec::new();
-        let mut total_processed = 0;
-        let mut collection_matches = 0;
-        let mut collection_mismatches = 0;
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to execute preload: {}", e)))?;
 
+        let mut loaded = 0;
         for row in rows {
-            total_processed += 1;
-            let (id_str, embedding_blob, metadata_json) =
+            let (id, embedding_blob, collection) =
                 row.map_err(|e| ZeroLatencyError::database(format!("Failed to read row: {}", e)))?;
+            if !in_collection(collection.as_deref(), collection_name) {
+                continue;
+            }
 
-            // Deserialize metadata to check collection
-            let metadata: VectorMetadata = serde_json::from_str(&metadata_json).map_err(|e| {
-                ZeroLatencyError::database(format!("Failed to deserialize metadata: {}", e))
-            })?;
-
-            // Filter by collection - handle legacy data without collection field
-            if let Some(doc_collection) = &metadata.collection {
-                tracing::debug!(
-                    "Document has collection field: '{}', searching for: '{}'",
-                    doc_collection,
-                    collection_name
-                );
-                if doc_collection != collection_name {
-                    collection_mismatches += 1;
-                    tracing::debug!(
-                        "Collection mismatch: document='{}', search='{}'",
-                        doc_collection,
-                        collection_name
-                    );
-                    continue; // Skip documents not in this collection
-                }
-                collection_matches += 1;
-            } else {
-                // Legacy documents without collection field - assume they belong to zero_latency_docs
-                // This provides backward compatibility for existing data
-                tracing::debug!(
-                    "Legacy document (no collection field) found for search '{}'",
-                    collection_name
-                );
-                if collection_name != "zero_latency_docs" && collection_name != "default" {
-                    collection_mismatches += 1;
-                    tracing::debug!(
-                        "Legacy document rejected for non-default collection '{}'",
-                        collection_name
-                    );
-                    continue; // Skip legacy documents if searching for specific non-default collection
-                }
-                collection_matches += 1;
-                tracing::debug!(
-                    "Legacy document (no collection field) included in search for '{}'",
-                    collection_name
-                );
+            loaded += 1;
+            if self.cache.len() < self.config.cache_size && !self.cache.contains_key(&id) {
+                let mut vector = self.scratch_vector();
+                self.deserialize_vector_into(&embedding_blob, &mut vector)?;
+                self.cache.insert(id, vector);
             }
+        }
 
-            let document_embedding = self.deserialize_vector(&embedding_blob)?;
-            let similarity = calculate_cosine_similarity(&query_vector, &document_embedding);
+        tracing::debug!(
+            "EmbeddedVectorStore: preloaded {} vectors for collection '{}' ({} cached)",
+            loaded,
+            collection_name,
+            self.cache.len()
+        );
+        Ok(loaded)
+    }
 
-            let document_id = Uuid::parse_str(&id_str)
-                .map_err(|e| ZeroLatencyError::database(format!("Invalid UUID: {}", e)))?;
+    async fn collection_vectors(&self, collection_name: &str) -> Result<Vec<VectorDocument>> {
+        let _permit = self.read_semaphore.acquire().await.unwrap();
 
-            results.push(SimilarityResult {
-                document_id,
-                similarity: Score::new(similarity).unwrap_or_else(|_| Score::new(0.0).unwrap()),
+        let conn = self.read_connection.lock().await;
+        let mut stmt = conn
+            .prepare(SELECT_ROWS)
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare scan: {}", e)))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                let id: String = row.get(0)?;
+                let embedding_blob: Vec<u8> = row.get(1)?;
+                let metadata: Value = row.get(2)?;
+                let content: Value = row.get(3)?;
+                let collection: Option<String> = row.get(4)?;
+                Ok((id, embedding_blob, metadata, content, collection))
+            })
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to execute scan: {}", e)))?;
+
+        let mut named_vectors = self.named_vectors_by_id(&conn)?;
+        let mut vectors = Vec::new();
+        for row in rows {
+            let (id, embedding_blob, metadata, content, collection) =
+                row.map_err(|e| ZeroLatencyError::database(format!("Failed to read row: {}", e)))?;
+            if !in_collection(collection.as_deref(), collection_name) {
+                continue;
+            }
+            let metadata = decode_metadata(&metadata, &content, self.cipher.as_ref())?;
+
+            vectors.push(VectorDocument {
+                id: Uuid::parse_str(&id)
+                    .map_err(|e| ZeroLatencyError::database(format!("Invalid UUID: {}", e)))?,
+                embedding: self.deserialize_vector(&embedding_blob)?,
+                named_vectors: named_vectors.remove(&id).unwrap_or_default(),
                 metadata,
             });
         }
 
-        // Sort by similarity score (descending)
-        results.sort_by(|a, b| {
-            b.similarity
-                .value()
-                .partial_cmp(&a.similarity.value())
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        Ok(vectors)
+    }
 
-        // Limit results
-        results.truncate(k);
+    async fn document_chunks(
+        &self,
+        collection_name: &str,
+        document_id: Uuid,
+        chunk_range: RangeInclusive<usize>,
+    ) -> Result<Vec<(Uuid, VectorMetadata)>> {
+        let _permit = self.read_semaphore.acquire().await.unwrap();
 
-        tracing::debug!("EmbeddedVectorStore: Collection-specific search in '{}' - processed {} vectors, {} matches, {} mismatches, returned {} results",
-                       collection_name, total_processed, collection_matches, collection_mismatches, results.len());
-        Ok(results)
+        let conn = self.read_connection.lock().await;
+        let mut stmt = conn
+            .prepare_cached(&format!(
+                "{} WHERE v.document_id = ? AND v.chunk_index BETWEEN ? AND ? \
+                 ORDER BY v.chunk_index",
+                SELECT_ROWS
+            ))
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare lookup: {}", e)))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    document_id.to_string(),
+                    *chunk_range.start() as i64,
+                    (*chunk_range.end()).min(i64::MAX as usize) as i64
+                ],
+                |row| {
+                    let id: String = row.get(0)?;
+                    let metadata: Value = row.get(2)?;
+                    let content: Value = row.get(3)?;
+                    let collection: Option<String> = row.get(4)?;
+                    Ok((id, metadata, content, collection))
+                },
+            )
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to look up chunks: {}", e)))?;
+
+        let mut chunks = Vec::new();
+        for row in rows {
+            let (id, metadata, content, collection) =
+                row.map_err(|e| ZeroLatencyError::database(format!("Failed to read row: {}", e)))?;
+            if in_collection(collection.as_deref(), collection_name) {
+                let id = Uuid::parse_str(&id)
+                    .map_err(|e| ZeroLatencyError::database(format!("Invalid UUID: {}", e)))?;
+                chunks.push((
+                    id,
+                    decode_metadata(&metadata, &content, self.cipher.as_ref())?,
+                ));
+            }
+        }
+        Ok(chunks)
+    }
+
+    async fn configure_collection(
+        &self,
+        collection_name: &str,
+        settings: CollectionVectorSettings,
+    ) -> Result<()> {
+        settings.validate()?;
+        let conn = self.connection.lock().await;
+        conn.execute(
+            "INSERT OR REPLACE INTO collection_settings (collection, dimension, metric) \
+             VALUES (?, ?, ?)",
+            params![
+                collection_name,
+                settings.dimension as i64,
+                settings.metric.name()
+            ],
+        )
+        .map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to store collection settings: {}", e))
+        })?;
+        self.collection_settings
+            .insert(collection_name.to_string(), settings);
+        Ok(())
     }
 
     async fn delete(&self, document_id: &str) -> Result<bool> {
         let _permit = self.write_semaphore.acquire().await.unwrap();
 
         let conn = self.connection.lock().await;
-        let changes = conn
-            .execute("DELETE FROM vectors WHERE id = ?", params![document_id])
+        let transaction = conn
+            .unchecked_transaction()
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to begin delete: {}", e)))?;
+        let deleted: Option<Option<String>> = transaction
+            .query_row(
+                "DELETE FROM vectors WHERE id = ? RETURNING content_hash",
+                params![document_id],
+                |row| row.get(0),
+            )
+            .optional()
             .map_err(|e| ZeroLatencyError::database(format!("Failed to delete document: {}", e)))?;
+        if let Some(Some(hash)) = &deleted {
+            release_content(&transaction, hash)?;
+        }
+        transaction
+            .execute(
+                "DELETE FROM named_vectors WHERE id = ?",
+                params![document_id],
+            )
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to delete vectors: {}", e)))?;
+        transaction
+            .commit()
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to commit delete: {}", e)))?;
+        if let Ok(id) = Uuid::parse_str(document_id) {
+            self.vectors.remove(id);
+            self.remove_named(&[id]);
+        }
 
         // Remove from cache with concurrent access
-        self.cache.remove(document_id);
+        if let Some((_, vector)) = self.cache.remove(document_id) {
+            self.recycle_vector(vector);
+        }
+
+        Ok(deleted.is_some())
+    }
+
+    async fn delete_batch(&self, document_ids: &[String]) -> Result<usize> {
+        let _permit = self.write_semaphore.acquire().await.unwrap();
+
+        let conn = self.connection.lock().await;
+        let transaction = conn
+            .unchecked_transaction()
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to begin delete: {}", e)))?;
+        let mut deleted = 0;
+        {
+            let mut stmt = transaction
+                .prepare("DELETE FROM vectors WHERE id = ? RETURNING content_hash")
+                .map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to prepare delete: {}", e))
+                })?;
+            let mut delete_named = transaction
+                .prepare("DELETE FROM named_vectors WHERE id = ?")
+                .map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to prepare delete: {}", e))
+                })?;
+            for document_id in document_ids {
+                delete_named.execute(params![document_id]).map_err(|e| {
+                    ZeroLatencyError::database(format!("Failed to delete vectors: {}", e))
+                })?;
+                let removed: Option<Option<String>> = stmt
+                    .query_row(params![document_id], |row| row.get(0))
+                    .optional()
+                    .map_err(|e| {
+                        ZeroLatencyError::database(format!("Failed to delete document: {}", e))
+                    })?;
+                match removed {
+                    Some(Some(hash)) => {
+                        release_content(&transaction, &hash)?;
+                        deleted += 1;
+                    }
+                    Some(None) => deleted += 1,
+                    None => {}
+                }
+            }
+        }
+        transaction
+            .commit()
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to commit delete: {}", e)))?;
+        let ids: Vec<Uuid> = document_ids
+            .iter()
+            .filter_map(|document_id| Uuid::parse_str(document_id).ok())
+            .collect();
+        self.vectors.remove_batch(ids.iter().copied());
+        self.remove_named(&ids);
+
+        for document_id in document_ids {
+            if let Some((_, vector)) = self.cache.remove(document_id) {
+                self.recycle_vector(vector);
+            }
+        }
 
-        Ok(changes > 0)
+        Ok(deleted)
     }
 
     async fn update(&self, document_id: &str, vector: Vec<f32>) -> Result<bool> {
@@ -444,16 +1480,122 @@ impl VectorRepository for EmbeddedVectorStore {
             .map_err(|e| ZeroLatencyError::database(format!("Failed to update document: {}", e)))?;
 
         if changes > 0 {
+            if let Ok(id) = Uuid::parse_str(document_id) {
+                self.vectors.update_embedding(id, vector.clone());
+            }
             // Update cache with concurrent access
-            self.cache.insert(document_id.to_string(), vector);
+            if let Some(previous) = self.cache.insert(document_id.to_string(), vector) {
+                self.recycle_vector(previous);
+            }
             Ok(true)
         } else {
             Ok(false)
         }
     }
 
+    async fn storage_info(&self) -> Result<StorageInfo> {
+        let conn = self.read_connection.lock().await;
+        // Embeddings are stored with an 8-byte length prefix and 4 bytes per value,
+        // after the sealing overhead when encrypted. Text of rows stored before
+        // content addressing is still inline in the metadata, which was never
+        // compressed.
+        let embedding_length = format!(
+            "(length(v.embedding) - CASE WHEN substr(v.embedding, 1, {}) = X'{}' \
+             THEN {} ELSE 0 END)",
+            encryption::MAGIC.len(),
+            encryption::MAGIC
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect::<String>(),
+            encryption::OVERHEAD
+        );
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT v.collection, COUNT(*), MIN({0}), MAX({0}), \
+                 COALESCE(SUM(COALESCE(c.size, length(CAST(CASE WHEN typeof(v.metadata) = 'text' \
+                     THEN json_extract(v.metadata, '$.content') END AS BLOB)), 0)), 0), \
+                 COALESCE(SUM(c.size), 0) \
+                 FROM vectors v LEFT JOIN chunk_contents c ON c.hash = v.content_hash \
+                 GROUP BY 1 ORDER BY 1",
+                embedding_length
+            ))
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare query: {}", e)))?;
+        let mut segments = stmt
+            .query_map([], |row| {
+                let collection: Option<String> = row.get(0)?;
+                let vector_count: i64 = row.get(1)?;
+                let shortest: i64 = row.get(2)?;
+                let longest: i64 = row.get(3)?;
+                let chunk_bytes: i64 = row.get(4)?;
+                let addressed_bytes: i64 = row.get(5)?;
+                let segment = StorageSegment {
+                    collection,
+                    vector_count: vector_count as usize,
+                    dimension: (shortest == longest && shortest >= 8)
+                        .then(|| (shortest as usize - 8) / std::mem::size_of::<f32>()),
+                    chunk_bytes: chunk_bytes as u64,
+                    deduplicated_bytes: 0,
+                };
+                Ok((segment, addressed_bytes as u64))
+            })
+            .and_then(|rows| rows.collect::<std::result::Result<Vec<_>, _>>())
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read storage segments: {}", e))
+            })?;
+
+        // Savings within a collection: its addressed text minus each distinct chunk once
+        let mut stmt = conn
+            .prepare(
+                "SELECT collection, SUM(size) FROM ( \
+                     SELECT DISTINCT v.collection, v.content_hash, c.size \
+                     FROM vectors v JOIN chunk_contents c ON c.hash = v.content_hash) \
+                 GROUP BY 1",
+            )
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare query: {}", e)))?;
+        let stored = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, i64>(1)? as u64,
+                ))
+            })
+            .and_then(|rows| rows.collect::<std::result::Result<Vec<_>, _>>())
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read chunk storage: {}", e))
+            })?;
+        for (segment, addressed_bytes) in &mut segments {
+            let unique = stored
+                .iter()
+                .find(|(collection, _)| *collection == segment.collection)
+                .map_or(0, |(_, bytes)| *bytes);
+            segment.deduplicated_bytes = addressed_bytes.saturating_sub(unique);
+        }
+
+        let stored_bytes: i64 = conn
+            .query_row(
+                "SELECT COALESCE(SUM(size), 0) FROM chunk_contents",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read chunk storage: {}", e))
+            })?;
+        let addressed_bytes: u64 = segments.iter().map(|(_, bytes)| bytes).sum();
+        let segments: Vec<StorageSegment> =
+            segments.into_iter().map(|(segment, _)| segment).collect();
+
+        Ok(StorageInfo {
+            backend: "embedded".to_string(),
+            vector_count: segments.iter().map(|segment| segment.vector_count).sum(),
+            disk_bytes: std::fs::metadata(&self.db_path).map(|m| m.len()).ok(),
+            cached_vectors: self.cache.len(),
+            deduplicated_bytes: addressed_bytes.saturating_sub(stored_bytes as u64),
+            segments,
+        })
+    }
+
     async fn count(&self) -> Result<usize> {
-        let conn = self.connection.lock().await;
+        let conn = self.read_connection.lock().await;
         let count: i64 = conn
             .query_row("SELECT COUNT(*) FROM vectors", [], |row| row.get(0))
             .map_err(|e| ZeroLatencyError::database(format!("Failed to count documents: {}", e)))?;
@@ -462,7 +1604,7 @@ impl VectorRepository for EmbeddedVectorStore {
 
     async fn health_check(&self) -> Result<HealthStatus> {
         // Test database connectivity
-        let conn = self.connection.lock().await;
+        let conn = self.read_connection.lock().await;
         conn.query_row("SELECT 1", [], |_| Ok(())).map_err(|e| {
             ZeroLatencyError::database(format!("Database health check failed: {}", e))
         })?;
@@ -471,6 +1613,166 @@ impl VectorRepository for EmbeddedVectorStore {
     }
 }
 
+/// Vector rows with their chunk text, which is `NULL` for rows stored
+/// before content addressing
+const SELECT_ROWS: &str = "SELECT v.id, v.embedding, v.metadata, c.content, v.collection \
+                           FROM vectors v LEFT JOIN chunk_contents c ON c.hash = v.content_hash";
+
+/// What a scan needs to filter and score rows, leaving metadata undecoded
+const SCAN_ROWS: &str = "SELECT id, embedding, collection FROM vectors";
+
+fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Whether a row belongs to a collection; rows stored without a collection
+/// belong to the default ones
+fn in_collection(collection: Option<&str>, collection_name: &str) -> bool {
+    match collection {
+        Some(doc_collection) => doc_collection == collection_name,
+        None => collection_name == "zero_latency_docs" || collection_name == "default",
+    }
+}
+
+/// Add a column to a table that doesn't have it yet, returning whether it was added
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<bool> {
+    let exists = conn
+        .prepare(&format!(
+            "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?",
+            table
+        ))
+        .and_then(|mut stmt| stmt.exists(params![column]))
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to inspect schema: {}", e)))?;
+    if exists {
+        return Ok(false);
+    }
+    conn.execute(
+        &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+        [],
+    )
+    .map_err(|e| ZeroLatencyError::database(format!("Failed to add {} column: {}", column, e)))?;
+    Ok(true)
+}
+
+/// Record the document and chunk index of rows stored before they were
+/// kept beside the metadata
+fn backfill_chunk_positions(conn: &Connection, cipher: Option<&Cipher>) -> Result<()> {
+    let rows = conn
+        .prepare("SELECT id, metadata FROM vectors WHERE document_id IS NULL")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to read chunk positions: {}", e))
+        })?;
+    for (id, metadata) in rows {
+        let metadata = decode_metadata(&metadata, &Value::Null, cipher)?;
+        conn.execute(
+            "UPDATE vectors SET document_id = ?, chunk_index = ? WHERE id = ?",
+            params![
+                metadata.document_id.to_string(),
+                metadata.chunk_index as i64,
+                id
+            ],
+        )
+        .map_err(|e| {
+            ZeroLatencyError::database(format!("Failed to backfill chunk positions: {}", e))
+        })?;
+    }
+    Ok(())
+}
+
+/// Decode stored metadata, restoring content-addressed chunk text
+fn decode_metadata(
+    metadata: &Value,
+    content: &Value,
+    cipher: Option<&Cipher>,
+) -> Result<VectorMetadata> {
+    let metadata_json = encryption::decode_text(ValueRef::from(metadata), cipher)?;
+    let mut metadata: VectorMetadata = serde_json::from_str(&metadata_json).map_err(|e| {
+        ZeroLatencyError::database(format!("Failed to deserialize metadata: {}", e))
+    })?;
+    // Rows stored before content addressing keep their text inline
+    if *content != Value::Null {
+        metadata.content = encryption::decode_text(ValueRef::from(content), cipher)?;
+    }
+    Ok(metadata)
+}
+
+/// Fill in the metadata of scored results, dropping any deleted since the
+/// snapshot they were scored on
+fn load_metadata(
+    conn: &Connection,
+    results: &mut Vec<SimilarityResult>,
+    cipher: Option<&Cipher>,
+) -> Result<()> {
+    let mut stmt = conn
+        .prepare(&format!("{} WHERE v.id = ?", SELECT_ROWS))
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to prepare lookup: {}", e)))?;
+    let mut loaded = Vec::with_capacity(results.len());
+    for mut result in results.drain(..) {
+        let row: Option<(Value, Value)> = stmt
+            .query_row(params![result.document_id.to_string()], |row| {
+                Ok((row.get(2)?, row.get(3)?))
+            })
+            .optional()
+            .map_err(|e| ZeroLatencyError::database(format!("Failed to load metadata: {}", e)))?;
+        if let Some((metadata, content)) = row {
+            result.metadata = decode_metadata(&metadata, &content, cipher)?;
+            loaded.push(result);
+        }
+    }
+    *results = loaded;
+    Ok(())
+}
+
+/// Take a reference to chunk text, storing it at the given compression level if it is new
+fn acquire_content(
+    conn: &Connection,
+    hash: &str,
+    content: &str,
+    level: Option<i32>,
+    cipher: Option<&Cipher>,
+) -> Result<()> {
+    let referenced = conn
+        .execute(
+            "UPDATE chunk_contents SET ref_count = ref_count + 1 WHERE hash = ?",
+            params![hash],
+        )
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to store chunk content: {}", e)))?;
+    if referenced == 0 {
+        conn.execute(
+            "INSERT INTO chunk_contents (hash, content, ref_count, size) VALUES (?, ?, 1, ?)",
+            params![
+                hash,
+                encryption::encode_text(content, level, cipher)?,
+                content.len() as i64
+            ],
+        )
+        .map_err(|e| ZeroLatencyError::database(format!("Failed to store chunk content: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Drop a reference to chunk text, deleting it with the last reference
+fn release_content(conn: &Connection, hash: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE chunk_contents SET ref_count = ref_count - 1 WHERE hash = ?",
+        params![hash],
+    )
+    .and_then(|_| {
+        conn.execute(
+            "DELETE FROM chunk_contents WHERE hash = ? AND ref_count <= 0",
+            params![hash],
+        )
+    })
+    .map_err(|e| ZeroLatencyError::database(format!("Failed to release chunk content: {}", e)))?;
+    Ok(())
+}
+
 /// Statistics for embedded vector store
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddedStats {
@@ -514,8 +1816,10 @@ impl SimilarityCalculator for CosineCalculator {
 
 #[cfg(test)]
 mod tests {
+    use super::super::compression;
     use super::*;
     use tempfile::tempdir;
+    use zero_latency_vector::TITLE_VECTOR;
 
     #[tokio::test]
     async fn test_embedded_store_basic_operations() {
@@ -526,6 +1830,7 @@ mod tests {
             cache_size: 100,
             enable_string_interning: false,
             enable_smart_caching: false,
+            ..Default::default()
         };
 
         let store = EmbeddedVectorStore::new(config).await.unwrap();
@@ -535,6 +1840,7 @@ mod tests {
         let doc = VectorDocument {
             id: doc_id,
             embedding: vec![1.0, 0.0, 0.0],
+            named_vectors: Default::default(),
             metadata: VectorMetadata {
                 document_id: Uuid::new_v4(),
                 chunk_index: 0,
@@ -544,6 +1850,7 @@ mod tests {
                 url: None,
                 custom: std::collections::HashMap::new(),
                 collection: Some("default".to_string()),
+                ..Default::default()
             },
         };
 
@@ -573,6 +1880,7 @@ mod tests {
             cache_size: 100,
             enable_string_interning: false,
             enable_smart_caching: false,
+            ..Default::default()
         };
 
         // Create store and insert data
@@ -581,6 +1889,7 @@ mod tests {
             let doc = VectorDocument {
                 id: Uuid::new_v4(),
                 embedding: vec![0.5, 0.5, 0.0],
+                named_vectors: Default::default(),
                 metadata: VectorMetadata {
                     document_id: Uuid::new_v4(),
                     chunk_index: 0,
@@ -590,6 +1899,7 @@ mod tests {
                     url: None,
                     custom: std::collections::HashMap::new(),
                     collection: Some("default".to_string()),
+                    ..Default::default()
                 },
             };
             store.insert(vec![doc]).await.unwrap();
@@ -605,21 +1915,752 @@ mod tests {
             assert_eq!(results[0].metadata.title, "persist1");
         }
     }
-}
 
-/// Calculate cosine similarity between two vectors
-fn calculate_cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
+    #[tokio::test]
+    async fn test_embedded_store_preloads_collection() {
+        let temp_dir = tempdir().unwrap();
+        let config = EmbeddedConfig {
+            db_path: temp_dir.path().join("preload_test.db"),
+            dimension: 3,
+            cache_size: 100,
+            enable_string_interning: false,
+            enable_smart_caching: false,
+            ..Default::default()
+        };
+
+        {
+            let store = EmbeddedVectorStore::new(config.clone()).await.unwrap();
+            let docs = ["guides", "guides", "api"]
+                .iter()
+                .map(|collection| VectorDocument {
+                    id: Uuid::new_v4(),
+                    embedding: vec![1.0, 0.0, 0.0],
+                    named_vectors: Default::default(),
+                    metadata: VectorMetadata {
+                        document_id: Uuid::new_v4(),
+                        chunk_index: 0,
+                        content: "content".to_string(),
+                        title: collection.to_string(),
+                        heading_path: vec![],
+                        url: None,
+                        custom: std::collections::HashMap::new(),
+                        collection: Some(collection.to_string()),
+                        ..Default::default()
+                    },
+                })
+                .collect();
+            store.insert(docs).await.unwrap();
+        }
+
+        // A fresh instance starts cold and only loads the requested collection
+        let store = EmbeddedVectorStore::new(config).await.unwrap();
+        assert_eq!(store.get_stats().await.unwrap().cache_size, 0);
+        assert_eq!(store.preload_collection("guides").await.unwrap(), 2);
+        assert_eq!(store.get_stats().await.unwrap().cache_size, 2);
+        assert_eq!(store.preload_collection("missing").await.unwrap(), 0);
+
+        let api_vectors = store.collection_vectors("api").await.unwrap();
+        assert_eq!(api_vectors.len(), 1);
+        assert_eq!(api_vectors[0].metadata.title, "api");
+        assert_eq!(api_vectors[0].embedding, vec![1.0, 0.0, 0.0]);
+
+        let info = store.storage_info().await.unwrap();
+        assert_eq!(info.vector_count, 3);
+        assert_eq!(info.cached_vectors, 2);
+        assert!(info.disk_bytes.unwrap() > 0);
+        let segments: Vec<(Option<&str>, usize, Option<usize>)> = info
+            .segments
+            .iter()
+            .map(|s| (s.collection.as_deref(), s.vector_count, s.dimension))
+            .collect();
+        assert_eq!(
+            segments,
+            vec![(Some("api"), 1, Some(3)), (Some("guides"), 2, Some(3))]
+        );
+    }
+
+    async fn content_ref_counts(store: &EmbeddedVectorStore) -> Vec<i64> {
+        let conn = store.connection.lock().await;
+        let mut stmt = conn
+            .prepare("SELECT ref_count FROM chunk_contents")
+            .unwrap();
+        let counts = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|count| count.unwrap())
+            .collect();
+        counts
+    }
+
+    #[tokio::test]
+    async fn test_embedded_store_deduplicates_chunk_content() {
+        let temp_dir = tempdir().unwrap();
+        let config = EmbeddedConfig {
+            db_path: temp_dir.path().join("dedup_test.db"),
+            dimension: 3,
+            cache_size: 100,
+            enable_string_interning: false,
+            enable_smart_caching: false,
+            ..Default::default()
+        };
+        let store = EmbeddedVectorStore::new(config).await.unwrap();
+
+        let boilerplate = "Copyright footer shared by every page";
+        let ids: Vec<Uuid> = (0..2).map(|_| Uuid::new_v4()).collect();
+        let docs = ids
+            .iter()
+            .map(|id| VectorDocument {
+                id: *id,
+                embedding: vec![1.0, 0.0, 0.0],
+                named_vectors: Default::default(),
+                metadata: VectorMetadata {
+                    document_id: Uuid::new_v4(),
+                    chunk_index: 0,
+                    content: boilerplate.to_string(),
+                    title: "page".to_string(),
+                    heading_path: vec![],
+                    url: None,
+                    custom: std::collections::HashMap::new(),
+                    collection: Some("guides".to_string()),
+                    ..Default::default()
+                },
+            })
+            .collect();
+        store.insert(docs).await.unwrap();
+
+        assert_eq!(content_ref_counts(&store).await, vec![2]);
+
+        let info = store.storage_info().await.unwrap();
+        let len = boilerplate.len() as u64;
+        assert_eq!(info.deduplicated_bytes, len);
+        assert_eq!(info.segments[0].chunk_bytes, 2 * len);
+        assert_eq!(info.segments[0].deduplicated_bytes, len);
+
+        let results = store.search(vec![1.0, 0.0, 0.0], 10).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.metadata.content == boilerplate));
+
+        assert!(store.delete(&ids[0].to_string()).await.unwrap());
+        assert_eq!(content_ref_counts(&store).await, vec![1]);
+        assert_eq!(store.storage_info().await.unwrap().deduplicated_bytes, 0);
+
+        assert!(store.delete(&ids[1].to_string()).await.unwrap());
+        assert!(content_ref_counts(&store).await.is_empty());
     }
 
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let magnitude_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let magnitude_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    #[tokio::test]
+    async fn test_embedded_store_compresses_per_collection() {
+        let temp_dir = tempdir().unwrap();
+        let config = EmbeddedConfig {
+            db_path: temp_dir.path().join("compression_test.db"),
+            dimension: 3,
+            cache_size: 100,
+            enable_string_interning: false,
+            enable_smart_caching: false,
+            compression: CompressionConfig {
+                enabled: true,
+                level: 3,
+                collections: [(
+                    "plain".to_string(),
+                    compression::CollectionCompression {
+                        enabled: Some(false),
+                        level: None,
+                    },
+                )]
+                .into(),
+            },
+            ..Default::default()
+        };
+        let store = EmbeddedVectorStore::new(config).await.unwrap();
+
+        let text = "Every section of the handbook repeats this paragraph. ".repeat(40);
+        let docs = [("guides", 0.0), ("plain", 1.0)]
+            .iter()
+            .map(|(collection, y)| VectorDocument {
+                id: Uuid::new_v4(),
+                embedding: vec![1.0, *y, 0.0],
+                named_vectors: Default::default(),
+                metadata: VectorMetadata {
+                    document_id: Uuid::new_v4(),
+                    chunk_index: 0,
+                    content: format!("{} {}", collection, text),
+                    title: collection.to_string(),
+                    heading_path: vec!["Handbook".to_string(); 8],
+                    url: None,
+                    custom: std::collections::HashMap::new(),
+                    collection: Some(collection.to_string()),
+                    ..Default::default()
+                },
+            })
+            .collect();
+        store.insert(docs).await.unwrap();
 
-    if magnitude_a == 0.0 || magnitude_b == 0.0 {
-        return 0.0;
+        {
+            let conn = store.connection.lock().await;
+            let stored: Vec<(String, String, String)> = conn
+                .prepare(
+                    "SELECT v.collection, typeof(v.metadata), typeof(c.content) \
+                     FROM vectors v JOIN chunk_contents c ON c.hash = v.content_hash \
+                     ORDER BY 1",
+                )
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .unwrap()
+                .map(|row| row.unwrap())
+                .collect();
+            assert_eq!(
+                stored,
+                vec![
+                    ("guides".to_string(), "blob".to_string(), "blob".to_string()),
+                    ("plain".to_string(), "text".to_string(), "text".to_string()),
+                ]
+            );
+        }
+
+        let results = store.search(vec![1.0, 0.0, 0.0], 1).await.unwrap();
+        assert_eq!(results[0].metadata.title, "guides");
+        assert_eq!(results[0].metadata.content, format!("guides {}", text));
+        let plain = store
+            .search_in_collection("plain", vec![1.0, 0.0, 0.0], 10)
+            .await
+            .unwrap();
+        assert_eq!(plain.len(), 1);
+        assert_eq!(plain[0].metadata.heading_path.len(), 8);
+
+        let guides = store.collection_vectors("guides").await.unwrap();
+        assert_eq!(guides[0].metadata.content, format!("guides {}", text));
+
+        // Statistics count the text as written, not as compressed
+        let info = store.storage_info().await.unwrap();
+        assert_eq!(
+            info.segments[0].chunk_bytes,
+            format!("guides {}", text).len() as u64
+        );
     }
 
-    dot_product / (magnitude_a * magnitude_b)
+    #[tokio::test]
+    async fn test_embedded_store_encrypts_at_rest() {
+        let temp_dir = tempdir().unwrap();
+        let key = "8f".repeat(32);
+        let config = |key: Option<&str>| EmbeddedConfig {
+            db_path: temp_dir.path().join("encryption_test.db"),
+            dimension: 3,
+            cache_size: 100,
+            enable_string_interning: false,
+            enable_smart_caching: false,
+            // Uncompressed, the text would be readable in the file if it
+            // weren't sealed
+            compression: CompressionConfig {
+                enabled: false,
+                level: 3,
+                collections: Default::default(),
+            },
+            encryption: EncryptionConfig {
+                key: key.map(str::to_string),
+                key_file: None,
+            },
+            ..Default::default()
+        };
+        let secret = "The launch code is swordfish. ".repeat(10);
+        let document = |title: &str, y: f32| VectorDocument {
+            id: Uuid::new_v4(),
+            embedding: vec![1.0, y, 0.0],
+            named_vectors: [(TITLE_VECTOR.to_string(), vec![0.0, 1.0, y])].into(),
+            metadata: VectorMetadata {
+                document_id: Uuid::new_v4(),
+                content: format!("{} {}", title, secret),
+                title: title.to_string(),
+                collection: Some("vault".to_string()),
+                ..Default::default()
+            },
+        };
+
+        // Rows written before a key is set are sealed when the store opens
+        // with it
+        let store = EmbeddedVectorStore::new(config(None)).await.unwrap();
+        store.insert(vec![document("before", 0.0)]).await.unwrap();
+        drop(store);
+        let store = EmbeddedVectorStore::new(config(Some(&key))).await.unwrap();
+        store.insert(vec![document("after", 1.0)]).await.unwrap();
+
+        let results = store.search(vec![1.0, 0.0, 0.0], 2).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].metadata.title, "before");
+        assert_eq!(results[0].metadata.content, format!("before {}", secret));
+        let vault = store.collection_vectors("vault").await.unwrap();
+        assert!(vault
+            .iter()
+            .all(|document| document.named_vectors.contains_key(TITLE_VECTOR)));
+        let info = store.storage_info().await.unwrap();
+        assert_eq!(info.segments[0].dimension, Some(3));
+        drop(store);
+
+        let file = std::fs::read(temp_dir.path().join("encryption_test.db")).unwrap();
+        assert!(!file.windows(9).any(|window| window == b"swordfish"));
+
+        // The store doesn't open without the key, or with another one
+        assert!(EmbeddedVectorStore::new(config(None)).await.is_err());
+        let other = "11".repeat(32);
+        assert!(EmbeddedVectorStore::new(config(Some(&other)))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_embedded_store_recycles_pooled_vectors() {
+        use crate::infrastructure::memory::VectorPoolConfig;
+
+        let temp_dir = tempdir().unwrap();
+        let config = EmbeddedConfig {
+            db_path: temp_dir.path().join("pool_test.db"),
+            dimension: 3,
+            cache_size: 100,
+            enable_string_interning: false,
+            enable_smart_caching: false,
+            ..Default::default()
+        };
+        let pool = Arc::new(VectorPool::new(VectorPoolConfig {
+            max_pool_size: 10,
+            dimension: 3,
+            dimension_tolerance: 1,
+        }));
+
+        let store = EmbeddedVectorStore::new(config)
+            .await
+            .unwrap()
+            .with_vector_pool(pool.clone());
+
+        let blob = store.serialize_vector(&[0.25, -1.0, 3.5]).unwrap();
+        let mut decoded = vec![9.0; 8];
+        store.deserialize_vector_into(&blob, &mut decoded).unwrap();
+        assert_eq!(decoded, store.deserialize_vector(&blob).unwrap());
+        assert!(store
+            .deserialize_vector_into(&blob[..9], &mut decoded)
+            .is_err());
+
+        let doc = VectorDocument {
+            id: Uuid::new_v4(),
+            embedding: vec![0.0, 1.0, 0.0],
+            named_vectors: Default::default(),
+            metadata: VectorMetadata {
+                document_id: Uuid::new_v4(),
+                chunk_index: 0,
+                content: "pooled content".to_string(),
+                title: "pooled".to_string(),
+                heading_path: vec![],
+                url: None,
+                custom: std::collections::HashMap::new(),
+                collection: Some("default".to_string()),
+                ..Default::default()
+            },
+        };
+        store.insert(vec![doc]).await.unwrap();
+
+        // Searches score the snapshot and hand their query vectors back to
+        // the pool, where the next buffer comes from
+        for _ in 0..2 {
+            let results = store.search(vec![0.0, 1.0, 0.0], 10).await.unwrap();
+            assert_eq!(results.len(), 1);
+            assert!(results[0].similarity.value() > 0.99);
+        }
+        assert_eq!(pool.stats().pool_size, 2);
+
+        store.recycle_vector(store.scratch_vector());
+        let stats = pool.stats();
+        assert_eq!(stats.misses, 0);
+        assert_eq!(stats.hits, 1);
+        assert!(stats.bytes_saved > 0);
+    }
+
+    fn stress_document(id: Uuid, position: f32) -> VectorDocument {
+        VectorDocument {
+            id,
+            embedding: vec![position.cos(), position.sin(), 0.0],
+            named_vectors: Default::default(),
+            metadata: VectorMetadata {
+                document_id: id,
+                chunk_index: 0,
+                content: format!("content {}", position),
+                title: format!("doc {}", position),
+                collection: Some("default".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_embedded_store_searches_do_not_wait_for_writers() {
+        let temp_dir = tempdir().unwrap();
+        let config = EmbeddedConfig {
+            db_path: temp_dir.path().join("snapshot_test.db"),
+            dimension: 3,
+            memtable_capacity: 4,
+            max_segments: 2,
+            ..Default::default()
+        };
+        let store = EmbeddedVectorStore::new(config).await.unwrap();
+        let ids: Vec<Uuid> = (0..10).map(|_| Uuid::new_v4()).collect();
+        let docs = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| stress_document(*id, i as f32 * 0.1))
+            .collect();
+        store.insert(docs).await.unwrap();
+        assert!(store.segment_count() <= 2);
+
+        // A writer holding the database still lets searches finish
+        let writer = store.connection.lock().await;
+        let results = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            store.search(vec![1.0, 0.0, 0.0], 3),
+        )
+        .await
+        .expect("search waited for the writer")
+        .unwrap();
+        drop(writer);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].document_id, ids[0]);
+
+        // Deletes and updates reach the snapshot
+        assert!(store.delete(&ids[0].to_string()).await.unwrap());
+        assert!(store
+            .update(&ids[9].to_string(), vec![1.0, 0.0, 0.0])
+            .await
+            .unwrap());
+        let results = store.search(vec![1.0, 0.0, 0.0], 3).await.unwrap();
+        assert_eq!(results[0].document_id, ids[9]);
+        assert!(results.iter().all(|result| result.document_id != ids[0]));
+        assert!(results[0].metadata.title.starts_with("doc 0.9"));
+    }
+
+    #[tokio::test]
+    async fn test_embedded_store_bulk_upserts_and_deletes() {
+        let temp_dir = tempdir().unwrap();
+        let config = EmbeddedConfig {
+            db_path: temp_dir.path().join("bulk_test.db"),
+            dimension: 3,
+            ..Default::default()
+        };
+        let store = EmbeddedVectorStore::new(config).await.unwrap();
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        store
+            .upsert_batch(
+                ids.iter()
+                    .enumerate()
+                    .map(|(i, id)| stress_document(*id, i as f32 * 0.1))
+                    .collect(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(store.count().await.unwrap(), 5);
+
+        // Replacing a stored vector keeps one row per id
+        store
+            .upsert_batch(vec![stress_document(ids[4], 0.0)])
+            .await
+            .unwrap();
+        assert_eq!(store.count().await.unwrap(), 5);
+        let results = store.search(vec![1.0, 0.0, 0.0], 2).await.unwrap();
+        let top: std::collections::HashSet<Uuid> =
+            results.iter().map(|result| result.document_id).collect();
+        assert_eq!(top, [ids[0], ids[4]].into_iter().collect());
+
+        let doomed = vec![
+            ids[0].to_string(),
+            ids[1].to_string(),
+            Uuid::new_v4().to_string(),
+        ];
+        assert_eq!(store.delete_batch(&doomed).await.unwrap(), 2);
+        assert_eq!(store.count().await.unwrap(), 3);
+        let results = store.search(vec![1.0, 0.0, 0.0], 10).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results
+            .iter()
+            .all(|result| result.document_id != ids[0] && result.document_id != ids[1]));
+    }
+
+    #[tokio::test]
+    async fn test_embedded_store_looks_up_neighboring_chunks() {
+        let temp_dir = tempdir().unwrap();
+        let config = EmbeddedConfig {
+            db_path: temp_dir.path().join("chunks_test.db"),
+            dimension: 3,
+            ..Default::default()
+        };
+        let store = EmbeddedVectorStore::new(config).await.unwrap();
+        let (document_id, other_document) = (Uuid::new_v4(), Uuid::new_v4());
+        let chunk = |document_id: Uuid, chunk_index: usize, collection: &str| {
+            let mut chunk = stress_document(Uuid::new_v4(), chunk_index as f32 * 0.1);
+            chunk.metadata.document_id = document_id;
+            chunk.metadata.chunk_index = chunk_index;
+            chunk.metadata.collection = Some(collection.to_string());
+            chunk
+        };
+        store
+            .upsert_batch(vec![
+                chunk(document_id, 3, "default"),
+                chunk(document_id, 0, "default"),
+                chunk(document_id, 1, "default"),
+                chunk(document_id, 2, "default"),
+                chunk(other_document, 1, "default"),
+                chunk(document_id, 1, "archive"),
+            ])
+            .await
+            .unwrap();
+
+        let chunks = store
+            .document_chunks("default", document_id, 1..=3)
+            .await
+            .unwrap();
+        let indexes: Vec<usize> = chunks.iter().map(|(_, chunk)| chunk.chunk_index).collect();
+        assert_eq!(indexes, vec![1, 2, 3]);
+        assert!(chunks
+            .iter()
+            .all(|(_, chunk)| chunk.document_id == document_id
+                && chunk.content.starts_with("content")));
+        assert!(store
+            .document_chunks("blog", document_id, 0..=3)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    /// Ids of the default collection's chunks nearest to `[1, 0, 0]` in the
+    /// vector space `name`
+    async fn named_search(store: &EmbeddedVectorStore, name: &str) -> Vec<Uuid> {
+        store
+            .search_named(
+                Some("default"),
+                name,
+                vec![1.0, 0.0, 0.0],
+                10,
+                &MetadataFilter::default(),
+            )
+            .await
+            .unwrap()
+            .iter()
+            .map(|result| result.document_id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_embedded_store_searches_named_vectors() {
+        let temp_dir = tempdir().unwrap();
+        let config = EmbeddedConfig {
+            db_path: temp_dir.path().join("named_test.db"),
+            dimension: 3,
+            ..Default::default()
+        };
+        let store = EmbeddedVectorStore::new(config.clone()).await.unwrap();
+        let with_title = |id: Uuid, position: f32, title: Vec<f32>| VectorDocument {
+            named_vectors: [(TITLE_VECTOR.to_string(), title)].into_iter().collect(),
+            ..stress_document(id, position)
+        };
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        store
+            .upsert_batch(vec![
+                with_title(ids[0], 0.0, vec![0.0, 0.0, 1.0]),
+                with_title(ids[1], 1.5, vec![1.0, 0.0, 0.0]),
+                stress_document(ids[2], 0.1),
+            ])
+            .await
+            .unwrap();
+
+        // Only chunks with a title vector are found in the title space
+        let filter = MetadataFilter::default();
+        assert_eq!(
+            named_search(&store, TITLE_VECTOR).await,
+            vec![ids[1], ids[0]]
+        );
+        assert_eq!(named_search(&store, DEFAULT_VECTOR).await[0], ids[0]);
+        assert!(named_search(&store, "summary").await.is_empty());
+        let other = store
+            .search_named(
+                Some("other"),
+                TITLE_VECTOR,
+                vec![1.0, 0.0, 0.0],
+                10,
+                &filter,
+            )
+            .await
+            .unwrap();
+        assert!(other.is_empty());
+
+        // Named vectors are persisted and copied with their collection
+        drop(store);
+        let store = EmbeddedVectorStore::new(config).await.unwrap();
+        assert_eq!(
+            named_search(&store, TITLE_VECTOR).await,
+            vec![ids[1], ids[0]]
+        );
+        let copied = store.collection_vectors("default").await.unwrap();
+        let copied = copied.iter().find(|vector| vector.id == ids[1]).unwrap();
+        assert_eq!(copied.vector(TITLE_VECTOR), Some(&[1.0, 0.0, 0.0][..]));
+
+        // Replacing a chunk without a title vector, or deleting it, drops it
+        store
+            .upsert_batch(vec![stress_document(ids[1], 1.5)])
+            .await
+            .unwrap();
+        assert!(store.delete(&ids[0].to_string()).await.unwrap());
+        assert!(named_search(&store, TITLE_VECTOR).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_embedded_store_scores_collections_by_their_metric() {
+        let temp_dir = tempdir().unwrap();
+        let config = EmbeddedConfig {
+            db_path: temp_dir.path().join("metric_test.db"),
+            dimension: 2,
+            ..Default::default()
+        };
+        let document = |collection: &str, embedding: Vec<f32>| VectorDocument {
+            id: Uuid::new_v4(),
+            embedding,
+            named_vectors: Default::default(),
+            metadata: VectorMetadata {
+                document_id: Uuid::new_v4(),
+                content: "metric content".to_string(),
+                collection: Some(collection.to_string()),
+                ..Default::default()
+            },
+        };
+        // Same direction as the query but far away, and nearby but off-axis
+        let (long, near) = (vec![10.0, 0.0], vec![0.9, 0.3]);
+        {
+            let store = EmbeddedVectorStore::new(config.clone()).await.unwrap();
+            for (collection, metric) in [
+                ("angles", SimilarityMetric::Cosine),
+                ("inner", SimilarityMetric::DotProduct),
+                ("places", SimilarityMetric::Euclidean),
+            ] {
+                store
+                    .configure_collection(
+                        collection,
+                        CollectionVectorSettings {
+                            dimension: 2,
+                            metric,
+                        },
+                    )
+                    .await
+                    .unwrap();
+                store
+                    .insert(vec![
+                        document(collection, long.clone()),
+                        document(collection, near.clone()),
+                    ])
+                    .await
+                    .unwrap();
+            }
+        }
+
+        // Settings survive a restart
+        let store = EmbeddedVectorStore::new(config).await.unwrap();
+        let nearest = |collection: &'static str| {
+            let store = &store;
+            async move {
+                let results = store
+                    .search_in_collection(collection, vec![1.0, 0.0], 2)
+                    .await
+                    .unwrap();
+                assert_eq!(results.len(), 2);
+                assert!(results
+                    .iter()
+                    .all(|r| (0.0..=1.0).contains(&r.similarity.value())));
+                results[0].similarity.value()
+            }
+        };
+        // Cosine ties the long vector at 1; dot favours it; euclidean the near one
+        assert!((nearest("angles").await - 1.0).abs() < 1e-6);
+        assert!(nearest("inner").await > 0.99);
+        assert!((nearest("places").await - 1.0 / (1.0 + 0.1f32.hypot(0.3))).abs() < 1e-5);
+
+        // Unscoped searches score each row by its own collection's metric
+        let results = store.search(vec![1.0, 0.0], 6).await.unwrap();
+        assert_eq!(results.len(), 6);
+        let farthest = results.last().unwrap();
+        assert_eq!(farthest.metadata.collection.as_deref(), Some("places"));
+        assert!((farthest.similarity.value() - 0.1).abs() < 1e-5);
+
+        let error = store
+            .search_in_collection("places", vec![1.0, 0.0, 0.0], 2)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("2-dimensional"), "{}", error);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_embedded_store_concurrent_readers_and_writers() {
+        let temp_dir = tempdir().unwrap();
+        let config = EmbeddedConfig {
+            db_path: temp_dir.path().join("stress_test.db"),
+            dimension: 3,
+            memtable_capacity: 8,
+            max_segments: 3,
+            ..Default::default()
+        };
+        let store = Arc::new(EmbeddedVectorStore::new(config).await.unwrap());
+
+        // The writer inserts batches and deletes every other document
+        let writer = {
+            let store = store.clone();
+            tokio::spawn(async move {
+                let mut kept = Vec::new();
+                for batch in 0..20 {
+                    let docs: Vec<VectorDocument> = (0..10)
+                        .map(|i| stress_document(Uuid::new_v4(), (batch * 10 + i) as f32 * 0.01))
+                        .collect();
+                    let ids: Vec<Uuid> = docs.iter().map(|doc| doc.id).collect();
+                    store.insert(docs).await.unwrap();
+                    for (i, id) in ids.into_iter().enumerate() {
+                        if i % 2 == 0 {
+                            assert!(store.delete(&id.to_string()).await.unwrap());
+                        } else {
+                            kept.push(id);
+                        }
+                    }
+                }
+                kept
+            })
+        };
+
+        let readers: Vec<_> = (0..4)
+            .map(|reader| {
+                let store = store.clone();
+                tokio::spawn(async move {
+                    let mut searches = 0;
+                    for i in 0..50 {
+                        let angle = (reader * 50 + i) as f32 * 0.01;
+                        let query = vec![angle.cos(), angle.sin(), 0.0];
+                        let results = store.search(query.clone(), 5).await.unwrap();
+                        assert!(results.len() <= 5);
+                        assert!(results
+                            .windows(2)
+                            .all(|pair| pair[0].similarity.value() >= pair[1].similarity.value()));
+                        let results = store
+                            .search_in_collection("default", query, 5)
+                            .await
+                            .unwrap();
+                        assert!(results.len() <= 5);
+                        searches += 1;
+                    }
+                    searches
+                })
+            })
+            .collect();
+
+        let kept = writer.await.unwrap();
+        for reader in readers {
+            assert_eq!(reader.await.unwrap(), 50);
+        }
+
+        assert_eq!(store.count().await.unwrap(), kept.len());
+        let results = store.search(vec![1.0, 0.0, 0.0], 1_000).await.unwrap();
+        let mut found: Vec<Uuid> = results.iter().map(|result| result.document_id).collect();
+        let mut expected = kept;
+        found.sort();
+        expected.sort();
+        assert_eq!(found, expected);
+        assert!(store.segment_count() <= 3);
+    }
 }
@@ -0,0 +1,272 @@
+/// Encryption at rest of the embedded vector store
+///
+/// With a key configured, embeddings, metadata and chunk text are sealed with
+/// AES-256-GCM before they are written, each value under its own random nonce.
+/// A sealed value is a blob of [`MAGIC`], the nonce and the ciphertext. Plain
+/// vectors start with their length and compressed text with a zstd frame, so
+/// neither can be mistaken for it: rows written before a key was set stay
+/// readable and are sealed when the store next opens with the key. Ids,
+/// collection names, chunk positions and content hashes stay in the clear so
+/// rows can be found without decrypting them.
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rusqlite::types::{Value, ValueRef};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::path::PathBuf;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use super::compression;
+
+/// Leading bytes of a sealed value
+pub const MAGIC: [u8; 4] = *b"ZLE\x01";
+
+const NONCE_LEN: usize = 12;
+
+/// Bytes sealing adds to a value: the magic, the nonce and the GCM tag
+pub const OVERHEAD: usize = MAGIC.len() + NONCE_LEN + 16;
+
+/// First byte of sealed text, recording how it was encoded before sealing
+const PLAIN_TEXT: u8 = 0;
+const COMPRESSED_TEXT: u8 = 1;
+
+/// Key of the embedded store's encryption at rest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    /// 256-bit key as 64 hex digits, e.g. from `openssl rand -hex 32`
+    #[serde(skip_serializing)]
+    pub key: Option<String>,
+
+    /// File holding the key, such as a secret mounted by the orchestrator;
+    /// used when `key` is unset
+    pub key_file: Option<PathBuf>,
+}
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Self {
+            key: var("DOC_INDEXER_ENCRYPTION_KEY"),
+            key_file: var("DOC_INDEXER_ENCRYPTION_KEY_FILE").map(PathBuf::from),
+        }
+    }
+}
+
+impl EncryptionConfig {
+    /// The cipher of the configured key, or `None` when encryption is off
+    pub fn cipher(&self) -> Result<Option<Cipher>> {
+        let key = match (&self.key, &self.key_file) {
+            (Some(key), _) => key.clone(),
+            (None, Some(path)) => std::fs::read_to_string(path).map_err(|e| {
+                ZeroLatencyError::configuration(format!(
+                    "Failed to read encryption key file {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?,
+            (None, None) => return Ok(None),
+        };
+        parse_key(key.trim()).map(|key| Some(Cipher::new(&key)))
+    }
+}
+
+fn parse_key(key: &str) -> Result<[u8; 32]> {
+    let invalid =
+        || ZeroLatencyError::configuration("Encryption key must be 64 hex digits (256 bits)");
+    if key.len() != 64 || !key.is_ascii() {
+        return Err(invalid());
+    }
+    let mut bytes = [0u8; 32];
+    for (byte, digits) in bytes.iter_mut().zip(key.as_bytes().chunks(2)) {
+        let digits = std::str::from_utf8(digits).map_err(|_| invalid())?;
+        *byte = u8::from_str_radix(digits, 16).map_err(|_| invalid())?;
+    }
+    Ok(bytes)
+}
+
+/// Seals and opens stored values with the configured key
+#[derive(Clone)]
+pub struct Cipher {
+    cipher: Aes256Gcm,
+}
+
+// The key must not end up in logs
+impl std::fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Cipher(AES-256-GCM)")
+    }
+}
+
+impl Cipher {
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(key.into()),
+        }
+    }
+
+    /// Encrypt `plaintext` under a fresh random nonce
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| ZeroLatencyError::database("Failed to encrypt stored value"))?;
+        let mut sealed = Vec::with_capacity(OVERHEAD + plaintext.len());
+        sealed.extend_from_slice(&MAGIC);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// Decrypt a value written by [`Cipher::seal`]
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < OVERHEAD || !is_sealed(sealed) {
+            return Err(ZeroLatencyError::database("Stored value is not encrypted"));
+        }
+        let (nonce, ciphertext) = sealed[MAGIC.len()..].split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                ZeroLatencyError::database(
+                    "Failed to decrypt stored value; is the encryption key the one it was written with?",
+                )
+            })
+    }
+}
+
+/// Whether stored bytes were sealed
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(&MAGIC)
+}
+
+/// Seal bytes if a cipher is given
+pub fn seal_bytes(bytes: Vec<u8>, cipher: Option<&Cipher>) -> Result<Vec<u8>> {
+    match cipher {
+        Some(cipher) => cipher.seal(&bytes),
+        None => Ok(bytes),
+    }
+}
+
+/// Open bytes written by [`seal_bytes`], with or without a cipher
+pub fn open_bytes<'a>(data: &'a [u8], cipher: Option<&Cipher>) -> Result<Cow<'a, [u8]>> {
+    if !is_sealed(data) {
+        return Ok(Cow::Borrowed(data));
+    }
+    cipher.ok_or_else(missing_key)?.open(data).map(Cow::Owned)
+}
+
+/// Encode text for storage like [`compression::encode`], sealing it if a
+/// cipher is given
+pub fn encode_text(text: &str, level: Option<i32>, cipher: Option<&Cipher>) -> Result<Value> {
+    let value = compression::encode(text, level)?;
+    match cipher {
+        Some(cipher) => seal_value(value, cipher),
+        None => Ok(value),
+    }
+}
+
+/// Seal text encoded by [`compression::encode`]; null and sealed values are
+/// returned as they are
+pub fn seal_value(value: Value, cipher: &Cipher) -> Result<Value> {
+    let (encoding, bytes) = match value {
+        Value::Text(text) => (PLAIN_TEXT, text.into_bytes()),
+        Value::Blob(compressed) if !is_sealed(&compressed) => (COMPRESSED_TEXT, compressed),
+        other => return Ok(other),
+    };
+    let mut payload = Vec::with_capacity(bytes.len() + 1);
+    payload.push(encoding);
+    payload.extend_from_slice(&bytes);
+    cipher.seal(&payload).map(Value::Blob)
+}
+
+/// Decode text written by [`encode_text`]
+pub fn decode_text(value: ValueRef<'_>, cipher: Option<&Cipher>) -> Result<String> {
+    let ValueRef::Blob(sealed) = value else {
+        return compression::decode(value);
+    };
+    if !is_sealed(sealed) {
+        return compression::decode(value);
+    }
+    let payload = cipher.ok_or_else(missing_key)?.open(sealed)?;
+    match payload.split_first() {
+        Some((&PLAIN_TEXT, text)) => compression::decode(ValueRef::Text(text)),
+        Some((&COMPRESSED_TEXT, compressed)) => compression::decode(ValueRef::Blob(compressed)),
+        _ => Err(ZeroLatencyError::database("Unknown stored text encoding")),
+    }
+}
+
+/// Error reading encrypted data without a key
+pub fn missing_key() -> ZeroLatencyError {
+    ZeroLatencyError::configuration(
+        "Stored data is encrypted; set DOC_INDEXER_ENCRYPTION_KEY or DOC_INDEXER_ENCRYPTION_KEY_FILE",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    fn cipher_for(key: &str) -> Cipher {
+        EncryptionConfig {
+            key: Some(key.to_string()),
+            key_file: None,
+        }
+        .cipher()
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sealed_values_round_trip() {
+        let cipher = cipher_for(KEY);
+        let text = "Licensed under the Apache License, Version 2.0. ".repeat(20);
+        for level in [None, Some(3)] {
+            let sealed = encode_text(&text, level, Some(&cipher)).unwrap();
+            let Value::Blob(bytes) = &sealed else {
+                panic!("sealed text is a blob");
+            };
+            assert!(is_sealed(bytes));
+            assert!(!bytes.windows(8).any(|window| window == b"Licensed"));
+            assert_eq!(
+                decode_text(ValueRef::from(&sealed), Some(&cipher)).unwrap(),
+                text
+            );
+        }
+
+        // Each value gets its own nonce
+        let first = cipher.seal(b"vector").unwrap();
+        assert_ne!(first, cipher.seal(b"vector").unwrap());
+        assert_eq!(
+            open_bytes(&first, Some(&cipher)).unwrap().as_ref(),
+            b"vector"
+        );
+
+        // Plain values stay readable, sealed ones need the right key
+        let plain = encode_text(&text, Some(3), None).unwrap();
+        assert_eq!(
+            decode_text(ValueRef::from(&plain), Some(&cipher)).unwrap(),
+            text
+        );
+        assert!(open_bytes(&first, None).is_err());
+        let other = cipher_for(&KEY.replace("1f", "ff"));
+        assert!(open_bytes(&first, Some(&other)).is_err());
+    }
+
+    #[test]
+    fn test_keys_must_be_256_bits_of_hex() {
+        let config = |key: &str| EncryptionConfig {
+            key: Some(key.to_string()),
+            key_file: None,
+        };
+        assert!(config(KEY).cipher().is_ok());
+        assert!(config(&KEY[..62]).cipher().is_err());
+        assert!(config(&KEY.replace('0', "g")).cipher().is_err());
+        let off = EncryptionConfig {
+            key: None,
+            key_file: None,
+        };
+        assert!(off.cipher().unwrap().is_none());
+    }
+}
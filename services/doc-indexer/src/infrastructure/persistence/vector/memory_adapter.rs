@@ -1,3 +1,4 @@
+use crate::infrastructure::memory::VectorPool;
 use async_trait::async_trait;
 /// In-memory vector store adapter
 ///
@@ -8,14 +9,19 @@ use std::sync::Arc;
 use dashmap::DashMap;
 use zero_latency_core::{models::HealthStatus, values::Score, Result};
 use zero_latency_vector::{
-    SimilarityCalculator, SimilarityResult, VectorDocument, VectorRepository,
+    CollectionVectorSettings, MetadataFilter, SimilarityCalculator, SimilarityResult, StorageInfo,
+    StorageSegment, VectorDocument, VectorRepository, DEFAULT_VECTOR,
 };
 
 /// In-memory vector store with concurrent-safe operations
 pub struct InMemoryVectorStore {
     // Use DashMap for lock-free concurrent operations
     documents: Arc<DashMap<String, VectorDocument>>,
+    /// Dimension and metric of configured collections; the others use the
+    /// similarity calculator
+    collection_settings: Arc<DashMap<String, CollectionVectorSettings>>,
     similarity_calculator: Arc<dyn SimilarityCalculator>,
+    vector_pool: Option<Arc<VectorPool>>,
 }
 
 impl InMemoryVectorStore {
@@ -23,7 +29,9 @@ impl InMemoryVectorStore {
     pub fn new() -> Self {
         Self {
             documents: Arc::new(DashMap::new()),
+            collection_settings: Arc::new(DashMap::new()),
             similarity_calculator: Arc::new(CosineCalculator),
+            vector_pool: None,
         }
     }
 
@@ -31,7 +39,41 @@ impl InMemoryVectorStore {
     pub fn with_similarity_calculator(calculator: Arc<dyn SimilarityCalculator>) -> Self {
         Self {
             documents: Arc::new(DashMap::new()),
+            collection_settings: Arc::new(DashMap::new()),
             similarity_calculator: calculator,
+            vector_pool: None,
+        }
+    }
+
+    /// Return query vectors and replaced embeddings to a shared pool
+    pub fn with_vector_pool(mut self, pool: Arc<VectorPool>) -> Self {
+        self.vector_pool = Some(pool);
+        self
+    }
+
+    /// Return a vector that is no longer needed to the pool
+    fn recycle_vector(&self, vector: Vec<f32>) {
+        if let Some(pool) = &self.vector_pool {
+            pool.return_vector(vector);
+        }
+    }
+
+    /// Score a stored vector by its collection's metric
+    fn score(&self, collection: Option<&str>, query: &[f32], embedding: &[f32]) -> Score {
+        let similarity = match collection.and_then(|name| self.collection_settings.get(name)) {
+            Some(settings) => settings.metric.score(query, embedding),
+            None => self
+                .similarity_calculator
+                .calculate_similarity(query, embedding),
+        };
+        Score::new(similarity).unwrap_or_else(|_| Score::new(0.0).unwrap())
+    }
+
+    /// Reject a query the collection's vectors cannot be compared with
+    fn check_query(&self, collection_name: &str, query: &[f32]) -> Result<()> {
+        match self.collection_settings.get(collection_name) {
+            Some(settings) => settings.check_query(collection_name, query),
+            None => Ok(()),
         }
     }
 
@@ -57,7 +99,9 @@ impl VectorRepository for InMemoryVectorStore {
         // Use lock-free operations for non-blocking indexing
         for document in vectors {
             let id = document.id.to_string();
-            self.documents.insert(id, document);
+            if let Some(previous) = self.documents.insert(id, document) {
+                self.recycle_vector(previous.embedding);
+            }
         }
         Ok(())
     }
@@ -68,13 +112,15 @@ impl VectorRepository for InMemoryVectorStore {
         // Use lock-free iteration over DashMap for non-blocking search
         for entry in self.documents.iter() {
             let document = entry.value();
-            let similarity = self
-                .similarity_calculator
-                .calculate_similarity(&query_vector, &document.embedding);
+            let similarity = self.score(
+                document.metadata.collection.as_deref(),
+                &query_vector,
+                &document.embedding,
+            );
 
             results.push(SimilarityResult {
                 document_id: document.id,
-                similarity: Score::new(similarity).unwrap_or_else(|_| Score::new(0.0).unwrap()),
+                similarity,
                 metadata: document.metadata.clone(),
             });
         }
@@ -90,6 +136,8 @@ impl VectorRepository for InMemoryVectorStore {
         // Limit results
         results.truncate(k);
 
+        self.recycle_vector(query_vector);
+
         Ok(results)
     }
 
@@ -99,6 +147,8 @@ impl VectorRepository for InMemoryVectorStore {
         query_vector: Vec<f32>,
         k: usize,
     ) -> Result<Vec<SimilarityResult>> {
+        self.check_query(collection_name, &query_vector)?;
+
         // For memory adapter, we'll filter by collection name in metadata
         let mut results = Vec::new();
 
@@ -115,13 +165,15 @@ impl VectorRepository for InMemoryVectorStore {
                 continue; // Skip documents without collection if not searching default
             }
 
-            let similarity = self
-                .similarity_calculator
-                .calculate_similarity(&query_vector, &document.embedding);
+            let similarity = self.score(
+                document.metadata.collection.as_deref(),
+                &query_vector,
+                &document.embedding,
+            );
 
             results.push(SimilarityResult {
                 document_id: document.id,
-                similarity: Score::new(similarity).unwrap_or_else(|_| Score::new(0.0).unwrap()),
+                similarity,
                 metadata: document.metadata.clone(),
             });
         }
@@ -137,6 +189,8 @@ impl VectorRepository for InMemoryVectorStore {
         // Limit results
         results.truncate(k);
 
+        self.recycle_vector(query_vector);
+
         tracing::debug!(
             "MemoryAdapter: Collection-specific search in '{}' returned {} results",
             collection_name,
@@ -145,13 +199,134 @@ impl VectorRepository for InMemoryVectorStore {
         Ok(results)
     }
 
+    async fn search_filtered(
+        &self,
+        collection_name: Option<&str>,
+        query_vector: Vec<f32>,
+        k: usize,
+        filter: &MetadataFilter,
+    ) -> Result<Vec<SimilarityResult>> {
+        self.search_named(collection_name, DEFAULT_VECTOR, query_vector, k, filter)
+            .await
+    }
+
+    async fn search_named(
+        &self,
+        collection_name: Option<&str>,
+        vector_name: &str,
+        query_vector: Vec<f32>,
+        k: usize,
+        filter: &MetadataFilter,
+    ) -> Result<Vec<SimilarityResult>> {
+        if let Some(collection_name) = collection_name {
+            self.check_query(collection_name, &query_vector)?;
+        }
+        let mut results = Vec::new();
+
+        // Apply the filter before ranking so it never eats into the top k
+        for entry in self.documents.iter() {
+            let document = entry.value();
+            let Some(vector) = document.vector(vector_name) else {
+                continue;
+            };
+
+            if let Some(collection_name) = collection_name {
+                let doc_collection = document.metadata.collection.as_deref();
+                if doc_collection.unwrap_or("default") != collection_name {
+                    continue;
+                }
+            }
+            if !filter.matches_metadata(&document.metadata) {
+                continue;
+            }
+
+            let similarity = self.score(
+                document.metadata.collection.as_deref(),
+                &query_vector,
+                vector,
+            );
+
+            results.push(SimilarityResult {
+                document_id: document.id,
+                similarity,
+                metadata: document.metadata.clone(),
+            });
+        }
+
+        // Sort by similarity score (descending)
+        results.sort_by(|a, b| {
+            b.similarity
+                .value()
+                .partial_cmp(&a.similarity.value())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(k);
+
+        self.recycle_vector(query_vector);
+
+        Ok(results)
+    }
+
+    async fn preload_collection(&self, collection_name: &str) -> Result<usize> {
+        // Everything is already resident, so report what the collection holds
+        Ok(self
+            .documents
+            .iter()
+            .filter(|entry| {
+                entry
+                    .value()
+                    .metadata
+                    .collection
+                    .as_deref()
+                    .unwrap_or("default")
+                    == collection_name
+            })
+            .count())
+    }
+
+    async fn collection_vectors(&self, collection_name: &str) -> Result<Vec<VectorDocument>> {
+        Ok(self
+            .documents
+            .iter()
+            .filter(|entry| {
+                entry
+                    .value()
+                    .metadata
+                    .collection
+                    .as_deref()
+                    .unwrap_or("default")
+                    == collection_name
+            })
+            .map(|entry| entry.value().clone())
+            .collect())
+    }
+
+    async fn configure_collection(
+        &self,
+        collection_name: &str,
+        settings: CollectionVectorSettings,
+    ) -> Result<()> {
+        settings.validate()?;
+        self.collection_settings
+            .insert(collection_name.to_string(), settings);
+        Ok(())
+    }
+
     async fn delete(&self, document_id: &str) -> Result<bool> {
-        Ok(self.documents.remove(document_id).is_some())
+        match self.documents.remove(document_id) {
+            Some((_, document)) => {
+                self.recycle_vector(document.embedding);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
     }
 
     async fn update(&self, document_id: &str, vector: Vec<f32>) -> Result<bool> {
         if let Some(mut doc_ref) = self.documents.get_mut(document_id) {
-            doc_ref.embedding = vector;
+            let previous = std::mem::replace(&mut doc_ref.embedding, vector);
+            drop(doc_ref);
+            self.recycle_vector(previous);
             Ok(true)
         } else {
             Ok(false)
@@ -162,6 +337,45 @@ impl VectorRepository for InMemoryVectorStore {
         Ok(HealthStatus::Healthy)
     }
 
+    async fn storage_info(&self) -> Result<StorageInfo> {
+        let mut segments: Vec<StorageSegment> = Vec::new();
+        for entry in self.documents.iter() {
+            let document = entry.value();
+            let dimension = document.embedding.len();
+            let chunk_bytes = document.metadata.content.len() as u64;
+            match segments
+                .iter_mut()
+                .find(|segment| segment.collection == document.metadata.collection)
+            {
+                Some(segment) => {
+                    segment.vector_count += 1;
+                    segment.chunk_bytes += chunk_bytes;
+                    if segment.dimension != Some(dimension) {
+                        segment.dimension = None;
+                    }
+                }
+                None => segments.push(StorageSegment {
+                    collection: document.metadata.collection.clone(),
+                    vector_count: 1,
+                    dimension: Some(dimension),
+                    chunk_bytes,
+                    // Each vector keeps its own copy of the text in memory
+                    deduplicated_bytes: 0,
+                }),
+            }
+        }
+        segments.sort_by(|a, b| a.collection.cmp(&b.collection));
+
+        Ok(StorageInfo {
+            backend: "memory".to_string(),
+            vector_count: self.documents.len(),
+            disk_bytes: None,
+            cached_vectors: 0,
+            deduplicated_bytes: 0,
+            segments,
+        })
+    }
+
     async fn count(&self) -> Result<usize> {
         Ok(self.documents.len())
     }
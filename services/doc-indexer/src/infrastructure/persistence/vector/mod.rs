@@ -7,9 +7,16 @@
 pub mod qdrant_adapter;
 
 pub mod memory_adapter;
+pub mod scoring;
 
+#[cfg(feature = "embedded")]
+pub mod compression;
 #[cfg(feature = "embedded")]
 pub mod embedded_adapter;
+#[cfg(feature = "embedded")]
+pub mod encryption;
+#[cfg(feature = "embedded")]
+pub mod snapshot;
 
 // Re-export commonly used types
 #[cfg(feature = "cloud")]
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use dashmap::DashMap;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 /// Qdrant vector store adapter
@@ -6,9 +7,13 @@ use serde::{Deserialize, Serialize};
 /// This adapter implements the VectorRepository trait for Qdrant vector database,
 /// providing concrete implementation for vector storage and retrieval operations.
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 use zero_latency_core::{models::HealthStatus, values::Score, Result, Uuid, ZeroLatencyError};
-use zero_latency_vector::{SimilarityResult, VectorDocument, VectorMetadata, VectorRepository};
+use zero_latency_vector::{
+    CollectionVectorSettings, MetadataFilter, SimilarityMetric, SimilarityResult, VectorDocument,
+    VectorMetadata, VectorRepository,
+};
 
 /// Qdrant-specific configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,12 +22,21 @@ pub struct QdrantConfig {
     pub collection_name: String,
     pub api_key: Option<String>,
     pub timeout_seconds: u64,
+    /// Points sent per upsert or delete request
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+}
+
+fn default_batch_size() -> usize {
+    256
 }
 
 /// Qdrant vector store adapter
 pub struct QdrantAdapter {
     config: QdrantConfig,
     client: Client,
+    /// Dimension and metric of the collections created through this adapter
+    collection_settings: DashMap<String, CollectionVectorSettings>,
 }
 
 impl QdrantAdapter {
@@ -43,7 +57,55 @@ impl QdrantAdapter {
 
         tracing::info!("QdrantAdapter: Successfully created REST client");
 
-        Ok(Self { config, client })
+        Ok(Self {
+            config,
+            client,
+            collection_settings: DashMap::new(),
+        })
+    }
+
+    /// Metric of a collection, rejecting queries it cannot compare
+    fn metric_of(&self, collection_name: &str, query: &[f32]) -> Result<SimilarityMetric> {
+        match self.collection_settings.get(collection_name) {
+            Some(settings) => {
+                settings.check_query(collection_name, query)?;
+                Ok(settings.metric)
+            }
+            None => Ok(SimilarityMetric::Cosine),
+        }
+    }
+
+    /// Send a request to the Qdrant REST API, failing on error statuses
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: serde_json::Value,
+    ) -> Result<()> {
+        let url = format!("{}{}", self.config.url, path);
+        let mut request = self
+            .client
+            .request(method, &url)
+            .header("Content-Type", "application/json")
+            .json(&body);
+        if let Some(api_key) = &self.config.api_key {
+            request = request.header("api-key", api_key);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            tracing::error!("QdrantAdapter: HTTP request failed: {}", e);
+            ZeroLatencyError::database(&format!("Qdrant HTTP request failed: {}", e))
+        })?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            tracing::error!("QdrantAdapter: HTTP error {}: {}", status, error_text);
+            return Err(ZeroLatencyError::database(&format!(
+                "Qdrant HTTP error {}: {}",
+                status, error_text
+            )));
+        }
+        Ok(())
     }
 
     /// Convert a Qdrant REST API search result to VectorDocument  
@@ -90,11 +152,14 @@ impl QdrantAdapter {
         let mut custom = HashMap::new();
         for (key, value) in &result.payload {
             if key.starts_with("custom_") {
-                if let Some(s) = value.as_str() {
-                    custom.insert(
-                        key.strip_prefix("custom_").unwrap().to_string(),
-                        s.to_string(),
-                    );
+                // Numeric payloads are kept in their JSON spelling for range filters
+                let value = match value {
+                    serde_json::Value::String(s) => Some(s.clone()),
+                    serde_json::Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                };
+                if let Some(s) = value {
+                    custom.insert(key.strip_prefix("custom_").unwrap().to_string(), s);
                 }
             }
         }
@@ -106,7 +171,13 @@ impl QdrantAdapter {
             title,
             heading_path,
             url,
+            collection: result
+                .payload
+                .get("collection")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
             custom,
+            ..Default::default()
         };
 
         // Convert id to string
@@ -119,19 +190,53 @@ impl QdrantAdapter {
         Ok(VectorDocument {
             id: Uuid::from_str(&id_str).unwrap_or_else(|_| Uuid::new_v4()),
             embedding: result.vector.clone().unwrap_or_default(),
+            named_vectors: Default::default(),
             metadata,
         })
     }
 }
 
+/// Payload of a point, in the layout `from_qdrant_rest_result` reads back
+fn to_qdrant_payload(metadata: &VectorMetadata) -> serde_json::Value {
+    let mut payload = serde_json::json!({
+        "document_id": metadata.document_id.to_string(),
+        "chunk_index": metadata.chunk_index,
+        "content": metadata.content,
+        "title": metadata.title,
+        "heading_path": metadata.heading_path.join("/"),
+        "url": metadata.url,
+        "collection": metadata.collection,
+    });
+    for (key, value) in &metadata.custom {
+        payload[format!("custom_{}", key)] = serde_json::Value::String(value.clone());
+    }
+    payload
+}
+
 #[derive(Debug, Deserialize)]
 struct QdrantSearchResponse {
     result: Vec<QdrantSearchResult>,
 }
 
+/// Page of points from the scroll endpoint
+#[derive(Debug, Deserialize)]
+struct QdrantScrollResponse {
+    result: QdrantScrollPage,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantScrollPage {
+    points: Vec<QdrantSearchResult>,
+    /// Id to continue from; null on the last page
+    #[serde(default)]
+    next_page_offset: serde_json::Value,
+}
+
 #[derive(Debug, Deserialize)]
 struct QdrantSearchResult {
     id: serde_json::Value, // Can be string or number
+    /// Unset for scrolled points, which are not scored
+    #[serde(default)]
     score: f32,
     payload: HashMap<String, serde_json::Value>,
     vector: Option<Vec<f32>>,
@@ -140,14 +245,48 @@ struct QdrantSearchResult {
 #[async_trait]
 impl VectorRepository for QdrantAdapter {
     async fn insert(&self, vectors: Vec<VectorDocument>) -> Result<()> {
-        // For now, insertion is handled separately via the indexing service
-        // This implementation focuses on search functionality
-        tracing::debug!("QdrantAdapter: Insert not implemented (using separate indexing)");
-        let _ = vectors; // Suppress unused warning
+        self.upsert_batch(vectors).await
+    }
+
+    async fn upsert_batch(&self, vectors: Vec<VectorDocument>) -> Result<()> {
+        // Points go to the Qdrant collection named after their collection
+        let mut by_collection: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+        for document in &vectors {
+            let collection = document
+                .metadata
+                .collection
+                .clone()
+                .unwrap_or_else(|| self.config.collection_name.clone());
+            by_collection
+                .entry(collection)
+                .or_default()
+                .push(serde_json::json!({
+                    "id": document.id.to_string(),
+                    "vector": document.embedding,
+                    "payload": to_qdrant_payload(&document.metadata),
+                }));
+        }
+
+        for (collection, points) in by_collection {
+            for batch in points.chunks(self.config.batch_size.max(1)) {
+                self.send(
+                    reqwest::Method::PUT,
+                    &format!("/collections/{}/points?wait=true", collection),
+                    serde_json::json!({ "points": batch }),
+                )
+                .await?;
+            }
+            tracing::debug!(
+                "QdrantAdapter: Upserted {} points into '{}'",
+                points.len(),
+                collection
+            );
+        }
         Ok(())
     }
 
     async fn search(&self, query_vector: Vec<f32>, k: usize) -> Result<Vec<SimilarityResult>> {
+        let metric = self.metric_of(&self.config.collection_name, &query_vector)?;
         println!(
             "🔍 QdrantAdapter: Searching in collection '{}' with vector size {} for {} results",
             self.config.collection_name,
@@ -156,13 +295,16 @@ impl VectorRepository for QdrantAdapter {
         );
 
         // Create search request payload
-        let search_payload = serde_json::json!({
+        let mut search_payload = serde_json::json!({
             "vector": query_vector,
             "limit": k,
             "with_payload": true,
-            "with_vector": true,
-            "score_threshold": 0.0
+            "with_vector": true
         });
+        // Distances are not scores, so only cosine results can be cut at 0
+        if metric == SimilarityMetric::Cosine {
+            search_payload["score_threshold"] = serde_json::json!(0.0);
+        }
 
         let url = format!(
             "{}/collections/{}/points/search",
@@ -218,7 +360,7 @@ impl VectorRepository for QdrantAdapter {
                 Ok(document) => {
                     similarity_results.push(SimilarityResult {
                         document_id: document.metadata.document_id.clone(),
-                        similarity: Score::new(result.score).unwrap_or_default(),
+                        similarity: Score::new(metric.normalize(result.score)).unwrap_or_default(),
                         metadata: document.metadata,
                     });
                 }
@@ -242,6 +384,7 @@ impl VectorRepository for QdrantAdapter {
         query_vector: Vec<f32>,
         k: usize,
     ) -> Result<Vec<SimilarityResult>> {
+        let metric = self.metric_of(collection_name, &query_vector)?;
         println!(
             "🔍 QdrantAdapter: Searching in collection '{}' with vector size {} for {} results",
             collection_name,
@@ -250,13 +393,16 @@ impl VectorRepository for QdrantAdapter {
         );
 
         // Create search request payload
-        let search_payload = serde_json::json!({
+        let mut search_payload = serde_json::json!({
             "vector": query_vector,
             "limit": k,
             "with_payload": true,
-            "with_vector": true,
-            "score_threshold": 0.0
+            "with_vector": true
         });
+        // Distances are not scores, so only cosine results can be cut at 0
+        if metric == SimilarityMetric::Cosine {
+            search_payload["score_threshold"] = serde_json::json!(0.0);
+        }
 
         let url = format!(
             "{}/collections/{}/points/search",
@@ -307,7 +453,7 @@ impl VectorRepository for QdrantAdapter {
                 Ok(document) => {
                     similarity_results.push(SimilarityResult {
                         document_id: document.metadata.document_id.clone(),
-                        similarity: Score::new(result.score).unwrap_or_default(),
+                        similarity: Score::new(metric.normalize(result.score)).unwrap_or_default(),
                         metadata: document.metadata,
                     });
                 }
@@ -325,10 +471,225 @@ impl VectorRepository for QdrantAdapter {
         Ok(similarity_results)
     }
 
+    async fn search_filtered(
+        &self,
+        collection_name: Option<&str>,
+        query_vector: Vec<f32>,
+        k: usize,
+        filter: &MetadataFilter,
+    ) -> Result<Vec<SimilarityResult>> {
+        let collection_name = collection_name.unwrap_or(&self.config.collection_name);
+        let metric = self.metric_of(collection_name, &query_vector)?;
+
+        // Exact conditions run inside Qdrant; globs and regexes have no payload
+        // equivalent, so over-fetch and post-filter when any are present
+        let must: Vec<serde_json::Value> = filter
+            .conditions()
+            .iter()
+            .filter_map(|condition| {
+                let value = condition.exact_value()?;
+                Some(serde_json::json!({
+                    "key": format!("custom_{}", condition.key()),
+                    "match": { "value": value }
+                }))
+            })
+            .collect();
+        let limit = if filter.is_exact() {
+            k
+        } else {
+            filter.fetch_limit(k)
+        };
+
+        let mut search_payload = serde_json::json!({
+            "vector": query_vector,
+            "limit": limit,
+            "with_payload": true,
+            "with_vector": true
+        });
+        if metric == SimilarityMetric::Cosine {
+            search_payload["score_threshold"] = serde_json::json!(0.0);
+        }
+        if !must.is_empty() {
+            search_payload["filter"] = serde_json::json!({ "must": must });
+        }
+
+        let url = format!(
+            "{}/collections/{}/points/search",
+            self.config.url, collection_name
+        );
+
+        let mut request = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&search_payload);
+
+        if let Some(api_key) = &self.config.api_key {
+            request = request.header("api-key", api_key);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            tracing::error!("QdrantAdapter: HTTP request failed: {}", e);
+            ZeroLatencyError::database(&format!("Qdrant HTTP request failed: {}", e))
+        })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            tracing::error!("QdrantAdapter: HTTP error {}: {}", status, error_text);
+            return Err(ZeroLatencyError::database(&format!(
+                "Qdrant HTTP error {}: {}",
+                status, error_text
+            )));
+        }
+
+        let search_response: QdrantSearchResponse = response.json().await.map_err(|e| {
+            tracing::error!("QdrantAdapter: Failed to parse response: {}", e);
+            ZeroLatencyError::database(&format!("Failed to parse Qdrant response: {}", e))
+        })?;
+
+        let mut similarity_results = Vec::new();
+        for result in &search_response.result {
+            match self.from_qdrant_rest_result(result) {
+                Ok(document) if filter.matches_metadata(&document.metadata) => {
+                    similarity_results.push(SimilarityResult {
+                        document_id: document.metadata.document_id,
+                        similarity: Score::new(metric.normalize(result.score)).unwrap_or_default(),
+                        metadata: document.metadata,
+                    });
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("QdrantAdapter: Failed to convert result: {}", e);
+                }
+            }
+        }
+        similarity_results.truncate(k);
+
+        tracing::debug!(
+            "QdrantAdapter: Filtered search in '{}' kept {} of {} results",
+            collection_name,
+            similarity_results.len(),
+            search_response.result.len()
+        );
+        Ok(similarity_results)
+    }
+
+    /// Scroll the document's points in the chunk range by payload filter,
+    /// a page of `batch_size` points at a time
+    async fn document_chunks(
+        &self,
+        collection_name: &str,
+        document_id: Uuid,
+        chunk_range: RangeInclusive<usize>,
+    ) -> Result<Vec<(Uuid, VectorMetadata)>> {
+        let url = format!(
+            "{}/collections/{}/points/scroll",
+            self.config.url, collection_name
+        );
+        let mut chunks = Vec::new();
+        let mut offset = serde_json::Value::Null;
+        loop {
+            let mut body = serde_json::json!({
+                "filter": {
+                    "must": [
+                        { "key": "document_id", "match": { "value": document_id.to_string() } },
+                        {
+                            "key": "chunk_index",
+                            "range": { "gte": chunk_range.start(), "lte": chunk_range.end() }
+                        }
+                    ]
+                },
+                "limit": self.config.batch_size.max(1),
+                "with_payload": true,
+                "with_vector": false
+            });
+            if !offset.is_null() {
+                body["offset"] = offset;
+            }
+            let mut request = self
+                .client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .json(&body);
+            if let Some(api_key) = &self.config.api_key {
+                request = request.header("api-key", api_key);
+            }
+
+            let response = request.send().await.map_err(|e| {
+                ZeroLatencyError::database(&format!("Qdrant HTTP request failed: {}", e))
+            })?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(ZeroLatencyError::database(&format!(
+                    "Qdrant HTTP error {}: {}",
+                    status, error_text
+                )));
+            }
+            let page: QdrantScrollResponse = response.json().await.map_err(|e| {
+                ZeroLatencyError::database(&format!("Failed to parse Qdrant response: {}", e))
+            })?;
+
+            for point in &page.result.points {
+                let document = self.from_qdrant_rest_result(point)?;
+                chunks.push((document.id, document.metadata));
+            }
+            offset = page.result.next_page_offset;
+            if offset.is_null() {
+                break;
+            }
+        }
+        chunks.sort_by_key(|(_, chunk)| chunk.chunk_index);
+        Ok(chunks)
+    }
+
+    /// Create the Qdrant collection with the metric, which Qdrant fixes
+    /// when a collection is created
+    async fn configure_collection(
+        &self,
+        collection_name: &str,
+        settings: CollectionVectorSettings,
+    ) -> Result<()> {
+        settings.validate()?;
+        let distance = match settings.metric {
+            SimilarityMetric::Cosine => "Cosine",
+            SimilarityMetric::DotProduct => "Dot",
+            SimilarityMetric::Euclidean => "Euclid",
+        };
+        self.send(
+            reqwest::Method::PUT,
+            &format!("/collections/{}", collection_name),
+            serde_json::json!({
+                "vectors": { "size": settings.dimension, "distance": distance }
+            }),
+        )
+        .await?;
+        self.collection_settings
+            .insert(collection_name.to_string(), settings);
+        Ok(())
+    }
+
     async fn delete(&self, document_id: &str) -> Result<bool> {
-        tracing::debug!("QdrantAdapter: Delete not fully implemented");
-        let _ = document_id; // Suppress unused warning
-        Ok(true)
+        let deleted = self.delete_batch(&[document_id.to_string()]).await?;
+        Ok(deleted > 0)
+    }
+
+    /// Qdrant does not report which points existed, so every id sent counts
+    /// as deleted
+    async fn delete_batch(&self, document_ids: &[String]) -> Result<usize> {
+        for batch in document_ids.chunks(self.config.batch_size.max(1)) {
+            self.send(
+                reqwest::Method::POST,
+                &format!(
+                    "/collections/{}/points/delete?wait=true",
+                    self.config.collection_name
+                ),
+                serde_json::json!({ "points": batch }),
+            )
+            .await?;
+        }
+        Ok(document_ids.len())
     }
 
     async fn update(&self, document_id: &str, vector: Vec<f32>) -> Result<bool> {
@@ -357,6 +718,7 @@ impl Default for QdrantConfig {
             collection_name: "zero_latency_docs".to_string(),
             api_key: None,
             timeout_seconds: 30,
+            batch_size: default_batch_size(),
         }
     }
 }
@@ -0,0 +1,269 @@
+/// Batch similarity scoring for brute-force vector scans
+///
+/// The embedded store scores every stored vector against the query. Rows are
+/// handed to a [`BatchScorer`] in flat batches so the work can be offloaded to
+/// a GPU when the `gpu` feature is enabled and a device is present; otherwise,
+/// and whenever a GPU batch fails, scoring runs on the CPU.
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use zero_latency_core::Result;
+
+/// Batches with fewer rows than this are scored on the CPU, where they finish
+/// before a device transfer would
+pub const GPU_MIN_BATCH: usize = 1024;
+
+/// Scores a query against rows of candidate vectors
+pub trait BatchScorer: Send + Sync {
+    /// Backend name reported in the service status
+    fn backend(&self) -> &'static str;
+
+    /// Cosine similarity of `query` against each `query.len()`-sized row of
+    /// `candidates`, appended to `scores` in row order
+    fn score(&self, query: &[f32], candidates: &[f32], scores: &mut Vec<f32>) -> Result<()>;
+}
+
+/// Scores rows one at a time on the calling thread
+pub struct CpuScorer;
+
+impl BatchScorer for CpuScorer {
+    fn backend(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn score(&self, query: &[f32], candidates: &[f32], scores: &mut Vec<f32>) -> Result<()> {
+        if query.is_empty() {
+            return Ok(());
+        }
+        scores.extend(
+            candidates
+                .chunks_exact(query.len())
+                .map(|row| cosine_similarity(query, row)),
+        );
+        Ok(())
+    }
+}
+
+/// Cosine similarity, or 0 for mismatched or zero-length vectors
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let magnitude_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let magnitude_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if magnitude_a == 0.0 || magnitude_b == 0.0 {
+        return 0.0;
+    }
+
+    dot_product / (magnitude_a * magnitude_b)
+}
+
+/// Outcome of the startup GPU probe, reported in `/api/status`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccelerationStatus {
+    /// Backend scoring brute-force searches: `cpu` or `cuda`
+    pub backend: String,
+    /// Whether this build includes the `gpu` feature
+    pub gpu_compiled: bool,
+    /// Name of the device in use
+    pub device: Option<String>,
+    /// Why scoring runs on the CPU, when it does
+    pub reason: Option<String>,
+}
+
+impl AccelerationStatus {
+    fn cpu(reason: impl Into<String>) -> Self {
+        Self {
+            backend: CpuScorer.backend().to_string(),
+            gpu_compiled: cfg!(feature = "gpu"),
+            device: None,
+            reason: Some(reason.into()),
+        }
+    }
+}
+
+impl Default for AccelerationStatus {
+    fn default() -> Self {
+        Self::cpu("vector backend does not scan vectors in process")
+    }
+}
+
+/// Pick the scorer for brute-force scans, probing for a GPU if allowed
+pub fn probe(enable_gpu: bool) -> (Arc<dyn BatchScorer>, AccelerationStatus) {
+    if !enable_gpu {
+        return (
+            Arc::new(CpuScorer),
+            AccelerationStatus::cpu("GPU scoring disabled"),
+        );
+    }
+
+    #[cfg(feature = "gpu")]
+    {
+        match cuda::CudaScorer::probe() {
+            Ok(scorer) => {
+                let status = AccelerationStatus {
+                    backend: scorer.backend().to_string(),
+                    gpu_compiled: true,
+                    device: Some(scorer.device_name().to_string()),
+                    reason: None,
+                };
+                tracing::info!("GPU scoring enabled on {}", scorer.device_name());
+                (Arc::new(scorer), status)
+            }
+            Err(reason) => {
+                tracing::info!("GPU scoring unavailable, using CPU: {}", reason);
+                (Arc::new(CpuScorer), AccelerationStatus::cpu(reason))
+            }
+        }
+    }
+
+    #[cfg(not(feature = "gpu"))]
+    {
+        (
+            Arc::new(CpuScorer),
+            AccelerationStatus::cpu("built without the 'gpu' feature"),
+        )
+    }
+}
+
+#[cfg(feature = "gpu")]
+mod cuda {
+    use super::BatchScorer;
+    use cudarc::driver::{CudaDevice, LaunchAsync, LaunchConfig};
+    use std::sync::Arc;
+    use zero_latency_core::{Result, ZeroLatencyError};
+
+    const MODULE: &str = "zl_scoring";
+    const KERNEL: &str = "cosine_scores";
+
+    /// One thread per row; the query norm is computed once on the host
+    const KERNEL_SOURCE: &str = r#"
+extern "C" __global__ void cosine_scores(
+    const float* query, const float* rows, float* out, int n, int dim, float query_norm)
+{
+    int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= n) return;
+    const float* row = rows + (size_t)i * dim;
+    float dot = 0.0f;
+    float norm = 0.0f;
+    for (int j = 0; j < dim; j++) {
+        dot += query[j] * row[j];
+        norm += row[j] * row[j];
+    }
+    out[i] = (norm == 0.0f || query_norm == 0.0f) ? 0.0f : dot / (sqrtf(norm) * query_norm);
+}
+"#;
+
+    /// Scores batches with a CUDA kernel on the first device
+    pub struct CudaScorer {
+        device: Arc<CudaDevice>,
+        device_name: String,
+    }
+
+    impl CudaScorer {
+        /// Open device 0 and compile the scoring kernel
+        ///
+        /// The driver library is loaded lazily and cudarc panics when it is
+        /// missing, so the probe treats a panic as "no GPU".
+        pub fn probe() -> std::result::Result<Self, String> {
+            std::panic::catch_unwind(Self::open)
+                .unwrap_or_else(|_| Err("CUDA driver library not found".to_string()))
+        }
+
+        fn open() -> std::result::Result<Self, String> {
+            let device = CudaDevice::new(0).map_err(|e| format!("no CUDA device: {}", e))?;
+            let ptx = cudarc::nvrtc::compile_ptx(KERNEL_SOURCE)
+                .map_err(|e| format!("failed to compile scoring kernel: {}", e))?;
+            device
+                .load_ptx(ptx, MODULE, &[KERNEL])
+                .map_err(|e| format!("failed to load scoring kernel: {}", e))?;
+            let device_name = device
+                .name()
+                .unwrap_or_else(|_| "CUDA device 0".to_string());
+
+            Ok(Self {
+                device,
+                device_name,
+            })
+        }
+
+        pub fn device_name(&self) -> &str {
+            &self.device_name
+        }
+    }
+
+    impl BatchScorer for CudaScorer {
+        fn backend(&self) -> &'static str {
+            "cuda"
+        }
+
+        fn score(&self, query: &[f32], candidates: &[f32], scores: &mut Vec<f32>) -> Result<()> {
+            let gpu_error = |e: cudarc::driver::DriverError| {
+                ZeroLatencyError::internal(format!("GPU scoring failed: {}", e))
+            };
+            if query.is_empty() {
+                return Ok(());
+            }
+            let rows = candidates.len() / query.len();
+            let query_norm = query.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+            let query_buffer = self.device.htod_sync_copy(query).map_err(gpu_error)?;
+            let rows_buffer = self.device.htod_sync_copy(candidates).map_err(gpu_error)?;
+            let mut out = self.device.alloc_zeros::<f32>(rows).map_err(gpu_error)?;
+            let kernel = self
+                .device
+                .get_func(MODULE, KERNEL)
+                .ok_or_else(|| ZeroLatencyError::internal("GPU scoring kernel not loaded"))?;
+
+            // SAFETY: the buffers match the kernel signature and `out` holds
+            // one float per row
+            unsafe {
+                kernel.launch(
+                    LaunchConfig::for_num_elems(rows as u32),
+                    (
+                        &query_buffer,
+                        &rows_buffer,
+                        &mut out,
+                        rows as i32,
+                        query.len() as i32,
+                        query_norm,
+                    ),
+                )
+            }
+            .map_err(gpu_error)?;
+
+            scores.extend(self.device.dtoh_sync_copy(&out).map_err(gpu_error)?);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_scorer_scores_rows_in_order() {
+        let query = [1.0, 0.0];
+        let candidates = [1.0, 0.0, 0.0, 2.0, 1.0, 1.0, 0.0, 0.0];
+        let mut scores = Vec::new();
+        CpuScorer.score(&query, &candidates, &mut scores).unwrap();
+
+        assert_eq!(scores.len(), 4);
+        assert_eq!(scores[0], 1.0);
+        assert_eq!(scores[1], 0.0);
+        assert!((scores[2] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        // Zero vectors score 0 rather than NaN
+        assert_eq!(scores[3], 0.0);
+    }
+
+    #[test]
+    fn test_probe_falls_back_to_cpu() {
+        let (scorer, status) = probe(false);
+        assert_eq!(scorer.backend(), "cpu");
+        assert_eq!(status.backend, "cpu");
+        assert!(status.reason.is_some());
+    }
+}
@@ -0,0 +1,327 @@
+use arc_swap::ArcSwap;
+/// Snapshots of the embedded store's vectors
+///
+/// Searches scan an immutable snapshot of every stored vector, so they never
+/// wait for indexing. A snapshot is a list of frozen segments plus a copy of
+/// the memtable, the small set of recently written rows. Writers apply a
+/// change to their own state and publish a new snapshot with an atomic
+/// pointer swap; searches that already loaded the previous one finish on it.
+///
+/// A full memtable is frozen into a segment that stores its vectors
+/// contiguously. Deleting or replacing a row of a frozen segment only marks
+/// it deleted in a copy of that segment's deletion set, and once there are
+/// more than `max_segments` segments the newer ones are merged, dropping
+/// deleted rows. SQLite stays the durable copy; the snapshot is rebuilt from
+/// it on startup.
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use zero_latency_core::Uuid;
+
+/// A stored vector and the collection it belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorRow {
+    pub id: Uuid,
+    pub collection: Option<String>,
+    pub embedding: Vec<f32>,
+}
+
+/// Rows frozen together; never modified once built
+#[derive(Debug, Default)]
+struct SegmentRows {
+    ids: Vec<Uuid>,
+    collections: Vec<Option<String>>,
+    embeddings: Vec<f32>,
+    /// Start of each row's vector in `embeddings`, plus the end of the last
+    offsets: Vec<usize>,
+}
+
+impl SegmentRows {
+    fn build<'a>(rows: impl IntoIterator<Item = (Uuid, Option<&'a str>, &'a [f32])>) -> Self {
+        let mut segment = SegmentRows {
+            offsets: vec![0],
+            ..Default::default()
+        };
+        for (id, collection, embedding) in rows {
+            segment.ids.push(id);
+            segment.collections.push(collection.map(str::to_string));
+            segment.embeddings.extend_from_slice(embedding);
+            segment.offsets.push(segment.embeddings.len());
+        }
+        segment
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn row(&self, row: usize) -> (Uuid, Option<&str>, &[f32]) {
+        (
+            self.ids[row],
+            self.collections[row].as_deref(),
+            &self.embeddings[self.offsets[row]..self.offsets[row + 1]],
+        )
+    }
+}
+
+/// A segment and the rows deleted from it since it was frozen
+#[derive(Debug, Clone)]
+struct FrozenSegment {
+    rows: Arc<SegmentRows>,
+    deleted: Arc<HashSet<usize>>,
+}
+
+impl FrozenSegment {
+    fn live_rows(&self) -> impl Iterator<Item = (Uuid, Option<&str>, &[f32])> {
+        (0..self.rows.len())
+            .filter(|row| !self.deleted.contains(row))
+            .map(|row| self.rows.row(row))
+    }
+
+    fn live_len(&self) -> usize {
+        self.rows.len() - self.deleted.len()
+    }
+}
+
+/// The vectors as of one point in time
+#[derive(Debug, Default)]
+pub struct VectorSnapshot {
+    segments: Vec<FrozenSegment>,
+    memtable: Vec<Arc<VectorRow>>,
+}
+
+impl VectorSnapshot {
+    /// Every live row: id, collection and vector
+    pub fn rows(&self) -> impl Iterator<Item = (Uuid, Option<&str>, &[f32])> {
+        self.segments
+            .iter()
+            .flat_map(FrozenSegment::live_rows)
+            .chain(
+                self.memtable
+                    .iter()
+                    .map(|row| (row.id, row.collection.as_deref(), row.embedding.as_slice())),
+            )
+    }
+
+    /// Frozen segments, not counting the memtable
+    #[cfg(test)]
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+}
+
+/// Where a live row is
+#[derive(Debug, Clone, Copy)]
+enum Location {
+    Memtable(usize),
+    Segment(usize, usize),
+}
+
+/// What writers change before publishing it as a snapshot
+#[derive(Debug, Default)]
+struct WriterState {
+    segments: Vec<FrozenSegment>,
+    memtable: Vec<Arc<VectorRow>>,
+    locations: HashMap<Uuid, Location>,
+}
+
+/// Vectors readable through lock-free snapshots while writers update them
+pub struct VectorSegments {
+    current: ArcSwap<VectorSnapshot>,
+    writer: Mutex<WriterState>,
+    memtable_capacity: usize,
+    max_segments: usize,
+}
+
+impl VectorSegments {
+    /// Segments holding `rows`, as loaded from storage
+    pub fn new(rows: Vec<VectorRow>, memtable_capacity: usize, max_segments: usize) -> Self {
+        let mut state = WriterState::default();
+        if !rows.is_empty() {
+            let segment = SegmentRows::build(
+                rows.iter()
+                    .map(|row| (row.id, row.collection.as_deref(), row.embedding.as_slice())),
+            );
+            state.push_segment(segment);
+        }
+        let segments = Self {
+            current: ArcSwap::from_pointee(VectorSnapshot::default()),
+            writer: Mutex::new(state),
+            memtable_capacity: memtable_capacity.max(1),
+            max_segments: max_segments.max(1),
+        };
+        segments.publish(&segments.writer.lock().unwrap());
+        segments
+    }
+
+    /// The latest published vectors; never waits for writers
+    pub fn snapshot(&self) -> Arc<VectorSnapshot> {
+        self.current.load_full()
+    }
+
+    /// Add or replace rows and publish them together
+    pub fn upsert(&self, rows: impl IntoIterator<Item = VectorRow>) {
+        let mut state = self.writer.lock().unwrap();
+        for row in rows {
+            self.insert(&mut state, row);
+        }
+        self.publish(&state);
+    }
+
+    /// Replace the vector of a row, keeping its collection; returns whether
+    /// the row exists
+    pub fn update_embedding(&self, id: Uuid, embedding: Vec<f32>) -> bool {
+        let mut state = self.writer.lock().unwrap();
+        let collection = match state.locations.get(&id) {
+            Some(Location::Memtable(position)) => state.memtable[*position].collection.clone(),
+            Some(Location::Segment(segment, row)) => {
+                state.segments[*segment].rows.collections[*row].clone()
+            }
+            None => return false,
+        };
+        self.insert(
+            &mut state,
+            VectorRow {
+                id,
+                collection,
+                embedding,
+            },
+        );
+        self.publish(&state);
+        true
+    }
+
+    /// Remove a row; returns whether it existed
+    pub fn remove(&self, id: Uuid) -> bool {
+        self.remove_batch([id]) == 1
+    }
+
+    /// Remove rows and publish the removals together; returns how many existed
+    pub fn remove_batch(&self, ids: impl IntoIterator<Item = Uuid>) -> usize {
+        let mut state = self.writer.lock().unwrap();
+        let removed = ids.into_iter().filter(|id| state.remove(*id)).count();
+        if removed > 0 {
+            self.publish(&state);
+        }
+        removed
+    }
+
+    fn insert(&self, state: &mut WriterState, row: VectorRow) {
+        state.remove(row.id);
+        state
+            .locations
+            .insert(row.id, Location::Memtable(state.memtable.len()));
+        state.memtable.push(Arc::new(row));
+        if state.memtable.len() >= self.memtable_capacity {
+            state.freeze_memtable();
+            if state.segments.len() > self.max_segments {
+                state.merge_segments();
+            }
+        }
+    }
+
+    fn publish(&self, state: &WriterState) {
+        self.current.store(Arc::new(VectorSnapshot {
+            segments: state.segments.clone(),
+            memtable: state.memtable.clone(),
+        }));
+    }
+}
+
+impl WriterState {
+    fn remove(&mut self, id: Uuid) -> bool {
+        match self.locations.remove(&id) {
+            Some(Location::Memtable(position)) => {
+                self.memtable.swap_remove(position);
+                if let Some(moved) = self.memtable.get(position) {
+                    self.locations
+                        .insert(moved.id, Location::Memtable(position));
+                }
+                true
+            }
+            Some(Location::Segment(segment, row)) => {
+                // Readers may still hold the old deletion set, so change a copy
+                let segment = &mut self.segments[segment];
+                let mut deleted = HashSet::clone(&segment.deleted);
+                deleted.insert(row);
+                segment.deleted = Arc::new(deleted);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn freeze_memtable(&mut self) {
+        let memtable = std::mem::take(&mut self.memtable);
+        let segment = SegmentRows::build(
+            memtable
+                .iter()
+                .map(|row| (row.id, row.collection.as_deref(), row.embedding.as_slice())),
+        );
+        self.push_segment(segment);
+    }
+
+    /// Merge every segment after the first into one, or all of them once the
+    /// newer ones together outgrow the first
+    fn merge_segments(&mut self) {
+        let base_len = self.segments.first().map_or(0, FrozenSegment::live_len);
+        let newer_len: usize = self.segments[1..].iter().map(FrozenSegment::live_len).sum();
+        let keep = usize::from(newer_len < base_len);
+        let merged: Vec<FrozenSegment> = self.segments.drain(keep..).collect();
+        let segment = SegmentRows::build(merged.iter().flat_map(FrozenSegment::live_rows));
+        self.push_segment(segment);
+    }
+
+    fn push_segment(&mut self, rows: SegmentRows) {
+        let index = self.segments.len();
+        for (row, id) in rows.ids.iter().enumerate() {
+            self.locations.insert(*id, Location::Segment(index, row));
+        }
+        self.segments.push(FrozenSegment {
+            rows: Arc::new(rows),
+            deleted: Arc::new(HashSet::new()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: Uuid, value: f32) -> VectorRow {
+        VectorRow {
+            id,
+            collection: Some("docs".to_string()),
+            embedding: vec![value, 0.0],
+        }
+    }
+
+    fn values(snapshot: &VectorSnapshot) -> Vec<f32> {
+        let mut values: Vec<f32> = snapshot.rows().map(|(_, _, v)| v[0]).collect();
+        values.sort_by(f32::total_cmp);
+        values
+    }
+
+    #[test]
+    fn test_snapshots_are_unaffected_by_later_writes() {
+        let ids: Vec<Uuid> = (0..6).map(|_| Uuid::new_v4()).collect();
+        let segments = VectorSegments::new(vec![row(ids[0], 0.0), row(ids[1], 1.0)], 2, 2);
+        let before = segments.snapshot();
+
+        // Fills and freezes the memtable twice, then merges the newer segments
+        segments.upsert((2..6).map(|i| row(ids[i], i as f32)));
+        segments.upsert([row(ids[1], 10.0)]);
+        assert!(segments.remove(ids[2]));
+        assert!(!segments.remove(Uuid::new_v4()));
+        assert!(segments.update_embedding(ids[5], vec![50.0, 0.0]));
+        assert!(!segments.update_embedding(Uuid::new_v4(), vec![0.0, 0.0]));
+
+        assert_eq!(values(&before), vec![0.0, 1.0]);
+        let after = segments.snapshot();
+        assert_eq!(values(&after), vec![0.0, 3.0, 4.0, 10.0, 50.0]);
+        assert_eq!(after.rows().count(), 5);
+        assert!(after.segment_count() <= 2);
+        assert!(after
+            .rows()
+            .all(|(_, collection, _)| collection == Some("docs")));
+    }
+}
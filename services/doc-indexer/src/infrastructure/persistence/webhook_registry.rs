@@ -0,0 +1,231 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+/// Webhook subscription registry
+///
+/// Stores the webhooks registered through `/api/webhooks`: where to deliver,
+/// which events to deliver and the secret payloads are signed with. Like the
+/// collection alias registry it is kept next to the vector store so
+/// subscriptions survive a restart, and only in memory with the in-memory
+/// backend.
+use std::path::PathBuf;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use super::write_json_atomic;
+
+/// Event a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "index.completed")]
+    IndexCompleted,
+    #[serde(rename = "index.failed")]
+    IndexFailed,
+    #[serde(rename = "reindex.completed")]
+    ReindexCompleted,
+    #[serde(rename = "reindex.failed")]
+    ReindexFailed,
+    #[serde(rename = "collection.created")]
+    CollectionCreated,
+    #[serde(rename = "collection.deleted")]
+    CollectionDeleted,
+    /// The service went from healthy to degraded or unhealthy
+    #[serde(rename = "health.degraded")]
+    HealthDegraded,
+    /// The production deployment changed status, e.g. from starting to healthy
+    #[serde(rename = "deployment.status_changed")]
+    DeploymentStatusChanged,
+    /// A resource or request metric crossed its alert threshold
+    #[serde(rename = "alert.triggered")]
+    AlertTriggered,
+    /// A metric that triggered an alert is back under its threshold
+    #[serde(rename = "alert.resolved")]
+    AlertResolved,
+    /// A scheduled digest of index changes was compiled
+    #[serde(rename = "digest.compiled")]
+    DigestCompiled,
+}
+
+impl WebhookEvent {
+    /// Name of the event as sent in payloads and headers
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::IndexCompleted => "index.completed",
+            WebhookEvent::IndexFailed => "index.failed",
+            WebhookEvent::ReindexCompleted => "reindex.completed",
+            WebhookEvent::ReindexFailed => "reindex.failed",
+            WebhookEvent::CollectionCreated => "collection.created",
+            WebhookEvent::CollectionDeleted => "collection.deleted",
+            WebhookEvent::HealthDegraded => "health.degraded",
+            WebhookEvent::DeploymentStatusChanged => "deployment.status_changed",
+            WebhookEvent::AlertTriggered => "alert.triggered",
+            WebhookEvent::AlertResolved => "alert.resolved",
+            WebhookEvent::DigestCompiled => "digest.compiled",
+        }
+    }
+}
+
+impl std::fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A registered webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    /// Events delivered to the webhook; empty means every event
+    pub events: Vec<WebhookEvent>,
+    /// Key of the HMAC-SHA256 payload signature
+    pub secret: String,
+    pub description: Option<String>,
+    /// Inactive webhooks are kept but receive nothing
+    pub active: bool,
+    /// Template the payload is rendered through instead of being sent as
+    /// JSON; it sees the JSON payload's fields as variables
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Content type of templated payloads; JSON when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Webhook {
+    /// Whether the webhook wants `event` delivered
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.active && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+/// Registry of webhooks keyed by id
+pub struct WebhookRegistry {
+    webhooks: DashMap<String, Webhook>,
+    // Backing file, or None when webhooks must not outlive the process
+    path: Option<PathBuf>,
+}
+
+impl WebhookRegistry {
+    /// Create a registry that only lives in memory
+    pub fn in_memory() -> Self {
+        Self {
+            webhooks: DashMap::new(),
+            path: None,
+        }
+    }
+
+    /// Load a registry from disk, starting empty if the file does not exist
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let webhooks = DashMap::new();
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to read webhooks: {}", e))
+            })?;
+            let stored: Vec<Webhook> = serde_json::from_str(&content).map_err(|e| {
+                ZeroLatencyError::database(format!("Failed to parse webhooks: {}", e))
+            })?;
+            for webhook in stored {
+                webhooks.insert(webhook.id.clone(), webhook);
+            }
+        }
+
+        Ok(Self {
+            webhooks,
+            path: Some(path),
+        })
+    }
+
+    /// All webhooks, oldest first
+    pub fn list(&self) -> Vec<Webhook> {
+        let mut webhooks: Vec<Webhook> = self
+            .webhooks
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        webhooks.sort_by_key(|webhook| webhook.created_at);
+        webhooks
+    }
+
+    /// A webhook by id
+    pub fn get(&self, id: &str) -> Option<Webhook> {
+        self.webhooks.get(id).map(|webhook| webhook.clone())
+    }
+
+    /// Add or replace a webhook
+    pub fn insert(&self, webhook: Webhook) {
+        self.webhooks.insert(webhook.id.clone(), webhook);
+    }
+
+    /// Remove a webhook, returning it
+    pub fn remove(&self, id: &str) -> Option<Webhook> {
+        self.webhooks.remove(id).map(|(_, webhook)| webhook)
+    }
+
+    /// Persist the registry to its backing file, if any
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        write_json_atomic(path, &self.list(), "webhooks")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(id: &str, events: Vec<WebhookEvent>) -> Webhook {
+        let now = chrono::Utc::now();
+        Webhook {
+            id: id.to_string(),
+            url: "http://localhost:9000/hook".to_string(),
+            events,
+            secret: "secret".to_string(),
+            description: None,
+            active: true,
+            template: None,
+            content_type: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_subscriptions() {
+        let all = webhook("a", Vec::new());
+        assert!(all.subscribes_to(WebhookEvent::HealthDegraded));
+
+        let mut some = webhook("b", vec![WebhookEvent::IndexCompleted]);
+        assert!(some.subscribes_to(WebhookEvent::IndexCompleted));
+        assert!(!some.subscribes_to(WebhookEvent::IndexFailed));
+
+        some.active = false;
+        assert!(!some.subscribes_to(WebhookEvent::IndexCompleted));
+    }
+
+    #[test]
+    fn test_registry_persists_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("webhooks.json");
+
+        let registry = WebhookRegistry::load(&file).unwrap();
+        registry.insert(webhook("a", vec![WebhookEvent::CollectionDeleted]));
+        registry.insert(webhook("b", Vec::new()));
+        registry.save().unwrap();
+
+        let reloaded = WebhookRegistry::load(&file).unwrap();
+        assert_eq!(reloaded.list().len(), 2);
+        assert_eq!(
+            reloaded.get("a").unwrap().events,
+            vec![WebhookEvent::CollectionDeleted]
+        );
+
+        reloaded.remove("a");
+        reloaded.save().unwrap();
+        assert!(WebhookRegistry::load(&file).unwrap().get("a").is_none());
+    }
+}
@@ -0,0 +1,600 @@
+/// Plugins for custom pipeline stages
+///
+/// Plugins add domain-specific processing without forking the service: a
+/// filter decides which files are indexed, a content handler turns files into
+/// indexable text and a ranker rescores search results. They are discovered
+/// at startup in the configured plugins directory, either as WebAssembly
+/// modules (`.wasm`, sandboxed, `wasm-plugins` feature) or as native
+/// libraries (`.so`, `.dylib`, `.dll`, unsandboxed, `native-plugins`
+/// feature and opt-in).
+///
+/// Both runtimes share one ABI: every stage is an exported function that
+/// takes a JSON message and returns a JSON reply, so a plugin only depends on
+/// the message shapes below and never on this crate's types.
+///
+/// | Export | Message | Reply |
+/// |--------|---------|-------|
+/// | `zl_info` | none | [`PluginInfo`] |
+/// | `zl_filter` | [`FilterInput`] | [`FilterOutput`] |
+/// | `zl_process` | [`ProcessInput`] | [`ProcessOutput`] |
+/// | `zl_rank` | [`RankInput`] | [`RankOutput`] |
+///
+/// Both runtimes also export `zl_abi_version`, which must return
+/// `PLUGIN_ABI_VERSION`. How messages cross the boundary is described in
+/// the runtime modules.
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use zero_latency_core::{values::Score, Result, ZeroLatencyError};
+use zero_latency_search::{SearchContext, SearchResult, SearchStep};
+
+use crate::config::PluginConfig;
+
+#[cfg(feature = "native-plugins")]
+pub mod native;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm;
+
+/// Version of the plugin ABI; plugins built for another version are refused
+#[cfg(any(feature = "wasm-plugins", feature = "native-plugins"))]
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Extensions of native plugin libraries
+const NATIVE_EXTENSIONS: [&str; 3] = ["so", "dylib", "dll"];
+
+/// Pipeline stage a plugin implements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginCapability {
+    /// `zl_filter`: decide whether a file is indexed
+    Filter,
+    /// `zl_process`: turn a file into indexable text
+    ContentHandler,
+    /// `zl_rank`: rescore search results
+    Ranker,
+}
+
+impl PluginCapability {
+    /// Name of the exported function implementing the stage
+    pub fn export(&self) -> &'static str {
+        match self {
+            PluginCapability::Filter => "zl_filter",
+            PluginCapability::ContentHandler => "zl_process",
+            PluginCapability::Ranker => "zl_rank",
+        }
+    }
+}
+
+/// How a plugin is run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginRuntime {
+    Wasm,
+    Native,
+}
+
+/// What a plugin reports about itself from `zl_info`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginInfo {
+    pub name: String,
+    pub version: String,
+    pub capabilities: Vec<PluginCapability>,
+    /// File extensions a content handler is offered, without the dot; every
+    /// file when empty
+    #[serde(default)]
+    pub extensions: Vec<String>,
+}
+
+/// Message for `zl_filter`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterInput {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Reply of `zl_filter`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterOutput {
+    pub index: bool,
+}
+
+/// Message for `zl_process`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessInput {
+    pub path: String,
+    pub content: String,
+}
+
+/// Reply of `zl_process`; no content leaves the file to the built-in handlers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessOutput {
+    pub content: Option<String>,
+}
+
+/// Message for `zl_rank`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankInput {
+    pub query: String,
+    pub results: Vec<RankCandidate>,
+}
+
+/// A search result as seen by a ranker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankCandidate {
+    pub id: String,
+    pub title: String,
+    pub path: String,
+    pub content: String,
+    pub score: f32,
+}
+
+/// Reply of `zl_rank`: a score between 0 and 1 per candidate, in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankOutput {
+    pub scores: Vec<f32>,
+}
+
+/// A loaded plugin, whatever its runtime
+pub trait Plugin: Send + Sync {
+    fn info(&self) -> &PluginInfo;
+
+    fn runtime(&self) -> PluginRuntime;
+
+    /// Call an exported function with a JSON message and return its JSON reply
+    fn call(&self, export: &str, input: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// A plugin as listed by `/api/plugins`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginSummary {
+    #[serde(flatten)]
+    pub info: PluginInfo,
+    pub runtime: PluginRuntime,
+    pub path: PathBuf,
+}
+
+struct LoadedPlugin {
+    plugin: Arc<dyn Plugin>,
+    path: PathBuf,
+}
+
+impl LoadedPlugin {
+    fn has(&self, capability: PluginCapability) -> bool {
+        self.plugin.info().capabilities.contains(&capability)
+    }
+
+    fn name(&self) -> &str {
+        &self.plugin.info().name
+    }
+
+    fn invoke<I: Serialize, O: serde::de::DeserializeOwned>(
+        &self,
+        capability: PluginCapability,
+        input: &I,
+    ) -> Result<O> {
+        let message = serde_json::to_vec(input).map_err(|e| {
+            ZeroLatencyError::serialization(format!("Failed to encode plugin message: {}", e))
+        })?;
+        let reply = self.plugin.call(capability.export(), &message)?;
+        serde_json::from_slice(&reply).map_err(|e| {
+            ZeroLatencyError::external_service(
+                format!("plugin {}", self.name()),
+                format!("invalid reply from {}: {}", capability.export(), e),
+            )
+        })
+    }
+}
+
+/// Loaded plugins and the stages they run in
+#[derive(Default)]
+pub struct PluginHost {
+    plugins: Vec<LoadedPlugin>,
+}
+
+impl PluginHost {
+    /// A host without plugins
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Load every plugin in the configured directory, in file name order
+    ///
+    /// A plugin that fails to load is logged and left out, so one broken
+    /// plugin doesn't keep the service from starting. A configured directory
+    /// that doesn't exist is an error.
+    pub fn discover(config: &PluginConfig) -> Result<Self> {
+        let mut host = Self::empty();
+        let Some(directory) = &config.directory else {
+            return Ok(host);
+        };
+
+        let entries = std::fs::read_dir(directory).map_err(|e| {
+            ZeroLatencyError::configuration(format!(
+                "Cannot read plugins directory {}: {}",
+                directory.display(),
+                e
+            ))
+        })?;
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match Self::load(&path, config) {
+                Ok(Some(plugin)) => {
+                    if let Err(e) = host.register(plugin, path.clone()) {
+                        tracing::warn!("Skipping plugin {}: {}", path.display(), e);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to load plugin {}: {}", path.display(), e),
+            }
+        }
+
+        tracing::info!(
+            "Loaded {} plugin(s) from {}",
+            host.plugins.len(),
+            directory.display()
+        );
+        Ok(host)
+    }
+
+    /// Load a plugin file, or `None` for files that are not plugins
+    fn load(path: &Path, config: &PluginConfig) -> Result<Option<Arc<dyn Plugin>>> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if extension == "wasm" {
+            #[cfg(feature = "wasm-plugins")]
+            return Ok(Some(Arc::new(wasm::WasmPlugin::load(path, config)?)));
+            #[cfg(not(feature = "wasm-plugins"))]
+            return Err(ZeroLatencyError::configuration(
+                "WASM plugins need a build with the wasm-plugins feature",
+            ));
+        }
+
+        if NATIVE_EXTENSIONS.contains(&extension.as_str()) {
+            if !config.allow_native {
+                return Err(ZeroLatencyError::configuration(
+                    "native plugins run unsandboxed; set DOC_INDEXER_PLUGINS_ALLOW_NATIVE=true to load them",
+                ));
+            }
+            #[cfg(feature = "native-plugins")]
+            return Ok(Some(Arc::new(native::NativePlugin::load(path)?)));
+            #[cfg(not(feature = "native-plugins"))]
+            return Err(ZeroLatencyError::configuration(
+                "native plugins need a build with the native-plugins feature",
+            ));
+        }
+
+        Ok(None)
+    }
+
+    /// Add a plugin; names must be unique
+    pub fn register(&mut self, plugin: Arc<dyn Plugin>, path: PathBuf) -> Result<()> {
+        let name = &plugin.info().name;
+        if self.plugins.iter().any(|loaded| loaded.name() == name) {
+            return Err(ZeroLatencyError::validation(
+                "name",
+                format!("a plugin named {} is already loaded", name),
+            ));
+        }
+        tracing::info!(
+            "Registered {:?} plugin {} {} ({:?})",
+            plugin.runtime(),
+            name,
+            plugin.info().version,
+            plugin.info().capabilities
+        );
+        self.plugins.push(LoadedPlugin { plugin, path });
+        Ok(())
+    }
+
+    /// Loaded plugins in the order they run
+    pub fn list(&self) -> Vec<PluginSummary> {
+        self.plugins
+            .iter()
+            .map(|loaded| PluginSummary {
+                info: loaded.plugin.info().clone(),
+                runtime: loaded.plugin.runtime(),
+                path: loaded.path.clone(),
+            })
+            .collect()
+    }
+
+    fn with(&self, capability: PluginCapability) -> impl Iterator<Item = &LoadedPlugin> {
+        self.plugins
+            .iter()
+            .filter(move |loaded| loaded.has(capability))
+    }
+
+    /// Whether every filter plugin lets the file be indexed
+    ///
+    /// A filter that fails is logged and doesn't exclude the file.
+    pub fn accepts(&self, path: &Path, size: u64) -> bool {
+        let input = FilterInput {
+            path: path.to_string_lossy().into_owned(),
+            size,
+        };
+        self.with(PluginCapability::Filter).all(|loaded| {
+            match loaded.invoke::<_, FilterOutput>(PluginCapability::Filter, &input) {
+                Ok(output) => output.index,
+                Err(e) => {
+                    tracing::warn!(
+                        "Filter plugin {} failed on {}: {}",
+                        loaded.name(),
+                        input.path,
+                        e
+                    );
+                    true
+                }
+            }
+        })
+    }
+
+    /// Text of a file from the first content handler plugin that takes it
+    ///
+    /// Returns `None` when no plugin handles the file, leaving it to the
+    /// built-in handlers.
+    pub fn process(&self, path: &Path, content: &str) -> Result<Option<String>> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        let input = ProcessInput {
+            path: path.to_string_lossy().into_owned(),
+            content: content.to_string(),
+        };
+
+        for loaded in self.with(PluginCapability::ContentHandler) {
+            let extensions = &loaded.plugin.info().extensions;
+            if !extensions.is_empty()
+                && !extensions
+                    .iter()
+                    .any(|wanted| wanted.eq_ignore_ascii_case(extension))
+            {
+                continue;
+            }
+            let output: ProcessOutput = loaded.invoke(PluginCapability::ContentHandler, &input)?;
+            if let Some(content) = output.content {
+                return Ok(Some(content));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether any ranker plugin is loaded
+    pub fn has_rankers(&self) -> bool {
+        self.with(PluginCapability::Ranker).next().is_some()
+    }
+
+    /// Rescore results with every ranker plugin in turn, best first
+    ///
+    /// A ranker that fails or replies with the wrong number of scores is
+    /// logged and skipped.
+    pub fn rank(&self, query: &str, results: &mut [SearchResult]) {
+        for loaded in self.with(PluginCapability::Ranker) {
+            let input = RankInput {
+                query: query.to_string(),
+                results: results
+                    .iter()
+                    .map(|result| RankCandidate {
+                        id: result.chunk_id.to_string(),
+                        title: result.title.clone(),
+                        path: result.uri.clone(),
+                        content: result.content.clone(),
+                        score: result.final_score.value(),
+                    })
+                    .collect(),
+            };
+            let scores = match loaded.invoke::<_, RankOutput>(PluginCapability::Ranker, &input) {
+                Ok(output) if output.scores.len() == results.len() => output.scores,
+                Ok(output) => {
+                    tracing::warn!(
+                        "Ranker plugin {} returned {} scores for {} results",
+                        loaded.name(),
+                        output.scores.len(),
+                        results.len()
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Ranker plugin {} failed: {}", loaded.name(), e);
+                    continue;
+                }
+            };
+
+            for (result, score) in results.iter_mut().zip(scores) {
+                let score = if score.is_finite() {
+                    score.clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                result.final_score = Score::new(score).unwrap_or(result.final_score);
+            }
+            results.sort_by(|a, b| b.final_score.value().total_cmp(&a.final_score.value()));
+        }
+    }
+}
+
+/// Search step running the ranker plugins after the built-in ranking
+pub struct PluginRankingStep {
+    host: Arc<PluginHost>,
+}
+
+impl PluginRankingStep {
+    pub fn new(host: Arc<PluginHost>) -> Self {
+        Self { host }
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchStep for PluginRankingStep {
+    fn name(&self) -> &str {
+        "plugin_ranking"
+    }
+
+    async fn execute(&self, context: &mut SearchContext) -> Result<()> {
+        let query = context.request.query.raw.clone();
+        self.host.rank(&query, &mut context.ranked_results);
+        context.metadata.ranking_method.push_str("+plugins");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plugin answering every call with a fixed reply
+    struct StaticPlugin {
+        info: PluginInfo,
+        reply: serde_json::Value,
+    }
+
+    impl Plugin for StaticPlugin {
+        fn info(&self) -> &PluginInfo {
+            &self.info
+        }
+
+        fn runtime(&self) -> PluginRuntime {
+            PluginRuntime::Native
+        }
+
+        fn call(&self, _export: &str, _input: &[u8]) -> Result<Vec<u8>> {
+            Ok(self.reply.to_string().into_bytes())
+        }
+    }
+
+    fn plugin(
+        name: &str,
+        capability: PluginCapability,
+        extensions: &[&str],
+        reply: serde_json::Value,
+    ) -> Arc<dyn Plugin> {
+        Arc::new(StaticPlugin {
+            info: PluginInfo {
+                name: name.to_string(),
+                version: "1.0.0".to_string(),
+                capabilities: vec![capability],
+                extensions: extensions.iter().map(|e| e.to_string()).collect(),
+            },
+            reply,
+        })
+    }
+
+    fn result(title: &str, score: f32) -> SearchResult {
+        SearchResult::from(zero_latency_vector::SimilarityResult {
+            document_id: zero_latency_core::Uuid::new_v4(),
+            similarity: Score::new(score).unwrap(),
+            metadata: zero_latency_vector::VectorMetadata {
+                title: title.to_string(),
+                ..Default::default()
+            },
+        })
+    }
+
+    #[test]
+    fn test_filters_and_content_handlers() {
+        let mut host = PluginHost::empty();
+        assert!(host.accepts(Path::new("a.md"), 10));
+
+        host.register(
+            plugin(
+                "proto",
+                PluginCapability::ContentHandler,
+                &["proto"],
+                serde_json::json!({ "content": "message Foo" }),
+            ),
+            PathBuf::from("proto.wasm"),
+        )
+        .unwrap();
+        host.register(
+            plugin(
+                "no-vendor",
+                PluginCapability::Filter,
+                &[],
+                serde_json::json!({ "index": false }),
+            ),
+            PathBuf::from("no-vendor.wasm"),
+        )
+        .unwrap();
+
+        assert!(!host.accepts(Path::new("a.md"), 10));
+        assert_eq!(
+            host.process(Path::new("api.PROTO"), "syntax").unwrap(),
+            Some("message Foo".to_string())
+        );
+        assert_eq!(host.process(Path::new("a.md"), "# A").unwrap(), None);
+
+        // Names are unique
+        let duplicate = plugin(
+            "proto",
+            PluginCapability::Ranker,
+            &[],
+            serde_json::json!({}),
+        );
+        assert!(host.register(duplicate, PathBuf::from("x.wasm")).is_err());
+        assert_eq!(host.list().len(), 2);
+    }
+
+    #[test]
+    fn test_rankers_rescore_and_ignore_bad_replies() {
+        let mut host = PluginHost::empty();
+        host.register(
+            plugin(
+                "short",
+                PluginCapability::Ranker,
+                &[],
+                serde_json::json!({ "scores": [0.5] }),
+            ),
+            PathBuf::from("short.wasm"),
+        )
+        .unwrap();
+
+        let mut results = vec![result("a", 0.9), result("b", 0.4)];
+        host.rank("query", &mut results);
+        assert_eq!(results[0].title, "a");
+
+        host.register(
+            plugin(
+                "reverse",
+                PluginCapability::Ranker,
+                &[],
+                serde_json::json!({ "scores": [0.1, 2.0] }),
+            ),
+            PathBuf::from("reverse.wasm"),
+        )
+        .unwrap();
+        assert!(host.has_rankers());
+        host.rank("query", &mut results);
+        assert_eq!(results[0].title, "b");
+        assert_eq!(results[0].final_score.value(), 1.0);
+    }
+
+    #[test]
+    fn test_discover_skips_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("README.md"), "# Plugins").unwrap();
+        std::fs::write(dir.path().join("native.so"), b"not a library").unwrap();
+
+        let config = PluginConfig {
+            directory: Some(dir.path().to_path_buf()),
+            allow_native: false,
+            fuel_per_call: 1_000,
+            memory_limit_mb: 1,
+        };
+        assert!(PluginHost::discover(&config).unwrap().list().is_empty());
+
+        let missing = PluginConfig {
+            directory: Some(dir.path().join("missing")),
+            ..config
+        };
+        assert!(PluginHost::discover(&missing).is_err());
+    }
+}
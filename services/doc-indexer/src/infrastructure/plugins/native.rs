@@ -0,0 +1,126 @@
+/// Native plugin runtime
+///
+/// A native plugin is a shared library exporting C functions:
+///
+/// ```c
+/// uint32_t zl_abi_version(void);
+/// uint8_t *zl_info(const uint8_t *message, size_t len, size_t *reply_len);
+/// uint8_t *zl_filter(const uint8_t *message, size_t len, size_t *reply_len);
+/// void zl_free(uint8_t *reply, size_t len);
+/// ```
+///
+/// Every stage has the signature of `zl_info`. It returns a reply the host
+/// hands back to `zl_free` once copied, or null on failure. Native plugins
+/// run in the service's process with its permissions, so they are only
+/// loaded when explicitly allowed.
+use libloading::{Library, Symbol};
+use std::path::Path;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use super::{Plugin, PluginInfo, PluginRuntime, PLUGIN_ABI_VERSION};
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type StageFn = unsafe extern "C" fn(*const u8, usize, *mut usize) -> *mut u8;
+type FreeFn = unsafe extern "C" fn(*mut u8, usize);
+
+/// A plugin loaded from a shared library
+pub struct NativePlugin {
+    info: PluginInfo,
+    library: Library,
+}
+
+impl NativePlugin {
+    /// Load the library at `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        // SAFETY: loading runs the library's initialisers; native plugins are
+        // trusted code the operator opted into
+        let library = unsafe { Library::new(path) }.map_err(load_error)?;
+
+        // SAFETY: the ABI fixes the signature of zl_abi_version
+        let abi_version = unsafe {
+            let function: Symbol<AbiVersionFn> =
+                library.get(b"zl_abi_version\0").map_err(load_error)?;
+            function()
+        };
+        if abi_version != PLUGIN_ABI_VERSION {
+            return Err(ZeroLatencyError::configuration(format!(
+                "plugin uses ABI version {}, expected {}",
+                abi_version, PLUGIN_ABI_VERSION
+            )));
+        }
+
+        let mut plugin = Self {
+            info: PluginInfo {
+                name: String::new(),
+                version: String::new(),
+                capabilities: Vec::new(),
+                extensions: Vec::new(),
+            },
+            library,
+        };
+        let reply = plugin.call("zl_info", &[])?;
+        plugin.info = serde_json::from_slice(&reply).map_err(|e| {
+            ZeroLatencyError::configuration(format!("invalid zl_info reply: {}", e))
+        })?;
+        Ok(plugin)
+    }
+
+    fn call_error(&self, export: &str, message: impl std::fmt::Display) -> ZeroLatencyError {
+        ZeroLatencyError::external_service(
+            format!("plugin {}", self.info.name),
+            format!("{} failed: {}", export, message),
+        )
+    }
+}
+
+impl Plugin for NativePlugin {
+    fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    fn runtime(&self) -> PluginRuntime {
+        PluginRuntime::Native
+    }
+
+    fn call(&self, export: &str, input: &[u8]) -> Result<Vec<u8>> {
+        let symbol = format!("{}\0", export);
+        // SAFETY: the ABI fixes the signatures of stage functions and zl_free;
+        // the reply is copied before it is handed back to the plugin
+        unsafe {
+            let function: Symbol<StageFn> = self
+                .library
+                .get(symbol.as_bytes())
+                .map_err(|e| self.call_error(export, e))?;
+            let free: Symbol<FreeFn> = self
+                .library
+                .get(b"zl_free\0")
+                .map_err(|e| self.call_error("zl_free", e))?;
+
+            let mut reply_len = 0usize;
+            let reply = function(input.as_ptr(), input.len(), &mut reply_len);
+            if reply.is_null() {
+                return Err(self.call_error(export, "plugin returned no reply"));
+            }
+            let bytes = std::slice::from_raw_parts(reply, reply_len).to_vec();
+            free(reply, reply_len);
+            Ok(bytes)
+        }
+    }
+}
+
+fn load_error(e: libloading::Error) -> ZeroLatencyError {
+    ZeroLatencyError::configuration(format!("invalid native plugin: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_rejects_non_libraries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.so");
+        std::fs::write(&path, b"not a library").unwrap();
+        assert!(NativePlugin::load(&path).is_err());
+    }
+}
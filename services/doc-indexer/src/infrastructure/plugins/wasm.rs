@@ -0,0 +1,238 @@
+/// WebAssembly plugin runtime
+///
+/// A WASM plugin is a core module exporting `memory`, `zl_alloc(len) -> ptr`,
+/// `zl_abi_version() -> i32` and one `(ptr: i32, len: i32) -> i64` function
+/// per stage. The host copies the JSON message into memory returned by
+/// `zl_alloc` and reads the reply from the returned value, which packs the
+/// reply's pointer in the high 32 bits and its length in the low 32 bits.
+///
+/// Plugins are sandboxed: a module may not import anything, so it has no
+/// access to files, the network or the clock. Each call runs in a fresh
+/// instance with a fuel budget and a memory cap, so a plugin can neither
+/// keep state between calls nor stall or exhaust the service.
+use std::path::Path;
+use wasmtime::{
+    Config as EngineConfig, Engine, Instance, InstancePre, Linker, Memory, Module, Store,
+    StoreLimits, StoreLimitsBuilder,
+};
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use super::{Plugin, PluginInfo, PluginRuntime, PLUGIN_ABI_VERSION};
+use crate::config::PluginConfig;
+
+/// A plugin compiled from a WebAssembly module
+pub struct WasmPlugin {
+    info: PluginInfo,
+    engine: Engine,
+    instance_pre: InstancePre<StoreLimits>,
+    fuel_per_call: u64,
+    memory_limit_bytes: usize,
+}
+
+impl WasmPlugin {
+    /// Compile the module at `path`
+    pub fn load(path: &Path, config: &PluginConfig) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| ZeroLatencyError::io(format!("Failed to read plugin: {}", e)))?;
+        Self::from_bytes(&bytes, config)
+    }
+
+    /// Compile a module from its binary (or, for tests, text) form
+    pub fn from_bytes(bytes: &[u8], config: &PluginConfig) -> Result<Self> {
+        let mut engine_config = EngineConfig::new();
+        engine_config.consume_fuel(true);
+        let engine = Engine::new(&engine_config).map_err(load_error)?;
+        let module = Module::new(&engine, bytes).map_err(load_error)?;
+
+        if let Some(import) = module.imports().next() {
+            return Err(ZeroLatencyError::configuration(format!(
+                "plugin imports {}::{}, but plugins may not import anything",
+                import.module(),
+                import.name()
+            )));
+        }
+        let instance_pre = Linker::new(&engine)
+            .instantiate_pre(&module)
+            .map_err(load_error)?;
+
+        let mut plugin = Self {
+            info: PluginInfo {
+                name: String::new(),
+                version: String::new(),
+                capabilities: Vec::new(),
+                extensions: Vec::new(),
+            },
+            engine,
+            instance_pre,
+            fuel_per_call: config.fuel_per_call,
+            memory_limit_bytes: config.memory_limit_mb.saturating_mul(1024 * 1024),
+        };
+
+        let (mut store, instance, _) = plugin.instantiate()?;
+        let abi_version = instance
+            .get_typed_func::<(), i32>(&mut store, "zl_abi_version")
+            .and_then(|function| function.call(&mut store, ()))
+            .map_err(load_error)?;
+        if abi_version as u32 != PLUGIN_ABI_VERSION {
+            return Err(ZeroLatencyError::configuration(format!(
+                "plugin uses ABI version {}, expected {}",
+                abi_version, PLUGIN_ABI_VERSION
+            )));
+        }
+
+        let reply = plugin.call("zl_info", &[])?;
+        plugin.info = serde_json::from_slice(&reply).map_err(|e| {
+            ZeroLatencyError::configuration(format!("invalid zl_info reply: {}", e))
+        })?;
+        Ok(plugin)
+    }
+
+    fn instantiate(&self) -> Result<(Store<StoreLimits>, Instance, Memory)> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.memory_limit_bytes)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(self.fuel_per_call).map_err(load_error)?;
+
+        let instance = self
+            .instance_pre
+            .instantiate(&mut store)
+            .map_err(load_error)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| ZeroLatencyError::configuration("plugin does not export memory"))?;
+        Ok((store, instance, memory))
+    }
+
+    fn call_error(&self, export: &str, message: impl std::fmt::Display) -> ZeroLatencyError {
+        ZeroLatencyError::external_service(
+            format!("plugin {}", self.info.name),
+            format!("{} failed: {}", export, message),
+        )
+    }
+}
+
+impl Plugin for WasmPlugin {
+    fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    fn runtime(&self) -> PluginRuntime {
+        PluginRuntime::Wasm
+    }
+
+    fn call(&self, export: &str, input: &[u8]) -> Result<Vec<u8>> {
+        let (mut store, instance, memory) = self.instantiate()?;
+        let len = i32::try_from(input.len())
+            .map_err(|_| self.call_error(export, "message is too large"))?;
+
+        let ptr = instance
+            .get_typed_func::<i32, i32>(&mut store, "zl_alloc")
+            .and_then(|alloc| alloc.call(&mut store, len))
+            .map_err(|e| self.call_error("zl_alloc", format!("{:#}", e)))?;
+        memory
+            .write(&mut store, ptr as u32 as usize, input)
+            .map_err(|e| self.call_error("zl_alloc", e))?;
+
+        let packed = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, export)
+            .and_then(|function| function.call(&mut store, (ptr, len)))
+            .map_err(|e| self.call_error(export, format!("{:#}", e)))?;
+
+        let reply_ptr = (packed as u64 >> 32) as usize;
+        let reply_len = (packed as u64 & 0xffff_ffff) as usize;
+        memory
+            .data(&store)
+            .get(reply_ptr..reply_ptr + reply_len)
+            .map(|reply| reply.to_vec())
+            .ok_or_else(|| self.call_error(export, "reply is outside the plugin's memory"))
+    }
+}
+
+fn load_error(e: wasmtime::Error) -> ZeroLatencyError {
+    ZeroLatencyError::configuration(format!("invalid WASM plugin: {:#}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::plugins::PluginCapability;
+
+    fn config() -> PluginConfig {
+        PluginConfig {
+            directory: None,
+            allow_native: false,
+            fuel_per_call: 1_000_000,
+            memory_limit_mb: 2,
+        }
+    }
+
+    /// Module echoing `zl_process` messages and looping forever in `zl_rank`
+    fn module(extra: &str) -> String {
+        let info =
+            r#"{"name":"echo","version":"0.1.0","capabilities":["content_handler","ranker"]}"#;
+        format!(
+            r#"(module
+                {extra}
+                (memory (export "memory") 1)
+                (global $heap (mut i32) (i32.const 1024))
+                (data (i32.const 0) "{data}")
+                (func (export "zl_abi_version") (result i32) (i32.const 1))
+                (func (export "zl_alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $heap))
+                    (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+                    (local.get $ptr))
+                (func (export "zl_info") (param i32 i32) (result i64)
+                    (i64.const {info_len}))
+                (func (export "zl_process") (param $ptr i32) (param $len i32) (result i64)
+                    (i64.or
+                        (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+                        (i64.extend_i32_u (local.get $len))))
+                (func (export "zl_rank") (param i32 i32) (result i64)
+                    (loop $forever (br $forever))
+                    (unreachable)))"#,
+            data = info.replace('"', "\\\""),
+            info_len = info.len(),
+        )
+    }
+
+    #[test]
+    fn test_messages_round_trip() {
+        let plugin = WasmPlugin::from_bytes(module("").as_bytes(), &config()).unwrap();
+        assert_eq!(plugin.info().name, "echo");
+        assert_eq!(
+            plugin.info().capabilities,
+            vec![PluginCapability::ContentHandler, PluginCapability::Ranker]
+        );
+
+        let message = br#"{"path":"a.proto","content":"syntax = \"proto3\";"}"#;
+        assert_eq!(
+            plugin.call("zl_process", message).unwrap(),
+            message.to_vec()
+        );
+        // Every call starts from a fresh instance
+        assert_eq!(
+            plugin.call("zl_process", message).unwrap(),
+            message.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_sandbox_limits() {
+        let plugin = WasmPlugin::from_bytes(module("").as_bytes(), &config()).unwrap();
+        let error = plugin.call("zl_rank", b"{}").unwrap_err().to_string();
+        assert!(error.contains("fuel"), "{}", error);
+        assert!(plugin.call("zl_filter", b"{}").is_err());
+
+        // Host functions are not available
+        let importing = module(r#"(import "env" "open" (func (param i32)))"#);
+        let error = WasmPlugin::from_bytes(importing.as_bytes(), &config())
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("env::open"), "{}", error);
+    }
+}
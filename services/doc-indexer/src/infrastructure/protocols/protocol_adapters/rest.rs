@@ -5,6 +5,7 @@
 //! the schema and delegate to domain services.
 
 use crate::application::ServiceContainer;
+use crate::infrastructure::api::http::request_id::current_request_id;
 use axum::{
     extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
@@ -116,7 +117,7 @@ impl RestAdapter {
             error: "Internal server error".to_string(),
             message: error.to_string(),
             code: "INTERNAL_ERROR".to_string(),
-            trace_id: None,
+            trace_id: current_request_id(),
             details: None,
         }
     }
@@ -457,7 +458,7 @@ impl RestAdapter {
                     error: "Collection not found".to_string(),
                     message: format!("Collection '{}' does not exist", name),
                     code: "COLLECTION_NOT_FOUND".to_string(),
-                    trace_id: None,
+                    trace_id: current_request_id(),
                     details: None,
                 };
                 (StatusCode::NOT_FOUND, Json(error)).into_response()
@@ -494,7 +495,7 @@ impl RestAdapter {
                     error: "Collection not found".to_string(),
                     message: format!("Collection '{}' does not exist", name),
                     code: "COLLECTION_NOT_FOUND".to_string(),
-                    trace_id: None,
+                    trace_id: current_request_id(),
                     details: None,
                 };
                 (StatusCode::NOT_FOUND, Json(error)).into_response()
@@ -546,7 +547,7 @@ impl RestAdapter {
                     error: "Collection not found".to_string(),
                     message: format!("Collection '{}' does not exist", name),
                     code: "COLLECTION_NOT_FOUND".to_string(),
-                    trace_id: None,
+                    trace_id: current_request_id(),
                     details: None,
                 };
                 (StatusCode::NOT_FOUND, Json(error)).into_response()
@@ -0,0 +1,473 @@
+/// Scripting hooks for lightweight per-collection customization
+///
+/// Scripts are written in Rhai and live in the configured scripts directory,
+/// in one folder per collection:
+///
+/// | File | Runs | Effect |
+/// |------|------|--------|
+/// | `<collection>/enrich.rhai` | For every document indexed into the collection | Changes `title`, `tags` and `metadata` |
+/// | `<collection>/score.rhai` | For every search result from the collection | Evaluates to the result's new score |
+///
+/// Scripts are reloaded when their file changes, so they can be edited
+/// while the service runs. A script that no longer compiles is reported and
+/// the previous version stays in effect. Scripts cannot read files, load
+/// modules or run for more than the configured number of operations.
+use rhai::{Dynamic, Engine, Map, Scope, AST, FLOAT, INT};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+use zero_latency_core::{models::Document, values::Score, Result, ZeroLatencyError};
+use zero_latency_search::{SearchContext, SearchResult, SearchStep};
+
+use crate::config::ScriptConfig;
+
+/// How long a script is used before its file is checked for changes
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Metadata keys the service maintains itself
+const RESERVED_METADATA_KEYS: [&str; 2] = ["collection", zero_latency_vector::PATH_KEY];
+
+/// Point in the pipeline a script runs at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptHook {
+    /// Per-document metadata enrichment at index time
+    Enrich,
+    /// Per-result score adjustment at query time
+    Score,
+}
+
+impl ScriptHook {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ScriptHook::Enrich => "enrich.rhai",
+            ScriptHook::Score => "score.rhai",
+        }
+    }
+}
+
+struct CachedScript {
+    // Last version that compiled, if any
+    ast: Option<Arc<AST>>,
+    modified: Option<SystemTime>,
+    checked_at: Instant,
+}
+
+/// Runs the scripts of each collection, reloading them as they change
+pub struct ScriptHost {
+    engine: Engine,
+    directory: Option<PathBuf>,
+    scripts: RwLock<HashMap<(String, ScriptHook), CachedScript>>,
+}
+
+impl ScriptHost {
+    pub fn new(config: &ScriptConfig) -> Self {
+        let mut engine = Engine::new();
+        engine.set_max_operations(config.max_operations);
+        engine.set_max_call_levels(32);
+        engine.set_max_expr_depths(64, 32);
+        engine.set_max_array_size(10_000);
+        engine.set_max_map_size(10_000);
+        // Scripts only see what they are given
+        engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+        engine.disable_symbol("eval");
+        engine.on_print(|text| tracing::info!("script: {}", text));
+        engine.on_debug(|text, _, position| tracing::debug!("script ({}): {}", position, text));
+
+        Self {
+            engine,
+            directory: config.directory.clone(),
+            scripts: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Whether a scripts directory is configured
+    pub fn is_enabled(&self) -> bool {
+        self.directory.is_some()
+    }
+
+    /// Run the collection's enrich script on a document about to be indexed
+    ///
+    /// The script can change the `title`, `tags` and `metadata` variables;
+    /// `path`, `content` and `content_type` are read-only. Metadata set to
+    /// `()` is removed.
+    pub fn enrich(&self, collection: &str, document: &mut Document) -> Result<()> {
+        let Some(ast) = self.script(collection, ScriptHook::Enrich) else {
+            return Ok(());
+        };
+
+        let mut scope = Scope::new();
+        scope.push_constant("path", document.path.to_string_lossy().into_owned());
+        scope.push_constant("content", document.content.clone());
+        scope.push_constant(
+            "content_type",
+            document.metadata.content_type.clone().unwrap_or_default(),
+        );
+        scope.push("title", document.title.clone());
+        scope.push(
+            "tags",
+            document
+                .metadata
+                .tags
+                .iter()
+                .map(|tag| Dynamic::from(tag.clone()))
+                .collect::<rhai::Array>(),
+        );
+        scope.push("metadata", to_map(&document.metadata.custom));
+
+        self.engine
+            .run_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| script_error(collection, ScriptHook::Enrich, e))?;
+
+        if let Some(title) = scope.get_value::<String>("title") {
+            document.title = title;
+        }
+        if let Some(tags) = scope.get_value::<rhai::Array>("tags") {
+            document.metadata.tags = tags
+                .into_iter()
+                .filter(|tag| !tag.is_unit())
+                .map(|tag| tag.to_string())
+                .collect();
+        }
+        if let Some(metadata) = scope.get_value::<Map>("metadata") {
+            let mut custom: HashMap<String, String> = metadata
+                .into_iter()
+                .filter(|(key, value)| {
+                    !value.is_unit() && !RESERVED_METADATA_KEYS.contains(&key.as_str())
+                })
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+            for key in RESERVED_METADATA_KEYS {
+                if let Some(value) = document.metadata.custom.remove(key) {
+                    custom.insert(key.to_string(), value);
+                }
+            }
+            document.metadata.custom = custom;
+        }
+        Ok(())
+    }
+
+    /// Run the score script of each result's collection and re-sort
+    ///
+    /// The script sees `query`, `score`, `title`, `path`, `content`,
+    /// `collection` and `metadata`, and evaluates to the new score, or
+    /// assigns it to `score`. Scores are clamped between 0 and 1; a result
+    /// whose script fails keeps its score. Returns whether any script ran.
+    pub fn adjust_scores(&self, query: &str, results: &mut [SearchResult]) -> bool {
+        let mut adjusted = false;
+        let mut failed = HashSet::new();
+        for result in results.iter_mut() {
+            let Some(collection) = result
+                .collection
+                .clone()
+                .or_else(|| result.custom_metadata.get("collection").cloned())
+            else {
+                continue;
+            };
+            let Some(ast) = self.script(&collection, ScriptHook::Score) else {
+                continue;
+            };
+
+            let mut scope = Scope::new();
+            scope.push_constant("query", query.to_string());
+            scope.push("score", result.final_score.value() as FLOAT);
+            scope.push_constant("title", result.title.clone());
+            scope.push_constant("path", result.uri.clone());
+            scope.push_constant("content", result.content.clone());
+            scope.push_constant("collection", collection.clone());
+            scope.push_constant("metadata", to_map(&result.custom_metadata));
+
+            let score = self
+                .engine
+                .eval_ast_with_scope::<Dynamic>(&mut scope, &ast)
+                .map(|value| {
+                    as_number(&value)
+                        .or_else(|| scope.get("score").and_then(as_number))
+                        .unwrap_or(0.0)
+                });
+            match score {
+                Ok(score) => {
+                    let score = if score.is_finite() {
+                        score.clamp(0.0, 1.0) as f32
+                    } else {
+                        0.0
+                    };
+                    result.final_score = Score::new(score).unwrap_or(result.final_score);
+                    adjusted = true;
+                }
+                Err(e) => {
+                    // Report a broken script once per search, not once per result
+                    if failed.insert(collection.clone()) {
+                        tracing::warn!("{}", script_error(&collection, ScriptHook::Score, e));
+                    }
+                }
+            }
+        }
+
+        if adjusted {
+            results.sort_by(|a, b| b.final_score.value().total_cmp(&a.final_score.value()));
+        }
+        adjusted
+    }
+
+    /// Current version of a collection's script, reloading it if the file changed
+    fn script(&self, collection: &str, hook: ScriptHook) -> Option<Arc<AST>> {
+        let directory = self.directory.as_ref()?;
+        // Collection names come from requests; never leave the scripts directory
+        if collection.is_empty() || collection.contains(['/', '\\']) || collection.starts_with('.')
+        {
+            return None;
+        }
+
+        let key = (collection.to_string(), hook);
+        if let Some(cached) = self.read().get(&key) {
+            if cached.checked_at.elapsed() < RELOAD_CHECK_INTERVAL {
+                return cached.ast.clone();
+            }
+        }
+
+        let path = directory.join(collection).join(hook.file_name());
+        let modified = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let mut scripts = self
+            .scripts
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let cached = scripts.entry(key).or_insert_with(|| CachedScript {
+            ast: None,
+            modified: None,
+            checked_at: Instant::now(),
+        });
+        cached.checked_at = Instant::now();
+
+        if modified.is_none() {
+            cached.ast = None;
+        } else if modified != cached.modified {
+            let compiled = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|source| self.engine.compile(source).map_err(|e| e.to_string()));
+            match compiled {
+                Ok(ast) => {
+                    tracing::info!("Loaded script {}", path.display());
+                    cached.ast = Some(Arc::new(ast));
+                }
+                Err(e) => tracing::warn!(
+                    "Script {} does not compile, keeping the previous version: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        cached.modified = modified;
+        cached.ast.clone()
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<(String, ScriptHook), CachedScript>> {
+        self.scripts
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Search step running the score scripts after ranking
+pub struct ScriptScoringStep {
+    host: Arc<ScriptHost>,
+}
+
+impl ScriptScoringStep {
+    pub fn new(host: Arc<ScriptHost>) -> Self {
+        Self { host }
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchStep for ScriptScoringStep {
+    fn name(&self) -> &str {
+        "script_scoring"
+    }
+
+    async fn execute(&self, context: &mut SearchContext) -> Result<()> {
+        let query = context.request.query.raw.clone();
+        if self.host.adjust_scores(&query, &mut context.ranked_results) {
+            context.metadata.ranking_method.push_str("+scripts");
+        }
+        Ok(())
+    }
+}
+
+fn to_map(values: &HashMap<String, String>) -> Map {
+    values
+        .iter()
+        .map(|(key, value)| (key.as_str().into(), Dynamic::from(value.clone())))
+        .collect()
+}
+
+fn as_number(value: &Dynamic) -> Option<f64> {
+    value
+        .as_float()
+        .ok()
+        .or_else(|| value.as_int().ok().map(|value: INT| value as FLOAT))
+}
+
+fn script_error(collection: &str, hook: ScriptHook, e: impl std::fmt::Display) -> ZeroLatencyError {
+    ZeroLatencyError::configuration(format!(
+        "script {}/{} failed: {}",
+        collection,
+        hook.file_name(),
+        e
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(directory: &std::path::Path) -> ScriptHost {
+        ScriptHost::new(&ScriptConfig {
+            directory: Some(directory.to_path_buf()),
+            max_operations: 10_000,
+        })
+    }
+
+    fn write_script(directory: &std::path::Path, collection: &str, hook: ScriptHook, source: &str) {
+        let folder = directory.join(collection);
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join(hook.file_name()), source).unwrap();
+    }
+
+    fn result(title: &str, collection: &str, score: f32) -> SearchResult {
+        let mut result = SearchResult::from(zero_latency_vector::SimilarityResult {
+            document_id: zero_latency_core::Uuid::new_v4(),
+            similarity: Score::new(score).unwrap(),
+            metadata: zero_latency_vector::VectorMetadata {
+                title: title.to_string(),
+                ..Default::default()
+            },
+        });
+        result.collection = Some(collection.to_string());
+        result
+    }
+
+    #[test]
+    fn test_enrich_updates_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        write_script(
+            dir.path(),
+            "docs",
+            ScriptHook::Enrich,
+            r#"
+                if path.ends_with(".md") { metadata.format = "markdown"; }
+                metadata.words = content.split(" ").len();
+                metadata.collection = "elsewhere";
+                metadata.draft = ();
+                tags.push("reviewed");
+                title = title.to_upper();
+            "#,
+        );
+
+        let mut document = Document {
+            id: zero_latency_core::Uuid::new_v4(),
+            title: "guide".to_string(),
+            content: "one two three".to_string(),
+            path: PathBuf::from("/docs/guide.md"),
+            last_modified: chrono::Utc::now(),
+            size: 13,
+            metadata: Default::default(),
+        };
+        document
+            .metadata
+            .custom
+            .insert("collection".to_string(), "docs".to_string());
+        document
+            .metadata
+            .custom
+            .insert("draft".to_string(), "true".to_string());
+
+        let host = host(dir.path());
+        host.enrich("docs", &mut document).unwrap();
+        assert_eq!(document.title, "GUIDE");
+        assert_eq!(document.metadata.tags, vec!["reviewed"]);
+        assert_eq!(document.metadata.custom["format"], "markdown");
+        assert_eq!(document.metadata.custom["words"], "3");
+        assert_eq!(document.metadata.custom["collection"], "docs");
+        assert!(!document.metadata.custom.contains_key("draft"));
+
+        // Collections without a script are left alone
+        let before = document.metadata.custom.clone();
+        host.enrich("notes", &mut document).unwrap();
+        assert_eq!(document.metadata.custom, before);
+    }
+
+    #[test]
+    fn test_score_adjustment_and_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        write_script(
+            dir.path(),
+            "docs",
+            ScriptHook::Score,
+            r#"if title.contains("faq") { score * 2.0 } else { score / 2 }"#,
+        );
+        write_script(dir.path(), "loops", ScriptHook::Score, "loop { }");
+
+        let host = host(dir.path());
+        let mut results = vec![
+            result("guide", "docs", 0.8),
+            result("faq", "docs", 0.6),
+            result("endless", "loops", 0.7),
+            result("other", "notes", 0.5),
+        ];
+        assert!(host.adjust_scores("install", &mut results));
+
+        let scores: Vec<(&str, f32)> = results
+            .iter()
+            .map(|result| (result.title.as_str(), result.final_score.value()))
+            .collect();
+        assert_eq!(
+            scores,
+            vec![
+                ("faq", 1.0),
+                ("endless", 0.7),
+                ("other", 0.5),
+                ("guide", 0.4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scripts_reload_when_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        write_script(dir.path(), "docs", ScriptHook::Score, "0.1");
+        let host = host(dir.path());
+        let score = |host: &ScriptHost| {
+            let mut results = vec![result("guide", "docs", 0.5)];
+            host.adjust_scores("q", &mut results);
+            results[0].final_score.value()
+        };
+        assert_eq!(score(&host), 0.1);
+
+        // Pretend the cached copy is due for a check
+        let backdate = |host: &ScriptHost| {
+            for cached in host.scripts.write().unwrap().values_mut() {
+                cached.checked_at = Instant::now() - RELOAD_CHECK_INTERVAL;
+                cached.modified = Some(SystemTime::UNIX_EPOCH);
+            }
+        };
+
+        write_script(dir.path(), "docs", ScriptHook::Score, "score = 0.2;");
+        backdate(&host);
+        assert_eq!(score(&host), 0.2);
+
+        // A broken edit keeps the previous version
+        write_script(dir.path(), "docs", ScriptHook::Score, "score = ");
+        backdate(&host);
+        assert_eq!(score(&host), 0.2);
+
+        std::fs::remove_file(dir.path().join("docs").join("score.rhai")).unwrap();
+        backdate(&host);
+        assert_eq!(score(&host), 0.5);
+
+        // Collection names cannot reach outside the directory
+        assert!(host.script("../docs", ScriptHook::Score).is_none());
+    }
+}
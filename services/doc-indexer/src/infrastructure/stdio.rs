@@ -8,7 +8,7 @@ use tracing::{error, info, warn};
 
 use crate::infrastructure::{
     api::http::handlers::AppState,
-    api::jsonrpc::{handlers::route_method, JsonRpcError, JsonRpcRequest, JsonRpcResponse},
+    api::jsonrpc::{handlers::route_method, JsonRpcRequest, JsonRpcResponse},
 };
 
 /// Stdio JSON-RPC server
@@ -28,11 +28,11 @@ impl StdioServer {
         info!("Starting stdio JSON-RPC server");
 
         let stdin = io::stdin();
-        let reader = BufReader::new(stdin);
+        let mut reader = BufReader::new(stdin);
 
-        for line in reader.lines() {
-            match line {
-                Ok(line) => {
+        loop {
+            match read_frame(&mut reader) {
+                Ok(Some(line)) => {
                     if line.trim().is_empty() {
                         continue;
                     }
@@ -40,6 +40,7 @@ impl StdioServer {
                     let response = self.handle_line(&line).await;
                     self.write_response(&response)?;
                 }
+                Ok(None) => break,
                 Err(e) => {
                     error!("Error reading from stdin: {}", e);
                     break;
@@ -62,15 +63,16 @@ impl StdioServer {
         let stdin_tx = tx.clone();
         tokio::spawn(async move {
             let stdin = io::stdin();
-            let reader = BufReader::new(stdin);
+            let mut reader = BufReader::new(stdin);
 
-            for line in reader.lines() {
-                match line {
-                    Ok(line) => {
+            loop {
+                match read_frame(&mut reader) {
+                    Ok(Some(line)) => {
                         if !line.trim().is_empty() && stdin_tx.send(line).await.is_err() {
                             break;
                         }
                     }
+                    Ok(None) => break,
                     Err(e) => {
                         error!("Error reading from stdin: {}", e);
                         break;
@@ -94,7 +96,7 @@ impl StdioServer {
 
     /// Handle a single line of input (JSON-RPC request)
     async fn handle_line(&self, line: &str) -> JsonRpcResponse {
-        match serde_json::from_str::<JsonRpcRequest>(line) {
+        match JsonRpcRequest::parse(line) {
             Ok(request) => {
                 info!("Processing JSON-RPC request: {}", request.method);
 
@@ -102,8 +104,8 @@ impl StdioServer {
                 route_method(&request.method, request.params, request.id, &self.app_state).await
             }
             Err(e) => {
-                warn!("Invalid JSON-RPC request: {}", e);
-                JsonRpcResponse::error(None, JsonRpcError::parse_error())
+                warn!("Invalid JSON-RPC request: {}", e.message);
+                JsonRpcResponse::error(None, e)
             }
         }
     }
@@ -137,20 +139,19 @@ impl StdioBatchProcessor {
         info!("Starting stdio batch processing");
 
         let stdin = io::stdin();
-        let reader = BufReader::new(stdin);
+        let mut reader = BufReader::new(stdin);
         let mut requests = Vec::new();
 
         // Read all requests from stdin
-        for line in reader.lines() {
-            let line = line?;
+        while let Some(line) = read_frame(&mut reader)? {
             if line.trim().is_empty() {
                 break; // Empty line signals end of batch
             }
 
-            match serde_json::from_str::<JsonRpcRequest>(&line) {
+            match JsonRpcRequest::parse(&line) {
                 Ok(request) => requests.push(request),
                 Err(e) => {
-                    warn!("Skipping invalid JSON-RPC request: {}", e);
+                    warn!("Skipping invalid JSON-RPC request: {}", e.message);
                 }
             }
         }
@@ -177,6 +178,31 @@ impl StdioBatchProcessor {
     }
 }
 
+/// Read one newline-delimited frame from the transport
+///
+/// Returns `None` at end of input. A trailing `\r` is stripped so CRLF
+/// clients work, a final frame without a newline is still returned, and
+/// invalid UTF-8 is replaced rather than treated as a fatal read error so a
+/// single malformed frame only fails its own request.
+pub fn read_frame<R: BufRead>(reader: &mut R) -> io::Result<Option<String>> {
+    let mut buf = Vec::new();
+    if reader.read_until(b'\n', &mut buf)? == 0 {
+        return Ok(None);
+    }
+
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+        if buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+    }
+
+    Ok(Some(match String::from_utf8(buf) {
+        Ok(line) => line,
+        Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+    }))
+}
+
 /// Utility functions for stdio transport
 pub mod utils {
     /// Check if we should use stdio mode based on command line arguments
@@ -202,3 +228,65 @@ pub mod utils {
         println!("then send an empty line to signal end of batch.");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::io::Cursor;
+
+    fn frames(input: &[u8]) -> Vec<String> {
+        let mut reader = Cursor::new(input);
+        let mut frames = Vec::new();
+        while let Some(frame) = read_frame(&mut reader).unwrap() {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn test_read_frame_seed_streams() {
+        let frames_crlf = frames(include_bytes!(
+            "../../fuzz/corpus/stdio_framing/crlf_and_blank_lines"
+        ));
+        assert_eq!(frames_crlf.len(), 4);
+        assert!(frames_crlf[1].is_empty());
+        assert!(frames_crlf.iter().all(|frame| !frame.ends_with('\r')));
+        assert!(JsonRpcRequest::parse(&frames_crlf[3]).is_ok());
+
+        let frames_utf8 = frames(include_bytes!(
+            "../../fuzz/corpus/stdio_framing/invalid_utf8"
+        ));
+        assert_eq!(frames_utf8.len(), 2);
+        assert!(frames_utf8[0].contains('\u{FFFD}'));
+        assert!(JsonRpcRequest::parse(&frames_utf8[1]).is_ok());
+
+        let frames_truncated = frames(include_bytes!(
+            "../../fuzz/corpus/stdio_framing/truncated_frame"
+        ));
+        assert_eq!(frames_truncated.len(), 1);
+        assert_eq!(
+            JsonRpcRequest::parse(&frames_truncated[0])
+                .unwrap_err()
+                .code,
+            crate::infrastructure::api::jsonrpc::error_codes::PARSE_ERROR
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn read_frame_never_fails_on_arbitrary_bytes(input in prop::collection::vec(any::<u8>(), 0..512)) {
+            for frame in frames(&input) {
+                prop_assert!(!frame.contains('\n'));
+                let _ = JsonRpcRequest::parse(&frame);
+            }
+        }
+
+        #[test]
+        fn read_frame_round_trips_lines(lines in prop::collection::vec("[^\r\n]*", 1..8), crlf: bool) {
+            let separator = if crlf { "\r\n" } else { "\n" };
+            let input: String = lines.iter().map(|line| format!("{}{}", line, separator)).collect();
+            prop_assert_eq!(frames(input.as_bytes()), lines);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+/// Webhook delivery
+///
+/// Posts event payloads to subscriber URLs, JSON unless the subscriber asked
+/// for something else. Each request carries the
+/// event name, a delivery id and, when a secret is known, an HMAC-SHA256
+/// signature of `"{timestamp}.{body}"` so receivers can check that the
+/// payload came from this service and is not a replay. Failed deliveries are
+/// retried with exponential backoff; client errors other than 408 and 429
+/// are final, since sending the same payload again won't change the answer.
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use zero_latency_core::{Result, ZeroLatencyError};
+
+use crate::config::WebhookConfig;
+
+/// Header with the event name, e.g. `index.completed`
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+
+/// Header with the delivery id, the same on every attempt of a delivery
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+/// Header with the Unix timestamp covered by the signature
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+
+/// Header with the payload signature, `sha256=<hex>`
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Content type of payloads that aren't rendered through a template
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
+const HMAC_BLOCK_SIZE: usize = 64;
+
+/// HMAC-SHA256 of `message` under `key` (RFC 2104)
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; HMAC_BLOCK_SIZE];
+    if key.len() > HMAC_BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|byte| byte ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|byte| byte ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// Signature header value for a payload sent at `timestamp`
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut message = format!("{}.", timestamp).into_bytes();
+    message.extend_from_slice(body);
+    let digest = hmac_sha256(secret.as_bytes(), &message);
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("sha256={}", hex)
+}
+
+/// Result of delivering one payload
+#[derive(Debug, Clone)]
+pub struct DeliveryOutcome {
+    pub delivered: bool,
+    pub attempts: u32,
+    /// Status of the last response, if any arrived
+    pub status_code: Option<u16>,
+    /// Why the last attempt failed
+    pub error: Option<String>,
+}
+
+/// Sends webhook payloads with retries
+pub struct WebhookDispatcher {
+    client: reqwest::Client,
+    config: WebhookConfig,
+}
+
+impl WebhookDispatcher {
+    pub fn new(config: WebhookConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .map_err(|e| {
+                ZeroLatencyError::configuration(format!("Failed to create HTTP client: {}", e))
+            })?;
+        Ok(Self { client, config })
+    }
+
+    /// Wait after failed attempt number `attempt` (1-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(
+            self.config
+                .initial_backoff_ms
+                .saturating_mul(factor)
+                .min(self.config.max_backoff_ms),
+        )
+    }
+
+    /// Post `body` to `url`, retrying until it is accepted or the attempts
+    /// run out; the payload is signed when a secret is given
+    pub async fn deliver(
+        &self,
+        url: &str,
+        secret: Option<&str>,
+        event: &str,
+        delivery_id: &str,
+        content_type: &str,
+        body: &[u8],
+    ) -> DeliveryOutcome {
+        let mut outcome = DeliveryOutcome {
+            delivered: false,
+            attempts: 0,
+            status_code: None,
+            error: None,
+        };
+
+        while outcome.attempts < self.config.max_attempts.max(1) {
+            if outcome.attempts > 0 {
+                tokio::time::sleep(self.backoff(outcome.attempts)).await;
+            }
+            outcome.attempts += 1;
+
+            let timestamp = chrono::Utc::now().timestamp();
+            let mut request = self
+                .client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .header(EVENT_HEADER, event)
+                .header(DELIVERY_HEADER, delivery_id)
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .body(body.to_vec());
+            if let Some(secret) = secret {
+                request = request.header(SIGNATURE_HEADER, sign_payload(secret, timestamp, body));
+            }
+
+            match request.send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    outcome.status_code = Some(status.as_u16());
+                    if status.is_success() {
+                        outcome.delivered = true;
+                        outcome.error = None;
+                        return outcome;
+                    }
+                    outcome.error = Some(format!("{} returned {}", url, status));
+                    let retryable = status.is_server_error()
+                        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                        || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+                    if !retryable {
+                        return outcome;
+                    }
+                }
+                Err(e) => {
+                    outcome.status_code = None;
+                    outcome.error = Some(format!("Request to {} failed: {}", url, e));
+                }
+            }
+            tracing::debug!(
+                "Webhook delivery {} attempt {} failed: {}",
+                delivery_id,
+                outcome.attempts,
+                outcome.error.as_deref().unwrap_or_default()
+            );
+        }
+
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::post, Router};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    fn dispatcher(max_attempts: u32) -> WebhookDispatcher {
+        WebhookDispatcher::new(WebhookConfig {
+            max_attempts,
+            initial_backoff_ms: 10,
+            max_backoff_ms: 40,
+            timeout_ms: 5_000,
+        })
+        .unwrap()
+    }
+
+    /// Serve `statuses` in turn, repeating the last one
+    async fn receiver(statuses: Vec<StatusCode>) -> (String, Arc<AtomicU32>) {
+        let hits = Arc::new(AtomicU32::new(0));
+        let counter = hits.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move || {
+                let hit = counter.fetch_add(1, Ordering::SeqCst) as usize;
+                let status = statuses[hit.min(statuses.len() - 1)];
+                async move { status }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, hits)
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231_vectors() {
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than a block are hashed first
+        assert_eq!(
+            hex(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_signature_covers_timestamp() {
+        let signature = sign_payload("secret", 1_700_000_000, b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_ne!(signature, sign_payload("secret", 1_700_000_001, b"{}"));
+        assert_ne!(signature, sign_payload("other", 1_700_000_000, b"{}"));
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let dispatcher = dispatcher(5);
+        assert_eq!(dispatcher.backoff(1), Duration::from_millis(10));
+        assert_eq!(dispatcher.backoff(2), Duration::from_millis(20));
+        assert_eq!(dispatcher.backoff(4), Duration::from_millis(40));
+        assert_eq!(dispatcher.backoff(60), Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn test_deliver_retries_server_errors() {
+        let (url, hits) = receiver(vec![
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::OK,
+        ])
+        .await;
+
+        let outcome = dispatcher(5)
+            .deliver(&url, Some("secret"), "ping", "d1", JSON_CONTENT_TYPE, b"{}")
+            .await;
+        assert!(outcome.delivered);
+        assert_eq!(outcome.attempts, 3);
+        assert_eq!(outcome.status_code, Some(200));
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_gives_up() {
+        // Client errors are final
+        let (url, hits) = receiver(vec![StatusCode::GONE]).await;
+        let outcome = dispatcher(5)
+            .deliver(&url, None, "ping", "d1", JSON_CONTENT_TYPE, b"{}")
+            .await;
+        assert!(!outcome.delivered);
+        assert_eq!(outcome.attempts, 1);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        // Server errors are retried until the attempts run out
+        let (url, hits) = receiver(vec![StatusCode::INTERNAL_SERVER_ERROR]).await;
+        let outcome = dispatcher(3)
+            .deliver(&url, None, "ping", "d2", JSON_CONTENT_TYPE, b"{}")
+            .await;
+        assert!(!outcome.delivered);
+        assert_eq!(outcome.attempts, 3);
+        assert_eq!(outcome.status_code, Some(500));
+        assert!(outcome.error.is_some());
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+}
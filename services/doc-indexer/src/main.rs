@@ -1,5 +1,5 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 /// Doc-Indexer service main entry point
 ///
 /// This service provides document indexing and search capabilities using
@@ -16,6 +16,11 @@ use application::ServiceContainer;
 use config::Config;
 use infrastructure::HttpServer;
 
+// jemalloc keeps the allocator statistics served under /debug/pprof/heap
+#[cfg(feature = "profiling")]
+#[global_allocator]
+static ALLOCATOR: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
 #[derive(Parser)]
 #[command(name = "doc-indexer")]
 #[command(about = "Document indexing and search service")]
@@ -36,6 +41,10 @@ struct Cli {
     #[arg(long)]
     structured_logs: bool,
 
+    /// Share of HTTP requests whose debug and trace logs are kept (0 to 1)
+    #[arg(long, default_value_t = 1.0)]
+    log_sample_rate: f64,
+
     /// Print example environment variables
     #[arg(long)]
     env_example: bool,
@@ -55,6 +64,39 @@ struct Cli {
     /// Path to documentation directory to index (overrides config)
     #[arg(long)]
     docs_path: Option<std::path::PathBuf>,
+
+    /// Run without cloud adapters, falling back to local ones (overrides config)
+    #[arg(long)]
+    offline: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Write an archive of all server state for disaster recovery
+    Backup {
+        /// Directory the archive is written to
+        #[arg(long)]
+        out: std::path::PathBuf,
+    },
+    /// Restore server state from a backup archive
+    Restore {
+        /// Archive written by `backup`
+        archive: std::path::PathBuf,
+
+        /// Replace existing state and skip the index settings check
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the version of this binary
+    About {
+        /// Print the features, backends, models and crate versions built in,
+        /// and the external services the binary may contact, as JSON
+        #[arg(long)]
+        build_info: bool,
+    },
 }
 
 #[tokio::main]
@@ -73,8 +115,19 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Build information doesn't depend on configuration
+    if let Some(Command::About { build_info }) = &cli.command {
+        if *build_info {
+            let info = application::services::build_info::BuildInfo::current();
+            println!("{}", serde_json::to_string_pretty(&info)?);
+        } else {
+            println!("doc-indexer {}", env!("CARGO_PKG_VERSION"));
+        }
+        return Ok(());
+    }
+
     // Initialize logging
-    init_logging(&cli.log_level, cli.structured_logs);
+    init_logging(&cli.log_level, cli.structured_logs, cli.log_sample_rate);
 
     info!("Starting doc-indexer service");
 
@@ -89,7 +142,15 @@ async fn main() -> Result<()> {
     }
 
     // Convert to service-specific config (compatibility layer)
-    let config = Config::from_app_config(app_config);
+    let mut config = Config::from_app_config(app_config);
+    if cli.offline {
+        config.offline.enabled = true;
+    }
+
+    // Backup and restore work on the data directory without starting the service
+    if let Some(command) = &cli.command {
+        return run_command(command, &config);
+    }
 
     // Create service container with all dependencies
     let container = match ServiceContainer::new(config.clone()).await {
@@ -151,35 +212,74 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Run a maintenance subcommand
+fn run_command(command: &Command, config: &Config) -> Result<()> {
+    use infrastructure::operations::backup;
+
+    match command {
+        Command::Backup { out } => {
+            let (archive, manifest) = backup::backup(config, out)?;
+            println!(
+                "Backed up {} files to {}",
+                manifest.files.len(),
+                archive.display()
+            );
+        }
+        Command::Restore { archive, force } => {
+            let manifest = backup::restore(config, archive, *force)?;
+            println!(
+                "Restored {} files from a backup taken {} by doc-indexer {}",
+                manifest.files.len(),
+                manifest.created_at.to_rfc3339(),
+                manifest.service_version
+            );
+        }
+        Command::About { .. } => unreachable!("handled before configuration is loaded"),
+    }
+    Ok(())
+}
+
 /// Initialize logging and tracing based on configuration
-fn init_logging(log_level: &str, structured: bool) {
-    use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+fn init_logging(log_level: &str, structured: bool, sample_rate: f64) {
+    use infrastructure::operations::log_level::RequestSampling;
+    use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer};
 
     let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new(format!("doc_indexer={},zero_latency_core=info,zero_latency_search=info,zero_latency_vector=info,zero_latency_observability=info", log_level)));
+        .unwrap_or_else(|_| EnvFilter::new(infrastructure::operations::log_level::directives(log_level)));
+
+    // Reloadable, so the admin API can change the filter at runtime
+    let (env_filter, handle) = reload::Layer::new(env_filter);
+    infrastructure::operations::log_level::register(handle);
+    infrastructure::operations::log_level::set_request_sample_rate(sample_rate);
 
     // Create base subscriber
     let subscriber = tracing_subscriber::registry().with(env_filter);
 
     if structured {
-        // Structured JSON logging for production
+        // Structured JSON logging for production; the field layout is
+        // documented in docs/services/doc-indexer/LOGGING.md
         subscriber
             .with(
                 fmt::layer()
                     .json()
                     .with_target(false)
-                    .with_current_span(false),
+                    .with_current_span(true)
+                    .with_span_list(false)
+                    .with_filter(RequestSampling),
             )
             .init();
     } else {
         // Human-readable logging for development
-        subscriber.with(fmt::layer().with_target(false)).init();
+        subscriber
+            .with(fmt::layer().with_target(false).with_filter(RequestSampling))
+            .init();
     }
 
     tracing::info!(
-        "Tracing initialized with level: {}, structured: {}",
+        "Tracing initialized with level: {}, structured: {}, request sample rate: {}",
         log_level,
-        structured
+        structured,
+        sample_rate
     );
 }
 
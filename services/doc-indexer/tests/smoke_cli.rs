@@ -1,74 +1,41 @@
-mod test_utils;
+use serde_json::Value;
+use std::process::Command;
+use zero_latency_test_utils::TestServer;
 
-use std::process::{Command, Stdio};
-use std::time::Duration;
-use tokio::runtime::Runtime;
-use reqwest::Client;
-use serde_json::json;
-use test_utils::{TestUtils, TestAssertions};
+const FIXTURE_DOC: &str = include_str!("fixtures/smoke_test_doc.txt");
+const COLLECTION: &str = "smoke_test";
 
-#[test]
-fn smoke_test_advanced_query_enhancement_and_ranking() {
-    let test_utils = TestUtils::new();
-    let config = test_utils.create_test_config();
-    
-    let mut child = Command::new(&config.binary_path)
-        .args([
-            "--docs-path",
-            &config.docs_path,
-            "--port",
-            &config.port.to_string(),
-            "--log-level",
-            "info",
-        ])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to start doc-indexer CLI for advanced search test");
-
-    let rt = Runtime::new().unwrap();
-    let client = Client::new();
-
-    // Wait for the server to be ready using test utilities
-    test_utils.wait_for_health_blocking(config.port, 30)
-        .expect("doc-indexer did not become healthy in time");
-
-    // Index the test document using unique collection name
-    let index_body = json!({
-        "path": config.docs_path,
-        "collection": config.collection_name
-    });
-    let resp = rt
-        .block_on(client.post(&config.index_url()).json(&index_body).send())
-        .expect("Failed to POST to /api/index");
-    TestAssertions::assert_success_response(&resp, "Indexing");
-
-    std::thread::sleep(Duration::from_secs(2));
-
-    // Search using a synonym or technical term (e.g., 'find' instead of 'search', 'api' for technical expansion)
-    let search_body = json!({
-        "query": "find Zero-Latency",
-        "collection": config.collection_name,
-        "limit": 5
-    });
-    let resp = rt
-        .block_on(client.post(&config.search_url()).json(&search_body).send())
-        .expect("Failed to POST to /api/search");
-    TestAssertions::assert_success_response(&resp, "Search");
-    
-    let json: serde_json::Value = rt
-        .block_on(resp.json())
-        .expect("Failed to parse search response");
-    TestAssertions::assert_search_results_not_empty(&json, "Advanced query search");
-
-    let results = json
-        .get("results")
-        .and_then(|r| r.as_array())
-        .expect("No results array in search response");
-    let found = results.iter().any(|res| {
-        res.get("content").map_or(false, |c| {
-            c.as_str()
-                .map_or(false, |s| s.contains("Zero-Latency doc-indexer smoke test"))
+async fn indexed_server() -> TestServer {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let indexed = server.index(COLLECTION).await.expect("Indexing failed");
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+
+    server
+}
+
+fn results(response: &Value) -> &Vec<Value> {
+    response["results"]
+        .as_array()
+        .expect("Search response should have a 'results' array")
+}
+
+#[tokio::test]
+async fn smoke_test_advanced_query_enhancement_and_ranking() {
+    let server = indexed_server().await;
+
+    // Search using a synonym or technical term (e.g., 'find' instead of 'search')
+    let response = server
+        .search("find Zero-Latency", COLLECTION)
+        .await
+        .unwrap();
+    let found = results(&response).iter().any(|result| {
+        result["content"].as_str().map_or(false, |content| {
+            content.contains("Zero-Latency doc-indexer smoke test")
         })
     });
     assert!(
@@ -76,132 +43,3337 @@ fn smoke_test_advanced_query_enhancement_and_ranking() {
         "Should find test document in search results for 'find Zero-Latency'"
     );
 
-    // Clean up
-    let _ = child.kill();
-    let _ = child.wait();
+    server.shutdown().await;
 }
 
-#[test]
-fn smoke_test_end_to_end_index_and_search() {
-    let test_utils = TestUtils::new();
-    let config = test_utils.create_test_config();
-    
-    let mut child = Command::new(&config.binary_path)
-        .args([
-            "--docs-path",
-            &config.docs_path,
-            "--port",
-            &config.port.to_string(),
-            "--log-level",
-            "info",
-        ])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to start doc-indexer CLI for end-to-end test");
-
-    // Use a tokio runtime for async HTTP
-    let rt = Runtime::new().unwrap();
-    let client = Client::new();
-
-    // Wait for the server to be ready using test utilities
-    test_utils.wait_for_health_blocking(config.port, 30)
-        .expect("doc-indexer did not become healthy in time");
-
-    // Index documents using unique collection name
-    let index_body = json!({
-        "path": config.docs_path,
-        "collection": config.collection_name
+#[tokio::test]
+async fn smoke_test_end_to_end_index_and_search() {
+    let server = indexed_server().await;
+
+    let response = server
+        .search("Zero-Latency doc-indexer smoke test", COLLECTION)
+        .await
+        .unwrap();
+    assert!(
+        !results(&response).is_empty(),
+        "End-to-end search returned no results"
+    );
+
+    let response = server
+        .search("document indexing service", COLLECTION)
+        .await
+        .unwrap();
+    assert!(
+        !results(&response).is_empty(),
+        "Semantic search returned no results"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_server_runs_with_docs_path() {
+    let server = TestServer::start().await.unwrap();
+
+    server
+        .get_json("/health")
+        .await
+        .expect("Health check failed");
+
+    // The index endpoint accepts an empty docs directory
+    let indexed = server
+        .index(COLLECTION)
+        .await
+        .expect("Index endpoint failed");
+    assert_eq!(indexed["documents_processed"], 0);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_request_id_propagation() {
+    let server = TestServer::start().await.unwrap();
+
+    // A caller-supplied ID is echoed and reported in error bodies
+    let response = server
+        .client()
+        .post(server.url("/api/collections"))
+        .header("x-request-id", "smoke-request-1")
+        .json(&serde_json::json!({ "name": "", "vector_size": 384 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["x-request-id"], "smoke-request-1");
+    assert_eq!(response.status(), 400);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["trace_id"], "smoke-request-1", "Body: {}", body);
+
+    // Requests without one are assigned an ID
+    let response = server.client().get(server.url("/health")).send().await.unwrap();
+    assert!(response.headers().contains_key("x-request-id"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_collection_search_defaults() {
+    let server = TestServer::start().await.unwrap();
+
+    server
+        .post_json(
+            "/api/collections",
+            &serde_json::json!({
+                "name": "tuned",
+                "vector_size": 384,
+                "search_defaults": { "limit": 3, "query_expansion": false, "rerank_depth": 5 },
+            }),
+        )
+        .await
+        .expect("Creating collection with search defaults failed");
+
+    // The collection's defaults apply when the request doesn't set them
+    let response = server
+        .post_json(
+            "/api/search",
+            &serde_json::json!({
+                "query": "smoke test",
+                "filters": { "collection_name": "tuned" },
+            }),
+        )
+        .await
+        .unwrap();
+    let metadata = &response["search_metadata"];
+    assert_eq!(metadata["effective_settings"]["limit"], 3, "{}", metadata);
+    assert_eq!(metadata["effective_settings"]["query_expansion"], false);
+    assert_eq!(metadata["effective_settings"]["rerank_depth"], 5);
+    assert_eq!(metadata["query_enhancement_applied"], false);
+
+    // Values in the request take precedence
+    let response = server.search("smoke test", "tuned").await.unwrap();
+    let settings = &response["search_metadata"]["effective_settings"];
+    assert_eq!(settings["limit"], 10);
+    assert_eq!(settings["query_expansion"], false);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_collection_distance_metric() {
+    let server = TestServer::start().await.unwrap();
+
+    let response = server
+        .client()
+        .post(server.url("/api/collections"))
+        .json(&serde_json::json!({
+            "name": "manhattan",
+            "vector_size": 384,
+            "distance_metric": "manhattan",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let created = server
+        .post_json(
+            "/api/collections",
+            &serde_json::json!({ "name": "inner", "vector_size": 384, "distance_metric": "dot" }),
+        )
+        .await
+        .expect("Creating a dot product collection failed");
+    assert_eq!(created["collection"]["distance_metric"], "dot", "{}", created);
+
+    // Searches of the collection are scored by its metric
+    let response = server.search("smoke test", "inner").await.unwrap();
+    assert!(results(&response).is_empty(), "{}", response);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_named_vector_spaces() {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .with_config(|config| config.embedding.title_vectors = true)
+        .start()
+        .await
+        .unwrap();
+    let indexed = server.index(COLLECTION).await.unwrap();
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+
+    // Title and content vectors are searched together and fused
+    let response = server
+        .post_json(
+            "/api/search",
+            &serde_json::json!({
+                "query": "Zero-Latency doc-indexer smoke test",
+                "filters": { "collection_name": COLLECTION },
+                "vector_spaces": [
+                    { "name": "content", "weight": 2.0 },
+                    { "name": "title" },
+                ],
+            }),
+        )
+        .await
+        .expect("Searching named vector spaces failed");
+    assert!(!results(&response).is_empty(), "{}", response);
+
+    let response = server
+        .client()
+        .post(server.url("/api/search"))
+        .json(&serde_json::json!({
+            "query": "smoke test",
+            "vector_spaces": [{ "name": "title" }, { "name": "title" }],
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_late_interaction() {
+    let enabled = cfg!(feature = "late-interaction");
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .with_config(move |config| config.late_interaction.enabled = enabled)
+        .start()
+        .await
+        .unwrap();
+    let indexed = server.index(COLLECTION).await.unwrap();
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+
+    let body = serde_json::json!({
+        "query": "advanced search pipeline integration",
+        "filters": { "collection_name": COLLECTION },
+        "late_interaction": true,
     });
-    let resp = rt
-        .block_on(client.post(&config.index_url()).json(&index_body).send())
-        .expect("Failed to POST to /api/index");
-    TestAssertions::assert_success_response(&resp, "Indexing");
-
-    // Wait for indexing to complete
-    std::thread::sleep(Duration::from_secs(3));
-
-    // Search for indexed content
-    let search_body = json!({
-        "query": "Zero-Latency doc-indexer smoke test",
-        "collection": config.collection_name,
-        "limit": 10
+    if !enabled {
+        // Built without token vectors, the request cannot be honoured
+        let response = server
+            .client()
+            .post(server.url("/api/search"))
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+        server.shutdown().await;
+        return;
+    }
+
+    let response = server.post_json("/api/search", &body).await.unwrap();
+    let ranking_method = response["search_metadata"]["ranking_method"]
+        .as_str()
+        .unwrap();
+    assert!(ranking_method.contains("+late_interaction"), "{}", response);
+    let top = &results(&response)[0];
+    assert!(
+        top["from_signals"]["late_interaction_score"].is_number(),
+        "{}",
+        response
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_extractive_answer() {
+    async fn search(server: &TestServer, query: &str, extract_answer: bool) -> Value {
+        let body = serde_json::json!({
+            "query": query,
+            "extract_answer": extract_answer,
+            "filters": { "collection_name": COLLECTION },
+        });
+        server.post_json("/api/search", &body).await.unwrap()
+    }
+
+    let server = indexed_server().await;
+    let response = search(&server, "What is the test document for?", true).await;
+    let answer = &response["answer"];
+    assert_eq!(
+        answer["text"],
+        "This is a test document for Zero-Latency advanced search pipeline integration tests.",
+        "{}",
+        response
+    );
+    assert_eq!(answer["extractor"], "lexical");
+    let stages = response["search_metadata"]["stage_timings"]
+        .as_array()
+        .unwrap();
+    assert!(stages
+        .iter()
+        .any(|stage| stage["stage"] == "answer_extraction"));
+
+    // Off by default, and only questions get an answer
+    let response = search(&server, "What is the test document for?", false).await;
+    assert!(response.get("answer").is_none(), "{}", response);
+    let response = search(&server, "test document", true).await;
+    assert!(response.get("answer").is_none(), "{}", response);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_search_summary() {
+    let server = indexed_server().await;
+    let body = serde_json::json!({
+        "query": "What is the test document for?",
+        "max_bullets": 2,
+        "filters": { "collection_name": COLLECTION },
     });
-    let resp = rt
-        .block_on(client.post(&config.search_url()).json(&search_body).send())
-        .expect("Failed to POST to /api/search");
-    TestAssertions::assert_success_response(&resp, "Search");
-
-    let json: serde_json::Value = rt
-        .block_on(resp.json())
-        .expect("Failed to parse search response");
-    TestAssertions::assert_search_results_not_empty(&json, "End-to-end search");
-
-    // Test semantic search capability
-    let semantic_search_body = json!({
-        "query": "document indexing service",
-        "collection": config.collection_name,
-        "limit": 5
+    let response = server
+        .post_json("/api/search/summarize", &body)
+        .await
+        .unwrap();
+    assert_eq!(response["summarizer"], "extractive", "{}", response);
+
+    let sources = response["sources"].as_array().unwrap();
+    let bullets = response["bullets"].as_array().unwrap();
+    assert!(!sources.is_empty() && sources.len() <= 5, "{}", response);
+    assert!(!bullets.is_empty() && bullets.len() <= 2, "{}", response);
+    for bullet in bullets {
+        for citation in bullet["citations"].as_array().unwrap() {
+            let id = citation.as_u64().unwrap();
+            assert!(
+                sources.iter().any(|source| source["id"] == id),
+                "{}",
+                response
+            );
+        }
+    }
+    let summary = response["summary"].as_str().unwrap();
+    assert!(
+        summary.starts_with("- ") && summary.contains("[1]"),
+        "{}",
+        response
+    );
+
+    let response = server
+        .client()
+        .post(server.url("/api/search/summarize"))
+        .json(&serde_json::json!({ "query": "smoke test", "max_bullets": 0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_search_session() {
+    async fn search(server: &TestServer, session: &str, query: &str) -> Value {
+        let body = serde_json::json!({
+            "query": query,
+            "filters": { "collection_name": COLLECTION },
+        });
+        let path = format!("/api/sessions/{}/search", session);
+        server.post_json(&path, &body).await.unwrap()
+    }
+
+    let server = indexed_server().await;
+    let session = server
+        .post_json("/api/sessions", &serde_json::json!({}))
+        .await
+        .unwrap();
+    let id = session["session_id"].as_str().unwrap().to_string();
+
+    let first = search(&server, &id, "advanced search pipeline").await;
+    assert!(first.get("contextualized_query").is_none(), "{}", first);
+    assert!(!results(&first).is_empty(), "{}", first);
+
+    // A follow-up is searched with the topic of the turn before it
+    let follow_up = search(&server, &id, "how is it indexed?").await;
+    assert_eq!(
+        follow_up["contextualized_query"], "how is it indexed? advanced search pipeline",
+        "{}",
+        follow_up
+    );
+
+    // Selected documents rank higher in later searches
+    let document_id = results(&first)[0]["document_id"].as_str().unwrap();
+    let selections = format!("/api/sessions/{}/selections", id);
+    let session = server
+        .post_json(
+            &selections,
+            &serde_json::json!({ "document_id": document_id }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(session["selected_documents"][0], document_id, "{}", session);
+    let again = search(&server, &id, "advanced search pipeline").await;
+    assert!(again["boosted_results"].as_u64().unwrap() >= 1, "{}", again);
+
+    let session = server
+        .get_json(&format!("/api/sessions/{}", id))
+        .await
+        .unwrap();
+    assert_eq!(session["turns"].as_array().unwrap().len(), 3, "{}", session);
+
+    let url = server.url(&format!("/api/sessions/{}", id));
+    let response = server.client().delete(&url).send().await.unwrap();
+    assert_eq!(response.status(), 204);
+    let response = server.client().get(&url).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_webhooks() {
+    use axum::{body::Bytes, http::HeaderMap, routing::post, Router};
+    use doc_indexer::infrastructure::webhooks::sign_payload;
+
+    // Receiver recording every delivery it gets
+    let (sender, mut deliveries) = tokio::sync::mpsc::unbounded_channel();
+    let receiver = Router::new().route(
+        "/hook",
+        post(move |headers: HeaderMap, body: Bytes| {
+            let sender = sender.clone();
+            async move {
+                sender.send((headers, body)).unwrap();
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let hook_url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, receiver).await.unwrap() });
+
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let secret = "smoke-test-webhook-secret";
+    let webhook = server
+        .post_json(
+            "/api/webhooks",
+            &serde_json::json!({
+                "url": hook_url,
+                "events": ["index.completed"],
+                "secret": secret,
+            }),
+        )
+        .await
+        .unwrap();
+    let id = webhook["id"].as_str().unwrap().to_string();
+    assert_eq!(webhook["secret"], secret, "{}", webhook);
+
+    let indexed = server.index(COLLECTION).await.expect("Indexing failed");
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+
+    // Only the subscribed event arrives, signed with the webhook's secret
+    let (headers, body) =
+        tokio::time::timeout(std::time::Duration::from_secs(10), deliveries.recv())
+            .await
+            .expect("No webhook delivery")
+            .unwrap();
+    let header = |name: &str| headers[name].to_str().unwrap().to_string();
+    assert_eq!(header("x-webhook-event"), "index.completed");
+    let timestamp: i64 = header("x-webhook-timestamp").parse().unwrap();
+    assert_eq!(
+        header("x-webhook-signature"),
+        sign_payload(secret, timestamp, &body)
+    );
+    let payload: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(payload["id"], header("x-webhook-delivery"));
+    assert_eq!(payload["data"]["collection"], COLLECTION, "{}", payload);
+    assert!(payload["data"]["documents_processed"].as_u64().unwrap() >= 1);
+
+    // The outcome shows up among the webhook's recent deliveries
+    let path = format!("/api/webhooks/{}", id);
+    let mut webhook = server.get_json(&path).await.unwrap();
+    for _ in 0..50 {
+        if !webhook["recent_deliveries"].as_array().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        webhook = server.get_json(&path).await.unwrap();
+    }
+    assert_eq!(
+        webhook["recent_deliveries"][0]["delivered"], true,
+        "{}",
+        webhook
+    );
+    assert!(webhook.get("secret").is_none(), "{}", webhook);
+
+    // A paused webhook receives nothing
+    let url = server.url(&path);
+    let paused: Value = server
+        .client()
+        .patch(&url)
+        .json(&serde_json::json!({ "active": false }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(paused["active"], false, "{}", paused);
+    server.index(COLLECTION).await.expect("Indexing failed");
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(deliveries.try_recv().is_err());
+
+    let response = server.client().delete(&url).send().await.unwrap();
+    assert_eq!(response.status(), 204);
+    let response = server.client().get(&url).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+    let response = server
+        .client()
+        .post(server.url("/api/webhooks"))
+        .json(&serde_json::json!({ "url": "ftp://example.com/hook" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_templated_webhooks() {
+    use axum::{body::Bytes, http::HeaderMap, routing::post, Router};
+
+    let (sender, mut deliveries) = tokio::sync::mpsc::unbounded_channel();
+    let receiver = Router::new().route(
+        "/hook",
+        post(move |headers: HeaderMap, body: Bytes| {
+            let sender = sender.clone();
+            async move {
+                sender.send((headers, body)).unwrap();
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let hook_url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, receiver).await.unwrap() });
+
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    // Templates that don't compile are rejected up front
+    let response = server
+        .client()
+        .post(server.url("/api/webhooks"))
+        .json(&serde_json::json!({ "url": hook_url, "template": "{% if %}" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server
+        .post_json(
+            "/api/webhooks",
+            &serde_json::json!({
+                "url": hook_url,
+                "events": ["index.completed"],
+                "template": "**{{ event }}**: {{ data.documents_processed }} documents in `{{ data.collection }}`",
+                "content_type": "text/markdown",
+            }),
+        )
+        .await
+        .unwrap();
+    server.index(COLLECTION).await.expect("Indexing failed");
+
+    let (headers, body) =
+        tokio::time::timeout(std::time::Duration::from_secs(10), deliveries.recv())
+            .await
+            .expect("No webhook delivery")
+            .unwrap();
+    assert_eq!(headers["content-type"], "text/markdown");
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.starts_with("**index.completed**: "), "{}", body);
+    assert!(
+        body.ends_with(&format!("documents in `{}`", COLLECTION)),
+        "{}",
+        body
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_change_events() {
+    let server = indexed_server().await;
+
+    let kinds = |page: &Value| -> Vec<String> {
+        page["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|event| event["kind"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let page = server
+        .get_json(&format!("/api/events?collection={}", COLLECTION))
+        .await
+        .unwrap();
+    // Indexing into a new collection creates it first
+    assert_eq!(
+        kinds(&page),
+        vec!["collection_created", "document_added"],
+        "{}",
+        page
+    );
+    let added = &page["events"][1];
+    assert!(added["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("smoke_test_doc.txt"));
+    let cursor = page["latest_sequence"].as_u64().unwrap();
+
+    // A changed file is reported as updated under the same path
+    let doc = server.docs_dir().join("smoke_test_doc.txt");
+    std::fs::write(&doc, format!("{}\nA new closing paragraph.\n", FIXTURE_DOC)).unwrap();
+    server.index(COLLECTION).await.expect("Reindexing failed");
+    server
+        .post_json(
+            "/api/collections",
+            &serde_json::json!({ "name": "scratch", "vector_size": 384 }),
+        )
+        .await
+        .unwrap();
+    let response = server
+        .client()
+        .delete(server.url("/api/collections/scratch"))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let page = server
+        .get_json(&format!("/api/events?since={}", cursor))
+        .await
+        .unwrap();
+    assert_eq!(
+        kinds(&page),
+        vec![
+            "document_updated",
+            "collection_created",
+            "collection_deleted"
+        ],
+        "{}",
+        page
+    );
+    assert_eq!(page["events"][0]["path"], added["path"]);
+    assert_eq!(page["events"][0]["sequence"].as_u64(), Some(cursor + 1));
+    assert_eq!(page["next_cursor"], page["latest_sequence"]);
+    assert_eq!(page["truncated"], false);
+
+    // Reading from the latest cursor returns nothing new
+    let latest = page["next_cursor"].as_u64().unwrap();
+    let page = server
+        .get_json(&format!("/api/events?since={}&limit=1", latest))
+        .await
+        .unwrap();
+    assert!(kinds(&page).is_empty());
+    assert_eq!(page["next_cursor"].as_u64(), Some(latest));
+
+    let response = server
+        .client()
+        .get(server.url("/api/events?limit=0"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_empty_results_invalidated_by_indexing() {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    // Nothing is indexed yet; the repeat is answered from the negative cache
+    for _ in 0..2 {
+        let response = server
+            .search("Zero-Latency doc-indexer smoke test", COLLECTION)
+            .await
+            .unwrap();
+        assert!(results(&response).is_empty(), "{}", response);
+    }
+
+    // Indexing invalidates the remembered miss
+    server.index(COLLECTION).await.expect("Indexing failed");
+    let response = server
+        .search("Zero-Latency doc-indexer smoke test", COLLECTION)
+        .await
+        .unwrap();
+    assert!(!results(&response).is_empty(), "{}", response);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_collection_cache_purge() {
+    let server = indexed_server().await;
+    for _ in 0..2 {
+        server
+            .search("Zero-Latency doc-indexer smoke test", COLLECTION)
+            .await
+            .unwrap();
+    }
+
+    let path = format!("/api/collections/{}/cache", COLLECTION);
+    let stats = server.get_json(&path).await.unwrap();
+    let embeddings = stats["caches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|cache| cache["name"] == "query_embeddings")
+        .unwrap_or_else(|| panic!("{}", stats));
+    assert_eq!(embeddings["entries"], 1, "{}", stats);
+    assert_eq!(embeddings["hits"], 1, "{}", stats);
+
+    // Other collections hold nothing of this one
+    let other = server
+        .get_json("/api/collections/other/cache")
+        .await
+        .unwrap();
+    assert_eq!(other["caches"][0]["entries"], 0, "{}", other);
+
+    let purge = server
+        .post_json(&format!("{}/purge", path), &serde_json::json!({}))
+        .await
+        .unwrap();
+    assert_eq!(purge["collection"], COLLECTION);
+    assert_eq!(purge["total_entries"], 1, "{}", purge);
+    let stats = server.get_json(&path).await.unwrap();
+    assert_eq!(stats["caches"][0]["entries"], 0, "{}", stats);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_digest() {
+    let server = indexed_server().await;
+
+    let digest = server.get_json("/api/digest?hours=1").await.unwrap();
+    assert_eq!(digest["created_collections"][0], COLLECTION, "{}", digest);
+    let collection = &digest["collections"][0];
+    assert_eq!(collection["collection"], COLLECTION);
+    assert_eq!(collection["added"], 1);
+    assert_eq!(collection["documents"][0]["change"], "added");
+    assert!(collection["documents"][0]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("smoke_test_doc.txt"));
+
+    // Without SMTP the digest only goes to webhooks
+    let delivery = server
+        .post_json("/api/digest/send?hours=1", &serde_json::json!({}))
+        .await
+        .unwrap();
+    assert_eq!(delivery["emailed"], serde_json::json!([]));
+    assert_eq!(delivery["digest"]["total_changes"], digest["total_changes"]);
+
+    let response = server
+        .client()
+        .get(server.url("/api/digest?hours=0"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_context_window() {
+    // 120 sentences make three chunks; the closing sentence is in the last
+    let handbook = (0..120)
+        .map(|i| match i {
+            90 => "The gearbox chapter continues here.".to_string(),
+            119 => "The zebra crossing closes the handbook.".to_string(),
+            _ => format!("Widget handbook sentence number {}.", i),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let server = TestServer::builder()
+        .with_document("handbook.txt", &handbook)
+        .start()
+        .await
+        .unwrap();
+    let indexed = server.index(COLLECTION).await.unwrap();
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+
+    let response = server
+        .post_json(
+            "/api/search",
+            &serde_json::json!({
+                "query": "zebra crossing closes the handbook",
+                "filters": { "collection_name": COLLECTION },
+                "context_window": 1,
+            }),
+        )
+        .await
+        .unwrap();
+    let hit = results(&response)
+        .iter()
+        .find(|result| result["custom_metadata"]["chunk_index"] == "2")
+        .unwrap_or_else(|| panic!("{}", response));
+    // The chunk before the hit is stitched in front of it
+    let content = hit["content"].as_str().unwrap();
+    let (before, after) = content.split_once("\n\n[...]\n\n").unwrap();
+    assert!(before.contains("gearbox"), "{}", content);
+    assert!(after.contains("zebra"), "{}", content);
+    assert_eq!(hit["custom_metadata"]["context_chunk_start"], "1");
+    assert_eq!(hit["custom_metadata"]["context_chunk_end"], "2");
+    assert_eq!(
+        response["search_metadata"]["effective_settings"]["context_window"],
+        1
+    );
+
+    let response = server
+        .client()
+        .post(server.url("/api/search"))
+        .json(&serde_json::json!({ "query": "zebra", "context_window": 50 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_document_outline() {
+    let guide = "Read this first.\n\n\
+        # Guide\n\n\
+        ## Install\n\n\
+        Run the turbine installer.\n\n\
+        ## Configure\n\n\
+        Edit the settings file.\n\n\
+        # Reference\n\n\
+        All the flags.\n";
+    let server = TestServer::builder()
+        .with_document("guide.md", guide)
+        .start()
+        .await
+        .unwrap();
+    let indexed = server.index(COLLECTION).await.unwrap();
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+
+    let response = server
+        .post_json(
+            "/api/search",
+            &serde_json::json!({
+                "query": "turbine installer",
+                "filters": { "collection_name": COLLECTION },
+            }),
+        )
+        .await
+        .unwrap();
+    let hit = results(&response)
+        .first()
+        .unwrap_or_else(|| panic!("{}", response));
+    let document_id = hit["custom_metadata"]["parent_document_id"].as_str().unwrap();
+
+    let path = format!("/api/documents/{}/outline?collection={}", document_id, COLLECTION);
+    let outline = server.get_json(&path).await.unwrap();
+    assert_eq!(outline["chunk_count"], 4, "{}", outline);
+    assert_eq!(outline["chunk_ids"].as_array().unwrap().len(), 1);
+    let sections = outline["sections"].as_array().unwrap();
+    assert_eq!(sections.len(), 2, "{}", outline);
+    assert_eq!(sections[0]["heading"], "Guide");
+    let subsections = sections[0]["sections"].as_array().unwrap();
+    assert_eq!(subsections[0]["heading"], "Install");
+    assert_eq!(subsections[0]["level"], 2);
+    assert_eq!(subsections[1]["heading"], "Configure");
+    assert_eq!(sections[1]["heading"], "Reference");
+
+    // A section's chunks come with their content
+    let section = server
+        .get_json(&format!("{}&section=Guide%20%3E%20Configure", path))
+        .await
+        .unwrap();
+    let chunks = section["chunks"].as_array().unwrap();
+    assert_eq!(chunks.len(), 1, "{}", section);
+    assert_eq!(chunks[0]["chunk_id"], subsections[1]["chunk_ids"][0]);
+    assert!(chunks[0]["content"]
+        .as_str()
+        .unwrap()
+        .contains("settings file"));
+
+    let missing = server
+        .client()
+        .get(server.url(&format!("{}&section=Appendix", path)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), 404);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_result_source_locations() {
+    let guide = "# Guide\n\n\
+        Read this first.\n\n\
+        ```\n\
+        setup --all\n\
+        ```\n\n\
+        ## Configure retries\n\n\
+        The pelican backoff doubles\n\
+        after every failed attempt.\n";
+    let server = TestServer::builder()
+        .with_document("guide.md", guide)
+        .start()
+        .await
+        .unwrap();
+    let indexed = server.index(COLLECTION).await.unwrap();
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+
+    let response = server
+        .post_json(
+            "/api/search",
+            &serde_json::json!({
+                "query": "pelican backoff",
+                "filters": { "collection_name": COLLECTION },
+            }),
+        )
+        .await
+        .unwrap();
+    let hit = results(&response)
+        .iter()
+        .find(|result| result["content"].as_str().unwrap().contains("pelican"))
+        .unwrap_or_else(|| panic!("{}", response));
+    // The dropped code block does not shift the lines
+    assert_eq!(hit["lines"], serde_json::json!({ "start": 9, "end": 12 }));
+    assert_eq!(hit["anchor"], "configure-retries");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_partial_reindex() {
+    // 120 sentences make three chunks of 50, 50 and 20 sentences
+    let sentences = |last: &str| -> String {
+        (0..120)
+            .map(|i| {
+                if i == 119 {
+                    last.to_string()
+                } else {
+                    format!("Widget handbook sentence number {}.", i)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let server = TestServer::builder()
+        .with_document("handbook.txt", sentences("The original closing sentence."))
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+    let indexed = server.index(COLLECTION).await.expect("Indexing failed");
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+
+    let churn = |job: &Value| -> Vec<(u64, u64, u64)> {
+        job["chunk_churn"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|file| {
+                (
+                    file["embedded"].as_u64().unwrap(),
+                    file["reused"].as_u64().unwrap(),
+                    file["removed"].as_u64().unwrap(),
+                )
+            })
+            .collect()
+    };
+    let job = server
+        .get_json(&format!("/api/jobs/{}", indexed["job_id"].as_str().unwrap()))
+        .await
+        .unwrap();
+    // A file indexed for the first time has no churn to report
+    assert!(churn(&job).is_empty(), "{}", job);
+
+    // Only the chunk holding the edited sentence is embedded again
+    std::fs::write(
+        server.docs_dir().join("handbook.txt"),
+        sentences("A rewritten closing sentence."),
+    )
+    .unwrap();
+    let reindexed = server.index(COLLECTION).await.expect("Reindexing failed");
+    let job = server
+        .get_json(&format!("/api/jobs/{}", reindexed["job_id"].as_str().unwrap()))
+        .await
+        .unwrap();
+    assert_eq!(churn(&job), vec![(1, 2, 1)], "{}", job);
+    assert!(job["chunk_churn"][0]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("handbook.txt"));
+
+    let response = server
+        .search("rewritten closing sentence", COLLECTION)
+        .await
+        .unwrap();
+    let contents: Vec<&str> = results(&response)
+        .iter()
+        .map(|result| result["content"].as_str().unwrap_or_default())
+        .collect();
+    assert_eq!(contents.len(), 3, "{}", response);
+    assert!(contents.iter().any(|c| c.contains("rewritten closing")));
+    assert!(!contents.iter().any(|c| c.contains("original closing")));
+
+    // A forced run embeds every chunk again
+    let forced = server
+        .post_json(
+            "/api/index",
+            &serde_json::json!({
+                "path": server.docs_dir().to_string_lossy(),
+                "collection": COLLECTION,
+                "force": true,
+            }),
+        )
+        .await
+        .unwrap();
+    let job = server
+        .get_json(&format!("/api/jobs/{}", forced["job_id"].as_str().unwrap()))
+        .await
+        .unwrap();
+    assert_eq!(churn(&job), vec![(3, 0, 3)], "{}", job);
+
+    server.shutdown().await;
+}
+
+#[cfg(feature = "wasm-plugins")]
+#[tokio::test]
+async fn smoke_test_wasm_content_handler_plugin() {
+    // Content handler for .proto files; wasmtime also accepts the text format
+    let reply = r#"{"content":"Zebra gateway protocol definition"}"#;
+    let info = r#"{"name":"proto","version":"0.1.0","capabilities":["content_handler"],"extensions":["proto"]}"#;
+    let module = format!(
+        r#"(module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "{info_data}")
+            (data (i32.const 512) "{reply_data}")
+            (func (export "zl_abi_version") (result i32) (i32.const 1))
+            (func (export "zl_alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "zl_info") (param i32 i32) (result i64) (i64.const {info_len}))
+            (func (export "zl_process") (param i32 i32) (result i64)
+                (i64.or (i64.shl (i64.const 512) (i64.const 32)) (i64.const {reply_len}))))"#,
+        info_data = info.replace('"', "\\\""),
+        info_len = info.len(),
+        reply_data = reply.replace('"', "\\\""),
+        reply_len = reply.len(),
+    );
+    let plugins = tempfile::tempdir().unwrap();
+    std::fs::write(plugins.path().join("proto.wasm"), module).unwrap();
+    let directory = plugins.path().to_path_buf();
+
+    let server = TestServer::builder()
+        .with_document("gateway.proto", "syntax = \"proto3\";")
+        .with_config(move |config| config.plugins.directory = Some(directory))
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let listed = server.get_json("/api/plugins").await.unwrap();
+    assert_eq!(listed[0]["name"], "proto", "{}", listed);
+    assert_eq!(listed[0]["runtime"], "wasm");
+
+    let indexed = server.index(COLLECTION).await.expect("Indexing failed");
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+    let response = server
+        .search("zebra gateway protocol", COLLECTION)
+        .await
+        .unwrap();
+    let processed = results(&response).iter().any(|result| {
+        let content = result["content"].as_str().unwrap_or_default();
+        content.starts_with("Zebra gateway protocol")
+    });
+    assert!(processed, "{}", response);
+
+    server.shutdown().await;
+}
+
+#[cfg(feature = "web-ui")]
+#[tokio::test]
+async fn smoke_test_web_ui() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    // "/ui" redirects to the page, whose assets are served next to it
+    let page = server.client().get(server.url("/ui")).send().await.unwrap();
+    assert_eq!(page.status(), 200);
+    assert_eq!(page.url().path(), "/ui/");
+    assert!(page.text().await.unwrap().contains("app.js"));
+
+    let script = server.client().get(server.url("/ui/app.js")).send().await.unwrap();
+    assert_eq!(script.status(), 200);
+    assert!(script.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/javascript"));
+
+    let missing = server.client().get(server.url("/ui/nope.js")).send().await.unwrap();
+    assert_eq!(missing.status(), 404);
+
+    server.shutdown().await;
+}
+
+#[cfg(feature = "profiling")]
+#[tokio::test]
+async fn smoke_test_profiling_endpoints() {
+    let server = TestServer::builder()
+        .with_config(|config| {
+            config.admin.token = Some("admin-secret".to_string());
+            config.admin.read_token = Some("read-secret".to_string());
+            config.profiling.enabled = true;
+            config.profiling.max_seconds = 5;
+        })
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+    let get = |path: &str, token: &str| {
+        server
+            .client()
+            .get(server.url(path))
+            .bearer_auth(token)
+            .send()
+    };
+
+    // Sampling costs CPU, so reading admin statistics isn't enough
+    let refused = get("/debug/pprof/heap", "read-secret").await.unwrap();
+    assert_eq!(refused.status(), 403);
+
+    let heap: Value = get("/debug/pprof/heap", "admin-secret")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(heap["allocated"].as_u64().is_some(), "{}", heap);
+
+    let too_long = get("/debug/pprof/profile?seconds=60", "admin-secret")
+        .await
+        .unwrap();
+    assert_eq!(too_long.status(), 400);
+
+    // An idle test server may not be sampled at all, which a pprof profile
+    // can still describe
+    let profile = get("/debug/pprof/profile?seconds=1&format=pprof", "admin-secret")
+        .await
+        .unwrap();
+    assert_eq!(profile.status(), 200);
+    assert_eq!(profile.headers()["content-type"], "application/octet-stream");
+    assert!(!profile.bytes().await.unwrap().is_empty());
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_federated_search() {
+    let server = indexed_server().await;
+    let indexed = server
+        .index("smoke_archive")
+        .await
+        .expect("Indexing failed");
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+
+    let response = server
+        .post_json(
+            "/api/search",
+            &serde_json::json!({
+                "query": "Zero-Latency doc-indexer smoke test",
+                "filters": { "collection_names": ["smoke_*"] },
+            }),
+        )
+        .await
+        .unwrap();
+
+    // Both collections contribute, each result tagged with its source
+    let mut collections: Vec<&str> = results(&response)
+        .iter()
+        .filter_map(|result| result["collection"].as_str())
+        .collect();
+    collections.sort();
+    collections.dedup();
+    assert_eq!(
+        collections,
+        vec!["smoke_archive", "smoke_test"],
+        "{}",
+        response
+    );
+    assert_eq!(response["search_metadata"]["ranking_method"], "federated");
+
+    // Unmatched patterns are rejected
+    let response = server
+        .client()
+        .post(server.url("/api/search"))
+        .json(&serde_json::json!({
+            "query": "smoke test",
+            "filters": { "collection_names": ["missing_*"] },
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_search_exclusions() {
+    let server = indexed_server().await;
+    let query = "Zero-Latency doc-indexer smoke test";
+
+    let response = server.search(query, COLLECTION).await.unwrap();
+    let document_id = results(&response)[0]["document_id"].clone();
+
+    // Documents listed in exclude_documents are dropped
+    let response = server
+        .post_json(
+            "/api/search",
+            &serde_json::json!({
+                "query": query,
+                "filters": { "collection_name": COLLECTION },
+                "exclude_documents": [document_id],
+            }),
+        )
+        .await
+        .unwrap();
+    assert!(
+        results(&response)
+            .iter()
+            .all(|result| result["document_id"] != document_id),
+        "{}",
+        response
+    );
+
+    // Exclusion operators in the query are applied and stripped
+    let response = server
+        .search(
+            &format!("{} NOT collection:{}", query, COLLECTION),
+            COLLECTION,
+        )
+        .await
+        .unwrap();
+    assert!(results(&response).is_empty(), "{}", response);
+    assert_eq!(response["search_metadata"]["query"]["raw"], query);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_phrase_query() {
+    let server = indexed_server().await;
+
+    let response = server
+        .search("\"doc-indexer smoke test\" Zero-Latency", COLLECTION)
+        .await
+        .unwrap();
+    assert!(!results(&response).is_empty(), "{}", response);
+    let parsed = &response["search_metadata"]["debug_info"]["parsed_query"];
+    assert_eq!(
+        parsed["phrases"],
+        serde_json::json!(["doc-indexer smoke test"]),
+        "{}",
+        response
+    );
+    assert_eq!(parsed["terms"], serde_json::json!(["Zero-Latency"]));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_metadata_pattern_filters() {
+    async fn search(server: &TestServer, patterns: Value) -> Value {
+        let request = serde_json::json!({
+            "query": "Zero-Latency doc-indexer smoke test",
+            "filters": { "collection_name": COLLECTION, "metadata_patterns": patterns },
+        });
+        server.post_json("/api/search", &request).await.unwrap()
+    }
+    let server = indexed_server().await;
+
+    // Path globs match relative to any directory
+    let response = search(&server, serde_json::json!({ "path": "**/smoke_*.txt" })).await;
+    assert!(!results(&response).is_empty(), "{}", response);
+
+    let response = search(&server, serde_json::json!({ "path": "guides/**" })).await;
+    assert!(results(&response).is_empty(), "{}", response);
+
+    // Malformed regexes are rejected rather than ignored
+    let response = server
+        .client()
+        .post(server.url("/api/search"))
+        .json(&serde_json::json!({
+            "query": "smoke test",
+            "filters": { "metadata_patterns": { "path": "re:(docs" } },
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_collection_warmup() {
+    let server = indexed_server().await;
+    server
+        .search("smoke test", COLLECTION)
+        .await
+        .expect("Search failed");
+    let popular = server
+        .get_json("/api/analytics/popular-queries")
+        .await
+        .unwrap();
+
+    let report = server
+        .post_json("/api/collections/smoke_test/warm", &serde_json::json!({}))
+        .await
+        .expect("Warm-up failed");
+    assert_eq!(report["collection"], COLLECTION, "{}", report);
+    assert!(report["vectors_loaded"].as_u64().unwrap() > 0, "{}", report);
+    assert_eq!(report["queries_primed"], 1, "{}", report);
+
+    // Replayed queries don't count as searches
+    let after = server
+        .get_json("/api/analytics/popular-queries")
+        .await
+        .unwrap();
+    assert_eq!(popular, after);
+
+    let response = server
+        .client()
+        .post(server.url("/api/collections/missing/warm"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_editor_lookup() {
+    let server = indexed_server().await;
+    let popular = server
+        .get_json("/api/analytics/popular-queries")
+        .await
+        .unwrap();
+
+    let request = serde_json::json!({
+        "symbol": "smoke test",
+        "collection": COLLECTION,
+        "context": { "file_path": "src/smoke_test_doc.rs" }
     });
-    let resp = rt
-        .block_on(client.post(&config.search_url()).json(&semantic_search_body).send())
-        .expect("Failed to POST semantic search");
-    TestAssertions::assert_success_response(&resp, "Semantic search");
+    let first = server.post_json("/api/lookup", &request).await.unwrap();
+    let lookups = first["results"].as_array().unwrap();
+    assert!(!lookups.is_empty() && lookups.len() <= 3, "{}", first);
+    assert!(
+        !lookups[0]["snippet"].as_str().unwrap().is_empty(),
+        "{}",
+        first
+    );
+    assert_eq!(first["cached"], false, "{}", first);
 
-    let semantic_json: serde_json::Value = rt
-        .block_on(resp.json())
-        .expect("Failed to parse semantic search response");
-    TestAssertions::assert_search_results_not_empty(&semantic_json, "Semantic search");
+    let second = server.post_json("/api/lookup", &request).await.unwrap();
+    assert_eq!(second["cached"], true, "{}", second);
+    assert_eq!(second["results"], first["results"]);
 
-    // Clean up
-    let _ = child.kill();
-    let _ = child.wait();
+    // Lookups don't count as searches
+    let after = server
+        .get_json("/api/analytics/popular-queries")
+        .await
+        .unwrap();
+    assert_eq!(popular, after);
+
+    let response = server
+        .client()
+        .post(server.url("/api/lookup"))
+        .json(&serde_json::json!({ "symbol": "  ", "collection": COLLECTION }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_embedding_migration() {
+    let server = indexed_server().await;
+    let migrate = server.url("/api/collections/smoke_test/migrate-embeddings");
+
+    // The stored vectors already match the model
+    let response = server.client().post(&migrate).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+
+    let response = server
+        .client()
+        .post(format!("{}?force=true&sample_size=3", migrate))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+    let started: Value = response.json().await.unwrap();
+    assert!(started["vectors"].as_u64().unwrap() > 0, "{}", started);
+
+    let job_path = format!("/api/jobs/{}", started["job_id"].as_str().unwrap());
+    let mut job = server.get_json(&job_path).await.unwrap();
+    for _ in 0..100 {
+        if job["status"] != "running" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        job = server.get_json(&job_path).await.unwrap();
+    }
+    assert_eq!(job["status"], "completed", "{}", job);
+    assert_eq!(job["kind"], "migrate", "{}", job);
+    assert_eq!(
+        job["migration"]["vectors_migrated"], started["vectors"],
+        "{}",
+        job
+    );
+    let validation = &job["migration"]["validation"];
+    assert_eq!(validation["matched"], validation["sampled"], "{}", job);
+
+    // Searches now read the shadow collection but report the public name
+    let response = server
+        .search("smoke test", COLLECTION)
+        .await
+        .expect("Search failed");
+    assert!(!results(&response).is_empty());
+    assert!(results(&response)
+        .iter()
+        .all(|result| result["collection"] == COLLECTION));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_vector_staleness() {
+    let server = indexed_server().await;
+
+    // Freshly indexed vectors all come from the current model
+    let report = server
+        .get_json("/api/collections/smoke_test/staleness")
+        .await
+        .unwrap();
+    assert!(report["total_vectors"].as_u64().unwrap() > 0, "{}", report);
+    assert_eq!(report["stale_vectors"], 0, "{}", report);
+    let models = report["models"].as_array().unwrap();
+    assert_eq!(models.len(), 1, "{}", report);
+    assert_eq!(models[0]["model"], report["current_model"]);
+    assert_eq!(models[0]["vectors"], report["total_vectors"]);
+
+    // So there is nothing to re-embed
+    let response = server
+        .client()
+        .post(server.url("/api/collections/smoke_test/reembed-stale"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let response = server
+        .client()
+        .get(server.url("/api/collections/missing/staleness"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_chunk_sample() {
+    let server = indexed_server().await;
+
+    let sample = server
+        .get_json("/api/collections/smoke_test/sample?n=5&neighbors=2&seed=7")
+        .await
+        .unwrap();
+    let total = sample["total_chunks"].as_u64().unwrap();
+    let chunks = sample["chunks"].as_array().unwrap();
+    assert!(total > 0, "{}", sample);
+    assert_eq!(chunks.len() as u64, total.min(5));
+    assert_eq!(sample["seed"], 7);
+    let chunk = &chunks[0];
+    assert!(!chunk["content"].as_str().unwrap().is_empty(), "{}", sample);
+    assert!(chunk["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("smoke_test_doc.txt"));
+    assert!(chunk["neighbors"].as_array().unwrap().len() as u64 <= 2);
+
+    // The same seed draws the same chunks
+    let again = server
+        .get_json("/api/collections/smoke_test/sample?n=5&neighbors=2&seed=7")
+        .await
+        .unwrap();
+    assert_eq!(again["chunks"], sample["chunks"]);
+
+    for path in [
+        "/api/collections/smoke_test/sample?n=0",
+        "/api/collections/smoke_test/sample?neighbors=100",
+    ] {
+        let response = server.client().get(server.url(path)).send().await.unwrap();
+        assert_eq!(response.status(), 400, "{}", path);
+    }
+    let response = server
+        .client()
+        .get(server.url("/api/collections/missing/sample"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_collection_projection() {
+    let server = indexed_server().await;
+    let projection = server.url("/api/collections/smoke_test/projection");
+
+    // Nothing is exported before a projection has run
+    let response = server.client().get(&projection).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+
+    let response = server
+        .client()
+        .post(format!("{}?method=pca&seed=3", projection))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+    let started: Value = response.json().await.unwrap();
+    assert_eq!(started["method"], "pca", "{}", started);
+
+    let job_path = format!("/api/jobs/{}", started["job_id"].as_str().unwrap());
+    let mut job = server.get_json(&job_path).await.unwrap();
+    for _ in 0..100 {
+        if job["status"] != "running" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        job = server.get_json(&job_path).await.unwrap();
+    }
+    assert_eq!(job["status"], "completed", "{}", job);
+    assert_eq!(job["kind"], "project");
+    assert_eq!(job["projection"]["points"], started["points"]);
+
+    let exported = server
+        .get_json("/api/collections/smoke_test/projection")
+        .await
+        .unwrap();
+    assert_eq!(exported["seed"], 3);
+    let points = exported["points"].as_array().unwrap();
+    assert_eq!(points.len() as u64, started["points"].as_u64().unwrap());
+    assert!(points[0]["x"].is_number() && points[0]["y"].is_number());
+
+    let csv = server
+        .client()
+        .get(format!("{}?format=csv", projection))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(csv.starts_with("x,y,chunk_id,"), "{}", csv);
+    assert_eq!(csv.lines().count(), points.len() + 1);
+
+    let response = server
+        .client()
+        .post(format!("{}?method=tsne", projection))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_duplicate_report() {
+    let server = TestServer::builder()
+        .with_document("repo/README.md", FIXTURE_DOC)
+        .with_document("site/getting-started.md", FIXTURE_DOC)
+        .with_document("site/other.md", "An unrelated page about configuring webhooks.")
+        .with_config(|config| config.dedup.canonical_sources = vec!["site/**".to_string()])
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+    let indexed = server.index(COLLECTION).await.expect("Indexing failed");
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+
+    let report = server
+        .get_json("/api/collections/smoke_test/duplicates")
+        .await
+        .unwrap();
+    assert_eq!(report["documents_scanned"], 3, "{}", report);
+    assert_eq!(report["redundant_documents"], 1, "{}", report);
+    let cluster = &report["clusters"][0];
+    assert_eq!(cluster["reason"], "canonical_source");
+    assert!(cluster["canonical"]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("site/getting-started.md"));
+    assert!(cluster["duplicates"][0]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("repo/README.md"));
+
+    let response = server
+        .client()
+        .get(server.url("/api/collections/smoke_test/duplicates?threshold=1.5"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_collection_health() {
+    let server = indexed_server().await;
+
+    let health = server
+        .get_json("/api/collections/smoke_test/health")
+        .await
+        .unwrap();
+    let score = health["score"].as_u64().unwrap();
+    assert!(score <= 100, "{}", health);
+    assert!(health["signals"]["total_vectors"].as_u64().unwrap() > 0);
+    assert_eq!(health["signals"]["orphaned_chunks"], 0, "{}", health);
+    assert_eq!(health["signals"]["stale_vectors"], 0, "{}", health);
+    for recommendation in health["recommendations"].as_array().unwrap() {
+        assert!(!recommendation["action"].as_str().unwrap().is_empty());
+    }
+
+    // Collection stats carry the same report
+    let stats = server
+        .get_json("/api/collections/smoke_test/stats")
+        .await
+        .unwrap();
+    assert_eq!(stats["health"]["score"], score, "{}", stats);
+
+    let response = server
+        .client()
+        .get(server.url("/api/collections/missing/health"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_elasticsearch_compat() {
+    let server = indexed_server().await;
+
+    let info = server.get_json("/api/es").await.unwrap();
+    assert!(info["version"]["number"].as_str().unwrap().starts_with('7'));
+
+    let response = server
+        .post_json(
+            "/api/es/smoke_test/_search",
+            &serde_json::json!({
+                "size": 3,
+                "query": { "bool": { "must": { "match": { "content": "smoke test" } } } }
+            }),
+        )
+        .await
+        .unwrap();
+    let hits = response["hits"]["hits"].as_array().unwrap();
+    assert!(!hits.is_empty(), "{}", response);
+    assert!(hits.len() <= 3);
+    assert_eq!(hits[0]["_index"], "smoke_test");
+    assert!(!hits[0]["_source"]["content"].as_str().unwrap().is_empty());
+
+    let body = concat!(
+        "{\"index\":\"smoke_test\"}\n",
+        "{\"size\":1,\"query\":{\"match\":{\"content\":\"Zero-Latency\"}}}\n",
+        "{\"index\":\"smoke_test\"}\n",
+        "{\"aggs\":{}}\n",
+    );
+    let response = server
+        .client()
+        .post(server.url("/api/es/_msearch"))
+        .header("content-type", "application/x-ndjson")
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let msearch: Value = response.json().await.unwrap();
+    let responses = msearch["responses"].as_array().unwrap();
+    assert_eq!(responses[0]["status"], 200, "{}", msearch);
+    assert_eq!(responses[0]["hits"]["hits"].as_array().unwrap().len(), 1);
+    assert_eq!(responses[1]["status"], 400, "{}", msearch);
+
+    let response = server
+        .client()
+        .post(server.url("/api/es/smoke_test/_search"))
+        .json(&serde_json::json!({ "query": { "fuzzy": { "title": "smok" } } }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_raw_document() {
+    let server = indexed_server().await;
+    let url = server.url("/api/collections/smoke_test/raw/smoke_test_doc.txt");
+
+    let response = server.client().get(&url).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/plain; charset=utf-8"
+    );
+    assert_eq!(response.headers()["accept-ranges"], "bytes");
+    assert_eq!(response.headers()["x-index-stale"], "false");
+    assert_eq!(
+        response.headers()["content-disposition"],
+        "inline; filename=\"smoke_test_doc.txt\""
+    );
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+    assert_eq!(response.text().await.unwrap(), FIXTURE_DOC);
+
+    let response = server
+        .client()
+        .get(&url)
+        .header("range", "bytes=0-4")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 206);
+    assert_eq!(
+        response.headers()["content-range"],
+        format!("bytes 0-4/{}", FIXTURE_DOC.len()).as_str()
+    );
+    assert_eq!(response.text().await.unwrap(), &FIXTURE_DOC[..5]);
+
+    let response = server
+        .client()
+        .get(&url)
+        .header("range", format!("bytes={}-", FIXTURE_DOC.len()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 416);
+
+    let response = server
+        .client()
+        .get(&url)
+        .header("if-none-match", &etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 304);
+
+    for path in ["missing.txt", "../smoke_test_doc.txt"] {
+        let response = server
+            .client()
+            .get(server.url(&format!("/api/collections/smoke_test/raw/{}", path)))
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_client_error(), "{}", path);
+    }
+
+    // Files changed since indexing are hashed again and flagged
+    let file = server.docs_dir().join("smoke_test_doc.txt");
+    let changed = format!("{}\nAppended after indexing.", FIXTURE_DOC);
+    std::fs::write(&file, &changed).unwrap();
+    let response = server.client().get(&url).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-index-stale"], "true");
+    assert_ne!(response.headers()["etag"], etag.as_str());
+    assert_eq!(response.text().await.unwrap(), changed);
+
+    // Files over the maximum document size are refused
+    let max = server.container().config().service.max_document_size;
+    std::fs::write(&file, vec![b'a'; max + 1]).unwrap();
+    let response = server.client().get(&url).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_metadata_query() {
+    let server = indexed_server().await;
+
+    let result = server
+        .post_json(
+            "/api/collections/smoke_test/documents/query",
+            &serde_json::json!({
+                "query": "SELECT path, chunks WHERE path LIKE '%smoke_test_doc.txt' ORDER BY path LIMIT 5"
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result["columns"], serde_json::json!(["path", "chunks"]));
+    assert_eq!(result["total_matched"], 1, "{}", result);
+    let row = &result["rows"][0];
+    assert!(row[0].as_str().unwrap().ends_with("smoke_test_doc.txt"));
+    assert!(row[1].as_str().unwrap().parse::<usize>().unwrap() > 0);
+
+    let none = server
+        .post_json(
+            "/api/collections/smoke_test/documents/query",
+            &serde_json::json!({ "query": "SELECT * WHERE tag = 'missing'" }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(none["total_matched"], 0, "{}", none);
+    assert_eq!(none["columns"].as_array().unwrap().len(), 9);
+
+    let response = server
+        .client()
+        .post(server.url("/api/collections/smoke_test/documents/query"))
+        .json(&serde_json::json!({ "query": "SELECT path WHERE" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let response = server
+        .client()
+        .post(server.url("/api/collections/missing/documents/query"))
+        .json(&serde_json::json!({ "query": "SELECT *" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_schema_validation() {
+    use doc_indexer::config::SchemaValidationMode;
+
+    let server = TestServer::builder()
+        .with_config(|config| config.service.schema_validation = SchemaValidationMode::Enforce)
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    // Responses that match the spec pass through
+    let health = server.get_json("/health").await.unwrap();
+    assert_eq!(health["status"], "healthy", "{}", health);
+    server.get_json("/api/jobs").await.unwrap();
+    server
+        .post_json("/api/lookup", &serde_json::json!({ "symbol": "smoke" }))
+        .await
+        .unwrap();
+    server
+        .post_json(
+            "/api/search/summarize",
+            &serde_json::json!({ "query": "smoke" }),
+        )
+        .await
+        .unwrap();
+    server
+        .post_json("/api/sessions", &serde_json::json!({}))
+        .await
+        .unwrap();
+    server
+        .post_json(
+            "/api/webhooks",
+            &serde_json::json!({ "url": "http://127.0.0.1:9/hook", "active": false }),
+        )
+        .await
+        .unwrap();
+
+    let response = server
+        .client()
+        .post(server.url("/api/search"))
+        .json(&serde_json::json!({ "query": "smoke test", "limit": "ten" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: Value = response.json().await.unwrap();
+    let message = body["error"]["message"].as_str().unwrap();
+    assert!(message.contains("/limit"), "{}", body);
+
+    server.shutdown().await;
+
+    // In log mode mismatches are only reported
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .with_config(|config| config.service.schema_validation = SchemaValidationMode::Log)
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let indexed = server.index(COLLECTION).await.expect("Indexing failed");
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+    let response = server
+        .search("smoke test", COLLECTION)
+        .await
+        .expect("Search failed");
+    assert!(!results(&response).is_empty());
+
+    server.shutdown().await;
+}
+
+#[test]
+fn smoke_test_cli_env_example() {
+    let output = Command::new(env!("CARGO_BIN_EXE_doc-indexer"))
+        .args(["--env-example"])
+        .output()
+        .expect("Failed to run doc-indexer CLI with --env-example");
+    assert!(
+        output.status.success(),
+        "doc-indexer CLI --env-example failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Match a variable that is always present in the output
+    assert!(
+        stdout.contains("DOC_INDEXER_LOG_LEVEL"),
+        "Expected env example output, got: {}",
+        stdout
+    );
 }
 
 #[test]
-fn smoke_test_cli_runs_with_docs_path() {
-    let test_utils = TestUtils::new();
-    let config = test_utils.create_test_config();
-
-    // Test that the CLI can start with custom docs path
-    let mut child = Command::new(&config.binary_path)
-        .args([
-            "--docs-path",
-            &config.docs_path,
-            "--port",
-            &config.port.to_string(),
-            "--log-level",
-            "debug",
-        ])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to start doc-indexer CLI with custom docs path");
-
-    // Wait for server to start
-    test_utils.wait_for_health_blocking(config.port, 30)
-        .expect("doc-indexer with custom docs path did not become healthy");
-
-    let rt = Runtime::new().unwrap();
-    let client = Client::new();
-
-    // Verify health endpoint responds
-    let resp = rt
-        .block_on(client.get(&config.health_url()).send())
-        .expect("Failed to GET health endpoint");
-    TestAssertions::assert_success_response(&resp, "Health check");
-
-    // Verify we can hit the index endpoint (even if no indexing occurs)
-    let index_body = json!({
-        "path": config.docs_path,
-        "collection": config.collection_name
+fn smoke_test_cli_stdio_help() {
+    let output = Command::new(env!("CARGO_BIN_EXE_doc-indexer"))
+        .args(["--stdio-help"])
+        .output()
+        .expect("Failed to run doc-indexer CLI with --stdio-help");
+    assert!(
+        output.status.success(),
+        "doc-indexer CLI --stdio-help failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.to_lowercase().contains("json-rpc"),
+        "Expected stdio help output, got: {}",
+        stdout
+    );
+}
+
+#[tokio::test]
+async fn smoke_test_collection_scripts() {
+    let scripts = tempfile::tempdir().unwrap();
+    let folder = scripts.path().join(COLLECTION);
+    std::fs::create_dir_all(&folder).unwrap();
+    std::fs::write(folder.join("enrich.rhai"), r#"metadata.team = "platform";"#).unwrap();
+    std::fs::write(
+        folder.join("score.rhai"),
+        r#"if metadata.team == "platform" { 0.42 } else { score }"#,
+    )
+    .unwrap();
+    let directory = scripts.path().to_path_buf();
+
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .with_config(move |config| config.scripts.directory = Some(directory))
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let indexed = server.index(COLLECTION).await.expect("Indexing failed");
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+    let response = server
+        .search("Zero-Latency doc-indexer smoke test", COLLECTION)
+        .await
+        .unwrap();
+
+    // The enrich script tagged the document and the score script read the tag
+    let result = &results(&response)[0];
+    let team = &result["custom_metadata"]["team"];
+    assert_eq!(team, "platform", "{}", response);
+    assert_eq!(result["final_score"], 0.42, "{}", response);
+    assert!(response["search_metadata"]["ranking_method"]
+        .as_str()
+        .unwrap_or_default()
+        .ends_with("+scripts"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_metadata_schema() {
+    let scripts = tempfile::tempdir().unwrap();
+    let folder = scripts.path().join(COLLECTION);
+    std::fs::create_dir_all(&folder).unwrap();
+    std::fs::write(
+        folder.join("enrich.rhai"),
+        r#"metadata.version = if path.ends_with("draft.txt") { "two" } else { "2" };"#,
+    )
+    .unwrap();
+    let directory = scripts.path().to_path_buf();
+
+    let server = TestServer::builder()
+        .with_document("release.txt", FIXTURE_DOC)
+        .with_document("draft.txt", "A draft of the next release notes.")
+        .with_config(move |config| config.scripts.directory = Some(directory))
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+    let schema_path = "/api/collections/smoke_test/schema";
+    let response = server
+        .client()
+        .get(server.url(schema_path))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    let schema = serde_json::json!({
+        "fields": [
+            { "name": "version", "type": "integer", "required": true },
+            { "name": "owner", "type": "string" }
+        ]
+    });
+    let response = server
+        .client()
+        .put(server.url(schema_path))
+        .json(&schema)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let stored = server.get_json(schema_path).await.unwrap();
+    assert_eq!(stored["fields"][1]["required"], false, "{}", stored);
+
+    // The draft's version isn't an integer, so only the release is indexed
+    let indexed = server.index(COLLECTION).await.expect("Indexing failed");
+    let errors = server
+        .get_json(&format!(
+            "/api/jobs/{}/errors",
+            indexed["job_id"].as_str().unwrap()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(errors["error_count"], 1, "{}", errors);
+    let diagnostic = &errors["diagnostics"][0];
+    assert!(diagnostic["path"].as_str().unwrap().ends_with("draft.txt"));
+    assert_eq!(diagnostic["stage"], "validate", "{}", errors);
+    assert_eq!(diagnostic["metadata_errors"][0]["field"], "version");
+
+    let response = server.search("release notes draft", COLLECTION).await.unwrap();
+    assert!(results(&response)
+        .iter()
+        .all(|result| result["custom_metadata"]["version"] == "2"));
+
+    // Duplicate fields are rejected; deleting the schema lifts the checks
+    let duplicate = serde_json::json!({
+        "fields": [
+            { "name": "version", "type": "integer" },
+            { "name": "version", "type": "string" }
+        ]
+    });
+    let response = server
+        .client()
+        .put(server.url(schema_path))
+        .json(&duplicate)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let response = server
+        .client()
+        .delete(server.url(schema_path))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_bulk_metadata_update() {
+    let server = TestServer::builder()
+        .with_document("guides/setup.txt", FIXTURE_DOC)
+        .with_document("notes.txt", "Meeting notes about the smoke test rollout.")
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+    server.index(COLLECTION).await.expect("Indexing failed");
+
+    let metadata_path = "/api/collections/smoke_test/documents/metadata";
+    let patch = |body: serde_json::Value| {
+        server
+            .client()
+            .patch(server.url(metadata_path))
+            .json(&body)
+            .send()
+    };
+    let response = patch(serde_json::json!({
+        "filter": { "path": "guides/*" },
+        "set": { "section": "handbook" }
+    }))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), 200);
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(report["documents_updated"], 1, "{}", report);
+
+    let sections = |response: &serde_json::Value| -> Vec<(bool, bool)> {
+        results(response)
+            .iter()
+            .map(|result| {
+                let path = result["custom_metadata"]["path"].as_str().unwrap_or_default();
+                (
+                    path.ends_with("setup.txt"),
+                    result["custom_metadata"]["section"] == "handbook",
+                )
+            })
+            .collect()
+    };
+    let response = server.search("smoke test", COLLECTION).await.unwrap();
+    assert!(!results(&response).is_empty(), "{}", response);
+    assert!(sections(&response).iter().all(|(guide, tagged)| guide == tagged));
+
+    // The patch is applied again when the files are re-indexed
+    server
+        .post_json(
+            "/api/index",
+            &serde_json::json!({
+                "path": server.docs_dir().to_string_lossy(),
+                "collection": COLLECTION,
+                "force": true,
+            }),
+        )
+        .await
+        .unwrap();
+    let response = server.search("smoke test", COLLECTION).await.unwrap();
+    assert!(sections(&response).iter().any(|(_, tagged)| *tagged));
+    assert!(sections(&response).iter().all(|(guide, tagged)| guide == tagged));
+
+    // Fields the indexer maintains cannot be patched
+    let response = patch(serde_json::json!({
+        "filter": { "path": "guides/*" },
+        "set": { "collection": "elsewhere" }
+    }))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_document_acls() {
+    let server = TestServer::builder()
+        .with_document("guides/setup.txt", FIXTURE_DOC)
+        .with_document("notes.txt", "Meeting notes about the smoke test rollout.")
+        .with_config(|config| {
+            config.access.enforce = true;
+            config.access.principal_token = Some("gateway-secret".to_string());
+        })
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+    server.index(COLLECTION).await.expect("Indexing failed");
+
+    // Connectors restrict documents through their metadata
+    let response = server
+        .client()
+        .patch(server.url("/api/collections/smoke_test/documents/metadata"))
+        .json(&serde_json::json!({
+            "filter": { "path": "guides/*" },
+            "set": { "acl_groups": "engineering" }
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let search = |groups: Option<&str>, token: &str| {
+        let mut request = server
+            .client()
+            .post(server.url("/api/search"))
+            .json(&serde_json::json!({
+                "query": "smoke test",
+                "limit": 10,
+                "filters": { "collection_name": COLLECTION },
+            }));
+        if let Some(groups) = groups {
+            request = request
+                .header("x-principal-user", "alice")
+                .header("x-principal-groups", groups)
+                .header("x-principal-token", token);
+        }
+        request.send()
+    };
+    let guides = |response: &serde_json::Value| {
+        results(response)
+            .iter()
+            .filter(|result| {
+                result["custom_metadata"]["path"]
+                    .as_str()
+                    .is_some_and(|path| path.ends_with("setup.txt"))
+            })
+            .count()
+    };
+
+    // Anonymous requests and principals outside the group only see the notes
+    for groups in [None, Some("sales")] {
+        let response: serde_json::Value = search(groups, "gateway-secret")
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(!results(&response).is_empty(), "{}", response);
+        assert_eq!(guides(&response), 0, "{}", response);
+    }
+    let response: serde_json::Value = search(Some("Engineering, ops"), "gateway-secret")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(guides(&response) > 0, "{}", response);
+
+    // Endpoints returning document content or metadata apply the same ACLs
+    let get = |path: &str, groups: Option<&str>| {
+        let mut request = server.client().get(server.url(path));
+        if let Some(groups) = groups {
+            request = request
+                .header("x-principal-user", "alice")
+                .header("x-principal-groups", groups)
+                .header("x-principal-token", "gateway-secret");
+        }
+        request.send()
+    };
+    let raw = "/api/collections/smoke_test/raw/guides/setup.txt";
+    assert_eq!(get(raw, None).await.unwrap().status(), 404);
+    assert_eq!(get(raw, Some("sales")).await.unwrap().status(), 404);
+    let response = get(raw, Some("engineering")).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().await.unwrap(), FIXTURE_DOC);
+
+    let sample: serde_json::Value = get("/api/collections/smoke_test/sample?n=50", None)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let chunks = sample["chunks"].as_array().unwrap();
+    assert!(!chunks.is_empty(), "{}", sample);
+    assert!(
+        chunks
+            .iter()
+            .all(|chunk| !chunk["path"].as_str().unwrap().ends_with("setup.txt")),
+        "{}",
+        sample
+    );
+
+    let query = |groups: Option<&str>| {
+        let mut request = server
+            .client()
+            .post(server.url("/api/collections/smoke_test/documents/query"))
+            .json(&serde_json::json!({ "query": "SELECT path" }));
+        if let Some(groups) = groups {
+            request = request
+                .header("x-principal-user", "alice")
+                .header("x-principal-groups", groups)
+                .header("x-principal-token", "gateway-secret");
+        }
+        request.send()
+    };
+    let result: serde_json::Value = query(None).await.unwrap().json().await.unwrap();
+    assert_eq!(result["total_matched"], 1, "{}", result);
+    assert!(result["rows"][0][0]
+        .as_str()
+        .unwrap()
+        .ends_with("notes.txt"));
+    let result: serde_json::Value = query(Some("engineering"))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(result["total_matched"], 2, "{}", result);
+
+    let duplicates = "/api/collections/smoke_test/duplicates";
+    let report: serde_json::Value = get(duplicates, None).await.unwrap().json().await.unwrap();
+    assert_eq!(report["documents_scanned"], 1, "{}", report);
+    let report: serde_json::Value = get(duplicates, Some("engineering"))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(report["documents_scanned"], 2, "{}", report);
+
+    let projection = "/api/collections/smoke_test/projection";
+    let started: Value = server
+        .client()
+        .post(server.url(&format!("{}?method=pca&seed=3", projection)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let job_path = format!("/api/jobs/{}", started["job_id"].as_str().unwrap());
+    let mut job = server.get_json(&job_path).await.unwrap();
+    for _ in 0..100 {
+        if job["status"] != "running" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        job = server.get_json(&job_path).await.unwrap();
+    }
+    assert_eq!(job["status"], "completed", "{}", job);
+    let csv = get(&format!("{}?format=csv", projection), None)
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(csv.contains("notes.txt"), "{}", csv);
+    assert!(!csv.contains("setup.txt"), "{}", csv);
+    let exported: Value = get(projection, Some("engineering"))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let paths: Vec<&str> = exported["points"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|point| point["path"].as_str())
+        .collect();
+    assert!(
+        paths.iter().any(|path| path.ends_with("setup.txt")),
+        "{}",
+        exported
+    );
+
+    // Principals are only taken from the gateway
+    let response = search(Some("engineering"), "guess").await.unwrap();
+    assert_eq!(response.status(), 403);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_admin_api() {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .with_config(|config| {
+            config.admin.token = Some("admin-secret".to_string());
+            config.admin.read_token = Some("read-secret".to_string());
+        })
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let indexed = server.index(COLLECTION).await.expect("Indexing failed");
+    assert_eq!(indexed["status"], "success", "Indexing: {}", indexed);
+    server.search("smoke test", COLLECTION).await.unwrap();
+
+    let admin = |method: reqwest::Method, path: &str, token: Option<&str>| {
+        let request = server.client().request(method, server.url(path));
+        match token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    };
+
+    // Without a valid token there is nothing to see
+    for token in [None, Some("wrong")] {
+        let response = admin(reqwest::Method::GET, "/api/admin/stats", token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 403);
+    }
+
+    let stats: Value = admin(reqwest::Method::GET, "/api/admin/stats", Some("read-secret"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let caches = stats["caches"].as_array().expect("caches");
+    let embeddings = caches
+        .iter()
+        .find(|cache| cache["name"] == "query_embeddings")
+        .expect("query embedding cache");
+    assert!(embeddings["misses"].as_u64().unwrap() >= 1, "{}", stats);
+    assert!(stats["storage"]["vector_count"].as_u64().unwrap() > 0, "{}", stats);
+    assert!(stats["vector_pool"]["max_pool_size"].is_u64(), "{}", stats);
+    assert!(stats["active_jobs"].is_array(), "{}", stats);
+    assert!(stats["memory"]["limit_bytes"].as_u64().unwrap() > 0, "{}", stats);
+    assert_eq!(stats["memory"]["under_pressure"], false, "{}", stats);
+
+    // The read token can look at tunables but not change them
+    let update = serde_json::json!({ "rate_limit": { "requests_per_second": 1, "burst": 1 } });
+    let tunables: Value = admin(reqwest::Method::GET, "/api/admin/tunables", Some("read-secret"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(tunables["rate_limit"]["requests_per_second"], 0, "{}", tunables);
+    let response = admin(reqwest::Method::PUT, "/api/admin/tunables", Some("read-secret"))
+        .json(&update)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let response = admin(reqwest::Method::PUT, "/api/admin/tunables", Some("admin-secret"))
+        .json(&update)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let tunables: Value = response.json().await.unwrap();
+    assert_eq!(tunables["rate_limit"]["requests_per_second"], 1, "{}", tunables);
+
+    // The new limit applies at once, except to health checks and the admin API
+    let statuses = [
+        server.client().get(server.url("/api/jobs")).send().await.unwrap(),
+        server.client().get(server.url("/api/jobs")).send().await.unwrap(),
+    ];
+    assert_eq!(statuses[0].status(), 200);
+    assert_eq!(statuses[1].status(), 429);
+    assert!(statuses[1].headers().contains_key("retry-after"));
+    server.get_json("/health").await.unwrap();
+    let response = admin(reqwest::Method::GET, "/api/admin/stats", Some("admin-secret"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_admin_log_level() {
+    let server = TestServer::builder()
+        .with_config(|config| {
+            config.admin.token = Some("admin-secret".to_string());
+            config.admin.read_token = Some("read-secret".to_string());
+        })
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let put = |body: Value, token: &str| {
+        server
+            .client()
+            .put(server.url("/api/admin/log-level"))
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+    };
+
+    let response = put(serde_json::json!({ "request_sample_rate": 0.25 }), "read-secret")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let response = put(serde_json::json!({ "request_sample_rate": 0.25 }), "admin-secret")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let levels: Value = server
+        .client()
+        .get(server.url("/api/admin/log-level"))
+        .bearer_auth("read-secret")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(levels["request_sample_rate"], 0.25, "{}", levels);
+    // The embedding test process installs no reloadable filter
+    assert!(levels["filter"].is_null(), "{}", levels);
+
+    for body in [
+        serde_json::json!({ "request_sample_rate": 2.0 }),
+        serde_json::json!({ "target": "not a target", "level": "debug" }),
+        serde_json::json!({ "target": "zero_latency_search", "level": "loud" }),
+        serde_json::json!({ "level": "debug" }),
+    ] {
+        let response = put(body.clone(), "admin-secret").await.unwrap();
+        assert_eq!(response.status(), 400, "{}", body);
+    }
+
+    let response = put(
+        serde_json::json!({ "target": "zero_latency_search", "level": "debug" }),
+        "admin-secret",
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), 500);
+    let error: Value = response.json().await.unwrap();
+    assert!(
+        error.to_string().contains("cannot be changed in this process"),
+        "{}",
+        error
+    );
+
+    put(serde_json::json!({ "request_sample_rate": 1.0 }), "admin-secret")
+        .await
+        .unwrap();
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_usage_export() {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .with_config(|config| config.admin.token = Some("admin-secret".to_string()))
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    // Indexing without a tenant header counts towards the default tenant
+    server.index(COLLECTION).await.expect("Indexing failed");
+    let search = serde_json::json!({
+        "query": "smoke test",
+        "limit": 10,
+        "filters": { "collection_name": COLLECTION },
     });
-    let resp = rt
-        .block_on(client.post(&config.index_url()).json(&index_body).send())
-        .expect("Failed to POST to index endpoint");
-    TestAssertions::assert_success_response(&resp, "Index endpoint");
-
-    // Clean up
-    let _ = child.kill();
-    let _ = child.wait();
+    for _ in 0..2 {
+        let response = server
+            .client()
+            .post(server.url("/api/search"))
+            .header("x-tenant-id", "acme")
+            .json(&search)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+    let response = server
+        .client()
+        .post(server.url("/api/search"))
+        .header("x-tenant-id", "not a tenant")
+        .json(&search)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let export = |query: &str| {
+        server
+            .client()
+            .get(server.url(&format!("/api/admin/usage{}", query)))
+            .bearer_auth("admin-secret")
+            .send()
+    };
+    let usage: Value = export("").await.unwrap().json().await.unwrap();
+    let records = usage["records"].as_array().expect("records");
+    let tenant = |name: &str| {
+        records
+            .iter()
+            .find(|record| record["tenant"] == name)
+            .unwrap_or_else(|| panic!("no usage of {}: {}", name, usage))
+    };
+    assert_eq!(tenant("acme")["searches"], 2, "{}", usage);
+    assert!(tenant("acme")["embedding_tokens"].as_u64().unwrap() > 0);
+    let default = tenant("default");
+    assert!(default["indexed_bytes"].as_u64().unwrap() >= FIXTURE_DOC.len() as u64);
+    assert!(default["stored_vectors"].as_u64().unwrap() > 0, "{}", usage);
+
+    let response = export("?period=day&tenant=acme&format=csv").await.unwrap();
+    assert_eq!(response.status(), 200);
+    let csv = response.text().await.unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", csv);
+    assert!(lines[0].starts_with("tenant,period_start,period_end,searches"));
+    assert!(lines[1].starts_with("acme,"));
+
+    // Usage is only visible to admins
+    let response = server
+        .client()
+        .get(server.url("/api/admin/usage"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_search_curation() {
+    let server = TestServer::builder()
+        .with_document("guides/setup.txt", FIXTURE_DOC)
+        .with_document("notes.txt", "Meeting notes about the smoke test rollout.")
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+    server.index(COLLECTION).await.expect("Indexing failed");
+
+    let query = "Meeting notes about the smoke test rollout";
+    let paths = |response: &serde_json::Value| -> Vec<String> {
+        results(response)
+            .iter()
+            .map(|result| {
+                result["custom_metadata"]["path"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect()
+    };
+    let unpinned = |response: &serde_json::Value| {
+        results(response)
+            .iter()
+            .all(|result| result["from_signals"]["curation"]["pinned"].is_null())
+    };
+    let response = server.search(query, COLLECTION).await.unwrap();
+    assert_eq!(paths(&response).len(), 2, "{}", response);
+    assert!(unpinned(&response), "{}", response);
+
+    // Pinning the guide for the exact query puts it first, marked as pinned
+    let pin = server
+        .post_json(
+            "/api/curation/pins",
+            &serde_json::json!({
+                "collection": COLLECTION,
+                "query": "meeting notes about the  smoke test rollout",
+                "documents": ["guides/*"],
+            }),
+        )
+        .await
+        .unwrap();
+    let pin_id = pin["id"].as_str().expect("pin id").to_string();
+
+    let response = server.search(query, COLLECTION).await.unwrap();
+    assert!(paths(&response)[0].ends_with("setup.txt"), "{}", response);
+    let mark = &results(&response)[0]["from_signals"]["curation"];
+    assert_eq!(mark["pinned"], 0, "{}", response);
+    assert_eq!(mark["rules"][0], pin_id.as_str());
+    // Other queries are unaffected
+    let response = server.search("smoke test rollout", COLLECTION).await.unwrap();
+    assert!(unpinned(&response), "{}", response);
+
+    // A boost marks every matching result with its factor
+    let boost = server
+        .post_json(
+            "/api/curation/boosts",
+            &serde_json::json!({ "collection": COLLECTION, "document": "notes.txt", "factor": 0.5 }),
+        )
+        .await
+        .unwrap();
+    let response = server.search("smoke test rollout", COLLECTION).await.unwrap();
+    let demoted = results(&response)
+        .iter()
+        .find(|result| {
+            result["custom_metadata"]["path"]
+                .as_str()
+                .unwrap_or_default()
+                .ends_with("notes.txt")
+        })
+        .expect("notes in results");
+    assert_eq!(demoted["from_signals"]["curation"]["boost"], 0.5, "{}", response);
+
+    let rules = server
+        .get_json(&format!("/api/curation?collection={}", COLLECTION))
+        .await
+        .unwrap();
+    assert_eq!(rules["pins"].as_array().unwrap().len(), 1, "{}", rules);
+    assert_eq!(rules["boosts"].as_array().unwrap().len(), 1, "{}", rules);
+
+    for path in [
+        format!("/api/curation/pins/{}", pin_id),
+        format!("/api/curation/boosts/{}", boost["id"].as_str().unwrap()),
+    ] {
+        let response = server.client().delete(server.url(&path)).send().await.unwrap();
+        assert_eq!(response.status(), 204);
+        let response = server.client().delete(server.url(&path)).send().await.unwrap();
+        assert_eq!(response.status(), 404);
+    }
+    let response = server.search(query, COLLECTION).await.unwrap();
+    assert!(unpinned(&response), "{}", response);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_blocklist() {
+    let server = TestServer::builder()
+        .with_document("deprecated/old.txt", FIXTURE_DOC)
+        .with_document("notes.txt", "Meeting notes about the smoke test rollout.")
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+    server.index(COLLECTION).await.expect("Indexing failed");
+
+    let deprecated_results = |response: &serde_json::Value| {
+        results(response)
+            .iter()
+            .filter(|result| {
+                result["custom_metadata"]["path"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .contains("deprecated/")
+            })
+            .count()
+    };
+    let response = server.search("smoke test", COLLECTION).await.unwrap();
+    assert!(deprecated_results(&response) > 0, "{}", response);
+
+    let entry = server
+        .post_json(
+            "/api/blocklist",
+            &serde_json::json!({
+                "collection": COLLECTION,
+                "path": "deprecated/**",
+                "reason": "superseded",
+            }),
+        )
+        .await
+        .unwrap();
+    let entry_id = entry["id"].as_str().expect("entry id").to_string();
+
+    // Blocked documents are hidden but stay indexed
+    let response = server.search("smoke test", COLLECTION).await.unwrap();
+    assert_eq!(deprecated_results(&response), 0, "{}", response);
+    assert!(!results(&response).is_empty(), "{}", response);
+    let entries = server
+        .get_json(&format!("/api/blocklist?collection={}", COLLECTION))
+        .await
+        .unwrap();
+    assert_eq!(entries[0]["reason"], "superseded", "{}", entries);
+
+    // A path and a document at once is rejected
+    let response = server
+        .client()
+        .post(server.url("/api/blocklist"))
+        .json(&serde_json::json!({ "collection": COLLECTION, "path": "a.txt", "document": "b" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let path = format!("/api/blocklist/{}", entry_id);
+    let response = server.client().delete(server.url(&path)).send().await.unwrap();
+    assert_eq!(response.status(), 204);
+    let response = server.search("smoke test", COLLECTION).await.unwrap();
+    assert!(deprecated_results(&response) > 0, "{}", response);
+
+    server.shutdown().await;
+}
+
+/// Copies of the same page in mirrored sources collapse into one result from
+/// the canonical source, listing the other copies as alternates
+#[tokio::test]
+async fn smoke_test_mirror_deduplication() {
+    let server = TestServer::builder()
+        .with_document("repo/README.txt", FIXTURE_DOC)
+        .with_document("site/getting-started.txt", FIXTURE_DOC)
+        .with_config(|config| {
+            config.dedup.enabled = true;
+            config.dedup.canonical_sources = vec!["site/**".to_string()];
+        })
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+    server.index(COLLECTION).await.expect("Indexing failed");
+
+    let response = server.search("smoke test", COLLECTION).await.unwrap();
+    let path = |result: &serde_json::Value| {
+        result["custom_metadata"]["path"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string()
+    };
+    let results = results(&response);
+    assert!(!results.is_empty(), "{}", response);
+    assert!(results.iter().all(|result| path(result).contains("site/")), "{}", response);
+    let alternates = results[0]["from_signals"]["alternates"]
+        .as_array()
+        .expect("alternates");
+    assert!(
+        alternates
+            .iter()
+            .any(|alternate| alternate["uri"].as_str().unwrap_or_default().contains("repo/")),
+        "{}",
+        response
+    );
+    assert!(response["search_metadata"]["ranking_method"]
+        .as_str()
+        .unwrap_or_default()
+        .ends_with("+dedup"));
+
+    server.shutdown().await;
+}
+
+/// Once a collection has a score background, final scores are calibrated
+/// against it and `min_score` filters on the calibrated values
+#[tokio::test]
+async fn smoke_test_score_calibration() {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .with_document("notes.txt", "Meeting notes about the smoke test rollout.")
+        .with_config(|config| config.calibration.min_samples = 4)
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+    server.index(COLLECTION).await.expect("Indexing failed");
+
+    let request = |query: &str, min_score: Option<f32>| {
+        serde_json::json!({
+            "query": query,
+            "filters": { "collection_name": COLLECTION },
+            "min_score": min_score,
+        })
+    };
+    for query in ["smoke test", "meeting notes", "rollout"] {
+        server.post_json("/api/search", &request(query, None)).await.unwrap();
+    }
+
+    let response = server
+        .post_json("/api/search", &request("smoke test document", None))
+        .await
+        .unwrap();
+    let method = response["search_metadata"]["ranking_method"].as_str().unwrap_or_default();
+    assert!(method.contains("+calibrated"), "{}", response);
+    let all = results(&response).len();
+    assert!(all > 0, "{}", response);
+    assert!(results(&response)
+        .iter()
+        .all(|result| result["from_signals"]["uncalibrated_score"].is_number()));
+
+    // A high threshold drops the weaker matches
+    let response = server
+        .post_json("/api/search", &request("smoke test document", Some(0.999_999)))
+        .await
+        .unwrap();
+    assert!(results(&response).len() < all, "{}", response);
+    assert!(response["search_metadata"]["effective_settings"]["min_score"].is_number());
+
+    // Thresholds outside 0-1 are rejected
+    let response = server
+        .client()
+        .post(server.url("/api/search"))
+        .json(&serde_json::json!({ "query": "smoke", "min_score": 2.0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+/// Responses carry a confidence label and an answerability hint, with the
+/// signals behind them in the debug info
+#[tokio::test]
+async fn smoke_test_response_confidence() {
+    let server = indexed_server().await;
+
+    let response = server.search("smoke test", COLLECTION).await.unwrap();
+    let confidence = response["confidence"].as_str().expect("confidence");
+    assert!(["high", "medium", "low"].contains(&confidence), "{}", response);
+    assert!(response["answerable"].is_boolean(), "{}", response);
+    let explained = &response["search_metadata"]["debug_info"]["confidence"];
+    assert_eq!(explained["level"], confidence);
+    assert_eq!(explained["signals"]["lexical_overlap"], 1.0, "{}", response);
+
+    // Results that share no words with the query cannot answer it
+    let response = server
+        .search("quantum entanglement spectroscopy", COLLECTION)
+        .await
+        .unwrap();
+    assert_eq!(response["answerable"], false, "{}", response);
+
+    server.shutdown().await;
+}
+
+/// Queries are classified by intent, which routes the pipeline and is
+/// reported in the response and in analytics
+#[tokio::test]
+async fn smoke_test_query_intent_routing() {
+    let server = indexed_server().await;
+
+    // Exact identifiers are searched without expansion
+    let response = server.search("DOC_INDEXER_PORT", COLLECTION).await.unwrap();
+    let metadata = &response["search_metadata"];
+    assert_eq!(metadata["intent"], "code_lookup", "{}", response);
+    assert_eq!(metadata["query_enhancement_applied"], false);
+    assert_eq!(metadata["debug_info"]["intent"], "code_lookup");
+
+    let response = server
+        .search("what is the smoke test?", COLLECTION)
+        .await
+        .unwrap();
+    assert_eq!(response["search_metadata"]["intent"], "question");
+
+    let response = server.search("smoke_test_doc.txt", COLLECTION).await.unwrap();
+    assert_eq!(response["search_metadata"]["intent"], "navigational");
+    assert!(
+        results(&response)[0]["uri"]
+            .as_str()
+            .unwrap()
+            .ends_with("smoke_test_doc.txt"),
+        "{}",
+        response
+    );
+
+    let summary = server.get_json("/api/analytics/summary").await.unwrap();
+    assert_eq!(summary["intents"]["code_lookup"], 1, "{}", summary);
+    assert_eq!(summary["intents"]["question"], 1);
+    assert_eq!(summary["intents"]["navigational"], 1);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_browse_mode() {
+    let server = indexed_server().await;
+
+    // Empty and stop-word-only queries list documents with facet counts
+    for query in ["", "the of"] {
+        let response = server.search(query, COLLECTION).await.unwrap();
+        assert_eq!(
+            response["search_metadata"]["ranking_method"], "browse:recent",
+            "{}",
+            response
+        );
+        assert_eq!(results(&response).len(), 1, "{}", response);
+        assert_eq!(results(&response)[0]["final_score"], 0.0);
+        assert_eq!(response["facets"]["type"]["txt"], 1);
+        assert_eq!(response["facets"]["collection"][COLLECTION], 1);
+        assert_eq!(response["total_count"], 1);
+    }
+
+    let response = server
+        .post_json(
+            "/api/search",
+            &serde_json::json!({
+                "query": "",
+                "browse_order": "popular",
+                "filters": { "collection_name": COLLECTION },
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        response["search_metadata"]["ranking_method"],
+        "browse:popular"
+    );
+
+    let response = server
+        .client()
+        .post(server.url("/api/search"))
+        .json(&serde_json::json!({
+            "query": "",
+            "browse_order": "newest",
+            "filters": { "collection_name": COLLECTION },
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn smoke_test_index_cost_confirmation() {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .with_config(|config| {
+            // A dollar per token, so any real job is above the threshold
+            config.cost.price_per_million_tokens = Some(1_000_000.0);
+            config.cost.confirm_threshold_usd = 1.0;
+        })
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let index = |confirm_cost: bool| {
+        server
+            .client()
+            .post(server.url("/api/index"))
+            .json(&serde_json::json!({
+                "path": server.docs_dir().to_string_lossy(),
+                "collection": COLLECTION,
+                "confirm_cost": confirm_cost,
+            }))
+            .send()
+    };
+
+    let response = index(false).await.unwrap();
+    assert_eq!(response.status(), 400);
+    let error = response.text().await.unwrap();
+    assert!(error.contains("confirm_cost"), "{}", error);
+
+    // The refused job was never started
+    let jobs: Value = server
+        .client()
+        .get(server.url("/api/jobs"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(jobs["total"], 0, "{}", jobs);
+
+    let response = index(true).await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    let estimate = &body["cost_estimate"];
+    assert!(estimate["tokens"].as_u64().unwrap() > 0, "{}", body);
+    assert_eq!(estimate["requires_confirmation"], true);
+    assert_eq!(estimate["documents"], body["documents_processed"]);
+}
+
+#[tokio::test]
+async fn smoke_test_offline_mode() {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .with_config(|config| {
+            config.offline.enabled = true;
+            config.summarizer.backend = zero_latency_config::SummarizerBackend::OpenAI;
+        })
+        .start()
+        .await
+        .expect("Offline mode should fall back to the extractive summarizer");
+
+    let status: Value = server
+        .client()
+        .get(server.url("/api/status"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(status["offline"]["enabled"], true, "{}", status);
+    let degraded = status["offline"]["degraded"].as_array().expect("degraded");
+    assert_eq!(degraded.len(), 1, "{}", status);
+    assert_eq!(degraded[0]["capability"], "summaries");
+
+    // Search still works on the local fallbacks
+    server.index(COLLECTION).await.expect("Indexing failed");
+    let results = server.search("smoke test", COLLECTION).await.unwrap();
+    assert!(!results["results"].as_array().unwrap().is_empty(), "{}", results);
+}
+
+#[tokio::test]
+async fn smoke_test_capabilities() {
+    let server = TestServer::builder()
+        .with_config(|config| config.admin.token = Some("admin-secret".to_string()))
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let response = server
+        .client()
+        .get(server.url("/api/capabilities"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let capabilities: Value = response.json().await.unwrap();
+
+    assert_eq!(capabilities["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(capabilities["summarizer"], "local");
+    assert_eq!(capabilities["offline"], false);
+    assert_eq!(capabilities["admin_api"], true);
+    assert!(capabilities["embeddings"]["dimension"].as_u64().unwrap() > 0);
+    let protocols = capabilities["protocols"].as_array().unwrap();
+    assert!(protocols.contains(&Value::from("jsonrpc")), "{}", capabilities);
+    let web_ui = protocols.contains(&Value::from("web_ui"));
+    assert_eq!(web_ui, cfg!(feature = "web-ui"));
+}
+
+#[tokio::test]
+async fn smoke_test_startup_validation() {
+    let server = TestServer::builder()
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    // Validation runs in the background; readiness waits for it
+    let mut report = Value::Null;
+    for _ in 0..50 {
+        let response = server
+            .client()
+            .get(server.url("/health/startup"))
+            .send()
+            .await
+            .unwrap();
+        let status = response.status();
+        report = response.json().await.unwrap();
+        if report["status"] != "running" {
+            assert_eq!(status, 200, "{}", report);
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(report["status"], "passed", "{}", report);
+    let checks = report["checks"].as_array().unwrap();
+    let vector_store = checks
+        .iter()
+        .find(|check| check["id"] == "vector_store")
+        .unwrap();
+    assert_eq!(vector_store["outcome"], "passed");
+    assert_eq!(vector_store["required"], true);
+    assert!(vector_store["duration_ms"].is_u64());
+
+    let readiness = server.get_json("/health/ready").await.unwrap();
+    assert_eq!(readiness["ready"], true, "{}", readiness);
+    assert_eq!(readiness["checks"]["startup"]["status"], "healthy");
+}
+
+#[tokio::test]
+async fn smoke_test_deployment_status() {
+    let server = TestServer::builder()
+        .with_config(|config| config.admin.token = Some("admin-secret".to_string()))
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let response = server
+        .client()
+        .get(server.url("/api/admin/deployment"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    // The deployment starts in the background once validation passes
+    let mut report = Value::Null;
+    for _ in 0..50 {
+        report = server
+            .client()
+            .get(server.url("/api/admin/deployment"))
+            .bearer_auth("admin-secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if report["status"] != "starting" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_ne!(report["status"], "starting", "{}", report);
+    assert_ne!(report["status"], "unhealthy", "{}", report);
+    assert!(report["uptime_seconds"].is_u64());
+    assert_eq!(report["components"]["startup_validator"]["state"], "passed");
+    assert_eq!(report["components"]["shutdown_handler"]["state"], "running");
+    assert_eq!(report["transitions"][0]["from"], "starting", "{}", report);
+    assert_eq!(report["transitions"][0]["reason"], "deployment started");
+}
+
+#[tokio::test]
+async fn smoke_test_build_info() {
+    let server = TestServer::builder()
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let response = server
+        .client()
+        .get(server.url("/api/build-info"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let info: Value = response.json().await.unwrap();
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(info["crates"]["zero-latency-core"], "0.1.0", "{}", info);
+    let services = info["external_services"].as_array().unwrap();
+    assert!(services.iter().any(|service| service["name"] == "smtp"));
+
+    // The CLI reports the same without loading configuration or a server
+    let output = Command::new(env!("CARGO_BIN_EXE_doc-indexer"))
+        .args(["about", "--build-info"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let printed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(printed, info);
+}
+
+#[tokio::test]
+async fn smoke_test_status_reports_api_version() {
+    use zero_latency_api::endpoints::version::{check, Compatibility, API_VERSION};
+
+    let server = TestServer::builder()
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let status: Value = server
+        .client()
+        .get(server.url("/api/status"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let api_version = status["api_version"].as_str().expect("api_version");
+    assert_eq!(api_version, API_VERSION.to_string());
+    assert_eq!(check(Some(api_version)), Compatibility::Compatible);
+}
+
+#[tokio::test]
+async fn smoke_test_background_index_job() {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let response = server
+        .client()
+        .post(server.url("/api/index"))
+        .json(&serde_json::json!({
+            "path": server.docs_dir().to_string_lossy(),
+            "collection": COLLECTION,
+            "background": true,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "running", "{}", body);
+    let job_id = body["job_id"].as_str().expect("job_id").to_string();
+
+    // The job keeps running without the request and is followed by polling
+    let mut job = Value::Null;
+    for _ in 0..100 {
+        job = server
+            .client()
+            .get(server.url(&format!("/api/jobs/{}", job_id)))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if job["status"] != "running" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(job["status"], "completed", "{}", job);
+    assert_eq!(job["documents_processed"], 1, "{}", job);
+
+    let results = server.search("smoke test", COLLECTION).await.unwrap();
+    assert!(!results["results"].as_array().unwrap().is_empty(), "{}", results);
+}
+
+/// Smoke test: a job with a checkpoint can only be resumed once it failed
+#[tokio::test]
+async fn smoke_test_completed_job_cannot_be_resumed() {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    let indexed = server.index(COLLECTION).await.unwrap();
+    let job_id = indexed["job_id"].as_str().expect("job_id");
+
+    let job: Value = server
+        .client()
+        .get(server.url(&format!("/api/jobs/{}", job_id)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(job["status"], "completed", "{}", job);
+    assert!(job.get("checkpoint").is_none(), "{}", job);
+
+    let response = server
+        .client()
+        .post(server.url(&format!("/api/jobs/{}/resume", job_id)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let response = server
+        .client()
+        .post(server.url("/api/jobs/missing/resume"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+/// Smoke test: searches and indexing work are counted in separate lanes
+#[tokio::test]
+async fn smoke_test_execution_lane_utilization() {
+    let server = TestServer::builder()
+        .with_document("smoke_test_doc.txt", FIXTURE_DOC)
+        .with_config(|config| {
+            config.admin.read_token = Some("read-secret".to_string());
+            config.admin.token = Some("admin-secret".to_string());
+            config.lanes.background_concurrency = 2;
+            config.lanes.search_target_p95_ms = 500;
+        })
+        .start()
+        .await
+        .expect("Failed to start in-process doc-indexer");
+
+    server.index(COLLECTION).await.expect("Indexing failed");
+    server.search("smoke test", COLLECTION).await.unwrap();
+
+    let stats: Value = server
+        .client()
+        .get(server.url("/api/admin/stats"))
+        .bearer_auth("read-secret")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let lanes = stats["lanes"].as_array().expect("lanes");
+    assert_eq!(lanes[0]["lane"], "interactive", "{}", stats);
+    assert_eq!(lanes[0]["completed"], 1, "{}", stats);
+    assert_eq!(lanes[0]["target_p95_ms"], 500.0, "{}", stats);
+    assert!(lanes[1].get("target_p95_ms").is_none(), "{}", stats);
+    assert_eq!(lanes[1]["lane"], "background", "{}", stats);
+    assert_eq!(lanes[1]["capacity"], 2, "{}", stats);
+    assert!(lanes[1]["completed"].as_u64().unwrap() >= 4, "{}", stats);
+    assert_eq!(lanes[1]["in_flight"], 0, "{}", stats);
 }
@@ -0,0 +1,223 @@
+// Bundled search UI for doc-indexer. Talks only to the public REST API.
+"use strict";
+
+const $ = (id) => document.getElementById(id);
+
+const state = {
+  results: [],
+  terms: [],
+  // Facet values the results are narrowed to, keyed by facet name
+  selected: {},
+  jobTimer: null,
+};
+
+async function api(path, body) {
+  const response = await fetch(path, body === undefined ? {} : {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify(body),
+  });
+  const payload = await response.json().catch(() => ({}));
+  if (!response.ok) {
+    throw new Error(payload.error?.message || response.statusText);
+  }
+  return payload;
+}
+
+function escapeHtml(text) {
+  return text.replace(/[&<>"']/g, (c) => ({
+    "&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;", "'": "&#39;",
+  })[c]);
+}
+
+// Words worth highlighting: drops exclusions (-word), field prefixes
+// (title:word) and boolean operators
+function queryTerms(query) {
+  return query
+    .split(/\s+/)
+    .filter((word) => word && !word.startsWith("-") && !/^(AND|OR|NOT)$/.test(word))
+    .map((word) => word.replace(/^\w+:/, "").replace(/["()*]/g, ""))
+    .filter((word) => word.length > 1);
+}
+
+function highlight(text, terms) {
+  const escaped = escapeHtml(text);
+  if (terms.length === 0) return escaped;
+  const pattern = terms
+    .map((term) => escapeHtml(term).replace(/[.*+?^${}()|[\]\\]/g, "\\$&"))
+    .join("|");
+  return escaped.replace(new RegExp(`(${pattern})`, "gi"), "<mark>$1</mark>");
+}
+
+function snippet(result) {
+  const text = result.snippet || result.content || "";
+  return text.length > 400 ? `${text.slice(0, 400)}…` : text;
+}
+
+function extension(result) {
+  const path = result.uri || result.document_path || "";
+  const match = /\.([A-Za-z0-9]+)$/.exec(path);
+  return match ? match[1].toLowerCase() : "none";
+}
+
+// Facets are computed from the returned results
+const FACETS = {
+  Collection: (result) => [result.collection || "unknown"],
+  Type: (result) => [extension(result)],
+  Tags: (result) => (result.custom_metadata?.tags || "")
+    .split(",").map((tag) => tag.trim()).filter(Boolean),
+};
+
+function matchesFacets(result) {
+  return Object.entries(state.selected).every(([facet, values]) =>
+    values.size === 0 || FACETS[facet](result).some((value) => values.has(value)));
+}
+
+function renderFacets() {
+  const html = Object.entries(FACETS).map(([facet, valuesOf]) => {
+    const counts = new Map();
+    for (const result of state.results) {
+      for (const value of valuesOf(result)) counts.set(value, (counts.get(value) || 0) + 1);
+    }
+    if (counts.size === 0) return "";
+    const selected = state.selected[facet] || new Set();
+    const options = [...counts.entries()]
+      .sort((a, b) => b[1] - a[1])
+      .map(([value, count]) => `<label><input type="checkbox" data-facet="${facet}"
+        value="${escapeHtml(value)}" ${selected.has(value) ? "checked" : ""}>
+        ${escapeHtml(value)} <span class="count">${count}</span></label>`)
+      .join("");
+    return `<h3>${facet}</h3>${options}`;
+  }).join("");
+  $("facets").innerHTML = html;
+}
+
+function renderResults() {
+  const shown = state.results.filter(matchesFacets);
+  $("results").innerHTML = shown.map((result) => {
+    const section = (result.section_path || []).join(" › ");
+    return `<li>
+      <div class="title">${highlight(result.title || result.uri, state.terms)}</div>
+      <div class="meta">${escapeHtml(result.uri || "")}${section ? ` · ${escapeHtml(section)}` : ""}
+        · ${escapeHtml(result.collection || "")} · score ${Number(result.final_score).toFixed(3)}</div>
+      <p class="snippet">${highlight(snippet(result), state.terms)}</p>
+    </li>`;
+  }).join("");
+}
+
+async function search(event) {
+  event.preventDefault();
+  const query = $("query").value.trim();
+  if (!query) return;
+
+  const request = { query, limit: 50, extract_answer: true };
+  const collection = $("collection").value;
+  if (collection) request.filters = { collection_name: collection };
+
+  $("search-status").textContent = "Searching…";
+  try {
+    const response = await api("/api/search", request);
+    state.results = response.results || [];
+    state.terms = queryTerms(query);
+    state.selected = {};
+    const millis = response.search_metadata?.execution_time;
+    $("search-status").textContent = `${state.results.length} results` +
+      (millis ? ` in ${Math.round((millis.secs * 1000) + (millis.nanos / 1e6))} ms` : "");
+
+    const answer = response.answer;
+    $("answer").hidden = !answer;
+    if (answer) {
+      $("answer").innerHTML = `<strong>${escapeHtml(answer.text)}</strong>
+        <div class="meta">${escapeHtml(answer.title || answer.uri)}</div>`;
+    }
+    renderFacets();
+    renderResults();
+  } catch (error) {
+    state.results = [];
+    $("search-status").textContent = `Search failed: ${error.message}`;
+    renderFacets();
+    renderResults();
+  }
+}
+
+function formatBytes(bytes) {
+  const units = ["B", "KB", "MB", "GB"];
+  let value = bytes;
+  let unit = 0;
+  while (value >= 1024 && unit < units.length - 1) {
+    value /= 1024;
+    unit += 1;
+  }
+  return `${value.toFixed(unit === 0 ? 0 : 1)} ${units[unit]}`;
+}
+
+function formatTime(timestamp) {
+  return timestamp ? new Date(timestamp).toLocaleString() : "—";
+}
+
+async function loadCollections() {
+  const { collections = [] } = await api("/api/collections");
+  const selector = $("collection");
+  const current = selector.value;
+  selector.innerHTML = `<option value="">Default collection</option>` + collections
+    .map((c) => `<option value="${escapeHtml(c.name)}">${escapeHtml(c.name)}</option>`)
+    .join("");
+  selector.value = current;
+
+  const rows = await Promise.all(collections.map(async (collection) => {
+    const { stats } = await api(`/api/collections/${encodeURIComponent(collection.name)}/stats`)
+      .catch(() => ({}));
+    return `<tr>
+      <td>${escapeHtml(collection.name)}</td>
+      <td>${escapeHtml(String(collection.status))}</td>
+      <td>${collection.vector_count}</td>
+      <td>${formatBytes(collection.size_bytes)}</td>
+      <td>${formatTime(stats?.last_indexed || collection.last_modified)}</td>
+    </tr>`;
+  }));
+  $("collection-rows").innerHTML = rows.join("");
+}
+
+async function loadJobs() {
+  const { jobs = [] } = await api("/api/jobs");
+  $("job-rows").innerHTML = jobs.map((job) => {
+    const problems = job.error || (job.diagnostics?.length ? `${job.diagnostics.length} diagnostics` : "");
+    return `<tr>
+      <td title="${escapeHtml(job.path)}">${escapeHtml(job.id.slice(0, 8))}</td>
+      <td>${escapeHtml(job.kind)}</td>
+      <td>${escapeHtml(job.collection)}</td>
+      <td class="${escapeHtml(job.status)}">${escapeHtml(job.status)}</td>
+      <td>${job.documents_processed}</td>
+      <td>${formatTime(job.started_at)}</td>
+      <td>${escapeHtml(problems)}</td>
+    </tr>`;
+  }).join("");
+}
+
+function showTab(name) {
+  document.querySelectorAll("nav button").forEach((button) =>
+    button.classList.toggle("active", button.dataset.tab === name));
+  document.querySelectorAll(".tab").forEach((tab) =>
+    tab.classList.toggle("active", tab.id === name));
+
+  clearInterval(state.jobTimer);
+  if (name === "collections") loadCollections().catch(console.error);
+  if (name === "jobs") {
+    loadJobs().catch(console.error);
+    state.jobTimer = setInterval(() => loadJobs().catch(console.error), 3000);
+  }
+}
+
+document.querySelectorAll("nav button").forEach((button) =>
+  button.addEventListener("click", () => showTab(button.dataset.tab)));
+$("search-form").addEventListener("submit", search);
+$("facets").addEventListener("change", (event) => {
+  const { facet } = event.target.dataset;
+  if (!facet) return;
+  const values = state.selected[facet] || (state.selected[facet] = new Set());
+  if (event.target.checked) values.add(event.target.value);
+  else values.delete(event.target.value);
+  renderResults();
+});
+
+loadCollections().catch(console.error);